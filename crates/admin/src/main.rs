@@ -0,0 +1,169 @@
+//! Database administration CLI
+//!
+//! Applies and checks the schema migrations embedded in this build and
+//! seeds the data a fresh database needs, so deployments don't need the
+//! sqlx CLI or hand-written SQL.
+//!
+//! ```sh
+//! # Apply pending migrations
+//! dguesser-admin migrate
+//!
+//! # Create the world map and make a user an admin
+//! dguesser-admin seed-defaults --admin-email admin@example.com
+//!
+//! # Exit with status 1 unless the schema matches this build
+//! dguesser-admin verify-schema
+//! ```
+
+use std::process::ExitCode;
+
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use dguesser_db::DbPool;
+use dguesser_db::schema::{self, SchemaReport};
+
+#[derive(Parser)]
+#[command(name = "dguesser-admin", about = "DGuesser database administration")]
+struct Cli {
+    /// Postgres connection URL
+    #[arg(long, env = "DATABASE_URL", hide_env_values = true)]
+    database_url: String,
+
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Apply pending migrations
+    Migrate {
+        /// List pending migrations without applying them
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Create the default world map and, optionally, an admin user
+    SeedDefaults {
+        /// Email of the user to make an admin (created if missing)
+        #[arg(long, env = "ADMIN_EMAIL")]
+        admin_email: Option<String>,
+
+        /// Display name for a created admin
+        #[arg(long, default_value = "Admin")]
+        admin_name: String,
+
+        /// Password to set for the admin (sign-in by email link or OAuth
+        /// only when unset)
+        #[arg(long, env = "ADMIN_PASSWORD", hide_env_values = true)]
+        admin_password: Option<String>,
+    },
+
+    /// Check that every migration in this build is applied unchanged
+    VerifySchema,
+}
+
+#[tokio::main]
+async fn main() -> Result<ExitCode> {
+    dotenvy::dotenv().ok();
+
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::from_default_env()
+                .add_directive("dguesser_admin=info".parse().unwrap()),
+        )
+        .init();
+
+    let cli = Cli::parse();
+    let pool = dguesser_db::create_pool(&cli.database_url)
+        .await
+        .context("Failed to connect to the database")?;
+
+    match cli.command {
+        Commands::Migrate { dry_run } => migrate(&pool, dry_run).await,
+        Commands::SeedDefaults { admin_email, admin_name, admin_password } => {
+            seed_defaults(&pool, admin_email.as_deref(), &admin_name, admin_password.as_deref())
+                .await
+        }
+        Commands::VerifySchema => verify_schema(&pool).await,
+    }
+}
+
+async fn migrate(pool: &DbPool, dry_run: bool) -> Result<ExitCode> {
+    let report = schema::check(pool).await?;
+    if let Some(version) = report.dirty {
+        anyhow::bail!("Migration {version} failed part way; fix it by hand before migrating");
+    }
+    if report.pending.is_empty() {
+        tracing::info!(applied = report.applied, "Schema is up to date");
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    for (version, description) in &report.pending {
+        tracing::info!(version, description, "Pending migration");
+    }
+    if dry_run {
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    schema::migrate(pool).await.context("Migration failed")?;
+    tracing::info!(applied = report.pending.len(), "Migrations applied");
+    Ok(ExitCode::SUCCESS)
+}
+
+async fn seed_defaults(
+    pool: &DbPool,
+    admin_email: Option<&str>,
+    admin_name: &str,
+    admin_password: Option<&str>,
+) -> Result<ExitCode> {
+    if dguesser_db::defaults::ensure_world_map(pool).await? {
+        tracing::info!(slug = dguesser_db::defaults::WORLD_MAP_SLUG, "Created world map");
+    } else {
+        tracing::info!(slug = dguesser_db::defaults::WORLD_MAP_SLUG, "World map exists");
+    }
+
+    let Some(email) = admin_email else {
+        return Ok(ExitCode::SUCCESS);
+    };
+    let email = dguesser_auth::credentials::normalize_email(email)?;
+    let password_hash = match admin_password {
+        Some(password) => {
+            dguesser_auth::credentials::validate_password(password)?;
+            Some(dguesser_auth::credentials::hash_password(password)?)
+        }
+        None => None,
+    };
+
+    let (user, created) =
+        dguesser_db::defaults::ensure_admin(pool, &email, admin_name, password_hash.as_deref())
+            .await?;
+    tracing::info!(user_id = %user.id, email, created, "Admin user ready");
+    Ok(ExitCode::SUCCESS)
+}
+
+async fn verify_schema(pool: &DbPool) -> Result<ExitCode> {
+    let report = schema::check(pool).await?;
+    print_report(&report);
+
+    if report.is_current() {
+        tracing::info!(applied = report.applied, "Schema matches this build");
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+fn print_report(report: &SchemaReport) {
+    if let Some(version) = report.dirty {
+        tracing::error!(version, "Migration failed part way");
+    }
+    for (version, description) in &report.pending {
+        tracing::warn!(version, description, "Migration not applied");
+    }
+    for version in &report.modified {
+        tracing::error!(version, "Applied migration was modified since");
+    }
+    for version in &report.unknown {
+        tracing::warn!(version, "Applied migration is unknown to this build");
+    }
+}
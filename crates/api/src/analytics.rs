@@ -0,0 +1,69 @@
+//! Analytics rollups
+//!
+//! The admin analytics endpoints read pre-aggregated tables instead of
+//! scanning `guesses` and `games` on every request. This background task
+//! recomputes yesterday's and today's daily rollups and the recent retention
+//! cohorts once an hour. The first run backfills the last few months.
+
+use std::time::Duration;
+
+use chrono::{Days, Utc};
+use dguesser_db::analytics;
+
+use crate::jobs::claim_interval;
+use crate::state::AppState;
+
+/// How often the rollups are refreshed
+const INTERVAL_SECS: u64 = 60 * 60;
+
+/// Days rolled up when no rollup exists yet
+const BACKFILL_DAYS: u64 = 90;
+
+/// Signup cohorts refreshed on every run
+const RETENTION_WEEKS: i32 = 26;
+
+/// Weeks after signup tracked for each cohort
+const RETENTION_MAX_OFFSET: i32 = 12;
+
+/// Spawn the background analytics rollup task.
+///
+/// A Redis key per interval ensures only one API instance runs each refresh.
+pub fn spawn_analytics_rollup_task(state: AppState) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(INTERVAL_SECS));
+
+        // Skip the first immediate tick so startup is not slowed down
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+
+            let slot = Utc::now().timestamp() as u64 / INTERVAL_SECS;
+            let key = format!("analytics:rollup:{slot}");
+            if !claim_interval(state.redis(), &key, INTERVAL_SECS).await {
+                continue;
+            }
+
+            match run_once(&state).await {
+                Ok(()) => tracing::info!("Analytics rollups refreshed"),
+                Err(e) => tracing::error!(error = %e, "Analytics rollup failed"),
+            }
+        }
+    });
+
+    tracing::info!(interval_secs = INTERVAL_SECS, "Analytics rollup task started");
+}
+
+/// Refresh the daily rollups and retention cohorts.
+async fn run_once(state: &AppState) -> Result<(), sqlx::Error> {
+    let today = Utc::now().date_naive();
+
+    // Yesterday is refreshed too so late writes around midnight are counted
+    let from = match analytics::last_rolled_up_day(state.db()).await? {
+        Some(last) => last.min(today - Days::new(1)),
+        None => today - Days::new(BACKFILL_DAYS),
+    };
+
+    analytics::refresh_daily(state.db(), from, today).await?;
+    analytics::refresh_retention(state.db(), RETENTION_WEEKS, RETENTION_MAX_OFFSET).await
+}
@@ -5,6 +5,7 @@
 
 use std::collections::HashSet;
 
+use dguesser_redis::RedisConnection;
 use redis::AsyncCommands;
 
 use crate::state::AppState;
@@ -46,31 +47,19 @@ impl CoPlayersCache {
 
     /// Invalidate co-player cache for a specific user
     #[allow(dead_code)]
-    pub async fn invalidate(client: &redis::Client, user_id: &str) {
+    pub async fn invalidate(redis: &RedisConnection, user_id: &str) {
         let key = Self::cache_key(user_id);
-        let mut conn = match client.get_multiplexed_async_connection().await {
-            Ok(conn) => conn,
-            Err(e) => {
-                tracing::warn!("Failed to connect to Redis for co-players invalidation: {}", e);
-                return;
-            }
-        };
+        let mut conn = redis.clone();
         if let Err(e) = conn.del::<_, ()>(&key).await {
             tracing::warn!("Failed to invalidate co-players cache: {}", e);
         }
     }
 
     /// Read from Redis cache
-    async fn get_cached(client: &redis::Client, user_id: &str) -> Option<HashSet<String>> {
+    async fn get_cached(redis: &RedisConnection, user_id: &str) -> Option<HashSet<String>> {
         let key = Self::cache_key(user_id);
 
-        let mut conn = match client.get_multiplexed_async_connection().await {
-            Ok(conn) => conn,
-            Err(e) => {
-                tracing::warn!("Failed to connect to Redis for co-players cache read: {}", e);
-                return None;
-            }
-        };
+        let mut conn = redis.clone();
 
         let data: Option<String> = match conn.get(&key).await {
             Ok(data) => data,
@@ -89,7 +78,7 @@ impl CoPlayersCache {
     }
 
     /// Write to Redis cache
-    async fn set_cached(client: &redis::Client, user_id: &str, co_players: &HashSet<String>) {
+    async fn set_cached(redis: &RedisConnection, user_id: &str, co_players: &HashSet<String>) {
         let key = Self::cache_key(user_id);
 
         let json = match serde_json::to_string(&co_players.iter().collect::<Vec<_>>()) {
@@ -100,13 +89,7 @@ impl CoPlayersCache {
             }
         };
 
-        let mut conn = match client.get_multiplexed_async_connection().await {
-            Ok(conn) => conn,
-            Err(e) => {
-                tracing::warn!("Failed to connect to Redis for co-players cache write: {}", e);
-                return;
-            }
-        };
+        let mut conn = redis.clone();
 
         if let Err(e) = conn.set_ex::<_, _, ()>(&key, &json, CO_PLAYERS_TTL_SECS).await {
             tracing::warn!("Failed to write co-players to cache: {}", e);
@@ -0,0 +1,78 @@
+//! Map heatmap caching with Redis
+
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+
+use dguesser_db::locations::HeatmapCell;
+use dguesser_redis::RedisConnection;
+
+/// TTL for cached heatmaps (10 minutes - aggregation scans the whole map)
+const HEATMAP_TTL_SECS: u64 = 600;
+
+/// Cached heatmap data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedHeatmap {
+    pub locations: Vec<HeatmapCell>,
+    pub guesses: Option<Vec<HeatmapCell>>,
+}
+
+/// Heatmap cache operations
+pub struct HeatmapCache;
+
+impl HeatmapCache {
+    /// Generate cache key for a heatmap query
+    fn cache_key(map_id: &str, precision: i32, include_guesses: bool) -> String {
+        format!("heatmap:{}:{}:{}", map_id, precision, include_guesses)
+    }
+
+    /// Get cached heatmap data
+    pub async fn get(
+        redis: &RedisConnection,
+        map_id: &str,
+        precision: i32,
+        include_guesses: bool,
+    ) -> Option<CachedHeatmap> {
+        let key = Self::cache_key(map_id, precision, include_guesses);
+
+        let mut conn = redis.clone();
+
+        let data: Option<String> = match conn.get(&key).await {
+            Ok(data) => data,
+            Err(e) => {
+                tracing::warn!("Failed to read from cache: {}", e);
+                return None;
+            }
+        };
+
+        data.and_then(|json| {
+            serde_json::from_str(&json)
+                .map_err(|e| tracing::warn!("Failed to deserialize cached heatmap: {}", e))
+                .ok()
+        })
+    }
+
+    /// Set cached heatmap data
+    pub async fn set(
+        redis: &RedisConnection,
+        map_id: &str,
+        precision: i32,
+        include_guesses: bool,
+        data: &CachedHeatmap,
+    ) {
+        let key = Self::cache_key(map_id, precision, include_guesses);
+
+        let json = match serde_json::to_string(data) {
+            Ok(json) => json,
+            Err(e) => {
+                tracing::warn!("Failed to serialize heatmap for cache: {}", e);
+                return;
+            }
+        };
+
+        let mut conn = redis.clone();
+
+        if let Err(e) = conn.set_ex::<_, _, ()>(&key, &json, HEATMAP_TTL_SECS).await {
+            tracing::warn!("Failed to write to cache: {}", e);
+        }
+    }
+}
@@ -1,14 +1,28 @@
 //! Leaderboard caching with Redis
+//!
+//! The top [`HOT_ENTRIES`] players of each leaderboard are kept in a sorted
+//! set, built from the database rollups on first read and rebuilt when it
+//! expires. The set is scored by leaderboard position so ties keep the
+//! database's tie-breaks; entry details live in a hash next to it. Pages and
+//! ranks past the cached players are read from the database.
 
+use dguesser_db::leaderboard::{self, Metric, Scope};
+use dguesser_redis::RedisConnection;
 use redis::AsyncCommands;
 use serde::{Deserialize, Serialize};
 
 use dguesser_protocol::api::leaderboard::{LeaderboardType, TimePeriod};
 
+use super::ResponseCache;
+use crate::state::AppState;
+
+/// Players kept in the hot set of each leaderboard
+const HOT_ENTRIES: i64 = 1000;
+
 /// TTL for all-time leaderboards (5 minutes)
-const ALL_TIME_TTL_SECS: u64 = 300;
+const ALL_TIME_TTL_SECS: i64 = 300;
 /// TTL for time-filtered leaderboards (30 seconds - more dynamic)
-const TIME_FILTERED_TTL_SECS: u64 = 30;
+const TIME_FILTERED_TTL_SECS: i64 = 30;
 
 /// Internal cached leaderboard entry (includes privacy flag, not sent to clients)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,140 +37,202 @@ pub struct CachedLeaderboardEntry {
     pub leaderboard_public: bool,
 }
 
-/// Cached leaderboard data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// One page of a leaderboard
+#[derive(Debug, Clone)]
 pub struct CachedLeaderboard {
     pub entries: Vec<CachedLeaderboardEntry>,
     pub total_players: i64,
 }
 
+/// Redis keys of one leaderboard's hot set
+struct HotKeys {
+    /// Sorted set of user IDs, scored by position
+    ranks: String,
+    /// Hash of user ID to entry JSON
+    entries: String,
+    /// Number of ranked players
+    total: String,
+}
+
 /// Leaderboard cache operations
 pub struct LeaderboardCache;
 
 impl LeaderboardCache {
-    /// Generate cache key for leaderboard query
-    fn cache_key(
-        lb_type: &LeaderboardType,
-        period: &TimePeriod,
-        limit: i64,
-        offset: i64,
-    ) -> String {
-        format!("leaderboard:{}:{}:{}:{}", lb_type.as_str(), period.as_str(), limit, offset)
+    /// Generate the cache keys of a leaderboard
+    fn cache_keys(lb_type: &LeaderboardType, period: &TimePeriod, scope: &Scope) -> HotKeys {
+        // The braces are a hash tag: all keys of one leaderboard land in
+        // the same Redis Cluster slot, which the MULTI rebuild needs
+        let base = format!(
+            "leaderboard:{{{}:{}:{}:{}:{}}}",
+            scope.org_id.as_deref().unwrap_or("-"),
+            lb_type.as_str(),
+            period.as_str(),
+            scope.map_id.as_deref().unwrap_or("*"),
+            scope.mode.map(|mode| mode.to_string()).unwrap_or_else(|| "*".to_string()),
+        );
+        HotKeys {
+            ranks: format!("{base}:ranks"),
+            entries: format!("{base}:entries"),
+            total: format!("{base}:total"),
+        }
     }
 
-    /// Get cached leaderboard data
-    pub async fn get(
-        client: &redis::Client,
+    /// Get a page of a leaderboard, from the hot set when it covers the page.
+    pub async fn get_page(
+        state: &AppState,
         lb_type: &LeaderboardType,
         period: &TimePeriod,
+        scope: &Scope,
         limit: i64,
         offset: i64,
-    ) -> Option<CachedLeaderboard> {
-        let key = Self::cache_key(lb_type, period, limit, offset);
+    ) -> Result<CachedLeaderboard, sqlx::Error> {
+        let metric = metric(lb_type);
 
-        let mut conn = match client.get_multiplexed_async_connection().await {
-            Ok(conn) => conn,
-            Err(e) => {
-                tracing::warn!("Failed to connect to Redis for cache read: {}", e);
-                return None;
-            }
-        };
+        if offset + limit > HOT_ENTRIES {
+            tracing::debug!("Leaderboard page past the hot set, fetching from DB");
+            let rows = leaderboard::get_top(state.db_read(), metric, scope, limit, offset).await?;
+            let total_players = leaderboard::count_ranked(state.db_read(), metric, scope).await?;
+            return Ok(CachedLeaderboard { entries: to_entries(rows, offset), total_players });
+        }
 
-        let data: Option<String> = match conn.get(&key).await {
-            Ok(data) => data,
-            Err(e) => {
-                tracing::warn!("Failed to read from cache: {}", e);
-                return None;
+        let keys = Self::cache_keys(lb_type, period, scope);
+        let mut conn = state.redis().clone();
+        match Self::read_page(&mut conn, &keys, limit, offset).await {
+            Ok(Some(page)) => {
+                tracing::debug!("Leaderboard cache hit");
+                return Ok(page);
             }
+            Ok(None) => tracing::debug!("Leaderboard cache miss, fetching from DB"),
+            Err(e) => tracing::warn!("Failed to read leaderboard from cache: {}", e),
+        }
+
+        let rows = leaderboard::get_top(state.db_read(), metric, scope, HOT_ENTRIES, 0).await?;
+        let total_players = leaderboard::count_ranked(state.db_read(), metric, scope).await?;
+        let entries = to_entries(rows, 0);
+
+        let ttl = match period {
+            TimePeriod::AllTime => ALL_TIME_TTL_SECS,
+            _ => TIME_FILTERED_TTL_SECS,
         };
+        if let Err(e) = Self::write_hot_set(&mut conn, &keys, &entries, total_players, ttl).await {
+            tracing::warn!("Failed to write leaderboard to cache: {}", e);
+        }
 
-        data.and_then(|json| {
-            serde_json::from_str(&json)
-                .map_err(|e| tracing::warn!("Failed to deserialize cached leaderboard: {}", e))
-                .ok()
-        })
+        let entries = entries.into_iter().skip(offset as usize).take(limit as usize).collect();
+        Ok(CachedLeaderboard { entries, total_players })
     }
 
-    /// Set cached leaderboard data
-    pub async fn set(
-        client: &redis::Client,
+    /// Get a user's rank and score, from the hot set when the user is in it.
+    pub async fn get_user_rank(
+        state: &AppState,
         lb_type: &LeaderboardType,
         period: &TimePeriod,
+        scope: &Scope,
+        user_id: &str,
+    ) -> Result<Option<(i64, i64)>, sqlx::Error> {
+        let keys = Self::cache_keys(lb_type, period, scope);
+        let mut conn = state.redis().clone();
+
+        let cached: redis::RedisResult<(Option<i64>, i64, Option<String>)> = redis::pipe()
+            .get(&keys.total)
+            .zcard(&keys.ranks)
+            .hget(&keys.entries, user_id)
+            .query_async(&mut conn)
+            .await;
+        match cached {
+            Ok((Some(_), _, Some(json))) => {
+                match serde_json::from_str::<CachedLeaderboardEntry>(&json) {
+                    Ok(entry) => return Ok(Some((entry.rank.into(), entry.score))),
+                    Err(e) => tracing::warn!("Failed to deserialize cached entry: {}", e),
+                }
+            }
+            // The hot set holds every ranked player, so the user isn't ranked
+            Ok((Some(total), cached, None)) if cached >= total => return Ok(None),
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Failed to read leaderboard rank from cache: {}", e),
+        }
+
+        leaderboard::get_user_rank(state.db_read(), metric(lb_type), scope, user_id).await
+    }
+
+    /// Read a page from the hot set. Returns `None` when the set isn't built.
+    async fn read_page(
+        conn: &mut RedisConnection,
+        keys: &HotKeys,
         limit: i64,
         offset: i64,
-        data: &CachedLeaderboard,
-    ) {
-        let key = Self::cache_key(lb_type, period, limit, offset);
-        let ttl = match period {
-            TimePeriod::AllTime => ALL_TIME_TTL_SECS,
-            _ => TIME_FILTERED_TTL_SECS,
+    ) -> redis::RedisResult<Option<CachedLeaderboard>> {
+        let (total_players, user_ids): (Option<i64>, Vec<String>) = redis::pipe()
+            .get(&keys.total)
+            .zrange(&keys.ranks, offset as isize, (offset + limit - 1) as isize)
+            .query_async(conn)
+            .await?;
+        let Some(total_players) = total_players else {
+            return Ok(None);
         };
+        if user_ids.is_empty() {
+            return Ok(Some(CachedLeaderboard { entries: Vec::new(), total_players }));
+        }
 
-        let json = match serde_json::to_string(data) {
-            Ok(json) => json,
-            Err(e) => {
-                tracing::warn!("Failed to serialize leaderboard for cache: {}", e);
-                return;
-            }
-        };
+        let entries: Vec<Option<String>> =
+            redis::cmd("HMGET").arg(&keys.entries).arg(&user_ids).query_async(conn).await?;
+        let entries: Option<Vec<CachedLeaderboardEntry>> = entries
+            .into_iter()
+            .map(|json| json.and_then(|json| serde_json::from_str(&json).ok()))
+            .collect();
 
-        let mut conn = match client.get_multiplexed_async_connection().await {
-            Ok(conn) => conn,
-            Err(e) => {
-                tracing::warn!("Failed to connect to Redis for cache write: {}", e);
-                return;
-            }
-        };
+        // A set expiring between the two reads is treated as a miss
+        Ok(entries.map(|entries| CachedLeaderboard { entries, total_players }))
+    }
+
+    /// Replace the hot set of a leaderboard.
+    async fn write_hot_set(
+        conn: &mut RedisConnection,
+        keys: &HotKeys,
+        entries: &[CachedLeaderboardEntry],
+        total_players: i64,
+        ttl: i64,
+    ) -> redis::RedisResult<()> {
+        let mut pipe = redis::pipe();
+        pipe.atomic().del(&keys.ranks).del(&keys.entries);
 
-        if let Err(e) = conn.set_ex::<_, _, ()>(&key, &json, ttl).await {
-            tracing::warn!("Failed to write to cache: {}", e);
+        if !entries.is_empty() {
+            let ranks: Vec<(u32, &str)> =
+                entries.iter().map(|entry| (entry.rank, entry.user_id.as_str())).collect();
+            let details: Vec<(&str, String)> = entries
+                .iter()
+                .map(|entry| {
+                    (entry.user_id.as_str(), serde_json::to_string(entry).unwrap_or_default())
+                })
+                .collect();
+            pipe.zadd_multiple(&keys.ranks, &ranks)
+                .expire(&keys.ranks, ttl)
+                .hset_multiple(&keys.entries, &details)
+                .expire(&keys.entries, ttl);
         }
+        pipe.set_ex(&keys.total, total_players, ttl as u64);
+
+        pipe.query_async(conn).await
     }
 
     /// Invalidate all leaderboard caches (call after game completion)
-    #[allow(dead_code)]
-    pub async fn invalidate_all(client: &redis::Client) {
-        let mut conn = match client.get_multiplexed_async_connection().await {
-            Ok(conn) => conn,
+    pub async fn invalidate_all(redis: &RedisConnection) {
+        ResponseCache::invalidate(redis, "leaderboard").await;
+
+        // Use SCAN to find all leaderboard keys and delete them
+        let keys = match redis.scan_match("leaderboard:*").await {
+            Ok(keys) => keys,
             Err(e) => {
-                tracing::warn!("Failed to connect to Redis for cache invalidation: {}", e);
+                tracing::warn!("Failed to scan leaderboard keys: {}", e);
                 return;
             }
         };
 
-        // Use SCAN to find all leaderboard keys and delete them
-        let pattern = "leaderboard:*";
-        let mut keys: Vec<String> = Vec::new();
-        let mut cursor: u64 = 0;
-        loop {
-            let result: Result<(u64, Vec<String>), _> = redis::cmd("SCAN")
-                .arg(cursor)
-                .arg("MATCH")
-                .arg(pattern)
-                .arg("COUNT")
-                .arg(100)
-                .query_async(&mut conn)
-                .await;
-            match result {
-                Ok((next_cursor, batch)) => {
-                    keys.extend(batch);
-                    cursor = next_cursor;
-                    if cursor == 0 {
-                        break;
-                    }
-                }
-                Err(e) => {
-                    tracing::warn!("Failed to scan leaderboard keys: {}", e);
-                    return;
-                }
-            }
-        }
-
         if keys.is_empty() {
             return;
         }
 
-        if let Err(e) = conn.del::<_, ()>(&keys).await {
+        if let Err(e) = redis.clone().del::<_, ()>(&keys).await {
             tracing::warn!("Failed to delete cached leaderboards: {}", e);
         } else {
             tracing::debug!("Invalidated {} leaderboard cache entries", keys.len());
@@ -164,6 +240,33 @@ impl LeaderboardCache {
     }
 }
 
+/// Database metric of a leaderboard type
+fn metric(lb_type: &LeaderboardType) -> Metric {
+    match lb_type {
+        LeaderboardType::TotalScore => Metric::TotalScore,
+        LeaderboardType::BestGame => Metric::BestScore,
+        LeaderboardType::GamesPlayed => Metric::GamesPlayed,
+        LeaderboardType::AverageScore => Metric::AverageScore,
+        LeaderboardType::Streak => Metric::Streak,
+    }
+}
+
+/// Number database rows from `offset`
+fn to_entries(rows: Vec<dguesser_db::LeaderboardRow>, offset: i64) -> Vec<CachedLeaderboardEntry> {
+    rows.into_iter()
+        .enumerate()
+        .map(|(i, row)| CachedLeaderboardEntry {
+            rank: (offset as u32) + (i as u32) + 1,
+            user_id: row.user_id,
+            display_name: row.display_name,
+            avatar_url: row.avatar_url,
+            score: row.score,
+            games_played: row.games_count,
+            leaderboard_public: row.leaderboard_public,
+        })
+        .collect()
+}
+
 /// Helper trait for type-safe string conversion
 trait AsStr {
     fn as_str(&self) -> &'static str;
@@ -176,6 +279,7 @@ impl AsStr for LeaderboardType {
             LeaderboardType::BestGame => "best_game",
             LeaderboardType::GamesPlayed => "games_played",
             LeaderboardType::AverageScore => "average_score",
+            LeaderboardType::Streak => "streak",
         }
     }
 }
@@ -0,0 +1,69 @@
+//! Per-location guess statistics caching with Redis
+
+use redis::AsyncCommands;
+
+use dguesser_core::game::LocationGuessStats;
+use dguesser_redis::RedisConnection;
+
+/// TTL for cached statistics (1 hour - the aggregation job refreshes hourly)
+const LOCATION_STATS_TTL_SECS: u64 = 3600;
+
+/// Location statistics cache operations
+///
+/// Locations without statistics are cached too, so unplayed locations do not
+/// hit the database on every guess.
+pub struct LocationStatsCache;
+
+impl LocationStatsCache {
+    /// Generate cache key for a location
+    fn cache_key(location_id: &str) -> String {
+        format!("location_stats:{}", location_id)
+    }
+
+    /// Get cached statistics; `Some(None)` means the location has none.
+    pub async fn get(
+        redis: &RedisConnection,
+        location_id: &str,
+    ) -> Option<Option<LocationGuessStats>> {
+        let key = Self::cache_key(location_id);
+
+        let mut conn = redis.clone();
+
+        let data: Option<String> = match conn.get(&key).await {
+            Ok(data) => data,
+            Err(e) => {
+                tracing::warn!("Failed to read from cache: {}", e);
+                return None;
+            }
+        };
+
+        data.and_then(|json| {
+            serde_json::from_str(&json)
+                .map_err(|e| tracing::warn!("Failed to deserialize cached location stats: {}", e))
+                .ok()
+        })
+    }
+
+    /// Set cached statistics (or their absence)
+    pub async fn set(
+        redis: &RedisConnection,
+        location_id: &str,
+        stats: Option<&LocationGuessStats>,
+    ) {
+        let key = Self::cache_key(location_id);
+
+        let json = match serde_json::to_string(&stats) {
+            Ok(json) => json,
+            Err(e) => {
+                tracing::warn!("Failed to serialize location stats for cache: {}", e);
+                return;
+            }
+        };
+
+        let mut conn = redis.clone();
+
+        if let Err(e) = conn.set_ex::<_, _, ()>(&key, &json, LOCATION_STATS_TTL_SECS).await {
+            tracing::warn!("Failed to write to cache: {}", e);
+        }
+    }
+}
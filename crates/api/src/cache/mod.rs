@@ -1,8 +1,15 @@
 //! Caching utilities
 
 pub mod co_players;
+pub mod heatmap;
 pub mod leaderboard;
+pub mod location_stats;
+pub mod response;
+pub mod streetview_metadata;
 
 pub use co_players::CoPlayersCache;
-#[allow(unused_imports)]
+pub use heatmap::{CachedHeatmap, HeatmapCache};
 pub use leaderboard::LeaderboardCache;
+pub use location_stats::LocationStatsCache;
+pub use response::{ResponseCache, response_cache};
+pub use streetview_metadata::StreetViewMetadataCache;
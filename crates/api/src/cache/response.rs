@@ -0,0 +1,476 @@
+//! Response caching for read-heavy endpoints
+//!
+//! [`response_cache`] serves GET requests to the routes in [`CACHED_ROUTES`]
+//! from Redis:
+//! - fresh entries are returned as they are
+//! - stale entries are returned right away while one request refreshes them
+//!   in the background (stale-while-revalidate)
+//! - every cached response carries an `ETag`, and a matching
+//!   `If-None-Match` gets `304 Not Modified` without a body
+//!
+//! Entries belong to a namespace with a generation counter that is part of
+//! every key. Bumping the counter orphans all of the namespace's entries at
+//! once: successful writes below the prefixes in [`INVALIDATIONS`] do this
+//! automatically, and handlers can call [`ResponseCache::invalidate`].
+//!
+//! Route lifetimes can be overridden per namespace by a runtime config reload.
+
+use axum::{
+    body::Body,
+    extract::{OriginalUri, State},
+    http::{HeaderMap, HeaderValue, Method, Request, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use dguesser_auth::AuthUser;
+use dguesser_protocol::api::admin::CacheTtl;
+use dguesser_redis::RedisConnection;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::i18n::Locale;
+use crate::middleware::CurrentOrg;
+use crate::state::AppState;
+
+/// Largest response body that is cached
+const MAX_CACHED_BODY_BYTES: usize = 1024 * 1024;
+
+/// How long one request may spend refreshing a stale entry before another
+/// request is allowed to try
+const REFRESH_LOCK_SECS: u64 = 30;
+
+/// Who may share a cached response
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheScope {
+    /// Same response for every caller
+    Public,
+    /// Same response for every signed-in caller; anonymous requests skip the
+    /// cache so the handler can reject them
+    Authenticated,
+    /// Response depends on the caller; cached per user, with anonymous
+    /// callers sharing one entry
+    PerUser,
+}
+
+/// A cached endpoint
+#[derive(Debug)]
+pub struct CachedRoute {
+    /// Path below `/api/v1`; `*` matches one segment
+    pub pattern: &'static str,
+    /// Namespace used for invalidation
+    pub namespace: &'static str,
+    pub scope: CacheScope,
+    /// Entries younger than this are served without a refresh
+    pub fresh_secs: u64,
+    /// How much longer stale entries are served while being refreshed
+    pub stale_secs: u64,
+}
+
+/// Endpoints served from the response cache
+pub const CACHED_ROUTES: &[CachedRoute] = &[
+    CachedRoute {
+        pattern: "/leaderboard",
+        namespace: "leaderboard",
+        scope: CacheScope::PerUser,
+        fresh_secs: 30,
+        stale_secs: 300,
+    },
+    CachedRoute {
+        pattern: "/maps",
+        namespace: "maps",
+        scope: CacheScope::PerUser,
+        fresh_secs: 60,
+        stale_secs: 600,
+    },
+    CachedRoute {
+        pattern: "/locations/countries",
+        namespace: "locations",
+        scope: CacheScope::Authenticated,
+        fresh_secs: 600,
+        stale_secs: 3600,
+    },
+    CachedRoute {
+        pattern: "/locations/countries/*/subdivisions",
+        namespace: "locations",
+        scope: CacheScope::Authenticated,
+        fresh_secs: 600,
+        stale_secs: 3600,
+    },
+    CachedRoute {
+        pattern: "/games/presets",
+        namespace: "presets",
+        scope: CacheScope::Public,
+        fresh_secs: 3600,
+        stale_secs: 86400,
+    },
+];
+
+/// Successful writes below a path prefix (below `/api/v1`) invalidate a
+/// namespace
+pub const INVALIDATIONS: &[(&str, &str)] =
+    &[("/maps", "maps"), ("/admin/maps", "maps"), ("/admin/locations", "locations")];
+
+/// A cached response as stored in Redis
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedResponse {
+    content_type: Option<String>,
+    etag: String,
+    body: String,
+    /// Unix time the response was produced
+    stored_at: i64,
+}
+
+impl CachedResponse {
+    /// Build the response, or `304 Not Modified` if the client already has it.
+    fn into_response(self, scope: CacheScope, if_none_match: Option<&str>) -> Response {
+        let mut response = if if_none_match.is_some_and(|tags| etag_matches(tags, &self.etag)) {
+            StatusCode::NOT_MODIFIED.into_response()
+        } else {
+            let mut response = Response::new(Body::from(self.body));
+            if let Some(value) = self.content_type.and_then(|ct| HeaderValue::from_str(&ct).ok()) {
+                response.headers_mut().insert(header::CONTENT_TYPE, value);
+            }
+            response
+        };
+        set_cache_headers(response.headers_mut(), scope, &self.etag);
+        response
+    }
+}
+
+/// Response cache operations
+pub struct ResponseCache;
+
+impl ResponseCache {
+    fn generation_key(namespace: &str) -> String {
+        format!("respcache:{}:gen", namespace)
+    }
+
+    /// Cache key for a request, or `None` if Redis is unavailable.
+    async fn cache_key(
+        redis: &RedisConnection,
+        namespace: &str,
+        viewer: &str,
+        path_and_query: &str,
+    ) -> Option<String> {
+        let mut conn = redis.clone();
+
+        let generation: Option<u64> = match conn.get(Self::generation_key(namespace)).await {
+            Ok(generation) => generation,
+            Err(e) => {
+                tracing::warn!("Failed to read response cache generation: {}", e);
+                return None;
+            }
+        };
+
+        Some(format!(
+            "respcache:{}:{}:{}:{}",
+            namespace,
+            generation.unwrap_or(0),
+            viewer,
+            path_and_query
+        ))
+    }
+
+    async fn get(redis: &RedisConnection, key: &str) -> Option<CachedResponse> {
+        let mut conn = redis.clone();
+        let data: Option<String> = match conn.get(key).await {
+            Ok(data) => data,
+            Err(e) => {
+                tracing::warn!("Failed to read from response cache: {}", e);
+                return None;
+            }
+        };
+
+        data.and_then(|json| {
+            serde_json::from_str(&json)
+                .map_err(|e| tracing::warn!("Failed to deserialize cached response: {}", e))
+                .ok()
+        })
+    }
+
+    async fn set(redis: &RedisConnection, key: &str, entry: &CachedResponse, ttl_secs: u64) {
+        let json = match serde_json::to_string(entry) {
+            Ok(json) => json,
+            Err(e) => {
+                tracing::warn!("Failed to serialize response for cache: {}", e);
+                return;
+            }
+        };
+
+        let mut conn = redis.clone();
+
+        if let Err(e) = conn.set_ex::<_, _, ()>(key, &json, ttl_secs).await {
+            tracing::warn!("Failed to write to response cache: {}", e);
+        }
+    }
+
+    /// Claim the refresh of a stale entry so only one request performs it.
+    async fn claim_refresh(redis: &RedisConnection, key: &str) -> bool {
+        let mut conn = redis.clone();
+        redis::cmd("SET")
+            .arg(format!("{}:refresh", key))
+            .arg("1")
+            .arg("NX")
+            .arg("EX")
+            .arg(REFRESH_LOCK_SECS)
+            .query_async::<Option<String>>(&mut conn)
+            .await
+            .map(|reply| reply.is_some())
+            .unwrap_or(false)
+    }
+
+    /// Drop every cached response in a namespace (call after writes that
+    /// change what the namespace's endpoints return)
+    pub async fn invalidate(redis: &RedisConnection, namespace: &str) {
+        let mut conn = redis.clone();
+
+        if let Err(e) = conn.incr::<_, _, ()>(Self::generation_key(namespace), 1).await {
+            tracing::warn!("Failed to invalidate {} response cache: {}", namespace, e);
+        } else {
+            tracing::debug!("Invalidated {} response cache", namespace);
+        }
+    }
+}
+
+/// Response cache middleware
+///
+/// Must run inside the session, locale and organization middleware, which put
+/// the signed-in user, the locale and the organization in the request
+/// extensions, and inside rate limiting so cached responses are still
+/// counted.
+pub async fn response_cache(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let uri = request
+        .extensions()
+        .get::<OriginalUri>()
+        .map(|original| original.0.clone())
+        .unwrap_or_else(|| request.uri().clone());
+    let path = uri.path().strip_prefix("/api/v1").unwrap_or(uri.path()).to_string();
+
+    if request.method() != Method::GET {
+        let is_write = request.method() != Method::HEAD && request.method() != Method::OPTIONS;
+        let response = next.run(request).await;
+        if is_write && response.status().is_success() {
+            for namespace in invalidated_namespaces(&path) {
+                ResponseCache::invalidate(state.redis(), namespace).await;
+            }
+        }
+        return response;
+    }
+
+    let Some(route) = cached_route(&path) else {
+        return next.run(request).await;
+    };
+    let ttl = state
+        .cache_ttl_override(route.namespace)
+        .unwrap_or(CacheTtl { fresh_secs: route.fresh_secs, stale_secs: route.stale_secs });
+    let user_id = request.extensions().get::<AuthUser>().map(|auth| auth.user_id.clone());
+    let viewer = match (route.scope, user_id) {
+        (CacheScope::Public, _) => "all".to_string(),
+        (CacheScope::Authenticated, Some(_)) => "auth".to_string(),
+        (CacheScope::Authenticated, None) => return next.run(request).await,
+        (CacheScope::PerUser, Some(user_id)) => user_id,
+        (CacheScope::PerUser, None) => "anon".to_string(),
+    };
+    // Localized responses differ per language, and every organization sees
+    // its own maps and leaderboards
+    let locale = request.extensions().get::<Locale>().copied().unwrap_or_default();
+    let org = request.extensions().get::<CurrentOrg>().cloned().unwrap_or_default();
+    let viewer = format!("{viewer}:{}:{}", locale.code(), org.key());
+    let path_and_query = uri.path_and_query().map_or(uri.path(), |pq| pq.as_str());
+    let Some(key) =
+        ResponseCache::cache_key(state.redis(), route.namespace, &viewer, path_and_query).await
+    else {
+        return next.run(request).await;
+    };
+    let if_none_match = request
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+
+    if let Some(entry) = ResponseCache::get(state.redis(), &key).await {
+        let age = Utc::now().timestamp() - entry.stored_at;
+        if age >= ttl.fresh_secs as i64 && ResponseCache::claim_refresh(state.redis(), &key).await {
+            tokio::spawn(async move {
+                let response = next.run(request).await;
+                let _ = store(&state, &key, ttl, response).await;
+            });
+        }
+        return entry.into_response(route.scope, if_none_match.as_deref());
+    }
+
+    let response = next.run(request).await;
+    match store(&state, &key, ttl, response).await {
+        Ok(entry) => entry.into_response(route.scope, if_none_match.as_deref()),
+        Err(response) => response,
+    }
+}
+
+/// Cache a successful response. Responses that can't be cached are handed
+/// back unchanged.
+async fn store(
+    state: &AppState,
+    key: &str,
+    ttl: CacheTtl,
+    response: Response,
+) -> Result<CachedResponse, Response> {
+    if response.status() != StatusCode::OK || response.headers().contains_key(header::SET_COOKIE) {
+        return Err(response);
+    }
+
+    let (parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::warn!("Failed to buffer response for cache: {}", e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+    };
+    let body = match std::str::from_utf8(&bytes) {
+        Ok(body) if bytes.len() <= MAX_CACHED_BODY_BYTES => body.to_string(),
+        _ => return Err(Response::from_parts(parts, Body::from(bytes))),
+    };
+
+    let entry = CachedResponse {
+        content_type: parts
+            .headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string),
+        etag: etag_for(body.as_bytes()),
+        body,
+        stored_at: Utc::now().timestamp(),
+    };
+    ResponseCache::set(state.redis(), key, &entry, ttl.fresh_secs + ttl.stale_secs).await;
+    Ok(entry)
+}
+
+/// The cached route matching a path (below `/api/v1`), if any
+fn cached_route(path: &str) -> Option<&'static CachedRoute> {
+    CACHED_ROUTES.iter().find(|route| path_matches(route.pattern, path))
+}
+
+/// Namespaces invalidated by a successful write to a path (below `/api/v1`)
+fn invalidated_namespaces(path: &str) -> impl Iterator<Item = &'static str> + '_ {
+    INVALIDATIONS
+        .iter()
+        .filter(move |(prefix, _)| {
+            path.strip_prefix(prefix).is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        })
+        .map(|(_, namespace)| *namespace)
+}
+
+fn path_matches(pattern: &str, path: &str) -> bool {
+    let path = path.strip_suffix('/').filter(|p| !p.is_empty()).unwrap_or(path);
+    let mut pattern_segments = pattern.split('/');
+    let mut path_segments = path.split('/');
+    loop {
+        match (pattern_segments.next(), path_segments.next()) {
+            (None, None) => return true,
+            (Some("*"), Some(segment)) if !segment.is_empty() => {}
+            (Some(expected), Some(segment)) if expected == segment => {}
+            _ => return false,
+        }
+    }
+}
+
+/// Strong ETag derived from the response body
+fn etag_for(body: &[u8]) -> String {
+    let digest = Sha256::digest(body);
+    let hex: String = digest[..16].iter().map(|b| format!("{:02x}", b)).collect();
+    format!("\"{}\"", hex)
+}
+
+/// Whether an `If-None-Match` header value matches an ETag (weak comparison)
+fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    let etag = etag.trim_start_matches("W/");
+    if_none_match
+        .split(',')
+        .map(str::trim)
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+}
+
+fn set_cache_headers(headers: &mut HeaderMap, scope: CacheScope, etag: &str) {
+    if let Ok(value) = HeaderValue::from_str(etag) {
+        headers.insert(header::ETAG, value);
+    }
+    // Clients keep a copy but check back every time, so the ETag decides
+    let cache_control = match scope {
+        CacheScope::Public => "public, no-cache",
+        CacheScope::Authenticated | CacheScope::PerUser => "private, no-cache",
+    };
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static(cache_control));
+    if scope != CacheScope::Public {
+        headers.insert(header::VARY, HeaderValue::from_static("cookie"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cached_route_matching() {
+        assert_eq!(cached_route("/leaderboard").map(|r| r.namespace), Some("leaderboard"));
+        assert_eq!(cached_route("/maps/").map(|r| r.namespace), Some("maps"));
+        assert_eq!(
+            cached_route("/locations/countries/FR/subdivisions").map(|r| r.namespace),
+            Some("locations")
+        );
+        assert!(cached_route("/maps/map_123").is_none());
+        assert!(cached_route("/maps/favorites").is_none());
+        assert!(cached_route("/locations/countries//subdivisions").is_none());
+        assert!(cached_route("/games/presets/extra").is_none());
+    }
+
+    #[test]
+    fn test_writes_invalidate_namespaces() {
+        let namespaces = |path| invalidated_namespaces(path).collect::<Vec<_>>();
+        assert_eq!(namespaces("/maps"), vec!["maps"]);
+        assert_eq!(namespaces("/maps/map_123/like"), vec!["maps"]);
+        assert_eq!(namespaces("/admin/maps/map_123/default"), vec!["maps"]);
+        assert_eq!(namespaces("/admin/locations/loc_1/review"), vec!["locations"]);
+        assert!(namespaces("/mapsearch").is_empty());
+        assert!(namespaces("/games").is_empty());
+    }
+
+    #[test]
+    fn test_etag_matching() {
+        let etag = etag_for(b"{\"maps\":[]}");
+        assert_eq!(etag, etag_for(b"{\"maps\":[]}"));
+        assert_ne!(etag, etag_for(b"{\"maps\":[1]}"));
+        assert_eq!(etag.len(), 34);
+
+        assert!(etag_matches(&etag, &etag));
+        assert!(etag_matches(&format!("W/{}", etag), &etag));
+        assert!(etag_matches(&format!("\"other\", {}", etag), &etag));
+        assert!(etag_matches("*", &etag));
+        assert!(!etag_matches("\"other\"", &etag));
+    }
+
+    #[test]
+    fn test_not_modified_when_etag_matches() {
+        let entry = CachedResponse {
+            content_type: Some("application/json".to_string()),
+            etag: etag_for(b"{}"),
+            body: "{}".to_string(),
+            stored_at: 0,
+        };
+
+        let response = entry.clone().into_response(CacheScope::PerUser, Some(&entry.etag));
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[header::ETAG], entry.etag.as_str());
+        assert_eq!(response.headers()[header::VARY], "cookie");
+
+        let response = entry.clone().into_response(CacheScope::Public, None);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+        assert_eq!(response.headers()[header::CACHE_CONTROL], "public, no-cache");
+    }
+}
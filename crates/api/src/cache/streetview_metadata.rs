@@ -0,0 +1,71 @@
+//! Street View panorama metadata caching with Redis
+
+use redis::AsyncCommands;
+
+use dguesser_protocol::api::streetview::StreetViewMetadataResponse;
+use dguesser_redis::RedisConnection;
+
+/// TTL for panoramas that exist (7 days - panoramas rarely move)
+const FOUND_TTL_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// TTL for panoramas that are gone (1 day, in case the lookup was wrong)
+const MISSING_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// Panorama metadata cache operations
+///
+/// Missing panoramas are cached too, so a dead panorama does not cost a
+/// metadata request every time a client asks about it.
+pub struct StreetViewMetadataCache;
+
+impl StreetViewMetadataCache {
+    /// Generate cache key for a panorama
+    fn cache_key(panorama_id: &str) -> String {
+        format!("streetview_metadata:{}", panorama_id)
+    }
+
+    /// Get cached metadata
+    pub async fn get(
+        redis: &RedisConnection,
+        panorama_id: &str,
+    ) -> Option<StreetViewMetadataResponse> {
+        let key = Self::cache_key(panorama_id);
+
+        let mut conn = redis.clone();
+
+        let data: Option<String> = match conn.get(&key).await {
+            Ok(data) => data,
+            Err(e) => {
+                tracing::warn!("Failed to read from cache: {}", e);
+                return None;
+            }
+        };
+
+        data.and_then(|json| {
+            serde_json::from_str(&json)
+                .map_err(|e| {
+                    tracing::warn!("Failed to deserialize cached panorama metadata: {}", e)
+                })
+                .ok()
+        })
+    }
+
+    /// Set cached metadata; `found` picks the TTL
+    pub async fn set(redis: &RedisConnection, metadata: &StreetViewMetadataResponse, found: bool) {
+        let key = Self::cache_key(&metadata.panorama_id);
+        let ttl = if found { FOUND_TTL_SECS } else { MISSING_TTL_SECS };
+
+        let json = match serde_json::to_string(metadata) {
+            Ok(json) => json,
+            Err(e) => {
+                tracing::warn!("Failed to serialize panorama metadata for cache: {}", e);
+                return;
+            }
+        };
+
+        let mut conn = redis.clone();
+
+        if let Err(e) = conn.set_ex::<_, _, ()>(&key, &json, ttl).await {
+            tracing::warn!("Failed to write to cache: {}", e);
+        }
+    }
+}
@@ -0,0 +1,153 @@
+//! CAPTCHA verification
+//!
+//! Verifies Cloudflare Turnstile or hCaptcha tokens with the provider's
+//! siteverify API. Both providers take the same form fields and answer with
+//! the same `success` flag, so one client covers them. Which requests need a
+//! CAPTCHA is decided by [`crate::middleware::captcha`].
+
+use std::time::Duration;
+
+use serde::Deserialize;
+
+/// CAPTCHA provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptchaProvider {
+    Turnstile,
+    HCaptcha,
+}
+
+impl CaptchaProvider {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "turnstile" => Some(Self::Turnstile),
+            "hcaptcha" => Some(Self::HCaptcha),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Turnstile => "turnstile",
+            Self::HCaptcha => "hcaptcha",
+        }
+    }
+
+    fn verify_url(self) -> &'static str {
+        match self {
+            Self::Turnstile => "https://challenges.cloudflare.com/turnstile/v0/siteverify",
+            Self::HCaptcha => "https://api.hcaptcha.com/siteverify",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct VerifyResponse {
+    success: bool,
+    #[serde(default, rename = "error-codes")]
+    error_codes: Vec<String>,
+}
+
+/// CAPTCHA client. Cheap to clone.
+#[derive(Clone)]
+pub struct CaptchaClient {
+    http: reqwest::Client,
+    provider: CaptchaProvider,
+    secret_key: String,
+    site_key: String,
+    /// Guest accounts from one IP in a day before a CAPTCHA is required
+    /// (0 requires one for every guest)
+    guest_threshold: u32,
+}
+
+impl CaptchaClient {
+    pub fn new(
+        provider: CaptchaProvider,
+        secret_key: String,
+        site_key: String,
+        guest_threshold: u32,
+    ) -> Self {
+        let http =
+            reqwest::Client::builder().timeout(Duration::from_secs(10)).build().unwrap_or_default();
+        Self { http, provider, secret_key, site_key, guest_threshold }
+    }
+
+    pub fn provider(&self) -> CaptchaProvider {
+        self.provider
+    }
+
+    /// Public site key the frontend renders the widget with
+    pub fn site_key(&self) -> &str {
+        &self.site_key
+    }
+
+    /// Whether a network that created `guests_today` guest accounts today
+    /// must solve a CAPTCHA for the next one.
+    pub fn required_after(&self, guests_today: u32) -> bool {
+        guests_today >= self.guest_threshold
+    }
+
+    /// Check a token the client got from the widget.
+    ///
+    /// Returns `Ok(false)` when the provider rejects the token, and an error
+    /// when the provider could not be asked.
+    pub async fn verify(&self, token: &str, remote_ip: Option<&str>) -> Result<bool, String> {
+        let mut form = vec![("secret", self.secret_key.as_str()), ("response", token)];
+        if let Some(ip) = remote_ip {
+            form.push(("remoteip", ip));
+        }
+
+        let response = self
+            .http
+            .post(self.provider.verify_url())
+            .form(&form)
+            .send()
+            .await
+            .map_err(|e| e.without_url().to_string())?;
+        let result =
+            response.json::<VerifyResponse>().await.map_err(|e| e.without_url().to_string())?;
+        if !result.success {
+            tracing::debug!(
+                provider = self.provider.as_str(),
+                errors = ?result.error_codes,
+                "CAPTCHA token rejected"
+            );
+        }
+        Ok(result.success)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_provider() {
+        assert_eq!(CaptchaProvider::parse("Turnstile"), Some(CaptchaProvider::Turnstile));
+        assert_eq!(CaptchaProvider::parse("hcaptcha"), Some(CaptchaProvider::HCaptcha));
+        assert_eq!(CaptchaProvider::parse("recaptcha"), None);
+    }
+
+    #[test]
+    fn test_required_after() {
+        let client = |threshold| {
+            CaptchaClient::new(
+                CaptchaProvider::Turnstile,
+                "secret".to_string(),
+                "site".to_string(),
+                threshold,
+            )
+        };
+        assert!(client(0).required_after(0));
+        assert!(!client(3).required_after(2));
+        assert!(client(3).required_after(3));
+    }
+
+    #[test]
+    fn test_parse_verify_response() {
+        let response: VerifyResponse =
+            serde_json::from_str(r#"{"success":false,"error-codes":["invalid-input-response"]}"#)
+                .unwrap();
+        assert!(!response.success);
+        assert_eq!(response.error_codes, ["invalid-input-response"]);
+    }
+}
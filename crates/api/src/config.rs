@@ -1,8 +1,19 @@
 //! API server configuration
 
 use std::env;
+use std::time::Duration;
 
 use anyhow::{Context, Result};
+use dguesser_core::join_code::{
+    DEFAULT_JOIN_CODE_LENGTH, MAX_JOIN_CODE_LENGTH, MIN_JOIN_CODE_LENGTH,
+};
+use dguesser_mailer::MailerConfig;
+use dguesser_push::PushConfig;
+use dguesser_redis::RedisConfig;
+
+use crate::captcha::{CaptchaClient, CaptchaProvider};
+use crate::middleware::rate_limit::{RateLimitRoutes, RateLimitTiers};
+use crate::storage::StorageConfig;
 
 /// Location provider type.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -34,6 +45,10 @@ pub struct R2LocationConfig {
     pub version: String,
     /// Maximum disabled hashes to cache in memory
     pub max_disabled_cache: usize,
+    /// Memory budget for cached pack ranges in MiB (0 disables the cache)
+    pub range_cache_mb: usize,
+    /// Share cached pack ranges between instances through Redis
+    pub range_cache_redis: bool,
 }
 
 impl R2LocationConfig {
@@ -45,8 +60,13 @@ impl R2LocationConfig {
             .ok()
             .and_then(|s| s.parse().ok())
             .unwrap_or(200_000);
+        let range_cache_mb =
+            env::var("LOCATION_RANGE_CACHE_MB").ok().and_then(|s| s.parse().ok()).unwrap_or(64);
+        let range_cache_redis = env::var("LOCATION_RANGE_CACHE_REDIS")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(false);
 
-        Some(Self { base_url, version, max_disabled_cache })
+        Some(Self { base_url, version, max_disabled_cache, range_cache_mb, range_cache_redis })
     }
 
     /// Get the local path (if local).
@@ -61,14 +81,178 @@ impl R2LocationConfig {
     }
 }
 
+/// Background location health checker configuration.
+#[derive(Debug, Clone)]
+pub struct LocationHealthConfig {
+    /// Google Maps API key for the Street View metadata API
+    pub api_key: String,
+    /// Seconds between runs
+    pub interval_secs: u64,
+    /// Locations checked per run
+    pub sample_size: i64,
+    /// Days between rechecks of inactive locations for re-approval (0 disables)
+    pub recheck_days: i32,
+}
+
+impl LocationHealthConfig {
+    /// Create from environment variables. Disabled when no API key is set.
+    pub fn from_env() -> Option<Self> {
+        let api_key = env::var("GOOGLE_MAPS_API_KEY").ok().filter(|k| !k.is_empty())?;
+        let interval_secs = env::var("LOCATION_HEALTH_INTERVAL_SECS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .filter(|secs| *secs > 0)
+            .unwrap_or(3600);
+        let sample_size = env::var("LOCATION_HEALTH_SAMPLE_SIZE")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .filter(|n| *n > 0)
+            .unwrap_or(100);
+        let recheck_days = env::var("LOCATION_HEALTH_RECHECK_DAYS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .filter(|days| *days >= 0)
+            .unwrap_or(7);
+
+        Some(Self { api_key, interval_secs, sample_size, recheck_days })
+    }
+}
+
+/// CAPTCHA configuration for guest-heavy endpoints.
+#[derive(Debug, Clone)]
+pub struct CaptchaConfig {
+    pub provider: CaptchaProvider,
+    /// Secret key for the provider's siteverify API
+    pub secret_key: String,
+    /// Public site key the frontend renders the widget with
+    pub site_key: String,
+    /// Guest accounts from one IP in a day before a CAPTCHA is required
+    /// (0 requires one for every guest)
+    pub guest_threshold: u32,
+}
+
+impl CaptchaConfig {
+    /// Create from environment variables. Disabled unless `CAPTCHA_PROVIDER`
+    /// is set.
+    pub fn from_env() -> Result<Option<Self>> {
+        let Some(provider) = env::var("CAPTCHA_PROVIDER").ok().filter(|p| !p.is_empty()) else {
+            return Ok(None);
+        };
+        let provider = CaptchaProvider::parse(&provider)
+            .with_context(|| format!("Unknown CAPTCHA_PROVIDER '{provider}'"))?;
+        let secret_key = env::var("CAPTCHA_SECRET_KEY")
+            .ok()
+            .filter(|k| !k.is_empty())
+            .context("CAPTCHA_SECRET_KEY is required when CAPTCHA_PROVIDER is set")?;
+        let site_key = env::var("CAPTCHA_SITE_KEY")
+            .ok()
+            .filter(|k| !k.is_empty())
+            .context("CAPTCHA_SITE_KEY is required when CAPTCHA_PROVIDER is set")?;
+        let guest_threshold =
+            env::var("CAPTCHA_GUEST_THRESHOLD").ok().and_then(|s| s.parse().ok()).unwrap_or(3);
+
+        Ok(Some(Self { provider, secret_key, site_key, guest_threshold }))
+    }
+
+    pub fn build(&self) -> CaptchaClient {
+        CaptchaClient::new(
+            self.provider,
+            self.secret_key.clone(),
+            self.site_key.clone(),
+            self.guest_threshold,
+        )
+    }
+}
+
+/// Game archival configuration.
+#[derive(Debug, Clone)]
+pub struct GameArchiveConfig {
+    /// Private bucket archives are written to, on the upload storage account
+    pub storage: StorageConfig,
+    /// Months after a game ended before it is archived
+    pub after_months: u32,
+    /// Seconds between runs
+    pub interval_secs: u64,
+    /// Games archived per run
+    pub batch_size: usize,
+}
+
+impl GameArchiveConfig {
+    /// Create from environment variables. Disabled unless object storage and
+    /// an archive bucket are configured.
+    pub fn from_env(storage: Option<&StorageConfig>) -> Option<Self> {
+        let bucket = env::var("GAME_ARCHIVE_R2_BUCKET").ok().filter(|b| !b.is_empty())?;
+        let storage = StorageConfig { bucket, ..storage?.clone() };
+        let after_months = env::var("GAME_ARCHIVE_AFTER_MONTHS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .filter(|months| *months > 0)
+            .unwrap_or(12);
+        let interval_secs = env::var("GAME_ARCHIVE_INTERVAL_SECS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .filter(|secs| *secs > 0)
+            .unwrap_or(3600);
+        let batch_size = env::var("GAME_ARCHIVE_BATCH_SIZE")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .filter(|n| *n > 0)
+            .unwrap_or(500);
+
+        Some(Self { storage, after_months, interval_secs, batch_size })
+    }
+}
+
+/// Who may pick their own join code for a multiplayer lobby.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VanityCodeAccess {
+    /// Everyone gets a random code
+    Off,
+    /// Hosts with a registered account
+    Registered,
+    /// Admins only
+    Admin,
+}
+
+/// Multiplayer join code configuration.
+#[derive(Debug, Clone)]
+pub struct JoinCodeConfig {
+    /// Length of random join codes
+    pub length: usize,
+    /// Who may pick a vanity code
+    pub vanity: VanityCodeAccess,
+}
+
+impl JoinCodeConfig {
+    /// Create from environment variables.
+    pub fn from_env() -> Self {
+        let length = env::var("JOIN_CODE_LENGTH")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_JOIN_CODE_LENGTH)
+            .clamp(MIN_JOIN_CODE_LENGTH, MAX_JOIN_CODE_LENGTH);
+        let vanity = match env::var("VANITY_JOIN_CODES").as_deref() {
+            Ok("off") => VanityCodeAccess::Off,
+            Ok("admin") => VanityCodeAccess::Admin,
+            _ => VanityCodeAccess::Registered,
+        };
+
+        Self { length, vanity }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Config {
     /// Server port
     pub port: u16,
     /// Database connection URL
     pub database_url: String,
-    /// Redis connection URL
-    pub redis_url: String,
+    /// Read replica connection URL for heavy read paths (optional)
+    pub database_replica_url: Option<String>,
+    /// Replication lag above which reads fall back to the primary
+    pub database_replica_max_lag: Duration,
+    /// Redis URL and topology (standalone, Sentinel or Cluster)
+    pub redis: RedisConfig,
     /// Frontend URL for CORS and redirects
     pub frontend_url: String,
     /// Google OAuth client ID
@@ -97,6 +281,48 @@ pub struct Config {
     /// Cookie domain for session cookies (e.g., ".dguesser.lol" for cross-subdomain)
     /// If not set, cookies are scoped to the exact domain that set them
     pub cookie_domain: Option<String>,
+    /// Domain whose subdomains serve organizations (e.g. "dguesser.lol" serves
+    /// the "acme" organization at acme.dguesser.lol)
+    pub org_base_domain: Option<String>,
+    /// Outgoing email configuration
+    pub mailer: MailerConfig,
+    /// Web Push VAPID keys (push notifications are disabled when unset)
+    pub push: Option<PushConfig>,
+    /// Shared secret for signing realtime socket handshake tokens (must match
+    /// the realtime server). Socket tokens are disabled when unset.
+    pub socket_token_secret: Option<String>,
+    /// Secret for signing solo round results. Solo scores are not checked
+    /// before reaching the leaderboards when unset.
+    pub score_signing_secret: Option<String>,
+    /// CAPTCHA for guest session creation and joining by code (disabled when
+    /// unset)
+    pub captcha: Option<CaptchaConfig>,
+    /// Per-tier rate limit multipliers
+    pub rate_limit_tiers: RateLimitTiers,
+    /// Limit and algorithm of each rate limited route group
+    pub rate_limits: RateLimitRoutes,
+    /// Google Maps API key for Street View lookups (panorama validation is
+    /// unavailable without one)
+    pub google_maps_api_key: Option<String>,
+    /// Secret for signing map tile tokens. Tiles are only proxied when this
+    /// and a Maps API key are set.
+    pub map_tile_token_secret: Option<String>,
+    /// Location health checker config (disabled without a Maps API key)
+    pub location_health: Option<LocationHealthConfig>,
+    /// Games per player whose panoramas are not served again (0 disables)
+    pub location_repeat_window: usize,
+    /// Hours without activity before a lobby or active game is abandoned
+    pub stale_game_hours: i32,
+    /// Days party chat messages are kept
+    pub chat_retention_days: i32,
+    /// Multiplayer join code length and vanity code access
+    pub join_codes: JoinCodeConfig,
+    /// Share of client error reports stored (panorama failures are always kept)
+    pub client_error_sample_rate: f64,
+    /// Object storage for avatar uploads (uploads are disabled when unset)
+    pub storage: Option<StorageConfig>,
+    /// Game archival to cold storage (disabled when unset)
+    pub game_archive: Option<GameArchiveConfig>,
 }
 
 impl Config {
@@ -120,18 +346,23 @@ impl Config {
             .and_then(|s| LocationProviderType::from_str(&s))
             .unwrap_or(LocationProviderType::Postgres);
 
-        // Parse R2 config if using R2 provider
-        let r2_location_config = if location_provider_type == LocationProviderType::R2 {
-            R2LocationConfig::from_env()
-        } else {
-            None
-        };
+        // Packs are available whenever R2 is configured; maps can opt into them
+        // through their source field even when the default is postgres
+        let r2_location_config = R2LocationConfig::from_env();
+
+        let storage = StorageConfig::from_env();
 
         Ok(Self {
             port,
             database_url: env::var("DATABASE_URL").context("DATABASE_URL not set")?,
-            redis_url: env::var("REDIS_URL")
-                .unwrap_or_else(|_| "redis://localhost:6379".to_string()),
+            database_replica_url: env::var("DATABASE_REPLICA_URL").ok().filter(|s| !s.is_empty()),
+            database_replica_max_lag: Duration::from_secs(
+                env::var("DATABASE_REPLICA_MAX_LAG_SECS")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(10),
+            ),
+            redis: RedisConfig::from_env()?,
             frontend_url,
             google_client_id: env::var("GOOGLE_CLIENT_ID").unwrap_or_default(),
             google_client_secret: env::var("GOOGLE_CLIENT_SECRET").unwrap_or_default(),
@@ -153,6 +384,42 @@ impl Config {
                 .map(|v| v == "true" || v == "1")
                 .unwrap_or(true), // Default: trust Cloudflare headers
             cookie_domain: env::var("COOKIE_DOMAIN").ok(),
+            org_base_domain: env::var("ORG_BASE_DOMAIN")
+                .ok()
+                .map(|d| d.trim().trim_start_matches('.').to_ascii_lowercase())
+                .filter(|d| !d.is_empty()),
+            mailer: MailerConfig::from_env().context("Invalid mail configuration")?,
+            push: PushConfig::from_env().context("Invalid push configuration")?,
+            socket_token_secret: env::var("SOCKET_TOKEN_SECRET").ok().filter(|s| !s.is_empty()),
+            score_signing_secret: env::var("SCORE_SIGNING_SECRET").ok().filter(|s| !s.is_empty()),
+            captcha: CaptchaConfig::from_env().context("Invalid CAPTCHA configuration")?,
+            rate_limit_tiers: RateLimitTiers::from_env(),
+            rate_limits: RateLimitRoutes::from_env(),
+            google_maps_api_key: env::var("GOOGLE_MAPS_API_KEY").ok().filter(|k| !k.is_empty()),
+            map_tile_token_secret: env::var("MAP_TILE_TOKEN_SECRET").ok().filter(|s| !s.is_empty()),
+            location_health: LocationHealthConfig::from_env(),
+            location_repeat_window: env::var("LOCATION_REPEAT_WINDOW_GAMES")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(10),
+            stale_game_hours: env::var("STALE_GAME_HOURS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|&h: &i32| h > 0)
+                .unwrap_or(12),
+            chat_retention_days: env::var("CHAT_RETENTION_DAYS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|&d: &i32| d > 0)
+                .unwrap_or(30),
+            join_codes: JoinCodeConfig::from_env(),
+            client_error_sample_rate: env::var("CLIENT_ERROR_SAMPLE_RATE")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|r: &f64| (0.0..=1.0).contains(r))
+                .unwrap_or(0.25),
+            game_archive: GameArchiveConfig::from_env(storage.as_ref()),
+            storage,
         })
     }
 
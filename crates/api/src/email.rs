@@ -0,0 +1,53 @@
+//! Account emails (verification, password reset, and sign-in alerts)
+//!
+//! Emails are rendered here and queued in the outbox; the delivery worker
+//! started in `main` sends them. A queueing failure is logged rather than
+//! surfaced, since the account action itself already succeeded and the user
+//! can ask for another link.
+
+use dguesser_auth::device_label;
+use dguesser_db::{User, UserDevice};
+use dguesser_mailer::{Template, queue, templates};
+
+use crate::state::AppState;
+
+/// Queue the email verification link for a newly registered or changed address.
+pub async fn send_verification_email(state: &AppState, user_id: &str, to: &str, token: &str) {
+    let link = format!("{}/auth/verify-email?token={token}", state.frontend_url());
+    let message = templates::email_verification(to, &link);
+    if let Err(e) =
+        queue::enqueue(state.db(), Some(user_id), Template::EmailVerification, &message).await
+    {
+        tracing::error!(error = %e, %user_id, "Failed to queue verification email");
+    }
+}
+
+/// Queue the password reset link.
+pub async fn send_password_reset_email(state: &AppState, user_id: &str, to: &str, token: &str) {
+    let link = format!("{}/auth/reset-password?token={token}", state.frontend_url());
+    let message = templates::password_reset(to, &link);
+    if let Err(e) =
+        queue::enqueue(state.db(), Some(user_id), Template::PasswordReset, &message).await
+    {
+        tracing::error!(error = %e, %user_id, "Failed to queue password reset email");
+    }
+}
+
+/// Queue an alert about a sign-in from a new device.
+///
+/// Only sent to users with a verified email; anyone else has no address we
+/// can trust to reach the account owner.
+pub async fn send_new_device_email(state: &AppState, user: &User, device: &UserDevice) {
+    let Some(to) = user.email.as_deref().filter(|_| user.email_verified) else {
+        return;
+    };
+
+    let label = device_label(device.browser.as_deref(), device.os.as_deref());
+    let link = format!("{}/account", state.frontend_url());
+    let message = templates::new_device_login(to, &label, device.last_country.as_deref(), &link);
+    if let Err(e) =
+        queue::enqueue(state.db(), Some(&user.id), Template::NewDeviceLogin, &message).await
+    {
+        tracing::error!(error = %e, user_id = %user.id, "Failed to queue new device email");
+    }
+}
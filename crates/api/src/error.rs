@@ -1,4 +1,11 @@
 //! API error handling
+//!
+//! Every error response uses the same JSON envelope ([`ApiErrorResponse`]):
+//! a machine-readable `code`, a human-readable `message`, the request's
+//! `trace_id` for correlating with logs, and for validation failures a list
+//! of per-field errors.
+
+use std::borrow::Cow;
 
 use axum::{
     Json,
@@ -7,20 +14,36 @@ use axum::{
 };
 use serde::Serialize;
 use tracing::error;
+use utoipa::ToSchema;
+
+use crate::middleware::locale::current_locale;
+use crate::middleware::trace_id::current_trace_id;
 
 /// API error response body
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ApiErrorResponse {
     /// Error code for programmatic handling
     pub code: String,
     /// Human-readable error message
     pub message: String,
-    /// Request ID for tracing (if available)
+    /// Trace ID correlating this response with server logs
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub request_id: Option<String>,
-    /// Additional error details
+    pub trace_id: Option<String>,
+    /// Per-field validation errors
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FieldError>,
+}
+
+/// A validation error for a single request field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct FieldError {
+    /// Path to the field, e.g. `settings.rounds` or `locations[2].lat`
+    pub field: String,
+    /// Validation rule that failed, e.g. `length` or `range`
+    pub code: String,
+    /// Human-readable description (if the rule defines one)
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub details: Option<serde_json::Value>,
+    pub message: Option<String>,
 }
 
 /// API error with status code, code, and message
@@ -34,8 +57,8 @@ pub struct ApiError {
     pub message: String,
     /// Internal error message (for logging only, not exposed)
     pub internal_message: Option<String>,
-    /// Additional error details
-    pub details: Option<serde_json::Value>,
+    /// Per-field validation errors
+    pub fields: Vec<FieldError>,
 }
 
 impl ApiError {
@@ -46,19 +69,27 @@ impl ApiError {
             code: code.into(),
             message: message.into(),
             internal_message: None,
-            details: None,
+            fields: Vec::new(),
         }
     }
 
+    /// Create an error for a bare status code, e.g. an extractor rejection
+    /// that did not go through `ApiError`.
+    pub fn from_status(status: StatusCode, message: Option<String>) -> Self {
+        let message = message
+            .unwrap_or_else(|| status.canonical_reason().unwrap_or("Request failed").to_string());
+        Self::new(status, code_for_status(status), message)
+    }
+
     /// Add internal error message for logging
     pub fn with_internal(mut self, msg: impl Into<String>) -> Self {
         self.internal_message = Some(msg.into());
         self
     }
 
-    /// Add error details
-    pub fn with_details(mut self, details: serde_json::Value) -> Self {
-        self.details = Some(details);
+    /// Add per-field validation errors
+    pub fn with_fields(mut self, fields: Vec<FieldError>) -> Self {
+        self.fields = fields;
         self
     }
 
@@ -104,7 +135,6 @@ impl ApiError {
     }
 
     /// Create a rate limited (429) error
-    #[allow(dead_code)]
     pub fn rate_limited() -> Self {
         Self::new(
             StatusCode::TOO_MANY_REQUESTS,
@@ -133,11 +163,15 @@ impl IntoResponse for ApiError {
             );
         }
 
+        let message = match current_locale().message(&self.message) {
+            Some(translated) => translated.to_string(),
+            None => self.message,
+        };
         let body = ApiErrorResponse {
             code: self.code,
-            message: self.message,
-            request_id: None, // Would be injected by middleware if needed
-            details: self.details,
+            message,
+            trace_id: current_trace_id(),
+            fields: self.fields,
         };
 
         (self.status, Json(body)).into_response()
@@ -178,6 +212,45 @@ impl From<dguesser_auth::AuthError> for ApiError {
             dguesser_auth::AuthError::MergeBlocked(message) => {
                 Self::conflict("AUTH_MERGE_BLOCKED", message)
             }
+            dguesser_auth::AuthError::IdentityConflict => Self::conflict(
+                "OAUTH_IDENTITY_CONFLICT",
+                "This sign-in account is already linked to another user",
+            ),
+            dguesser_auth::AuthError::ProviderAlreadyLinked(provider) => Self::conflict(
+                "PROVIDER_ALREADY_LINKED",
+                format!("A {} account is already linked", provider),
+            ),
+            dguesser_auth::AuthError::ProviderNotLinked(provider) => Self::new(
+                StatusCode::NOT_FOUND,
+                "PROVIDER_NOT_LINKED",
+                format!("No {} account is linked", provider),
+            ),
+            dguesser_auth::AuthError::LastLoginMethod => Self::conflict(
+                "LAST_LOGIN_METHOD",
+                "Link another sign-in method before removing this one",
+            ),
+            dguesser_auth::AuthError::InvalidCredentials => Self::new(
+                StatusCode::UNAUTHORIZED,
+                "INVALID_CREDENTIALS",
+                "Invalid email or password",
+            ),
+            dguesser_auth::AuthError::EmailTaken => {
+                Self::conflict("EMAIL_TAKEN", "An account with this email already exists")
+            }
+            dguesser_auth::AuthError::InvalidToken => {
+                Self::bad_request("INVALID_TOKEN", "This link is invalid or has expired")
+            }
+            dguesser_auth::AuthError::Credential(e) => match e {
+                dguesser_auth::CredentialError::WeakPassword(message) => {
+                    Self::bad_request("WEAK_PASSWORD", message)
+                }
+                dguesser_auth::CredentialError::InvalidEmail => {
+                    Self::bad_request("INVALID_EMAIL", "Invalid email address")
+                }
+                dguesser_auth::CredentialError::Hash(_) => {
+                    Self::internal().with_internal(e.to_string())
+                }
+            },
             dguesser_auth::AuthError::OAuth(e) => {
                 // Log the actual error for debugging, but don't expose details to client
                 tracing::warn!(error = %e, "OAuth error");
@@ -218,9 +291,58 @@ impl From<dguesser_core::location::LocationError> for ApiError {
 
 impl From<validator::ValidationErrors> for ApiError {
     fn from(err: validator::ValidationErrors) -> Self {
-        let details = serde_json::to_value(&err).ok();
-        Self::bad_request("VALIDATION_ERROR", "Validation failed")
-            .with_details(details.unwrap_or_else(|| serde_json::json!({"errors": err.to_string()})))
+        let mut fields = Vec::new();
+        collect_field_errors(&err, "", &mut fields);
+        fields.sort_by(|a, b| a.field.cmp(&b.field).then_with(|| a.code.cmp(&b.code)));
+        Self::bad_request("VALIDATION_ERROR", "Validation failed").with_fields(fields)
+    }
+}
+
+/// Flatten nested validator errors into dotted field paths.
+fn collect_field_errors(
+    errors: &validator::ValidationErrors,
+    prefix: &str,
+    out: &mut Vec<FieldError>,
+) {
+    use validator::ValidationErrorsKind;
+
+    for (name, kind) in errors.errors() {
+        let path: Cow<'_, str> =
+            if prefix.is_empty() { Cow::Borrowed(name) } else { format!("{prefix}.{name}").into() };
+        match kind {
+            ValidationErrorsKind::Field(field_errors) => {
+                out.extend(field_errors.iter().map(|e| FieldError {
+                    field: path.to_string(),
+                    code: e.code.to_string(),
+                    message: e.message.as_ref().map(|m| m.to_string()),
+                }));
+            }
+            ValidationErrorsKind::Struct(nested) => collect_field_errors(nested, &path, out),
+            ValidationErrorsKind::List(items) => {
+                for (index, nested) in items {
+                    collect_field_errors(nested, &format!("{path}[{index}]"), out);
+                }
+            }
+        }
+    }
+}
+
+/// Machine-readable code for a bare HTTP status.
+fn code_for_status(status: StatusCode) -> &'static str {
+    match status {
+        StatusCode::BAD_REQUEST => "BAD_REQUEST",
+        StatusCode::UNAUTHORIZED => "UNAUTHORIZED",
+        StatusCode::FORBIDDEN => "FORBIDDEN",
+        StatusCode::NOT_FOUND => "NOT_FOUND",
+        StatusCode::METHOD_NOT_ALLOWED => "METHOD_NOT_ALLOWED",
+        StatusCode::CONFLICT => "CONFLICT",
+        StatusCode::PAYLOAD_TOO_LARGE => "PAYLOAD_TOO_LARGE",
+        StatusCode::UNSUPPORTED_MEDIA_TYPE => "UNSUPPORTED_MEDIA_TYPE",
+        StatusCode::UNPROCESSABLE_ENTITY => "INVALID_REQUEST_BODY",
+        StatusCode::TOO_MANY_REQUESTS => "RATE_LIMITED",
+        StatusCode::SERVICE_UNAVAILABLE => "SERVICE_UNAVAILABLE",
+        s if s.is_server_error() => "INTERNAL_ERROR",
+        _ => "REQUEST_FAILED",
     }
 }
 
@@ -269,4 +391,71 @@ mod tests {
         assert_eq!(err.code, "AUTH_MERGE_BLOCKED");
         assert_eq!(err.message, "Finish your game first");
     }
+
+    #[test]
+    fn test_auth_last_login_method_maps_to_conflict() {
+        let err = ApiError::from(dguesser_auth::AuthError::LastLoginMethod);
+
+        assert_eq!(err.status, StatusCode::CONFLICT);
+        assert_eq!(err.code, "LAST_LOGIN_METHOD");
+    }
+
+    #[test]
+    fn test_validation_errors_map_to_fields() {
+        use validator::Validate;
+
+        #[derive(Validate)]
+        struct Inner {
+            #[validate(range(min = 1, max = 10))]
+            rounds: u32,
+        }
+
+        #[derive(Validate)]
+        struct Outer {
+            #[validate(length(min = 1, message = "Name is required"))]
+            name: String,
+            #[validate(nested)]
+            settings: Inner,
+        }
+
+        let err =
+            Outer { name: String::new(), settings: Inner { rounds: 0 } }.validate().unwrap_err();
+        let err = ApiError::from(err);
+
+        assert_eq!(err.status, StatusCode::BAD_REQUEST);
+        assert_eq!(err.code, "VALIDATION_ERROR");
+        assert_eq!(
+            err.fields,
+            vec![
+                FieldError {
+                    field: "name".to_string(),
+                    code: "length".to_string(),
+                    message: Some("Name is required".to_string()),
+                },
+                FieldError {
+                    field: "settings.rounds".to_string(),
+                    code: "range".to_string(),
+                    message: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_status_uses_status_code() {
+        let err = ApiError::from_status(StatusCode::METHOD_NOT_ALLOWED, None);
+        assert_eq!(err.code, "METHOD_NOT_ALLOWED");
+        assert_eq!(err.message, "Method Not Allowed");
+
+        let err = ApiError::from_status(StatusCode::BAD_GATEWAY, None);
+        assert_eq!(err.code, "INTERNAL_ERROR");
+    }
+
+    #[test]
+    fn test_auth_invalid_credentials_maps_to_unauthorized() {
+        let err = ApiError::from(dguesser_auth::AuthError::InvalidCredentials);
+
+        assert_eq!(err.status, StatusCode::UNAUTHORIZED);
+        assert_eq!(err.code, "INVALID_CREDENTIALS");
+    }
 }
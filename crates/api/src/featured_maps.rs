@@ -0,0 +1,55 @@
+//! Featured map scheduler
+//!
+//! Admins schedule system maps to be the default map for a period, e.g. for
+//! weekly themed events. This background task applies the schedule once a
+//! minute; scheduling or cancelling a feature applies it right away.
+
+use std::time::Duration;
+
+use chrono::Utc;
+use dguesser_db::featured_maps::{self, ScheduleChanges};
+
+use crate::jobs::claim_interval;
+use crate::state::AppState;
+
+/// How often the schedule is applied
+const INTERVAL_SECS: u64 = 60;
+
+/// Spawn the background featured map scheduler.
+///
+/// A Redis key per interval ensures only one API instance runs each check.
+pub fn spawn_featured_map_task(state: AppState) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(INTERVAL_SECS));
+
+        loop {
+            interval.tick().await;
+
+            let slot = Utc::now().timestamp() as u64 / INTERVAL_SECS;
+            let key = format!("featured_maps:schedule:{slot}");
+            if !claim_interval(state.redis(), &key, INTERVAL_SECS).await {
+                continue;
+            }
+
+            if let Err(e) = apply_schedule(&state).await {
+                tracing::error!(error = %e, "Featured map schedule failed");
+            }
+        }
+    });
+
+    tracing::info!(interval_secs = INTERVAL_SECS, "Featured map scheduler started");
+}
+
+/// Start and end the features that are due.
+pub async fn apply_schedule(state: &AppState) -> Result<ScheduleChanges, sqlx::Error> {
+    let changes = featured_maps::run_schedule(state.db()).await?;
+    if changes.started + changes.ended + changes.skipped > 0 {
+        tracing::info!(
+            started = changes.started,
+            ended = changes.ended,
+            skipped = changes.skipped,
+            "Featured map schedule applied"
+        );
+    }
+    Ok(changes)
+}
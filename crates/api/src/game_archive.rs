@@ -0,0 +1,242 @@
+//! Game archival to cold storage
+//!
+//! Games that ended more than `GAME_ARCHIVE_AFTER_MONTHS` ago are written to
+//! the archive bucket as gzipped JSON lines, one line per row, and their
+//! rounds, guesses, hints, challenge progress and rerolls are deleted. The `games`
+//! and `game_players` rows stay as the summary. Viewing an archived game
+//! needs a [`rehydrate`] first; restored games are archived again once they
+//! have not been restored for [`REHYDRATED_KEEP_DAYS`].
+
+use std::io::{BufRead, BufReader, Write};
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::body::Bytes;
+use chrono::{DateTime, Datelike, Months, Utc};
+use dguesser_db::game_archive::{self, GameDetailRows};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+
+use crate::config::GameArchiveConfig;
+use crate::state::AppState;
+use crate::storage::{ObjectStorage, StorageError};
+
+/// Days a restored game stays in the database before it is archived again
+const REHYDRATED_KEEP_DAYS: i64 = 7;
+
+/// Archive file format version
+const FORMAT_VERSION: u32 = 1;
+
+const CONTENT_TYPE: &str = "application/gzip";
+
+/// Game archival errors
+#[derive(Debug, thiserror::Error)]
+pub enum ArchiveError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+    #[error("archive {0} is missing")]
+    Missing(String),
+    #[error("invalid archive: {0}")]
+    Invalid(String),
+}
+
+/// One line of an archive file
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+enum ArchiveLine {
+    /// First line of every archive
+    Header {
+        version: u32,
+        game_id: String,
+        archived_at: DateTime<Utc>,
+    },
+    Round(serde_json::Value),
+    Guess(serde_json::Value),
+    PlayerRound(serde_json::Value),
+    RoundReroll(serde_json::Value),
+    RoundHint(serde_json::Value),
+}
+
+/// Object key of a game's archive, grouped by the month it ended
+fn archive_key(game_id: &str, ended_at: DateTime<Utc>) -> String {
+    format!("games/{:04}/{:02}/{game_id}.jsonl.gz", ended_at.year(), ended_at.month())
+}
+
+/// Write a game's rows as gzipped JSON lines.
+fn encode(game_id: &str, rows: &GameDetailRows) -> std::io::Result<Vec<u8>> {
+    let header = ArchiveLine::Header {
+        version: FORMAT_VERSION,
+        game_id: game_id.to_string(),
+        archived_at: Utc::now(),
+    };
+    let lines = std::iter::once(header)
+        .chain(rows.rounds.iter().cloned().map(ArchiveLine::Round))
+        .chain(rows.guesses.iter().cloned().map(ArchiveLine::Guess))
+        .chain(rows.player_rounds.iter().cloned().map(ArchiveLine::PlayerRound))
+        .chain(rows.round_rerolls.iter().cloned().map(ArchiveLine::RoundReroll))
+        .chain(rows.round_hints.iter().cloned().map(ArchiveLine::RoundHint));
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    for line in lines {
+        serde_json::to_writer(&mut encoder, &line)?;
+        encoder.write_all(b"\n")?;
+    }
+    encoder.finish()
+}
+
+/// Read a game's rows back from an archive.
+fn decode(game_id: &str, archive: &[u8]) -> Result<GameDetailRows, ArchiveError> {
+    let invalid = |e: &dyn std::fmt::Display| ArchiveError::Invalid(e.to_string());
+    let mut lines = BufReader::new(GzDecoder::new(archive)).lines();
+
+    let header = lines.next().ok_or_else(|| ArchiveError::Invalid("empty archive".into()))?;
+    match serde_json::from_str(&header.map_err(|e| invalid(&e))?).map_err(|e| invalid(&e))? {
+        ArchiveLine::Header { version: FORMAT_VERSION, game_id: archived, .. }
+            if archived == game_id => {}
+        _ => return Err(ArchiveError::Invalid(format!("header does not match game {game_id}"))),
+    }
+
+    let mut rows = GameDetailRows::default();
+    for line in lines {
+        match serde_json::from_str(&line.map_err(|e| invalid(&e))?).map_err(|e| invalid(&e))? {
+            ArchiveLine::Header { .. } => {
+                return Err(ArchiveError::Invalid("repeated header".into()));
+            }
+            ArchiveLine::Round(row) => rows.rounds.push(row),
+            ArchiveLine::Guess(row) => rows.guesses.push(row),
+            ArchiveLine::PlayerRound(row) => rows.player_rounds.push(row),
+            ArchiveLine::RoundReroll(row) => rows.round_rerolls.push(row),
+            ArchiveLine::RoundHint(row) => rows.round_hints.push(row),
+        }
+    }
+    Ok(rows)
+}
+
+/// Archive the oldest game due for archival. Returns its ID, or `None` when
+/// no game is due.
+///
+/// The game row stays locked while the archive uploads, so concurrent
+/// archivers pick different games.
+async fn archive_next(
+    state: &AppState,
+    storage: &dyn ObjectStorage,
+    after_months: u32,
+) -> Result<Option<String>, ArchiveError> {
+    let now = Utc::now();
+    let ended_before = now.checked_sub_months(Months::new(after_months)).unwrap_or(now);
+    let rehydrated_before = now - chrono::Duration::days(REHYDRATED_KEEP_DAYS);
+
+    let mut tx = state.db().begin().await?;
+    let Some(game) =
+        game_archive::lock_next_candidate(&mut tx, ended_before, rehydrated_before).await?
+    else {
+        return Ok(None);
+    };
+
+    let rows = game_archive::export_game(&mut tx, &game.id).await?;
+    let key = archive_key(&game.id, game.ended_at);
+    let archive = encode(&game.id, &rows).map_err(|e| ArchiveError::Invalid(e.to_string()))?;
+    storage.put(&key, Bytes::from(archive), CONTENT_TYPE).await?;
+
+    game_archive::mark_archived(&mut tx, &game.id, &key).await?;
+    tx.commit().await?;
+
+    tracing::debug!(game_id = %game.id, key, rounds = rows.rounds.len(), "Archived game");
+    Ok(Some(game.id))
+}
+
+/// Restore an archived game's rounds and guesses. Returns false if the game
+/// is not archived.
+pub async fn rehydrate(
+    state: &AppState,
+    storage: &dyn ObjectStorage,
+    game_id: &str,
+) -> Result<bool, ArchiveError> {
+    let Some(status) = game_archive::get_status(state.db(), game_id).await? else {
+        return Ok(false);
+    };
+    let (Some(_), Some(key)) = (status.archived_at, status.archive_key) else {
+        return Ok(false);
+    };
+
+    let archive = storage.get(&key).await?.ok_or_else(|| ArchiveError::Missing(key.clone()))?;
+    let rows = decode(game_id, &archive)?;
+
+    let mut tx = state.db().begin().await?;
+    let restored = game_archive::restore_game(&mut tx, game_id, &rows).await?;
+    tx.commit().await?;
+
+    if restored {
+        tracing::info!(game_id, key, "Rehydrated archived game");
+    }
+    Ok(restored)
+}
+
+/// Spawn the background task that archives old games.
+pub fn spawn_archive_task(state: AppState, config: GameArchiveConfig) {
+    let Some(storage) = state.archive_storage().map(Arc::clone) else {
+        return;
+    };
+    let interval_secs = config.interval_secs;
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+
+        // Skip the first immediate tick so startup is not slowed down
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+
+            let mut archived = 0;
+            while archived < config.batch_size {
+                match archive_next(&state, storage.as_ref(), config.after_months).await {
+                    Ok(Some(_)) => archived += 1,
+                    Ok(None) => break,
+                    Err(e) => {
+                        tracing::error!(error = %e, "Game archival failed");
+                        break;
+                    }
+                }
+            }
+            if archived > 0 {
+                tracing::info!(archived, "Archived old games");
+            }
+        }
+    });
+
+    tracing::info!(after_months = config.after_months, interval_secs, "Game archiver started");
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_archive_roundtrip() {
+        let rows = GameDetailRows {
+            rounds: vec![json!({"id": "rnd_1", "round_number": 1})],
+            guesses: vec![json!({"id": "gss_1", "score": 4200}), json!({"id": "gss_2"})],
+            player_rounds: vec![],
+            round_rerolls: vec![json!({"id": 7, "rerolled_by": null})],
+            round_hints: vec![json!({"round_id": "rnd_1", "kind": "continent"})],
+        };
+
+        let archive = encode("gam_1", &rows).unwrap();
+        assert_eq!(decode("gam_1", &archive).unwrap(), rows);
+        assert!(matches!(decode("gam_2", &archive), Err(ArchiveError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_archive_key_groups_by_month() {
+        let ended_at = Utc.with_ymd_and_hms(2025, 3, 9, 12, 0, 0).unwrap();
+        assert_eq!(archive_key("gam_1", ended_at), "games/2025/03/gam_1.jsonl.gz");
+    }
+}
@@ -0,0 +1,231 @@
+//! Localization of API responses
+//!
+//! Each supported locale has a bundle in `crates/api/locales/` with:
+//! - `countries`: display names keyed by ISO 3166-1 alpha-2 code
+//! - `subdivisions`: display names keyed by ISO 3166-2 code
+//! - `messages`: translations of error messages, keyed by their English text
+//!
+//! Bundles only need the entries that differ from English. Lookups fall back
+//! to the English bundle (English country names come from
+//! [`dguesser_core::geo::countries`]), and callers fall back to the code or
+//! message itself.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use axum::{extract::FromRequestParts, http::request::Parts};
+use dguesser_core::geo::countries::{self, Country};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A supported response language
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+    Fr,
+    Es,
+}
+
+/// Translations for one locale
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Bundle {
+    #[serde(default)]
+    countries: HashMap<String, String>,
+    #[serde(default)]
+    subdivisions: HashMap<String, String>,
+    #[serde(default)]
+    messages: HashMap<String, String>,
+}
+
+static BUNDLES: LazyLock<[Bundle; 4]> = LazyLock::new(|| {
+    Locale::ALL.map(|locale| {
+        serde_json::from_str(locale.source())
+            .unwrap_or_else(|e| panic!("invalid {} locale bundle: {e}", locale.code()))
+    })
+});
+
+impl Locale {
+    /// All supported locales
+    pub const ALL: [Locale; 4] = [Locale::En, Locale::De, Locale::Fr, Locale::Es];
+
+    /// Language code, as used in `lang` parameters and `Content-Language`
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::En => "en",
+            Self::De => "de",
+            Self::Fr => "fr",
+            Self::Es => "es",
+        }
+    }
+
+    /// Parse a language tag such as `de` or `fr-CA`; only the primary
+    /// language subtag is considered.
+    pub fn parse(tag: &str) -> Option<Self> {
+        let language = tag.trim().split(['-', '_']).next()?;
+        Self::ALL.into_iter().find(|locale| locale.code().eq_ignore_ascii_case(language))
+    }
+
+    /// Pick the supported locale the client prefers most from an
+    /// `Accept-Language` header.
+    pub fn from_accept_language(header: &str) -> Option<Self> {
+        let mut best: Option<(Self, f32)> = None;
+        for item in header.split(',') {
+            let mut parts = item.split(';');
+            let Some(locale) = parts.next().and_then(Self::parse) else {
+                continue;
+            };
+            let quality = parts
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())
+                .unwrap_or(0.0);
+            // Ties keep the earlier entry, as listed by the client
+            if quality > 0.0 && best.is_none_or(|(_, q)| quality > q) {
+                best = Some((locale, quality));
+            }
+        }
+        best.map(|(locale, _)| locale)
+    }
+
+    fn source(self) -> &'static str {
+        match self {
+            Self::En => include_str!("../locales/en.json"),
+            Self::De => include_str!("../locales/de.json"),
+            Self::Fr => include_str!("../locales/fr.json"),
+            Self::Es => include_str!("../locales/es.json"),
+        }
+    }
+
+    fn bundle(self) -> &'static Bundle {
+        &BUNDLES[self as usize]
+    }
+
+    /// Look up an entry in this locale's bundle, falling back to English.
+    fn lookup(
+        self,
+        table: impl Fn(&'static Bundle) -> &'static HashMap<String, String>,
+        key: &str,
+    ) -> Option<&'static str> {
+        table(self.bundle())
+            .get(key)
+            .or_else(|| table(Self::En.bundle()).get(key))
+            .map(String::as_str)
+    }
+
+    /// Display name of a country, by ISO 3166-1 alpha-2 code.
+    pub fn country_name(self, code: &str) -> Option<&'static str> {
+        let country = countries::lookup(code)?;
+        Some(self.localized_name(country))
+    }
+
+    /// Display name of a known country.
+    pub fn localized_name(self, country: &Country) -> &'static str {
+        self.bundle().countries.get(country.code).map_or(country.name, String::as_str)
+    }
+
+    /// Display name of a subdivision, by ISO 3166-2 code.
+    pub fn subdivision_name(self, code: &str) -> Option<&'static str> {
+        self.lookup(|b| &b.subdivisions, &code.to_ascii_uppercase())
+    }
+
+    /// Translation of an English message, if this locale has one.
+    pub fn message(self, message: &str) -> Option<&'static str> {
+        self.bundle().messages.get(message).map(String::as_str)
+    }
+
+    /// Every known country with its display name, sorted by name.
+    pub fn countries(self) -> Vec<(&'static Country, &'static str)> {
+        let mut countries: Vec<_> = countries::all()
+            .iter()
+            .map(|country| (country, self.localized_name(country)))
+            .collect();
+        countries.sort_by_cached_key(|(_, name)| collation_key(name));
+        countries
+    }
+}
+
+/// Sort key that orders accented letters with their base letter, so
+/// "Ägypten" sorts next to "Afghanistan" rather than after "Z".
+pub fn collation_key(name: &str) -> String {
+    name.chars()
+        .flat_map(char::to_lowercase)
+        .map(|c| match c {
+            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => 'a',
+            'ç' => 'c',
+            'è' | 'é' | 'ê' | 'ë' => 'e',
+            'ì' | 'í' | 'î' | 'ï' => 'i',
+            'ñ' => 'n',
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' => 'o',
+            'ù' | 'ú' | 'û' | 'ü' => 'u',
+            'ý' | 'ÿ' => 'y',
+            c => c,
+        })
+        .collect()
+}
+
+/// Locale negotiated for the request by the locale middleware; English when
+/// the middleware did not run.
+impl<S> FromRequestParts<S> for Locale
+where
+    S: Send + Sync,
+{
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts.extensions.get::<Locale>().copied().unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundles_are_valid() {
+        for locale in Locale::ALL {
+            let bundle = locale.bundle();
+            let english = Locale::En.bundle();
+            for code in bundle.countries.keys() {
+                assert!(countries::lookup(code).is_some(), "{code} is not a known country");
+            }
+            for code in bundle.subdivisions.keys() {
+                assert!(english.subdivisions.contains_key(code), "{code} missing from en");
+            }
+        }
+        assert!(Locale::En.bundle().messages.is_empty(), "English messages need no entries");
+    }
+
+    #[test]
+    fn test_lookup_falls_back_to_english() {
+        assert_eq!(Locale::De.country_name("de"), Some("Deutschland"));
+        assert_eq!(Locale::Fr.country_name("DE"), Some("Allemagne"));
+        assert_eq!(Locale::De.subdivision_name("US-TX"), Some("Texas"));
+        assert_eq!(Locale::De.subdivision_name("DE-BY"), Some("Bayern"));
+        assert_eq!(Locale::En.country_name("ZZ"), None);
+        assert_eq!(Locale::Es.message("Game not found"), Some("Partida no encontrada"));
+        assert_eq!(Locale::En.message("Game not found"), None);
+    }
+
+    #[test]
+    fn test_accept_language() {
+        assert_eq!(Locale::from_accept_language("de-DE,de;q=0.9,en;q=0.8"), Some(Locale::De));
+        assert_eq!(Locale::from_accept_language("en;q=0.5, fr-CA"), Some(Locale::Fr));
+        assert_eq!(Locale::from_accept_language("ja, es;q=0.3"), Some(Locale::Es));
+        assert_eq!(Locale::from_accept_language("es;q=0, *"), None);
+        assert_eq!(Locale::from_accept_language(""), None);
+        assert_eq!(Locale::parse("FR_be"), Some(Locale::Fr));
+    }
+
+    #[test]
+    fn test_countries_sorted_by_localized_name() {
+        let countries = Locale::De.countries();
+        assert_eq!(countries.len(), countries::all().len());
+        let position = |code| countries.iter().position(|(c, _)| c.code == code).unwrap();
+        // Ägypten sorts with A, Österreich with O
+        assert!(position("EG") < position("AL"));
+        assert!(position("OM") < position("AT") && position("AT") < position("PK"));
+    }
+}
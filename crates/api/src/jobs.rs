@@ -0,0 +1,28 @@
+//! Coordination for periodic background jobs
+//!
+//! Every API instance runs the same interval tasks, so each run is claimed in
+//! Redis first and only the instance that wins the claim performs it.
+
+use dguesser_redis::RedisConnection;
+
+/// Claim the run identified by `key` so only one instance performs it.
+///
+/// The claim expires after `ttl_secs`, normally the job's interval. Returns
+/// false if another instance already holds it or Redis is unreachable.
+pub async fn claim_interval(redis: &RedisConnection, key: &str, ttl_secs: u64) -> bool {
+    let claimed = redis::cmd("SET")
+        .arg(key)
+        .arg("1")
+        .arg("NX")
+        .arg("EX")
+        .arg(ttl_secs)
+        .query_async::<Option<String>>(&mut redis.clone())
+        .await;
+    match claimed {
+        Ok(reply) => reply.is_some(),
+        Err(e) => {
+            tracing::error!(error = %e, key, "Failed to claim a job run in Redis");
+            false
+        }
+    }
+}
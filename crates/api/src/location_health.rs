@@ -0,0 +1,210 @@
+//! Location health checker
+//!
+//! Street View panoramas disappear over time (removed imagery, privacy
+//! takedowns). This background task periodically samples active locations,
+//! asks the Street View metadata API whether each panorama still exists, and
+//! deactivates dead ones through [`LocationProvider::mark_location_failed`] so
+//! they stop being served. Each run's totals are stored for the admin
+//! dashboard.
+//!
+//! Part of each sample rechecks inactive locations (dead, rejected or
+//! flagged). Google sometimes republishes coverage, so when such a panorama
+//! exists again the location is proposed for re-approval in a separate admin
+//! queue instead of being reactivated on its own.
+//!
+//! [`LocationProvider::mark_location_failed`]: dguesser_core::location::LocationProvider::mark_location_failed
+
+use std::time::Duration;
+
+use chrono::Utc;
+use dguesser_core::streetview::QuotaState;
+use dguesser_db::location_health::{self, HealthCheckCounts, HealthCheckTarget};
+use futures::StreamExt;
+
+use crate::config::LocationHealthConfig;
+use crate::jobs::claim_interval;
+use crate::state::AppState;
+use crate::street_view::{PanoramaQuery, PanoramaStatus, StreetViewClient};
+
+/// Metadata lookups in flight at once
+const CONCURRENCY: usize = 8;
+
+/// Run summaries older than this are deleted
+const RETENTION_DAYS: i32 = 90;
+
+/// Share of each sample spent rechecking inactive locations, in percent
+const RECHECK_PERCENT: i64 = 10;
+
+/// Spawn the background location health checker.
+///
+/// A Redis key per interval ensures only one API instance runs each check.
+pub fn spawn_location_health_task(
+    state: AppState,
+    config: LocationHealthConfig,
+    packs_enabled: bool,
+) {
+    let interval_secs = config.interval_secs;
+
+    tokio::spawn(async move {
+        let client = StreetViewClient::new(Some(config.api_key.clone()))
+            .with_quota_store(state.redis().clone());
+        let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+
+        // Skip the first immediate tick so startup is not slowed down
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+
+            // Don't spend a run on errors while the quota is exhausted
+            let now = Utc::now();
+            if let QuotaState::Open { until } = client.quota().await.state(now) {
+                tracing::info!(paused_until = %until, "Street View quota exhausted, skipping health check");
+                continue;
+            }
+
+            let slot = now.timestamp() as u64 / interval_secs;
+            let key = format!("locations:health_check:{slot}");
+            if !claim_interval(state.redis(), &key, interval_secs).await {
+                continue;
+            }
+
+            match run_once(&state, &client, &config, packs_enabled).await {
+                Ok(counts) => tracing::info!(
+                    checked = counts.checked,
+                    healthy = counts.healthy,
+                    dead = counts.dead,
+                    errors = counts.errors,
+                    revived = counts.revived,
+                    "Location health check finished"
+                ),
+                Err(e) => tracing::error!(error = %e, "Location health check failed"),
+            }
+        }
+    });
+
+    tracing::info!(interval_secs, "Location health checker started");
+}
+
+/// Check one sample of locations and record the run.
+async fn run_once(
+    state: &AppState,
+    client: &StreetViewClient,
+    config: &LocationHealthConfig,
+    packs_enabled: bool,
+) -> Result<HealthCheckCounts, sqlx::Error> {
+    let started_at = Utc::now();
+    let recheck_size =
+        if config.recheck_days > 0 { recheck_sample_size(config.sample_size) } else { 0 };
+    let rechecks = if recheck_size > 0 {
+        location_health::sample_recheck_targets(state.db(), recheck_size, config.recheck_days)
+            .await?
+    } else {
+        Vec::new()
+    };
+    let active_size = config.sample_size - rechecks.len() as i64;
+    let targets = location_health::sample_targets(state.db(), active_size).await?;
+
+    let mut results = futures::stream::iter(
+        targets.into_iter().map(|t| (t, false)).chain(rechecks.into_iter().map(|t| (t, true))),
+    )
+    .map(|(target, recheck)| async move {
+        let status = client.lookup(PanoramaQuery::Panorama(&target.panorama_id)).await;
+        (target, recheck, status)
+    })
+    .buffer_unordered(CONCURRENCY);
+
+    let mut counts = HealthCheckCounts::default();
+    let mut healthy_ids = Vec::new();
+    let mut still_dead_ids = Vec::new();
+
+    while let Some((target, recheck, status)) = results.next().await {
+        match status {
+            PanoramaStatus::Exists(_) if recheck => {
+                counts.checked += 1;
+                counts.revived += 1;
+                if let Err(e) = location_health::propose_reapproval(state.db(), &target.id).await {
+                    tracing::error!(location_id = %target.id, error = %e, "Failed to propose re-approval");
+                }
+            }
+            PanoramaStatus::Exists(_) => {
+                counts.checked += 1;
+                counts.healthy += 1;
+                healthy_ids.push(target.id);
+            }
+            PanoramaStatus::Missing(_) if recheck => {
+                counts.checked += 1;
+                still_dead_ids.push(target.id);
+            }
+            PanoramaStatus::Missing(reason) => {
+                counts.checked += 1;
+                counts.dead += 1;
+                mark_dead(state, &target, &reason, packs_enabled).await;
+            }
+            PanoramaStatus::Error(e) => {
+                counts.checked += 1;
+                counts.errors += 1;
+                tracing::debug!(location_id = %target.id, error = %e, "Panorama lookup failed");
+            }
+            PanoramaStatus::Fatal(status) => {
+                counts.checked += 1;
+                counts.errors += 1;
+                tracing::error!(status = %status, "Street View metadata API refused requests");
+                break;
+            }
+        }
+    }
+    drop(results);
+
+    location_health::mark_healthy(state.db(), &healthy_ids).await?;
+    location_health::mark_rechecked(state.db(), &still_dead_ids).await?;
+    location_health::record_run(state.db(), started_at, counts).await?;
+    location_health::cleanup_runs(state.db(), RETENTION_DAYS).await?;
+
+    Ok(counts)
+}
+
+/// Locations rechecked for re-approval out of a run of `sample_size`.
+fn recheck_sample_size(sample_size: i64) -> i64 {
+    (sample_size * RECHECK_PERCENT / 100).max(1).min(sample_size)
+}
+
+/// Deactivate a location whose panorama no longer exists.
+async fn mark_dead(
+    state: &AppState,
+    target: &HealthCheckTarget,
+    reason: &str,
+    packs_enabled: bool,
+) {
+    // Packs identify locations by panorama; disable it there too so
+    // pack-backed maps stop serving it before the next pack build
+    if packs_enabled
+        && let Err(e) = state.location_provider().mark_location_failed(&target.panorama_id).await
+    {
+        tracing::warn!(location_id = %target.id, error = %e, "Failed to disable dead location");
+    }
+
+    // Always record the outcome in Postgres, which the packs are built from
+    let reason = format!("health_check: {reason}");
+    if let Err(e) = location_health::mark_dead(state.db(), &target.id, &reason).await {
+        tracing::error!(location_id = %target.id, error = %e, "Failed to record dead location");
+    }
+}
+
+/// Deactivate the locations showing a panorama that a metadata lookup found
+/// missing outside the health checker.
+pub async fn mark_panorama_dead(state: &AppState, panorama_id: &str, reason: &str) {
+    if state.pack_cache().is_some()
+        && let Err(e) = state.location_provider().mark_location_failed(panorama_id).await
+    {
+        tracing::warn!(panorama_id, error = %e, "Failed to disable dead panorama");
+    }
+
+    match location_health::mark_dead_by_panorama(state.db(), panorama_id, reason).await {
+        Ok(ids) if !ids.is_empty() => {
+            tracing::info!(panorama_id, locations = ?ids, "Deactivated locations with dead panorama");
+        }
+        Ok(_) => {}
+        Err(e) => tracing::error!(panorama_id, error = %e, "Failed to record dead panorama"),
+    }
+}
@@ -0,0 +1,58 @@
+//! Location guess statistics
+//!
+//! Round results compare a guess against everyone's past guesses on the same
+//! location. This background task re-aggregates the locations guessed since
+//! the previous run once an hour, then re-rates their difficulty; the first
+//! run aggregates all history.
+
+use std::time::Duration;
+
+use chrono::Utc;
+use dguesser_db::location_stats;
+
+use crate::jobs::claim_interval;
+use crate::state::AppState;
+
+/// How often the statistics are refreshed
+const INTERVAL_SECS: u64 = 60 * 60;
+
+/// Spawn the background location statistics task.
+///
+/// A Redis key per interval ensures only one API instance runs each refresh.
+pub fn spawn_location_stats_task(state: AppState) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(INTERVAL_SECS));
+
+        // Skip the first immediate tick so startup is not slowed down
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+
+            let slot = Utc::now().timestamp() as u64 / INTERVAL_SECS;
+            let key = format!("location_stats:refresh:{slot}");
+            if !claim_interval(state.redis(), &key, INTERVAL_SECS).await {
+                continue;
+            }
+
+            match run_once(&state).await {
+                Ok((locations, rated)) => {
+                    tracing::info!(locations, rated, "Location guess stats refreshed")
+                }
+                Err(e) => tracing::error!(error = %e, "Location guess stats refresh failed"),
+            }
+        }
+    });
+
+    tracing::info!(interval_secs = INTERVAL_SECS, "Location stats task started");
+}
+
+/// Refresh the locations guessed since the last run and re-rate them.
+///
+/// Returns how many locations were refreshed and how many were rated.
+async fn run_once(state: &AppState) -> Result<(u64, u64), sqlx::Error> {
+    let since = location_stats::last_refreshed_at(state.db()).await?;
+    let refreshed = location_stats::refresh(state.db(), since).await?;
+    let rated = location_stats::refresh_difficulty(state.db(), since).await?;
+    Ok((refreshed, rated))
+}
@@ -6,14 +6,34 @@ use std::time::Duration;
 use tokio::signal;
 use tower_http::cors::CorsLayer;
 
+mod analytics;
 mod cache;
+mod captcha;
 mod config;
+mod email;
 mod error;
+mod extract;
+mod featured_maps;
+mod game_archive;
+mod i18n;
+mod jobs;
+mod location_health;
+mod location_stats;
 mod logging;
+mod map_exchange;
+mod map_tiles;
 mod middleware;
+mod outbox;
+mod presence;
+mod render;
 mod routes;
+mod runtime_config;
+mod score_chain;
 mod socket;
 mod state;
+mod static_map;
+mod storage;
+mod street_view;
 
 use config::Config;
 use state::AppState;
@@ -40,11 +60,64 @@ async fn main() -> anyhow::Result<()> {
     // Create application state
     let state = AppState::new(&config).await?;
 
+    // Route heavy reads away from the replica while it lags behind
+    if state.db_pools().has_replica() {
+        spawn_replica_lag_task(state.db_pools().clone());
+    }
+
     // Spawn background task for session cleanup (runs every hour)
-    spawn_session_cleanup_task(state.db().clone());
+    spawn_session_cleanup_task(
+        state.db().clone(),
+        config.stale_game_hours,
+        config.chat_retention_days,
+    );
+
+    // Start email delivery and the weekly digest scheduler
+    let mailer = config.mailer.build()?;
+    if is_production && config.mailer.provider == dguesser_mailer::MailProvider::Log {
+        tracing::warn!("MAIL_PROVIDER is 'log' in production; emails will not be delivered");
+    }
+    tracing::info!(provider = mailer.name(), "Mailer configured");
+    dguesser_mailer::queue::spawn_delivery_worker(state.db().clone(), mailer);
+    spawn_weekly_digest_task(state.clone());
+
+    // Deliver queued push notifications
+    if let Some(push) = state.push() {
+        dguesser_push::queue::spawn_delivery_worker(state.db().clone(), push.clone());
+    }
+
+    // Periodically verify that location panoramas still exist
+    match config.location_health.clone() {
+        Some(health_config) => location_health::spawn_location_health_task(
+            state.clone(),
+            health_config,
+            config.r2_location_config.is_some(),
+        ),
+        None => tracing::info!("GOOGLE_MAPS_API_KEY not set, location health checker disabled"),
+    }
+
+    // Keep the admin analytics rollups current
+    analytics::spawn_analytics_rollup_task(state.clone());
+
+    // Switch the default map for scheduled featured map events
+    featured_maps::spawn_featured_map_task(state.clone());
+
+    // Aggregate past guesses per location for round result comparisons
+    location_stats::spawn_location_stats_task(state.clone());
+
+    // Publish Socket.IO events queued by game changes
+    outbox::spawn_relay_task(state.clone());
+
+    // Move old games to cold storage
+    match config.game_archive.clone() {
+        Some(archive_config) => game_archive::spawn_archive_task(state.clone(), archive_config),
+        None => tracing::info!("GAME_ARCHIVE_R2_BUCKET not set, game archival disabled"),
+    }
 
     // Build CORS layer
-    let cors = build_cors_layer(&config);
+    runtime_config::spawn_config_watcher(state.clone());
+
+    let cors = build_cors_layer(&config, state.clone());
 
     // Build router (disable Swagger UI in production)
     let app = routes::create_router(state, cors, is_production);
@@ -62,7 +135,10 @@ async fn main() -> anyhow::Result<()> {
 }
 
 /// Build CORS layer based on configuration
-fn build_cors_layer(config: &Config) -> CorsLayer {
+///
+/// The frontend URL and organization subdomains are always allowed; runtime
+/// config reloads can allow more origins.
+fn build_cors_layer(config: &Config, state: AppState) -> CorsLayer {
     use http::HeaderValue;
     use std::time::Duration;
     use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin};
@@ -80,18 +156,35 @@ fn build_cors_layer(config: &Config) -> CorsLayer {
     // This returns whatever method/headers the browser requested in the preflight,
     // which is the correct behavior for APIs with credentials.
     CorsLayer::new()
-        .allow_origin(AllowOrigin::exact(origin))
+        .allow_origin(AllowOrigin::predicate(move |request_origin, _| {
+            request_origin == origin
+                || request_origin
+                    .to_str()
+                    .is_ok_and(|o| state.is_extra_cors_origin(o) || state.orgs().is_org_origin(o))
+        }))
         .allow_methods(AllowMethods::mirror_request())
         .allow_headers(AllowHeaders::mirror_request())
         .allow_credentials(true)
+        .expose_headers([
+            http::HeaderName::from_static(crate::middleware::trace_id::TRACE_ID_HEADER),
+            http::HeaderName::from_static("ratelimit-limit"),
+            http::HeaderName::from_static("ratelimit-remaining"),
+            http::HeaderName::from_static("ratelimit-reset"),
+            http::HeaderName::from_static("ratelimit-policy"),
+            http::header::RETRY_AFTER,
+        ])
         .max_age(Duration::from_secs(3600))
 }
 
 /// Spawn a background task that periodically cleans up expired sessions
 ///
 /// This prevents database bloat from accumulated expired sessions.
-/// Runs every hour and deletes sessions where expires_at < NOW().
-fn spawn_session_cleanup_task(db: sqlx::PgPool) {
+/// Runs every hour and deletes sessions where expires_at < NOW(), along with
+/// stale email verification and password reset tokens, and outbox rows and
+/// client error reports older than 30 days. Solo and streak games idle for `stale_game_hours` are
+/// abandoned; multiplayer games are swept by the realtime server. Party chat
+/// older than `chat_retention_days` is deleted.
+fn spawn_session_cleanup_task(db: sqlx::PgPool, stale_game_hours: i32, chat_retention_days: i32) {
     const CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60); // 1 hour
 
     tokio::spawn(async move {
@@ -115,12 +208,168 @@ fn spawn_session_cleanup_task(db: sqlx::PgPool) {
                     tracing::error!(error = %e, "Failed to cleanup expired sessions");
                 }
             }
+
+            match dguesser_db::emails::cleanup_finished(&db, 30).await {
+                Ok(deleted) if deleted > 0 => {
+                    tracing::info!(deleted_count = deleted, "Cleaned up delivered emails");
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::error!(error = %e, "Failed to cleanup email outbox");
+                }
+            }
+
+            match dguesser_db::socket_outbox::cleanup_finished(&db, 1).await {
+                Ok(deleted) if deleted > 0 => {
+                    tracing::info!(deleted_count = deleted, "Cleaned up relayed socket events");
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::error!(error = %e, "Failed to cleanup socket event outbox");
+                }
+            }
+
+            match dguesser_db::client_errors::delete_older_than(&db, 30).await {
+                Ok(deleted) if deleted > 0 => {
+                    tracing::info!(deleted_count = deleted, "Cleaned up client error reports");
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::error!(error = %e, "Failed to cleanup client error reports");
+                }
+            }
+
+            match dguesser_db::parties::delete_party_messages_older_than(&db, chat_retention_days)
+                .await
+            {
+                Ok(deleted) if deleted > 0 => {
+                    tracing::info!(deleted_count = deleted, "Deleted expired chat messages");
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::error!(error = %e, "Failed to delete expired chat messages");
+                }
+            }
+
+            match dguesser_db::push::cleanup_finished(&db, 7).await {
+                Ok(deleted) if deleted > 0 => {
+                    tracing::info!(
+                        deleted_count = deleted,
+                        "Cleaned up delivered push notifications"
+                    );
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::error!(error = %e, "Failed to cleanup push outbox");
+                }
+            }
+
+            match dguesser_db::challenges::abandon_expired(
+                &db,
+                routes::challenges::CHALLENGE_EXPIRY_DAYS,
+            )
+            .await
+            {
+                Ok(abandoned) if abandoned > 0 => {
+                    tracing::info!(abandoned_count = abandoned, "Abandoned expired challenges");
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::error!(error = %e, "Failed to abandon expired challenges");
+                }
+            }
+
+            match dguesser_db::games::abandon_stale(
+                &db,
+                &[dguesser_db::GameMode::Solo, dguesser_db::GameMode::Streak],
+                stale_game_hours,
+            )
+            .await
+            {
+                Ok(abandoned) if !abandoned.is_empty() => {
+                    tracing::info!(abandoned_count = abandoned.len(), "Abandoned stale games");
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::error!(error = %e, "Failed to abandon stale games");
+                }
+            }
+
+            match dguesser_db::credentials::cleanup_tokens(&db).await {
+                Ok(deleted) if deleted > 0 => {
+                    tracing::info!(deleted_count = deleted, "Cleaned up stale auth tokens");
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::error!(error = %e, "Failed to cleanup auth tokens");
+                }
+            }
         }
     });
 
     tracing::info!("Session cleanup task started (runs hourly)");
 }
 
+/// Spawn a background task that checks read replica lag every few seconds
+///
+/// Reads fall back to the primary while the replica is more than the
+/// configured lag behind or unreachable, and return once it catches up.
+fn spawn_replica_lag_task(pools: dguesser_db::DbPools) {
+    const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            match pools.check_replica_lag().await {
+                Some(Ok(lag)) => tracing::trace!(lag_ms = lag.as_millis() as u64, "Replica lag"),
+                Some(Err(e)) => tracing::warn!(error = %e, "Failed to check replica lag"),
+                None => break,
+            }
+        }
+    });
+}
+
+/// Spawn a background task that queues the weekly stats digest
+///
+/// Checks hourly and queues digests during Monday 09:00 UTC. A Redis key per
+/// ISO week ensures only one instance sends them, once.
+fn spawn_weekly_digest_task(state: AppState) {
+    use chrono::{Datelike, Timelike, Utc, Weekday};
+    const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+    const CLAIM_TTL_SECS: u64 = 8 * 24 * 60 * 60;
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            let now = Utc::now();
+            if now.weekday() != Weekday::Mon || now.hour() != 9 {
+                continue;
+            }
+
+            let week = now.iso_week();
+            let key = format!("mail:weekly_digest:{}-W{:02}", week.year(), week.week());
+            if !jobs::claim_interval(state.redis(), &key, CLAIM_TTL_SECS).await {
+                continue;
+            }
+
+            match dguesser_mailer::digest::enqueue_weekly_digests(state.db(), state.frontend_url())
+                .await
+            {
+                Ok(count) => tracing::info!(count, "Queued weekly digest emails"),
+                Err(e) => tracing::error!(error = %e, "Failed to queue weekly digests"),
+            }
+        }
+    });
+
+    tracing::info!("Weekly digest task started (Mondays 09:00 UTC)");
+}
+
 /// Wait for shutdown signal (SIGTERM or SIGINT)
 async fn shutdown_signal() {
     let ctrl_c = async {
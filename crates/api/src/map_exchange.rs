@@ -0,0 +1,536 @@
+//! Map import/export file formats
+//!
+//! Maps are exchanged as GeoJSON FeatureCollections of Point features or as
+//! CSV with one location per row. Both carry the same fields, so a map
+//! exported from one instance imports into another as-is:
+//!
+//! ```text
+//! lat,lng,panorama_id,country_code,subdivision_code,capture_date,heading,provider
+//! 48.858400,2.294500,CAoSLEFGMVFpcE,FR,FR-75,2021-06,180,google_streetview
+//! ```
+//!
+//! Rows without a provider are Google Street View locations.
+//!
+//! Parsing works on a blocking reader and reports one result per row, so
+//! callers can feed it a request body through a sync bridge and validate
+//! files of any size without buffering them.
+
+use std::fmt;
+use std::io::Read;
+
+use chrono::NaiveDate;
+use dguesser_core::geo::countries::CountryCode;
+use dguesser_core::location::{Location, Map};
+use dguesser_core::streetview::{ImageryError, ImageryProvider};
+use serde::de::{self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde_json::{Value, json};
+
+/// CSV columns written on export, in order.
+pub const CSV_COLUMNS: [&str; 8] = [
+    "lat",
+    "lng",
+    "panorama_id",
+    "country_code",
+    "subdivision_code",
+    "capture_date",
+    "heading",
+    "provider",
+];
+
+/// Supported file formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExchangeFormat {
+    GeoJson,
+    Csv,
+}
+
+impl ExchangeFormat {
+    /// Guess the format from a request Content-Type.
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        let mime = content_type.split(';').next().unwrap_or_default().trim();
+        match mime {
+            "application/geo+json" | "application/json" => Some(Self::GeoJson),
+            "text/csv" => Some(Self::Csv),
+            _ => None,
+        }
+    }
+
+    /// Content-Type for exported files.
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::GeoJson => "application/geo+json",
+            Self::Csv => "text/csv; charset=utf-8",
+        }
+    }
+
+    /// File extension for exported files.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::GeoJson => "geojson",
+            Self::Csv => "csv",
+        }
+    }
+}
+
+/// One location as it appears in an exchange file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExchangeLocation {
+    pub lat: f64,
+    pub lng: f64,
+    pub panorama_id: Option<String>,
+    pub country_code: Option<String>,
+    pub subdivision_code: Option<String>,
+    pub capture_date: Option<NaiveDate>,
+    pub heading: Option<f64>,
+    pub provider: ImageryProvider,
+}
+
+impl ExchangeLocation {
+    /// Exchange representation of a stored location.
+    pub fn from_location(location: &Location) -> Self {
+        Self {
+            lat: location.lat,
+            lng: location.lng,
+            panorama_id: Some(location.panorama_id.clone()),
+            country_code: location.country_code.clone(),
+            subdivision_code: location.subdivision_code.clone(),
+            capture_date: location.capture_date,
+            heading: location.heading,
+            provider: location.provider,
+        }
+    }
+
+    /// Panorama ID to store the location under. Rows without one get a
+    /// coordinate-based ID, like locations added from Street View URLs.
+    pub fn storage_panorama_id(&self) -> String {
+        self.panorama_id.clone().unwrap_or_else(|| format!("url_{:.6}_{:.6}", self.lat, self.lng))
+    }
+
+    /// Check ranges and formats, normalizing codes to upper case.
+    fn validate(mut self) -> Result<Self, String> {
+        if !self.lat.is_finite() || !(-90.0..=90.0).contains(&self.lat) {
+            return Err(format!("Latitude {} out of range", self.lat));
+        }
+        if !self.lng.is_finite() || !(-180.0..=180.0).contains(&self.lng) {
+            return Err(format!("Longitude {} out of range", self.lng));
+        }
+        self.provider.validate_image_id(self.panorama_id.as_deref()).map_err(|e| e.to_string())?;
+        if let Some(code) = &mut self.country_code {
+            *code = CountryCode::parse(code).map_err(|e| e.to_string())?.to_string();
+        }
+        if let Some(code) = &mut self.subdivision_code {
+            if code.is_empty() || code.len() > 10 {
+                return Err(format!("Invalid subdivision code '{}'", code));
+            }
+            code.make_ascii_uppercase();
+        }
+        if let Some(heading) = self.heading
+            && !(heading.is_finite() && (0.0..=360.0).contains(&heading))
+        {
+            return Err(format!("Heading {} out of range", heading));
+        }
+        Ok(self)
+    }
+}
+
+/// Parse an imagery provider name.
+fn parse_provider(value: &str) -> Result<ImageryProvider, String> {
+    value.to_ascii_lowercase().parse().map_err(|e: ImageryError| e.to_string())
+}
+
+/// Parse a capture date given as `YYYY-MM` or `YYYY-MM-DD`.
+fn parse_capture_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(&format!("{value}-01"), "%Y-%m-%d"))
+        .map_err(|_| format!("Invalid capture date '{}'", value))
+}
+
+/// Format a capture date the way Street View reports it (`YYYY-MM`).
+fn format_capture_date(date: NaiveDate) -> String {
+    date.format("%Y-%m").to_string()
+}
+
+// =============================================================================
+// Export
+// =============================================================================
+
+/// Opening of an exported GeoJSON FeatureCollection, up to the first feature.
+pub fn geojson_header(map: &Map) -> String {
+    let name = json!(map.name);
+    let description = json!(map.description);
+    format!(
+        "{{\"type\":\"FeatureCollection\",\"name\":{name},\"description\":{description},\"features\":[\n"
+    )
+}
+
+/// Closing of an exported GeoJSON FeatureCollection.
+pub const GEOJSON_FOOTER: &str = "\n]}\n";
+
+/// One exported GeoJSON feature, prefixed with a separator unless it is the
+/// first.
+pub fn geojson_feature(location: &ExchangeLocation, first: bool) -> String {
+    let feature = json!({
+        "type": "Feature",
+        "geometry": { "type": "Point", "coordinates": [location.lng, location.lat] },
+        "properties": {
+            "panorama_id": location.panorama_id,
+            "country_code": location.country_code,
+            "subdivision_code": location.subdivision_code,
+            "capture_date": location.capture_date.map(format_capture_date),
+            "heading": location.heading,
+            "provider": location.provider,
+        },
+    });
+    if first { feature.to_string() } else { format!(",\n{feature}") }
+}
+
+/// Header line of an exported CSV file.
+pub fn csv_header() -> String {
+    format!("{}\n", CSV_COLUMNS.join(","))
+}
+
+/// One exported CSV row, including the trailing newline.
+pub fn csv_row(location: &ExchangeLocation) -> String {
+    let fields = [
+        format!("{:.6}", location.lat),
+        format!("{:.6}", location.lng),
+        location.panorama_id.clone().unwrap_or_default(),
+        location.country_code.clone().unwrap_or_default(),
+        location.subdivision_code.clone().unwrap_or_default(),
+        location.capture_date.map(format_capture_date).unwrap_or_default(),
+        location.heading.map(|h| h.to_string()).unwrap_or_default(),
+        location.provider.to_string(),
+    ];
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    // Writing to a Vec cannot fail
+    let _ = writer.write_record(&fields);
+    let bytes = writer.into_inner().unwrap_or_default();
+    String::from_utf8(bytes).unwrap_or_default()
+}
+
+// =============================================================================
+// Import
+// =============================================================================
+
+/// Result of parsing one row. Rows are numbered from 1 (CSV data rows
+/// after the header, or features in file order).
+pub type ParsedRow = (usize, Result<ExchangeLocation, String>);
+
+/// Parse an exchange file, calling `on_row` for every row in order.
+///
+/// `on_row` returns false to stop reading early. Errors in individual rows
+/// are reported through `on_row`; the returned error is for files that
+/// cannot be read at all.
+pub fn parse<R: Read>(
+    format: ExchangeFormat,
+    reader: R,
+    on_row: impl FnMut(ParsedRow) -> bool,
+) -> Result<(), String> {
+    match format {
+        ExchangeFormat::Csv => parse_csv(reader, on_row),
+        ExchangeFormat::GeoJson => parse_geojson(reader, on_row),
+    }
+}
+
+/// Positions of the known columns in a CSV header.
+struct CsvColumns {
+    lat: usize,
+    lng: usize,
+    panorama_id: Option<usize>,
+    country_code: Option<usize>,
+    subdivision_code: Option<usize>,
+    capture_date: Option<usize>,
+    heading: Option<usize>,
+    provider: Option<usize>,
+}
+
+impl CsvColumns {
+    fn from_header(header: &csv::StringRecord) -> Result<Self, String> {
+        let find = |names: &[&str]| {
+            header.iter().position(|h| names.iter().any(|n| h.trim().eq_ignore_ascii_case(n)))
+        };
+        Ok(Self {
+            lat: find(&["lat", "latitude"]).ok_or("CSV header has no 'lat' column")?,
+            lng: find(&["lng", "lon", "longitude"]).ok_or("CSV header has no 'lng' column")?,
+            panorama_id: find(&["panorama_id", "panoid", "pano_id"]),
+            country_code: find(&["country_code", "country"]),
+            subdivision_code: find(&["subdivision_code", "subdivision"]),
+            capture_date: find(&["capture_date"]),
+            heading: find(&["heading"]),
+            provider: find(&["provider"]),
+        })
+    }
+
+    fn parse_row(&self, record: &csv::StringRecord) -> Result<ExchangeLocation, String> {
+        let text = |index: Option<usize>| {
+            index
+                .and_then(|i| record.get(i))
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(String::from)
+        };
+        let number = |index: usize, name: &str| {
+            let value = record.get(index).map(str::trim).unwrap_or_default();
+            value.parse::<f64>().map_err(|_| format!("Invalid {} '{}'", name, value))
+        };
+
+        ExchangeLocation {
+            lat: number(self.lat, "latitude")?,
+            lng: number(self.lng, "longitude")?,
+            panorama_id: text(self.panorama_id),
+            country_code: text(self.country_code),
+            subdivision_code: text(self.subdivision_code),
+            capture_date: text(self.capture_date).map(|d| parse_capture_date(&d)).transpose()?,
+            heading: text(self.heading)
+                .map(|h| h.parse::<f64>().map_err(|_| format!("Invalid heading '{}'", h)))
+                .transpose()?,
+            provider: text(self.provider)
+                .map(|p| parse_provider(&p))
+                .transpose()?
+                .unwrap_or_default(),
+        }
+        .validate()
+    }
+}
+
+fn parse_csv<R: Read>(reader: R, mut on_row: impl FnMut(ParsedRow) -> bool) -> Result<(), String> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(reader);
+    let header = reader.headers().map_err(|e| format!("Invalid CSV header: {}", e))?.clone();
+    let columns = CsvColumns::from_header(&header)?;
+
+    let mut record = csv::StringRecord::new();
+    let mut row = 0;
+    loop {
+        row += 1;
+        let parsed = match reader.read_record(&mut record) {
+            Ok(false) => return Ok(()),
+            Ok(true) => columns.parse_row(&record),
+            // Malformed bytes poison the rest of the reader; report and stop
+            Err(e) if e.is_io_error() => return Err(format!("Failed to read CSV: {}", e)),
+            Err(e) => Err(format!("Invalid CSV row: {}", e)),
+        };
+        if !on_row((row, parsed)) {
+            return Ok(());
+        }
+    }
+}
+
+/// Convert a GeoJSON Point feature into a location.
+fn parse_feature(feature: Value) -> Result<ExchangeLocation, String> {
+    let geometry = feature.get("geometry").ok_or("Feature has no geometry")?;
+    if geometry.get("type").and_then(Value::as_str) != Some("Point") {
+        return Err("Feature geometry must be a Point".to_string());
+    }
+    let coordinates = geometry
+        .get("coordinates")
+        .and_then(Value::as_array)
+        .filter(|c| c.len() >= 2)
+        .ok_or("Point has no coordinates")?;
+    let lng = coordinates[0].as_f64().ok_or("Invalid longitude")?;
+    let lat = coordinates[1].as_f64().ok_or("Invalid latitude")?;
+
+    let properties = feature.get("properties");
+    let text = |key: &str| {
+        properties
+            .and_then(|p| p.get(key))
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(String::from)
+    };
+
+    ExchangeLocation {
+        lat,
+        lng,
+        panorama_id: text("panorama_id"),
+        country_code: text("country_code"),
+        subdivision_code: text("subdivision_code"),
+        capture_date: text("capture_date").map(|d| parse_capture_date(&d)).transpose()?,
+        heading: properties.and_then(|p| p.get("heading")).and_then(Value::as_f64),
+        provider: text("provider").map(|p| parse_provider(&p)).transpose()?.unwrap_or_default(),
+    }
+    .validate()
+}
+
+/// Reads a FeatureCollection, handing each feature to a callback as soon as
+/// it is parsed instead of collecting the `features` array.
+struct FeatureCollectionVisitor<F> {
+    on_row: F,
+}
+
+/// Signals that the callback asked to stop reading.
+const STOPPED: &str = "import stopped";
+
+impl<'de, F: FnMut(ParsedRow) -> bool> Visitor<'de> for FeatureCollectionVisitor<F> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a GeoJSON FeatureCollection")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(mut self, mut map: A) -> Result<(), A::Error> {
+        let mut found = false;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "features" => {
+                    map.next_value_seed(FeaturesSeed { on_row: &mut self.on_row })?;
+                    found = true;
+                }
+                "type" => {
+                    let kind: String = map.next_value()?;
+                    if kind != "FeatureCollection" {
+                        return Err(de::Error::custom(format!(
+                            "expected a FeatureCollection, found {kind}"
+                        )));
+                    }
+                }
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        if found { Ok(()) } else { Err(de::Error::missing_field("features")) }
+    }
+}
+
+struct FeaturesSeed<'a, F> {
+    on_row: &'a mut F,
+}
+
+impl<'de, F: FnMut(ParsedRow) -> bool> DeserializeSeed<'de> for FeaturesSeed<'_, F> {
+    type Value = ();
+
+    fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, F: FnMut(ParsedRow) -> bool> Visitor<'de> for FeaturesSeed<'_, F> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an array of features")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let mut row = 0;
+        while let Some(feature) = seq.next_element::<Value>()? {
+            row += 1;
+            if !(self.on_row)((row, parse_feature(feature))) {
+                return Err(de::Error::custom(STOPPED));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_geojson<R: Read>(reader: R, on_row: impl FnMut(ParsedRow) -> bool) -> Result<(), String> {
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    match de::Deserializer::deserialize_map(&mut deserializer, FeatureCollectionVisitor { on_row })
+    {
+        Ok(()) => Ok(()),
+        Err(e) if e.to_string().starts_with(STOPPED) => Ok(()),
+        Err(e) => Err(format!("Invalid GeoJSON: {}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(format: ExchangeFormat, input: &str) -> Vec<ParsedRow> {
+        let mut rows = Vec::new();
+        parse(format, input.as_bytes(), |row| {
+            rows.push(row);
+            true
+        })
+        .unwrap();
+        rows
+    }
+
+    fn location() -> ExchangeLocation {
+        ExchangeLocation {
+            lat: 48.8584,
+            lng: 2.2945,
+            panorama_id: Some("pano_a".to_string()),
+            country_code: Some("FR".to_string()),
+            subdivision_code: None,
+            capture_date: NaiveDate::from_ymd_opt(2021, 6, 1),
+            heading: Some(180.0),
+            provider: ImageryProvider::GoogleStreetView,
+        }
+    }
+
+    #[test]
+    fn test_csv_roundtrip() {
+        let file = format!("{}{}", csv_header(), csv_row(&location()));
+        let rows = collect(ExchangeFormat::Csv, &file);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].1.as_ref().unwrap(), &location());
+    }
+
+    #[test]
+    fn test_geojson_roundtrip() {
+        let map_header = "{\"type\":\"FeatureCollection\",\"name\":\"Test\",\"features\":[\n";
+        let file = format!(
+            "{map_header}{}{}{GEOJSON_FOOTER}",
+            geojson_feature(&location(), true),
+            geojson_feature(&location(), false)
+        );
+        let rows = collect(ExchangeFormat::GeoJson, &file);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].0, 2);
+        assert_eq!(rows[1].1.as_ref().unwrap(), &location());
+    }
+
+    #[test]
+    fn test_csv_reports_invalid_rows() {
+        let file = "Latitude,Longitude,country\n10,20,us\n95,20,\nabc,1,\n1,2,USA\n";
+        let rows = collect(ExchangeFormat::Csv, file);
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[0].1.as_ref().unwrap().country_code.as_deref(), Some("US"));
+        assert!(rows[1].1.as_ref().unwrap_err().contains("Latitude"));
+        assert!(rows[2].1.as_ref().unwrap_err().contains("latitude"));
+        assert!(rows[3].1.as_ref().unwrap_err().contains("country code"));
+    }
+
+    #[test]
+    fn test_provider_column_validates_image_ids() {
+        let file = "lat,lng,panorama_id,provider\n\
+                    1,2,498763468214164,mapillary\n\
+                    1,2,,mapillary\n\
+                    1,2,CAoSLEFGMVFp,Mapillary\n\
+                    1,2,,bing_streetside\n\
+                    1,2,,streetview\n";
+        let rows = collect(ExchangeFormat::Csv, file);
+        assert_eq!(rows[0].1.as_ref().unwrap().provider, ImageryProvider::Mapillary);
+        assert!(rows[1].1.as_ref().unwrap_err().contains("need an image ID"));
+        assert!(rows[2].1.as_ref().unwrap_err().contains("Invalid Mapillary image ID"));
+        assert_eq!(rows[3].1.as_ref().unwrap().provider, ImageryProvider::BingStreetside);
+        assert!(rows[4].1.as_ref().unwrap_err().contains("Unknown imagery provider"));
+    }
+
+    #[test]
+    fn test_geojson_rejects_non_points_and_stops_early() {
+        let file = r#"{"features": [
+            {"type": "Feature", "geometry": {"type": "LineString", "coordinates": []}},
+            {"type": "Feature", "geometry": {"type": "Point", "coordinates": [1, 2]}},
+            {"type": "Feature", "geometry": {"type": "Point", "coordinates": [3, 4]}}
+        ], "type": "FeatureCollection"}"#;
+
+        let mut rows = Vec::new();
+        parse(ExchangeFormat::GeoJson, file.as_bytes(), |row| {
+            rows.push(row);
+            rows.len() < 2
+        })
+        .unwrap();
+        assert_eq!(rows.len(), 2);
+        assert!(rows[0].1.is_err());
+        assert_eq!(rows[1].1.as_ref().unwrap().lat, 2.0);
+
+        let err = parse(ExchangeFormat::GeoJson, r#"{"type": "Feature"}"#.as_bytes(), |_| true);
+        assert!(err.is_err());
+    }
+}
@@ -0,0 +1,494 @@
+//! Map Tiles API sessions and tile proxying
+//!
+//! Street View and map imagery is served through the API so the Maps key
+//! never reaches the browser. Each user gets their own Map Tiles session per
+//! map type, created on demand, cached in Redis and replaced a day before it
+//! expires. Clients receive a short-lived signed tile token instead of the
+//! session itself and fetch tiles through the proxy, which counts every tile
+//! against the user for billing attribution.
+//!
+//! Tile tokens look like `mt1.<user_id>.<map type>.<expires_unix>.<hex mac>`.
+
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use dguesser_auth::{SignedTokenError, TokenSigner};
+use dguesser_redis::RedisConnection;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+
+const CREATE_SESSION_URL: &str = "https://tile.googleapis.com/v1/createSession";
+const TILES_BASE_URL: &str = "https://tile.googleapis.com/v1";
+
+/// How long a tile token stays valid; clients request a new one before then
+pub const TILE_TOKEN_TTL_SECS: i64 = 60 * 60;
+
+/// Sessions are replaced this long before Google expires them
+const ROTATE_BEFORE_SECS: i64 = 24 * 60 * 60;
+
+/// Daily usage counters are kept for a quarter
+const USAGE_TTL_SECS: i64 = 90 * 24 * 60 * 60;
+
+/// Largest tile accepted from upstream
+const MAX_TILE_BYTES: usize = 2 * 1024 * 1024;
+
+/// Highest zoom level proxied (Street View tops out at 5)
+const MAX_ZOOM: u8 = 22;
+
+const TOKEN_VERSION: &str = "mt1";
+
+/// Imagery a session serves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TileMapType {
+    StreetView,
+    Roadmap,
+    Satellite,
+}
+
+impl TileMapType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::StreetView => "streetview",
+            Self::Roadmap => "roadmap",
+            Self::Satellite => "satellite",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "streetview" => Some(Self::StreetView),
+            "roadmap" => Some(Self::Roadmap),
+            "satellite" => Some(Self::Satellite),
+            _ => None,
+        }
+    }
+}
+
+/// A Map Tiles API session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TileSession {
+    /// Session token passed with every tile request
+    pub session: String,
+    pub expires_at: DateTime<Utc>,
+    pub tile_width: u32,
+    pub tile_height: u32,
+    /// "jpeg" or "png"
+    pub image_format: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateSessionResponse {
+    session: String,
+    /// Expiry as Unix seconds, sent as a string
+    expiry: String,
+    tile_width: u32,
+    tile_height: u32,
+    image_format: String,
+}
+
+impl CreateSessionResponse {
+    fn into_session(self) -> Option<TileSession> {
+        let expires_at = DateTime::from_timestamp(self.expiry.parse().ok()?, 0)?;
+        Some(TileSession {
+            session: self.session,
+            expires_at,
+            tile_width: self.tile_width,
+            tile_height: self.tile_height,
+            image_format: self.image_format,
+        })
+    }
+}
+
+/// One tile to fetch.
+#[derive(Debug, Clone, Copy)]
+pub struct TileCoords<'a> {
+    pub z: u8,
+    pub x: u32,
+    pub y: u32,
+    /// Panorama, required for Street View tiles
+    pub panorama_id: Option<&'a str>,
+}
+
+impl TileCoords<'_> {
+    /// Whether the coordinates exist at this zoom level.
+    pub fn is_valid(&self) -> bool {
+        let size = 1u64 << self.z.min(MAX_ZOOM);
+        self.z <= MAX_ZOOM && u64::from(self.x) < size && u64::from(self.y) < size
+    }
+}
+
+/// A fetched tile.
+#[derive(Debug)]
+pub struct Tile {
+    pub bytes: Vec<u8>,
+    pub content_type: String,
+}
+
+/// Tiles one user fetched on one day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TileUsage {
+    pub user_id: String,
+    pub map_type: TileMapType,
+    pub tiles: u64,
+}
+
+/// Verified contents of a tile token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TileTokenClaims {
+    pub user_id: String,
+    pub map_type: TileMapType,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Map Tiles errors
+#[derive(Debug, thiserror::Error)]
+pub enum MapTilesError {
+    #[error("map tiles are not configured")]
+    NotConfigured,
+    #[error("map tiles request failed: {0}")]
+    Request(reqwest::Error),
+    #[error("map tiles API returned {0}")]
+    Status(u16),
+    #[error("invalid map tiles session response")]
+    InvalidSession,
+    #[error("tile is too large")]
+    TooLarge,
+    #[error("session store failed: {0}")]
+    Redis(#[from] redis::RedisError),
+}
+
+/// Errors from verifying a tile token.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum TileTokenError {
+    #[error("Malformed tile token")]
+    Malformed,
+    #[error("Invalid tile token signature")]
+    BadSignature,
+    #[error("Tile token expired")]
+    Expired,
+}
+
+impl From<SignedTokenError> for TileTokenError {
+    fn from(e: SignedTokenError) -> Self {
+        match e {
+            SignedTokenError::Malformed => Self::Malformed,
+            SignedTokenError::BadSignature => Self::BadSignature,
+            SignedTokenError::Expired => Self::Expired,
+        }
+    }
+}
+
+/// Map Tiles client. Cheap to clone.
+#[derive(Clone)]
+pub struct MapTilesClient {
+    http: reqwest::Client,
+    /// Maps API key; tiles are unavailable without one
+    api_key: Option<String>,
+    /// Tile token signer; tiles are unavailable without a secret
+    tokens: Option<TokenSigner>,
+    /// Where sessions and usage counters are kept
+    redis: RedisConnection,
+}
+
+impl MapTilesClient {
+    pub fn new(
+        api_key: Option<String>,
+        token_secret: Option<&str>,
+        redis: RedisConnection,
+    ) -> Self {
+        let http =
+            reqwest::Client::builder().timeout(Duration::from_secs(10)).build().unwrap_or_default();
+        let tokens = token_secret.map(|secret| TokenSigner::new(TOKEN_VERSION, secret));
+        Self { http, api_key, tokens, redis }
+    }
+
+    /// Whether tiles can be served.
+    pub fn is_configured(&self) -> bool {
+        self.api_key.is_some() && self.tokens.is_some()
+    }
+
+    /// The user's session for a map type, created when there is none or the
+    /// cached one is about to expire.
+    pub async fn session_for(
+        &self,
+        user_id: &str,
+        map_type: TileMapType,
+        now: DateTime<Utc>,
+    ) -> Result<TileSession, MapTilesError> {
+        let key = session_key(user_id, map_type);
+        let mut conn = self.redis.clone();
+
+        let cached: Option<String> = conn.get(&key).await?;
+        if let Some(session) =
+            cached.and_then(|json| serde_json::from_str::<TileSession>(&json).ok())
+            && !needs_rotation(&session, now)
+        {
+            return Ok(session);
+        }
+
+        let session = self.create_session(map_type).await?;
+        let ttl = (session.expires_at - now).num_seconds() - ROTATE_BEFORE_SECS;
+        if ttl > 0
+            && let Ok(json) = serde_json::to_string(&session)
+        {
+            conn.set_ex::<_, _, ()>(&key, json, ttl as u64).await?;
+        }
+        tracing::info!(
+            user_id,
+            map_type = map_type.as_str(),
+            expires_at = %session.expires_at,
+            "Created map tiles session"
+        );
+        Ok(session)
+    }
+
+    async fn create_session(&self, map_type: TileMapType) -> Result<TileSession, MapTilesError> {
+        let api_key = self.api_key.as_deref().ok_or(MapTilesError::NotConfigured)?;
+        let body = serde_json::json!({
+            "mapType": map_type.as_str(),
+            "language": "en-US",
+            "region": "US",
+        });
+
+        // Errors carry the URL, which includes the key
+        let response = self
+            .http
+            .post(CREATE_SESSION_URL)
+            .query(&[("key", api_key)])
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| MapTilesError::Request(e.without_url()))?;
+        if !response.status().is_success() {
+            return Err(MapTilesError::Status(response.status().as_u16()));
+        }
+        response
+            .json::<CreateSessionResponse>()
+            .await
+            .map_err(|e| MapTilesError::Request(e.without_url()))?
+            .into_session()
+            .ok_or(MapTilesError::InvalidSession)
+    }
+
+    /// Fetch one tile with the user's session.
+    pub async fn fetch_tile(
+        &self,
+        session: &TileSession,
+        map_type: TileMapType,
+        coords: TileCoords<'_>,
+    ) -> Result<Tile, MapTilesError> {
+        let api_key = self.api_key.as_deref().ok_or(MapTilesError::NotConfigured)?;
+        let TileCoords { z, x, y, panorama_id } = coords;
+
+        let request = match map_type {
+            TileMapType::StreetView => self
+                .http
+                .get(format!("{TILES_BASE_URL}/streetview/tiles/{z}/{x}/{y}"))
+                .query(&[("panoId", panorama_id.unwrap_or_default())]),
+            TileMapType::Roadmap | TileMapType::Satellite => {
+                self.http.get(format!("{TILES_BASE_URL}/2dtiles/{z}/{x}/{y}"))
+            }
+        };
+        let response = request
+            .query(&[("session", session.session.as_str()), ("key", api_key)])
+            .send()
+            .await
+            .map_err(|e| MapTilesError::Request(e.without_url()))?;
+        if !response.status().is_success() {
+            return Err(MapTilesError::Status(response.status().as_u16()));
+        }
+        if response.content_length().is_some_and(|len| len as usize > MAX_TILE_BYTES) {
+            return Err(MapTilesError::TooLarge);
+        }
+
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or(match session.image_format.as_str() {
+                "png" => "image/png",
+                _ => "image/jpeg",
+            })
+            .to_string();
+        let bytes = response.bytes().await.map_err(|e| MapTilesError::Request(e.without_url()))?;
+        if bytes.len() > MAX_TILE_BYTES {
+            return Err(MapTilesError::TooLarge);
+        }
+        Ok(Tile { bytes: bytes.to_vec(), content_type })
+    }
+
+    /// Count a served tile against the user.
+    pub async fn record_tile(&self, user_id: &str, map_type: TileMapType, date: NaiveDate) {
+        let key = usage_key(date);
+        let field = format!("{user_id}:{}", map_type.as_str());
+        let result = redis::pipe()
+            .hincr(&key, field, 1)
+            .ignore()
+            .expire(&key, USAGE_TTL_SECS)
+            .ignore()
+            .query_async::<()>(&mut self.redis.clone())
+            .await;
+        if let Err(e) = result {
+            tracing::warn!(error = %e, "Failed to record map tile usage");
+        }
+    }
+
+    /// Tiles served per user and map type on a day (UTC), most first.
+    pub async fn usage_on(&self, date: NaiveDate) -> Result<Vec<TileUsage>, MapTilesError> {
+        let mut conn = self.redis.clone();
+        let counts: Vec<(String, u64)> = conn.hgetall(usage_key(date)).await?;
+
+        let mut usage: Vec<TileUsage> = counts
+            .into_iter()
+            .filter_map(|(field, tiles)| {
+                let (user_id, map_type) = field.rsplit_once(':')?;
+                Some(TileUsage {
+                    user_id: user_id.to_string(),
+                    map_type: TileMapType::parse(map_type)?,
+                    tiles,
+                })
+            })
+            .collect();
+        usage.sort_by(|a, b| b.tiles.cmp(&a.tiles).then_with(|| a.user_id.cmp(&b.user_id)));
+        Ok(usage)
+    }
+
+    /// Mint a tile token for the user, valid for at most
+    /// [`TILE_TOKEN_TTL_SECS`] and never past the session's expiry.
+    pub fn mint_token(
+        &self,
+        user_id: &str,
+        map_type: TileMapType,
+        session_expires_at: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Result<(String, DateTime<Utc>), MapTilesError> {
+        let tokens = self.tokens.as_ref().ok_or(MapTilesError::NotConfigured)?;
+        let ttl = chrono::Duration::seconds(TILE_TOKEN_TTL_SECS).min(session_expires_at - now);
+        Ok(tokens.mint(&[user_id, map_type.as_str()], ttl, now))
+    }
+
+    /// Verify a tile token's signature and expiry.
+    pub fn verify_token(
+        &self,
+        token: &str,
+        now: DateTime<Utc>,
+    ) -> Result<TileTokenClaims, TileTokenError> {
+        let tokens = self.tokens.as_ref().ok_or(TileTokenError::BadSignature)?;
+        let verified = tokens.verify(token, 2, now)?;
+        let [user_id, map_type] = verified.fields[..] else {
+            return Err(TileTokenError::Malformed);
+        };
+        let map_type = TileMapType::parse(map_type).ok_or(TileTokenError::Malformed)?;
+
+        Ok(TileTokenClaims {
+            user_id: user_id.to_string(),
+            map_type,
+            expires_at: verified.expires_at,
+        })
+    }
+}
+
+/// Whether a session is close enough to expiry to be replaced.
+fn needs_rotation(session: &TileSession, now: DateTime<Utc>) -> bool {
+    (session.expires_at - now).num_seconds() <= ROTATE_BEFORE_SECS
+}
+
+fn session_key(user_id: &str, map_type: TileMapType) -> String {
+    format!("map_tiles:session:{user_id}:{}", map_type.as_str())
+}
+
+fn usage_key(date: NaiveDate) -> String {
+    format!("map_tiles:usage:{}", date.format("%Y-%m-%d"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client() -> MapTilesClient {
+        let redis = RedisConnection::open_url("redis://127.0.0.1/").unwrap();
+        MapTilesClient::new(Some("key".to_string()), Some("secret"), redis)
+    }
+
+    #[tokio::test]
+    async fn test_token_round_trip() {
+        let client = client();
+        let now = Utc::now();
+        let session_expiry = now + chrono::Duration::days(14);
+        let (token, expires_at) = client
+            .mint_token("usr_V1StGXR8_Z5j", TileMapType::StreetView, session_expiry, now)
+            .unwrap();
+
+        let claims = client.verify_token(&token, now).unwrap();
+        assert_eq!(claims.user_id, "usr_V1StGXR8_Z5j");
+        assert_eq!(claims.map_type, TileMapType::StreetView);
+        assert_eq!(claims.expires_at.timestamp(), expires_at.timestamp());
+    }
+
+    #[tokio::test]
+    async fn test_token_never_outlives_session() {
+        let client = client();
+        let now = Utc::now();
+        let session_expiry = now + chrono::Duration::minutes(5);
+        let (token, expires_at) = client
+            .mint_token("usr_V1StGXR8_Z5j", TileMapType::Roadmap, session_expiry, now)
+            .unwrap();
+
+        assert_eq!(expires_at, session_expiry);
+        let later = now + chrono::Duration::minutes(6);
+        assert_eq!(client.verify_token(&token, later), Err(TileTokenError::Expired));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_tampered_token() {
+        let client = client();
+        let now = Utc::now();
+        let (token, _) = client
+            .mint_token(
+                "usr_V1StGXR8_Z5j",
+                TileMapType::Roadmap,
+                now + chrono::Duration::days(1),
+                now,
+            )
+            .unwrap();
+
+        let tampered = token.replacen("roadmap", "satellite", 1);
+        assert_eq!(client.verify_token(&tampered, now), Err(TileTokenError::BadSignature));
+        assert_eq!(client.verify_token("mt1.usr_x", now), Err(TileTokenError::Malformed));
+    }
+
+    #[test]
+    fn test_session_rotation() {
+        let now = Utc::now();
+        let session = |expires_in: chrono::Duration| TileSession {
+            session: "s".to_string(),
+            expires_at: now + expires_in,
+            tile_width: 512,
+            tile_height: 512,
+            image_format: "jpeg".to_string(),
+        };
+        assert!(!needs_rotation(&session(chrono::Duration::days(13)), now));
+        assert!(needs_rotation(&session(chrono::Duration::hours(23)), now));
+    }
+
+    #[test]
+    fn test_tile_coords() {
+        let coords = |z, x, y| TileCoords { z, x, y, panorama_id: None };
+        assert!(coords(0, 0, 0).is_valid());
+        assert!(coords(3, 7, 7).is_valid());
+        assert!(!coords(3, 8, 0).is_valid());
+        assert!(!coords(MAX_ZOOM + 1, 0, 0).is_valid());
+    }
+
+    #[test]
+    fn test_parse_session_response() {
+        let response: CreateSessionResponse = serde_json::from_str(
+            r#"{"session":"IgYIAhA","expiry":"1700000000","tileWidth":512,"tileHeight":512,"imageFormat":"jpeg"}"#,
+        )
+        .unwrap();
+        let session = response.into_session().unwrap();
+        assert_eq!(session.expires_at.timestamp(), 1_700_000_000);
+        assert_eq!(session.tile_width, 512);
+    }
+}
@@ -0,0 +1,194 @@
+//! Abuse throttles for guest accounts and lobbies
+//!
+//! Layered on top of the per-request rate limits: each network may create a
+//! limited number of guest accounts per day, and each user may hold a
+//! limited number of open lobbies. Going over a threshold starts a cooldown
+//! during which the action is refused outright. Counters live in Redis and
+//! fail open when it is unavailable; the request rate limits still apply.
+
+use axum::http::StatusCode;
+use dguesser_redis::RedisConnection;
+use redis::AsyncCommands;
+
+use crate::error::ApiError;
+use crate::state::AppState;
+
+/// Multiplayer lobbies a user may have open at once
+pub const MAX_OPEN_LOBBIES: i64 = 3;
+
+/// A counter that starts a cooldown once it goes over its limit.
+#[derive(Clone, Copy, Debug)]
+struct Throttle {
+    /// Redis key prefix
+    prefix: &'static str,
+    /// Hits allowed per window
+    limit: u32,
+    /// Counting window in seconds
+    window_secs: u64,
+    /// Cooldown started when the limit is exceeded, in seconds
+    cooldown_secs: u64,
+}
+
+/// Guest accounts per IP: 20 a day, then a 6 hour cooldown
+const GUEST_ACCOUNTS: Throttle = Throttle {
+    prefix: "abuse:guests",
+    limit: 20,
+    window_secs: 24 * 60 * 60,
+    cooldown_secs: 6 * 60 * 60,
+};
+
+/// Lobbies refused for being over [`MAX_OPEN_LOBBIES`]: 5 in 10 minutes,
+/// then a 15 minute cooldown
+const LOBBY_REFUSALS: Throttle =
+    Throttle { prefix: "abuse:lobbies", limit: 5, window_secs: 10 * 60, cooldown_secs: 15 * 60 };
+
+impl Throttle {
+    fn counter_key(&self, subject: &str) -> String {
+        format!("{}:{}", self.prefix, subject)
+    }
+
+    fn cooldown_key(&self, subject: &str) -> String {
+        format!("{}:cooldown:{}", self.prefix, subject)
+    }
+
+    /// Seconds left on the subject's cooldown, if one is running.
+    async fn cooldown_remaining(&self, redis: &RedisConnection, subject: &str) -> Option<u64> {
+        let mut conn = redis.clone();
+        let ttl: i64 = conn.ttl(self.cooldown_key(subject)).await.ok()?;
+        (ttl > 0).then_some(ttl as u64)
+    }
+
+    /// Count a hit. Returns true if it went over the limit, which starts the
+    /// cooldown and resets the counter.
+    async fn hit(&self, redis: &RedisConnection, subject: &str) -> bool {
+        let key = self.counter_key(subject);
+        let mut conn = redis.clone();
+        let count: u32 = match conn.incr(&key, 1).await {
+            Ok(count) => count,
+            Err(e) => {
+                tracing::warn!(error = %e, prefix = self.prefix, "Failed to count throttled action");
+                return false;
+            }
+        };
+        if count == 1 {
+            let _ = conn.expire::<_, ()>(&key, self.window_secs as i64).await;
+        }
+        if count <= self.limit {
+            return false;
+        }
+
+        let result: Result<(), _> = redis::pipe()
+            .set_ex(self.cooldown_key(subject), 1, self.cooldown_secs)
+            .ignore()
+            .del(&key)
+            .ignore()
+            .query_async(&mut conn)
+            .await;
+        if let Err(e) = result {
+            tracing::warn!(error = %e, prefix = self.prefix, "Failed to start abuse cooldown");
+        }
+        tracing::warn!(
+            prefix = self.prefix,
+            subject,
+            cooldown_secs = self.cooldown_secs,
+            "Abuse threshold exceeded, cooldown started"
+        );
+        true
+    }
+}
+
+/// Guest accounts created from `ip` in the current window (0 if unknown).
+pub async fn guest_creations(redis: &RedisConnection, ip: &str) -> u32 {
+    let mut conn = redis.clone();
+    conn.get::<_, Option<u32>>(GUEST_ACCOUNTS.counter_key(ip)).await.ok().flatten().unwrap_or(0)
+}
+
+/// Check and count a guest account created from `ip`.
+///
+/// Requests without a known IP are not throttled here.
+pub async fn check_guest_creation(
+    redis: &RedisConnection,
+    ip: Option<&str>,
+) -> Result<(), ApiError> {
+    let Some(ip) = ip else { return Ok(()) };
+
+    if let Some(remaining) = GUEST_ACCOUNTS.cooldown_remaining(redis, ip).await {
+        return Err(cooldown_error(remaining));
+    }
+    if GUEST_ACCOUNTS.hit(redis, ip).await {
+        return Err(ApiError::new(
+            StatusCode::TOO_MANY_REQUESTS,
+            "GUEST_LIMIT",
+            format!(
+                "Too many guest accounts from this network, try again in {}",
+                format_wait(GUEST_ACCOUNTS.cooldown_secs)
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Check that a user may open another multiplayer lobby.
+///
+/// Refusals count toward a cooldown, so a client retrying in a loop is
+/// locked out for a while.
+pub async fn check_lobby_creation(state: &AppState, user_id: &str) -> Result<(), ApiError> {
+    if let Some(remaining) = LOBBY_REFUSALS.cooldown_remaining(state.redis(), user_id).await {
+        return Err(cooldown_error(remaining));
+    }
+
+    let open = dguesser_db::games::count_open_lobbies(state.db(), user_id).await?;
+    if open < MAX_OPEN_LOBBIES {
+        return Ok(());
+    }
+
+    if LOBBY_REFUSALS.hit(state.redis(), user_id).await {
+        return Err(cooldown_error(LOBBY_REFUSALS.cooldown_secs));
+    }
+    Err(ApiError::new(
+        StatusCode::TOO_MANY_REQUESTS,
+        "LOBBY_LIMIT",
+        format!("You can have at most {MAX_OPEN_LOBBIES} open lobbies at a time"),
+    ))
+}
+
+fn cooldown_error(remaining_secs: u64) -> ApiError {
+    ApiError::new(
+        StatusCode::TOO_MANY_REQUESTS,
+        "COOLDOWN",
+        format!("Temporarily blocked, try again in {}", format_wait(remaining_secs)),
+    )
+}
+
+/// Human-readable wait, rounded up to whole minutes or hours.
+fn format_wait(secs: u64) -> String {
+    let minutes = secs.div_ceil(60).max(1);
+    match minutes {
+        1 => "1 minute".to_string(),
+        m if m < 60 => format!("{m} minutes"),
+        m => match m.div_ceil(60) {
+            1 => "1 hour".to_string(),
+            h => format!("{h} hours"),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_wait() {
+        assert_eq!(format_wait(0), "1 minute");
+        assert_eq!(format_wait(61), "2 minutes");
+        assert_eq!(format_wait(15 * 60), "15 minutes");
+        assert_eq!(format_wait(60 * 60), "1 hour");
+        assert_eq!(format_wait(6 * 60 * 60 - 30), "6 hours");
+    }
+
+    #[test]
+    fn test_throttle_keys() {
+        assert_eq!(GUEST_ACCOUNTS.counter_key("192.0.2.1"), "abuse:guests:192.0.2.1");
+        assert_eq!(LOBBY_REFUSALS.cooldown_key("usr_x"), "abuse:lobbies:cooldown:usr_x");
+    }
+}
@@ -0,0 +1,125 @@
+//! CAPTCHA middleware for guest-heavy endpoints
+//!
+//! Creating a guest session and joining a game by code (which creates a
+//! guest for visitors without a session) need a solved CAPTCHA once the
+//! caller's network has created [`CaptchaClient::required_after`] guests
+//! today. The token from the widget is sent in the `X-Captcha-Token` header.
+//! Signed-in users are never asked. When the provider cannot be reached the
+//! request is let through; the guest throttle in [`super::abuse`] still
+//! applies.
+//!
+//! [`CaptchaClient::required_after`]: crate::captcha::CaptchaClient::required_after
+
+use axum::{
+    body::Body,
+    extract::{FromRequestParts, OriginalUri, State},
+    http::{Method, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use dguesser_auth::MaybeAuthUser;
+
+use crate::error::ApiError;
+use crate::middleware::abuse;
+use crate::middleware::client_ip::RequestClient;
+use crate::state::AppState;
+
+/// Header carrying the CAPTCHA token
+pub const CAPTCHA_HEADER: &str = "x-captcha-token";
+
+/// Endpoints (below `/api/v1`) that may ask for a CAPTCHA
+const CAPTCHA_PATHS: [&str; 2] = ["/auth/guest", "/games/join"];
+
+/// Longest token accepted (Turnstile allows 2048 characters)
+const MAX_TOKEN_LEN: usize = 2048;
+
+/// Require a CAPTCHA from anonymous callers of guest-creating endpoints when
+/// their network looks abusive.
+pub async fn captcha_guard(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some(captcha) = state.captcha() else {
+        return next.run(request).await;
+    };
+    if !is_captcha_path(&request) {
+        return next.run(request).await;
+    }
+
+    // Only look up the session on the guarded endpoints
+    let (mut parts, body) = request.into_parts();
+    let Ok(MaybeAuthUser(auth)) = MaybeAuthUser::from_request_parts(&mut parts, &state).await;
+    let request = Request::from_parts(parts, body);
+    if auth.is_some() {
+        return next.run(request).await;
+    }
+
+    // Guests are counted per IP, so callers without one are not counted either
+    let Some(ip) = RequestClient::from_headers(request.headers(), state.client_ip_config()).ip
+    else {
+        return next.run(request).await;
+    };
+    if !captcha.required_after(abuse::guest_creations(state.redis(), &ip).await) {
+        return next.run(request).await;
+    }
+
+    let token = request
+        .headers()
+        .get(CAPTCHA_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|t| !t.is_empty() && t.len() <= MAX_TOKEN_LEN);
+    let Some(token) = token else {
+        return ApiError::new(
+            StatusCode::FORBIDDEN,
+            "CAPTCHA_REQUIRED",
+            "Complete the CAPTCHA to continue",
+        )
+        .into_response();
+    };
+
+    match captcha.verify(token, Some(&ip)).await {
+        Ok(true) => next.run(request).await,
+        Ok(false) => ApiError::new(
+            StatusCode::FORBIDDEN,
+            "CAPTCHA_FAILED",
+            "CAPTCHA verification failed, please try again",
+        )
+        .into_response(),
+        Err(e) => {
+            tracing::warn!(error = %e, "CAPTCHA provider unavailable, skipping verification");
+            next.run(request).await
+        }
+    }
+}
+
+fn is_captcha_path(request: &Request<Body>) -> bool {
+    if request.method() != Method::POST {
+        return false;
+    }
+    let uri = request
+        .extensions()
+        .get::<OriginalUri>()
+        .map(|original| original.0.clone())
+        .unwrap_or_else(|| request.uri().clone());
+    let path = uri.path().strip_prefix("/api/v1").unwrap_or(uri.path());
+    CAPTCHA_PATHS.contains(&path.trim_end_matches('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: Method, path: &str) -> Request<Body> {
+        Request::builder().method(method).uri(path).body(Body::empty()).unwrap()
+    }
+
+    #[test]
+    fn test_is_captcha_path() {
+        assert!(is_captcha_path(&request(Method::POST, "/api/v1/auth/guest")));
+        assert!(is_captcha_path(&request(Method::POST, "/api/v1/games/join/")));
+        assert!(!is_captcha_path(&request(Method::GET, "/api/v1/auth/guest")));
+        assert!(!is_captcha_path(&request(Method::POST, "/api/v1/auth/login")));
+    }
+}
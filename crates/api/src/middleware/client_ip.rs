@@ -7,6 +7,7 @@ use std::net::{IpAddr, SocketAddr};
 
 use axum::extract::ConnectInfo;
 use axum::http::{HeaderMap, Request};
+use dguesser_auth::ClientInfo;
 
 /// Configuration for client IP extraction
 #[derive(Clone, Debug)]
@@ -149,6 +150,51 @@ pub fn extract_ip_from_headers(headers: &HeaderMap, config: &ClientIpConfig) ->
     get_real_ip(headers)
 }
 
+/// Request metadata recorded when a session is created.
+#[derive(Clone, Debug, Default)]
+pub struct RequestClient {
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub country: Option<String>,
+}
+
+impl RequestClient {
+    /// Collect IP, user agent, and country from request headers.
+    pub fn from_headers(headers: &HeaderMap, config: &ClientIpConfig) -> Self {
+        Self {
+            ip: extract_ip_from_headers(headers, config),
+            user_agent: headers.get("user-agent").and_then(|v| v.to_str().ok()).map(String::from),
+            country: extract_country_from_headers(headers, config),
+        }
+    }
+
+    /// Borrow as the auth service's client info.
+    pub fn info(&self) -> ClientInfo<'_> {
+        ClientInfo {
+            ip: self.ip.as_deref(),
+            user_agent: self.user_agent.as_deref(),
+            country: self.country.as_deref(),
+        }
+    }
+}
+
+/// Approximate client country from Cloudflare's CF-IPCountry header.
+///
+/// Only trusted when `trust_cloudflare` is enabled; returns `None` when the
+/// header is missing or holds a placeholder (unknown location or Tor).
+pub fn extract_country_from_headers(
+    headers: &HeaderMap,
+    config: &ClientIpConfig,
+) -> Option<String> {
+    if !config.trust_cloudflare {
+        return None;
+    }
+    headers
+        .get("cf-ipcountry")
+        .and_then(|v| v.to_str().ok())
+        .and_then(dguesser_auth::device::normalize_country)
+}
+
 #[cfg(test)]
 mod tests {
     use axum::http::{HeaderName, HeaderValue};
@@ -291,4 +337,18 @@ mod tests {
         let ip = extract_ip_from_headers(&headers, &config);
         assert_eq!(ip, None);
     }
+
+    #[test]
+    fn test_country_requires_cloudflare_trust() {
+        let headers = make_headers(&[("cf-ipcountry", "de")]);
+
+        let trusted = ClientIpConfig { trusted_proxy_count: 0, trust_cloudflare: true };
+        assert_eq!(extract_country_from_headers(&headers, &trusted), Some("DE".to_string()));
+
+        let untrusted = ClientIpConfig { trusted_proxy_count: 0, trust_cloudflare: false };
+        assert_eq!(extract_country_from_headers(&headers, &untrusted), None);
+
+        let unknown = make_headers(&[("cf-ipcountry", "XX")]);
+        assert_eq!(extract_country_from_headers(&unknown, &trusted), None);
+    }
 }
@@ -0,0 +1,98 @@
+//! Locale negotiation middleware
+//!
+//! Picks the response language for every request from, in order:
+//! - a `lang` query parameter (e.g. `?lang=de`)
+//! - the `Accept-Language` header
+//! - English
+//!
+//! The locale is put in the request extensions for the [`Locale`] extractor
+//! and kept for the rest of the request so [`ApiError`] responses can be
+//! translated.
+//!
+//! [`ApiError`]: crate::error::ApiError
+
+use axum::{
+    body::Body,
+    http::{HeaderValue, Request, header},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::i18n::Locale;
+
+tokio::task_local! {
+    static LOCALE: Locale;
+}
+
+/// Locale of the request currently being handled; English outside a
+/// request.
+pub fn current_locale() -> Locale {
+    LOCALE.try_with(|locale| *locale).unwrap_or_default()
+}
+
+fn negotiate(request: &Request<Body>) -> Locale {
+    let from_query = request.uri().query().and_then(|query| {
+        query.split('&').find_map(|pair| pair.strip_prefix("lang=")).and_then(Locale::parse)
+    });
+    from_query
+        .or_else(|| {
+            request
+                .headers()
+                .get(header::ACCEPT_LANGUAGE)
+                .and_then(|v| v.to_str().ok())
+                .and_then(Locale::from_accept_language)
+        })
+        .unwrap_or_default()
+}
+
+/// Locale middleware
+///
+/// Runs outside the trace ID middleware so error bodies it rewrites are
+/// translated too.
+pub async fn locale(mut request: Request<Body>, next: Next) -> Response {
+    let locale = negotiate(&request);
+    request.extensions_mut().insert(locale);
+
+    let mut response = LOCALE.scope(locale, next.run(request)).await;
+
+    let headers = response.headers_mut();
+    headers.insert(header::CONTENT_LANGUAGE, HeaderValue::from_static(locale.code()));
+    headers.append(header::VARY, HeaderValue::from_static("accept-language"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(uri: &str, accept_language: Option<&str>) -> Request<Body> {
+        let mut builder = Request::builder().uri(uri);
+        if let Some(value) = accept_language {
+            builder = builder.header(header::ACCEPT_LANGUAGE, value);
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    #[test]
+    fn test_negotiate() {
+        assert_eq!(negotiate(&request("/api/v1/meta/countries", None)), Locale::En);
+        assert_eq!(negotiate(&request("/x", Some("fr-FR,fr;q=0.9"))), Locale::Fr);
+        assert_eq!(negotiate(&request("/x?page=2&lang=de", Some("fr"))), Locale::De);
+        // Unsupported query values fall through to the header
+        assert_eq!(negotiate(&request("/x?lang=xx", Some("es"))), Locale::Es);
+    }
+
+    #[tokio::test]
+    async fn test_errors_are_translated() {
+        use axum::response::IntoResponse;
+
+        use crate::error::ApiError;
+
+        let response =
+            LOCALE.scope(Locale::De, async { ApiError::not_found("Game").into_response() }).await;
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["code"], "NOT_FOUND");
+        assert_eq!(body["message"], "Spiel nicht gefunden");
+    }
+}
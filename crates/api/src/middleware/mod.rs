@@ -1,9 +1,21 @@
 //! API middleware
 
+pub mod abuse;
+pub mod captcha;
 pub mod client_ip;
+pub mod locale;
+pub mod org;
 pub mod rate_limit;
 pub mod security_headers;
+pub mod trace_id;
 
-pub use client_ip::extract_ip_from_headers;
-pub use rate_limit::{rate_limit, rate_limit_auth, rate_limit_game};
+pub use captcha::captcha_guard;
+pub use client_ip::RequestClient;
+pub use locale::locale;
+pub use org::{CurrentOrg, resolve_org};
+pub use rate_limit::{
+    LoginThrottle, rate_limit, rate_limit_auth, rate_limit_game, rate_limit_map_tiles,
+    rate_limit_streetview, rate_limit_telemetry,
+};
 pub use security_headers::security_headers;
+pub use trace_id::trace_id;
@@ -0,0 +1,299 @@
+//! Organization resolution middleware
+//!
+//! Every API request is served either for the public instance or for one
+//! organization. The organization is picked from, in order:
+//! - an `X-Organization` header holding its slug
+//! - the `Origin` host, then the `Host`: a subdomain of `ORG_BASE_DOMAIN` is
+//!   an organization slug, any other host may be an organization's custom
+//!   domain
+//!
+//! An unknown slug in the header is rejected; hosts that match no
+//! organization are served as the public instance. The result is put in the
+//! request extensions for the [`CurrentOrg`] extractor.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use axum::{
+    body::Body,
+    extract::{FromRequestParts, State},
+    http::{Request, header, request::Parts},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use dguesser_db::organizations::{OrgRole, Organization};
+
+use crate::error::ApiError;
+use crate::state::AppState;
+
+/// Header naming the organization of a request
+pub const ORG_HEADER: &str = "x-organization";
+
+/// How long resolved organizations (and misses) are remembered
+const CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Entries kept before the cache is emptied, as hosts come from clients
+const CACHE_MAX_ENTRIES: usize = 10_000;
+
+/// Subdomains of the base domain that never name an organization
+pub const RESERVED_SLUGS: &[&str] = &["admin", "api", "app", "realtime", "static", "www", "ws"];
+
+/// The organization a request is served for; `None` for the public instance
+#[derive(Debug, Clone, Default)]
+pub struct CurrentOrg(pub Option<Arc<Organization>>);
+
+impl CurrentOrg {
+    /// ID of the organization, if any
+    pub fn id(&self) -> Option<&str> {
+        self.0.as_deref().map(|org| org.id.as_str())
+    }
+
+    /// Slug of the organization, or "-" for the public instance
+    pub fn key(&self) -> &str {
+        self.0.as_deref().map_or("-", |org| org.slug.as_str())
+    }
+
+    /// Check that a user may play in this organization. Anyone may play in
+    /// the public instance; returns the user's role otherwise.
+    pub async fn require_member(
+        &self,
+        state: &AppState,
+        user_id: &str,
+    ) -> Result<Option<OrgRole>, ApiError> {
+        let Some(org_id) = self.id() else {
+            return Ok(None);
+        };
+        match dguesser_db::organizations::get_role(state.db(), org_id, user_id).await? {
+            Some(role) => Ok(Some(role)),
+            None => Err(ApiError::new(
+                axum::http::StatusCode::FORBIDDEN,
+                "NOT_ORG_MEMBER",
+                "You are not a member of this organization",
+            )),
+        }
+    }
+
+    /// Check that a user administers this organization, and return it.
+    pub async fn require_admin(
+        &self,
+        state: &AppState,
+        user_id: &str,
+    ) -> Result<Arc<Organization>, ApiError> {
+        let Some(org) = self.0.clone() else {
+            return Err(ApiError::not_found("Organization"));
+        };
+        match self.require_member(state, user_id).await? {
+            Some(OrgRole::Admin) => Ok(org),
+            _ => Err(ApiError::forbidden("Organization admin access required")),
+        }
+    }
+}
+
+/// Organization resolved by the org middleware; the public instance when the
+/// middleware did not run.
+impl<S> FromRequestParts<S> for CurrentOrg
+where
+    S: Send + Sync,
+{
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts.extensions.get::<CurrentOrg>().cloned().unwrap_or_default())
+    }
+}
+
+/// How a request names its organization
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum OrgLookup {
+    /// Slug from the header; must exist
+    Header(String),
+    /// Subdomain of the base domain
+    Subdomain(String),
+    /// Any other host
+    Domain(String),
+}
+
+impl OrgLookup {
+    fn cache_key(&self) -> String {
+        match self {
+            Self::Header(slug) | Self::Subdomain(slug) => format!("slug:{slug}"),
+            Self::Domain(domain) => format!("domain:{domain}"),
+        }
+    }
+}
+
+/// Lowercase host of a `Host` or `Origin` header value, without scheme or
+/// port
+fn host_of(value: &str) -> Option<String> {
+    let host = value.split_once("://").map_or(value, |(_, rest)| rest);
+    let host = host.split(['/', ':']).next()?.trim().to_ascii_lowercase();
+    (!host.is_empty()).then_some(host)
+}
+
+fn lookup_for(request: &Request<Body>, base_domain: Option<&str>) -> Option<OrgLookup> {
+    let headers = request.headers();
+    if let Some(slug) = headers.get(ORG_HEADER).and_then(|v| v.to_str().ok()) {
+        let slug = slug.trim().to_ascii_lowercase();
+        if !slug.is_empty() {
+            return Some(OrgLookup::Header(slug));
+        }
+    }
+
+    let host = [header::ORIGIN, header::HOST]
+        .iter()
+        .filter_map(|name| headers.get(name).and_then(|v| v.to_str().ok()))
+        .find_map(host_of)?;
+
+    match base_domain {
+        Some(base) if host == base => None,
+        Some(base) => match host.strip_suffix(base).and_then(|sub| sub.strip_suffix('.')) {
+            Some(sub) if sub.contains('.') || RESERVED_SLUGS.contains(&sub) => None,
+            Some(sub) => Some(OrgLookup::Subdomain(sub.to_string())),
+            None => Some(OrgLookup::Domain(host)),
+        },
+        None => Some(OrgLookup::Domain(host)),
+    }
+}
+
+/// A resolved organization (or a miss) and when it was resolved
+type CacheEntry = (Option<Arc<Organization>>, Instant);
+
+/// Recently resolved organizations, shared by all requests
+#[derive(Debug)]
+pub struct OrgCache {
+    base_domain: Option<String>,
+    entries: RwLock<HashMap<String, CacheEntry>>,
+}
+
+impl OrgCache {
+    pub fn new(base_domain: Option<String>) -> Self {
+        Self { base_domain, entries: RwLock::new(HashMap::new()) }
+    }
+
+    /// Whether an origin is an organization subdomain of the base domain
+    pub fn is_org_origin(&self, origin: &str) -> bool {
+        let Some(base) = &self.base_domain else {
+            return false;
+        };
+        origin.starts_with("https://")
+            && host_of(origin).is_some_and(|host| {
+                host.strip_suffix(base.as_str())
+                    .and_then(|sub| sub.strip_suffix('.'))
+                    .is_some_and(|sub| !sub.is_empty() && !sub.contains('.'))
+            })
+    }
+
+    /// Forget every resolved organization (call after an organization
+    /// changes)
+    pub fn invalidate(&self) {
+        self.entries.write().unwrap_or_else(|poisoned| poisoned.into_inner()).clear();
+    }
+
+    fn get(&self, key: &str) -> Option<Option<Arc<Organization>>> {
+        let entries = self.entries.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+        entries.get(key).filter(|(_, at)| at.elapsed() < CACHE_TTL).map(|(org, _)| org.clone())
+    }
+
+    fn insert(&self, key: String, org: Option<Arc<Organization>>) {
+        let mut entries = self.entries.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if entries.len() >= CACHE_MAX_ENTRIES {
+            entries.clear();
+        }
+        entries.insert(key, (org, Instant::now()));
+    }
+
+    async fn resolve(
+        &self,
+        state: &AppState,
+        lookup: &OrgLookup,
+    ) -> Result<Option<Arc<Organization>>, sqlx::Error> {
+        let key = lookup.cache_key();
+        if let Some(org) = self.get(&key) {
+            return Ok(org);
+        }
+
+        let org = match lookup {
+            OrgLookup::Header(slug) | OrgLookup::Subdomain(slug) => {
+                dguesser_db::organizations::get_by_slug(state.db(), slug).await?
+            }
+            OrgLookup::Domain(domain) => {
+                dguesser_db::organizations::get_by_domain(state.db(), domain).await?
+            }
+        };
+        let org = org.map(Arc::new);
+        self.insert(key, org.clone());
+        Ok(org)
+    }
+}
+
+/// Organization middleware
+///
+/// Runs outside the session and response cache middleware, which read the
+/// organization from the request extensions.
+pub async fn resolve_org(
+    State(state): State<AppState>,
+    mut request: Request<Body>,
+    next: Next,
+) -> Response {
+    let orgs = state.orgs();
+    let org = match lookup_for(&request, orgs.base_domain.as_deref()) {
+        None => None,
+        Some(lookup) => match orgs.resolve(&state, &lookup).await {
+            Ok(None) if matches!(lookup, OrgLookup::Header(_)) => {
+                return ApiError::not_found("Organization").into_response();
+            }
+            Ok(org) => org,
+            Err(e) => return ApiError::from(e).into_response(),
+        },
+    };
+
+    request.extensions_mut().insert(CurrentOrg(org));
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(headers: &[(&str, &str)]) -> Request<Body> {
+        let mut builder = Request::builder().uri("/api/v1/maps");
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    #[test]
+    fn test_lookup() {
+        let base = Some("dguesser.lol");
+        let lookup = |headers: &[(&str, &str)]| lookup_for(&request(headers), base);
+
+        assert_eq!(lookup(&[("host", "api.dguesser.lol")]), None);
+        assert_eq!(lookup(&[("host", "dguesser.lol")]), None);
+        assert_eq!(
+            lookup(&[("origin", "https://Acme.dguesser.lol"), ("host", "api.dguesser.lol")]),
+            Some(OrgLookup::Subdomain("acme".into()))
+        );
+        assert_eq!(
+            lookup(&[("x-organization", "school"), ("origin", "https://acme.dguesser.lol")]),
+            Some(OrgLookup::Header("school".into()))
+        );
+        assert_eq!(
+            lookup(&[("host", "geo.example.edu:8443")]),
+            Some(OrgLookup::Domain("geo.example.edu".into()))
+        );
+        // Nested subdomains are not organizations
+        assert_eq!(lookup(&[("host", "a.b.dguesser.lol")]), None);
+    }
+
+    #[test]
+    fn test_org_origins() {
+        let cache = OrgCache::new(Some("dguesser.lol".into()));
+        assert!(cache.is_org_origin("https://acme.dguesser.lol"));
+        assert!(!cache.is_org_origin("http://acme.dguesser.lol"));
+        assert!(!cache.is_org_origin("https://acme.dguesser.lol.evil.com"));
+        assert!(!cache.is_org_origin("https://evildguesser.lol"));
+        assert!(!OrgCache::new(None).is_org_origin("https://acme.dguesser.lol"));
+    }
+}
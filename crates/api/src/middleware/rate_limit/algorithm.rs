@@ -0,0 +1,485 @@
+//! Rate limiting algorithms
+//!
+//! Each algorithm counts a request with a single Lua script, so reading and
+//! updating a client's state is one atomic round trip no matter how many API
+//! instances share the Redis server. Only allowed requests are counted, so a
+//! client that keeps retrying while limited is let back in once its earlier
+//! requests age out. Times are passed in by the caller in milliseconds since
+//! the epoch.
+
+use std::sync::LazyLock;
+
+use dguesser_redis::RedisConnection;
+use redis::Script;
+
+/// How requests are counted against a limit
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RateLimitAlgorithm {
+    /// One counter per fixed window. Cheapest, but a client can send twice
+    /// the limit across a window boundary.
+    FixedWindow,
+    /// Fixed window counters, with the previous window weighted by how much
+    /// of it still overlaps the sliding window.
+    #[default]
+    SlidingWindow,
+    /// Timestamp of every allowed request in the window. Exact, but stores
+    /// one entry per request.
+    SlidingLog,
+    /// Bucket of `limit` tokens refilled evenly over the window. Allows
+    /// bursts up to the limit while holding the average rate.
+    TokenBucket,
+}
+
+impl RateLimitAlgorithm {
+    /// Parse a name like `token_bucket` (case-insensitive, `-` or `_`)
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "fixed_window" => Some(Self::FixedWindow),
+            "sliding_window" => Some(Self::SlidingWindow),
+            "sliding_log" => Some(Self::SlidingLog),
+            "token_bucket" => Some(Self::TokenBucket),
+            _ => None,
+        }
+    }
+
+    /// Name used in logs and configuration
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::FixedWindow => "fixed_window",
+            Self::SlidingWindow => "sliding_window",
+            Self::SlidingLog => "sliding_log",
+            Self::TokenBucket => "token_bucket",
+        }
+    }
+
+    /// Count a request for `key` and decide whether it is allowed.
+    pub async fn check(
+        self,
+        conn: &mut RedisConnection,
+        key: &str,
+        limit: u32,
+        window_secs: u64,
+        now_ms: u64,
+    ) -> redis::RedisResult<Decision> {
+        let window_ms = window_secs.max(1) * 1000;
+        match self {
+            Self::FixedWindow => fixed_window(conn, key, limit, window_ms, now_ms).await,
+            Self::SlidingWindow => sliding_window(conn, key, limit, window_ms, now_ms).await,
+            Self::SlidingLog => sliding_log(conn, key, limit, window_ms, now_ms).await,
+            Self::TokenBucket => token_bucket(conn, key, limit, window_ms, now_ms).await,
+        }
+    }
+}
+
+/// Outcome of counting one request
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Decision {
+    pub allowed: bool,
+    /// Requests left before the limit is reached
+    pub remaining: u32,
+    /// Seconds until the full limit is available again
+    pub reset_secs: u64,
+    /// Seconds until a rejected request can be retried
+    pub retry_after_secs: u64,
+}
+
+/// Whole seconds covering `ms`, at least one
+fn ceil_secs(ms: u64) -> u64 {
+    ms.div_ceil(1000).max(1)
+}
+
+/// Milliseconds left in the fixed window containing `now_ms`
+fn window_remaining_ms(now_ms: u64, window_ms: u64) -> u64 {
+    window_ms - now_ms % window_ms
+}
+
+/// KEYS[1]: window counter. ARGV[1]: window (ms), ARGV[2]: limit.
+/// Returns `{allowed, count}`, the count including this request if allowed.
+static FIXED_WINDOW: LazyLock<Script> = LazyLock::new(|| {
+    Script::new(
+        r"
+local count = tonumber(redis.call('GET', KEYS[1]) or '0')
+if count >= tonumber(ARGV[2]) then
+  return {0, count}
+end
+count = redis.call('INCR', KEYS[1])
+if count == 1 then
+  redis.call('PEXPIRE', KEYS[1], ARGV[1])
+end
+return {1, count}
+",
+    )
+});
+
+async fn fixed_window(
+    conn: &mut RedisConnection,
+    key: &str,
+    limit: u32,
+    window_ms: u64,
+    now_ms: u64,
+) -> redis::RedisResult<Decision> {
+    let counter = format!("{}:{}", key, now_ms / window_ms);
+    let (allowed, count): (u8, u32) =
+        FIXED_WINDOW.key(counter).arg(window_ms).arg(limit).invoke_async(conn).await?;
+
+    let reset_secs = ceil_secs(window_remaining_ms(now_ms, window_ms));
+    Ok(Decision {
+        allowed: allowed == 1,
+        remaining: limit.saturating_sub(count),
+        reset_secs,
+        retry_after_secs: reset_secs,
+    })
+}
+
+/// KEYS[1]: current window counter, KEYS[2]: previous window counter, both
+/// hash-tagged with the client key so they share a Redis Cluster slot.
+/// ARGV[1]: counter lifetime (ms), two windows so the previous count is
+/// still there when weighted. ARGV[2]: limit, ARGV[3]: weight of the previous
+/// window. Returns `{allowed, current, previous}`, the current count
+/// including this request if allowed.
+static SLIDING_WINDOW: LazyLock<Script> = LazyLock::new(|| {
+    Script::new(
+        r"
+local current = tonumber(redis.call('GET', KEYS[1]) or '0')
+local previous = tonumber(redis.call('GET', KEYS[2]) or '0')
+if previous * tonumber(ARGV[3]) + current + 1 > tonumber(ARGV[2]) then
+  return {0, current, previous}
+end
+current = redis.call('INCR', KEYS[1])
+redis.call('PEXPIRE', KEYS[1], ARGV[1])
+return {1, current, previous}
+",
+    )
+});
+
+/// Share of the previous fixed window that still overlaps the sliding window
+/// ending `elapsed_ms` into the current one.
+fn previous_weight(elapsed_ms: u64, window_ms: u64) -> f64 {
+    window_ms.saturating_sub(elapsed_ms) as f64 / window_ms as f64
+}
+
+/// Estimate the request count in the sliding window ending now.
+///
+/// Counts are kept in fixed windows; the previous window's count is weighted
+/// by how much of it still overlaps the sliding window. This smooths bursts at
+/// window boundaries without storing every request timestamp.
+fn sliding_window_count(previous: u32, current: u32, elapsed_ms: u64, window_ms: u64) -> f64 {
+    previous as f64 * previous_weight(elapsed_ms, window_ms) + current as f64
+}
+
+async fn sliding_window(
+    conn: &mut RedisConnection,
+    key: &str,
+    limit: u32,
+    window_ms: u64,
+    now_ms: u64,
+) -> redis::RedisResult<Decision> {
+    let index = now_ms / window_ms;
+    let elapsed_ms = now_ms % window_ms;
+    let (allowed, current, previous): (u8, u32, u32) = SLIDING_WINDOW
+        .key(format!("{{{}}}:{}", key, index))
+        .key(format!("{{{}}}:{}", key, index.saturating_sub(1)))
+        .arg(window_ms * 2)
+        .arg(limit)
+        .arg(previous_weight(elapsed_ms, window_ms))
+        .invoke_async(conn)
+        .await?;
+
+    let count = sliding_window_count(previous, current, elapsed_ms, window_ms);
+    let reset_secs = ceil_secs(window_remaining_ms(now_ms, window_ms));
+    Ok(Decision {
+        allowed: allowed == 1,
+        remaining: limit.saturating_sub(count.ceil() as u32),
+        reset_secs,
+        retry_after_secs: reset_secs,
+    })
+}
+
+/// KEYS[1]: sorted set of request times. ARGV[1]: now (ms), ARGV[2]: window
+/// (ms), ARGV[3]: limit, ARGV[4]: unique member for this request.
+/// Rejected requests are not logged. Returns `{allowed, count, ms until the
+/// oldest entry expires, ms until the newest expires}`.
+static SLIDING_LOG: LazyLock<Script> = LazyLock::new(|| {
+    Script::new(
+        r"
+local now = tonumber(ARGV[1])
+local window = tonumber(ARGV[2])
+redis.call('ZREMRANGEBYSCORE', KEYS[1], '-inf', now - window)
+local count = redis.call('ZCARD', KEYS[1])
+local allowed = 0
+if count < tonumber(ARGV[3]) then
+  redis.call('ZADD', KEYS[1], now, ARGV[4])
+  count = count + 1
+  allowed = 1
+end
+redis.call('PEXPIRE', KEYS[1], window)
+local oldest = redis.call('ZRANGE', KEYS[1], 0, 0, 'WITHSCORES')
+local newest = redis.call('ZRANGE', KEYS[1], -1, -1, 'WITHSCORES')
+local retry = 0
+local reset = 0
+if oldest[2] then
+  retry = tonumber(oldest[2]) + window - now
+  reset = tonumber(newest[2]) + window - now
+end
+return {allowed, count, retry, reset}
+",
+    )
+});
+
+async fn sliding_log(
+    conn: &mut RedisConnection,
+    key: &str,
+    limit: u32,
+    window_ms: u64,
+    now_ms: u64,
+) -> redis::RedisResult<Decision> {
+    let member = format!("{}-{}", now_ms, uuid::Uuid::new_v4().simple());
+    let (allowed, count, retry_ms, reset_ms): (u8, u32, u64, u64) = SLIDING_LOG
+        .key(format!("{}:log", key))
+        .arg(now_ms)
+        .arg(window_ms)
+        .arg(limit)
+        .arg(member)
+        .invoke_async(conn)
+        .await?;
+
+    Ok(Decision {
+        allowed: allowed == 1,
+        remaining: limit.saturating_sub(count),
+        reset_secs: ceil_secs(reset_ms),
+        retry_after_secs: ceil_secs(retry_ms),
+    })
+}
+
+/// KEYS[1]: bucket hash (`tokens`, `at`). ARGV[1]: capacity, ARGV[2]: time
+/// to refill an empty bucket (ms), ARGV[3]: now (ms). Returns `{allowed,
+/// whole tokens left, ms until full, ms until the next token}`.
+static TOKEN_BUCKET: LazyLock<Script> = LazyLock::new(|| {
+    Script::new(
+        r"
+local capacity = tonumber(ARGV[1])
+local window = tonumber(ARGV[2])
+local now = tonumber(ARGV[3])
+local rate = capacity / window
+local bucket = redis.call('HMGET', KEYS[1], 'tokens', 'at')
+local tokens = tonumber(bucket[1]) or capacity
+local at = tonumber(bucket[2]) or now
+tokens = math.min(capacity, tokens + math.max(0, now - at) * rate)
+local allowed = 0
+local retry = 0
+if tokens >= 1 then
+  tokens = tokens - 1
+  allowed = 1
+else
+  retry = math.ceil((1 - tokens) / rate)
+end
+redis.call('HSET', KEYS[1], 'tokens', tostring(tokens), 'at', now)
+redis.call('PEXPIRE', KEYS[1], window)
+return {allowed, math.floor(tokens), math.ceil((capacity - tokens) / rate), retry}
+",
+    )
+});
+
+async fn token_bucket(
+    conn: &mut RedisConnection,
+    key: &str,
+    limit: u32,
+    window_ms: u64,
+    now_ms: u64,
+) -> redis::RedisResult<Decision> {
+    let (allowed, tokens, reset_ms, retry_ms): (u8, u32, u64, u64) = TOKEN_BUCKET
+        .key(format!("{}:bucket", key))
+        .arg(limit.max(1))
+        .arg(window_ms)
+        .arg(now_ms)
+        .invoke_async(conn)
+        .await?;
+
+    Ok(Decision {
+        allowed: allowed == 1,
+        remaining: tokens,
+        reset_secs: ceil_secs(reset_ms),
+        retry_after_secs: ceil_secs(retry_ms),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_algorithm() {
+        assert_eq!(
+            RateLimitAlgorithm::parse("token_bucket"),
+            Some(RateLimitAlgorithm::TokenBucket)
+        );
+        assert_eq!(
+            RateLimitAlgorithm::parse(" Sliding-Log "),
+            Some(RateLimitAlgorithm::SlidingLog)
+        );
+        assert_eq!(RateLimitAlgorithm::parse("leaky_bucket"), None);
+
+        for algorithm in [
+            RateLimitAlgorithm::FixedWindow,
+            RateLimitAlgorithm::SlidingWindow,
+            RateLimitAlgorithm::SlidingLog,
+            RateLimitAlgorithm::TokenBucket,
+        ] {
+            assert_eq!(RateLimitAlgorithm::parse(algorithm.as_str()), Some(algorithm));
+        }
+    }
+
+    #[test]
+    fn test_sliding_window_count() {
+        // At the start of a window the previous window counts fully
+        assert_eq!(sliding_window_count(10, 1, 0, 60_000), 11.0);
+        // Halfway through, half of it still overlaps
+        assert_eq!(sliding_window_count(10, 1, 30_000, 60_000), 6.0);
+        // No previous traffic
+        assert_eq!(sliding_window_count(0, 5, 59_000, 60_000), 5.0);
+    }
+
+    #[test]
+    fn test_window_timing() {
+        assert_eq!(window_remaining_ms(120_000, 60_000), 60_000);
+        assert_eq!(window_remaining_ms(150_500, 60_000), 29_500);
+        assert_eq!(ceil_secs(29_500), 30);
+        assert_eq!(ceil_secs(0), 1);
+    }
+
+    // The script tests need a running Redis instance. They are ignored by
+    // default and can be run with:
+    // cargo test -p dguesser-api rate_limit -- --ignored
+
+    /// Start of a fixed one-minute window, in milliseconds
+    const WINDOW_START_MS: u64 = 1_700_000_040_000;
+
+    async fn test_conn() -> RedisConnection {
+        RedisConnection::open_url("redis://127.0.0.1:6379").unwrap()
+    }
+
+    fn test_key() -> String {
+        format!("test:rate_limit:{}", uuid::Uuid::new_v4().simple())
+    }
+
+    /// Count a request against a limit of 2 per minute
+    async fn check(
+        conn: &mut RedisConnection,
+        algorithm: RateLimitAlgorithm,
+        key: &str,
+        now_ms: u64,
+    ) -> Decision {
+        algorithm.check(conn, key, 2, 60, now_ms).await.expect("Rate limit script failed")
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Redis"]
+    async fn test_fixed_window_script() {
+        let mut conn = test_conn().await;
+        let key = test_key();
+        let algorithm = RateLimitAlgorithm::FixedWindow;
+        let now = WINDOW_START_MS + 30_000;
+
+        assert_eq!(
+            check(&mut conn, algorithm, &key, now).await,
+            Decision { allowed: true, remaining: 1, reset_secs: 30, retry_after_secs: 30 }
+        );
+        assert!(check(&mut conn, algorithm, &key, now).await.allowed);
+        for _ in 0..3 {
+            assert_eq!(
+                check(&mut conn, algorithm, &key, now).await,
+                Decision { allowed: false, remaining: 0, reset_secs: 30, retry_after_secs: 30 }
+            );
+        }
+
+        // Rejected requests are not counted
+        let counter = format!("{}:{}", key, now / 60_000);
+        let count: u32 = redis::cmd("GET").arg(counter).query_async(&mut conn).await.unwrap();
+        assert_eq!(count, 2);
+
+        // The next window starts empty
+        let next = check(&mut conn, algorithm, &key, WINDOW_START_MS + 60_000).await;
+        assert_eq!((next.allowed, next.remaining, next.reset_secs), (true, 1, 60));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Redis"]
+    async fn test_sliding_window_script() {
+        let mut conn = test_conn().await;
+        let key = test_key();
+        let algorithm = RateLimitAlgorithm::SlidingWindow;
+
+        assert_eq!(
+            check(&mut conn, algorithm, &key, WINDOW_START_MS).await,
+            Decision { allowed: true, remaining: 1, reset_secs: 60, retry_after_secs: 60 }
+        );
+        assert!(check(&mut conn, algorithm, &key, WINDOW_START_MS).await.allowed);
+        for _ in 0..3 {
+            let rejected = check(&mut conn, algorithm, &key, WINDOW_START_MS).await;
+            assert_eq!((rejected.allowed, rejected.remaining), (false, 0));
+        }
+
+        // Halfway through the next window the previous two requests weigh
+        // one, leaving room for one more. Had the rejected requests been
+        // counted, the previous window would weigh 2.5 and this would fail.
+        let halfway = WINDOW_START_MS + 90_000;
+        assert_eq!(
+            check(&mut conn, algorithm, &key, halfway).await,
+            Decision { allowed: true, remaining: 0, reset_secs: 30, retry_after_secs: 30 }
+        );
+        assert_eq!(
+            check(&mut conn, algorithm, &key, halfway).await,
+            Decision { allowed: false, remaining: 0, reset_secs: 30, retry_after_secs: 30 }
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Redis"]
+    async fn test_sliding_log_script() {
+        let mut conn = test_conn().await;
+        let key = test_key();
+        let algorithm = RateLimitAlgorithm::SlidingLog;
+
+        let first = check(&mut conn, algorithm, &key, WINDOW_START_MS).await;
+        assert_eq!((first.allowed, first.remaining, first.reset_secs), (true, 1, 60));
+        let second = check(&mut conn, algorithm, &key, WINDOW_START_MS + 10_000).await;
+        assert_eq!((second.allowed, second.remaining, second.reset_secs), (true, 0, 60));
+
+        // Retry once the oldest request leaves the window; the limit is fully
+        // available once the newest one has
+        assert_eq!(
+            check(&mut conn, algorithm, &key, WINDOW_START_MS + 20_000).await,
+            Decision { allowed: false, remaining: 0, reset_secs: 50, retry_after_secs: 40 }
+        );
+
+        let later = check(&mut conn, algorithm, &key, WINDOW_START_MS + 60_001).await;
+        assert_eq!((later.allowed, later.remaining), (true, 0));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Redis"]
+    async fn test_token_bucket_script() {
+        let mut conn = test_conn().await;
+        let key = test_key();
+        let algorithm = RateLimitAlgorithm::TokenBucket;
+
+        // Two tokens, one refilled every 30 seconds
+        assert_eq!(
+            check(&mut conn, algorithm, &key, WINDOW_START_MS).await,
+            Decision { allowed: true, remaining: 1, reset_secs: 30, retry_after_secs: 1 }
+        );
+        let second = check(&mut conn, algorithm, &key, WINDOW_START_MS).await;
+        assert_eq!((second.allowed, second.remaining, second.reset_secs), (true, 0, 60));
+        assert_eq!(
+            check(&mut conn, algorithm, &key, WINDOW_START_MS).await,
+            Decision { allowed: false, remaining: 0, reset_secs: 60, retry_after_secs: 30 }
+        );
+
+        // Half a token has refilled after 15 seconds
+        assert_eq!(
+            check(&mut conn, algorithm, &key, WINDOW_START_MS + 15_000).await,
+            Decision { allowed: false, remaining: 0, reset_secs: 45, retry_after_secs: 15 }
+        );
+        assert!(check(&mut conn, algorithm, &key, WINDOW_START_MS + 30_000).await.allowed);
+    }
+}
@@ -0,0 +1,671 @@
+//! Rate limiting middleware using Redis with in-memory fallback
+//!
+//! This middleware provides rate limiting with the following security properties:
+//! - Secure client IP extraction (prevents X-Forwarded-For spoofing)
+//! - Per-user limits for signed-in requests, per-IP limits otherwise
+//! - Tiered limits (anonymous, guest, registered, admin)
+//! - Counters kept in Redis by atomic Lua scripts, shared across API instances,
+//!   with a choice of algorithm per route group (see [`algorithm`])
+//! - Limits and algorithms per route group from the environment (see
+//!   [`RateLimitRoutes::from_env`])
+//! - `RateLimit-*` response headers as in the IETF RateLimit header fields draft
+//! - In-memory fallback when Redis is unavailable (fail-closed, not fail-open)
+//!
+//! The signed-in user is read from the request extensions, where the session
+//! middleware puts it, so rate limiting adds no database lookups.
+
+pub mod algorithm;
+
+use std::env;
+use std::num::NonZeroU32;
+use std::sync::{Arc, LazyLock};
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::{HeaderValue, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use dguesser_auth::AuthUser;
+use dguesser_protocol::api::admin::RuntimeSettings;
+use dguesser_redis::RedisConnection;
+use governor::{DefaultKeyedRateLimiter, Quota, RateLimiter};
+use redis::{AsyncCommands, Script};
+
+pub use self::algorithm::{Decision, RateLimitAlgorithm};
+use crate::error::ApiError;
+use crate::middleware::client_ip::extract_client_ip;
+use crate::state::AppState;
+
+/// Rate limit configuration
+#[derive(Clone, Debug)]
+pub struct RateLimitConfig {
+    /// Max requests per window for the base (anonymous) tier
+    pub max_requests: u32,
+    /// Window duration in seconds
+    pub window_secs: u64,
+    /// Key prefix for Redis
+    pub prefix: &'static str,
+    /// How requests are counted
+    pub algorithm: RateLimitAlgorithm,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self::new(100, 60, "ratelimit:api")
+    }
+}
+
+impl RateLimitConfig {
+    fn new(max_requests: u32, window_secs: u64, prefix: &'static str) -> Self {
+        Self { max_requests, window_secs, prefix, algorithm: RateLimitAlgorithm::default() }
+    }
+
+    /// Rate limit for authentication endpoints
+    pub fn auth() -> Self {
+        Self::new(20, 60, "ratelimit:auth")
+    }
+
+    /// Rate limit for game actions
+    pub fn game() -> Self {
+        Self::new(60, 60, "ratelimit:game")
+    }
+
+    /// Rate limit for client error reports
+    pub fn telemetry() -> Self {
+        Self::new(30, 60, "ratelimit:telemetry")
+    }
+
+    /// Rate limit for Street View metadata lookups
+    pub fn streetview() -> Self {
+        Self::new(30, 60, "ratelimit:streetview")
+    }
+
+    /// Rate limit for proxied map tiles
+    pub fn map_tiles() -> Self {
+        Self::new(600, 60, "ratelimit:tiles")
+    }
+
+    /// Failed password sign-ins allowed per account
+    pub fn login() -> Self {
+        Self::new(5, 900, "ratelimit:login")
+    }
+
+    /// Apply `RATE_LIMIT_{group}` (`<requests>/<window secs>`, e.g. `20/60`)
+    /// and `RATE_LIMIT_{group}_ALGORITHM`, keeping the current values for
+    /// unset or invalid ones.
+    fn with_env(mut self, group: &str, algorithm: RateLimitAlgorithm) -> Self {
+        if let Some((max_requests, window_secs)) =
+            env::var(format!("RATE_LIMIT_{group}")).ok().as_deref().and_then(parse_limit)
+        {
+            self.max_requests = max_requests;
+            self.window_secs = window_secs;
+        }
+        self.algorithm = env::var(format!("RATE_LIMIT_{group}_ALGORITHM"))
+            .ok()
+            .as_deref()
+            .and_then(RateLimitAlgorithm::parse)
+            .unwrap_or(algorithm);
+        self
+    }
+}
+
+/// Parse `<requests>/<window secs>`, e.g. `20/60`
+fn parse_limit(value: &str) -> Option<(u32, u64)> {
+    let (max_requests, window_secs) = value.trim().split_once('/')?;
+    let max_requests = max_requests.trim().parse().ok().filter(|n| *n > 0)?;
+    let window_secs = window_secs.trim().parse().ok().filter(|n| *n > 0)?;
+    Some((max_requests, window_secs))
+}
+
+/// Limits of each rate limited route group
+#[derive(Clone, Debug)]
+pub struct RateLimitRoutes {
+    /// Everything under `/api/v1` without a stricter group
+    pub api: RateLimitConfig,
+    pub auth: RateLimitConfig,
+    pub game: RateLimitConfig,
+    pub telemetry: RateLimitConfig,
+    pub streetview: RateLimitConfig,
+    pub map_tiles: RateLimitConfig,
+}
+
+impl Default for RateLimitRoutes {
+    fn default() -> Self {
+        Self {
+            api: RateLimitConfig::default(),
+            auth: RateLimitConfig::auth(),
+            game: RateLimitConfig::game(),
+            telemetry: RateLimitConfig::telemetry(),
+            streetview: RateLimitConfig::streetview(),
+            map_tiles: RateLimitConfig::map_tiles(),
+        }
+    }
+}
+
+impl RateLimitRoutes {
+    /// Load limits from the environment. `RATE_LIMIT_ALGORITHM` sets the
+    /// algorithm of every group (default `sliding_window`); each group can
+    /// override it and its limit with `RATE_LIMIT_{API,AUTH,GAME,TELEMETRY,
+    /// STREETVIEW,MAP_TILES}[_ALGORITHM]`.
+    pub fn from_env() -> Self {
+        let algorithm = env::var("RATE_LIMIT_ALGORITHM")
+            .ok()
+            .as_deref()
+            .and_then(RateLimitAlgorithm::parse)
+            .unwrap_or_default();
+        let defaults = Self::default();
+        Self {
+            api: defaults.api.with_env("API", algorithm),
+            auth: defaults.auth.with_env("AUTH", algorithm),
+            game: defaults.game.with_env("GAME", algorithm),
+            telemetry: defaults.telemetry.with_env("TELEMETRY", algorithm),
+            streetview: defaults.streetview.with_env("STREETVIEW", algorithm),
+            map_tiles: defaults.map_tiles.with_env("MAP_TILES", algorithm),
+        }
+    }
+}
+
+/// Who a request is rate limited as
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RateLimitTier {
+    /// No session; limited per IP
+    Anonymous,
+    /// Guest session
+    Guest,
+    /// Registered (non-guest) user
+    Registered,
+    /// Admin user
+    Admin,
+}
+
+impl RateLimitTier {
+    /// Tier for the request's signed-in user, if any
+    pub fn for_user(auth: Option<&AuthUser>) -> Self {
+        match auth {
+            None => Self::Anonymous,
+            Some(auth) if auth.role.is_admin() => Self::Admin,
+            Some(auth) if auth.is_guest => Self::Guest,
+            Some(_) => Self::Registered,
+        }
+    }
+
+    /// Name used in logs
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Anonymous => "anonymous",
+            Self::Guest => "guest",
+            Self::Registered => "registered",
+            Self::Admin => "admin",
+        }
+    }
+}
+
+/// Per-tier multipliers applied to every route group's base limit
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RateLimitTiers {
+    pub anonymous: u32,
+    pub guest: u32,
+    pub registered: u32,
+    pub admin: u32,
+}
+
+impl Default for RateLimitTiers {
+    fn default() -> Self {
+        Self { anonymous: 1, guest: 1, registered: 2, admin: 5 }
+    }
+}
+
+impl RateLimitTiers {
+    /// Load multipliers from `RATE_LIMIT_{GUEST,REGISTERED,ADMIN}_MULTIPLIER`,
+    /// falling back to the defaults for unset or invalid values.
+    pub fn from_env() -> Self {
+        let multiplier = |name: &str, default: u32| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse::<u32>().ok())
+                .filter(|v| *v > 0)
+                .unwrap_or(default)
+        };
+        let defaults = Self::default();
+        Self {
+            anonymous: defaults.anonymous,
+            guest: multiplier("RATE_LIMIT_GUEST_MULTIPLIER", defaults.guest),
+            registered: multiplier("RATE_LIMIT_REGISTERED_MULTIPLIER", defaults.registered),
+            admin: multiplier("RATE_LIMIT_ADMIN_MULTIPLIER", defaults.admin),
+        }
+    }
+
+    /// Apply the multipliers set by a runtime config reload
+    pub fn with_overrides(&self, settings: &RuntimeSettings) -> Self {
+        Self {
+            anonymous: self.anonymous,
+            guest: settings.rate_limit_guest_multiplier.unwrap_or(self.guest),
+            registered: settings.rate_limit_registered_multiplier.unwrap_or(self.registered),
+            admin: settings.rate_limit_admin_multiplier.unwrap_or(self.admin),
+        }
+    }
+
+    /// Max requests per window for a tier in a route group
+    pub fn limit(&self, config: &RateLimitConfig, tier: RateLimitTier) -> u32 {
+        let multiplier = match tier {
+            RateLimitTier::Anonymous => self.anonymous,
+            RateLimitTier::Guest => self.guest,
+            RateLimitTier::Registered => self.registered,
+            RateLimitTier::Admin => self.admin,
+        };
+        config.max_requests.saturating_mul(multiplier.max(1))
+    }
+}
+
+/// In-memory fallback rate limiter for when Redis is unavailable
+///
+/// Uses a keyed rate limiter that tracks limits per user or IP address.
+/// Uses stricter limits (50% of normal) since it's per-instance rather than global.
+pub type FallbackRateLimiter = DefaultKeyedRateLimiter<String>;
+
+/// Create a new fallback rate limiter with the given requests per minute
+pub fn create_fallback_limiter(requests_per_minute: u32) -> Arc<FallbackRateLimiter> {
+    // Use 50% of normal limits for fallback since it's per-instance
+    let fallback_limit = (requests_per_minute / 2).max(1);
+    let quota = Quota::per_minute(NonZeroU32::new(fallback_limit).unwrap());
+    Arc::new(RateLimiter::keyed(quota))
+}
+
+/// Rate limiting middleware
+///
+/// This middleware checks Redis to enforce rate limits per user (or per IP
+/// address for anonymous requests). If Redis is unavailable, falls back to
+/// in-memory rate limiting with stricter limits. If the limit is exceeded,
+/// returns 429 Too Many Requests.
+pub async fn rate_limit(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let config = state.rate_limits().api.clone();
+    rate_limit_with_config(State(state), config, request, next).await
+}
+
+/// Rate limiting middleware with custom config
+pub async fn rate_limit_with_config(
+    State(state): State<AppState>,
+    config: RateLimitConfig,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    // Extract client IP using secure method
+    let ip_config = state.client_ip_config();
+    let ip = extract_client_ip(&request, ip_config);
+
+    let auth = request.extensions().get::<AuthUser>();
+    let tier = RateLimitTier::for_user(auth);
+    let limit = state.rate_limit_tiers().limit(&config, tier);
+
+    // Use route-group key (prefix:subject) instead of per-path key
+    // This prevents attackers from bypassing rate limits by hitting different paths
+    // within the same route group (e.g., /auth/google vs /auth/microsoft)
+    let key = rate_limit_key(config.prefix, auth, &ip);
+
+    // Keep path for logging only
+    let path = request.uri().path();
+
+    // Try Redis-based rate limiting first
+    let mut conn = state.redis().clone();
+    let now_ms = chrono::Utc::now().timestamp_millis().max(0) as u64;
+    match config.algorithm.check(&mut conn, &key, limit, config.window_secs, now_ms).await {
+        Ok(decision) if decision.allowed => {
+            // Add rate limit headers to response
+            let mut response = next.run(request).await;
+            add_rate_limit_headers(&mut response, limit, config.window_secs, &decision);
+            response
+        }
+        Ok(decision) => {
+            tracing::warn!(
+                ip = %ip,
+                key = %key,
+                tier = tier.as_str(),
+                path = %path,
+                route_group = %config.prefix,
+                algorithm = config.algorithm.as_str(),
+                limit,
+                "Rate limit exceeded"
+            );
+            let mut response = rate_limit_response(decision.retry_after_secs);
+            add_rate_limit_headers(&mut response, limit, config.window_secs, &decision);
+            response
+        }
+        Err(e) => {
+            // Fall back to in-memory rate limiting
+            tracing::warn!(
+                error = %e,
+                ip = %ip,
+                key = %key,
+                path = %path,
+                route_group = %config.prefix,
+                "Redis unavailable, using fallback rate limiter"
+            );
+
+            match check_fallback_rate_limit(&state, &key) {
+                FallbackResult::Allowed => {
+                    let mut response = next.run(request).await;
+                    // Add headers indicating fallback mode
+                    response.headers_mut().insert("x-ratelimit-fallback", "true".parse().unwrap());
+                    response
+                }
+                FallbackResult::Exceeded => {
+                    tracing::warn!(
+                        ip = %ip,
+                        key = %key,
+                        path = %path,
+                        route_group = %config.prefix,
+                        "Fallback rate limit exceeded"
+                    );
+                    rate_limit_response(config.window_secs)
+                }
+            }
+        }
+    }
+}
+
+/// Counter key for a request: per user when signed in, per IP otherwise
+fn rate_limit_key(prefix: &str, auth: Option<&AuthUser>, ip: &str) -> String {
+    match auth {
+        Some(auth) => format!("{}:user:{}", prefix, auth.user_id),
+        None => format!("{}:ip:{}", prefix, ip),
+    }
+}
+
+/// Result of fallback rate limit check
+enum FallbackResult {
+    Allowed,
+    Exceeded,
+}
+
+/// Check rate limit using in-memory fallback
+fn check_fallback_rate_limit(state: &AppState, key: &str) -> FallbackResult {
+    let limiter = state.fallback_rate_limiter();
+
+    match limiter.check_key(&key.to_string()) {
+        Ok(_) => FallbackResult::Allowed,
+        Err(_) => FallbackResult::Exceeded,
+    }
+}
+
+/// `RateLimit-Policy` value, e.g. `100;w=60`
+fn policy_header(limit: u32, window_secs: u64) -> String {
+    format!("{};w={}", limit, window_secs)
+}
+
+/// Add the IETF draft rate limit headers to a response
+fn add_rate_limit_headers(
+    response: &mut Response,
+    limit: u32,
+    window_secs: u64,
+    decision: &Decision,
+) {
+    let headers = response.headers_mut();
+    headers.insert("ratelimit-limit", HeaderValue::from(limit));
+    headers.insert("ratelimit-remaining", HeaderValue::from(decision.remaining));
+    headers.insert("ratelimit-reset", HeaderValue::from(decision.reset_secs));
+    if let Ok(policy) = HeaderValue::from_str(&policy_header(limit, window_secs)) {
+        headers.insert("ratelimit-policy", policy);
+    }
+}
+
+/// Create rate limit exceeded response
+fn rate_limit_response(retry_after: u64) -> Response {
+    let mut response = ApiError::rate_limited().into_response();
+    response.headers_mut().insert("retry-after", HeaderValue::from(retry_after));
+    response
+}
+
+/// Rate limiting middleware for authentication endpoints
+pub async fn rate_limit_auth(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let config = state.rate_limits().auth.clone();
+    rate_limit_with_config(State(state), config, request, next).await
+}
+
+/// Rate limiting middleware for game endpoints
+pub async fn rate_limit_game(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let config = state.rate_limits().game.clone();
+    rate_limit_with_config(State(state), config, request, next).await
+}
+
+/// Rate limiting middleware for client error reports
+pub async fn rate_limit_telemetry(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let config = state.rate_limits().telemetry.clone();
+    rate_limit_with_config(State(state), config, request, next).await
+}
+
+/// Rate limiting middleware for Street View metadata lookups
+pub async fn rate_limit_streetview(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let config = state.rate_limits().streetview.clone();
+    rate_limit_with_config(State(state), config, request, next).await
+}
+
+/// Rate limiting middleware for proxied map tiles
+pub async fn rate_limit_map_tiles(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let config = state.rate_limits().map_tiles.clone();
+    rate_limit_with_config(State(state), config, request, next).await
+}
+
+/// KEYS[1]: failure counter. ARGV[1]: window (s). Returns the failure count.
+static LOGIN_FAILURE: LazyLock<Script> = LazyLock::new(|| {
+    Script::new(
+        r"
+local count = redis.call('INCR', KEYS[1])
+if count == 1 then
+  redis.call('EXPIRE', KEYS[1], ARGV[1])
+end
+return count
+",
+    )
+});
+
+/// Per-account throttle for failed password sign-ins.
+///
+/// Failures are counted per email rather than per IP, so guessing one
+/// account's password from many addresses is still slowed down. Fails open
+/// when Redis is unavailable; the per-IP auth limiter still applies.
+pub struct LoginThrottle;
+
+impl LoginThrottle {
+    fn key(email: &str) -> String {
+        format!("{}:{}", RateLimitConfig::login().prefix, email.trim().to_lowercase())
+    }
+
+    /// Whether sign-in for this email is temporarily locked.
+    pub async fn is_locked(redis: &RedisConnection, email: &str) -> bool {
+        let mut conn = redis.clone();
+        let count: Option<u32> = conn.get(Self::key(email)).await.unwrap_or(None);
+        count.is_some_and(|count| count >= RateLimitConfig::login().max_requests)
+    }
+
+    /// Record a failed sign-in attempt.
+    pub async fn record_failure(redis: &RedisConnection, email: &str) {
+        let config = RateLimitConfig::login();
+        let key = Self::key(email);
+        let mut conn = redis.clone();
+        let recorded: redis::RedisResult<u32> =
+            LOGIN_FAILURE.key(&key).arg(config.window_secs).invoke_async(&mut conn).await;
+        if let Err(e) = recorded {
+            tracing::warn!(error = %e, "Failed to record failed login");
+        }
+    }
+
+    /// Clear failures after a successful sign-in or password reset.
+    pub async fn reset(redis: &RedisConnection, email: &str) {
+        let _ = redis.clone().del::<_, ()>(Self::key(email)).await;
+    }
+}
+
+/// Create a rate limiting layer for specific routes
+#[allow(dead_code)]
+pub fn rate_limit_layer(
+    config: RateLimitConfig,
+) -> impl Fn(
+    State<AppState>,
+    Request<Body>,
+    Next,
+) -> std::pin::Pin<Box<dyn std::future::Future<Output = Response> + Send>>
++ Clone {
+    move |state: State<AppState>, request: Request<Body>, next: Next| {
+        let config = config.clone();
+        Box::pin(async move { rate_limit_with_config(state, config, request, next).await })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config() {
+        let config = RateLimitConfig::default();
+        assert_eq!(config.max_requests, 100);
+        assert_eq!(config.window_secs, 60);
+    }
+
+    #[test]
+    fn test_auth_config() {
+        let config = RateLimitConfig::auth();
+        assert_eq!(config.max_requests, 20);
+        assert_eq!(config.window_secs, 60);
+    }
+
+    #[test]
+    fn test_game_config() {
+        let config = RateLimitConfig::game();
+        assert_eq!(config.max_requests, 60);
+        assert_eq!(config.window_secs, 60);
+    }
+
+    #[test]
+    fn test_telemetry_config() {
+        let config = RateLimitConfig::telemetry();
+        assert_eq!(config.max_requests, 30);
+        assert_eq!(config.window_secs, 60);
+    }
+
+    #[test]
+    fn test_streetview_config() {
+        let config = RateLimitConfig::streetview();
+        assert_eq!(config.max_requests, 30);
+        assert_eq!(config.window_secs, 60);
+    }
+
+    #[test]
+    fn test_tier_limits() {
+        let tiers = RateLimitTiers::default();
+        let config = RateLimitConfig::default();
+        assert_eq!(tiers.limit(&config, RateLimitTier::Anonymous), 100);
+        assert_eq!(tiers.limit(&config, RateLimitTier::Guest), 100);
+        assert_eq!(tiers.limit(&config, RateLimitTier::Registered), 200);
+        assert_eq!(tiers.limit(&config, RateLimitTier::Admin), 500);
+
+        let zero = RateLimitTiers { registered: 0, ..Default::default() };
+        assert_eq!(zero.limit(&config, RateLimitTier::Registered), 100);
+
+        let settings =
+            RuntimeSettings { rate_limit_registered_multiplier: Some(4), ..Default::default() };
+        let reloaded = tiers.with_overrides(&settings);
+        assert_eq!(reloaded.limit(&config, RateLimitTier::Registered), 400);
+        assert_eq!(reloaded.limit(&config, RateLimitTier::Admin), 500);
+    }
+
+    #[test]
+    fn test_tier_for_user() {
+        let user = AuthUser {
+            user_id: "usr_V1StGXR8_Z5j".to_string(),
+            session_id: "ses_x".to_string(),
+            is_guest: true,
+            role: dguesser_db::UserRole::User,
+            impersonation: None,
+        };
+        assert_eq!(RateLimitTier::for_user(None), RateLimitTier::Anonymous);
+        assert_eq!(RateLimitTier::for_user(Some(&user)), RateLimitTier::Guest);
+
+        let registered = AuthUser { is_guest: false, ..user.clone() };
+        assert_eq!(RateLimitTier::for_user(Some(&registered)), RateLimitTier::Registered);
+
+        let admin = AuthUser { role: dguesser_db::UserRole::Admin, ..registered };
+        assert_eq!(RateLimitTier::for_user(Some(&admin)), RateLimitTier::Admin);
+    }
+
+    #[test]
+    fn test_rate_limit_key_prefers_user() {
+        let user = AuthUser {
+            user_id: "usr_V1StGXR8_Z5j".to_string(),
+            session_id: "ses_x".to_string(),
+            is_guest: false,
+            role: dguesser_db::UserRole::User,
+            impersonation: None,
+        };
+        assert_eq!(
+            rate_limit_key("ratelimit:api", Some(&user), "192.0.2.1"),
+            "ratelimit:api:user:usr_V1StGXR8_Z5j"
+        );
+        assert_eq!(
+            rate_limit_key("ratelimit:api", None, "192.0.2.1"),
+            "ratelimit:api:ip:192.0.2.1"
+        );
+    }
+
+    #[test]
+    fn test_parse_limit() {
+        assert_eq!(parse_limit("20/60"), Some((20, 60)));
+        assert_eq!(parse_limit(" 600 / 60 "), Some((600, 60)));
+        assert_eq!(parse_limit("0/60"), None);
+        assert_eq!(parse_limit("20/0"), None);
+        assert_eq!(parse_limit("20"), None);
+        assert_eq!(parse_limit("twenty/60"), None);
+    }
+
+    #[test]
+    fn test_routes_default_to_group_configs() {
+        let routes = RateLimitRoutes::default();
+        assert_eq!(routes.auth.max_requests, 20);
+        assert_eq!(routes.map_tiles.max_requests, 600);
+        assert_eq!(routes.api.prefix, "ratelimit:api");
+        assert_eq!(routes.game.algorithm, RateLimitAlgorithm::SlidingWindow);
+    }
+
+    #[test]
+    fn test_policy_header() {
+        assert_eq!(policy_header(100, 60), "100;w=60");
+    }
+
+    #[test]
+    fn test_login_throttle_key_is_case_insensitive() {
+        assert_eq!(LoginThrottle::key(" Player@Example.com"), "ratelimit:login:player@example.com");
+    }
+
+    #[test]
+    fn test_fallback_limiter_creation() {
+        let limiter = create_fallback_limiter(100);
+        // Should allow first request
+        assert!(limiter.check_key(&"192.0.2.1".to_string()).is_ok());
+    }
+}
@@ -0,0 +1,173 @@
+//! Trace ID middleware
+//!
+//! Every request gets a trace ID that is:
+//! - taken from an incoming `X-Trace-Id` header when it is well formed (so a
+//!   trace can span the frontend, proxies and the API), or generated otherwise
+//! - recorded on the request's tracing span, so every log line carries it
+//! - returned in the `X-Trace-Id` response header and in error bodies
+//!
+//! The middleware also makes sure every error response uses the JSON error
+//! envelope, including rejections produced by axum extractors and middleware
+//! that respond with plain text.
+
+use axum::{
+    body::Body,
+    http::{HeaderValue, Request, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::error::ApiError;
+
+/// Header carrying the trace ID on requests and responses
+pub const TRACE_ID_HEADER: &str = "x-trace-id";
+
+/// Longest incoming trace ID that is accepted
+const MAX_TRACE_ID_LEN: usize = 64;
+
+/// Largest plain-text error body that is copied into the envelope
+const MAX_ERROR_BODY_BYTES: usize = 1024;
+
+tokio::task_local! {
+    static TRACE_ID: TraceId;
+}
+
+/// Trace ID of the current request, stored in the request extensions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceId(pub String);
+
+impl TraceId {
+    /// Use a well-formed incoming trace ID, or generate a new one
+    fn from_request(request: &Request<Body>) -> Self {
+        request
+            .headers()
+            .get(TRACE_ID_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .filter(|v| is_valid_trace_id(v))
+            .map(|v| Self(v.to_string()))
+            .unwrap_or_else(|| Self(uuid::Uuid::new_v4().simple().to_string()))
+    }
+}
+
+/// Trace ID of the request currently being handled, if any.
+///
+/// Available anywhere inside the handler future, including
+/// [`ApiError::into_response`].
+pub fn current_trace_id() -> Option<String> {
+    TRACE_ID.try_with(|id| id.0.clone()).ok()
+}
+
+/// Only accept short IDs made of URL-safe characters, so client-supplied
+/// values cannot inject anything into logs or headers.
+fn is_valid_trace_id(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= MAX_TRACE_ID_LEN
+        && value.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+}
+
+/// Trace ID middleware
+///
+/// Must be the outermost layer that sees every request (inside CORS and
+/// locale negotiation) so the tracing span and all other middleware run with
+/// the trace ID in place.
+pub async fn trace_id(mut request: Request<Body>, next: Next) -> Response {
+    let trace_id = TraceId::from_request(&request);
+    request.extensions_mut().insert(trace_id.clone());
+
+    let response = TRACE_ID.scope(trace_id.clone(), next.run(request)).await;
+    let mut response = TRACE_ID.scope(trace_id.clone(), into_envelope(response)).await;
+
+    if let Ok(value) = HeaderValue::from_str(&trace_id.0) {
+        response.headers_mut().insert(TRACE_ID_HEADER, value);
+    }
+    response
+}
+
+/// Rewrite non-JSON error responses into the JSON error envelope.
+async fn into_envelope(response: Response) -> Response {
+    let status = response.status();
+    if !(status.is_client_error() || status.is_server_error()) || is_json(&response) {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    // Server error bodies are never echoed back; they may contain internals
+    let message = if status.is_server_error() {
+        None
+    } else {
+        axum::body::to_bytes(body, MAX_ERROR_BODY_BYTES)
+            .await
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes.to_vec()).ok())
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty())
+    };
+
+    let mut envelope = ApiError::from_status(status, message).into_response();
+    for (name, value) in parts.headers.iter() {
+        if name != header::CONTENT_TYPE && name != header::CONTENT_LENGTH {
+            envelope.headers_mut().append(name, value.clone());
+        }
+    }
+    envelope
+}
+
+fn is_json(response: &Response) -> bool {
+    response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+
+    use super::*;
+
+    #[test]
+    fn test_trace_id_validation() {
+        assert!(is_valid_trace_id("0f8fad5b-d9cb-469f-a165-70867728950e"));
+        assert!(is_valid_trace_id("abc_123"));
+        assert!(!is_valid_trace_id(""));
+        assert!(!is_valid_trace_id("has space"));
+        assert!(!is_valid_trace_id("line\nbreak"));
+        assert!(!is_valid_trace_id(&"a".repeat(65)));
+    }
+
+    #[test]
+    fn test_incoming_trace_id_is_reused() {
+        let request = Request::builder()
+            .header(TRACE_ID_HEADER, "client-trace-1")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(TraceId::from_request(&request).0, "client-trace-1");
+
+        let request =
+            Request::builder().header(TRACE_ID_HEADER, "bad value!").body(Body::empty()).unwrap();
+        let generated = TraceId::from_request(&request).0;
+        assert_eq!(generated.len(), 32);
+    }
+
+    #[tokio::test]
+    async fn test_plain_text_error_is_wrapped() {
+        let response = (StatusCode::UNAUTHORIZED, "No session cookie").into_response();
+        let response = TRACE_ID.scope(TraceId("t1".to_string()), into_envelope(response)).await;
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert!(is_json(&response));
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["code"], "UNAUTHORIZED");
+        assert_eq!(body["message"], "No session cookie");
+        assert_eq!(body["trace_id"], "t1");
+    }
+
+    #[tokio::test]
+    async fn test_success_is_untouched() {
+        let response = (StatusCode::OK, "hello").into_response();
+        let response = into_envelope(response).await;
+        assert!(!is_json(&response));
+    }
+}
@@ -0,0 +1,164 @@
+//! Socket.IO event outbox
+//!
+//! Handlers queue events with [`enqueue`] inside the transaction that changes
+//! the game, then wake the relay. The relay publishes queued events to Redis
+//! in order and marks them delivered, so an event survives a crash between
+//! the commit and the publish. Delivery is at least once.
+
+use std::time::Duration;
+
+use chrono::Utc;
+use dguesser_db::socket_outbox::{self, QueuedSocketEvent};
+use serde::Serialize;
+use sqlx::PgConnection;
+
+use crate::socket;
+use crate::state::AppState;
+
+/// How often the relay polls when it isn't woken up, which picks up events
+/// queued by instances that died before publishing them.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Maximum events claimed per poll.
+const BATCH_SIZE: i64 = 100;
+
+/// Attempts before an event is given up on; live events are stale by then.
+const MAX_ATTEMPTS: i32 = 10;
+
+/// First retry delay; doubles on every further attempt.
+const BASE_RETRY_MS: i64 = 500;
+
+/// Upper bound for the retry delay.
+const MAX_RETRY_MS: i64 = 60_000;
+
+/// Queue an event for `room` as part of the caller's transaction.
+///
+/// Call [`AppState::wake_outbox_relay`] after committing.
+pub async fn enqueue<T: Serialize>(
+    conn: &mut PgConnection,
+    room: &str,
+    event: &str,
+    payload: &T,
+) -> Result<(), sqlx::Error> {
+    let payload = serde_json::to_value(payload).unwrap_or_default();
+    socket_outbox::enqueue(conn, room, event, payload).await?;
+    Ok(())
+}
+
+/// Delay before retrying after `attempts` failed attempts.
+fn retry_delay(attempts: i32) -> chrono::Duration {
+    let exponent = attempts.saturating_sub(1).clamp(0, 20) as u32;
+    chrono::Duration::milliseconds(BASE_RETRY_MS.saturating_mul(1 << exponent).min(MAX_RETRY_MS))
+}
+
+/// Claim and publish one batch of due events. Returns how many were claimed.
+async fn relay_due(state: &AppState) -> Result<usize, sqlx::Error> {
+    let batch = socket_outbox::claim_due(state.db(), BATCH_SIZE).await?;
+    let claimed = batch.len();
+
+    let mut delivered = Vec::with_capacity(claimed);
+    let mut pending = batch.into_iter();
+    while let Some(event) = pending.next() {
+        match socket::emit_to_room(state.redis(), &event.room, &event.event, &event.payload).await {
+            Ok(()) => delivered.push(event.id),
+            Err(e) => {
+                // Later events wait for this one so a room sees them in order
+                socket_outbox::mark_delivered(state.db(), &delivered).await?;
+                reschedule(state, event, pending.map(|event| event.id), &e.to_string()).await?;
+                return Ok(claimed);
+            }
+        }
+    }
+
+    socket_outbox::mark_delivered(state.db(), &delivered).await?;
+    Ok(claimed)
+}
+
+/// Put a failed event and the rest of its batch back in the queue.
+async fn reschedule(
+    state: &AppState,
+    failed: QueuedSocketEvent,
+    rest: impl Iterator<Item = i64>,
+    error: &str,
+) -> Result<(), sqlx::Error> {
+    let retry_at = Utc::now() + retry_delay(failed.attempts);
+    let rest: Vec<i64> = rest.collect();
+
+    if failed.attempts >= MAX_ATTEMPTS {
+        socket_outbox::mark_failed(state.db(), &[failed.id], error, None).await?;
+        tracing::error!(
+            event_id = failed.id,
+            room = %failed.room,
+            event = %failed.event,
+            attempts = failed.attempts,
+            error,
+            "Socket event delivery failed permanently"
+        );
+    } else {
+        socket_outbox::mark_failed(state.db(), &[failed.id], error, Some(retry_at)).await?;
+        tracing::warn!(
+            event_id = failed.id,
+            room = %failed.room,
+            event = %failed.event,
+            attempts = failed.attempts,
+            error,
+            "Socket event delivery failed, will retry"
+        );
+    }
+    if !rest.is_empty() {
+        socket_outbox::mark_failed(
+            state.db(),
+            &rest,
+            "Waiting for an earlier event",
+            Some(retry_at),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Spawn the background task that publishes queued events.
+///
+/// Safe to run on several instances at once: rows are claimed with
+/// `FOR UPDATE SKIP LOCKED`.
+pub fn spawn_relay_task(state: AppState) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let wakeup = state.outbox_wakeup();
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = wakeup.notified() => {}
+            }
+
+            // Drain the backlog before sleeping again
+            loop {
+                match relay_due(&state).await {
+                    Ok(claimed) if claimed as i64 == BATCH_SIZE => continue,
+                    Ok(_) => break,
+                    Err(e) => {
+                        tracing::error!(error = %e, "Socket event relay error");
+                        break;
+                    }
+                }
+            }
+        }
+    });
+
+    tracing::info!("Socket event relay started");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_backs_off_to_a_cap() {
+        assert_eq!(retry_delay(1), chrono::Duration::milliseconds(500));
+        assert_eq!(retry_delay(2), chrono::Duration::seconds(1));
+        assert_eq!(retry_delay(4), chrono::Duration::seconds(4));
+        assert_eq!(retry_delay(MAX_ATTEMPTS), chrono::Duration::minutes(1));
+    }
+}
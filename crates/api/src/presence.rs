@@ -0,0 +1,79 @@
+//! Reading user presence recorded by the realtime server
+
+use chrono::Utc;
+use dguesser_protocol::socket::presence::{
+    PRESENCE_TTL_SECS, PresenceActivity, PresenceInfo, PresenceVisibility, activity_key,
+    presence_key,
+};
+use dguesser_redis::RedisConnection;
+
+use crate::error::ApiError;
+use crate::state::AppState;
+
+/// Read the presence of several users, in the order given.
+pub async fn read_presence(
+    redis: &RedisConnection,
+    user_ids: &[&str],
+) -> Result<Vec<PresenceInfo>, redis::RedisError> {
+    if user_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let cutoff = Utc::now().timestamp() - PRESENCE_TTL_SECS;
+    let mut conn = redis.clone();
+    let mut pipe = redis::pipe();
+    for user_id in user_ids {
+        pipe.cmd("ZCOUNT").arg(presence_key(user_id)).arg(cutoff).arg("+inf");
+    }
+    let counts: Vec<i64> = pipe.query_async(&mut conn).await?;
+
+    let keys: Vec<String> = user_ids.iter().map(|id| activity_key(id)).collect();
+    let activities: Vec<Option<String>> =
+        redis::cmd("MGET").arg(&keys).query_async(&mut conn).await?;
+
+    Ok(counts
+        .into_iter()
+        .zip(activities)
+        .map(|(count, activity)| {
+            if count == 0 {
+                return PresenceInfo::default();
+            }
+            let activity = activity.and_then(|json| {
+                serde_json::from_str::<PresenceActivity>(&json)
+                    .inspect_err(|e| tracing::warn!(error = %e, "Invalid presence activity"))
+                    .ok()
+            });
+            PresenceInfo::online(activity)
+        })
+        .collect())
+}
+
+/// Presence of a user as seen by `viewer_id`: offline when hidden by the
+/// user's visibility setting or when Redis is unavailable.
+pub async fn visible_presence(
+    state: &AppState,
+    viewer_id: Option<&str>,
+    user_id: &str,
+) -> Result<PresenceInfo, ApiError> {
+    let is_self = viewer_id == Some(user_id);
+    let profile = dguesser_db::profiles::get(state.db(), user_id).await?;
+    let visibility = PresenceVisibility::parse(&profile.presence_visibility);
+
+    let is_friend = match viewer_id {
+        Some(viewer_id) if !is_self && visibility == PresenceVisibility::Friends => {
+            dguesser_db::friends::are_friends(state.db(), viewer_id, user_id).await?
+        }
+        _ => false,
+    };
+    if !visibility.allows(is_self, is_friend) {
+        return Ok(PresenceInfo::default());
+    }
+
+    match read_presence(state.redis(), &[user_id]).await {
+        Ok(mut presence) => Ok(presence.pop().unwrap_or_default()),
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to read presence");
+            Ok(PresenceInfo::default())
+        }
+    }
+}
@@ -0,0 +1,316 @@
+//! Card layouts for profiles and game results
+//!
+//! Cards use the 1200×630 size social networks expect for link previews.
+
+use super::{Anchor, Color, Scene, format_distance, format_number, truncate};
+
+const WIDTH: u32 = 1200;
+const HEIGHT: u32 = 630;
+
+const BACKGROUND: Color = Color::rgb(0x0f, 0x17, 0x2a);
+const PANEL: Color = Color::rgb(0x1e, 0x29, 0x3b);
+const TEXT: Color = Color::rgb(0xf8, 0xfa, 0xfc);
+const MUTED: Color = Color::rgb(0x94, 0xa3, 0xb8);
+const DEFAULT_ACCENT: Color = Color::rgb(0x3b, 0x82, 0xf6);
+
+/// Left edge of the content area
+const LEFT: i32 = 80;
+
+/// Stats card for a public profile
+#[derive(Debug, Clone)]
+pub struct ProfileCard<'a> {
+    pub display_name: &'a str,
+    pub username: Option<&'a str>,
+    pub country_code: Option<&'a str>,
+    /// Profile color as `#rrggbb`
+    pub profile_color: Option<&'a str>,
+    pub games_played: i64,
+    pub total_score: i64,
+    pub best_score: i64,
+    pub best_streak: Option<i64>,
+    /// All-time rank by total score
+    pub rank: Option<i64>,
+}
+
+impl ProfileCard<'_> {
+    pub fn scene(&self) -> Scene {
+        let accent = self.profile_color.and_then(Color::from_hex).unwrap_or(DEFAULT_ACCENT);
+        let mut scene = frame(accent);
+
+        scene.text(LEFT, 230, 72, true, Anchor::Start, TEXT, truncate(self.display_name, 26));
+
+        let subtitle: Vec<String> = [
+            self.username.map(|u| format!("@{u}")),
+            self.country_code.map(str::to_string),
+            self.rank.map(|r| format!("Rank #{}", format_number(r))),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if !subtitle.is_empty() {
+            scene.text(LEFT, 290, 32, false, Anchor::Start, MUTED, subtitle.join(" \u{b7} "));
+        }
+
+        let mut stats = vec![
+            ("GAMES", format_number(self.games_played)),
+            ("TOTAL SCORE", format_number(self.total_score)),
+            ("BEST GAME", format_number(self.best_score)),
+        ];
+        if let Some(streak) = self.best_streak {
+            stats.push(("BEST STREAK", format_number(streak)));
+        }
+        stat_panels(&mut scene, &stats);
+
+        scene
+    }
+}
+
+/// One row of a game result card
+#[derive(Debug, Clone)]
+pub struct GameCardStanding {
+    pub rank: u8,
+    pub display_name: String,
+    pub score: i64,
+}
+
+/// Results card for a finished game
+#[derive(Debug, Clone)]
+pub struct GameCard {
+    /// Game mode, e.g. "solo"
+    pub mode: String,
+    pub rounds: usize,
+    /// Whether scores are streak lengths
+    pub streak: bool,
+    /// Standings, best first
+    pub standings: Vec<GameCardStanding>,
+}
+
+/// Standings shown on a game card
+const MAX_CARD_STANDINGS: usize = 5;
+
+impl GameCard {
+    pub fn scene(&self) -> Scene {
+        let mut scene = frame(DEFAULT_ACCENT);
+
+        let title = if self.streak {
+            "Country streak".to_string()
+        } else {
+            let mut mode = self.mode.clone();
+            if let Some(first) = mode.get_mut(..1) {
+                first.make_ascii_uppercase();
+            }
+            format!("{mode} game \u{b7} {} rounds", self.rounds)
+        };
+        scene.text(LEFT, 200, 56, true, Anchor::Start, TEXT, title);
+
+        match self.standings.as_slice() {
+            [single] => {
+                let (label, value) = if self.streak {
+                    ("STREAK", format!("{} countries", format_number(single.score)))
+                } else {
+                    ("FINAL SCORE", format_number(single.score))
+                };
+                scene.text(
+                    LEFT,
+                    270,
+                    32,
+                    false,
+                    Anchor::Start,
+                    MUTED,
+                    truncate(&single.display_name, 40),
+                );
+                stat_panels(&mut scene, &[(label, value)]);
+            }
+            standings => {
+                let unit = if self.streak { " countries" } else { "" };
+                for (row, standing) in standings.iter().take(MAX_CARD_STANDINGS).enumerate() {
+                    let y = 270 + row as i32 * 62;
+                    let color = if row == 0 { TEXT } else { MUTED };
+                    scene.rect(LEFT - 16, y - 42, WIDTH - 2 * LEFT as u32 + 32, 56, 12, PANEL);
+                    scene.text(
+                        LEFT,
+                        y,
+                        32,
+                        row == 0,
+                        Anchor::Start,
+                        color,
+                        format!("#{}", standing.rank),
+                    );
+                    scene.text(
+                        LEFT + 100,
+                        y,
+                        32,
+                        row == 0,
+                        Anchor::Start,
+                        color,
+                        truncate(&standing.display_name, 32),
+                    );
+                    scene.text(
+                        WIDTH as i32 - LEFT,
+                        y,
+                        32,
+                        row == 0,
+                        Anchor::End,
+                        color,
+                        format!("{}{unit}", format_number(standing.score)),
+                    );
+                }
+            }
+        }
+
+        scene
+    }
+}
+
+/// Result card for one player's round, drawn over a map of the guess and
+/// the actual location
+#[derive(Debug, Clone)]
+pub struct RoundCard {
+    pub round_number: u8,
+    pub total_rounds: Option<usize>,
+    /// Points scored, if the player guessed
+    pub score: Option<i64>,
+    /// Guess distance, if the player guessed
+    pub distance_meters: Option<f64>,
+    /// Whether the player named the right country (streak games)
+    pub correct_country: Option<bool>,
+}
+
+impl RoundCard {
+    pub fn scene(&self) -> Scene {
+        let mut scene = Scene::new(WIDTH, HEIGHT, BACKGROUND);
+
+        // The map fills the card; text sits on solid panels above it
+        scene.rect(32, 32, 220, 64, 16, BACKGROUND);
+        scene.text(56, 77, 28, true, Anchor::Start, DEFAULT_ACCENT, "DGUESSER");
+        scene.rect(0, 470, WIDTH, HEIGHT - 470, 0, BACKGROUND);
+        scene.rect(0, 470, WIDTH, 8, 0, DEFAULT_ACCENT);
+
+        let round = match self.total_rounds {
+            Some(total) => format!("ROUND {} OF {total}", self.round_number),
+            None => format!("ROUND {}", self.round_number),
+        };
+        scene.text(LEFT, 530, 28, false, Anchor::Start, MUTED, round);
+
+        let result = match (self.correct_country, self.score) {
+            (Some(true), _) => "Correct country".to_string(),
+            (Some(false), _) => "Wrong country".to_string(),
+            (None, Some(score)) => format!("{} points", format_number(score)),
+            (None, None) => "No guess".to_string(),
+        };
+        scene.text(LEFT, 595, 56, true, Anchor::Start, TEXT, result);
+
+        if let Some(distance) = self.distance_meters {
+            let right = WIDTH as i32 - LEFT;
+            scene.text(right, 530, 28, false, Anchor::End, MUTED, "DISTANCE");
+            scene.text(right, 595, 48, true, Anchor::End, TEXT, format_distance(distance));
+        }
+
+        scene
+    }
+}
+
+/// Background, accent bar and branding shared by every card
+fn frame(accent: Color) -> Scene {
+    let mut scene = Scene::new(WIDTH, HEIGHT, BACKGROUND);
+    scene.rect(0, 0, 24, HEIGHT, 0, accent);
+    scene.text(LEFT, 110, 28, true, Anchor::Start, accent, "DGUESSER");
+    scene.text(
+        WIDTH as i32 - LEFT,
+        HEIGHT as i32 - 50,
+        24,
+        false,
+        Anchor::End,
+        MUTED,
+        "Can you guess where?",
+    );
+    scene
+}
+
+/// A row of labelled stat panels below the heading
+fn stat_panels(scene: &mut Scene, stats: &[(&str, String)]) {
+    const GAP: u32 = 24;
+    let count = stats.len().max(1) as u32;
+    let available = WIDTH - 2 * LEFT as u32;
+    let width = (available - GAP * (count - 1)) / count;
+
+    for (i, (label, value)) in stats.iter().enumerate() {
+        let x = LEFT + (i as u32 * (width + GAP)) as i32;
+        scene.rect(x, 350, width, 160, 16, PANEL);
+        scene.text(x + 28, 400, 24, false, Anchor::Start, MUTED, *label);
+        // Keep values inside the panel: 0.6 × size per character
+        let size =
+            (width.saturating_sub(56) * 10 / (6 * value.chars().count().max(1) as u32)).min(56);
+        scene.text(x + 28, 475, size, true, Anchor::Start, TEXT, value.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_card_renders_both_formats() {
+        let card = ProfileCard {
+            display_name: "Cool <Player>",
+            username: Some("coolplayer42"),
+            country_code: Some("SE"),
+            profile_color: Some("#10b981"),
+            games_played: 128,
+            total_score: 1_234_567,
+            best_score: 24_870,
+            best_streak: Some(31),
+            rank: Some(42),
+        };
+        let scene = card.scene();
+
+        let svg = scene.to_svg();
+        assert!(svg.contains("Cool &lt;Player&gt;"));
+        assert!(svg.contains("1,234,567"));
+        assert!(svg.contains("#10b981"));
+
+        let png = scene.to_png();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        // Flat colors compress far below the 2.2 MB of raw pixels
+        assert!(png.len() < 200_000, "PNG is {} bytes", png.len());
+    }
+
+    #[test]
+    fn round_card_shows_score_and_distance() {
+        let card = RoundCard {
+            round_number: 3,
+            total_rounds: Some(5),
+            score: Some(4321),
+            distance_meters: Some(123_456.0),
+            correct_country: None,
+        };
+        let svg = card.scene().to_svg();
+        assert!(svg.contains("ROUND 3 OF 5"));
+        assert!(svg.contains("4,321 points"));
+        assert!(svg.contains("123 km"));
+
+        // Drawn over an undecodable map, the card still renders
+        let png = card.scene().to_png_over(b"not a png");
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    }
+
+    #[test]
+    fn game_card_lists_top_standings() {
+        let card = GameCard {
+            mode: "multiplayer".to_string(),
+            rounds: 5,
+            streak: false,
+            standings: (1..=8)
+                .map(|rank| GameCardStanding {
+                    rank,
+                    display_name: format!("Player {rank}"),
+                    score: 25_000 - rank as i64 * 1000,
+                })
+                .collect(),
+        };
+        let svg = card.scene().to_svg();
+        assert!(svg.contains("Multiplayer game"));
+        assert!(svg.contains("Player 5"));
+        assert!(!svg.contains("Player 6"));
+    }
+}
@@ -0,0 +1,292 @@
+//! Server-side rendering of shareable image cards
+//!
+//! A card is laid out once as a [`Scene`] of rectangles and text, then
+//! written out as SVG or rasterized to PNG. Social networks only accept
+//! raster preview images, so PNG is the default; SVG keeps real fonts and
+//! full Unicode for embedding.
+//!
+//! The rasterizer draws text with a built-in 5x7 bitmap font and sizes it to
+//! match a monospace font in SVG, so both outputs share one layout.
+
+mod cards;
+mod font;
+mod raster;
+
+pub use cards::{GameCard, GameCardStanding, ProfileCard, RoundCard};
+
+use serde::Deserialize;
+
+/// Image format for rendered cards
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CardFormat {
+    #[default]
+    Png,
+    Svg,
+}
+
+impl CardFormat {
+    /// Content-Type of the rendered image.
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Svg => "image/svg+xml",
+        }
+    }
+}
+
+/// An RGB color
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Parse a `#rrggbb` color.
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.strip_prefix('#')?;
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        Some(Self::rgb(channel(0)?, channel(2)?, channel(4)?))
+    }
+
+    fn to_hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+/// Horizontal alignment of text relative to its x coordinate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Anchor {
+    Start,
+    End,
+}
+
+#[derive(Debug, Clone)]
+enum Element {
+    Rect { x: i32, y: i32, width: u32, height: u32, radius: u32, fill: Color },
+    Text { x: i32, y: i32, size: u32, bold: bool, anchor: Anchor, fill: Color, text: String },
+}
+
+/// A fixed-size drawing made of rectangles and single-line text
+#[derive(Debug, Clone)]
+pub struct Scene {
+    width: u32,
+    height: u32,
+    background: Color,
+    elements: Vec<Element>,
+}
+
+impl Scene {
+    pub fn new(width: u32, height: u32, background: Color) -> Self {
+        Self { width, height, background, elements: Vec::new() }
+    }
+
+    /// Add a filled rectangle with optionally rounded corners.
+    pub fn rect(&mut self, x: i32, y: i32, width: u32, height: u32, radius: u32, fill: Color) {
+        self.elements.push(Element::Rect { x, y, width, height, radius, fill });
+    }
+
+    /// Add a line of text with its baseline at `y`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn text(
+        &mut self,
+        x: i32,
+        y: i32,
+        size: u32,
+        bold: bool,
+        anchor: Anchor,
+        fill: Color,
+        text: impl Into<String>,
+    ) {
+        self.elements.push(Element::Text { x, y, size, bold, anchor, fill, text: text.into() });
+    }
+
+    /// Write the scene as a standalone SVG document.
+    pub fn to_svg(&self) -> String {
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}"><rect width="{w}" height="{h}" fill="{bg}"/>"#,
+            w = self.width,
+            h = self.height,
+            bg = self.background.to_hex(),
+        );
+        for element in &self.elements {
+            match element {
+                Element::Rect { x, y, width, height, radius, fill } => {
+                    svg.push_str(&format!(
+                        r#"<rect x="{x}" y="{y}" width="{width}" height="{height}" rx="{radius}" fill="{}"/>"#,
+                        fill.to_hex()
+                    ));
+                }
+                Element::Text { x, y, size, bold, anchor, fill, text } => {
+                    let anchor = match anchor {
+                        Anchor::Start => "start",
+                        Anchor::End => "end",
+                    };
+                    svg.push_str(&format!(
+                        r#"<text x="{x}" y="{y}" font-family="ui-monospace, Menlo, Consolas, 'DejaVu Sans Mono', monospace" font-size="{size}" font-weight="{}" text-anchor="{anchor}" fill="{}">{}</text>"#,
+                        if *bold { "bold" } else { "normal" },
+                        fill.to_hex(),
+                        escape_xml(text),
+                    ));
+                }
+            }
+        }
+        svg.push_str("</svg>");
+        svg
+    }
+
+    /// Rasterize the scene and encode it as PNG.
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut canvas = raster::Canvas::new(self.width, self.height, self.background);
+        self.draw(&mut canvas);
+        canvas.encode_png()
+    }
+
+    /// Rasterize the scene over a PNG image (e.g. a map), drawn from the top
+    /// left corner. Falls back to the plain background if the image can't be
+    /// decoded.
+    pub fn to_png_over(&self, backdrop_png: &[u8]) -> Vec<u8> {
+        let mut canvas = raster::Canvas::new(self.width, self.height, self.background);
+        match raster::Canvas::decode_png(backdrop_png) {
+            Some(backdrop) => canvas.draw_canvas(0, 0, &backdrop),
+            None => tracing::warn!("Failed to decode card backdrop image"),
+        }
+        self.draw(&mut canvas);
+        canvas.encode_png()
+    }
+
+    fn draw(&self, canvas: &mut raster::Canvas) {
+        for element in &self.elements {
+            match element {
+                Element::Rect { x, y, width, height, radius, fill } => {
+                    canvas.fill_rect(*x, *y, *width, *height, *radius, *fill);
+                }
+                Element::Text { x, y, size, bold, anchor, fill, text } => {
+                    let width = text_width(text, *size) as i32;
+                    let left = match anchor {
+                        Anchor::Start => *x,
+                        Anchor::End => x - width,
+                    };
+                    canvas.draw_text(left, *y, *size, *bold, *fill, text);
+                }
+            }
+        }
+    }
+
+    /// Render in the requested format.
+    pub fn render(&self, format: CardFormat) -> Vec<u8> {
+        match format {
+            CardFormat::Png => self.to_png(),
+            CardFormat::Svg => self.to_svg().into_bytes(),
+        }
+    }
+}
+
+/// Approximate rendered width of monospace text
+fn text_width(text: &str, size: u32) -> u32 {
+    (text.chars().count() as u32 * font::ADVANCE * size) / 10
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Shorten text to at most `max` characters, marking the cut with "..."
+pub(crate) fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut short: String = text.chars().take(max.saturating_sub(3)).collect();
+    short.push_str("...");
+    short
+}
+
+/// Format a number with thousands separators (e.g. 12,345)
+pub(crate) fn format_number(n: i64) -> String {
+    let digits = n.unsigned_abs().to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3 + 1);
+    if n < 0 {
+        out.push('-');
+    }
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Format a distance in meters as "850 m" or "1,234 km"
+pub(crate) fn format_distance(meters: f64) -> String {
+    if meters < 1000.0 {
+        format!("{} m", meters.max(0.0).round() as i64)
+    } else {
+        format!("{} km", format_number((meters / 1000.0).round() as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn svg_escapes_text() {
+        let mut scene = Scene::new(100, 50, Color::rgb(0, 0, 0));
+        scene.text(0, 20, 12, false, Anchor::Start, Color::rgb(255, 255, 255), "<b>&\"x\"");
+        let svg = scene.to_svg();
+        assert!(svg.contains("&lt;b&gt;&amp;&quot;x&quot;"));
+        assert!(!svg.contains("<b>"));
+    }
+
+    #[test]
+    fn parses_hex_colors() {
+        assert_eq!(Color::from_hex("#3b82f6"), Some(Color::rgb(0x3b, 0x82, 0xf6)));
+        assert_eq!(Color::from_hex("3b82f6"), None);
+        assert_eq!(Color::from_hex("#3b82f"), None);
+        assert_eq!(Color::from_hex("#zzzzzz"), None);
+    }
+
+    #[test]
+    fn formats_numbers() {
+        assert_eq!(format_number(0), "0");
+        assert_eq!(format_number(999), "999");
+        assert_eq!(format_number(1000), "1,000");
+        assert_eq!(format_number(1234567), "1,234,567");
+        assert_eq!(format_number(-12345), "-12,345");
+    }
+
+    #[test]
+    fn formats_distances() {
+        assert_eq!(format_distance(849.6), "850 m");
+        assert_eq!(format_distance(1499.0), "1 km");
+        assert_eq!(format_distance(12_345_678.0), "12,346 km");
+    }
+
+    #[test]
+    fn truncates_long_text() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("a very long display name", 10), "a very ...");
+    }
+}
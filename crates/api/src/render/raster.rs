@@ -0,0 +1,436 @@
+//! RGB canvas and PNG encoder
+//!
+//! Cards are flat colors and blocky text, so the encoder only needs what
+//! compresses that well: a single fixed-Huffman deflate block whose matches
+//! repeat the previous pixel or the row above. Backdrop images (maps) are
+//! decoded with the `png` crate.
+
+use super::Color;
+use super::font::{self, ADVANCE, GLYPH_HEIGHT, GLYPH_WIDTH};
+
+/// An RGB pixel buffer
+pub struct Canvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    /// Create a canvas filled with `background`.
+    pub fn new(width: u32, height: u32, background: Color) -> Self {
+        let pixels = [background.r, background.g, background.b].repeat((width * height) as usize);
+        Self { width, height, pixels }
+    }
+
+    /// Decode a PNG of any color type, dropping transparency.
+    pub fn decode_png(bytes: &[u8]) -> Option<Self> {
+        let mut decoder = png::Decoder::new(bytes);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info().ok()?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let frame = reader.next_frame(&mut buffer).ok()?;
+        buffer.truncate(frame.buffer_size());
+
+        let pixels = match frame.color_type {
+            png::ColorType::Rgb => buffer,
+            png::ColorType::Rgba => {
+                buffer.chunks_exact(4).flat_map(|px| [px[0], px[1], px[2]]).collect()
+            }
+            png::ColorType::Grayscale => buffer.iter().flat_map(|&v| [v, v, v]).collect(),
+            png::ColorType::GrayscaleAlpha => {
+                buffer.chunks_exact(2).flat_map(|px| [px[0], px[0], px[0]]).collect()
+            }
+            // Palettes are expanded by `normalize_to_color8`
+            png::ColorType::Indexed => return None,
+        };
+        Some(Self { width: frame.width, height: frame.height, pixels })
+    }
+
+    /// Copy another canvas with its top-left corner at (`x`, `y`). Clips to
+    /// the canvas.
+    pub fn draw_canvas(&mut self, x: i32, y: i32, other: &Canvas) {
+        for row in 0..other.height as i32 {
+            let py = y + row;
+            if py < 0 || py >= self.height as i32 {
+                continue;
+            }
+            let x0 = x.max(0);
+            let x1 = (x + other.width as i32).min(self.width as i32);
+            if x0 >= x1 {
+                return;
+            }
+            let src = ((row as u32 * other.width + (x0 - x) as u32) * 3) as usize;
+            let dst = ((py as u32 * self.width + x0 as u32) * 3) as usize;
+            let len = (x1 - x0) as usize * 3;
+            self.pixels[dst..dst + len].copy_from_slice(&other.pixels[src..src + len]);
+        }
+    }
+
+    /// Fill a rectangle, optionally with rounded corners. Clips to the canvas.
+    pub fn fill_rect(&mut self, x: i32, y: i32, w: u32, h: u32, radius: u32, color: Color) {
+        let x0 = x.max(0);
+        let y0 = y.max(0);
+        let x1 = (x + w as i32).min(self.width as i32);
+        let y1 = (y + h as i32).min(self.height as i32);
+        let r = radius.min(w / 2).min(h / 2) as i32;
+
+        for py in y0..y1 {
+            for px in x0..x1 {
+                if r > 0 && !inside_rounded(px - x, py - y, w as i32, h as i32, r) {
+                    continue;
+                }
+                let i = ((py as u32 * self.width + px as u32) * 3) as usize;
+                self.pixels[i..i + 3].copy_from_slice(&[color.r, color.g, color.b]);
+            }
+        }
+    }
+
+    /// Draw text with its left edge at `x` and its baseline at `y`.
+    ///
+    /// `size` matches an SVG font size: glyphs are 0.7 × size tall and
+    /// advance 0.6 × size, like a typical monospace font.
+    pub fn draw_text(&mut self, x: i32, y: i32, size: u32, bold: bool, color: Color, text: &str) {
+        let unit = size as f32 / 10.0;
+        let top = y as f32 - GLYPH_HEIGHT as f32 * unit;
+        let embolden = if bold { (unit / 2.0).ceil().max(1.0) as u32 } else { 0 };
+
+        for (index, c) in text.chars().enumerate() {
+            let origin = x as f32 + (index as u32 * ADVANCE) as f32 * unit;
+            let glyph = font::glyph(c);
+            for (row, bits) in glyph.iter().enumerate() {
+                for col in 0..GLYPH_WIDTH {
+                    if bits & (1 << (GLYPH_WIDTH - 1 - col)) == 0 {
+                        continue;
+                    }
+                    let px0 = (origin + col as f32 * unit).floor() as i32;
+                    let px1 = (origin + (col + 1) as f32 * unit).floor() as i32;
+                    let py0 = (top + row as f32 * unit).floor() as i32;
+                    let py1 = (top + (row + 1) as f32 * unit).floor() as i32;
+                    let w = (px1 - px0).max(1) as u32 + embolden;
+                    let h = (py1 - py0).max(1) as u32;
+                    self.fill_rect(px0, py0, w, h, 0, color);
+                }
+            }
+        }
+    }
+
+    /// Encode the canvas as a PNG.
+    pub fn encode_png(&self) -> Vec<u8> {
+        // Every scanline starts with filter type 0 (none)
+        let stride = self.width as usize * 3;
+        let mut raw = Vec::with_capacity((stride + 1) * self.height as usize);
+        for row in self.pixels.chunks(stride) {
+            raw.push(0);
+            raw.extend_from_slice(row);
+        }
+
+        let mut idat = vec![0x78, 0x01];
+        idat.extend_from_slice(&deflate(&raw, stride + 1));
+        idat.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+        let mut ihdr = Vec::with_capacity(13);
+        ihdr.extend_from_slice(&self.width.to_be_bytes());
+        ihdr.extend_from_slice(&self.height.to_be_bytes());
+        // 8-bit RGB, default compression/filter, no interlacing
+        ihdr.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        write_chunk(&mut png, b"IHDR", &ihdr);
+        write_chunk(&mut png, b"IDAT", &idat);
+        write_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+}
+
+/// Whether a point (relative to the rectangle) lies inside its rounded corners
+fn inside_rounded(x: i32, y: i32, w: i32, h: i32, r: i32) -> bool {
+    let cx = if x < r {
+        r
+    } else if x >= w - r {
+        w - r - 1
+    } else {
+        return true;
+    };
+    let cy = if y < r {
+        r
+    } else if y >= h - r {
+        h - r - 1
+    } else {
+        return true;
+    };
+    let (dx, dy) = (x - cx, y - cy);
+    dx * dx + dy * dy <= r * r
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut c = n as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 != 0 { 0xEDB8_8320 ^ (c >> 1) } else { c >> 1 };
+            k += 1;
+        }
+        table[n] = c;
+        n += 1;
+    }
+    table
+};
+
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |c, &b| CRC_TABLE[((c ^ b as u32) & 0xFF) as usize] ^ (c >> 8))
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= 65_521;
+        b %= 65_521;
+    }
+    (b << 16) | a
+}
+
+/// Base lengths for length codes 257..=285
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] =
+    [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+
+/// Base distances for distance codes 0..=29
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+
+/// Compress `data` as one fixed-Huffman deflate block, matching against the
+/// previous pixel and the same pixel one scanline (`row_len` bytes) up.
+fn deflate(data: &[u8], row_len: usize) -> Vec<u8> {
+    let mut out = BitWriter::default();
+    // BFINAL = 1, BTYPE = 01 (fixed Huffman)
+    out.bits(1, 1);
+    out.bits(1, 2);
+
+    let mut i = 0;
+    while i < data.len() {
+        let mut best = (0, 0);
+        for distance in [3, row_len] {
+            if distance > i || distance > 32_768 {
+                continue;
+            }
+            let max = MAX_MATCH.min(data.len() - i);
+            let len = (0..max).take_while(|&k| data[i + k] == data[i + k - distance]).count();
+            if len > best.0 {
+                best = (len, distance);
+            }
+        }
+
+        if best.0 >= MIN_MATCH {
+            out.length(best.0 as u16);
+            out.distance(best.1 as u16);
+            i += best.0;
+        } else {
+            out.literal(data[i] as u16);
+            i += 1;
+        }
+    }
+    out.literal(256);
+    out.finish()
+}
+
+#[derive(Default)]
+struct BitWriter {
+    out: Vec<u8>,
+    acc: u64,
+    count: u32,
+}
+
+impl BitWriter {
+    /// Write `n` bits of `value`, least significant first.
+    fn bits(&mut self, value: u32, n: u32) {
+        self.acc |= (value as u64) << self.count;
+        self.count += n;
+        while self.count >= 8 {
+            self.out.push(self.acc as u8);
+            self.acc >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    /// Write a Huffman code, most significant bit first.
+    fn code(&mut self, code: u32, len: u32) {
+        self.bits(code.reverse_bits() >> (32 - len), len);
+    }
+
+    /// Write a literal/length symbol with the fixed Huffman table.
+    fn literal(&mut self, symbol: u16) {
+        let symbol = symbol as u32;
+        match symbol {
+            0..=143 => self.code(0x30 + symbol, 8),
+            144..=255 => self.code(0x190 + symbol - 144, 9),
+            256..=279 => self.code(symbol - 256, 7),
+            _ => self.code(0xC0 + symbol - 280, 8),
+        }
+    }
+
+    fn length(&mut self, len: u16) {
+        let index = LENGTH_BASE.iter().rposition(|&base| base <= len).unwrap_or(0);
+        self.literal(257 + index as u16);
+        self.bits((len - LENGTH_BASE[index]) as u32, LENGTH_EXTRA[index] as u32);
+    }
+
+    fn distance(&mut self, distance: u16) {
+        let index = DIST_BASE.iter().rposition(|&base| base <= distance).unwrap_or(0);
+        self.code(index as u32, 5);
+        self.bits((distance - DIST_BASE[index]) as u32, DIST_EXTRA[index] as u32);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.out.push(self.acc as u8);
+        }
+        self.out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimal inflater for fixed-Huffman blocks, to check the encoder round-trips.
+    fn inflate_fixed(data: &[u8]) -> Vec<u8> {
+        let mut pos = 0usize;
+        let mut bit = |n: u32| -> u32 {
+            let mut v = 0;
+            for k in 0..n {
+                let b = (data[pos / 8] >> (pos % 8)) & 1;
+                v |= (b as u32) << k;
+                pos += 1;
+            }
+            v
+        };
+        assert_eq!(bit(1), 1, "single final block");
+        assert_eq!(bit(2), 1, "fixed Huffman");
+
+        let mut out = Vec::new();
+        loop {
+            let mut code = 0;
+            for _ in 0..7 {
+                code = (code << 1) | bit(1);
+            }
+            let symbol = if code <= 0x17 {
+                256 + code
+            } else {
+                code = (code << 1) | bit(1);
+                match code {
+                    0x30..=0xBF => code - 0x30,
+                    0xC0..=0xC7 => 280 + code - 0xC0,
+                    _ => {
+                        code = (code << 1) | bit(1);
+                        144 + code - 0x190
+                    }
+                }
+            };
+            match symbol {
+                0..=255 => out.push(symbol as u8),
+                256 => return out,
+                _ => {
+                    let i = (symbol - 257) as usize;
+                    let len = LENGTH_BASE[i] as usize + bit(LENGTH_EXTRA[i] as u32) as usize;
+                    let mut d = 0;
+                    for _ in 0..5 {
+                        d = (d << 1) | bit(1);
+                    }
+                    let d = d as usize;
+                    let dist = DIST_BASE[d] as usize + bit(DIST_EXTRA[d] as u32) as usize;
+                    for _ in 0..len {
+                        out.push(out[out.len() - dist]);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn deflate_round_trips() {
+        let mut data = vec![0u8; 10_000];
+        for (i, b) in data.iter_mut().enumerate() {
+            *b = match i % 1000 {
+                0..=299 => 7,
+                300..=310 => (i % 1000 * 7) as u8,
+                _ => ((i % 1000 / 3) % 5) as u8 * 40,
+            };
+        }
+        let compressed = deflate(&data, 1000);
+        assert!(compressed.len() < data.len() / 4);
+        assert_eq!(inflate_fixed(&compressed), data);
+    }
+
+    #[test]
+    fn checksums_match_known_values() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+    }
+
+    #[test]
+    fn png_has_valid_structure() {
+        let mut canvas = Canvas::new(40, 20, Color::rgb(10, 20, 30));
+        canvas.fill_rect(5, 5, 10, 10, 3, Color::rgb(255, 0, 0));
+        canvas.draw_text(2, 18, 10, true, Color::rgb(255, 255, 255), "Hi 42");
+        let png = canvas.encode_png();
+
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(u32::from_be_bytes(png[16..20].try_into().unwrap()), 40);
+        assert_eq!(u32::from_be_bytes(png[20..24].try_into().unwrap()), 20);
+
+        // IDAT inflates back to the raw scanlines
+        let idat_len = u32::from_be_bytes(png[33..37].try_into().unwrap()) as usize;
+        assert_eq!(&png[37..41], b"IDAT");
+        let idat = &png[41..41 + idat_len];
+        let raw = inflate_fixed(&idat[2..idat.len() - 4]);
+        assert_eq!(raw.len(), (40 * 3 + 1) * 20);
+        assert_eq!(u32::from_be_bytes(idat[idat.len() - 4..].try_into().unwrap()), adler32(&raw));
+        assert!(png.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xAE, 0x42, 0x60, 0x82]));
+    }
+
+    #[test]
+    fn decodes_and_draws_other_canvases() {
+        let mut map = Canvas::new(30, 10, Color::rgb(200, 220, 240));
+        map.fill_rect(10, 0, 10, 10, 0, Color::rgb(40, 160, 60));
+        let decoded = Canvas::decode_png(&map.encode_png()).expect("valid PNG");
+        assert_eq!((decoded.width, decoded.height), (30, 10));
+        assert_eq!(decoded.pixels, map.pixels);
+
+        let mut card = Canvas::new(20, 20, Color::rgb(0, 0, 0));
+        card.draw_canvas(-10, 15, &decoded);
+        // Green column lands at x 0..10, clipped below y 20
+        assert_eq!(&card.pixels[(15 * 20) * 3..(15 * 20) * 3 + 3], &[40, 160, 60]);
+        assert_eq!(&card.pixels[(15 * 20 + 10) * 3..(15 * 20 + 10) * 3 + 3], &[200, 220, 240]);
+        assert_eq!(&card.pixels[..3], &[0, 0, 0]);
+
+        assert!(Canvas::decode_png(b"not a png").is_none());
+    }
+}
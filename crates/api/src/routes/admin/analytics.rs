@@ -0,0 +1,331 @@
+//! Admin API routes for game and player analytics.
+//!
+//! All figures come from the rollup tables maintained by
+//! [`crate::analytics`], so they lag live data by up to an hour.
+
+use std::collections::BTreeMap;
+
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+use dguesser_auth::RequireAdmin;
+use dguesser_core::geo::countries::continent_of;
+use dguesser_db::analytics::{CountryGuesses, DailyGames, RetentionRow};
+use dguesser_protocol::api::admin::{
+    ActivityAnalyticsResponse, AnalyticsParams, ContinentAnalyticsResponse, ContinentGuessStats,
+    CountryGuessStats, DailyActivityPoint, GuessAnalyticsResponse, GuessDistributionPoint,
+    ModeGameStats, Percentiles, RetentionAnalyticsResponse, RetentionCohort, RetentionParams,
+};
+
+use crate::error::ApiError;
+use crate::state::AppState;
+
+/// Get daily active users, new users, and games per mode.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/analytics/activity",
+    tag = "admin",
+    params(
+        ("days" = Option<i32>, Query, description = "Days of history (default 30, max 365)")
+    ),
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Daily activity", body = ActivityAnalyticsResponse),
+        (status = 403, description = "Admin access required"),
+    )
+)]
+pub(super) async fn get_activity(
+    State(state): State<AppState>,
+    RequireAdmin(_auth): RequireAdmin,
+    Query(params): Query<AnalyticsParams>,
+) -> Result<Json<ActivityAnalyticsResponse>, ApiError> {
+    let days = params.days.clamp(1, 365);
+
+    let activity = dguesser_db::analytics::daily_activity(state.db_read(), days).await?;
+    let games = dguesser_db::analytics::daily_games(state.db_read(), days).await?;
+    let refreshed_at = dguesser_db::analytics::last_refreshed_at(state.db_read()).await?;
+
+    let mut games_by_day: BTreeMap<_, Vec<&DailyGames>> = BTreeMap::new();
+    let mut games_by_mode: BTreeMap<&str, Vec<&DailyGames>> = BTreeMap::new();
+    for row in &games {
+        games_by_day.entry(row.day).or_default().push(row);
+        games_by_mode.entry(&row.mode).or_default().push(row);
+    }
+
+    let daily = activity
+        .into_iter()
+        .map(|day| DailyActivityPoint {
+            date: day.day,
+            active_users: day.active_users,
+            new_users: day.new_users,
+            guesses: day.guesses,
+            games: games_by_day
+                .get(&day.day)
+                .map(|rows| rows.iter().map(|row| mode_stats(&row.mode, [*row])).collect())
+                .unwrap_or_default(),
+        })
+        .collect();
+
+    let totals_by_mode =
+        games_by_mode.into_iter().map(|(mode, rows)| mode_stats(mode, rows)).collect();
+
+    Ok(Json(ActivityAnalyticsResponse { daily, totals_by_mode, refreshed_at }))
+}
+
+/// Get daily guess score and distance percentiles.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/analytics/guesses",
+    tag = "admin",
+    params(
+        ("days" = Option<i32>, Query, description = "Days of history (default 30, max 365)")
+    ),
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Guess distributions", body = GuessAnalyticsResponse),
+        (status = 403, description = "Admin access required"),
+    )
+)]
+pub(super) async fn get_guess_distribution(
+    State(state): State<AppState>,
+    RequireAdmin(_auth): RequireAdmin,
+    Query(params): Query<AnalyticsParams>,
+) -> Result<Json<GuessAnalyticsResponse>, ApiError> {
+    let days = params.days.clamp(1, 365);
+
+    let activity = dguesser_db::analytics::daily_activity(state.db_read(), days).await?;
+
+    Ok(Json(GuessAnalyticsResponse {
+        daily: activity
+            .into_iter()
+            .map(|day| GuessDistributionPoint {
+                date: day.day,
+                guesses: day.guesses,
+                score: day.score_percentiles.as_deref().and_then(percentiles),
+                distance_km: day.distance_percentiles.as_deref().and_then(percentiles),
+            })
+            .collect(),
+    }))
+}
+
+/// Get weekly signup cohorts and how many of each stayed active.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/analytics/retention",
+    tag = "admin",
+    params(
+        ("weeks" = Option<i32>, Query, description = "Signup cohorts (default 12, max 52)")
+    ),
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Retention cohorts", body = RetentionAnalyticsResponse),
+        (status = 403, description = "Admin access required"),
+    )
+)]
+pub(super) async fn get_retention(
+    State(state): State<AppState>,
+    RequireAdmin(_auth): RequireAdmin,
+    Query(params): Query<RetentionParams>,
+) -> Result<Json<RetentionAnalyticsResponse>, ApiError> {
+    let weeks = params.weeks.clamp(1, 52);
+
+    let rows = dguesser_db::analytics::retention(state.db_read(), weeks).await?;
+
+    Ok(Json(RetentionAnalyticsResponse { cohorts: retention_cohorts(rows) }))
+}
+
+/// Get guess counts, scores and distances by continent and country of the
+/// round location.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/analytics/continents",
+    tag = "admin",
+    params(
+        ("days" = Option<i32>, Query, description = "Days of history (default 30, max 365)")
+    ),
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Guesses by continent", body = ContinentAnalyticsResponse),
+        (status = 403, description = "Admin access required"),
+    )
+)]
+pub(super) async fn get_continents(
+    State(state): State<AppState>,
+    RequireAdmin(_auth): RequireAdmin,
+    Query(params): Query<AnalyticsParams>,
+) -> Result<Json<ContinentAnalyticsResponse>, ApiError> {
+    let days = params.days.clamp(1, 365);
+
+    let rows = dguesser_db::analytics::country_guesses(state.db_read(), days).await?;
+
+    Ok(Json(ContinentAnalyticsResponse { continents: continent_stats(rows) }))
+}
+
+/// Sum game rows of one mode into stats.
+fn mode_stats<'a>(mode: &str, rows: impl IntoIterator<Item = &'a DailyGames>) -> ModeGameStats {
+    let mut stats =
+        ModeGameStats { mode: mode.to_string(), created: 0, finished: 0, avg_duration_secs: None };
+    let mut duration_secs = 0;
+    for row in rows {
+        stats.created += i64::from(row.games_created);
+        stats.finished += i64::from(row.games_finished);
+        duration_secs += row.duration_secs_total;
+    }
+    if stats.finished > 0 {
+        stats.avg_duration_secs = Some(duration_secs as f64 / stats.finished as f64);
+    }
+    stats
+}
+
+/// Convert stored percentile values, ordered as
+/// [`dguesser_db::analytics::PERCENTILES`].
+fn percentiles(values: &[f64]) -> Option<Percentiles> {
+    match *values {
+        [p10, p25, p50, p75, p90] => Some(Percentiles { p10, p25, p50, p75, p90 }),
+        _ => None,
+    }
+}
+
+/// Group country rows, ordered by guesses, into continents ordered by guesses.
+fn continent_stats(rows: Vec<CountryGuesses>) -> Vec<ContinentGuessStats> {
+    let average = |total: f64, guesses: i64| if guesses > 0 { total / guesses as f64 } else { 0.0 };
+
+    let mut by_continent: BTreeMap<&str, Vec<CountryGuesses>> = BTreeMap::new();
+    for row in rows {
+        let continent = continent_of(&row.country_code).map_or("unknown", |c| c.as_str());
+        by_continent.entry(continent).or_default().push(row);
+    }
+
+    let mut continents: Vec<ContinentGuessStats> = by_continent
+        .into_iter()
+        .map(|(continent, rows)| {
+            let guesses = rows.iter().map(|row| row.guesses).sum();
+            let score_total: i64 = rows.iter().map(|row| row.score_total).sum();
+            let distance_km_total: f64 = rows.iter().map(|row| row.distance_km_total).sum();
+            ContinentGuessStats {
+                continent: continent.to_string(),
+                guesses,
+                avg_score: average(score_total as f64, guesses),
+                avg_distance_km: average(distance_km_total, guesses),
+                countries: rows
+                    .into_iter()
+                    .map(|row| CountryGuessStats {
+                        avg_score: average(row.score_total as f64, row.guesses),
+                        avg_distance_km: average(row.distance_km_total, row.guesses),
+                        country_code: row.country_code,
+                        guesses: row.guesses,
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+    continents.sort_by_key(|c| std::cmp::Reverse(c.guesses));
+    continents
+}
+
+/// Group retention rows, ordered by cohort and week offset, into cohorts.
+fn retention_cohorts(rows: Vec<RetentionRow>) -> Vec<RetentionCohort> {
+    let mut cohorts: Vec<RetentionCohort> = Vec::new();
+    for row in rows {
+        let cohort = match cohorts.last_mut() {
+            Some(cohort) if cohort.cohort_week == row.cohort_week => cohort,
+            _ => {
+                cohorts.push(RetentionCohort {
+                    cohort_week: row.cohort_week,
+                    size: row.cohort_size,
+                    retained_users: Vec::new(),
+                    retention: Vec::new(),
+                });
+                cohorts.last_mut().expect("cohort was just pushed")
+            }
+        };
+        cohort.retained_users.push(row.retained_users);
+        cohort.retention.push(if row.cohort_size > 0 {
+            f64::from(row.retained_users) / f64::from(row.cohort_size)
+        } else {
+            0.0
+        });
+    }
+    cohorts
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn games(day: u32, mode: &str, created: i32, finished: i32, secs: i64) -> DailyGames {
+        DailyGames {
+            day: NaiveDate::from_ymd_opt(2026, 5, day).unwrap(),
+            mode: mode.to_string(),
+            games_created: created,
+            games_finished: finished,
+            duration_secs_total: secs,
+        }
+    }
+
+    #[test]
+    fn test_mode_stats_averages_finished_games() {
+        let rows = [games(1, "solo", 4, 2, 600), games(2, "solo", 1, 1, 300)];
+        let stats = mode_stats("solo", &rows);
+        assert_eq!(stats.created, 5);
+        assert_eq!(stats.finished, 3);
+        assert_eq!(stats.avg_duration_secs, Some(300.0));
+
+        let stats = mode_stats("multiplayer", &[games(1, "multiplayer", 2, 0, 0)]);
+        assert_eq!(stats.avg_duration_secs, None);
+    }
+
+    #[test]
+    fn test_percentiles() {
+        assert_eq!(
+            percentiles(&[1.0, 2.0, 3.0, 4.0, 5.0]),
+            Some(Percentiles { p10: 1.0, p25: 2.0, p50: 3.0, p75: 4.0, p90: 5.0 })
+        );
+        assert_eq!(percentiles(&[1.0, 2.0]), None);
+    }
+
+    #[test]
+    fn test_continent_stats() {
+        let row = |code: &str, guesses, score_total, distance_km_total| CountryGuesses {
+            country_code: code.to_string(),
+            guesses,
+            score_total,
+            distance_km_total,
+        };
+        let continents = continent_stats(vec![
+            row("SE", 4, 16_000, 400.0),
+            row("BR", 3, 3_000, 6_000.0),
+            row("FR", 2, 10_000, 100.0),
+            row("ZZ", 1, 0, 10_000.0),
+        ]);
+
+        let names: Vec<_> = continents.iter().map(|c| c.continent.as_str()).collect();
+        assert_eq!(names, ["europe", "south_america", "unknown"]);
+        assert_eq!(continents[0].guesses, 6);
+        assert_eq!(continents[0].avg_score, 26_000.0 / 6.0);
+        assert_eq!(continents[0].avg_distance_km, 500.0 / 6.0);
+        assert_eq!(continents[0].countries[0].country_code, "SE");
+        assert_eq!(continents[0].countries[1].avg_score, 5_000.0);
+    }
+
+    #[test]
+    fn test_retention_cohorts() {
+        let week = |d| NaiveDate::from_ymd_opt(2026, 5, d).unwrap();
+        let row = |d, offset, size, retained| RetentionRow {
+            cohort_week: week(d),
+            week_offset: offset,
+            cohort_size: size,
+            retained_users: retained,
+        };
+        let cohorts = retention_cohorts(vec![row(4, 0, 10, 8), row(4, 1, 10, 5), row(11, 0, 4, 4)]);
+
+        assert_eq!(cohorts.len(), 2);
+        assert_eq!(cohorts[0].retained_users, vec![8, 5]);
+        assert_eq!(cohorts[0].retention, vec![0.8, 0.5]);
+        assert_eq!(cohorts[1].cohort_week, week(11));
+        assert_eq!(cohorts[1].retention, vec![1.0]);
+    }
+}
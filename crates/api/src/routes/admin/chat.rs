@@ -0,0 +1,60 @@
+//! Admin API routes for reading chat transcripts.
+//!
+//! Chat lives in parties, so a game's transcript is its party's chat while
+//! the game ran. Messages caught by the chat filter show their original text.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use dguesser_auth::RequireAdmin;
+use dguesser_db::parties::PartyMessage;
+use dguesser_protocol::api::admin::{ChatTranscriptMessage, GameChatTranscriptResponse};
+
+use crate::error::ApiError;
+use crate::state::AppState;
+
+fn transcript_message(message: PartyMessage) -> ChatTranscriptMessage {
+    ChatTranscriptMessage {
+        id: message.id,
+        user_id: message.user_id,
+        display_name: message.display_name,
+        content: message.content,
+        original_content: message.original_content,
+        sent_at: message.created_at,
+    }
+}
+
+/// Get the chat sent during a game.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/games/{game_id}/chat",
+    tag = "admin",
+    params(
+        ("game_id" = String, Path, description = "Game ID")
+    ),
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Chat transcript", body = GameChatTranscriptResponse),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Game not found"),
+    )
+)]
+pub(super) async fn get_game_chat(
+    State(state): State<AppState>,
+    RequireAdmin(auth): RequireAdmin,
+    Path(game_id): Path<String>,
+) -> Result<Json<GameChatTranscriptResponse>, ApiError> {
+    dguesser_db::games::get_game_by_id(state.db(), &game_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Game"))?;
+    let party_id = dguesser_db::parties::get_game_party_id(state.db(), &game_id).await?;
+    let messages = dguesser_db::parties::get_game_chat(state.db(), &game_id).await?;
+
+    tracing::info!(game_id = %game_id, moderator_id = %auth.user_id, "Game chat transcript viewed");
+    Ok(Json(GameChatTranscriptResponse {
+        game_id,
+        party_id,
+        messages: messages.into_iter().map(transcript_message).collect(),
+    }))
+}
@@ -0,0 +1,63 @@
+//! Admin API route for reloading operational settings without a restart.
+
+use axum::{Json, extract::State};
+use dguesser_auth::RequireAdmin;
+use dguesser_protocol::api::admin::{ReloadConfigRequest, ReloadConfigResponse};
+
+use crate::error::ApiError;
+use crate::runtime_config;
+use crate::state::AppState;
+
+/// Audit log action for config reloads
+const ACTION_RELOAD: &str = "config.reload";
+
+/// Reload the runtime config.
+///
+/// With `settings`, validates and stores them for every API and realtime
+/// instance; other instances pick them up within a few seconds. Without,
+/// re-reads the stored settings. Either way this instance applies them right
+/// away and the reload is recorded in the audit log.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/reload",
+    tag = "admin",
+    request_body = ReloadConfigRequest,
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Config reloaded", body = ReloadConfigResponse),
+        (status = 400, description = "Invalid settings"),
+        (status = 403, description = "Admin access required"),
+    )
+)]
+pub(super) async fn reload_config(
+    State(state): State<AppState>,
+    RequireAdmin(auth): RequireAdmin,
+    Json(req): Json<ReloadConfigRequest>,
+) -> Result<Json<ReloadConfigResponse>, ApiError> {
+    let stored = match req.settings {
+        Some(settings) => {
+            runtime_config::validate(&settings)
+                .map_err(|message| ApiError::bad_request("INVALID_CONFIG", message))?;
+            runtime_config::store(state.redis(), settings, &auth.user_id).await?
+        }
+        None => runtime_config::load(state.redis()).await?.unwrap_or_default(),
+    };
+
+    let changed = runtime_config::apply(&state, stored.clone());
+
+    let mut conn = state.db().acquire().await?;
+    dguesser_db::audit_log::record(
+        &mut conn,
+        &auth.user_id,
+        ACTION_RELOAD,
+        None,
+        serde_json::json!({
+            "version": stored.version,
+            "changed": changed,
+            "settings": stored.settings,
+        }),
+    )
+    .await?;
+
+    Ok(Json(ReloadConfigResponse { config: stored, changed }))
+}
@@ -0,0 +1,376 @@
+//! Admin API routes for inspecting running games.
+//!
+//! Game actors live on the realtime servers and save their state to Redis
+//! after every change (see the realtime crate's `redis_state`), so a snapshot
+//! is at most a couple of seconds behind the actor. Ending a game abandons it
+//! in the database; the realtime server running it notices and stops its
+//! actor within half a minute.
+
+use std::collections::HashMap;
+
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use chrono::{DateTime, Utc};
+use dguesser_auth::RequireAdmin;
+use dguesser_protocol::api::admin::{
+    ForceEndGameRequest, ForceEndGameResponse, GAME_STATE_KEY_PREFIX, LiveGameItem,
+    LiveGameStateResponse, LiveGamesResponse, LivePlayerState, LiveRoundState,
+};
+use dguesser_protocol::socket::events::server as events;
+use dguesser_protocol::socket::payloads::GameAbandonedPayload;
+use dguesser_redis::RedisConnection;
+use redis::AsyncCommands;
+use serde::Deserialize;
+
+use crate::error::ApiError;
+use crate::socket;
+use crate::state::AppState;
+
+/// Audit log action for ending a game
+const ACTION_FORCE_END: &str = "game.force_end";
+
+/// Reason shown to players when the admin gives none
+const DEFAULT_END_REASON: &str = "Ended by an administrator";
+
+/// The fields of the realtime server's cached game state this module reads.
+/// Unknown fields are ignored, so the realtime side can add fields freely.
+#[derive(Debug, Deserialize)]
+struct CachedGame {
+    game_id: String,
+    status: String,
+    round_number: u8,
+    total_rounds: u8,
+    players: HashMap<String, CachedPlayer>,
+    current_round: Option<CachedRound>,
+    settings_json: String,
+    #[serde(default)]
+    between_rounds_ends_at: Option<i64>,
+    #[serde(default)]
+    skip_votes: Vec<String>,
+    #[serde(default)]
+    time_budget_ends_at: Option<i64>,
+    #[serde(default)]
+    paused_at_ms: Option<i64>,
+    #[serde(default)]
+    rerolls_used: u8,
+    #[serde(default)]
+    teacher_paused: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct CachedPlayer {
+    user_id: String,
+    display_name: String,
+    is_host: bool,
+    total_score: u32,
+    connected: bool,
+    disconnect_time_ms: Option<i64>,
+    #[serde(default)]
+    rtt_ms: Option<u32>,
+    #[serde(default)]
+    rtt_samples: Vec<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CachedRound {
+    round_id: String,
+    round_number: u8,
+    location_id: Option<String>,
+    started_at_ms: i64,
+    time_limit_ms: Option<u32>,
+    guesses: HashMap<String, serde_json::Value>,
+}
+
+impl CachedRound {
+    fn ends_at_ms(&self) -> Option<i64> {
+        self.time_limit_ms.map(|limit| self.started_at_ms + i64::from(limit))
+    }
+}
+
+impl CachedGame {
+    fn is_paused(&self) -> bool {
+        self.paused_at_ms.is_some() || self.teacher_paused
+    }
+
+    fn summary(&self) -> LiveGameItem {
+        LiveGameItem {
+            game_id: self.game_id.clone(),
+            status: self.status.clone(),
+            round_number: self.round_number,
+            total_rounds: self.total_rounds,
+            players: self.players.len(),
+            connected_players: self.players.values().filter(|p| p.connected).count(),
+            round_ends_at: self
+                .current_round
+                .as_ref()
+                .and_then(CachedRound::ends_at_ms)
+                .and_then(from_ms),
+            paused: self.is_paused(),
+        }
+    }
+
+    fn into_state(self) -> LiveGameStateResponse {
+        let mut players: Vec<LivePlayerState> = self
+            .players
+            .into_values()
+            .map(|p| LivePlayerState {
+                user_id: p.user_id,
+                display_name: p.display_name,
+                is_host: p.is_host,
+                total_score: p.total_score,
+                connected: p.connected,
+                disconnected_at: p.disconnect_time_ms.and_then(from_ms),
+                rtt_ms: p.rtt_ms,
+                rtt_samples: p.rtt_samples,
+            })
+            .collect();
+        players.sort_by_key(|p| std::cmp::Reverse(p.total_score));
+
+        LiveGameStateResponse {
+            game_id: self.game_id,
+            status: self.status,
+            round_number: self.round_number,
+            total_rounds: self.total_rounds,
+            settings: serde_json::from_str(&self.settings_json).unwrap_or_default(),
+            players,
+            current_round: self.current_round.map(|r| {
+                let mut guessed: Vec<String> = r.guesses.keys().cloned().collect();
+                guessed.sort();
+                LiveRoundState {
+                    ends_at: r.ends_at_ms().and_then(from_ms),
+                    round_id: r.round_id,
+                    round_number: r.round_number,
+                    location_id: r.location_id,
+                    started_at: from_ms(r.started_at_ms),
+                    time_limit_ms: r.time_limit_ms,
+                    guessed,
+                }
+            }),
+            between_rounds_ends_at: self.between_rounds_ends_at.and_then(from_ms),
+            time_budget_ends_at: self.time_budget_ends_at.and_then(from_ms),
+            paused_at: self.paused_at_ms.and_then(from_ms),
+            teacher_paused: self.teacher_paused,
+            skip_votes: self.skip_votes,
+            rerolls_used: self.rerolls_used,
+        }
+    }
+}
+
+fn from_ms(ms: i64) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp_millis(ms)
+}
+
+fn parse_cached(game_id: &str, json: &str) -> Option<CachedGame> {
+    match serde_json::from_str(json) {
+        Ok(game) => Some(game),
+        Err(e) => {
+            tracing::warn!(error = %e, game_id = %game_id, "Unreadable cached game state");
+            None
+        }
+    }
+}
+
+/// IDs of all games with cached state.
+async fn live_game_ids(redis: &RedisConnection) -> Result<Vec<String>, ApiError> {
+    let keys = redis.scan_match(&format!("{GAME_STATE_KEY_PREFIX}*")).await?;
+    Ok(keys
+        .iter()
+        .filter_map(|key| key.strip_prefix(GAME_STATE_KEY_PREFIX))
+        .map(String::from)
+        .collect())
+}
+
+/// List games running on the realtime servers.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/games/live",
+    tag = "admin",
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Running games", body = LiveGamesResponse),
+        (status = 403, description = "Admin access required"),
+    )
+)]
+pub(super) async fn list_live_games(
+    State(state): State<AppState>,
+    RequireAdmin(_auth): RequireAdmin,
+) -> Result<Json<LiveGamesResponse>, ApiError> {
+    let mut conn = state.redis().clone();
+    let game_ids = live_game_ids(&conn).await?;
+    if game_ids.is_empty() {
+        return Ok(Json(LiveGamesResponse { games: Vec::new() }));
+    }
+
+    let keys: Vec<String> =
+        game_ids.iter().map(|id| format!("{GAME_STATE_KEY_PREFIX}{id}")).collect();
+    let values: Vec<Option<String>> = conn.mget(&keys).await?;
+
+    let mut games: Vec<LiveGameItem> = game_ids
+        .iter()
+        .zip(values)
+        .filter_map(|(id, json)| parse_cached(id, &json?))
+        .map(|game| game.summary())
+        .collect();
+    games.sort_by(|a, b| a.game_id.cmp(&b.game_id));
+
+    Ok(Json(LiveGamesResponse { games }))
+}
+
+/// Get the last saved state of a running game.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/games/{game_id}/state",
+    tag = "admin",
+    params(
+        ("game_id" = String, Path, description = "Game ID")
+    ),
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Game state snapshot", body = LiveGameStateResponse),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Game is not running"),
+    )
+)]
+pub(super) async fn get_live_game_state(
+    State(state): State<AppState>,
+    RequireAdmin(_auth): RequireAdmin,
+    Path(game_id): Path<String>,
+) -> Result<Json<LiveGameStateResponse>, ApiError> {
+    let mut conn = state.redis().clone();
+    let json: Option<String> = conn.get(format!("{GAME_STATE_KEY_PREFIX}{game_id}")).await?;
+    let game = json
+        .and_then(|json| parse_cached(&game_id, &json))
+        .ok_or_else(|| ApiError::not_found("Running game"))?;
+
+    Ok(Json(game.into_state()))
+}
+
+/// End a running game.
+///
+/// Abandons the game, tells the players in the room and drops its cached
+/// state. The action is recorded in the audit log.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/games/{game_id}/end",
+    tag = "admin",
+    params(
+        ("game_id" = String, Path, description = "Game ID")
+    ),
+    request_body = ForceEndGameRequest,
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Game ended", body = ForceEndGameResponse),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Game not found"),
+    )
+)]
+pub(super) async fn force_end_game(
+    State(state): State<AppState>,
+    RequireAdmin(auth): RequireAdmin,
+    Path(game_id): Path<String>,
+    req: Option<Json<ForceEndGameRequest>>,
+) -> Result<Json<ForceEndGameResponse>, ApiError> {
+    let req = req.map(|Json(req)| req).unwrap_or_default();
+    let reason = req
+        .reason
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty())
+        .unwrap_or_else(|| DEFAULT_END_REASON.to_string());
+
+    dguesser_db::games::get_game_by_id(state.db(), &game_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Game"))?;
+    let ended = dguesser_db::games::abandon(state.db(), &game_id).await?;
+
+    let mut conn = state.redis().clone();
+    let _: () = conn.del(format!("{GAME_STATE_KEY_PREFIX}{game_id}")).await?;
+
+    if ended {
+        let payload = GameAbandonedPayload { game_id: game_id.clone(), reason: reason.clone() };
+        if let Err(e) =
+            socket::emit_to_room(state.redis(), &game_id, events::GAME_ABANDONED, &payload).await
+        {
+            tracing::warn!(error = %e, game_id = %game_id, "Failed to notify players of ended game");
+        }
+
+        let mut db_conn = state.db().acquire().await?;
+        dguesser_db::audit_log::record(
+            &mut db_conn,
+            &auth.user_id,
+            ACTION_FORCE_END,
+            None,
+            serde_json::json!({ "game_id": game_id, "reason": reason }),
+        )
+        .await?;
+    }
+
+    Ok(Json(ForceEndGameResponse { game_id, ended }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cached_state() {
+        let json = r#"{
+            "game_id": "gam_FybH2oF9Xaw8",
+            "status": "round_in_progress",
+            "round_number": 2,
+            "total_rounds": 5,
+            "players": {
+                "usr_V1StGXR8_Z5j": {
+                    "user_id": "usr_V1StGXR8_Z5j",
+                    "display_name": "Alice",
+                    "avatar_url": null,
+                    "is_host": true,
+                    "total_score": 4200,
+                    "connected": true,
+                    "disconnect_time_ms": null,
+                    "handicap": 1.0,
+                    "rtt_ms": 85,
+                    "rtt_samples": [80, 85, 90]
+                },
+                "usr_3kTMd9Qb_aZx": {
+                    "user_id": "usr_3kTMd9Qb_aZx",
+                    "display_name": "Bob",
+                    "avatar_url": null,
+                    "is_host": false,
+                    "total_score": 3100,
+                    "connected": false,
+                    "disconnect_time_ms": 1767225590000
+                }
+            },
+            "current_round": {
+                "round_id": "rnd_Qw8dK2mZ4pLs",
+                "round_number": 2,
+                "location_lat": 48.85,
+                "location_lng": 2.35,
+                "panorama_id": null,
+                "location_id": "loc_a8Kd93Lmq0Zp",
+                "started_at_ms": 1767225600000,
+                "time_limit_ms": 60000,
+                "guesses": {
+                    "usr_V1StGXR8_Z5j": { "lat": 48.0, "lng": 2.0, "distance": 100.0, "score": 4900 }
+                }
+            },
+            "settings_json": "{\"rounds\":5}"
+        }"#;
+
+        let game = parse_cached("gam_FybH2oF9Xaw8", json).unwrap();
+        let summary = game.summary();
+        assert_eq!(summary.players, 2);
+        assert_eq!(summary.connected_players, 1);
+        assert_eq!(summary.round_ends_at.unwrap().timestamp_millis(), 1767225660000);
+        assert!(!summary.paused);
+
+        let state = game.into_state();
+        assert_eq!(state.players[0].display_name, "Alice");
+        assert_eq!(state.players[0].rtt_samples, vec![80, 85, 90]);
+        assert_eq!(state.players[1].rtt_ms, None);
+        assert_eq!(state.current_round.unwrap().guessed, vec!["usr_V1StGXR8_Z5j"]);
+        assert_eq!(state.settings["rounds"], 5);
+    }
+}
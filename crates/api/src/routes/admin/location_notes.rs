@@ -0,0 +1,229 @@
+//! Admin API routes for internal location notes.
+//!
+//! Admins can note and tag any location; map curators do the same through
+//! the map routes for locations in their maps. Notes show up in the review
+//! queue and location details, and can be searched here by text and tag.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use dguesser_auth::RequireAdmin;
+use dguesser_core::location::{normalize_tags, parse_tag_list};
+use dguesser_db::location_notes::{LocationNote, NoteFilter};
+use dguesser_protocol::api::admin::{
+    CreateLocationNoteRequest, LocationNoteItem, LocationNotesParams, LocationNotesResponse,
+};
+
+use crate::error::ApiError;
+use crate::state::AppState;
+
+/// Longest note body, in characters
+const MAX_NOTE_CHARS: usize = 2000;
+/// Most tags on one note
+const MAX_NOTE_TAGS: usize = 10;
+
+/// Audit log action for deleting someone else's note
+const ACTION_NOTE_DELETE: &str = "location_note.delete";
+
+pub(crate) fn note_item(note: LocationNote) -> LocationNoteItem {
+    LocationNoteItem {
+        id: note.id,
+        location_id: note.location_id,
+        author_id: note.author_id,
+        author_name: note.author_name,
+        map_id: note.map_id,
+        body: note.body,
+        tags: note.tags,
+        created_at: note.created_at,
+    }
+}
+
+/// Check a new note, returning its trimmed body and normalized tags.
+pub(crate) fn validate_note(
+    req: &CreateLocationNoteRequest,
+) -> Result<(String, Vec<String>), ApiError> {
+    let body = req.body.trim();
+    if body.is_empty() || body.chars().count() > MAX_NOTE_CHARS {
+        return Err(ApiError::bad_request(
+            "INVALID_NOTE",
+            format!("Notes must be 1-{MAX_NOTE_CHARS} characters"),
+        ));
+    }
+    let tags = normalize_tags(&req.tags);
+    if tags.len() > MAX_NOTE_TAGS {
+        return Err(ApiError::bad_request(
+            "TOO_MANY_TAGS",
+            format!("A note can have at most {MAX_NOTE_TAGS} tags"),
+        ));
+    }
+    Ok((body.to_string(), tags))
+}
+
+/// Search location notes, newest first.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/location-notes",
+    tag = "admin",
+    params(
+        ("q" = Option<String>, Query, description = "Text the note contains"),
+        ("tag" = Option<String>, Query, description = "Tag the note has"),
+        ("location_id" = Option<String>, Query, description = "Only notes on this location"),
+        ("author_id" = Option<String>, Query, description = "Only notes by this user"),
+        ("page" = Option<i64>, Query, description = "Page number (1-indexed)"),
+        ("per_page" = Option<i64>, Query, description = "Items per page (max 100)")
+    ),
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Location notes", body = LocationNotesResponse),
+        (status = 403, description = "Admin access required"),
+    )
+)]
+pub(super) async fn search_location_notes(
+    State(state): State<AppState>,
+    RequireAdmin(_auth): RequireAdmin,
+    Query(params): Query<LocationNotesParams>,
+) -> Result<Json<LocationNotesResponse>, ApiError> {
+    let page = params.page.max(1);
+    let per_page = params.per_page.clamp(1, 100);
+    let text = params.q.as_deref().map(str::trim).filter(|q| !q.is_empty());
+    let tag = params.tag.as_deref().and_then(|t| parse_tag_list(t).into_iter().next());
+
+    let filter = NoteFilter {
+        text,
+        tag: tag.as_deref(),
+        location_id: params.location_id.as_deref(),
+        author_id: params.author_id.as_deref(),
+    };
+    let (notes, total) = dguesser_db::location_notes::search(
+        state.db_read(),
+        filter,
+        per_page,
+        (page - 1) * per_page,
+    )
+    .await?;
+
+    Ok(Json(LocationNotesResponse {
+        notes: notes.into_iter().map(note_item).collect(),
+        total,
+        page,
+        per_page,
+        total_pages: (total as f64 / per_page as f64).ceil() as i64,
+    }))
+}
+
+/// Add an internal note to a location.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/locations/{location_id}/notes",
+    tag = "admin",
+    params(
+        ("location_id" = String, Path, description = "Location ID")
+    ),
+    request_body = CreateLocationNoteRequest,
+    security(("session" = [])),
+    responses(
+        (status = 201, description = "Note added", body = LocationNoteItem),
+        (status = 400, description = "Empty or overlong note, or too many tags"),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Location not found"),
+    )
+)]
+pub(super) async fn create_location_note(
+    State(state): State<AppState>,
+    RequireAdmin(auth): RequireAdmin,
+    Path(location_id): Path<String>,
+    Json(req): Json<CreateLocationNoteRequest>,
+) -> Result<(StatusCode, Json<LocationNoteItem>), ApiError> {
+    let (body, tags) = validate_note(&req)?;
+    dguesser_db::locations::get_location_by_id(state.db(), &location_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Location"))?;
+
+    let note = dguesser_db::location_notes::create(
+        state.db(),
+        &location_id,
+        &auth.user_id,
+        None,
+        &body,
+        &tags,
+    )
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(note_item(note))))
+}
+
+/// Delete a location note. Deleting another user's note is audited.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/location-notes/{note_id}",
+    tag = "admin",
+    params(
+        ("note_id" = i64, Path, description = "Note ID")
+    ),
+    security(("session" = [])),
+    responses(
+        (status = 204, description = "Note deleted"),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Note not found"),
+    )
+)]
+pub(super) async fn delete_location_note(
+    State(state): State<AppState>,
+    RequireAdmin(auth): RequireAdmin,
+    Path(note_id): Path<i64>,
+) -> Result<StatusCode, ApiError> {
+    let note = dguesser_db::location_notes::get(state.db(), note_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Note"))?;
+    if !dguesser_db::location_notes::delete(state.db(), note_id).await? {
+        return Err(ApiError::not_found("Note"));
+    }
+
+    if note.author_id.as_deref() != Some(auth.user_id.as_str()) {
+        let mut conn = state.db().acquire().await?;
+        dguesser_db::audit_log::record(
+            &mut conn,
+            &auth.user_id,
+            ACTION_NOTE_DELETE,
+            note.author_id.as_deref(),
+            serde_json::json!({
+                "note_id": note.id,
+                "location_id": note.location_id,
+                "body": note.body,
+            }),
+        )
+        .await?;
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(body: &str, tags: &[&str]) -> CreateLocationNoteRequest {
+        CreateLocationNoteRequest {
+            body: body.to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_validate_note() {
+        let (body, tags) =
+            validate_note(&request("  pano replaced 2025-03 ", &["Borderline Trekker", "snow"]))
+                .unwrap();
+        assert_eq!(body, "pano replaced 2025-03");
+        assert_eq!(tags, vec!["borderline-trekker", "snow"]);
+
+        assert!(validate_note(&request("   ", &[])).is_err());
+        assert!(validate_note(&request(&"x".repeat(MAX_NOTE_CHARS + 1), &[])).is_err());
+
+        let many: Vec<String> = (0..=MAX_NOTE_TAGS).map(|i| format!("tag{i}")).collect();
+        let many: Vec<&str> = many.iter().map(String::as_str).collect();
+        assert!(validate_note(&request("note", &many)).is_err());
+    }
+}
@@ -0,0 +1,535 @@
+//! Admin API routes for managing system maps.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use chrono::{DateTime, Utc};
+use dguesser_auth::RequireAdmin;
+use dguesser_core::location::{Map, MapRules};
+use dguesser_db::featured_maps::FeaturedMap;
+use dguesser_protocol::api::admin::{
+    CreateSystemMapRequest, FeaturedMapScheduleResponse, ScheduleFeaturedMapRequest,
+    ScheduledFeatureItem, SetMapActiveRequest, SystemMapItem, SystemMapsListResponse,
+    UpdateSystemMapRequest,
+};
+
+use crate::error::ApiError;
+use crate::featured_maps::apply_schedule;
+use crate::state::AppState;
+
+/// Longest period a map can be featured for
+const MAX_FEATURE_DAYS: i64 = 31;
+
+/// Days finished features stay in the schedule listing
+const FEATURE_HISTORY_DAYS: i32 = 30;
+
+/// List all system maps, inactive ones included.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/maps",
+    tag = "admin",
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "System maps", body = SystemMapsListResponse),
+        (status = 403, description = "Admin access required"),
+    )
+)]
+pub(super) async fn list_system_maps(
+    State(state): State<AppState>,
+    RequireAdmin(_auth): RequireAdmin,
+) -> Result<Json<SystemMapsListResponse>, ApiError> {
+    let maps = dguesser_db::locations::list_system_maps(state.db_read()).await?;
+
+    Ok(Json(SystemMapsListResponse { maps: maps.into_iter().map(system_map_item).collect() }))
+}
+
+/// Create a system map.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/maps",
+    tag = "admin",
+    request_body = CreateSystemMapRequest,
+    security(("session" = [])),
+    responses(
+        (status = 201, description = "Map created", body = SystemMapItem),
+        (status = 400, description = "Invalid request"),
+        (status = 403, description = "Admin access required"),
+        (status = 409, description = "Slug taken"),
+    )
+)]
+pub(super) async fn create_system_map(
+    State(state): State<AppState>,
+    RequireAdmin(auth): RequireAdmin,
+    Json(body): Json<CreateSystemMapRequest>,
+) -> Result<(StatusCode, Json<SystemMapItem>), ApiError> {
+    validate_slug(&body.slug)?;
+    let name = validate_name(&body.name)?;
+    let description = body.description.as_deref().map(validate_description).transpose()?;
+    let rules = body.rules.map(parse_rules).transpose()?.unwrap_or_default();
+
+    if !dguesser_db::locations::is_map_slug_available(state.db(), &body.slug).await? {
+        return Err(ApiError::conflict(
+            "SLUG_TAKEN",
+            format!("A map with slug '{}' already exists", body.slug),
+        ));
+    }
+
+    let map = dguesser_db::locations::create_map(
+        state.db(),
+        &body.slug,
+        name,
+        description.filter(|d| !d.is_empty()),
+        &rules,
+        false,
+    )
+    .await?;
+
+    // Go through set_default_map so there is only ever one default
+    let map = if body.is_default {
+        dguesser_db::locations::set_default_map(state.db(), &map.id)
+            .await?
+            .ok_or_else(|| ApiError::not_found("Map"))?
+    } else {
+        map
+    };
+
+    tracing::info!(map_id = %map.id, slug = %map.slug, admin = %auth.user_id, "System map created");
+
+    Ok((StatusCode::CREATED, Json(system_map_item(map))))
+}
+
+/// Edit a system map's name, description, or rules.
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/maps/{map_id}",
+    tag = "admin",
+    params(
+        ("map_id" = String, Path, description = "Map ID")
+    ),
+    request_body = UpdateSystemMapRequest,
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Map updated", body = SystemMapItem),
+        (status = 400, description = "Invalid request"),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Map not found"),
+    )
+)]
+pub(super) async fn update_system_map(
+    State(state): State<AppState>,
+    RequireAdmin(auth): RequireAdmin,
+    Path(map_id): Path<String>,
+    Json(body): Json<UpdateSystemMapRequest>,
+) -> Result<Json<SystemMapItem>, ApiError> {
+    let name = body.name.as_deref().map(validate_name).transpose()?;
+    let description = body.description.as_deref().map(validate_description).transpose()?;
+    let rules = body.rules.map(parse_rules).transpose()?;
+
+    let map = dguesser_db::locations::update_system_map(
+        state.db(),
+        &map_id,
+        name,
+        description,
+        rules.as_ref(),
+    )
+    .await?
+    .ok_or_else(|| ApiError::not_found("Map"))?;
+
+    tracing::info!(map_id = %map.id, admin = %auth.user_id, "System map updated");
+
+    Ok(Json(system_map_item(map)))
+}
+
+/// Make a system map the default map.
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/maps/{map_id}/default",
+    tag = "admin",
+    params(
+        ("map_id" = String, Path, description = "Map ID")
+    ),
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Default map set", body = SystemMapItem),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Map not found"),
+        (status = 409, description = "Map is inactive"),
+    )
+)]
+pub(super) async fn set_default_map(
+    State(state): State<AppState>,
+    RequireAdmin(auth): RequireAdmin,
+    Path(map_id): Path<String>,
+) -> Result<Json<SystemMapItem>, ApiError> {
+    let map = dguesser_db::locations::get_system_map(state.db(), &map_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Map"))?;
+
+    if !map.active {
+        return Err(ApiError::conflict(
+            "MAP_INACTIVE",
+            "Activate the map before making it the default",
+        ));
+    }
+
+    let map = dguesser_db::locations::set_default_map(state.db(), &map_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Map"))?;
+
+    tracing::info!(map_id = %map.id, admin = %auth.user_id, "Default map changed");
+
+    Ok(Json(system_map_item(map)))
+}
+
+/// Activate or deactivate a system map.
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/maps/{map_id}/active",
+    tag = "admin",
+    params(
+        ("map_id" = String, Path, description = "Map ID")
+    ),
+    request_body = SetMapActiveRequest,
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Map updated", body = SystemMapItem),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Map not found"),
+        (status = 409, description = "The default map cannot be deactivated"),
+    )
+)]
+pub(super) async fn set_map_active(
+    State(state): State<AppState>,
+    RequireAdmin(auth): RequireAdmin,
+    Path(map_id): Path<String>,
+    Json(body): Json<SetMapActiveRequest>,
+) -> Result<Json<SystemMapItem>, ApiError> {
+    let map = dguesser_db::locations::get_system_map(state.db(), &map_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Map"))?;
+
+    if map.is_default && !body.active {
+        return Err(ApiError::conflict(
+            "DEFAULT_MAP",
+            "Make another map the default before deactivating this one",
+        ));
+    }
+
+    let map = dguesser_db::locations::set_system_map_active(state.db(), &map_id, body.active)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Map"))?;
+
+    tracing::info!(
+        map_id = %map.id,
+        active = %map.active,
+        admin = %auth.user_id,
+        "System map activation changed"
+    );
+
+    Ok(Json(system_map_item(map)))
+}
+
+/// List the featured map schedule.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/maps/featured",
+    tag = "admin",
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Featured map schedule", body = FeaturedMapScheduleResponse),
+        (status = 403, description = "Admin access required"),
+    )
+)]
+pub(super) async fn list_featured_maps(
+    State(state): State<AppState>,
+    RequireAdmin(_auth): RequireAdmin,
+) -> Result<Json<FeaturedMapScheduleResponse>, ApiError> {
+    let features = dguesser_db::featured_maps::list(state.db_read(), FEATURE_HISTORY_DAYS).await?;
+
+    Ok(Json(FeaturedMapScheduleResponse {
+        features: features.into_iter().map(scheduled_feature_item).collect(),
+    }))
+}
+
+/// Schedule a system map to be the default map for a period.
+///
+/// When the feature ends the map that was the default before it is restored,
+/// unless the default was changed by hand in the meantime.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/maps/featured",
+    tag = "admin",
+    request_body = ScheduleFeaturedMapRequest,
+    security(("session" = [])),
+    responses(
+        (status = 201, description = "Feature scheduled", body = ScheduledFeatureItem),
+        (status = 400, description = "Invalid schedule"),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Map not found"),
+        (status = 409, description = "Map is inactive or another feature overlaps"),
+    )
+)]
+pub(super) async fn schedule_featured_map(
+    State(state): State<AppState>,
+    RequireAdmin(auth): RequireAdmin,
+    Json(body): Json<ScheduleFeaturedMapRequest>,
+) -> Result<(StatusCode, Json<ScheduledFeatureItem>), ApiError> {
+    validate_feature_period(body.starts_at, body.ends_at, Utc::now())?;
+    let title = body.title.as_deref().map(str::trim).filter(|t| !t.is_empty());
+    if title.is_some_and(|t| t.chars().count() > 100) {
+        return Err(ApiError::bad_request(
+            "TITLE_TOO_LONG",
+            "Title must be at most 100 characters",
+        ));
+    }
+
+    let map = dguesser_db::locations::get_system_map(state.db(), &body.map_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Map"))?;
+    if !map.active {
+        return Err(ApiError::conflict("MAP_INACTIVE", "Activate the map before featuring it"));
+    }
+
+    if let Some(other) =
+        dguesser_db::featured_maps::find_overlapping(state.db(), body.starts_at, body.ends_at)
+            .await?
+    {
+        return Err(ApiError::conflict(
+            "SCHEDULE_CONFLICT",
+            format!("Overlaps the feature of '{}' starting {}", other.map_name, other.starts_at),
+        ));
+    }
+
+    let feature = dguesser_db::featured_maps::create(
+        state.db(),
+        &map.id,
+        title,
+        body.starts_at,
+        body.ends_at,
+        &auth.user_id,
+    )
+    .await?;
+
+    tracing::info!(
+        feature_id = feature.id,
+        map_id = %map.id,
+        starts_at = %feature.starts_at,
+        ends_at = %feature.ends_at,
+        admin = %auth.user_id,
+        "Featured map scheduled"
+    );
+
+    // Start it now if the period has already begun
+    if feature.starts_at <= Utc::now() {
+        apply_schedule(&state).await?;
+    }
+
+    Ok((StatusCode::CREATED, Json(scheduled_feature_item(feature))))
+}
+
+/// Cancel a feature.
+///
+/// A pending feature is removed; a running one ends immediately and the
+/// previous default map is restored.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/maps/featured/{feature_id}",
+    tag = "admin",
+    params(
+        ("feature_id" = i64, Path, description = "Feature ID")
+    ),
+    security(("session" = [])),
+    responses(
+        (status = 204, description = "Feature cancelled"),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Feature not found or already finished"),
+    )
+)]
+pub(super) async fn cancel_featured_map(
+    State(state): State<AppState>,
+    RequireAdmin(auth): RequireAdmin,
+    Path(feature_id): Path<i64>,
+) -> Result<StatusCode, ApiError> {
+    if !dguesser_db::featured_maps::cancel(state.db(), feature_id).await? {
+        return Err(ApiError::not_found("Feature"));
+    }
+    apply_schedule(&state).await?;
+
+    tracing::info!(feature_id, admin = %auth.user_id, "Featured map cancelled");
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn scheduled_feature_item(feature: FeaturedMap) -> ScheduledFeatureItem {
+    ScheduledFeatureItem {
+        id: feature.id,
+        map_id: feature.map_id,
+        map_slug: feature.map_slug,
+        map_name: feature.map_name,
+        title: feature.title,
+        starts_at: feature.starts_at,
+        ends_at: feature.ends_at,
+        created_by: feature.created_by,
+        activated_at: feature.activated_at,
+        finished_at: feature.finished_at,
+    }
+}
+
+/// Check a feature period ends after it starts, is in the future, and is not
+/// too long.
+fn validate_feature_period(
+    starts_at: DateTime<Utc>,
+    ends_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Result<(), ApiError> {
+    let invalid = |message: String| ApiError::bad_request("INVALID_SCHEDULE", message);
+    if ends_at <= starts_at {
+        return Err(invalid("The feature must end after it starts".to_string()));
+    }
+    if ends_at <= now {
+        return Err(invalid("The feature must end in the future".to_string()));
+    }
+    if ends_at - starts_at.max(now) > chrono::Duration::days(MAX_FEATURE_DAYS) {
+        return Err(invalid(format!("A map can be featured for at most {MAX_FEATURE_DAYS} days")));
+    }
+    Ok(())
+}
+
+fn system_map_item(map: Map) -> SystemMapItem {
+    SystemMapItem {
+        rules: serde_json::to_value(&map.rules).unwrap_or_default(),
+        id: map.id,
+        slug: map.slug,
+        name: map.name,
+        description: map.description,
+        is_default: map.is_default,
+        active: map.active,
+        location_count: map.location_count,
+        play_count: map.play_count,
+        created_at: map.created_at,
+        updated_at: map.updated_at,
+    }
+}
+
+/// Check a slug is lowercase letters, digits, and single hyphens.
+fn validate_slug(slug: &str) -> Result<(), ApiError> {
+    let valid = (1..=100).contains(&slug.len())
+        && slug.split('-').all(|part| {
+            !part.is_empty() && part.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        });
+    if valid {
+        Ok(())
+    } else {
+        Err(ApiError::bad_request(
+            "INVALID_SLUG",
+            "Slug must be lowercase letters, digits, and hyphens (max 100 characters)",
+        ))
+    }
+}
+
+fn validate_name(name: &str) -> Result<&str, ApiError> {
+    let name = name.trim();
+    if name.len() < 3 {
+        return Err(ApiError::bad_request(
+            "NAME_TOO_SHORT",
+            "Map name must be at least 3 characters",
+        ));
+    }
+    if name.len() > 100 {
+        return Err(ApiError::bad_request(
+            "NAME_TOO_LONG",
+            "Map name must be at most 100 characters",
+        ));
+    }
+    Ok(name)
+}
+
+fn validate_description(description: &str) -> Result<&str, ApiError> {
+    let description = description.trim();
+    if description.len() > 500 {
+        return Err(ApiError::bad_request(
+            "DESCRIPTION_TOO_LONG",
+            "Description must be at most 500 characters",
+        ));
+    }
+    Ok(description)
+}
+
+/// Parse and check map rules from a request body.
+fn parse_rules(value: serde_json::Value) -> Result<MapRules, ApiError> {
+    let invalid = |message: String| ApiError::bad_request("INVALID_RULES", message);
+
+    let mut rules: MapRules =
+        serde_json::from_value(value).map_err(|e| invalid(format!("Invalid map rules: {e}")))?;
+
+    // The region column is only maintained for user maps
+    if rules.region.is_some() {
+        return Err(invalid("System maps do not support regions".to_string()));
+    }
+    rules.validate().map_err(|e| invalid(e.to_string()))?;
+    rules.normalize();
+
+    Ok(rules)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_validate_slug() {
+        assert!(validate_slug("modern-europe").is_ok());
+        assert!(validate_slug("usa2024").is_ok());
+        assert!(validate_slug("").is_err());
+        assert!(validate_slug("Modern-Europe").is_err());
+        assert!(validate_slug("-europe").is_err());
+        assert!(validate_slug("modern--europe").is_err());
+        assert!(validate_slug("modern europe").is_err());
+    }
+
+    #[test]
+    fn test_validate_feature_period() {
+        let now = Utc::now();
+        let days = chrono::Duration::days;
+        assert!(validate_feature_period(now + days(1), now + days(8), now).is_ok());
+        // Already started: only the remaining time counts
+        assert!(validate_feature_period(now - days(10), now + days(30), now).is_ok());
+        assert!(validate_feature_period(now + days(8), now + days(1), now).is_err());
+        assert!(validate_feature_period(now - days(8), now - days(1), now).is_err());
+        assert!(validate_feature_period(now, now + days(MAX_FEATURE_DAYS + 1), now).is_err());
+    }
+
+    #[test]
+    fn test_parse_rules_normalizes_countries() {
+        let rules = parse_rules(json!({ "countries": ["fr", "De"], "min_year": 2018 })).unwrap();
+        assert_eq!(rules.countries, vec!["FR", "DE"]);
+        assert_eq!(rules.min_year, Some(2018));
+        assert!(!rules.outdoor_only);
+    }
+
+    #[test]
+    fn test_parse_rules_rejects_invalid_rules() {
+        assert!(parse_rules(json!({ "countries": ["FRA"] })).is_err());
+        assert!(parse_rules(json!({ "countries": ["XX"] })).is_err());
+        assert!(
+            parse_rules(json!({
+                "country_distribution": { "type": "weighted", "weights": { "QQ": 1 } }
+            }))
+            .is_err()
+        );
+        assert!(parse_rules(json!({ "min_year": 2020, "max_year": 2015 })).is_err());
+        assert!(parse_rules(json!({ "min_spread_distance_km": -1.0 })).is_err());
+        assert!(parse_rules(json!({ "scoring": { "max_points": 0 } })).is_err());
+        assert!(parse_rules(json!({ "outdoor_only": "yes" })).is_err());
+        assert!(
+            parse_rules(json!({
+                "region": { "type": "Polygon", "coordinates": [[[0, 0], [1, 0], [1, 1], [0, 0]]] }
+            }))
+            .is_err()
+        );
+    }
+}
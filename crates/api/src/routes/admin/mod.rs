@@ -1,15 +1,34 @@
-//! Admin API routes for managing flagged locations.
+//! Admin API routes for managing flagged locations and their notes, player
+//! reports, system maps and organizations, for game analytics, for user support, display-name
+//! and chat moderation, for inspecting running games, and for reloading operational settings.
+
+pub mod analytics;
+pub mod chat;
+pub mod config;
+pub mod live_games;
+pub mod location_notes;
+pub mod maps;
+pub mod names;
+pub mod orgs;
+pub mod player_reports;
+pub mod reapprovals;
+pub mod review_claims;
+pub mod support;
 
 use axum::{
     Json, Router,
     extract::{Path, Query, State},
-    routing::{get, put},
+    routing::{delete, get, post, put},
 };
 use dguesser_auth::RequireAdmin;
+use dguesser_core::streetview::QuotaState;
+use dguesser_db::locations::QueueAssignment;
 use dguesser_protocol::api::admin::{
-    AdminStatsResponse, LocationDetailResponse, LocationReportItem, LocationReportWithLocation,
-    ReportsListResponse, ReviewQueueItem, ReviewQueueResponse, UpdateReviewStatusRequest,
-    UpdateReviewStatusResponse,
+    AdminStatsResponse, HealthCheckRunItem, HealthTrendPoint, LocationDetailResponse,
+    LocationHealthParams, LocationHealthResponse, LocationReportItem, LocationReportWithLocation,
+    MapTileUsageEntry, MapTileUsageResponse, ModerateProfileRequest, ModerateProfileResponse,
+    PackCacheStatsResponse, ReportsListResponse, ReviewQueueItem, ReviewQueueResponse,
+    StreetViewQuotaResponse, UpdateReviewStatusRequest, UpdateReviewStatusResponse,
 };
 use serde::Deserialize;
 use utoipa::ToSchema;
@@ -21,10 +40,47 @@ use crate::state::AppState;
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/stats", get(get_stats))
+        .route("/locations/health", get(get_location_health))
+        .route("/locations/cache", get(get_pack_cache_stats))
+        .route("/streetview/quota", get(get_streetview_quota))
+        .route("/streetview/usage", get(get_map_tile_usage))
         .route("/locations/review-queue", get(get_review_queue))
+        .route("/locations/review-queue/claim", post(review_claims::claim_review_batch))
+        .route("/locations/review-queue/claims", delete(review_claims::release_review_claims))
+        .route("/locations/review-stats", get(review_claims::get_reviewer_stats))
+        .route("/locations/reapprovals", get(reapprovals::list_reapprovals))
+        .route("/locations/reapprovals/{location_id}", post(reapprovals::decide_reapproval))
         .route("/locations/{location_id}", get(get_location_detail))
         .route("/locations/{location_id}/review", put(update_review_status))
+        .route("/locations/{location_id}/notes", post(location_notes::create_location_note))
+        .route("/location-notes", get(location_notes::search_location_notes))
+        .route("/location-notes/{note_id}", delete(location_notes::delete_location_note))
         .route("/reports", get(get_reports))
+        .route("/player-reports", get(player_reports::list_player_reports))
+        .route("/player-reports/{report_id}/status", put(player_reports::update_player_report))
+        .route("/games/live", get(live_games::list_live_games))
+        .route("/games/{game_id}/state", get(live_games::get_live_game_state))
+        .route("/games/{game_id}/end", post(live_games::force_end_game))
+        .route("/games/{game_id}/chat", get(chat::get_game_chat))
+        .route("/users/{user_id}/profile/moderate", post(moderate_profile))
+        .route("/users/{user_id}/rename", post(names::force_rename))
+        .route("/names/flags", get(names::list_name_flags))
+        .route("/names/flags/{flag_id}/dismiss", post(names::dismiss_name_flag))
+        .route("/users/{user_id}/impersonate", post(support::start_impersonation))
+        .route("/audit-log", get(support::get_audit_log))
+        .route("/reload", post(config::reload_config))
+        .route("/maps", get(maps::list_system_maps).post(maps::create_system_map))
+        .route("/maps/featured", get(maps::list_featured_maps).post(maps::schedule_featured_map))
+        .route("/maps/featured/{feature_id}", delete(maps::cancel_featured_map))
+        .route("/maps/{map_id}", put(maps::update_system_map))
+        .route("/maps/{map_id}/default", put(maps::set_default_map))
+        .route("/maps/{map_id}/active", put(maps::set_map_active))
+        .route("/orgs", get(orgs::list_orgs).post(orgs::create_org))
+        .route("/orgs/{org_id}/active", put(orgs::set_org_active))
+        .route("/analytics/activity", get(analytics::get_activity))
+        .route("/analytics/guesses", get(analytics::get_guess_distribution))
+        .route("/analytics/retention", get(analytics::get_retention))
+        .route("/analytics/continents", get(analytics::get_continents))
 }
 
 /// Get admin dashboard statistics.
@@ -42,7 +98,7 @@ async fn get_stats(
     State(state): State<AppState>,
     RequireAdmin(_auth): RequireAdmin,
 ) -> Result<Json<AdminStatsResponse>, ApiError> {
-    let stats = dguesser_db::locations::get_location_stats(state.db()).await?;
+    let stats = dguesser_db::locations::get_location_stats(state.db_read()).await?;
 
     Ok(Json(AdminStatsResponse {
         total_locations: stats.total_locations,
@@ -55,6 +111,177 @@ async fn get_stats(
     }))
 }
 
+/// Get location coverage health trends from the background health checker.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/locations/health",
+    tag = "admin",
+    params(
+        ("days" = Option<i32>, Query, description = "Days of history (default 30, max 90)")
+    ),
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Location health trends", body = LocationHealthResponse),
+        (status = 403, description = "Admin access required"),
+    )
+)]
+async fn get_location_health(
+    State(state): State<AppState>,
+    RequireAdmin(_auth): RequireAdmin,
+    Query(params): Query<LocationHealthParams>,
+) -> Result<Json<LocationHealthResponse>, ApiError> {
+    let days = params.days.clamp(1, 90);
+
+    let last_run = dguesser_db::location_health::latest_run(state.db_read()).await?;
+    let daily = dguesser_db::location_health::daily_totals(state.db_read(), days).await?;
+
+    Ok(Json(LocationHealthResponse {
+        last_run: last_run.map(|run| HealthCheckRunItem {
+            id: run.id,
+            started_at: run.started_at,
+            finished_at: run.finished_at,
+            checked: run.checked,
+            healthy: run.healthy,
+            dead: run.dead,
+            errors: run.errors,
+            revived: run.revived,
+        }),
+        daily: daily
+            .into_iter()
+            .map(|day| HealthTrendPoint {
+                date: day.day,
+                runs: day.runs,
+                checked: day.checked,
+                healthy: day.healthy,
+                dead: day.dead,
+                errors: day.errors,
+                revived: day.revived,
+            })
+            .collect(),
+    }))
+}
+
+/// Get pack range cache counters for this API instance.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/locations/cache",
+    tag = "admin",
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Pack cache statistics", body = PackCacheStatsResponse),
+        (status = 403, description = "Admin access required"),
+    )
+)]
+async fn get_pack_cache_stats(
+    State(state): State<AppState>,
+    RequireAdmin(_auth): RequireAdmin,
+) -> Json<PackCacheStatsResponse> {
+    let Some(cache) = state.pack_cache() else {
+        return Json(PackCacheStatsResponse::default());
+    };
+
+    let stats = cache.stats();
+    let served = stats.block_hits + stats.redis_hits;
+    let reads = stats.block_hits + stats.block_misses;
+    Json(PackCacheStatsResponse {
+        enabled: true,
+        block_hits: stats.block_hits,
+        block_misses: stats.block_misses,
+        redis_hits: stats.redis_hits,
+        index_hits: stats.index_hits,
+        index_misses: stats.index_misses,
+        hit_rate: if reads == 0 { 0.0 } else { served as f64 / reads as f64 },
+        blocks: stats.blocks,
+        bytes: stats.bytes,
+    })
+}
+
+/// Get the Street View metadata API quota status.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/streetview/quota",
+    tag = "admin",
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Street View quota status", body = StreetViewQuotaResponse),
+        (status = 403, description = "Admin access required"),
+    )
+)]
+async fn get_streetview_quota(
+    State(state): State<AppState>,
+    RequireAdmin(_auth): RequireAdmin,
+) -> Json<StreetViewQuotaResponse> {
+    let client = state.street_view();
+    let quota = client.quota().await;
+    let now = chrono::Utc::now();
+    let (state_name, paused_until) = match quota.state(now) {
+        QuotaState::Closed => ("closed", None),
+        QuotaState::Open { until } => ("open", Some(until)),
+        QuotaState::HalfOpen => ("half_open", None),
+    };
+
+    Json(StreetViewQuotaResponse {
+        configured: client.can_validate(),
+        state: state_name.to_string(),
+        paused_until,
+        consecutive_trips: quota.consecutive_trips,
+        last_tripped_at: quota.last_tripped_at,
+        requests_today: client.requests_on(now.date_naive()).await,
+    })
+}
+
+/// Query parameters for map tile usage
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct MapTileUsageQuery {
+    /// Day to report (UTC, default today)
+    pub date: Option<chrono::NaiveDate>,
+}
+
+/// Get map tiles served per user on a day, for billing attribution.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/streetview/usage",
+    tag = "admin",
+    params(
+        ("date" = Option<String>, Query, description = "Day to report (YYYY-MM-DD, default today)")
+    ),
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Map tile usage", body = MapTileUsageResponse),
+        (status = 403, description = "Admin access required"),
+    )
+)]
+async fn get_map_tile_usage(
+    State(state): State<AppState>,
+    RequireAdmin(_auth): RequireAdmin,
+    Query(query): Query<MapTileUsageQuery>,
+) -> Result<Json<MapTileUsageResponse>, ApiError> {
+    let date = query.date.unwrap_or_else(|| chrono::Utc::now().date_naive());
+    let usage = state.map_tiles().usage_on(date).await.map_err(|e| {
+        ApiError::service_unavailable("Map tile usage is unavailable").with_internal(e.to_string())
+    })?;
+    let users =
+        dguesser_db::UserLoader::load(state.db_read(), usage.iter().map(|u| u.user_id.as_str()))
+            .await?;
+
+    Ok(Json(MapTileUsageResponse {
+        date,
+        total_tiles: usage.iter().map(|u| u.tiles).sum(),
+        users: usage
+            .into_iter()
+            .map(|u| MapTileUsageEntry {
+                display_name: users
+                    .get(&u.user_id)
+                    .map(|user| user.display_name.clone())
+                    .unwrap_or_else(|| "Unknown".to_string()),
+                user_id: u.user_id,
+                map_type: u.map_type.as_str().to_string(),
+                tiles: u.tiles,
+            })
+            .collect(),
+    }))
+}
+
 /// Query parameters for review queue
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct ReviewQueueQuery {
@@ -66,6 +293,8 @@ pub struct ReviewQueueQuery {
     pub per_page: i64,
     /// Filter by status (pending, flagged, or all)
     pub status: Option<String>,
+    /// Filter by claim: mine, unclaimed, or all
+    pub assignment: Option<String>,
 }
 
 fn default_page() -> i64 {
@@ -84,7 +313,8 @@ fn default_per_page() -> i64 {
     params(
         ("page" = Option<i64>, Query, description = "Page number (1-indexed)"),
         ("per_page" = Option<i64>, Query, description = "Items per page"),
-        ("status" = Option<String>, Query, description = "Filter by status: pending, flagged, or all")
+        ("status" = Option<String>, Query, description = "Filter by status: pending, flagged, or all"),
+        ("assignment" = Option<String>, Query, description = "Filter by claim: mine, unclaimed, or all")
     ),
     security(("session" = [])),
     responses(
@@ -94,28 +324,47 @@ fn default_per_page() -> i64 {
 )]
 async fn get_review_queue(
     State(state): State<AppState>,
-    RequireAdmin(_auth): RequireAdmin,
+    RequireAdmin(auth): RequireAdmin,
     Query(params): Query<ReviewQueueQuery>,
 ) -> Result<Json<ReviewQueueResponse>, ApiError> {
     let page = params.page.max(1);
     let per_page = params.per_page.clamp(1, 100);
     let status_filter = params.status.as_deref();
+    let assignment = match params.assignment.as_deref() {
+        Some("mine") => QueueAssignment::ClaimedBy(&auth.user_id),
+        Some("unclaimed") => QueueAssignment::Unclaimed,
+        _ => QueueAssignment::Any,
+    };
 
+    // Claims are read from the primary so a fresh claim shows up at once
     let (locations, total) = dguesser_db::locations::get_review_queue_paginated(
         state.db(),
         page,
         per_page,
         status_filter,
+        assignment,
     )
     .await?;
 
+    let location_ids: Vec<String> = locations.iter().map(|l| l.id.clone()).collect();
+    let mut notes =
+        dguesser_db::location_notes::list_for_locations(state.db_read(), &location_ids).await?;
+    let mut claims =
+        dguesser_db::review_claims::active_for_locations(state.db(), &location_ids).await?;
+
     // Get report counts for each location
     let mut items = Vec::with_capacity(locations.len());
     for loc in locations {
         let report_count =
-            dguesser_db::locations::get_report_count_for_location(state.db(), &loc.id)
+            dguesser_db::locations::get_report_count_for_location(state.db_read(), &loc.id)
                 .await
                 .unwrap_or(0);
+        let loc_notes = notes
+            .extract_if(.., |n| n.location_id == loc.id)
+            .map(location_notes::note_item)
+            .collect();
+        let claim =
+            claims.iter().position(|c| c.location_id == loc.id).map(|i| claims.swap_remove(i));
 
         items.push(ReviewQueueItem {
             id: loc.id,
@@ -128,6 +377,10 @@ async fn get_review_queue(
             last_report_reason: loc.last_failure_reason,
             review_status: loc.review_status.to_string(),
             created_at: loc.created_at,
+            notes: loc_notes,
+            claimed_by: claim.as_ref().map(|c| c.reviewer_id.clone()),
+            claim_expires_at: claim.as_ref().map(|c| c.expires_at),
+            claimed_by_name: claim.and_then(|c| c.reviewer_name),
         });
     }
 
@@ -175,6 +428,8 @@ async fn get_location_detail(
         })
         .collect();
 
+    let notes = dguesser_db::location_notes::list_for_location(state.db(), &location_id).await?;
+
     Ok(Json(LocationDetailResponse {
         id: location.id,
         panorama_id: location.panorama_id,
@@ -183,7 +438,7 @@ async fn get_location_detail(
         country_code: location.country_code,
         subdivision_code: location.subdivision_code,
         capture_date: location.capture_date,
-        provider: location.provider,
+        provider: location.provider.to_string(),
         active: location.active,
         validation_status: location.validation_status.to_string(),
         source: location.source.to_string(),
@@ -194,6 +449,9 @@ async fn get_location_detail(
         roads_100: location.roads_100,
         elevation: location.elevation,
         heading: location.heading,
+        difficulty: location.difficulty,
+        median_guess_distance_meters: location.median_guess_distance_meters,
+        median_guess_score: location.median_guess_score,
         failure_count: location.failure_count,
         last_failure_reason: location.last_failure_reason,
         review_status: location.review_status.to_string(),
@@ -201,6 +459,7 @@ async fn get_location_detail(
         reviewed_by: location.reviewed_by,
         created_at: location.created_at,
         reports: report_items,
+        notes: notes.into_iter().map(location_notes::note_item).collect(),
     }))
 }
 
@@ -245,6 +504,20 @@ async fn update_review_status(
         .await?
         .ok_or_else(|| ApiError::not_found("Location"))?;
 
+    // Don't step on another reviewer's claimed work
+    if let Some(claim) = dguesser_db::review_claims::get_active(state.db(), &location_id).await?
+        && claim.reviewer_id != auth.user_id
+    {
+        return Err(ApiError::conflict(
+            "CLAIMED_BY_OTHER",
+            format!(
+                "Location is claimed by {} until {}",
+                claim.reviewer_name.as_deref().unwrap_or("another reviewer"),
+                claim.expires_at.format("%H:%M UTC")
+            ),
+        ));
+    }
+
     // Update the review status
     dguesser_db::locations::update_location_review_status(
         state.db(),
@@ -253,6 +526,14 @@ async fn update_review_status(
         Some(&auth.user_id),
     )
     .await?;
+    dguesser_db::review_claims::record_decision(
+        state.db(),
+        &location_id,
+        &auth.user_id,
+        &body.status,
+        body.notes.as_deref(),
+    )
+    .await?;
 
     // Determine if location is now active (rejected = inactive)
     let active = body.status != "rejected" && location.active;
@@ -351,3 +632,59 @@ async fn get_reports(
 
     Ok(Json(ReportsListResponse { reports: items, total, page, per_page, total_pages }))
 }
+
+/// Reset parts of a user's profile and lock or unlock it against edits.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/users/{user_id}/profile/moderate",
+    tag = "admin",
+    params(
+        ("user_id" = String, Path, description = "User ID")
+    ),
+    request_body = ModerateProfileRequest,
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Profile moderated", body = ModerateProfileResponse),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "User not found"),
+    )
+)]
+async fn moderate_profile(
+    State(state): State<AppState>,
+    RequireAdmin(auth): RequireAdmin,
+    Path(user_id): Path<String>,
+    Json(req): Json<ModerateProfileRequest>,
+) -> Result<Json<ModerateProfileResponse>, ApiError> {
+    let reset = dguesser_db::profiles::ProfileReset {
+        avatar: req.reset_avatar,
+        bio: req.reset_bio,
+        display_name: req.reset_display_name,
+        country: req.reset_country,
+        color: req.reset_color,
+    };
+    let previous =
+        dguesser_db::profiles::moderate(state.db(), &user_id, &auth.user_id, reset, req.locked)
+            .await?
+            .ok_or_else(|| ApiError::not_found("User"))?;
+
+    if req.reset_avatar
+        && let Some(key) = previous.avatar_key.as_deref()
+    {
+        crate::routes::users::delete_uploaded_avatar(&state, key).await;
+    }
+    if req.reset_avatar || req.reset_display_name {
+        // Leaderboards show names and avatars
+        crate::cache::LeaderboardCache::invalidate_all(state.redis()).await;
+    }
+
+    let locked = req.locked.unwrap_or(previous.locked);
+    tracing::info!(
+        user_id = %user_id,
+        moderator_id = %auth.user_id,
+        ?reset,
+        locked,
+        "Profile moderated"
+    );
+
+    Ok(Json(ModerateProfileResponse { message: "Profile moderated".to_string(), user_id, locked }))
+}
@@ -0,0 +1,162 @@
+//! Admin API routes for display-name moderation: reviewing names flagged
+//! when they were set, and renaming users.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use dguesser_auth::RequireAdmin;
+use dguesser_db::name_flags::NameFlag;
+use dguesser_protocol::api::admin::{
+    ForceRenameRequest, ForceRenameResponse, NameFlagItem, NameFlagsParams, NameFlagsResponse,
+};
+
+use crate::cache::LeaderboardCache;
+use crate::error::ApiError;
+use crate::routes::users::validate_display_name;
+use crate::state::AppState;
+
+/// Audit log action for a forced rename
+const ACTION_RENAME: &str = "user.rename";
+
+fn flag_item(flag: NameFlag) -> NameFlagItem {
+    NameFlagItem {
+        id: flag.id,
+        user_id: flag.user_id,
+        display_name: flag.display_name,
+        current_display_name: flag.current_display_name,
+        reason: flag.reason,
+        status: flag.status,
+        created_at: flag.created_at,
+    }
+}
+
+/// List display names waiting for review.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/names/flags",
+    tag = "admin",
+    params(
+        ("page" = Option<i64>, Query, description = "Page number (1-indexed)"),
+        ("per_page" = Option<i64>, Query, description = "Items per page (max 100)")
+    ),
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Pending name flags", body = NameFlagsResponse),
+        (status = 403, description = "Admin access required"),
+    )
+)]
+pub(super) async fn list_name_flags(
+    State(state): State<AppState>,
+    RequireAdmin(_auth): RequireAdmin,
+    Query(params): Query<NameFlagsParams>,
+) -> Result<Json<NameFlagsResponse>, ApiError> {
+    let page = params.page.max(1);
+    let per_page = params.per_page.clamp(1, 100);
+
+    let (flags, total) =
+        dguesser_db::name_flags::list_pending(state.db(), per_page, (page - 1) * per_page).await?;
+
+    Ok(Json(NameFlagsResponse {
+        flags: flags.into_iter().map(flag_item).collect(),
+        total,
+        page,
+        per_page,
+        total_pages: (total + per_page - 1) / per_page,
+    }))
+}
+
+/// Dismiss a flag, keeping the user's name.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/names/flags/{flag_id}/dismiss",
+    tag = "admin",
+    params(
+        ("flag_id" = String, Path, description = "Flag ID")
+    ),
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Flag dismissed", body = NameFlagItem),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "No pending flag with this ID"),
+    )
+)]
+pub(super) async fn dismiss_name_flag(
+    State(state): State<AppState>,
+    RequireAdmin(auth): RequireAdmin,
+    Path(flag_id): Path<String>,
+) -> Result<Json<NameFlagItem>, ApiError> {
+    let flag = dguesser_db::name_flags::dismiss(state.db(), &flag_id, &auth.user_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Name flag"))?;
+
+    tracing::info!(flag_id = %flag_id, moderator_id = %auth.user_id, "Name flag dismissed");
+    Ok(Json(flag_item(flag)))
+}
+
+/// Rename a user, resolving any pending flag on their name.
+///
+/// Without a name the user gets a generic one. The rename is recorded in
+/// the audit log.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/users/{user_id}/rename",
+    tag = "admin",
+    params(
+        ("user_id" = String, Path, description = "User ID")
+    ),
+    request_body = ForceRenameRequest,
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "User renamed", body = ForceRenameResponse),
+        (status = 400, description = "Invalid display name"),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "User not found"),
+    )
+)]
+pub(super) async fn force_rename(
+    State(state): State<AppState>,
+    RequireAdmin(auth): RequireAdmin,
+    Path(user_id): Path<String>,
+    Json(req): Json<ForceRenameRequest>,
+) -> Result<Json<ForceRenameResponse>, ApiError> {
+    let new_name = req.display_name.as_deref().map(validate_display_name).transpose()?;
+
+    let previous = dguesser_db::users::get_by_id(state.db(), &user_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("User"))?;
+    let (display_name, locked) = dguesser_db::profiles::force_rename(
+        state.db(),
+        &user_id,
+        &auth.user_id,
+        new_name.as_ref().map(|checked| checked.name.as_str()),
+        req.locked,
+    )
+    .await?
+    .ok_or_else(|| ApiError::not_found("User"))?;
+
+    let mut conn = state.db().acquire().await?;
+    dguesser_db::audit_log::record(
+        &mut conn,
+        &auth.user_id,
+        ACTION_RENAME,
+        Some(&user_id),
+        serde_json::json!({
+            "previous": previous.display_name,
+            "display_name": display_name,
+            "locked": locked,
+        }),
+    )
+    .await?;
+
+    // Leaderboards show names
+    LeaderboardCache::invalidate_all(state.redis()).await;
+
+    tracing::info!(
+        user_id = %user_id,
+        moderator_id = %auth.user_id,
+        locked,
+        "User renamed by moderator"
+    );
+    Ok(Json(ForceRenameResponse { user_id, display_name, locked }))
+}
@@ -0,0 +1,188 @@
+//! Admin API routes for creating and suspending organizations.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use dguesser_auth::RequireAdmin;
+use dguesser_db::organizations::OrgRole;
+use dguesser_protocol::api::orgs::{
+    CreateOrganizationRequest, OrganizationInfo, OrganizationsListResponse,
+    SetOrganizationActiveRequest,
+};
+
+use crate::error::ApiError;
+use crate::middleware::org::RESERVED_SLUGS;
+use crate::routes::orgs::{organization_info, validate_org_name};
+use crate::state::AppState;
+
+/// List all organizations, inactive ones included.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/orgs",
+    tag = "admin",
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Organizations", body = OrganizationsListResponse),
+        (status = 403, description = "Admin access required"),
+    )
+)]
+pub(super) async fn list_orgs(
+    State(state): State<AppState>,
+    RequireAdmin(_auth): RequireAdmin,
+) -> Result<Json<OrganizationsListResponse>, ApiError> {
+    let orgs = dguesser_db::organizations::list(state.db_read()).await?;
+
+    Ok(Json(OrganizationsListResponse {
+        organizations: orgs.iter().map(organization_info).collect(),
+    }))
+}
+
+/// Create an organization, optionally with its first admin.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/orgs",
+    tag = "admin",
+    request_body = CreateOrganizationRequest,
+    security(("session" = [])),
+    responses(
+        (status = 201, description = "Organization created", body = OrganizationInfo),
+        (status = 400, description = "Invalid request"),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Admin user not found"),
+        (status = 409, description = "Slug or domain taken"),
+    )
+)]
+pub(super) async fn create_org(
+    State(state): State<AppState>,
+    RequireAdmin(auth): RequireAdmin,
+    Json(body): Json<CreateOrganizationRequest>,
+) -> Result<(StatusCode, Json<OrganizationInfo>), ApiError> {
+    validate_slug(&body.slug)?;
+    let name = validate_org_name(&body.name)?;
+    let domain = body.domain.as_deref().map(normalize_domain).transpose()?;
+
+    if let Some(admin_id) = &body.admin_user_id
+        && dguesser_db::users::get_by_id(state.db(), admin_id).await?.is_none()
+    {
+        return Err(ApiError::not_found("User"));
+    }
+    if dguesser_db::organizations::is_taken(state.db(), &body.slug, domain.as_deref()).await? {
+        return Err(ApiError::conflict(
+            "ORG_TAKEN",
+            "An organization with this slug or domain already exists",
+        ));
+    }
+
+    let org =
+        dguesser_db::organizations::create(state.db(), &body.slug, name, domain.as_deref()).await?;
+    if let Some(admin_id) = &body.admin_user_id {
+        dguesser_db::organizations::upsert_member(state.db(), &org.id, admin_id, OrgRole::Admin)
+            .await?;
+    }
+    // Forget hosts that resolved to no organization before
+    state.orgs().invalidate();
+
+    tracing::info!(org_id = %org.id, slug = %org.slug, admin = %auth.user_id, "Organization created");
+
+    Ok((StatusCode::CREATED, Json(organization_info(&org))))
+}
+
+/// Activate or deactivate an organization. Requests for an inactive
+/// organization are served as the public instance, or rejected when they
+/// name it in the header.
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/orgs/{org_id}/active",
+    tag = "admin",
+    params(
+        ("org_id" = String, Path, description = "Organization ID")
+    ),
+    request_body = SetOrganizationActiveRequest,
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Organization updated", body = OrganizationInfo),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Organization not found"),
+    )
+)]
+pub(super) async fn set_org_active(
+    State(state): State<AppState>,
+    RequireAdmin(auth): RequireAdmin,
+    Path(org_id): Path<String>,
+    Json(body): Json<SetOrganizationActiveRequest>,
+) -> Result<Json<OrganizationInfo>, ApiError> {
+    let org = dguesser_db::organizations::set_active(state.db(), &org_id, body.active)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Organization"))?;
+    state.orgs().invalidate();
+
+    tracing::info!(
+        org_id = %org.id,
+        active = %org.active,
+        admin = %auth.user_id,
+        "Organization activation changed"
+    );
+
+    Ok(Json(organization_info(&org)))
+}
+
+fn validate_slug(slug: &str) -> Result<(), ApiError> {
+    let valid = (1..=50).contains(&slug.len())
+        && slug.split('-').all(|part| {
+            !part.is_empty() && part.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        });
+    if !valid {
+        return Err(ApiError::bad_request(
+            "INVALID_SLUG",
+            "Slug must be lowercase letters, digits, and hyphens (max 50 characters)",
+        ));
+    }
+    if RESERVED_SLUGS.contains(&slug) {
+        return Err(ApiError::bad_request("RESERVED_SLUG", format!("'{slug}' is reserved")));
+    }
+    Ok(())
+}
+
+/// Lowercase a custom domain and check it looks like a hostname
+fn normalize_domain(domain: &str) -> Result<String, ApiError> {
+    let domain = domain.trim().trim_end_matches('.').to_ascii_lowercase();
+    let valid = domain.len() <= 253
+        && domain.contains('.')
+        && domain.split('.').all(|label| {
+            (1..=63).contains(&label.len())
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+    if valid {
+        Ok(domain)
+    } else {
+        Err(ApiError::bad_request(
+            "INVALID_DOMAIN",
+            "Domain must be a hostname like geo.example.edu",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_slug() {
+        assert!(validate_slug("geo-101").is_ok());
+        assert!(validate_slug("Geo").is_err());
+        assert!(validate_slug("-geo").is_err());
+        assert!(validate_slug("api").is_err());
+    }
+
+    #[test]
+    fn test_normalize_domain() {
+        assert_eq!(normalize_domain(" Geo.Example.EDU. ").unwrap(), "geo.example.edu");
+        assert!(normalize_domain("localhost").is_err());
+        assert!(normalize_domain("https://geo.example.edu").is_err());
+        assert!(normalize_domain("-a.example.edu").is_err());
+    }
+}
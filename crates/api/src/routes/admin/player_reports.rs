@@ -0,0 +1,198 @@
+//! Admin API routes for the player report queue.
+//!
+//! Each report shows the reported player's other signals (open reports,
+//! cheat signals, a flagged name) so repeat offenders stand out. Status
+//! changes follow `dguesser_core::reports::ReportStatus` and are recorded in
+//! the audit log.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use dguesser_auth::RequireAdmin;
+use dguesser_core::reports::{MAX_REPORT_NOTE_CHARS, ReportCategory, ReportStatus};
+use dguesser_db::player_reports::{PlayerReportRow, ReportFilter};
+use dguesser_protocol::api::admin::{
+    PlayerReportItem, PlayerReportsParams, PlayerReportsResponse, ReportedPlayerSignals,
+    UpdatePlayerReportRequest, UpdatePlayerReportResponse,
+};
+
+use crate::error::ApiError;
+use crate::state::AppState;
+
+/// Audit log action for a report status change
+const ACTION_REPORT_STATUS: &str = "player_report.status";
+
+fn report_item(row: PlayerReportRow) -> PlayerReportItem {
+    let report = row.report;
+    PlayerReportItem {
+        id: report.id,
+        reporter_id: report.reporter_id,
+        reporter_name: row.reporter_name,
+        reported_user_id: report.reported_user_id,
+        reported_name: row.reported_name,
+        category: report.category,
+        game_id: report.game_id,
+        chat_excerpt: report.chat_excerpt,
+        notes: report.notes,
+        status: report.status,
+        resolution_note: report.resolution_note,
+        reviewed_by: report.reviewed_by,
+        reviewed_at: report.reviewed_at,
+        created_at: report.created_at,
+        signals: ReportedPlayerSignals {
+            open_reports: row.open_reports,
+            total_reports: row.total_reports,
+            cheat_signals: row.cheat_signals,
+            name_flagged: row.name_flagged,
+        },
+    }
+}
+
+/// List player reports, oldest first.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/player-reports",
+    tag = "admin",
+    params(
+        ("status" = Option<String>, Query, description = "Filter by status"),
+        ("category" = Option<String>, Query, description = "Filter by category"),
+        ("user_id" = Option<String>, Query, description = "Only reports about this player"),
+        ("page" = Option<i64>, Query, description = "Page number (1-indexed)"),
+        ("per_page" = Option<i64>, Query, description = "Items per page (max 100)")
+    ),
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Player reports", body = PlayerReportsResponse),
+        (status = 400, description = "Invalid filter"),
+        (status = 403, description = "Admin access required"),
+    )
+)]
+pub(super) async fn list_player_reports(
+    State(state): State<AppState>,
+    RequireAdmin(_auth): RequireAdmin,
+    Query(params): Query<PlayerReportsParams>,
+) -> Result<Json<PlayerReportsResponse>, ApiError> {
+    let status = params.status.as_deref().map(parse_status).transpose()?;
+    let category = params
+        .category
+        .as_deref()
+        .map(|c| {
+            c.parse::<ReportCategory>()
+                .map_err(|message| ApiError::bad_request("INVALID_CATEGORY", message))
+        })
+        .transpose()?;
+    let page = params.page.max(1);
+    let per_page = params.per_page.clamp(1, 100);
+
+    let filter = ReportFilter {
+        status: status.map(ReportStatus::as_str),
+        category: category.map(ReportCategory::as_str),
+        reported_user_id: params.user_id.as_deref(),
+    };
+    let (rows, total) =
+        dguesser_db::player_reports::list(state.db(), filter, per_page, (page - 1) * per_page)
+            .await?;
+
+    Ok(Json(PlayerReportsResponse {
+        reports: rows.into_iter().map(report_item).collect(),
+        total,
+        page,
+        per_page,
+        total_pages: (total + per_page - 1) / per_page,
+    }))
+}
+
+/// Move a player report through the workflow.
+///
+/// Open reports can be taken into review, and open or reviewing reports can
+/// be actioned or dismissed. Closed reports can be reopened.
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/player-reports/{report_id}/status",
+    tag = "admin",
+    params(
+        ("report_id" = String, Path, description = "Report ID")
+    ),
+    request_body = UpdatePlayerReportRequest,
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Status changed", body = UpdatePlayerReportResponse),
+        (status = 400, description = "Invalid status or note"),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Report not found"),
+        (status = 409, description = "Transition not allowed or report changed meanwhile"),
+    )
+)]
+pub(super) async fn update_player_report(
+    State(state): State<AppState>,
+    RequireAdmin(auth): RequireAdmin,
+    Path(report_id): Path<String>,
+    Json(req): Json<UpdatePlayerReportRequest>,
+) -> Result<Json<UpdatePlayerReportResponse>, ApiError> {
+    let next = parse_status(&req.status)?;
+    let note = req.resolution_note.as_deref().map(str::trim).filter(|n| !n.is_empty());
+    if note.is_some_and(|n| n.chars().count() > MAX_REPORT_NOTE_CHARS) {
+        return Err(ApiError::bad_request(
+            "INVALID_NOTE",
+            format!("Resolution note must be at most {MAX_REPORT_NOTE_CHARS} characters"),
+        ));
+    }
+
+    let report = dguesser_db::player_reports::get(state.db(), &report_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Report"))?;
+    let current: ReportStatus =
+        report.status.parse().map_err(|e: String| ApiError::internal().with_internal(e))?;
+    if !current.can_transition_to(next) {
+        return Err(ApiError::conflict(
+            "INVALID_TRANSITION",
+            format!("A {current} report can't be moved to {next}"),
+        ));
+    }
+
+    let updated = dguesser_db::player_reports::update_status(
+        state.db(),
+        &report_id,
+        current.as_str(),
+        next.as_str(),
+        note,
+        &auth.user_id,
+    )
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => ApiError::conflict(
+            "ALREADY_OPEN",
+            "The reporter already has an open report about this player for this",
+        ),
+        e => e.into(),
+    })?
+    .ok_or_else(|| {
+        ApiError::conflict("REPORT_CHANGED", "The report was updated meanwhile, reload it")
+    })?;
+
+    let mut conn = state.db().acquire().await?;
+    dguesser_db::audit_log::record(
+        &mut conn,
+        &auth.user_id,
+        ACTION_REPORT_STATUS,
+        Some(&updated.reported_user_id),
+        serde_json::json!({
+            "report_id": updated.id,
+            "from": current.as_str(),
+            "to": next.as_str(),
+            "note": note,
+        }),
+    )
+    .await?;
+
+    Ok(Json(UpdatePlayerReportResponse {
+        id: updated.id,
+        status: updated.status,
+        reviewed_at: updated.reviewed_at,
+    }))
+}
+
+fn parse_status(status: &str) -> Result<ReportStatus, ApiError> {
+    status.parse().map_err(|message: String| ApiError::bad_request("INVALID_STATUS", message))
+}
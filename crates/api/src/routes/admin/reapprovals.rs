@@ -0,0 +1,124 @@
+//! Admin API routes for the re-approval queue.
+//!
+//! The location health checker rechecks dead, rejected and flagged locations
+//! and proposes the ones whose panorama exists again. Accepting a proposal
+//! reactivates and approves the location; pack-backed maps pick it up with
+//! the next pack build. Decisions count towards reviewer throughput.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use dguesser_auth::RequireAdmin;
+use dguesser_protocol::api::admin::{
+    DecideReapprovalRequest, ReapprovalItem, ReapprovalQueueParams, ReapprovalQueueResponse,
+};
+
+use crate::error::ApiError;
+use crate::state::AppState;
+
+/// Get pending re-approval proposals, oldest first.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/locations/reapprovals",
+    tag = "admin",
+    params(
+        ("page" = Option<i64>, Query, description = "Page number (1-indexed)"),
+        ("per_page" = Option<i64>, Query, description = "Items per page (max 100)")
+    ),
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Re-approval queue", body = ReapprovalQueueResponse),
+        (status = 403, description = "Admin access required"),
+    )
+)]
+pub(super) async fn list_reapprovals(
+    State(state): State<AppState>,
+    RequireAdmin(_auth): RequireAdmin,
+    Query(params): Query<ReapprovalQueueParams>,
+) -> Result<Json<ReapprovalQueueResponse>, ApiError> {
+    let page = params.page.max(1);
+    let per_page = params.per_page.clamp(1, 100);
+
+    let (proposals, total) = dguesser_db::location_health::list_reapprovals(
+        state.db_read(),
+        per_page,
+        (page - 1) * per_page,
+    )
+    .await?;
+
+    Ok(Json(ReapprovalQueueResponse {
+        locations: proposals
+            .into_iter()
+            .map(|p| ReapprovalItem {
+                location_id: p.location_id,
+                panorama_id: p.panorama_id,
+                lat: p.lat,
+                lng: p.lng,
+                country_code: p.country_code,
+                previous_status: p.previous_status,
+                previous_reason: p.previous_reason,
+                proposed_at: p.proposed_at,
+            })
+            .collect(),
+        total,
+        page,
+        per_page,
+        total_pages: (total as f64 / per_page as f64).ceil() as i64,
+    }))
+}
+
+/// Accept or dismiss a re-approval proposal.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/locations/reapprovals/{location_id}",
+    tag = "admin",
+    params(
+        ("location_id" = String, Path, description = "Location ID")
+    ),
+    request_body = DecideReapprovalRequest,
+    security(("session" = [])),
+    responses(
+        (status = 204, description = "Proposal decided"),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "No pending proposal for this location"),
+    )
+)]
+pub(super) async fn decide_reapproval(
+    State(state): State<AppState>,
+    RequireAdmin(auth): RequireAdmin,
+    Path(location_id): Path<String>,
+    Json(req): Json<DecideReapprovalRequest>,
+) -> Result<StatusCode, ApiError> {
+    let decided = if req.accept {
+        dguesser_db::location_health::accept_reapproval(state.db(), &location_id, &auth.user_id)
+            .await?
+    } else {
+        dguesser_db::location_health::dismiss_reapproval(state.db(), &location_id, &auth.user_id)
+            .await?
+    };
+    if !decided {
+        return Err(ApiError::not_found("Re-approval proposal"));
+    }
+
+    if req.accept {
+        dguesser_db::review_claims::record_decision(
+            state.db(),
+            &location_id,
+            &auth.user_id,
+            "approved",
+            Some("re-approved after health check"),
+        )
+        .await?;
+    }
+
+    tracing::info!(
+        location_id = %location_id,
+        accepted = req.accept,
+        reviewer = %auth.user_id,
+        "Re-approval proposal decided"
+    );
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,153 @@
+//! Admin API routes for review queue assignment.
+//!
+//! A reviewer claims a batch from the front of the queue and works through
+//! it; claimed locations drop out of other reviewers' batches until the
+//! claim lapses after [`CLAIM_MINUTES`] or the location is reviewed.
+
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+use chrono::{Duration, Utc};
+use dguesser_auth::RequireAdmin;
+use dguesser_protocol::api::admin::{
+    ClaimReviewBatchRequest, ClaimReviewBatchResponse, ReleaseReviewClaimsResponse,
+    ReviewerStatsItem, ReviewerStatsParams, ReviewerStatsResponse,
+};
+
+use crate::error::ApiError;
+use crate::state::AppState;
+
+/// How long a claim holds a location
+pub(super) const CLAIM_MINUTES: i32 = 30;
+/// Batch size when the request doesn't give one
+const DEFAULT_BATCH: i64 = 20;
+/// Most locations one reviewer can hold at once
+const MAX_ACTIVE_CLAIMS: i64 = 50;
+
+/// How many more locations a reviewer holding `active` claims may take.
+fn batch_size(requested: Option<i64>, active: i64) -> i64 {
+    requested.unwrap_or(DEFAULT_BATCH).max(0).min(MAX_ACTIVE_CLAIMS - active).max(0)
+}
+
+/// Claim a batch of unclaimed locations from the front of the review queue.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/locations/review-queue/claim",
+    tag = "admin",
+    request_body = ClaimReviewBatchRequest,
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Locations claimed", body = ClaimReviewBatchResponse),
+        (status = 403, description = "Admin access required"),
+        (status = 409, description = "Already holding the maximum number of claims"),
+    )
+)]
+pub(super) async fn claim_review_batch(
+    State(state): State<AppState>,
+    RequireAdmin(auth): RequireAdmin,
+    req: Option<Json<ClaimReviewBatchRequest>>,
+) -> Result<Json<ClaimReviewBatchResponse>, ApiError> {
+    let req = req.map(|Json(req)| req).unwrap_or_default();
+    let active = dguesser_db::review_claims::count_active(state.db(), &auth.user_id).await?;
+    let count = batch_size(req.count, active);
+    if count == 0 {
+        return Err(ApiError::conflict(
+            "TOO_MANY_CLAIMS",
+            format!("Finish or release your {active} claimed locations before claiming more"),
+        ));
+    }
+
+    let location_ids =
+        dguesser_db::review_claims::claim_batch(state.db(), &auth.user_id, count, CLAIM_MINUTES)
+            .await?;
+    let expires_at = (!location_ids.is_empty())
+        .then(|| Utc::now() + Duration::minutes(i64::from(CLAIM_MINUTES)));
+
+    tracing::info!(
+        reviewer = %auth.user_id,
+        claimed = location_ids.len(),
+        "Claimed review batch"
+    );
+
+    Ok(Json(ClaimReviewBatchResponse {
+        active_claims: active + location_ids.len() as i64,
+        location_ids,
+        expires_at,
+    }))
+}
+
+/// Release all of the caller's review claims.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/locations/review-queue/claims",
+    tag = "admin",
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Claims released", body = ReleaseReviewClaimsResponse),
+        (status = 403, description = "Admin access required"),
+    )
+)]
+pub(super) async fn release_review_claims(
+    State(state): State<AppState>,
+    RequireAdmin(auth): RequireAdmin,
+) -> Result<Json<ReleaseReviewClaimsResponse>, ApiError> {
+    let released = dguesser_db::review_claims::release_all(state.db(), &auth.user_id).await?;
+    Ok(Json(ReleaseReviewClaimsResponse { released }))
+}
+
+/// Get per-reviewer review throughput.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/locations/review-stats",
+    tag = "admin",
+    params(
+        ("days" = Option<i32>, Query, description = "Days of decisions (default 7, max 90)")
+    ),
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Reviewer throughput", body = ReviewerStatsResponse),
+        (status = 403, description = "Admin access required"),
+    )
+)]
+pub(super) async fn get_reviewer_stats(
+    State(state): State<AppState>,
+    RequireAdmin(_auth): RequireAdmin,
+    Query(params): Query<ReviewerStatsParams>,
+) -> Result<Json<ReviewerStatsResponse>, ApiError> {
+    let days = params.days.clamp(1, 90);
+    let since = Utc::now() - Duration::days(i64::from(days));
+    let stats = dguesser_db::review_claims::reviewer_stats(state.db_read(), since).await?;
+
+    Ok(Json(ReviewerStatsResponse {
+        days,
+        reviewers: stats
+            .into_iter()
+            .map(|s| ReviewerStatsItem {
+                reviewer_id: s.reviewer_id,
+                reviewer_name: s.reviewer_name,
+                reviewed: s.reviewed,
+                approved: s.approved,
+                rejected: s.rejected,
+                flagged: s.flagged,
+                in_progress: s.in_progress,
+                avg_review_secs: s.avg_review_secs,
+            })
+            .collect(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_size() {
+        assert_eq!(batch_size(None, 0), DEFAULT_BATCH);
+        assert_eq!(batch_size(Some(5), 0), 5);
+        assert_eq!(batch_size(Some(100), 0), MAX_ACTIVE_CLAIMS);
+        assert_eq!(batch_size(Some(20), MAX_ACTIVE_CLAIMS - 5), 5);
+        assert_eq!(batch_size(None, MAX_ACTIVE_CLAIMS), 0);
+        assert_eq!(batch_size(Some(-3), 0), 0);
+    }
+}
@@ -0,0 +1,163 @@
+//! Admin API routes for user support: signing in as a user and the audit log
+//! that records it.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode, header::SET_COOKIE},
+    response::IntoResponse,
+};
+use chrono::Duration;
+use dguesser_auth::{RequireAdmin, build_cookie_header};
+use dguesser_db::impersonation::NewImpersonation;
+use dguesser_protocol::api::admin::{
+    AuditLogItem, AuditLogParams, AuditLogResponse, ImpersonationResponse,
+    StartImpersonationRequest,
+};
+
+use crate::error::ApiError;
+use crate::middleware::RequestClient;
+use crate::state::AppState;
+
+/// Default impersonation session lifetime, in minutes
+const DEFAULT_IMPERSONATION_MINUTES: i64 = 15;
+/// Allowed impersonation session lifetimes, in minutes
+const IMPERSONATION_MINUTES: std::ops::RangeInclusive<i64> = 5..=60;
+
+/// Sign in as a user for support.
+///
+/// Switches the admin's browser to a short-lived session for the user
+/// (read-only unless asked otherwise) and records it in the audit log.
+/// Ending the impersonation restores the admin's own session.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/users/{user_id}/impersonate",
+    tag = "admin",
+    params(
+        ("user_id" = String, Path, description = "User to sign in as")
+    ),
+    request_body = StartImpersonationRequest,
+    security(("session" = [])),
+    responses(
+        (status = 201, description = "Impersonation started", body = ImpersonationResponse),
+        (status = 400, description = "Invalid request"),
+        (status = 403, description = "Admin access required, or the user is an admin"),
+        (status = 404, description = "User not found"),
+    )
+)]
+pub(super) async fn start_impersonation(
+    State(state): State<AppState>,
+    RequireAdmin(auth): RequireAdmin,
+    Path(user_id): Path<String>,
+    headers: HeaderMap,
+    Json(req): Json<StartImpersonationRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let reason = req.reason.trim();
+    if !(3..=500).contains(&reason.chars().count()) {
+        return Err(ApiError::bad_request(
+            "INVALID_REASON",
+            "Reason must be between 3 and 500 characters",
+        ));
+    }
+    let minutes = req.minutes.unwrap_or(DEFAULT_IMPERSONATION_MINUTES);
+    if !IMPERSONATION_MINUTES.contains(&minutes) {
+        return Err(ApiError::bad_request(
+            "INVALID_DURATION",
+            format!(
+                "Impersonation must last between {} and {} minutes",
+                IMPERSONATION_MINUTES.start(),
+                IMPERSONATION_MINUTES.end()
+            ),
+        ));
+    }
+    if user_id == auth.user_id {
+        return Err(ApiError::bad_request("INVALID_TARGET", "You cannot impersonate yourself"));
+    }
+
+    let user = dguesser_db::users::get_by_id(state.db(), &user_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("User"))?;
+    if user.is_admin() {
+        return Err(ApiError::forbidden("Admins cannot be impersonated"));
+    }
+
+    let client = RequestClient::from_headers(&headers, state.client_ip_config());
+    let session = dguesser_db::impersonation::start(
+        state.db(),
+        &NewImpersonation {
+            admin_id: &auth.user_id,
+            admin_session_id: &auth.session_id,
+            user_id: &user_id,
+            read_only: req.read_only,
+            reason,
+            ttl: Duration::minutes(minutes),
+            ip: client.ip.as_deref(),
+            user_agent: client.user_agent.as_deref(),
+        },
+    )
+    .await?;
+
+    tracing::warn!(
+        admin_id = %auth.user_id,
+        user_id = %user_id,
+        read_only = req.read_only,
+        minutes,
+        "Impersonation started"
+    );
+
+    let cookie = build_cookie_header(&session.session_id, state.session_config(), minutes * 60);
+    Ok((
+        StatusCode::CREATED,
+        [(SET_COOKIE, cookie)],
+        Json(ImpersonationResponse {
+            user_id,
+            read_only: session.read_only,
+            expires_at: session.expires_at,
+        }),
+    ))
+}
+
+/// List recorded admin actions, newest first.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/audit-log",
+    tag = "admin",
+    params(
+        ("user_id" = Option<String>, Query, description = "Only entries about this user"),
+        ("page" = Option<i64>, Query, description = "Page number (default 1)"),
+        ("per_page" = Option<i64>, Query, description = "Items per page (default 50, max 100)")
+    ),
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Audit log entries", body = AuditLogResponse),
+        (status = 403, description = "Admin access required"),
+    )
+)]
+pub(super) async fn get_audit_log(
+    State(state): State<AppState>,
+    RequireAdmin(_auth): RequireAdmin,
+    Query(params): Query<AuditLogParams>,
+) -> Result<Json<AuditLogResponse>, ApiError> {
+    let page = params.page.max(1);
+    let per_page = params.per_page.clamp(1, 100);
+
+    let entries = dguesser_db::audit_log::list(
+        state.db_read(),
+        params.user_id.as_deref(),
+        per_page,
+        (page - 1) * per_page,
+    )
+    .await?
+    .into_iter()
+    .map(|entry| AuditLogItem {
+        id: entry.id,
+        actor_id: entry.actor_id,
+        action: entry.action,
+        target_user_id: entry.target_user_id,
+        details: entry.details,
+        created_at: entry.created_at,
+    })
+    .collect();
+
+    Ok(Json(AuditLogResponse { entries, page, per_page }))
+}
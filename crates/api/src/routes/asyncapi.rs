@@ -0,0 +1,51 @@
+//! AsyncAPI document for the socket protocol
+
+use axum::Json;
+use dguesser_protocol::socket::asyncapi;
+use serde_json::Value;
+
+/// AsyncAPI 3.0 description of the realtime server's socket events, served
+/// next to the Scalar docs.
+pub async fn asyncapi_json() -> Json<Value> {
+    Json(asyncapi::document())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect_refs<'a>(value: &'a Value, refs: &mut Vec<&'a str>) {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(target)) = map.get("$ref") {
+                    refs.push(target);
+                }
+                map.values().for_each(|v| collect_refs(v, refs));
+            }
+            Value::Array(items) => items.iter().for_each(|v| collect_refs(v, refs)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn test_document_refs_resolve() {
+        let doc = asyncapi::document();
+        let mut refs = Vec::new();
+        collect_refs(&doc, &mut refs);
+
+        assert!(!refs.is_empty());
+        for target in refs {
+            let pointer = target.strip_prefix('#').expect("local reference");
+            assert!(doc.pointer(pointer).is_some(), "unresolved reference {target}");
+        }
+    }
+
+    #[test]
+    fn test_event_directions_are_unique() {
+        let events = asyncapi::events();
+        let mut seen = std::collections::HashSet::new();
+        for event in &events {
+            assert!(seen.insert((event.name, event.direction)), "{} listed twice", event.name);
+        }
+    }
+}
@@ -2,31 +2,48 @@
 
 use axum::{
     Json, Router,
-    extract::{Query, State},
+    extract::{Path, Query, State},
     http::{HeaderMap, StatusCode, header::SET_COOKIE},
-    response::{IntoResponse, Redirect},
+    response::{IntoResponse, Redirect, Response},
     routing::{get, post},
 };
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 use crate::{
-    cache::CoPlayersCache, error::ApiError, middleware::extract_ip_from_headers, state::AppState,
+    cache::CoPlayersCache,
+    email,
+    error::ApiError,
+    middleware::{LoginThrottle, RequestClient, abuse},
+    routes::users::{record_display_name_flag, validate_display_name},
+    state::AppState,
 };
 use dguesser_auth::{
-    AuthUser, MaybeAuthUser, OAuthProvider, OAuthState, build_cookie_header,
-    build_delete_cookie_header, create_guest_session, handle_oauth_callback,
+    AuthResult, AuthUser, MaybeAuthUser, OAuthIdentity, OAuthProvider, OAuthState, RequireAuth,
+    build_cookie_header, build_delete_cookie_header, create_guest_session, handle_oauth_callback,
+    link_oauth_account, login_with_password, register_with_password,
 };
+use dguesser_protocol::api::auth::OAuthUrlResponse;
 
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/guest", post(create_guest))
         .route("/me", get(get_current_user))
         .route("/logout", post(logout))
+        .route("/impersonation/end", post(end_impersonation))
+        .route("/register", post(register))
+        .route("/login", post(login))
+        .route("/verify-email", post(verify_email))
+        .route("/verify-email/resend", post(resend_verification))
+        .route("/password-reset", post(request_password_reset))
+        .route("/password-reset/confirm", post(confirm_password_reset))
+        .route("/socket-token", post(create_socket_token))
+        .route("/captcha", get(get_captcha_settings))
         .route("/google", get(google_redirect))
         .route("/google/callback", get(google_callback))
         .route("/microsoft", get(microsoft_redirect))
         .route("/microsoft/callback", get(microsoft_callback))
+        .route("/link/{provider}", post(start_link))
 }
 
 /// Response for current user
@@ -42,6 +59,8 @@ pub struct CurrentUserResponse {
     pub display_name: String,
     /// Email address (if authenticated)
     pub email: Option<String>,
+    /// Whether the email address has been verified
+    pub email_verified: bool,
     /// Avatar URL
     pub avatar_url: Option<String>,
     /// Whether this is a guest user
@@ -57,6 +76,20 @@ pub struct CurrentUserResponse {
     pub best_score: i32,
     /// Whether the user has opted into public leaderboard visibility
     pub leaderboard_public: bool,
+    /// Set when an admin is signed in as this user for support
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub impersonation: Option<ImpersonationInfo>,
+}
+
+/// The admin behind an impersonation session
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImpersonationInfo {
+    /// Admin who started the impersonation
+    pub admin_id: String,
+    /// Whether only reads are allowed
+    pub read_only: bool,
+    /// When the session expires
+    pub expires_at: chrono::DateTime<chrono::Utc>,
 }
 
 impl CurrentUserResponse {
@@ -66,6 +99,7 @@ impl CurrentUserResponse {
             username: user.username.clone(),
             display_name: user.display_name.clone(),
             email: user.email.clone(),
+            email_verified: user.email_verified,
             avatar_url: user.avatar_url.clone(),
             is_guest: user.kind == dguesser_db::UserKind::Guest,
             role: user.role.clone(),
@@ -73,17 +107,31 @@ impl CurrentUserResponse {
             total_score: user.total_score,
             best_score: user.best_score,
             leaderboard_public: user.leaderboard_public,
+            impersonation: None,
         }
     }
 }
 
+/// Request to create a guest session
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct CreateGuestRequest {
+    /// Display name (3-50 characters); a random one is generated if omitted
+    #[schema(example = "Swift Explorer 4521")]
+    pub display_name: Option<String>,
+}
+
 /// Create a guest session
+///
+/// The body is optional.
 #[utoipa::path(
     post,
     path = "/api/v1/auth/guest",
+    request_body(content = Option<CreateGuestRequest>),
     responses(
         (status = 200, description = "Existing session returned", body = CurrentUserResponse),
         (status = 201, description = "New guest session created", body = CurrentUserResponse),
+        (status = 400, description = "Invalid display name"),
+        (status = 429, description = "Guest account limit reached or cooldown active"),
     ),
     tag = "auth"
 )]
@@ -91,6 +139,7 @@ pub async fn create_guest(
     State(state): State<AppState>,
     headers: HeaderMap,
     MaybeAuthUser(existing): MaybeAuthUser,
+    req: Option<Json<CreateGuestRequest>>,
 ) -> Result<impl IntoResponse, ApiError> {
     // If already has valid session, return existing user
     if let Some(auth) = existing {
@@ -101,13 +150,24 @@ pub async fn create_guest(
         return Ok((StatusCode::OK, Json(CurrentUserResponse::from_user(&user))).into_response());
     }
 
-    // Extract IP (using secure method) and user agent
-    let ip = extract_ip_from_headers(&headers, state.client_ip_config());
-    let user_agent = headers.get("user-agent").and_then(|v| v.to_str().ok());
+    let Json(req) = req.unwrap_or_default();
+    let display_name = req.display_name.as_deref().map(validate_display_name).transpose()?;
+
+    // Extract IP (using secure method), user agent, and country
+    let client = RequestClient::from_headers(&headers, state.client_ip_config());
+    abuse::check_guest_creation(state.redis(), client.ip.as_deref()).await?;
 
     // Create guest session
-    let result =
-        create_guest_session(state.db(), state.session_config(), ip.as_deref(), user_agent).await?;
+    let result = create_guest_session(
+        state.db(),
+        display_name.as_ref().map(|checked| checked.name.as_str()),
+        state.session_config(),
+        client.info(),
+    )
+    .await?;
+    if let Some(ref checked) = display_name {
+        record_display_name_flag(&state, &result.user_id, checked).await;
+    }
 
     // Get the created user
     let user = dguesser_db::users::get_by_id(state.db(), &result.user_id)
@@ -143,7 +203,13 @@ pub async fn get_current_user(
         .await?
         .ok_or_else(|| ApiError::not_found("User"))?;
 
-    Ok(Json(CurrentUserResponse::from_user(&user)))
+    let mut response = CurrentUserResponse::from_user(&user);
+    response.impersonation = auth.impersonation.map(|imp| ImpersonationInfo {
+        admin_id: imp.admin_id,
+        read_only: imp.read_only,
+        expires_at: imp.expires_at,
+    });
+    Ok(Json(response))
 }
 
 /// Logout - revoke session
@@ -160,6 +226,10 @@ pub async fn logout(
     State(state): State<AppState>,
     auth: AuthUser,
 ) -> Result<impl IntoResponse, ApiError> {
+    if auth.impersonation.is_some() {
+        return finish_impersonation(&state, &auth.session_id).await;
+    }
+
     dguesser_db::sessions::revoke(state.db(), &auth.session_id).await?;
 
     let delete_cookie = build_delete_cookie_header(state.session_config());
@@ -167,6 +237,469 @@ pub async fn logout(
     Ok((StatusCode::OK, [(SET_COOKIE, delete_cookie)]))
 }
 
+/// End impersonation and return to the admin's own session
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/impersonation/end",
+    responses(
+        (status = 200, description = "Impersonation ended"),
+        (status = 400, description = "Not an impersonation session"),
+        (status = 401, description = "Not authenticated"),
+    ),
+    tag = "auth"
+)]
+pub async fn end_impersonation(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<impl IntoResponse, ApiError> {
+    if auth.impersonation.is_none() {
+        return Err(ApiError::bad_request(
+            "NOT_IMPERSONATING",
+            "This session is not an impersonation session",
+        ));
+    }
+    finish_impersonation(&state, &auth.session_id).await
+}
+
+/// Revoke an impersonation session and restore the admin's session cookie,
+/// or clear the cookie if the admin's session is gone.
+async fn finish_impersonation(
+    state: &AppState,
+    session_id: &str,
+) -> Result<(StatusCode, [(axum::http::HeaderName, String); 1]), ApiError> {
+    let ended = dguesser_db::impersonation::end(state.db(), session_id).await?;
+
+    let admin_session = match ended.and_then(|s| s.admin_session_id) {
+        Some(admin_session_id) => {
+            dguesser_db::sessions::get_valid(state.db(), &admin_session_id).await?
+        }
+        None => None,
+    };
+
+    let cookie = match admin_session {
+        Some(session) => {
+            let max_age = (session.expires_at - chrono::Utc::now()).num_seconds().max(0);
+            build_cookie_header(&session.id, state.session_config(), max_age)
+        }
+        None => build_delete_cookie_header(state.session_config()),
+    };
+
+    Ok((StatusCode::OK, [(SET_COOKIE, cookie)]))
+}
+
+/// Request to register with email and password
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RegisterRequest {
+    /// Email address (a verification link is sent here)
+    #[schema(example = "player@example.com")]
+    pub email: String,
+    /// Password (8-128 characters)
+    pub password: String,
+    /// Display name (3-50 characters); guests keep their current name if omitted
+    pub display_name: Option<String>,
+}
+
+/// Request to sign in with email and password
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct LoginRequest {
+    /// Email address
+    #[schema(example = "player@example.com")]
+    pub email: String,
+    /// Password
+    pub password: String,
+}
+
+/// Request carrying a single-use token from an email link
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct VerifyEmailRequest {
+    /// Token from the verification link
+    pub token: String,
+}
+
+/// Request to send a password reset link
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PasswordResetRequest {
+    /// Email address of the account
+    #[schema(example = "player@example.com")]
+    pub email: String,
+}
+
+/// Request to set a new password with a reset token
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PasswordResetConfirmRequest {
+    /// Token from the reset link
+    pub token: String,
+    /// New password (8-128 characters)
+    pub password: String,
+}
+
+/// Generic acknowledgement for account email flows
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuthMessageResponse {
+    /// Human-readable status message
+    pub message: String,
+}
+
+/// Register with email and password
+///
+/// A guest session is upgraded in place, keeping its history. The email
+/// starts unverified and a verification link is sent.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/register",
+    request_body = RegisterRequest,
+    responses(
+        (status = 201, description = "Account created and signed in", body = CurrentUserResponse),
+        (status = 400, description = "Invalid email, password, or display name"),
+        (status = 409, description = "Email already in use"),
+    ),
+    tag = "auth"
+)]
+pub async fn register(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    MaybeAuthUser(existing): MaybeAuthUser,
+    Json(req): Json<RegisterRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let display_name = req.display_name.as_deref().map(validate_display_name).transpose()?;
+
+    let client = RequestClient::from_headers(&headers, state.client_ip_config());
+
+    let registration = register_with_password(
+        state.db(),
+        &req.email,
+        &req.password,
+        display_name.as_ref().map(|checked| checked.name.as_str()),
+        existing.as_ref().map(|a| a.session_id.as_str()),
+        state.session_config(),
+        client.info(),
+    )
+    .await?;
+
+    email::send_verification_email(
+        &state,
+        &registration.auth.user_id,
+        &registration.email,
+        &registration.verification_token,
+    )
+    .await;
+    if let Some(ref checked) = display_name {
+        record_display_name_flag(&state, &registration.auth.user_id, checked).await;
+    }
+
+    signed_in_response(&state, StatusCode::CREATED, &registration.auth).await
+}
+
+/// Sign in with email and password
+///
+/// A guest session is merged into the account, like OAuth sign-in. Repeated
+/// failures for the same email are temporarily locked out.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/login",
+    request_body = LoginRequest,
+    responses(
+        (status = 200, description = "Signed in", body = CurrentUserResponse),
+        (status = 401, description = "Invalid email or password"),
+        (status = 409, description = "Guest session cannot be merged right now"),
+        (status = 429, description = "Too many failed attempts"),
+    ),
+    tag = "auth"
+)]
+pub async fn login(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    MaybeAuthUser(existing): MaybeAuthUser,
+    Json(req): Json<LoginRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    if LoginThrottle::is_locked(state.redis(), &req.email).await {
+        return Err(ApiError::rate_limited());
+    }
+
+    let client = RequestClient::from_headers(&headers, state.client_ip_config());
+
+    let result = match login_with_password(
+        state.db(),
+        &req.email,
+        &req.password,
+        existing.as_ref().map(|a| a.session_id.as_str()),
+        state.session_config(),
+        client.info(),
+    )
+    .await
+    {
+        Ok(result) => result,
+        Err(err) => {
+            if matches!(err, dguesser_auth::AuthError::InvalidCredentials) {
+                LoginThrottle::record_failure(state.redis(), &req.email).await;
+            }
+            return Err(err.into());
+        }
+    };
+
+    LoginThrottle::reset(state.redis(), &req.email).await;
+
+    if result.merged_from_guest.is_some() {
+        crate::cache::LeaderboardCache::invalidate_all(state.redis()).await;
+        for user_id in &result.invalidate_co_player_cache_for {
+            CoPlayersCache::invalidate(state.redis(), user_id).await;
+        }
+    }
+
+    alert_new_device(&state, &result).await;
+
+    signed_in_response(&state, StatusCode::OK, &result).await
+}
+
+/// Confirm an email address
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/verify-email",
+    request_body = VerifyEmailRequest,
+    responses(
+        (status = 200, description = "Email verified", body = AuthMessageResponse),
+        (status = 400, description = "Invalid or expired token"),
+    ),
+    tag = "auth"
+)]
+pub async fn verify_email(
+    State(state): State<AppState>,
+    Json(req): Json<VerifyEmailRequest>,
+) -> Result<Json<AuthMessageResponse>, ApiError> {
+    let user_id = dguesser_auth::verify_email(state.db(), &req.token).await?;
+    tracing::info!(%user_id, "Email verified");
+
+    Ok(Json(AuthMessageResponse { message: "Email verified".to_string() }))
+}
+
+/// Resend the verification email for the current user
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/verify-email/resend",
+    responses(
+        (status = 200, description = "Verification email sent if needed", body = AuthMessageResponse),
+        (status = 401, description = "Not authenticated"),
+    ),
+    tag = "auth"
+)]
+pub async fn resend_verification(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<Json<AuthMessageResponse>, ApiError> {
+    let message = match dguesser_auth::resend_verification(state.db(), &auth.user_id).await? {
+        Some(issued) => {
+            email::send_verification_email(&state, &issued.user_id, &issued.email, &issued.token)
+                .await;
+            "Verification email sent"
+        }
+        None => "No unverified email on this account",
+    };
+
+    Ok(Json(AuthMessageResponse { message: message.to_string() }))
+}
+
+/// Request a password reset link
+///
+/// Always succeeds so the response does not reveal whether an account exists.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/password-reset",
+    request_body = PasswordResetRequest,
+    responses(
+        (status = 200, description = "Reset link sent if the account exists", body = AuthMessageResponse),
+    ),
+    tag = "auth"
+)]
+pub async fn request_password_reset(
+    State(state): State<AppState>,
+    Json(req): Json<PasswordResetRequest>,
+) -> Result<Json<AuthMessageResponse>, ApiError> {
+    if let Some(issued) = dguesser_auth::request_password_reset(state.db(), &req.email).await? {
+        email::send_password_reset_email(&state, &issued.user_id, &issued.email, &issued.token)
+            .await;
+    }
+
+    Ok(Json(AuthMessageResponse {
+        message: "If an account exists for that email, a reset link has been sent".to_string(),
+    }))
+}
+
+/// Set a new password with a reset token
+///
+/// Signs out every session for the account, including this one.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/password-reset/confirm",
+    request_body = PasswordResetConfirmRequest,
+    responses(
+        (status = 200, description = "Password updated", body = AuthMessageResponse),
+        (status = 400, description = "Invalid or expired token, or weak password"),
+    ),
+    tag = "auth"
+)]
+pub async fn confirm_password_reset(
+    State(state): State<AppState>,
+    Json(req): Json<PasswordResetConfirmRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let user_id = dguesser_auth::reset_password(state.db(), &req.token, &req.password).await?;
+    tracing::info!(%user_id, "Password reset completed");
+
+    if let Some(user) = dguesser_db::users::get_by_id(state.db(), &user_id).await?
+        && let Some(email) = user.email.as_deref()
+    {
+        LoginThrottle::reset(state.redis(), email).await;
+    }
+
+    let delete_cookie = build_delete_cookie_header(state.session_config());
+
+    Ok((
+        StatusCode::OK,
+        [(SET_COOKIE, delete_cookie)],
+        Json(AuthMessageResponse { message: "Password updated".to_string() }),
+    ))
+}
+
+/// Request body for minting a socket token
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct SocketTokenRequest {
+    /// Game the socket will join; the token is only valid for this game
+    #[schema(example = "gam_FyB2aQ9-xL3k")]
+    pub game_id: Option<String>,
+}
+
+/// Short-lived token for the realtime socket handshake
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SocketTokenResponse {
+    /// Token to pass as the `token` query parameter when connecting
+    pub token: String,
+    /// When the token stops being accepted
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// CAPTCHA widget settings
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CaptchaSettingsResponse {
+    /// "turnstile" or "hcaptcha"; absent when CAPTCHA is disabled
+    #[schema(example = "turnstile")]
+    pub provider: Option<String>,
+    /// Site key to render the widget with
+    pub site_key: Option<String>,
+    /// Whether the caller must solve a CAPTCHA before creating a guest session
+    /// or joining by code
+    pub required: bool,
+}
+
+/// Get CAPTCHA settings
+///
+/// When `required` is set, send the widget's token in the `X-Captcha-Token`
+/// header of `POST /api/v1/auth/guest` and `POST /api/v1/games/join`. Those
+/// endpoints answer `CAPTCHA_REQUIRED` when a token is needed but missing.
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/captcha",
+    responses(
+        (status = 200, description = "CAPTCHA settings", body = CaptchaSettingsResponse),
+    ),
+    tag = "auth"
+)]
+pub async fn get_captcha_settings(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    MaybeAuthUser(auth): MaybeAuthUser,
+) -> Json<CaptchaSettingsResponse> {
+    let Some(captcha) = state.captcha() else {
+        return Json(CaptchaSettingsResponse { provider: None, site_key: None, required: false });
+    };
+
+    let client = RequestClient::from_headers(&headers, state.client_ip_config());
+    let required = match (&auth, client.ip.as_deref()) {
+        (None, Some(ip)) => captcha.required_after(abuse::guest_creations(state.redis(), ip).await),
+        _ => false,
+    };
+
+    Json(CaptchaSettingsResponse {
+        provider: Some(captcha.provider().as_str().to_string()),
+        site_key: Some(captcha.site_key().to_string()),
+        required,
+    })
+}
+
+/// Mint a realtime socket token
+///
+/// The token authenticates the socket during the handshake, so the realtime
+/// server does not need a database lookup or a separate `auth` event. Tokens
+/// live for one minute; request a new one for each connection attempt.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/socket-token",
+    request_body = SocketTokenRequest,
+    responses(
+        (status = 200, description = "Token minted", body = SocketTokenResponse),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "Game not found"),
+        (status = 503, description = "Socket tokens not configured"),
+    ),
+    tag = "auth"
+)]
+pub async fn create_socket_token(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    req: Option<Json<SocketTokenRequest>>,
+) -> Result<Json<SocketTokenResponse>, ApiError> {
+    let signer = state
+        .socket_token_signer()
+        .ok_or_else(|| ApiError::service_unavailable("Socket tokens are not configured"))?;
+    let req = req.map(|Json(req)| req).unwrap_or_default();
+
+    if let Some(ref game_id) = req.game_id {
+        let game = dguesser_db::games::get_game_by_id(state.db(), game_id)
+            .await?
+            .ok_or_else(|| ApiError::not_found("Game"))?;
+        if game.status != dguesser_db::GameStatus::Lobby
+            && game.status != dguesser_db::GameStatus::Active
+        {
+            return Err(ApiError::bad_request("GAME_ENDED", "Game has ended"));
+        }
+    }
+
+    let (token, expires_at) =
+        signer.mint(&auth.user_id, req.game_id.as_deref(), chrono::Utc::now());
+
+    Ok(Json(SocketTokenResponse { token, expires_at }))
+}
+
+/// Build the response for a successful password sign-in or registration.
+async fn signed_in_response(
+    state: &AppState,
+    status: StatusCode,
+    result: &AuthResult,
+) -> Result<Response, ApiError> {
+    let user = dguesser_db::users::get_by_id(state.db(), &result.user_id)
+        .await?
+        .ok_or_else(|| ApiError::internal().with_internal("Signed-in user not found"))?;
+
+    let cookie = build_cookie_header(
+        &result.session_id,
+        state.session_config(),
+        state.session_config().max_age_seconds(),
+    );
+
+    Ok((status, [(SET_COOKIE, cookie)], Json(CurrentUserResponse::from_user(&user)))
+        .into_response())
+}
+
+/// Email the user if this sign-in came from a device they have not used before.
+async fn alert_new_device(state: &AppState, result: &AuthResult) {
+    let Some(device) = &result.new_device else {
+        return;
+    };
+    match dguesser_db::users::get_by_id(state.db(), &result.user_id).await {
+        Ok(Some(user)) => email::send_new_device_email(state, &user, device).await,
+        Ok(None) => {}
+        Err(e) => tracing::warn!(error = %e, "Failed to load user for new device alert"),
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct OAuthQuery {
     redirect_to: Option<String>,
@@ -249,9 +782,12 @@ async fn google_callback(
         }
     };
 
+    if stored_state.is_link() {
+        return Ok(complete_link(&state, stored_state, identity, existing).await);
+    }
+
     // Extract request metadata (using secure IP extraction)
-    let ip = extract_ip_from_headers(&headers, state.client_ip_config());
-    let user_agent = headers.get("user-agent").and_then(|v| v.to_str().ok());
+    let client = RequestClient::from_headers(&headers, state.client_ip_config());
 
     // Handle OAuth callback
     let current_session = existing.as_ref().map(|a| a.session_id.as_str());
@@ -260,8 +796,7 @@ async fn google_callback(
         identity,
         current_session,
         state.session_config(),
-        ip.as_deref(),
-        user_agent,
+        client.info(),
     )
     .await
     {
@@ -279,6 +814,8 @@ async fn google_callback(
         }
     }
 
+    alert_new_device(&state, &result).await;
+
     // Build session cookie
     let cookie = build_cookie_header(
         &result.session_id,
@@ -367,6 +904,12 @@ mod tests {
         let err = dguesser_auth::AuthError::MergeBlocked("blocked".to_string());
         assert_eq!(auth_error_code(&err), "AUTH_MERGE_BLOCKED");
     }
+
+    #[test]
+    fn test_auth_error_code_for_identity_conflict() {
+        let err = dguesser_auth::AuthError::IdentityConflict;
+        assert_eq!(auth_error_code(&err), "OAUTH_IDENTITY_CONFLICT");
+    }
 }
 
 /// Initiate Microsoft OAuth
@@ -439,9 +982,12 @@ async fn microsoft_callback(
         }
     };
 
+    if stored_state.is_link() {
+        return Ok(complete_link(&state, stored_state, identity, existing).await);
+    }
+
     // Extract request metadata (using secure IP extraction)
-    let ip = extract_ip_from_headers(&headers, state.client_ip_config());
-    let user_agent = headers.get("user-agent").and_then(|v| v.to_str().ok());
+    let client = RequestClient::from_headers(&headers, state.client_ip_config());
 
     let current_session = existing.as_ref().map(|a| a.session_id.as_str());
     let result = match handle_oauth_callback(
@@ -449,8 +995,7 @@ async fn microsoft_callback(
         identity,
         current_session,
         state.session_config(),
-        ip.as_deref(),
-        user_agent,
+        client.info(),
     )
     .await
     {
@@ -468,6 +1013,8 @@ async fn microsoft_callback(
         }
     }
 
+    alert_new_device(&state, &result).await;
+
     let cookie = build_cookie_header(
         &result.session_id,
         state.session_config(),
@@ -483,12 +1030,108 @@ async fn microsoft_callback(
     Ok(([(SET_COOKIE, cookie)], Redirect::temporary(&redirect_url)).into_response())
 }
 
+/// Start linking an additional OAuth provider to the current account
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/link/{provider}",
+    params(
+        ("provider" = String, Path, description = "OAuth provider (google or microsoft)"),
+        ("redirect_to" = Option<String>, Query, description = "URL to redirect after linking")
+    ),
+    responses(
+        (status = 200, description = "Provider authorization URL", body = OAuthUrlResponse),
+        (status = 400, description = "Unknown provider"),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Guest accounts cannot link providers"),
+        (status = 409, description = "Provider already linked"),
+        (status = 503, description = "Provider not configured"),
+    ),
+    tag = "auth"
+)]
+pub async fn start_link(
+    State(state): State<AppState>,
+    RequireAuth(auth): RequireAuth,
+    Path(provider): Path<String>,
+    Query(query): Query<OAuthQuery>,
+) -> Result<Json<OAuthUrlResponse>, ApiError> {
+    let provider: OAuthProvider = provider
+        .parse()
+        .map_err(|_| ApiError::bad_request("INVALID_PROVIDER", "Unknown OAuth provider"))?;
+
+    if dguesser_db::oauth::is_provider_linked(state.db(), &auth.user_id, &provider.to_string())
+        .await?
+    {
+        return Err(dguesser_auth::AuthError::ProviderAlreadyLinked(provider).into());
+    }
+
+    let oauth_state = OAuthState::for_link(provider, &auth.user_id, query.redirect_to);
+
+    let url = match provider {
+        OAuthProvider::Google => state
+            .google_oauth()
+            .ok_or_else(|| ApiError::service_unavailable("Google OAuth not configured"))?
+            .authorization_url(&oauth_state.state, &oauth_state.nonce),
+        OAuthProvider::Microsoft => state
+            .microsoft_oauth()
+            .ok_or_else(|| ApiError::service_unavailable("Microsoft OAuth not configured"))?
+            .authorization_url(&oauth_state.state, &oauth_state.nonce),
+    };
+
+    // Store state in Redis for validation on callback
+    state.oauth_state_store().store(&oauth_state).await?;
+
+    Ok(Json(OAuthUrlResponse { url }))
+}
+
+/// Finish an account-linking flow started by [`start_link`].
+///
+/// The callback must arrive on the same account that started the flow; the
+/// session is kept as-is since linking never changes which user is signed in.
+async fn complete_link(
+    state: &AppState,
+    stored_state: OAuthState,
+    identity: OAuthIdentity,
+    existing: Option<AuthUser>,
+) -> Response {
+    let provider = identity.provider;
+    let Some(link_user_id) = stored_state.link_user_id.as_deref() else {
+        return auth_error_redirect(state, "OAUTH_ERROR").into_response();
+    };
+
+    if existing.as_ref().map(|auth| auth.user_id.as_str()) != Some(link_user_id) {
+        tracing::warn!(%provider, "OAuth link callback arrived without the initiating session");
+        return auth_error_redirect(state, "AUTH_SESSION_ERROR").into_response();
+    }
+
+    if let Err(err) = link_oauth_account(state.db(), identity, link_user_id).await {
+        tracing::warn!(error = %err, %provider, "OAuth account link failed");
+        return auth_error_redirect(state, auth_error_code(&err)).into_response();
+    }
+
+    tracing::info!(user_id = %link_user_id, %provider, "Linked OAuth provider");
+
+    let redirect_url = stored_state
+        .redirect_to
+        .filter(|url| is_safe_redirect(url, state.frontend_url()))
+        .unwrap_or_else(|| format!("{}/account?linked={provider}", state.frontend_url()));
+
+    Redirect::temporary(&redirect_url).into_response()
+}
+
 fn auth_error_code(err: &dguesser_auth::AuthError) -> &'static str {
     match err {
         dguesser_auth::AuthError::MergeBlocked(_) => "AUTH_MERGE_BLOCKED",
+        dguesser_auth::AuthError::IdentityConflict => "OAUTH_IDENTITY_CONFLICT",
+        dguesser_auth::AuthError::ProviderAlreadyLinked(_) => "PROVIDER_ALREADY_LINKED",
+        dguesser_auth::AuthError::ProviderNotLinked(_) => "PROVIDER_NOT_LINKED",
+        dguesser_auth::AuthError::LastLoginMethod => "LAST_LOGIN_METHOD",
         dguesser_auth::AuthError::OAuth(_) => "OAUTH_ERROR",
         dguesser_auth::AuthError::SessionNotFound => "AUTH_SESSION_ERROR",
-        dguesser_auth::AuthError::Database(_) => "AUTH_ERROR",
+        dguesser_auth::AuthError::Database(_)
+        | dguesser_auth::AuthError::Credential(_)
+        | dguesser_auth::AuthError::InvalidCredentials
+        | dguesser_auth::AuthError::EmailTaken
+        | dguesser_auth::AuthError::InvalidToken => "AUTH_ERROR",
     }
 }
 
@@ -0,0 +1,980 @@
+//! Challenge routes
+//!
+//! Asynchronous head-to-head games between friends. All rounds are created
+//! when the challenge is, and each player plays them on their own schedule
+//! through these endpoints. Scores and results stay hidden until every player
+//! has finished.
+//!
+//! The database is the source of truth; the core reducer runs in its
+//! per-player progression mode for validation, scoring, and deciding when
+//! the challenge is over.
+
+use std::collections::HashMap;
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+};
+use chrono::{DateTime, Duration, Utc};
+use dguesser_auth::RequireAuth;
+use dguesser_core::game::{
+    GameCommand, GameEvent, GamePhase, GameSettings, GameState, PlayerState, RoundProgression,
+    RoundState, reduce, validate_location_count,
+};
+use dguesser_db::challenges::PlayerRound;
+use dguesser_db::{Game, GameMode, GameStatus, JoinCodeChoice, Round};
+use dguesser_push::Notification;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use super::games::{
+    CurrentRoundInfo, GameResultsResponse, GuessResultResponse, LocationInfo,
+    NO_GUESS_DISTANCE_METERS, NO_GUESS_LAT, NO_GUESS_LNG, NO_GUESS_SCORE, RoundInfo, SettingsDto,
+    SubmitGuessRequest, UserGuessInfo, build_game_results, global_guess_stats,
+    location_guess_stats, map_scoring, record_panorama_mismatch, rounds_plan_payload,
+    select_location,
+};
+use crate::error::ApiError;
+use crate::extract::ValidatedJson;
+use crate::middleware::CurrentOrg;
+use crate::state::AppState;
+
+/// Days a challenge stays open before unfinished ones are abandoned
+pub const CHALLENGE_EXPIRY_DAYS: i32 = 7;
+
+/// Maximum active challenges per user
+const MAX_ACTIVE_CHALLENGES: i64 = 20;
+
+/// Days finished challenges stay in the challenge list
+const RECENT_DAYS: i32 = 14;
+
+/// Maximum challenges listed
+const LIST_LIMIT: i64 = 50;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_challenges).post(create_challenge))
+        .route("/{id}", get(get_challenge))
+        .route("/{id}/rounds/current", get(get_current_round))
+        .route("/{id}/rounds/next", post(next_round))
+        .route("/{id}/rounds/{round}/guess", post(submit_guess))
+}
+
+// =============================================================================
+// DTOs
+// =============================================================================
+
+/// Create challenge request
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateChallengeRequest {
+    /// Friend to challenge
+    #[schema(example = "usr_V1StGXR8_Z5j")]
+    pub opponent_id: String,
+    /// Number of rounds (1-20)
+    #[validate(range(min = 1, max = 20))]
+    #[schema(example = 5)]
+    pub rounds: Option<u8>,
+    /// Time limit per round in seconds (0 = unlimited, max 600)
+    #[validate(range(max = 600))]
+    #[schema(example = 120)]
+    pub time_limit_seconds: Option<u32>,
+    /// Map/region identifier
+    #[validate(length(max = 100))]
+    #[schema(example = "world")]
+    pub map_id: Option<String>,
+    /// Allow movement in Street View
+    pub movement_allowed: Option<bool>,
+    /// Allow zooming
+    pub zoom_allowed: Option<bool>,
+    /// Allow rotation/panning
+    pub rotation_allowed: Option<bool>,
+}
+
+/// Create challenge response
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreateChallengeResponse {
+    /// Game ID of the challenge
+    #[schema(example = "gam_FybH2oF9Xaw8")]
+    pub id: String,
+    /// When the challenge is abandoned if not finished
+    pub expires_at: DateTime<Utc>,
+}
+
+/// A challenge in the challenge list
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ChallengeListItem {
+    /// Game ID of the challenge
+    #[schema(example = "gam_FybH2oF9Xaw8")]
+    pub id: String,
+    /// Game status: active, finished, or abandoned
+    #[schema(example = "active")]
+    pub status: String,
+    /// Whether you sent the challenge
+    pub created_by_you: bool,
+    /// When the challenge was sent
+    pub created_at: DateTime<Utc>,
+    /// When the challenge is abandoned if not finished
+    pub expires_at: DateTime<Utc>,
+    /// When the challenge ended
+    pub ended_at: Option<DateTime<Utc>>,
+    /// Total number of rounds
+    pub total_rounds: u8,
+    /// Opponent's user ID
+    pub opponent_id: String,
+    /// Opponent's display name
+    pub opponent_display_name: String,
+    /// Opponent's avatar URL
+    pub opponent_avatar_url: Option<String>,
+    /// Rounds you have played
+    pub rounds_played: u8,
+    /// Rounds your opponent has played
+    pub opponent_rounds_played: u8,
+    /// Whether you still have rounds to play
+    pub your_turn: bool,
+    /// Your score so far
+    pub score: u32,
+    /// Opponent's score, once the challenge is finished
+    pub opponent_score: Option<u32>,
+}
+
+/// Challenge list response
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ChallengeListResponse {
+    /// Active challenges and recently finished ones, newest first
+    pub challenges: Vec<ChallengeListItem>,
+}
+
+/// A player's progress in a challenge
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ChallengePlayerInfo {
+    /// User ID
+    pub user_id: String,
+    /// Display name
+    pub display_name: String,
+    /// Avatar URL
+    pub avatar_url: Option<String>,
+    /// Rounds the player has guessed on or run out of time in
+    pub rounds_played: u8,
+    /// Whether the player has played every round
+    pub finished: bool,
+    /// Total score; other players' scores are hidden until the challenge ends
+    pub score: Option<u32>,
+}
+
+/// Challenge details
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ChallengeDetails {
+    /// Game ID of the challenge
+    #[schema(example = "gam_FybH2oF9Xaw8")]
+    pub id: String,
+    /// Game status: active, finished, or abandoned
+    #[schema(example = "active")]
+    pub status: String,
+    /// User who sent the challenge
+    pub created_by: String,
+    /// When the challenge was sent
+    pub created_at: DateTime<Utc>,
+    /// When the challenge is abandoned if not finished
+    pub expires_at: DateTime<Utc>,
+    /// When the challenge ended
+    pub ended_at: Option<DateTime<Utc>>,
+    /// Challenge settings
+    pub settings: SettingsDto,
+    /// Players and their progress
+    pub players: Vec<ChallengePlayerInfo>,
+    /// Whether you still have rounds to play
+    pub your_turn: bool,
+    /// Full results, once every player has finished
+    pub results: Option<GameResultsResponse>,
+}
+
+// =============================================================================
+// State Loading Helpers
+// =============================================================================
+
+/// A challenge loaded from the database
+struct LoadedChallenge {
+    game: Game,
+    /// All rounds, ordered by round number
+    rounds: Vec<Round>,
+    /// Core state with each player's latest round
+    state: GameState,
+}
+
+impl LoadedChallenge {
+    /// Database round for a round number
+    fn round(&self, round_number: u8) -> Option<&Round> {
+        self.rounds.iter().find(|r| r.round_number == i16::from(round_number))
+    }
+
+    /// Rounds a player has guessed on or run out of time in
+    fn rounds_played(&self, user_id: &str, now: DateTime<Utc>) -> u8 {
+        let Some(round) = self.state.player_round(user_id) else {
+            return 0;
+        };
+        if round.has_guessed(user_id) || round.is_timed_out(now) {
+            round.round_number
+        } else {
+            round.round_number - 1
+        }
+    }
+}
+
+/// Load a challenge and build its per-player core state.
+async fn load_challenge(db: &dguesser_db::DbPool, id: &str) -> Result<LoadedChallenge, ApiError> {
+    let game = dguesser_db::games::get_game_by_id(db, id)
+        .await?
+        .filter(|g| g.mode == GameMode::Challenge)
+        .ok_or_else(|| ApiError::not_found("Challenge"))?;
+
+    let db_players = dguesser_db::games::get_players(db, id).await?;
+    let rounds = dguesser_db::games::get_rounds_for_game(db, id).await?;
+    let player_rounds = dguesser_db::challenges::get_player_rounds(db, id).await?;
+
+    let settings: GameSettings = serde_json::from_value(game.settings.clone()).unwrap_or_default();
+
+    let mut state = GameState::new(game.id.clone(), settings);
+    state.progression = RoundProgression::PerPlayer;
+    state.phase = match game.status {
+        GameStatus::Lobby => GamePhase::Lobby,
+        GameStatus::Active => GamePhase::Active,
+        GameStatus::Finished | GameStatus::Abandoned => GamePhase::Finished,
+    };
+    state.created_at = game.created_at;
+    state.started_at = game.started_at;
+
+    for p in &db_players {
+        let user = dguesser_db::users::get_by_id(db, &p.user_id).await?;
+        let mut player = PlayerState::new(
+            p.user_id.clone(),
+            user.as_ref().map(|u| u.display_name.clone()).unwrap_or_default(),
+            user.as_ref().and_then(|u| u.avatar_url.clone()),
+            p.is_host,
+        );
+        player.total_score = p.score_total.max(0) as u32;
+        state.players.insert(p.user_id.clone(), player);
+    }
+
+    // Player rounds are ordered by round number, so the last one wins
+    let mut latest: HashMap<&str, &PlayerRound> = HashMap::new();
+    for pr in &player_rounds {
+        latest.insert(&pr.user_id, pr);
+    }
+
+    for (user_id, pr) in latest {
+        let Some(db_round) = rounds.iter().find(|r| r.id == pr.round_id) else {
+            continue;
+        };
+
+        let mut round = RoundState::new(
+            db_round.round_number as u8,
+            db_round.location_lat,
+            db_round.location_lng,
+            db_round.panorama_id.clone(),
+            db_round.location_id.clone(),
+            db_round.heading,
+            db_round.provider.parse().unwrap_or_default(),
+            db_round.time_limit_ms.map(|t| t as u32),
+            pr.started_at,
+        );
+        round.country_code = db_round.country_code.clone();
+
+        if let Some(g) = dguesser_db::games::get_guess(db, &db_round.id, user_id).await? {
+            round.guesses.insert(
+                user_id.to_string(),
+                dguesser_core::game::Guess {
+                    user_id: g.user_id,
+                    lat: g.guess_lat,
+                    lng: g.guess_lng,
+                    distance_meters: g.distance_meters,
+                    score: g.score.max(0) as u32,
+                    time_taken_ms: g.time_taken_ms.map(|t| t as u32),
+                    reported_panorama_id: None,
+                    country_code: g.country_code,
+                    submitted_at: g.submitted_at,
+                },
+            );
+        }
+
+        state.round_number = state.round_number.max(round.round_number);
+        state.player_rounds.insert(user_id.to_string(), round);
+    }
+
+    Ok(LoadedChallenge { game, rounds, state })
+}
+
+/// Load a challenge the user plays in, recording rounds that ran out of time
+/// and finishing the challenge once every player is done.
+async fn load_for_player(
+    state: &AppState,
+    id: &str,
+    user_id: &str,
+) -> Result<LoadedChallenge, ApiError> {
+    let now = Utc::now();
+    let mut challenge = load_challenge(state.db(), id).await?;
+
+    if !challenge.state.players.contains_key(user_id) {
+        return Err(ApiError::forbidden("Not a player in this challenge"));
+    }
+
+    if challenge.state.phase != GamePhase::Active {
+        return Ok(challenge);
+    }
+
+    // Rounds that ran out of time count as played with no guess
+    let mut changed = false;
+    for (player_id, round) in &challenge.state.player_rounds {
+        if round.has_guessed(player_id) || !round.is_timed_out(now) {
+            continue;
+        }
+        let Some(db_round) = challenge.round(round.round_number) else {
+            continue;
+        };
+
+        dguesser_db::games::create_guess(
+            state.db(),
+            &db_round.id,
+            player_id,
+            NO_GUESS_LAT,
+            NO_GUESS_LNG,
+            NO_GUESS_DISTANCE_METERS,
+            NO_GUESS_SCORE,
+            None,
+            None,
+            None,
+        )
+        .await?;
+        dguesser_db::challenges::complete_player_round(state.db(), &db_round.id, player_id).await?;
+        changed = true;
+    }
+
+    if changed {
+        challenge = load_challenge(state.db(), id).await?;
+    }
+
+    let result = reduce(&challenge.state, GameCommand::Tick, now);
+    if ended(&result.events) {
+        finalize_challenge(state, &challenge, &result.state, user_id).await?;
+        challenge = load_challenge(state.db(), id).await?;
+    }
+
+    Ok(challenge)
+}
+
+/// Whether the reducer ended the game
+fn ended(events: &[GameEvent]) -> bool {
+    events.iter().any(|e| matches!(e, GameEvent::GameEnded { .. }))
+}
+
+/// Persist the end of a challenge and let the other players know.
+async fn finalize_challenge(
+    state: &AppState,
+    challenge: &LoadedChallenge,
+    game_state: &GameState,
+    finished_by: &str,
+) -> Result<(), ApiError> {
+    let db = state.db();
+    let game_id = &challenge.game.id;
+
+    dguesser_db::games::update_game_status(db, game_id, GameStatus::Finished).await?;
+    dguesser_db::games::set_final_rankings(db, game_id).await?;
+
+    let mut scores = Vec::new();
+    for player in game_state.players.values() {
+        let score = player.total_score as i32;
+        dguesser_db::users::update_stats(db, &player.user_id, score).await?;
+        scores.push(score);
+    }
+    if let Err(e) = dguesser_db::leaderboard::record_game(db, game_id).await {
+        tracing::warn!(game_id, error = %e, "Failed to record game on leaderboards");
+    }
+
+    // Map popularity is informational; never fail the challenge over it
+    let map_id = &game_state.settings.map_id;
+    if let Err(e) = dguesser_db::locations::record_map_plays(db, map_id, &scores).await {
+        tracing::warn!(game_id, map_id, error = %e, "Failed to record map play");
+    }
+
+    let name = display_name(game_state, finished_by);
+    for player in game_state.players.values().filter(|p| p.user_id != finished_by) {
+        notify(state, &player.user_id, Notification::challenge_results(&name, game_id)).await;
+    }
+
+    tracing::info!(game_id = %game_id, "Challenge finished");
+
+    Ok(())
+}
+
+/// Queue a push notification, logging failures.
+async fn notify(state: &AppState, user_id: &str, notification: Notification) {
+    if let Err(e) = dguesser_push::queue::notify(state.db(), user_id, &notification).await {
+        tracing::warn!(user_id, error = %e, "Failed to queue challenge notification");
+    }
+}
+
+fn display_name(state: &GameState, user_id: &str) -> String {
+    state.get_player(user_id).map(|p| p.display_name.clone()).unwrap_or_default()
+}
+
+fn expires_at(created_at: DateTime<Utc>) -> DateTime<Utc> {
+    created_at + Duration::days(i64::from(CHALLENGE_EXPIRY_DAYS))
+}
+
+fn location_info(round: &RoundState) -> LocationInfo {
+    LocationInfo {
+        lat: round.location_lat,
+        lng: round.location_lng,
+        panorama_id: round.panorama_id.clone(),
+        location_id: round.location_id.clone(),
+        provider: round.provider,
+    }
+}
+
+/// Convert a reducer error to an API error.
+fn reducer_error(result: &dguesser_core::game::ReducerResult) -> ApiError {
+    match result.get_error() {
+        Some(GameEvent::Error { code, message }) => match code.as_str() {
+            "NOT_IN_GAME" => ApiError::forbidden(message),
+            "ALREADY_GUESSED" | "ROUND_IN_PROGRESS" => ApiError::conflict(code, message),
+            _ => ApiError::bad_request(code, message),
+        },
+        _ => ApiError::internal().with_internal("Unknown reducer error"),
+    }
+}
+
+// =============================================================================
+// Route Handlers
+// =============================================================================
+
+/// Challenge a friend
+///
+/// Every round is chosen up front so both players get the same locations.
+#[utoipa::path(
+    post,
+    path = "/api/v1/challenges",
+    request_body = CreateChallengeRequest,
+    responses(
+        (status = 201, description = "Challenge created", body = CreateChallengeResponse),
+        (status = 400, description = "Invalid settings or too many active challenges"),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Not friends with the opponent"),
+    ),
+    tag = "challenges"
+)]
+pub async fn create_challenge(
+    State(state): State<AppState>,
+    org: CurrentOrg,
+    RequireAuth(auth): RequireAuth,
+    ValidatedJson(req): ValidatedJson<CreateChallengeRequest>,
+) -> Result<(StatusCode, Json<CreateChallengeResponse>), ApiError> {
+    if req.opponent_id == auth.user_id {
+        return Err(ApiError::bad_request("SELF_CHALLENGE", "You cannot challenge yourself"));
+    }
+
+    if !dguesser_db::friends::are_friends(state.db(), &auth.user_id, &req.opponent_id).await? {
+        return Err(ApiError::forbidden("You can only challenge friends"));
+    }
+
+    // Both players must belong to the organization the challenge is played in
+    org.require_member(&state, &auth.user_id).await?;
+    if let Some(org_id) = org.id()
+        && dguesser_db::organizations::get_role(state.db(), org_id, &req.opponent_id)
+            .await?
+            .is_none()
+    {
+        return Err(ApiError::forbidden("Your opponent is not a member of this organization"));
+    }
+
+    let active = dguesser_db::challenges::count_active(state.db(), &auth.user_id).await?;
+    if active >= MAX_ACTIVE_CHALLENGES {
+        return Err(ApiError::bad_request(
+            "TOO_MANY_CHALLENGES",
+            format!("You can have at most {MAX_ACTIVE_CHALLENGES} active challenges"),
+        ));
+    }
+
+    let settings = serde_json::json!({
+        "rounds": req.rounds.unwrap_or(5),
+        "time_limit_seconds": req.time_limit_seconds.unwrap_or(120),
+        "map_id": req.map_id.clone().unwrap_or_else(|| "world".to_string()),
+        "movement_allowed": req.movement_allowed.unwrap_or(true),
+        "zoom_allowed": req.zoom_allowed.unwrap_or(true),
+        "rotation_allowed": req.rotation_allowed.unwrap_or(true),
+    });
+
+    let mut core_settings: GameSettings =
+        serde_json::from_value(settings.clone()).unwrap_or_default();
+    if let Err(errors) = dguesser_core::game::validate_settings(&core_settings) {
+        return Err(ApiError::bad_request("INVALID_SETTINGS", errors.join(", ")));
+    }
+
+    let map_id = &core_settings.map_id;
+    let location_count = state.location_provider().get_location_count(map_id).await.unwrap_or(0);
+    let validation = validate_location_count(core_settings.rounds, location_count);
+    if let Some(error_msg) = validation.error_message() {
+        return Err(ApiError::bad_request("INSUFFICIENT_LOCATIONS", &error_msg));
+    }
+
+    // Challenges start right away, so the map's scoring curve is fixed now
+    core_settings.scoring = map_scoring(&state, map_id).await;
+    let map_id = &core_settings.map_id;
+
+    let game = dguesser_db::games::create_game(
+        state.db(),
+        GameMode::Challenge,
+        &auth.user_id,
+        JoinCodeChoice::None,
+        serde_json::to_value(&core_settings).unwrap_or(settings),
+        org.id(),
+    )
+    .await?;
+
+    dguesser_db::games::add_player(state.db(), &game.id, &auth.user_id, true).await?;
+    dguesser_db::games::add_player(state.db(), &game.id, &req.opponent_id, false).await?;
+
+    // Rounds are started per player, so they are created without a start time
+    let user_ids = vec![auth.user_id.clone(), req.opponent_id.clone()];
+    let time_limit_ms = (core_settings.time_limit_seconds > 0)
+        .then(|| (core_settings.time_limit_seconds * 1000) as i32);
+    let mut exclude_ids = Vec::new();
+    let mut previous_locations = Vec::new();
+    for round_number in 1..=core_settings.rounds {
+        let location =
+            select_location(&state, &game.id, &user_ids, map_id, &exclude_ids, &previous_locations)
+                .await;
+
+        dguesser_db::games::create_round(
+            state.db(),
+            &game.id,
+            i16::from(round_number),
+            location.lat,
+            location.lng,
+            location.panorama_id.as_deref(),
+            location.location_id.as_deref(),
+            location.heading,
+            location.provider,
+            location.country_code.as_deref(),
+            time_limit_ms,
+        )
+        .await?;
+
+        exclude_ids.extend(location.panorama_id);
+        previous_locations.push((location.lat, location.lng));
+    }
+
+    dguesser_db::games::update_game_status(state.db(), &game.id, GameStatus::Active).await?;
+
+    let challenger = dguesser_db::users::get_by_id(state.db(), &auth.user_id)
+        .await?
+        .map(|u| u.display_name)
+        .unwrap_or_default();
+    notify(&state, &req.opponent_id, Notification::challenge_turn(&challenger, &game.id)).await;
+
+    tracing::info!(
+        game_id = %game.id,
+        user_id = %auth.user_id,
+        opponent_id = %req.opponent_id,
+        "Challenge created"
+    );
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateChallengeResponse { id: game.id, expires_at: expires_at(game.created_at) }),
+    ))
+}
+
+/// List your active and recently finished challenges
+#[utoipa::path(
+    get,
+    path = "/api/v1/challenges",
+    responses(
+        (status = 200, description = "Challenges", body = ChallengeListResponse),
+        (status = 401, description = "Not authenticated"),
+    ),
+    tag = "challenges"
+)]
+pub async fn list_challenges(
+    State(state): State<AppState>,
+    RequireAuth(auth): RequireAuth,
+) -> Result<Json<ChallengeListResponse>, ApiError> {
+    let rows =
+        dguesser_db::challenges::list_for_user(state.db(), &auth.user_id, RECENT_DAYS, LIST_LIMIT)
+            .await?;
+
+    let challenges = rows
+        .into_iter()
+        .map(|row| {
+            let total_rounds =
+                row.settings.get("rounds").and_then(|v| v.as_u64()).unwrap_or(5) as u8;
+            let finished = row.status == GameStatus::Finished;
+            let rounds_played = row.rounds_played.min(i64::from(total_rounds)) as u8;
+            ChallengeListItem {
+                id: row.game_id,
+                status: row.status.to_string(),
+                created_by_you: row.created_by == auth.user_id,
+                created_at: row.created_at,
+                expires_at: expires_at(row.created_at),
+                ended_at: row.ended_at,
+                total_rounds,
+                opponent_id: row.opponent_id,
+                opponent_display_name: row.opponent_display_name,
+                opponent_avatar_url: row.opponent_avatar_url,
+                rounds_played,
+                opponent_rounds_played: row.opponent_rounds_played.min(i64::from(total_rounds))
+                    as u8,
+                your_turn: row.status == GameStatus::Active && rounds_played < total_rounds,
+                score: row.score.max(0) as u32,
+                opponent_score: finished.then_some(row.opponent_score.max(0) as u32),
+            }
+        })
+        .collect();
+
+    Ok(Json(ChallengeListResponse { challenges }))
+}
+
+/// Get a challenge with every player's progress
+///
+/// Results are included once every player has finished.
+#[utoipa::path(
+    get,
+    path = "/api/v1/challenges/{id}",
+    params(
+        ("id" = String, Path, description = "Game ID of the challenge")
+    ),
+    responses(
+        (status = 200, description = "Challenge details", body = ChallengeDetails),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Not a player in this challenge"),
+        (status = 404, description = "Challenge not found"),
+    ),
+    tag = "challenges"
+)]
+pub async fn get_challenge(
+    State(state): State<AppState>,
+    RequireAuth(auth): RequireAuth,
+    Path(id): Path<String>,
+) -> Result<Json<ChallengeDetails>, ApiError> {
+    let now = Utc::now();
+    let challenge = load_for_player(&state, &id, &auth.user_id).await?;
+    let finished = challenge.game.status == GameStatus::Finished;
+    let game_state = &challenge.state;
+
+    let mut players: Vec<_> = game_state
+        .players
+        .values()
+        .map(|p| ChallengePlayerInfo {
+            user_id: p.user_id.clone(),
+            display_name: p.display_name.clone(),
+            avatar_url: p.avatar_url.clone(),
+            rounds_played: challenge.rounds_played(&p.user_id, now),
+            finished: game_state.player_finished(&p.user_id, now),
+            score: (finished || p.user_id == auth.user_id).then_some(p.total_score),
+        })
+        .collect();
+    // Challenger first
+    players.sort_by_key(|p| p.user_id != challenge.game.created_by);
+
+    let results = if finished { Some(build_game_results(state.db(), &id).await?) } else { None };
+
+    let settings = &game_state.settings;
+    Ok(Json(ChallengeDetails {
+        id: challenge.game.id.clone(),
+        status: challenge.game.status.to_string(),
+        created_by: challenge.game.created_by.clone(),
+        created_at: challenge.game.created_at,
+        expires_at: expires_at(challenge.game.created_at),
+        ended_at: challenge.game.ended_at,
+        settings: SettingsDto {
+            rounds: settings.rounds,
+            time_limit_seconds: settings.time_limit_seconds,
+            map_id: settings.map_id.clone(),
+            movement_allowed: settings.movement_allowed,
+            zoom_allowed: settings.zoom_allowed,
+            rotation_allowed: settings.rotation_allowed,
+            time_budget_seconds: settings.time_budget_seconds,
+            reconnect_grace_seconds: settings.reconnect_grace_seconds,
+            disconnect_policy: settings.disconnect_policy,
+            reactions_enabled: settings.reactions_enabled,
+            allow_late_join: settings.allow_late_join,
+            rounds_plan: rounds_plan_payload(&settings.rounds_plan),
+            classroom: settings.classroom,
+            hints_enabled: settings.hints_enabled,
+            hint_cost: settings.hint_cost,
+        },
+        your_turn: game_state.phase == GamePhase::Active
+            && !game_state.player_finished(&auth.user_id, now),
+        players,
+        results,
+    }))
+}
+
+/// Get your current round in a challenge (for resuming)
+#[utoipa::path(
+    get,
+    path = "/api/v1/challenges/{id}/rounds/current",
+    params(
+        ("id" = String, Path, description = "Game ID of the challenge")
+    ),
+    responses(
+        (status = 200, description = "Your current round", body = CurrentRoundInfo),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Not a player in this challenge"),
+        (status = 404, description = "Challenge not found or no round started"),
+    ),
+    tag = "challenges"
+)]
+pub async fn get_current_round(
+    State(state): State<AppState>,
+    RequireAuth(auth): RequireAuth,
+    Path(id): Path<String>,
+) -> Result<Json<CurrentRoundInfo>, ApiError> {
+    let now = Utc::now();
+    let challenge = load_for_player(&state, &id, &auth.user_id).await?;
+
+    let round = challenge
+        .state
+        .player_round(&auth.user_id)
+        .ok_or_else(|| ApiError::not_found("No round started"))?;
+
+    let user_guess = round.guesses.get(&auth.user_id).map(|g| UserGuessInfo {
+        guess_lat: g.lat,
+        guess_lng: g.lng,
+        distance_meters: g.distance_meters,
+        score: g.score,
+    });
+
+    Ok(Json(CurrentRoundInfo {
+        round_number: round.round_number,
+        total_rounds: challenge.state.settings.rounds,
+        location: location_info(round),
+        started_at: round.started_at,
+        time_remaining_ms: round.time_remaining_ms(now),
+        has_guessed: user_guess.is_some(),
+        user_guess,
+    }))
+}
+
+/// Start your next round in a challenge
+#[utoipa::path(
+    post,
+    path = "/api/v1/challenges/{id}/rounds/next",
+    params(
+        ("id" = String, Path, description = "Game ID of the challenge")
+    ),
+    responses(
+        (status = 200, description = "Round started", body = RoundInfo),
+        (status = 400, description = "Challenge not active or all rounds played"),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Not a player in this challenge"),
+        (status = 404, description = "Challenge not found"),
+        (status = 409, description = "Current round not finished yet"),
+    ),
+    tag = "challenges"
+)]
+pub async fn next_round(
+    State(state): State<AppState>,
+    RequireAuth(auth): RequireAuth,
+    Path(id): Path<String>,
+) -> Result<Json<RoundInfo>, ApiError> {
+    let now = Utc::now();
+    let challenge = load_for_player(&state, &id, &auth.user_id).await?;
+
+    // Every player gets the same location for a round number
+    let round_number =
+        challenge.state.player_round(&auth.user_id).map_or(1, |r| r.round_number + 1);
+    let db_round = challenge.round(round_number);
+    let location = db_round
+        .map(|r| {
+            dguesser_core::game::LocationData::full(
+                r.location_lat,
+                r.location_lng,
+                r.panorama_id.clone(),
+                r.location_id.clone(),
+                r.heading,
+                r.provider.parse().unwrap_or_default(),
+            )
+            .with_country_code(r.country_code.clone())
+        })
+        .unwrap_or_else(|| dguesser_core::game::LocationData::new(0.0, 0.0, None));
+
+    let result = reduce(
+        &challenge.state,
+        GameCommand::StartPlayerRound { user_id: auth.user_id.clone(), location },
+        now,
+    );
+    if result.has_error() {
+        return Err(reducer_error(&result));
+    }
+
+    let db_round = db_round.ok_or_else(|| ApiError::internal().with_internal("Round not found"))?;
+    let started =
+        dguesser_db::challenges::start_player_round(state.db(), &db_round.id, &auth.user_id)
+            .await
+            .map_err(|e| match &e {
+                // Lost a race with a concurrent request for the same round
+                sqlx::Error::Database(db) if db.is_unique_violation() => {
+                    ApiError::conflict("ROUND_IN_PROGRESS", "Round already started")
+                }
+                _ => e.into(),
+            })?;
+
+    let round = result
+        .state
+        .player_round(&auth.user_id)
+        .ok_or_else(|| ApiError::internal().with_internal("Round not recorded"))?;
+
+    Ok(Json(RoundInfo {
+        round_number: round.round_number,
+        location: location_info(round),
+        started_at: started.started_at,
+        time_limit_ms: round.time_limit_ms,
+    }))
+}
+
+/// Submit a guess for your current round in a challenge
+#[utoipa::path(
+    post,
+    path = "/api/v1/challenges/{id}/rounds/{round_number}/guess",
+    params(
+        ("id" = String, Path, description = "Game ID of the challenge"),
+        ("round_number" = u8, Path, description = "Round number (1-based)")
+    ),
+    request_body = SubmitGuessRequest,
+    responses(
+        (status = 200, description = "Guess result", body = GuessResultResponse),
+        (status = 400, description = "Wrong round, time expired, or panorama mismatch in a no-move challenge"),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Not a player in this challenge"),
+        (status = 404, description = "Challenge not found"),
+        (status = 409, description = "Already submitted guess"),
+    ),
+    tag = "challenges"
+)]
+pub async fn submit_guess(
+    State(state): State<AppState>,
+    RequireAuth(auth): RequireAuth,
+    Path((id, round_number)): Path<(String, u8)>,
+    ValidatedJson(req): ValidatedJson<SubmitGuessRequest>,
+) -> Result<Json<GuessResultResponse>, ApiError> {
+    let now = Utc::now();
+    let challenge = load_for_player(&state, &id, &auth.user_id).await?;
+
+    let current =
+        challenge.state.player_round(&auth.user_id).ok_or_else(|| ApiError::not_found("Round"))?;
+    if current.round_number != round_number {
+        return Err(ApiError::bad_request(
+            "WRONG_ROUND",
+            "Round number does not match your current round",
+        ));
+    }
+    let db_round = challenge
+        .round(round_number)
+        .ok_or_else(|| ApiError::internal().with_internal("Round not found"))?;
+
+    let result = reduce(
+        &challenge.state,
+        GameCommand::SubmitGuess {
+            user_id: auth.user_id.clone(),
+            lat: req.lat,
+            lng: req.lng,
+            time_taken_ms: req.time_taken_ms,
+            reported_panorama_id: req.panorama_id.clone(),
+            country_code: req.country_code.clone(),
+        },
+        now,
+    );
+
+    if result.has_error() {
+        if result.get_error().and_then(|e| e.error_code()) == Some("PANORAMA_MISMATCH") {
+            record_panorama_mismatch(
+                &state,
+                &auth.user_id,
+                &id,
+                Some(&db_round.id),
+                current.panorama_id.as_deref(),
+                req.panorama_id.as_deref(),
+            )
+            .await;
+        }
+        return Err(reducer_error(&result));
+    }
+
+    let guess = result
+        .state
+        .player_round(&auth.user_id)
+        .and_then(|r| r.guesses.get(&auth.user_id))
+        .ok_or_else(|| ApiError::internal().with_internal("Guess not recorded"))?;
+
+    dguesser_db::games::create_guess(
+        state.db(),
+        &db_round.id,
+        &auth.user_id,
+        req.lat,
+        req.lng,
+        guess.distance_meters,
+        guess.score as i32,
+        req.time_taken_ms.map(|t| t as i32),
+        req.panorama_id.as_deref(),
+        req.country_code.as_deref(),
+    )
+    .await?;
+    dguesser_db::challenges::complete_player_round(state.db(), &db_round.id, &auth.user_id).await?;
+    let total_score =
+        dguesser_db::games::update_player_score(state.db(), &id, &auth.user_id, guess.score as i32)
+            .await?;
+
+    if ended(&result.events) {
+        finalize_challenge(&state, &challenge, &result.state, &auth.user_id).await?;
+    } else if result.state.player_finished(&auth.user_id, now) {
+        // Let everyone still playing know it is their turn
+        let name = display_name(&result.state, &auth.user_id);
+        for player in result.state.players.values() {
+            if !result.state.player_finished(&player.user_id, now) {
+                notify(&state, &player.user_id, Notification::challenge_turn(&name, &id)).await;
+            }
+        }
+    }
+
+    let stats = location_guess_stats(&state, current.location_id.as_deref()).await;
+
+    Ok(Json(GuessResultResponse {
+        distance_meters: guess.distance_meters,
+        score: guess.score,
+        total_score: total_score.max(0) as u32,
+        correct_location: location_info(current),
+        percentile: stats.as_ref().and_then(|s| s.percentile_beaten(guess.distance_meters)),
+        global_stats: stats.as_ref().map(global_guess_stats),
+        correct_country_code: None,
+        streak: None,
+        next_round: None,
+        score_signature: None,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expires_at() {
+        let created_at = DateTime::parse_from_rfc3339("2026-05-01T12:00:00Z").unwrap().to_utc();
+        assert_eq!(expires_at(created_at).to_rfc3339(), "2026-05-08T12:00:00+00:00");
+    }
+
+    #[test]
+    fn test_reducer_error_maps_status() {
+        let state = GameState::new("gam_test".to_string(), GameSettings::default());
+        let result = reduce(
+            &state,
+            GameCommand::StartPlayerRound {
+                user_id: "usr_1".to_string(),
+                location: dguesser_core::game::LocationData::new(0.0, 0.0, None),
+            },
+            Utc::now(),
+        );
+        assert_eq!(reducer_error(&result).status, StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,342 @@
+//! Country quiz warm-up routes
+//!
+//! Players waiting in a multiplayer lobby can play a quick quiz: identify a
+//! country from its flag or its outline box, picking from four choices.
+//! Questions and scores live in Redis only for as long as the lobby does;
+//! nothing is written to the database. Questions and answers are broadcast
+//! to the game room so everyone in the lobby plays along.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use chrono::Utc;
+use dguesser_auth::AuthUser;
+use dguesser_core::geo::countries;
+use dguesser_core::geo::quiz::{self, QUIZ_ANSWER_WINDOW_MS, QuizPrompt};
+use dguesser_db::{GameMode, GameStatus};
+use dguesser_protocol::socket::events::server as events;
+use dguesser_protocol::socket::payloads::{
+    QuizAnsweredPayload, QuizBounds, QuizChoice, QuizQuestionPayload, QuizScore,
+};
+use dguesser_redis::RedisConnection;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{error::ApiError, socket, state::AppState};
+
+/// How long quiz state outlives the last question, in seconds
+const QUIZ_TTL_SECS: i64 = 60 * 60;
+
+// =============================================================================
+// DTOs
+// =============================================================================
+
+/// Answer a quiz question
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct QuizAnswerRequest {
+    /// Question being answered
+    #[schema(example = "q_1715000000000")]
+    pub question_id: String,
+    /// Chosen country code
+    #[schema(example = "SE")]
+    pub answer: String,
+}
+
+/// Result of an answer, with the correct country
+#[derive(Debug, Serialize, ToSchema)]
+pub struct QuizAnswerResponse {
+    pub correct: bool,
+    /// Points the answer earned
+    #[schema(example = 150)]
+    pub points: u32,
+    /// The correct country
+    pub answer: QuizChoice,
+    /// Lobby scores, highest first
+    pub scores: Vec<QuizScore>,
+}
+
+/// Current quiz state for a lobby
+#[derive(Debug, Serialize, ToSchema)]
+pub struct QuizStateResponse {
+    /// Latest question, if one was asked
+    pub question: Option<QuizQuestionPayload>,
+    /// The latest question's answer, once it has expired
+    pub answer: Option<QuizChoice>,
+    /// Lobby scores, highest first
+    pub scores: Vec<QuizScore>,
+}
+
+/// A question as stored in Redis, including its answer
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredQuestion {
+    id: String,
+    prompt: QuizPrompt,
+    answer: String,
+    choices: Vec<String>,
+    asked_at: i64,
+    expires_at: i64,
+}
+
+// =============================================================================
+// Handlers
+// =============================================================================
+
+/// Ask a new warm-up quiz question (players in the lobby)
+#[utoipa::path(
+    post,
+    path = "/api/v1/games/{id}/quiz/question",
+    params(
+        ("id" = String, Path, description = "Game ID")
+    ),
+    responses(
+        (status = 201, description = "Question asked and broadcast", body = QuizQuestionPayload),
+        (status = 400, description = "Game not in lobby"),
+        (status = 403, description = "Not a player in the game"),
+        (status = 404, description = "Game not found"),
+        (status = 409, description = "A question is still open"),
+    ),
+    tag = "games"
+)]
+pub async fn ask_question(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(id): Path<String>,
+) -> Result<(StatusCode, Json<QuizQuestionPayload>), ApiError> {
+    lobby_player(&state, &id, &auth.user_id).await?;
+
+    let now = Utc::now().timestamp_millis();
+    let mut conn = state.redis().clone();
+
+    // Only one question is open at a time; the lock expires with it
+    let opened: Option<String> = redis::cmd("SET")
+        .arg(open_key(&id))
+        .arg(&auth.user_id)
+        .arg("NX")
+        .arg("PX")
+        .arg(QUIZ_ANSWER_WINDOW_MS)
+        .query_async(&mut conn)
+        .await?;
+    if opened.is_none() {
+        return Err(ApiError::conflict("QUIZ_QUESTION_OPEN", "The current question is still open"));
+    }
+
+    let question = quiz::generate_question(&mut rand::rng());
+    let stored = StoredQuestion {
+        id: format!("q_{now}"),
+        prompt: question.prompt,
+        answer: question.answer.code.to_string(),
+        choices: question.choices.iter().map(|c| c.code.to_string()).collect(),
+        asked_at: now,
+        expires_at: now + QUIZ_ANSWER_WINDOW_MS,
+    };
+    let json = serde_json::to_string(&stored)
+        .map_err(|e| ApiError::internal().with_internal(e.to_string()))?;
+    conn.set_ex::<_, _, ()>(question_key(&id), json, QUIZ_TTL_SECS as u64).await?;
+
+    let payload = question_payload(&stored);
+    if let Err(e) = socket::emit_to_room(state.redis(), &id, events::QUIZ_QUESTION, &payload).await
+    {
+        tracing::warn!(game_id = %id, error = %e, "Failed to broadcast quiz question");
+    }
+
+    Ok((StatusCode::CREATED, Json(payload)))
+}
+
+/// Answer the open warm-up quiz question
+#[utoipa::path(
+    post,
+    path = "/api/v1/games/{id}/quiz/answer",
+    params(
+        ("id" = String, Path, description = "Game ID")
+    ),
+    request_body = QuizAnswerRequest,
+    responses(
+        (status = 200, description = "Answer scored and broadcast", body = QuizAnswerResponse),
+        (status = 400, description = "Game not in lobby, question closed or invalid choice"),
+        (status = 403, description = "Not a player in the game"),
+        (status = 404, description = "Game or question not found"),
+        (status = 409, description = "Already answered"),
+    ),
+    tag = "games"
+)]
+pub async fn answer_question(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(id): Path<String>,
+    Json(req): Json<QuizAnswerRequest>,
+) -> Result<Json<QuizAnswerResponse>, ApiError> {
+    lobby_player(&state, &id, &auth.user_id).await?;
+
+    let mut conn = state.redis().clone();
+    let question = load_question(&mut conn, &id)
+        .await?
+        .filter(|q| q.id == req.question_id)
+        .ok_or_else(|| ApiError::not_found("Question"))?;
+
+    let now = Utc::now().timestamp_millis();
+    if now > question.expires_at {
+        return Err(ApiError::bad_request("QUIZ_QUESTION_CLOSED", "The question has expired"));
+    }
+    let answer = req.answer.trim().to_ascii_uppercase();
+    if !question.choices.contains(&answer) {
+        return Err(ApiError::bad_request("INVALID_CHOICE", "Answer is not one of the choices"));
+    }
+
+    let answered_key = answered_key(&id, &question.id);
+    let added: i64 = conn.sadd(&answered_key, &auth.user_id).await?;
+    if added == 0 {
+        return Err(ApiError::conflict("ALREADY_ANSWERED", "You already answered this question"));
+    }
+    conn.expire::<_, ()>(&answered_key, QUIZ_TTL_SECS).await?;
+
+    let correct = answer == question.answer;
+    let points = quiz::answer_points(correct, now - question.asked_at);
+    let scores_key = scores_key(&id);
+    conn.hincr::<_, _, _, ()>(&scores_key, &auth.user_id, points).await?;
+    conn.expire::<_, ()>(&scores_key, QUIZ_TTL_SECS).await?;
+    let scores = load_scores(&mut conn, &id).await?;
+
+    let payload = QuizAnsweredPayload {
+        question_id: question.id.clone(),
+        user_id: auth.user_id.clone(),
+        correct,
+        points,
+        scores: scores.clone(),
+    };
+    if let Err(e) = socket::emit_to_room(state.redis(), &id, events::QUIZ_ANSWERED, &payload).await
+    {
+        tracing::warn!(game_id = %id, error = %e, "Failed to broadcast quiz answer");
+    }
+
+    Ok(Json(QuizAnswerResponse { correct, points, answer: choice(&question.answer), scores }))
+}
+
+/// Get the lobby's warm-up quiz state
+#[utoipa::path(
+    get,
+    path = "/api/v1/games/{id}/quiz",
+    params(
+        ("id" = String, Path, description = "Game ID")
+    ),
+    responses(
+        (status = 200, description = "Quiz state", body = QuizStateResponse),
+        (status = 400, description = "Game not in lobby"),
+        (status = 403, description = "Not a player in the game"),
+        (status = 404, description = "Game not found"),
+    ),
+    tag = "games"
+)]
+pub async fn get_quiz(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(id): Path<String>,
+) -> Result<Json<QuizStateResponse>, ApiError> {
+    lobby_player(&state, &id, &auth.user_id).await?;
+
+    let mut conn = state.redis().clone();
+    let question = load_question(&mut conn, &id).await?;
+    let scores = load_scores(&mut conn, &id).await?;
+
+    let now = Utc::now().timestamp_millis();
+    let answer = question.as_ref().filter(|q| now > q.expires_at).map(|q| choice(&q.answer));
+    Ok(Json(QuizStateResponse {
+        question: question.as_ref().map(question_payload),
+        answer,
+        scores,
+    }))
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn question_key(game_id: &str) -> String {
+    format!("quiz:{game_id}:question")
+}
+
+fn open_key(game_id: &str) -> String {
+    format!("quiz:{game_id}:open")
+}
+
+fn answered_key(game_id: &str, question_id: &str) -> String {
+    format!("quiz:{game_id}:answered:{question_id}")
+}
+
+fn scores_key(game_id: &str) -> String {
+    format!("quiz:{game_id}:scores")
+}
+
+/// Check the game is a multiplayer lobby the user is in.
+async fn lobby_player(state: &AppState, game_id: &str, user_id: &str) -> Result<(), ApiError> {
+    let game = dguesser_db::games::get_game_by_id(state.db(), game_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Game"))?;
+    if game.mode != GameMode::Multiplayer || game.status != GameStatus::Lobby {
+        return Err(ApiError::bad_request(
+            "GAME_NOT_IN_LOBBY",
+            "The quiz is only available in a multiplayer lobby",
+        ));
+    }
+    if !dguesser_db::games::is_player_in_game(state.db(), game_id, user_id).await? {
+        return Err(ApiError::forbidden("Not a player in this game"));
+    }
+    Ok(())
+}
+
+async fn load_question(
+    conn: &mut RedisConnection,
+    game_id: &str,
+) -> Result<Option<StoredQuestion>, ApiError> {
+    let json: Option<String> = conn.get(question_key(game_id)).await?;
+    Ok(json.and_then(|json| {
+        serde_json::from_str(&json)
+            .inspect_err(|e| tracing::warn!(game_id, error = %e, "Invalid stored quiz question"))
+            .ok()
+    }))
+}
+
+/// Scores for a lobby, highest first.
+async fn load_scores(
+    conn: &mut RedisConnection,
+    game_id: &str,
+) -> Result<Vec<QuizScore>, ApiError> {
+    let raw: Vec<(String, u32)> = conn.hgetall(scores_key(game_id)).await?;
+    let mut scores: Vec<QuizScore> =
+        raw.into_iter().map(|(user_id, score)| QuizScore { user_id, score }).collect();
+    scores.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.user_id.cmp(&b.user_id)));
+    Ok(scores)
+}
+
+fn choice(code: &str) -> QuizChoice {
+    QuizChoice {
+        code: code.to_string(),
+        name: countries::lookup(code).map(|c| c.name).unwrap_or(code).to_string(),
+    }
+}
+
+fn question_payload(question: &StoredQuestion) -> QuizQuestionPayload {
+    let country = countries::lookup(&question.answer);
+    let (flag, bounds) = match question.prompt {
+        QuizPrompt::Flag => (country.map(|c| c.flag()), None),
+        QuizPrompt::Bounds => (
+            None,
+            country.map(|c| QuizBounds {
+                min_lat: c.bounds.min_lat,
+                min_lng: c.bounds.min_lng,
+                max_lat: c.bounds.max_lat,
+                max_lng: c.bounds.max_lng,
+            }),
+        ),
+    };
+    QuizQuestionPayload {
+        question_id: question.id.clone(),
+        prompt: question.prompt.as_str().to_string(),
+        flag,
+        bounds,
+        choices: question.choices.iter().map(|code| choice(code)).collect(),
+        expires_at: question.expires_at,
+    }
+}
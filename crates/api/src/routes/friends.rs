@@ -0,0 +1,391 @@
+//! Friend routes
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{delete, get, post},
+};
+use std::collections::{HashMap, HashSet};
+
+use dguesser_auth::RequireAuth;
+use dguesser_db::UserKind;
+use dguesser_db::friends::FriendUser;
+use dguesser_protocol::api::friends::{
+    FriendItem, FriendRequestItem, FriendRequestsResponse, FriendsListResponse, FriendshipStatus,
+    SendFriendRequest, SendFriendRequestResponse,
+};
+use dguesser_protocol::socket::events;
+use dguesser_protocol::socket::payloads::FriendRequestPayload;
+use dguesser_protocol::socket::presence::{PresenceInfo, PresenceVisibility};
+
+use crate::error::ApiError;
+use crate::presence::read_presence;
+use crate::socket;
+use crate::state::AppState;
+
+/// Maximum friends plus pending requests per user
+const MAX_FRIENDS: usize = 200;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_friends))
+        .route("/{user_id}", delete(remove_friend))
+        .route("/requests", get(list_requests).post(send_request))
+        .route("/requests/{user_id}/accept", post(accept_request))
+        .route("/requests/{user_id}/decline", post(decline_request))
+}
+
+/// Who a friend request is addressed to
+#[derive(Debug, PartialEq, Eq)]
+enum RequestTarget<'a> {
+    UserId(&'a str),
+    Username(&'a str),
+}
+
+/// List your friends with their online status and current activity
+#[utoipa::path(
+    get,
+    path = "/api/v1/friends",
+    responses(
+        (status = 200, description = "Friend list", body = FriendsListResponse),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Guests cannot have friends"),
+    ),
+    tag = "friends"
+)]
+pub async fn list_friends(
+    State(state): State<AppState>,
+    RequireAuth(auth): RequireAuth,
+) -> Result<Json<FriendsListResponse>, ApiError> {
+    let friends = dguesser_db::friends::list_friends(state.db(), &auth.user_id).await?;
+
+    let presence = friend_presence(&state, &friends).await?;
+
+    let friends = friends
+        .into_iter()
+        .zip(presence)
+        .map(|(f, presence)| FriendItem {
+            user_id: f.user_id,
+            username: f.username,
+            display_name: f.display_name,
+            avatar_url: f.avatar_url,
+            online: presence.is_online(),
+            presence,
+            since: f.since,
+        })
+        .collect();
+
+    Ok(Json(FriendsListResponse { friends }))
+}
+
+/// List pending friend requests you have sent and received
+#[utoipa::path(
+    get,
+    path = "/api/v1/friends/requests",
+    responses(
+        (status = 200, description = "Pending requests", body = FriendRequestsResponse),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Guests cannot have friends"),
+    ),
+    tag = "friends"
+)]
+pub async fn list_requests(
+    State(state): State<AppState>,
+    RequireAuth(auth): RequireAuth,
+) -> Result<Json<FriendRequestsResponse>, ApiError> {
+    let incoming = dguesser_db::friends::list_incoming_requests(state.db(), &auth.user_id).await?;
+    let outgoing = dguesser_db::friends::list_outgoing_requests(state.db(), &auth.user_id).await?;
+
+    Ok(Json(FriendRequestsResponse {
+        incoming: incoming.into_iter().map(request_item).collect(),
+        outgoing: outgoing.into_iter().map(request_item).collect(),
+    }))
+}
+
+/// Send a friend request
+///
+/// If the other user already sent you a request, it is accepted instead.
+#[utoipa::path(
+    post,
+    path = "/api/v1/friends/requests",
+    request_body = SendFriendRequest,
+    responses(
+        (status = 201, description = "Request sent or accepted", body = SendFriendRequestResponse),
+        (status = 400, description = "Invalid request"),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Guests cannot have friends"),
+        (status = 404, description = "User not found"),
+        (status = 409, description = "Already friends or request already sent"),
+    ),
+    tag = "friends"
+)]
+pub async fn send_request(
+    State(state): State<AppState>,
+    RequireAuth(auth): RequireAuth,
+    Json(req): Json<SendFriendRequest>,
+) -> Result<(StatusCode, Json<SendFriendRequestResponse>), ApiError> {
+    let target = match request_target(&req)? {
+        RequestTarget::UserId(id) => dguesser_db::users::get_by_id(state.db(), id).await?,
+        RequestTarget::Username(name) => {
+            dguesser_db::users::get_by_username(state.db(), &name.to_lowercase()).await?
+        }
+    }
+    .filter(|u| u.deleted_at.is_none())
+    .ok_or_else(|| ApiError::not_found("User"))?;
+
+    if target.id == auth.user_id {
+        return Err(ApiError::bad_request("SELF_REQUEST", "You cannot befriend yourself"));
+    }
+    if target.kind == UserKind::Guest {
+        return Err(ApiError::bad_request("GUEST_USER", "Guests cannot have friends"));
+    }
+
+    let status =
+        match dguesser_db::friends::get_friendship(state.db(), &auth.user_id, &target.id).await? {
+            Some(f) if f.is_accepted() => {
+                return Err(ApiError::conflict("ALREADY_FRIENDS", "You are already friends"));
+            }
+            Some(f) if f.requester_id == auth.user_id => {
+                return Err(ApiError::conflict("REQUEST_EXISTS", "Friend request already sent"));
+            }
+            Some(_) => {
+                dguesser_db::friends::accept_request(state.db(), &target.id, &auth.user_id)
+                    .await?
+                    .ok_or_else(|| ApiError::not_found("Friend request"))?;
+                FriendshipStatus::Accepted
+            }
+            None => {
+                ensure_below_limit(&state, &auth.user_id).await?;
+                dguesser_db::friends::create_request(state.db(), &auth.user_id, &target.id)
+                    .await
+                    .map_err(|e| match &e {
+                    // Lost a race with a concurrent request between the two users
+                    sqlx::Error::Database(db) if db.is_unique_violation() => {
+                        ApiError::conflict("REQUEST_EXISTS", "Friend request already sent")
+                    }
+                    _ => e.into(),
+                })?;
+                FriendshipStatus::Pending
+            }
+        };
+
+    let event = match status {
+        FriendshipStatus::Pending => events::friend::REQUEST_RECEIVED,
+        FriendshipStatus::Accepted => events::friend::REQUEST_ACCEPTED,
+    };
+    notify_user(&state, &auth.user_id, &target.id, event).await;
+
+    tracing::info!(
+        user_id = %auth.user_id,
+        target_id = %target.id,
+        status = ?status,
+        "Friend request sent"
+    );
+
+    Ok((StatusCode::CREATED, Json(SendFriendRequestResponse { user_id: target.id, status })))
+}
+
+/// Accept a friend request
+#[utoipa::path(
+    post,
+    path = "/api/v1/friends/requests/{user_id}/accept",
+    params(
+        ("user_id" = String, Path, description = "User who sent the request")
+    ),
+    responses(
+        (status = 204, description = "Request accepted"),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Guests cannot have friends"),
+        (status = 404, description = "Friend request not found"),
+    ),
+    tag = "friends"
+)]
+pub async fn accept_request(
+    State(state): State<AppState>,
+    RequireAuth(auth): RequireAuth,
+    Path(user_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    dguesser_db::friends::accept_request(state.db(), &user_id, &auth.user_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Friend request"))?;
+
+    notify_user(&state, &auth.user_id, &user_id, events::friend::REQUEST_ACCEPTED).await;
+
+    tracing::info!(user_id = %auth.user_id, friend_id = %user_id, "Friend request accepted");
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Decline a friend request sent to you
+#[utoipa::path(
+    post,
+    path = "/api/v1/friends/requests/{user_id}/decline",
+    params(
+        ("user_id" = String, Path, description = "User who sent the request")
+    ),
+    responses(
+        (status = 204, description = "Request declined"),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Guests cannot have friends"),
+        (status = 404, description = "Friend request not found"),
+    ),
+    tag = "friends"
+)]
+pub async fn decline_request(
+    State(state): State<AppState>,
+    RequireAuth(auth): RequireAuth,
+    Path(user_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    match dguesser_db::friends::get_friendship(state.db(), &auth.user_id, &user_id).await? {
+        Some(f) if !f.is_accepted() && f.addressee_id == auth.user_id => {
+            dguesser_db::friends::delete_friendship(state.db(), &auth.user_id, &user_id).await?;
+            Ok(StatusCode::NO_CONTENT)
+        }
+        _ => Err(ApiError::not_found("Friend request")),
+    }
+}
+
+/// Remove a friend, or cancel a friend request you sent
+#[utoipa::path(
+    delete,
+    path = "/api/v1/friends/{user_id}",
+    params(
+        ("user_id" = String, Path, description = "Friend's user ID")
+    ),
+    responses(
+        (status = 204, description = "Friend removed"),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Guests cannot have friends"),
+        (status = 404, description = "Friend not found"),
+    ),
+    tag = "friends"
+)]
+pub async fn remove_friend(
+    State(state): State<AppState>,
+    RequireAuth(auth): RequireAuth,
+    Path(user_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    match dguesser_db::friends::get_friendship(state.db(), &auth.user_id, &user_id).await? {
+        Some(f) if f.is_accepted() || f.requester_id == auth.user_id => {
+            dguesser_db::friends::delete_friendship(state.db(), &auth.user_id, &user_id).await?;
+            tracing::info!(user_id = %auth.user_id, friend_id = %user_id, "Friend removed");
+            Ok(StatusCode::NO_CONTENT)
+        }
+        _ => Err(ApiError::not_found("Friend")),
+    }
+}
+
+/// Pick the user a request is addressed to; the user ID wins if both are set.
+fn request_target(req: &SendFriendRequest) -> Result<RequestTarget<'_>, ApiError> {
+    fn non_empty(s: &Option<String>) -> Option<&str> {
+        s.as_deref().map(str::trim).filter(|s| !s.is_empty())
+    }
+
+    if let Some(id) = non_empty(&req.user_id) {
+        Ok(RequestTarget::UserId(id))
+    } else if let Some(name) = non_empty(&req.username) {
+        Ok(RequestTarget::Username(name.trim_start_matches('@')))
+    } else {
+        Err(ApiError::bad_request("MISSING_USER", "Provide a user_id or username"))
+    }
+}
+
+/// Reject new requests once a user has too many friends and pending requests.
+async fn ensure_below_limit(state: &AppState, user_id: &str) -> Result<(), ApiError> {
+    let friends = dguesser_db::friends::list_friend_ids(state.db(), user_id).await?;
+    let outgoing = dguesser_db::friends::list_outgoing_requests(state.db(), user_id).await?;
+    if friends.len() + outgoing.len() >= MAX_FRIENDS {
+        return Err(ApiError::conflict(
+            "TOO_MANY_FRIENDS",
+            format!("You can have at most {MAX_FRIENDS} friends and pending requests"),
+        ));
+    }
+    Ok(())
+}
+
+/// Presence of each friend, in the order given. Friends who hide their
+/// presence from everyone appear offline.
+async fn friend_presence(
+    state: &AppState,
+    friends: &[FriendUser],
+) -> Result<Vec<PresenceInfo>, ApiError> {
+    let ids: Vec<String> = friends.iter().map(|f| f.user_id.clone()).collect();
+    let hidden: HashSet<String> =
+        dguesser_db::profiles::get_presence_visibilities(state.db(), &ids)
+            .await?
+            .into_iter()
+            .filter(|(_, visibility)| !PresenceVisibility::parse(visibility).allows(false, true))
+            .map(|(user_id, _)| user_id)
+            .collect();
+
+    let visible: Vec<&str> =
+        ids.iter().map(String::as_str).filter(|id| !hidden.contains(*id)).collect();
+    let mut presence = match read_presence(state.redis(), &visible).await {
+        Ok(presence) => visible.into_iter().zip(presence).collect(),
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to read friend presence");
+            HashMap::new()
+        }
+    };
+
+    Ok(ids.iter().map(|id| presence.remove(id.as_str()).unwrap_or_default()).collect())
+}
+
+/// Tell a user about a friend request event from `from_user_id`.
+async fn notify_user(state: &AppState, from_user_id: &str, to_user_id: &str, event: &str) {
+    let from = match dguesser_db::users::get_by_id(state.db(), from_user_id).await {
+        Ok(Some(user)) => user,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to load user for friend notification");
+            return;
+        }
+    };
+
+    let payload = FriendRequestPayload {
+        user_id: from.id,
+        display_name: from.display_name,
+        avatar_url: from.avatar_url,
+    };
+    // Fire and forget, the request itself already succeeded
+    if let Err(e) = socket::emit_to_room(state.redis(), to_user_id, event, &payload).await {
+        tracing::warn!(error = %e, user_id = %to_user_id, event, "Failed to notify user");
+    }
+}
+
+fn request_item(user: FriendUser) -> FriendRequestItem {
+    FriendRequestItem {
+        user_id: user.user_id,
+        username: user.username,
+        display_name: user.display_name,
+        avatar_url: user.avatar_url,
+        sent_at: user.since,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn req(user_id: Option<&str>, username: Option<&str>) -> SendFriendRequest {
+        SendFriendRequest {
+            user_id: user_id.map(String::from),
+            username: username.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_request_target() {
+        assert_eq!(
+            request_target(&req(Some("usr_abc"), Some("bob"))).unwrap(),
+            RequestTarget::UserId("usr_abc")
+        );
+        assert_eq!(
+            request_target(&req(Some("  "), Some(" @bob "))).unwrap(),
+            RequestTarget::Username("bob")
+        );
+        assert!(request_target(&req(None, None)).is_err());
+        assert!(request_target(&req(None, Some(""))).is_err());
+    }
+}
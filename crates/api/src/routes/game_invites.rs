@@ -0,0 +1,359 @@
+//! Game invite routes
+//!
+//! Hosts create invite links for a multiplayer lobby as an alternative to
+//! its join code. Each invite expires, can be used a limited number of
+//! times, and can be revoked. Redeeming an invite returns the lobby like
+//! joining by code does; the player is added once their socket joins.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+};
+use chrono::{DateTime, Duration, Utc};
+use dguesser_auth::{AuthUser, MaybeAuthUser, credentials};
+use dguesser_db::game_invites::{GameInvite, RedeemError};
+use dguesser_db::{GameMode, GameStatus};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use super::games::{GameDetails, accepts_joins, join_session, lobby_join_response};
+use crate::{error::ApiError, extract::ValidatedJson, state::AppState};
+
+/// Maximum players one invite can admit
+const MAX_INVITE_USES: i32 = 50;
+/// Default invite lifetime, in minutes
+const DEFAULT_INVITE_MINUTES: i64 = 24 * 60;
+/// Maximum invite lifetime, in minutes
+const MAX_INVITE_MINUTES: i64 = 7 * 24 * 60;
+/// Maximum usable invites per game
+const MAX_ACTIVE_INVITES: i64 = 20;
+
+// =============================================================================
+// DTOs
+// =============================================================================
+
+/// Create invite request
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct CreateGameInviteRequest {
+    /// How many players can redeem the invite (1-50, default 1)
+    #[schema(example = 1)]
+    pub max_uses: Option<i32>,
+    /// Minutes until the invite expires (5-10080, default 1440)
+    #[schema(example = 60)]
+    pub expires_in_minutes: Option<i64>,
+}
+
+/// A game invite, without its token
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GameInviteItem {
+    /// Invite ID
+    pub id: i64,
+    /// Players the invite admits
+    pub max_uses: i32,
+    /// Players who redeemed it
+    pub uses: i32,
+    /// When the invite stops working
+    pub expires_at: DateTime<Utc>,
+    /// When the host revoked the invite
+    pub revoked_at: Option<DateTime<Utc>>,
+    /// Whether the invite can still be redeemed
+    pub usable: bool,
+    /// When the invite was created
+    pub created_at: DateTime<Utc>,
+}
+
+/// Create invite response. The token is only returned here.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreateGameInviteResponse {
+    #[serde(flatten)]
+    pub invite: GameInviteItem,
+    /// Secret invite token
+    pub token: String,
+    /// Link that redeems the invite
+    #[schema(example = "https://dguesser.com/invite/3q2-7wEjYfG_Hx1hT0vq8Q")]
+    pub url: String,
+}
+
+/// Game invites response
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GameInvitesResponse {
+    /// Invites for the game, newest first
+    pub invites: Vec<GameInviteItem>,
+}
+
+/// Redeem invite request
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct RedeemGameInviteRequest {
+    /// Invite token from the invite link
+    #[validate(length(min = 1, max = 128))]
+    pub token: String,
+}
+
+// =============================================================================
+// Handlers
+// =============================================================================
+
+/// Create an invite link for a lobby (host only)
+#[utoipa::path(
+    post,
+    path = "/api/v1/games/{id}/invites",
+    params(
+        ("id" = String, Path, description = "Game ID")
+    ),
+    request_body = CreateGameInviteRequest,
+    responses(
+        (status = 201, description = "Invite created", body = CreateGameInviteResponse),
+        (status = 400, description = "Invalid limits or game not in lobby"),
+        (status = 403, description = "Not the host"),
+        (status = 404, description = "Game not found"),
+        (status = 409, description = "Too many active invites"),
+    ),
+    tag = "games"
+)]
+pub async fn create_invite(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(id): Path<String>,
+    req: Option<Json<CreateGameInviteRequest>>,
+) -> Result<(StatusCode, Json<CreateGameInviteResponse>), ApiError> {
+    let req = req.map(|Json(req)| req).unwrap_or_default();
+    let (max_uses, lifetime) = invite_limits(&req)?;
+
+    let game = host_game(&state, &id, &auth.user_id).await?;
+    if game.status != GameStatus::Lobby {
+        return Err(ApiError::bad_request(
+            "GAME_NOT_IN_LOBBY",
+            "Invites can only be created in the lobby",
+        ));
+    }
+    if dguesser_db::game_invites::count_usable(state.db(), &id).await? >= MAX_ACTIVE_INVITES {
+        return Err(ApiError::conflict(
+            "TOO_MANY_INVITES",
+            format!("A game can have at most {MAX_ACTIVE_INVITES} active invites"),
+        ));
+    }
+
+    let (token, token_hash) = credentials::generate_token();
+    let invite = dguesser_db::game_invites::create(
+        state.db(),
+        &id,
+        &token_hash,
+        &auth.user_id,
+        max_uses,
+        Utc::now() + lifetime,
+    )
+    .await?;
+
+    tracing::info!(game_id = %id, invite_id = invite.id, max_uses, "Created game invite");
+
+    let url = format!("{}/invite/{}", state.frontend_url().trim_end_matches('/'), token);
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateGameInviteResponse { invite: invite_item(invite), token, url }),
+    ))
+}
+
+/// List a game's invites (host only)
+#[utoipa::path(
+    get,
+    path = "/api/v1/games/{id}/invites",
+    params(
+        ("id" = String, Path, description = "Game ID")
+    ),
+    responses(
+        (status = 200, description = "Game invites", body = GameInvitesResponse),
+        (status = 403, description = "Not the host"),
+        (status = 404, description = "Game not found"),
+    ),
+    tag = "games"
+)]
+pub async fn list_invites(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(id): Path<String>,
+) -> Result<Json<GameInvitesResponse>, ApiError> {
+    host_game(&state, &id, &auth.user_id).await?;
+
+    let invites = dguesser_db::game_invites::list_for_game(state.db(), &id).await?;
+    Ok(Json(GameInvitesResponse { invites: invites.into_iter().map(invite_item).collect() }))
+}
+
+/// Revoke an invite (host only)
+#[utoipa::path(
+    delete,
+    path = "/api/v1/games/{id}/invites/{invite_id}",
+    params(
+        ("id" = String, Path, description = "Game ID"),
+        ("invite_id" = i64, Path, description = "Invite ID")
+    ),
+    responses(
+        (status = 204, description = "Invite revoked"),
+        (status = 403, description = "Not the host"),
+        (status = 404, description = "Game or invite not found"),
+    ),
+    tag = "games"
+)]
+pub async fn revoke_invite(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path((id, invite_id)): Path<(String, i64)>,
+) -> Result<StatusCode, ApiError> {
+    host_game(&state, &id, &auth.user_id).await?;
+
+    if !dguesser_db::game_invites::revoke(state.db(), &id, invite_id).await? {
+        return Err(ApiError::not_found("Invite"));
+    }
+
+    tracing::info!(game_id = %id, invite_id, "Revoked game invite");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Redeem an invite (lookup only, player added via Socket.IO)
+///
+/// Creates a guest session if the caller is not signed in. Redeeming the
+/// same invite again does not use it up further.
+#[utoipa::path(
+    post,
+    path = "/api/v1/games/invites/redeem",
+    request_body = RedeemGameInviteRequest,
+    responses(
+        (status = 200, description = "Game details", body = GameDetails),
+        (status = 201, description = "Game details (guest session created)", body = GameDetails),
+        (status = 400, description = "Game not joinable"),
+        (status = 404, description = "Invite not found"),
+        (status = 410, description = "Invite expired, revoked, or used up"),
+    ),
+    tag = "games"
+)]
+pub async fn redeem_invite(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    MaybeAuthUser(maybe_auth): MaybeAuthUser,
+    ValidatedJson(req): ValidatedJson<RedeemGameInviteRequest>,
+) -> Result<(StatusCode, HeaderMap, Json<GameDetails>), ApiError> {
+    let token_hash = credentials::hash_token(req.token.trim());
+
+    // Check the invite before creating a guest session for the caller
+    let invite = dguesser_db::game_invites::get_by_token_hash(state.db(), &token_hash)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Invite"))?;
+    if invite.revoked_at.is_some() || invite.expires_at <= Utc::now() {
+        return Err(invite_unusable());
+    }
+
+    let game = dguesser_db::games::get_game_by_id(state.db(), &invite.game_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Game"))?;
+    if !accepts_joins(&game) {
+        return Err(ApiError::bad_request(
+            "GAME_NOT_JOINABLE",
+            "This game has already started or ended",
+        ));
+    }
+
+    let session = join_session(&state, &headers, maybe_auth).await?;
+    match dguesser_db::game_invites::redeem(state.db(), &token_hash, &session.user_id).await? {
+        Ok(invite) => {
+            tracing::info!(
+                game_id = %invite.game_id,
+                invite_id = invite.id,
+                user_id = %session.user_id,
+                "Redeemed game invite"
+            );
+        }
+        Err(RedeemError::NotFound) => return Err(ApiError::not_found("Invite")),
+        Err(RedeemError::Unusable) => return Err(invite_unusable()),
+    }
+
+    lobby_join_response(&state, game, &session).await
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Load a multiplayer game the user hosts.
+async fn host_game(
+    state: &AppState,
+    game_id: &str,
+    user_id: &str,
+) -> Result<dguesser_db::games::Game, ApiError> {
+    let game = dguesser_db::games::get_game_by_id(state.db(), game_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Game"))?;
+    if game.mode != GameMode::Multiplayer {
+        return Err(ApiError::bad_request("INVALID_MODE", "Only multiplayer games have invites"));
+    }
+    let is_host = dguesser_db::games::get_players(state.db(), game_id)
+        .await?
+        .iter()
+        .any(|p| p.user_id == user_id && p.is_host);
+    if !is_host {
+        return Err(ApiError::forbidden("Only the host can manage invites"));
+    }
+    Ok(game)
+}
+
+/// Check the requested use limit and lifetime, applying defaults.
+fn invite_limits(req: &CreateGameInviteRequest) -> Result<(i32, Duration), ApiError> {
+    let max_uses = req.max_uses.unwrap_or(1);
+    if !(1..=MAX_INVITE_USES).contains(&max_uses) {
+        return Err(ApiError::bad_request(
+            "INVALID_MAX_USES",
+            format!("An invite can admit 1 to {MAX_INVITE_USES} players"),
+        ));
+    }
+    let minutes = req.expires_in_minutes.unwrap_or(DEFAULT_INVITE_MINUTES);
+    if !(5..=MAX_INVITE_MINUTES).contains(&minutes) {
+        return Err(ApiError::bad_request(
+            "INVALID_EXPIRY",
+            "An invite must expire between 5 minutes and 7 days from now",
+        ));
+    }
+    Ok((max_uses, Duration::minutes(minutes)))
+}
+
+fn invite_unusable() -> ApiError {
+    ApiError::new(
+        StatusCode::GONE,
+        "INVITE_EXPIRED",
+        "This invite has expired, been revoked, or been used up",
+    )
+}
+
+fn invite_item(invite: GameInvite) -> GameInviteItem {
+    GameInviteItem {
+        usable: invite.is_usable(Utc::now()),
+        id: invite.id,
+        max_uses: invite.max_uses,
+        uses: invite.uses,
+        expires_at: invite.expires_at,
+        revoked_at: invite.revoked_at,
+        created_at: invite.created_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invite_limits() {
+        let (uses, lifetime) = invite_limits(&CreateGameInviteRequest::default()).unwrap();
+        assert_eq!(uses, 1);
+        assert_eq!(lifetime, Duration::hours(24));
+
+        let req = CreateGameInviteRequest { max_uses: Some(8), expires_in_minutes: Some(30) };
+        assert_eq!(invite_limits(&req).unwrap(), (8, Duration::minutes(30)));
+
+        for (max_uses, expires_in_minutes) in [(0, 60), (51, 60), (1, 4), (1, 7 * 24 * 60 + 1)] {
+            let req = CreateGameInviteRequest {
+                max_uses: Some(max_uses),
+                expires_in_minutes: Some(expires_in_minutes),
+            };
+            assert!(invite_limits(&req).is_err());
+        }
+    }
+}
@@ -0,0 +1,485 @@
+//! Game share link routes
+//!
+//! A player of a finished game creates a public link to its replay. Anyone
+//! with the link can open the replay without signing in; it is redacted to
+//! display names, so no user IDs or account details leave the server. Links
+//! expire and can be revoked by the player who created them.
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    routing::get,
+};
+use chrono::{DateTime, Duration, Utc};
+use dguesser_auth::{AuthUser, MaybeAuthUser, credentials};
+use dguesser_db::GameMode;
+use dguesser_db::game_shares::GameShareLink;
+use dguesser_protocol::api::game::{GameSummaryResponse, GuessHighlight, RoundHighlight};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::game_summary::{build_summary, ensure_not_archived, finished_game};
+use crate::{error::ApiError, state::AppState};
+
+/// Default link lifetime, in days
+const DEFAULT_SHARE_DAYS: i64 = 7;
+/// Maximum link lifetime, in days
+const MAX_SHARE_DAYS: i64 = 30;
+/// Maximum working links per player and game
+const MAX_ACTIVE_SHARES: i64 = 5;
+
+/// Create the public replay router
+pub fn replay_router() -> Router<AppState> {
+    Router::new().route("/{token}", get(get_shared_replay))
+}
+
+// =============================================================================
+// DTOs
+// =============================================================================
+
+/// Create share link request
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct CreateGameShareRequest {
+    /// Days until the link expires (1-30, default 7)
+    #[schema(example = 7)]
+    pub expires_in_days: Option<i64>,
+}
+
+/// A share link, without its token
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GameShareItem {
+    /// Share link ID
+    pub id: i64,
+    /// Times the replay was opened through the link
+    pub views: i32,
+    /// When the link stops working
+    pub expires_at: DateTime<Utc>,
+    /// When the link was revoked
+    pub revoked_at: Option<DateTime<Utc>>,
+    /// Whether the link still opens the replay
+    pub active: bool,
+    /// When the link was created
+    pub created_at: DateTime<Utc>,
+}
+
+/// Create share link response. The token is only returned here.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreateGameShareResponse {
+    #[serde(flatten)]
+    pub share: GameShareItem,
+    /// Secret share token
+    pub token: String,
+    /// Public replay link
+    #[schema(example = "https://dguesser.com/replay/3q2-7wEjYfG_Hx1hT0vq8Q")]
+    pub url: String,
+}
+
+/// Share links response
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GameSharesResponse {
+    /// The caller's links for the game, newest first
+    pub shares: Vec<GameShareItem>,
+}
+
+/// A player in a shared replay
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReplayPlayer {
+    /// Display name
+    pub display_name: String,
+    /// Final score (best streak in streak games)
+    pub score: u32,
+    /// Final rank (1 = winner)
+    pub rank: u8,
+}
+
+/// One guess in a shared replay
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReplayGuess {
+    /// Index of the guessing player in `players`
+    pub player: usize,
+    /// Guessed latitude
+    pub guess_lat: f64,
+    /// Guessed longitude
+    pub guess_lng: f64,
+    /// Distance from correct location in meters
+    pub distance_meters: f64,
+    /// Score awarded
+    pub score: u32,
+    /// How long the player took to guess, in milliseconds
+    pub time_taken_ms: Option<u32>,
+}
+
+/// One round of a shared replay
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReplayRound {
+    /// Round number
+    pub round_number: u8,
+    /// Correct location latitude
+    pub location_lat: f64,
+    /// Correct location longitude
+    pub location_lng: f64,
+    /// Country of the correct location (ISO 3166-1 alpha-2)
+    pub country_code: Option<String>,
+    /// Guesses, best first
+    pub guesses: Vec<ReplayGuess>,
+}
+
+/// A guess singled out in a shared replay
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReplayHighlight {
+    /// Round the guess was made in
+    pub round_number: u8,
+    /// Display name of the guessing player
+    pub display_name: String,
+    /// Distance from correct location in meters
+    pub distance_meters: f64,
+    /// Score awarded
+    pub score: u32,
+}
+
+/// Standout moments of a shared replay
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReplayHighlights {
+    /// The highest-scoring guess
+    pub best_guess: Option<ReplayHighlight>,
+    /// The round players were closest in on average
+    pub closest_round: Option<RoundHighlight>,
+    /// The guess furthest from its location
+    pub biggest_blunder: Option<ReplayHighlight>,
+}
+
+/// A redacted game replay, readable without an account
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SharedReplayResponse {
+    /// Game mode
+    #[schema(example = "multiplayer")]
+    pub mode: String,
+    /// Players, best first
+    pub players: Vec<ReplayPlayer>,
+    /// Rounds in order, with each player's guess
+    pub rounds: Vec<ReplayRound>,
+    /// Standout moments of the game
+    pub highlights: ReplayHighlights,
+    /// Whether the signed-in viewer played in the game
+    pub viewer_is_player: bool,
+    /// When the link stops working
+    pub expires_at: DateTime<Utc>,
+}
+
+// =============================================================================
+// Handlers
+// =============================================================================
+
+/// Create a public replay link for a finished game (players only)
+#[utoipa::path(
+    post,
+    path = "/api/v1/games/{id}/share",
+    params(
+        ("id" = String, Path, description = "Game ID")
+    ),
+    request_body = CreateGameShareRequest,
+    responses(
+        (status = 201, description = "Share link created", body = CreateGameShareResponse),
+        (status = 400, description = "Invalid expiry, game not finished, or a challenge"),
+        (status = 403, description = "Not a player in this game"),
+        (status = 404, description = "Game not found"),
+        (status = 409, description = "Too many active links or game archived"),
+    ),
+    tag = "games"
+)]
+pub async fn create_share(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(id): Path<String>,
+    req: Option<Json<CreateGameShareRequest>>,
+) -> Result<(StatusCode, Json<CreateGameShareResponse>), ApiError> {
+    let req = req.map(|Json(req)| req).unwrap_or_default();
+    let lifetime = share_lifetime(&req)?;
+
+    finished_game(&state, &id).await?;
+    require_player(&state, &id, &auth.user_id).await?;
+    if dguesser_db::game_shares::count_active(state.db(), &id, &auth.user_id).await?
+        >= MAX_ACTIVE_SHARES
+    {
+        return Err(ApiError::conflict(
+            "TOO_MANY_SHARES",
+            format!("You can have at most {MAX_ACTIVE_SHARES} active links per game"),
+        ));
+    }
+
+    let (token, token_hash) = credentials::generate_token();
+    let share = dguesser_db::game_shares::create(
+        state.db(),
+        &id,
+        &token_hash,
+        &auth.user_id,
+        Utc::now() + lifetime,
+    )
+    .await?;
+
+    tracing::info!(game_id = %id, share_id = share.id, "Created game share link");
+
+    let url = format!("{}/replay/{}", state.frontend_url().trim_end_matches('/'), token);
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateGameShareResponse { share: share_item(share), token, url }),
+    ))
+}
+
+/// List the caller's share links for a game
+#[utoipa::path(
+    get,
+    path = "/api/v1/games/{id}/share",
+    params(
+        ("id" = String, Path, description = "Game ID")
+    ),
+    responses(
+        (status = 200, description = "Share links", body = GameSharesResponse),
+        (status = 403, description = "Not a player in this game"),
+    ),
+    tag = "games"
+)]
+pub async fn list_shares(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(id): Path<String>,
+) -> Result<Json<GameSharesResponse>, ApiError> {
+    require_player(&state, &id, &auth.user_id).await?;
+
+    let shares = dguesser_db::game_shares::list_for_game(state.db(), &id, &auth.user_id).await?;
+    Ok(Json(GameSharesResponse { shares: shares.into_iter().map(share_item).collect() }))
+}
+
+/// Revoke one of the caller's share links
+#[utoipa::path(
+    delete,
+    path = "/api/v1/games/{id}/share/{share_id}",
+    params(
+        ("id" = String, Path, description = "Game ID"),
+        ("share_id" = i64, Path, description = "Share link ID")
+    ),
+    responses(
+        (status = 204, description = "Share link revoked"),
+        (status = 404, description = "Share link not found"),
+    ),
+    tag = "games"
+)]
+pub async fn revoke_share(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path((id, share_id)): Path<(String, i64)>,
+) -> Result<StatusCode, ApiError> {
+    if !dguesser_db::game_shares::revoke(state.db(), &id, share_id, &auth.user_id).await? {
+        return Err(ApiError::not_found("Share link"));
+    }
+
+    tracing::info!(game_id = %id, share_id, "Revoked game share link");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Open a shared replay (no sign-in needed)
+#[utoipa::path(
+    get,
+    path = "/api/v1/replays/{token}",
+    params(
+        ("token" = String, Path, description = "Share token from the replay link")
+    ),
+    responses(
+        (status = 200, description = "Redacted replay", body = SharedReplayResponse),
+        (status = 404, description = "Link not found, expired, or revoked"),
+        (status = 409, description = "Game archived"),
+    ),
+    tag = "games"
+)]
+pub async fn get_shared_replay(
+    State(state): State<AppState>,
+    MaybeAuthUser(auth): MaybeAuthUser,
+    Path(token): Path<String>,
+) -> Result<Json<SharedReplayResponse>, ApiError> {
+    let token_hash = credentials::hash_token(token.trim());
+    let share = dguesser_db::game_shares::open(state.db(), &token_hash)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Replay"))?;
+
+    let game = finished_game(&state, &share.game_id).await?;
+    ensure_not_archived(&state, &share.game_id).await?;
+    let mode = game.mode;
+
+    let players = dguesser_db::games::get_players(state.db(), &share.game_id).await?;
+    let viewer_is_player =
+        auth.is_some_and(|auth| players.iter().any(|p| p.user_id == auth.user_id));
+    let summary = build_summary(&state, game, &players).await?;
+
+    Ok(Json(redact_summary(summary, mode, viewer_is_player, share.expires_at)))
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+async fn require_player(state: &AppState, game_id: &str, user_id: &str) -> Result<(), ApiError> {
+    if !dguesser_db::games::is_player_in_game(state.db(), game_id, user_id).await? {
+        return Err(ApiError::forbidden("Not a player in this game"));
+    }
+    Ok(())
+}
+
+/// Check the requested lifetime, applying the default.
+fn share_lifetime(req: &CreateGameShareRequest) -> Result<Duration, ApiError> {
+    let days = req.expires_in_days.unwrap_or(DEFAULT_SHARE_DAYS);
+    if !(1..=MAX_SHARE_DAYS).contains(&days) {
+        return Err(ApiError::bad_request(
+            "INVALID_EXPIRY",
+            format!("A share link must expire between 1 and {MAX_SHARE_DAYS} days from now"),
+        ));
+    }
+    Ok(Duration::days(days))
+}
+
+fn share_item(share: GameShareLink) -> GameShareItem {
+    GameShareItem {
+        active: share.is_active(Utc::now()),
+        id: share.id,
+        views: share.views,
+        expires_at: share.expires_at,
+        revoked_at: share.revoked_at,
+        created_at: share.created_at,
+    }
+}
+
+/// Replace user IDs with display names and player indexes.
+fn redact_summary(
+    summary: GameSummaryResponse,
+    mode: GameMode,
+    viewer_is_player: bool,
+    expires_at: DateTime<Utc>,
+) -> SharedReplayResponse {
+    let player_index = |user_id: &str| summary.players.iter().position(|p| p.user_id == user_id);
+    let highlight = |h: GuessHighlight| ReplayHighlight {
+        round_number: h.round_number,
+        display_name: h.display_name,
+        distance_meters: h.distance_meters,
+        score: h.score,
+    };
+
+    let rounds = summary
+        .rounds
+        .into_iter()
+        .map(|round| ReplayRound {
+            round_number: round.round_number,
+            location_lat: round.location_lat,
+            location_lng: round.location_lng,
+            country_code: round.country_code,
+            guesses: round
+                .guesses
+                .into_iter()
+                // Guesses by players not in the standings (a classroom
+                // teacher) have nobody to point at
+                .filter_map(|g| {
+                    Some(ReplayGuess {
+                        player: player_index(&g.user_id)?,
+                        guess_lat: g.guess_lat,
+                        guess_lng: g.guess_lng,
+                        distance_meters: g.distance_meters,
+                        score: g.score,
+                        time_taken_ms: g.time_taken_ms,
+                    })
+                })
+                .collect(),
+        })
+        .collect();
+
+    SharedReplayResponse {
+        mode: mode.to_string(),
+        players: summary
+            .players
+            .into_iter()
+            .enumerate()
+            .map(|(index, p)| ReplayPlayer {
+                display_name: p.display_name,
+                score: p.score,
+                rank: p.rank.unwrap_or((index + 1) as u8),
+            })
+            .collect(),
+        rounds,
+        highlights: ReplayHighlights {
+            best_guess: summary.highlights.best_guess.map(highlight),
+            closest_round: summary.highlights.closest_round,
+            biggest_blunder: summary.highlights.biggest_blunder.map(highlight),
+        },
+        viewer_is_player,
+        expires_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dguesser_protocol::api::game::{
+        PlayerGameStats, SummaryGuess, SummaryHighlights, SummaryRound,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_share_lifetime() {
+        assert_eq!(share_lifetime(&CreateGameShareRequest::default()).unwrap(), Duration::days(7));
+        let req = CreateGameShareRequest { expires_in_days: Some(30) };
+        assert_eq!(share_lifetime(&req).unwrap(), Duration::days(30));
+        for days in [0, 31, -1] {
+            assert!(
+                share_lifetime(&CreateGameShareRequest { expires_in_days: Some(days) }).is_err()
+            );
+        }
+    }
+
+    #[test]
+    fn test_redact_summary() {
+        let player = |user_id: &str, name: &str, score: u32, rank: u8| PlayerGameStats {
+            user_id: user_id.to_string(),
+            display_name: name.to_string(),
+            score,
+            rank: Some(rank),
+            streak: None,
+        };
+        let guess = |user_id: &str| SummaryGuess {
+            user_id: user_id.to_string(),
+            guess_lat: 1.0,
+            guess_lng: 2.0,
+            distance_meters: 100.0,
+            score: 4_900,
+            time_taken_ms: Some(3_000),
+        };
+        let summary = GameSummaryResponse {
+            game_id: "gam_FybH2oF9Xaw8".to_string(),
+            players: vec![player("usr_a", "Alice", 9_000, 1), player("usr_b", "Bob", 5_000, 2)],
+            rounds: vec![SummaryRound {
+                round_number: 1,
+                location_lat: 0.0,
+                location_lng: 0.0,
+                country_code: Some("FR".to_string()),
+                guesses: vec![guess("usr_b"), guess("usr_teacher")],
+            }],
+            highlights: SummaryHighlights {
+                best_guess: Some(GuessHighlight {
+                    round_number: 1,
+                    user_id: "usr_b".to_string(),
+                    display_name: "Bob".to_string(),
+                    distance_meters: 100.0,
+                    score: 4_900,
+                }),
+                ..Default::default()
+            },
+        };
+
+        let replay = redact_summary(summary, GameMode::Multiplayer, false, Utc::now());
+        let json = serde_json::to_string(&replay).unwrap();
+        assert!(!json.contains("usr_"), "replay must not leak user IDs: {json}");
+        assert!(!json.contains("gam_"), "replay must not leak the game ID: {json}");
+
+        assert_eq!(replay.players[1].display_name, "Bob");
+        assert_eq!(replay.rounds[0].guesses.len(), 1);
+        assert_eq!(replay.rounds[0].guesses[0].player, 1);
+        assert_eq!(replay.highlights.best_guess.unwrap().display_name, "Bob");
+    }
+}
@@ -0,0 +1,257 @@
+//! Post-game summary route
+//!
+//! Breaks a finished game down round by round for the results screen: every
+//! player's guess with its distance, score and time, plus a few highlights
+//! worked out from them.
+
+use std::collections::HashMap;
+
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use dguesser_auth::AuthUser;
+use dguesser_core::game::GameSettings;
+use dguesser_db::games::{Game, GamePlayer};
+use dguesser_db::{GameMode, GameStatus, UserLoader};
+use dguesser_protocol::api::game::{
+    GameSummaryResponse, GuessHighlight, PlayerGameStats, RoundHighlight, SummaryGuess,
+    SummaryHighlights, SummaryRound,
+};
+
+use super::games::reconcile_solo_game;
+use crate::{error::ApiError, state::AppState};
+
+/// Get a round-by-round breakdown of a finished game.
+#[utoipa::path(
+    get,
+    path = "/api/v1/games/{id}/summary",
+    params(
+        ("id" = String, Path, description = "Game ID")
+    ),
+    responses(
+        (status = 200, description = "Game summary", body = GameSummaryResponse),
+        (status = 400, description = "Game is not finished or is a challenge"),
+        (status = 403, description = "Not a player in this game"),
+        (status = 404, description = "Game not found"),
+        (status = 409, description = "Game is archived"),
+    ),
+    tag = "games"
+)]
+pub async fn get_game_summary(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(id): Path<String>,
+) -> Result<Json<GameSummaryResponse>, ApiError> {
+    let game = finished_game(&state, &id).await?;
+
+    let players = dguesser_db::games::get_players(state.db(), &id).await?;
+    if !players.iter().any(|p| p.user_id == auth.user_id) {
+        return Err(ApiError::forbidden("Not a player in this game"));
+    }
+    ensure_not_archived(&state, &id).await?;
+
+    Ok(Json(build_summary(&state, game, &players).await?))
+}
+
+/// Load a finished game that has a summary (challenges don't).
+pub(crate) async fn finished_game(state: &AppState, id: &str) -> Result<Game, ApiError> {
+    reconcile_solo_game(state, id).await?;
+
+    let game = dguesser_db::games::get_game_by_id(state.db(), id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Game"))?;
+
+    // Each challenge player sees their own results through /api/v1/challenges
+    if game.mode == GameMode::Challenge {
+        return Err(ApiError::bad_request(
+            "INVALID_MODE",
+            "Challenge results are available through /api/v1/challenges",
+        ));
+    }
+    if game.status != GameStatus::Finished {
+        return Err(ApiError::bad_request(
+            "GAME_NOT_FINISHED",
+            "The summary is only available after the game has finished",
+        ));
+    }
+    Ok(game)
+}
+
+/// Refuse games whose rounds have been moved to cold storage.
+pub(crate) async fn ensure_not_archived(state: &AppState, id: &str) -> Result<(), ApiError> {
+    if dguesser_db::game_archive::get_status(state.db(), id)
+        .await?
+        .is_some_and(|status| status.archived_at.is_some())
+    {
+        return Err(ApiError::conflict(
+            "GAME_ARCHIVED",
+            "Restore the game with POST /api/v1/games/{id}/rehydrate first",
+        ));
+    }
+    Ok(())
+}
+
+/// Break a finished game down round by round.
+pub(crate) async fn build_summary(
+    state: &AppState,
+    game: Game,
+    players: &[GamePlayer],
+) -> Result<GameSummaryResponse, ApiError> {
+    let id = game.id;
+    let settings: GameSettings = serde_json::from_value(game.settings).unwrap_or_default();
+    let rounds = dguesser_db::games::get_rounds_for_game(state.db(), &id).await?;
+    let guesses = dguesser_db::games::get_game_summary_guesses(state.db(), &id).await?;
+    let users = UserLoader::load(state.db(), players.iter().map(|p| p.user_id.as_str())).await?;
+
+    let mut names = HashMap::new();
+    let mut standings = Vec::new();
+    for player in players {
+        // A classroom teacher watches rather than plays
+        if settings.classroom && player.is_host {
+            continue;
+        }
+        let display_name = users
+            .get(&player.user_id)
+            .map(|u| u.display_name.clone())
+            .unwrap_or_else(|| "Unknown".to_string());
+        names.insert(player.user_id.clone(), display_name.clone());
+
+        let score = player.score_total.max(0) as u32;
+        standings.push(PlayerGameStats {
+            user_id: player.user_id.clone(),
+            display_name,
+            score,
+            rank: None,
+            streak: (game.mode == GameMode::Streak).then_some(score),
+        });
+    }
+    standings.sort_by_key(|s| std::cmp::Reverse(s.score));
+    for (index, standing) in standings.iter_mut().enumerate() {
+        standing.rank = Some((index + 1) as u8);
+    }
+
+    let mut summary_rounds: Vec<SummaryRound> = rounds
+        .into_iter()
+        .map(|round| SummaryRound {
+            round_number: round.round_number as u8,
+            location_lat: round.location_lat,
+            location_lng: round.location_lng,
+            country_code: round.country_code,
+            guesses: Vec::new(),
+        })
+        .collect();
+    for guess in guesses {
+        // Timed-out solo rounds are stored with a negative distance
+        if guess.distance_meters < 0.0 {
+            continue;
+        }
+        let Some(round) =
+            summary_rounds.iter_mut().find(|r| i16::from(r.round_number) == guess.round_number)
+        else {
+            continue;
+        };
+        round.guesses.push(SummaryGuess {
+            user_id: guess.user_id,
+            guess_lat: guess.guess_lat,
+            guess_lng: guess.guess_lng,
+            distance_meters: guess.distance_meters,
+            score: guess.score.max(0) as u32,
+            time_taken_ms: guess.time_taken_ms.map(|t| t.max(0) as u32),
+        });
+    }
+
+    let highlights = summary_highlights(&summary_rounds, &names);
+
+    Ok(GameSummaryResponse { game_id: id, players: standings, rounds: summary_rounds, highlights })
+}
+
+/// Pick the best guess, the closest round on average and the biggest blunder
+fn summary_highlights(
+    rounds: &[SummaryRound],
+    names: &HashMap<String, String>,
+) -> SummaryHighlights {
+    let guesses = || rounds.iter().flat_map(|r| r.guesses.iter().map(move |g| (r.round_number, g)));
+    let highlight = |(round_number, guess): (u8, &SummaryGuess)| GuessHighlight {
+        round_number,
+        user_id: guess.user_id.clone(),
+        display_name: names.get(&guess.user_id).cloned().unwrap_or_else(|| "Unknown".to_string()),
+        distance_meters: guess.distance_meters,
+        score: guess.score,
+    };
+
+    let best_guess = guesses()
+        .max_by(|(_, a), (_, b)| {
+            a.score.cmp(&b.score).then(b.distance_meters.total_cmp(&a.distance_meters))
+        })
+        .map(highlight);
+    let biggest_blunder = guesses()
+        .max_by(|(_, a), (_, b)| a.distance_meters.total_cmp(&b.distance_meters))
+        .map(highlight);
+    let closest_round = rounds
+        .iter()
+        .filter(|r| !r.guesses.is_empty())
+        .map(|r| RoundHighlight {
+            round_number: r.round_number,
+            average_distance_meters: r.guesses.iter().map(|g| g.distance_meters).sum::<f64>()
+                / r.guesses.len() as f64,
+        })
+        .min_by(|a, b| a.average_distance_meters.total_cmp(&b.average_distance_meters));
+
+    SummaryHighlights { best_guess, closest_round, biggest_blunder }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guess(user_id: &str, distance_meters: f64, score: u32) -> SummaryGuess {
+        SummaryGuess {
+            user_id: user_id.to_string(),
+            guess_lat: 0.0,
+            guess_lng: 0.0,
+            distance_meters,
+            score,
+            time_taken_ms: None,
+        }
+    }
+
+    fn round(round_number: u8, guesses: Vec<SummaryGuess>) -> SummaryRound {
+        SummaryRound {
+            round_number,
+            location_lat: 0.0,
+            location_lng: 0.0,
+            country_code: None,
+            guesses,
+        }
+    }
+
+    #[test]
+    fn test_summary_highlights() {
+        let names = HashMap::from([("usr_a".to_string(), "Alice".to_string())]);
+        let rounds = vec![
+            round(1, vec![guess("usr_a", 1_000.0, 4_900), guess("usr_b", 3_000_000.0, 200)]),
+            round(2, vec![guess("usr_a", 500.0, 4_900), guess("usr_b", 20_000.0, 4_000)]),
+            round(3, vec![]),
+        ];
+
+        let highlights = summary_highlights(&rounds, &names);
+
+        // Equal scores go to the closer guess
+        let best = highlights.best_guess.unwrap();
+        assert_eq!((best.round_number, best.display_name.as_str()), (2, "Alice"));
+        let blunder = highlights.biggest_blunder.unwrap();
+        assert_eq!((blunder.round_number, blunder.display_name.as_str()), (1, "Unknown"));
+        let closest = highlights.closest_round.unwrap();
+        assert_eq!(closest.round_number, 2);
+        assert_eq!(closest.average_distance_meters, 10_250.0);
+    }
+
+    #[test]
+    fn test_summary_highlights_without_guesses() {
+        let highlights = summary_highlights(&[round(1, vec![])], &HashMap::new());
+        assert!(highlights.best_guess.is_none());
+        assert!(highlights.closest_round.is_none());
+        assert!(highlights.biggest_blunder.is_none());
+    }
+}
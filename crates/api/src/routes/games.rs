@@ -10,7 +10,7 @@ use std::collections::HashMap;
 
 use axum::{
     Json, Router,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     routing::{get, post},
 };
 use chrono::{DateTime, Utc};
@@ -18,32 +18,68 @@ use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use validator::Validate;
 
-use axum::http::{HeaderMap, header::SET_COOKIE};
+use axum::http::{
+    HeaderMap,
+    header::{self, SET_COOKIE},
+};
 
-use crate::{error::ApiError, middleware::extract_ip_from_headers, socket, state::AppState};
+use super::{country_quiz, game_invites, game_shares, game_summary};
+use crate::{
+    cache::LocationStatsCache,
+    config::VanityCodeAccess,
+    error::ApiError,
+    extract::ValidatedJson,
+    game_archive::{self, ArchiveError},
+    middleware::{CurrentOrg, RequestClient, abuse},
+    outbox,
+    render::{GameCard, GameCardStanding, RoundCard},
+    state::AppState,
+};
 use dguesser_auth::{AuthUser, MaybeAuthUser, build_cookie_header, create_guest_session};
 use dguesser_core::game::{
-    GameCommand, GameEvent, GamePhase, GameSettings, GameState, LocationData, PlayerState,
-    RoundState, reduce, validate_location_count,
+    CheatSignalKind, DEFAULT_HINT_COST, DisconnectPolicy, GameCommand, GameEvent, GamePhase,
+    GamePreset, GameSettings, GameState, LocationData, LocationGuessStats, MapRounds, PlayerState,
+    RoundState, ScoringConfig, apply_handicap, reduce, validate_location_count,
 };
-use dguesser_db::{GameMode, GameStatus};
+use dguesser_core::join_code::{normalize_join_code, validate_vanity_code};
+use dguesser_core::streetview::ImageryProvider;
+use dguesser_db::{GameMode, GameStatus, JoinCodeChoice, UserLoader};
 use dguesser_protocol::socket::{
-    events::server::SETTINGS_UPDATED,
-    payloads::{GameSettingsPayload, SettingsUpdatedPayload},
+    events::server::{JOIN_CODE_ROTATED, SETTINGS_UPDATED},
+    payloads::{
+        GameSettingsPayload, GlobalGuessStats, JoinCodeRotatedPayload, MapRoundsPayload,
+        SettingsUpdatedPayload,
+    },
 };
+use dguesser_redis::RedisConnection;
 
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/", post(create_game))
         .route("/join", post(join_game_by_code))
+        .route("/invites/redeem", post(game_invites::redeem_invite))
         .route("/{id}", get(get_game))
         .route("/{id}/results", get(get_game_results))
+        .route("/{id}/summary", get(game_summary::get_game_summary))
+        .route("/{id}/card", get(get_game_card))
+        .route("/{id}/rehydrate", post(rehydrate_game))
+        .route("/{id}/export", get(export_game_results))
         .route("/{id}/start", post(start_game))
         .route("/{id}/settings", axum::routing::patch(update_settings))
+        .route("/{id}/code/rotate", post(rotate_join_code))
+        .route("/{id}/invites", get(game_invites::list_invites).post(game_invites::create_invite))
+        .route("/{id}/invites/{invite_id}", axum::routing::delete(game_invites::revoke_invite))
+        .route("/{id}/share", get(game_shares::list_shares).post(game_shares::create_share))
+        .route("/{id}/share/{share_id}", axum::routing::delete(game_shares::revoke_share))
+        .route("/{id}/quiz", get(country_quiz::get_quiz))
+        .route("/{id}/quiz/question", post(country_quiz::ask_question))
+        .route("/{id}/quiz/answer", post(country_quiz::answer_question))
         .route("/{id}/rounds/current", get(get_current_round))
         .route("/{id}/rounds/next", post(next_round))
         .route("/{id}/rounds/{round}/timeout", post(timeout_round))
         .route("/{id}/rounds/{round}/guess", post(submit_guess))
+        .route("/{id}/rounds/{round}/hint", post(request_hint))
+        .route("/{id}/rounds/{round}/card", get(get_round_card))
         .route("/history", get(get_game_history))
         .route("/presets", get(get_presets))
 }
@@ -54,7 +90,7 @@ pub fn router() -> Router<AppState> {
 /// Create game request
 #[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct CreateGameRequest {
-    /// Game mode: "solo" or "multiplayer"
+    /// Game mode: "solo", "multiplayer" or "streak"
     #[schema(example = "solo")]
     pub mode: String,
     /// Number of rounds (1-20)
@@ -75,6 +111,44 @@ pub struct CreateGameRequest {
     pub zoom_allowed: Option<bool>,
     /// Allow rotation/panning
     pub rotation_allowed: Option<bool>,
+    /// Time-attack budget in seconds shared by all rounds (0 = off, max 1800)
+    #[validate(range(max = 1800))]
+    #[schema(example = 180)]
+    pub time_budget_seconds: Option<u32>,
+    /// Seconds a disconnected player has to reconnect (5-300)
+    #[validate(range(min = 5, max = 300))]
+    #[schema(example = 30)]
+    pub reconnect_grace_seconds: Option<u32>,
+    /// What happens when a player disconnects: "remove_player",
+    /// "keep_scoring_zero" or "pause_if_host"
+    #[schema(value_type = Option<String>, example = "keep_scoring_zero")]
+    pub disconnect_policy: Option<DisconnectPolicy>,
+    /// Let players send emoji reactions (default true)
+    pub reactions_enabled: Option<bool>,
+    /// Let players join after the game has started, scoring from the round
+    /// they join (default false)
+    pub allow_late_join: Option<bool>,
+    /// Let players take hints during a round (default false)
+    pub hints_enabled: Option<bool>,
+    /// Score points each hint costs (max 5000, default 500)
+    #[validate(range(max = 5000))]
+    #[schema(example = 500)]
+    pub hint_cost: Option<u32>,
+    /// Maps the rounds are played on, in round order; the runs must add up
+    /// to the number of rounds (default: every round on `map_id`)
+    pub rounds_plan: Option<Vec<MapRoundsPayload>>,
+    /// Vanity join code for a multiplayer lobby (4-8 letters or digits)
+    #[schema(example = "PARTY1")]
+    pub join_code: Option<String>,
+    /// Classroom game: the host is a teacher who sees every guess live, can
+    /// pause the game and export the results, but does not play (multiplayer
+    /// only, default false)
+    pub classroom: Option<bool>,
+    /// Preset the settings start from: a built-in preset ID (see
+    /// `/api/v1/games/presets`) or one of the user's saved presets. Fields
+    /// set in this request override the preset's.
+    #[schema(example = "speedround")]
+    pub preset_id: Option<String>,
 }
 
 /// Create game response
@@ -115,8 +189,11 @@ pub struct GameDetails {
     pub players: Vec<PlayerInfo>,
     /// Current round number
     pub current_round: u8,
-    /// Total number of rounds
+    /// Total number of rounds (0 = unbounded streak or time attack)
     pub total_rounds: u8,
+    /// Whether the rounds and guesses are archived; restore them with
+    /// `POST /api/v1/games/{id}/rehydrate`
+    pub archived: bool,
 }
 
 /// Join game by code request
@@ -144,6 +221,9 @@ pub struct PlayerInfo {
     pub is_guest: bool,
     /// Total score in this game
     pub score: i32,
+    /// Multiplier applied to this player's round scores
+    #[schema(example = 1.0)]
+    pub handicap: f64,
 }
 
 /// Round info response
@@ -164,7 +244,7 @@ pub struct RoundInfo {
 pub struct CurrentRoundInfo {
     /// Round number (1-based)
     pub round_number: u8,
-    /// Total rounds in the game
+    /// Total rounds in the game (0 = unbounded streak or time attack)
     pub total_rounds: u8,
     /// Location to guess
     pub location: LocationInfo,
@@ -203,6 +283,9 @@ pub struct LocationInfo {
     /// Location ID for reporting (if from location database)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub location_id: Option<String>,
+    /// Imagery provider the panorama comes from, which decides the viewer
+    #[schema(value_type = String, example = "google_streetview")]
+    pub provider: ImageryProvider,
 }
 
 /// Submit guess request
@@ -216,6 +299,42 @@ pub struct SubmitGuessRequest {
     pub lng: f64,
     /// Time taken in milliseconds
     pub time_taken_ms: Option<u32>,
+    /// Panorama the client was viewing when it guessed.
+    /// Must match the round's panorama in no-move modes.
+    #[validate(length(max = 128))]
+    pub panorama_id: Option<String>,
+    /// Country the player names (ISO 3166-1 alpha-2), required in streak games
+    #[validate(length(equal = 2))]
+    #[schema(example = "FR")]
+    pub country_code: Option<String>,
+}
+
+/// Hint request
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct HintRequest {
+    /// Hint kind: "continent", "country_letter" or "direction"
+    #[schema(example = "continent")]
+    pub kind: String,
+    /// Latitude the direction hint is measured from (the player's marker)
+    #[validate(range(min = -90.0, max = 90.0))]
+    pub from_lat: Option<f64>,
+    /// Longitude the direction hint is measured from (the player's marker)
+    #[validate(range(min = -180.0, max = 180.0))]
+    pub from_lng: Option<f64>,
+}
+
+/// Hint response
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HintResponse {
+    /// Hint kind
+    #[schema(example = "continent")]
+    pub kind: String,
+    /// What the hint reveals: a continent ID, a letter or a compass point
+    #[schema(example = "europe")]
+    pub value: String,
+    /// Points taken off this round's score (0 when the hint was already taken)
+    #[schema(example = 500)]
+    pub cost: u32,
 }
 
 /// Guess result response
@@ -229,6 +348,25 @@ pub struct GuessResultResponse {
     pub total_score: u32,
     /// Correct location
     pub correct_location: LocationInfo,
+    /// Percentage of past guesses on this location that were further away
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub percentile: Option<f64>,
+    /// How past players did on this location (absent until enough have guessed)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub global_stats: Option<GlobalGuessStats>,
+    /// Country of the location (streak games only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correct_country_code: Option<String>,
+    /// Countries named correctly in a row so far (streak games only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub streak: Option<u32>,
+    /// Round started straight after this guess (time-attack games only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_round: Option<RoundInfo>,
+    /// Signature chaining this round's score to the previous rounds (solo
+    /// games, when score signing is configured)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score_signature: Option<String>,
 }
 
 /// Player's result within a completed round.
@@ -261,6 +399,9 @@ pub struct FinalStandingInfo {
     pub display_name: String,
     /// Final total score
     pub total_score: u32,
+    /// Number of countries named correctly in a row (streak games only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub streak: Option<u32>,
 }
 
 /// Completed round data for the game summary view.
@@ -322,6 +463,48 @@ pub struct UpdateSettingsRequest {
     pub zoom_allowed: Option<bool>,
     /// Allow rotation/panning
     pub rotation_allowed: Option<bool>,
+    /// Time-attack budget in seconds shared by all rounds (0 = off, max 1800)
+    #[validate(range(max = 1800))]
+    #[schema(example = 180)]
+    pub time_budget_seconds: Option<u32>,
+    /// Seconds a disconnected player has to reconnect (5-300)
+    #[validate(range(min = 5, max = 300))]
+    #[schema(example = 30)]
+    pub reconnect_grace_seconds: Option<u32>,
+    /// What happens when a player disconnects: "remove_player",
+    /// "keep_scoring_zero" or "pause_if_host"
+    #[schema(value_type = Option<String>, example = "keep_scoring_zero")]
+    pub disconnect_policy: Option<DisconnectPolicy>,
+    /// Let players send emoji reactions (default true)
+    pub reactions_enabled: Option<bool>,
+    /// Let players join after the game has started, scoring from the round
+    /// they join (default false)
+    pub allow_late_join: Option<bool>,
+    /// Let players take hints during a round (default false)
+    pub hints_enabled: Option<bool>,
+    /// Score points each hint costs (max 5000, default 500)
+    #[validate(range(max = 5000))]
+    #[schema(example = 500)]
+    pub hint_cost: Option<u32>,
+    /// Maps the rounds are played on, in round order; the runs must add up
+    /// to the number of rounds (default: every round on `map_id`)
+    pub rounds_plan: Option<Vec<MapRoundsPayload>>,
+}
+
+/// Rotate join code request
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct RotateJoinCodeRequest {
+    /// Vanity code to switch to (a random code when omitted)
+    #[schema(example = "PARTY1")]
+    pub code: Option<String>,
+}
+
+/// Rotate join code response
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RotateJoinCodeResponse {
+    /// New join code
+    #[schema(example = "XK4P9M")]
+    pub join_code: String,
 }
 
 /// Update settings response
@@ -346,12 +529,88 @@ pub struct SettingsDto {
     pub zoom_allowed: bool,
     /// Allow rotation/panning
     pub rotation_allowed: bool,
+    /// Time-attack budget in seconds shared by all rounds (0 = off)
+    pub time_budget_seconds: u32,
+    /// Seconds a disconnected player has to reconnect
+    pub reconnect_grace_seconds: u32,
+    /// What happens when a player disconnects
+    #[schema(value_type = String, example = "keep_scoring_zero")]
+    pub disconnect_policy: DisconnectPolicy,
+    /// Whether players can send emoji reactions
+    pub reactions_enabled: bool,
+    /// Whether players can join after the game has started
+    pub allow_late_join: bool,
+    /// Maps the rounds are played on, in round order (empty = every round
+    /// on `map_id`)
+    pub rounds_plan: Vec<MapRoundsPayload>,
+    /// Whether the host is a teacher who watches and does not play
+    #[serde(default)]
+    pub classroom: bool,
+    /// Whether players can take hints during a round
+    #[serde(default)]
+    pub hints_enabled: bool,
+    /// Score points each hint costs
+    #[serde(default = "default_hint_cost")]
+    pub hint_cost: u32,
+}
+
+fn default_hint_cost() -> u32 {
+    DEFAULT_HINT_COST
+}
+
+impl SettingsDto {
+    pub(super) fn from_settings(settings: &GameSettings) -> Self {
+        Self {
+            rounds: settings.rounds,
+            time_limit_seconds: settings.time_limit_seconds,
+            map_id: settings.map_id.clone(),
+            movement_allowed: settings.movement_allowed,
+            zoom_allowed: settings.zoom_allowed,
+            rotation_allowed: settings.rotation_allowed,
+            time_budget_seconds: settings.time_budget_seconds,
+            reconnect_grace_seconds: settings.reconnect_grace_seconds,
+            disconnect_policy: settings.disconnect_policy,
+            reactions_enabled: settings.reactions_enabled,
+            allow_late_join: settings.allow_late_join,
+            rounds_plan: rounds_plan_payload(&settings.rounds_plan),
+            classroom: settings.classroom,
+            hints_enabled: settings.hints_enabled,
+            hint_cost: settings.hint_cost,
+        }
+    }
+
+    /// Core settings with these values; scoring and streak keep their defaults
+    pub(super) fn into_settings(self) -> GameSettings {
+        GameSettings {
+            rounds: self.rounds,
+            time_limit_seconds: self.time_limit_seconds,
+            map_id: self.map_id,
+            movement_allowed: self.movement_allowed,
+            zoom_allowed: self.zoom_allowed,
+            rotation_allowed: self.rotation_allowed,
+            time_budget_seconds: self.time_budget_seconds,
+            reconnect_grace_seconds: self.reconnect_grace_seconds,
+            disconnect_policy: self.disconnect_policy,
+            reactions_enabled: self.reactions_enabled,
+            allow_late_join: self.allow_late_join,
+            rounds_plan: self
+                .rounds_plan
+                .into_iter()
+                .map(|run| MapRounds { map_id: run.map_id, rounds: run.rounds })
+                .collect(),
+            classroom: self.classroom,
+            hints_enabled: self.hints_enabled,
+            hint_cost: self.hint_cost,
+            ..GameSettings::default()
+        }
+    }
 }
 
-const SOLO_NO_GUESS_LAT: f64 = 0.0;
-const SOLO_NO_GUESS_LNG: f64 = 0.0;
-const SOLO_NO_GUESS_DISTANCE_METERS: f64 = -1.0;
-const SOLO_NO_GUESS_SCORE: i32 = 0;
+/// Placeholder guess recorded when a round runs out of time without a guess
+pub(super) const NO_GUESS_LAT: f64 = 0.0;
+pub(super) const NO_GUESS_LNG: f64 = 0.0;
+pub(super) const NO_GUESS_DISTANCE_METERS: f64 = -1.0;
+pub(super) const NO_GUESS_SCORE: i32 = 0;
 
 /// Game preset info
 #[derive(Debug, Serialize, ToSchema)]
@@ -371,42 +630,74 @@ pub struct PresetInfo {
 // =============================================================================
 
 async fn finalize_solo_game(
-    db: &dguesser_db::DbPool,
+    state: &AppState,
     game_id: &str,
+    settings: &GameSettings,
     user_id: &str,
     total_score: i32,
 ) -> Result<(), ApiError> {
+    let db = state.db();
     dguesser_db::games::update_game_status(db, game_id, GameStatus::Finished).await?;
     dguesser_db::games::set_final_rankings(db, game_id).await?;
     dguesser_db::games::set_game_total_score(db, game_id, total_score).await?;
+
+    // A game whose rounds don't check out stays finished but off the
+    // leaderboards and out of the player's stats
+    if !verify_score_chain(state, game_id, user_id, total_score).await? {
+        return Ok(());
+    }
+
+    if let Err(e) = dguesser_db::leaderboard::record_game(db, game_id).await {
+        tracing::warn!(game_id, error = %e, "Failed to record game on leaderboards");
+    }
+
+    // A streak's score is its length; it has its own leaderboard
+    if settings.streak {
+        return Ok(());
+    }
+
+    let map_id = settings.map_id.as_str();
     dguesser_db::users::update_stats(db, user_id, total_score).await?;
 
+    // Map popularity is informational; never fail the game over it
+    if let Err(e) = dguesser_db::locations::record_map_plays(db, map_id, &[total_score]).await {
+        tracing::warn!(game_id, map_id, error = %e, "Failed to record map play");
+    }
+
     Ok(())
 }
 
-async fn build_game_results(
+pub(super) async fn build_game_results(
     db: &dguesser_db::DbPool,
     game_id: &str,
 ) -> Result<GameResultsResponse, ApiError> {
+    let game = dguesser_db::games::get_game_by_id(db, game_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Game"))?;
     let players = dguesser_db::games::get_players(db, game_id).await?;
     let rounds = dguesser_db::games::get_rounds_for_game(db, game_id).await?;
 
+    let users = UserLoader::load(db, players.iter().map(|p| p.user_id.as_str())).await?;
     let mut player_names = HashMap::new();
     let mut final_standings = Vec::new();
     for player in &players {
-        let user = dguesser_db::users::get_by_id(db, &player.user_id).await?;
-        let display_name = user.map(|u| u.display_name).unwrap_or_else(|| "Unknown".to_string());
+        let display_name = users
+            .get(&player.user_id)
+            .map(|u| u.display_name.clone())
+            .unwrap_or_else(|| "Unknown".to_string());
 
         player_names.insert(player.user_id.clone(), display_name.clone());
+        let total_score = player.score_total.max(0) as u32;
         final_standings.push(FinalStandingInfo {
             rank: 0,
             user_id: player.user_id.clone(),
             display_name,
-            total_score: player.score_total.max(0) as u32,
+            total_score,
+            streak: (game.mode == GameMode::Streak).then_some(total_score),
         });
     }
 
-    final_standings.sort_by(|a, b| b.total_score.cmp(&a.total_score));
+    final_standings.sort_by_key(|s| std::cmp::Reverse(s.total_score));
     for (index, standing) in final_standings.iter_mut().enumerate() {
         standing.rank = (index + 1) as u8;
     }
@@ -445,6 +736,7 @@ async fn build_game_results(
                 lng: round.location_lng,
                 panorama_id: round.panorama_id,
                 location_id: round.location_id,
+                provider: round.provider.parse().unwrap_or_default(),
             },
             results,
         });
@@ -457,12 +749,13 @@ async fn build_game_results(
     })
 }
 
-async fn reconcile_solo_game(db: &dguesser_db::DbPool, game_id: &str) -> Result<(), ApiError> {
+pub(super) async fn reconcile_solo_game(state: &AppState, game_id: &str) -> Result<(), ApiError> {
+    let db = state.db();
     let game = dguesser_db::games::get_game_by_id(db, game_id)
         .await?
         .ok_or_else(|| ApiError::not_found("Game"))?;
 
-    if game.mode != GameMode::Solo || game.status != GameStatus::Active {
+    if !game.mode.is_single_player() || game.status != GameStatus::Active {
         return Ok(());
     }
 
@@ -488,25 +781,38 @@ async fn reconcile_solo_game(db: &dguesser_db::DbPool, game_id: &str) -> Result<
     }
 
     if !has_guess && round_timed_out {
-        dguesser_db::games::create_guess(
+        let guess = dguesser_db::games::create_guess(
             db,
             &round.id,
             &player.user_id,
-            SOLO_NO_GUESS_LAT,
-            SOLO_NO_GUESS_LNG,
-            SOLO_NO_GUESS_DISTANCE_METERS,
-            SOLO_NO_GUESS_SCORE,
+            NO_GUESS_LAT,
+            NO_GUESS_LNG,
+            NO_GUESS_DISTANCE_METERS,
+            NO_GUESS_SCORE,
+            None,
+            None,
             None,
         )
         .await?;
+        sign_solo_guess(state, game_id, round.round_number, &guess).await?;
     }
 
     if round.ended_at.is_none() {
         dguesser_db::games::end_round(db, &round.id).await?;
     }
 
-    if round.round_number as u8 >= settings.rounds {
-        finalize_solo_game(db, game_id, &player.user_id, player.score_total).await?;
+    // A streak lives while every round so far has scored its point; a
+    // time-attack round only times out once the whole budget is spent
+    let game_over = if settings.streak {
+        player.score_total < i32::from(round.round_number)
+            || round.round_number >= i16::from(u8::MAX)
+    } else if settings.is_time_attack() {
+        round_timed_out || round.round_number >= i16::from(u8::MAX)
+    } else {
+        round.round_number as u8 >= settings.rounds
+    };
+    if game_over {
+        finalize_solo_game(state, game_id, &settings, &player.user_id, player.score_total).await?;
     }
 
     Ok(())
@@ -524,6 +830,14 @@ async fn load_game_state(
         .await?
         .ok_or_else(|| ApiError::not_found("Game"))?;
 
+    // Challenge rounds all exist up front; playing them here would reveal them
+    if db_game.mode == GameMode::Challenge {
+        return Err(ApiError::bad_request(
+            "INVALID_MODE",
+            "Challenges are played through /api/v1/challenges",
+        ));
+    }
+
     let db_players = dguesser_db::games::get_players(db, game_id).await?;
     let db_rounds = dguesser_db::games::get_rounds_for_game(db, game_id).await?;
 
@@ -550,16 +864,20 @@ async fn load_game_state(
     };
 
     // Build player states
+    let users = UserLoader::load(db, db_players.iter().map(|p| p.user_id.as_str()))
+        .await
+        .unwrap_or_default();
     let mut players = HashMap::new();
     for p in &db_players {
-        let user = dguesser_db::users::get_by_id(db, &p.user_id).await.ok().flatten();
+        let user = users.get(&p.user_id);
         let mut player = PlayerState::new(
             p.user_id.clone(),
-            user.as_ref().map(|u| u.display_name.clone()).unwrap_or_default(),
-            user.as_ref().and_then(|u| u.avatar_url.clone()),
+            user.map(|u| u.display_name.clone()).unwrap_or_default(),
+            user.and_then(|u| u.avatar_url.clone()),
             p.is_host,
         );
         player.total_score = p.score_total as u32;
+        player.handicap = p.handicap;
         player.connected = true; // For REST API, assume connected
         players.insert(p.user_id.clone(), player);
     }
@@ -577,9 +895,14 @@ async fn load_game_state(
             db_round.panorama_id.clone(),
             db_round.location_id.clone(),
             db_round.heading,
+            db_round.provider.parse().unwrap_or_default(),
             db_round.time_limit_ms.map(|t| t as u32),
             db_round.started_at.unwrap_or_else(Utc::now),
         );
+        round.country_code = db_round.country_code.clone();
+        round.hints = dguesser_db::round_hints::get_hints_for_round(db, &db_round.id)
+            .await
+            .unwrap_or_default();
 
         // Add guesses to round state
         for g in db_guesses {
@@ -592,6 +915,8 @@ async fn load_game_state(
                     distance_meters: g.distance_meters,
                     score: g.score as u32,
                     time_taken_ms: g.time_taken_ms.map(|t| t as u32),
+                    reported_panorama_id: None,
+                    country_code: g.country_code,
                     submitted_at: g.submitted_at,
                 },
             );
@@ -610,6 +935,13 @@ async fn load_game_state(
     state.round_number = db_rounds.len() as u8;
     state.created_at = db_game.created_at;
     state.started_at = db_game.started_at;
+    if let Some(started_at) = db_game.started_at
+        && state.settings.is_time_attack()
+    {
+        state.time_budget_ends_at = Some(
+            started_at.timestamp_millis() + i64::from(state.settings.time_budget_seconds) * 1000,
+        );
+    }
 
     Ok((state, current_round_db_id))
 }
@@ -652,33 +984,70 @@ fn reducer_error_to_api_error(result: &dguesser_core::game::ReducerResult) -> Ap
         (status = 201, description = "Game created", body = CreateGameResponse),
         (status = 400, description = "Invalid request"),
         (status = 401, description = "Not authenticated"),
+        (status = 429, description = "Open lobby limit reached or cooldown active"),
     ),
     tag = "games"
 )]
 pub async fn create_game(
     State(state): State<AppState>,
+    org: CurrentOrg,
     auth: AuthUser,
-    Json(req): Json<CreateGameRequest>,
+    ValidatedJson(req): ValidatedJson<CreateGameRequest>,
 ) -> Result<Json<CreateGameResponse>, ApiError> {
-    // Validate request
-    req.validate()?;
-
     // Parse game mode
     let mode = match req.mode.as_str() {
         "solo" => GameMode::Solo,
         "multiplayer" => GameMode::Multiplayer,
-        "challenge" => GameMode::Challenge,
+        "streak" => GameMode::Streak,
+        "challenge" => {
+            return Err(ApiError::bad_request(
+                "INVALID_MODE",
+                "Challenges are created through /api/v1/challenges",
+            ));
+        }
         _ => return Err(ApiError::bad_request("INVALID_MODE", "Invalid game mode")),
     };
 
+    let base = preset_base_settings(&state, &auth.user_id, req.preset_id.as_deref()).await?;
+
+    let classroom = req.classroom.unwrap_or(base.classroom);
+    if classroom && mode != GameMode::Multiplayer {
+        return Err(ApiError::bad_request(
+            "INVALID_MODE",
+            "Only multiplayer games can be classroom games",
+        ));
+    }
+
+    // Games in an organization are for its members
+    org.require_member(&state, &auth.user_id).await?;
+
+    if mode == GameMode::Multiplayer {
+        abuse::check_lobby_creation(&state, &auth.user_id).await?;
+    }
+
     // Build settings
     let settings = serde_json::json!({
-        "rounds": req.rounds.unwrap_or(5),
-        "time_limit_seconds": req.time_limit_seconds.unwrap_or(120),
-        "map_id": req.map_id.clone().unwrap_or_else(|| "world".to_string()),
-        "movement_allowed": req.movement_allowed.unwrap_or(true),
-        "zoom_allowed": req.zoom_allowed.unwrap_or(true),
-        "rotation_allowed": req.rotation_allowed.unwrap_or(true),
+        "rounds": req.rounds.unwrap_or(base.rounds),
+        "time_limit_seconds": req.time_limit_seconds.unwrap_or(base.time_limit_seconds),
+        "map_id": req.map_id.clone().unwrap_or(base.map_id),
+        "movement_allowed": req.movement_allowed.unwrap_or(base.movement_allowed),
+        "zoom_allowed": req.zoom_allowed.unwrap_or(base.zoom_allowed),
+        "rotation_allowed": req.rotation_allowed.unwrap_or(base.rotation_allowed),
+        "streak": mode == GameMode::Streak,
+        "time_budget_seconds": req.time_budget_seconds.unwrap_or(base.time_budget_seconds),
+        "reconnect_grace_seconds": req
+            .reconnect_grace_seconds
+            .unwrap_or(base.reconnect_grace_seconds),
+        "disconnect_policy": req.disconnect_policy.unwrap_or(base.disconnect_policy),
+        "reactions_enabled": req.reactions_enabled.unwrap_or(base.reactions_enabled),
+        "allow_late_join": req.allow_late_join.unwrap_or(base.allow_late_join),
+        "rounds_plan": req
+            .rounds_plan
+            .clone()
+            .unwrap_or_else(|| rounds_plan_payload(&base.rounds_plan)),
+        "classroom": classroom,
+        "hints_enabled": req.hints_enabled.unwrap_or(base.hints_enabled),
+        "hint_cost": req.hint_cost.unwrap_or(base.hint_cost),
     });
 
     // Validate settings using core rules
@@ -687,18 +1056,35 @@ pub async fn create_game(
         return Err(ApiError::bad_request("INVALID_SETTINGS", errors.join(", ")));
     }
 
-    // Generate join code for multiplayer
-    let join_code = if mode == GameMode::Multiplayer { Some(generate_join_code()) } else { None };
+    // Multiplayer lobbies get a join code, picked by the host or random
+    let vanity_code = match req.join_code.as_deref() {
+        Some(_) if mode != GameMode::Multiplayer => {
+            return Err(ApiError::bad_request(
+                "INVALID_CODE",
+                "Only multiplayer games have a join code",
+            ));
+        }
+        Some(code) => Some(vanity_join_code(&state, &auth, code).await?),
+        None => None,
+    };
+    let join_code = match (&vanity_code, mode) {
+        (Some(code), _) => JoinCodeChoice::Vanity(code),
+        (None, GameMode::Multiplayer) => JoinCodeChoice::Random(state.join_codes().length),
+        (None, _) => JoinCodeChoice::None,
+    };
 
     // Create game in database
     let game = dguesser_db::games::create_game(
         state.db(),
         mode,
         &auth.user_id,
-        join_code.as_deref(),
+        join_code,
         settings,
+        org.id(),
     )
-    .await?;
+    .await
+    .map_err(join_code_taken)?;
+    let join_code = game.join_code.clone();
 
     // Add creator as first player (host)
     dguesser_db::games::add_player(state.db(), &game.id, &auth.user_id, true).await?;
@@ -716,6 +1102,7 @@ pub async fn create_game(
         (status = 201, description = "Game details (guest session created)", body = GameDetails),
         (status = 400, description = "Invalid code format"),
         (status = 404, description = "Game not found or not joinable"),
+        (status = 429, description = "Guest account limit reached or cooldown active"),
     ),
     tag = "games"
 )]
@@ -723,58 +1110,105 @@ pub async fn join_game_by_code(
     State(state): State<AppState>,
     headers: HeaderMap,
     MaybeAuthUser(maybe_auth): MaybeAuthUser,
-    Json(req): Json<JoinGameRequest>,
+    ValidatedJson(req): ValidatedJson<JoinGameRequest>,
 ) -> Result<(axum::http::StatusCode, HeaderMap, Json<GameDetails>), ApiError> {
-    // Validate request
-    req.validate()?;
-
     // Auto-create guest session if not authenticated
-    let (is_new_session, session_id) = match maybe_auth {
-        Some(auth) => (false, Some(auth.session_id)),
-        None => {
-            // Extract IP (using secure method) and user agent for guest creation
-            let ip = extract_ip_from_headers(&headers, state.client_ip_config());
-            let user_agent = headers.get("user-agent").and_then(|v| v.to_str().ok());
-
-            let result =
-                create_guest_session(state.db(), state.session_config(), ip.as_deref(), user_agent)
-                    .await?;
-            (true, Some(result.session_id))
-        }
-    };
+    let session = join_session(&state, &headers, maybe_auth).await?;
 
-    // Validate code format (6 alphanumeric characters)
-    let code = req.code.trim().to_uppercase();
-    if code.len() != 6 || !code.chars().all(|c| c.is_ascii_alphanumeric()) {
-        return Err(ApiError::bad_request(
-            "INVALID_CODE",
-            "Join code must be 6 alphanumeric characters",
-        ));
-    }
+    // Validate code format (4-8 alphanumeric characters)
+    let code = normalize_join_code(&req.code).ok_or_else(|| {
+        ApiError::bad_request("INVALID_CODE", "Join code must be 4-8 alphanumeric characters")
+    })?;
 
     // Look up game by join code
     let game = dguesser_db::games::get_game_by_join_code(state.db(), &code)
         .await?
         .ok_or_else(|| ApiError::not_found("Game not found with this code"))?;
 
-    // Validate game is in lobby status
-    if game.status != GameStatus::Lobby {
+    // Validate game is in lobby status, or takes late joiners
+    if !accepts_joins(&game) {
         return Err(ApiError::bad_request(
             "GAME_NOT_JOINABLE",
             "This game has already started or ended",
         ));
     }
 
+    lobby_join_response(&state, game, &session).await
+}
+
+/// Map playlist of a game, as sent to clients
+pub(super) fn rounds_plan_payload(plan: &[MapRounds]) -> Vec<MapRoundsPayload> {
+    plan.iter()
+        .map(|run| MapRoundsPayload { map_id: run.map_id.clone(), rounds: run.rounds })
+        .collect()
+}
+
+/// Whether new players can join a game: any lobby, or a live multiplayer
+/// game that allows late joins.
+pub(super) fn accepts_joins(game: &dguesser_db::games::Game) -> bool {
+    match game.status {
+        GameStatus::Lobby => true,
+        GameStatus::Active => {
+            game.mode == GameMode::Multiplayer
+                && serde_json::from_value::<GameSettings>(game.settings.clone())
+                    .is_ok_and(|settings| settings.allow_late_join)
+        }
+        _ => false,
+    }
+}
+
+/// Session a player joins a lobby with.
+pub(super) struct JoinSession {
+    pub user_id: String,
+    /// Guest session created for this request, to be set as a cookie
+    pub new_session_id: Option<String>,
+}
+
+/// Use the caller's session, or create a guest session if there is none.
+pub(super) async fn join_session(
+    state: &AppState,
+    headers: &HeaderMap,
+    maybe_auth: Option<AuthUser>,
+) -> Result<JoinSession, ApiError> {
+    match maybe_auth {
+        Some(auth) => Ok(JoinSession { user_id: auth.user_id, new_session_id: None }),
+        None => {
+            // Extract IP (using secure method) and user agent for guest creation
+            let client = RequestClient::from_headers(headers, state.client_ip_config());
+            abuse::check_guest_creation(state.redis(), client.ip.as_deref()).await?;
+
+            let result =
+                create_guest_session(state.db(), None, state.session_config(), client.info())
+                    .await?;
+            Ok(JoinSession { user_id: result.user_id, new_session_id: Some(result.session_id) })
+        }
+    }
+}
+
+/// Lobby details for a player about to join over Socket.IO, with the session
+/// cookie set when a guest session was created.
+pub(super) async fn lobby_join_response(
+    state: &AppState,
+    game: dguesser_db::games::Game,
+    session: &JoinSession,
+) -> Result<(axum::http::StatusCode, HeaderMap, Json<GameDetails>), ApiError> {
     // Get players for response
     let players = dguesser_db::games::get_players(state.db(), &game.id).await?;
     let rounds = dguesser_db::games::get_rounds_for_game(state.db(), &game.id).await?;
 
     // Build player info list
     let mut player_infos = Vec::new();
+    let users = UserLoader::load(state.db(), players.iter().map(|p| p.user_id.as_str())).await?;
     for p in players {
-        let user = dguesser_db::users::get_by_id(state.db(), &p.user_id).await?;
-        let (display_name, avatar_url, is_guest) = user
-            .map(|u| (u.display_name, u.avatar_url, u.kind == dguesser_db::UserKind::Guest))
+        let (display_name, avatar_url, is_guest) = users
+            .get(&p.user_id)
+            .map(|u| {
+                (
+                    u.display_name.clone(),
+                    u.avatar_url.clone(),
+                    u.kind == dguesser_db::UserKind::Guest,
+                )
+            })
             .unwrap_or_else(|| ("Unknown".to_string(), None, true));
         player_infos.push(PlayerInfo {
             user_id: p.user_id,
@@ -783,6 +1217,7 @@ pub async fn join_game_by_code(
             is_host: p.is_host,
             is_guest,
             score: p.score_total,
+            handicap: p.handicap,
         });
     }
 
@@ -800,22 +1235,22 @@ pub async fn join_game_by_code(
         players: player_infos,
         current_round: rounds.len() as u8,
         total_rounds,
+        archived: false,
     };
 
     // Build response with Set-Cookie header if new session was created
     let mut response_headers = HeaderMap::new();
-    let status = if is_new_session {
-        if let Some(sid) = session_id {
+    let status = match &session.new_session_id {
+        Some(sid) => {
             let cookie = build_cookie_header(
-                &sid,
+                sid,
                 state.session_config(),
                 state.session_config().max_age_seconds(),
             );
             response_headers.insert(SET_COOKIE, cookie.parse().unwrap());
+            axum::http::StatusCode::CREATED
         }
-        axum::http::StatusCode::CREATED
-    } else {
-        axum::http::StatusCode::OK
+        None => axum::http::StatusCode::OK,
     };
 
     Ok((status, response_headers, Json(game_details)))
@@ -840,7 +1275,7 @@ pub async fn get_game(
     auth: AuthUser,
     Path(id): Path<String>,
 ) -> Result<Json<GameDetails>, ApiError> {
-    reconcile_solo_game(state.db(), &id).await?;
+    reconcile_solo_game(&state, &id).await?;
 
     let game = dguesser_db::games::get_game_by_id(state.db(), &id)
         .await?
@@ -851,29 +1286,49 @@ pub async fn get_game(
 
     // Check if user is a player or has access to this game
     let is_player = players.iter().any(|p| p.user_id == auth.user_id);
-    let is_joinable_lobby = game.mode == GameMode::Multiplayer && game.status == GameStatus::Lobby;
-    if !is_player && !is_joinable_lobby {
+    let is_joinable = game.mode == GameMode::Multiplayer && accepts_joins(&game);
+    if !is_player && !is_joinable {
         return Err(ApiError::forbidden("Not a player in this game"));
     }
 
+    // Challenge scores stay hidden until every player has finished
+    let hide_scores = game.mode == GameMode::Challenge && game.status != GameStatus::Finished;
+
     // Get display names and avatars for players
     let mut player_infos = Vec::new();
+    let users = UserLoader::load(state.db(), players.iter().map(|p| p.user_id.as_str())).await?;
     for p in players {
-        let user = dguesser_db::users::get_by_id(state.db(), &p.user_id).await?;
-        let (display_name, avatar_url, is_guest) = user
-            .map(|u| (u.display_name, u.avatar_url, u.kind == dguesser_db::UserKind::Guest))
+        let (display_name, avatar_url, is_guest) = users
+            .get(&p.user_id)
+            .map(|u| {
+                (
+                    u.display_name.clone(),
+                    u.avatar_url.clone(),
+                    u.kind == dguesser_db::UserKind::Guest,
+                )
+            })
             .unwrap_or_else(|| ("Unknown".to_string(), None, true));
+        let score = if hide_scores && p.user_id != auth.user_id { 0 } else { p.score_total };
         player_infos.push(PlayerInfo {
             user_id: p.user_id,
             display_name,
             avatar_url,
             is_host: p.is_host,
             is_guest,
-            score: p.score_total,
+            score,
+            handicap: p.handicap,
         });
     }
 
-    let total_rounds = game.settings.get("rounds").and_then(|v| v.as_u64()).unwrap_or(5) as u8;
+    let total_rounds = serde_json::from_value::<GameSettings>(game.settings.clone())
+        .unwrap_or_default()
+        .total_rounds();
+
+    // Archived games have no rounds until they are restored
+    let archived = rounds.is_empty()
+        && dguesser_db::game_archive::get_status(state.db(), &id)
+            .await?
+            .is_some_and(|status| status.archived_at.is_some());
 
     Ok(Json(GameDetails {
         id: game.id,
@@ -887,9 +1342,155 @@ pub async fn get_game(
         players: player_infos,
         current_round: rounds.len() as u8,
         total_rounds,
+        archived,
     }))
 }
 
+/// Restore an archived game
+///
+/// Brings back the rounds and guesses of a game moved to cold storage so it
+/// can be viewed again. Restoring a game that is not archived does nothing.
+#[utoipa::path(
+    post,
+    path = "/api/v1/games/{id}/rehydrate",
+    params(
+        ("id" = String, Path, description = "Game ID (e.g., gam_FybH2oF9Xaw8)")
+    ),
+    responses(
+        (status = 204, description = "Game restored or not archived"),
+        (status = 403, description = "Not a player in this game"),
+        (status = 404, description = "Game not found"),
+        (status = 503, description = "Game archive not configured"),
+    ),
+    tag = "games"
+)]
+pub async fn rehydrate_game(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(id): Path<String>,
+) -> Result<axum::http::StatusCode, ApiError> {
+    let status = dguesser_db::game_archive::get_status(state.db(), &id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Game"))?;
+    let players = dguesser_db::games::get_players(state.db(), &id).await?;
+    if !players.iter().any(|p| p.user_id == auth.user_id) {
+        return Err(ApiError::forbidden("Not a player in this game"));
+    }
+    if status.archived_at.is_none() {
+        return Ok(axum::http::StatusCode::NO_CONTENT);
+    }
+
+    let storage = state
+        .archive_storage()
+        .ok_or_else(|| ApiError::service_unavailable("Game archive is not configured"))?;
+    match game_archive::rehydrate(&state, storage.as_ref(), &id).await {
+        Ok(_) => Ok(axum::http::StatusCode::NO_CONTENT),
+        Err(ArchiveError::Database(e)) => Err(e.into()),
+        Err(e) => {
+            tracing::error!(game_id = %id, error = %e, "Failed to rehydrate game");
+            Err(ApiError::internal().with_internal(e.to_string()))
+        }
+    }
+}
+
+/// Export a classroom game's results as CSV
+///
+/// One row per student and round, with the student's total score and final
+/// rank repeated on each row. Only the teacher can export.
+#[utoipa::path(
+    get,
+    path = "/api/v1/games/{id}/export",
+    params(
+        ("id" = String, Path, description = "Game ID (e.g., gam_FybH2oF9Xaw8)")
+    ),
+    responses(
+        (status = 200, description = "Results as CSV", content_type = "text/csv"),
+        (status = 400, description = "Not a classroom game"),
+        (status = 403, description = "Not the teacher of this game"),
+        (status = 404, description = "Game not found"),
+        (status = 409, description = "Game is archived"),
+    ),
+    tag = "games"
+)]
+pub async fn export_game_results(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(id): Path<String>,
+) -> Result<axum::response::Response, ApiError> {
+    use axum::response::IntoResponse;
+
+    let game = dguesser_db::games::get_game_by_id(state.db(), &id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Game"))?;
+    let settings: GameSettings = serde_json::from_value(game.settings).unwrap_or_default();
+    if !settings.classroom {
+        return Err(ApiError::bad_request("NOT_CLASSROOM", "Not a classroom game"));
+    }
+
+    let players = dguesser_db::games::get_players(state.db(), &id).await?;
+    if !players.iter().any(|p| p.is_host && p.user_id == auth.user_id) {
+        return Err(ApiError::forbidden("Only the teacher can export results"));
+    }
+    if dguesser_db::game_archive::get_status(state.db(), &id)
+        .await?
+        .is_some_and(|status| status.archived_at.is_some())
+    {
+        return Err(ApiError::conflict(
+            "GAME_ARCHIVED",
+            "Restore the game with POST /api/v1/games/{id}/rehydrate first",
+        ));
+    }
+
+    let rows = dguesser_db::games::get_classroom_export(state.db(), &id).await?;
+    let disposition = format!("attachment; filename=\"{id}.csv\"");
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        classroom_csv(&rows),
+    )
+        .into_response())
+}
+
+fn classroom_csv(rows: &[dguesser_db::games::ClassroomExportRow]) -> String {
+    fn opt<T: ToString>(value: Option<T>) -> String {
+        value.map(|v| v.to_string()).unwrap_or_default()
+    }
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    // Writing to a Vec cannot fail
+    let _ = writer.write_record([
+        "student_id",
+        "student_name",
+        "round",
+        "guess_lat",
+        "guess_lng",
+        "distance_meters",
+        "score",
+        "time_taken_ms",
+        "total_score",
+        "final_rank",
+    ]);
+    for row in rows {
+        let _ = writer.write_record([
+            row.user_id.clone(),
+            row.display_name.clone(),
+            row.round_number.to_string(),
+            opt(row.guess_lat.map(|lat| format!("{lat:.6}"))),
+            opt(row.guess_lng.map(|lng| format!("{lng:.6}"))),
+            opt(row.distance_meters.map(|d| format!("{d:.0}"))),
+            opt(row.score),
+            opt(row.time_taken_ms),
+            row.score_total.to_string(),
+            opt(row.final_rank),
+        ]);
+    }
+    let bytes = writer.into_inner().unwrap_or_default();
+    String::from_utf8(bytes).unwrap_or_default()
+}
+
 /// Get persisted results for a finished solo game.
 #[utoipa::path(
     get,
@@ -910,13 +1511,13 @@ pub async fn get_game_results(
     auth: AuthUser,
     Path(id): Path<String>,
 ) -> Result<Json<GameResultsResponse>, ApiError> {
-    reconcile_solo_game(state.db(), &id).await?;
+    reconcile_solo_game(&state, &id).await?;
 
     let game = dguesser_db::games::get_game_by_id(state.db(), &id)
         .await?
         .ok_or_else(|| ApiError::not_found("Game"))?;
 
-    if game.mode != GameMode::Solo {
+    if !game.mode.is_single_player() {
         return Err(ApiError::bad_request(
             "INVALID_MODE",
             "Persisted results via API are only available for solo games",
@@ -938,6 +1539,226 @@ pub async fn get_game_results(
     Ok(Json(build_game_results(state.db(), &id).await?))
 }
 
+/// Render a shareable results card for a finished game.
+///
+/// Cards are public so link previews work. Players who haven't made
+/// themselves public on the leaderboard appear anonymized.
+#[utoipa::path(
+    get,
+    path = "/api/v1/games/{id}/card",
+    params(
+        ("id" = String, Path, description = "Game ID"),
+        ("format" = Option<String>, Query, description = "Image format: png (default) or svg"),
+    ),
+    responses(
+        (status = 200, description = "Results card image (PNG, or SVG with `format=svg`)", content_type = "image/png"),
+        (status = 400, description = "Game is not finished"),
+        (status = 404, description = "Game not found"),
+    ),
+    tag = "games"
+)]
+pub async fn get_game_card(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<super::users::CardQuery>,
+) -> Result<axum::response::Response, ApiError> {
+    let game = dguesser_db::games::get_game_by_id(state.db_read(), &id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Game"))?;
+    if game.status != GameStatus::Finished {
+        return Err(ApiError::bad_request(
+            "GAME_NOT_FINISHED",
+            "Cards are only available after the game has finished",
+        ));
+    }
+
+    let players = dguesser_db::games::get_players(state.db_read(), &id).await?;
+    let users =
+        UserLoader::load(state.db_read(), players.iter().map(|p| p.user_id.as_str())).await?;
+
+    let mut standings: Vec<GameCardStanding> = players
+        .iter()
+        .map(|player| GameCardStanding {
+            rank: 0,
+            display_name: users
+                .get(&player.user_id)
+                .filter(|u| u.leaderboard_public)
+                .map(|u| u.display_name.clone())
+                .unwrap_or_else(|| "Anonymous Player".to_string()),
+            score: player.score_total.max(0).into(),
+        })
+        .collect();
+    standings.sort_by_key(|s| std::cmp::Reverse(s.score));
+    for (index, standing) in standings.iter_mut().enumerate() {
+        standing.rank = (index + 1) as u8;
+    }
+
+    let scene = GameCard {
+        mode: game.mode.to_string(),
+        rounds: serde_json::from_value::<GameSettings>(game.settings.clone())
+            .unwrap_or_default()
+            .total_rounds() as usize,
+        streak: game.mode == GameMode::Streak,
+        standings,
+    }
+    .scene();
+
+    super::users::card_response(scene, query.format).await
+}
+
+/// Query params for round result cards
+#[derive(Debug, Deserialize)]
+pub struct RoundCardQuery {
+    /// Player whose result to show (defaults to the signed-in user)
+    pub user_id: Option<String>,
+}
+
+/// How long a rendered round card stays marked as stored in object storage
+const ROUND_CARD_CACHE_TTL_SECS: u64 = 30 * 24 * 60 * 60;
+
+/// Render a shareable result image for one player's round: a map with the
+/// guess and the actual location, plus the score.
+///
+/// Available once the round is over. Images are stored in object storage
+/// (when configured) and later requests redirect there.
+#[utoipa::path(
+    get,
+    path = "/api/v1/games/{id}/rounds/{round_number}/card",
+    params(
+        ("id" = String, Path, description = "Game ID"),
+        ("round_number" = u8, Path, description = "Round number (1-based)"),
+        ("user_id" = Option<String>, Query, description = "Player to show (defaults to you)"),
+    ),
+    responses(
+        (status = 200, description = "Round result image", content_type = "image/png"),
+        (status = 307, description = "Redirect to the stored image"),
+        (status = 400, description = "Round is not over, or no player given"),
+        (status = 404, description = "Game, round or player not found"),
+    ),
+    tag = "games"
+)]
+pub async fn get_round_card(
+    State(state): State<AppState>,
+    maybe_auth: MaybeAuthUser,
+    Path((id, round_number)): Path<(String, u8)>,
+    Query(query): Query<RoundCardQuery>,
+) -> Result<axum::response::Response, ApiError> {
+    use axum::response::{IntoResponse, Redirect};
+
+    let user_id = query
+        .user_id
+        .or_else(|| maybe_auth.0.map(|auth| auth.user_id))
+        .ok_or_else(|| ApiError::bad_request("PLAYER_REQUIRED", "Choose whose result to show"))?;
+
+    let game = dguesser_db::games::get_game_by_id(state.db_read(), &id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Game"))?;
+    let round = dguesser_db::games::get_rounds_for_game(state.db_read(), &id)
+        .await?
+        .into_iter()
+        .find(|r| r.round_number == i16::from(round_number))
+        .ok_or_else(|| ApiError::not_found("Round"))?;
+    // Cards show the location, so never while the round can still be played
+    if game.status != GameStatus::Finished && round.ended_at.is_none() {
+        return Err(ApiError::bad_request(
+            "ROUND_NOT_FINISHED",
+            "Round cards are only available after the round has ended",
+        ));
+    }
+    if !dguesser_db::games::is_player_in_game(state.db_read(), &id, &user_id).await? {
+        return Err(ApiError::not_found("Player"));
+    }
+
+    let key = format!("cards/rounds/{}/{user_id}.png", round.id);
+    let marker = format!("round_card:{}:{user_id}", round.id);
+    if let Some(storage) = state.storage()
+        && round_card_stored(state.redis(), &marker).await
+    {
+        return Ok(Redirect::temporary(&storage.public_url(&key)).into_response());
+    }
+
+    let guess = dguesser_db::games::get_guess(state.db_read(), &round.id, &user_id).await?;
+    let settings: GameSettings = serde_json::from_value(game.settings.clone()).unwrap_or_default();
+    let streak = game.mode == GameMode::Streak;
+    let scene = RoundCard {
+        round_number,
+        total_rounds: (!streak).then(|| settings.total_rounds() as usize),
+        score: guess.as_ref().map(|g| i64::from(g.score)),
+        distance_meters: guess.as_ref().filter(|_| !streak).map(|g| g.distance_meters),
+        correct_country: guess
+            .as_ref()
+            .filter(|_| streak)
+            .map(|g| g.country_code.is_some() && g.country_code == round.country_code),
+    }
+    .scene();
+
+    let map = crate::static_map::ResultMap {
+        width: 1200,
+        height: 470,
+        actual: (round.location_lat, round.location_lng),
+        guess: guess.as_ref().map(|g| (g.guess_lat, g.guess_lng)),
+    };
+    let map = match state.static_map().fetch(&map).await {
+        Ok(map) => Some(map),
+        Err(e) => {
+            tracing::warn!(error = %e, round_id = %round.id, "Failed to fetch result map");
+            None
+        }
+    };
+
+    let has_map = map.is_some();
+    let image = tokio::task::spawn_blocking(move || match map {
+        Some(map) => scene.to_png_over(&map),
+        None => scene.to_png(),
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, "Card rendering panicked");
+        ApiError::internal()
+    })?;
+
+    // Only complete cards are stored, so a missing map is retried later
+    if has_map && let Some(storage) = state.storage() {
+        match storage.put(&key, image.clone().into(), "image/png").await {
+            Ok(()) => mark_round_card_stored(state.redis(), &marker).await,
+            Err(e) => tracing::warn!(error = %e, key = %key, "Failed to store round card"),
+        }
+    }
+
+    Ok((
+        [
+            (axum::http::header::CONTENT_TYPE, "image/png"),
+            (axum::http::header::CACHE_CONTROL, super::users::CARD_CACHE_CONTROL),
+        ],
+        image,
+    )
+        .into_response())
+}
+
+/// Whether a round card was already stored in object storage
+async fn round_card_stored(redis: &RedisConnection, marker: &str) -> bool {
+    let result: Result<bool, redis::RedisError> =
+        redis::cmd("EXISTS").arg(marker).query_async(&mut redis.clone()).await;
+    result.unwrap_or_else(|e| {
+        tracing::warn!(error = %e, "Failed to check round card cache");
+        false
+    })
+}
+
+/// Remember that a round card was stored in object storage
+async fn mark_round_card_stored(redis: &RedisConnection, marker: &str) {
+    let result: Result<(), redis::RedisError> = redis::cmd("SET")
+        .arg(marker)
+        .arg(1)
+        .arg("EX")
+        .arg(ROUND_CARD_CACHE_TTL_SECS)
+        .query_async(&mut redis.clone())
+        .await;
+    if let Err(e) = result {
+        tracing::warn!(error = %e, "Failed to mark round card as stored");
+    }
+}
+
 /// Start a game (transition from lobby to active)
 #[utoipa::path(
     post,
@@ -961,18 +1782,26 @@ pub async fn start_game(
     let now = Utc::now();
 
     // Load current game state
-    let (game_state, _) = load_game_state(state.db(), &id).await?;
-
-    // MAP-004: Validate that the map has enough locations for the requested rounds
-    let map_id = &game_state.settings.map_id;
-    let location_count = state.location_provider().get_location_count(map_id).await.unwrap_or(0);
-    let validation = validate_location_count(game_state.settings.rounds, location_count);
-    if let Some(error_msg) = validation.error_message() {
-        return Err(ApiError::bad_request("INSUFFICIENT_LOCATIONS", &error_msg));
+    let (mut game_state, _) = load_game_state(state.db(), &id).await?;
+
+    // MAP-004: Validate that each map has enough locations for its rounds
+    for (map_id, rounds) in game_state.settings.rounds_per_map() {
+        let location_count =
+            state.location_provider().get_location_count(map_id).await.unwrap_or(0);
+        let validation = validate_location_count(rounds, location_count);
+        if let Some(error_msg) = validation.error_message() {
+            return Err(ApiError::bad_request("INSUFFICIENT_LOCATIONS", &error_msg));
+        }
     }
+    let map_id = &game_state.settings.map_id;
+
+    // The map's scoring curve is fixed for the game when it starts
+    game_state.settings.scoring = map_scoring(&state, map_id).await;
+    let map_id = game_state.settings.map_for_round(1);
 
     // Select location for first round (no previous locations)
-    let location = select_location(state.location_provider(), map_id, &[], &[]).await;
+    let user_ids: Vec<String> = game_state.players.keys().cloned().collect();
+    let location = select_location(&state, &id, &user_ids, map_id, &[], &[]).await;
 
     // Use reducer for validation
     let result = reduce(
@@ -987,12 +1816,10 @@ pub async fn start_game(
 
     // Persist to database
     dguesser_db::games::update_game_status(state.db(), &id, GameStatus::Active).await?;
+    let settings_json = serde_json::to_value(&result.state.settings).unwrap_or_default();
+    dguesser_db::games::update_game_settings(state.db(), &id, settings_json).await?;
 
-    let time_limit_ms = if game_state.settings.time_limit_seconds > 0 {
-        Some(game_state.settings.time_limit_seconds * 1000)
-    } else {
-        None
-    };
+    let time_limit_ms = result.state.round_time_limit_ms(now);
 
     let round = dguesser_db::games::create_round(
         state.db(),
@@ -1003,6 +1830,8 @@ pub async fn start_game(
         location.panorama_id.as_deref(),
         location.location_id.as_deref(),
         location.heading,
+        location.provider,
+        location.country_code.as_deref(),
         time_limit_ms.map(|t| t as i32),
     )
     .await?;
@@ -1016,6 +1845,7 @@ pub async fn start_game(
             lng: location.lng,
             panorama_id: location.panorama_id,
             location_id: location.location_id,
+            provider: location.provider,
         },
         started_at: now,
         time_limit_ms,
@@ -1044,7 +1874,7 @@ pub async fn get_current_round(
 ) -> Result<Json<CurrentRoundInfo>, ApiError> {
     let now = Utc::now();
 
-    reconcile_solo_game(state.db(), &id).await?;
+    reconcile_solo_game(&state, &id).await?;
 
     // Load game state
     let (game_state, _) = load_game_state(state.db(), &id).await?;
@@ -1079,12 +1909,13 @@ pub async fn get_current_round(
 
     Ok(Json(CurrentRoundInfo {
         round_number: round.round_number,
-        total_rounds: game_state.settings.rounds,
+        total_rounds: game_state.total_rounds(),
         location: LocationInfo {
             lat: round.location_lat,
             lng: round.location_lng,
             panorama_id: round.panorama_id.clone(),
             location_id: round.location_id.clone(),
+            provider: round.provider,
         },
         started_at: round.started_at,
         time_remaining_ms,
@@ -1128,7 +1959,7 @@ pub async fn next_round(
         .await?
         .ok_or_else(|| ApiError::not_found("Game"))?;
 
-    if db_game.mode != GameMode::Solo {
+    if !db_game.mode.is_single_player() {
         return Err(ApiError::bad_request(
             "INVALID_MODE",
             "Next round via API only available for solo games",
@@ -1160,71 +1991,81 @@ pub async fn next_round(
         dguesser_db::games::end_round(state.db(), round_id).await?;
     }
 
+    advance_solo_round(&state, &id, &auth.user_id, &game_state, now).await.map(Json)
+}
+
+/// Start the next round of a solo game whose current round has ended, or
+/// finalize the game when no round is left.
+async fn advance_solo_round(
+    state: &AppState,
+    id: &str,
+    user_id: &str,
+    game_state: &GameState,
+    now: DateTime<Utc>,
+) -> Result<RoundInfo, ApiError> {
     // Check if game should end
-    if game_state.round_number >= game_state.settings.rounds {
-        let player_score =
-            game_state.players.get(&auth.user_id).map(|p| p.total_score).unwrap_or(0);
-        finalize_solo_game(state.db(), &id, &auth.user_id, player_score as i32).await?;
+    if !game_state.can_start_round(now) {
+        let player_score = game_state.players.get(user_id).map(|p| p.total_score).unwrap_or(0);
+        finalize_solo_game(state, id, &game_state.settings, user_id, player_score as i32).await?;
 
         return Err(ApiError::bad_request("GAME_COMPLETE", "All rounds completed"));
     }
 
     // Select location for next round with distance constraints
-    let map_id = &game_state.settings.map_id;
-    let db_rounds = dguesser_db::games::get_rounds_for_game(state.db(), &id).await?;
+    let map_id = game_state.settings.map_for_round(game_state.round_number + 1);
+    let db_rounds = dguesser_db::games::get_rounds_for_game(state.db(), id).await?;
     let exclude_ids: Vec<String> = db_rounds.iter().filter_map(|r| r.panorama_id.clone()).collect();
     let previous_locations: Vec<(f64, f64)> =
         db_rounds.iter().map(|r| (r.location_lat, r.location_lng)).collect();
+    let user_ids: Vec<String> = game_state.players.keys().cloned().collect();
     let location =
-        select_location(state.location_provider(), map_id, &exclude_ids, &previous_locations).await;
+        select_location(state, id, &user_ids, map_id, &exclude_ids, &previous_locations).await;
 
     // Use reducer for validation
     let result =
-        reduce(&game_state, GameCommand::AdvanceRound { next_location: location.clone() }, now);
+        reduce(game_state, GameCommand::AdvanceRound { next_location: location.clone() }, now);
 
     if result.has_error() {
         // Game is complete
-        let player_score =
-            game_state.players.get(&auth.user_id).map(|p| p.total_score).unwrap_or(0);
-        finalize_solo_game(state.db(), &id, &auth.user_id, player_score as i32).await?;
+        let player_score = game_state.players.get(user_id).map(|p| p.total_score).unwrap_or(0);
+        finalize_solo_game(state, id, &game_state.settings, user_id, player_score as i32).await?;
 
         return Err(ApiError::bad_request("GAME_COMPLETE", "All rounds completed"));
     }
 
     // Persist to database
     let next_round_number = result.state.round_number;
-    let time_limit_ms = if game_state.settings.time_limit_seconds > 0 {
-        Some(game_state.settings.time_limit_seconds * 1000)
-    } else {
-        None
-    };
+    let time_limit_ms = result.state.round_time_limit_ms(now);
 
     let round = dguesser_db::games::create_round(
         state.db(),
-        &id,
+        id,
         next_round_number as i16,
         location.lat,
         location.lng,
         location.panorama_id.as_deref(),
         location.location_id.as_deref(),
         location.heading,
+        location.provider,
+        location.country_code.as_deref(),
         time_limit_ms.map(|t| t as i32),
     )
     .await?;
 
     dguesser_db::games::start_round(state.db(), &round.id).await?;
 
-    Ok(Json(RoundInfo {
+    Ok(RoundInfo {
         round_number: next_round_number,
         location: LocationInfo {
             lat: location.lat,
             lng: location.lng,
             panorama_id: location.panorama_id,
             location_id: location.location_id,
+            provider: location.provider,
         },
         started_at: now,
         time_limit_ms,
-    }))
+    })
 }
 
 /// Submit a guess for a round
@@ -1238,7 +2079,7 @@ pub async fn next_round(
     request_body = SubmitGuessRequest,
     responses(
         (status = 200, description = "Guess result", body = GuessResultResponse),
-        (status = 400, description = "Invalid coordinates or time expired"),
+        (status = 400, description = "Invalid coordinates, time expired, or panorama mismatch in a no-move game"),
         (status = 404, description = "Game or round not found"),
         (status = 409, description = "Already submitted guess"),
     ),
@@ -1248,13 +2089,10 @@ pub async fn submit_guess(
     State(state): State<AppState>,
     auth: AuthUser,
     Path((game_id, round_number)): Path<(String, u8)>,
-    Json(req): Json<SubmitGuessRequest>,
+    ValidatedJson(req): ValidatedJson<SubmitGuessRequest>,
 ) -> Result<Json<GuessResultResponse>, ApiError> {
     let now = Utc::now();
 
-    // Validate request (coordinates range checked by validator)
-    req.validate()?;
-
     // Load game state
     let (game_state, current_round_db_id) = load_game_state(state.db(), &game_id).await?;
     let db_game = dguesser_db::games::get_game_by_id(state.db(), &game_id)
@@ -1280,32 +2118,46 @@ pub async fn submit_guess(
             lat: req.lat,
             lng: req.lng,
             time_taken_ms: req.time_taken_ms,
+            reported_panorama_id: req.panorama_id.clone(),
+            country_code: req.country_code.clone(),
         },
         now,
     );
 
     if result.has_error() {
+        if extract_reducer_error(&result).is_some_and(|(code, _)| code == "PANORAMA_MISMATCH") {
+            record_panorama_mismatch(
+                &state,
+                &auth.user_id,
+                &game_id,
+                current_round_db_id.as_deref(),
+                current_round.panorama_id.as_deref(),
+                req.panorama_id.as_deref(),
+            )
+            .await;
+        }
         return Err(reducer_error_to_api_error(&result));
     }
 
-    // Extract guess result from updated state
+    // Extract guess result from updated state (a time-attack guess ends the
+    // round straight away)
     let guess = result
         .state
-        .current_round
-        .as_ref()
+        .latest_round()
         .and_then(|r| r.guesses.get(&auth.user_id))
         .ok_or_else(|| ApiError::internal().with_internal("Guess not recorded"))?;
 
     let distance = guess.distance_meters;
     let score = guess.score;
-    let is_last_round = current_round.round_number >= game_state.settings.rounds;
+    let is_last_round = !result.state.can_start_round(now);
+    let streak = game_state.settings.streak;
 
     // Get round DB ID
     let round_db_id = current_round_db_id
         .ok_or_else(|| ApiError::internal().with_internal("Round DB ID not found"))?;
 
     // Persist to database
-    dguesser_db::games::create_guess(
+    let stored_guess = dguesser_db::games::create_guess(
         state.db(),
         &round_db_id,
         &auth.user_id,
@@ -1314,22 +2166,38 @@ pub async fn submit_guess(
         distance,
         score as i32,
         req.time_taken_ms.map(|t| t as i32),
+        req.panorama_id.as_deref(),
+        req.country_code.as_deref(),
     )
     .await?;
 
-    // Update player's total score and get the new total
+    // Update player's total score (with their handicap) and get the new total
+    let points =
+        game_state.players.get(&auth.user_id).map_or(score, |p| apply_handicap(score, p.handicap));
     let total_score =
-        dguesser_db::games::update_player_score(state.db(), &game_id, &auth.user_id, score as i32)
+        dguesser_db::games::update_player_score(state.db(), &game_id, &auth.user_id, points as i32)
             .await?;
 
-    if db_game.mode == GameMode::Solo {
+    let mut next_round = None;
+    let mut score_signature = None;
+    if db_game.mode.is_single_player() {
+        score_signature =
+            sign_solo_guess(&state, &game_id, i16::from(round_number), &stored_guess).await?;
         dguesser_db::games::end_round(state.db(), &round_db_id).await?;
 
         if is_last_round {
-            finalize_solo_game(state.db(), &game_id, &auth.user_id, total_score).await?;
+            finalize_solo_game(&state, &game_id, &game_state.settings, &auth.user_id, total_score)
+                .await?;
+        } else if game_state.settings.is_time_attack() {
+            // The clock keeps running, so the next round starts right away
+            next_round = Some(
+                advance_solo_round(&state, &game_id, &auth.user_id, &result.state, now).await?,
+            );
         }
     }
 
+    let stats = location_guess_stats(&state, current_round.location_id.as_deref()).await;
+
     Ok(Json(GuessResultResponse {
         distance_meters: distance,
         score,
@@ -1339,10 +2207,89 @@ pub async fn submit_guess(
             lng: current_round.location_lng,
             panorama_id: current_round.panorama_id.clone(),
             location_id: current_round.location_id.clone(),
+            provider: current_round.provider,
         },
+        percentile: stats.as_ref().and_then(|s| s.percentile_beaten(distance)),
+        global_stats: stats.as_ref().map(global_guess_stats),
+        correct_country_code: current_round.country_code.clone().filter(|_| streak),
+        streak: streak.then_some(total_score as u32),
+        next_round,
+        score_signature,
     }))
 }
 
+/// Take a hint for the current round. Each new hint costs the game's hint
+/// cost, taken off the player's guess for the round; asking again for a hint
+/// already taken returns it for free.
+#[utoipa::path(
+    post,
+    path = "/api/v1/games/{game_id}/rounds/{round_number}/hint",
+    params(
+        ("game_id" = String, Path, description = "Game ID"),
+        ("round_number" = u8, Path, description = "Round number (1-based)")
+    ),
+    request_body = HintRequest,
+    responses(
+        (status = 200, description = "Hint revealed", body = HintResponse),
+        (status = 400, description = "Hints disabled, unknown kind, or round not active"),
+        (status = 403, description = "Not a player in this game"),
+        (status = 404, description = "Game or round not found"),
+        (status = 409, description = "Already submitted guess"),
+    ),
+    tag = "games"
+)]
+pub async fn request_hint(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path((game_id, round_number)): Path<(String, u8)>,
+    ValidatedJson(req): ValidatedJson<HintRequest>,
+) -> Result<Json<HintResponse>, ApiError> {
+    let now = Utc::now();
+    let kind =
+        req.kind.parse().map_err(|_| ApiError::bad_request("INVALID_HINT", "Unknown hint kind"))?;
+
+    let (game_state, current_round_db_id) = load_game_state(state.db(), &game_id).await?;
+    let current_round =
+        game_state.current_round.as_ref().ok_or_else(|| ApiError::not_found("Round"))?;
+    if current_round.round_number != round_number {
+        return Err(ApiError::bad_request(
+            "WRONG_ROUND",
+            "Round number does not match current round",
+        ));
+    }
+
+    let result = reduce(
+        &game_state,
+        GameCommand::RequestHint {
+            user_id: auth.user_id.clone(),
+            kind,
+            from: req.from_lat.zip(req.from_lng),
+        },
+        now,
+    );
+    if result.has_error() {
+        return Err(reducer_error_to_api_error(&result));
+    }
+
+    let (hint, cost) = result
+        .events
+        .iter()
+        .find_map(|e| match e {
+            GameEvent::HintRevealed { hint, cost, .. } => Some((hint, *cost)),
+            _ => None,
+        })
+        .ok_or_else(|| ApiError::internal().with_internal("Hint not revealed"))?;
+
+    if cost > 0 {
+        let round_db_id = current_round_db_id
+            .ok_or_else(|| ApiError::internal().with_internal("Round DB ID not found"))?;
+        dguesser_db::round_hints::record_hint(state.db(), &round_db_id, &auth.user_id, hint, cost)
+            .await?;
+    }
+
+    Ok(Json(HintResponse { kind: hint.kind().as_str().to_string(), value: hint.value(), cost }))
+}
+
 /// Submit a timed-out round with no guess for a solo game.
 #[utoipa::path(
     post,
@@ -1372,7 +2319,7 @@ pub async fn timeout_round(
         .await?
         .ok_or_else(|| ApiError::not_found("Game"))?;
 
-    if db_game.mode != GameMode::Solo {
+    if !db_game.mode.is_single_player() {
         return Err(ApiError::bad_request(
             "INVALID_MODE",
             "Round timeout via API is only available for solo games",
@@ -1408,36 +2355,53 @@ pub async fn timeout_round(
     let round_db_id = current_round_db_id
         .ok_or_else(|| ApiError::internal().with_internal("Round DB ID not found"))?;
 
-    dguesser_db::games::create_guess(
+    let guess = dguesser_db::games::create_guess(
         state.db(),
         &round_db_id,
         &auth.user_id,
-        SOLO_NO_GUESS_LAT,
-        SOLO_NO_GUESS_LNG,
-        SOLO_NO_GUESS_DISTANCE_METERS,
-        SOLO_NO_GUESS_SCORE,
+        NO_GUESS_LAT,
+        NO_GUESS_LNG,
+        NO_GUESS_DISTANCE_METERS,
+        NO_GUESS_SCORE,
+        None,
+        None,
         None,
     )
     .await?;
+    let score_signature =
+        sign_solo_guess(&state, &game_id, i16::from(round_number), &guess).await?;
 
     dguesser_db::games::end_round(state.db(), &round_db_id).await?;
 
     let total_score =
         game_state.players.get(&auth.user_id).map(|p| p.total_score).unwrap_or(0) as i32;
-    if current_round.round_number >= game_state.settings.rounds {
-        finalize_solo_game(state.db(), &game_id, &auth.user_id, total_score).await?;
+    // Running out of time ends a streak, and a time-attack round only runs
+    // out once the whole budget is spent
+    let streak = game_state.settings.streak;
+    if streak || !game_state.can_start_round(now) {
+        finalize_solo_game(&state, &game_id, &game_state.settings, &auth.user_id, total_score)
+            .await?;
     }
 
+    let stats = location_guess_stats(&state, current_round.location_id.as_deref()).await;
+
     Ok(Json(GuessResultResponse {
-        distance_meters: SOLO_NO_GUESS_DISTANCE_METERS,
-        score: SOLO_NO_GUESS_SCORE as u32,
+        distance_meters: NO_GUESS_DISTANCE_METERS,
+        score: NO_GUESS_SCORE as u32,
         total_score: total_score as u32,
         correct_location: LocationInfo {
             lat: current_round.location_lat,
             lng: current_round.location_lng,
             panorama_id: current_round.panorama_id.clone(),
             location_id: current_round.location_id.clone(),
+            provider: current_round.provider,
         },
+        percentile: None,
+        global_stats: stats.as_ref().map(global_guess_stats),
+        correct_country_code: current_round.country_code.clone().filter(|_| streak),
+        streak: streak.then_some(total_score as u32),
+        next_round: None,
+        score_signature,
     }))
 }
 
@@ -1455,11 +2419,11 @@ pub async fn get_game_history(
     State(state): State<AppState>,
     auth: AuthUser,
 ) -> Result<Json<Vec<GameSummary>>, ApiError> {
-    let games = dguesser_db::games::get_user_games(state.db(), &auth.user_id, 20).await?;
+    let games = dguesser_db::games::get_user_games(state.db_read(), &auth.user_id, 20).await?;
 
     let mut summaries = Vec::new();
     for game in games {
-        reconcile_solo_game(state.db(), &game.id).await?;
+        reconcile_solo_game(&state, &game.id).await?;
 
         let game = dguesser_db::games::get_game_by_id(state.db(), &game.id)
             .await?
@@ -1500,13 +2464,10 @@ pub async fn update_settings(
     State(state): State<AppState>,
     auth: AuthUser,
     Path(id): Path<String>,
-    Json(req): Json<UpdateSettingsRequest>,
+    ValidatedJson(req): ValidatedJson<UpdateSettingsRequest>,
 ) -> Result<Json<UpdateSettingsResponse>, ApiError> {
     let now = Utc::now();
 
-    // Validate request
-    req.validate()?;
-
     // Load current game state
     let (game_state, _) = load_game_state(state.db(), &id).await?;
 
@@ -1530,6 +2491,33 @@ pub async fn update_settings(
     if let Some(rotation_allowed) = req.rotation_allowed {
         new_settings.rotation_allowed = rotation_allowed;
     }
+    if let Some(time_budget_seconds) = req.time_budget_seconds {
+        new_settings.time_budget_seconds = time_budget_seconds;
+    }
+    if let Some(reconnect_grace_seconds) = req.reconnect_grace_seconds {
+        new_settings.reconnect_grace_seconds = reconnect_grace_seconds;
+    }
+    if let Some(disconnect_policy) = req.disconnect_policy {
+        new_settings.disconnect_policy = disconnect_policy;
+    }
+    if let Some(reactions_enabled) = req.reactions_enabled {
+        new_settings.reactions_enabled = reactions_enabled;
+    }
+    if let Some(allow_late_join) = req.allow_late_join {
+        new_settings.allow_late_join = allow_late_join;
+    }
+    if let Some(hints_enabled) = req.hints_enabled {
+        new_settings.hints_enabled = hints_enabled;
+    }
+    if let Some(hint_cost) = req.hint_cost {
+        new_settings.hint_cost = hint_cost;
+    }
+    if let Some(rounds_plan) = &req.rounds_plan {
+        new_settings.rounds_plan = rounds_plan
+            .iter()
+            .map(|run| MapRounds { map_id: run.map_id.clone(), rounds: run.rounds })
+            .collect();
+    }
 
     // Use reducer for validation
     let result = reduce(
@@ -1545,11 +2533,7 @@ pub async fn update_settings(
         return Err(reducer_error_to_api_error(&result));
     }
 
-    // Persist to database
-    let settings_json = serde_json::to_value(&new_settings).unwrap_or_default();
-    dguesser_db::games::update_game_settings(state.db(), &id, settings_json).await?;
-
-    // Broadcast settings update to connected clients via Socket.IO
+    // Announce the change to connected clients via Socket.IO
     let socket_payload = SettingsUpdatedPayload {
         game_id: id.clone(),
         settings: GameSettingsPayload {
@@ -1559,19 +2543,25 @@ pub async fn update_settings(
             movement_allowed: new_settings.movement_allowed,
             zoom_allowed: new_settings.zoom_allowed,
             rotation_allowed: new_settings.rotation_allowed,
+            time_budget_seconds: new_settings.time_budget_seconds,
+            reconnect_grace_seconds: new_settings.reconnect_grace_seconds,
+            disconnect_policy: new_settings.disconnect_policy.to_string(),
+            reactions_enabled: new_settings.reactions_enabled,
+            allow_late_join: new_settings.allow_late_join,
+            rounds_plan: rounds_plan_payload(&new_settings.rounds_plan),
+            classroom: new_settings.classroom,
+            hints_enabled: new_settings.hints_enabled,
+            hint_cost: new_settings.hint_cost,
         },
     };
 
-    // Emit to game room - fire and forget, don't fail the API call if emit fails
-    if let Err(e) =
-        socket::emit_to_room(state.redis(), &id, SETTINGS_UPDATED, &socket_payload).await
-    {
-        tracing::warn!(
-            game_id = %id,
-            error = %e,
-            "Failed to broadcast settings update to socket room"
-        );
-    }
+    // Persist with the event, which is published once the change commits
+    let settings_json = serde_json::to_value(&new_settings).unwrap_or_default();
+    let mut tx = state.db().begin().await?;
+    dguesser_db::games::update_game_settings(&mut *tx, &id, settings_json).await?;
+    outbox::enqueue(&mut tx, &id, SETTINGS_UPDATED, &socket_payload).await?;
+    tx.commit().await?;
+    state.wake_outbox_relay();
 
     Ok(Json(UpdateSettingsResponse {
         settings: SettingsDto {
@@ -1581,10 +2571,119 @@ pub async fn update_settings(
             movement_allowed: new_settings.movement_allowed,
             zoom_allowed: new_settings.zoom_allowed,
             rotation_allowed: new_settings.rotation_allowed,
+            time_budget_seconds: new_settings.time_budget_seconds,
+            reconnect_grace_seconds: new_settings.reconnect_grace_seconds,
+            disconnect_policy: new_settings.disconnect_policy,
+            reactions_enabled: new_settings.reactions_enabled,
+            allow_late_join: new_settings.allow_late_join,
+            rounds_plan: rounds_plan_payload(&new_settings.rounds_plan),
+            classroom: new_settings.classroom,
+            hints_enabled: new_settings.hints_enabled,
+            hint_cost: new_settings.hint_cost,
         },
     }))
 }
 
+/// Replace a lobby's join code (host only, lobby only)
+///
+/// Old invites stop working. The host may pick a vanity code, otherwise a
+/// new random code is generated.
+#[utoipa::path(
+    post,
+    path = "/api/v1/games/{id}/code/rotate",
+    params(
+        ("id" = String, Path, description = "Game ID")
+    ),
+    request_body = RotateJoinCodeRequest,
+    responses(
+        (status = 200, description = "Join code replaced", body = RotateJoinCodeResponse),
+        (status = 400, description = "Invalid code or game not in lobby"),
+        (status = 403, description = "Not the host, or vanity codes not allowed"),
+        (status = 404, description = "Game not found"),
+        (status = 409, description = "Join code already in use"),
+    ),
+    tag = "games"
+)]
+pub async fn rotate_join_code(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(id): Path<String>,
+    req: Option<Json<RotateJoinCodeRequest>>,
+) -> Result<Json<RotateJoinCodeResponse>, ApiError> {
+    let req = req.map(|Json(req)| req).unwrap_or_default();
+
+    let game = dguesser_db::games::get_game_by_id(state.db(), &id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Game"))?;
+    if game.mode != GameMode::Multiplayer {
+        return Err(ApiError::bad_request(
+            "INVALID_MODE",
+            "Only multiplayer games have a join code",
+        ));
+    }
+    let is_host = dguesser_db::games::get_players(state.db(), &id)
+        .await?
+        .iter()
+        .any(|p| p.user_id == auth.user_id && p.is_host);
+    if !is_host {
+        return Err(ApiError::forbidden("Only the host can change the join code"));
+    }
+
+    let vanity_code = match req.code.as_deref() {
+        Some(code) => Some(vanity_join_code(&state, &auth, code).await?),
+        None => None,
+    };
+    let choice = match &vanity_code {
+        Some(code) => JoinCodeChoice::Vanity(code),
+        None => JoinCodeChoice::Random(state.join_codes().length),
+    };
+
+    let mut tx = state.db().begin().await?;
+    let join_code = dguesser_db::games::rotate_join_code(&mut tx, &id, choice)
+        .await
+        .map_err(join_code_taken)?
+        .ok_or_else(|| {
+            ApiError::bad_request("GAME_NOT_IN_LOBBY", "The join code can only change in the lobby")
+        })?;
+    let payload = JoinCodeRotatedPayload { game_id: id.clone(), join_code: join_code.clone() };
+    outbox::enqueue(&mut tx, &id, JOIN_CODE_ROTATED, &payload).await?;
+    tx.commit().await?;
+    state.wake_outbox_relay();
+
+    tracing::info!(game_id = %id, user_id = %auth.user_id, "Rotated join code");
+
+    Ok(Json(RotateJoinCodeResponse { join_code }))
+}
+
+/// ID of a built-in preset, e.g. "speedround"
+fn builtin_preset_id(preset: GamePreset) -> String {
+    format!("{:?}", preset).to_lowercase()
+}
+
+/// Settings a new game starts from: a built-in preset, one of the user's
+/// saved presets, or the classic defaults
+async fn preset_base_settings(
+    state: &AppState,
+    user_id: &str,
+    preset_id: Option<&str>,
+) -> Result<GameSettings, ApiError> {
+    // Built-in settings use the reconnection grace period of the runtime config
+    let builtin = |mut settings: GameSettings| {
+        settings.reconnect_grace_seconds = state.default_reconnect_grace_secs();
+        settings
+    };
+    let Some(preset_id) = preset_id else {
+        return Ok(builtin(GameSettings::default()));
+    };
+    if let Some(preset) = GamePreset::all().iter().find(|p| builtin_preset_id(**p) == preset_id) {
+        return Ok(builtin(GameSettings::from_preset(*preset)));
+    }
+    let preset = dguesser_db::settings_presets::get_for_user(state.db(), preset_id, user_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Preset"))?;
+    Ok(serde_json::from_value(preset.settings).unwrap_or_default())
+}
+
 /// Get available game presets
 #[utoipa::path(
     get,
@@ -1595,24 +2694,15 @@ pub async fn update_settings(
     tag = "games"
 )]
 pub async fn get_presets() -> Json<Vec<PresetInfo>> {
-    use dguesser_core::game::rules::GamePreset;
-
     let presets: Vec<PresetInfo> = GamePreset::all()
         .iter()
         .map(|preset| {
             let settings = GameSettings::from_preset(*preset);
             PresetInfo {
-                id: format!("{:?}", preset).to_lowercase(),
+                id: builtin_preset_id(*preset),
                 name: preset.display_name().to_string(),
                 description: preset.description().to_string(),
-                settings: SettingsDto {
-                    rounds: settings.rounds,
-                    time_limit_seconds: settings.time_limit_seconds,
-                    map_id: settings.map_id,
-                    movement_allowed: settings.movement_allowed,
-                    zoom_allowed: settings.zoom_allowed,
-                    rotation_allowed: settings.rotation_allowed,
-                },
+                settings: SettingsDto::from_settings(&settings),
             }
         })
         .collect();
@@ -1624,30 +2714,194 @@ pub async fn get_presets() -> Json<Vec<PresetInfo>> {
 // Helper Functions
 // =============================================================================
 
-/// Generate a random 6-character join code
-fn generate_join_code() -> String {
-    use rand::RngExt;
-    const CHARSET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
-    let mut rng = rand::rng();
-    (0..6)
-        .map(|_| {
-            let idx = rng.random_range(0..CHARSET.len());
-            CHARSET[idx] as char
-        })
-        .collect()
+/// Sign a stored solo round result onto the player's score chain. Returns
+/// the signature, or `None` when score signing isn't configured.
+async fn sign_solo_guess(
+    state: &AppState,
+    game_id: &str,
+    round_number: i16,
+    guess: &dguesser_db::games::Guess,
+) -> Result<Option<String>, ApiError> {
+    let Some(chain) = state.score_chain() else {
+        return Ok(None);
+    };
+
+    let links = dguesser_db::games::get_score_chain(state.db(), game_id, &guess.user_id).await?;
+    let previous = links
+        .iter()
+        .rfind(|link| link.round_number == round_number - 1)
+        .and_then(|link| link.score_mac.as_deref());
+    let signature = chain.sign(game_id, round_number, guess.score, previous);
+    dguesser_db::games::set_guess_score_mac(state.db(), &guess.id, &signature).await?;
+    Ok(Some(signature))
+}
+
+/// Check a finished solo game's score chain. A failed check is recorded as a
+/// cheat signal and returns `false`; without score signing every game passes.
+async fn verify_score_chain(
+    state: &AppState,
+    game_id: &str,
+    user_id: &str,
+    total_score: i32,
+) -> Result<bool, ApiError> {
+    let Some(chain) = state.score_chain() else {
+        return Ok(true);
+    };
+
+    let links = dguesser_db::games::get_score_chain(state.db(), game_id, user_id).await?;
+    let handicap = dguesser_db::games::get_players(state.db(), game_id)
+        .await?
+        .into_iter()
+        .find(|p| p.user_id == user_id)
+        .map_or(1.0, |p| p.handicap);
+    let Err(e) = chain.verify(game_id, &links, handicap, total_score) else {
+        return Ok(true);
+    };
+
+    tracing::warn!(user_id, game_id, error = %e, "Solo game failed the score chain check");
+    if let Err(e) = dguesser_db::anti_cheat::record_signal(
+        state.db(),
+        user_id,
+        game_id,
+        None,
+        CheatSignalKind::ScoreChainMismatch,
+        serde_json::json!({ "error": e.to_string(), "total_score": total_score }),
+    )
+    .await
+    {
+        tracing::error!(error = %e, "Failed to record cheat signal");
+    }
+    Ok(false)
+}
+
+/// Record a guess made away from the round's panorama in a no-move mode.
+pub(super) async fn record_panorama_mismatch(
+    state: &AppState,
+    user_id: &str,
+    game_id: &str,
+    round_id: Option<&str>,
+    expected: Option<&str>,
+    reported: Option<&str>,
+) {
+    tracing::warn!(user_id, game_id, ?expected, ?reported, "Rejected guess from wrong panorama");
+    let details = serde_json::json!({ "expected": expected, "reported": reported });
+    if let Err(e) = dguesser_db::anti_cheat::record_signal(
+        state.db(),
+        user_id,
+        game_id,
+        round_id,
+        CheatSignalKind::PanoramaMismatch,
+        details,
+    )
+    .await
+    {
+        tracing::error!(error = %e, "Failed to record cheat signal");
+    }
+}
+
+/// Validate a host-requested vanity join code.
+///
+/// Checks the host may pick one and that no active party already uses it;
+/// clashes with other games surface when the code is stored.
+async fn vanity_join_code(
+    state: &AppState,
+    auth: &AuthUser,
+    code: &str,
+) -> Result<String, ApiError> {
+    let allowed = match state.join_codes().vanity {
+        VanityCodeAccess::Off => false,
+        VanityCodeAccess::Registered => !auth.is_guest,
+        VanityCodeAccess::Admin => auth.role.is_admin(),
+    };
+    if !allowed {
+        return Err(ApiError::forbidden("You can't choose a join code"));
+    }
+
+    let code =
+        validate_vanity_code(code).map_err(|msg| ApiError::bad_request("INVALID_CODE", msg))?;
+    if dguesser_db::parties::get_party_by_join_code(state.db(), &code).await?.is_some() {
+        return Err(join_code_taken_error());
+    }
+    Ok(code)
+}
+
+/// Map a join code clash when storing a game to a conflict
+fn join_code_taken(e: sqlx::Error) -> ApiError {
+    match &e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => join_code_taken_error(),
+        _ => e.into(),
+    }
+}
+
+fn join_code_taken_error() -> ApiError {
+    ApiError::conflict("JOIN_CODE_TAKEN", "This join code is already in use")
+}
+
+/// Scoring curve configured on a map; the default curve if the map can't be loaded.
+pub(super) async fn map_scoring(state: &AppState, map_id: &str) -> ScoringConfig {
+    match state.location_provider().get_map(map_id).await {
+        Ok(map) => map.rules.scoring_config(),
+        Err(e) => {
+            tracing::warn!(map_id, error = %e, "Failed to load map scoring, using default");
+            ScoringConfig::default()
+        }
+    }
+}
+
+/// Past guess statistics for a location, if it has enough guesses to compare.
+///
+/// Reads through the Redis cache; the table is only refreshed hourly.
+pub(super) async fn location_guess_stats(
+    state: &AppState,
+    location_id: Option<&str>,
+) -> Option<LocationGuessStats> {
+    let location_id = location_id?;
+
+    let stats = match LocationStatsCache::get(state.redis(), location_id).await {
+        Some(cached) => cached,
+        None => {
+            let stats = match dguesser_db::location_stats::get_for_location(state.db(), location_id)
+                .await
+            {
+                Ok(row) => row.map(LocationGuessStats::from),
+                Err(e) => {
+                    tracing::warn!(location_id, error = %e, "Failed to load location stats");
+                    return None;
+                }
+            };
+            LocationStatsCache::set(state.redis(), location_id, stats.as_ref()).await;
+            stats
+        }
+    };
+
+    stats.filter(LocationGuessStats::is_meaningful)
+}
+
+/// Summary of past guesses sent alongside round results.
+pub(super) fn global_guess_stats(stats: &LocationGuessStats) -> GlobalGuessStats {
+    GlobalGuessStats {
+        guesses: stats.guesses,
+        median_distance_meters: stats.median_distance_meters,
+    }
 }
 
 /// Select a location for a round with distance-based spread.
 ///
 /// Uses `SelectionConstraints` to rank candidates by spread from previous
 /// round locations, with an optional hard minimum distance when configured.
-async fn select_location(
-    provider: &dyn dguesser_core::location::LocationProvider,
+/// Panoramas the players saw in recent games are skipped, and the country of
+/// their previous round is ranked lower.
+pub(super) async fn select_location(
+    state: &AppState,
+    game_id: &str,
+    user_ids: &[String],
     map_id: &str,
     exclude_ids: &[String],
     previous_locations: &[(f64, f64)],
 ) -> LocationData {
-    use dguesser_core::location::SelectionConstraints;
+    use dguesser_core::location::{LocationError, SelectionConstraints};
+
+    let provider = state.location_provider();
 
     // Get optional hard minimum spread distance from the map's rules.
     let min_distance_km =
@@ -1655,7 +2909,7 @@ async fn select_location(
 
     // Build constraints from previous locations. Relative spread applies by default,
     // and an explicit map rule can add a hard minimum distance floor.
-    let constraints = if previous_locations.is_empty() {
+    let mut constraints = if previous_locations.is_empty() {
         SelectionConstraints::none()
     } else {
         SelectionConstraints::with_optional_min_distance(
@@ -1664,14 +2918,42 @@ async fn select_location(
         )
     };
 
-    match provider.select_location_with_constraints(map_id, exclude_ids, &constraints).await {
-        Ok(loc) => LocationData::full(
-            loc.lat,
-            loc.lng,
-            if loc.panorama_id.is_empty() { None } else { Some(loc.panorama_id) },
-            Some(loc.id),
-            loc.heading,
-        ),
+    if let Some(recent) = state.recent_locations() {
+        constraints = recent.load(user_ids).await.apply(constraints);
+    }
+
+    let mut selected =
+        provider.select_location_with_constraints(map_id, exclude_ids, &constraints).await;
+
+    // Small maps can run out of panoramas the players have not seen yet
+    if matches!(selected, Err(LocationError::NoLocationsAvailable(_)))
+        && !constraints.exclude_panoramas.is_empty()
+    {
+        tracing::debug!(map_id = %map_id, "All locations seen recently, allowing repeats");
+        selected = provider
+            .select_location_with_constraints(
+                map_id,
+                exclude_ids,
+                &constraints.without_recent_panoramas(),
+            )
+            .await;
+    }
+
+    match selected {
+        Ok(loc) => {
+            if let Some(recent) = state.recent_locations() {
+                recent.record(user_ids, game_id, &loc).await;
+            }
+            LocationData::full(
+                loc.lat,
+                loc.lng,
+                if loc.panorama_id.is_empty() { None } else { Some(loc.panorama_id) },
+                Some(loc.id),
+                loc.heading,
+                loc.provider,
+            )
+            .with_country_code(loc.country_code)
+        }
         Err(e) => {
             tracing::warn!(error = %e, map_id = %map_id, "Failed to select location, using random");
             use rand::RngExt;
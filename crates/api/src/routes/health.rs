@@ -1,7 +1,8 @@
 //! Health check endpoints
 
 use axum::{Json, extract::State, http::StatusCode};
-
+use dguesser_core::streetview::QuotaState;
+use dguesser_locations::health::CheckOutcome;
 use serde::Serialize;
 use utoipa::ToSchema;
 
@@ -27,6 +28,21 @@ pub struct HealthChecks {
     pub database: CheckResult,
     /// Redis health status
     pub redis: CheckResult,
+    /// Read replica status (if configured). A lagging replica doesn't make the
+    /// service unhealthy since reads fall back to the primary.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replica: Option<CheckResult>,
+    /// Location provider self-test (a location can be selected from the
+    /// default map)
+    pub locations: CheckResult,
+    /// R2 pack storage reachability (if packs are configured)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pack_storage: Option<CheckResult>,
+    /// Street View metadata API quota (if an API key is configured). An
+    /// exhausted quota only pauses panorama validation, so it degrades the
+    /// service without making it unhealthy.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub street_view: Option<CheckResult>,
 }
 
 /// Result of an individual health check
@@ -52,11 +68,29 @@ impl CheckResult {
     fn unhealthy(error: String) -> Self {
         Self { status: "unhealthy".to_string(), latency_ms: None, error: Some(error) }
     }
+
+    fn degraded(error: String) -> Self {
+        Self { status: "degraded".to_string(), latency_ms: None, error: Some(error) }
+    }
+
+    fn is_healthy(&self) -> bool {
+        self.status == "healthy"
+    }
+}
+
+impl From<CheckOutcome> for CheckResult {
+    fn from(outcome: CheckOutcome) -> Self {
+        match outcome {
+            Ok(latency_ms) => Self::healthy(latency_ms),
+            Err(error) => Self::unhealthy(error),
+        }
+    }
 }
 
 /// Detailed health check endpoint
 ///
-/// Returns health status of all dependencies (database, Redis).
+/// Returns health status of all dependencies: database, Redis, the location
+/// provider, R2 pack storage and the Street View quota.
 #[utoipa::path(
     get,
     path = "/health",
@@ -67,15 +101,34 @@ impl CheckResult {
     tag = "health"
 )]
 pub async fn health_check(State(state): State<AppState>) -> (StatusCode, Json<HealthResponse>) {
-    let (db_check, redis_check) =
-        tokio::join!(check_database(state.db()), check_redis(state.redis()));
-
-    let overall_healthy = db_check.status == "healthy" && redis_check.status == "healthy";
+    let (db_check, redis_check, replica_check, location_checks, street_view_check) = tokio::join!(
+        check_database(state.db()),
+        check_redis(&state),
+        check_replica(state.db_pools()),
+        state.location_health().check(),
+        check_street_view(&state)
+    );
+
+    let overall_healthy =
+        db_check.is_healthy() && redis_check.is_healthy() && location_checks.is_ready();
+    let degraded = street_view_check.as_ref().is_some_and(|check| !check.is_healthy());
+    let status = match (overall_healthy, degraded) {
+        (false, _) => "unhealthy",
+        (true, true) => "degraded",
+        (true, false) => "healthy",
+    };
 
     let response = HealthResponse {
-        status: if overall_healthy { "healthy".to_string() } else { "unhealthy".to_string() },
+        status: status.to_string(),
         version: env!("CARGO_PKG_VERSION"),
-        checks: HealthChecks { database: db_check, redis: redis_check },
+        checks: HealthChecks {
+            database: db_check,
+            redis: redis_check,
+            replica: replica_check,
+            locations: location_checks.provider.into(),
+            pack_storage: location_checks.storage.map(CheckResult::from),
+            street_view: street_view_check,
+        },
     };
 
     let status_code =
@@ -101,7 +154,9 @@ pub async fn liveness() -> StatusCode {
 
 /// Readiness check for Kubernetes/Railway probes
 ///
-/// Returns 200 OK only if all dependencies are healthy.
+/// Returns 200 OK only if games can start: the database and Redis are
+/// reachable, pack storage (if used) is reachable, and the location provider
+/// passes its self-test.
 #[utoipa::path(
     get,
     path = "/readyz",
@@ -112,10 +167,13 @@ pub async fn liveness() -> StatusCode {
     tag = "health"
 )]
 pub async fn readiness(State(state): State<AppState>) -> StatusCode {
-    let (db_check, redis_check) =
-        tokio::join!(check_database(state.db()), check_redis(state.redis()));
+    let (db_check, redis_check, location_checks) = tokio::join!(
+        check_database(state.db()),
+        check_redis(&state),
+        state.location_health().check()
+    );
 
-    if db_check.status == "healthy" && redis_check.status == "healthy" {
+    if db_check.is_healthy() && redis_check.is_healthy() && location_checks.is_ready() {
         StatusCode::OK
     } else {
         StatusCode::SERVICE_UNAVAILABLE
@@ -135,21 +193,54 @@ async fn check_database(pool: &sqlx::PgPool) -> CheckResult {
     }
 }
 
-/// Check Redis connectivity
-async fn check_redis(client: &redis::Client) -> CheckResult {
+/// Check read replica lag (`None` without a replica)
+async fn check_replica(pools: &dguesser_db::DbPools) -> Option<CheckResult> {
     let start = std::time::Instant::now();
 
-    match client.get_multiplexed_async_connection().await {
-        Ok(mut conn) => match redis::cmd("PING").query_async::<String>(&mut conn).await {
-            Ok(_) => CheckResult::healthy(start.elapsed().as_millis() as u64),
-            Err(e) => {
-                tracing::error!(error = %e, "Redis PING failed");
-                CheckResult::unhealthy("Redis ping failed".to_string())
-            }
+    Some(match pools.check_replica_lag().await? {
+        Ok(_) if pools.replica_in_use() => CheckResult::healthy(start.elapsed().as_millis() as u64),
+        Ok(lag) => CheckResult {
+            status: "lagging".to_string(),
+            latency_ms: None,
+            error: Some(format!("Replica is {}s behind, reads use the primary", lag.as_secs())),
         },
         Err(e) => {
-            tracing::error!(error = %e, "Redis connection failed");
-            CheckResult::unhealthy("Redis connection failed".to_string())
+            tracing::warn!(error = %e, "Replica health check failed");
+            CheckResult::unhealthy("Replica connection failed".to_string())
+        }
+    })
+}
+
+/// Check the shared Street View quota breaker (`None` without an API key)
+async fn check_street_view(state: &AppState) -> Option<CheckResult> {
+    let client = state.street_view();
+    if !client.can_validate() {
+        return None;
+    }
+
+    Some(match client.quota().await.state(chrono::Utc::now()) {
+        QuotaState::Open { until } => {
+            CheckResult::degraded(format!("Quota exhausted, lookups paused until {until}"))
         }
+        QuotaState::Closed | QuotaState::HalfOpen => {
+            CheckResult { status: "healthy".to_string(), latency_ms: None, error: None }
+        }
+    })
+}
+
+/// Check Redis connectivity; with Sentinel, also check the node is still the
+/// master, which moves the connection to the new master after a failover
+async fn check_redis(state: &AppState) -> CheckResult {
+    let start = std::time::Instant::now();
+    let mut conn = state.redis().clone();
+
+    if let Err(e) = redis::cmd("PING").query_async::<String>(&mut conn).await {
+        tracing::error!(error = %e, "Redis PING failed");
+        return CheckResult::unhealthy("Redis ping failed".to_string());
+    }
+    if let Err(e) = conn.check_master().await {
+        tracing::error!(error = %e, "Redis master check failed");
+        return CheckResult::unhealthy("Redis node is no longer the master".to_string());
     }
+    CheckResult::healthy(start.elapsed().as_millis() as u64)
 }
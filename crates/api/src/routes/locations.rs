@@ -8,10 +8,12 @@ use axum::{
 };
 use chrono::Datelike;
 use dguesser_auth::AuthUser;
+use dguesser_core::location::{DifficultyBand, parse_tag_list};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 use crate::error::ApiError;
+use crate::i18n::Locale;
 use crate::state::AppState;
 
 // =============================================================================
@@ -55,6 +57,15 @@ pub struct SearchLocationsQuery {
     pub outdoor_only: bool,
     /// Exclude locations already in this map
     pub exclude_map_id: Option<String>,
+    /// Only locations rated in this difficulty band
+    #[schema(value_type = Option<String>, example = "hard")]
+    pub difficulty: Option<DifficultyBand>,
+    /// Comma-separated tags; locations must have all of them
+    #[schema(example = "snow,tollbooth")]
+    pub tags: Option<String>,
+    /// Free text matched against tags, country, subdivision and surface
+    #[schema(example = "desert")]
+    pub q: Option<String>,
     /// Page number (1-based)
     #[serde(default = "default_page")]
     pub page: i64,
@@ -88,6 +99,10 @@ pub struct LocationSearchItem {
     pub subdivision_code: Option<String>,
     /// Capture year
     pub capture_year: Option<i32>,
+    /// Difficulty rating (0-100) from past guesses; absent until rated
+    pub difficulty: Option<i16>,
+    /// Location tags
+    pub tags: Vec<String>,
 }
 
 /// Location search response.
@@ -109,6 +124,9 @@ pub struct CountryInfo {
     /// ISO 3166-1 alpha-2 code
     #[schema(example = "US")]
     pub code: String,
+    /// Display name in the response language
+    #[schema(example = "United States")]
+    pub name: String,
     /// Number of locations
     pub count: i64,
 }
@@ -126,6 +144,9 @@ pub struct SubdivisionInfo {
     /// ISO 3166-2 code
     #[schema(example = "US-CA")]
     pub code: String,
+    /// Display name in the response language, or the code when unknown
+    #[schema(example = "California")]
+    pub name: String,
     /// Number of locations
     pub count: i64,
 }
@@ -168,6 +189,9 @@ pub fn router() -> Router<AppState> {
         ("max_year" = Option<i32>, Query, description = "Maximum capture year"),
         ("outdoor_only" = Option<bool>, Query, description = "Only outdoor locations"),
         ("exclude_map_id" = Option<String>, Query, description = "Exclude locations in this map"),
+        ("difficulty" = Option<String>, Query, description = "Difficulty band: easy, medium, hard, or extreme"),
+        ("tags" = Option<String>, Query, description = "Comma-separated tags the locations must all have"),
+        ("q" = Option<String>, Query, description = "Free text matched against tags, country, subdivision and surface"),
         ("page" = Option<i64>, Query, description = "Page number (default 1)"),
         ("per_page" = Option<i64>, Query, description = "Items per page (default 50, max 100)"),
     ),
@@ -188,6 +212,9 @@ pub async fn search_locations(
         max_year: query.max_year,
         outdoor_only: query.outdoor_only,
         exclude_map_id: query.exclude_map_id,
+        difficulty: query.difficulty,
+        tags: query.tags.as_deref().map(parse_tag_list).unwrap_or_default(),
+        text: query.q.filter(|q| !q.trim().is_empty()),
     };
 
     let page = query.page.max(1);
@@ -207,6 +234,8 @@ pub async fn search_locations(
             country_code: l.country_code,
             subdivision_code: l.subdivision_code,
             capture_year: l.capture_date.map(|d| d.year()),
+            difficulty: l.difficulty,
+            tags: l.tags,
         })
         .collect();
 
@@ -226,10 +255,17 @@ pub async fn search_locations(
 pub async fn get_countries(
     State(state): State<AppState>,
     _auth: AuthUser,
+    locale: Locale,
 ) -> Result<Json<CountriesResponse>, ApiError> {
     let countries = dguesser_db::locations::get_available_countries(state.db()).await?;
 
-    let items = countries.into_iter().map(|(code, count)| CountryInfo { code, count }).collect();
+    let items = countries
+        .into_iter()
+        .map(|(code, count)| {
+            let name = locale.country_name(&code).unwrap_or(&code).to_string();
+            CountryInfo { code, name, count }
+        })
+        .collect();
 
     Ok(Json(CountriesResponse { countries: items }))
 }
@@ -251,12 +287,18 @@ pub async fn get_subdivisions(
     State(state): State<AppState>,
     Path(code): Path<String>,
     _auth: AuthUser,
+    locale: Locale,
 ) -> Result<Json<SubdivisionsResponse>, ApiError> {
     let subdivisions =
         dguesser_db::locations::get_available_subdivisions(state.db(), &code).await?;
 
-    let items =
-        subdivisions.into_iter().map(|(code, count)| SubdivisionInfo { code, count }).collect();
+    let items = subdivisions
+        .into_iter()
+        .map(|(code, count)| {
+            let name = locale.subdivision_name(&code).unwrap_or(&code).to_string();
+            SubdivisionInfo { code, name, count }
+        })
+        .collect();
 
     Ok(Json(SubdivisionsResponse { subdivisions: items }))
 }
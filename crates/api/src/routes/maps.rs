@@ -3,22 +3,38 @@
 //! This module handles REST API endpoints for user-created maps,
 //! including CRUD operations and location management.
 
+use std::collections::HashSet;
+
 use axum::{
     Json, Router,
+    body::Body,
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
     routing::{delete, get, post, put},
 };
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use dguesser_auth::{AuthUser, MaybeAuthUser};
-use dguesser_core::location::MapVisibility;
-use dguesser_core::streetview::{StreetViewUrlError, parse_streetview_url};
+use dguesser_core::game::ScoringConfig;
+use dguesser_core::location::{DifficultyBand, Map, MapRegion, MapRules, MapVisibility};
+use dguesser_core::streetview::{
+    ImageryProvider, OVER_QUERY_LIMIT, StreetViewUrlError, is_short_link, parse_streetview_url,
+};
+use dguesser_db::locations::MapSort;
+use dguesser_db::map_versions::{self, MapVersionKind};
+use dguesser_protocol::api::admin::{CreateLocationNoteRequest, LocationNoteItem};
 use futures::future::join_all;
-use serde::{Deserialize, Serialize};
+use futures::{StreamExt, TryStreamExt, stream};
+use serde::{Deserialize, Deserializer, Serialize};
 use utoipa::ToSchema;
 
+use super::admin::location_notes::{note_item, validate_note};
+use crate::cache::{CachedHeatmap, HeatmapCache};
 use crate::error::ApiError;
+use crate::map_exchange::{self, ExchangeFormat, ExchangeLocation, ParsedRow};
+use crate::middleware::CurrentOrg;
 use crate::state::AppState;
+use crate::street_view::{PanoramaQuery, PanoramaStatus};
 
 // =============================================================================
 // Constants
@@ -30,6 +46,22 @@ const MAX_MAPS_PER_USER: i64 = 50;
 const MAX_LOCATIONS_PER_MAP: i32 = 10_000;
 /// Maximum URLs per import request
 const MAX_URLS_PER_IMPORT: usize = 100;
+/// URLs resolved (short links, panorama lookups) at once
+const URL_RESOLVE_CONCURRENCY: usize = 8;
+/// Maximum rows read from an import file, valid or not
+const MAX_IMPORT_ROWS: usize = 20_000;
+/// Locations linked to an imported map per database round trip
+const IMPORT_BATCH_SIZE: usize = 500;
+/// Locations read per page while exporting
+const EXPORT_PAGE_SIZE: i64 = 1_000;
+/// Default heatmap geohash precision (~39km x 20km cells)
+const DEFAULT_HEATMAP_PRECISION: i32 = 4;
+/// Maximum collaborators per map, including pending invitations
+const MAX_COLLABORATORS_PER_MAP: i64 = 20;
+/// Maximum versions per history page
+const MAX_VERSIONS_PER_PAGE: i64 = 100;
+/// Upcoming features listed
+const MAX_UPCOMING_FEATURES: i64 = 10;
 
 // =============================================================================
 // Router
@@ -41,6 +73,11 @@ pub fn router() -> Router<AppState> {
         // List and create maps
         .route("/", get(list_maps))
         .route("/", post(create_map))
+        .route("/favorites", get(list_favorite_maps))
+        .route("/featured", get(get_featured_maps))
+        .route("/import", post(import_map))
+        .route("/invitations", get(list_invitations))
+        .route("/validate-rules", post(validate_rules))
         // Single map operations
         .route("/{id}", get(get_map))
         .route("/{id}", put(update_map))
@@ -50,6 +87,28 @@ pub fn router() -> Router<AppState> {
         .route("/{id}/locations", post(add_locations))
         .route("/{id}/locations/from-urls", post(add_locations_from_urls))
         .route("/{id}/locations/{location_id}", delete(remove_location))
+        .route(
+            "/{id}/locations/{location_id}/notes",
+            get(list_location_notes).post(create_location_note),
+        )
+        .route("/{id}/locations/{location_id}/notes/{note_id}", delete(delete_location_note))
+        // Import/export
+        .route("/{id}/export", get(export_map))
+        // Coverage
+        .route("/{id}/heatmap", get(get_map_heatmap))
+        // Likes
+        .route("/{id}/like", post(like_map))
+        .route("/{id}/like", delete(unlike_map))
+        // Collaborators
+        .route("/{id}/collaborators", get(list_collaborators))
+        .route("/{id}/collaborators", post(invite_collaborator))
+        .route("/{id}/collaborators/{user_id}", delete(remove_collaborator))
+        .route("/{id}/invitation", post(accept_invitation))
+        .route("/{id}/invitation", delete(decline_invitation))
+        // Version history
+        .route("/{id}/versions", get(list_versions))
+        .route("/{id}/versions/{version}", get(get_version))
+        .route("/{id}/versions/{version}/restore", post(restore_version))
 }
 
 // =============================================================================
@@ -77,12 +136,30 @@ pub struct MapSummary {
     pub is_system_map: bool,
     /// Whether the current user owns this map
     pub is_owned: bool,
+    /// Whether the current user can add and remove locations
+    pub can_edit: bool,
     /// Number of locations in the map
     pub location_count: i32,
+    /// Number of users who liked the map
+    pub like_count: i32,
+    /// Number of finished plays
+    pub play_count: i32,
+    /// Average final score per play
+    pub avg_score: Option<f64>,
+    /// Whether the current user liked the map
+    pub is_liked: bool,
     /// When the map was created
     pub created_at: DateTime<Utc>,
 }
 
+/// Query params for listing maps.
+#[derive(Debug, Deserialize)]
+pub struct ListMapsQuery {
+    /// Sort order: "newest" (default), "popular", or "most_liked"
+    #[serde(default)]
+    pub sort: MapSort,
+}
+
 /// List maps response.
 #[derive(Debug, Serialize, ToSchema)]
 pub struct ListMapsResponse {
@@ -102,6 +179,20 @@ pub struct CreateMapRequest {
     /// Visibility: "private", "unlisted", or "public"
     #[schema(example = "private")]
     pub visibility: Option<String>,
+    /// Optional GeoJSON Polygon or MultiPolygon (`[lng, lat]` positions).
+    /// When set, the map plays every location inside the area.
+    #[schema(value_type = Option<Object>)]
+    pub region: Option<MapRegion>,
+    /// Only play locations from this imagery provider (optional)
+    #[schema(value_type = Option<String>, example = "google_streetview")]
+    pub provider: Option<ImageryProvider>,
+    /// Custom scoring curve (optional); omitted fields use the defaults
+    #[schema(value_type = Option<Object>)]
+    pub scoring: Option<ScoringConfig>,
+    /// Only play locations rated in this difficulty band (optional):
+    /// "easy", "medium", "hard", or "extreme"
+    #[schema(value_type = Option<String>, example = "hard")]
+    pub difficulty: Option<DifficultyBand>,
 }
 
 /// Create map response.
@@ -133,10 +224,36 @@ pub struct MapDetails {
     pub is_system_map: bool,
     /// Whether the current user owns this map
     pub is_owned: bool,
+    /// Whether the current user can add and remove locations
+    pub can_edit: bool,
     /// Whether this is the default map
     pub is_default: bool,
     /// Number of locations
     pub location_count: i32,
+    /// Number of users who liked the map
+    pub like_count: i32,
+    /// Number of finished plays
+    pub play_count: i32,
+    /// Average final score per play
+    pub avg_score: Option<f64>,
+    /// Whether the current user liked the map
+    pub is_liked: bool,
+    /// GeoJSON polygon boundary, for maps defined by an area
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub region: Option<MapRegion>,
+    /// Imagery provider the map is restricted to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>, example = "google_streetview")]
+    pub provider: Option<ImageryProvider>,
+    /// Scoring curve games on this map use: `max_points`, `zero_score_distance`
+    /// (meters), `curve_exponent`, and `perfect_radius_meters`
+    #[schema(value_type = Object)]
+    pub scoring: ScoringConfig,
+    /// Difficulty band the map's locations are restricted to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>, example = "hard")]
+    pub difficulty: Option<DifficultyBand>,
     /// When the map was created
     pub created_at: DateTime<Utc>,
     /// When the map was last updated
@@ -152,6 +269,188 @@ pub struct UpdateMapRequest {
     pub description: Option<String>,
     /// New visibility (optional)
     pub visibility: Option<String>,
+    /// New GeoJSON polygon boundary (optional); `null` removes it
+    #[serde(default, deserialize_with = "deserialize_some")]
+    #[schema(value_type = Option<Object>)]
+    pub region: Option<Option<MapRegion>>,
+    /// New scoring curve (optional); `null` restores the default curve
+    #[serde(default, deserialize_with = "deserialize_some")]
+    #[schema(value_type = Option<Object>)]
+    pub scoring: Option<Option<ScoringConfig>>,
+    /// New difficulty band (optional); `null` allows every difficulty
+    #[serde(default, deserialize_with = "deserialize_some")]
+    #[schema(value_type = Option<String>, example = "hard")]
+    pub difficulty: Option<Option<DifficultyBand>>,
+}
+
+/// Like or unlike response.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MapLikeResponse {
+    /// Whether the current user now likes the map
+    pub liked: bool,
+    /// New like count
+    pub like_count: i32,
+}
+
+/// Invite collaborator request.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct InviteCollaboratorRequest {
+    /// Username of the user to invite
+    #[schema(example = "geoguru")]
+    pub username: String,
+}
+
+/// A map collaborator or pending invitee.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MapCollaboratorItem {
+    /// User ID
+    #[schema(example = "usr_FybH2oF9Xaw8")]
+    pub user_id: String,
+    /// Username
+    pub username: Option<String>,
+    /// Display name
+    pub display_name: String,
+    /// Avatar URL
+    pub avatar_url: Option<String>,
+    /// Invitation status: "pending" or "accepted"
+    #[schema(example = "accepted")]
+    pub status: String,
+    /// When the user was invited
+    pub invited_at: DateTime<Utc>,
+    /// When the invitation was accepted
+    pub accepted_at: Option<DateTime<Utc>>,
+}
+
+impl From<dguesser_db::locations::MapCollaborator> for MapCollaboratorItem {
+    fn from(c: dguesser_db::locations::MapCollaborator) -> Self {
+        let status = if c.is_accepted() { "accepted" } else { "pending" };
+        Self {
+            user_id: c.user_id,
+            username: c.username,
+            display_name: c.display_name,
+            avatar_url: c.avatar_url,
+            status: status.to_string(),
+            invited_at: c.created_at,
+            accepted_at: c.accepted_at,
+        }
+    }
+}
+
+/// Map collaborators response.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MapCollaboratorsResponse {
+    /// Collaborators and pending invitees, oldest first
+    pub collaborators: Vec<MapCollaboratorItem>,
+}
+
+/// A pending invitation to edit a map.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MapInvitationItem {
+    /// Map ID
+    #[schema(example = "map_FybH2oF9Xaw8")]
+    pub map_id: String,
+    /// Map slug
+    pub map_slug: String,
+    /// Map name
+    pub map_name: String,
+    /// ID of the user who sent the invitation
+    pub invited_by: Option<String>,
+    /// Display name of the user who sent the invitation
+    pub inviter_name: Option<String>,
+    /// When the invitation was sent
+    pub invited_at: DateTime<Utc>,
+}
+
+/// Pending invitations response.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MapInvitationsResponse {
+    /// Pending invitations, newest first
+    pub invitations: Vec<MapInvitationItem>,
+}
+
+/// Query params for listing map versions.
+#[derive(Debug, Deserialize)]
+pub struct ListVersionsQuery {
+    /// Page number (1-based)
+    #[serde(default = "default_page")]
+    pub page: i64,
+    /// Items per page (max 100)
+    #[serde(default = "default_per_page")]
+    pub per_page: i64,
+}
+
+/// One entry in a map's version history.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MapVersionItem {
+    /// Version number, from 1
+    pub version: i32,
+    /// Change kind: "initial", "locations", "rules", "import", or "restore"
+    #[schema(example = "locations")]
+    pub kind: String,
+    /// User who made the change
+    pub user_id: Option<String>,
+    /// Number of locations added
+    pub added_count: i32,
+    /// Number of locations removed
+    pub removed_count: i32,
+    /// Map rules after the change
+    #[schema(value_type = Object)]
+    pub rules: serde_json::Value,
+    /// Version restored, for restore entries
+    pub restored_from: Option<i32>,
+    /// When the change was made
+    pub created_at: DateTime<Utc>,
+}
+
+/// Map version history response.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MapVersionsResponse {
+    /// Versions, newest first
+    pub versions: Vec<MapVersionItem>,
+    /// Total number of versions
+    pub total: i64,
+    /// Current page
+    pub page: i64,
+    /// Items per page
+    pub per_page: i64,
+}
+
+/// A map version with its full location diff.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MapVersionDetails {
+    /// Version number, from 1
+    pub version: i32,
+    /// Change kind: "initial", "locations", "rules", "import", or "restore"
+    #[schema(example = "locations")]
+    pub kind: String,
+    /// User who made the change
+    pub user_id: Option<String>,
+    /// IDs of locations added
+    pub added_location_ids: Vec<String>,
+    /// IDs of locations removed
+    pub removed_location_ids: Vec<String>,
+    /// Map rules after the change
+    #[schema(value_type = Object)]
+    pub rules: serde_json::Value,
+    /// Version restored, for restore entries
+    pub restored_from: Option<i32>,
+    /// When the change was made
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<map_versions::MapVersion> for MapVersionDetails {
+    fn from(v: map_versions::MapVersion) -> Self {
+        Self {
+            version: v.version,
+            kind: v.kind.to_string(),
+            user_id: v.user_id,
+            added_location_ids: v.added_location_ids,
+            removed_location_ids: v.removed_location_ids,
+            rules: v.rules,
+            restored_from: v.restored_from,
+            created_at: v.created_at,
+        }
+    }
 }
 
 /// Location item in map.
@@ -203,6 +502,162 @@ pub struct MapLocationsResponse {
     pub per_page: i64,
 }
 
+/// Query params for exporting a map.
+#[derive(Debug, Deserialize)]
+pub struct ExportMapQuery {
+    /// File format: "geojson" (default) or "csv"
+    pub format: Option<ExchangeFormat>,
+}
+
+/// Query params for importing a map.
+#[derive(Debug, Deserialize)]
+pub struct ImportMapQuery {
+    /// Name of the new map (3-100 characters)
+    pub name: String,
+    /// Description (optional, max 500 characters)
+    pub description: Option<String>,
+    /// Visibility: "private" (default), "unlisted", or "public"
+    pub visibility: Option<String>,
+    /// File format: "geojson" or "csv"; guessed from Content-Type when omitted
+    pub format: Option<ExchangeFormat>,
+}
+
+/// Result of importing one row.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImportRowResult {
+    /// Row number (CSV data row or feature index, from 1)
+    pub row: usize,
+    /// Whether the row was imported
+    pub success: bool,
+    /// Error message if the row was rejected
+    pub error: Option<String>,
+    /// Location ID if the row was imported
+    pub location_id: Option<String>,
+    /// Whether the location already existed
+    pub already_exists: bool,
+}
+
+/// Import map response.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImportMapResponse {
+    /// New map ID
+    #[schema(example = "map_FybH2oF9Xaw8")]
+    pub id: String,
+    /// Generated slug
+    pub slug: String,
+    /// Results for each row read
+    pub results: Vec<ImportRowResult>,
+    /// Number of locations added to the map
+    pub added: usize,
+    /// Number of rows rejected
+    pub failed: usize,
+    /// Whether reading stopped early at the row or location limit
+    pub truncated: bool,
+}
+
+/// Query params for a map heatmap.
+#[derive(Debug, Deserialize)]
+pub struct HeatmapQuery {
+    /// Geohash precision (1-6)
+    #[serde(default = "default_heatmap_precision")]
+    pub precision: i32,
+    /// Also bin where players guessed
+    #[serde(default)]
+    pub guesses: bool,
+}
+
+fn default_heatmap_precision() -> i32 {
+    DEFAULT_HEATMAP_PRECISION
+}
+
+/// One heatmap bin.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HeatmapBin {
+    /// Geohash of the cell
+    #[schema(example = "u09t")]
+    pub geohash: String,
+    /// Latitude of the cell center
+    pub lat: f64,
+    /// Longitude of the cell center
+    pub lng: f64,
+    /// Number of points in the cell
+    pub count: i64,
+}
+
+/// Map heatmap response.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MapHeatmapResponse {
+    /// Map ID
+    #[schema(example = "map_FybH2oF9Xaw8")]
+    pub map_id: String,
+    /// Geohash precision the bins were computed at
+    pub precision: i32,
+    /// Playable locations per cell, busiest first
+    pub locations: Vec<HeatmapBin>,
+    /// Guesses per cell, busiest first (only when requested)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guesses: Option<Vec<HeatmapBin>>,
+}
+
+/// A map featured, or scheduled to be featured, as the default map.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FeaturedMapItem {
+    /// Map ID
+    #[schema(example = "map_FybH2oF9Xaw8")]
+    pub map_id: String,
+    /// Map slug
+    #[schema(example = "islands")]
+    pub slug: String,
+    /// Map name
+    pub name: String,
+    /// Event name
+    #[schema(example = "Island Week")]
+    pub title: Option<String>,
+    /// When the feature starts
+    pub starts_at: DateTime<Utc>,
+    /// When the feature ends
+    pub ends_at: DateTime<Utc>,
+}
+
+/// Featured maps response.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FeaturedMapsResponse {
+    /// The running feature, if any
+    pub current: Option<FeaturedMapItem>,
+    /// Features that have not started yet, soonest first
+    pub upcoming: Vec<FeaturedMapItem>,
+}
+
+/// Rules validation request.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ValidateRulesRequest {
+    /// Draft map rules, as stored on a map
+    #[schema(value_type = Object)]
+    pub rules: serde_json::Value,
+    /// Count within this map's location list instead of every location
+    #[schema(example = "map_FybH2oF9Xaw8")]
+    pub map_id: Option<String>,
+}
+
+/// Matching locations in one country.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RulesCountryCount {
+    /// ISO 3166-1 alpha-2 code; absent for locations without a country
+    #[schema(example = "SE")]
+    pub country_code: Option<String>,
+    /// Number of matching locations
+    pub count: i64,
+}
+
+/// Rules validation response.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ValidateRulesResponse {
+    /// Active locations matching the rules
+    pub total: i64,
+    /// Matching locations per country, most first
+    pub countries: Vec<RulesCountryCount>,
+}
+
 /// Add locations request.
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct AddLocationsRequest {
@@ -226,6 +681,29 @@ pub struct AddLocationsFromUrlsRequest {
     /// Street View URLs to parse and add
     #[schema(example = json!(["https://www.google.com/maps/@48.8584,2.2945,3a"]))]
     pub urls: Vec<String>,
+    /// Check each panorama against the Street View metadata API before adding
+    #[serde(default)]
+    pub validate: bool,
+}
+
+/// Outcome of importing one URL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UrlImportStatus {
+    /// A new location was created
+    Added,
+    /// An existing location with the same panorama was used
+    AlreadyExists,
+    /// The URL is not a Street View URL
+    InvalidUrl,
+    /// A short link could not be followed
+    ShortLinkFailed,
+    /// Street View has no panorama for the URL
+    PanoramaNotFound,
+    /// The panorama could not be checked
+    ValidationFailed,
+    /// The location could not be saved
+    Error,
 }
 
 /// Result of parsing a URL.
@@ -233,16 +711,50 @@ pub struct AddLocationsFromUrlsRequest {
 pub struct UrlParseResult {
     /// Original URL
     pub url: String,
+    /// Outcome for this URL
+    pub status: UrlImportStatus,
     /// Whether parsing succeeded
     pub success: bool,
     /// Error message if parsing failed
     pub error: Option<String>,
     /// Location ID if successfully added
     pub location_id: Option<String>,
+    /// Panorama ID of the added location
+    pub panorama_id: Option<String>,
     /// Whether the location already existed
     pub already_exists: bool,
 }
 
+impl UrlParseResult {
+    fn added(url: String, location_id: String, panorama_id: String, already_exists: bool) -> Self {
+        Self {
+            url,
+            status: if already_exists {
+                UrlImportStatus::AlreadyExists
+            } else {
+                UrlImportStatus::Added
+            },
+            success: true,
+            error: None,
+            location_id: Some(location_id),
+            panorama_id: Some(panorama_id),
+            already_exists,
+        }
+    }
+
+    fn failed(url: String, status: UrlImportStatus, error: String) -> Self {
+        Self {
+            url,
+            status,
+            success: false,
+            error: Some(error),
+            location_id: None,
+            panorama_id: None,
+            already_exists: false,
+        }
+    }
+}
+
 /// Add locations from URLs response.
 #[derive(Debug, Serialize, ToSchema)]
 pub struct AddLocationsFromUrlsResponse {
@@ -260,23 +772,75 @@ pub struct AddLocationsFromUrlsResponse {
 
 /// List maps visible to the current user.
 ///
-/// Returns public maps and the user's own maps.
+/// Returns public maps, the user's own maps, and maps they collaborate on.
 #[utoipa::path(
     get,
     path = "/api/v1/maps",
     tag = "maps",
+    params(
+        ("sort" = Option<String>, Query, description = "Sort order: newest (default), popular, or most_liked"),
+    ),
     responses(
         (status = 200, description = "List of maps", body = ListMapsResponse),
     )
 )]
 pub async fn list_maps(
     State(state): State<AppState>,
+    org: CurrentOrg,
+    Query(query): Query<ListMapsQuery>,
     MaybeAuthUser(auth): MaybeAuthUser,
 ) -> Result<Json<ListMapsResponse>, ApiError> {
     let user_id = auth.as_ref().map(|a| a.user_id.as_str());
 
-    let maps = dguesser_db::locations::list_visible_maps(state.db(), user_id).await?;
+    let maps = dguesser_db::locations::list_visible_maps(state.db(), user_id, query.sort, org.id())
+        .await?;
+    let (liked, editable) = match user_id {
+        Some(uid) => (
+            dguesser_db::locations::get_liked_map_ids(state.db(), uid).await?,
+            dguesser_db::locations::get_editable_map_ids(state.db(), uid).await?,
+        ),
+        None => (HashSet::new(), HashSet::new()),
+    };
+
+    Ok(Json(ListMapsResponse {
+        maps: build_summaries(&state, maps, user_id, &liked, &editable).await,
+    }))
+}
+
+/// List the current user's favorite (liked) maps.
+///
+/// Most recently liked first. Maps that became private are left out.
+#[utoipa::path(
+    get,
+    path = "/api/v1/maps/favorites",
+    tag = "maps",
+    responses(
+        (status = 200, description = "Liked maps", body = ListMapsResponse),
+        (status = 401, description = "Not authenticated"),
+    )
+)]
+pub async fn list_favorite_maps(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<Json<ListMapsResponse>, ApiError> {
+    let maps = dguesser_db::locations::list_liked_maps(state.db(), &auth.user_id).await?;
+    let liked = maps.iter().map(|m| m.id.clone()).collect();
+    let editable = dguesser_db::locations::get_editable_map_ids(state.db(), &auth.user_id).await?;
+
+    Ok(Json(ListMapsResponse {
+        maps: build_summaries(&state, maps, Some(&auth.user_id), &liked, &editable).await,
+    }))
+}
 
+/// Build list summaries for maps, with live location counts. `editable`
+/// holds the maps the user collaborates on.
+async fn build_summaries(
+    state: &AppState,
+    maps: Vec<Map>,
+    user_id: Option<&str>,
+    liked: &HashSet<String>,
+    editable: &HashSet<String>,
+) -> Vec<MapSummary> {
     // Fetch location counts from provider in parallel (works for both R2 and PostgreSQL)
     // This is much faster than sequential fetching when cache is cold
     let count_futures: Vec<_> = maps
@@ -298,12 +862,14 @@ pub async fn list_maps(
     let counts = join_all(count_futures).await;
 
     // Build summaries with the fetched counts
-    let summaries: Vec<MapSummary> = maps
-        .into_iter()
+    maps.into_iter()
         .zip(counts)
         .map(|(m, location_count)| {
             let is_system = m.is_system_map();
             let is_owned = user_id.is_some_and(|uid| m.is_owned_by(uid));
+            let can_edit = is_owned || editable.contains(&m.id);
+            let avg_score = m.average_score();
+            let is_liked = liked.contains(&m.id);
 
             MapSummary {
                 id: m.id,
@@ -313,13 +879,16 @@ pub async fn list_maps(
                 visibility: m.visibility.to_string(),
                 is_system_map: is_system,
                 is_owned,
+                can_edit,
                 location_count,
+                like_count: m.like_count,
+                play_count: m.play_count,
+                avg_score,
+                is_liked,
                 created_at: m.created_at,
             }
         })
-        .collect();
-
-    Ok(Json(ListMapsResponse { maps: summaries }))
+        .collect()
 }
 
 /// Create a new map.
@@ -337,11 +906,48 @@ pub async fn list_maps(
 )]
 pub async fn create_map(
     State(state): State<AppState>,
+    org: CurrentOrg,
     auth: AuthUser,
     Json(body): Json<CreateMapRequest>,
 ) -> Result<(StatusCode, Json<CreateMapResponse>), ApiError> {
+    if let Some(ref scoring) = body.scoring {
+        validate_scoring(scoring)?;
+    }
+
+    let mut params = prepare_new_map(
+        &state,
+        &org,
+        &auth.user_id,
+        &body.name,
+        body.description,
+        body.visibility.as_deref(),
+        body.region,
+        body.provider,
+    )
+    .await?;
+    params.scoring = body.scoring;
+    params.difficulty = body.difficulty;
+
+    let map = dguesser_db::locations::create_user_map(state.db(), &auth.user_id, &params).await?;
+
+    Ok((StatusCode::CREATED, Json(CreateMapResponse { id: map.id, slug: map.slug })))
+}
+
+/// Validate a new map's fields and check the user can create it in the
+/// organization.
+#[allow(clippy::too_many_arguments)]
+async fn prepare_new_map(
+    state: &AppState,
+    org: &CurrentOrg,
+    user_id: &str,
+    name: &str,
+    description: Option<String>,
+    visibility: Option<&str>,
+    region: Option<MapRegion>,
+    provider: Option<ImageryProvider>,
+) -> Result<dguesser_db::locations::CreateUserMapParams, ApiError> {
     // Validate name
-    let name = body.name.trim();
+    let name = name.trim();
     if name.len() < 3 {
         return Err(ApiError::bad_request(
             "NAME_TOO_SHORT",
@@ -356,7 +962,7 @@ pub async fn create_map(
     }
 
     // Validate description
-    if let Some(ref desc) = body.description
+    if let Some(ref desc) = description
         && desc.len() > 500
     {
         return Err(ApiError::bad_request(
@@ -366,7 +972,7 @@ pub async fn create_map(
     }
 
     // Parse visibility
-    let visibility = match body.visibility.as_deref() {
+    let visibility = match visibility {
         Some("private") | None => MapVisibility::Private,
         Some("unlisted") => MapVisibility::Unlisted,
         Some("public") => MapVisibility::Public,
@@ -378,8 +984,15 @@ pub async fn create_map(
         }
     };
 
+    if let Some(ref region) = region {
+        validate_region(region)?;
+    }
+
+    // Only members create maps in an organization
+    org.require_member(state, user_id).await?;
+
     // Check user's map count
-    let map_count = dguesser_db::locations::get_user_map_count(state.db(), &auth.user_id).await?;
+    let map_count = dguesser_db::locations::get_user_map_count(state.db(), user_id).await?;
 
     if map_count >= MAX_MAPS_PER_USER {
         return Err(ApiError::conflict(
@@ -401,17 +1014,17 @@ pub async fn create_map(
         ));
     }
 
-    // Create the map
-    let params = dguesser_db::locations::CreateUserMapParams {
-        slug: slug.clone(),
+    Ok(dguesser_db::locations::CreateUserMapParams {
+        slug,
         name: name.to_string(),
-        description: body.description.clone(),
+        description,
         visibility,
-    };
-
-    let map = dguesser_db::locations::create_user_map(state.db(), &auth.user_id, &params).await?;
-
-    Ok((StatusCode::CREATED, Json(CreateMapResponse { id: map.id, slug: map.slug })))
+        region,
+        provider,
+        scoring: None,
+        difficulty: None,
+        org_id: org.id().map(str::to_string),
+    })
 }
 
 /// Get map details.
@@ -429,17 +1042,22 @@ pub async fn create_map(
 )]
 pub async fn get_map(
     State(state): State<AppState>,
+    org: CurrentOrg,
     Path(id): Path<String>,
     MaybeAuthUser(auth): MaybeAuthUser,
 ) -> Result<Json<MapDetails>, ApiError> {
     let user_id = auth.as_ref().map(|a| a.user_id.as_str());
 
-    let map = dguesser_db::locations::get_map_if_visible(state.db(), &id, user_id)
+    let map = dguesser_db::locations::get_map_if_visible(state.db(), &id, user_id, org.id())
         .await?
         .ok_or_else(|| ApiError::not_found("Map"))?;
 
     let is_system = map.is_system_map();
     let is_owned = user_id.is_some_and(|uid| map.is_owned_by(uid));
+    let can_edit = match user_id {
+        Some(uid) => can_edit_locations(&state, &map, uid).await?,
+        None => false,
+    };
 
     // Use location provider for count (R2 reads from manifest, PostgreSQL from DB)
     let location_count = state
@@ -448,6 +1066,12 @@ pub async fn get_map(
         .await
         .unwrap_or(map.location_count as i64) as i32;
 
+    let is_liked = match user_id {
+        Some(uid) => dguesser_db::locations::has_liked_map(state.db(), uid, &map.id).await?,
+        None => false,
+    };
+    let avg_score = map.average_score();
+
     Ok(Json(MapDetails {
         id: map.id,
         slug: map.slug,
@@ -456,8 +1080,17 @@ pub async fn get_map(
         visibility: map.visibility.to_string(),
         is_system_map: is_system,
         is_owned,
+        can_edit,
         is_default: map.is_default,
         location_count,
+        like_count: map.like_count,
+        play_count: map.play_count,
+        avg_score,
+        is_liked,
+        region: map.rules.region,
+        provider: map.rules.provider,
+        scoring: map.rules.scoring.unwrap_or_default(),
+        difficulty: map.rules.difficulty,
         created_at: map.created_at,
         updated_at: map.updated_at,
     }))
@@ -482,14 +1115,16 @@ pub async fn get_map(
 )]
 pub async fn update_map(
     State(state): State<AppState>,
+    org: CurrentOrg,
     Path(id): Path<String>,
     auth: AuthUser,
     Json(body): Json<UpdateMapRequest>,
 ) -> Result<Json<MapDetails>, ApiError> {
     // Get the map and check ownership
-    let map = dguesser_db::locations::get_map_if_visible(state.db(), &id, Some(&auth.user_id))
-        .await?
-        .ok_or_else(|| ApiError::not_found("Map"))?;
+    let map =
+        dguesser_db::locations::get_map_if_visible(state.db(), &id, Some(&auth.user_id), org.id())
+            .await?
+            .ok_or_else(|| ApiError::not_found("Map"))?;
 
     if !map.is_owned_by(&auth.user_id) {
         return Err(ApiError::forbidden("You can only update your own maps"));
@@ -535,16 +1170,36 @@ pub async fn update_map(
         None => None,
     };
 
+    if let Some(Some(ref region)) = body.region {
+        validate_region(region)?;
+    }
+    if let Some(Some(ref scoring)) = body.scoring {
+        validate_scoring(scoring)?;
+    }
+
     // Update the map
     let params = dguesser_db::locations::UpdateMapParams {
         name: body.name.as_ref().map(|n| n.trim().to_string()),
         description: body.description.as_ref().map(|d| Some(d.clone())),
         visibility,
+        region: body.region.clone(),
+        scoring: body.scoring.clone(),
+        difficulty: body.difficulty,
     };
 
     let updated = dguesser_db::locations::update_map(state.db(), &id, &params).await?;
 
+    if (body.region.is_some() || body.scoring.is_some() || body.difficulty.is_some())
+        && let Err(e) =
+            map_versions::record_rules_change(state.db(), &map.id, &auth.user_id, &map.rules).await
+    {
+        tracing::warn!(map_id = %map.id, error = %e, "Failed to record map rules version");
+    }
+
     let is_system = updated.is_system_map();
+    let is_liked =
+        dguesser_db::locations::has_liked_map(state.db(), &auth.user_id, &updated.id).await?;
+    let avg_score = updated.average_score();
 
     Ok(Json(MapDetails {
         id: updated.id,
@@ -554,8 +1209,17 @@ pub async fn update_map(
         visibility: updated.visibility.to_string(),
         is_system_map: is_system,
         is_owned: true,
+        can_edit: true,
         is_default: updated.is_default,
         location_count: updated.location_count,
+        like_count: updated.like_count,
+        play_count: updated.play_count,
+        avg_score,
+        is_liked,
+        region: updated.rules.region,
+        provider: updated.rules.provider,
+        scoring: updated.rules.scoring.unwrap_or_default(),
+        difficulty: updated.rules.difficulty,
         created_at: updated.created_at,
         updated_at: updated.updated_at,
     }))
@@ -578,13 +1242,15 @@ pub async fn update_map(
 )]
 pub async fn delete_map(
     State(state): State<AppState>,
+    org: CurrentOrg,
     Path(id): Path<String>,
     auth: AuthUser,
 ) -> Result<StatusCode, ApiError> {
     // Get the map and check ownership
-    let map = dguesser_db::locations::get_map_if_visible(state.db(), &id, Some(&auth.user_id))
-        .await?
-        .ok_or_else(|| ApiError::not_found("Map"))?;
+    let map =
+        dguesser_db::locations::get_map_if_visible(state.db(), &id, Some(&auth.user_id), org.id())
+            .await?
+            .ok_or_else(|| ApiError::not_found("Map"))?;
 
     if !map.is_owned_by(&auth.user_id) {
         return Err(ApiError::forbidden("You can only delete your own maps"));
@@ -613,6 +1279,7 @@ pub async fn delete_map(
 )]
 pub async fn get_map_locations(
     State(state): State<AppState>,
+    org: CurrentOrg,
     Path(id): Path<String>,
     Query(query): Query<ListLocationsQuery>,
     MaybeAuthUser(auth): MaybeAuthUser,
@@ -620,7 +1287,7 @@ pub async fn get_map_locations(
     let user_id = auth.as_ref().map(|a| a.user_id.as_str());
 
     // Check map exists and is visible
-    let map = dguesser_db::locations::get_map_if_visible(state.db(), &id, user_id)
+    let map = dguesser_db::locations::get_map_if_visible(state.db(), &id, user_id, org.id())
         .await?
         .ok_or_else(|| ApiError::not_found("Map"))?;
 
@@ -646,88 +1313,614 @@ pub async fn get_map_locations(
     Ok(Json(MapLocationsResponse { locations: items, total: map.location_count, page, per_page }))
 }
 
-/// Add locations to a map.
+/// Export a map's locations as a file.
+///
+/// The file is streamed, so large maps do not have to fit in memory.
 #[utoipa::path(
-    post,
-    path = "/api/v1/maps/{id}/locations",
+    get,
+    path = "/api/v1/maps/{id}/export",
     tag = "maps",
     params(
-        ("id" = String, Path, description = "Map ID")
+        ("id" = String, Path, description = "Map ID"),
+        ("format" = Option<String>, Query, description = "File format: geojson (default) or csv"),
     ),
-    request_body = AddLocationsRequest,
     responses(
-        (status = 200, description = "Locations added", body = AddLocationsResponse),
-        (status = 400, description = "Invalid request or limit exceeded"),
-        (status = 401, description = "Not authenticated"),
-        (status = 403, description = "Not the owner"),
+        (status = 200, description = "GeoJSON FeatureCollection or CSV file", body = String),
         (status = 404, description = "Map not found"),
     )
 )]
-pub async fn add_locations(
+pub async fn export_map(
     State(state): State<AppState>,
+    org: CurrentOrg,
     Path(id): Path<String>,
-    auth: AuthUser,
-    Json(body): Json<AddLocationsRequest>,
-) -> Result<Json<AddLocationsResponse>, ApiError> {
-    // Get the map and check ownership
-    let map = dguesser_db::locations::get_map_if_visible(state.db(), &id, Some(&auth.user_id))
+    Query(query): Query<ExportMapQuery>,
+    MaybeAuthUser(auth): MaybeAuthUser,
+) -> Result<Response, ApiError> {
+    let user_id = auth.as_ref().map(|a| a.user_id.as_str());
+
+    let map = dguesser_db::locations::get_map_if_visible(state.db(), &id, user_id, org.id())
         .await?
         .ok_or_else(|| ApiError::not_found("Map"))?;
 
-    if !map.is_owned_by(&auth.user_id) {
-        return Err(ApiError::forbidden("You can only add locations to your own maps"));
-    }
-
-    // Check location limit
-    let new_count = map.location_count + body.location_ids.len() as i32;
-    if new_count > MAX_LOCATIONS_PER_MAP {
-        return Err(ApiError::bad_request(
-            "LOCATION_LIMIT_EXCEEDED",
-            format!(
-                "Map can have at most {} locations. Current: {}, Adding: {}",
-                MAX_LOCATIONS_PER_MAP,
-                map.location_count,
-                body.location_ids.len()
-            ),
-        ));
-    }
-
-    // Add locations
-    let added =
-        dguesser_db::locations::add_locations_to_map_batch(state.db(), &id, &body.location_ids)
+    let format = query.format.unwrap_or(ExchangeFormat::GeoJson);
+    let (head, foot) = match format {
+        ExchangeFormat::GeoJson => {
+            (map_exchange::geojson_header(&map), map_exchange::GEOJSON_FOOTER.to_string())
+        }
+        ExchangeFormat::Csv => (map_exchange::csv_header(), String::new()),
+    };
+    let disposition = format!("attachment; filename=\"{}.{}\"", map.slug, format.extension());
+
+    // Walk the map page by page; each page becomes one body chunk
+    let pool = state.db().clone();
+    let pages = stream::try_unfold((Some(None::<String>), true), move |(cursor, first)| {
+        let pool = pool.clone();
+        let map = map.clone();
+        async move {
+            let Some(after) = cursor else {
+                return Ok(None);
+            };
+            let page = dguesser_db::locations::get_map_locations_after(
+                &pool,
+                &map,
+                after.as_deref(),
+                EXPORT_PAGE_SIZE,
+            )
             .await?;
-
-    // Get updated count
-    let updated_map =
-        dguesser_db::locations::get_map_if_visible(state.db(), &id, Some(&auth.user_id))
-            .await?
-            .ok_or_else(|| ApiError::not_found("Map"))?;
-
-    Ok(Json(AddLocationsResponse { added, total: updated_map.location_count }))
+            let Some(last) = page.last() else {
+                return Ok(None);
+            };
+
+            // A short page is the last one
+            let next = (page.len() as i64 == EXPORT_PAGE_SIZE).then(|| Some(last.id.clone()));
+            let mut chunk = String::new();
+            for (i, location) in page.iter().enumerate() {
+                let location = ExchangeLocation::from_location(location);
+                match format {
+                    ExchangeFormat::GeoJson => {
+                        chunk.push_str(&map_exchange::geojson_feature(&location, first && i == 0))
+                    }
+                    ExchangeFormat::Csv => chunk.push_str(&map_exchange::csv_row(&location)),
+                }
+            }
+            Ok::<_, dguesser_core::location::LocationError>(Some((chunk, (next, false))))
+        }
+    })
+    .inspect_err(|e| tracing::error!(error = %e, "Map export failed mid-stream"));
+
+    let body = stream::once(async move { Ok(head) })
+        .chain(pages)
+        .chain(stream::once(async move { Ok(foot) }));
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        Body::from_stream(body),
+    )
+        .into_response())
 }
 
-/// Add locations from Street View URLs.
+/// Import a map from a GeoJSON or CSV file.
+///
+/// Creates a new map owned by the current user from the request body, which
+/// is read as it arrives. Every row is validated separately: bad rows are
+/// reported and skipped without failing the import.
 #[utoipa::path(
     post,
-    path = "/api/v1/maps/{id}/locations/from-urls",
+    path = "/api/v1/maps/import",
     tag = "maps",
     params(
-        ("id" = String, Path, description = "Map ID")
+        ("name" = String, Query, description = "Name of the new map"),
+        ("description" = Option<String>, Query, description = "Description"),
+        ("visibility" = Option<String>, Query, description = "private (default), unlisted, or public"),
+        ("format" = Option<String>, Query, description = "geojson or csv (default: from Content-Type)"),
     ),
-    request_body = AddLocationsFromUrlsRequest,
+    request_body(content = String, description = "GeoJSON FeatureCollection of Points, or CSV with lat/lng columns"),
     responses(
-        (status = 200, description = "Locations parsed and added", body = AddLocationsFromUrlsResponse),
-        (status = 400, description = "Invalid request or limit exceeded"),
+        (status = 201, description = "Map imported", body = ImportMapResponse),
+        (status = 400, description = "Invalid request or unreadable file"),
         (status = 401, description = "Not authenticated"),
-        (status = 403, description = "Not the owner"),
-        (status = 404, description = "Map not found"),
+        (status = 409, description = "Map limit reached or slug taken"),
     )
 )]
-pub async fn add_locations_from_urls(
+pub async fn import_map(
     State(state): State<AppState>,
-    Path(id): Path<String>,
+    org: CurrentOrg,
     auth: AuthUser,
-    Json(body): Json<AddLocationsFromUrlsRequest>,
+    Query(query): Query<ImportMapQuery>,
+    headers: HeaderMap,
+    body: Body,
+) -> Result<(StatusCode, Json<ImportMapResponse>), ApiError> {
+    let format = query
+        .format
+        .or_else(|| {
+            headers
+                .get(header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .and_then(ExchangeFormat::from_content_type)
+        })
+        .ok_or_else(|| {
+            ApiError::bad_request(
+                "UNKNOWN_FORMAT",
+                "Set format=geojson or format=csv, or send a GeoJSON or CSV Content-Type",
+            )
+        })?;
+
+    let params = prepare_new_map(
+        &state,
+        &org,
+        &auth.user_id,
+        &query.name,
+        query.description,
+        query.visibility.as_deref(),
+        None,
+        None,
+    )
+    .await?;
+    let map = dguesser_db::locations::create_user_map(state.db(), &auth.user_id, &params).await?;
+
+    // Parse on a blocking thread straight from the body stream. The bounded
+    // channel applies backpressure, so the body is only read as fast as rows
+    // are stored.
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<ParsedRow>(IMPORT_BATCH_SIZE);
+    let reader = tokio_util::io::SyncIoBridge::new(tokio_util::io::StreamReader::new(
+        body.into_data_stream().map_err(std::io::Error::other),
+    ));
+    let parser = tokio::task::spawn_blocking(move || {
+        map_exchange::parse(format, reader, |row| tx.blocking_send(row).is_ok())
+    });
+
+    let mut results = Vec::new();
+    let mut pending = Vec::with_capacity(IMPORT_BATCH_SIZE);
+    let mut added = 0;
+    let mut imported = 0;
+    let mut truncated = false;
+
+    while let Some((row, parsed)) = rx.recv().await {
+        if results.len() >= MAX_IMPORT_ROWS || imported >= MAX_LOCATIONS_PER_MAP as usize {
+            truncated = true;
+            break;
+        }
+
+        let result = match parsed {
+            Ok(location) => match import_location(&state, &location).await {
+                Ok((location_id, already_exists)) => {
+                    pending.push(location_id.clone());
+                    imported += 1;
+                    ImportRowResult {
+                        row,
+                        success: true,
+                        error: None,
+                        location_id: Some(location_id),
+                        already_exists,
+                    }
+                }
+                Err(e) => ImportRowResult {
+                    row,
+                    success: false,
+                    error: Some(format!("Failed to create location: {}", e)),
+                    location_id: None,
+                    already_exists: false,
+                },
+            },
+            Err(error) => ImportRowResult {
+                row,
+                success: false,
+                error: Some(error),
+                location_id: None,
+                already_exists: false,
+            },
+        };
+        results.push(result);
+
+        if pending.len() >= IMPORT_BATCH_SIZE {
+            added += map_versions::add_locations(
+                state.db(),
+                &map.id,
+                &auth.user_id,
+                &pending,
+                MapVersionKind::Import,
+            )
+            .await?;
+            pending.clear();
+        }
+    }
+    // Dropping the receiver makes the parser stop at its next row
+    drop(rx);
+
+    added += map_versions::add_locations(
+        state.db(),
+        &map.id,
+        &auth.user_id,
+        &pending,
+        MapVersionKind::Import,
+    )
+    .await?;
+
+    let parse_result = parser.await.map_err(|e| {
+        tracing::error!(error = %e, "Map import parser panicked");
+        ApiError::internal()
+    })?;
+    if let Err(message) = parse_result
+        && !truncated
+    {
+        if results.is_empty() {
+            // Nothing was readable; don't leave an empty map behind
+            dguesser_db::locations::delete_map(state.db(), &map.id).await?;
+            return Err(ApiError::bad_request("INVALID_FILE", message));
+        }
+        tracing::warn!(map_id = %map.id, error = %message, "Map import stopped at unreadable data");
+        truncated = true;
+    }
+
+    let failed = results.iter().filter(|r| !r.success).count();
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ImportMapResponse { id: map.id, slug: map.slug, results, added, failed, truncated }),
+    ))
+}
+
+/// Find an imported location by panorama ID, or create it.
+/// Returns the location ID and whether it already existed.
+async fn import_location(
+    state: &AppState,
+    location: &ExchangeLocation,
+) -> Result<(String, bool), dguesser_core::location::LocationError> {
+    let panorama_id = location.storage_panorama_id();
+    if let Some(existing) =
+        dguesser_db::locations::get_location_by_panorama_id(state.db(), &panorama_id).await?
+    {
+        return Ok((existing.id, true));
+    }
+
+    let params = dguesser_db::locations::CreateLocationParams {
+        panorama_id,
+        lat: location.lat,
+        lng: location.lng,
+        country_code: location.country_code.clone(),
+        subdivision_code: location.subdivision_code.clone(),
+        capture_date: location.capture_date,
+        provider: location.provider,
+        source: "imported".to_string(),
+        heading: location.heading,
+        review_status: "approved".to_string(),
+        ..Default::default()
+    };
+    let created = dguesser_db::locations::create_location_full(state.db(), &params).await?;
+    Ok((created.id, false))
+}
+
+/// Get the current and upcoming featured maps.
+///
+/// A featured map is the default map for the period of a scheduled event.
+#[utoipa::path(
+    get,
+    path = "/api/v1/maps/featured",
+    tag = "maps",
+    responses(
+        (status = 200, description = "Current and upcoming features", body = FeaturedMapsResponse),
+    )
+)]
+pub async fn get_featured_maps(
+    State(state): State<AppState>,
+) -> Result<Json<FeaturedMapsResponse>, ApiError> {
+    let current = dguesser_db::featured_maps::current(state.db_read()).await?;
+    let upcoming =
+        dguesser_db::featured_maps::upcoming(state.db_read(), MAX_UPCOMING_FEATURES).await?;
+
+    Ok(Json(FeaturedMapsResponse {
+        current: current.map(featured_map_item),
+        upcoming: upcoming.into_iter().map(featured_map_item).collect(),
+    }))
+}
+
+fn featured_map_item(feature: dguesser_db::featured_maps::FeaturedMap) -> FeaturedMapItem {
+    FeaturedMapItem {
+        map_id: feature.map_id,
+        slug: feature.map_slug,
+        name: feature.map_name,
+        title: feature.title,
+        starts_at: feature.starts_at,
+        ends_at: feature.ends_at,
+    }
+}
+
+/// Validate draft map rules and count the locations they match.
+///
+/// Lets creators see how year, outdoor, provider and difficulty filters
+/// change a map's pool before saving. With `map_id` the map's own location
+/// list is counted (unless the rules set a region); otherwise every active
+/// location is.
+#[utoipa::path(
+    post,
+    path = "/api/v1/maps/validate-rules",
+    tag = "maps",
+    request_body = ValidateRulesRequest,
+    responses(
+        (status = 200, description = "Matching location counts", body = ValidateRulesResponse),
+        (status = 400, description = "Invalid rules"),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "Map not found"),
+    )
+)]
+pub async fn validate_rules(
+    State(state): State<AppState>,
+    org: CurrentOrg,
+    auth: AuthUser,
+    Json(body): Json<ValidateRulesRequest>,
+) -> Result<Json<ValidateRulesResponse>, ApiError> {
+    let invalid = |message: String| ApiError::bad_request("INVALID_RULES", message);
+    let mut rules: MapRules = serde_json::from_value(body.rules)
+        .map_err(|e| invalid(format!("Invalid map rules: {e}")))?;
+    rules.validate().map_err(|e| invalid(e.to_string()))?;
+    rules.normalize();
+
+    let map_id = match body.map_id {
+        Some(id) => Some(
+            dguesser_db::locations::get_map_if_visible(
+                state.db(),
+                &id,
+                Some(&auth.user_id),
+                org.id(),
+            )
+            .await?
+            .ok_or_else(|| ApiError::not_found("Map"))?
+            .id,
+        ),
+        None => None,
+    };
+
+    let counts = dguesser_db::locations::count_locations_by_rules(
+        state.db_read(),
+        &rules,
+        map_id.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(ValidateRulesResponse {
+        total: counts.iter().map(|(_, count)| count).sum(),
+        countries: counts
+            .into_iter()
+            .map(|(country_code, count)| RulesCountryCount { country_code, count })
+            .collect(),
+    }))
+}
+
+/// Get a coverage heatmap for a map.
+///
+/// Bins the map's playable locations into geohash cells and, when `guesses`
+/// is set, where players guessed in games on the map. Results are cached
+/// briefly, so fresh edits may take a few minutes to show up.
+#[utoipa::path(
+    get,
+    path = "/api/v1/maps/{id}/heatmap",
+    tag = "maps",
+    params(
+        ("id" = String, Path, description = "Map ID"),
+        ("precision" = Option<i32>, Query, description = "Geohash precision (default 4, 1-6)"),
+        ("guesses" = Option<bool>, Query, description = "Include guess heatmap (default false)"),
+    ),
+    responses(
+        (status = 200, description = "Map heatmap", body = MapHeatmapResponse),
+        (status = 400, description = "Invalid precision"),
+        (status = 404, description = "Map not found"),
+    )
+)]
+pub async fn get_map_heatmap(
+    State(state): State<AppState>,
+    org: CurrentOrg,
+    Path(id): Path<String>,
+    Query(query): Query<HeatmapQuery>,
+    MaybeAuthUser(auth): MaybeAuthUser,
+) -> Result<Json<MapHeatmapResponse>, ApiError> {
+    let user_id = auth.as_ref().map(|a| a.user_id.as_str());
+
+    if !dguesser_db::locations::HEATMAP_PRECISION_RANGE.contains(&query.precision) {
+        return Err(ApiError::bad_request(
+            "INVALID_PRECISION",
+            "Precision must be between 1 and 6",
+        ));
+    }
+
+    let map = dguesser_db::locations::get_map_if_visible(state.db(), &id, user_id, org.id())
+        .await?
+        .ok_or_else(|| ApiError::not_found("Map"))?;
+
+    let redis = state.redis();
+    let heatmap = match HeatmapCache::get(redis, &map.id, query.precision, query.guesses).await {
+        Some(cached) => cached,
+        None => {
+            let locations =
+                dguesser_db::locations::get_map_location_heatmap(state.db(), &map, query.precision)
+                    .await?;
+            let guesses = if query.guesses {
+                Some(
+                    dguesser_db::locations::get_map_guess_heatmap(
+                        state.db(),
+                        &map,
+                        query.precision,
+                    )
+                    .await?,
+                )
+            } else {
+                None
+            };
+            let heatmap = CachedHeatmap { locations, guesses };
+            HeatmapCache::set(redis, &map.id, query.precision, query.guesses, &heatmap).await;
+            heatmap
+        }
+    };
+
+    let to_bins = |cells: Vec<dguesser_db::locations::HeatmapCell>| {
+        cells
+            .into_iter()
+            .map(|c| HeatmapBin { geohash: c.geohash, lat: c.lat, lng: c.lng, count: c.count })
+            .collect::<Vec<_>>()
+    };
+
+    Ok(Json(MapHeatmapResponse {
+        map_id: map.id,
+        precision: query.precision,
+        locations: to_bins(heatmap.locations),
+        guesses: heatmap.guesses.map(to_bins),
+    }))
+}
+
+/// Like a map.
+///
+/// Liked maps show up in the user's favorites. Liking twice is a no-op.
+#[utoipa::path(
+    post,
+    path = "/api/v1/maps/{id}/like",
+    tag = "maps",
+    params(
+        ("id" = String, Path, description = "Map ID")
+    ),
+    responses(
+        (status = 200, description = "Map liked", body = MapLikeResponse),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "Map not found"),
+    )
+)]
+pub async fn like_map(
+    State(state): State<AppState>,
+    org: CurrentOrg,
+    Path(id): Path<String>,
+    auth: AuthUser,
+) -> Result<Json<MapLikeResponse>, ApiError> {
+    let map =
+        dguesser_db::locations::get_map_if_visible(state.db(), &id, Some(&auth.user_id), org.id())
+            .await?
+            .ok_or_else(|| ApiError::not_found("Map"))?;
+
+    let like_count = dguesser_db::locations::like_map(state.db(), &auth.user_id, &map.id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Map"))?;
+
+    Ok(Json(MapLikeResponse { liked: true, like_count }))
+}
+
+/// Remove a like from a map.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/maps/{id}/like",
+    tag = "maps",
+    params(
+        ("id" = String, Path, description = "Map ID")
+    ),
+    responses(
+        (status = 200, description = "Like removed", body = MapLikeResponse),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "Map not found"),
+    )
+)]
+pub async fn unlike_map(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    auth: AuthUser,
+) -> Result<Json<MapLikeResponse>, ApiError> {
+    // No visibility check: users can always drop a like, even from a map
+    // that has since been made private
+    let like_count = dguesser_db::locations::unlike_map(state.db(), &auth.user_id, &id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Map"))?;
+
+    Ok(Json(MapLikeResponse { liked: false, like_count }))
+}
+
+/// Add locations to a map.
+#[utoipa::path(
+    post,
+    path = "/api/v1/maps/{id}/locations",
+    tag = "maps",
+    params(
+        ("id" = String, Path, description = "Map ID")
+    ),
+    request_body = AddLocationsRequest,
+    responses(
+        (status = 200, description = "Locations added", body = AddLocationsResponse),
+        (status = 400, description = "Invalid request or limit exceeded"),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Not the owner or an editor"),
+        (status = 404, description = "Map not found"),
+    )
+)]
+pub async fn add_locations(
+    State(state): State<AppState>,
+    org: CurrentOrg,
+    Path(id): Path<String>,
+    auth: AuthUser,
+    Json(body): Json<AddLocationsRequest>,
+) -> Result<Json<AddLocationsResponse>, ApiError> {
+    // Get the map and check edit access
+    let map =
+        dguesser_db::locations::get_map_if_visible(state.db(), &id, Some(&auth.user_id), org.id())
+            .await?
+            .ok_or_else(|| ApiError::not_found("Map"))?;
+
+    if !can_edit_locations(&state, &map, &auth.user_id).await? {
+        return Err(ApiError::forbidden("You can only edit maps you own or collaborate on"));
+    }
+
+    // Check location limit
+    let new_count = map.location_count + body.location_ids.len() as i32;
+    if new_count > MAX_LOCATIONS_PER_MAP {
+        return Err(ApiError::bad_request(
+            "LOCATION_LIMIT_EXCEEDED",
+            format!(
+                "Map can have at most {} locations. Current: {}, Adding: {}",
+                MAX_LOCATIONS_PER_MAP,
+                map.location_count,
+                body.location_ids.len()
+            ),
+        ));
+    }
+
+    // Add locations
+    let added = map_versions::add_locations(
+        state.db(),
+        &map.id,
+        &auth.user_id,
+        &body.location_ids,
+        MapVersionKind::Locations,
+    )
+    .await?;
+
+    // Get updated count
+    let updated_map =
+        dguesser_db::locations::get_map_if_visible(state.db(), &id, Some(&auth.user_id), org.id())
+            .await?
+            .ok_or_else(|| ApiError::not_found("Map"))?;
+
+    Ok(Json(AddLocationsResponse { added, total: updated_map.location_count }))
+}
+
+/// Add locations from Street View URLs.
+#[utoipa::path(
+    post,
+    path = "/api/v1/maps/{id}/locations/from-urls",
+    tag = "maps",
+    params(
+        ("id" = String, Path, description = "Map ID")
+    ),
+    request_body = AddLocationsFromUrlsRequest,
+    responses(
+        (status = 200, description = "Locations parsed and added", body = AddLocationsFromUrlsResponse),
+        (status = 400, description = "Invalid request, limit exceeded, or validation unavailable"),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Not the owner or an editor"),
+        (status = 404, description = "Map not found"),
+    )
+)]
+pub async fn add_locations_from_urls(
+    State(state): State<AppState>,
+    org: CurrentOrg,
+    Path(id): Path<String>,
+    auth: AuthUser,
+    Json(body): Json<AddLocationsFromUrlsRequest>,
 ) -> Result<Json<AddLocationsFromUrlsResponse>, ApiError> {
     // Check URL count limit
     if body.urls.len() > MAX_URLS_PER_IMPORT {
@@ -737,100 +1930,87 @@ pub async fn add_locations_from_urls(
         ));
     }
 
-    // Get the map and check ownership
-    let map = dguesser_db::locations::get_map_if_visible(state.db(), &id, Some(&auth.user_id))
-        .await?
-        .ok_or_else(|| ApiError::not_found("Map"))?;
+    // Get the map and check edit access
+    let map =
+        dguesser_db::locations::get_map_if_visible(state.db(), &id, Some(&auth.user_id), org.id())
+            .await?
+            .ok_or_else(|| ApiError::not_found("Map"))?;
 
-    if !map.is_owned_by(&auth.user_id) {
-        return Err(ApiError::forbidden("You can only add locations to your own maps"));
+    if !can_edit_locations(&state, &map, &auth.user_id).await? {
+        return Err(ApiError::forbidden("You can only edit maps you own or collaborate on"));
+    }
+
+    if body.validate && !state.street_view().can_validate() {
+        return Err(ApiError::bad_request(
+            "VALIDATION_UNAVAILABLE",
+            "Panorama validation is not configured on this server",
+        ));
     }
 
-    // Process each URL
+    // Resolve URLs concurrently, keeping the request order
+    let validate = body.validate;
+    let resolved: Vec<_> = stream::iter(body.urls.clone())
+        .map(|url| {
+            let state = state.clone();
+            async move { resolve_streetview_url(&state, &url, validate).await }
+        })
+        .buffered(URL_RESOLVE_CONCURRENCY)
+        .collect()
+        .await;
+
+    // Find or create locations one at a time so repeated panoramas in the
+    // same request share a location
     let mut results = Vec::with_capacity(body.urls.len());
     let mut location_ids_to_add = Vec::new();
 
-    for url in &body.urls {
-        let result = match parse_streetview_url(url) {
-            Ok(info) => {
-                // Check if we already have this location by coordinates
-                // For now, we'll create a unique panorama ID based on coordinates
-                let pano_id = info
-                    .panorama_id
-                    .clone()
-                    .unwrap_or_else(|| format!("url_{:.6}_{:.6}", info.lat, info.lng));
-
-                // Check if location with this panorama ID exists
-                match dguesser_db::locations::get_location_by_panorama_id(state.db(), &pano_id)
-                    .await
+    for (url, resolved) in body.urls.iter().zip(resolved) {
+        let pano = match resolved {
+            Ok(pano) => pano,
+            Err((status, error)) => {
+                results.push(UrlParseResult::failed(url.clone(), status, error));
+                continue;
+            }
+        };
+
+        let result = match dguesser_db::locations::get_location_by_panorama_id(
+            state.db(),
+            &pano.panorama_id,
+        )
+        .await
+        {
+            Ok(Some(existing)) => {
+                location_ids_to_add.push(existing.id.clone());
+                UrlParseResult::added(url.clone(), existing.id, pano.panorama_id, true)
+            }
+            Ok(None) => {
+                match dguesser_db::locations::create_location(
+                    state.db(),
+                    &pano.panorama_id,
+                    pano.lat,
+                    pano.lng,
+                    None, // country_code - would need reverse geocoding
+                    None, // subdivision_code
+                    pano.capture_date,
+                    ImageryProvider::GoogleStreetView,
+                )
+                .await
                 {
-                    Ok(Some(existing)) => {
-                        location_ids_to_add.push(existing.id.clone());
-                        UrlParseResult {
-                            url: url.clone(),
-                            success: true,
-                            error: None,
-                            location_id: Some(existing.id),
-                            already_exists: true,
-                        }
+                    Ok(loc) => {
+                        location_ids_to_add.push(loc.id.clone());
+                        UrlParseResult::added(url.clone(), loc.id, pano.panorama_id, false)
                     }
-                    Ok(None) => {
-                        // Create new location
-                        match dguesser_db::locations::create_location(
-                            state.db(),
-                            &pano_id,
-                            info.lat,
-                            info.lng,
-                            None, // country_code - would need reverse geocoding
-                            None, // subdivision_code
-                            None, // capture_date
-                            "google_streetview",
-                        )
-                        .await
-                        {
-                            Ok(loc) => {
-                                location_ids_to_add.push(loc.id.clone());
-                                UrlParseResult {
-                                    url: url.clone(),
-                                    success: true,
-                                    error: None,
-                                    location_id: Some(loc.id),
-                                    already_exists: false,
-                                }
-                            }
-                            Err(e) => UrlParseResult {
-                                url: url.clone(),
-                                success: false,
-                                error: Some(format!("Failed to create location: {}", e)),
-                                location_id: None,
-                                already_exists: false,
-                            },
-                        }
-                    }
-                    Err(e) => UrlParseResult {
-                        url: url.clone(),
-                        success: false,
-                        error: Some(format!("Database error: {}", e)),
-                        location_id: None,
-                        already_exists: false,
-                    },
+                    Err(e) => UrlParseResult::failed(
+                        url.clone(),
+                        UrlImportStatus::Error,
+                        format!("Failed to create location: {}", e),
+                    ),
                 }
             }
-            Err(e) => UrlParseResult {
-                url: url.clone(),
-                success: false,
-                error: Some(match e {
-                    StreetViewUrlError::InvalidFormat(s) => format!("Invalid URL format: {}", s),
-                    StreetViewUrlError::MissingCoordinates => {
-                        "Missing coordinates in URL".to_string()
-                    }
-                    StreetViewUrlError::InvalidLatitude(s) => format!("Invalid latitude: {}", s),
-                    StreetViewUrlError::InvalidLongitude(s) => format!("Invalid longitude: {}", s),
-                    StreetViewUrlError::NotStreetViewUrl => "Not a Street View URL".to_string(),
-                }),
-                location_id: None,
-                already_exists: false,
-            },
+            Err(e) => UrlParseResult::failed(
+                url.clone(),
+                UrlImportStatus::Error,
+                format!("Database error: {}", e),
+            ),
         };
         results.push(result);
     }
@@ -848,19 +2028,98 @@ pub async fn add_locations_from_urls(
     }
 
     // Add locations to map
-    let added =
-        dguesser_db::locations::add_locations_to_map_batch(state.db(), &id, &location_ids_to_add)
-            .await?;
+    let added = map_versions::add_locations(
+        state.db(),
+        &map.id,
+        &auth.user_id,
+        &location_ids_to_add,
+        MapVersionKind::Locations,
+    )
+    .await?;
 
     // Get updated count
     let updated_map =
-        dguesser_db::locations::get_map_if_visible(state.db(), &id, Some(&auth.user_id))
+        dguesser_db::locations::get_map_if_visible(state.db(), &id, Some(&auth.user_id), org.id())
             .await?
             .ok_or_else(|| ApiError::not_found("Map"))?;
 
     Ok(Json(AddLocationsFromUrlsResponse { results, added, total: updated_map.location_count }))
 }
 
+/// A Street View URL resolved to the panorama to add.
+struct ResolvedPanorama {
+    panorama_id: String,
+    lat: f64,
+    lng: f64,
+    capture_date: Option<NaiveDate>,
+}
+
+/// Parse a Street View URL, following short links and, when `validate` is
+/// set, checking the panorama against the metadata API.
+async fn resolve_streetview_url(
+    state: &AppState,
+    url: &str,
+    validate: bool,
+) -> Result<ResolvedPanorama, (UrlImportStatus, String)> {
+    let info = if is_short_link(url) {
+        let target = state.street_view().resolve_short_link(url).await.map_err(|e| {
+            (UrlImportStatus::ShortLinkFailed, format!("Could not follow short link: {}", e))
+        })?;
+        parse_streetview_url(&target)
+    } else {
+        parse_streetview_url(url)
+    }
+    .map_err(|e| (UrlImportStatus::InvalidUrl, url_error_message(e)))?;
+
+    if !validate {
+        // Without a panorama ID, derive a stable one from the coordinates
+        let panorama_id =
+            info.panorama_id.unwrap_or_else(|| format!("url_{:.6}_{:.6}", info.lat, info.lng));
+        return Ok(ResolvedPanorama {
+            panorama_id,
+            lat: info.lat,
+            lng: info.lng,
+            capture_date: None,
+        });
+    }
+
+    let query = match info.panorama_id.as_deref() {
+        Some(panorama_id) => PanoramaQuery::Panorama(panorama_id),
+        None => PanoramaQuery::Location { lat: info.lat, lng: info.lng },
+    };
+    match state.street_view().lookup(query).await {
+        PanoramaStatus::Exists(meta) => Ok(ResolvedPanorama {
+            panorama_id: meta.panorama_id,
+            lat: meta.lat,
+            lng: meta.lng,
+            capture_date: meta.capture_date,
+        }),
+        PanoramaStatus::Missing(status) => Err((
+            UrlImportStatus::PanoramaNotFound,
+            format!("No Street View panorama found ({})", status),
+        )),
+        PanoramaStatus::Fatal(e) if e.starts_with(OVER_QUERY_LIMIT) => Err((
+            UrlImportStatus::ValidationFailed,
+            "Street View quota exceeded, try again later".to_string(),
+        )),
+        PanoramaStatus::Error(e) | PanoramaStatus::Fatal(e) => {
+            Err((UrlImportStatus::ValidationFailed, format!("Could not check panorama: {}", e)))
+        }
+    }
+}
+
+/// User-facing message for a URL that could not be parsed.
+fn url_error_message(error: StreetViewUrlError) -> String {
+    match error {
+        StreetViewUrlError::InvalidFormat(s) => format!("Invalid URL format: {}", s),
+        StreetViewUrlError::MissingCoordinates => "Missing coordinates in URL".to_string(),
+        StreetViewUrlError::InvalidLatitude(s) => format!("Invalid latitude: {}", s),
+        StreetViewUrlError::InvalidLongitude(s) => format!("Invalid longitude: {}", s),
+        StreetViewUrlError::NotStreetViewUrl => "Not a Street View URL".to_string(),
+        StreetViewUrlError::UnresolvedShortLink => "Short link could not be resolved".to_string(),
+    }
+}
+
 /// Remove a location from a map.
 #[utoipa::path(
     delete,
@@ -873,39 +2132,595 @@ pub async fn add_locations_from_urls(
     responses(
         (status = 204, description = "Location removed"),
         (status = 401, description = "Not authenticated"),
-        (status = 403, description = "Not the owner"),
+        (status = 403, description = "Not the owner or an editor"),
         (status = 404, description = "Map or location not found"),
     )
 )]
 pub async fn remove_location(
     State(state): State<AppState>,
+    org: CurrentOrg,
     Path((id, location_id)): Path<(String, String)>,
     auth: AuthUser,
 ) -> Result<StatusCode, ApiError> {
-    // Get the map and check ownership
-    let map = dguesser_db::locations::get_map_if_visible(state.db(), &id, Some(&auth.user_id))
-        .await?
-        .ok_or_else(|| ApiError::not_found("Map"))?;
+    // Get the map and check edit access
+    let map =
+        dguesser_db::locations::get_map_if_visible(state.db(), &id, Some(&auth.user_id), org.id())
+            .await?
+            .ok_or_else(|| ApiError::not_found("Map"))?;
 
-    if !map.is_owned_by(&auth.user_id) {
-        return Err(ApiError::forbidden("You can only remove locations from your own maps"));
+    if !can_edit_locations(&state, &map, &auth.user_id).await? {
+        return Err(ApiError::forbidden("You can only edit maps you own or collaborate on"));
     }
 
     // Remove the location
     let removed =
-        dguesser_db::locations::remove_location_from_map(state.db(), &id, &location_id).await?;
+        map_versions::remove_locations(state.db(), &map.id, &auth.user_id, &[location_id]).await?;
 
-    if !removed {
+    if removed == 0 {
         return Err(ApiError::not_found("Location in map"));
     }
 
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// List the internal notes on a location in a map (owner and editors).
+///
+/// Notes are shared between admins and the curators of every map the
+/// location is in.
+#[utoipa::path(
+    get,
+    path = "/api/v1/maps/{id}/locations/{location_id}/notes",
+    tag = "maps",
+    params(
+        ("id" = String, Path, description = "Map ID"),
+        ("location_id" = String, Path, description = "Location ID")
+    ),
+    responses(
+        (status = 200, description = "Notes, newest first", body = Vec<LocationNoteItem>),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Not the owner or an editor"),
+        (status = 404, description = "Map or location not found"),
+    )
+)]
+pub async fn list_location_notes(
+    State(state): State<AppState>,
+    org: CurrentOrg,
+    Path((id, location_id)): Path<(String, String)>,
+    auth: AuthUser,
+) -> Result<Json<Vec<LocationNoteItem>>, ApiError> {
+    curated_location(&state, &org, &id, &location_id, &auth.user_id).await?;
+
+    let notes = dguesser_db::location_notes::list_for_location(state.db(), &location_id).await?;
+    Ok(Json(notes.into_iter().map(note_item).collect()))
+}
+
+/// Add an internal note to a location in a map (owner and editors).
+#[utoipa::path(
+    post,
+    path = "/api/v1/maps/{id}/locations/{location_id}/notes",
+    tag = "maps",
+    params(
+        ("id" = String, Path, description = "Map ID"),
+        ("location_id" = String, Path, description = "Location ID")
+    ),
+    request_body = CreateLocationNoteRequest,
+    responses(
+        (status = 201, description = "Note added", body = LocationNoteItem),
+        (status = 400, description = "Empty or overlong note, or too many tags"),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Not the owner or an editor"),
+        (status = 404, description = "Map or location not found"),
+    )
+)]
+pub async fn create_location_note(
+    State(state): State<AppState>,
+    org: CurrentOrg,
+    Path((id, location_id)): Path<(String, String)>,
+    auth: AuthUser,
+    Json(req): Json<CreateLocationNoteRequest>,
+) -> Result<(StatusCode, Json<LocationNoteItem>), ApiError> {
+    let (body, tags) = validate_note(&req)?;
+    let map = curated_location(&state, &org, &id, &location_id, &auth.user_id).await?;
+
+    let note = dguesser_db::location_notes::create(
+        state.db(),
+        &location_id,
+        &auth.user_id,
+        Some(&map.id),
+        &body,
+        &tags,
+    )
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(note_item(note))))
+}
+
+/// Delete a note you wrote on a location in a map.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/maps/{id}/locations/{location_id}/notes/{note_id}",
+    tag = "maps",
+    params(
+        ("id" = String, Path, description = "Map ID"),
+        ("location_id" = String, Path, description = "Location ID"),
+        ("note_id" = i64, Path, description = "Note ID")
+    ),
+    responses(
+        (status = 204, description = "Note deleted"),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Not the note's author"),
+        (status = 404, description = "Map, location or note not found"),
+    )
+)]
+pub async fn delete_location_note(
+    State(state): State<AppState>,
+    org: CurrentOrg,
+    Path((id, location_id, note_id)): Path<(String, String, i64)>,
+    auth: AuthUser,
+) -> Result<StatusCode, ApiError> {
+    curated_location(&state, &org, &id, &location_id, &auth.user_id).await?;
+
+    let note = dguesser_db::location_notes::get(state.db(), note_id)
+        .await?
+        .filter(|n| n.location_id == location_id)
+        .ok_or_else(|| ApiError::not_found("Note"))?;
+    if note.author_id.as_deref() != Some(auth.user_id.as_str()) {
+        return Err(ApiError::forbidden("You can only delete your own notes"));
+    }
+    dguesser_db::location_notes::delete(state.db(), note_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List a map's collaborators and pending invitations.
+///
+/// Visible to the owner and to collaborators.
+#[utoipa::path(
+    get,
+    path = "/api/v1/maps/{id}/collaborators",
+    tag = "maps",
+    params(
+        ("id" = String, Path, description = "Map ID")
+    ),
+    responses(
+        (status = 200, description = "Map collaborators", body = MapCollaboratorsResponse),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Not the owner or a collaborator"),
+        (status = 404, description = "Map not found"),
+    )
+)]
+pub async fn list_collaborators(
+    State(state): State<AppState>,
+    org: CurrentOrg,
+    Path(id): Path<String>,
+    auth: AuthUser,
+) -> Result<Json<MapCollaboratorsResponse>, ApiError> {
+    let map =
+        dguesser_db::locations::get_map_if_visible(state.db(), &id, Some(&auth.user_id), org.id())
+            .await?
+            .ok_or_else(|| ApiError::not_found("Map"))?;
+
+    let collaborators = dguesser_db::locations::list_map_collaborators(state.db(), &map.id).await?;
+
+    if !map.is_owned_by(&auth.user_id) && !collaborators.iter().any(|c| c.user_id == auth.user_id) {
+        return Err(ApiError::forbidden("Only the owner and collaborators can see collaborators"));
+    }
+
+    Ok(Json(MapCollaboratorsResponse {
+        collaborators: collaborators.into_iter().map(Into::into).collect(),
+    }))
+}
+
+/// Invite a user to edit a map.
+///
+/// The invitee can add and remove locations once they accept.
+#[utoipa::path(
+    post,
+    path = "/api/v1/maps/{id}/collaborators",
+    tag = "maps",
+    params(
+        ("id" = String, Path, description = "Map ID")
+    ),
+    request_body = InviteCollaboratorRequest,
+    responses(
+        (status = 201, description = "Invitation sent", body = MapCollaboratorItem),
+        (status = 400, description = "Cannot invite yourself"),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Not the owner"),
+        (status = 404, description = "Map or user not found"),
+        (status = 409, description = "Already invited or collaborator limit reached"),
+    )
+)]
+pub async fn invite_collaborator(
+    State(state): State<AppState>,
+    org: CurrentOrg,
+    Path(id): Path<String>,
+    auth: AuthUser,
+    Json(body): Json<InviteCollaboratorRequest>,
+) -> Result<(StatusCode, Json<MapCollaboratorItem>), ApiError> {
+    let map =
+        dguesser_db::locations::get_map_if_visible(state.db(), &id, Some(&auth.user_id), org.id())
+            .await?
+            .ok_or_else(|| ApiError::not_found("Map"))?;
+
+    if !map.is_owned_by(&auth.user_id) {
+        return Err(ApiError::forbidden("Only the map owner can invite collaborators"));
+    }
+
+    let username = body.username.trim().to_lowercase();
+    let invitee = dguesser_db::users::get_by_username(state.db(), &username)
+        .await?
+        .ok_or_else(|| ApiError::not_found("User"))?;
+
+    if invitee.id == auth.user_id {
+        return Err(ApiError::bad_request("CANNOT_INVITE_SELF", "You already own this map"));
+    }
+
+    let count = dguesser_db::locations::count_map_collaborators(state.db(), &map.id).await?;
+    if count >= MAX_COLLABORATORS_PER_MAP {
+        return Err(ApiError::conflict(
+            "COLLABORATOR_LIMIT_REACHED",
+            format!("A map can have at most {} collaborators", MAX_COLLABORATORS_PER_MAP),
+        ));
+    }
+
+    let invited = dguesser_db::locations::invite_map_collaborator(
+        state.db(),
+        &map.id,
+        &invitee.id,
+        &auth.user_id,
+    )
+    .await?;
+    if !invited {
+        return Err(ApiError::conflict(
+            "ALREADY_INVITED",
+            "This user is already invited to edit the map",
+        ));
+    }
+
+    let collaborator =
+        dguesser_db::locations::get_map_collaborator(state.db(), &map.id, &invitee.id)
+            .await?
+            .ok_or_else(|| ApiError::not_found("Collaborator"))?;
+
+    Ok((StatusCode::CREATED, Json(collaborator.into())))
+}
+
+/// Remove a collaborator from a map.
+///
+/// The owner can remove anyone; collaborators can remove themselves to leave
+/// the map.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/maps/{id}/collaborators/{user_id}",
+    tag = "maps",
+    params(
+        ("id" = String, Path, description = "Map ID"),
+        ("user_id" = String, Path, description = "User ID of the collaborator")
+    ),
+    responses(
+        (status = 204, description = "Collaborator removed"),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Not the owner"),
+        (status = 404, description = "Map or collaborator not found"),
+    )
+)]
+pub async fn remove_collaborator(
+    State(state): State<AppState>,
+    org: CurrentOrg,
+    Path((id, user_id)): Path<(String, String)>,
+    auth: AuthUser,
+) -> Result<StatusCode, ApiError> {
+    let map =
+        dguesser_db::locations::get_map_if_visible(state.db(), &id, Some(&auth.user_id), org.id())
+            .await?
+            .ok_or_else(|| ApiError::not_found("Map"))?;
+
+    if !map.is_owned_by(&auth.user_id) && user_id != auth.user_id {
+        return Err(ApiError::forbidden("Only the map owner can remove other collaborators"));
+    }
+
+    let removed =
+        dguesser_db::locations::remove_map_collaborator(state.db(), &map.id, &user_id).await?;
+    if !removed {
+        return Err(ApiError::not_found("Collaborator"));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List the current user's pending map invitations.
+#[utoipa::path(
+    get,
+    path = "/api/v1/maps/invitations",
+    tag = "maps",
+    responses(
+        (status = 200, description = "Pending invitations", body = MapInvitationsResponse),
+        (status = 401, description = "Not authenticated"),
+    )
+)]
+pub async fn list_invitations(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<Json<MapInvitationsResponse>, ApiError> {
+    let invitations =
+        dguesser_db::locations::list_map_invitations(state.db(), &auth.user_id).await?;
+
+    Ok(Json(MapInvitationsResponse {
+        invitations: invitations
+            .into_iter()
+            .map(|i| MapInvitationItem {
+                map_id: i.map_id,
+                map_slug: i.map_slug,
+                map_name: i.map_name,
+                invited_by: i.invited_by,
+                inviter_name: i.inviter_name,
+                invited_at: i.created_at,
+            })
+            .collect(),
+    }))
+}
+
+/// Accept an invitation to edit a map.
+#[utoipa::path(
+    post,
+    path = "/api/v1/maps/{id}/invitation",
+    tag = "maps",
+    params(
+        ("id" = String, Path, description = "Map ID")
+    ),
+    responses(
+        (status = 200, description = "Invitation accepted", body = MapCollaboratorItem),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "No pending invitation"),
+    )
+)]
+pub async fn accept_invitation(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    auth: AuthUser,
+) -> Result<Json<MapCollaboratorItem>, ApiError> {
+    let accepted =
+        dguesser_db::locations::accept_map_invitation(state.db(), &id, &auth.user_id).await?;
+    if !accepted {
+        return Err(ApiError::not_found("Invitation"));
+    }
+
+    let collaborator = dguesser_db::locations::get_map_collaborator(state.db(), &id, &auth.user_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Invitation"))?;
+
+    Ok(Json(collaborator.into()))
+}
+
+/// Decline an invitation to edit a map.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/maps/{id}/invitation",
+    tag = "maps",
+    params(
+        ("id" = String, Path, description = "Map ID")
+    ),
+    responses(
+        (status = 204, description = "Invitation declined"),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "No pending invitation"),
+    )
+)]
+pub async fn decline_invitation(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    auth: AuthUser,
+) -> Result<StatusCode, ApiError> {
+    let pending = dguesser_db::locations::get_map_collaborator(state.db(), &id, &auth.user_id)
+        .await?
+        .is_some_and(|c| !c.is_accepted());
+    if !pending {
+        return Err(ApiError::not_found("Invitation"));
+    }
+
+    dguesser_db::locations::remove_map_collaborator(state.db(), &id, &auth.user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List a map's version history.
+///
+/// Visible to the owner and editors.
+#[utoipa::path(
+    get,
+    path = "/api/v1/maps/{id}/versions",
+    tag = "maps",
+    params(
+        ("id" = String, Path, description = "Map ID"),
+        ("page" = Option<i64>, Query, description = "Page number (default 1)"),
+        ("per_page" = Option<i64>, Query, description = "Items per page (default 50, max 100)"),
+    ),
+    responses(
+        (status = 200, description = "Map versions", body = MapVersionsResponse),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Not the owner or an editor"),
+        (status = 404, description = "Map not found"),
+    )
+)]
+pub async fn list_versions(
+    State(state): State<AppState>,
+    org: CurrentOrg,
+    Path(id): Path<String>,
+    Query(query): Query<ListVersionsQuery>,
+    auth: AuthUser,
+) -> Result<Json<MapVersionsResponse>, ApiError> {
+    let map =
+        dguesser_db::locations::get_map_if_visible(state.db(), &id, Some(&auth.user_id), org.id())
+            .await?
+            .ok_or_else(|| ApiError::not_found("Map"))?;
+
+    if !can_edit_locations(&state, &map, &auth.user_id).await? {
+        return Err(ApiError::forbidden("Only the owner and editors can see map history"));
+    }
+
+    let page = query.page.max(1);
+    let per_page = query.per_page.clamp(1, MAX_VERSIONS_PER_PAGE);
+    let offset = (page - 1) * per_page;
+
+    let versions = map_versions::list_versions(state.db(), &map.id, per_page, offset).await?;
+    let total = map_versions::count_versions(state.db(), &map.id).await?;
+
+    let versions = versions
+        .into_iter()
+        .map(|v| MapVersionItem {
+            version: v.version,
+            kind: v.kind.to_string(),
+            user_id: v.user_id,
+            added_count: v.added_count,
+            removed_count: v.removed_count,
+            rules: v.rules,
+            restored_from: v.restored_from,
+            created_at: v.created_at,
+        })
+        .collect();
+
+    Ok(Json(MapVersionsResponse { versions, total, page, per_page }))
+}
+
+/// Get one map version with the location IDs it added and removed.
+#[utoipa::path(
+    get,
+    path = "/api/v1/maps/{id}/versions/{version}",
+    tag = "maps",
+    params(
+        ("id" = String, Path, description = "Map ID"),
+        ("version" = i32, Path, description = "Version number")
+    ),
+    responses(
+        (status = 200, description = "Map version", body = MapVersionDetails),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Not the owner or an editor"),
+        (status = 404, description = "Map or version not found"),
+    )
+)]
+pub async fn get_version(
+    State(state): State<AppState>,
+    org: CurrentOrg,
+    Path((id, version)): Path<(String, i32)>,
+    auth: AuthUser,
+) -> Result<Json<MapVersionDetails>, ApiError> {
+    let map =
+        dguesser_db::locations::get_map_if_visible(state.db(), &id, Some(&auth.user_id), org.id())
+            .await?
+            .ok_or_else(|| ApiError::not_found("Map"))?;
+
+    if !can_edit_locations(&state, &map, &auth.user_id).await? {
+        return Err(ApiError::forbidden("Only the owner and editors can see map history"));
+    }
+
+    let version = map_versions::get_version(state.db(), &map.id, version)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Version"))?;
+
+    Ok(Json(version.into()))
+}
+
+/// Restore a map to an earlier version.
+///
+/// Undoes every later change to the map's locations and rules, and records
+/// the restore as a new version so it can be undone in turn.
+#[utoipa::path(
+    post,
+    path = "/api/v1/maps/{id}/versions/{version}/restore",
+    tag = "maps",
+    params(
+        ("id" = String, Path, description = "Map ID"),
+        ("version" = i32, Path, description = "Version number to restore")
+    ),
+    responses(
+        (status = 200, description = "Map restored; returns the new version", body = MapVersionDetails),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Not the owner"),
+        (status = 404, description = "Map or version not found"),
+    )
+)]
+pub async fn restore_version(
+    State(state): State<AppState>,
+    org: CurrentOrg,
+    Path((id, version)): Path<(String, i32)>,
+    auth: AuthUser,
+) -> Result<Json<MapVersionDetails>, ApiError> {
+    let map =
+        dguesser_db::locations::get_map_if_visible(state.db(), &id, Some(&auth.user_id), org.id())
+            .await?
+            .ok_or_else(|| ApiError::not_found("Map"))?;
+
+    // Restoring can change rules, which only the owner may edit
+    if !map.is_owned_by(&auth.user_id) {
+        return Err(ApiError::forbidden("Only the map owner can restore versions"));
+    }
+
+    let restored = map_versions::restore_version(state.db(), &map.id, version, &auth.user_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Version"))?;
+
+    Ok(Json(restored.into()))
+}
+
 // =============================================================================
 // Helper Functions
 // =============================================================================
 
+/// Check whether a user can add and remove a map's locations: the owner and
+/// accepted collaborators can, but only the owner can change settings or
+/// delete the map.
+async fn can_edit_locations(state: &AppState, map: &Map, user_id: &str) -> Result<bool, ApiError> {
+    if map.is_owned_by(user_id) {
+        return Ok(true);
+    }
+    if map.is_system_map() {
+        return Ok(false);
+    }
+    Ok(dguesser_db::locations::is_map_editor(state.db(), &map.id, user_id).await?)
+}
+
+/// Load a map the user can edit and check the location is in it.
+async fn curated_location(
+    state: &AppState,
+    org: &CurrentOrg,
+    map_id: &str,
+    location_id: &str,
+    user_id: &str,
+) -> Result<Map, ApiError> {
+    let map =
+        dguesser_db::locations::get_map_if_visible(state.db(), map_id, Some(user_id), org.id())
+            .await?
+            .ok_or_else(|| ApiError::not_found("Map"))?;
+    if !can_edit_locations(state, &map, user_id).await? {
+        return Err(ApiError::forbidden("Only the map's owner and editors can see location notes"));
+    }
+    if !dguesser_db::locations::is_location_in_map(state.db(), &map.id, location_id).await? {
+        return Err(ApiError::not_found("Location in map"));
+    }
+    Ok(map)
+}
+
+/// Check a map's custom scoring curve produces sensible scores.
+fn validate_scoring(scoring: &ScoringConfig) -> Result<(), ApiError> {
+    scoring.validate().map_err(|e| ApiError::bad_request("INVALID_SCORING", e.to_string()))
+}
+
+/// Check an uploaded map region is well formed and within size limits.
+fn validate_region(region: &MapRegion) -> Result<(), ApiError> {
+    region.validate().map_err(|e| ApiError::bad_request("INVALID_REGION", e.to_string()))
+}
+
+/// Deserialize a present field as `Some`, so an explicit `null` becomes
+/// `Some(None)` while a missing field stays `None`.
+fn deserialize_some<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
 /// Generate a URL-friendly slug from a name.
 fn generate_slug(name: &str) -> String {
     name.to_lowercase()
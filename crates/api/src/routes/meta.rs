@@ -0,0 +1,80 @@
+//! Reference data endpoints
+
+use axum::{Json, Router, routing::get};
+use dguesser_core::geo::countries::Continent;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::i18n::Locale;
+use crate::state::AppState;
+
+/// A country with its localized name.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CountryName {
+    /// ISO 3166-1 alpha-2 code
+    #[schema(example = "DE")]
+    pub code: &'static str,
+    /// Display name in the response language
+    #[schema(example = "Deutschland")]
+    pub name: &'static str,
+    /// Continent the country is grouped under
+    #[schema(value_type = String, example = "europe")]
+    pub continent: Continent,
+    /// Flag emoji
+    #[schema(example = "🇩🇪")]
+    pub flag: String,
+    /// International calling code
+    #[schema(example = "+49")]
+    pub calling_code: &'static str,
+    /// Bounding box as `[min_lat, min_lng, max_lat, max_lng]`; `min_lng` is
+    /// greater than `max_lng` for countries spanning the antimeridian
+    pub bounds: [f64; 4],
+}
+
+/// Country names response.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CountryNamesResponse {
+    /// Language the names are in
+    pub lang: Locale,
+    /// Every known country, sorted by name
+    pub countries: Vec<CountryName>,
+}
+
+/// Create the meta router.
+pub fn router() -> Router<AppState> {
+    Router::new().route("/countries", get(get_countries))
+}
+
+/// List country names in the requested language.
+///
+/// The language comes from the `lang` parameter, then `Accept-Language`,
+/// and defaults to English.
+#[utoipa::path(
+    get,
+    path = "/api/v1/meta/countries",
+    tag = "meta",
+    params(
+        ("lang" = Option<Locale>, Query, description = "Response language; overrides Accept-Language")
+    ),
+    responses(
+        (status = 200, description = "Localized country names", body = CountryNamesResponse),
+    )
+)]
+pub async fn get_countries(locale: Locale) -> Json<CountryNamesResponse> {
+    let countries = locale
+        .countries()
+        .into_iter()
+        .map(|(country, name)| {
+            let b = country.bounds;
+            CountryName {
+                code: country.code,
+                name,
+                continent: country.continent,
+                flag: country.flag(),
+                calling_code: country.calling_code,
+                bounds: [b.min_lat, b.min_lng, b.max_lat, b.max_lng],
+            }
+        })
+        .collect();
+    Json(CountryNamesResponse { lang: locale, countries })
+}
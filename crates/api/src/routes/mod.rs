@@ -5,19 +5,40 @@ use tower_http::{cors::CorsLayer, trace::TraceLayer};
 use utoipa::OpenApi;
 use utoipa_scalar::{Scalar, Servable};
 
-use crate::middleware::{rate_limit, rate_limit_auth, rate_limit_game, security_headers};
+use crate::cache::response_cache;
+use crate::middleware::trace_id::TraceId;
+use crate::middleware::{
+    captcha_guard, locale, rate_limit, rate_limit_auth, rate_limit_game, rate_limit_map_tiles,
+    rate_limit_streetview, rate_limit_telemetry, resolve_org, security_headers, trace_id,
+};
 use crate::state::AppState;
+use dguesser_auth::{impersonation_guard, session_renewal};
 
 pub mod admin;
+pub mod asyncapi;
 pub mod auth;
+pub mod challenges;
+pub mod country_quiz;
+pub mod friends;
+pub mod game_invites;
+pub mod game_shares;
+pub mod game_summary;
 pub mod games;
 pub mod health;
 pub mod leaderboard;
 pub mod locations;
 pub mod maps;
+pub mod meta;
+pub mod notifications;
+pub mod orgs;
 pub mod parties;
+pub mod practice;
+pub mod reports;
 pub mod service;
 pub mod sessions;
+pub mod settings_presets;
+pub mod streetview;
+pub mod telemetry;
 pub mod users;
 
 /// OpenAPI documentation
@@ -31,56 +52,213 @@ pub mod users;
         auth::create_guest,
         auth::get_current_user,
         auth::logout,
+        auth::end_impersonation,
         auth::google_redirect,
         auth::microsoft_redirect,
+        auth::start_link,
+        auth::register,
+        auth::login,
+        auth::verify_email,
+        auth::resend_verification,
+        auth::request_password_reset,
+        auth::confirm_password_reset,
+        auth::create_socket_token,
+        auth::get_captcha_settings,
         games::create_game,
         games::get_game,
         games::get_game_results,
+        game_summary::get_game_summary,
+        games::rehydrate_game,
+        games::export_game_results,
+        games::get_game_card,
+        games::get_round_card,
         games::start_game,
         games::get_current_round,
         games::timeout_round,
         games::submit_guess,
+        games::request_hint,
         games::get_game_history,
+        games::rotate_join_code,
+        game_invites::create_invite,
+        game_invites::list_invites,
+        game_invites::revoke_invite,
+        game_invites::redeem_invite,
+        game_shares::create_share,
+        game_shares::list_shares,
+        game_shares::revoke_share,
+        game_shares::get_shared_replay,
+        country_quiz::get_quiz,
+        country_quiz::ask_question,
+        country_quiz::answer_question,
+        practice::create_round,
+        practice::guess_round,
         users::get_profile,
         users::update_profile,
+        users::upload_avatar,
+        users::delete_avatar,
         users::get_user_profile,
+        users::get_user_presence,
         users::get_user_by_username,
+        users::get_public_profile,
+        users::get_profile_card,
         users::delete_account,
+        users::list_linked_providers,
+        users::unlink_provider,
+        challenges::create_challenge,
+        challenges::list_challenges,
+        challenges::get_challenge,
+        challenges::get_current_round,
+        challenges::next_round,
+        challenges::submit_guess,
+        friends::list_friends,
+        friends::list_requests,
+        friends::send_request,
+        friends::accept_request,
+        friends::decline_request,
+        friends::remove_friend,
+        notifications::get_public_key,
+        notifications::subscribe,
+        notifications::unsubscribe,
+        notifications::get_preferences,
+        notifications::update_preferences,
+        users::get_email_preferences,
+        users::update_email_preferences,
+        settings_presets::list_presets,
+        settings_presets::create_preset,
+        settings_presets::update_preset,
+        settings_presets::delete_preset,
         sessions::list_sessions,
         sessions::revoke_session,
         sessions::revoke_other_sessions,
+        sessions::list_devices,
+        sessions::set_device_trust,
+        sessions::forget_device,
         leaderboard::get_leaderboard,
         locations::report_location,
         locations::search_locations,
         locations::get_countries,
         locations::get_subdivisions,
+        telemetry::report_client_error,
+        streetview::get_metadata,
+        reports::report_player,
+        streetview::create_tile_session,
+        streetview::get_tile,
+        meta::get_countries,
         maps::list_maps,
+        maps::list_favorite_maps,
         maps::create_map,
+        maps::validate_rules,
+        maps::get_featured_maps,
         maps::get_map,
         maps::update_map,
         maps::delete_map,
         maps::get_map_locations,
+        maps::export_map,
+        maps::import_map,
+        maps::get_map_heatmap,
+        maps::like_map,
+        maps::unlike_map,
+        maps::list_collaborators,
+        maps::invite_collaborator,
+        maps::remove_collaborator,
+        maps::list_invitations,
+        maps::accept_invitation,
+        maps::decline_invitation,
+        maps::list_versions,
+        maps::get_version,
+        maps::restore_version,
         maps::add_locations,
         maps::add_locations_from_urls,
         maps::remove_location,
+        maps::list_location_notes,
+        maps::create_location_note,
+        maps::delete_location_note,
+        orgs::get_current_org,
+        orgs::update_current_org,
+        orgs::list_members,
+        orgs::add_member,
+        orgs::remove_member,
         admin::get_stats,
+        admin::get_location_health,
+        admin::get_pack_cache_stats,
+        admin::get_streetview_quota,
+        admin::get_map_tile_usage,
         admin::get_review_queue,
         admin::get_location_detail,
         admin::update_review_status,
+        admin::review_claims::claim_review_batch,
+        admin::review_claims::release_review_claims,
+        admin::review_claims::get_reviewer_stats,
+        admin::reapprovals::list_reapprovals,
+        admin::reapprovals::decide_reapproval,
         admin::get_reports,
+        admin::player_reports::list_player_reports,
+        admin::player_reports::update_player_report,
+        admin::location_notes::search_location_notes,
+        admin::location_notes::create_location_note,
+        admin::location_notes::delete_location_note,
+        admin::moderate_profile,
+        admin::chat::get_game_chat,
+        admin::live_games::list_live_games,
+        admin::live_games::get_live_game_state,
+        admin::live_games::force_end_game,
+        admin::names::force_rename,
+        admin::names::list_name_flags,
+        admin::names::dismiss_name_flag,
+        admin::maps::list_system_maps,
+        admin::maps::create_system_map,
+        admin::maps::update_system_map,
+        admin::maps::set_default_map,
+        admin::maps::set_map_active,
+        admin::maps::list_featured_maps,
+        admin::maps::schedule_featured_map,
+        admin::maps::cancel_featured_map,
+        admin::orgs::list_orgs,
+        admin::orgs::create_org,
+        admin::orgs::set_org_active,
+        admin::analytics::get_activity,
+        admin::analytics::get_guess_distribution,
+        admin::analytics::get_retention,
+        admin::analytics::get_continents,
+        admin::support::start_impersonation,
+        admin::support::get_audit_log,
+        admin::config::reload_config,
     ),
     components(schemas(
+        crate::error::ApiErrorResponse,
+        crate::error::FieldError,
         dguesser_protocol::api::auth::MeResponse,
         dguesser_protocol::api::auth::GuestSessionResponse,
         dguesser_protocol::api::auth::LogoutResponse,
+        dguesser_protocol::api::auth::OAuthUrlResponse,
+        auth::CreateGuestRequest,
+        auth::RegisterRequest,
+        auth::LoginRequest,
+        auth::VerifyEmailRequest,
+        auth::PasswordResetRequest,
+        auth::PasswordResetConfirmRequest,
+        auth::AuthMessageResponse,
+        auth::SocketTokenRequest,
+        auth::SocketTokenResponse,
+        auth::CaptchaSettingsResponse,
+        auth::CurrentUserResponse,
+        auth::ImpersonationInfo,
         dguesser_protocol::api::user::UserProfile,
         dguesser_protocol::api::user::UpdateProfileRequest,
         dguesser_protocol::api::game::CreateGameRequest,
         dguesser_protocol::api::game::GameInfo,
         dguesser_protocol::api::game::GameSettingsResponse,
         dguesser_protocol::api::game::GuessResult,
+        dguesser_protocol::api::game::PlayerGameStats,
+        dguesser_protocol::api::game::GameSummaryResponse,
+        dguesser_protocol::api::game::SummaryRound,
+        dguesser_protocol::api::game::SummaryGuess,
+        dguesser_protocol::api::game::SummaryHighlights,
+        dguesser_protocol::api::game::GuessHighlight,
+        dguesser_protocol::api::game::RoundHighlight,
         dguesser_protocol::api::leaderboard::LeaderboardType,
         dguesser_protocol::api::leaderboard::TimePeriod,
+        dguesser_protocol::api::leaderboard::LeaderboardMode,
         dguesser_protocol::api::leaderboard::LeaderboardEntry,
         leaderboard::LeaderboardResponse,
         games::CreateGameResponse,
@@ -91,18 +269,70 @@ pub mod users;
         games::UserGuessInfo,
         games::LocationInfo,
         games::GuessResultResponse,
+        dguesser_protocol::socket::payloads::GlobalGuessStats,
         games::RoundResultInfo,
         games::FinalStandingInfo,
         games::CompletedRoundInfo,
         games::GameResultsResponse,
         games::GameSummary,
         games::SubmitGuessRequest,
+        games::HintRequest,
+        games::HintResponse,
+        games::SettingsDto,
+        games::RotateJoinCodeRequest,
+        games::RotateJoinCodeResponse,
+        game_invites::CreateGameInviteRequest,
+        game_invites::GameInviteItem,
+        game_invites::CreateGameInviteResponse,
+        game_invites::GameInvitesResponse,
+        game_invites::RedeemGameInviteRequest,
+        game_shares::CreateGameShareRequest,
+        game_shares::GameShareItem,
+        game_shares::CreateGameShareResponse,
+        game_shares::GameSharesResponse,
+        game_shares::ReplayPlayer,
+        game_shares::ReplayGuess,
+        game_shares::ReplayRound,
+        game_shares::ReplayHighlight,
+        game_shares::ReplayHighlights,
+        game_shares::SharedReplayResponse,
+        practice::CreatePracticeRoundRequest,
+        practice::PracticeRoundResponse,
+        practice::PracticeGuessRequest,
+        practice::PracticeGuessResponse,
+        country_quiz::QuizAnswerRequest,
+        country_quiz::QuizAnswerResponse,
+        country_quiz::QuizStateResponse,
+        dguesser_protocol::socket::payloads::QuizQuestionPayload,
+        dguesser_protocol::socket::payloads::QuizChoice,
+        dguesser_protocol::socket::payloads::QuizBounds,
+        dguesser_protocol::socket::payloads::QuizScore,
+        challenges::CreateChallengeRequest,
+        challenges::CreateChallengeResponse,
+        challenges::ChallengeListItem,
+        challenges::ChallengeListResponse,
+        challenges::ChallengePlayerInfo,
+        challenges::ChallengeDetails,
         users::UserProfileResponse,
+        users::UserPresenceResponse,
+        users::PublicProfileResponse,
+        users::PublicProfileStats,
         users::UpdateProfileRequest,
         users::DeleteAccountResponse,
+        users::LinkedProviderInfo,
+        users::LinkedProvidersResponse,
+        users::UnlinkProviderResponse,
+        users::EmailPreferences,
+        settings_presets::CreatePresetRequest,
+        settings_presets::UpdatePresetRequest,
+        settings_presets::SettingsPresetInfo,
+        settings_presets::SettingsPresetsResponse,
         sessions::SessionInfo,
         sessions::SessionsListResponse,
         sessions::RevokeSessionResponse,
+        sessions::DeviceInfo,
+        sessions::DevicesListResponse,
+        sessions::SetDeviceTrustRequest,
         locations::ReportLocationRequest,
         locations::ReportLocationResponse,
         locations::SearchLocationsQuery,
@@ -112,24 +342,60 @@ pub mod users;
         locations::CountriesResponse,
         locations::SubdivisionInfo,
         locations::SubdivisionsResponse,
+        telemetry::ClientErrorReport,
+        meta::CountryName,
+        meta::CountryNamesResponse,
+        crate::i18n::Locale,
         maps::MapSummary,
         maps::ListMapsResponse,
         maps::CreateMapRequest,
         maps::CreateMapResponse,
+        maps::ValidateRulesRequest,
+        maps::RulesCountryCount,
+        maps::ValidateRulesResponse,
+        maps::FeaturedMapItem,
+        maps::FeaturedMapsResponse,
         maps::MapDetails,
+        maps::MapLikeResponse,
+        maps::InviteCollaboratorRequest,
+        maps::MapCollaboratorItem,
+        maps::MapCollaboratorsResponse,
+        maps::MapInvitationItem,
+        maps::MapInvitationsResponse,
+        maps::MapVersionItem,
+        maps::MapVersionsResponse,
+        maps::MapVersionDetails,
         maps::UpdateMapRequest,
         maps::MapLocationItem,
         maps::MapLocationsResponse,
+        maps::ImportRowResult,
+        maps::ImportMapResponse,
+        maps::HeatmapBin,
+        maps::MapHeatmapResponse,
         maps::AddLocationsRequest,
         maps::AddLocationsResponse,
         maps::AddLocationsFromUrlsRequest,
         maps::UrlParseResult,
+        maps::UrlImportStatus,
         maps::AddLocationsFromUrlsResponse,
         health::HealthResponse,
         health::HealthChecks,
         health::CheckResult,
         dguesser_protocol::api::service::ServiceInfo,
+        dguesser_protocol::version::ProtocolInfo,
         dguesser_protocol::api::admin::AdminStatsResponse,
+        dguesser_protocol::api::admin::LocationHealthResponse,
+        dguesser_protocol::api::admin::HealthCheckRunItem,
+        dguesser_protocol::api::admin::HealthTrendPoint,
+        dguesser_protocol::api::admin::PackCacheStatsResponse,
+        dguesser_protocol::api::admin::StreetViewQuotaResponse,
+        dguesser_protocol::api::streetview::StreetViewMetadataResponse,
+        dguesser_protocol::api::reports::CreatePlayerReportRequest,
+        dguesser_protocol::api::reports::PlayerReportResponse,
+        dguesser_protocol::api::streetview::CreateTileSessionRequest,
+        dguesser_protocol::api::streetview::TileSessionResponse,
+        dguesser_protocol::api::admin::MapTileUsageResponse,
+        dguesser_protocol::api::admin::MapTileUsageEntry,
         dguesser_protocol::api::admin::ReviewQueueItem,
         dguesser_protocol::api::admin::ReviewQueueResponse,
         dguesser_protocol::api::admin::LocationDetailResponse,
@@ -137,7 +403,95 @@ pub mod users;
         dguesser_protocol::api::admin::ReportsListResponse,
         dguesser_protocol::api::admin::LocationReportWithLocation,
         dguesser_protocol::api::admin::UpdateReviewStatusRequest,
+        dguesser_protocol::api::admin::ReapprovalQueueParams,
+        dguesser_protocol::api::admin::ReapprovalItem,
+        dguesser_protocol::api::admin::ReapprovalQueueResponse,
+        dguesser_protocol::api::admin::DecideReapprovalRequest,
+        dguesser_protocol::api::admin::ClaimReviewBatchRequest,
+        dguesser_protocol::api::admin::ClaimReviewBatchResponse,
+        dguesser_protocol::api::admin::ReleaseReviewClaimsResponse,
+        dguesser_protocol::api::admin::ReviewerStatsParams,
+        dguesser_protocol::api::admin::ReviewerStatsItem,
+        dguesser_protocol::api::admin::ReviewerStatsResponse,
+        dguesser_protocol::api::admin::ModerateProfileRequest,
+        dguesser_protocol::api::admin::ModerateProfileResponse,
+        dguesser_protocol::api::admin::PlayerReportItem,
+        dguesser_protocol::api::admin::LocationNoteItem,
+        dguesser_protocol::api::admin::CreateLocationNoteRequest,
+        dguesser_protocol::api::admin::LocationNotesParams,
+        dguesser_protocol::api::admin::LocationNotesResponse,
+        dguesser_protocol::api::admin::PlayerReportsResponse,
+        dguesser_protocol::api::admin::ReportedPlayerSignals,
+        dguesser_protocol::api::admin::UpdatePlayerReportRequest,
+        dguesser_protocol::api::admin::UpdatePlayerReportResponse,
+        dguesser_protocol::api::admin::ChatTranscriptMessage,
+        dguesser_protocol::api::admin::GameChatTranscriptResponse,
+        dguesser_protocol::api::admin::LiveGameItem,
+        dguesser_protocol::api::admin::LiveGamesResponse,
+        dguesser_protocol::api::admin::LivePlayerState,
+        dguesser_protocol::api::admin::LiveRoundState,
+        dguesser_protocol::api::admin::LiveGameStateResponse,
+        dguesser_protocol::api::admin::ForceEndGameRequest,
+        dguesser_protocol::api::admin::ForceEndGameResponse,
+        dguesser_protocol::api::admin::ForceRenameRequest,
+        dguesser_protocol::api::admin::ForceRenameResponse,
+        dguesser_protocol::api::admin::NameFlagItem,
+        dguesser_protocol::api::admin::NameFlagsResponse,
         dguesser_protocol::api::admin::UpdateReviewStatusResponse,
+        dguesser_protocol::api::admin::SystemMapItem,
+        dguesser_protocol::api::admin::SystemMapsListResponse,
+        dguesser_protocol::api::admin::CreateSystemMapRequest,
+        dguesser_protocol::api::admin::UpdateSystemMapRequest,
+        dguesser_protocol::api::admin::SetMapActiveRequest,
+        dguesser_protocol::api::admin::ScheduleFeaturedMapRequest,
+        dguesser_protocol::api::admin::ScheduledFeatureItem,
+        dguesser_protocol::api::admin::FeaturedMapScheduleResponse,
+        dguesser_protocol::api::admin::ModeGameStats,
+        dguesser_protocol::api::admin::DailyActivityPoint,
+        dguesser_protocol::api::admin::ActivityAnalyticsResponse,
+        dguesser_protocol::api::admin::Percentiles,
+        dguesser_protocol::api::admin::GuessDistributionPoint,
+        dguesser_protocol::api::admin::GuessAnalyticsResponse,
+        dguesser_protocol::api::admin::RetentionCohort,
+        dguesser_protocol::api::admin::CountryGuessStats,
+        dguesser_protocol::api::admin::ContinentGuessStats,
+        dguesser_protocol::api::admin::ContinentAnalyticsResponse,
+        dguesser_protocol::api::admin::RetentionAnalyticsResponse,
+        dguesser_protocol::api::admin::StartImpersonationRequest,
+        dguesser_protocol::api::admin::ImpersonationResponse,
+        dguesser_protocol::api::admin::AuditLogItem,
+        dguesser_protocol::api::admin::AuditLogResponse,
+        dguesser_protocol::api::admin::RuntimeSettings,
+        dguesser_protocol::api::admin::CacheTtl,
+        dguesser_protocol::api::admin::StoredRuntimeSettings,
+        dguesser_protocol::api::admin::ReloadConfigRequest,
+        dguesser_protocol::api::admin::ReloadConfigResponse,
+        dguesser_protocol::api::orgs::OrgBranding,
+        dguesser_protocol::api::orgs::OrganizationInfo,
+        dguesser_protocol::api::orgs::UpdateOrganizationRequest,
+        dguesser_protocol::api::orgs::OrgMemberRole,
+        dguesser_protocol::api::orgs::OrgMemberItem,
+        dguesser_protocol::api::orgs::OrgMembersResponse,
+        dguesser_protocol::api::orgs::AddOrgMemberRequest,
+        dguesser_protocol::api::orgs::CreateOrganizationRequest,
+        dguesser_protocol::api::orgs::OrganizationsListResponse,
+        dguesser_protocol::api::orgs::SetOrganizationActiveRequest,
+        dguesser_protocol::api::friends::SendFriendRequest,
+        dguesser_protocol::api::friends::FriendshipStatus,
+        dguesser_protocol::api::friends::SendFriendRequestResponse,
+        dguesser_protocol::api::friends::FriendItem,
+        dguesser_protocol::socket::presence::PresenceInfo,
+        dguesser_protocol::socket::presence::PresenceStatus,
+        dguesser_protocol::socket::presence::PresenceVisibility,
+        dguesser_protocol::api::friends::FriendsListResponse,
+        dguesser_protocol::api::friends::FriendRequestItem,
+        dguesser_protocol::api::friends::FriendRequestsResponse,
+        dguesser_protocol::api::notifications::PushPublicKeyResponse,
+        dguesser_protocol::api::notifications::PushSubscriptionKeys,
+        dguesser_protocol::api::notifications::RegisterPushSubscriptionRequest,
+        dguesser_protocol::api::notifications::RemovePushSubscriptionRequest,
+        dguesser_protocol::api::notifications::NotificationPreferencesResponse,
+        dguesser_protocol::api::notifications::UpdateNotificationPreferencesRequest,
     )),
     tags(
         (name = "service", description = "Service information endpoints"),
@@ -145,10 +499,19 @@ pub mod users;
         (name = "auth", description = "Authentication endpoints"),
         (name = "games", description = "Game management endpoints"),
         (name = "users", description = "User profile endpoints"),
+        (name = "challenges", description = "Asynchronous challenges between friends"),
+        (name = "practice", description = "Unscored practice rounds kept outside of games"),
+        (name = "friends", description = "Friend list and request endpoints"),
+        (name = "notifications", description = "Push notification endpoints"),
         (name = "sessions", description = "Session management endpoints"),
         (name = "leaderboard", description = "Global leaderboard endpoints"),
         (name = "locations", description = "Location management endpoints"),
         (name = "maps", description = "Map builder endpoints"),
+        (name = "orgs", description = "Organization branding and members"),
+        (name = "meta", description = "Localized reference data"),
+        (name = "telemetry", description = "Client error reporting"),
+        (name = "streetview", description = "Street View metadata lookups"),
+        (name = "reports", description = "Reporting players"),
         (name = "admin", description = "Admin dashboard endpoints"),
     ),
     info(
@@ -166,13 +529,41 @@ pub struct ApiDoc;
 /// * `cors` - CORS layer configuration
 /// * `is_production` - If true, API docs are disabled for security
 pub fn create_router(state: AppState, cors: CorsLayer, is_production: bool) -> Router {
+    // Guest-creating endpoints ask anonymous callers for a CAPTCHA when
+    // their network looks abusive, inside rate limiting
+    let captcha = middleware::from_fn_with_state(state.clone(), captcha_guard);
+
     // Auth routes with stricter rate limiting (20/min)
-    let auth_routes =
-        auth::router().layer(middleware::from_fn_with_state(state.clone(), rate_limit_auth));
+    let auth_routes = auth::router()
+        .layer(captcha.clone())
+        .layer(middleware::from_fn_with_state(state.clone(), rate_limit_auth));
+
+    // Read-heavy endpoints are served from the response cache, inside rate
+    // limiting so cached responses still count
+    let cache = middleware::from_fn_with_state(state.clone(), response_cache);
 
     // Game routes with game-specific rate limiting (60/min)
-    let game_routes =
-        games::router().layer(middleware::from_fn_with_state(state.clone(), rate_limit_game));
+    let game_routes = games::router()
+        .layer(cache.clone())
+        .layer(captcha)
+        .layer(middleware::from_fn_with_state(state.clone(), rate_limit_game));
+
+    // Practice rounds share the game rate limit
+    let practice_routes =
+        practice::router().layer(middleware::from_fn_with_state(state.clone(), rate_limit_game));
+
+    // Client error reports with their own rate limit (30/min)
+    let telemetry_routes = telemetry::router()
+        .layer(middleware::from_fn_with_state(state.clone(), rate_limit_telemetry));
+
+    // Street View metadata lookups and tile sessions spend shared quota
+    // (30/min per user); tiles are loaded dozens at a time (600/min)
+    let streetview_routes = streetview::router()
+        .layer(middleware::from_fn_with_state(state.clone(), rate_limit_streetview))
+        .merge(
+            streetview::tile_router()
+                .layer(middleware::from_fn_with_state(state.clone(), rate_limit_map_tiles)),
+        );
 
     // Other API routes with default rate limiting (100/min)
     let other_routes = Router::new()
@@ -181,13 +572,31 @@ pub fn create_router(state: AppState, cors: CorsLayer, is_production: bool) -> R
         .nest("/leaderboard", leaderboard::router())
         .nest("/locations", locations::router())
         .nest("/maps", maps::router())
+        .nest("/orgs", orgs::router())
+        .nest("/meta", meta::router())
         .nest("/parties", parties::router())
+        .nest("/challenges", challenges::router())
+        .nest("/friends", friends::router())
+        .nest("/notifications", notifications::router())
+        .nest("/reports", reports::router())
+        .nest("/replays", game_shares::replay_router())
         .nest("/admin", admin::router())
+        .layer(cache)
         .layer(middleware::from_fn_with_state(state.clone(), rate_limit));
 
-    // Combine all API routes
-    let api_routes =
-        Router::new().nest("/auth", auth_routes).nest("/games", game_routes).merge(other_routes);
+    // Combine all API routes; sessions past half their lifetime are renewed,
+    // and impersonation sessions are limited once the session is resolved.
+    // The organization is resolved first so every handler sees it
+    let api_routes = Router::new()
+        .nest("/auth", auth_routes)
+        .nest("/games", game_routes)
+        .nest("/practice", practice_routes)
+        .nest("/telemetry", telemetry_routes)
+        .nest("/streetview", streetview_routes)
+        .merge(other_routes)
+        .layer(middleware::from_fn(impersonation_guard))
+        .layer(middleware::from_fn_with_state(state.clone(), session_renewal::<AppState>))
+        .layer(middleware::from_fn_with_state(state.clone(), resolve_org));
 
     // Create the main application router with state
     let app = Router::new()
@@ -201,16 +610,36 @@ pub fn create_router(state: AppState, cors: CorsLayer, is_production: bool) -> R
         .nest("/api/v1", api_routes)
         .with_state(state);
 
-    // Conditionally add Scalar docs (disabled in production for security)
+    // Conditionally add Scalar docs and the AsyncAPI document (disabled in
+    // production for security)
     let app = if is_production {
         tracing::info!("API docs disabled in production");
         app
     } else {
-        tracing::info!("Scalar API docs enabled at /docs");
+        tracing::info!("Scalar API docs enabled at /docs, socket events at /asyncapi.json");
         app.merge(Scalar::with_url("/docs", ApiDoc::openapi()))
+            .route("/asyncapi.json", axum::routing::get(asyncapi::asyncapi_json))
     };
 
     // Add global layers
-    // Note: Layers are applied in reverse order - first listed is outermost
-    app.layer(middleware::from_fn(security_headers)).layer(TraceLayer::new_for_http()).layer(cors)
+    // Note: Layers are applied in reverse order - last listed is outermost.
+    // The trace ID is assigned before the request span is created so the span
+    // (and every log line in it) carries it. The locale is negotiated outside
+    // that so error bodies rewritten by the trace ID middleware are translated.
+    app.layer(middleware::from_fn(security_headers))
+        .layer(TraceLayer::new_for_http().make_span_with(request_span))
+        .layer(middleware::from_fn(trace_id))
+        .layer(middleware::from_fn(locale))
+        .layer(cors)
+}
+
+/// Tracing span for a request, tagged with its trace ID
+fn request_span(request: &axum::http::Request<axum::body::Body>) -> tracing::Span {
+    let trace_id = request.extensions().get::<TraceId>().map(|id| id.0.as_str()).unwrap_or("-");
+    tracing::info_span!(
+        "request",
+        method = %request.method(),
+        uri = %request.uri(),
+        trace_id = %trace_id,
+    )
 }
@@ -0,0 +1,198 @@
+//! Push notification routes
+
+use axum::{
+    Json, Router,
+    extract::State,
+    http::{HeaderMap, StatusCode, header},
+    routing::{get, post},
+};
+use dguesser_auth::AuthUser;
+use dguesser_db::push::NotificationPreferences;
+use dguesser_protocol::api::notifications::{
+    NotificationPreferencesResponse, PushPublicKeyResponse, RegisterPushSubscriptionRequest,
+    RemovePushSubscriptionRequest, UpdateNotificationPreferencesRequest,
+};
+use dguesser_push::{client, crypto};
+
+use crate::error::ApiError;
+use crate::state::AppState;
+
+/// Subscriptions kept per user; registering more drops the oldest
+const MAX_SUBSCRIPTIONS_PER_USER: i64 = 10;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/push/public-key", get(get_public_key))
+        .route("/push/subscriptions", post(subscribe).delete(unsubscribe))
+        .route("/preferences", get(get_preferences).put(update_preferences))
+}
+
+/// Get the VAPID public key for subscribing to push notifications
+#[utoipa::path(
+    get,
+    path = "/api/v1/notifications/push/public-key",
+    responses(
+        (status = 200, description = "VAPID public key", body = PushPublicKeyResponse),
+        (status = 503, description = "Push notifications not configured"),
+    ),
+    tag = "notifications"
+)]
+pub async fn get_public_key(
+    State(state): State<AppState>,
+) -> Result<Json<PushPublicKeyResponse>, ApiError> {
+    let push = state
+        .push()
+        .ok_or_else(|| ApiError::service_unavailable("Push notifications are not configured"))?;
+
+    Ok(Json(PushPublicKeyResponse { public_key: push.public_key().to_string() }))
+}
+
+/// Register this browser's push subscription
+#[utoipa::path(
+    post,
+    path = "/api/v1/notifications/push/subscriptions",
+    request_body = RegisterPushSubscriptionRequest,
+    responses(
+        (status = 204, description = "Subscription registered"),
+        (status = 400, description = "Invalid subscription"),
+        (status = 401, description = "Not authenticated"),
+        (status = 503, description = "Push notifications not configured"),
+    ),
+    tag = "notifications"
+)]
+pub async fn subscribe(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Json(req): Json<RegisterPushSubscriptionRequest>,
+) -> Result<StatusCode, ApiError> {
+    if state.push().is_none() {
+        return Err(ApiError::service_unavailable("Push notifications are not configured"));
+    }
+
+    client::validate_endpoint(&req.endpoint)
+        .map_err(|e| ApiError::bad_request("INVALID_ENDPOINT", e.to_string()))?;
+    validate_keys(&req.keys.p256dh, &req.keys.auth)?;
+
+    let user_agent = headers
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(|ua| ua.chars().take(512).collect::<String>());
+
+    dguesser_db::push::upsert_subscription(
+        state.db(),
+        &auth.user_id,
+        &req.endpoint,
+        &req.keys.p256dh,
+        &req.keys.auth,
+        user_agent.as_deref(),
+    )
+    .await?;
+    dguesser_db::push::prune_subscriptions(state.db(), &auth.user_id, MAX_SUBSCRIPTIONS_PER_USER)
+        .await?;
+
+    tracing::info!(user_id = %auth.user_id, "Push subscription registered");
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Remove this browser's push subscription
+#[utoipa::path(
+    delete,
+    path = "/api/v1/notifications/push/subscriptions",
+    request_body = RemovePushSubscriptionRequest,
+    responses(
+        (status = 204, description = "Subscription removed"),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "Subscription not found"),
+    ),
+    tag = "notifications"
+)]
+pub async fn unsubscribe(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Json(req): Json<RemovePushSubscriptionRequest>,
+) -> Result<StatusCode, ApiError> {
+    if !dguesser_db::push::delete_subscription(state.db(), &auth.user_id, &req.endpoint).await? {
+        return Err(ApiError::not_found("Subscription"));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Get your notification preferences
+#[utoipa::path(
+    get,
+    path = "/api/v1/notifications/preferences",
+    responses(
+        (status = 200, description = "Notification preferences", body = NotificationPreferencesResponse),
+        (status = 401, description = "Not authenticated"),
+    ),
+    tag = "notifications"
+)]
+pub async fn get_preferences(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<Json<NotificationPreferencesResponse>, ApiError> {
+    let prefs = dguesser_db::push::get_preferences(state.db(), &auth.user_id).await?;
+    Ok(Json(preferences_response(prefs)))
+}
+
+/// Update your notification preferences
+#[utoipa::path(
+    put,
+    path = "/api/v1/notifications/preferences",
+    request_body = UpdateNotificationPreferencesRequest,
+    responses(
+        (status = 200, description = "Preferences updated", body = NotificationPreferencesResponse),
+        (status = 401, description = "Not authenticated"),
+    ),
+    tag = "notifications"
+)]
+pub async fn update_preferences(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Json(req): Json<UpdateNotificationPreferencesRequest>,
+) -> Result<Json<NotificationPreferencesResponse>, ApiError> {
+    let prefs = dguesser_db::push::update_preferences(
+        state.db(),
+        &auth.user_id,
+        req.challenge_turns,
+        req.friend_invites,
+        req.daily_challenge,
+    )
+    .await?;
+
+    Ok(Json(preferences_response(prefs)))
+}
+
+/// Check subscription keys decode to an uncompressed P-256 key and a 16-byte secret.
+fn validate_keys(p256dh: &str, auth: &str) -> Result<(), ApiError> {
+    let invalid =
+        |e: dguesser_push::PushError| ApiError::bad_request("INVALID_KEYS", e.to_string());
+    let p256dh = crypto::decode_key(p256dh).map_err(invalid)?;
+    let auth = crypto::decode_key(auth).map_err(invalid)?;
+    crypto::validate_subscription_keys(&p256dh, &auth).map_err(invalid)
+}
+
+fn preferences_response(prefs: NotificationPreferences) -> NotificationPreferencesResponse {
+    NotificationPreferencesResponse {
+        challenge_turns: prefs.challenge_turns,
+        friend_invites: prefs.friend_invites,
+        daily_challenge: prefs.daily_challenge,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_keys() {
+        let p256dh = "BCVxsr7N_eNgVRqvHtD0zTZsEc6-VV-JvLexhqUzORcxaOzi6-AYWXvTBHm4bjyPjs7Vd8pZGH6SRpkNtoIAiw4";
+        assert!(validate_keys(p256dh, "BTBZMqHH6r4Tts7J_aSIgg").is_ok());
+        assert!(validate_keys(p256dh, "BTBZMqHH6r4T").is_err());
+        assert!(validate_keys("BTBZMqHH6r4Tts7J_aSIgg", "BTBZMqHH6r4Tts7J_aSIgg").is_err());
+        assert!(validate_keys("%%%", "BTBZMqHH6r4Tts7J_aSIgg").is_err());
+    }
+}
@@ -0,0 +1,290 @@
+//! Organization routes
+//!
+//! Endpoints for the organization a request is served for (see
+//! [`crate::middleware::org`]): its branding for every visitor, and member
+//! and branding management for its admins. Organizations are created by
+//! site admins under `/admin/orgs`.
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{delete, get},
+};
+use dguesser_auth::AuthUser;
+use dguesser_db::organizations::{OrgMember, OrgRole, Organization};
+use dguesser_protocol::api::orgs::{
+    AddOrgMemberRequest, OrgBranding, OrgMemberItem, OrgMemberRole, OrgMembersResponse,
+    OrganizationInfo, UpdateOrganizationRequest,
+};
+
+use crate::error::ApiError;
+use crate::middleware::CurrentOrg;
+use crate::state::AppState;
+
+/// Longest logo URL accepted
+const MAX_LOGO_URL_LEN: usize = 500;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/current", get(get_current_org).put(update_current_org))
+        .route("/current/members", get(list_members).post(add_member))
+        .route("/current/members/{user_id}", delete(remove_member))
+}
+
+/// Get the organization this request is served for.
+///
+/// Frontends call this on load to apply the organization's branding.
+#[utoipa::path(
+    get,
+    path = "/api/v1/orgs/current",
+    tag = "orgs",
+    responses(
+        (status = 200, description = "The organization", body = OrganizationInfo),
+        (status = 404, description = "Served as the public instance"),
+    )
+)]
+pub async fn get_current_org(org: CurrentOrg) -> Result<Json<OrganizationInfo>, ApiError> {
+    let org = org.0.ok_or_else(|| ApiError::not_found("Organization"))?;
+    Ok(Json(organization_info(&org)))
+}
+
+/// Change the organization's name or branding (organization admins only).
+#[utoipa::path(
+    put,
+    path = "/api/v1/orgs/current",
+    tag = "orgs",
+    request_body = UpdateOrganizationRequest,
+    responses(
+        (status = 200, description = "Organization updated", body = OrganizationInfo),
+        (status = 400, description = "Invalid request"),
+        (status = 403, description = "Organization admin access required"),
+        (status = 404, description = "Served as the public instance"),
+    )
+)]
+pub async fn update_current_org(
+    State(state): State<AppState>,
+    org: CurrentOrg,
+    auth: AuthUser,
+    Json(body): Json<UpdateOrganizationRequest>,
+) -> Result<Json<OrganizationInfo>, ApiError> {
+    let current = org.require_admin(&state, &auth.user_id).await?;
+
+    let name = body.name.as_deref().map(validate_org_name).transpose()?;
+    let branding = body
+        .branding
+        .map(|branding| {
+            validate_branding(&branding)?;
+            serde_json::to_value(branding).map_err(|_| ApiError::internal())
+        })
+        .transpose()?;
+
+    let updated =
+        dguesser_db::organizations::update(state.db(), &current.id, name, branding.as_ref())
+            .await?
+            .ok_or_else(|| ApiError::not_found("Organization"))?;
+    state.orgs().invalidate();
+
+    tracing::info!(org_id = %updated.id, user_id = %auth.user_id, "Organization updated");
+
+    Ok(Json(organization_info(&updated)))
+}
+
+/// List the organization's members (organization admins only).
+#[utoipa::path(
+    get,
+    path = "/api/v1/orgs/current/members",
+    tag = "orgs",
+    responses(
+        (status = 200, description = "Members", body = OrgMembersResponse),
+        (status = 403, description = "Organization admin access required"),
+        (status = 404, description = "Served as the public instance"),
+    )
+)]
+pub async fn list_members(
+    State(state): State<AppState>,
+    org: CurrentOrg,
+    auth: AuthUser,
+) -> Result<Json<OrgMembersResponse>, ApiError> {
+    let current = org.require_admin(&state, &auth.user_id).await?;
+
+    let members = dguesser_db::organizations::list_members(state.db(), &current.id).await?;
+
+    Ok(Json(OrgMembersResponse { members: members.into_iter().map(member_item).collect() }))
+}
+
+/// Add a member, or change a member's role (organization admins only).
+#[utoipa::path(
+    post,
+    path = "/api/v1/orgs/current/members",
+    tag = "orgs",
+    request_body = AddOrgMemberRequest,
+    responses(
+        (status = 204, description = "Member added"),
+        (status = 403, description = "Organization admin access required"),
+        (status = 404, description = "User or organization not found"),
+        (status = 409, description = "The last admin cannot be demoted"),
+    )
+)]
+pub async fn add_member(
+    State(state): State<AppState>,
+    org: CurrentOrg,
+    auth: AuthUser,
+    Json(body): Json<AddOrgMemberRequest>,
+) -> Result<StatusCode, ApiError> {
+    let current = org.require_admin(&state, &auth.user_id).await?;
+
+    if dguesser_db::users::get_by_id(state.db(), &body.user_id).await?.is_none() {
+        return Err(ApiError::not_found("User"));
+    }
+
+    let role = match body.role {
+        OrgMemberRole::Member => OrgRole::Member,
+        OrgMemberRole::Admin => OrgRole::Admin,
+    };
+    if role == OrgRole::Member {
+        ensure_other_admin(&state, &current.id, &body.user_id).await?;
+    }
+
+    dguesser_db::organizations::upsert_member(state.db(), &current.id, &body.user_id, role).await?;
+
+    tracing::info!(
+        org_id = %current.id,
+        member = %body.user_id,
+        role = role.as_str(),
+        user_id = %auth.user_id,
+        "Organization member added"
+    );
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Remove a member. Admins can remove anyone; members can leave.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/orgs/current/members/{user_id}",
+    tag = "orgs",
+    params(
+        ("user_id" = String, Path, description = "User ID")
+    ),
+    responses(
+        (status = 204, description = "Member removed"),
+        (status = 403, description = "Organization admin access required"),
+        (status = 404, description = "Not a member"),
+        (status = 409, description = "The last admin cannot be removed"),
+    )
+)]
+pub async fn remove_member(
+    State(state): State<AppState>,
+    org: CurrentOrg,
+    auth: AuthUser,
+    Path(user_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    let current = if user_id == auth.user_id {
+        org.require_member(&state, &auth.user_id).await?;
+        org.0.clone().ok_or_else(|| ApiError::not_found("Organization"))?
+    } else {
+        org.require_admin(&state, &auth.user_id).await?
+    };
+
+    ensure_other_admin(&state, &current.id, &user_id).await?;
+
+    if !dguesser_db::organizations::remove_member(state.db(), &current.id, &user_id).await? {
+        return Err(ApiError::not_found("Member"));
+    }
+
+    tracing::info!(
+        org_id = %current.id,
+        member = %user_id,
+        user_id = %auth.user_id,
+        "Organization member removed"
+    );
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Reject demoting or removing a user who is the organization's only admin.
+async fn ensure_other_admin(state: &AppState, org_id: &str, user_id: &str) -> Result<(), ApiError> {
+    let is_admin = dguesser_db::organizations::get_role(state.db(), org_id, user_id).await?
+        == Some(OrgRole::Admin);
+    if is_admin && dguesser_db::organizations::count_admins(state.db(), org_id).await? <= 1 {
+        return Err(ApiError::conflict("LAST_ORG_ADMIN", "Make another member an admin first"));
+    }
+    Ok(())
+}
+
+pub(super) fn validate_org_name(name: &str) -> Result<&str, ApiError> {
+    let name = name.trim();
+    if !(3..=100).contains(&name.chars().count()) {
+        return Err(ApiError::bad_request(
+            "INVALID_NAME",
+            "Organization name must be 3-100 characters",
+        ));
+    }
+    Ok(name)
+}
+
+fn is_hex_color(color: &str) -> bool {
+    color.len() == 7 && color.starts_with('#') && color[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn validate_branding(branding: &OrgBranding) -> Result<(), ApiError> {
+    if let Some(url) = &branding.logo_url
+        && (!url.starts_with("https://") || url.len() > MAX_LOGO_URL_LEN)
+    {
+        return Err(ApiError::bad_request(
+            "INVALID_LOGO_URL",
+            format!("Logo URL must be an https URL of at most {MAX_LOGO_URL_LEN} characters"),
+        ));
+    }
+    let colors = [&branding.primary_color, &branding.accent_color];
+    if colors.into_iter().flatten().any(|color| !is_hex_color(color)) {
+        return Err(ApiError::bad_request("INVALID_COLOR", "Colors must be #rrggbb"));
+    }
+    Ok(())
+}
+
+pub(super) fn organization_info(org: &Organization) -> OrganizationInfo {
+    OrganizationInfo {
+        id: org.id.clone(),
+        slug: org.slug.clone(),
+        name: org.name.clone(),
+        domain: org.domain.clone(),
+        branding: serde_json::from_value(org.branding.clone()).unwrap_or_default(),
+        active: org.active,
+        created_at: org.created_at,
+    }
+}
+
+fn member_item(member: OrgMember) -> OrgMemberItem {
+    OrgMemberItem {
+        user_id: member.user_id,
+        display_name: member.display_name,
+        avatar_url: member.avatar_url,
+        role: match member.role {
+            OrgRole::Member => OrgMemberRole::Member,
+            OrgRole::Admin => OrgMemberRole::Admin,
+        },
+        joined_at: member.joined_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_branding() {
+        let branding = |logo: Option<&str>, color: Option<&str>| OrgBranding {
+            logo_url: logo.map(str::to_string),
+            primary_color: color.map(str::to_string),
+            accent_color: None,
+        };
+
+        assert!(validate_branding(&OrgBranding::default()).is_ok());
+        assert!(validate_branding(&branding(Some("https://x.edu/l.png"), Some("#1D4ed8"))).is_ok());
+        assert!(validate_branding(&branding(Some("http://x.edu/l.png"), None)).is_err());
+        assert!(validate_branding(&branding(None, Some("blue"))).is_err());
+        assert!(validate_branding(&branding(None, Some("#12345g"))).is_err());
+    }
+}
@@ -3,9 +3,11 @@
 use axum::{
     Json, Router,
     extract::State,
+    http::StatusCode,
     routing::{get, post},
 };
 use dguesser_auth::middleware::AuthUser;
+use dguesser_core::join_code::{DEFAULT_JOIN_CODE_LENGTH, generate_join_code, normalize_join_code};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
@@ -17,6 +19,7 @@ pub fn router() -> Router<AppState> {
         .route("/", post(create_party))
         .route("/join", post(join_party_by_code))
         .route("/{id}", get(get_party))
+        .route("/{id}/leave", post(leave_party))
 }
 
 // =============================================================================
@@ -93,7 +96,7 @@ async fn create_party(
     }
 
     let party_id = dguesser_core::generate_party_id();
-    let join_code = generate_join_code();
+    let join_code = generate_join_code(DEFAULT_JOIN_CODE_LENGTH);
     let settings = req.settings.unwrap_or_else(|| serde_json::json!({}));
 
     // Create in DB
@@ -119,13 +122,9 @@ async fn join_party_by_code(
     _auth: AuthUser,
     Json(req): Json<JoinByCodeRequest>,
 ) -> Result<Json<JoinByCodeResponse>, ApiError> {
-    let code = req.code.trim().to_uppercase();
-    if code.len() != 6 || !code.chars().all(|c| c.is_ascii_alphanumeric()) {
-        return Err(ApiError::bad_request(
-            "INVALID_CODE",
-            "Join code must be 6 alphanumeric characters",
-        ));
-    }
+    let code = normalize_join_code(&req.code).ok_or_else(|| {
+        ApiError::bad_request("INVALID_CODE", "Join code must be 4-8 alphanumeric characters")
+    })?;
 
     // Try party first
     if let Some(party) = dguesser_db::parties::get_party_by_join_code(state.db(), &code).await? {
@@ -191,18 +190,37 @@ async fn get_party(
     }))
 }
 
-// =============================================================================
-// Helpers
-// =============================================================================
+/// Leave a party
+///
+/// A running party actor notices the departure on its next roster sync and
+/// tells the remaining members.
+async fn leave_party(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> Result<StatusCode, ApiError> {
+    let party = dguesser_db::parties::get_party_by_id(state.db(), &id)
+        .await?
+        .filter(|p| p.status == "active")
+        .ok_or_else(|| ApiError::not_found("Party"))?;
+
+    let members = dguesser_db::parties::get_party_members(state.db(), &party.id).await?;
+    if !members.iter().any(|m| m.user_id == auth.user_id) {
+        return Err(ApiError::not_found("Party"));
+    }
+
+    dguesser_db::parties::remove_party_member(state.db(), &party.id, &auth.user_id).await?;
+
+    // Members are ordered by join time, so the longest-tenured one takes over
+    match members.iter().find(|m| m.user_id != auth.user_id) {
+        None => dguesser_db::parties::disband_party(state.db(), &party.id).await?,
+        Some(next) if party.host_id == auth.user_id => {
+            dguesser_db::parties::update_party_host(state.db(), &party.id, &next.user_id).await?
+        }
+        Some(_) => {}
+    }
+
+    tracing::info!(user_id = %auth.user_id, party_id = %party.id, "Left party via REST");
 
-fn generate_join_code() -> String {
-    use rand::RngExt;
-    const CHARSET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
-    let mut rng = rand::rng();
-    (0..6)
-        .map(|_| {
-            let idx = rng.random_range(0..CHARSET.len());
-            CHARSET[idx] as char
-        })
-        .collect()
+    Ok(StatusCode::NO_CONTENT)
 }
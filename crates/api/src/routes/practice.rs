@@ -0,0 +1,237 @@
+//! Practice routes
+//!
+//! Practice rounds are single locations played outside of any game. They
+//! use the same location provider and scoring curve as real rounds, but are
+//! kept in Redis only: nothing reaches the games tables, leaderboards or
+//! stats. Each guess reveals the location straight away, and the player can
+//! keep guessing the same round to see how close they could have been.
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    routing::post,
+};
+use chrono::{DateTime, Duration, Utc};
+use dguesser_auth::AuthUser;
+use dguesser_core::game::{GameSettings, ScoringConfig, calculate_score};
+use dguesser_core::geo::distance::haversine_distance;
+use dguesser_core::id::generate_practice_round_id;
+use dguesser_core::location::GameLocation;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use super::games::LocationInfo;
+use crate::{error::ApiError, extract::ValidatedJson, state::AppState};
+
+/// How long a practice round can be played, in seconds
+const PRACTICE_ROUND_TTL_SECS: u64 = 60 * 60;
+
+/// Create the practice router
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/round", post(create_round))
+        .route("/round/{round_id}/guess", post(guess_round))
+}
+
+// =============================================================================
+// DTOs
+// =============================================================================
+
+/// Start a practice round
+#[derive(Debug, Default, Deserialize, Validate, ToSchema)]
+pub struct CreatePracticeRoundRequest {
+    /// Map ID or slug (defaults to the world map)
+    #[validate(length(max = 100))]
+    #[schema(example = "world")]
+    pub map_id: Option<String>,
+}
+
+/// A practice round
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PracticeRoundResponse {
+    /// Practice round ID (prc_xxxxxxxxxxxx)
+    #[schema(example = "prc_V1StGXR8_Z5j")]
+    pub round_id: String,
+    /// Map the location was drawn from
+    pub map_id: String,
+    /// Location to guess
+    pub location: LocationInfo,
+    /// When the round can no longer be guessed
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Guess a practice round
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct PracticeGuessRequest {
+    /// Guessed latitude
+    #[validate(range(min = -90.0, max = 90.0))]
+    pub lat: f64,
+    /// Guessed longitude
+    #[validate(range(min = -180.0, max = 180.0))]
+    pub lng: f64,
+}
+
+/// A scored practice guess, with the location revealed
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PracticeGuessResponse {
+    /// Distance from the correct location in meters
+    pub distance_meters: f64,
+    /// Score the guess would have earned
+    pub score: u32,
+    /// Best possible score on this map
+    pub max_score: u32,
+    /// Guesses made on this round so far, including this one
+    pub attempts: u32,
+    /// Best score over all attempts
+    pub best_score: u32,
+    /// The correct location
+    pub location: LocationInfo,
+    /// Country of the location (ISO 3166-1 alpha-2), if known
+    pub country_code: Option<String>,
+}
+
+/// A practice round as stored in Redis
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredRound {
+    user_id: String,
+    map_id: String,
+    location: GameLocation,
+    scoring: ScoringConfig,
+    expires_at: DateTime<Utc>,
+    attempts: u32,
+    best_score: u32,
+}
+
+// =============================================================================
+// Handlers
+// =============================================================================
+
+/// Start a practice round
+#[utoipa::path(
+    post,
+    path = "/api/v1/practice/round",
+    request_body = CreatePracticeRoundRequest,
+    responses(
+        (status = 201, description = "Practice round started", body = PracticeRoundResponse),
+        (status = 400, description = "Map has no locations"),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "Map not found"),
+    ),
+    tag = "practice"
+)]
+pub async fn create_round(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    req: Option<Json<CreatePracticeRoundRequest>>,
+) -> Result<(StatusCode, Json<PracticeRoundResponse>), ApiError> {
+    let req = req.map(|Json(req)| req).unwrap_or_default();
+    req.validate()?;
+    let map_id = req.map_id.unwrap_or_else(|| GameSettings::default().map_id);
+
+    let provider = state.location_provider();
+    let map = provider.get_map(&map_id).await?;
+    let location = provider.select_location(&map.id, &[]).await?;
+
+    let round_id = generate_practice_round_id();
+    let round = StoredRound {
+        user_id: auth.user_id,
+        map_id: map.id,
+        location,
+        scoring: map.rules.scoring_config(),
+        expires_at: Utc::now() + Duration::seconds(PRACTICE_ROUND_TTL_SECS as i64),
+        attempts: 0,
+        best_score: 0,
+    };
+    save_round(&state, &round_id, &round).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(PracticeRoundResponse {
+            round_id,
+            map_id: round.map_id.clone(),
+            location: location_info(&round.location),
+            expires_at: round.expires_at,
+        }),
+    ))
+}
+
+/// Guess a practice round; the location is revealed in the response.
+/// A round can be guessed any number of times until it expires.
+#[utoipa::path(
+    post,
+    path = "/api/v1/practice/round/{round_id}/guess",
+    params(
+        ("round_id" = String, Path, description = "Practice round ID")
+    ),
+    request_body = PracticeGuessRequest,
+    responses(
+        (status = 200, description = "Guess scored", body = PracticeGuessResponse),
+        (status = 400, description = "Invalid coordinates"),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "Practice round not found or expired"),
+    ),
+    tag = "practice"
+)]
+pub async fn guess_round(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(round_id): Path<String>,
+    ValidatedJson(req): ValidatedJson<PracticeGuessRequest>,
+) -> Result<Json<PracticeGuessResponse>, ApiError> {
+    let mut conn = state.redis().clone();
+    let json: Option<String> = conn.get(round_key(&round_id)).await?;
+    let mut round: StoredRound = json
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .filter(|r: &StoredRound| r.user_id == auth.user_id)
+        .ok_or_else(|| ApiError::not_found("Practice round"))?;
+
+    let distance = haversine_distance(round.location.lat, round.location.lng, req.lat, req.lng);
+    let score = calculate_score(distance, &round.scoring);
+    round.attempts += 1;
+    round.best_score = round.best_score.max(score);
+    save_round(&state, &round_id, &round).await?;
+
+    Ok(Json(PracticeGuessResponse {
+        distance_meters: distance,
+        score,
+        max_score: round.scoring.max_points,
+        attempts: round.attempts,
+        best_score: round.best_score,
+        location: location_info(&round.location),
+        country_code: round.location.country_code.clone(),
+    }))
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn round_key(round_id: &str) -> String {
+    format!("practice:{round_id}")
+}
+
+/// Store a round until its expiry; guesses don't extend it.
+async fn save_round(state: &AppState, round_id: &str, round: &StoredRound) -> Result<(), ApiError> {
+    let ttl = (round.expires_at - Utc::now()).num_seconds();
+    if ttl <= 0 {
+        return Err(ApiError::not_found("Practice round"));
+    }
+    let json = serde_json::to_string(round)
+        .map_err(|e| ApiError::internal().with_internal(e.to_string()))?;
+    let mut conn = state.redis().clone();
+    conn.set_ex::<_, _, ()>(round_key(round_id), json, ttl as u64).await?;
+    Ok(())
+}
+
+fn location_info(location: &GameLocation) -> LocationInfo {
+    LocationInfo {
+        lat: location.lat,
+        lng: location.lng,
+        panorama_id: Some(location.panorama_id.clone()).filter(|id| !id.is_empty()),
+        location_id: Some(location.id.clone()),
+        provider: location.provider,
+    }
+}
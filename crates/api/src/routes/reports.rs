@@ -0,0 +1,207 @@
+//! Player report routes
+//!
+//! Players report each other for cheating or abuse. Reports land in the
+//! admin queue (`/api/v1/admin/player-reports`) and feed the other
+//! moderation signals: a name report flags the player's display name for
+//! review, and a cheating report records a cheat signal for the game.
+
+use axum::{Json, Router, extract::State, http::StatusCode, routing::post};
+use chrono::{Duration, Utc};
+use dguesser_auth::AuthUser;
+use dguesser_core::game::CheatSignalKind;
+use dguesser_core::reports::{MAX_CHAT_EXCERPT_CHARS, MAX_REPORT_NOTE_CHARS, ReportCategory};
+use dguesser_db::player_reports::{NewPlayerReport, PlayerReport};
+use dguesser_protocol::api::reports::{CreatePlayerReportRequest, PlayerReportResponse};
+
+use crate::{error::ApiError, state::AppState};
+
+/// Reports one user may file per day
+const MAX_REPORTS_PER_DAY: i64 = 20;
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/users", post(report_player))
+}
+
+/// Report a player
+///
+/// A player can have one open report per reporter and category. Cheating
+/// and griefing reports name the game, which both players must have played.
+#[utoipa::path(
+    post,
+    path = "/api/v1/reports/users",
+    tag = "reports",
+    request_body = CreatePlayerReportRequest,
+    responses(
+        (status = 201, description = "Report submitted", body = PlayerReportResponse),
+        (status = 400, description = "Invalid report"),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "Player not found"),
+        (status = 409, description = "Already reported"),
+        (status = 429, description = "Daily report limit reached"),
+    )
+)]
+pub async fn report_player(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Json(req): Json<CreatePlayerReportRequest>,
+) -> Result<(StatusCode, Json<PlayerReportResponse>), ApiError> {
+    let category: ReportCategory = req.category.parse().map_err(|_| {
+        ApiError::bad_request(
+            "INVALID_CATEGORY",
+            format!(
+                "Invalid report category '{}'. Valid categories: {}",
+                req.category,
+                ReportCategory::ALL.map(ReportCategory::as_str).join(", ")
+            ),
+        )
+    })?;
+    if req.user_id == auth.user_id {
+        return Err(ApiError::bad_request("CANNOT_REPORT_SELF", "You can't report yourself"));
+    }
+
+    let chat_excerpt =
+        optional_text(req.chat_excerpt.as_deref(), MAX_CHAT_EXCERPT_CHARS, "chat excerpt")?;
+    let notes = optional_text(req.notes.as_deref(), MAX_REPORT_NOTE_CHARS, "notes")?;
+    let game_id = req.game_id.as_deref().map(str::trim).filter(|id| !id.is_empty());
+    if category.requires_game() && game_id.is_none() {
+        return Err(ApiError::bad_request("GAME_REQUIRED", "Name the game this happened in"));
+    }
+    if category.requires_chat_excerpt() && chat_excerpt.is_none() {
+        return Err(ApiError::bad_request(
+            "CHAT_EXCERPT_REQUIRED",
+            "Quote the chat messages you are reporting",
+        ));
+    }
+    if category == ReportCategory::Other && notes.is_none() {
+        return Err(ApiError::bad_request("NOTES_REQUIRED", "Describe what happened"));
+    }
+
+    dguesser_db::users::get_by_id(state.db(), &req.user_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Player"))?;
+    if let Some(game_id) = game_id {
+        let reporter_played =
+            dguesser_db::games::was_player_in_game(state.db(), game_id, &auth.user_id).await?;
+        let reported_played =
+            dguesser_db::games::was_player_in_game(state.db(), game_id, &req.user_id).await?;
+        if !reporter_played || !reported_played {
+            return Err(ApiError::bad_request(
+                "NOT_IN_GAME",
+                "You can only report players from games you played together",
+            ));
+        }
+    }
+
+    let since = Utc::now() - Duration::days(1);
+    let filed =
+        dguesser_db::player_reports::count_by_reporter_since(state.db(), &auth.user_id, since)
+            .await?;
+    if filed >= MAX_REPORTS_PER_DAY {
+        return Err(ApiError::new(
+            StatusCode::TOO_MANY_REQUESTS,
+            "REPORT_LIMIT",
+            "You have filed too many reports today, try again tomorrow",
+        ));
+    }
+
+    let report = dguesser_db::player_reports::create(
+        state.db(),
+        NewPlayerReport {
+            reporter_id: &auth.user_id,
+            reported_user_id: &req.user_id,
+            category: category.as_str(),
+            game_id,
+            chat_excerpt: chat_excerpt.as_deref(),
+            notes: notes.as_deref(),
+        },
+    )
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => ApiError::conflict(
+            "ALREADY_REPORTED",
+            "You already reported this player for this, a moderator will look at it",
+        ),
+        e => e.into(),
+    })?;
+
+    link_signals(&state, &report, category).await;
+
+    tracing::info!(
+        report_id = %report.id,
+        reporter_id = %auth.user_id,
+        reported_user_id = %report.reported_user_id,
+        category = category.as_str(),
+        "Player reported"
+    );
+
+    Ok((
+        StatusCode::CREATED,
+        Json(PlayerReportResponse {
+            id: report.id,
+            status: report.status,
+            created_at: report.created_at,
+        }),
+    ))
+}
+
+/// Feed a report into the other moderation signals. Failures are logged;
+/// the report itself is already stored.
+async fn link_signals(state: &AppState, report: &PlayerReport, category: ReportCategory) {
+    let result = match (category, report.game_id.as_deref()) {
+        (ReportCategory::AbusiveName, _) => {
+            dguesser_db::name_flags::flag_reported(state.db(), &report.reported_user_id).await
+        }
+        (ReportCategory::Cheating, Some(game_id)) => dguesser_db::anti_cheat::record_signal(
+            state.db(),
+            &report.reported_user_id,
+            game_id,
+            None,
+            CheatSignalKind::PlayerReport,
+            serde_json::json!({ "report_id": report.id, "reporter_id": report.reporter_id }),
+        )
+        .await
+        .map(|_| ()),
+        _ => Ok(()),
+    };
+    if let Err(e) = result {
+        tracing::warn!(error = %e, report_id = %report.id, "Failed to link player report");
+    }
+}
+
+/// Trim optional free text, treating blank as absent.
+fn optional_text(
+    text: Option<&str>,
+    max_chars: usize,
+    field: &str,
+) -> Result<Option<String>, ApiError> {
+    let Some(text) = text.map(str::trim).filter(|t| !t.is_empty()) else {
+        return Ok(None);
+    };
+    if text.chars().count() > max_chars {
+        return Err(ApiError::bad_request(
+            "INVALID_REPORT",
+            format!("The {field} must be at most {max_chars} characters"),
+        ));
+    }
+    if text.chars().any(|c| c.is_control() && c != '\n') {
+        return Err(ApiError::bad_request(
+            "INVALID_REPORT",
+            format!("The {field} contains invalid characters"),
+        ));
+    }
+    Ok(Some(text.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_optional_text() {
+        assert_eq!(optional_text(None, 10, "notes").unwrap(), None);
+        assert_eq!(optional_text(Some("   "), 10, "notes").unwrap(), None);
+        assert_eq!(optional_text(Some(" hi\nthere "), 10, "notes").unwrap().unwrap(), "hi\nthere");
+        assert!(optional_text(Some("way too long"), 5, "notes").is_err());
+        assert!(optional_text(Some("bell\u{7}"), 10, "notes").is_err());
+    }
+}
@@ -2,6 +2,7 @@
 
 use axum::{Json, extract::State};
 use dguesser_protocol::api::service::ServiceInfo;
+use dguesser_protocol::version::ProtocolInfo;
 
 use crate::state::AppState;
 
@@ -29,5 +30,6 @@ pub async fn service_info(State(state): State<AppState>) -> Json<ServiceInfo> {
         rust_version: env!("RUST_VERSION"),
         build_timestamp: env!("BUILD_TIMESTAMP"),
         uptime_seconds: state.uptime_seconds(),
+        protocol: ProtocolInfo::current(),
     })
 }
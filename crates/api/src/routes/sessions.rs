@@ -1,21 +1,67 @@
 //! Session management routes
 
+use std::collections::HashMap;
+
 use axum::{
     Json, Router,
-    extract::{Path, State},
-    routing::{delete, get},
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{delete, get, put},
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 use crate::{error::ApiError, state::AppState};
 use dguesser_auth::AuthUser;
+use dguesser_db::UserDevice;
 
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/", get(list_sessions))
         .route("/{session_id}", delete(revoke_session))
         .route("/others", delete(revoke_other_sessions))
+        .route("/devices", get(list_devices))
+        .route("/devices/{device_id}", delete(forget_device))
+        .route("/devices/{device_id}/trust", put(set_device_trust))
+}
+
+/// Device a session was created from
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DeviceInfo {
+    /// Device ID
+    #[schema(example = "dev_V1StGXR8_Z5j")]
+    pub id: String,
+    /// Browser family (e.g. "Firefox")
+    pub browser: Option<String>,
+    /// Operating system family (e.g. "Windows")
+    pub os: Option<String>,
+    /// Device class: desktop, mobile, tablet, bot, or unknown
+    #[schema(example = "desktop")]
+    pub device_type: String,
+    /// Approximate country of the most recent sign-in (ISO 3166-1 alpha-2)
+    #[schema(example = "DE")]
+    pub last_country: Option<String>,
+    /// Whether the user marked this device as trusted
+    pub trusted: bool,
+    /// First sign-in from this device
+    pub first_seen_at: chrono::DateTime<chrono::Utc>,
+    /// Most recent sign-in from this device
+    pub last_seen_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<UserDevice> for DeviceInfo {
+    fn from(device: UserDevice) -> Self {
+        Self {
+            id: device.id,
+            browser: device.browser,
+            os: device.os,
+            device_type: device.device_type,
+            last_country: device.last_country,
+            trusted: device.trusted,
+            first_seen_at: device.first_seen_at,
+            last_seen_at: device.last_seen_at,
+        }
+    }
 }
 
 /// Session info response
@@ -30,6 +76,10 @@ pub struct SessionInfo {
     pub ip_address: Option<String>,
     /// User agent string
     pub user_agent: Option<String>,
+    /// Approximate country the session was created from (ISO 3166-1 alpha-2)
+    pub country: Option<String>,
+    /// Device the session was created from
+    pub device: Option<DeviceInfo>,
     /// When the session was created
     pub created_at: chrono::DateTime<chrono::Utc>,
     /// When the session was last accessed
@@ -45,6 +95,28 @@ pub struct SessionsListResponse {
     pub sessions: Vec<SessionInfo>,
 }
 
+/// List of devices response
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DevicesListResponse {
+    /// Devices the user has signed in from, most recent first
+    pub devices: Vec<DeviceInfo>,
+}
+
+/// Request to change a device's trusted flag
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetDeviceTrustRequest {
+    /// Whether the device is trusted
+    pub trusted: bool,
+}
+
+/// Query for revoking other sessions
+#[derive(Debug, Deserialize)]
+pub struct RevokeOthersQuery {
+    /// Keep sessions on trusted devices signed in
+    #[serde(default)]
+    keep_trusted: bool,
+}
+
 /// Response after revoking session(s)
 #[derive(Debug, Serialize, ToSchema)]
 pub struct RevokeSessionResponse {
@@ -74,6 +146,12 @@ pub async fn list_sessions(
     auth: AuthUser,
 ) -> Result<Json<SessionsListResponse>, ApiError> {
     let sessions = dguesser_db::sessions::get_user_sessions(state.db(), &auth.user_id).await?;
+    let devices: HashMap<String, UserDevice> =
+        dguesser_db::devices::list_for_user(state.db(), &auth.user_id)
+            .await?
+            .into_iter()
+            .map(|d| (d.id.clone(), d))
+            .collect();
 
     let session_infos: Vec<SessionInfo> = sessions
         .into_iter()
@@ -82,6 +160,8 @@ pub async fn list_sessions(
             is_current: s.id == auth.session_id,
             ip_address: s.ip_address,
             user_agent: s.user_agent,
+            country: s.geo_country,
+            device: s.device_id.as_ref().and_then(|id| devices.get(id).cloned()).map(Into::into),
             created_at: s.created_at,
             last_accessed_at: s.last_accessed_at,
             expires_at: s.expires_at,
@@ -137,6 +217,9 @@ pub async fn revoke_session(
 #[utoipa::path(
     delete,
     path = "/api/v1/sessions/others",
+    params(
+        ("keep_trusted" = Option<bool>, Query, description = "Keep sessions on trusted devices (default: false)")
+    ),
     responses(
         (status = 200, description = "Sessions revoked", body = RevokeSessionResponse),
         (status = 401, description = "Not authenticated"),
@@ -146,13 +229,112 @@ pub async fn revoke_session(
 pub async fn revoke_other_sessions(
     State(state): State<AppState>,
     auth: AuthUser,
+    Query(query): Query<RevokeOthersQuery>,
 ) -> Result<Json<RevokeSessionResponse>, ApiError> {
-    let count =
+    let count = if query.keep_trusted {
+        dguesser_db::sessions::revoke_all_except_trusted(
+            state.db(),
+            &auth.user_id,
+            &auth.session_id,
+        )
+        .await?
+    } else {
         dguesser_db::sessions::revoke_all_except(state.db(), &auth.user_id, &auth.session_id)
-            .await?;
+            .await?
+    };
 
     Ok(Json(RevokeSessionResponse {
         message: format!("Revoked {} other session(s)", count),
         revoked_count: count,
     }))
 }
+
+/// List devices the current user has signed in from
+#[utoipa::path(
+    get,
+    path = "/api/v1/sessions/devices",
+    responses(
+        (status = 200, description = "List of devices", body = DevicesListResponse),
+        (status = 401, description = "Not authenticated"),
+    ),
+    tag = "sessions"
+)]
+pub async fn list_devices(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<Json<DevicesListResponse>, ApiError> {
+    let devices = dguesser_db::devices::list_for_user(state.db(), &auth.user_id).await?;
+
+    Ok(Json(DevicesListResponse { devices: devices.into_iter().map(Into::into).collect() }))
+}
+
+/// Mark a device as trusted or untrusted
+///
+/// Sessions on trusted devices can be kept when signing out everywhere else.
+#[utoipa::path(
+    put,
+    path = "/api/v1/sessions/devices/{device_id}/trust",
+    params(
+        ("device_id" = String, Path, description = "Device ID")
+    ),
+    request_body = SetDeviceTrustRequest,
+    responses(
+        (status = 204, description = "Device updated"),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "Device not found"),
+    ),
+    tag = "sessions"
+)]
+pub async fn set_device_trust(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(device_id): Path<String>,
+    Json(req): Json<SetDeviceTrustRequest>,
+) -> Result<StatusCode, ApiError> {
+    let updated =
+        dguesser_db::devices::set_trusted(state.db(), &auth.user_id, &device_id, req.trusted)
+            .await?;
+    if !updated {
+        return Err(ApiError::not_found("Device"));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Forget a device and sign it out
+///
+/// Revokes every session on the device. The next sign-in from it is treated
+/// as a new device.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/sessions/devices/{device_id}",
+    params(
+        ("device_id" = String, Path, description = "Device ID")
+    ),
+    responses(
+        (status = 200, description = "Device forgotten", body = RevokeSessionResponse),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Cannot forget the current device"),
+        (status = 404, description = "Device not found"),
+    ),
+    tag = "sessions"
+)]
+pub async fn forget_device(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(device_id): Path<String>,
+) -> Result<Json<RevokeSessionResponse>, ApiError> {
+    let current = dguesser_db::sessions::get_valid(state.db(), &auth.session_id).await?;
+    if current.and_then(|s| s.device_id).as_deref() == Some(device_id.as_str()) {
+        return Err(ApiError::forbidden("Cannot forget the current device. Use logout instead."));
+    }
+
+    let count = dguesser_db::devices::forget(state.db(), &auth.user_id, &device_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Device"))?;
+
+    Ok(Json(RevokeSessionResponse {
+        message: format!("Device forgotten, revoked {} session(s)", count),
+        revoked_count: count,
+    }))
+}
@@ -0,0 +1,269 @@
+//! Saved settings preset routes
+//!
+//! Users save named game settings under `/api/v1/users/me/presets` and start
+//! games from them by passing the preset's ID as `preset_id` when creating a
+//! game, like the built-in presets.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use chrono::{DateTime, Utc};
+use dguesser_auth::AuthUser;
+use dguesser_core::game::GameSettings;
+use dguesser_db::settings_presets::SettingsPreset;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::games::SettingsDto;
+use crate::{error::ApiError, state::AppState};
+
+/// Maximum saved presets per user
+const MAX_PRESETS_PER_USER: i64 = 20;
+/// Maximum preset name length, in characters
+const MAX_PRESET_NAME_CHARS: usize = 50;
+
+// =============================================================================
+// DTOs
+// =============================================================================
+
+/// Save preset request
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreatePresetRequest {
+    /// Preset name (1-50 characters, unique per user)
+    #[schema(example = "Europe sprint")]
+    pub name: String,
+    /// Settings to save
+    pub settings: SettingsDto,
+}
+
+/// Update preset request. Omitted fields are left unchanged.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdatePresetRequest {
+    /// New preset name (1-50 characters, unique per user)
+    pub name: Option<String>,
+    /// New settings
+    pub settings: Option<SettingsDto>,
+}
+
+/// A saved settings preset
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SettingsPresetInfo {
+    /// Preset ID, usable as `preset_id` when creating a game
+    #[schema(example = "prs_V1StGXR8_Z5j")]
+    pub id: String,
+    /// Preset name
+    #[schema(example = "Europe sprint")]
+    pub name: String,
+    /// Saved settings
+    pub settings: SettingsDto,
+    /// When the preset was saved
+    pub created_at: DateTime<Utc>,
+    /// When the preset was last changed
+    pub updated_at: DateTime<Utc>,
+}
+
+/// The user's saved presets
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SettingsPresetsResponse {
+    /// Presets, oldest first
+    pub presets: Vec<SettingsPresetInfo>,
+    /// How many presets a user can save
+    #[schema(example = 20)]
+    pub limit: i64,
+}
+
+// =============================================================================
+// Route Handlers
+// =============================================================================
+
+/// List the current user's saved settings presets
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/me/presets",
+    responses(
+        (status = 200, description = "Saved presets", body = SettingsPresetsResponse),
+        (status = 401, description = "Not authenticated"),
+    ),
+    tag = "users"
+)]
+pub async fn list_presets(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<Json<SettingsPresetsResponse>, ApiError> {
+    let presets = dguesser_db::settings_presets::list_for_user(state.db(), &auth.user_id).await?;
+
+    Ok(Json(SettingsPresetsResponse {
+        presets: presets.into_iter().map(preset_info).collect(),
+        limit: MAX_PRESETS_PER_USER,
+    }))
+}
+
+/// Save a settings preset
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/me/presets",
+    request_body = CreatePresetRequest,
+    responses(
+        (status = 201, description = "Preset saved", body = SettingsPresetInfo),
+        (status = 400, description = "Invalid name or settings, or preset limit reached"),
+        (status = 401, description = "Not authenticated"),
+        (status = 409, description = "A preset with this name exists"),
+    ),
+    tag = "users"
+)]
+pub async fn create_preset(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Json(req): Json<CreatePresetRequest>,
+) -> Result<(StatusCode, Json<SettingsPresetInfo>), ApiError> {
+    let name = validate_preset_name(&req.name)?;
+    let settings = validate_preset_settings(req.settings)?;
+
+    let count = dguesser_db::settings_presets::count_for_user(state.db(), &auth.user_id).await?;
+    if count >= MAX_PRESETS_PER_USER {
+        return Err(ApiError::bad_request(
+            "PRESET_LIMIT",
+            format!("You can save at most {MAX_PRESETS_PER_USER} presets"),
+        ));
+    }
+
+    let preset = dguesser_db::settings_presets::create(state.db(), &auth.user_id, name, &settings)
+        .await
+        .map_err(preset_name_taken)?;
+
+    Ok((StatusCode::CREATED, Json(preset_info(preset))))
+}
+
+/// Rename a settings preset or replace its settings
+#[utoipa::path(
+    put,
+    path = "/api/v1/users/me/presets/{preset_id}",
+    params(
+        ("preset_id" = String, Path, description = "Preset ID")
+    ),
+    request_body = UpdatePresetRequest,
+    responses(
+        (status = 200, description = "Preset updated", body = SettingsPresetInfo),
+        (status = 400, description = "Invalid name or settings"),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "Preset not found"),
+        (status = 409, description = "A preset with this name exists"),
+    ),
+    tag = "users"
+)]
+pub async fn update_preset(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(preset_id): Path<String>,
+    Json(req): Json<UpdatePresetRequest>,
+) -> Result<Json<SettingsPresetInfo>, ApiError> {
+    let name = req.name.as_deref().map(validate_preset_name).transpose()?;
+    let settings = req.settings.map(validate_preset_settings).transpose()?;
+
+    let preset = dguesser_db::settings_presets::update(
+        state.db(),
+        &preset_id,
+        &auth.user_id,
+        name,
+        settings.as_ref(),
+    )
+    .await
+    .map_err(preset_name_taken)?
+    .ok_or_else(|| ApiError::not_found("Preset"))?;
+
+    Ok(Json(preset_info(preset)))
+}
+
+/// Delete a settings preset
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/me/presets/{preset_id}",
+    params(
+        ("preset_id" = String, Path, description = "Preset ID")
+    ),
+    responses(
+        (status = 204, description = "Preset deleted"),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "Preset not found"),
+    ),
+    tag = "users"
+)]
+pub async fn delete_preset(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(preset_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    if !dguesser_db::settings_presets::delete(state.db(), &preset_id, &auth.user_id).await? {
+        return Err(ApiError::not_found("Preset"));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// =============================================================================
+// Helper Functions
+// =============================================================================
+
+fn preset_info(preset: SettingsPreset) -> SettingsPresetInfo {
+    let settings: GameSettings = serde_json::from_value(preset.settings).unwrap_or_default();
+    SettingsPresetInfo {
+        id: preset.id,
+        name: preset.name,
+        settings: SettingsDto::from_settings(&settings),
+        created_at: preset.created_at,
+        updated_at: preset.updated_at,
+    }
+}
+
+fn validate_preset_name(name: &str) -> Result<&str, ApiError> {
+    let name = name.trim();
+    if !(1..=MAX_PRESET_NAME_CHARS).contains(&name.chars().count()) {
+        return Err(ApiError::bad_request(
+            "INVALID_NAME",
+            format!("Preset name must be 1-{MAX_PRESET_NAME_CHARS} characters"),
+        ));
+    }
+    Ok(name)
+}
+
+/// Check the settings with the core rules and turn them into the stored JSON
+fn validate_preset_settings(dto: SettingsDto) -> Result<serde_json::Value, ApiError> {
+    let settings = dto.into_settings();
+    if let Err(errors) = dguesser_core::game::validate_settings(&settings) {
+        return Err(ApiError::bad_request("INVALID_SETTINGS", errors.join(", ")));
+    }
+    serde_json::to_value(&settings)
+        .map_err(|e| ApiError::internal().with_internal(format!("Serialize settings: {e}")))
+}
+
+fn preset_name_taken(err: sqlx::Error) -> ApiError {
+    match &err {
+        sqlx::Error::Database(db) if db.is_unique_violation() => {
+            ApiError::conflict("PRESET_NAME_TAKEN", "You already have a preset with this name")
+        }
+        _ => err.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_preset_name() {
+        assert_eq!(validate_preset_name("  Europe sprint ").unwrap(), "Europe sprint");
+        assert!(validate_preset_name("   ").is_err());
+        assert!(validate_preset_name(&"é".repeat(MAX_PRESET_NAME_CHARS)).is_ok());
+        assert!(validate_preset_name(&"a".repeat(MAX_PRESET_NAME_CHARS + 1)).is_err());
+    }
+
+    #[test]
+    fn test_validate_preset_settings() {
+        let settings = SettingsDto::from_settings(&GameSettings::default());
+        assert!(validate_preset_settings(settings.clone()).is_ok());
+
+        let invalid = SettingsDto { rounds: 0, ..settings };
+        assert!(validate_preset_settings(invalid).is_err());
+    }
+}
@@ -0,0 +1,306 @@
+//! Street View metadata proxy and map tiles
+//!
+//! The frontend checks panoramas through the server instead of calling the
+//! metadata API with its own key. Lookups are cached in Redis, count toward
+//! the shared quota, and panoramas found missing deactivate the locations
+//! that show them.
+//!
+//! Imagery is fetched the same way: a signed-in user asks for a tile token
+//! and loads tiles through [`get_tile`], which fetches them with the user's
+//! Map Tiles session (see [`crate::map_tiles`]).
+
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use chrono::Utc;
+use dguesser_auth::AuthUser;
+use dguesser_protocol::api::streetview::{
+    CreateTileSessionRequest, StreetViewMetadataResponse, TileSessionResponse,
+};
+use serde::Deserialize;
+
+use crate::cache::StreetViewMetadataCache;
+use crate::location_health::mark_panorama_dead;
+use crate::map_tiles::{MapTilesError, TileCoords, TileMapType};
+use crate::street_view::{PanoramaQuery, PanoramaStatus};
+use crate::{error::ApiError, state::AppState};
+
+/// Longest panorama ID accepted
+const MAX_PANORAMA_ID_LEN: usize = 128;
+
+/// Browsers may keep tiles for a day; imagery rarely changes
+const TILE_CACHE_CONTROL: &str = "private, max-age=86400";
+
+/// Metadata lookup query
+#[derive(Debug, Deserialize)]
+pub struct MetadataQuery {
+    /// Panorama ID to look up
+    pub pano: String,
+}
+
+/// Tile query
+#[derive(Debug, Deserialize)]
+pub struct TileQuery {
+    /// Tile token from `POST /api/v1/streetview/session`
+    pub token: String,
+    /// Panorama ID (Street View tiles only)
+    pub pano: Option<String>,
+}
+
+/// Metadata lookups and tile sessions, which spend quota per request
+pub fn router() -> Router<AppState> {
+    Router::new().route("/metadata", get(get_metadata)).route("/session", post(create_tile_session))
+}
+
+/// Tile proxy, requested many times per panorama or map view
+pub fn tile_router() -> Router<AppState> {
+    Router::new().route("/tiles/{z}/{x}/{y}", get(get_tile))
+}
+
+/// Look up a Street View panorama.
+///
+/// Answers from the cache when possible. A panorama that no longer exists
+/// is reported as "not_found" and its locations are deactivated.
+#[utoipa::path(
+    get,
+    path = "/api/v1/streetview/metadata",
+    params(
+        ("pano" = String, Query, description = "Panorama ID")
+    ),
+    responses(
+        (status = 200, description = "Panorama metadata", body = StreetViewMetadataResponse),
+        (status = 400, description = "Invalid panorama ID"),
+        (status = 401, description = "Not authenticated"),
+        (status = 429, description = "Rate limit exceeded"),
+        (status = 502, description = "Metadata lookup failed"),
+        (status = 503, description = "Street View lookups unavailable"),
+    ),
+    tag = "streetview"
+)]
+pub async fn get_metadata(
+    State(state): State<AppState>,
+    _auth: AuthUser,
+    Query(query): Query<MetadataQuery>,
+) -> Result<Json<StreetViewMetadataResponse>, ApiError> {
+    let panorama_id = validate_panorama_id(&query.pano)?;
+
+    if let Some(cached) = StreetViewMetadataCache::get(state.redis(), panorama_id).await {
+        return Ok(Json(cached));
+    }
+
+    let client = state.street_view();
+    if !client.can_validate() {
+        return Err(ApiError::service_unavailable("Street View lookups are not configured"));
+    }
+
+    let (metadata, found) = match client.lookup(PanoramaQuery::Panorama(panorama_id)).await {
+        PanoramaStatus::Exists(meta) => (
+            StreetViewMetadataResponse {
+                panorama_id: panorama_id.to_string(),
+                status: "ok".to_string(),
+                lat: Some(meta.lat),
+                lng: Some(meta.lng),
+                capture_date: meta.capture_date,
+            },
+            true,
+        ),
+        PanoramaStatus::Missing(status) => {
+            mark_panorama_dead(&state, panorama_id, &format!("metadata_proxy: {status}")).await;
+            (
+                StreetViewMetadataResponse {
+                    panorama_id: panorama_id.to_string(),
+                    status: "not_found".to_string(),
+                    lat: None,
+                    lng: None,
+                    capture_date: None,
+                },
+                false,
+            )
+        }
+        PanoramaStatus::Fatal(e) => {
+            tracing::warn!(error = %e, "Street View metadata proxy unavailable");
+            return Err(ApiError::service_unavailable(
+                "Street View lookups are paused, try again later",
+            ));
+        }
+        PanoramaStatus::Error(e) => {
+            return Err(ApiError::new(
+                StatusCode::BAD_GATEWAY,
+                "LOOKUP_FAILED",
+                "Could not check panorama",
+            )
+            .with_internal(e));
+        }
+    };
+
+    StreetViewMetadataCache::set(state.redis(), &metadata, found).await;
+    Ok(Json(metadata))
+}
+
+/// Get a signed tile token for a map type.
+///
+/// Tokens are valid for an hour (less when the underlying session expires
+/// sooner); request a new one before `expires_at`. Sessions are created per
+/// user and replaced before Google expires them.
+#[utoipa::path(
+    post,
+    path = "/api/v1/streetview/session",
+    request_body = CreateTileSessionRequest,
+    responses(
+        (status = 200, description = "Tile token", body = TileSessionResponse),
+        (status = 400, description = "Unknown map type"),
+        (status = 401, description = "Not authenticated"),
+        (status = 429, description = "Rate limit exceeded"),
+        (status = 502, description = "Could not create a session"),
+        (status = 503, description = "Map tiles are not configured"),
+    ),
+    tag = "streetview"
+)]
+pub async fn create_tile_session(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Json(req): Json<CreateTileSessionRequest>,
+) -> Result<Json<TileSessionResponse>, ApiError> {
+    let map_type = TileMapType::parse(&req.map_type).ok_or_else(|| {
+        ApiError::bad_request(
+            "INVALID_MAP_TYPE",
+            "Map type must be streetview, roadmap or satellite",
+        )
+    })?;
+
+    let tiles = state.map_tiles();
+    if !tiles.is_configured() {
+        return Err(ApiError::service_unavailable("Map tiles are not configured"));
+    }
+
+    let now = Utc::now();
+    let session = tiles.session_for(&auth.user_id, map_type, now).await.map_err(map_tiles_error)?;
+    let (token, expires_at) = tiles
+        .mint_token(&auth.user_id, map_type, session.expires_at, now)
+        .map_err(map_tiles_error)?;
+
+    let mut tile_url = format!("/api/v1/streetview/tiles/{{z}}/{{x}}/{{y}}?token={token}");
+    if map_type == TileMapType::StreetView {
+        tile_url.push_str("&pano={pano}");
+    }
+
+    Ok(Json(TileSessionResponse {
+        token,
+        expires_at,
+        map_type: map_type.as_str().to_string(),
+        tile_width: session.tile_width,
+        tile_height: session.tile_height,
+        image_format: session.image_format,
+        tile_url,
+    }))
+}
+
+/// Fetch one tile with a tile token.
+///
+/// Every tile served counts toward the token owner's usage.
+#[utoipa::path(
+    get,
+    path = "/api/v1/streetview/tiles/{z}/{x}/{y}",
+    params(
+        ("z" = u8, Path, description = "Zoom level"),
+        ("x" = u32, Path, description = "Tile column"),
+        ("y" = u32, Path, description = "Tile row"),
+        ("token" = String, Query, description = "Tile token"),
+        ("pano" = Option<String>, Query, description = "Panorama ID (Street View tiles only)"),
+    ),
+    responses(
+        (status = 200, description = "Tile image", content_type = "image/jpeg"),
+        (status = 400, description = "Invalid tile or panorama"),
+        (status = 401, description = "Invalid or expired tile token"),
+        (status = 404, description = "Tile not found"),
+        (status = 502, description = "Could not fetch the tile"),
+        (status = 503, description = "Map tiles are not configured"),
+    ),
+    tag = "streetview"
+)]
+pub async fn get_tile(
+    State(state): State<AppState>,
+    Path((z, x, y)): Path<(u8, u32, u32)>,
+    Query(query): Query<TileQuery>,
+) -> Result<Response, ApiError> {
+    let tiles = state.map_tiles();
+    if !tiles.is_configured() {
+        return Err(ApiError::service_unavailable("Map tiles are not configured"));
+    }
+
+    let now = Utc::now();
+    let claims = tiles
+        .verify_token(&query.token, now)
+        .map_err(|e| ApiError::unauthorized_with_message(e.to_string()))?;
+
+    let panorama_id = match (claims.map_type, query.pano.as_deref()) {
+        (TileMapType::StreetView, Some(pano)) => Some(validate_panorama_id(pano)?),
+        (TileMapType::StreetView, None) => {
+            return Err(ApiError::bad_request(
+                "INVALID_PANORAMA_ID",
+                "Street View tiles need a panorama",
+            ));
+        }
+        _ => None,
+    };
+    let coords = TileCoords { z, x, y, panorama_id };
+    if !coords.is_valid() {
+        return Err(ApiError::bad_request("INVALID_TILE", "Tile is outside the zoom level"));
+    }
+
+    let session =
+        tiles.session_for(&claims.user_id, claims.map_type, now).await.map_err(map_tiles_error)?;
+    let tile = match tiles.fetch_tile(&session, claims.map_type, coords).await {
+        Ok(tile) => tile,
+        Err(MapTilesError::Status(404)) => return Err(ApiError::not_found("Tile")),
+        Err(e) => return Err(map_tiles_error(e)),
+    };
+    tiles.record_tile(&claims.user_id, claims.map_type, now.date_naive()).await;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, tile.content_type),
+            (header::CACHE_CONTROL, TILE_CACHE_CONTROL.to_string()),
+        ],
+        tile.bytes,
+    )
+        .into_response())
+}
+
+fn map_tiles_error(err: MapTilesError) -> ApiError {
+    match err {
+        MapTilesError::NotConfigured => {
+            ApiError::service_unavailable("Map tiles are not configured")
+        }
+        e => ApiError::new(StatusCode::BAD_GATEWAY, "TILES_FAILED", "Could not load map tiles")
+            .with_internal(e.to_string()),
+    }
+}
+
+fn validate_panorama_id(pano: &str) -> Result<&str, ApiError> {
+    let pano = pano.trim();
+    let valid = !pano.is_empty()
+        && pano.len() <= MAX_PANORAMA_ID_LEN
+        && pano.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(ApiError::bad_request("INVALID_PANORAMA_ID", "Invalid panorama ID"));
+    }
+    Ok(pano)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_panorama_id() {
+        assert_eq!(validate_panorama_id(" CAoSLEFGMVFp-_x ").unwrap(), "CAoSLEFGMVFp-_x");
+        assert!(validate_panorama_id("").is_err());
+        assert!(validate_panorama_id("abc&key=x").is_err());
+        assert!(validate_panorama_id(&"a".repeat(MAX_PANORAMA_ID_LEN + 1)).is_err());
+    }
+}
@@ -0,0 +1,161 @@
+//! Client telemetry routes
+//!
+//! The frontend reports errors it cannot recover from so they show up
+//! somewhere other than the player's console. Reports are sampled, except
+//! panorama failures linked to a location: those always count toward the
+//! location's failure counter.
+
+use axum::{
+    Router,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    routing::post,
+};
+use dguesser_auth::MaybeAuthUser;
+use dguesser_db::client_errors::{NewClientError, PANO_LOAD_FAILED};
+use rand::RngExt;
+use serde::Deserialize;
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::middleware::client_ip::RequestClient;
+use crate::{error::ApiError, extract::ValidatedJson, state::AppState};
+
+/// Error kinds the frontend reports
+const CLIENT_ERROR_KINDS: [&str; 4] =
+    [PANO_LOAD_FAILED, "socket_disconnected", "map_load_failed", "unhandled"];
+/// Maximum size of a report's serialized context, in bytes
+const MAX_CONTEXT_BYTES: usize = 4096;
+/// Stored user agents are cut to this many characters
+const MAX_USER_AGENT_CHARS: usize = 256;
+
+// =============================================================================
+// DTOs
+// =============================================================================
+
+/// Client error report
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ClientErrorReport {
+    /// What went wrong: "pano_load_failed", "socket_disconnected",
+    /// "map_load_failed" or "unhandled"
+    #[schema(example = "pano_load_failed")]
+    pub kind: String,
+    /// Error message
+    #[validate(length(max = 1000))]
+    pub message: Option<String>,
+    /// Location being shown when the error happened
+    #[validate(length(max = 16))]
+    pub location_id: Option<String>,
+    /// Panorama being shown, used to find the location when its ID is unknown
+    #[validate(length(max = 128))]
+    pub panorama_id: Option<String>,
+    /// Game being played
+    #[validate(length(max = 16))]
+    pub game_id: Option<String>,
+    /// Page path the error happened on
+    #[validate(length(max = 512))]
+    #[schema(example = "/game/gam_abc123")]
+    pub page: Option<String>,
+    /// Additional details (a JSON object of at most 4 KB)
+    #[schema(value_type = Option<Object>)]
+    pub context: Option<serde_json::Value>,
+}
+
+// =============================================================================
+// Router
+// =============================================================================
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/client-errors", post(report_client_error))
+}
+
+// =============================================================================
+// Handlers
+// =============================================================================
+
+/// Report a client-side error.
+///
+/// Always accepted unless invalid; most reports are sampled. Panorama
+/// failures from signed-in players count toward the location's failure
+/// counter once per player per day.
+#[utoipa::path(
+    post,
+    path = "/api/v1/telemetry/client-errors",
+    tag = "telemetry",
+    request_body = ClientErrorReport,
+    responses(
+        (status = 202, description = "Report accepted"),
+        (status = 400, description = "Invalid report"),
+        (status = 429, description = "Too many reports"),
+    )
+)]
+pub async fn report_client_error(
+    State(state): State<AppState>,
+    MaybeAuthUser(auth): MaybeAuthUser,
+    headers: HeaderMap,
+    ValidatedJson(report): ValidatedJson<ClientErrorReport>,
+) -> Result<StatusCode, ApiError> {
+    if !CLIENT_ERROR_KINDS.contains(&report.kind.as_str()) {
+        return Err(ApiError::bad_request(
+            "INVALID_KIND",
+            format!(
+                "Invalid error kind '{}'. Valid kinds: {}",
+                report.kind,
+                CLIENT_ERROR_KINDS.join(", ")
+            ),
+        ));
+    }
+
+    let context = report.context.unwrap_or_else(|| serde_json::json!({}));
+    if !context.is_object() || context.to_string().len() > MAX_CONTEXT_BYTES {
+        return Err(ApiError::bad_request(
+            "INVALID_CONTEXT",
+            format!("Context must be a JSON object of at most {MAX_CONTEXT_BYTES} bytes"),
+        ));
+    }
+
+    let is_pano_failure = report.kind == PANO_LOAD_FAILED
+        && (report.location_id.is_some() || report.panorama_id.is_some());
+    if !is_pano_failure && rand::rng().random::<f64>() >= state.client_error_sample_rate() {
+        return Ok(StatusCode::ACCEPTED);
+    }
+
+    let client = RequestClient::from_headers(&headers, state.client_ip_config());
+    let user_agent: Option<String> =
+        client.user_agent.map(|ua| ua.chars().take(MAX_USER_AGENT_CHARS).collect());
+    let user_id = auth.as_ref().map(|a| a.user_id.as_str());
+
+    let location_id = dguesser_db::client_errors::insert(
+        state.db(),
+        &NewClientError {
+            kind: &report.kind,
+            message: report.message.as_deref(),
+            user_id,
+            location_id: report.location_id.as_deref(),
+            panorama_id: report.panorama_id.as_deref(),
+            game_id: report.game_id.as_deref(),
+            page: report.page.as_deref(),
+            user_agent: user_agent.as_deref(),
+            context,
+        },
+    )
+    .await?;
+
+    // Anonymous reports cannot be deduplicated, so they are stored but not counted
+    if let (true, Some(location_id), Some(user_id)) = (is_pano_failure, location_id, user_id)
+        && dguesser_db::client_errors::record_pano_failure(state.db(), &location_id, user_id)
+            .await?
+    {
+        match dguesser_db::locations::auto_flag_location(state.db(), &location_id).await {
+            Ok(true) => {
+                tracing::warn!(location_id = %location_id, "Location flagged after panorama load failures");
+            }
+            Ok(false) => {}
+            Err(e) => {
+                tracing::warn!(error = %e, location_id = %location_id, "Failed to flag location");
+            }
+        }
+    }
+
+    Ok(StatusCode::ACCEPTED)
+}
@@ -2,7 +2,10 @@
 
 use axum::{
     Json, Router,
-    extract::{Path, State},
+    body::Bytes,
+    extract::{DefaultBodyLimit, Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
     routing::{delete, get, put},
 };
 use std::sync::LazyLock;
@@ -11,8 +14,20 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
-use crate::{cache::CoPlayersCache, error::ApiError, state::AppState};
-use dguesser_auth::{AuthUser, MaybeAuthUser};
+use super::settings_presets;
+use crate::{
+    cache::{CoPlayersCache, LeaderboardCache},
+    error::ApiError,
+    render::{CardFormat, ProfileCard},
+    state::AppState,
+    storage::ImageFormat,
+};
+use dguesser_auth::{AuthUser, MaybeAuthUser, OAuthProvider, unlink_oauth_account};
+use dguesser_core::geo::countries::CountryCode;
+use dguesser_core::moderation::{self, CheckedName};
+use dguesser_db::leaderboard::Scope;
+use dguesser_protocol::api::leaderboard::{LeaderboardType, TimePeriod};
+use dguesser_protocol::socket::presence::{PresenceInfo, PresenceVisibility};
 
 /// Reserved usernames that cannot be used
 const RESERVED_USERNAMES: &[&str] = &[
@@ -55,6 +70,12 @@ const RESERVED_USERNAMES: &[&str] = &[
 static USERNAME_REGEX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"^[a-z0-9][a-z0-9_]*[a-z0-9]$|^[a-z0-9]{1,2}$").unwrap());
 
+/// Largest accepted avatar upload
+const MAX_AVATAR_BYTES: usize = 512 * 1024;
+
+/// Longest bio, in characters
+const MAX_BIO_CHARS: usize = 160;
+
 /// Allowed avatar URL domains (OAuth providers and common avatar services)
 const ALLOWED_AVATAR_DOMAINS: &[&str] = &[
     "lh3.googleusercontent.com",     // Google
@@ -71,8 +92,30 @@ pub fn router() -> Router<AppState> {
         .route("/me", get(get_profile))
         .route("/me", put(update_profile))
         .route("/me", delete(delete_account))
+        .route(
+            "/me/avatar",
+            put(upload_avatar)
+                .delete(delete_avatar)
+                .layer(DefaultBodyLimit::max(MAX_AVATAR_BYTES + 1024)),
+        )
+        .route("/me/oauth", get(list_linked_providers))
+        .route("/me/oauth/{provider}", delete(unlink_provider))
+        .route("/me/email-preferences", get(get_email_preferences))
+        .route("/me/email-preferences", put(update_email_preferences))
+        .route(
+            "/me/presets",
+            get(settings_presets::list_presets).post(settings_presets::create_preset),
+        )
+        .route(
+            "/me/presets/{preset_id}",
+            put(settings_presets::update_preset).delete(settings_presets::delete_preset),
+        )
         .route("/u/{username}", get(get_user_by_username))
         .route("/{id}", get(get_user_profile))
+        .route("/{id}/presence", get(get_user_presence))
+        // `{id}` is the username here; path segments must share a name
+        .route("/{id}/public", get(get_public_profile))
+        .route("/{id}/card", get(get_profile_card))
 }
 
 /// User profile response
@@ -98,10 +141,22 @@ pub struct UserProfileResponse {
     pub best_score: i32,
     /// Whether the user has opted into public leaderboard visibility
     pub leaderboard_public: bool,
+    /// Short bio
+    pub bio: Option<String>,
+    /// Home country (ISO 3166-1 alpha-2), shown as a flag
+    #[schema(example = "SE")]
+    pub country_code: Option<String>,
+    /// Profile accent color
+    #[schema(example = "#3b82f6")]
+    pub profile_color: Option<String>,
+    /// Whether a moderator has locked the profile against edits
+    pub profile_locked: bool,
+    /// Who can see whether you are online and what you are playing
+    pub presence_visibility: PresenceVisibility,
 }
 
-impl From<dguesser_db::User> for UserProfileResponse {
-    fn from(user: dguesser_db::User) -> Self {
+impl UserProfileResponse {
+    fn new(user: dguesser_db::User, profile: dguesser_db::profiles::UserProfile) -> Self {
         Self {
             id: user.id,
             username: user.username,
@@ -112,10 +167,92 @@ impl From<dguesser_db::User> for UserProfileResponse {
             total_score: user.total_score,
             best_score: user.best_score,
             leaderboard_public: user.leaderboard_public,
+            bio: profile.bio,
+            country_code: profile.country_code,
+            profile_color: profile.profile_color,
+            profile_locked: profile.locked,
+            presence_visibility: PresenceVisibility::parse(&profile.presence_visibility),
         }
     }
+
+    /// Load the profile fields for a user
+    async fn load(state: &AppState, user: dguesser_db::User) -> Result<Self, ApiError> {
+        let profile = dguesser_db::profiles::get(state.db(), &user.id).await?;
+        Ok(Self::new(user, profile))
+    }
+}
+
+/// Public profile with stats, for opted-in users
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PublicProfileResponse {
+    /// Unique username
+    #[schema(example = "coolplayer42")]
+    pub username: String,
+    /// Display name
+    pub display_name: String,
+    /// Avatar URL
+    pub avatar_url: Option<String>,
+    /// Short bio
+    pub bio: Option<String>,
+    /// Home country (ISO 3166-1 alpha-2)
+    #[schema(example = "SE")]
+    pub country_code: Option<String>,
+    /// Profile accent color
+    #[schema(example = "#3b82f6")]
+    pub profile_color: Option<String>,
+    /// When the account was created
+    pub member_since: chrono::DateTime<chrono::Utc>,
+    /// Lifetime stats
+    pub stats: PublicProfileStats,
+    /// Path of the shareable stats card image (PNG; add `?format=svg` for SVG)
+    #[schema(example = "/api/v1/users/coolplayer42/card")]
+    pub card_url: String,
+}
+
+/// Lifetime stats on a public profile
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PublicProfileStats {
+    /// Number of games played
+    pub games_played: i32,
+    /// Total score across all games
+    pub total_score: i64,
+    /// Best score in a single game
+    pub best_score: i32,
+    /// Average score per game
+    pub average_score: Option<f64>,
+    /// Rounds with a submitted guess
+    pub rounds_played: i64,
+    /// Mean guess distance in meters
+    pub average_distance_meters: Option<f64>,
+    /// Longest country streak
+    pub best_streak: Option<i64>,
+    /// All-time rank by total score
+    pub rank: Option<i64>,
 }
 
+/// A user's presence
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UserPresenceResponse {
+    /// User ID
+    #[schema(example = "usr_V1StGXR8_Z5j")]
+    pub user_id: String,
+    /// Current status and activity; `offline` when hidden by the user's
+    /// privacy settings
+    #[serde(flatten)]
+    pub presence: PresenceInfo,
+}
+
+/// Query params for rendered cards
+#[derive(Debug, Deserialize)]
+pub struct CardQuery {
+    /// Image format: "png" (default) or "svg"
+    #[serde(default)]
+    pub format: CardFormat,
+}
+
+/// How long browsers and crawlers may cache rendered cards
+pub(crate) const CARD_CACHE_CONTROL: &str = "public, max-age=600";
+
 /// Update profile request
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateProfileRequest {
@@ -129,6 +266,16 @@ pub struct UpdateProfileRequest {
     pub avatar_url: Option<String>,
     /// Whether to show identity publicly on the leaderboard
     pub leaderboard_public: Option<bool>,
+    /// Short bio (max 160 characters, empty clears it)
+    pub bio: Option<String>,
+    /// Home country code (ISO 3166-1 alpha-2, empty clears it)
+    #[schema(example = "SE")]
+    pub country_code: Option<String>,
+    /// Profile color as `#rrggbb` (empty clears it)
+    #[schema(example = "#3b82f6")]
+    pub profile_color: Option<String>,
+    /// Who can see whether you are online and what you are playing
+    pub presence_visibility: Option<PresenceVisibility>,
 }
 
 /// Delete account response
@@ -138,6 +285,39 @@ pub struct DeleteAccountResponse {
     pub message: String,
 }
 
+/// A linked OAuth provider
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LinkedProviderInfo {
+    /// Provider name
+    #[schema(example = "google")]
+    pub provider: String,
+    /// Email reported by the provider when linked
+    pub email: Option<String>,
+    /// When the provider was linked
+    pub linked_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Linked OAuth providers response
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LinkedProvidersResponse {
+    /// Providers linked to the current account
+    pub providers: Vec<LinkedProviderInfo>,
+}
+
+/// Unlink provider response
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UnlinkProviderResponse {
+    /// Confirmation message
+    pub message: String,
+}
+
+/// Email preferences
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct EmailPreferences {
+    /// Receive a weekly summary of your games (requires a verified email)
+    pub weekly_digest: bool,
+}
+
 /// Get current user's profile
 #[utoipa::path(
     get,
@@ -156,7 +336,7 @@ pub async fn get_profile(
         .await?
         .ok_or_else(|| ApiError::not_found("User"))?;
 
-    Ok(Json(UserProfileResponse::from(user)))
+    Ok(Json(UserProfileResponse::load(&state, user).await?))
 }
 
 /// Validate a username
@@ -242,6 +422,88 @@ fn validate_avatar_url(url: &str) -> Result<(), ApiError> {
     Ok(())
 }
 
+/// Validate a display name, returning it trimmed along with any review flag
+pub(crate) fn validate_display_name(name: &str) -> Result<CheckedName, ApiError> {
+    moderation::check_display_name(name).map_err(|e| ApiError::bad_request(e.code(), e.to_string()))
+}
+
+/// Flag a display name a user just set for review, or drop the pending flag
+/// left by an earlier name. Failures are logged; the name is already saved.
+pub(crate) async fn record_display_name_flag(
+    state: &AppState,
+    user_id: &str,
+    checked: &CheckedName,
+) {
+    let result = match checked.flag {
+        Some(reason) => {
+            tracing::info!(
+                user_id = %user_id,
+                reason = reason.as_str(),
+                "Display name flagged for review"
+            );
+            dguesser_db::name_flags::flag(state.db(), user_id, &checked.name, reason.as_str()).await
+        }
+        None => dguesser_db::name_flags::clear_pending(state.db(), user_id).await,
+    };
+    if let Err(e) = result {
+        tracing::warn!(error = %e, user_id = %user_id, "Failed to record display name flag");
+    }
+}
+
+/// Validate and normalize a bio (empty clears it)
+fn validate_bio(bio: &str) -> Result<Option<String>, ApiError> {
+    let bio = bio.trim();
+    if bio.chars().count() > MAX_BIO_CHARS {
+        return Err(ApiError::bad_request(
+            "INVALID_BIO",
+            format!("Bio must be at most {} characters", MAX_BIO_CHARS),
+        ));
+    }
+    if bio.chars().any(|c| c.is_control() && c != '\n') {
+        return Err(ApiError::bad_request("INVALID_BIO", "Bio contains invalid characters"));
+    }
+    Ok((!bio.is_empty()).then(|| bio.to_string()))
+}
+
+/// Validate and normalize a home country code (empty clears it)
+fn validate_country_code(code: &str) -> Result<Option<String>, ApiError> {
+    if code.is_empty() {
+        return Ok(None);
+    }
+    let code = CountryCode::parse(code)
+        .map_err(|_| ApiError::bad_request("INVALID_COUNTRY", "Unknown country code"))?;
+    Ok(Some(code.to_string()))
+}
+
+/// Validate and normalize a `#rrggbb` profile color (empty clears it)
+fn validate_profile_color(color: &str) -> Result<Option<String>, ApiError> {
+    if color.is_empty() {
+        return Ok(None);
+    }
+    let valid = color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if !valid {
+        return Err(ApiError::bad_request(
+            "INVALID_PROFILE_COLOR",
+            "Profile color must be a hex color like #3b82f6",
+        ));
+    }
+    Ok(Some(color.to_ascii_lowercase()))
+}
+
+/// Reject profile edits while a moderator has locked the profile
+async fn ensure_profile_unlocked(state: &AppState, user_id: &str) -> Result<(), ApiError> {
+    if dguesser_db::profiles::get(state.db(), user_id).await?.locked {
+        return Err(ApiError::new(
+            axum::http::StatusCode::FORBIDDEN,
+            "PROFILE_LOCKED",
+            "Your profile has been locked by a moderator",
+        ));
+    }
+    Ok(())
+}
+
 /// Update current user's profile
 #[utoipa::path(
     put,
@@ -251,6 +513,7 @@ fn validate_avatar_url(url: &str) -> Result<(), ApiError> {
         (status = 200, description = "Profile updated", body = UserProfileResponse),
         (status = 400, description = "Invalid request"),
         (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Profile locked by a moderator"),
         (status = 409, description = "Username already taken"),
     ),
     tag = "users"
@@ -260,6 +523,30 @@ pub async fn update_profile(
     auth: AuthUser,
     Json(req): Json<UpdateProfileRequest>,
 ) -> Result<Json<UserProfileResponse>, ApiError> {
+    let customizes = req.display_name.is_some()
+        || req.avatar_url.is_some()
+        || req.bio.is_some()
+        || req.country_code.is_some()
+        || req.profile_color.is_some();
+    if customizes {
+        ensure_profile_unlocked(&state, &auth.user_id).await?;
+    }
+
+    let current = dguesser_db::users::get_by_id(state.db(), &auth.user_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("User"))?;
+    if current.kind == dguesser_db::UserKind::Guest
+        && (req.bio.is_some() || req.country_code.is_some() || req.profile_color.is_some())
+    {
+        return Err(ApiError::forbidden("Sign in to customize your profile"));
+    }
+
+    // Validate the new profile fields before changing anything
+    let display_name = req.display_name.as_deref().map(validate_display_name).transpose()?;
+    let bio = req.bio.as_deref().map(validate_bio).transpose()?;
+    let country_code = req.country_code.as_deref().map(validate_country_code).transpose()?;
+    let profile_color = req.profile_color.as_deref().map(validate_profile_color).transpose()?;
+
     // Validate and update username if provided
     if let Some(ref username) = req.username {
         validate_username(username)?;
@@ -267,8 +554,7 @@ pub async fn update_profile(
         // Check availability
         if !dguesser_db::users::is_username_available(state.db(), username).await? {
             // Check if it's our own current username
-            let current_user = dguesser_db::users::get_by_id(state.db(), &auth.user_id).await?;
-            if current_user.as_ref().and_then(|u| u.username.as_ref()) != Some(username) {
+            if current.username.as_ref() != Some(username) {
                 return Err(ApiError::conflict("USERNAME_TAKEN", "This username is already taken"));
             }
         }
@@ -276,26 +562,44 @@ pub async fn update_profile(
         dguesser_db::users::update_username(state.db(), &auth.user_id, Some(username)).await?;
     }
 
-    // Validate display name if provided
-    if let Some(ref name) = req.display_name
-        && (name.len() < 3 || name.len() > 50)
-    {
-        return Err(ApiError::bad_request(
-            "INVALID_DISPLAY_NAME",
-            "Display name must be between 3 and 50 characters",
-        ));
-    }
-
     // Update display name if provided
-    if let Some(ref name) = req.display_name {
-        dguesser_db::users::update_display_name(state.db(), &auth.user_id, name).await?;
+    if let Some(ref checked) = display_name {
+        dguesser_db::users::update_display_name(state.db(), &auth.user_id, &checked.name).await?;
+        record_display_name_flag(&state, &auth.user_id, checked).await;
     }
 
-    // Validate and update avatar if provided
-    if let Some(ref avatar) = req.avatar_url {
+    // Validate and update avatar if provided. Resubmitting the current avatar
+    // (e.g. an uploaded one) is a no-op.
+    if let Some(ref avatar) = req.avatar_url
+        && current.avatar_url.as_ref() != Some(avatar)
+    {
         validate_avatar_url(avatar)?;
         let avatar_option = if avatar.is_empty() { None } else { Some(avatar.as_str()) };
-        dguesser_db::users::update_avatar(state.db(), &auth.user_id, avatar_option).await?;
+        // Switching to a linked avatar discards any uploaded one
+        let previous = dguesser_db::profiles::set_uploaded_avatar(
+            state.db(),
+            &auth.user_id,
+            None,
+            avatar_option,
+        )
+        .await?;
+        if let Some(key) = previous {
+            delete_uploaded_avatar(&state, &key).await;
+        }
+    }
+
+    if bio.is_some()
+        || country_code.is_some()
+        || profile_color.is_some()
+        || req.presence_visibility.is_some()
+    {
+        let update = dguesser_db::profiles::ProfileUpdate {
+            bio: bio.as_ref().map(Option::as_deref),
+            country_code: country_code.as_ref().map(Option::as_deref),
+            profile_color: profile_color.as_ref().map(Option::as_deref),
+            presence_visibility: req.presence_visibility.map(PresenceVisibility::as_str),
+        };
+        dguesser_db::profiles::update(state.db(), &auth.user_id, &update).await?;
     }
 
     // Update leaderboard public setting if provided
@@ -310,7 +614,120 @@ pub async fn update_profile(
         .await?
         .ok_or_else(|| ApiError::not_found("User"))?;
 
-    Ok(Json(UserProfileResponse::from(user)))
+    Ok(Json(UserProfileResponse::load(&state, user).await?))
+}
+
+/// Upload a custom avatar
+///
+/// The body is the image itself (PNG, JPEG or WebP, at most 512 KiB). It
+/// replaces the current avatar. Only registered users can upload avatars.
+#[utoipa::path(
+    put,
+    path = "/api/v1/users/me/avatar",
+    request_body(content = Vec<u8>, description = "PNG, JPEG or WebP image", content_type = "image/*"),
+    responses(
+        (status = 200, description = "Avatar uploaded", body = UserProfileResponse),
+        (status = 400, description = "Not a supported image or too large"),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Guests can't upload avatars, or the profile is locked"),
+        (status = 503, description = "Uploads are not configured"),
+    ),
+    tag = "users"
+)]
+pub async fn upload_avatar(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    body: Bytes,
+) -> Result<Json<UserProfileResponse>, ApiError> {
+    let storage = state
+        .storage()
+        .ok_or_else(|| ApiError::service_unavailable("Avatar uploads are not available"))?;
+    let user = dguesser_db::users::get_by_id(state.db(), &auth.user_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("User"))?;
+    if user.kind == dguesser_db::UserKind::Guest {
+        return Err(ApiError::forbidden("Sign in to upload an avatar"));
+    }
+    ensure_profile_unlocked(&state, &auth.user_id).await?;
+
+    if body.len() > MAX_AVATAR_BYTES {
+        return Err(ApiError::bad_request(
+            "AVATAR_TOO_LARGE",
+            format!("Avatar must be at most {} KiB", MAX_AVATAR_BYTES / 1024),
+        ));
+    }
+    let format = ImageFormat::sniff(&body).ok_or_else(|| {
+        ApiError::bad_request("INVALID_AVATAR", "Avatar must be a PNG, JPEG or WebP image")
+    })?;
+
+    // A fresh key per upload so CDN caches never serve the old image
+    let key = format!(
+        "avatars/{}/{}.{}",
+        auth.user_id,
+        uuid::Uuid::new_v4().simple(),
+        format.extension()
+    );
+    storage.put(&key, body, format.content_type()).await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to store avatar");
+        ApiError::service_unavailable("Avatar upload failed, try again later")
+    })?;
+
+    let url = storage.public_url(&key);
+    let previous = dguesser_db::profiles::set_uploaded_avatar(
+        state.db(),
+        &auth.user_id,
+        Some(&key),
+        Some(&url),
+    )
+    .await?;
+    if let Some(previous) = previous {
+        delete_uploaded_avatar(&state, &previous).await;
+    }
+
+    let user = dguesser_db::users::get_by_id(state.db(), &auth.user_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("User"))?;
+    Ok(Json(UserProfileResponse::load(&state, user).await?))
+}
+
+/// Remove the current avatar
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/me/avatar",
+    responses(
+        (status = 200, description = "Avatar removed", body = UserProfileResponse),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Profile locked by a moderator"),
+    ),
+    tag = "users"
+)]
+pub async fn delete_avatar(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<Json<UserProfileResponse>, ApiError> {
+    ensure_profile_unlocked(&state, &auth.user_id).await?;
+
+    let previous =
+        dguesser_db::profiles::set_uploaded_avatar(state.db(), &auth.user_id, None, None).await?;
+    if let Some(previous) = previous {
+        delete_uploaded_avatar(&state, &previous).await;
+    }
+
+    let user = dguesser_db::users::get_by_id(state.db(), &auth.user_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("User"))?;
+    Ok(Json(UserProfileResponse::load(&state, user).await?))
+}
+
+/// Delete a replaced or reset avatar from storage. Failures only leave an
+/// orphaned object behind, so they are logged and ignored.
+pub(crate) async fn delete_uploaded_avatar(state: &AppState, key: &str) {
+    let Some(storage) = state.storage() else {
+        return;
+    };
+    if let Err(e) = storage.delete(key).await {
+        tracing::warn!(key, error = %e, "Failed to delete old avatar");
+    }
 }
 
 /// Check if a viewer has permission to see a user's profile.
@@ -367,7 +784,38 @@ pub async fn get_user_profile(
         return Err(ApiError::not_found("User"));
     }
 
-    Ok(Json(UserProfileResponse::from(user)))
+    Ok(Json(UserProfileResponse::load(&state, user).await?))
+}
+
+/// Get a user's presence
+///
+/// Shows whether the user is online, in a lobby or in a game (with mode and
+/// map). Users choose who may see this; anyone else sees `offline`.
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/{id}/presence",
+    params(
+        ("id" = String, Path, description = "User ID (e.g., usr_V1StGXR8_Z5j)")
+    ),
+    responses(
+        (status = 200, description = "User presence", body = UserPresenceResponse),
+        (status = 404, description = "User not found"),
+    ),
+    tag = "users"
+)]
+pub async fn get_user_presence(
+    State(state): State<AppState>,
+    maybe_auth: MaybeAuthUser,
+    Path(id): Path<String>,
+) -> Result<Json<UserPresenceResponse>, ApiError> {
+    let user = dguesser_db::users::get_by_id(state.db(), &id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("User"))?;
+
+    let viewer_id = maybe_auth.0.as_ref().map(|auth| auth.user_id.as_str());
+    let presence = crate::presence::visible_presence(&state, viewer_id, &user.id).await?;
+
+    Ok(Json(UserPresenceResponse { user_id: user.id, presence }))
 }
 
 /// Get a user's public profile by username
@@ -397,7 +845,162 @@ pub async fn get_user_by_username(
         return Err(ApiError::not_found("User"));
     }
 
-    Ok(Json(UserProfileResponse::from(user)))
+    Ok(Json(UserProfileResponse::load(&state, user).await?))
+}
+
+/// Look up a user whose public profile the viewer may see.
+///
+/// Public profiles are opt-in: they exist for users who made themselves
+/// public on the leaderboard. Users can always preview their own.
+async fn find_public_user(
+    state: &AppState,
+    viewer: &MaybeAuthUser,
+    username: &str,
+) -> Result<dguesser_db::User, ApiError> {
+    let user = dguesser_db::users::get_by_username(state.db_read(), username)
+        .await?
+        .ok_or_else(|| ApiError::not_found("User"))?;
+
+    let is_self = viewer.0.as_ref().is_some_and(|auth| auth.user_id == user.id);
+    if !user.leaderboard_public && !is_self {
+        return Err(ApiError::not_found("User"));
+    }
+    Ok(user)
+}
+
+/// Load the stats shown on public profiles and cards
+async fn load_public_stats(
+    state: &AppState,
+    user: &dguesser_db::User,
+) -> Result<PublicProfileStats, ApiError> {
+    let db = state.db_read();
+    let guesses = dguesser_db::profiles::get_guess_stats(db, &user.id).await?;
+    let scope = Scope::default();
+    let rank = LeaderboardCache::get_user_rank(
+        state,
+        &LeaderboardType::TotalScore,
+        &TimePeriod::AllTime,
+        &scope,
+        &user.id,
+    )
+    .await?
+    .map(|(rank, _)| rank);
+    let streak = LeaderboardCache::get_user_rank(
+        state,
+        &LeaderboardType::Streak,
+        &TimePeriod::AllTime,
+        &scope,
+        &user.id,
+    )
+    .await?;
+
+    Ok(PublicProfileStats {
+        games_played: user.games_played,
+        total_score: user.total_score,
+        best_score: user.best_score,
+        average_score: (user.games_played > 0)
+            .then(|| user.total_score as f64 / user.games_played as f64),
+        rounds_played: guesses.rounds_played,
+        average_distance_meters: guesses.average_distance_meters,
+        best_streak: streak.map(|(_, score)| score),
+        rank,
+    })
+}
+
+/// Get a user's public profile with stats
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/{username}/public",
+    params(
+        ("username" = String, Path, description = "Username (e.g., coolplayer42)")
+    ),
+    responses(
+        (status = 200, description = "Public profile", body = PublicProfileResponse),
+        (status = 404, description = "User not found or profile not public"),
+    ),
+    tag = "users"
+)]
+pub async fn get_public_profile(
+    State(state): State<AppState>,
+    maybe_auth: MaybeAuthUser,
+    Path(username): Path<String>,
+) -> Result<Json<PublicProfileResponse>, ApiError> {
+    let user = find_public_user(&state, &maybe_auth, &username).await?;
+    let stats = load_public_stats(&state, &user).await?;
+    let profile = dguesser_db::profiles::get(state.db_read(), &user.id).await?;
+    let username = user.username.unwrap_or(username);
+
+    Ok(Json(PublicProfileResponse {
+        card_url: format!("/api/v1/users/{username}/card"),
+        username,
+        display_name: user.display_name,
+        avatar_url: user.avatar_url,
+        bio: profile.bio,
+        country_code: profile.country_code,
+        profile_color: profile.profile_color,
+        member_since: user.created_at,
+        stats,
+    }))
+}
+
+/// Render a shareable stats card for a public profile
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/{username}/card",
+    params(
+        ("username" = String, Path, description = "Username (e.g., coolplayer42)"),
+        ("format" = Option<String>, Query, description = "Image format: png (default) or svg"),
+    ),
+    responses(
+        (status = 200, description = "Stats card image (PNG, or SVG with `format=svg`)", content_type = "image/png"),
+        (status = 404, description = "User not found or profile not public"),
+    ),
+    tag = "users"
+)]
+pub async fn get_profile_card(
+    State(state): State<AppState>,
+    maybe_auth: MaybeAuthUser,
+    Path(username): Path<String>,
+    Query(query): Query<CardQuery>,
+) -> Result<Response, ApiError> {
+    let user = find_public_user(&state, &maybe_auth, &username).await?;
+    let stats = load_public_stats(&state, &user).await?;
+    let profile = dguesser_db::profiles::get(state.db_read(), &user.id).await?;
+
+    let scene = ProfileCard {
+        display_name: &user.display_name,
+        username: user.username.as_deref(),
+        country_code: profile.country_code.as_deref(),
+        profile_color: profile.profile_color.as_deref(),
+        games_played: stats.games_played.into(),
+        total_score: stats.total_score,
+        best_score: stats.best_score.into(),
+        best_streak: stats.best_streak,
+        rank: stats.rank,
+    }
+    .scene();
+
+    card_response(scene, query.format).await
+}
+
+/// Render a card off the async runtime and wrap it in an image response
+pub(crate) async fn card_response(
+    scene: crate::render::Scene,
+    format: CardFormat,
+) -> Result<Response, ApiError> {
+    let image = tokio::task::spawn_blocking(move || scene.render(format)).await.map_err(|e| {
+        tracing::error!(error = %e, "Card rendering panicked");
+        ApiError::internal()
+    })?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type()),
+            (header::CACHE_CONTROL, CARD_CACHE_CONTROL),
+        ],
+        image,
+    )
+        .into_response())
 }
 
 /// Delete current user's account (soft delete)
@@ -424,3 +1027,132 @@ pub async fn delete_account(
         message: "Your account has been scheduled for deletion. You have 30 days to recover it by signing in again.".to_string(),
     }))
 }
+
+/// List OAuth providers linked to the current account
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/me/oauth",
+    responses(
+        (status = 200, description = "Linked providers", body = LinkedProvidersResponse),
+        (status = 401, description = "Not authenticated"),
+    ),
+    tag = "users"
+)]
+pub async fn list_linked_providers(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<Json<LinkedProvidersResponse>, ApiError> {
+    let accounts = dguesser_db::oauth::get_accounts_for_user(state.db(), &auth.user_id).await?;
+
+    let providers = accounts
+        .into_iter()
+        .map(|a| LinkedProviderInfo {
+            provider: a.provider,
+            email: a.provider_email,
+            linked_at: a.created_at,
+        })
+        .collect();
+
+    Ok(Json(LinkedProvidersResponse { providers }))
+}
+
+/// Unlink an OAuth provider from the current account
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/me/oauth/{provider}",
+    params(
+        ("provider" = String, Path, description = "OAuth provider (google or microsoft)")
+    ),
+    responses(
+        (status = 200, description = "Provider unlinked", body = UnlinkProviderResponse),
+        (status = 400, description = "Unknown provider"),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "Provider not linked"),
+        (status = 409, description = "Provider is the last login method"),
+    ),
+    tag = "users"
+)]
+pub async fn unlink_provider(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(provider): Path<String>,
+) -> Result<Json<UnlinkProviderResponse>, ApiError> {
+    let provider: OAuthProvider = provider
+        .parse()
+        .map_err(|_| ApiError::bad_request("INVALID_PROVIDER", "Unknown OAuth provider"))?;
+
+    unlink_oauth_account(state.db(), &auth.user_id, provider).await?;
+
+    Ok(Json(UnlinkProviderResponse { message: format!("Unlinked {} account", provider) }))
+}
+
+/// Get the current user's email preferences
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/me/email-preferences",
+    responses(
+        (status = 200, description = "Email preferences", body = EmailPreferences),
+        (status = 401, description = "Not authenticated"),
+    ),
+    tag = "users"
+)]
+pub async fn get_email_preferences(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<Json<EmailPreferences>, ApiError> {
+    let weekly_digest = dguesser_db::emails::get_weekly_digest(state.db(), &auth.user_id).await?;
+
+    Ok(Json(EmailPreferences { weekly_digest }))
+}
+
+/// Update the current user's email preferences
+#[utoipa::path(
+    put,
+    path = "/api/v1/users/me/email-preferences",
+    request_body = EmailPreferences,
+    responses(
+        (status = 200, description = "Updated email preferences", body = EmailPreferences),
+        (status = 401, description = "Not authenticated"),
+    ),
+    tag = "users"
+)]
+pub async fn update_email_preferences(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Json(req): Json<EmailPreferences>,
+) -> Result<Json<EmailPreferences>, ApiError> {
+    dguesser_db::emails::set_weekly_digest(state.db(), &auth.user_id, req.weekly_digest).await?;
+
+    Ok(Json(req))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bio_is_trimmed_and_limited() {
+        assert_eq!(validate_bio("  hello  ").unwrap(), Some("hello".to_string()));
+        assert_eq!(validate_bio("   ").unwrap(), None);
+        assert!(validate_bio(&"é".repeat(MAX_BIO_CHARS)).is_ok());
+        assert!(validate_bio(&"a".repeat(MAX_BIO_CHARS + 1)).is_err());
+        assert!(validate_bio("bell\u{7}").is_err());
+    }
+
+    #[test]
+    fn country_code_must_be_known() {
+        assert_eq!(validate_country_code("se").unwrap(), Some("SE".to_string()));
+        assert_eq!(validate_country_code("").unwrap(), None);
+        assert!(validate_country_code("XX").is_err());
+        assert!(validate_country_code("SWE").is_err());
+    }
+
+    #[test]
+    fn profile_color_is_hex() {
+        assert_eq!(validate_profile_color("#3B82F6").unwrap(), Some("#3b82f6".to_string()));
+        assert_eq!(validate_profile_color("").unwrap(), None);
+        assert!(validate_profile_color("3b82f6").is_err());
+        assert!(validate_profile_color("#3b82f").is_err());
+        assert!(validate_profile_color("#gggggg").is_err());
+    }
+}
@@ -0,0 +1,273 @@
+//! Runtime config reloading
+//!
+//! Rate limit multipliers, extra CORS origins, response cache lifetimes and
+//! grace periods can be changed without a restart. `POST /admin/reload` stores new
+//! [`RuntimeSettings`] in Redis under an increasing version; every API and
+//! realtime instance polls the version and applies newer settings. The
+//! instance that handled the request applies them right away.
+
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use chrono::Utc;
+use dguesser_protocol::api::admin::{RUNTIME_SETTINGS_KEY, RuntimeSettings, StoredRuntimeSettings};
+use dguesser_redis::RedisConnection;
+use redis::{AsyncCommands, Script};
+
+use crate::cache::response::CACHED_ROUTES;
+use crate::state::AppState;
+
+/// How often instances check for new settings
+const POLL_INTERVAL_SECS: u64 = 10;
+
+/// Allowed rate limit multipliers
+const MULTIPLIERS: std::ops::RangeInclusive<u32> = 1..=100;
+
+/// Allowed party host grace periods, in seconds
+const PARTY_HOST_GRACE_SECS: std::ops::RangeInclusive<u64> = 5..=600;
+
+/// Allowed reconnection grace periods, in seconds (as for game settings)
+const RECONNECT_GRACE_SECS: std::ops::RangeInclusive<u32> = 5..=300;
+
+/// Longest response cache lifetime (fresh plus stale), in seconds
+const MAX_CACHE_TTL_SECS: u64 = 7 * 24 * 3600;
+
+/// Spawn the background task that applies settings stored by other
+/// instances.
+pub fn spawn_config_watcher(state: AppState) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(POLL_INTERVAL_SECS));
+
+        loop {
+            interval.tick().await;
+
+            match load(state.redis()).await {
+                Ok(Some(stored)) if stored.version > state.runtime_config().version => {
+                    apply(&state, stored);
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!(error = %e, "Failed to load runtime config"),
+            }
+        }
+    });
+
+    tracing::info!(interval_secs = POLL_INTERVAL_SECS, "Runtime config watcher started");
+}
+
+/// Read the stored settings, if any were ever stored.
+pub async fn load(
+    redis: &RedisConnection,
+) -> Result<Option<StoredRuntimeSettings>, redis::RedisError> {
+    let mut conn = redis.clone();
+    let json: Option<String> = conn.get(RUNTIME_SETTINGS_KEY).await?;
+    Ok(json.and_then(|json| match serde_json::from_str(&json) {
+        Ok(stored) => Some(stored),
+        Err(e) => {
+            tracing::error!(error = %e, "Stored runtime config is invalid, ignoring it");
+            None
+        }
+    }))
+}
+
+/// Store the settings under a version, unless another reload already took
+/// it: returns 0 then, and the caller retries with the next version. The
+/// version and the settings change together, so they always match.
+static STORE: LazyLock<Script> = LazyLock::new(|| {
+    Script::new(
+        r"
+        local current = tonumber(redis.call('GET', KEYS[2]) or '0')
+        if current + 1 ~= tonumber(ARGV[1]) then
+            return 0
+        end
+        redis.call('SET', KEYS[2], ARGV[1])
+        redis.call('SET', KEYS[1], ARGV[2])
+        return 1
+        ",
+    )
+});
+
+/// Store new settings under the next version.
+pub async fn store(
+    redis: &RedisConnection,
+    settings: RuntimeSettings,
+    updated_by: &str,
+) -> Result<StoredRuntimeSettings, redis::RedisError> {
+    let mut conn = redis.clone();
+    // Hash-tagged with the settings key so both share a Redis Cluster slot
+    let version_key = format!("{{{RUNTIME_SETTINGS_KEY}}}:version");
+    loop {
+        let current: Option<u64> = conn.get(&version_key).await?;
+        let stored = StoredRuntimeSettings {
+            version: current.unwrap_or(0) + 1,
+            settings: settings.clone(),
+            updated_by: Some(updated_by.to_string()),
+            updated_at: Some(Utc::now()),
+        };
+        let json = serde_json::to_string(&stored).expect("runtime settings serialize");
+        let written: bool = STORE
+            .key(RUNTIME_SETTINGS_KEY)
+            .key(&version_key)
+            .arg(stored.version)
+            .arg(json)
+            .invoke_async(&mut conn)
+            .await?;
+        if written {
+            return Ok(stored);
+        }
+    }
+}
+
+/// Apply settings to this instance. Returns the names of the settings that
+/// changed.
+pub fn apply(state: &AppState, stored: StoredRuntimeSettings) -> Vec<String> {
+    let previous = state.set_runtime_config(stored.clone());
+    let changed = changed_settings(&previous.settings, &stored.settings);
+    tracing::info!(
+        version = stored.version,
+        updated_by = stored.updated_by.as_deref().unwrap_or("-"),
+        changed = ?changed,
+        "Applied runtime config"
+    );
+    changed
+}
+
+/// Check settings before they are stored.
+pub fn validate(settings: &RuntimeSettings) -> Result<(), String> {
+    let multipliers = [
+        ("rate_limit_guest_multiplier", settings.rate_limit_guest_multiplier),
+        ("rate_limit_registered_multiplier", settings.rate_limit_registered_multiplier),
+        ("rate_limit_admin_multiplier", settings.rate_limit_admin_multiplier),
+        ("socket_rate_limit_multiplier", settings.socket_rate_limit_multiplier),
+    ];
+    for (name, value) in multipliers {
+        if value.is_some_and(|v| !MULTIPLIERS.contains(&v)) {
+            return Err(format!(
+                "{name} must be between {} and {}",
+                MULTIPLIERS.start(),
+                MULTIPLIERS.end()
+            ));
+        }
+    }
+
+    for origin in &settings.extra_cors_origins {
+        if !is_origin(origin) {
+            return Err(format!("{origin:?} is not an origin like https://example.com"));
+        }
+    }
+
+    for (namespace, ttl) in &settings.cache_ttls {
+        if !CACHED_ROUTES.iter().any(|route| route.namespace == namespace) {
+            return Err(format!("Unknown cache namespace {namespace:?}"));
+        }
+        if ttl.fresh_secs == 0 || ttl.fresh_secs + ttl.stale_secs > MAX_CACHE_TTL_SECS {
+            return Err(format!(
+                "Cache lifetime for {namespace:?} must be between 1 and {MAX_CACHE_TTL_SECS} seconds"
+            ));
+        }
+    }
+
+    if settings.party_host_grace_secs.is_some_and(|v| !PARTY_HOST_GRACE_SECS.contains(&v)) {
+        return Err(format!(
+            "party_host_grace_secs must be between {} and {}",
+            PARTY_HOST_GRACE_SECS.start(),
+            PARTY_HOST_GRACE_SECS.end()
+        ));
+    }
+
+    if settings.reconnect_grace_secs.is_some_and(|v| !RECONNECT_GRACE_SECS.contains(&v)) {
+        return Err(format!(
+            "reconnect_grace_secs must be between {} and {}",
+            RECONNECT_GRACE_SECS.start(),
+            RECONNECT_GRACE_SECS.end()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Whether a string is a bare `http(s)://host[:port]` origin
+fn is_origin(origin: &str) -> bool {
+    let Some(host) = origin.strip_prefix("https://").or_else(|| origin.strip_prefix("http://"))
+    else {
+        return false;
+    };
+    !host.is_empty()
+        && host.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ':'))
+}
+
+/// Names of the settings that differ between `old` and `new`
+fn changed_settings(old: &RuntimeSettings, new: &RuntimeSettings) -> Vec<String> {
+    let old = serde_json::to_value(old).unwrap_or_default();
+    let new = serde_json::to_value(new).unwrap_or_default();
+    let (Some(old), Some(new)) = (old.as_object(), new.as_object()) else {
+        return Vec::new();
+    };
+    new.iter()
+        .filter(|(name, value)| old.get(*name) != Some(value))
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use dguesser_protocol::api::admin::CacheTtl;
+
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(&RuntimeSettings::default()).is_ok());
+
+        let valid = RuntimeSettings {
+            rate_limit_registered_multiplier: Some(3),
+            extra_cors_origins: vec!["https://staging.example.com".into()],
+            cache_ttls: [("maps".to_string(), CacheTtl { fresh_secs: 10, stale_secs: 60 })].into(),
+            party_host_grace_secs: Some(60),
+            reconnect_grace_secs: Some(120),
+            ..Default::default()
+        };
+        assert!(validate(&valid).is_ok());
+
+        let invalid = [
+            RuntimeSettings { rate_limit_guest_multiplier: Some(0), ..Default::default() },
+            RuntimeSettings { socket_rate_limit_multiplier: Some(1000), ..Default::default() },
+            RuntimeSettings {
+                extra_cors_origins: vec!["https://example.com/".into()],
+                ..Default::default()
+            },
+            RuntimeSettings {
+                extra_cors_origins: vec!["example.com".into()],
+                ..Default::default()
+            },
+            RuntimeSettings {
+                cache_ttls: [("nope".to_string(), CacheTtl { fresh_secs: 10, stale_secs: 0 })]
+                    .into(),
+                ..Default::default()
+            },
+            RuntimeSettings {
+                cache_ttls: [("maps".to_string(), CacheTtl { fresh_secs: 0, stale_secs: 60 })]
+                    .into(),
+                ..Default::default()
+            },
+            RuntimeSettings { party_host_grace_secs: Some(1), ..Default::default() },
+            RuntimeSettings { reconnect_grace_secs: Some(600), ..Default::default() },
+        ];
+        for settings in invalid {
+            assert!(validate(&settings).is_err(), "{settings:?} should be invalid");
+        }
+    }
+
+    #[test]
+    fn test_changed_settings() {
+        let old = RuntimeSettings::default();
+        let new = RuntimeSettings {
+            rate_limit_admin_multiplier: Some(10),
+            extra_cors_origins: vec!["https://a.example.com".into()],
+            ..Default::default()
+        };
+        let mut changed = changed_settings(&old, &new);
+        changed.sort();
+        assert_eq!(changed, ["extra_cors_origins", "rate_limit_admin_multiplier"]);
+        assert!(changed_settings(&new, &new).is_empty());
+    }
+}
@@ -4,13 +4,13 @@
 //! These events are received by the realtime server's Redis adapter
 //! and forwarded to connected clients.
 
-use redis::aio::MultiplexedConnection;
+use dguesser_redis::RedisConnection;
 use serde::Serialize;
 use socketioxide_emitter::{Driver, IoEmitter};
 
 /// Redis driver for socketioxide-emitter
 #[derive(Clone)]
-struct RedisDriver(MultiplexedConnection);
+struct RedisDriver(RedisConnection);
 
 impl Driver for RedisDriver {
     type Error = redis::RedisError;
@@ -24,13 +24,12 @@ impl Driver for RedisDriver {
 
 /// Emit a Socket.IO event to all clients in a room
 pub async fn emit_to_room<T: Serialize>(
-    redis: &redis::Client,
+    redis: &RedisConnection,
     room: &str,
     event: &str,
     payload: &T,
 ) -> Result<(), SocketEmitError> {
-    let conn = redis.get_multiplexed_async_connection().await?;
-    let driver = RedisDriver(conn);
+    let driver = RedisDriver(redis.clone());
 
     IoEmitter::new()
         .to(room.to_string())
@@ -1,17 +1,35 @@
 //! Application state
 
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use std::time::Instant;
 
-use dguesser_auth::{GoogleOAuth, MicrosoftOAuth, OAuthStateStore, SessionConfig};
-use dguesser_core::location::LocationProvider;
-use dguesser_db::{DbPool, LocationRepository};
-use dguesser_locations::reader::{FileReader, HttpReader};
-use dguesser_locations::{PackProvider, PackProviderConfig};
-
-use crate::config::{Config, LocationProviderType};
+use dguesser_auth::{
+    GoogleOAuth, MicrosoftOAuth, OAuthStateStore, SessionConfig, SocketTokenSigner,
+};
+use dguesser_core::location::{LocationProvider, MapLocationSource};
+use dguesser_db::{DbPool, DbPools, LocationRepository};
+use dguesser_locations::reader::{FileReader, HttpReader, RangeReader};
+use dguesser_locations::{
+    CachedReader, LocationHealth, PackProvider, PackProviderConfig, RangeCache, RangeCacheConfig,
+    RecentLocations, RoutedProvider,
+};
+use dguesser_protocol::api::admin::{CacheTtl, StoredRuntimeSettings};
+use dguesser_push::WebPushClient;
+use dguesser_redis::RedisConnection;
+use tokio::sync::Notify;
+
+use crate::captcha::CaptchaClient;
+use crate::config::{Config, JoinCodeConfig, LocationProviderType, R2LocationConfig};
+use crate::map_tiles::MapTilesClient;
 use crate::middleware::client_ip::ClientIpConfig;
-use crate::middleware::rate_limit::{FallbackRateLimiter, create_fallback_limiter};
+use crate::middleware::org::OrgCache;
+use crate::middleware::rate_limit::{
+    FallbackRateLimiter, RateLimitRoutes, RateLimitTiers, create_fallback_limiter,
+};
+use crate::score_chain::ScoreChain;
+use crate::static_map::StaticMapClient;
+use crate::storage::ObjectStorage;
+use crate::street_view::StreetViewClient;
 
 /// Shared application state
 #[derive(Clone)]
@@ -20,35 +38,81 @@ pub struct AppState {
 }
 
 struct AppStateInner {
-    db: DbPool,
-    redis: redis::Client,
+    db: DbPools,
+    /// Shared connection for every Redis command, following failovers
+    redis: RedisConnection,
+    /// Wakes the socket event relay after events are queued
+    outbox_wakeup: Arc<Notify>,
     oauth_state_store: OAuthStateStore,
     session_config: SessionConfig,
     google_oauth: Option<GoogleOAuth>,
     microsoft_oauth: Option<MicrosoftOAuth>,
     frontend_url: String,
     location_provider: Arc<dyn LocationProvider>,
+    /// Readiness checks for the location provider and pack storage
+    location_health: LocationHealth,
+    /// Pack range cache (when packs are enabled and the cache is not disabled)
+    pack_cache: Option<Arc<RangeCache>>,
+    /// Recently served locations per player (unless disabled)
+    recent_locations: Option<RecentLocations>,
     started_at: Instant,
     is_production: bool,
     /// Configuration for secure client IP extraction
     client_ip_config: ClientIpConfig,
     /// In-memory fallback rate limiter for when Redis is unavailable
     fallback_rate_limiter: Arc<FallbackRateLimiter>,
+    /// Per-tier rate limit multipliers the server was started with
+    rate_limit_tiers: RateLimitTiers,
+    /// Limit and algorithm of each rate limited route group
+    rate_limits: RateLimitRoutes,
+    /// Settings reloaded at runtime (see [`crate::runtime_config`])
+    runtime_config: RwLock<StoredRuntimeSettings>,
+    /// Signer for realtime socket handshake tokens (if configured)
+    socket_token_signer: Option<SocketTokenSigner>,
+    /// Signer for solo round results (if configured)
+    score_chain: Option<ScoreChain>,
+    /// Street View metadata and short link client
+    street_view: StreetViewClient,
+    /// CAPTCHA verification for guest-heavy endpoints (if configured)
+    captcha: Option<CaptchaClient>,
+    /// Map Tiles sessions, tile tokens and tile usage
+    map_tiles: MapTilesClient,
+    /// Web Push client (if VAPID keys are configured)
+    push: Option<Arc<WebPushClient>>,
+    /// Multiplayer join code configuration
+    join_codes: JoinCodeConfig,
+    /// Share of client error reports stored
+    client_error_sample_rate: f64,
+    /// Object storage for avatar uploads and cached images (if configured)
+    storage: Option<Arc<dyn ObjectStorage>>,
+    /// Private bucket for archived games (if configured)
+    archive_storage: Option<Arc<dyn ObjectStorage>>,
+    /// Static map images for round result cards
+    static_map: StaticMapClient,
+    /// Organizations resolved from request hosts and headers
+    orgs: OrgCache,
 }
 
 impl AppState {
     /// Create new application state from config
     pub async fn new(config: &Config) -> anyhow::Result<Self> {
-        // Create database pool
-        let db = dguesser_db::create_pool(&config.database_url).await?;
-        tracing::info!("Connected to database");
+        // Create database pools (the replica serves heavy reads when configured)
+        let pools = DbPools::connect(
+            &config.database_url,
+            config.database_replica_url.as_deref(),
+            config.database_replica_max_lag,
+        )
+        .await?;
+        let db = pools.primary().clone();
+        tracing::info!(replica = pools.has_replica(), "Connected to database");
 
         // Run migrations
-        sqlx::migrate!("../../migrations").run(&db).await?;
+        dguesser_db::schema::migrate(&db).await?;
         tracing::info!("Database migrations completed");
 
-        // Create Redis client and OAuth state store
-        let redis = redis::Client::open(config.redis_url.as_str())?;
+        // Connect to Redis (directly, through Sentinel or as a cluster) and
+        // create the OAuth state store
+        let redis = RedisConnection::connect(&config.redis).await?;
         let oauth_state_store = OAuthStateStore::new(redis.clone());
         tracing::info!("Connected to Redis");
 
@@ -88,73 +152,53 @@ impl AppState {
             None
         };
 
-        // Create location provider based on config
-        let location_provider: Arc<dyn LocationProvider> = match config.location_provider_type {
-            LocationProviderType::Postgres => {
+        // Maps are served from Postgres or from R2 packs depending on their
+        // source; LOCATION_PROVIDER picks the default for maps without one
+        let database: Arc<dyn LocationProvider> = Arc::new(LocationRepository::new(db.clone()));
+        let default_source = match config.location_provider_type {
+            LocationProviderType::Postgres => MapLocationSource::Database,
+            LocationProviderType::R2 => MapLocationSource::Pack,
+        };
+        let pack_cache = match &config.r2_location_config {
+            Some(r2_config) => range_cache(r2_config, &redis),
+            None => None,
+        };
+        let location_provider: Arc<dyn LocationProvider> = match &config.r2_location_config {
+            None => {
+                if default_source == MapLocationSource::Pack {
+                    tracing::warn!(
+                        "LOCATION_PROVIDER=r2 but LOCATION_R2_URL is not set, using PostgreSQL"
+                    );
+                }
                 tracing::info!("Using PostgreSQL location provider");
-                Arc::new(LocationRepository::new(db.clone()))
+                database
             }
-            LocationProviderType::R2 => {
-                let r2_config = config
-                    .r2_location_config
-                    .as_ref()
-                    .expect("R2 config required when LOCATION_PROVIDER=r2");
-
+            Some(r2_config) => {
                 let pack_config = PackProviderConfig {
                     cache_indexes: true,
                     max_disabled_cache: r2_config.max_disabled_cache,
+                    ..PackProviderConfig::default()
                 };
 
-                // Load maps from database to register with PackProvider
-                let maps = dguesser_db::locations::list_maps(&db).await.unwrap_or_default();
-                let map_count = maps.len();
-
                 if let Some(local_path) = r2_config.local_path() {
-                    tracing::info!(path = %local_path, version = %r2_config.version, "Using local file location provider");
+                    tracing::info!(path = %local_path, version = %r2_config.version, default = %default_source, "Using local file location packs");
                     let reader = FileReader::new(local_path, &r2_config.version);
-                    let provider = Arc::new(PackProvider::new(reader, pack_config));
-
-                    // Register maps with provider
-                    for map in maps {
-                        tracing::debug!(map_id = %map.id, slug = %map.slug, "Registering map");
-                        provider.register_map(map).await;
-                    }
-                    tracing::info!("Registered {} maps with R2 location provider", map_count);
-
-                    // Spawn background task to warm the cache
-                    let provider_clone = Arc::clone(&provider);
-                    tokio::spawn(async move {
-                        if let Err(e) = provider_clone.warm_cache().await {
-                            tracing::warn!(error = %e, "Location cache warm-up failed");
-                        }
-                    });
-
-                    provider as Arc<dyn LocationProvider>
+                    routed_provider(database, reader, &pack_cache, pack_config, default_source)
                 } else {
-                    tracing::info!(url = %r2_config.base_url, version = %r2_config.version, "Using R2 HTTP location provider");
+                    tracing::info!(url = %r2_config.base_url, version = %r2_config.version, default = %default_source, "Using R2 HTTP location packs");
                     let reader = HttpReader::new(&r2_config.base_url, &r2_config.version);
-                    let provider = Arc::new(PackProvider::new(reader, pack_config));
-
-                    // Register maps with provider
-                    for map in maps {
-                        tracing::debug!(map_id = %map.id, slug = %map.slug, "Registering map");
-                        provider.register_map(map).await;
-                    }
-                    tracing::info!("Registered {} maps with R2 location provider", map_count);
-
-                    // Spawn background task to warm the cache
-                    let provider_clone = Arc::clone(&provider);
-                    tokio::spawn(async move {
-                        if let Err(e) = provider_clone.warm_cache().await {
-                            tracing::warn!(error = %e, "Location cache warm-up failed");
-                        }
-                    });
-
-                    provider as Arc<dyn LocationProvider>
+                    routed_provider(database, reader, &pack_cache, pack_config, default_source)
                 }
             }
         };
 
+        let location_health = LocationHealth::new(
+            location_provider.clone(),
+            config.r2_location_config.as_ref().map(pack_storage),
+        );
+
+        let recent_locations = recent_locations(config.location_repeat_window, &redis);
+
         // Create client IP config for secure IP extraction
         let client_ip_config = ClientIpConfig::from_config(config);
         tracing::info!(
@@ -166,35 +210,147 @@ impl AppState {
         // Create fallback rate limiter (50 req/min per IP, per instance)
         let fallback_rate_limiter = create_fallback_limiter(100);
         tracing::info!("Created fallback rate limiter");
+        tracing::info!(tiers = ?config.rate_limit_tiers, "Configured rate limit tiers");
+        for (group, limits) in [
+            ("api", &config.rate_limits.api),
+            ("auth", &config.rate_limits.auth),
+            ("game", &config.rate_limits.game),
+            ("telemetry", &config.rate_limits.telemetry),
+            ("streetview", &config.rate_limits.streetview),
+            ("map_tiles", &config.rate_limits.map_tiles),
+        ] {
+            tracing::info!(
+                group,
+                max_requests = limits.max_requests,
+                window_secs = limits.window_secs,
+                algorithm = limits.algorithm.as_str(),
+                "Configured rate limit"
+            );
+        }
+
+        let socket_token_signer = config.socket_token_secret.as_deref().map(SocketTokenSigner::new);
+        if socket_token_signer.is_none() {
+            tracing::warn!("SOCKET_TOKEN_SECRET not set, socket handshake tokens disabled");
+        }
+
+        let score_chain = config.score_signing_secret.as_deref().map(ScoreChain::new);
+        if score_chain.is_none() {
+            tracing::warn!("SCORE_SIGNING_SECRET not set, solo scores are not signed");
+        }
+
+        let street_view = StreetViewClient::new(config.google_maps_api_key.clone())
+            .with_quota_store(redis.clone());
+        if !street_view.can_validate() {
+            tracing::warn!("GOOGLE_MAPS_API_KEY not set, panorama validation disabled");
+        }
+
+        let captcha = config.captcha.as_ref().map(|captcha| captcha.build());
+        match &captcha {
+            Some(captcha) => {
+                tracing::info!(provider = captcha.provider().as_str(), "CAPTCHA configured")
+            }
+            None => tracing::info!("CAPTCHA_PROVIDER not set, CAPTCHA disabled"),
+        }
+
+        let map_tiles = MapTilesClient::new(
+            config.google_maps_api_key.clone(),
+            config.map_tile_token_secret.as_deref(),
+            redis.clone(),
+        );
+        if !map_tiles.is_configured() {
+            tracing::warn!(
+                "GOOGLE_MAPS_API_KEY or MAP_TILE_TOKEN_SECRET not set, map tiles disabled"
+            );
+        }
+
+        let push = match &config.push {
+            Some(push_config) => Some(Arc::new(push_config.build()?)),
+            None => {
+                tracing::warn!("VAPID keys not set, push notifications disabled");
+                None
+            }
+        };
+
+        let static_map = StaticMapClient::new(config.google_maps_api_key.clone());
+        if !static_map.is_configured() {
+            tracing::warn!("GOOGLE_MAPS_API_KEY not set, round cards render without maps");
+        }
+
+        let storage = config.storage.as_ref().map(|storage_config| storage_config.build());
+        match &storage {
+            Some(storage) => tracing::info!(backend = storage.name(), "Object storage configured"),
+            None => tracing::warn!("Object storage not configured, avatar uploads disabled"),
+        }
+        let archive_storage = config.game_archive.as_ref().map(|archive| archive.storage.build());
 
         Ok(Self {
             inner: Arc::new(AppStateInner {
-                db,
+                db: pools,
                 redis,
+                outbox_wakeup: Arc::new(Notify::new()),
                 oauth_state_store,
                 session_config,
                 google_oauth,
                 microsoft_oauth,
                 frontend_url: config.frontend_url.clone(),
                 location_provider,
+                location_health,
+                pack_cache,
+                recent_locations,
                 started_at: Instant::now(),
                 is_production: config.is_production,
                 client_ip_config,
                 fallback_rate_limiter,
+                rate_limit_tiers: config.rate_limit_tiers.clone(),
+                rate_limits: config.rate_limits.clone(),
+                runtime_config: RwLock::new(StoredRuntimeSettings::default()),
+                socket_token_signer,
+                score_chain,
+                street_view,
+                captcha,
+                map_tiles,
+                push,
+                join_codes: config.join_codes.clone(),
+                client_error_sample_rate: config.client_error_sample_rate,
+                storage,
+                archive_storage,
+                static_map,
+                orgs: OrgCache::new(config.org_base_domain.clone()),
             }),
         })
     }
 
-    /// Get the database connection pool
+    /// Get the primary database connection pool
     pub fn db(&self) -> &DbPool {
+        self.inner.db.primary()
+    }
+
+    /// Get the pool for heavy reads that tolerate replication lag (the
+    /// replica when configured and caught up, otherwise the primary)
+    pub fn db_read(&self) -> &DbPool {
+        self.inner.db.reader()
+    }
+
+    /// Get the primary and replica pools
+    pub fn db_pools(&self) -> &DbPools {
         &self.inner.db
     }
 
-    /// Get the Redis client
-    pub fn redis(&self) -> &redis::Client {
+    /// Get the shared Redis connection
+    pub fn redis(&self) -> &RedisConnection {
         &self.inner.redis
     }
 
+    /// Notified when socket events are queued (see [`crate::outbox`])
+    pub fn outbox_wakeup(&self) -> Arc<Notify> {
+        self.inner.outbox_wakeup.clone()
+    }
+
+    /// Publish socket events queued by a committed transaction
+    pub fn wake_outbox_relay(&self) {
+        self.inner.outbox_wakeup.notify_one();
+    }
+
     /// Get the OAuth state store for CSRF protection
     pub fn oauth_state_store(&self) -> &OAuthStateStore {
         &self.inner.oauth_state_store
@@ -225,6 +381,21 @@ impl AppState {
         self.inner.location_provider.as_ref()
     }
 
+    /// Get the location readiness checks
+    pub fn location_health(&self) -> &LocationHealth {
+        &self.inner.location_health
+    }
+
+    /// Get the pack range cache, if enabled
+    pub fn pack_cache(&self) -> Option<&RangeCache> {
+        self.inner.pack_cache.as_deref()
+    }
+
+    /// Get the recent location history store, if enabled
+    pub fn recent_locations(&self) -> Option<&RecentLocations> {
+        self.inner.recent_locations.as_ref()
+    }
+
     /// Get uptime in seconds since service started
     pub fn uptime_seconds(&self) -> u64 {
         self.inner.started_at.elapsed().as_secs()
@@ -244,6 +415,190 @@ impl AppState {
     pub fn fallback_rate_limiter(&self) -> &Arc<FallbackRateLimiter> {
         &self.inner.fallback_rate_limiter
     }
+
+    /// Get the per-tier rate limit multipliers, with runtime overrides
+    pub fn rate_limit_tiers(&self) -> RateLimitTiers {
+        self.inner.rate_limit_tiers.with_overrides(&self.read_runtime_config().settings)
+    }
+
+    /// Get the limit and algorithm of each rate limited route group
+    pub fn rate_limits(&self) -> &RateLimitRoutes {
+        &self.inner.rate_limits
+    }
+
+    /// Get the runtime config applied to this instance
+    pub fn runtime_config(&self) -> StoredRuntimeSettings {
+        self.read_runtime_config().clone()
+    }
+
+    /// Replace the runtime config, returning the previous one
+    pub fn set_runtime_config(&self, stored: StoredRuntimeSettings) -> StoredRuntimeSettings {
+        let mut current =
+            self.inner.runtime_config.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        std::mem::replace(&mut current, stored)
+    }
+
+    /// Check if an origin was allowed by a runtime config reload
+    pub fn is_extra_cors_origin(&self, origin: &str) -> bool {
+        self.read_runtime_config().settings.extra_cors_origins.iter().any(|o| o == origin)
+    }
+
+    /// Get the reconnection grace period for new games, in seconds
+    pub fn default_reconnect_grace_secs(&self) -> u32 {
+        self.read_runtime_config()
+            .settings
+            .reconnect_grace_secs
+            .unwrap_or(dguesser_core::game::DEFAULT_RECONNECT_GRACE_SECONDS)
+    }
+
+    /// Get the runtime response cache lifetime for a namespace, if overridden
+    pub fn cache_ttl_override(&self, namespace: &str) -> Option<CacheTtl> {
+        self.read_runtime_config().settings.cache_ttls.get(namespace).copied()
+    }
+
+    fn read_runtime_config(&self) -> std::sync::RwLockReadGuard<'_, StoredRuntimeSettings> {
+        self.inner.runtime_config.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Get the Street View metadata and short link client
+    pub fn street_view(&self) -> &StreetViewClient {
+        &self.inner.street_view
+    }
+
+    /// Get the socket handshake token signer (if configured)
+    pub fn socket_token_signer(&self) -> Option<&SocketTokenSigner> {
+        self.inner.socket_token_signer.as_ref()
+    }
+
+    /// Get the solo round result signer (if configured)
+    pub fn score_chain(&self) -> Option<&ScoreChain> {
+        self.inner.score_chain.as_ref()
+    }
+
+    /// Get the Web Push client (if configured)
+    pub fn push(&self) -> Option<&Arc<WebPushClient>> {
+        self.inner.push.as_ref()
+    }
+
+    /// Get the multiplayer join code configuration
+    pub fn join_codes(&self) -> &JoinCodeConfig {
+        &self.inner.join_codes
+    }
+
+    /// Get the share of client error reports stored
+    pub fn client_error_sample_rate(&self) -> f64 {
+        self.inner.client_error_sample_rate
+    }
+
+    /// Get the object storage for uploads (if configured)
+    pub fn storage(&self) -> Option<&Arc<dyn ObjectStorage>> {
+        self.inner.storage.as_ref()
+    }
+
+    /// Get the storage for archived games (if configured)
+    pub fn archive_storage(&self) -> Option<&Arc<dyn ObjectStorage>> {
+        self.inner.archive_storage.as_ref()
+    }
+
+    /// Get the organization cache
+    pub fn orgs(&self) -> &OrgCache {
+        &self.inner.orgs
+    }
+
+    /// Get the CAPTCHA client, if configured
+    pub fn captcha(&self) -> Option<&CaptchaClient> {
+        self.inner.captcha.as_ref()
+    }
+
+    /// Get the Map Tiles client
+    pub fn map_tiles(&self) -> &MapTilesClient {
+        &self.inner.map_tiles
+    }
+
+    /// Get the static map client
+    pub fn static_map(&self) -> &StaticMapClient {
+        &self.inner.static_map
+    }
+}
+
+/// Create the recent location history store unless the window is 0.
+fn recent_locations(window_games: usize, redis: &RedisConnection) -> Option<RecentLocations> {
+    if window_games == 0 {
+        tracing::info!("Location repeat protection disabled");
+        return None;
+    }
+    tracing::info!(window_games, "Location repeat protection enabled");
+    Some(RecentLocations::new(redis.clone(), window_games))
+}
+
+/// Uncached reader for the pack storage, used by health checks.
+fn pack_storage(r2_config: &R2LocationConfig) -> Arc<dyn RangeReader> {
+    match r2_config.local_path() {
+        Some(local_path) => Arc::new(FileReader::new(local_path, &r2_config.version)),
+        None => Arc::new(HttpReader::new(&r2_config.base_url, &r2_config.version)),
+    }
+}
+
+/// Create the pack range cache, with a Redis tier if configured.
+fn range_cache(r2_config: &R2LocationConfig, redis: &RedisConnection) -> Option<Arc<RangeCache>> {
+    if r2_config.range_cache_mb == 0 {
+        return None;
+    }
+
+    let cache = RangeCache::new(RangeCacheConfig {
+        max_bytes: r2_config.range_cache_mb * 1024 * 1024,
+        ..RangeCacheConfig::default()
+    });
+    let cache = if r2_config.range_cache_redis {
+        cache.with_redis(redis.clone(), &format!("locations:packs:{}", r2_config.version))
+    } else {
+        cache
+    };
+
+    tracing::info!(
+        max_mb = r2_config.range_cache_mb,
+        redis = r2_config.range_cache_redis,
+        "Pack range cache enabled"
+    );
+    Some(Arc::new(cache))
+}
+
+/// Build a provider that serves pack-backed maps from `reader` (through
+/// `pack_cache` if set) and everything else (plus pack failures) from
+/// `database`.
+fn routed_provider<R: RangeReader + 'static>(
+    database: Arc<dyn LocationProvider>,
+    reader: R,
+    pack_cache: &Option<Arc<RangeCache>>,
+    pack_config: PackProviderConfig,
+    default_source: MapLocationSource,
+) -> Arc<dyn LocationProvider> {
+    match pack_cache {
+        Some(cache) => {
+            let reader = CachedReader::new(reader, Arc::clone(cache));
+            pack_routed_provider(database, reader, pack_config, default_source)
+        }
+        None => pack_routed_provider(database, reader, pack_config, default_source),
+    }
+}
+
+fn pack_routed_provider<R: RangeReader + 'static>(
+    database: Arc<dyn LocationProvider>,
+    reader: R,
+    pack_config: PackProviderConfig,
+    default_source: MapLocationSource,
+) -> Arc<dyn LocationProvider> {
+    let packs = Arc::new(PackProvider::new(reader, pack_config));
+
+    // Spawn background task to warm the cache
+    let packs_clone = Arc::clone(&packs);
+    tokio::spawn(async move {
+        if let Err(e) = packs_clone.warm_cache().await {
+            tracing::warn!(error = %e, "Location cache warm-up failed");
+        }
+    });
+
+    Arc::new(RoutedProvider::new(database, packs, default_source))
 }
 
 // Implement AuthState trait for middleware
@@ -0,0 +1,158 @@
+//! Static map images for round result cards
+//!
+//! Wraps the Google Static Maps API, which draws a map with markers and a
+//! path as a single PNG. Requests are billed per image, so callers cache
+//! what they render.
+
+use std::time::Duration;
+
+const STATIC_MAP_URL: &str = "https://maps.googleapis.com/maps/api/staticmap";
+
+/// Largest width or height the API accepts before scaling
+const MAX_SIZE: u32 = 640;
+
+/// Pixel density of requested maps; sizes are halved before requesting
+const SCALE: u32 = 2;
+
+/// Largest map image accepted from the API
+const MAX_IMAGE_BYTES: usize = 4 * 1024 * 1024;
+
+/// A map showing where a player guessed and where the location was.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResultMap {
+    /// Output width in pixels
+    pub width: u32,
+    /// Output height in pixels
+    pub height: u32,
+    /// Actual location (lat, lng)
+    pub actual: (f64, f64),
+    /// The player's guess (lat, lng), if they made one
+    pub guess: Option<(f64, f64)>,
+}
+
+impl ResultMap {
+    /// Query parameters for the map, without the API key.
+    fn query(&self) -> Vec<(&'static str, String)> {
+        let width = (self.width / SCALE).min(MAX_SIZE);
+        let height = (self.height / SCALE).min(MAX_SIZE);
+        let point = |(lat, lng): (f64, f64)| format!("{lat:.6},{lng:.6}");
+
+        let mut query = vec![
+            ("size", format!("{width}x{height}")),
+            ("scale", SCALE.to_string()),
+            ("format", "png".to_string()),
+            ("maptype", "roadmap".to_string()),
+            ("markers", format!("color:red|label:A|{}", point(self.actual))),
+        ];
+        match self.guess {
+            Some(guess) => {
+                query.push(("markers", format!("color:blue|label:G|{}", point(guess))));
+                query.push((
+                    "path",
+                    format!(
+                        "color:0x1e293bcc|weight:3|geodesic:true|{}|{}",
+                        point(guess),
+                        point(self.actual)
+                    ),
+                ));
+            }
+            // A lone marker would be drawn at street level
+            None => query.push(("zoom", "3".to_string())),
+        }
+        query
+    }
+}
+
+/// Static map errors
+#[derive(Debug, thiserror::Error)]
+pub enum StaticMapError {
+    #[error("static maps are not configured")]
+    NotConfigured,
+    #[error("static map request failed: {0}")]
+    Request(reqwest::Error),
+    #[error("static map API returned {0}")]
+    Status(u16),
+    #[error("static map image is too large")]
+    TooLarge,
+}
+
+/// Static Maps client. Cheap to clone.
+#[derive(Clone)]
+pub struct StaticMapClient {
+    http: reqwest::Client,
+    /// Maps API key; maps are unavailable without one
+    api_key: Option<String>,
+}
+
+impl StaticMapClient {
+    pub fn new(api_key: Option<String>) -> Self {
+        let http =
+            reqwest::Client::builder().timeout(Duration::from_secs(10)).build().unwrap_or_default();
+        Self { http, api_key }
+    }
+
+    /// Whether maps can be fetched.
+    pub fn is_configured(&self) -> bool {
+        self.api_key.is_some()
+    }
+
+    /// Fetch a result map as PNG.
+    pub async fn fetch(&self, map: &ResultMap) -> Result<Vec<u8>, StaticMapError> {
+        let api_key = self.api_key.as_deref().ok_or(StaticMapError::NotConfigured)?;
+
+        // Errors carry the URL, which includes the key
+        let response = self
+            .http
+            .get(STATIC_MAP_URL)
+            .query(&map.query())
+            .query(&[("key", api_key)])
+            .send()
+            .await
+            .map_err(|e| StaticMapError::Request(e.without_url()))?;
+        if !response.status().is_success() {
+            return Err(StaticMapError::Status(response.status().as_u16()));
+        }
+        if response.content_length().is_some_and(|len| len as usize > MAX_IMAGE_BYTES) {
+            return Err(StaticMapError::TooLarge);
+        }
+
+        let bytes = response.bytes().await.map_err(|e| StaticMapError::Request(e.without_url()))?;
+        if bytes.len() > MAX_IMAGE_BYTES {
+            return Err(StaticMapError::TooLarge);
+        }
+        Ok(bytes.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_marks_guess_and_actual_location() {
+        let map = ResultMap {
+            width: 1200,
+            height: 630,
+            actual: (59.3293, 18.0686),
+            guess: Some((48.8566, 2.3522)),
+        };
+        let query = map.query();
+
+        assert!(query.contains(&("size", "600x315".to_string())));
+        assert!(query.contains(&("markers", "color:red|label:A|59.329300,18.068600".to_string())));
+        assert!(query.contains(&("markers", "color:blue|label:G|48.856600,2.352200".to_string())));
+        assert!(query.iter().any(|(name, value)| *name == "path"
+            && value.ends_with("48.856600,2.352200|59.329300,18.068600")));
+        assert!(!query.iter().any(|(name, _)| *name == "zoom"));
+    }
+
+    #[test]
+    fn query_zooms_out_without_guess() {
+        let map = ResultMap { width: 2000, height: 630, actual: (0.0, 0.0), guess: None };
+        let query = map.query();
+
+        assert!(query.contains(&("size", "640x315".to_string())));
+        assert!(query.contains(&("zoom", "3".to_string())));
+        assert!(!query.iter().any(|(name, _)| *name == "path"));
+    }
+}
@@ -0,0 +1,133 @@
+//! Object storage for user uploads (avatars) and rendered result images
+//!
+//! Uploads go to an S3-compatible bucket (Cloudflare R2) and are served from
+//! a public URL in front of it. Uploads are disabled when storage isn't
+//! configured.
+
+mod r2;
+
+use std::env;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use axum::body::Bytes;
+
+pub use r2::R2Storage;
+
+/// Object storage errors
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    #[error("storage request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("storage returned {status}: {body}")]
+    Status { status: u16, body: String },
+}
+
+/// A bucket of objects, publicly readable unless used for archives.
+#[async_trait]
+pub trait ObjectStorage: Send + Sync {
+    /// Short backend name for logs
+    fn name(&self) -> &'static str;
+
+    /// Store an object, replacing any existing one with the same key
+    async fn put(&self, key: &str, body: Bytes, content_type: &str) -> Result<(), StorageError>;
+
+    /// Read an object, or `None` if it doesn't exist
+    async fn get(&self, key: &str) -> Result<Option<Bytes>, StorageError>;
+
+    /// Delete an object (deleting a missing object succeeds)
+    async fn delete(&self, key: &str) -> Result<(), StorageError>;
+
+    /// Public URL an object is served from
+    fn public_url(&self, key: &str) -> String;
+}
+
+/// Object storage configuration (`STORAGE_*` environment variables)
+#[derive(Debug, Clone)]
+pub struct StorageConfig {
+    /// S3 API endpoint, e.g. `https://<account>.r2.cloudflarestorage.com`
+    pub endpoint: String,
+    pub bucket: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// Public base URL objects are served from, e.g. `https://uploads.dguesser.lol`
+    pub public_url: String,
+}
+
+impl StorageConfig {
+    /// Load from the environment. Returns `None` unless every setting is present.
+    pub fn from_env() -> Option<Self> {
+        let var = |name: &str| env::var(name).ok().filter(|v| !v.is_empty());
+        Some(Self {
+            endpoint: var("STORAGE_R2_ENDPOINT")?.trim_end_matches('/').to_string(),
+            bucket: var("STORAGE_R2_BUCKET")?,
+            access_key_id: var("STORAGE_R2_ACCESS_KEY_ID")?,
+            secret_access_key: var("STORAGE_R2_SECRET_ACCESS_KEY")?,
+            public_url: var("STORAGE_PUBLIC_URL")?.trim_end_matches('/').to_string(),
+        })
+    }
+
+    /// Build the storage backend
+    pub fn build(&self) -> Arc<dyn ObjectStorage> {
+        Arc::new(R2Storage::new(self.clone()))
+    }
+}
+
+/// Image formats accepted for avatars
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Webp,
+}
+
+impl ImageFormat {
+    /// Detect the format from the file's magic bytes
+    pub fn sniff(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(Self::Png)
+        } else if bytes.starts_with(&[0xff, 0xd8, 0xff]) {
+            Some(Self::Jpeg)
+        } else if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+            Some(Self::Webp)
+        } else {
+            None
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Jpeg => "image/jpeg",
+            Self::Webp => "image/webp",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Jpeg => "jpg",
+            Self::Webp => "webp",
+        }
+    }
+}
+
+/// Lowercase hex encoding
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_image_formats() {
+        assert_eq!(ImageFormat::sniff(b"\x89PNG\r\n\x1a\n...."), Some(ImageFormat::Png));
+        assert_eq!(ImageFormat::sniff(&[0xff, 0xd8, 0xff, 0xe0]), Some(ImageFormat::Jpeg));
+        assert_eq!(ImageFormat::sniff(b"RIFF\0\0\0\0WEBPVP8 "), Some(ImageFormat::Webp));
+        assert_eq!(ImageFormat::sniff(b"GIF89a"), None);
+        assert_eq!(ImageFormat::sniff(b"<svg xmlns="), None);
+        assert_eq!(ImageFormat::sniff(b""), None);
+    }
+}
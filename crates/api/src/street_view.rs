@@ -0,0 +1,348 @@
+//! Street View metadata and link resolution
+//!
+//! Wraps the Street View metadata API, which reports whether a panorama
+//! exists and where it is, and follows Google Maps short links to the full
+//! URLs that [`dguesser_core::streetview`] can parse. Metadata requests are
+//! free of charge but limited by quota: once the API answers
+//! `OVER_QUERY_LIMIT`, a [`QuotaBreaker`] shared through Redis pauses lookups
+//! from every API instance until the backoff expires. Requests sent are
+//! counted per day in Redis so quota usage can be tracked centrally.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{NaiveDate, Utc};
+use dguesser_core::streetview::{
+    OVER_QUERY_LIMIT, QUOTA_REDIS_KEY, QuotaBreaker, QuotaState, is_google_maps_url, is_short_link,
+};
+use dguesser_redis::RedisConnection;
+use redis::AsyncCommands;
+use serde::Deserialize;
+
+const METADATA_URL: &str = "https://maps.googleapis.com/maps/api/streetview/metadata";
+
+/// Stored quota state expires a day after it last changed
+const QUOTA_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// Daily metadata request counters, `{prefix}:{YYYY-MM-DD}`
+const REQUEST_COUNT_KEY_PREFIX: &str = "streetview:metadata:requests";
+
+/// Request counters are kept for a week after the day they count
+const REQUEST_COUNT_TTL_SECS: i64 = 8 * 24 * 60 * 60;
+
+/// Redirects followed when resolving a short link
+const MAX_SHORT_LINK_REDIRECTS: usize = 5;
+
+/// Where a panorama is, according to the metadata API.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PanoramaMetadata {
+    pub panorama_id: String,
+    pub lat: f64,
+    pub lng: f64,
+    /// Capture month, when reported
+    pub capture_date: Option<NaiveDate>,
+}
+
+/// Result of looking up one panorama.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PanoramaStatus {
+    /// Panorama exists
+    Exists(PanoramaMetadata),
+    /// Panorama is gone; the API status is kept as the failure reason
+    Missing(String),
+    /// Lookup failed for this panorama; try again later
+    Error(String),
+    /// Lookup failed because of the API key or quota; stop looking up more
+    Fatal(String),
+}
+
+impl PanoramaStatus {
+    /// Classify a Street View metadata API response.
+    fn from_response(response: MetadataResponse) -> Self {
+        match response.status.as_str() {
+            "OK" => match (response.pano_id, response.location) {
+                (Some(panorama_id), Some(location)) => Self::Exists(PanoramaMetadata {
+                    panorama_id,
+                    lat: location.lat,
+                    lng: location.lng,
+                    capture_date: response.date.as_deref().and_then(parse_capture_date),
+                }),
+                _ => Self::Error("Incomplete metadata".to_string()),
+            },
+            "ZERO_RESULTS" | "NOT_FOUND" => Self::Missing(response.status),
+            "OVER_QUERY_LIMIT" | "REQUEST_DENIED" => Self::Fatal(response.status),
+            _ => Self::Error(response.status),
+        }
+    }
+}
+
+/// Parse a metadata capture date (`YYYY-MM`).
+fn parse_capture_date(date: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(&format!("{date}-01"), "%Y-%m-%d").ok()
+}
+
+#[derive(Debug, Deserialize)]
+struct MetadataResponse {
+    status: String,
+    pano_id: Option<String>,
+    location: Option<MetadataLocation>,
+    date: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MetadataLocation {
+    lat: f64,
+    lng: f64,
+}
+
+/// What to look up.
+#[derive(Debug, Clone, Copy)]
+pub enum PanoramaQuery<'a> {
+    /// A specific panorama
+    Panorama(&'a str),
+    /// The nearest panorama to a point
+    Location { lat: f64, lng: f64 },
+}
+
+/// Street View client. Cheap to clone.
+#[derive(Clone)]
+pub struct StreetViewClient {
+    http: reqwest::Client,
+    /// Maps API key; metadata lookups are unavailable without one
+    api_key: Option<String>,
+    /// Where the quota breaker is shared between instances (if configured)
+    redis: Option<RedisConnection>,
+    /// Quota breaker of this instance, used when Redis is unavailable
+    local_quota: Arc<Mutex<QuotaBreaker>>,
+}
+
+impl StreetViewClient {
+    pub fn new(api_key: Option<String>) -> Self {
+        let redirects = reqwest::redirect::Policy::custom(|attempt| {
+            if attempt.previous().len() >= MAX_SHORT_LINK_REDIRECTS {
+                attempt.error("too many redirects")
+            } else if is_short_link(attempt.url().as_str())
+                || is_google_maps_url(attempt.url().as_str())
+            {
+                attempt.follow()
+            } else {
+                attempt.stop()
+            }
+        });
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .redirect(redirects)
+            .build()
+            .unwrap_or_default();
+        Self { http, api_key, redis: None, local_quota: Arc::default() }
+    }
+
+    /// Share the quota breaker with other instances through Redis.
+    pub fn with_quota_store(mut self, redis: RedisConnection) -> Self {
+        self.redis = Some(redis);
+        self
+    }
+
+    /// Whether panoramas can be looked up.
+    pub fn can_validate(&self) -> bool {
+        self.api_key.is_some()
+    }
+
+    /// Look up a panorama in the metadata API.
+    ///
+    /// Fails with [`PanoramaStatus::Fatal`] without sending a request while
+    /// the quota breaker is open.
+    pub async fn lookup(&self, query: PanoramaQuery<'_>) -> PanoramaStatus {
+        let Some(api_key) = self.api_key.as_deref() else {
+            return PanoramaStatus::Fatal("No API key configured".to_string());
+        };
+
+        let now = Utc::now();
+        let mut quota = self.quota().await;
+        if let QuotaState::Open { until } = quota.state(now) {
+            return PanoramaStatus::Fatal(format!(
+                "{OVER_QUERY_LIMIT} (paused until {})",
+                until.format("%H:%M:%S UTC")
+            ));
+        }
+
+        self.count_request(now.date_naive()).await;
+        let status = self.request(api_key, query).await;
+        match &status {
+            PanoramaStatus::Fatal(reason) if reason == OVER_QUERY_LIMIT => {
+                let until = quota.trip(now);
+                tracing::warn!(
+                    trips = quota.consecutive_trips,
+                    paused_until = %until,
+                    "Street View quota exhausted, pausing metadata lookups"
+                );
+                self.save_quota(&quota).await;
+            }
+            PanoramaStatus::Exists(_) | PanoramaStatus::Missing(_) => {
+                if quota.record_success() {
+                    tracing::info!("Street View quota available again");
+                    self.save_quota(&quota).await;
+                }
+            }
+            PanoramaStatus::Error(_) | PanoramaStatus::Fatal(_) => {}
+        }
+        status
+    }
+
+    async fn request(&self, api_key: &str, query: PanoramaQuery<'_>) -> PanoramaStatus {
+        let request = self.http.get(METADATA_URL).query(&[("key", api_key)]);
+        let request = match query {
+            PanoramaQuery::Panorama(panorama_id) => request.query(&[("pano", panorama_id)]),
+            PanoramaQuery::Location { lat, lng } => {
+                request.query(&[("location", format!("{lat},{lng}"))])
+            }
+        };
+
+        match request.send().await {
+            Ok(response) => match response.json::<MetadataResponse>().await {
+                Ok(meta) => PanoramaStatus::from_response(meta),
+                Err(e) => PanoramaStatus::Error(e.to_string()),
+            },
+            Err(e) => PanoramaStatus::Error(e.without_url().to_string()),
+        }
+    }
+
+    /// Current quota breaker, shared through Redis when configured.
+    pub async fn quota(&self) -> QuotaBreaker {
+        if let Some(redis) = &self.redis {
+            match read_quota(redis).await {
+                Ok(quota) => return quota.unwrap_or_default(),
+                Err(e) => tracing::warn!(error = %e, "Failed to read Street View quota state"),
+            }
+        }
+        self.local_quota.lock().unwrap().clone()
+    }
+
+    async fn save_quota(&self, quota: &QuotaBreaker) {
+        *self.local_quota.lock().unwrap() = quota.clone();
+
+        let Some(redis) = &self.redis else { return };
+        let json = match serde_json::to_string(quota) {
+            Ok(json) => json,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to serialize Street View quota state");
+                return;
+            }
+        };
+        let result = redis.clone().set_ex::<_, _, ()>(QUOTA_REDIS_KEY, json, QUOTA_TTL_SECS).await;
+        if let Err(e) = result {
+            tracing::warn!(error = %e, "Failed to store Street View quota state");
+        }
+    }
+
+    /// Count a metadata request toward the day's usage.
+    async fn count_request(&self, date: NaiveDate) {
+        let Some(redis) = &self.redis else { return };
+        let key = request_count_key(date);
+        let result = redis::pipe()
+            .incr(&key, 1)
+            .ignore()
+            .expire(&key, REQUEST_COUNT_TTL_SECS)
+            .ignore()
+            .query_async::<()>(&mut redis.clone())
+            .await;
+        if let Err(e) = result {
+            tracing::warn!(error = %e, "Failed to count Street View metadata request");
+        }
+    }
+
+    /// Metadata requests sent by all instances on a day (UTC), if counted.
+    pub async fn requests_on(&self, date: NaiveDate) -> Option<u64> {
+        let redis = self.redis.as_ref()?;
+        let result: Result<Option<u64>, _> = redis.clone().get(request_count_key(date)).await;
+        match result {
+            Ok(count) => Some(count.unwrap_or(0)),
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to read Street View request count");
+                None
+            }
+        }
+    }
+
+    /// Follow a Google Maps short link to the URL it points at. Only short
+    /// links and Google Maps URLs are requested.
+    pub async fn resolve_short_link(&self, url: &str) -> Result<String, String> {
+        let url = url.trim();
+        if !is_short_link(url) {
+            return Err("Not a short link".to_string());
+        }
+        let url = if url.contains("://") { url.to_string() } else { format!("https://{url}") };
+
+        let response = self.http.get(&url).send().await.map_err(|e| e.without_url().to_string())?;
+        Ok(response.url().to_string())
+    }
+}
+
+fn request_count_key(date: NaiveDate) -> String {
+    format!("{REQUEST_COUNT_KEY_PREFIX}:{}", date.format("%Y-%m-%d"))
+}
+
+/// Read the shared quota breaker; `None` if no quota problem is recorded.
+async fn read_quota(redis: &RedisConnection) -> Result<Option<QuotaBreaker>, redis::RedisError> {
+    let mut conn = redis.clone();
+    let json: Option<String> = conn.get(QUOTA_REDIS_KEY).await?;
+    Ok(json.and_then(|json| {
+        serde_json::from_str(&json)
+            .map_err(|e| tracing::warn!(error = %e, "Invalid stored Street View quota state"))
+            .ok()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(status: &str) -> MetadataResponse {
+        MetadataResponse {
+            status: status.to_string(),
+            pano_id: Some("CAoSLEFGMVFp".to_string()),
+            location: Some(MetadataLocation { lat: 48.8584, lng: 2.2945 }),
+            date: Some("2021-06".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_classify_api_status() {
+        assert_eq!(
+            PanoramaStatus::from_response(response("OK")),
+            PanoramaStatus::Exists(PanoramaMetadata {
+                panorama_id: "CAoSLEFGMVFp".to_string(),
+                lat: 48.8584,
+                lng: 2.2945,
+                capture_date: NaiveDate::from_ymd_opt(2021, 6, 1),
+            })
+        );
+        assert_eq!(
+            PanoramaStatus::from_response(response("ZERO_RESULTS")),
+            PanoramaStatus::Missing("ZERO_RESULTS".to_string())
+        );
+        assert_eq!(
+            PanoramaStatus::from_response(response("NOT_FOUND")),
+            PanoramaStatus::Missing("NOT_FOUND".to_string())
+        );
+        assert_eq!(
+            PanoramaStatus::from_response(response("OVER_QUERY_LIMIT")),
+            PanoramaStatus::Fatal(OVER_QUERY_LIMIT.to_string())
+        );
+        assert!(matches!(
+            PanoramaStatus::from_response(response("REQUEST_DENIED")),
+            PanoramaStatus::Fatal(_)
+        ));
+        assert!(matches!(
+            PanoramaStatus::from_response(response("UNKNOWN_ERROR")),
+            PanoramaStatus::Error(_)
+        ));
+    }
+
+    #[test]
+    fn test_ok_without_location_is_an_error() {
+        let mut meta = response("OK");
+        meta.location = None;
+        assert!(matches!(PanoramaStatus::from_response(meta), PanoramaStatus::Error(_)));
+    }
+}
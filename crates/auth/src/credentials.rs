@@ -0,0 +1,160 @@
+//! Native email/password credentials.
+//!
+//! This module provides password hashing with Argon2id, password policy
+//! validation, and single-use tokens for email verification and password
+//! resets. Raw tokens are only ever sent to the user; the database stores a
+//! SHA-256 digest so a leaked table cannot be replayed.
+
+use argon2::Argon2;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use sha2::{Digest, Sha256};
+
+/// Minimum password length in characters.
+pub const MIN_PASSWORD_LEN: usize = 8;
+
+/// Maximum password length in characters (bounds hashing cost).
+pub const MAX_PASSWORD_LEN: usize = 128;
+
+/// Email verification tokens are valid for 24 hours.
+pub const VERIFICATION_TOKEN_TTL_HOURS: i64 = 24;
+
+/// Password reset tokens are valid for 1 hour.
+pub const RESET_TOKEN_TTL_HOURS: i64 = 1;
+
+/// Errors from credential operations.
+#[derive(Debug, thiserror::Error)]
+pub enum CredentialError {
+    /// Password does not meet the policy
+    #[error("{0}")]
+    WeakPassword(&'static str),
+    /// Email address is malformed
+    #[error("Invalid email address")]
+    InvalidEmail,
+    /// Hashing backend failed
+    #[error("Password hashing failed: {0}")]
+    Hash(String),
+}
+
+/// Validate a password against the password policy.
+pub fn validate_password(password: &str) -> Result<(), CredentialError> {
+    let len = password.chars().count();
+    if len < MIN_PASSWORD_LEN {
+        return Err(CredentialError::WeakPassword("Password must be at least 8 characters"));
+    }
+    if len > MAX_PASSWORD_LEN {
+        return Err(CredentialError::WeakPassword("Password must be at most 128 characters"));
+    }
+    if password.trim().is_empty() {
+        return Err(CredentialError::WeakPassword("Password cannot be blank"));
+    }
+    Ok(())
+}
+
+/// Normalize an email address for storage and lookup.
+///
+/// Trims whitespace and lowercases the address, then performs a minimal
+/// structural check (`local@domain.tld`). Deliverability is proven by the
+/// verification email, not by this function.
+pub fn normalize_email(email: &str) -> Result<String, CredentialError> {
+    let email = email.trim().to_lowercase();
+    let (local, domain) = email.split_once('@').ok_or(CredentialError::InvalidEmail)?;
+
+    if local.is_empty()
+        || domain.len() < 3
+        || !domain.contains('.')
+        || domain.starts_with('.')
+        || domain.ends_with('.')
+        || email.len() > 255
+        || email.chars().any(char::is_whitespace)
+    {
+        return Err(CredentialError::InvalidEmail);
+    }
+
+    Ok(email)
+}
+
+/// Hash a password with Argon2id and a random salt.
+///
+/// Returns a PHC-format string that embeds the algorithm parameters and salt.
+pub fn hash_password(password: &str) -> Result<String, CredentialError> {
+    use rand::Rng;
+
+    let mut salt_bytes = [0u8; 16];
+    rand::rng().fill_bytes(&mut salt_bytes);
+    let salt =
+        SaltString::encode_b64(&salt_bytes).map_err(|e| CredentialError::Hash(e.to_string()))?;
+
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| CredentialError::Hash(e.to_string()))
+}
+
+/// Verify a password against a stored PHC hash.
+///
+/// Returns `false` for malformed hashes rather than erroring so callers can
+/// treat every failure as "invalid credentials".
+pub fn verify_password(password: &str, password_hash: &str) -> bool {
+    let Ok(parsed) = PasswordHash::new(password_hash) else {
+        return false;
+    };
+    Argon2::default().verify_password(password.as_bytes(), &parsed).is_ok()
+}
+
+/// Generate a single-use token.
+///
+/// Returns `(raw_token, token_hash)`. Send the raw token to the user and
+/// persist only the hash.
+pub fn generate_token() -> (String, String) {
+    let raw = dguesser_core::generate_session_token();
+    let hash = hash_token(&raw);
+    (raw, hash)
+}
+
+/// Hash a raw token for storage or lookup.
+pub fn hash_token(raw: &str) -> String {
+    hex::encode(Sha256::digest(raw.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_and_verify_password() {
+        let hash = hash_password("correct horse battery").unwrap();
+        assert!(hash.starts_with("$argon2id$"));
+        assert!(verify_password("correct horse battery", &hash));
+        assert!(!verify_password("wrong password", &hash));
+    }
+
+    #[test]
+    fn test_verify_password_malformed_hash() {
+        assert!(!verify_password("anything", "not-a-phc-string"));
+    }
+
+    #[test]
+    fn test_validate_password_length() {
+        assert!(validate_password("short").is_err());
+        assert!(validate_password("longenough").is_ok());
+        assert!(validate_password(&"a".repeat(129)).is_err());
+        assert!(validate_password("        ").is_err());
+    }
+
+    #[test]
+    fn test_normalize_email() {
+        assert_eq!(normalize_email("  Player@Example.COM ").unwrap(), "player@example.com");
+        assert!(normalize_email("no-at-sign").is_err());
+        assert!(normalize_email("@example.com").is_err());
+        assert!(normalize_email("player@localhost").is_err());
+        assert!(normalize_email("a b@example.com").is_err());
+    }
+
+    #[test]
+    fn test_generate_token_hash_matches() {
+        let (raw, hash) = generate_token();
+        assert_eq!(raw.len(), 43);
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash_token(&raw), hash);
+    }
+}
@@ -0,0 +1,230 @@
+//! Device identification from request metadata.
+//!
+//! Sessions record which browser, operating system, and device class they
+//! were created from so users can recognise their sessions and be alerted
+//! about sign-ins from devices they have not used before.
+//!
+//! The user agent parser is deliberately coarse: it recognises the major
+//! browser and OS families and ignores versions, so that routine browser
+//! updates do not make a known device look new.
+
+use sha2::{Digest, Sha256};
+
+/// Broad device class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceType {
+    Desktop,
+    Mobile,
+    Tablet,
+    Bot,
+    Unknown,
+}
+
+impl DeviceType {
+    /// Database representation.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeviceType::Desktop => "desktop",
+            DeviceType::Mobile => "mobile",
+            DeviceType::Tablet => "tablet",
+            DeviceType::Bot => "bot",
+            DeviceType::Unknown => "unknown",
+        }
+    }
+}
+
+/// Browser and operating system parsed from a user agent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceInfo {
+    pub browser: Option<&'static str>,
+    pub os: Option<&'static str>,
+    pub device_type: DeviceType,
+}
+
+impl DeviceInfo {
+    /// Parse a `User-Agent` header.
+    pub fn from_user_agent(user_agent: Option<&str>) -> Self {
+        let Some(ua) = user_agent.map(str::trim).filter(|ua| !ua.is_empty()) else {
+            return Self { browser: None, os: None, device_type: DeviceType::Unknown };
+        };
+
+        let lower = ua.to_ascii_lowercase();
+        if is_bot(&lower) {
+            return Self { browser: None, os: None, device_type: DeviceType::Bot };
+        }
+
+        let os = parse_os(&lower);
+        let browser = parse_browser(&lower);
+        let device_type = parse_device_type(&lower, os);
+
+        Self { browser, os, device_type }
+    }
+
+    /// Stable fingerprint of this device class, used to recognise a user's
+    /// returning devices. Versions are excluded so updates do not change it.
+    pub fn key(&self) -> String {
+        let raw = format!(
+            "{}|{}|{}",
+            self.browser.unwrap_or("unknown"),
+            self.os.unwrap_or("unknown"),
+            self.device_type.as_str()
+        );
+        hex::encode(Sha256::digest(raw.as_bytes()))
+    }
+
+    /// Human-readable label such as "Firefox on Windows".
+    pub fn label(&self) -> String {
+        device_label(self.browser, self.os)
+    }
+}
+
+/// Human-readable label for a browser and OS pair, as stored on a device.
+pub fn device_label(browser: Option<&str>, os: Option<&str>) -> String {
+    match (browser, os) {
+        (Some(browser), Some(os)) => format!("{browser} on {os}"),
+        (Some(browser), None) => browser.to_string(),
+        (None, Some(os)) => format!("Unknown browser on {os}"),
+        (None, None) => "Unknown device".to_string(),
+    }
+}
+
+fn is_bot(ua: &str) -> bool {
+    ["bot", "crawler", "spider", "curl/", "wget/", "python-requests", "headless"]
+        .iter()
+        .any(|needle| ua.contains(needle))
+}
+
+fn parse_os(ua: &str) -> Option<&'static str> {
+    // Order matters: iPadOS and Android UAs also mention other platforms.
+    if ua.contains("iphone") || ua.contains("ipad") || ua.contains("ipod") {
+        Some("iOS")
+    } else if ua.contains("android") {
+        Some("Android")
+    } else if ua.contains("cros ") {
+        Some("ChromeOS")
+    } else if ua.contains("windows") {
+        Some("Windows")
+    } else if ua.contains("mac os x") || ua.contains("macintosh") {
+        Some("macOS")
+    } else if ua.contains("linux") {
+        Some("Linux")
+    } else {
+        None
+    }
+}
+
+fn parse_browser(ua: &str) -> Option<&'static str> {
+    // Order matters: most Chromium browsers also claim "chrome" and "safari".
+    if ua.contains("edg/") || ua.contains("edga/") || ua.contains("edgios/") {
+        Some("Edge")
+    } else if ua.contains("opr/") || ua.contains("opera") {
+        Some("Opera")
+    } else if ua.contains("samsungbrowser/") {
+        Some("Samsung Internet")
+    } else if ua.contains("firefox/") || ua.contains("fxios/") {
+        Some("Firefox")
+    } else if ua.contains("chrome/") || ua.contains("crios/") || ua.contains("chromium/") {
+        Some("Chrome")
+    } else if ua.contains("safari/") {
+        Some("Safari")
+    } else {
+        None
+    }
+}
+
+fn parse_device_type(ua: &str, os: Option<&str>) -> DeviceType {
+    if ua.contains("ipad")
+        || ua.contains("tablet")
+        || (os == Some("Android") && !ua.contains("mobile"))
+    {
+        DeviceType::Tablet
+    } else if ua.contains("mobi") || ua.contains("iphone") || ua.contains("ipod") {
+        DeviceType::Mobile
+    } else if os.is_some() {
+        DeviceType::Desktop
+    } else {
+        DeviceType::Unknown
+    }
+}
+
+/// Normalise a country code from a geo header such as `CF-IPCountry`.
+///
+/// Returns `None` for the placeholder values Cloudflare uses for unknown
+/// locations (`XX`) and Tor exits (`T1`).
+pub fn normalize_country(code: &str) -> Option<String> {
+    let code = code.trim().to_ascii_uppercase();
+    if code.len() != 2 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    if code == "XX" || code == "T1" {
+        return None;
+    }
+    Some(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHROME_WINDOWS: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+        (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36";
+    const EDGE_WINDOWS: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+        (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36 Edg/124.0.0.0";
+    const SAFARI_IPHONE: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_4 like Mac OS X) \
+        AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.4 Mobile/15E148 Safari/604.1";
+    const FIREFOX_LINUX: &str =
+        "Mozilla/5.0 (X11; Linux x86_64; rv:125.0) Gecko/20100101 Firefox/125.0";
+    const CHROME_ANDROID_TABLET: &str = "Mozilla/5.0 (Linux; Android 14; SM-X710) \
+        AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36";
+
+    #[test]
+    fn test_parse_common_user_agents() {
+        let info = DeviceInfo::from_user_agent(Some(CHROME_WINDOWS));
+        assert_eq!(info.browser, Some("Chrome"));
+        assert_eq!(info.os, Some("Windows"));
+        assert_eq!(info.device_type, DeviceType::Desktop);
+
+        let info = DeviceInfo::from_user_agent(Some(EDGE_WINDOWS));
+        assert_eq!(info.browser, Some("Edge"));
+
+        let info = DeviceInfo::from_user_agent(Some(SAFARI_IPHONE));
+        assert_eq!(info.browser, Some("Safari"));
+        assert_eq!(info.os, Some("iOS"));
+        assert_eq!(info.device_type, DeviceType::Mobile);
+
+        let info = DeviceInfo::from_user_agent(Some(FIREFOX_LINUX));
+        assert_eq!(info.label(), "Firefox on Linux");
+
+        let info = DeviceInfo::from_user_agent(Some(CHROME_ANDROID_TABLET));
+        assert_eq!(info.os, Some("Android"));
+        assert_eq!(info.device_type, DeviceType::Tablet);
+    }
+
+    #[test]
+    fn test_parse_missing_and_bot_user_agents() {
+        assert_eq!(DeviceInfo::from_user_agent(None).device_type, DeviceType::Unknown);
+        assert_eq!(DeviceInfo::from_user_agent(Some("  ")).label(), "Unknown device");
+        assert_eq!(
+            DeviceInfo::from_user_agent(Some("Googlebot/2.1 (+http://www.google.com/bot.html)"))
+                .device_type,
+            DeviceType::Bot
+        );
+    }
+
+    #[test]
+    fn test_key_ignores_versions() {
+        let old = DeviceInfo::from_user_agent(Some(CHROME_WINDOWS));
+        let new = DeviceInfo::from_user_agent(Some(&CHROME_WINDOWS.replace("124.0", "125.0")));
+        assert_eq!(old.key(), new.key());
+        assert_eq!(old.key().len(), 64);
+        assert_ne!(old.key(), DeviceInfo::from_user_agent(Some(EDGE_WINDOWS)).key());
+    }
+
+    #[test]
+    fn test_normalize_country() {
+        assert_eq!(normalize_country("de").as_deref(), Some("DE"));
+        assert_eq!(normalize_country("XX"), None);
+        assert_eq!(normalize_country("T1"), None);
+        assert_eq!(normalize_country("USA"), None);
+    }
+}
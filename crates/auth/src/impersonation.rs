@@ -0,0 +1,122 @@
+//! Support impersonation.
+//!
+//! Admins can sign in as a user to see what they see. The impersonation
+//! session behaves like the user's own session, except that
+//! [`impersonation_guard`] limits what it may do: read-only sessions can only
+//! read, and no impersonation session can delete anything or touch sign-in,
+//! sessions, or admin tools.
+
+use axum::{
+    extract::Request,
+    http::{HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+
+use crate::middleware::AuthUser;
+
+/// Path (relative to the API root) that ends impersonation; always allowed.
+pub const END_IMPERSONATION_PATH: &str = "/auth/impersonation/end";
+
+/// Signing out of an impersonation session also ends it, so it is allowed too
+const LOGOUT_PATH: &str = "/auth/logout";
+
+/// Response header set on impersonated requests ("read-only" or "read-write")
+pub const IMPERSONATION_HEADER: &str = "x-impersonation";
+
+/// Path prefixes no impersonation session may change anything under
+const PROTECTED_PREFIXES: [&str; 3] = ["/auth", "/sessions", "/admin"];
+
+/// The admin behind an impersonated request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Impersonation {
+    /// Admin who started the impersonation
+    pub admin_id: String,
+    /// Whether only reads are allowed
+    pub read_only: bool,
+    /// When the impersonation session expires
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Impersonation {
+    /// Whether an impersonated request may run. `path` is relative to the
+    /// API root (e.g. `/users/me`).
+    pub fn allows(&self, method: &Method, path: &str) -> bool {
+        if path == END_IMPERSONATION_PATH || path == LOGOUT_PATH {
+            return true;
+        }
+        if matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) {
+            return !is_under(path, "/admin");
+        }
+        if self.read_only || *method == Method::DELETE {
+            return false;
+        }
+        !PROTECTED_PREFIXES.iter().any(|prefix| is_under(path, prefix))
+    }
+}
+
+fn is_under(path: &str, prefix: &str) -> bool {
+    path.strip_prefix(prefix).is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Middleware that blocks what an impersonation session may not do and marks
+/// impersonated responses with the [`IMPERSONATION_HEADER`].
+///
+/// Must run inside [`crate::session_renewal`], which resolves the session.
+pub async fn impersonation_guard(request: Request, next: Next) -> Response {
+    let impersonation =
+        request.extensions().get::<AuthUser>().and_then(|auth| auth.impersonation.clone());
+    let Some(impersonation) = impersonation else {
+        return next.run(request).await;
+    };
+
+    if !impersonation.allows(request.method(), request.uri().path()) {
+        tracing::info!(
+            admin_id = %impersonation.admin_id,
+            method = %request.method(),
+            path = %request.uri().path(),
+            "Blocked impersonated request"
+        );
+        return (StatusCode::FORBIDDEN, "Not allowed while impersonating").into_response();
+    }
+
+    let mut response = next.run(request).await;
+    let mode = if impersonation.read_only { "read-only" } else { "read-write" };
+    response.headers_mut().insert(IMPERSONATION_HEADER, HeaderValue::from_static(mode));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn impersonation(read_only: bool) -> Impersonation {
+        Impersonation { admin_id: "usr_admin".to_string(), read_only, expires_at: Utc::now() }
+    }
+
+    #[test]
+    fn test_read_only_allows_reads() {
+        let imp = impersonation(true);
+        assert!(imp.allows(&Method::GET, "/users/me"));
+        assert!(imp.allows(&Method::GET, "/games/gam_abc"));
+        assert!(imp.allows(&Method::POST, END_IMPERSONATION_PATH));
+        assert!(!imp.allows(&Method::POST, "/games"));
+        assert!(!imp.allows(&Method::PUT, "/users/me"));
+        assert!(!imp.allows(&Method::GET, "/admin/stats"));
+    }
+
+    #[test]
+    fn test_read_write_blocks_destructive_actions() {
+        let imp = impersonation(false);
+        assert!(imp.allows(&Method::POST, "/games"));
+        assert!(imp.allows(&Method::PUT, "/users/me"));
+        assert!(!imp.allows(&Method::DELETE, "/users/me"));
+        assert!(imp.allows(&Method::POST, "/auth/logout"));
+        assert!(!imp.allows(&Method::POST, "/auth/password-reset"));
+        assert!(!imp.allows(&Method::POST, "/sessions/revoke-others"));
+        assert!(!imp.allows(&Method::PUT, "/admin/maps/map_x/default"));
+        // Prefixes match whole path segments
+        assert!(imp.allows(&Method::POST, "/authors"));
+    }
+}
@@ -3,22 +3,40 @@
 //! This crate provides:
 //! - Session management with secure cookie handling
 //! - OAuth providers (Google, Microsoft)
+//! - Email/password credentials with verification and reset tokens
+//! - Device recognition from user agents for session tracking
+//! - Signed handshake tokens for realtime sockets
+//! - Admin impersonation sessions for support
 //! - Auth middleware extractors for Axum
 //! - Service layer for authentication flows
 
+pub mod credentials;
+pub mod device;
+pub mod impersonation;
 pub mod middleware;
 pub mod oauth;
 pub mod service;
 pub mod session;
+pub mod signed_token;
+pub mod socket_token;
 
 // Re-export commonly used types
-pub use middleware::{AuthState, AuthUser, MaybeAuthUser, RequireAdmin, RequireAuth};
+pub use credentials::CredentialError;
+pub use device::{DeviceInfo, DeviceType, device_label};
+pub use impersonation::{Impersonation, impersonation_guard};
+pub use middleware::{
+    AuthState, AuthUser, MaybeAuthUser, RequireAdmin, RequireAuth, session_renewal,
+};
 pub use oauth::google::GoogleOAuth;
 pub use oauth::microsoft::MicrosoftOAuth;
 pub use oauth::state_store::OAuthStateStore;
 pub use oauth::{OAuthError, OAuthIdentity, OAuthProvider, OAuthState};
 pub use service::{
-    AuthError, AuthResult, create_guest_session, handle_oauth_callback, logout,
-    logout_other_sessions,
+    AuthError, AuthResult, ClientInfo, IssuedToken, PasswordRegistration, create_guest_session,
+    handle_oauth_callback, link_oauth_account, login_with_password, logout, logout_other_sessions,
+    register_with_password, request_password_reset, resend_verification, reset_password,
+    unlink_oauth_account, verify_email,
 };
 pub use session::{SameSite, SessionConfig, build_cookie_header, build_delete_cookie_header};
+pub use signed_token::{MacKey, SignedTokenError, TokenSigner, VerifiedToken};
+pub use socket_token::{SocketTokenClaims, SocketTokenError, SocketTokenSigner};
@@ -5,13 +5,23 @@
 //! - `MaybeAuthUser`: Optional authentication (returns None if no session)
 //! - `RequireAuth`: Requires an authenticated (non-guest) user
 //! - `RequireAdmin`: Requires a user with admin role
+//!
+//! It also provides [`session_renewal`], a middleware that transparently
+//! renews sessions past half their lifetime.
 
 use axum::{
-    extract::FromRequestParts,
-    http::{StatusCode, header::COOKIE, request::Parts},
+    extract::{FromRequestParts, Request, State},
+    http::{
+        HeaderValue, StatusCode,
+        header::{COOKIE, SET_COOKIE},
+        request::Parts,
+    },
+    middleware::Next,
+    response::Response,
 };
 
-use crate::session::SessionConfig;
+use crate::impersonation::Impersonation;
+use crate::session::{SessionConfig, build_cookie_header};
 use dguesser_db::{UserKind, UserRole};
 
 /// Authenticated user extracted from session cookie.
@@ -28,6 +38,8 @@ pub struct AuthUser {
     pub is_guest: bool,
     /// User's role (user or admin)
     pub role: UserRole,
+    /// The admin behind the session, if it is an impersonation session
+    pub impersonation: Option<Impersonation>,
 }
 
 /// Optional authentication extractor.
@@ -81,6 +93,114 @@ fn extract_session_id(parts: &Parts, cookie_name: &str) -> Option<String> {
         .next_back()
 }
 
+/// Middleware that resolves the request's session once and slides its expiry.
+///
+/// The signed-in user is stored in the request extensions as an [`AuthUser`],
+/// so the extractors and other middleware (e.g. per-user rate limits) can use
+/// it without another database lookup.
+///
+/// When the session is past half its lifetime, a successor session is issued
+/// and sent back in a `Set-Cookie` header. The old token stays valid for
+/// [`SessionConfig::renewal_overlap_seconds`] so requests racing this one
+/// still succeed. Handlers downstream see the renewed session.
+///
+/// Responses that already set the session cookie themselves (sign-in,
+/// logout) are left untouched.
+pub async fn session_renewal<S: AuthState>(
+    State(state): State<S>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let config = state.session_config();
+    let (mut parts, body) = request.into_parts();
+
+    let Some(session_id) = extract_session_id(&parts, &config.cookie_name) else {
+        return next.run(Request::from_parts(parts, body)).await;
+    };
+
+    let pool = state.db_pool();
+    let session = match dguesser_db::sessions::get_valid(pool, &session_id).await {
+        Ok(Some(session)) => session,
+        Ok(None) => return next.run(Request::from_parts(parts, body)).await,
+        Err(e) => {
+            tracing::error!("Database error validating session: {}", e);
+            return next.run(Request::from_parts(parts, body)).await;
+        }
+    };
+
+    let impersonation = load_impersonation(pool, &session.id).await;
+
+    // Impersonation sessions keep their fixed lifetime
+    let mut renewed = None;
+    if impersonation.is_none() && config.needs_renewal(session.expires_at, chrono::Utc::now()) {
+        match dguesser_db::sessions::renew(
+            pool,
+            &session.id,
+            config.ttl_hours,
+            config.renewal_overlap_seconds,
+        )
+        .await
+        {
+            Ok(new_session) => renewed = new_session,
+            Err(e) => tracing::warn!(error = %e, "Failed to renew session"),
+        }
+    }
+
+    let current = renewed.as_ref().unwrap_or(&session);
+    match dguesser_db::users::get_by_id(pool, &current.user_id).await {
+        Ok(Some(user)) => {
+            parts.extensions.insert(AuthUser {
+                user_id: user.id.clone(),
+                session_id: current.id.clone(),
+                is_guest: user.kind == UserKind::Guest,
+                role: user.role(),
+                impersonation,
+            });
+        }
+        Ok(None) => {}
+        Err(e) => tracing::error!("Database error fetching user: {}", e),
+    }
+
+    let mut response = next.run(Request::from_parts(parts, body)).await;
+
+    if let Some(new_session) = renewed
+        && !sets_session_cookie(&response, &config.cookie_name)
+    {
+        let cookie = build_cookie_header(&new_session.id, config, config.max_age_seconds());
+        if let Ok(value) = HeaderValue::from_str(&cookie) {
+            response.headers_mut().append(SET_COOKIE, value);
+        }
+    }
+
+    response
+}
+
+/// Load the impersonation behind a session, if any.
+async fn load_impersonation(pool: &sqlx::PgPool, session_id: &str) -> Option<Impersonation> {
+    match dguesser_db::impersonation::get_by_session(pool, session_id).await {
+        Ok(session) => session.filter(|s| s.ended_at.is_none()).map(|s| Impersonation {
+            admin_id: s.admin_id,
+            read_only: s.read_only,
+            expires_at: s.expires_at,
+        }),
+        Err(e) => {
+            tracing::error!("Database error fetching impersonation: {}", e);
+            None
+        }
+    }
+}
+
+/// Whether the response already sets (or clears) the session cookie.
+fn sets_session_cookie(response: &Response, cookie_name: &str) -> bool {
+    let prefix = format!("{}=", cookie_name);
+    response
+        .headers()
+        .get_all(SET_COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .any(|v| v.starts_with(&prefix))
+}
+
 impl<S> FromRequestParts<S> for AuthUser
 where
     S: AuthState,
@@ -91,6 +211,12 @@ where
         let session_config = state.session_config();
         let pool = state.db_pool();
 
+        // Already resolved (and possibly renewed) by the session middleware
+        if let Some(auth) = parts.extensions.get::<AuthUser>() {
+            touch_session(pool, &auth.session_id);
+            return Ok(auth.clone());
+        }
+
         // Extract session ID from cookie
         let session_id = extract_session_id(parts, &session_config.cookie_name)
             .ok_or((StatusCode::UNAUTHORIZED, "No session cookie"))?;
@@ -113,23 +239,29 @@ where
             })?
             .ok_or((StatusCode::UNAUTHORIZED, "User not found"))?;
 
-        // Touch session to update last_accessed_at
-        // Fire and forget - don't block the request
-        let pool_clone = pool.clone();
-        let sid_clone = session_id.clone();
-        tokio::spawn(async move {
-            let _ = dguesser_db::sessions::touch(&pool_clone, &sid_clone).await;
-        });
+        touch_session(pool, &session_id);
+        let impersonation = load_impersonation(pool, &session_id).await;
 
         Ok(AuthUser {
             user_id: session.user_id,
             session_id,
             is_guest: user.kind == UserKind::Guest,
             role: user.role(),
+            impersonation,
         })
     }
 }
 
+/// Touch session to update last_accessed_at.
+/// Fire and forget - don't block the request.
+fn touch_session(pool: &sqlx::PgPool, session_id: &str) {
+    let pool = pool.clone();
+    let session_id = session_id.to_string();
+    tokio::spawn(async move {
+        let _ = dguesser_db::sessions::touch(&pool, &session_id).await;
+    });
+}
+
 impl<S> FromRequestParts<S> for MaybeAuthUser
 where
     S: AuthState,
@@ -210,6 +342,20 @@ mod tests {
         assert_eq!(session_id, None);
     }
 
+    #[test]
+    fn test_sets_session_cookie() {
+        let mut response = Response::new(axum::body::Body::empty());
+        assert!(!sets_session_cookie(&response, "dguesser_sid"));
+
+        response.headers_mut().append(SET_COOKIE, HeaderValue::from_static("other=1; Path=/"));
+        assert!(!sets_session_cookie(&response, "dguesser_sid"));
+
+        response
+            .headers_mut()
+            .append(SET_COOKIE, HeaderValue::from_static("dguesser_sid=; Max-Age=0; Path=/"));
+        assert!(sets_session_cookie(&response, "dguesser_sid"));
+    }
+
     #[test]
     fn test_extract_session_id_duplicate_cookies_takes_last() {
         // When multiple cookies with the same name exist (due to different Domain attributes),
@@ -112,6 +112,9 @@ pub struct OAuthState {
     pub provider: OAuthProvider,
     /// Where to redirect the user after successful auth
     pub redirect_to: Option<String>,
+    /// User ID that initiated an account-linking flow (None for regular sign-in)
+    #[serde(default)]
+    pub link_user_id: Option<String>,
     /// Unix timestamp when this state was created
     pub created_at: i64,
 }
@@ -137,10 +140,28 @@ impl OAuthState {
             nonce: hex::encode(nonce_bytes),
             provider,
             redirect_to,
+            link_user_id: None,
             created_at: chrono::Utc::now().timestamp(),
         }
     }
 
+    /// Create OAuth state for linking an additional provider to an existing user.
+    ///
+    /// The callback uses `link_user_id` to attach the returned identity to this
+    /// user instead of running the regular sign-in flow.
+    pub fn for_link(
+        provider: OAuthProvider,
+        user_id: impl Into<String>,
+        redirect_to: Option<String>,
+    ) -> Self {
+        Self { link_user_id: Some(user_id.into()), ..Self::new(provider, redirect_to) }
+    }
+
+    /// Whether this state belongs to an account-linking flow.
+    pub fn is_link(&self) -> bool {
+        self.link_user_id.is_some()
+    }
+
     /// Check if this OAuth state has expired.
     ///
     /// OAuth states expire after 5 minutes to prevent stale authorization flows.
@@ -198,6 +219,22 @@ mod tests {
         assert_eq!(state.redirect_to, Some("/dashboard".to_string()));
     }
 
+    #[test]
+    fn test_oauth_state_for_link() {
+        let state = OAuthState::for_link(OAuthProvider::Google, "usr_V1StGXR8_Z5j", None);
+        assert!(state.is_link());
+        assert_eq!(state.link_user_id.as_deref(), Some("usr_V1StGXR8_Z5j"));
+        assert!(!OAuthState::new(OAuthProvider::Google, None).is_link());
+    }
+
+    #[test]
+    fn test_oauth_state_deserializes_without_link_user() {
+        let json =
+            r#"{"state":"s","nonce":"n","provider":"google","redirect_to":null,"created_at":0}"#;
+        let state: OAuthState = serde_json::from_str(json).unwrap();
+        assert!(state.link_user_id.is_none());
+    }
+
     #[test]
     fn test_oauth_state_validate() {
         let state = OAuthState::new(OAuthProvider::Google, None);
@@ -3,6 +3,7 @@
 //! This module provides secure storage and validation of OAuth state parameters
 //! to prevent CSRF attacks during the OAuth authorization flow.
 
+use dguesser_redis::RedisConnection;
 use redis::AsyncCommands;
 
 use super::{OAuthError, OAuthState};
@@ -21,13 +22,13 @@ const STATE_TTL_SECONDS: u64 = 300;
 /// 3. Enforcing TTL to prevent stale authorization flows
 #[derive(Clone)]
 pub struct OAuthStateStore {
-    client: redis::Client,
+    conn: RedisConnection,
 }
 
 impl OAuthStateStore {
     /// Create a new OAuth state store backed by Redis.
-    pub fn new(client: redis::Client) -> Self {
-        Self { client }
+    pub fn new(conn: RedisConnection) -> Self {
+        Self { conn }
     }
 
     /// Store OAuth state in Redis with TTL.
@@ -35,11 +36,7 @@ impl OAuthStateStore {
     /// The state is keyed by its random state value, making it retrievable
     /// only by whoever initiated the OAuth flow.
     pub async fn store(&self, state: &OAuthState) -> Result<(), OAuthError> {
-        let mut conn = self
-            .client
-            .get_multiplexed_async_connection()
-            .await
-            .map_err(|e| OAuthError::StateStorage(format!("Redis connection failed: {}", e)))?;
+        let mut conn = self.conn.clone();
 
         let key = format!("{}{}", KEY_PREFIX, state.state);
         let value = serde_json::to_string(state)
@@ -64,11 +61,7 @@ impl OAuthStateStore {
     /// - State not found (invalid or already consumed)
     /// - State has expired
     pub async fn validate_and_consume(&self, state_param: &str) -> Result<OAuthState, OAuthError> {
-        let mut conn = self
-            .client
-            .get_multiplexed_async_connection()
-            .await
-            .map_err(|e| OAuthError::StateStorage(format!("Redis connection failed: {}", e)))?;
+        let mut conn = self.conn.clone();
 
         let key = format!("{}{}", KEY_PREFIX, state_param);
 
@@ -126,8 +119,8 @@ mod tests {
     // They are marked as ignored by default and can be run with:
     // cargo test -p dguesser-auth state_store -- --ignored
 
-    fn get_test_redis_client() -> Option<redis::Client> {
-        redis::Client::open("redis://127.0.0.1:6379").ok()
+    fn get_test_redis_client() -> Option<RedisConnection> {
+        RedisConnection::open_url("redis://127.0.0.1:6379").ok()
     }
 
     #[tokio::test]
@@ -3,9 +3,27 @@
 //! This module provides high-level authentication functions that coordinate
 //! between database operations and OAuth providers.
 
-use crate::oauth::{OAuthError, OAuthIdentity};
+use crate::credentials::{self, CredentialError};
+use crate::device::DeviceInfo;
+use crate::oauth::{OAuthError, OAuthIdentity, OAuthProvider};
 use crate::session::SessionConfig;
-use dguesser_db::{User, UserKind, games, oauth as db_oauth, parties, sessions, users};
+use dguesser_core::moderation::check_display_name;
+use dguesser_db::credentials::{self as db_credentials, AuthTokenPurpose};
+use dguesser_db::{
+    OAuthAccount, UnlinkOutcome, User, UserDevice, UserKind, devices, games, oauth as db_oauth,
+    parties, sessions, users,
+};
+
+/// Request metadata recorded with a new session.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ClientInfo<'a> {
+    /// Client IP address
+    pub ip: Option<&'a str>,
+    /// Raw `User-Agent` header
+    pub user_agent: Option<&'a str>,
+    /// ISO 3166-1 alpha-2 country code, if known
+    pub country: Option<&'a str>,
+}
 
 /// Result of an authentication flow.
 #[derive(Debug)]
@@ -20,6 +38,9 @@ pub struct AuthResult {
     pub merged_from_guest: Option<String>,
     /// User IDs whose co-player caches should be invalidated after this auth flow.
     pub invalidate_co_player_cache_for: Vec<String>,
+    /// Set when the user signed in from a device they have not used before
+    /// (and they already had other devices), so they can be alerted.
+    pub new_device: Option<UserDevice>,
 }
 
 /// Errors that can occur during authentication operations.
@@ -37,6 +58,52 @@ pub enum AuthError {
     /// Guest account cannot be merged right now without breaking realtime state
     #[error("Guest merge blocked: {0}")]
     MergeBlocked(String),
+    /// The provider identity is already attached to a different account
+    #[error("OAuth identity already linked to another account")]
+    IdentityConflict,
+    /// The user already has a different identity linked for this provider
+    #[error("Provider already linked: {0}")]
+    ProviderAlreadyLinked(OAuthProvider),
+    /// The user has no identity linked for this provider
+    #[error("Provider not linked: {0}")]
+    ProviderNotLinked(OAuthProvider),
+    /// Removing this login method would leave the account without a way to sign in
+    #[error("Cannot remove the last login method")]
+    LastLoginMethod,
+    /// Email or password input was rejected
+    #[error("Credential error: {0}")]
+    Credential(#[from] CredentialError),
+    /// Email and password do not match an account
+    #[error("Invalid email or password")]
+    InvalidCredentials,
+    /// Another account already uses this email address
+    #[error("Email already in use")]
+    EmailTaken,
+    /// Verification or reset token is unknown, expired, or already used
+    #[error("Invalid or expired token")]
+    InvalidToken,
+}
+
+/// Result of registering with email and password.
+#[derive(Debug)]
+pub struct PasswordRegistration {
+    /// Session and user for the new account
+    pub auth: AuthResult,
+    /// Normalized email address the account was registered with
+    pub email: String,
+    /// Raw email verification token to deliver to the user
+    pub verification_token: String,
+}
+
+/// A single-use token ready to be delivered by email.
+#[derive(Debug)]
+pub struct IssuedToken {
+    /// User the token was issued for
+    pub user_id: String,
+    /// Address to deliver the token to
+    pub email: String,
+    /// Raw token (only its hash is stored)
+    pub token: String,
 }
 
 /// Handle OAuth callback and create or link user account.
@@ -56,15 +123,13 @@ pub enum AuthError {
 /// * `identity` - Verified identity from OAuth provider
 /// * `current_session` - Current session ID if user has one
 /// * `session_config` - Session configuration for TTL
-/// * `ip` - Client IP address for session tracking
-/// * `user_agent` - Client user agent for session tracking
+/// * `client` - Client IP, user agent, and country for session tracking
 pub async fn handle_oauth_callback(
     pool: &sqlx::PgPool,
     identity: OAuthIdentity,
     current_session: Option<&str>,
     session_config: &SessionConfig,
-    ip: Option<&str>,
-    user_agent: Option<&str>,
+    client: ClientInfo<'_>,
 ) -> Result<AuthResult, AuthError> {
     // Load the current session user first so we can safely decide whether to
     // upgrade a guest in place or merge it into an existing account.
@@ -82,11 +147,11 @@ pub async fn handle_oauth_callback(
     let verified_email = verified_email(&identity);
 
     let existing_oauth = db_oauth::get_by_provider(pool, &provider, &identity.subject).await?;
-    let email_match = if existing_oauth.is_none()
+    let email_holder = if existing_oauth.is_none()
         && current_user.as_ref().map(|user| user.kind == UserKind::Guest).unwrap_or(true)
     {
         if let Some(email) = verified_email {
-            users::get_by_email(pool, email).await?
+            db_credentials::get_user_by_email_ci(pool, &email.to_lowercase()).await?
         } else {
             None
         }
@@ -94,6 +159,13 @@ pub async fn handle_oauth_callback(
         None
     };
 
+    // Only an account whose address was verified may be matched by email. An
+    // unverified password account with the same address keeps it, and the
+    // OAuth user is created without an email instead of taking it over.
+    let email_held_unverified = email_holder.as_ref().is_some_and(|user| !user.email_verified);
+    let email_match = email_holder.filter(|user| user.email_verified);
+    let new_user_email = verified_email.filter(|_| !email_held_unverified);
+
     let linked_oauth_user = if let Some(oauth_account) = existing_oauth {
         Some(
             users::get_by_id(pool, &oauth_account.user_id)
@@ -129,6 +201,14 @@ pub async fn handle_oauth_callback(
                 ensure_guest_merge_safe(pool, &user.id).await?;
 
                 let merge = users::merge_guest_into_user(pool, &user.id, &target_user.id).await?;
+                tracing::info!(
+                    guest_user_id = %user.id,
+                    target_user_id = %target_user.id,
+                    games = merge.games_merged,
+                    guesses = merge.guesses_merged,
+                    maps = merge.maps_transferred,
+                    "Merged guest account into existing user"
+                );
 
                 if needs_provider_link {
                     db_oauth::link_account(
@@ -179,20 +259,15 @@ pub async fn handle_oauth_callback(
         }
     } else if let Some(user) = current_user {
         if user.kind == UserKind::Guest {
-            // Upgrade guest to authenticated in-place (same user_id, so any live state
-            // remains consistent).
-            users::upgrade_to_authenticated(
+            // Upgrade guest to authenticated in-place (same user_id, so history and any
+            // live state remain attached). The upgrade and provider link commit together
+            // so a failed link never leaves an account without a login method.
+            users::upgrade_guest_with_oauth(
                 pool,
                 &user.id,
-                verified_email,
-                identity.name.as_deref(),
+                new_user_email,
+                provider_display_name(&identity).as_deref(),
                 identity.picture.as_deref(),
-            )
-            .await?;
-
-            db_oauth::link_account(
-                pool,
-                &user.id,
                 &provider,
                 &identity.subject,
                 identity.email.as_deref(),
@@ -215,12 +290,13 @@ pub async fn handle_oauth_callback(
         }
     } else {
         // No current session - create new authenticated user
-        let display_name = identity.name.clone().unwrap_or_else(|| "New Player".to_string());
+        let display_name =
+            provider_display_name(&identity).unwrap_or_else(|| "New Player".to_string());
 
         let user = users::create_authenticated(
             pool,
             &display_name,
-            verified_email,
+            new_user_email,
             identity.picture.as_deref(),
         )
         .await?;
@@ -243,8 +319,7 @@ pub async fn handle_oauth_callback(
     }
 
     // Create new session
-    let session =
-        sessions::create(pool, &user_id, session_config.ttl_hours, ip, user_agent).await?;
+    let session = start_session(pool, &user_id, session_config, client).await?;
 
     Ok(AuthResult {
         user_id,
@@ -252,35 +327,365 @@ pub async fn handle_oauth_callback(
         is_new_user,
         merged_from_guest: merged_from,
         invalidate_co_player_cache_for: invalidate_ids,
+        new_device: session.new_device,
+    })
+}
+
+/// Link an additional OAuth identity to an existing signed-in user.
+///
+/// Unlike [`handle_oauth_callback`], this never switches accounts or merges
+/// users: it either attaches the identity to `user_id` or fails with a
+/// conflict. Linking an identity that is already attached to the same user is
+/// a no-op.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `identity` - Verified identity from OAuth provider
+/// * `user_id` - User that initiated the link flow
+pub async fn link_oauth_account(
+    pool: &sqlx::PgPool,
+    identity: OAuthIdentity,
+    user_id: &str,
+) -> Result<OAuthAccount, AuthError> {
+    let provider = identity.provider.to_string();
+
+    if let Some(existing) = db_oauth::get_by_provider(pool, &provider, &identity.subject).await? {
+        if existing.user_id == user_id {
+            return Ok(existing);
+        }
+        return Err(AuthError::IdentityConflict);
+    }
+
+    if db_oauth::is_provider_linked(pool, user_id, &provider).await? {
+        return Err(AuthError::ProviderAlreadyLinked(identity.provider));
+    }
+
+    let account = db_oauth::link_account(
+        pool,
+        user_id,
+        &provider,
+        &identity.subject,
+        identity.email.as_deref(),
+    )
+    .await?;
+
+    Ok(account)
+}
+
+/// Unlink an OAuth provider from a user.
+///
+/// Fails with [`AuthError::LastLoginMethod`] if the provider is the only way
+/// the user can still sign in.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `user_id` - User to unlink the provider from
+/// * `provider` - Provider to unlink
+pub async fn unlink_oauth_account(
+    pool: &sqlx::PgPool,
+    user_id: &str,
+    provider: OAuthProvider,
+) -> Result<(), AuthError> {
+    match db_oauth::unlink_account_if_not_last(pool, user_id, &provider.to_string()).await? {
+        UnlinkOutcome::Unlinked => Ok(()),
+        UnlinkOutcome::NotLinked => Err(AuthError::ProviderNotLinked(provider)),
+        UnlinkOutcome::LastLoginMethod => Err(AuthError::LastLoginMethod),
+    }
+}
+
+/// Register a new account with email and password.
+///
+/// If the current session belongs to a guest, the guest is upgraded in place
+/// so their history stays attached; otherwise a new user is created. The
+/// email starts unverified and a verification token is issued. Session is
+/// always rotated.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `email` - Email address as entered by the user
+/// * `password` - Plaintext password
+/// * `display_name` - Optional display name (guests keep theirs if `None`)
+/// * `current_session` - Current session ID if user has one
+/// * `session_config` - Session configuration for TTL
+/// * `client` - Client IP, user agent, and country for session tracking
+pub async fn register_with_password(
+    pool: &sqlx::PgPool,
+    email: &str,
+    password: &str,
+    display_name: Option<&str>,
+    current_session: Option<&str>,
+    session_config: &SessionConfig,
+    client: ClientInfo<'_>,
+) -> Result<PasswordRegistration, AuthError> {
+    let email = credentials::normalize_email(email)?;
+    credentials::validate_password(password)?;
+
+    if db_credentials::get_user_by_email_ci(pool, &email).await?.is_some() {
+        return Err(AuthError::EmailTaken);
+    }
+
+    let password_hash = credentials::hash_password(password)?;
+    let current_user = load_session_user(pool, current_session).await?;
+
+    let (user, is_new_user) = match current_user {
+        Some(user) if user.kind == UserKind::Guest => {
+            let user = db_credentials::upgrade_guest_with_password(
+                pool,
+                &user.id,
+                display_name,
+                &email,
+                &password_hash,
+            )
+            .await
+            .map_err(map_email_conflict)?;
+            (user, false)
+        }
+        _ => {
+            let user = db_credentials::create_user_with_password(
+                pool,
+                display_name.unwrap_or("New Player"),
+                &email,
+                &password_hash,
+            )
+            .await
+            .map_err(map_email_conflict)?;
+            (user, true)
+        }
+    };
+
+    let verification_token = issue_token(
+        pool,
+        &user.id,
+        AuthTokenPurpose::EmailVerification,
+        &email,
+        credentials::VERIFICATION_TOKEN_TTL_HOURS,
+    )
+    .await?;
+
+    let session = rotate_session(pool, &user.id, current_session, session_config, client).await?;
+
+    Ok(PasswordRegistration {
+        auth: AuthResult {
+            user_id: user.id,
+            session_id: session.id,
+            is_new_user,
+            merged_from_guest: None,
+            invalidate_co_player_cache_for: Vec::new(),
+            new_device: session.new_device,
+        },
+        email,
+        verification_token,
+    })
+}
+
+/// Sign in with email and password.
+///
+/// A guest session is merged into the account exactly like the OAuth flow.
+/// Unknown emails and wrong passwords fail identically with
+/// [`AuthError::InvalidCredentials`].
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `email` - Email address as entered by the user
+/// * `password` - Plaintext password
+/// * `current_session` - Current session ID if user has one
+/// * `session_config` - Session configuration for TTL
+/// * `client` - Client IP, user agent, and country for session tracking
+pub async fn login_with_password(
+    pool: &sqlx::PgPool,
+    email: &str,
+    password: &str,
+    current_session: Option<&str>,
+    session_config: &SessionConfig,
+    client: ClientInfo<'_>,
+) -> Result<AuthResult, AuthError> {
+    let email = credentials::normalize_email(email).map_err(|_| AuthError::InvalidCredentials)?;
+
+    let Some(login) = db_credentials::get_login_by_email(pool, &email).await? else {
+        // Spend the same hashing time as a real check so response timing does
+        // not reveal which emails have accounts.
+        credentials::verify_password(password, &DUMMY_PASSWORD_HASH);
+        return Err(AuthError::InvalidCredentials);
+    };
+
+    if !credentials::verify_password(password, &login.password_hash) {
+        return Err(AuthError::InvalidCredentials);
+    }
+
+    let target_user = login.user;
+    let current_user = load_session_user(pool, current_session).await?;
+
+    let (merged_from, invalidate_ids) = match current_user {
+        Some(user) if user.kind == UserKind::Guest && user.id != target_user.id => {
+            ensure_guest_merge_safe(pool, &user.id).await?;
+
+            let merge = users::merge_guest_into_user(pool, &user.id, &target_user.id).await?;
+            tracing::info!(
+                guest_user_id = %user.id,
+                target_user_id = %target_user.id,
+                games = merge.games_merged,
+                guesses = merge.guesses_merged,
+                maps = merge.maps_transferred,
+                "Merged guest account into existing user"
+            );
+
+            (Some(user.id), merge.co_player_cache_user_ids)
+        }
+        _ => (None, Vec::new()),
+    };
+
+    let session =
+        rotate_session(pool, &target_user.id, current_session, session_config, client).await?;
+
+    Ok(AuthResult {
+        user_id: target_user.id,
+        session_id: session.id,
+        is_new_user: false,
+        merged_from_guest: merged_from,
+        invalidate_co_player_cache_for: invalidate_ids,
+        new_device: session.new_device,
     })
 }
 
+/// Confirm an email address with a verification token.
+///
+/// Returns the verified user's ID. Fails with [`AuthError::InvalidToken`] if
+/// the token is unknown, expired, already used, or was issued for an address
+/// the user no longer has.
+pub async fn verify_email(pool: &sqlx::PgPool, raw_token: &str) -> Result<String, AuthError> {
+    let token = db_credentials::consume_token(
+        pool,
+        &credentials::hash_token(raw_token),
+        AuthTokenPurpose::EmailVerification,
+    )
+    .await?
+    .ok_or(AuthError::InvalidToken)?;
+
+    let email = token.email.as_deref().ok_or(AuthError::InvalidToken)?;
+    if !db_credentials::mark_email_verified(pool, &token.user_id, email).await? {
+        return Err(AuthError::InvalidToken);
+    }
+
+    Ok(token.user_id)
+}
+
+/// Issue a fresh verification token for a signed-in user.
+///
+/// Returns `None` if the user has no email or it is already verified.
+pub async fn resend_verification(
+    pool: &sqlx::PgPool,
+    user_id: &str,
+) -> Result<Option<IssuedToken>, AuthError> {
+    let Some(user) = users::get_by_id(pool, user_id).await? else {
+        return Err(AuthError::SessionNotFound);
+    };
+    let Some(email) = user.email.filter(|_| !user.email_verified) else {
+        return Ok(None);
+    };
+
+    let token = issue_token(
+        pool,
+        &user.id,
+        AuthTokenPurpose::EmailVerification,
+        &email,
+        credentials::VERIFICATION_TOKEN_TTL_HOURS,
+    )
+    .await?;
+
+    Ok(Some(IssuedToken { user_id: user.id, email, token }))
+}
+
+/// Issue a password reset token for the account with this email.
+///
+/// Returns `None` when no account can be reset, so callers can respond
+/// identically either way and avoid revealing which emails are registered.
+/// Accounts without a password can only be reset through a verified address.
+pub async fn request_password_reset(
+    pool: &sqlx::PgPool,
+    email: &str,
+) -> Result<Option<IssuedToken>, AuthError> {
+    let Ok(email) = credentials::normalize_email(email) else {
+        return Ok(None);
+    };
+    let Some(user) = db_credentials::get_user_by_email_ci(pool, &email).await? else {
+        return Ok(None);
+    };
+    if !user.email_verified && !db_credentials::has_password(pool, &user.id).await? {
+        return Ok(None);
+    }
+
+    let token = issue_token(
+        pool,
+        &user.id,
+        AuthTokenPurpose::PasswordReset,
+        &email,
+        credentials::RESET_TOKEN_TTL_HOURS,
+    )
+    .await?;
+
+    Ok(Some(IssuedToken { user_id: user.id, email, token }))
+}
+
+/// Set a new password using a reset token.
+///
+/// Receiving the reset email proves ownership of the address, so it is marked
+/// verified. All of the user's sessions are revoked. Returns the user ID.
+pub async fn reset_password(
+    pool: &sqlx::PgPool,
+    raw_token: &str,
+    new_password: &str,
+) -> Result<String, AuthError> {
+    credentials::validate_password(new_password)?;
+
+    let token = db_credentials::consume_token(
+        pool,
+        &credentials::hash_token(raw_token),
+        AuthTokenPurpose::PasswordReset,
+    )
+    .await?
+    .ok_or(AuthError::InvalidToken)?;
+
+    let password_hash = credentials::hash_password(new_password)?;
+    db_credentials::set_password(pool, &token.user_id, &password_hash).await?;
+
+    if let Some(email) = token.email.as_deref() {
+        db_credentials::mark_email_verified(pool, &token.user_id, email).await?;
+    }
+
+    sessions::revoke_all_for_user(pool, &token.user_id).await?;
+
+    Ok(token.user_id)
+}
+
 /// Create a guest session for anonymous users.
 ///
-/// This creates a new guest user with a generated display name and a new session.
-/// Guest users can play games and later upgrade to authenticated users.
+/// This creates a new guest user and a new session. Guest users can play
+/// games and later upgrade to authenticated users.
 ///
 /// # Arguments
 ///
 /// * `pool` - Database connection pool
+/// * `display_name` - Already validated display name, or `None` to generate one
 /// * `session_config` - Session configuration for TTL
-/// * `ip` - Client IP address for session tracking
-/// * `user_agent` - Client user agent for session tracking
+/// * `client` - Client IP, user agent, and country for session tracking
 pub async fn create_guest_session(
     pool: &sqlx::PgPool,
+    display_name: Option<&str>,
     session_config: &SessionConfig,
-    ip: Option<&str>,
-    user_agent: Option<&str>,
+    client: ClientInfo<'_>,
 ) -> Result<AuthResult, AuthError> {
-    // Generate a friendly guest name
-    let display_name = generate_guest_name();
+    // Generate a friendly guest name unless the guest picked one
+    let display_name = display_name.map_or_else(generate_guest_name, str::to_string);
 
     // Create guest user
     let user = users::create_guest(pool, &display_name).await?;
 
     // Create session
-    let session =
-        sessions::create(pool, &user.id, session_config.ttl_hours, ip, user_agent).await?;
+    let session = start_session(pool, &user.id, session_config, client).await?;
 
     Ok(AuthResult {
         user_id: user.id,
@@ -288,6 +693,7 @@ pub async fn create_guest_session(
         is_new_user: true,
         merged_from_guest: None,
         invalidate_co_player_cache_for: Vec::new(),
+        new_device: None,
     })
 }
 
@@ -322,6 +728,104 @@ pub async fn logout_other_sessions(
     Ok(count)
 }
 
+/// Argon2 hash of a throwaway password, verified against when no account
+/// matches so unknown emails cost as much as wrong passwords.
+static DUMMY_PASSWORD_HASH: std::sync::LazyLock<String> = std::sync::LazyLock::new(|| {
+    credentials::hash_password("dguesser-timing-equalizer").unwrap_or_default()
+});
+
+async fn load_session_user(
+    pool: &sqlx::PgPool,
+    current_session: Option<&str>,
+) -> Result<Option<User>, AuthError> {
+    let Some(sid) = current_session else {
+        return Ok(None);
+    };
+    match sessions::get_valid(pool, sid).await? {
+        Some(session) => Ok(users::get_by_id(pool, &session.user_id).await?),
+        None => Ok(None),
+    }
+}
+
+/// A freshly created session.
+struct StartedSession {
+    id: String,
+    new_device: Option<UserDevice>,
+}
+
+/// Revoke the old session (if any) and create a new one for `user_id`.
+async fn rotate_session(
+    pool: &sqlx::PgPool,
+    user_id: &str,
+    current_session: Option<&str>,
+    session_config: &SessionConfig,
+    client: ClientInfo<'_>,
+) -> Result<StartedSession, AuthError> {
+    if let Some(old_sid) = current_session {
+        let _ = sessions::revoke(pool, old_sid).await;
+    }
+    start_session(pool, user_id, session_config, client).await
+}
+
+/// Record the client's device for `user_id` and create a session on it.
+async fn start_session(
+    pool: &sqlx::PgPool,
+    user_id: &str,
+    session_config: &SessionConfig,
+    client: ClientInfo<'_>,
+) -> Result<StartedSession, AuthError> {
+    let device = DeviceInfo::from_user_agent(client.user_agent);
+    let device_key = device.key();
+    let recorded = devices::record_sighting(
+        pool,
+        user_id,
+        &devices::DeviceSighting {
+            device_key: &device_key,
+            browser: device.browser,
+            os: device.os,
+            device_type: device.device_type.as_str(),
+            country: client.country,
+        },
+    )
+    .await?;
+
+    let session = sessions::create(
+        pool,
+        user_id,
+        session_config.ttl_hours,
+        client.ip,
+        client.user_agent,
+        Some(&recorded.device.id),
+        client.country,
+    )
+    .await?;
+
+    let new_device = (recorded.is_new && recorded.had_other_devices).then_some(recorded.device);
+    Ok(StartedSession { id: session.id, new_device })
+}
+
+async fn issue_token(
+    pool: &sqlx::PgPool,
+    user_id: &str,
+    purpose: AuthTokenPurpose,
+    email: &str,
+    ttl_hours: i64,
+) -> Result<String, AuthError> {
+    let (raw, hash) = credentials::generate_token();
+    db_credentials::create_token(pool, &hash, user_id, purpose, Some(email), ttl_hours).await?;
+    Ok(raw)
+}
+
+/// Map a unique violation on `users.email` (lost race) to [`AuthError::EmailTaken`].
+fn map_email_conflict(err: sqlx::Error) -> AuthError {
+    match &err {
+        sqlx::Error::Database(db_err) if db_err.constraint() == Some("users_email_unique") => {
+            AuthError::EmailTaken
+        }
+        _ => AuthError::Database(err),
+    }
+}
+
 fn verified_email(identity: &OAuthIdentity) -> Option<&str> {
     identity.email.as_deref().filter(|_| identity.email_verified)
 }
@@ -346,6 +850,13 @@ async fn ensure_guest_merge_safe(
     Ok(())
 }
 
+/// The name reported by an OAuth provider, if it passes display-name
+/// moderation.
+fn provider_display_name(identity: &OAuthIdentity) -> Option<String> {
+    let name = identity.name.as_deref()?;
+    check_display_name(name).ok().map(|checked| checked.name)
+}
+
 /// Generate a friendly guest display name.
 ///
 /// Names follow the pattern: "{Adjective} {Noun} {Number}"
@@ -3,6 +3,12 @@
 //! This module provides configuration for session cookies and helpers for
 //! building cookie headers. Session tokens are generated using ChaCha20 RNG
 //! from the `dguesser_core` crate.
+//!
+//! Sessions slide: once a session is past half its lifetime, the next request
+//! renews it (see [`crate::middleware::session_renewal`]), so active users stay
+//! signed in while idle sessions still expire after `ttl_hours`.
+
+use chrono::{DateTime, Utc};
 
 /// Session cookie configuration.
 #[derive(Debug, Clone)]
@@ -19,6 +25,9 @@ pub struct SessionConfig {
     pub secure: bool,
     /// SameSite attribute
     pub same_site: SameSite,
+    /// How long a renewed session's old token stays valid, in seconds, so
+    /// requests already in flight with it do not fail
+    pub renewal_overlap_seconds: i64,
 }
 
 /// SameSite cookie attribute.
@@ -41,6 +50,7 @@ impl Default for SessionConfig {
             path: "/".to_string(),
             secure: true,
             same_site: SameSite::Lax,
+            renewal_overlap_seconds: 30,
         }
     }
 }
@@ -58,6 +68,12 @@ impl SessionConfig {
     pub fn max_age_seconds(&self) -> i64 {
         self.ttl_hours * 3600
     }
+
+    /// Whether a session expiring at `expires_at` is past half its lifetime
+    /// and should be renewed.
+    pub fn needs_renewal(&self, expires_at: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        (expires_at - now).num_seconds() < self.max_age_seconds() / 2
+    }
 }
 
 /// Build a Set-Cookie header value for setting a session cookie.
@@ -178,6 +194,17 @@ mod tests {
         assert_eq!(config.max_age_seconds(), 168 * 3600);
     }
 
+    #[test]
+    fn test_needs_renewal_after_half_lifetime() {
+        let config = SessionConfig::default();
+        let now = Utc::now();
+
+        assert!(!config.needs_renewal(now + chrono::Duration::hours(168), now));
+        assert!(!config.needs_renewal(now + chrono::Duration::hours(85), now));
+        assert!(config.needs_renewal(now + chrono::Duration::hours(83), now));
+        assert!(config.needs_renewal(now + chrono::Duration::seconds(10), now));
+    }
+
     #[test]
     fn test_build_cookie_header() {
         let config = SessionConfig::default();
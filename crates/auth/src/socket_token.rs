@@ -0,0 +1,153 @@
+//! Signed handshake tokens for realtime sockets.
+//!
+//! The API mints a short-lived token for a signed-in user, optionally bound to
+//! a game, and the client passes it in the Socket.IO handshake query. The
+//! realtime server verifies the HMAC-SHA256 signature with the shared secret,
+//! so authenticating a socket needs no database round-trip.
+//!
+//! Tokens look like `st1.<user_id>.<game_id or ->.<expires_unix>.<hex mac>`.
+//! IDs use the nanoid alphabet, which never contains `.`, so the format needs
+//! no escaping and is safe in a query string.
+
+use chrono::{DateTime, Utc};
+
+use crate::signed_token::{SignedTokenError, TokenSigner};
+
+/// How long a socket token stays valid. Tokens are only checked during the
+/// handshake, so this just needs to cover the time to open the connection.
+pub const SOCKET_TOKEN_TTL_SECS: i64 = 60;
+
+const TOKEN_VERSION: &str = "st1";
+const NO_GAME: &str = "-";
+
+/// Verified contents of a socket token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SocketTokenClaims {
+    /// User the socket authenticates as
+    pub user_id: String,
+    /// Game the socket may join, if the token is bound to one
+    pub game_id: Option<String>,
+    /// When the token stops being accepted
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Errors from verifying a socket token.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum SocketTokenError {
+    /// Token is not in the expected format
+    #[error("Malformed socket token")]
+    Malformed,
+    /// Signature does not match
+    #[error("Invalid socket token signature")]
+    BadSignature,
+    /// Token is past its expiry
+    #[error("Socket token expired")]
+    Expired,
+}
+
+impl From<SignedTokenError> for SocketTokenError {
+    fn from(e: SignedTokenError) -> Self {
+        match e {
+            SignedTokenError::Malformed => Self::Malformed,
+            SignedTokenError::BadSignature => Self::BadSignature,
+            SignedTokenError::Expired => Self::Expired,
+        }
+    }
+}
+
+/// Mints and verifies socket tokens with a shared secret.
+#[derive(Debug, Clone)]
+pub struct SocketTokenSigner {
+    signer: TokenSigner,
+}
+
+impl SocketTokenSigner {
+    /// Create a signer from the shared secret (`SOCKET_TOKEN_SECRET`).
+    pub fn new(secret: &str) -> Self {
+        Self { signer: TokenSigner::new(TOKEN_VERSION, secret) }
+    }
+
+    /// Mint a token for `user_id`, optionally bound to `game_id`.
+    pub fn mint(
+        &self,
+        user_id: &str,
+        game_id: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> (String, DateTime<Utc>) {
+        self.signer.mint(
+            &[user_id, game_id.unwrap_or(NO_GAME)],
+            chrono::Duration::seconds(SOCKET_TOKEN_TTL_SECS),
+            now,
+        )
+    }
+
+    /// Verify a token's signature and expiry.
+    pub fn verify(
+        &self,
+        token: &str,
+        now: DateTime<Utc>,
+    ) -> Result<SocketTokenClaims, SocketTokenError> {
+        let verified = self.signer.verify(token, 2, now)?;
+        let [user_id, game_id] = verified.fields[..] else {
+            return Err(SocketTokenError::Malformed);
+        };
+
+        Ok(SocketTokenClaims {
+            user_id: user_id.to_string(),
+            game_id: (game_id != NO_GAME).then(|| game_id.to_string()),
+            expires_at: verified.expires_at,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_with_game() {
+        let signer = SocketTokenSigner::new("secret");
+        let now = Utc::now();
+        let (token, expires_at) = signer.mint("usr_V1StGXR8_Z5j", Some("gam_FyB2aQ9-xL3k"), now);
+
+        let claims = signer.verify(&token, now).unwrap();
+        assert_eq!(claims.user_id, "usr_V1StGXR8_Z5j");
+        assert_eq!(claims.game_id.as_deref(), Some("gam_FyB2aQ9-xL3k"));
+        assert_eq!(claims.expires_at.timestamp(), expires_at.timestamp());
+    }
+
+    #[test]
+    fn test_round_trip_without_game() {
+        let signer = SocketTokenSigner::new("secret");
+        let now = Utc::now();
+        let (token, _) = signer.mint("usr_V1StGXR8_Z5j", None, now);
+
+        assert_eq!(signer.verify(&token, now).unwrap().game_id, None);
+    }
+
+    #[test]
+    fn test_rejects_expired_token() {
+        let signer = SocketTokenSigner::new("secret");
+        let now = Utc::now();
+        let (token, _) = signer.mint("usr_V1StGXR8_Z5j", None, now);
+
+        let later = now + chrono::Duration::seconds(SOCKET_TOKEN_TTL_SECS + 1);
+        assert_eq!(signer.verify(&token, later), Err(SocketTokenError::Expired));
+    }
+
+    #[test]
+    fn test_rejects_tampered_or_foreign_token() {
+        let signer = SocketTokenSigner::new("secret");
+        let now = Utc::now();
+        let (token, _) = signer.mint("usr_V1StGXR8_Z5j", Some("gam_FyB2aQ9-xL3k"), now);
+
+        let tampered = token.replace("gam_FyB2aQ9-xL3k", "gam_otherGame123");
+        assert_eq!(signer.verify(&tampered, now), Err(SocketTokenError::BadSignature));
+
+        let other = SocketTokenSigner::new("other-secret");
+        assert_eq!(other.verify(&token, now), Err(SocketTokenError::BadSignature));
+
+        assert_eq!(signer.verify("garbage", now), Err(SocketTokenError::Malformed));
+        assert_eq!(signer.verify("st1.a.b.c.zz", now), Err(SocketTokenError::Malformed));
+    }
+}
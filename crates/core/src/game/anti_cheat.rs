@@ -0,0 +1,96 @@
+//! Server-side checks on what clients report alongside their guesses.
+//!
+//! Movement, zoom, and rotation restrictions are applied by the client's
+//! Street View viewer, so a modified client can ignore them. The server can
+//! still verify one thing: in no-move modes the client cannot legitimately
+//! leave the round's starting panorama, so the panorama it reports at guess
+//! time must be the round's panorama.
+
+use serde::{Deserialize, Serialize};
+
+use super::rules::GameSettings;
+
+/// Outcome of checking the panorama a client reported with its guess.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanoramaCheck {
+    /// Nothing to check: movement is allowed, the round has no panorama, or
+    /// the client did not report one.
+    Unchecked,
+    /// The reported panorama is the round's panorama.
+    Matches,
+    /// The client reported a different panorama in a no-move mode.
+    Mismatch,
+}
+
+/// Check the panorama a client reported at guess time against the round's.
+pub fn check_reported_panorama(
+    settings: &GameSettings,
+    round_panorama: Option<&str>,
+    reported_panorama: Option<&str>,
+) -> PanoramaCheck {
+    if settings.movement_allowed {
+        return PanoramaCheck::Unchecked;
+    }
+    match (round_panorama, reported_panorama) {
+        (Some(expected), Some(reported)) if expected == reported => PanoramaCheck::Matches,
+        (Some(_), Some(_)) => PanoramaCheck::Mismatch,
+        // Older clients do not report a panorama; random fallback rounds have none
+        _ => PanoramaCheck::Unchecked,
+    }
+}
+
+/// Kind of suspicious behavior recorded for review.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheatSignalKind {
+    /// Guess reported from a different panorama in a no-move mode.
+    PanoramaMismatch,
+    /// Another player in the game reported this player for cheating.
+    PlayerReport,
+    /// A finished solo game's round results failed the score chain check.
+    ScoreChainMismatch,
+}
+
+impl CheatSignalKind {
+    /// Database representation.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::PanoramaMismatch => "panorama_mismatch",
+            Self::PlayerReport => "player_report",
+            Self::ScoreChainMismatch => "score_chain_mismatch",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::GamePreset;
+
+    #[test]
+    fn test_check_reported_panorama() {
+        let classic = GameSettings::from_preset(GamePreset::Classic);
+        assert_eq!(
+            check_reported_panorama(&classic, Some("pano_a"), Some("pano_b")),
+            PanoramaCheck::Unchecked
+        );
+
+        let no_move = GameSettings::from_preset(GamePreset::NoMove);
+        assert_eq!(
+            check_reported_panorama(&no_move, Some("pano_a"), Some("pano_a")),
+            PanoramaCheck::Matches
+        );
+        assert_eq!(
+            check_reported_panorama(&no_move, Some("pano_a"), Some("pano_b")),
+            PanoramaCheck::Mismatch
+        );
+        assert_eq!(
+            check_reported_panorama(&no_move, Some("pano_a"), None),
+            PanoramaCheck::Unchecked
+        );
+        assert_eq!(
+            check_reported_panorama(&no_move, None, Some("pano_b")),
+            PanoramaCheck::Unchecked
+        );
+    }
+}
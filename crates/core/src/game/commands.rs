@@ -5,7 +5,9 @@
 
 use serde::{Deserialize, Serialize};
 
+use super::hints::HintKind;
 use super::rules::GameSettings;
+use crate::streetview::ImageryProvider;
 
 /// Location data for starting or advancing a round.
 ///
@@ -23,12 +25,26 @@ pub struct LocationData {
     pub location_id: Option<String>,
     /// Default heading/direction for the panorama (degrees, 0-360)
     pub heading: Option<f64>,
+    /// Provider of the imagery
+    #[serde(default)]
+    pub provider: ImageryProvider,
+    /// Country of the location (ISO 3166-1 alpha-2), if known
+    #[serde(default)]
+    pub country_code: Option<String>,
 }
 
 impl LocationData {
     /// Create a new location data instance.
     pub fn new(lat: f64, lng: f64, panorama_id: Option<String>) -> Self {
-        Self { lat, lng, panorama_id, location_id: None, heading: None }
+        Self {
+            lat,
+            lng,
+            panorama_id,
+            location_id: None,
+            heading: None,
+            provider: ImageryProvider::default(),
+            country_code: None,
+        }
     }
 
     /// Create a new location data instance with location ID.
@@ -38,7 +54,15 @@ impl LocationData {
         panorama_id: Option<String>,
         location_id: String,
     ) -> Self {
-        Self { lat, lng, panorama_id, location_id: Some(location_id), heading: None }
+        Self {
+            lat,
+            lng,
+            panorama_id,
+            location_id: Some(location_id),
+            heading: None,
+            provider: ImageryProvider::default(),
+            country_code: None,
+        }
     }
 
     /// Create a new location data with all optional fields.
@@ -48,8 +72,15 @@ impl LocationData {
         panorama_id: Option<String>,
         location_id: Option<String>,
         heading: Option<f64>,
+        provider: ImageryProvider,
     ) -> Self {
-        Self { lat, lng, panorama_id, location_id, heading }
+        Self { lat, lng, panorama_id, location_id, heading, provider, country_code: None }
+    }
+
+    /// Set the country of the location.
+    pub fn with_country_code(mut self, country_code: Option<String>) -> Self {
+        self.country_code = country_code;
+        self
     }
 }
 
@@ -107,7 +138,21 @@ pub enum GameCommand {
         first_location: LocationData,
     },
 
+    /// A player starts their next round in a per-player game.
+    ///
+    /// Only valid in the `Active` phase of a `PerPlayer` game, once the
+    /// player's previous round was guessed or timed out. The caller passes the
+    /// same location every player gets for that round number.
+    StartPlayerRound {
+        /// User ID of the player starting the round
+        user_id: String,
+        /// Location for the player's next round
+        location: LocationData,
+    },
+
     /// A player submits a guess for the current round.
+    ///
+    /// In per-player games this is the player's own current round.
     SubmitGuess {
         /// User ID of the player guessing
         user_id: String,
@@ -117,6 +162,23 @@ pub enum GameCommand {
         lng: f64,
         /// Time taken to submit the guess in milliseconds
         time_taken_ms: Option<u32>,
+        /// Panorama the client was viewing when it guessed, if reported
+        reported_panorama_id: Option<String>,
+        /// Country the player named (required in streak mode)
+        country_code: Option<String>,
+    },
+
+    /// A player takes a hint about the current round's location.
+    ///
+    /// Each kind is revealed once per round; taking it again repeats the
+    /// first reveal at no cost. The cost comes off the player's guess.
+    RequestHint {
+        /// User ID of the player asking
+        user_id: String,
+        /// What to reveal
+        kind: HintKind,
+        /// (lat, lng) a direction hint points from
+        from: Option<(f64, f64)>,
     },
 
     /// End the current round.
@@ -138,6 +200,17 @@ pub enum GameCommand {
         next_location: LocationData,
     },
 
+    /// Host replaces the current round's location (e.g. a broken panorama).
+    ///
+    /// Only valid early in a shared round, up to a per-game quota. Discards
+    /// the round's guesses and restarts the round timer.
+    RerollRound {
+        /// User ID of the host requesting the reroll
+        user_id: String,
+        /// Replacement location for the round
+        new_location: LocationData,
+    },
+
     /// End the entire game.
     ///
     /// Calculates final standings and transitions to `Finished`.
@@ -162,6 +235,18 @@ pub enum GameCommand {
         settings: GameSettings,
     },
 
+    /// Set a player's score multiplier.
+    ///
+    /// Only valid in the `Lobby` phase and only by the host.
+    SetHandicap {
+        /// User ID of the player attempting to update (must be host)
+        user_id: String,
+        /// User ID of the player whose multiplier changes
+        player_id: String,
+        /// Multiplier applied to the player's round scores
+        handicap: f64,
+    },
+
     /// Host force-skips the between-rounds wait.
     ///
     /// Only valid in the `BetweenRounds` phase and only by the host.
@@ -179,6 +264,21 @@ pub enum GameCommand {
         /// User ID of the player voting to skip
         user_id: String,
     },
+
+    /// The teacher pauses a classroom game.
+    ///
+    /// Only valid during rounds and between rounds. Round timers stop until
+    /// the teacher resumes.
+    Pause {
+        /// User ID of the teacher
+        user_id: String,
+    },
+
+    /// The teacher resumes a classroom game they paused.
+    Resume {
+        /// User ID of the teacher
+        user_id: String,
+    },
 }
 
 impl GameCommand {
@@ -190,10 +290,16 @@ impl GameCommand {
             | GameCommand::Disconnect { user_id }
             | GameCommand::Reconnect { user_id }
             | GameCommand::Start { user_id, .. }
+            | GameCommand::StartPlayerRound { user_id, .. }
             | GameCommand::SubmitGuess { user_id, .. }
+            | GameCommand::RerollRound { user_id, .. }
             | GameCommand::UpdateSettings { user_id, .. }
+            | GameCommand::SetHandicap { user_id, .. }
             | GameCommand::SkipWait { user_id }
-            | GameCommand::VoteSkipWait { user_id } => Some(user_id),
+            | GameCommand::VoteSkipWait { user_id }
+            | GameCommand::Pause { user_id }
+            | GameCommand::Resume { user_id }
+            | GameCommand::RequestHint { user_id, .. } => Some(user_id),
             GameCommand::EndRound
             | GameCommand::AdvanceRound { .. }
             | GameCommand::EndGame
@@ -207,7 +313,11 @@ impl GameCommand {
             self,
             GameCommand::Start { .. }
                 | GameCommand::UpdateSettings { .. }
+                | GameCommand::SetHandicap { .. }
                 | GameCommand::SkipWait { .. }
+                | GameCommand::RerollRound { .. }
+                | GameCommand::Pause { .. }
+                | GameCommand::Resume { .. }
         )
     }
 
@@ -219,14 +329,20 @@ impl GameCommand {
             GameCommand::Disconnect { .. } => "Disconnect",
             GameCommand::Reconnect { .. } => "Reconnect",
             GameCommand::Start { .. } => "Start",
+            GameCommand::StartPlayerRound { .. } => "StartPlayerRound",
             GameCommand::SubmitGuess { .. } => "SubmitGuess",
             GameCommand::EndRound => "EndRound",
             GameCommand::AdvanceRound { .. } => "AdvanceRound",
+            GameCommand::RerollRound { .. } => "RerollRound",
             GameCommand::EndGame => "EndGame",
             GameCommand::Tick => "Tick",
             GameCommand::UpdateSettings { .. } => "UpdateSettings",
+            GameCommand::SetHandicap { .. } => "SetHandicap",
             GameCommand::SkipWait { .. } => "SkipWait",
             GameCommand::VoteSkipWait { .. } => "VoteSkipWait",
+            GameCommand::Pause { .. } => "Pause",
+            GameCommand::Resume { .. } => "Resume",
+            GameCommand::RequestHint { .. } => "RequestHint",
         }
     }
 }
@@ -262,6 +378,8 @@ mod tests {
             lat: 0.0,
             lng: 0.0,
             time_taken_ms: None,
+            reported_panorama_id: None,
+            country_code: None,
         };
         assert!(!guess.requires_host());
     }
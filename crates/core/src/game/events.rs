@@ -9,6 +9,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use super::hints::Hint;
 use super::rules::GameSettings;
 
 /// Events emitted by the game reducer.
@@ -49,6 +50,7 @@ pub enum GameEvent {
     /// A new round has started.
     RoundStarted {
         round_number: u8,
+        /// Rounds in the game (0 = unbounded streak)
         total_rounds: u8,
         location_lat: f64,
         location_lng: f64,
@@ -58,9 +60,40 @@ pub enum GameEvent {
         started_at: DateTime<Utc>,
     },
 
+    /// A player started their own next round in a per-player game.
+    PlayerRoundStarted {
+        user_id: String,
+        round_number: u8,
+        total_rounds: u8,
+        /// Time limit in milliseconds (None = unlimited)
+        time_limit_ms: Option<u32>,
+        started_at: DateTime<Utc>,
+    },
+
     /// A player submitted a guess (details hidden from other players).
     GuessSubmitted { user_id: String, display_name: String },
 
+    /// A player took a hint (sent to that player only).
+    HintRevealed {
+        user_id: String,
+        round_number: u8,
+        hint: Hint,
+        /// Points the hint costs; 0 when it was already revealed
+        cost: u32,
+    },
+
+    /// The host rerolled the current round; a `RoundStarted` for the new
+    /// location follows.
+    RoundRerolled {
+        round_number: u8,
+        /// User ID of the host
+        rerolled_by: String,
+        /// Players whose guesses were discarded
+        discarded_guesses: Vec<String>,
+        /// Rerolls the host has left in this game
+        rerolls_left: u8,
+    },
+
     /// A round has ended with full results.
     RoundEnded {
         round_number: u8,
@@ -82,6 +115,14 @@ pub enum GameEvent {
         settings: GameSettings,
     },
 
+    /// A player's score multiplier changed (in lobby).
+    HandicapUpdated {
+        /// User ID of the player
+        user_id: String,
+        /// New multiplier applied to the player's round scores
+        handicap: f64,
+    },
+
     /// The host force-skipped the between-rounds wait.
     WaitSkipped,
 
@@ -101,6 +142,22 @@ pub enum GameEvent {
     /// The between-rounds wait expired (timer elapsed).
     BetweenRoundsExpired,
 
+    /// The game paused because the host disconnected, or the teacher of a
+    /// classroom game paused it.
+    GamePaused {
+        /// User ID of the host
+        user_id: String,
+        /// How long the game waits for the host at most, in milliseconds
+        /// (None = until the teacher resumes)
+        grace_period_ms: Option<u32>,
+    },
+
+    /// The game resumed after a pause; round timers moved on by the pause.
+    GameResumed {
+        /// How long the game was paused, in milliseconds
+        paused_ms: i64,
+    },
+
     /// The game was abandoned (all players disconnected for too long).
     GameAbandoned {
         /// Reason for abandonment
@@ -145,15 +202,21 @@ impl GameEvent {
             GameEvent::PlayerTimedOut { .. } => "PlayerTimedOut",
             GameEvent::GameStarted { .. } => "GameStarted",
             GameEvent::RoundStarted { .. } => "RoundStarted",
+            GameEvent::PlayerRoundStarted { .. } => "PlayerRoundStarted",
             GameEvent::GuessSubmitted { .. } => "GuessSubmitted",
+            GameEvent::HintRevealed { .. } => "HintRevealed",
+            GameEvent::RoundRerolled { .. } => "RoundRerolled",
             GameEvent::RoundEnded { .. } => "RoundEnded",
             GameEvent::ScoresUpdated { .. } => "ScoresUpdated",
             GameEvent::GameEnded { .. } => "GameEnded",
             GameEvent::SettingsUpdated { .. } => "SettingsUpdated",
+            GameEvent::HandicapUpdated { .. } => "HandicapUpdated",
             GameEvent::WaitSkipped => "WaitSkipped",
             GameEvent::SkipVoteRecorded { .. } => "SkipVoteRecorded",
             GameEvent::SkipVotePassed => "SkipVotePassed",
             GameEvent::BetweenRoundsExpired => "BetweenRoundsExpired",
+            GameEvent::GamePaused { .. } => "GamePaused",
+            GameEvent::GameResumed { .. } => "GameResumed",
             GameEvent::GameAbandoned { .. } => "GameAbandoned",
             GameEvent::Error { .. } => "Error",
         }
@@ -188,10 +251,12 @@ pub struct ScoreData {
     pub display_name: String,
     /// Avatar URL
     pub avatar_url: Option<String>,
-    /// Total score across all rounds
+    /// Total score across all rounds, with the handicap applied
     pub total_score: u32,
-    /// Score from the current round (0 if not yet guessed)
+    /// Score from the current round before the handicap (0 if not yet guessed)
     pub round_score: u32,
+    /// Multiplier applied to round scores
+    pub handicap: f64,
     /// Whether the player has guessed this round
     pub has_guessed: bool,
     /// Current rank (1 = first place)
@@ -211,6 +276,12 @@ pub struct FinalStandingData {
     pub display_name: String,
     /// Total score
     pub total_score: u32,
+    /// Streak length (streak games only)
+    #[serde(default)]
+    pub streak: Option<u32>,
+    /// First round a late joiner played (`None` if they played every round)
+    #[serde(default)]
+    pub joined_round: Option<u8>,
 }
 
 #[cfg(test)]
@@ -0,0 +1,214 @@
+//! Round hints a player can take during a round, each costing score points.
+//!
+//! Hints are revealed once per round and player: asking for the same kind
+//! again returns the hint already given, for free. Their cost comes off the
+//! player's guess in that round.
+
+use serde::{Deserialize, Serialize};
+
+use super::state::RoundState;
+use crate::geo::countries::{self, Continent};
+use crate::geo::distance::initial_bearing;
+
+/// What a hint reveals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HintKind {
+    /// The continent the location is on
+    Continent,
+    /// First letter of the country's English name
+    CountryLetter,
+    /// Compass direction from a point the player picks to the location
+    Direction,
+}
+
+impl HintKind {
+    /// Every hint kind
+    pub const ALL: [HintKind; 3] = [Self::Continent, Self::CountryLetter, Self::Direction];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Continent => "continent",
+            Self::CountryLetter => "country_letter",
+            Self::Direction => "direction",
+        }
+    }
+}
+
+impl std::str::FromStr for HintKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == s)
+            .ok_or_else(|| format!("Invalid hint kind: {s}"))
+    }
+}
+
+/// One of the eight compass points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum CompassPoint {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl CompassPoint {
+    const ALL: [CompassPoint; 8] =
+        [Self::N, Self::NE, Self::E, Self::SE, Self::S, Self::SW, Self::W, Self::NW];
+
+    /// Nearest compass point to a bearing in degrees clockwise from north.
+    pub fn from_bearing(bearing: f64) -> Self {
+        let sector = (bearing.rem_euclid(360.0) / 45.0).round() as usize % 8;
+        Self::ALL[sector]
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::N => "N",
+            Self::NE => "NE",
+            Self::E => "E",
+            Self::SE => "SE",
+            Self::S => "S",
+            Self::SW => "SW",
+            Self::W => "W",
+            Self::NW => "NW",
+        }
+    }
+}
+
+/// A revealed hint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Hint {
+    Continent { continent: Continent },
+    CountryLetter { letter: char },
+    Direction { from_lat: f64, from_lng: f64, direction: CompassPoint },
+}
+
+impl Hint {
+    pub fn kind(&self) -> HintKind {
+        match self {
+            Self::Continent { .. } => HintKind::Continent,
+            Self::CountryLetter { .. } => HintKind::CountryLetter,
+            Self::Direction { .. } => HintKind::Direction,
+        }
+    }
+
+    /// The revealed value as shown to players: a continent ID, a letter or
+    /// a compass point.
+    pub fn value(&self) -> String {
+        match self {
+            Self::Continent { continent } => continent.as_str().to_string(),
+            Self::CountryLetter { letter } => letter.to_string(),
+            Self::Direction { direction, .. } => direction.as_str().to_string(),
+        }
+    }
+}
+
+/// Reveal a hint about a round's location. `from` is the (lat, lng) the
+/// direction hint points from. Errors carry a reducer error code and message.
+pub fn reveal(
+    kind: HintKind,
+    round: &RoundState,
+    from: Option<(f64, f64)>,
+) -> Result<Hint, (&'static str, &'static str)> {
+    const NO_COUNTRY: (&str, &str) =
+        ("HINT_UNAVAILABLE", "This location has no country to hint at");
+
+    match kind {
+        HintKind::Continent => {
+            let country = round.country_code.as_deref().and_then(countries::lookup);
+            country
+                .map(|country| Hint::Continent { continent: country.continent })
+                .ok_or(NO_COUNTRY)
+        }
+        HintKind::CountryLetter => {
+            let country = round.country_code.as_deref().and_then(countries::lookup);
+            country
+                .and_then(|country| country.name.chars().next())
+                .map(|letter| Hint::CountryLetter { letter })
+                .ok_or(NO_COUNTRY)
+        }
+        HintKind::Direction => {
+            let Some((from_lat, from_lng)) = from else {
+                return Err(("POINT_REQUIRED", "Direction hints need a point to point from"));
+            };
+            if !(-90.0..=90.0).contains(&from_lat) || !(-180.0..=180.0).contains(&from_lng) {
+                return Err(("INVALID_POINT", "Point is not a valid coordinate"));
+            }
+            let bearing =
+                initial_bearing(from_lat, from_lng, round.location_lat, round.location_lng);
+            Ok(Hint::Direction {
+                from_lat,
+                from_lng,
+                direction: CompassPoint::from_bearing(bearing),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+    use crate::streetview::ImageryProvider;
+
+    fn paris_round() -> RoundState {
+        let mut round = RoundState::new(
+            1,
+            48.8566,
+            2.3522,
+            None,
+            None,
+            None,
+            ImageryProvider::default(),
+            None,
+            Utc::now(),
+        );
+        round.country_code = Some("fr".to_string());
+        round
+    }
+
+    #[test]
+    fn test_reveal_country_hints() {
+        let round = paris_round();
+        assert_eq!(
+            reveal(HintKind::Continent, &round, None),
+            Ok(Hint::Continent { continent: Continent::Europe })
+        );
+        assert_eq!(
+            reveal(HintKind::CountryLetter, &round, None),
+            Ok(Hint::CountryLetter { letter: 'F' })
+        );
+
+        let mut unknown = paris_round();
+        unknown.country_code = None;
+        assert_eq!(reveal(HintKind::Continent, &unknown, None).unwrap_err().0, "HINT_UNAVAILABLE");
+    }
+
+    #[test]
+    fn test_reveal_direction() {
+        let round = paris_round();
+        // From Madrid, Paris is to the north-northeast
+        let hint = reveal(HintKind::Direction, &round, Some((40.4168, -3.7038))).unwrap();
+        assert_eq!(hint.value(), "NE");
+        assert_eq!(reveal(HintKind::Direction, &round, None).unwrap_err().0, "POINT_REQUIRED");
+    }
+
+    #[test]
+    fn test_compass_point_from_bearing() {
+        assert_eq!(CompassPoint::from_bearing(0.0), CompassPoint::N);
+        assert_eq!(CompassPoint::from_bearing(350.0), CompassPoint::N);
+        assert_eq!(CompassPoint::from_bearing(100.0), CompassPoint::E);
+        assert_eq!(CompassPoint::from_bearing(225.0), CompassPoint::SW);
+    }
+}
@@ -0,0 +1,91 @@
+//! Historical guess statistics per location
+
+use serde::{Deserialize, Serialize};
+
+/// Number of equal steps between stored distance quantiles (every 5%).
+pub const DISTANCE_QUANTILE_STEPS: usize = 20;
+
+/// Fewest historical guesses before a location's statistics are shown.
+pub const MIN_GUESSES_FOR_STATS: u32 = 10;
+
+/// Fractions at which guess distances are stored: 0.0, 0.05, ..., 1.0.
+pub fn distance_quantile_fractions() -> Vec<f64> {
+    (0..=DISTANCE_QUANTILE_STEPS).map(|i| i as f64 / DISTANCE_QUANTILE_STEPS as f64).collect()
+}
+
+/// Aggregated distances of past guesses on one location
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LocationGuessStats {
+    /// Number of guesses aggregated
+    pub guesses: u32,
+    /// Median guess distance in meters
+    pub median_distance_meters: f64,
+    /// Guess distance (meters) at each of [`distance_quantile_fractions`]
+    pub distance_quantiles: Vec<f64>,
+}
+
+impl LocationGuessStats {
+    /// Whether enough guesses were aggregated to compare against.
+    pub fn is_meaningful(&self) -> bool {
+        self.guesses >= MIN_GUESSES_FOR_STATS
+            && self.distance_quantiles.len() == DISTANCE_QUANTILE_STEPS + 1
+    }
+
+    /// Percentage (0-100, one decimal) of past guesses that were further
+    /// away than `distance_meters`.
+    ///
+    /// Returns `None` for no-guess results or when there is too little data.
+    pub fn percentile_beaten(&self, distance_meters: f64) -> Option<f64> {
+        if !self.is_meaningful() || distance_meters < 0.0 {
+            return None;
+        }
+
+        let q = &self.distance_quantiles;
+        let rank = if distance_meters <= q[0] {
+            0.0
+        } else if distance_meters >= q[DISTANCE_QUANTILE_STEPS] {
+            1.0
+        } else {
+            // First quantile above the distance; interpolate within its step
+            let upper = q.iter().position(|&d| d > distance_meters).unwrap_or(q.len() - 1);
+            let (lo, hi) = (q[upper - 1], q[upper]);
+            let within = (distance_meters - lo) / (hi - lo);
+            (upper as f64 - 1.0 + within) / DISTANCE_QUANTILE_STEPS as f64
+        };
+
+        Some(((1.0 - rank) * 1000.0).round() / 10.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(guesses: u32) -> LocationGuessStats {
+        // Evenly spread distances: 0 km, 50 km, ..., 1000 km
+        LocationGuessStats {
+            guesses,
+            median_distance_meters: 500_000.0,
+            distance_quantiles: (0..=DISTANCE_QUANTILE_STEPS)
+                .map(|i| i as f64 * 50_000.0)
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_percentile_beaten() {
+        let stats = stats(100);
+        assert_eq!(stats.percentile_beaten(0.0), Some(100.0));
+        assert_eq!(stats.percentile_beaten(250_000.0), Some(75.0));
+        assert_eq!(stats.percentile_beaten(500_000.0), Some(50.0));
+        assert_eq!(stats.percentile_beaten(525_000.0), Some(47.5));
+        assert_eq!(stats.percentile_beaten(5_000_000.0), Some(0.0));
+    }
+
+    #[test]
+    fn test_percentile_requires_data() {
+        assert_eq!(stats(MIN_GUESSES_FOR_STATS - 1).percentile_beaten(100.0), None);
+        assert_eq!(stats(100).percentile_beaten(-1.0), None);
+        assert_eq!(distance_quantile_fractions().len(), DISTANCE_QUANTILE_STEPS + 1);
+    }
+}
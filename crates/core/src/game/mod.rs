@@ -22,24 +22,37 @@
 //!
 //! # Modules
 //!
+//! - [`anti_cheat`] - Server-side checks on client-reported guess data
 //! - [`commands`] - Commands that can be applied to game state
 //! - [`events`] - Events emitted by the reducer for broadcasting/persistence
+//! - [`hints`] - Round hints players can take for score points
+//! - [`location_stats`] - Historical guess statistics per location
 //! - [`reducer`] - The pure reduce function (heart of the game logic)
 //! - [`rules`] - Game settings and validation
 //! - [`scoring`] - Score calculation algorithms
+//! - `simulation` - Randomized command sequences and invariant checks for
+//!   testing the reducer (tests and the `simulation` feature only)
 //! - [`state`] - Core state types (GameState, PlayerState, RoundState)
 
+pub mod anti_cheat;
 pub mod commands;
 pub mod events;
+pub mod hints;
+pub mod location_stats;
 pub mod reducer;
 pub mod rules;
 pub mod scoring;
+#[cfg(any(test, feature = "simulation"))]
+pub mod simulation;
 pub mod state;
 
 // Re-export commonly used types for convenience
+pub use anti_cheat::{CheatSignalKind, PanoramaCheck, check_reported_panorama};
 pub use commands::{GameCommand, LocationData};
 pub use events::{FinalStandingData, GameEvent, RoundResultData, ScoreData};
+pub use hints::{CompassPoint, Hint, HintKind};
+pub use location_stats::LocationGuessStats;
 pub use reducer::{BETWEEN_ROUNDS_WAIT_MS, ReducerResult, reduce};
 pub use rules::*;
 pub use scoring::*;
-pub use state::{GamePhase, GameState, Guess, PlayerState, RoundState};
+pub use state::{GamePhase, GameState, Guess, PlayerState, RoundProgression, RoundState};
@@ -8,16 +8,17 @@
 
 use chrono::{DateTime, Utc};
 
+use super::anti_cheat::{PanoramaCheck, check_reported_panorama};
 use super::commands::{GameCommand, LocationData};
 use super::events::{FinalStandingData, GameEvent, RoundResultData, ScoreData};
-use super::rules::{GameSettings, validate_settings};
-use super::scoring::{ScoringConfig, calculate_score};
-use super::state::{GamePhase, GameState, Guess, PlayerState, RoundState};
+use super::hints::{self, HintKind};
+use super::rules::{DisconnectPolicy, GameSettings, validate_settings};
+use super::scoring::{
+    MAX_HANDICAP, MIN_HANDICAP, apply_handicap, calculate_score, is_valid_handicap, streak_score,
+};
+use super::state::{GamePhase, GameState, Guess, PlayerState, RoundProgression, RoundState};
 use crate::geo::distance::haversine_distance;
 
-/// Grace period for reconnection in lobby in milliseconds (30 seconds).
-pub const LOBBY_RECONNECTION_GRACE_PERIOD_MS: u32 = 30_000;
-
 /// Timeout for abandonment when all players disconnect during active game (2 minutes).
 pub const ALL_DISCONNECTED_TIMEOUT_MS: u32 = 120_000;
 
@@ -27,6 +28,12 @@ pub const MAX_PLAYERS_PER_GAME: usize = 50;
 /// Duration of the between-rounds wait in milliseconds (20 seconds).
 pub const BETWEEN_ROUNDS_WAIT_MS: i64 = 20_000;
 
+/// How long after a round starts the host can still reroll it (15 seconds).
+pub const REROLL_WINDOW_MS: i64 = 15_000;
+
+/// Maximum rounds the host can reroll per game.
+pub const MAX_REROLLS_PER_GAME: u8 = 2;
+
 /// Result of applying a command to the game state.
 #[derive(Debug)]
 pub struct ReducerResult {
@@ -89,26 +96,48 @@ pub fn reduce(state: &GameState, command: GameCommand, now: DateTime<Utc>) -> Re
             handle_join(state.clone(), user_id, display_name, avatar_url, is_host)
         }
 
-        GameCommand::Leave { user_id } => handle_leave(state.clone(), user_id),
+        GameCommand::Leave { user_id } => handle_leave(state.clone(), user_id, now),
 
         GameCommand::Disconnect { user_id } => handle_disconnect(state.clone(), user_id, now),
 
-        GameCommand::Reconnect { user_id } => handle_reconnect(state.clone(), user_id),
+        GameCommand::Reconnect { user_id } => handle_reconnect(state.clone(), user_id, now),
 
         GameCommand::Start { user_id, first_location } => {
             handle_start(state.clone(), user_id, first_location, now)
         }
 
-        GameCommand::SubmitGuess { user_id, lat, lng, time_taken_ms } => {
-            handle_submit_guess(state.clone(), user_id, lat, lng, time_taken_ms, now)
+        GameCommand::StartPlayerRound { user_id, location } => {
+            handle_start_player_round(state.clone(), user_id, location, now)
         }
 
+        GameCommand::SubmitGuess {
+            user_id,
+            lat,
+            lng,
+            time_taken_ms,
+            reported_panorama_id,
+            country_code,
+        } => handle_submit_guess(
+            state.clone(),
+            user_id,
+            lat,
+            lng,
+            time_taken_ms,
+            reported_panorama_id,
+            country_code,
+            now,
+        ),
+
         GameCommand::EndRound => handle_end_round(state.clone(), now),
 
         GameCommand::AdvanceRound { next_location } => {
             handle_advance_round(state.clone(), next_location, now)
         }
 
+        GameCommand::RerollRound { user_id, new_location } => {
+            handle_reroll_round(state.clone(), user_id, new_location, now)
+        }
+
         GameCommand::EndGame => handle_end_game(state.clone()),
 
         GameCommand::Tick => handle_tick(state.clone(), now),
@@ -117,9 +146,21 @@ pub fn reduce(state: &GameState, command: GameCommand, now: DateTime<Utc>) -> Re
             handle_update_settings(state.clone(), user_id, settings)
         }
 
+        GameCommand::SetHandicap { user_id, player_id, handicap } => {
+            handle_set_handicap(state.clone(), user_id, player_id, handicap)
+        }
+
         GameCommand::SkipWait { user_id } => handle_skip_wait(state.clone(), user_id),
 
         GameCommand::VoteSkipWait { user_id } => handle_vote_skip_wait(state.clone(), user_id),
+
+        GameCommand::Pause { user_id } => handle_pause(state.clone(), user_id, now),
+
+        GameCommand::Resume { user_id } => handle_resume(state.clone(), user_id, now),
+
+        GameCommand::RequestHint { user_id, kind, from } => {
+            handle_request_hint(state.clone(), user_id, kind, from, now)
+        }
     }
 }
 
@@ -134,8 +175,9 @@ fn handle_join(
     avatar_url: Option<String>,
     is_host: bool,
 ) -> ReducerResult {
-    // Can only join in lobby
-    if state.phase != GamePhase::Lobby {
+    // Can only join in lobby, unless the game takes late joiners
+    let late = state.phase != GamePhase::Lobby;
+    if late && !state.accepts_late_join() {
         return ReducerResult::error(state, "GAME_STARTED", "Cannot join a game in progress");
     }
 
@@ -153,25 +195,36 @@ fn handle_join(
         );
     }
 
-    // Add player
-    state.players.insert(
-        user_id.clone(),
-        PlayerState::new(user_id.clone(), display_name.clone(), avatar_url.clone(), is_host),
-    );
+    // Add player; a late joiner is never the host and scores from the next
+    // round they can play
+    let is_host = is_host && !late;
+    let mut player =
+        PlayerState::new(user_id.clone(), display_name.clone(), avatar_url.clone(), is_host);
+    if late {
+        player.joined_round = Some(state.next_playable_round());
+        // Someone is here again, so the game is no longer abandoned
+        state.all_disconnected_at = None;
+    }
+    state.players.insert(user_id.clone(), player);
 
     let event = GameEvent::PlayerJoined { user_id, display_name, avatar_url, is_host };
 
     ReducerResult::with_events(state, vec![event])
 }
 
-fn handle_leave(mut state: GameState, user_id: String) -> ReducerResult {
+fn handle_leave(mut state: GameState, user_id: String, now: DateTime<Utc>) -> ReducerResult {
     let Some(player) = state.players.remove(&user_id) else {
         return ReducerResult::error(state, "NOT_IN_GAME", "Player not in this game");
     };
 
-    let event = GameEvent::PlayerLeft { user_id, display_name: player.display_name };
+    let mut events = vec![GameEvent::PlayerLeft { user_id, display_name: player.display_name }];
 
-    ReducerResult::with_events(state, vec![event])
+    // A host who leaves for good will not be coming back to unpause
+    if player.is_host {
+        events.extend(resume_game(&mut state, now));
+    }
+
+    ReducerResult::with_events(state, events)
 }
 
 fn handle_disconnect(mut state: GameState, user_id: String, now: DateTime<Utc>) -> ReducerResult {
@@ -187,12 +240,22 @@ fn handle_disconnect(mut state: GameState, user_id: String, now: DateTime<Utc>)
     player.connected = false;
     player.disconnected_at = Some(now);
     let display_name = player.display_name.clone();
-
-    // In lobby: 30-second grace period before being removed
-    // During active game: player stays until game ends (no individual timeout)
-    let grace_period_ms = match state.phase {
-        GamePhase::Lobby => Some(LOBBY_RECONNECTION_GRACE_PERIOD_MS),
-        _ => None, // No individual timeout during active game
+    let is_host = player.is_host;
+
+    // In lobby: grace period before being removed
+    // During active game: depends on the disconnect policy
+    let grace_ms = state.settings.reconnect_grace_ms();
+    let in_round = matches!(state.phase, GamePhase::RoundInProgress | GamePhase::BetweenRounds);
+    let pause = in_round
+        && is_host
+        && state.settings.disconnect_policy == DisconnectPolicy::PauseIfHost
+        && state.paused_at.is_none();
+    let grace_period_ms = match (state.phase, state.settings.disconnect_policy) {
+        (GamePhase::Lobby, _) => Some(grace_ms),
+        (GamePhase::Finished, _) => None,
+        (_, DisconnectPolicy::RemovePlayer) => Some(grace_ms),
+        (_, DisconnectPolicy::PauseIfHost) if pause => Some(grace_ms),
+        _ => None, // Player stays until the game ends
     };
 
     // Check if ALL players are now disconnected (for game abandonment tracking)
@@ -206,6 +269,15 @@ fn handle_disconnect(mut state: GameState, user_id: String, now: DateTime<Utc>)
 
     let mut events = vec![event];
 
+    // Round timers stop until the host is back or the grace period runs out
+    if pause {
+        state.paused_at = Some(now);
+        events.push(GameEvent::GamePaused {
+            user_id: user_id.clone(),
+            grace_period_ms: Some(grace_ms),
+        });
+    }
+
     // If between rounds, remove disconnected player's vote and recheck threshold
     if state.phase == GamePhase::BetweenRounds {
         state.skip_votes.remove(&user_id);
@@ -220,7 +292,7 @@ fn handle_disconnect(mut state: GameState, user_id: String, now: DateTime<Utc>)
     ReducerResult::with_events(state, events)
 }
 
-fn handle_reconnect(mut state: GameState, user_id: String) -> ReducerResult {
+fn handle_reconnect(mut state: GameState, user_id: String, now: DateTime<Utc>) -> ReducerResult {
     let Some(player) = state.players.get_mut(&user_id) else {
         return ReducerResult::error(state, "NOT_IN_GAME", "Player not in this game");
     };
@@ -233,12 +305,40 @@ fn handle_reconnect(mut state: GameState, user_id: String) -> ReducerResult {
     player.connected = true;
     player.disconnected_at = None;
 
+    let display_name = player.display_name.clone();
+    let is_host = player.is_host;
+
     // A player reconnected, so we're no longer in "all disconnected" state
     state.all_disconnected_at = None;
 
-    let event = GameEvent::PlayerReconnected { user_id, display_name: player.display_name.clone() };
+    let mut events = vec![GameEvent::PlayerReconnected { user_id, display_name }];
 
-    ReducerResult::with_events(state, vec![event])
+    // A teacher's own pause lasts until they resume
+    if is_host && !state.teacher_paused {
+        events.extend(resume_game(&mut state, now));
+    }
+
+    ReducerResult::with_events(state, events)
+}
+
+/// Resume a paused game, moving the round timers on by the time spent paused.
+fn resume_game(state: &mut GameState, now: DateTime<Utc>) -> Option<GameEvent> {
+    let paused_at = state.paused_at.take()?;
+    state.teacher_paused = false;
+    let paused = now - paused_at;
+    let paused_ms = paused.num_milliseconds();
+
+    if let Some(round) = state.current_round.as_mut() {
+        round.started_at += paused;
+    }
+    if let Some(ends_at) = state.between_rounds_ends_at.as_mut() {
+        *ends_at += paused_ms;
+    }
+    if let Some(ends_at) = state.time_budget_ends_at.as_mut() {
+        *ends_at += paused_ms;
+    }
+
+    Some(GameEvent::GameResumed { paused_ms })
 }
 
 fn handle_start(
@@ -262,33 +362,50 @@ fn handle_start(
         return ReducerResult::error(state, "NO_PLAYERS", "Cannot start with no players");
     }
 
+    // One player's miss ends a streak game, so streaks are played alone
+    if state.settings.streak && state.players.len() > 1 {
+        return ReducerResult::error(state, "STREAK_SOLO_ONLY", "Streak games are single player");
+    }
+
+    // Per-player games wait for each player to start their own first round
+    if state.progression == RoundProgression::PerPlayer {
+        state.phase = GamePhase::Active;
+        state.started_at = Some(now);
+        return ReducerResult::with_events(state, vec![GameEvent::GameStarted { started_at: now }]);
+    }
+
     // Update state
     state.phase = GamePhase::RoundInProgress;
     state.started_at = Some(now);
     state.round_number = 1;
 
-    let time_limit_ms = if state.settings.time_limit_seconds > 0 {
-        Some(state.settings.time_limit_seconds * 1000)
-    } else {
-        None
-    };
+    // Time attack: the whole game shares one countdown from here
+    if state.settings.is_time_attack() {
+        state.time_budget_ends_at =
+            Some(now.timestamp_millis() + i64::from(state.settings.time_budget_seconds) * 1000);
+    }
 
-    state.current_round = Some(RoundState::new(
+    let time_limit_ms = state.round_time_limit_ms(now);
+
+    let mut round = RoundState::new(
         1,
         first_location.lat,
         first_location.lng,
         first_location.panorama_id.clone(),
         first_location.location_id.clone(),
         first_location.heading,
+        first_location.provider,
         time_limit_ms,
         now,
-    ));
+    );
+    round.country_code = first_location.country_code;
+    state.current_round = Some(round);
 
     let events = vec![
         GameEvent::GameStarted { started_at: now },
         GameEvent::RoundStarted {
             round_number: 1,
-            total_rounds: state.settings.rounds,
+            total_rounds: state.total_rounds(),
             location_lat: first_location.lat,
             location_lng: first_location.lng,
             panorama_id: first_location.panorama_id,
@@ -300,19 +417,111 @@ fn handle_start(
     ReducerResult::with_events(state, events)
 }
 
+fn handle_start_player_round(
+    mut state: GameState,
+    user_id: String,
+    location: LocationData,
+    now: DateTime<Utc>,
+) -> ReducerResult {
+    if state.progression != RoundProgression::PerPlayer {
+        return ReducerResult::error(state, "INVALID_MODE", "Rounds are shared in this game");
+    }
+
+    if state.phase != GamePhase::Active {
+        return ReducerResult::error(state, "NOT_ACTIVE", "Game is not in progress");
+    }
+
+    if !state.players.contains_key(&user_id) {
+        return ReducerResult::error(state, "NOT_IN_GAME", "Player not in this game");
+    }
+
+    // The previous round must be guessed or out of time before moving on
+    let round_number = match state.player_rounds.get(&user_id) {
+        Some(round) if !round.has_guessed(&user_id) && !round.is_timed_out(now) => {
+            return ReducerResult::error(
+                state,
+                "ROUND_IN_PROGRESS",
+                "Finish your current round first",
+            );
+        }
+        Some(round) => round.round_number + 1,
+        None => 1,
+    };
+
+    if round_number > state.settings.rounds {
+        return ReducerResult::error(state, "GAME_COMPLETE", "All rounds completed");
+    }
+
+    let time_limit_ms = if state.settings.time_limit_seconds > 0 {
+        Some(state.settings.time_limit_seconds * 1000)
+    } else {
+        None
+    };
+
+    let mut round = RoundState::new(
+        round_number,
+        location.lat,
+        location.lng,
+        location.panorama_id,
+        location.location_id,
+        location.heading,
+        location.provider,
+        time_limit_ms,
+        now,
+    );
+    round.country_code = location.country_code;
+    state.player_rounds.insert(user_id.clone(), round);
+    // Track the furthest round any player has reached
+    state.round_number = state.round_number.max(round_number);
+
+    let event = GameEvent::PlayerRoundStarted {
+        user_id,
+        round_number,
+        total_rounds: state.settings.rounds,
+        time_limit_ms,
+        started_at: now,
+    };
+
+    ReducerResult::with_events(state, vec![event])
+}
+
+#[allow(clippy::too_many_arguments)]
 fn handle_submit_guess(
     mut state: GameState,
     user_id: String,
     lat: f64,
     lng: f64,
     time_taken_ms: Option<u32>,
+    reported_panorama_id: Option<String>,
+    country_code: Option<String>,
     now: DateTime<Utc>,
 ) -> ReducerResult {
+    if state.is_teacher(&user_id) {
+        return ReducerResult::error(state, "TEACHER_CANNOT_GUESS", "The teacher does not guess");
+    }
+
+    if state.progression == RoundProgression::PerPlayer {
+        return handle_submit_player_guess(
+            state,
+            user_id,
+            lat,
+            lng,
+            time_taken_ms,
+            reported_panorama_id,
+            country_code,
+            now,
+        );
+    }
+
     // Validate game phase
     if state.phase != GamePhase::RoundInProgress {
         return ReducerResult::error(state, "NOT_IN_ROUND", "No round is currently in progress");
     }
 
+    if state.paused_at.is_some() {
+        return ReducerResult::error(state, "GAME_PAUSED", "The game is paused");
+    }
+
     // Check player exists
     let Some(player) = state.players.get(&user_id) else {
         return ReducerResult::error(state, "NOT_IN_GAME", "Player not in this game");
@@ -334,9 +543,35 @@ fn handle_submit_guess(
         return ReducerResult::error(state, "TIME_EXPIRED", "Round time has expired");
     }
 
-    // Calculate distance and score
+    // No-move modes: the guess must come from the round's panorama
+    let panorama_check = check_reported_panorama(
+        &state.settings,
+        round.panorama_id.as_deref(),
+        reported_panorama_id.as_deref(),
+    );
+    if panorama_check == PanoramaCheck::Mismatch {
+        return ReducerResult::error(
+            state,
+            "PANORAMA_MISMATCH",
+            "Guess was not made from the round's starting position",
+        );
+    }
+
+    // Calculate distance and score; streak rounds score the named country
     let distance = haversine_distance(round.location_lat, round.location_lng, lat, lng);
-    let score = calculate_score(distance, &ScoringConfig::default());
+    let score = if state.settings.streak {
+        let Some(named) = country_code.as_deref() else {
+            return ReducerResult::error(
+                state,
+                "COUNTRY_REQUIRED",
+                "Streak guesses must name a country",
+            );
+        };
+        streak_score(named, round.country_code.as_deref())
+    } else {
+        calculate_score(distance, &state.settings.scoring)
+            .saturating_sub(round.hint_cost(&user_id, state.settings.hint_cost))
+    };
 
     // Record the guess
     round.guesses.insert(
@@ -348,13 +583,15 @@ fn handle_submit_guess(
             distance_meters: distance,
             score,
             time_taken_ms,
+            reported_panorama_id,
+            country_code,
             submitted_at: now,
         },
     );
 
     // Update player's total score
     if let Some(player) = state.players.get_mut(&user_id) {
-        player.total_score += score;
+        player.total_score += apply_handicap(score, player.handicap);
     }
 
     // Build events
@@ -363,6 +600,99 @@ fn handle_submit_guess(
     // Add score update event
     events.push(build_scores_update(&state));
 
+    // Time attack: the round ends as soon as everyone has guessed
+    let connected_ids = state.connected_player_ids();
+    if state.settings.is_time_attack()
+        && state.current_round.as_ref().is_some_and(|r| r.all_guessed(&connected_ids))
+    {
+        let ended = handle_end_round(state, now);
+        events.extend(ended.events);
+        return ReducerResult::with_events(ended.state, events);
+    }
+
+    ReducerResult::with_events(state, events)
+}
+
+/// Guess on the player's own round in a per-player game.
+///
+/// Scores stay hidden until every player has finished, so no scoreboard
+/// update is emitted; the game ends with the last player's last guess.
+#[allow(clippy::too_many_arguments)]
+fn handle_submit_player_guess(
+    mut state: GameState,
+    user_id: String,
+    lat: f64,
+    lng: f64,
+    time_taken_ms: Option<u32>,
+    reported_panorama_id: Option<String>,
+    country_code: Option<String>,
+    now: DateTime<Utc>,
+) -> ReducerResult {
+    if state.phase != GamePhase::Active {
+        return ReducerResult::error(state, "NOT_ACTIVE", "Game is not in progress");
+    }
+
+    let Some(player) = state.players.get(&user_id) else {
+        return ReducerResult::error(state, "NOT_IN_GAME", "Player not in this game");
+    };
+    let display_name = player.display_name.clone();
+
+    let Some(round) = state.player_rounds.get_mut(&user_id) else {
+        return ReducerResult::error(state, "NO_ROUND", "No active round");
+    };
+
+    if round.guesses.contains_key(&user_id) {
+        return ReducerResult::error(state, "ALREADY_GUESSED", "Already submitted a guess");
+    }
+
+    if round.is_timed_out(now) {
+        return ReducerResult::error(state, "TIME_EXPIRED", "Round time has expired");
+    }
+
+    let panorama_check = check_reported_panorama(
+        &state.settings,
+        round.panorama_id.as_deref(),
+        reported_panorama_id.as_deref(),
+    );
+    if panorama_check == PanoramaCheck::Mismatch {
+        return ReducerResult::error(
+            state,
+            "PANORAMA_MISMATCH",
+            "Guess was not made from the round's starting position",
+        );
+    }
+
+    let distance = haversine_distance(round.location_lat, round.location_lng, lat, lng);
+    let score = calculate_score(distance, &state.settings.scoring)
+        .saturating_sub(round.hint_cost(&user_id, state.settings.hint_cost));
+
+    round.guesses.insert(
+        user_id.clone(),
+        Guess {
+            user_id: user_id.clone(),
+            lat,
+            lng,
+            distance_meters: distance,
+            score,
+            time_taken_ms,
+            reported_panorama_id,
+            country_code,
+            submitted_at: now,
+        },
+    );
+
+    if let Some(player) = state.players.get_mut(&user_id) {
+        player.total_score += apply_handicap(score, player.handicap);
+    }
+
+    let mut events = vec![GameEvent::GuessSubmitted { user_id, display_name }];
+
+    if state.all_players_finished(now) {
+        let ended = handle_end_game(state);
+        events.extend(ended.events);
+        return ReducerResult::with_events(ended.state, events);
+    }
+
     ReducerResult::with_events(state, events)
 }
 
@@ -411,8 +741,10 @@ fn handle_end_round(mut state: GameState, now: DateTime<Utc>) -> ReducerResult {
     state.completed_rounds.push(round);
     state.phase = GamePhase::BetweenRounds;
 
-    // Set the between-rounds deadline and clear any previous skip votes
-    state.between_rounds_ends_at = Some(now.timestamp_millis() + BETWEEN_ROUNDS_WAIT_MS);
+    // Set the between-rounds deadline and clear any previous skip votes;
+    // time attack moves straight on since the clock keeps running
+    let wait_ms = if state.settings.is_time_attack() { 0 } else { BETWEEN_ROUNDS_WAIT_MS };
+    state.between_rounds_ends_at = Some(now.timestamp_millis() + wait_ms);
     state.skip_votes.clear();
 
     ReducerResult::with_events(state, vec![event])
@@ -432,10 +764,8 @@ fn handle_advance_round(
         );
     }
 
-    let next_round_number = state.round_number + 1;
-
     // Check if game should end instead
-    if next_round_number > state.settings.rounds {
+    if !state.can_start_round(now) {
         return ReducerResult::error(
             state,
             "GAME_COMPLETE",
@@ -444,31 +774,31 @@ fn handle_advance_round(
     }
 
     // Update state
+    let next_round_number = state.round_number + 1;
     state.round_number = next_round_number;
     state.phase = GamePhase::RoundInProgress;
     state.between_rounds_ends_at = None;
     state.skip_votes.clear();
 
-    let time_limit_ms = if state.settings.time_limit_seconds > 0 {
-        Some(state.settings.time_limit_seconds * 1000)
-    } else {
-        None
-    };
+    let time_limit_ms = state.round_time_limit_ms(now);
 
-    state.current_round = Some(RoundState::new(
+    let mut round = RoundState::new(
         next_round_number,
         next_location.lat,
         next_location.lng,
         next_location.panorama_id.clone(),
         next_location.location_id.clone(),
         next_location.heading,
+        next_location.provider,
         time_limit_ms,
         now,
-    ));
+    );
+    round.country_code = next_location.country_code;
+    state.current_round = Some(round);
 
     let event = GameEvent::RoundStarted {
         round_number: next_round_number,
-        total_rounds: state.settings.rounds,
+        total_rounds: state.total_rounds(),
         location_lat: next_location.lat,
         location_lng: next_location.lng,
         panorama_id: next_location.panorama_id,
@@ -479,23 +809,113 @@ fn handle_advance_round(
     ReducerResult::with_events(state, vec![event])
 }
 
+fn handle_reroll_round(
+    mut state: GameState,
+    user_id: String,
+    new_location: LocationData,
+    now: DateTime<Utc>,
+) -> ReducerResult {
+    if state.phase != GamePhase::RoundInProgress || state.progression != RoundProgression::Shared {
+        return ReducerResult::error(state, "NOT_IN_ROUND", "No round is currently in progress");
+    }
+
+    if !state.is_host(&user_id) {
+        return ReducerResult::error(state, "NOT_HOST", "Only the host can reroll a round");
+    }
+
+    if state.rerolls_used >= MAX_REROLLS_PER_GAME {
+        return ReducerResult::error(
+            state,
+            "REROLL_LIMIT",
+            &format!("Rounds can only be rerolled {MAX_REROLLS_PER_GAME} times per game"),
+        );
+    }
+
+    let Some(round) = state.current_round.take() else {
+        return ReducerResult::error(state, "NOT_IN_ROUND", "No round is currently in progress");
+    };
+    if (now - round.started_at).num_milliseconds() > REROLL_WINDOW_MS {
+        state.current_round = Some(round);
+        return ReducerResult::error(
+            state,
+            "REROLL_WINDOW_CLOSED",
+            &format!(
+                "Rounds can only be rerolled in their first {} seconds",
+                REROLL_WINDOW_MS / 1000
+            ),
+        );
+    }
+
+    // Take back the points scored on the old location
+    let mut discarded_guesses = Vec::new();
+    for guess in round.guesses.values() {
+        if let Some(player) = state.players.get_mut(&guess.user_id) {
+            let points = apply_handicap(guess.score, player.handicap);
+            player.total_score = player.total_score.saturating_sub(points);
+        }
+        discarded_guesses.push(guess.user_id.clone());
+    }
+
+    state.rerolls_used += 1;
+    let time_limit_ms = state.round_time_limit_ms(now);
+
+    let mut new_round = RoundState::new(
+        round.round_number,
+        new_location.lat,
+        new_location.lng,
+        new_location.panorama_id.clone(),
+        new_location.location_id.clone(),
+        new_location.heading,
+        new_location.provider,
+        time_limit_ms,
+        now,
+    );
+    new_round.country_code = new_location.country_code;
+    state.current_round = Some(new_round);
+
+    let events = vec![
+        GameEvent::RoundRerolled {
+            round_number: round.round_number,
+            rerolled_by: user_id,
+            discarded_guesses,
+            rerolls_left: MAX_REROLLS_PER_GAME - state.rerolls_used,
+        },
+        GameEvent::RoundStarted {
+            round_number: round.round_number,
+            total_rounds: state.total_rounds(),
+            location_lat: new_location.lat,
+            location_lng: new_location.lng,
+            panorama_id: new_location.panorama_id,
+            time_limit_ms,
+            started_at: now,
+        },
+    ];
+
+    ReducerResult::with_events(state, events)
+}
+
 fn handle_end_game(mut state: GameState) -> ReducerResult {
-    // Build final standings sorted by score (descending)
+    // Build final standings sorted by score (descending); the teacher of a
+    // classroom game is not ranked
     let mut standings: Vec<_> = state
         .players
         .values()
-        .map(|p| (p.user_id.clone(), p.display_name.clone(), p.total_score))
+        .filter(|p| !state.is_teacher(&p.user_id))
+        .map(|p| (p.user_id.clone(), p.display_name.clone(), p.total_score, p.joined_round))
         .collect();
-    standings.sort_by(|a, b| b.2.cmp(&a.2));
+    standings.sort_by_key(|s| std::cmp::Reverse(s.2));
 
     let final_standings: Vec<FinalStandingData> = standings
         .iter()
         .enumerate()
-        .map(|(i, (user_id, display_name, score))| FinalStandingData {
+        .map(|(i, (user_id, display_name, score, joined_round))| FinalStandingData {
             rank: (i + 1) as u8,
             user_id: user_id.clone(),
             display_name: display_name.clone(),
             total_score: *score,
+            // Every correct country scores one point
+            streak: state.settings.streak.then_some(*score),
+            joined_round: *joined_round,
         })
         .collect();
 
@@ -509,10 +929,33 @@ fn handle_end_game(mut state: GameState) -> ReducerResult {
 }
 
 fn handle_tick(mut state: GameState, now: DateTime<Utc>) -> ReducerResult {
+    // Per-player games have no shared timer; they end once the last player's
+    // last round is guessed or out of time
+    if state.progression == RoundProgression::PerPlayer {
+        if state.phase == GamePhase::Active && state.all_players_finished(now) {
+            return handle_end_game(state);
+        }
+        return ReducerResult::unchanged(state);
+    }
+
     let mut events = Vec::new();
 
+    // A paused game resumes once the host's grace period runs out; a
+    // teacher's pause only if the teacher has been gone that long
+    let grace_ms = state.settings.reconnect_grace_ms() as i64;
+    let waited_ms = if state.teacher_paused {
+        state.get_host().and_then(|host| host.disconnected_at).map(|at| now - at)
+    } else {
+        state.paused_at.map(|at| now - at)
+    };
+    if waited_ms.is_some_and(|waited| waited.num_milliseconds() > grace_ms) {
+        events.extend(resume_game(&mut state, now));
+    }
+    let paused = state.paused_at.is_some();
+
     // Check for round timeout
     if state.phase == GamePhase::RoundInProgress
+        && !paused
         && let Some(round) = &state.current_round
     {
         let connected_ids = state.connected_player_ids();
@@ -524,12 +967,15 @@ fn handle_tick(mut state: GameState, now: DateTime<Utc>) -> ReducerResult {
 
         if timed_out || all_guessed {
             // End the round - recursively process EndRound
-            return reduce(&state, GameCommand::EndRound, now);
+            let ended = reduce(&state, GameCommand::EndRound, now);
+            events.extend(ended.events);
+            return ReducerResult::with_events(ended.state, events);
         }
     }
 
     // Check for between-rounds wait expiry
     if state.phase == GamePhase::BetweenRounds
+        && !paused
         && let Some(ends_at) = state.between_rounds_ends_at
         && now.timestamp_millis() >= ends_at
     {
@@ -538,16 +984,25 @@ fn handle_tick(mut state: GameState, now: DateTime<Utc>) -> ReducerResult {
         return ReducerResult::with_events(state, events);
     }
 
-    // Check for disconnection grace period timeouts - ONLY in Lobby phase
-    // During active games, disconnected players stay until game ends
-    if state.phase == GamePhase::Lobby {
+    // Check for disconnection grace period timeouts: always in the lobby,
+    // and during games that remove disconnected players as long as someone
+    // is still connected (otherwise abandonment below applies)
+    let remove_disconnected = match state.phase {
+        GamePhase::Lobby => true,
+        GamePhase::RoundInProgress | GamePhase::BetweenRounds => {
+            state.settings.disconnect_policy == DisconnectPolicy::RemovePlayer
+                && state.connected_player_count() > 0
+        }
+        _ => false,
+    };
+    if remove_disconnected {
         let timed_out_players: Vec<(String, String)> = state
             .players
             .iter()
             .filter_map(|(id, p)| {
                 if let Some(disconnected_at) = p.disconnected_at {
                     let elapsed = (now - disconnected_at).num_milliseconds();
-                    if elapsed > LOBBY_RECONNECTION_GRACE_PERIOD_MS as i64 {
+                    if elapsed > grace_ms {
                         return Some((id.clone(), p.display_name.clone()));
                     }
                 }
@@ -634,31 +1089,67 @@ fn handle_update_settings(
     ReducerResult::with_events(state, vec![event])
 }
 
-fn handle_skip_wait(mut state: GameState, user_id: String) -> ReducerResult {
-    // Must be between rounds
-    if state.phase != GamePhase::BetweenRounds {
+fn handle_set_handicap(
+    mut state: GameState,
+    user_id: String,
+    player_id: String,
+    handicap: f64,
+) -> ReducerResult {
+    if state.phase != GamePhase::Lobby {
         return ReducerResult::error(
             state,
-            "INVALID_STATE",
-            "Can only skip wait when between rounds",
+            "GAME_STARTED",
+            "Cannot change handicaps after game has started",
         );
     }
 
-    // Must be host
     if !state.is_host(&user_id) {
-        return ReducerResult::error(state, "NOT_HOST", "Only the host can force skip the wait");
+        return ReducerResult::error(state, "NOT_HOST", "Only the host can set handicaps");
     }
 
-    // Clear wait state
-    state.between_rounds_ends_at = None;
-    state.skip_votes.clear();
-
-    ReducerResult::with_events(state, vec![GameEvent::WaitSkipped])
-}
-
-fn handle_vote_skip_wait(mut state: GameState, user_id: String) -> ReducerResult {
-    // Must be between rounds
-    if state.phase != GamePhase::BetweenRounds {
+    if !is_valid_handicap(handicap) {
+        return ReducerResult::error(
+            state,
+            "INVALID_HANDICAP",
+            &format!("Handicap must be between {MIN_HANDICAP} and {MAX_HANDICAP}"),
+        );
+    }
+
+    let Some(player) = state.players.get_mut(&player_id) else {
+        return ReducerResult::error(state, "NOT_IN_GAME", "Player not in this game");
+    };
+    player.handicap = handicap;
+
+    let event = GameEvent::HandicapUpdated { user_id: player_id, handicap };
+
+    ReducerResult::with_events(state, vec![event])
+}
+
+fn handle_skip_wait(mut state: GameState, user_id: String) -> ReducerResult {
+    // Must be between rounds
+    if state.phase != GamePhase::BetweenRounds {
+        return ReducerResult::error(
+            state,
+            "INVALID_STATE",
+            "Can only skip wait when between rounds",
+        );
+    }
+
+    // Must be host
+    if !state.is_host(&user_id) {
+        return ReducerResult::error(state, "NOT_HOST", "Only the host can force skip the wait");
+    }
+
+    // Clear wait state
+    state.between_rounds_ends_at = None;
+    state.skip_votes.clear();
+
+    ReducerResult::with_events(state, vec![GameEvent::WaitSkipped])
+}
+
+fn handle_vote_skip_wait(mut state: GameState, user_id: String) -> ReducerResult {
+    // Must be between rounds
+    if state.phase != GamePhase::BetweenRounds {
         return ReducerResult::error(
             state,
             "INVALID_STATE",
@@ -711,6 +1202,102 @@ fn handle_vote_skip_wait(mut state: GameState, user_id: String) -> ReducerResult
     ReducerResult::with_events(state, events)
 }
 
+fn handle_pause(mut state: GameState, user_id: String, now: DateTime<Utc>) -> ReducerResult {
+    if !state.is_teacher(&user_id) {
+        return ReducerResult::error(state, "NOT_TEACHER", "Only the teacher can pause the game");
+    }
+
+    if !matches!(state.phase, GamePhase::RoundInProgress | GamePhase::BetweenRounds) {
+        return ReducerResult::error(state, "INVALID_STATE", "Can only pause a game in progress");
+    }
+
+    if state.teacher_paused {
+        return ReducerResult::error(state, "ALREADY_PAUSED", "The game is already paused");
+    }
+
+    // Taking over a pause for a disconnect keeps its start, so the timers
+    // still move on by the whole pause
+    if state.paused_at.is_none() {
+        state.paused_at = Some(now);
+    }
+    state.teacher_paused = true;
+
+    ReducerResult::with_events(
+        state,
+        vec![GameEvent::GamePaused { user_id, grace_period_ms: None }],
+    )
+}
+
+fn handle_resume(mut state: GameState, user_id: String, now: DateTime<Utc>) -> ReducerResult {
+    if !state.is_teacher(&user_id) {
+        return ReducerResult::error(state, "NOT_TEACHER", "Only the teacher can resume the game");
+    }
+
+    if !state.teacher_paused {
+        return ReducerResult::error(state, "NOT_PAUSED", "The game is not paused");
+    }
+
+    let events = resume_game(&mut state, now).into_iter().collect();
+    ReducerResult::with_events(state, events)
+}
+
+fn handle_request_hint(
+    mut state: GameState,
+    user_id: String,
+    kind: HintKind,
+    from: Option<(f64, f64)>,
+    now: DateTime<Utc>,
+) -> ReducerResult {
+    if !state.settings.hints_enabled {
+        return ReducerResult::error(state, "HINTS_DISABLED", "Hints are off in this game");
+    }
+
+    if !state.players.contains_key(&user_id) {
+        return ReducerResult::error(state, "NOT_IN_GAME", "Player not in this game");
+    }
+    if state.is_teacher(&user_id) {
+        return ReducerResult::error(state, "TEACHER_CANNOT_GUESS", "The teacher does not guess");
+    }
+
+    if state.paused_at.is_some() {
+        return ReducerResult::error(state, "GAME_PAUSED", "The game is paused");
+    }
+
+    let round = if state.progression == RoundProgression::PerPlayer {
+        state.player_rounds.get_mut(&user_id).filter(|_| state.phase == GamePhase::Active)
+    } else {
+        state.current_round.as_mut().filter(|_| state.phase == GamePhase::RoundInProgress)
+    };
+    let Some(round) = round else {
+        return ReducerResult::error(state, "NOT_IN_ROUND", "No round is currently in progress");
+    };
+
+    if round.guesses.contains_key(&user_id) {
+        return ReducerResult::error(state, "ALREADY_GUESSED", "Already submitted a guess");
+    }
+    if round.is_timed_out(now) {
+        return ReducerResult::error(state, "TIME_EXPIRED", "Round time has expired");
+    }
+
+    let round_number = round.round_number;
+    let taken = round.hints.get(&user_id).and_then(|hints| hints.iter().find(|h| h.kind() == kind));
+    let (hint, cost) = match taken {
+        Some(hint) => (hint.clone(), 0),
+        None => match hints::reveal(kind, round, from) {
+            Ok(hint) => {
+                round.hints.entry(user_id.clone()).or_default().push(hint.clone());
+                (hint, state.settings.hint_cost)
+            }
+            Err((code, message)) => return ReducerResult::error(state, code, message),
+        },
+    };
+
+    ReducerResult::with_events(
+        state,
+        vec![GameEvent::HintRevealed { user_id, round_number, hint, cost }],
+    )
+}
+
 // =============================================================================
 // Helper Functions
 // =============================================================================
@@ -720,6 +1307,7 @@ fn build_scores_update(state: &GameState) -> GameEvent {
     let mut scores: Vec<_> = state
         .players
         .values()
+        .filter(|p| !state.is_teacher(&p.user_id))
         .map(|p| {
             let round_score = state
                 .current_round
@@ -737,6 +1325,7 @@ fn build_scores_update(state: &GameState) -> GameEvent {
                 p.avatar_url.clone(),
                 p.total_score,
                 round_score,
+                p.handicap,
                 has_guessed,
                 p.connected,
             )
@@ -744,23 +1333,29 @@ fn build_scores_update(state: &GameState) -> GameEvent {
         .collect();
 
     // Sort by total score descending
-    scores.sort_by(|a, b| b.3.cmp(&a.3));
+    scores.sort_by_key(|s| std::cmp::Reverse(s.3));
 
     let scores: Vec<ScoreData> = scores
         .iter()
         .enumerate()
-        .map(|(i, (user_id, display_name, avatar_url, total, round, guessed, connected))| {
-            ScoreData {
-                user_id: user_id.clone(),
-                display_name: display_name.clone(),
-                avatar_url: avatar_url.clone(),
-                total_score: *total,
-                round_score: *round,
-                has_guessed: *guessed,
-                rank: (i + 1) as u8,
-                connected: *connected,
-            }
-        })
+        .map(
+            |(
+                i,
+                (user_id, display_name, avatar_url, total, round, handicap, guessed, connected),
+            )| {
+                ScoreData {
+                    user_id: user_id.clone(),
+                    display_name: display_name.clone(),
+                    avatar_url: avatar_url.clone(),
+                    total_score: *total,
+                    round_score: *round,
+                    handicap: *handicap,
+                    has_guessed: *guessed,
+                    rank: (i + 1) as u8,
+                    connected: *connected,
+                }
+            },
+        )
         .collect();
 
     GameEvent::ScoresUpdated { scores }
@@ -793,6 +1388,38 @@ mod tests {
         );
     }
 
+    /// Error code of a rejected command
+    fn error_code(result: &ReducerResult) -> Option<&str> {
+        result.get_error().and_then(|e| e.error_code())
+    }
+
+    /// Lobby hosted by `usr_host` with `players` joined, after `configure`
+    /// has changed the default settings
+    fn lobby(players: &[&str], configure: impl FnOnce(&mut GameSettings)) -> GameState {
+        let mut state = test_state();
+        configure(&mut state.settings);
+        add_host(&mut state);
+        for user_id in players {
+            add_player(&mut state, user_id);
+        }
+        state
+    }
+
+    /// Have `usr_host` start the game at `first_location`
+    fn start(state: &GameState, first_location: LocationData, now: DateTime<Utc>) -> ReducerResult {
+        reduce(state, GameCommand::Start { user_id: "usr_host".to_string(), first_location }, now)
+    }
+
+    /// Game started by `usr_host` with `players` joined, after `configure`
+    /// has changed the default settings
+    fn started_game(
+        players: &[&str],
+        now: DateTime<Utc>,
+        configure: impl FnOnce(&mut GameSettings),
+    ) -> GameState {
+        start(&lobby(players, configure), LocationData::new(0.0, 0.0, None), now).state
+    }
+
     // -------------------------------------------------------------------------
     // Join Tests
     // -------------------------------------------------------------------------
@@ -862,6 +1489,54 @@ mod tests {
         assert_eq!(result.get_error().unwrap().error_code(), Some("GAME_STARTED"));
     }
 
+    #[test]
+    fn test_late_join() {
+        let join = |state: &GameState, user_id: &str| {
+            reduce(
+                state,
+                GameCommand::Join {
+                    user_id: user_id.to_string(),
+                    display_name: user_id.to_string(),
+                    avatar_url: None,
+                    is_host: true,
+                },
+                Utc::now(),
+            )
+        };
+
+        let mut state = test_state();
+        state.settings.allow_late_join = true;
+        add_host(&mut state);
+        state.phase = GamePhase::RoundInProgress;
+        state.round_number = 3;
+
+        // Joining mid-round plays the current round, never as host
+        let result = join(&state, "usr_late");
+        assert!(!result.has_error());
+        let player = &result.state.players["usr_late"];
+        assert_eq!(player.joined_round, Some(3));
+        assert_eq!(player.total_score, 0);
+        assert!(!player.is_host);
+        assert!(matches!(result.events[0], GameEvent::PlayerJoined { is_host: false, .. }));
+
+        // Joining between rounds starts with the next one
+        state.phase = GamePhase::BetweenRounds;
+        assert_eq!(join(&state, "usr_late").state.players["usr_late"].joined_round, Some(4));
+
+        state.phase = GamePhase::Finished;
+        assert_eq!(
+            join(&state, "usr_late").get_error().unwrap().error_code(),
+            Some("GAME_STARTED")
+        );
+
+        state.phase = GamePhase::RoundInProgress;
+        state.progression = RoundProgression::PerPlayer;
+        assert_eq!(
+            join(&state, "usr_late").get_error().unwrap().error_code(),
+            Some("GAME_STARTED")
+        );
+    }
+
     #[test]
     fn test_join_game_full() {
         let mut state = test_state();
@@ -988,6 +1663,8 @@ mod tests {
                 lat: 51.5,
                 lng: -0.1,
                 time_taken_ms: Some(5000),
+                reported_panorama_id: None,
+                country_code: None,
             },
             now,
         );
@@ -1011,6 +1688,44 @@ mod tests {
         assert!(result.events.iter().any(|e| matches!(e, GameEvent::ScoresUpdated { .. })));
     }
 
+    #[test]
+    fn test_submit_guess_rejects_wrong_panorama_in_no_move() {
+        let mut state = test_state();
+        state.settings.movement_allowed = false;
+        add_host(&mut state);
+        let now = Utc::now();
+
+        let result = reduce(
+            &state,
+            GameCommand::Start {
+                user_id: "usr_host".to_string(),
+                first_location: LocationData::new(51.5, -0.1, Some("pano_round".to_string())),
+            },
+            now,
+        );
+        state = result.state;
+
+        let guess = |panorama: &str| GameCommand::SubmitGuess {
+            user_id: "usr_host".to_string(),
+            lat: 51.5,
+            lng: -0.1,
+            time_taken_ms: None,
+            reported_panorama_id: Some(panorama.to_string()),
+            country_code: None,
+        };
+
+        let result = reduce(&state, guess("pano_elsewhere"), now);
+        assert!(
+            matches!(result.get_error(), Some(GameEvent::Error { code, .. }) if code == "PANORAMA_MISMATCH")
+        );
+        assert!(state.current_round.as_ref().unwrap().guesses.is_empty());
+
+        let result = reduce(&state, guess("pano_round"), now);
+        assert!(!result.has_error());
+        let round = result.state.current_round.as_ref().unwrap();
+        assert_eq!(round.guesses["usr_host"].reported_panorama_id.as_deref(), Some("pano_round"));
+    }
+
     #[test]
     fn test_submit_guess_already_guessed() {
         let mut state = test_state();
@@ -1035,6 +1750,8 @@ mod tests {
                 lat: 0.0,
                 lng: 0.0,
                 time_taken_ms: None,
+                reported_panorama_id: None,
+                country_code: None,
             },
             now,
         );
@@ -1048,6 +1765,8 @@ mod tests {
                 lat: 10.0,
                 lng: 10.0,
                 time_taken_ms: None,
+                reported_panorama_id: None,
+                country_code: None,
             },
             now,
         );
@@ -1084,6 +1803,8 @@ mod tests {
                 lat: 0.0,
                 lng: 0.0,
                 time_taken_ms: None,
+                reported_panorama_id: None,
+                country_code: None,
             },
             expired,
         );
@@ -1124,6 +1845,8 @@ mod tests {
                     lat: 0.0,
                     lng: 0.0,
                     time_taken_ms: None,
+                    reported_panorama_id: None,
+                    country_code: None,
                 },
                 now,
             );
@@ -1280,6 +2003,8 @@ mod tests {
                 lat: 0.0,
                 lng: 0.0,
                 time_taken_ms: None,
+                reported_panorama_id: None,
+                country_code: None,
             },
             now,
         );
@@ -1355,7 +2080,7 @@ mod tests {
         // Should have grace period in lobby
         match &result.events[0] {
             GameEvent::PlayerDisconnected { grace_period_ms, .. } => {
-                assert_eq!(*grace_period_ms, Some(LOBBY_RECONNECTION_GRACE_PERIOD_MS));
+                assert_eq!(*grace_period_ms, Some(state.settings.reconnect_grace_ms()));
             }
             _ => panic!("Expected PlayerDisconnected event"),
         }
@@ -1422,6 +2147,198 @@ mod tests {
         assert!(!result.state.players.get("usr_p1").unwrap().connected);
     }
 
+    #[test]
+    fn test_remove_player_policy_removes_after_grace_period() {
+        let now = Utc::now();
+        let state = started_game(&["usr_p1"], now, |settings| {
+            settings.disconnect_policy = DisconnectPolicy::RemovePlayer;
+            settings.reconnect_grace_seconds = 10;
+        });
+
+        let result = reduce(&state, GameCommand::Disconnect { user_id: "usr_p1".to_string() }, now);
+        assert!(matches!(
+            result.events[0],
+            GameEvent::PlayerDisconnected { grace_period_ms: Some(10_000), .. }
+        ));
+
+        let state =
+            reduce(&result.state, GameCommand::Tick, now + chrono::Duration::seconds(5)).state;
+        assert!(state.players.contains_key("usr_p1"));
+
+        let result = reduce(&state, GameCommand::Tick, now + chrono::Duration::seconds(11));
+        assert!(!result.state.players.contains_key("usr_p1"));
+        assert!(matches!(result.events[0], GameEvent::PlayerTimedOut { .. }));
+    }
+
+    #[test]
+    fn test_host_disconnect_pauses_game() {
+        let now = Utc::now();
+        let state = started_game(&["usr_p1"], now, |settings| {
+            settings.disconnect_policy = DisconnectPolicy::PauseIfHost;
+            settings.reconnect_grace_seconds = 10;
+        });
+
+        // A regular player's disconnect does not pause
+        let result = reduce(&state, GameCommand::Disconnect { user_id: "usr_p1".to_string() }, now);
+        assert_eq!(result.events.len(), 1);
+        assert!(result.state.paused_at.is_none());
+
+        let result =
+            reduce(&state, GameCommand::Disconnect { user_id: "usr_host".to_string() }, now);
+        assert!(matches!(
+            result.events[1],
+            GameEvent::GamePaused { grace_period_ms: Some(10_000), .. }
+        ));
+        let state = result.state;
+
+        let result = reduce(
+            &state,
+            GameCommand::SubmitGuess {
+                user_id: "usr_p1".to_string(),
+                lat: 0.0,
+                lng: 0.0,
+                time_taken_ms: None,
+                reported_panorama_id: None,
+                country_code: None,
+            },
+            now,
+        );
+        assert_eq!(result.get_error().unwrap().error_code(), Some("GAME_PAUSED"));
+
+        // The host is back after 8 seconds; the round clock moves on by as much
+        let back = now + chrono::Duration::seconds(8);
+        let result =
+            reduce(&state, GameCommand::Reconnect { user_id: "usr_host".to_string() }, back);
+        assert!(matches!(result.events[1], GameEvent::GameResumed { paused_ms: 8_000 }));
+        assert_eq!(result.state.current_round.as_ref().unwrap().started_at, back);
+    }
+
+    #[test]
+    fn test_pause_ends_after_grace_period() {
+        let now = Utc::now();
+        let state = started_game(&["usr_p1"], now, |settings| {
+            settings.disconnect_policy = DisconnectPolicy::PauseIfHost;
+            settings.reconnect_grace_seconds = 10;
+        });
+        let state =
+            reduce(&state, GameCommand::Disconnect { user_id: "usr_host".to_string() }, now).state;
+
+        let state = reduce(&state, GameCommand::Tick, now + chrono::Duration::seconds(5)).state;
+        assert!(state.paused_at.is_some());
+
+        let result = reduce(&state, GameCommand::Tick, now + chrono::Duration::seconds(11));
+        assert!(result.state.paused_at.is_none());
+        assert!(matches!(result.events[0], GameEvent::GameResumed { .. }));
+    }
+
+    #[test]
+    fn test_teacher_pause_lasts_until_resume() {
+        let now = Utc::now();
+        let state = started_game(&["usr_p1"], now, |settings| settings.classroom = true);
+
+        let result = reduce(&state, GameCommand::Pause { user_id: "usr_p1".to_string() }, now);
+        assert_eq!(result.get_error().unwrap().error_code(), Some("NOT_TEACHER"));
+
+        let result = reduce(&state, GameCommand::Pause { user_id: "usr_host".to_string() }, now);
+        assert!(matches!(result.events[0], GameEvent::GamePaused { grace_period_ms: None, .. }));
+        let state = result.state;
+
+        let result = guess(&state, "usr_p1", now);
+        assert_eq!(result.get_error().unwrap().error_code(), Some("GAME_PAUSED"));
+
+        // Neither the grace period nor a reconnect ends a teacher's pause
+        let later = now + chrono::Duration::minutes(5);
+        let state = reduce(&state, GameCommand::Tick, later).state;
+        assert!(state.paused_at.is_some());
+        let state =
+            reduce(&state, GameCommand::Reconnect { user_id: "usr_host".to_string() }, later).state;
+        assert!(state.teacher_paused);
+
+        let result = reduce(&state, GameCommand::Resume { user_id: "usr_host".to_string() }, later);
+        assert!(matches!(result.events[0], GameEvent::GameResumed { paused_ms: 300_000 }));
+        assert!(!result.state.teacher_paused);
+        assert_eq!(result.state.current_round.as_ref().unwrap().started_at, later);
+    }
+
+    #[test]
+    fn test_pause_requires_classroom() {
+        let now = Utc::now();
+        let state = started_game(&["usr_p1"], now, |_| {});
+
+        let result = reduce(&state, GameCommand::Pause { user_id: "usr_host".to_string() }, now);
+        assert_eq!(result.get_error().unwrap().error_code(), Some("NOT_TEACHER"));
+    }
+
+    #[test]
+    fn test_teacher_does_not_play() {
+        let now = Utc::now();
+        let state = started_game(&["usr_p1"], now, |settings| settings.classroom = true);
+
+        let result = guess(&state, "usr_host", now);
+        assert_eq!(result.get_error().unwrap().error_code(), Some("TEACHER_CANNOT_GUESS"));
+
+        // The only student guessing ends the round
+        let result = guess(&state, "usr_p1", now);
+        let result = reduce(&result.state, GameCommand::Tick, now);
+        assert_eq!(result.state.phase, GamePhase::BetweenRounds);
+
+        let result = reduce(&result.state, GameCommand::EndGame, now);
+        let standings = result
+            .events
+            .iter()
+            .find_map(|e| match e {
+                GameEvent::GameEnded { final_standings } => Some(final_standings),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(standings.len(), 1);
+        assert_eq!(standings[0].user_id, "usr_p1");
+    }
+
+    fn request_hint(state: &GameState, kind: HintKind, now: DateTime<Utc>) -> ReducerResult {
+        reduce(
+            state,
+            GameCommand::RequestHint { user_id: "usr_host".to_string(), kind, from: None },
+            now,
+        )
+    }
+
+    #[test]
+    fn test_hint_cost_comes_off_guess() {
+        let now = Utc::now();
+        let location = LocationData::new(48.0, 2.0, None).with_country_code(Some("FR".to_string()));
+        let state =
+            start(&lobby(&[], |settings| settings.hints_enabled = true), location, now).state;
+
+        let result = request_hint(&state, HintKind::Continent, now);
+        assert!(matches!(result.events[0], GameEvent::HintRevealed { cost: 500, .. }));
+
+        // Taking the same hint again repeats it for free
+        let result = request_hint(&result.state, HintKind::Continent, now);
+        assert!(matches!(result.events[0], GameEvent::HintRevealed { cost: 0, .. }));
+
+        let result = request_hint(&result.state, HintKind::CountryLetter, now);
+        let result = guess(&result.state, "usr_host", now);
+        let guess = &result.state.current_round.as_ref().unwrap().guesses["usr_host"];
+        assert_eq!(guess.score, 5000 - 2 * 500);
+        assert_eq!(result.state.players["usr_host"].total_score, 4000);
+
+        let result = request_hint(&result.state, HintKind::Direction, now);
+        assert_eq!(result.get_error().unwrap().error_code(), Some("ALREADY_GUESSED"));
+    }
+
+    #[test]
+    fn test_hints_disabled() {
+        let now = Utc::now();
+        let location = LocationData::new(48.0, 2.0, None).with_country_code(Some("FR".to_string()));
+        let mut state =
+            start(&lobby(&[], |settings| settings.hints_enabled = true), location, now).state;
+        state.settings.hints_enabled = false;
+
+        let result = request_hint(&state, HintKind::Continent, now);
+        assert_eq!(result.get_error().unwrap().error_code(), Some("HINTS_DISABLED"));
+    }
+
     #[test]
     fn test_all_players_disconnect_triggers_abandonment() {
         let mut state = test_state();
@@ -1527,6 +2444,94 @@ mod tests {
         assert!(result.state.skip_votes.is_empty());
     }
 
+    #[test]
+    fn test_set_handicap_scales_total_score() {
+        let mut state = test_state();
+        add_host(&mut state);
+        add_player(&mut state, "usr_new");
+        let now = Utc::now();
+
+        let result = reduce(
+            &state,
+            GameCommand::SetHandicap {
+                user_id: "usr_host".to_string(),
+                player_id: "usr_new".to_string(),
+                handicap: 1.25,
+            },
+            now,
+        );
+        assert!(!result.has_error());
+        assert!(matches!(
+            result.events.as_slice(),
+            [GameEvent::HandicapUpdated { user_id, handicap }] if user_id == "usr_new" && *handicap == 1.25
+        ));
+        state = result.state;
+
+        state = reduce(
+            &state,
+            GameCommand::Start {
+                user_id: "usr_host".to_string(),
+                first_location: LocationData::new(0.0, 0.0, None),
+            },
+            now,
+        )
+        .state;
+
+        // 1,000 km away scores 4,000 points before the handicap
+        let result = reduce(
+            &state,
+            GameCommand::SubmitGuess {
+                user_id: "usr_new".to_string(),
+                lat: 0.0,
+                lng: 8.993,
+                time_taken_ms: None,
+                reported_panorama_id: None,
+                country_code: None,
+            },
+            now,
+        );
+        let guess = &result.state.current_round.as_ref().unwrap().guesses["usr_new"];
+        let player = &result.state.players["usr_new"];
+        assert_eq!(player.total_score, apply_handicap(guess.score, 1.25));
+        assert!(player.total_score > guess.score);
+
+        let Some(GameEvent::ScoresUpdated { scores }) =
+            result.events.iter().find(|e| matches!(e, GameEvent::ScoresUpdated { .. }))
+        else {
+            panic!("expected scores update");
+        };
+        let score = scores.iter().find(|s| s.user_id == "usr_new").unwrap();
+        assert_eq!(score.round_score, guess.score);
+        assert_eq!(score.handicap, 1.25);
+    }
+
+    #[test]
+    fn test_set_handicap_validation() {
+        let mut state = test_state();
+        add_host(&mut state);
+        add_player(&mut state, "usr_new");
+        let now = Utc::now();
+
+        let set = |state: &GameState, user_id: &str, player_id: &str, handicap: f64| {
+            reduce(
+                state,
+                GameCommand::SetHandicap {
+                    user_id: user_id.to_string(),
+                    player_id: player_id.to_string(),
+                    handicap,
+                },
+                now,
+            )
+        };
+
+        assert_eq!(error_code(&set(&state, "usr_new", "usr_new", 1.5)), Some("NOT_HOST"));
+        assert_eq!(error_code(&set(&state, "usr_host", "usr_new", 3.0)), Some("INVALID_HANDICAP"));
+        assert_eq!(error_code(&set(&state, "usr_host", "usr_gone", 1.5)), Some("NOT_IN_GAME"));
+
+        state.phase = GamePhase::RoundInProgress;
+        assert_eq!(error_code(&set(&state, "usr_host", "usr_new", 1.5)), Some("GAME_STARTED"));
+    }
+
     #[test]
     fn test_skip_wait_host_only() {
         let mut state = test_state();
@@ -1625,6 +2630,205 @@ mod tests {
         assert!(result.state.between_rounds_ends_at.is_none());
     }
 
+    #[test]
+    fn test_reroll_round() {
+        let now = Utc::now();
+        let location = LocationData::new(48.8566, 2.3522, None);
+        let mut state = start(&lobby(&["usr_p1"], |_| {}), location, now).state;
+        state = guess_as(&state, "usr_p1", now).state;
+        assert!(state.players["usr_p1"].total_score > 0);
+
+        let reroll = |state: &GameState, user_id: &str, at: DateTime<Utc>| {
+            reduce(
+                state,
+                GameCommand::RerollRound {
+                    user_id: user_id.to_string(),
+                    new_location: LocationData::new(35.6762, 139.6503, None),
+                },
+                at,
+            )
+        };
+
+        assert_eq!(error_code(&reroll(&state, "usr_p1", now)), Some("NOT_HOST"));
+        let late = now + chrono::Duration::milliseconds(REROLL_WINDOW_MS + 1);
+        assert_eq!(error_code(&reroll(&state, "usr_host", late)), Some("REROLL_WINDOW_CLOSED"));
+
+        // The guess and its points are discarded and the round restarts
+        let later = now + chrono::Duration::seconds(5);
+        let result = reroll(&state, "usr_host", later);
+        assert!(!result.has_error());
+        let round = result.state.current_round.as_ref().unwrap();
+        assert_eq!(round.round_number, 1);
+        assert_eq!(round.location_lat, 35.6762);
+        assert_eq!(round.started_at, later);
+        assert!(round.guesses.is_empty());
+        assert_eq!(result.state.players["usr_p1"].total_score, 0);
+        assert!(matches!(
+            &result.events[0],
+            GameEvent::RoundRerolled { discarded_guesses, rerolls_left: 1, .. }
+                if discarded_guesses == &["usr_p1".to_string()]
+        ));
+        assert!(matches!(result.events[1], GameEvent::RoundStarted { round_number: 1, .. }));
+
+        state = reroll(&result.state, "usr_host", later).state;
+        assert_eq!(error_code(&reroll(&state, "usr_host", later)), Some("REROLL_LIMIT"));
+    }
+
+    // -------------------------------------------------------------------------
+    // Per-Player Progression Tests
+    // -------------------------------------------------------------------------
+
+    fn per_player_state() -> GameState {
+        let mut state = lobby(&["usr_p1"], |settings| settings.rounds = 2);
+        state.progression = RoundProgression::PerPlayer;
+        state.phase = GamePhase::Active;
+        state
+    }
+
+    fn start_player_round(state: &GameState, user_id: &str, now: DateTime<Utc>) -> ReducerResult {
+        reduce(
+            state,
+            GameCommand::StartPlayerRound {
+                user_id: user_id.to_string(),
+                location: LocationData::new(48.8566, 2.3522, None),
+            },
+            now,
+        )
+    }
+
+    fn guess(state: &GameState, user_id: &str, now: DateTime<Utc>) -> ReducerResult {
+        reduce(
+            state,
+            GameCommand::SubmitGuess {
+                user_id: user_id.to_string(),
+                lat: 48.0,
+                lng: 2.0,
+                time_taken_ms: None,
+                reported_panorama_id: None,
+                country_code: None,
+            },
+            now,
+        )
+    }
+
+    #[test]
+    fn test_per_player_start_waits_for_players() {
+        let mut state = per_player_state();
+        state.phase = GamePhase::Lobby;
+        let now = Utc::now();
+
+        let result = reduce(
+            &state,
+            GameCommand::Start {
+                user_id: "usr_host".to_string(),
+                first_location: LocationData::new(0.0, 0.0, None),
+            },
+            now,
+        );
+
+        assert_eq!(result.state.phase, GamePhase::Active);
+        assert!(result.state.current_round.is_none());
+        assert!(result.state.player_rounds.is_empty());
+        assert_eq!(result.events.len(), 1);
+    }
+
+    #[test]
+    fn test_per_player_rounds_advance_independently() {
+        let now = Utc::now();
+        let state = start_player_round(&per_player_state(), "usr_host", now).state;
+        let state = guess(&state, "usr_host", now).state;
+        let result = start_player_round(&state, "usr_host", now);
+
+        assert!(!result.has_error());
+        assert_eq!(result.state.player_round("usr_host").unwrap().round_number, 2);
+        assert!(result.state.player_round("usr_p1").is_none());
+        assert_eq!(result.state.round_number, 2);
+
+        // The other player still starts from round 1
+        let result = start_player_round(&result.state, "usr_p1", now);
+        assert_eq!(result.state.player_round("usr_p1").unwrap().round_number, 1);
+    }
+
+    #[test]
+    fn test_per_player_round_must_be_finished_first() {
+        let now = Utc::now();
+        let state = start_player_round(&per_player_state(), "usr_host", now).state;
+
+        let result = start_player_round(&state, "usr_host", now);
+        assert_eq!(result.get_error().unwrap().error_code(), Some("ROUND_IN_PROGRESS"));
+
+        // A timed-out round counts as played
+        let later = now + chrono::Duration::seconds(121);
+        let result = start_player_round(&state, "usr_host", later);
+        assert_eq!(result.state.player_round("usr_host").unwrap().round_number, 2);
+    }
+
+    #[test]
+    fn test_per_player_guess_hides_scores() {
+        let now = Utc::now();
+        let state = start_player_round(&per_player_state(), "usr_p1", now).state;
+        let result = guess(&state, "usr_p1", now);
+
+        assert!(!result.has_error());
+        assert!(result.state.players["usr_p1"].total_score > 0);
+        assert!(result.events.iter().all(|e| !matches!(e, GameEvent::ScoresUpdated { .. })));
+        assert_eq!(
+            guess(&result.state, "usr_p1", now).get_error().unwrap().error_code(),
+            Some("ALREADY_GUESSED")
+        );
+    }
+
+    #[test]
+    fn test_per_player_game_ends_when_everyone_finishes() {
+        let now = Utc::now();
+        let play_all = |mut state: GameState, user_id: &str| {
+            for _ in 0..state.settings.rounds {
+                state = start_player_round(&state, user_id, now).state;
+                state = guess(&state, user_id, now).state;
+            }
+            state
+        };
+
+        let state = play_all(per_player_state(), "usr_host");
+        assert_eq!(state.phase, GamePhase::Active);
+
+        let state = play_all(state, "usr_p1");
+        assert_eq!(state.phase, GamePhase::Finished);
+        assert_eq!(state.player_round("usr_p1").unwrap().round_number, 2);
+        assert_eq!(
+            start_player_round(&state, "usr_p1", now).get_error().unwrap().error_code(),
+            Some("NOT_ACTIVE")
+        );
+    }
+
+    #[test]
+    fn test_per_player_tick_ends_after_last_timeout() {
+        let now = Utc::now();
+        let mut state = per_player_state();
+        state.settings.rounds = 1;
+        state = start_player_round(&state, "usr_host", now).state;
+        state = guess(&state, "usr_host", now).state;
+        state = start_player_round(&state, "usr_p1", now).state;
+
+        let result = reduce(&state, GameCommand::Tick, now);
+        assert!(!result.changed);
+
+        let later = now + chrono::Duration::seconds(121);
+        let result = reduce(&state, GameCommand::Tick, later);
+        assert_eq!(result.state.phase, GamePhase::Finished);
+        assert!(matches!(result.events[0], GameEvent::GameEnded { .. }));
+    }
+
+    #[test]
+    fn test_start_player_round_rejected_in_shared_game() {
+        let mut state = test_state();
+        add_host(&mut state);
+        state.phase = GamePhase::Active;
+
+        let result = start_player_round(&state, "usr_host", Utc::now());
+        assert_eq!(result.get_error().unwrap().error_code(), Some("INVALID_MODE"));
+    }
+
     #[test]
     fn test_disconnect_removes_vote_and_rechecks_threshold() {
         let mut state = test_state();
@@ -1651,4 +2855,151 @@ mod tests {
         assert_eq!(state.skip_votes.len(), 1); // p1's vote preserved
         assert!(!state.skip_votes.contains("usr_p2")); // p2 didn't vote
     }
+
+    // -------------------------------------------------------------------------
+    // Streak Tests
+    // -------------------------------------------------------------------------
+
+    fn streak_location(country: &str) -> LocationData {
+        LocationData::new(48.8566, 2.3522, None).with_country_code(Some(country.to_string()))
+    }
+
+    fn streak_guess(state: &GameState, country: Option<&str>, now: DateTime<Utc>) -> ReducerResult {
+        reduce(
+            state,
+            GameCommand::SubmitGuess {
+                user_id: "usr_host".to_string(),
+                lat: 0.0,
+                lng: 0.0,
+                time_taken_ms: None,
+                reported_panorama_id: None,
+                country_code: country.map(str::to_string),
+            },
+            now,
+        )
+    }
+
+    #[test]
+    fn test_streak_continues_until_miss() {
+        let now = Utc::now();
+        let state = lobby(&[], |settings| {
+            settings.streak = true;
+            settings.rounds = 1;
+        });
+
+        let result = start(&state, streak_location("FR"), now);
+        assert!(matches!(result.events[1], GameEvent::RoundStarted { total_rounds: 0, .. }));
+        let mut state = result.state;
+
+        // Naming the right country scores the round's single point
+        let result = streak_guess(&state, Some("fr"), now);
+        assert!(!result.has_error());
+        state = reduce(&result.state, GameCommand::EndRound, now).state;
+        assert_eq!(state.players["usr_host"].total_score, 1);
+
+        // Streaks ignore the configured number of rounds
+        let result =
+            reduce(&state, GameCommand::AdvanceRound { next_location: streak_location("JP") }, now);
+        assert!(!result.has_error());
+        state = result.state;
+        assert_eq!(state.round_number, 2);
+
+        // One miss ends the game
+        state = streak_guess(&state, Some("KR"), now).state;
+        state = reduce(&state, GameCommand::EndRound, now).state;
+        let result =
+            reduce(&state, GameCommand::AdvanceRound { next_location: streak_location("JP") }, now);
+        assert_eq!(result.get_error().unwrap().error_code(), Some("GAME_COMPLETE"));
+
+        let result = reduce(&state, GameCommand::EndGame, now);
+        let GameEvent::GameEnded { final_standings } = &result.events[0] else {
+            panic!("expected GameEnded");
+        };
+        assert_eq!(final_standings[0].streak, Some(1));
+    }
+
+    #[test]
+    fn test_streak_requires_country_and_single_player() {
+        let mut state = lobby(&["usr_p1"], |settings| settings.streak = true);
+        let now = Utc::now();
+
+        let result = start(&state, streak_location("FR"), now);
+        assert_eq!(result.get_error().unwrap().error_code(), Some("STREAK_SOLO_ONLY"));
+
+        state.players.remove("usr_p1");
+        state = start(&state, streak_location("FR"), now).state;
+        let result = streak_guess(&state, None, now);
+        assert_eq!(result.get_error().unwrap().error_code(), Some("COUNTRY_REQUIRED"));
+    }
+
+    // -------------------------------------------------------------------------
+    // Time Attack Tests
+    // -------------------------------------------------------------------------
+
+    fn guess_as(state: &GameState, user_id: &str, now: DateTime<Utc>) -> ReducerResult {
+        reduce(
+            state,
+            GameCommand::SubmitGuess {
+                user_id: user_id.to_string(),
+                lat: 48.0,
+                lng: 2.0,
+                time_taken_ms: None,
+                reported_panorama_id: None,
+                country_code: None,
+            },
+            now,
+        )
+    }
+
+    #[test]
+    fn test_time_attack_shares_budget_across_rounds() {
+        let mut state = test_state();
+        state.settings.time_budget_seconds = 180;
+        state.settings.rounds = 1;
+        add_host(&mut state);
+        add_player(&mut state, "usr_p1");
+        let now = Utc::now();
+        let location = LocationData::new(48.8566, 2.3522, None);
+
+        let result = reduce(
+            &state,
+            GameCommand::Start {
+                user_id: "usr_host".to_string(),
+                first_location: location.clone(),
+            },
+            now,
+        );
+        assert!(matches!(
+            result.events[1],
+            GameEvent::RoundStarted { total_rounds: 0, time_limit_ms: Some(180_000), .. }
+        ));
+        state = result.state;
+
+        // The round ends on the last guess, with no wait before the next one
+        let later = now + chrono::Duration::seconds(60);
+        state = guess_as(&state, "usr_host", later).state;
+        assert_eq!(state.phase, GamePhase::RoundInProgress);
+        let result = guess_as(&state, "usr_p1", later);
+        assert!(result.events.iter().any(|e| matches!(e, GameEvent::RoundEnded { .. })));
+        assert_eq!(result.state.phase, GamePhase::BetweenRounds);
+        assert!(result.state.latest_round().unwrap().has_guessed("usr_p1"));
+        assert_eq!(result.state.between_rounds_ends_at, Some(later.timestamp_millis()));
+        state = result.state;
+
+        // The next round only gets what is left of the budget
+        let result =
+            reduce(&state, GameCommand::AdvanceRound { next_location: location.clone() }, later);
+        assert!(matches!(
+            result.events[0],
+            GameEvent::RoundStarted { round_number: 2, time_limit_ms: Some(120_000), .. }
+        ));
+        state = result.state;
+
+        // Running out of budget times the round out and ends the game
+        let expired = now + chrono::Duration::seconds(181);
+        state = reduce(&state, GameCommand::Tick, expired).state;
+        assert_eq!(state.phase, GamePhase::BetweenRounds);
+        let result = reduce(&state, GameCommand::AdvanceRound { next_location: location }, expired);
+        assert_eq!(result.get_error().unwrap().error_code(), Some("GAME_COMPLETE"));
+    }
 }
@@ -1,7 +1,12 @@
 //! Game rules and configuration
 
+use std::fmt;
+use std::str::FromStr;
+
 use serde::{Deserialize, Serialize};
 
+use super::scoring::ScoringConfig;
+
 /// Game preset configurations
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -47,6 +52,78 @@ impl GamePreset {
     }
 }
 
+/// Default time a disconnected player has to reconnect, in seconds
+pub const DEFAULT_RECONNECT_GRACE_SECONDS: u32 = 30;
+
+fn default_reconnect_grace_seconds() -> u32 {
+    DEFAULT_RECONNECT_GRACE_SECONDS
+}
+
+fn default_reactions_enabled() -> bool {
+    true
+}
+
+/// Default score points each round hint costs
+pub const DEFAULT_HINT_COST: u32 = 500;
+
+fn default_hint_cost() -> u32 {
+    DEFAULT_HINT_COST
+}
+
+/// What happens when a player disconnects during a game
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DisconnectPolicy {
+    /// Remove the player once the grace period runs out
+    RemovePlayer,
+    /// Keep the player in the game, scoring zero for rounds they miss
+    #[default]
+    KeepScoringZero,
+    /// Pause the game while the host is away, for up to the grace period
+    PauseIfHost,
+}
+
+impl DisconnectPolicy {
+    /// Every policy
+    pub const ALL: [DisconnectPolicy; 3] =
+        [Self::RemovePlayer, Self::KeepScoringZero, Self::PauseIfHost];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::RemovePlayer => "remove_player",
+            Self::KeepScoringZero => "keep_scoring_zero",
+            Self::PauseIfHost => "pause_if_host",
+        }
+    }
+}
+
+impl fmt::Display for DisconnectPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DisconnectPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|policy| policy.as_str() == s)
+            .ok_or_else(|| format!("Invalid disconnect policy: {s}"))
+    }
+}
+
+/// A run of consecutive rounds played on one map, as part of a mixed-map
+/// game's [`GameSettings::rounds_plan`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MapRounds {
+    /// Map/region identifier
+    pub map_id: String,
+    /// Number of consecutive rounds played on the map
+    pub rounds: u8,
+}
+
 /// Game settings that affect rules
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GameSettings {
@@ -62,6 +139,44 @@ pub struct GameSettings {
     pub zoom_allowed: bool,
     /// Whether rotation is allowed
     pub rotation_allowed: bool,
+    /// Scoring curve, taken from the map when the game starts
+    #[serde(default)]
+    pub scoring: ScoringConfig,
+    /// Streak mode: rounds continue until the player names the wrong
+    /// country, and `rounds` only sets how many locations the map must have
+    #[serde(default)]
+    pub streak: bool,
+    /// Time attack: one budget in seconds shared by as many rounds as the
+    /// players can get through (0 = off). Replaces the per-round time limit.
+    #[serde(default)]
+    pub time_budget_seconds: u32,
+    /// Time a disconnected player has to reconnect, in seconds
+    #[serde(default = "default_reconnect_grace_seconds")]
+    pub reconnect_grace_seconds: u32,
+    /// What happens when a player disconnects during a game
+    #[serde(default)]
+    pub disconnect_policy: DisconnectPolicy,
+    /// Whether players can send emoji reactions during the game
+    #[serde(default = "default_reactions_enabled")]
+    pub reactions_enabled: bool,
+    /// Whether players can join a live multiplayer game after it started.
+    /// Late joiners score from the round they join; missed rounds score 0.
+    #[serde(default)]
+    pub allow_late_join: bool,
+    /// Classroom mode: the host is a teacher who does not play, sees every
+    /// guess as it comes in and can pause the game
+    #[serde(default)]
+    pub classroom: bool,
+    /// Whether players can take hints during a round
+    #[serde(default)]
+    pub hints_enabled: bool,
+    /// Score points each hint costs, taken off the player's guess
+    #[serde(default = "default_hint_cost")]
+    pub hint_cost: u32,
+    /// Maps the rounds are played on, in round order. Empty plays every
+    /// round on `map_id`, which still sets the scoring curve either way.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rounds_plan: Vec<MapRounds>,
 }
 
 impl Default for GameSettings {
@@ -81,6 +196,17 @@ impl GameSettings {
                 movement_allowed: true,
                 zoom_allowed: true,
                 rotation_allowed: true,
+                scoring: ScoringConfig::default(),
+                streak: false,
+                time_budget_seconds: 0,
+                reconnect_grace_seconds: DEFAULT_RECONNECT_GRACE_SECONDS,
+                disconnect_policy: DisconnectPolicy::default(),
+                reactions_enabled: true,
+                allow_late_join: false,
+                classroom: false,
+                hints_enabled: false,
+                hint_cost: DEFAULT_HINT_COST,
+                rounds_plan: Vec::new(),
             },
             GamePreset::NoMove => Self {
                 rounds: 5,
@@ -89,6 +215,17 @@ impl GameSettings {
                 movement_allowed: false,
                 zoom_allowed: false,
                 rotation_allowed: true,
+                scoring: ScoringConfig::default(),
+                streak: false,
+                time_budget_seconds: 0,
+                reconnect_grace_seconds: DEFAULT_RECONNECT_GRACE_SECONDS,
+                disconnect_policy: DisconnectPolicy::default(),
+                reactions_enabled: true,
+                allow_late_join: false,
+                classroom: false,
+                hints_enabled: false,
+                hint_cost: DEFAULT_HINT_COST,
+                rounds_plan: Vec::new(),
             },
             GamePreset::SpeedRound => Self {
                 rounds: 5,
@@ -97,6 +234,17 @@ impl GameSettings {
                 movement_allowed: true,
                 zoom_allowed: true,
                 rotation_allowed: true,
+                scoring: ScoringConfig::default(),
+                streak: false,
+                time_budget_seconds: 0,
+                reconnect_grace_seconds: DEFAULT_RECONNECT_GRACE_SECONDS,
+                disconnect_policy: DisconnectPolicy::default(),
+                reactions_enabled: true,
+                allow_late_join: false,
+                classroom: false,
+                hints_enabled: false,
+                hint_cost: DEFAULT_HINT_COST,
+                rounds_plan: Vec::new(),
             },
             GamePreset::Explorer => Self {
                 rounds: 10,
@@ -105,6 +253,17 @@ impl GameSettings {
                 movement_allowed: true,
                 zoom_allowed: true,
                 rotation_allowed: true,
+                scoring: ScoringConfig::default(),
+                streak: false,
+                time_budget_seconds: 0,
+                reconnect_grace_seconds: DEFAULT_RECONNECT_GRACE_SECONDS,
+                disconnect_policy: DisconnectPolicy::default(),
+                reactions_enabled: true,
+                allow_late_join: false,
+                classroom: false,
+                hints_enabled: false,
+                hint_cost: DEFAULT_HINT_COST,
+                rounds_plan: Vec::new(),
             },
             GamePreset::Custom => Self {
                 rounds: 5,
@@ -113,10 +272,64 @@ impl GameSettings {
                 movement_allowed: true,
                 zoom_allowed: true,
                 rotation_allowed: true,
+                scoring: ScoringConfig::default(),
+                streak: false,
+                time_budget_seconds: 0,
+                reconnect_grace_seconds: DEFAULT_RECONNECT_GRACE_SECONDS,
+                disconnect_policy: DisconnectPolicy::default(),
+                reactions_enabled: true,
+                allow_late_join: false,
+                classroom: false,
+                hints_enabled: false,
+                hint_cost: DEFAULT_HINT_COST,
+                rounds_plan: Vec::new(),
             },
         }
     }
 
+    /// Whether this is a time-attack game.
+    pub fn is_time_attack(&self) -> bool {
+        self.time_budget_seconds > 0
+    }
+
+    /// Time a disconnected player has to reconnect, in milliseconds.
+    pub fn reconnect_grace_ms(&self) -> u32 {
+        self.reconnect_grace_seconds * 1000
+    }
+
+    /// Number of rounds the game is played over (0 = unbounded: streak or
+    /// time attack).
+    pub fn total_rounds(&self) -> u8 {
+        if self.streak || self.is_time_attack() { 0 } else { self.rounds }
+    }
+
+    /// Map a round (1-indexed) is played on.
+    pub fn map_for_round(&self, round_number: u8) -> &str {
+        let mut last_round = 0u16;
+        for run in &self.rounds_plan {
+            last_round += u16::from(run.rounds);
+            if u16::from(round_number) <= last_round {
+                return &run.map_id;
+            }
+        }
+        &self.map_id
+    }
+
+    /// Number of rounds played on each map, in order of first use.
+    pub fn rounds_per_map(&self) -> Vec<(&str, u8)> {
+        if self.rounds_plan.is_empty() {
+            return vec![(self.map_id.as_str(), self.rounds)];
+        }
+        let mut totals: Vec<(&str, u8)> = Vec::new();
+        for run in &self.rounds_plan {
+            match totals.iter_mut().find(|(map_id, _)| *map_id == run.map_id) {
+                Some((_, rounds)) => *rounds = rounds.saturating_add(run.rounds),
+                None => totals.push((&run.map_id, run.rounds)),
+            }
+        }
+        totals
+    }
+
     /// Detect which preset matches the current settings (if any)
     pub fn detect_preset(&self) -> GamePreset {
         for preset in GamePreset::all() {
@@ -146,10 +359,53 @@ pub fn validate_settings(settings: &GameSettings) -> Result<(), Vec<&'static str
         errors.push("Time limit cannot exceed 10 minutes");
     }
 
+    if settings.time_budget_seconds > 1800 {
+        errors.push("Time budget cannot exceed 30 minutes");
+    }
+
+    if !(5..=300).contains(&settings.reconnect_grace_seconds) {
+        errors.push("Reconnection grace period must be between 5 seconds and 5 minutes");
+    }
+
+    if settings.streak && settings.is_time_attack() {
+        errors.push("Streak and time attack cannot be combined");
+    }
+
+    if settings.streak && settings.allow_late_join {
+        errors.push("Late joins are not available in streak games");
+    }
+
+    if settings.streak && settings.classroom {
+        errors.push("Streak games cannot be classroom games");
+    }
+
+    if settings.streak && settings.hints_enabled {
+        errors.push("Hints are not available in streak games");
+    }
+
+    if settings.hint_cost > 5000 {
+        errors.push("Hint cost cannot exceed 5000 points");
+    }
+
     if settings.map_id.is_empty() || settings.map_id.len() > 50 {
         errors.push("Invalid map ID");
     }
 
+    if !settings.rounds_plan.is_empty() {
+        if settings.streak || settings.is_time_attack() {
+            errors.push("Map playlists need a fixed number of rounds");
+        }
+        if settings.rounds_plan.iter().any(|run| run.map_id.is_empty() || run.map_id.len() > 50) {
+            errors.push("Invalid map ID in map playlist");
+        }
+        let planned: u32 = settings.rounds_plan.iter().map(|run| u32::from(run.rounds)).sum();
+        if settings.rounds_plan.iter().any(|run| run.rounds == 0)
+            || planned != u32::from(settings.rounds)
+        {
+            errors.push("Map playlist must cover each round exactly once");
+        }
+    }
+
     if errors.is_empty() { Ok(()) } else { Err(errors) }
 }
 
@@ -222,6 +478,29 @@ pub fn can_submit_guess(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_rounds_plan() {
+        let run = |map_id: &str, rounds| MapRounds { map_id: map_id.to_string(), rounds };
+        let mut settings = GameSettings {
+            rounds: 4,
+            rounds_plan: vec![run("world", 1), run("japan", 2), run("world", 1)],
+            ..GameSettings::default()
+        };
+        assert!(validate_settings(&settings).is_ok());
+        assert_eq!(settings.map_for_round(1), "world");
+        assert_eq!(settings.map_for_round(2), "japan");
+        assert_eq!(settings.map_for_round(3), "japan");
+        assert_eq!(settings.map_for_round(4), "world");
+        assert_eq!(settings.rounds_per_map(), vec![("world", 2), ("japan", 2)]);
+
+        settings.rounds = 5;
+        assert!(validate_settings(&settings).is_err());
+
+        settings.rounds_plan.clear();
+        assert_eq!(settings.map_for_round(3), settings.map_id);
+        assert_eq!(settings.rounds_per_map(), vec![(settings.map_id.as_str(), 5)]);
+    }
+
     #[test]
     fn test_default_settings_valid() {
         let settings = GameSettings::default();
@@ -246,6 +525,48 @@ mod tests {
         assert!(validate_settings(&settings).is_err());
     }
 
+    #[test]
+    fn test_invalid_time_budget() {
+        let settings = GameSettings { time_budget_seconds: 3600, ..Default::default() };
+        assert!(validate_settings(&settings).is_err());
+
+        let settings =
+            GameSettings { time_budget_seconds: 180, streak: true, ..Default::default() };
+        assert!(validate_settings(&settings).is_err());
+
+        let settings = GameSettings { time_budget_seconds: 180, ..Default::default() };
+        assert!(validate_settings(&settings).is_ok());
+        assert_eq!(settings.total_rounds(), 0);
+    }
+
+    #[test]
+    fn test_invalid_reconnect_grace() {
+        let settings = GameSettings { reconnect_grace_seconds: 0, ..Default::default() };
+        assert!(validate_settings(&settings).is_err());
+
+        let settings = GameSettings { reconnect_grace_seconds: 600, ..Default::default() };
+        assert!(validate_settings(&settings).is_err());
+
+        // Games saved before the setting existed keep the old grace period
+        let settings: GameSettings = serde_json::from_value(serde_json::json!({
+            "rounds": 5,
+            "time_limit_seconds": 120,
+            "map_id": "world",
+            "movement_allowed": true,
+            "zoom_allowed": true,
+            "rotation_allowed": true,
+        }))
+        .unwrap();
+        assert_eq!(settings.reconnect_grace_ms(), 30_000);
+        assert_eq!(settings.disconnect_policy, DisconnectPolicy::KeepScoringZero);
+        assert!(settings.reactions_enabled);
+        assert!(!settings.allow_late_join);
+
+        for policy in DisconnectPolicy::ALL {
+            assert_eq!(policy.as_str().parse::<DisconnectPolicy>(), Ok(policy));
+        }
+    }
+
     #[test]
     fn test_invalid_empty_map_id() {
         let settings = GameSettings { map_id: "".to_string(), ..Default::default() };
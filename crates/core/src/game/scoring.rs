@@ -1,7 +1,25 @@
 //! Scoring algorithms
 
+use serde::{Deserialize, Serialize};
+
+/// Upper bound on a configured maximum score per round.
+pub const MAX_SCORE_LIMIT: u32 = 100_000;
+
+/// Upper bound on the zero-score distance: half the Earth's circumference.
+pub const MAX_ZERO_SCORE_DISTANCE: f64 = 20_037_509.0;
+
+/// Smallest score multiplier a host can give a player.
+pub const MIN_HANDICAP: f64 = 0.5;
+
+/// Largest score multiplier a host can give a player.
+pub const MAX_HANDICAP: f64 = 2.0;
+
 /// Scoring configuration
-#[derive(Debug, Clone)]
+///
+/// Maps can override the default curve; missing fields fall back to the
+/// defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ScoringConfig {
     /// Maximum points possible per round
     pub max_points: u32,
@@ -9,6 +27,8 @@ pub struct ScoringConfig {
     pub zero_score_distance: f64,
     /// Scoring curve exponent (higher = steeper dropoff)
     pub curve_exponent: f64,
+    /// Guesses within this distance (in meters) score maximum points
+    pub perfect_radius_meters: f64,
 }
 
 impl Default for ScoringConfig {
@@ -17,14 +37,49 @@ impl Default for ScoringConfig {
             max_points: 5000,
             zero_score_distance: 5_000_000.0, // 5,000 km - roughly continent-scale
             curve_exponent: 1.5,              // Steeper dropoff for far guesses
+            perfect_radius_meters: 0.0,
         }
     }
 }
 
+/// Reasons a scoring configuration is rejected.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ScoringError {
+    #[error("max_points must be between 1 and {MAX_SCORE_LIMIT}")]
+    MaxPoints,
+    #[error("zero_score_distance must be between 1000 and {MAX_ZERO_SCORE_DISTANCE} meters")]
+    ZeroScoreDistance,
+    #[error("curve_exponent must be between 0.1 and 10")]
+    CurveExponent,
+    #[error("perfect_radius_meters must be at least 0 and less than zero_score_distance")]
+    PerfectRadius,
+}
+
+impl ScoringConfig {
+    /// Check the curve produces sensible scores.
+    pub fn validate(&self) -> Result<(), ScoringError> {
+        if self.max_points == 0 || self.max_points > MAX_SCORE_LIMIT {
+            return Err(ScoringError::MaxPoints);
+        }
+        if !(1_000.0..=MAX_ZERO_SCORE_DISTANCE).contains(&self.zero_score_distance) {
+            return Err(ScoringError::ZeroScoreDistance);
+        }
+        if !(0.1..=10.0).contains(&self.curve_exponent) {
+            return Err(ScoringError::CurveExponent);
+        }
+        if !(self.perfect_radius_meters >= 0.0
+            && self.perfect_radius_meters < self.zero_score_distance)
+        {
+            return Err(ScoringError::PerfectRadius);
+        }
+        Ok(())
+    }
+}
+
 /// Calculate score based on distance from target.
 /// Uses exponential decay formula similar to GeoGuessr.
 pub fn calculate_score(distance_meters: f64, config: &ScoringConfig) -> u32 {
-    if distance_meters <= 0.0 {
+    if distance_meters <= config.perfect_radius_meters {
         return config.max_points;
     }
 
@@ -42,7 +97,7 @@ pub fn calculate_score(distance_meters: f64, config: &ScoringConfig) -> u32 {
 
 /// Alternative scoring: logarithmic decay (more forgiving at close distances)
 pub fn calculate_score_logarithmic(distance_meters: f64, config: &ScoringConfig) -> u32 {
-    if distance_meters <= 1.0 {
+    if distance_meters <= config.perfect_radius_meters.max(1.0) {
         return config.max_points;
     }
 
@@ -58,6 +113,24 @@ pub fn calculate_score_logarithmic(distance_meters: f64, config: &ScoringConfig)
     score.max(0.0).round() as u32
 }
 
+/// Points a round score adds to a player's total after their handicap.
+pub fn apply_handicap(score: u32, handicap: f64) -> u32 {
+    (score as f64 * handicap).round() as u32
+}
+
+/// Whether a score multiplier is within the allowed range.
+pub fn is_valid_handicap(handicap: f64) -> bool {
+    (MIN_HANDICAP..=MAX_HANDICAP).contains(&handicap)
+}
+
+/// Score a streak round: one point when the named country is the round's
+/// country, so a streak game's total is its streak length.
+///
+/// Rounds whose country is unknown cannot be matched.
+pub fn streak_score(named_country: &str, round_country: Option<&str>) -> u32 {
+    u32::from(round_country.is_some_and(|c| c.eq_ignore_ascii_case(named_country.trim())))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -103,6 +176,67 @@ mod tests {
         assert_eq!(calculate_score_logarithmic(5_000_001.0, &config), 0);
     }
 
+    #[test]
+    fn test_streak_score() {
+        assert_eq!(streak_score("FR", Some("FR")), 1);
+        assert_eq!(streak_score(" fr", Some("FR")), 1);
+        assert_eq!(streak_score("BE", Some("FR")), 0);
+        assert_eq!(streak_score("FR", None), 0);
+    }
+
+    #[test]
+    fn test_perfect_radius() {
+        let config = ScoringConfig { perfect_radius_meters: 50_000.0, ..Default::default() };
+        assert_eq!(calculate_score(50_000.0, &config), 5000);
+        assert!(calculate_score(60_000.0, &config) < 5000);
+    }
+
+    #[test]
+    fn test_custom_curve() {
+        let config = ScoringConfig {
+            max_points: 1000,
+            zero_score_distance: 100_000.0,
+            curve_exponent: 1.0,
+            perfect_radius_meters: 0.0,
+        };
+        assert_eq!(calculate_score(50_000.0, &config), 500);
+        assert_eq!(calculate_score(100_000.0, &config), 0);
+    }
+
+    #[test]
+    fn test_partial_config_uses_defaults() {
+        let config: ScoringConfig = serde_json::from_str(r#"{"max_points": 1000}"#).unwrap();
+        assert_eq!(config.max_points, 1000);
+        assert_eq!(config.zero_score_distance, 5_000_000.0);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(ScoringConfig::default().validate().is_ok());
+
+        let config = ScoringConfig { max_points: 0, ..Default::default() };
+        assert_eq!(config.validate(), Err(ScoringError::MaxPoints));
+
+        let config = ScoringConfig { zero_score_distance: f64::NAN, ..Default::default() };
+        assert_eq!(config.validate(), Err(ScoringError::ZeroScoreDistance));
+
+        let config = ScoringConfig { curve_exponent: 0.0, ..Default::default() };
+        assert_eq!(config.validate(), Err(ScoringError::CurveExponent));
+
+        let config = ScoringConfig { perfect_radius_meters: 5_000_000.0, ..Default::default() };
+        assert_eq!(config.validate(), Err(ScoringError::PerfectRadius));
+    }
+
+    #[test]
+    fn test_apply_handicap() {
+        assert_eq!(apply_handicap(4000, 1.0), 4000);
+        assert_eq!(apply_handicap(4000, 1.25), 5000);
+        assert_eq!(apply_handicap(3333, 0.5), 1667);
+        assert!(is_valid_handicap(1.25));
+        assert!(!is_valid_handicap(2.5));
+        assert!(!is_valid_handicap(f64::NAN));
+    }
+
     #[test]
     fn test_continent_scale_scoring() {
         let config = ScoringConfig::default();
@@ -0,0 +1,490 @@
+//! Deterministic simulation testing for the reducer.
+//!
+//! [`CommandGenerator`] produces random but reproducible command sequences:
+//! players joining, leaving, disconnecting and reconnecting, guesses, host
+//! actions and ticks from a clock that mostly moves forward but sometimes
+//! jumps past timeouts or lags behind, as it does when commands arrive from
+//! instances with skewed clocks. [`simulate`] feeds them through [`reduce`]
+//! and checks [`check_step`]'s invariants after every step, so a failure
+//! names the seed and step that reproduce it.
+//!
+//! Available to other crates' tests with the `simulation` feature:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! dguesser-core = { workspace = true, features = ["simulation"] }
+//! ```
+
+use chrono::{DateTime, Duration, Utc};
+use rand::{RngExt, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use super::commands::{GameCommand, LocationData};
+use super::events::GameEvent;
+use super::hints::HintKind;
+use super::reducer::{ReducerResult, reduce};
+use super::rules::{DisconnectPolicy, GameSettings, validate_settings};
+use super::state::{GamePhase, GameState, RoundProgression};
+
+/// Country codes locations and streak guesses are drawn from
+const COUNTRIES: [&str; 5] = ["us", "fr", "jp", "br", "au"];
+
+/// Simulated time the first step happens at (2024-01-01T00:00:00Z)
+const EPOCH_SECS: i64 = 1_704_067_200;
+
+/// A command and the time it is applied at.
+#[derive(Debug, Clone)]
+pub struct SimStep {
+    pub command: GameCommand,
+    pub now: DateTime<Utc>,
+}
+
+/// Seeded generator of plausible (and some implausible) game commands.
+///
+/// Commands are picked with the current state in mind, mostly naming players
+/// who are in the game and the round's actual panorama, so that sequences
+/// get past the lobby instead of bouncing off validation.
+pub struct CommandGenerator {
+    rng: ChaCha8Rng,
+    players: Vec<String>,
+    clock: DateTime<Utc>,
+    next_location: u32,
+}
+
+impl CommandGenerator {
+    /// Create a generator drawing from a pool of four players.
+    pub fn new(seed: u64) -> Self {
+        Self::with_players(seed, 4)
+    }
+
+    /// Create a generator drawing from a pool of `count` players.
+    pub fn with_players(seed: u64, count: usize) -> Self {
+        Self {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            players: (0..count).map(|i| format!("usr_sim{i}")).collect(),
+            clock: DateTime::from_timestamp(EPOCH_SECS, 0).expect("valid timestamp"),
+            next_location: 0,
+        }
+    }
+
+    /// User IDs commands are issued for.
+    pub fn players(&self) -> &[String] {
+        &self.players
+    }
+
+    /// Latest simulated time.
+    pub fn now(&self) -> DateTime<Utc> {
+        self.clock
+    }
+
+    /// A fresh lobby with random valid settings: shared or per-player, timed
+    /// or not, with any disconnect policy and occasionally streak or time
+    /// attack.
+    pub fn initial_state(&mut self, game_id: &str) -> GameState {
+        let mut state = GameState::new(game_id.to_string(), self.settings());
+        if self.rng.random_ratio(1, 5) {
+            state.progression = RoundProgression::PerPlayer;
+        }
+        state.created_at = self.clock;
+        state
+    }
+
+    /// Random settings, falling back to the defaults if the combination is
+    /// invalid.
+    pub fn settings(&mut self) -> GameSettings {
+        let mut settings = GameSettings {
+            rounds: self.rng.random_range(1..=5),
+            time_limit_seconds: if self.rng.random_ratio(1, 4) {
+                0
+            } else {
+                self.rng.random_range(5..=60)
+            },
+            reconnect_grace_seconds: self.rng.random_range(5..=60),
+            disconnect_policy: DisconnectPolicy::ALL[self.rng.random_range(0..3)],
+            allow_late_join: self.rng.random_bool(0.5),
+            classroom: self.rng.random_ratio(1, 4),
+            hints_enabled: self.rng.random_bool(0.5),
+            ..GameSettings::default()
+        };
+        match self.rng.random_range(0..10) {
+            0 => settings.streak = true,
+            1 => settings.time_budget_seconds = self.rng.random_range(30..=120),
+            _ => {}
+        }
+        if validate_settings(&settings).is_ok() { settings } else { GameSettings::default() }
+    }
+
+    /// Pick the next command for `state` and the time to apply it at.
+    pub fn next_step(&mut self, state: &GameState) -> SimStep {
+        let now = self.tick_clock();
+
+        // Hosts of lobbies with company get on with it
+        if state.phase == GamePhase::Lobby && state.players.len() > 1 && self.rng.random_ratio(1, 4)
+        {
+            let command = GameCommand::Start {
+                user_id: self.host_of(state),
+                first_location: self.location(),
+            };
+            return SimStep { command, now };
+        }
+
+        let command = match self.rng.random_range(0..105) {
+            0..26 => GameCommand::Tick,
+            26..46 => self.guess(state),
+            46..54 => GameCommand::Join {
+                user_id: self.any_player(),
+                display_name: "Sim".to_string(),
+                avatar_url: None,
+                is_host: state.get_host().is_none() || self.rng.random_ratio(1, 20),
+            },
+            54..60 => GameCommand::Disconnect { user_id: self.player_in(state) },
+            60..66 => GameCommand::Reconnect { user_id: self.player_in(state) },
+            66..69 => GameCommand::Leave { user_id: self.player_in(state) },
+            69..74 => {
+                GameCommand::Start { user_id: self.host_of(state), first_location: self.location() }
+            }
+            74..79 => GameCommand::StartPlayerRound {
+                user_id: self.player_in(state),
+                location: self.location(),
+            },
+            // What the game actor does once the between-rounds wait is over
+            79..84 if state.phase == GamePhase::BetweenRounds && !state.can_start_round(now) => {
+                GameCommand::EndGame
+            }
+            79..84 => GameCommand::AdvanceRound { next_location: self.location() },
+            84..87 => GameCommand::EndRound,
+            87..90 => GameCommand::VoteSkipWait { user_id: self.player_in(state) },
+            90..92 => GameCommand::SkipWait { user_id: self.host_of(state) },
+            92..95 => GameCommand::RerollRound {
+                user_id: self.host_of(state),
+                new_location: self.location(),
+            },
+            95..97 => GameCommand::UpdateSettings {
+                user_id: self.host_of(state),
+                settings: self.settings(),
+            },
+            97..99 => GameCommand::SetHandicap {
+                user_id: self.host_of(state),
+                player_id: self.player_in(state),
+                handicap: self.rng.random_range(0.0..3.0),
+            },
+            99..101 if state.teacher_paused => GameCommand::Resume { user_id: self.host_of(state) },
+            99..101 => GameCommand::Pause { user_id: self.host_of(state) },
+            101..104 => GameCommand::RequestHint {
+                user_id: self.player_in(state),
+                kind: HintKind::ALL[self.rng.random_range(0..HintKind::ALL.len())],
+                from: Some((
+                    self.rng.random_range(-90.0..=90.0),
+                    self.rng.random_range(-180.0..=180.0),
+                )),
+            },
+            // Ended early, say by an admin
+            _ if self.rng.random_ratio(1, 5) => GameCommand::EndGame,
+            _ => GameCommand::Tick,
+        };
+        SimStep { command, now }
+    }
+
+    /// Move the clock on and pick the time for the next step.
+    ///
+    /// Usually a few seconds pass; sometimes enough for rounds, grace periods
+    /// and abandonment to time out. One step in twenty is stamped up to three
+    /// seconds behind the clock, as if handled by an instance whose clock
+    /// lags.
+    fn tick_clock(&mut self) -> DateTime<Utc> {
+        let advance_ms = match self.rng.random_range(0..50) {
+            0..42 => self.rng.random_range(0..1_500),
+            42..49 => self.rng.random_range(1_500..40_000),
+            _ => self.rng.random_range(40_000..200_000),
+        };
+        self.clock += Duration::milliseconds(advance_ms);
+        if self.rng.random_ratio(1, 20) {
+            self.clock - Duration::milliseconds(self.rng.random_range(1..3_000))
+        } else {
+            self.clock
+        }
+    }
+
+    fn guess(&mut self, state: &GameState) -> GameCommand {
+        let user_id = self.player_in(state);
+        let round = match state.progression {
+            RoundProgression::Shared => state.current_round.as_ref(),
+            RoundProgression::PerPlayer => state.player_rounds.get(&user_id),
+        };
+        let (lat, lng) = match round {
+            Some(round) if self.rng.random_bool(0.5) => (
+                (round.location_lat + self.rng.random_range(-5.0..5.0)).clamp(-90.0, 90.0),
+                (round.location_lng + self.rng.random_range(-5.0..5.0)).clamp(-180.0, 180.0),
+            ),
+            _ => (self.rng.random_range(-90.0..=90.0), self.rng.random_range(-180.0..=180.0)),
+        };
+        let reported_panorama_id = match self.rng.random_range(0..10) {
+            0 => None,
+            1 => Some("pano_elsewhere".to_string()),
+            _ => round.and_then(|round| round.panorama_id.clone()),
+        };
+        let country_code = match round.and_then(|round| round.country_code.clone()) {
+            Some(code) if self.rng.random_ratio(2, 3) => Some(code),
+            _ => Some(self.country()),
+        };
+        GameCommand::SubmitGuess {
+            user_id,
+            lat,
+            lng,
+            time_taken_ms: Some(self.rng.random_range(0..120_000)),
+            reported_panorama_id,
+            country_code,
+        }
+    }
+
+    fn location(&mut self) -> LocationData {
+        self.next_location += 1;
+        LocationData::new(
+            self.rng.random_range(-60.0..70.0),
+            self.rng.random_range(-180.0..180.0),
+            Some(format!("pano_{}", self.next_location)),
+        )
+        .with_country_code(Some(self.country()))
+    }
+
+    fn country(&mut self) -> String {
+        COUNTRIES[self.rng.random_range(0..COUNTRIES.len())].to_string()
+    }
+
+    /// Any player from the pool, or now and then a stranger
+    fn any_player(&mut self) -> String {
+        if self.rng.random_ratio(1, 20) {
+            return "usr_stranger".to_string();
+        }
+        self.players[self.rng.random_range(0..self.players.len())].clone()
+    }
+
+    /// Usually a player in the game
+    fn player_in(&mut self, state: &GameState) -> String {
+        let mut ids = state.all_player_ids();
+        if ids.is_empty() || self.rng.random_ratio(1, 10) {
+            return self.any_player();
+        }
+        ids.sort_unstable();
+        ids[self.rng.random_range(0..ids.len())].to_string()
+    }
+
+    /// Usually the host
+    fn host_of(&mut self, state: &GameState) -> String {
+        match state.get_host() {
+            Some(host) if self.rng.random_ratio(9, 10) => host.user_id.clone(),
+            _ => self.player_in(state),
+        }
+    }
+}
+
+/// An invariant broken during a simulation.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("seed {seed}, step {step} ({command}): {message}")]
+pub struct InvariantViolation {
+    /// Seed that reproduces the failure
+    pub seed: u64,
+    /// Index of the step that broke the invariant
+    pub step: usize,
+    /// The command applied in that step
+    pub command: String,
+    pub message: String,
+}
+
+/// Run `steps` generated commands from a fresh lobby, checking invariants
+/// after each. Returns the final state.
+pub fn simulate(seed: u64, steps: usize) -> Result<GameState, InvariantViolation> {
+    let mut generator = CommandGenerator::new(seed);
+    let mut state = generator.initial_state("gam_simulated");
+    for step in 0..steps {
+        let SimStep { command, now } = generator.next_step(&state);
+        let description = format!("{command:?} at {}", now.timestamp_millis());
+        let result = reduce(&state, command.clone(), now);
+        check_step(&state, &command, now, &result).map_err(|message| InvariantViolation {
+            seed,
+            step,
+            command: description,
+            message,
+        })?;
+        state = result.state;
+    }
+    Ok(state)
+}
+
+/// Check the invariants that must hold for one reducer step:
+///
+/// - phases only move forward, and a finished game stays finished
+/// - scores never decrease, except when a reroll takes back the points
+///   scored on a discarded location
+/// - no guess is accepted once its round has timed out, or while paused,
+///   or from the teacher of a classroom game
+/// - a guess never scores more than the round's hints leave, and hints are
+///   only revealed in games that allow them
+/// - rejected commands leave the state unchanged
+/// - bounded games never get past their last round
+pub fn check_step(
+    before: &GameState,
+    command: &GameCommand,
+    now: DateTime<Utc>,
+    result: &ReducerResult,
+) -> Result<(), String> {
+    let after = &result.state;
+
+    if !is_legal_transition(before.phase, after.phase) {
+        return Err(format!("illegal phase transition {} -> {}", before.phase, after.phase));
+    }
+
+    if result.has_error() && (result.changed || before.phase != after.phase) {
+        return Err("rejected command changed the state".to_string());
+    }
+
+    let rerolled = result.events.iter().any(|e| matches!(e, GameEvent::RoundRerolled { .. }));
+    if !rerolled {
+        for (id, player) in &after.players {
+            if let Some(previous) = before.players.get(id)
+                && player.total_score < previous.total_score
+            {
+                return Err(format!(
+                    "score of {id} decreased from {} to {}",
+                    previous.total_score, player.total_score
+                ));
+            }
+        }
+    }
+
+    let accepted_guess =
+        result.events.iter().any(|e| matches!(e, GameEvent::GuessSubmitted { .. }));
+    if accepted_guess && let GameCommand::SubmitGuess { user_id, .. } = command {
+        let round = match before.progression {
+            RoundProgression::Shared => before.current_round.as_ref(),
+            RoundProgression::PerPlayer => before.player_rounds.get(user_id),
+        };
+        let Some(round) = round else {
+            return Err(format!("guess by {user_id} accepted without a round"));
+        };
+        if round.is_timed_out(now) {
+            return Err(format!(
+                "guess by {user_id} accepted after round {} timed out",
+                round.round_number
+            ));
+        }
+        if before.paused_at.is_some() {
+            return Err(format!("guess by {user_id} accepted while paused"));
+        }
+        if before.is_teacher(user_id) {
+            return Err(format!("guess by teacher {user_id} accepted"));
+        }
+        let max = before.settings.scoring.max_points;
+        let hint_cost = round.hint_cost(user_id, before.settings.hint_cost);
+        let scored = match before.progression {
+            RoundProgression::Shared => after.latest_round(),
+            RoundProgression::PerPlayer => after.player_rounds.get(user_id),
+        }
+        .and_then(|round| round.guesses.get(user_id))
+        .map_or(0, |guess| guess.score);
+        if !before.settings.streak && scored > max.saturating_sub(hint_cost) {
+            return Err(format!("guess by {user_id} scored {scored} despite {hint_cost} in hints"));
+        }
+    }
+
+    let revealed = result.events.iter().any(|e| matches!(e, GameEvent::HintRevealed { .. }));
+    if revealed && !before.settings.hints_enabled {
+        return Err("hint revealed in a game without hints".to_string());
+    }
+
+    let total_rounds = after.total_rounds();
+    if total_rounds > 0 && after.round_number > total_rounds {
+        return Err(format!("round {} of a {total_rounds}-round game", after.round_number));
+    }
+
+    Ok(())
+}
+
+/// Whether the reducer may move a game from `from` to `to` in one step
+fn is_legal_transition(from: GamePhase, to: GamePhase) -> bool {
+    use GamePhase::*;
+    from == to
+        || matches!(
+            (from, to),
+            (Lobby, Active | RoundInProgress | Finished)
+                | (Active, Finished)
+                | (RoundInProgress, BetweenRounds | Finished)
+                | (BetweenRounds, RoundInProgress | Finished)
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simulated_games_keep_invariants() {
+        for seed in 0..300 {
+            if let Err(violation) = simulate(seed, 400) {
+                panic!("{violation}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_generator_is_deterministic() {
+        let commands = |seed| {
+            let mut generator = CommandGenerator::new(seed);
+            let mut state = generator.initial_state("gam_simulated");
+            (0..200)
+                .map(|_| {
+                    let step = generator.next_step(&state);
+                    state = reduce(&state, step.command.clone(), step.now).state;
+                    format!("{step:?}")
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(commands(7), commands(7));
+        assert_ne!(commands(7), commands(8));
+    }
+
+    #[test]
+    fn test_simulations_reach_every_phase() {
+        let mut phases = Vec::new();
+        let mut guesses = 0;
+        for seed in 0..100 {
+            let mut generator = CommandGenerator::new(seed);
+            let mut state = generator.initial_state("gam_simulated");
+            for _ in 0..300 {
+                let step = generator.next_step(&state);
+                let result = reduce(&state, step.command, step.now);
+                guesses += result
+                    .events
+                    .iter()
+                    .filter(|e| matches!(e, GameEvent::GuessSubmitted { .. }))
+                    .count();
+                state = result.state;
+                phases.push(state.phase);
+            }
+        }
+        for phase in [
+            GamePhase::Lobby,
+            GamePhase::Active,
+            GamePhase::RoundInProgress,
+            GamePhase::BetweenRounds,
+            GamePhase::Finished,
+        ] {
+            assert!(phases.contains(&phase), "no simulation reached {phase}");
+        }
+        assert!(guesses > 100, "only {guesses} guesses were accepted");
+    }
+
+    #[test]
+    fn test_check_step_rejects_illegal_transition() {
+        let mut generator = CommandGenerator::new(1);
+        let before = generator.initial_state("gam_simulated");
+        let mut after = before.clone();
+        after.phase = GamePhase::BetweenRounds;
+        let result =
+            ReducerResult { state: after, events: vec![GameEvent::WaitSkipped], changed: true };
+
+        let error = check_step(&before, &GameCommand::Tick, generator.now(), &result).unwrap_err();
+        assert!(error.contains("illegal phase transition"));
+        assert!(!is_legal_transition(GamePhase::Finished, GamePhase::Lobby));
+    }
+}
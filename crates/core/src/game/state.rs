@@ -8,7 +8,9 @@ use std::collections::{HashMap, HashSet};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use super::hints::Hint;
 use super::rules::GameSettings;
+use crate::streetview::ImageryProvider;
 
 /// Unified game phase - represents the current state of a game's lifecycle.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -38,6 +40,20 @@ impl std::fmt::Display for GamePhase {
     }
 }
 
+/// How players move through a game's rounds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundProgression {
+    /// All players play each round together (solo and live multiplayer)
+    #[default]
+    Shared,
+    /// Each player plays the same rounds on their own schedule (async challenges).
+    ///
+    /// The game stays in [`GamePhase::Active`] until every player has played
+    /// the last round, and each player's round lives in `player_rounds`.
+    PerPlayer,
+}
+
 /// Player state within a game.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlayerState {
@@ -55,6 +71,17 @@ pub struct PlayerState {
     pub connected: bool,
     /// When the player disconnected (for grace period tracking)
     pub disconnected_at: Option<DateTime<Utc>>,
+    /// Multiplier applied to round scores before they are added to the total
+    #[serde(default = "default_handicap")]
+    pub handicap: f64,
+    /// First round a late joiner could play (`None` if they joined in the
+    /// lobby); the rounds before it score 0
+    #[serde(default)]
+    pub joined_round: Option<u8>,
+}
+
+fn default_handicap() -> f64 {
+    1.0
 }
 
 impl PlayerState {
@@ -73,6 +100,8 @@ impl PlayerState {
             total_score: 0,
             connected: true,
             disconnected_at: None,
+            handicap: default_handicap(),
+            joined_round: None,
         }
     }
 }
@@ -92,6 +121,12 @@ pub struct Guess {
     pub score: u32,
     /// Time taken to submit the guess in milliseconds
     pub time_taken_ms: Option<u32>,
+    /// Panorama the client reported at guess time
+    #[serde(default)]
+    pub reported_panorama_id: Option<String>,
+    /// Country the player named (streak mode)
+    #[serde(default)]
+    pub country_code: Option<String>,
     /// When the guess was submitted
     pub submitted_at: DateTime<Utc>,
 }
@@ -111,12 +146,21 @@ pub struct RoundState {
     pub location_id: Option<String>,
     /// Default heading/direction for panorama (degrees, 0-360)
     pub heading: Option<f64>,
+    /// Provider of the imagery
+    #[serde(default)]
+    pub provider: ImageryProvider,
+    /// Country of the location, which streak guesses must name
+    #[serde(default)]
+    pub country_code: Option<String>,
     /// When the round started
     pub started_at: DateTime<Utc>,
     /// Time limit in milliseconds (None = unlimited)
     pub time_limit_ms: Option<u32>,
     /// Guesses submitted by players (keyed by user_id)
     pub guesses: HashMap<String, Guess>,
+    /// Hints players took this round, in order (keyed by user_id)
+    #[serde(default)]
+    pub hints: HashMap<String, Vec<Hint>>,
 }
 
 impl RoundState {
@@ -129,6 +173,7 @@ impl RoundState {
         panorama_id: Option<String>,
         location_id: Option<String>,
         heading: Option<f64>,
+        provider: ImageryProvider,
         time_limit_ms: Option<u32>,
         started_at: DateTime<Utc>,
     ) -> Self {
@@ -139,12 +184,21 @@ impl RoundState {
             panorama_id,
             location_id,
             heading,
+            provider,
+            country_code: None,
             started_at,
             time_limit_ms,
             guesses: HashMap::new(),
+            hints: HashMap::new(),
         }
     }
 
+    /// Points a player's hints this round cost in total.
+    pub fn hint_cost(&self, user_id: &str, cost_per_hint: u32) -> u32 {
+        let taken = self.hints.get(user_id).map_or(0, Vec::len) as u32;
+        taken * cost_per_hint
+    }
+
     /// Check if the round has timed out.
     pub fn is_timed_out(&self, now: DateTime<Utc>) -> bool {
         match self.time_limit_ms {
@@ -210,6 +264,25 @@ pub struct GameState {
     /// User IDs of players who have voted to skip the between-rounds wait
     #[serde(default)]
     pub skip_votes: HashSet<String>,
+    /// Whether rounds are shared or played per player
+    #[serde(default)]
+    pub progression: RoundProgression,
+    /// Each player's latest round in per-player games (keyed by user_id)
+    #[serde(default)]
+    pub player_rounds: HashMap<String, RoundState>,
+    /// Unix timestamp (ms) when a time-attack game's shared budget runs out
+    #[serde(default)]
+    pub time_budget_ends_at: Option<i64>,
+    /// When the game was paused waiting for the host to reconnect
+    #[serde(default)]
+    pub paused_at: Option<DateTime<Utc>>,
+    /// Number of rounds the host has rerolled
+    #[serde(default)]
+    pub rerolls_used: u8,
+    /// Whether the teacher paused the game; it stays paused until they
+    /// resume, whatever the grace period
+    #[serde(default)]
+    pub teacher_paused: bool,
 }
 
 impl GameState {
@@ -228,12 +301,23 @@ impl GameState {
             all_disconnected_at: None,
             between_rounds_ends_at: None,
             skip_votes: HashSet::new(),
+            progression: RoundProgression::Shared,
+            player_rounds: HashMap::new(),
+            time_budget_ends_at: None,
+            paused_at: None,
+            rerolls_used: 0,
+            teacher_paused: false,
         }
     }
 
-    /// Get IDs of all connected players.
+    /// Get IDs of all connected players who guess (everyone but the
+    /// teacher of a classroom game).
     pub fn connected_player_ids(&self) -> Vec<&str> {
-        self.players.values().filter(|p| p.connected).map(|p| p.user_id.as_str()).collect()
+        self.players
+            .values()
+            .filter(|p| p.connected && !self.is_teacher(&p.user_id))
+            .map(|p| p.user_id.as_str())
+            .collect()
     }
 
     /// Get IDs of all players (connected or not).
@@ -241,16 +325,85 @@ impl GameState {
         self.players.keys().map(|s| s.as_str()).collect()
     }
 
-    /// Get the total number of rounds configured.
+    /// Get the total number of rounds configured (0 = unbounded streak or
+    /// time attack).
     pub fn total_rounds(&self) -> u8 {
-        self.settings.rounds
+        self.settings.total_rounds()
     }
 
     /// Check if the game has more rounds remaining.
+    ///
+    /// Streak games go on until the streak is broken and time-attack games
+    /// until the budget runs out (see [`Self::can_start_round`]), both up to
+    /// `u8::MAX` rounds.
     pub fn has_more_rounds(&self) -> bool {
+        if self.settings.streak {
+            return self.round_number < u8::MAX && !self.streak_broken();
+        }
+        if self.settings.is_time_attack() {
+            return self.round_number < u8::MAX;
+        }
         self.round_number < self.settings.rounds
     }
 
+    /// Check if another round can start at `now`.
+    pub fn can_start_round(&self, now: DateTime<Utc>) -> bool {
+        self.has_more_rounds() && !self.time_budget_spent(now)
+    }
+
+    /// Check if a time-attack game has used up its time budget.
+    pub fn time_budget_spent(&self, now: DateTime<Utc>) -> bool {
+        self.time_budget_ends_at.is_some_and(|ends_at| now.timestamp_millis() >= ends_at)
+    }
+
+    /// Time limit for a round starting at `now`: whatever is left of the
+    /// budget in time attack, otherwise the per-round limit.
+    pub fn round_time_limit_ms(&self, now: DateTime<Utc>) -> Option<u32> {
+        if let Some(ends_at) = self.time_budget_ends_at {
+            return Some((ends_at - now.timestamp_millis()).max(0) as u32);
+        }
+        if self.settings.time_limit_seconds > 0 {
+            Some(self.settings.time_limit_seconds * 1000)
+        } else {
+            None
+        }
+    }
+
+    /// Get the current round, or the last completed one between rounds.
+    pub fn latest_round(&self) -> Option<&RoundState> {
+        self.current_round.as_ref().or(self.completed_rounds.last())
+    }
+
+    /// Check if a player missed the country of the latest round (or has not
+    /// guessed on it), which ends a streak game.
+    pub fn streak_broken(&self) -> bool {
+        let Some(round) = self.latest_round() else {
+            return false;
+        };
+        self.players.keys().any(|id| round.guesses.get(id).is_none_or(|g| g.score == 0))
+    }
+
+    /// Check if a new player can join now that the game has started.
+    ///
+    /// Only live games that allow late joins accept them, and only until the
+    /// game finishes.
+    pub fn accepts_late_join(&self) -> bool {
+        self.settings.allow_late_join
+            && self.progression == RoundProgression::Shared
+            && matches!(
+                self.phase,
+                GamePhase::Active | GamePhase::RoundInProgress | GamePhase::BetweenRounds
+            )
+    }
+
+    /// First round a player joining now would play.
+    pub fn next_playable_round(&self) -> u8 {
+        match self.phase {
+            GamePhase::RoundInProgress => self.round_number,
+            _ => self.round_number.saturating_add(1),
+        }
+    }
+
     /// Get a player by user ID.
     pub fn get_player(&self, user_id: &str) -> Option<&PlayerState> {
         self.players.get(user_id)
@@ -276,11 +429,38 @@ impl GameState {
         self.players.values().filter(|p| p.connected).count()
     }
 
-    /// Get the number of votes required to skip the between-rounds wait (majority >50%).
+    /// Check if a user is the teacher: the host of a classroom game, who
+    /// watches instead of playing.
+    pub fn is_teacher(&self, user_id: &str) -> bool {
+        self.settings.classroom && self.is_host(user_id)
+    }
+
+    /// Get the number of votes required to skip the between-rounds wait
+    /// (majority >50% of the connected players who guess).
     pub fn skip_votes_required(&self) -> usize {
-        let connected = self.connected_player_count();
+        let connected = self.connected_player_ids().len();
         (connected / 2) + 1
     }
+
+    /// Get a player's latest round in a per-player game.
+    pub fn player_round(&self, user_id: &str) -> Option<&RoundState> {
+        self.player_rounds.get(user_id)
+    }
+
+    /// Check if a player has guessed on (or run out of time in) the last round
+    /// of a per-player game.
+    pub fn player_finished(&self, user_id: &str, now: DateTime<Utc>) -> bool {
+        self.player_rounds.get(user_id).is_some_and(|round| {
+            round.round_number >= self.settings.rounds
+                && (round.has_guessed(user_id) || round.is_timed_out(now))
+        })
+    }
+
+    /// Check if every player has finished a per-player game.
+    pub fn all_players_finished(&self, now: DateTime<Utc>) -> bool {
+        !self.players.is_empty()
+            && self.players.keys().all(|id| self.is_teacher(id) || self.player_finished(id, now))
+    }
 }
 
 #[cfg(test)]
@@ -305,7 +485,17 @@ mod tests {
     #[test]
     fn test_round_timeout() {
         let now = Utc::now();
-        let round = RoundState::new(1, 0.0, 0.0, None, None, None, Some(60_000), now);
+        let round = RoundState::new(
+            1,
+            0.0,
+            0.0,
+            None,
+            None,
+            None,
+            ImageryProvider::default(),
+            Some(60_000),
+            now,
+        );
 
         // Not timed out immediately
         assert!(!round.is_timed_out(now));
@@ -322,7 +512,8 @@ mod tests {
     #[test]
     fn test_round_no_timeout_when_unlimited() {
         let now = Utc::now();
-        let round = RoundState::new(1, 0.0, 0.0, None, None, None, None, now);
+        let round =
+            RoundState::new(1, 0.0, 0.0, None, None, None, ImageryProvider::default(), None, now);
 
         // Never times out
         let far_future = now + chrono::Duration::hours(24);
@@ -332,7 +523,17 @@ mod tests {
     #[test]
     fn test_time_remaining() {
         let now = Utc::now();
-        let round = RoundState::new(1, 0.0, 0.0, None, None, None, Some(60_000), now);
+        let round = RoundState::new(
+            1,
+            0.0,
+            0.0,
+            None,
+            None,
+            None,
+            ImageryProvider::default(),
+            Some(60_000),
+            now,
+        );
 
         // Full time at start
         assert_eq!(round.time_remaining_ms(now), Some(60_000));
@@ -349,7 +550,8 @@ mod tests {
     #[test]
     fn test_all_guessed() {
         let now = Utc::now();
-        let mut round = RoundState::new(1, 0.0, 0.0, None, None, None, None, now);
+        let mut round =
+            RoundState::new(1, 0.0, 0.0, None, None, None, ImageryProvider::default(), None, now);
 
         let player_ids = vec!["usr_1", "usr_2"];
 
@@ -366,6 +568,8 @@ mod tests {
                 distance_meters: 0.0,
                 score: 5000,
                 time_taken_ms: None,
+                reported_panorama_id: None,
+                country_code: None,
                 submitted_at: now,
             },
         );
@@ -381,12 +585,117 @@ mod tests {
                 distance_meters: 0.0,
                 score: 5000,
                 time_taken_ms: None,
+                reported_panorama_id: None,
+                country_code: None,
                 submitted_at: now,
             },
         );
         assert!(round.all_guessed(&player_ids));
     }
 
+    #[test]
+    fn test_player_finished() {
+        let now = Utc::now();
+        let mut state = GameState::new("gam_test".to_string(), test_settings());
+        state.settings.rounds = 2;
+        state.progression = RoundProgression::PerPlayer;
+        for id in ["usr_1", "usr_2"] {
+            state.players.insert(
+                id.to_string(),
+                PlayerState::new(id.to_string(), id.to_string(), None, false),
+            );
+        }
+
+        let round = |number| {
+            RoundState::new(
+                number,
+                0.0,
+                0.0,
+                None,
+                None,
+                None,
+                ImageryProvider::default(),
+                Some(60_000),
+                now,
+            )
+        };
+        state.player_rounds.insert("usr_1".to_string(), round(1));
+        state.player_rounds.insert("usr_2".to_string(), round(2));
+        assert!(!state.player_finished("usr_1", now));
+        assert!(!state.player_finished("usr_2", now));
+
+        // The last round counts as played once its time runs out
+        let later = now + chrono::Duration::seconds(61);
+        assert!(state.player_finished("usr_2", later));
+        assert!(!state.all_players_finished(later));
+
+        state.player_rounds.insert("usr_1".to_string(), round(2));
+        assert!(state.all_players_finished(later));
+    }
+
+    #[test]
+    fn test_streak_rounds_until_miss() {
+        let now = Utc::now();
+        let mut state = GameState::new("gam_test".to_string(), test_settings());
+        state.settings.streak = true;
+        state.players.insert(
+            "usr_1".to_string(),
+            PlayerState::new("usr_1".to_string(), "Player 1".to_string(), None, true),
+        );
+        assert_eq!(state.total_rounds(), 0);
+
+        state.round_number = 1;
+        let mut round =
+            RoundState::new(1, 0.0, 0.0, None, None, None, ImageryProvider::default(), None, now);
+        state.current_round = Some(round.clone());
+        assert!(!state.has_more_rounds(), "an unanswered round ends the streak");
+
+        let guess = |score| Guess {
+            user_id: "usr_1".to_string(),
+            lat: 0.0,
+            lng: 0.0,
+            distance_meters: 0.0,
+            score,
+            time_taken_ms: None,
+            reported_panorama_id: None,
+            country_code: Some("FR".to_string()),
+            submitted_at: now,
+        };
+        round.guesses.insert("usr_1".to_string(), guess(1));
+        state.current_round = Some(round.clone());
+        assert!(state.has_more_rounds());
+
+        // The limit on rounds does not apply to streaks
+        state.round_number = state.settings.rounds;
+        assert!(state.has_more_rounds());
+
+        round.guesses.insert("usr_1".to_string(), guess(0));
+        state.current_round = None;
+        state.completed_rounds.push(round);
+        assert!(!state.has_more_rounds());
+    }
+
+    #[test]
+    fn test_time_budget() {
+        let now = Utc::now();
+        let mut state = GameState::new("gam_test".to_string(), test_settings());
+        assert_eq!(state.round_time_limit_ms(now), Some(120_000));
+
+        state.settings.time_budget_seconds = 180;
+        state.time_budget_ends_at = Some(now.timestamp_millis() + 180_000);
+        state.round_number = state.settings.rounds;
+        assert_eq!(state.total_rounds(), 0);
+        assert!(state.can_start_round(now), "the limit on rounds does not apply");
+
+        let later = now + chrono::Duration::seconds(150);
+        assert_eq!(state.round_time_limit_ms(later), Some(30_000));
+
+        let expired = now + chrono::Duration::seconds(180);
+        assert!(state.time_budget_spent(expired));
+        assert!(!state.can_start_round(expired));
+        assert_eq!(state.round_time_limit_ms(expired), Some(0));
+    }
+
     #[test]
     fn test_connected_player_ids() {
         let mut state = GameState::new("gam_test".to_string(), test_settings());
@@ -0,0 +1,502 @@
+//! ISO 3166-1 country metadata.
+//!
+//! Country codes arrive as raw strings from clients, map rules, imports and
+//! location data. [`CountryCode::parse`] checks them against the embedded
+//! table and normalizes them to upper case; [`lookup`] returns the metadata
+//! (English name, continent, calling code, bounding box) for a code.
+
+use serde::{Deserialize, Serialize};
+
+/// A continent, as used to group countries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Continent {
+    Africa,
+    Asia,
+    Europe,
+    NorthAmerica,
+    Oceania,
+    SouthAmerica,
+}
+
+impl Continent {
+    /// All continents, in display order.
+    pub const ALL: [Continent; 6] = [
+        Continent::Africa,
+        Continent::Asia,
+        Continent::Europe,
+        Continent::NorthAmerica,
+        Continent::Oceania,
+        Continent::SouthAmerica,
+    ];
+
+    /// Stable identifier, as serialized.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Continent::Africa => "africa",
+            Continent::Asia => "asia",
+            Continent::Europe => "europe",
+            Continent::NorthAmerica => "north_america",
+            Continent::Oceania => "oceania",
+            Continent::SouthAmerica => "south_america",
+        }
+    }
+
+    /// English display name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Continent::Africa => "Africa",
+            Continent::Asia => "Asia",
+            Continent::Europe => "Europe",
+            Continent::NorthAmerica => "North America",
+            Continent::Oceania => "Oceania",
+            Continent::SouthAmerica => "South America",
+        }
+    }
+}
+
+/// A latitude/longitude box around a country's territory.
+///
+/// Boxes of countries spanning the antimeridian (Russia, Fiji, ...) have
+/// `min_lng > max_lng`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BoundingBox {
+    pub min_lat: f64,
+    pub min_lng: f64,
+    pub max_lat: f64,
+    pub max_lng: f64,
+}
+
+impl BoundingBox {
+    /// Whether the box wraps around from 180° to -180° longitude.
+    pub fn crosses_antimeridian(&self) -> bool {
+        self.min_lng > self.max_lng
+    }
+
+    /// Whether a point lies inside the box.
+    pub fn contains(&self, lat: f64, lng: f64) -> bool {
+        let lng_inside = if self.crosses_antimeridian() {
+            lng >= self.min_lng || lng <= self.max_lng
+        } else {
+            (self.min_lng..=self.max_lng).contains(&lng)
+        };
+        (self.min_lat..=self.max_lat).contains(&lat) && lng_inside
+    }
+}
+
+/// Metadata for one country or territory.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Country {
+    /// ISO 3166-1 alpha-2 code
+    pub code: &'static str,
+    /// English short name
+    pub name: &'static str,
+    pub continent: Continent,
+    /// International calling code, e.g. `+46` or `+1-876`
+    pub calling_code: &'static str,
+    pub bounds: BoundingBox,
+}
+
+impl Country {
+    /// Flag emoji, built from the code's regional indicator symbols.
+    pub fn flag(&self) -> String {
+        self.code
+            .chars()
+            .filter_map(|c| char::from_u32(0x1F1E6 + (c as u32).checked_sub('A' as u32)?))
+            .collect()
+    }
+}
+
+/// Reasons a country code is rejected.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CountryError {
+    #[error("Invalid country code '{0}'")]
+    Unknown(String),
+}
+
+/// A validated ISO 3166-1 alpha-2 country code, in upper case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct CountryCode(&'static str);
+
+impl CountryCode {
+    /// Parse a code case-insensitively, rejecting codes not in the table.
+    pub fn parse(code: &str) -> Result<Self, CountryError> {
+        lookup(code)
+            .map(|country| Self(country.code))
+            .ok_or_else(|| CountryError::Unknown(code.to_string()))
+    }
+
+    pub fn as_str(self) -> &'static str {
+        self.0
+    }
+
+    /// Metadata for this country.
+    pub fn country(self) -> &'static Country {
+        lookup(self.0).expect("country codes are only built from the table")
+    }
+}
+
+impl std::fmt::Display for CountryCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl TryFrom<String> for CountryCode {
+    type Error = CountryError;
+
+    fn try_from(code: String) -> Result<Self, Self::Error> {
+        Self::parse(&code)
+    }
+}
+
+impl From<CountryCode> for String {
+    fn from(code: CountryCode) -> Self {
+        code.0.to_string()
+    }
+}
+
+/// Every known country, sorted by code.
+pub fn all() -> &'static [Country] {
+    COUNTRIES
+}
+
+/// Metadata for a country code, matched case-insensitively.
+pub fn lookup(code: &str) -> Option<&'static Country> {
+    let code = code.trim();
+    if code.len() != 2 {
+        return None;
+    }
+    let code = code.to_ascii_uppercase();
+    COUNTRIES
+        .binary_search_by(|country| country.code.cmp(code.as_str()))
+        .ok()
+        .map(|i| &COUNTRIES[i])
+}
+
+/// Continent of a country code, if known.
+pub fn continent_of(code: &str) -> Option<Continent> {
+    lookup(code).map(|country| country.continent)
+}
+
+const fn country(
+    code: &'static str,
+    name: &'static str,
+    continent: Continent,
+    calling_code: &'static str,
+    [min_lat, min_lng, max_lat, max_lng]: [f64; 4],
+) -> Country {
+    Country {
+        code,
+        name,
+        continent,
+        calling_code,
+        bounds: BoundingBox { min_lat, min_lng, max_lat, max_lng },
+    }
+}
+
+use Continent::*;
+
+/// `country(code, name, continent, calling code, [min_lat, min_lng, max_lat,
+/// max_lng])`, sorted by code. Boxes are rounded outwards to 0.01°.
+#[rustfmt::skip]
+const COUNTRIES: &[Country] = &[
+    country("AD", "Andorra", Europe, "+376", [42.43, 1.41, 42.66, 1.79]),
+    country("AE", "United Arab Emirates", Asia, "+971", [22.63, 51.58, 26.08, 56.38]),
+    country("AF", "Afghanistan", Asia, "+93", [29.38, 60.48, 38.49, 74.89]),
+    country("AG", "Antigua and Barbuda", NorthAmerica, "+1-268", [16.99, -61.91, 17.73, -61.67]),
+    country("AL", "Albania", Europe, "+355", [39.64, 19.26, 42.66, 21.06]),
+    country("AM", "Armenia", Asia, "+374", [38.84, 43.45, 41.30, 46.63]),
+    country("AO", "Angola", Africa, "+244", [-18.04, 11.64, -4.39, 24.08]),
+    country("AR", "Argentina", SouthAmerica, "+54", [-55.06, -73.58, -21.78, -53.64]),
+    country("AS", "American Samoa", Oceania, "+1-684", [-14.60, -171.09, -11.05, -168.14]),
+    country("AT", "Austria", Europe, "+43", [46.37, 9.53, 49.02, 17.16]),
+    country("AU", "Australia", Oceania, "+61", [-43.64, 113.34, -10.67, 153.64]),
+    country("AW", "Aruba", NorthAmerica, "+297", [12.41, -70.06, 12.63, -69.87]),
+    country("AX", "Åland Islands", Europe, "+358", [59.74, 19.47, 60.49, 21.10]),
+    country("AZ", "Azerbaijan", Asia, "+994", [38.39, 44.77, 41.91, 50.63]),
+    country("BA", "Bosnia and Herzegovina", Europe, "+387", [42.56, 15.72, 45.28, 19.62]),
+    country("BB", "Barbados", NorthAmerica, "+1-246", [13.04, -59.65, 13.34, -59.42]),
+    country("BD", "Bangladesh", Asia, "+880", [20.74, 88.01, 26.63, 92.67]),
+    country("BE", "Belgium", Europe, "+32", [49.50, 2.54, 51.50, 6.41]),
+    country("BF", "Burkina Faso", Africa, "+226", [9.40, -5.52, 15.08, 2.41]),
+    country("BG", "Bulgaria", Europe, "+359", [41.23, 22.36, 44.22, 28.61]),
+    country("BH", "Bahrain", Asia, "+973", [25.79, 50.38, 26.29, 50.66]),
+    country("BI", "Burundi", Africa, "+257", [-4.47, 29.00, -2.31, 30.85]),
+    country("BJ", "Benin", Africa, "+229", [6.23, 0.77, 12.41, 3.85]),
+    country("BM", "Bermuda", NorthAmerica, "+1-441", [32.25, -64.89, 32.39, -64.64]),
+    country("BN", "Brunei", Asia, "+673", [4.00, 114.08, 5.05, 115.36]),
+    country("BO", "Bolivia", SouthAmerica, "+591", [-22.90, -69.64, -9.68, -57.45]),
+    country("BR", "Brazil", SouthAmerica, "+55", [-33.75, -73.99, 5.27, -34.79]),
+    country("BS", "Bahamas", NorthAmerica, "+1-242", [20.91, -79.60, 27.26, -72.71]),
+    country("BT", "Bhutan", Asia, "+975", [26.70, 88.75, 28.32, 92.13]),
+    country("BW", "Botswana", Africa, "+267", [-26.91, 19.99, -17.78, 29.38]),
+    country("BY", "Belarus", Europe, "+375", [51.26, 23.18, 56.17, 32.78]),
+    country("BZ", "Belize", NorthAmerica, "+501", [15.89, -89.23, 18.50, -87.78]),
+    country("CA", "Canada", NorthAmerica, "+1", [41.68, -141.00, 83.11, -52.62]),
+    country("CD", "DR Congo", Africa, "+243", [-13.46, 12.20, 5.39, 31.31]),
+    country("CF", "Central African Republic", Africa, "+236", [2.22, 14.42, 11.01, 27.46]),
+    country("CG", "Republic of the Congo", Africa, "+242", [-5.03, 11.09, 3.70, 18.65]),
+    country("CH", "Switzerland", Europe, "+41", [45.82, 5.96, 47.81, 10.49]),
+    country("CI", "Côte d'Ivoire", Africa, "+225", [4.36, -8.60, 10.74, -2.49]),
+    country("CK", "Cook Islands", Oceania, "+682", [-21.96, -165.85, -8.95, -157.31]),
+    country("CL", "Chile", SouthAmerica, "+56", [-55.98, -109.45, -17.50, -66.42]),
+    country("CM", "Cameroon", Africa, "+237", [1.65, 8.49, 13.08, 16.19]),
+    country("CN", "China", Asia, "+86", [18.16, 73.50, 53.56, 134.77]),
+    country("CO", "Colombia", SouthAmerica, "+57", [-4.23, -81.73, 13.39, -66.85]),
+    country("CR", "Costa Rica", NorthAmerica, "+506", [8.03, -85.95, 11.22, -82.55]),
+    country("CU", "Cuba", NorthAmerica, "+53", [19.83, -84.95, 23.23, -74.13]),
+    country("CV", "Cape Verde", Africa, "+238", [14.80, -25.36, 17.21, -22.66]),
+    country("CW", "Curaçao", NorthAmerica, "+599", [12.03, -69.16, 12.39, -68.74]),
+    country("CY", "Cyprus", Europe, "+357", [34.57, 32.27, 35.70, 34.60]),
+    country("CZ", "Czechia", Europe, "+420", [48.55, 12.09, 51.06, 18.86]),
+    country("DE", "Germany", Europe, "+49", [47.27, 5.87, 55.06, 15.04]),
+    country("DJ", "Djibouti", Africa, "+253", [10.93, 41.77, 12.71, 43.42]),
+    country("DK", "Denmark", Europe, "+45", [54.56, 8.08, 57.75, 15.20]),
+    country("DM", "Dominica", NorthAmerica, "+1-767", [15.20, -61.48, 15.64, -61.24]),
+    country("DO", "Dominican Republic", NorthAmerica, "+1-809", [17.47, -72.01, 19.93, -68.32]),
+    country("DZ", "Algeria", Africa, "+213", [18.96, -8.67, 37.09, 11.98]),
+    country("EC", "Ecuador", SouthAmerica, "+593", [-5.01, -92.01, 1.68, -75.19]),
+    country("EE", "Estonia", Europe, "+372", [57.51, 21.76, 59.68, 28.21]),
+    country("EG", "Egypt", Africa, "+20", [21.99, 24.70, 31.67, 36.90]),
+    country("ER", "Eritrea", Africa, "+291", [12.36, 36.44, 18.00, 43.14]),
+    country("ES", "Spain", Europe, "+34", [27.64, -18.16, 43.79, 4.33]),
+    country("ET", "Ethiopia", Africa, "+251", [3.40, 32.99, 14.89, 47.99]),
+    country("FI", "Finland", Europe, "+358", [59.81, 20.55, 70.09, 31.59]),
+    country("FJ", "Fiji", Oceania, "+679", [-20.68, 177.00, -12.48, -178.23]),
+    country("FK", "Falkland Islands", SouthAmerica, "+500", [-52.90, -61.35, -51.02, -57.71]),
+    country("FM", "Micronesia", Oceania, "+691", [0.83, 137.33, 10.09, 163.04]),
+    country("FO", "Faroe Islands", Europe, "+298", [61.39, -7.69, 62.40, -6.26]),
+    country("FR", "France", Europe, "+33", [41.33, -5.14, 51.09, 9.56]),
+    country("GA", "Gabon", Africa, "+241", [-3.98, 8.70, 2.32, 14.50]),
+    country("GB", "United Kingdom", Europe, "+44", [49.86, -8.65, 60.86, 1.77]),
+    country("GD", "Grenada", NorthAmerica, "+1-473", [11.98, -61.80, 12.53, -61.38]),
+    country("GE", "Georgia", Asia, "+995", [41.05, 40.01, 43.59, 46.74]),
+    country("GF", "French Guiana", SouthAmerica, "+594", [2.11, -54.60, 5.78, -51.61]),
+    country("GG", "Guernsey", Europe, "+44", [49.40, -2.68, 49.73, -2.16]),
+    country("GH", "Ghana", Africa, "+233", [4.74, -3.26, 11.17, 1.19]),
+    country("GI", "Gibraltar", Europe, "+350", [36.11, -5.36, 36.16, -5.34]),
+    country("GL", "Greenland", NorthAmerica, "+299", [59.78, -73.04, 83.63, -11.31]),
+    country("GM", "Gambia", Africa, "+220", [13.06, -16.83, 13.83, -13.80]),
+    country("GN", "Guinea", Africa, "+224", [7.19, -15.08, 12.68, -7.64]),
+    country("GP", "Guadeloupe", NorthAmerica, "+590", [15.83, -61.81, 16.52, -61.00]),
+    country("GQ", "Equatorial Guinea", Africa, "+240", [-1.47, 5.60, 3.79, 11.34]),
+    country("GR", "Greece", Europe, "+30", [34.80, 19.37, 41.75, 29.65]),
+    country("GT", "Guatemala", NorthAmerica, "+502", [13.74, -92.25, 17.82, -88.22]),
+    country("GU", "Guam", Oceania, "+1-671", [13.24, 144.62, 13.65, 144.96]),
+    country("GW", "Guinea-Bissau", Africa, "+245", [10.86, -16.71, 12.68, -13.64]),
+    country("GY", "Guyana", SouthAmerica, "+592", [1.17, -61.41, 8.56, -56.48]),
+    country("HK", "Hong Kong", Asia, "+852", [22.15, 113.83, 22.56, 114.43]),
+    country("HN", "Honduras", NorthAmerica, "+504", [12.98, -89.36, 17.45, -83.13]),
+    country("HR", "Croatia", Europe, "+385", [42.39, 13.49, 46.55, 19.45]),
+    country("HT", "Haiti", NorthAmerica, "+509", [18.02, -74.48, 20.09, -71.62]),
+    country("HU", "Hungary", Europe, "+36", [45.74, 16.11, 48.59, 22.90]),
+    country("ID", "Indonesia", Asia, "+62", [-11.01, 95.01, 6.08, 141.02]),
+    country("IE", "Ireland", Europe, "+353", [51.42, -10.48, 55.39, -5.99]),
+    country("IL", "Israel", Asia, "+972", [29.49, 34.27, 33.33, 35.90]),
+    country("IM", "Isle of Man", Europe, "+44", [54.04, -4.80, 54.42, -4.31]),
+    country("IN", "India", Asia, "+91", [6.75, 68.18, 35.67, 97.40]),
+    country("IQ", "Iraq", Asia, "+964", [29.06, 38.79, 37.38, 48.57]),
+    country("IR", "Iran", Asia, "+98", [25.06, 44.03, 39.78, 63.33]),
+    country("IS", "Iceland", Europe, "+354", [63.30, -24.55, 66.57, -13.50]),
+    country("IT", "Italy", Europe, "+39", [35.49, 6.63, 47.09, 18.52]),
+    country("JE", "Jersey", Europe, "+44", [49.16, -2.26, 49.27, -2.01]),
+    country("JM", "Jamaica", NorthAmerica, "+1-876", [17.70, -78.37, 18.53, -76.18]),
+    country("JO", "Jordan", Asia, "+962", [29.19, 34.96, 33.37, 39.30]),
+    country("JP", "Japan", Asia, "+81", [24.04, 122.93, 45.55, 145.82]),
+    country("KE", "Kenya", Africa, "+254", [-4.68, 33.91, 5.03, 41.91]),
+    country("KG", "Kyrgyzstan", Asia, "+996", [39.17, 69.25, 43.27, 80.28]),
+    country("KH", "Cambodia", Asia, "+855", [10.41, 102.33, 14.69, 107.63]),
+    country("KI", "Kiribati", Oceania, "+686", [-11.44, 169.51, 4.72, -150.21]),
+    country("KM", "Comoros", Africa, "+269", [-12.42, 43.22, -11.36, 44.54]),
+    country("KN", "Saint Kitts and Nevis", NorthAmerica, "+1-869", [17.09, -62.86, 17.42, -62.53]),
+    country("KP", "North Korea", Asia, "+850", [37.67, 124.21, 43.01, 130.70]),
+    country("KR", "South Korea", Asia, "+82", [33.11, 124.61, 38.61, 131.87]),
+    country("KW", "Kuwait", Asia, "+965", [28.52, 46.55, 30.10, 48.43]),
+    country("KY", "Cayman Islands", NorthAmerica, "+1-345", [19.26, -81.43, 19.76, -79.72]),
+    country("KZ", "Kazakhstan", Asia, "+7", [40.57, 46.49, 55.44, 87.32]),
+    country("LA", "Laos", Asia, "+856", [13.91, 100.08, 22.51, 107.64]),
+    country("LB", "Lebanon", Asia, "+961", [33.05, 35.10, 34.69, 36.62]),
+    country("LC", "Saint Lucia", NorthAmerica, "+1-758", [13.71, -61.08, 14.11, -60.87]),
+    country("LI", "Liechtenstein", Europe, "+423", [47.05, 9.47, 47.27, 9.64]),
+    country("LK", "Sri Lanka", Asia, "+94", [5.92, 79.52, 9.84, 81.88]),
+    country("LR", "Liberia", Africa, "+231", [4.35, -11.49, 8.55, -7.37]),
+    country("LS", "Lesotho", Africa, "+266", [-30.68, 27.01, -28.57, 29.46]),
+    country("LT", "Lithuania", Europe, "+370", [53.90, 20.95, 56.45, 26.84]),
+    country("LU", "Luxembourg", Europe, "+352", [49.45, 5.73, 50.18, 6.53]),
+    country("LV", "Latvia", Europe, "+371", [55.67, 20.97, 58.08, 28.24]),
+    country("LY", "Libya", Africa, "+218", [19.50, 9.39, 33.17, 25.15]),
+    country("MA", "Morocco", Africa, "+212", [27.66, -13.17, 35.92, -1.01]),
+    country("MC", "Monaco", Europe, "+377", [43.72, 7.41, 43.75, 7.44]),
+    country("MD", "Moldova", Europe, "+373", [45.47, 26.62, 48.49, 30.13]),
+    country("ME", "Montenegro", Europe, "+382", [41.85, 18.43, 43.56, 20.36]),
+    country("MG", "Madagascar", Africa, "+261", [-25.61, 43.22, -11.95, 50.48]),
+    country("MH", "Marshall Islands", Oceania, "+692", [4.57, 160.80, 14.62, 172.17]),
+    country("MK", "North Macedonia", Europe, "+389", [40.85, 20.45, 42.37, 23.03]),
+    country("ML", "Mali", Africa, "+223", [10.16, -12.24, 25.00, 4.24]),
+    country("MM", "Myanmar", Asia, "+95", [9.78, 92.17, 28.55, 101.17]),
+    country("MN", "Mongolia", Asia, "+976", [41.58, 87.75, 52.15, 119.93]),
+    country("MO", "Macao", Asia, "+853", [22.11, 113.53, 22.22, 113.60]),
+    country("MP", "Northern Mariana Islands", Oceania, "+1-670", [14.11, 145.12, 20.55, 145.87]),
+    country("MQ", "Martinique", NorthAmerica, "+596", [14.39, -61.23, 14.88, -60.81]),
+    country("MR", "Mauritania", Africa, "+222", [14.72, -17.07, 27.30, -4.83]),
+    country("MT", "Malta", Europe, "+356", [35.79, 14.18, 36.08, 14.58]),
+    country("MU", "Mauritius", Africa, "+230", [-20.53, 56.51, -10.33, 63.50]),
+    country("MV", "Maldives", Asia, "+960", [-0.69, 72.64, 7.11, 73.76]),
+    country("MW", "Malawi", Africa, "+265", [-17.13, 32.67, -9.37, 35.92]),
+    country("MX", "Mexico", NorthAmerica, "+52", [14.53, -118.40, 32.72, -86.71]),
+    country("MY", "Malaysia", Asia, "+60", [0.85, 99.64, 7.36, 119.27]),
+    country("MZ", "Mozambique", Africa, "+258", [-26.87, 30.22, -10.47, 40.84]),
+    country("NA", "Namibia", Africa, "+264", [-28.97, 11.72, -16.96, 25.26]),
+    country("NC", "New Caledonia", Oceania, "+687", [-22.70, 163.56, -19.55, 168.14]),
+    country("NE", "Niger", Africa, "+227", [11.69, 0.17, 23.52, 16.00]),
+    country("NG", "Nigeria", Africa, "+234", [4.27, 2.67, 13.89, 14.68]),
+    country("NI", "Nicaragua", NorthAmerica, "+505", [10.71, -87.69, 15.03, -82.59]),
+    country("NL", "Netherlands", Europe, "+31", [50.75, 3.36, 53.55, 7.23]),
+    country("NO", "Norway", Europe, "+47", [57.96, 4.65, 71.19, 31.10]),
+    country("NP", "Nepal", Asia, "+977", [26.35, 80.06, 30.45, 88.20]),
+    country("NR", "Nauru", Oceania, "+674", [-0.56, 166.90, -0.50, 166.96]),
+    country("NZ", "New Zealand", Oceania, "+64", [-47.29, 166.43, -34.39, -176.17]),
+    country("OM", "Oman", Asia, "+968", [16.65, 52.00, 26.40, 59.84]),
+    country("PA", "Panama", NorthAmerica, "+507", [7.20, -83.05, 9.65, -77.16]),
+    country("PE", "Peru", SouthAmerica, "+51", [-18.35, -81.33, -0.04, -68.65]),
+    country("PF", "French Polynesia", Oceania, "+689", [-27.65, -154.73, -7.90, -134.93]),
+    country("PG", "Papua New Guinea", Oceania, "+675", [-11.66, 140.84, -1.32, 155.97]),
+    country("PH", "Philippines", Asia, "+63", [4.59, 116.93, 21.12, 126.60]),
+    country("PK", "Pakistan", Asia, "+92", [23.69, 60.87, 37.10, 77.84]),
+    country("PL", "Poland", Europe, "+48", [49.00, 14.12, 54.84, 24.15]),
+    country("PM", "Saint Pierre and Miquelon", NorthAmerica, "+508", [46.75, -56.41, 47.14, -56.12]),
+    country("PN", "Pitcairn Islands", Oceania, "+64", [-25.08, -130.75, -23.92, -124.77]),
+    country("PR", "Puerto Rico", NorthAmerica, "+1-787", [17.88, -67.95, 18.52, -65.22]),
+    country("PS", "Palestine", Asia, "+970", [31.22, 34.22, 32.55, 35.57]),
+    country("PT", "Portugal", Europe, "+351", [32.40, -31.28, 42.15, -6.19]),
+    country("PW", "Palau", Oceania, "+680", [2.95, 131.12, 8.10, 134.73]),
+    country("PY", "Paraguay", SouthAmerica, "+595", [-27.61, -62.65, -19.29, -54.26]),
+    country("QA", "Qatar", Asia, "+974", [24.47, 50.75, 26.18, 51.64]),
+    country("RE", "Réunion", Africa, "+262", [-21.39, 55.22, -20.87, 55.84]),
+    country("RO", "Romania", Europe, "+40", [43.62, 20.26, 48.27, 29.69]),
+    country("RS", "Serbia", Europe, "+381", [42.23, 18.82, 46.19, 23.01]),
+    country("RU", "Russia", Europe, "+7", [41.19, 19.64, 81.86, -169.05]),
+    country("RW", "Rwanda", Africa, "+250", [-2.84, 28.86, -1.05, 30.90]),
+    country("SA", "Saudi Arabia", Asia, "+966", [16.38, 34.50, 32.16, 55.67]),
+    country("SB", "Solomon Islands", Oceania, "+677", [-11.86, 155.51, -5.00, 167.28]),
+    country("SC", "Seychelles", Africa, "+248", [-10.22, 46.20, -3.71, 56.30]),
+    country("SD", "Sudan", Africa, "+249", [8.68, 21.81, 22.23, 38.58]),
+    country("SE", "Sweden", Europe, "+46", [55.34, 11.11, 69.06, 24.17]),
+    country("SG", "Singapore", Asia, "+65", [1.16, 103.60, 1.47, 104.09]),
+    country("SI", "Slovenia", Europe, "+386", [45.42, 13.38, 46.88, 16.61]),
+    country("SJ", "Svalbard and Jan Mayen", Europe, "+47", [74.34, 10.49, 80.83, 33.64]),
+    country("SK", "Slovakia", Europe, "+421", [47.73, 16.83, 49.61, 22.57]),
+    country("SL", "Sierra Leone", Africa, "+232", [6.92, -13.30, 10.00, -10.27]),
+    country("SM", "San Marino", Europe, "+378", [43.89, 12.40, 43.99, 12.52]),
+    country("SN", "Senegal", Africa, "+221", [12.31, -17.54, 16.69, -11.35]),
+    country("SO", "Somalia", Africa, "+252", [-1.68, 40.99, 11.99, 51.41]),
+    country("SR", "Suriname", SouthAmerica, "+597", [1.84, -58.07, 6.01, -53.98]),
+    country("SS", "South Sudan", Africa, "+211", [3.49, 23.44, 12.24, 35.95]),
+    country("ST", "São Tomé and Príncipe", Africa, "+239", [0.02, 6.46, 1.70, 7.47]),
+    country("SV", "El Salvador", NorthAmerica, "+503", [13.15, -90.13, 14.45, -87.68]),
+    country("SY", "Syria", Asia, "+963", [32.31, 35.73, 37.32, 42.38]),
+    country("SZ", "Eswatini", Africa, "+268", [-27.32, 30.79, -25.72, 32.14]),
+    country("TC", "Turks and Caicos Islands", NorthAmerica, "+1-649", [21.19, -72.48, 21.96, -71.08]),
+    country("TD", "Chad", Africa, "+235", [7.44, 13.47, 23.45, 24.00]),
+    country("TG", "Togo", Africa, "+228", [6.10, -0.15, 11.14, 1.81]),
+    country("TH", "Thailand", Asia, "+66", [5.61, 97.34, 20.46, 105.64]),
+    country("TJ", "Tajikistan", Asia, "+992", [36.67, 67.34, 41.04, 75.15]),
+    country("TL", "Timor-Leste", Asia, "+670", [-9.50, 124.04, -8.13, 127.34]),
+    country("TM", "Turkmenistan", Asia, "+993", [35.13, 52.44, 42.80, 66.71]),
+    country("TN", "Tunisia", Africa, "+216", [30.23, 7.52, 37.35, 11.60]),
+    country("TO", "Tonga", Oceania, "+676", [-21.46, -175.68, -15.56, -173.70]),
+    country("TR", "Türkiye", Asia, "+90", [35.81, 25.66, 42.11, 44.82]),
+    country("TT", "Trinidad and Tobago", NorthAmerica, "+1-868", [10.04, -61.93, 11.36, -60.49]),
+    country("TV", "Tuvalu", Oceania, "+688", [-10.80, 176.06, -5.64, 179.87]),
+    country("TW", "Taiwan", Asia, "+886", [21.90, 119.31, 25.30, 122.01]),
+    country("TZ", "Tanzania", Africa, "+255", [-11.75, 29.33, -0.98, 40.44]),
+    country("UA", "Ukraine", Europe, "+380", [44.38, 22.14, 52.38, 40.23]),
+    country("UG", "Uganda", Africa, "+256", [-1.48, 29.57, 4.23, 35.04]),
+    country("US", "United States", NorthAmerica, "+1", [18.91, 172.45, 71.39, -66.95]),
+    country("UY", "Uruguay", SouthAmerica, "+598", [-34.97, -58.44, -30.09, -53.07]),
+    country("UZ", "Uzbekistan", Asia, "+998", [37.18, 55.99, 45.59, 73.13]),
+    country("VA", "Vatican City", Europe, "+379", [41.90, 12.45, 41.91, 12.46]),
+    country("VC", "Saint Vincent and the Grenadines", NorthAmerica, "+1-784", [12.58, -61.46, 13.38, -61.11]),
+    country("VE", "Venezuela", SouthAmerica, "+58", [0.65, -73.38, 12.20, -59.80]),
+    country("VG", "British Virgin Islands", NorthAmerica, "+1-284", [18.31, -64.85, 18.75, -64.27]),
+    country("VI", "U.S. Virgin Islands", NorthAmerica, "+1-340", [17.68, -65.09, 18.40, -64.56]),
+    country("VN", "Vietnam", Asia, "+84", [8.56, 102.14, 23.39, 109.46]),
+    country("VU", "Vanuatu", Oceania, "+678", [-20.25, 166.52, -13.07, 170.24]),
+    country("WS", "Samoa", Oceania, "+685", [-14.08, -172.80, -13.43, -171.40]),
+    country("XK", "Kosovo", Europe, "+383", [41.86, 20.01, 43.27, 21.79]),
+    country("YE", "Yemen", Asia, "+967", [12.11, 42.55, 19.00, 54.53]),
+    country("YT", "Mayotte", Africa, "+262", [-13.00, 45.02, -12.64, 45.30]),
+    country("ZA", "South Africa", Africa, "+27", [-46.97, 16.46, -22.13, 32.89]),
+    country("ZM", "Zambia", Africa, "+260", [-18.08, 21.99, -8.20, 33.71]),
+    country("ZW", "Zimbabwe", Africa, "+263", [-22.42, 25.24, -15.61, 33.06]),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_is_sorted_and_well_formed() {
+        for pair in COUNTRIES.windows(2) {
+            assert!(
+                pair[0].code < pair[1].code,
+                "{} must come before {}",
+                pair[0].code,
+                pair[1].code
+            );
+        }
+        for country in COUNTRIES {
+            let b = country.bounds;
+            assert!(
+                country.code.len() == 2 && country.code.chars().all(|c| c.is_ascii_uppercase())
+            );
+            assert!(country.calling_code.starts_with('+'), "{} calling code", country.code);
+            assert!(b.min_lat < b.max_lat, "{} latitude range", country.code);
+            assert!((-90.0..=90.0).contains(&b.min_lat) && (-90.0..=90.0).contains(&b.max_lat));
+            assert!((-180.0..=180.0).contains(&b.min_lng) && (-180.0..=180.0).contains(&b.max_lng));
+        }
+    }
+
+    #[test]
+    fn test_every_weighted_country_has_metadata() {
+        for code in crate::location::country_codes() {
+            assert!(lookup(code).is_some(), "{code} has no metadata");
+        }
+    }
+
+    #[test]
+    fn test_parse_country_code() {
+        assert_eq!(CountryCode::parse("se").unwrap().as_str(), "SE");
+        assert_eq!(CountryCode::parse(" FR ").unwrap().country().name, "France");
+        assert!(CountryCode::parse("XX").is_err());
+        assert!(CountryCode::parse("FRA").is_err());
+        assert!(CountryCode::parse("").is_err());
+
+        let code: CountryCode = serde_json::from_str("\"jp\"").unwrap();
+        assert_eq!(serde_json::to_string(&code).unwrap(), "\"JP\"");
+        assert!(serde_json::from_str::<CountryCode>("\"ZZ\"").is_err());
+    }
+
+    #[test]
+    fn test_metadata() {
+        let sweden = lookup("SE").unwrap();
+        assert_eq!(sweden.continent, Continent::Europe);
+        assert_eq!(sweden.calling_code, "+46");
+        assert_eq!(sweden.flag(), "🇸🇪");
+        assert!(sweden.bounds.contains(59.33, 18.07));
+        assert!(!sweden.bounds.contains(48.86, 2.35));
+        assert_eq!(continent_of("br"), Some(Continent::SouthAmerica));
+        assert_eq!(continent_of("ZZ"), None);
+    }
+
+    #[test]
+    fn test_antimeridian_bounds() {
+        let fiji = lookup("FJ").unwrap().bounds;
+        assert!(fiji.crosses_antimeridian());
+        assert!(fiji.contains(-17.7, 178.4));
+        assert!(fiji.contains(-16.8, -179.9));
+        assert!(!fiji.contains(-17.7, 0.0));
+    }
+}
@@ -18,6 +18,19 @@ pub fn haversine_distance(lat1: f64, lng1: f64, lat2: f64, lng2: f64) -> f64 {
     EARTH_RADIUS_METERS * c
 }
 
+/// Initial compass bearing from the first point towards the second along the
+/// great circle, in degrees clockwise from north (0-360).
+pub fn initial_bearing(lat1: f64, lng1: f64, lat2: f64, lng2: f64) -> f64 {
+    let lat1_rad = lat1.to_radians();
+    let lat2_rad = lat2.to_radians();
+    let delta_lng = (lng2 - lng1).to_radians();
+
+    let y = delta_lng.sin() * lat2_rad.cos();
+    let x = lat1_rad.cos() * lat2_rad.sin() - lat1_rad.sin() * lat2_rad.cos() * delta_lng.cos();
+
+    y.atan2(x).to_degrees().rem_euclid(360.0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -41,4 +54,14 @@ mod tests {
         let dist = haversine_distance(40.7128, -74.0060, 51.5074, -0.1278);
         assert!((dist - 5_570_000.0).abs() < 50_000.0);
     }
+
+    #[test]
+    fn test_initial_bearing() {
+        // Due north and due east along the equator
+        assert!((initial_bearing(0.0, 0.0, 10.0, 0.0) - 0.0).abs() < 0.001);
+        assert!((initial_bearing(0.0, 0.0, 0.0, 10.0) - 90.0).abs() < 0.001);
+        // London to New York heads west-northwest
+        let bearing = initial_bearing(51.5074, -0.1278, 40.7128, -74.0060);
+        assert!((bearing - 288.3).abs() < 1.0, "got {bearing}");
+    }
 }
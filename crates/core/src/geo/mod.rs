@@ -1,5 +1,7 @@
 //! Geographic calculations
 
+pub mod countries;
 pub mod distance;
+pub mod quiz;
 
 pub use distance::*;
@@ -0,0 +1,122 @@
+//! Country quiz questions for the lobby warm-up.
+//!
+//! Each question shows a prompt drawn from the [`countries`](super::countries)
+//! table (the country's flag or its bounding box) and asks the player to
+//! pick the country from a few choices. Wrong choices come from the same
+//! continent where possible so the question isn't trivial.
+
+use rand::Rng;
+use rand::prelude::{IndexedRandom, SliceRandom};
+use serde::{Deserialize, Serialize};
+
+use super::countries::{self, Country};
+
+/// Choices offered per question, including the answer
+pub const QUIZ_CHOICES: usize = 4;
+
+/// How long players have to answer, in milliseconds
+pub const QUIZ_ANSWER_WINDOW_MS: i64 = 15_000;
+
+/// Points for a correct answer
+pub const QUIZ_BASE_POINTS: u32 = 100;
+
+/// Extra points for an instant answer, shrinking to zero at the deadline
+pub const QUIZ_SPEED_BONUS: u32 = 100;
+
+/// What a question shows the player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuizPrompt {
+    /// The country's flag emoji
+    Flag,
+    /// The country's bounding box, drawn on a map
+    Bounds,
+}
+
+impl QuizPrompt {
+    pub const ALL: [QuizPrompt; 2] = [QuizPrompt::Flag, QuizPrompt::Bounds];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            QuizPrompt::Flag => "flag",
+            QuizPrompt::Bounds => "bounds",
+        }
+    }
+}
+
+/// A generated question.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuizQuestion {
+    pub prompt: QuizPrompt,
+    pub answer: &'static Country,
+    /// Choices in display order, one of them the answer
+    pub choices: Vec<&'static Country>,
+}
+
+/// Pick a random country and prompt, with [`QUIZ_CHOICES`] shuffled choices.
+pub fn generate_question<R: Rng + ?Sized>(rng: &mut R) -> QuizQuestion {
+    let all = countries::all();
+    let answer = all.choose(rng).expect("country table is not empty");
+    let prompt = *QuizPrompt::ALL.choose(rng).expect("prompts are not empty");
+
+    let mut same_continent: Vec<&'static Country> =
+        all.iter().filter(|c| c.continent == answer.continent && c.code != answer.code).collect();
+    same_continent.shuffle(rng);
+
+    let mut choices = vec![answer];
+    choices.extend(same_continent.into_iter().take(QUIZ_CHOICES - 1));
+    if choices.len() < QUIZ_CHOICES {
+        let mut others: Vec<&'static Country> =
+            all.iter().filter(|c| c.continent != answer.continent).collect();
+        others.shuffle(rng);
+        let missing = QUIZ_CHOICES - choices.len();
+        choices.extend(others.into_iter().take(missing));
+    }
+    choices.shuffle(rng);
+
+    QuizQuestion { prompt, answer, choices }
+}
+
+/// Points for an answer given `elapsed_ms` after the question was asked.
+///
+/// Wrong answers and answers after the window score nothing.
+pub fn answer_points(correct: bool, elapsed_ms: i64) -> u32 {
+    if !correct || !(0..=QUIZ_ANSWER_WINDOW_MS).contains(&elapsed_ms) {
+        return 0;
+    }
+    let remaining = (QUIZ_ANSWER_WINDOW_MS - elapsed_ms) as f64 / QUIZ_ANSWER_WINDOW_MS as f64;
+    QUIZ_BASE_POINTS + (f64::from(QUIZ_SPEED_BONUS) * remaining).round() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    #[test]
+    fn test_generate_question() {
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        for _ in 0..200 {
+            let question = generate_question(&mut rng);
+            assert_eq!(question.choices.len(), QUIZ_CHOICES);
+            assert!(question.choices.contains(&question.answer));
+
+            let mut codes: Vec<&str> = question.choices.iter().map(|c| c.code).collect();
+            codes.sort_unstable();
+            codes.dedup();
+            assert_eq!(codes.len(), QUIZ_CHOICES, "choices must be distinct");
+        }
+    }
+
+    #[test]
+    fn test_answer_points() {
+        assert_eq!(answer_points(true, 0), QUIZ_BASE_POINTS + QUIZ_SPEED_BONUS);
+        assert_eq!(answer_points(true, QUIZ_ANSWER_WINDOW_MS / 2), 150);
+        assert_eq!(answer_points(true, QUIZ_ANSWER_WINDOW_MS), QUIZ_BASE_POINTS);
+        assert_eq!(answer_points(true, QUIZ_ANSWER_WINDOW_MS + 1), 0);
+        assert_eq!(answer_points(true, -1), 0);
+        assert_eq!(answer_points(false, 0), 0);
+    }
+}
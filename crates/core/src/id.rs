@@ -46,6 +46,16 @@ pub enum EntityPrefix {
     Map,
     Report,
     Party,
+    Email,
+    Device,
+    HealthCheck,
+    CheatSignal,
+    PushSubscription,
+    Organization,
+    SettingsPreset,
+    NameFlag,
+    PlayerReport,
+    PracticeRound,
 }
 
 impl EntityPrefix {
@@ -62,6 +72,16 @@ impl EntityPrefix {
             EntityPrefix::Map => "map_",
             EntityPrefix::Report => "rpt_",
             EntityPrefix::Party => "pty_",
+            EntityPrefix::Email => "eml_",
+            EntityPrefix::Device => "dev_",
+            EntityPrefix::HealthCheck => "lhc_",
+            EntityPrefix::CheatSignal => "chs_",
+            EntityPrefix::PushSubscription => "psb_",
+            EntityPrefix::Organization => "org_",
+            EntityPrefix::SettingsPreset => "prs_",
+            EntityPrefix::NameFlag => "nfl_",
+            EntityPrefix::PlayerReport => "prp_",
+            EntityPrefix::PracticeRound => "prc_",
         }
     }
 }
@@ -126,6 +146,66 @@ pub fn generate_party_id() -> String {
     format!("{}{}", EntityPrefix::Party.as_str(), generate_id(ENTITY_ID_LEN))
 }
 
+/// Generate a prefixed ID for a queued email entity.
+/// Format: `eml_XXXXXXXXXXXX` (16 chars total, ~71 bits entropy)
+pub fn generate_email_id() -> String {
+    format!("{}{}", EntityPrefix::Email.as_str(), generate_id(ENTITY_ID_LEN))
+}
+
+/// Generate a prefixed ID for a user device entity.
+/// Format: `dev_XXXXXXXXXXXX` (16 chars total, ~71 bits entropy)
+pub fn generate_device_id() -> String {
+    format!("{}{}", EntityPrefix::Device.as_str(), generate_id(ENTITY_ID_LEN))
+}
+
+/// Generate a prefixed ID for a location health check run.
+/// Format: `lhc_XXXXXXXXXXXX` (16 chars total, ~71 bits entropy)
+pub fn generate_health_check_id() -> String {
+    format!("{}{}", EntityPrefix::HealthCheck.as_str(), generate_id(ENTITY_ID_LEN))
+}
+
+/// Generate a prefixed ID for a recorded cheating signal.
+/// Format: `chs_XXXXXXXXXXXX` (16 chars total, ~71 bits entropy)
+pub fn generate_cheat_signal_id() -> String {
+    format!("{}{}", EntityPrefix::CheatSignal.as_str(), generate_id(ENTITY_ID_LEN))
+}
+
+/// Generate a prefixed ID for a Web Push subscription.
+/// Format: `psb_XXXXXXXXXXXX` (16 chars total, ~71 bits entropy)
+pub fn generate_push_subscription_id() -> String {
+    format!("{}{}", EntityPrefix::PushSubscription.as_str(), generate_id(ENTITY_ID_LEN))
+}
+
+/// Generate a prefixed ID for an organization.
+/// Format: `org_XXXXXXXXXXXX` (16 chars total, ~71 bits entropy)
+pub fn generate_org_id() -> String {
+    format!("{}{}", EntityPrefix::Organization.as_str(), generate_id(ENTITY_ID_LEN))
+}
+
+/// Generate a prefixed ID for a user's saved settings preset.
+/// Format: `prs_XXXXXXXXXXXX` (16 chars total, ~71 bits entropy)
+pub fn generate_preset_id() -> String {
+    format!("{}{}", EntityPrefix::SettingsPreset.as_str(), generate_id(ENTITY_ID_LEN))
+}
+
+/// Generate a prefixed ID for a flagged display name.
+/// Format: `nfl_XXXXXXXXXXXX` (16 chars total, ~71 bits entropy)
+pub fn generate_name_flag_id() -> String {
+    format!("{}{}", EntityPrefix::NameFlag.as_str(), generate_id(ENTITY_ID_LEN))
+}
+
+/// Generate a prefixed ID for a report about a player.
+/// Format: `prp_XXXXXXXXXXXX` (16 chars total, ~71 bits entropy)
+pub fn generate_player_report_id() -> String {
+    format!("{}{}", EntityPrefix::PlayerReport.as_str(), generate_id(ENTITY_ID_LEN))
+}
+
+/// Generate a prefixed ID for a practice round (kept in Redis only).
+/// Format: `prc_XXXXXXXXXXXX` (16 chars total, ~71 bits entropy)
+pub fn generate_practice_round_id() -> String {
+    format!("{}{}", EntityPrefix::PracticeRound.as_str(), generate_id(ENTITY_ID_LEN))
+}
+
 /// Parse the prefix from an ID string.
 /// Returns `None` if the ID doesn't have a recognized prefix.
 pub fn parse_prefix(id: &str) -> Option<EntityPrefix> {
@@ -149,6 +229,26 @@ pub fn parse_prefix(id: &str) -> Option<EntityPrefix> {
         Some(EntityPrefix::Report)
     } else if id.starts_with("pty_") {
         Some(EntityPrefix::Party)
+    } else if id.starts_with("eml_") {
+        Some(EntityPrefix::Email)
+    } else if id.starts_with("dev_") {
+        Some(EntityPrefix::Device)
+    } else if id.starts_with("lhc_") {
+        Some(EntityPrefix::HealthCheck)
+    } else if id.starts_with("chs_") {
+        Some(EntityPrefix::CheatSignal)
+    } else if id.starts_with("psb_") {
+        Some(EntityPrefix::PushSubscription)
+    } else if id.starts_with("org_") {
+        Some(EntityPrefix::Organization)
+    } else if id.starts_with("prs_") {
+        Some(EntityPrefix::SettingsPreset)
+    } else if id.starts_with("nfl_") {
+        Some(EntityPrefix::NameFlag)
+    } else if id.starts_with("prp_") {
+        Some(EntityPrefix::PlayerReport)
+    } else if id.starts_with("prc_") {
+        Some(EntityPrefix::PracticeRound)
     } else {
         None
     }
@@ -235,6 +335,41 @@ mod tests {
         assert_eq!(id.len(), 16);
     }
 
+    #[test]
+    fn test_email_id_format() {
+        let id = generate_email_id();
+        assert!(id.starts_with("eml_"));
+        assert_eq!(id.len(), 16);
+    }
+
+    #[test]
+    fn test_device_id_format() {
+        let id = generate_device_id();
+        assert!(id.starts_with("dev_"));
+        assert_eq!(id.len(), 16);
+    }
+
+    #[test]
+    fn test_health_check_id_format() {
+        let id = generate_health_check_id();
+        assert!(id.starts_with("lhc_"));
+        assert_eq!(id.len(), 16);
+    }
+
+    #[test]
+    fn test_cheat_signal_id_format() {
+        let id = generate_cheat_signal_id();
+        assert!(id.starts_with("chs_"));
+        assert_eq!(id.len(), 16);
+    }
+
+    #[test]
+    fn test_push_subscription_id_format() {
+        let id = generate_push_subscription_id();
+        assert!(id.starts_with("psb_"));
+        assert_eq!(id.len(), 16);
+    }
+
     #[test]
     fn test_parse_prefix() {
         assert_eq!(parse_prefix("usr_abcdefghijkl"), Some(EntityPrefix::User));
@@ -247,6 +382,16 @@ mod tests {
         assert_eq!(parse_prefix("map_abcdefghijkl"), Some(EntityPrefix::Map));
         assert_eq!(parse_prefix("rpt_abcdefghijkl"), Some(EntityPrefix::Report));
         assert_eq!(parse_prefix("pty_abcdefghijkl"), Some(EntityPrefix::Party));
+        assert_eq!(parse_prefix("eml_abcdefghijkl"), Some(EntityPrefix::Email));
+        assert_eq!(parse_prefix("dev_abcdefghijkl"), Some(EntityPrefix::Device));
+        assert_eq!(parse_prefix("lhc_abcdefghijkl"), Some(EntityPrefix::HealthCheck));
+        assert_eq!(parse_prefix("chs_abcdefghijkl"), Some(EntityPrefix::CheatSignal));
+        assert_eq!(parse_prefix("psb_abcdefghijkl"), Some(EntityPrefix::PushSubscription));
+        assert_eq!(parse_prefix("org_abcdefghijkl"), Some(EntityPrefix::Organization));
+        assert_eq!(parse_prefix("prs_abcdefghijkl"), Some(EntityPrefix::SettingsPreset));
+        assert_eq!(parse_prefix("nfl_abcdefghijkl"), Some(EntityPrefix::NameFlag));
+        assert_eq!(parse_prefix("prp_abcdefghijkl"), Some(EntityPrefix::PlayerReport));
+        assert_eq!(parse_prefix("prc_abcdefghijkl"), Some(EntityPrefix::PracticeRound));
         assert_eq!(parse_prefix("unknown_id"), None);
     }
 }
@@ -0,0 +1,107 @@
+//! Join codes for multiplayer lobbies.
+//!
+//! Random codes use an alphabet without look-alike characters (no I, O, 0
+//! or 1). Hosts may pick a vanity code instead; those may use any letter or
+//! digit but must not contain a reserved word.
+
+use crate::moderation;
+
+/// Characters used in random join codes.
+const CHARSET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+/// Length of random join codes unless configured otherwise.
+pub const DEFAULT_JOIN_CODE_LENGTH: usize = 6;
+
+/// Shortest accepted join code.
+pub const MIN_JOIN_CODE_LENGTH: usize = 4;
+
+/// Longest accepted join code (the database column is `VARCHAR(8)`).
+pub const MAX_JOIN_CODE_LENGTH: usize = 8;
+
+/// Words that may not appear in a join code: staff impersonation and
+/// offensive terms. Matched after [`moderation::normalize`] undoes
+/// digit-for-letter substitutions.
+const RESERVED_WORDS: &[&str] = &[
+    "admin", "anal", "bitch", "cock", "cunt", "dguess", "dick", "fag", "fuck", "hitler", "kkk",
+    "mod", "nazi", "nigg", "official", "piss", "porn", "pussy", "rape", "retard", "sex", "shit",
+    "slut", "staff", "support", "system", "twat", "whore",
+];
+
+/// Generate a random join code of `length` characters (clamped to the
+/// accepted range) that contains no reserved word.
+pub fn generate_join_code(length: usize) -> String {
+    use rand::RngExt;
+    let length = length.clamp(MIN_JOIN_CODE_LENGTH, MAX_JOIN_CODE_LENGTH);
+    let mut rng = rand::rng();
+    loop {
+        let code: String = (0..length)
+            .map(|_| {
+                let idx = rng.random_range(0..CHARSET.len());
+                CHARSET[idx] as char
+            })
+            .collect();
+        if !contains_reserved_word(&code) {
+            return code;
+        }
+    }
+}
+
+/// Normalize a code typed by a player: trimmed and uppercased.
+///
+/// Returns `None` if it can't be a join code.
+pub fn normalize_join_code(input: &str) -> Option<String> {
+    let code = input.trim().to_uppercase();
+    let valid = (MIN_JOIN_CODE_LENGTH..=MAX_JOIN_CODE_LENGTH).contains(&code.len())
+        && code.chars().all(|c| c.is_ascii_alphanumeric());
+    valid.then_some(code)
+}
+
+/// Validate a host-chosen vanity code, returning it normalized.
+pub fn validate_vanity_code(input: &str) -> Result<String, &'static str> {
+    let code = normalize_join_code(input).ok_or("Join code must be 4-8 letters or digits")?;
+    if contains_reserved_word(&code) {
+        return Err("This join code is not allowed");
+    }
+    Ok(code)
+}
+
+/// Whether a code contains a reserved word, reading digits as the letters
+/// they commonly stand in for.
+fn contains_reserved_word(code: &str) -> bool {
+    let letters = moderation::normalize(code);
+    RESERVED_WORDS.iter().any(|word| letters.contains(word))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_join_code() {
+        assert_eq!(generate_join_code(DEFAULT_JOIN_CODE_LENGTH).len(), 6);
+        assert_eq!(generate_join_code(2).len(), MIN_JOIN_CODE_LENGTH);
+        assert_eq!(generate_join_code(20).len(), MAX_JOIN_CODE_LENGTH);
+
+        let code = generate_join_code(8);
+        assert!(code.bytes().all(|b| CHARSET.contains(&b)));
+        assert_eq!(normalize_join_code(&code), Some(code));
+    }
+
+    #[test]
+    fn test_normalize_join_code() {
+        assert_eq!(normalize_join_code(" abc123 "), Some("ABC123".to_string()));
+        assert_eq!(normalize_join_code("ABC"), None);
+        assert_eq!(normalize_join_code("ABCDEFGHJ"), None);
+        assert_eq!(normalize_join_code("AB-123"), None);
+    }
+
+    #[test]
+    fn test_validate_vanity_code() {
+        assert_eq!(validate_vanity_code("party1"), Ok("PARTY1".to_string()));
+        assert_eq!(validate_vanity_code("GG2026"), Ok("GG2026".to_string()));
+        assert!(validate_vanity_code("x").is_err());
+        assert!(validate_vanity_code("admin1").is_err());
+        assert!(validate_vanity_code("4DM1N").is_err());
+        assert!(validate_vanity_code("SH1TS").is_err());
+    }
+}
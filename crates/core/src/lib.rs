@@ -1,18 +1,23 @@
 //! Core domain logic for DGuesser
 //!
 //! This crate contains game rules, scoring algorithms, geographic calculations,
-//! location management, and ID/session token generation utilities.
+//! location management, display-name moderation, player reports, and ID/session/join code generation utilities.
 
 pub mod game;
 pub mod geo;
 pub mod id;
+pub mod join_code;
 pub mod location;
+pub mod moderation;
+pub mod reports;
 pub mod session;
 pub mod streetview;
 
 pub use id::{
-    EntityPrefix, generate_game_id, generate_guess_id, generate_location_id, generate_map_id,
-    generate_oauth_id, generate_party_id, generate_report_id, generate_round_id,
-    generate_session_id, generate_user_id, parse_prefix,
+    EntityPrefix, generate_cheat_signal_id, generate_device_id, generate_email_id,
+    generate_game_id, generate_guess_id, generate_health_check_id, generate_location_id,
+    generate_map_id, generate_name_flag_id, generate_oauth_id, generate_org_id, generate_party_id,
+    generate_player_report_id, generate_preset_id, generate_push_subscription_id,
+    generate_report_id, generate_round_id, generate_session_id, generate_user_id, parse_prefix,
 };
 pub use session::{generate_prefixed_session_token, generate_session_token, is_valid_token_format};
@@ -0,0 +1,287 @@
+//! Static country statistics used to weight location selection.
+//!
+//! Figures are rounded (area in km², population as of ~2023) and only need to
+//! be right in relative terms: they decide how often a world map lands in
+//! each country, not anything shown to players.
+
+/// `(ISO 3166-1 alpha-2, area km², population)`, sorted by code.
+const COUNTRY_STATS: &[(&str, u64, u64)] = &[
+    ("AD", 468, 80_000),
+    ("AE", 83_600, 9_440_000),
+    ("AF", 652_230, 41_100_000),
+    ("AG", 442, 94_000),
+    ("AL", 28_748, 2_750_000),
+    ("AM", 29_743, 2_780_000),
+    ("AO", 1_246_700, 36_700_000),
+    ("AR", 2_780_400, 46_000_000),
+    ("AS", 199, 44_000),
+    ("AT", 83_879, 9_100_000),
+    ("AU", 7_692_024, 26_600_000),
+    ("AW", 180, 106_000),
+    ("AX", 1_580, 30_000),
+    ("AZ", 86_600, 10_100_000),
+    ("BA", 51_209, 3_200_000),
+    ("BB", 430, 282_000),
+    ("BD", 147_570, 172_000_000),
+    ("BE", 30_528, 11_800_000),
+    ("BF", 274_200, 22_700_000),
+    ("BG", 110_994, 6_450_000),
+    ("BH", 786, 1_480_000),
+    ("BI", 27_834, 13_200_000),
+    ("BJ", 114_763, 13_700_000),
+    ("BM", 54, 64_000),
+    ("BN", 5_765, 450_000),
+    ("BO", 1_098_581, 12_400_000),
+    ("BR", 8_515_767, 216_000_000),
+    ("BS", 13_943, 410_000),
+    ("BT", 38_394, 780_000),
+    ("BW", 581_730, 2_680_000),
+    ("BY", 207_600, 9_200_000),
+    ("BZ", 22_966, 410_000),
+    ("CA", 9_984_670, 40_000_000),
+    ("CD", 2_344_858, 102_000_000),
+    ("CF", 622_984, 5_700_000),
+    ("CG", 342_000, 6_100_000),
+    ("CH", 41_285, 8_800_000),
+    ("CI", 322_463, 28_900_000),
+    ("CK", 236, 17_000),
+    ("CL", 756_102, 19_600_000),
+    ("CM", 475_442, 28_600_000),
+    ("CN", 9_596_961, 1_410_000_000),
+    ("CO", 1_141_748, 52_100_000),
+    ("CR", 51_100, 5_200_000),
+    ("CU", 109_884, 11_100_000),
+    ("CV", 4_033, 600_000),
+    ("CW", 444, 150_000),
+    ("CY", 9_251, 1_260_000),
+    ("CZ", 78_871, 10_900_000),
+    ("DE", 357_592, 84_500_000),
+    ("DJ", 23_200, 1_140_000),
+    ("DK", 42_943, 5_950_000),
+    ("DM", 751, 73_000),
+    ("DO", 48_671, 11_300_000),
+    ("DZ", 2_381_741, 45_600_000),
+    ("EC", 283_561, 18_000_000),
+    ("EE", 45_339, 1_370_000),
+    ("EG", 1_002_450, 112_700_000),
+    ("ER", 117_600, 3_750_000),
+    ("ES", 505_990, 48_400_000),
+    ("ET", 1_104_300, 126_500_000),
+    ("FI", 338_424, 5_600_000),
+    ("FJ", 18_274, 936_000),
+    ("FK", 12_173, 3_700),
+    ("FM", 702, 115_000),
+    ("FO", 1_399, 54_000),
+    ("FR", 551_695, 68_200_000),
+    ("GA", 267_668, 2_440_000),
+    ("GB", 242_495, 68_300_000),
+    ("GD", 344, 126_000),
+    ("GE", 69_700, 3_700_000),
+    ("GF", 83_534, 300_000),
+    ("GG", 78, 64_000),
+    ("GH", 238_533, 34_100_000),
+    ("GI", 7, 33_000),
+    ("GL", 2_166_086, 56_000),
+    ("GM", 10_689, 2_770_000),
+    ("GN", 245_857, 14_200_000),
+    ("GP", 1_628, 380_000),
+    ("GQ", 28_051, 1_710_000),
+    ("GR", 131_957, 10_400_000),
+    ("GT", 108_889, 18_100_000),
+    ("GU", 544, 172_000),
+    ("GW", 36_125, 2_150_000),
+    ("GY", 214_969, 810_000),
+    ("HK", 1_106, 7_500_000),
+    ("HN", 112_492, 10_600_000),
+    ("HR", 56_594, 3_850_000),
+    ("HT", 27_750, 11_700_000),
+    ("HU", 93_028, 9_600_000),
+    ("ID", 1_904_569, 277_500_000),
+    ("IE", 70_273, 5_270_000),
+    ("IL", 22_072, 9_800_000),
+    ("IM", 572, 84_000),
+    ("IN", 3_287_263, 1_430_000_000),
+    ("IQ", 438_317, 45_500_000),
+    ("IR", 1_648_195, 89_200_000),
+    ("IS", 103_000, 390_000),
+    ("IT", 301_340, 58_900_000),
+    ("JE", 118, 103_000),
+    ("JM", 10_991, 2_830_000),
+    ("JO", 89_342, 11_300_000),
+    ("JP", 377_975, 124_500_000),
+    ("KE", 580_367, 55_100_000),
+    ("KG", 199_951, 7_000_000),
+    ("KH", 181_035, 16_900_000),
+    ("KI", 811, 133_000),
+    ("KM", 1_862, 850_000),
+    ("KN", 261, 47_000),
+    ("KP", 120_538, 26_200_000),
+    ("KR", 100_210, 51_700_000),
+    ("KW", 17_818, 4_310_000),
+    ("KY", 264, 69_000),
+    ("KZ", 2_724_900, 19_900_000),
+    ("LA", 236_800, 7_600_000),
+    ("LB", 10_452, 5_350_000),
+    ("LC", 616, 180_000),
+    ("LI", 160, 40_000),
+    ("LK", 65_610, 22_000_000),
+    ("LR", 111_369, 5_400_000),
+    ("LS", 30_355, 2_330_000),
+    ("LT", 65_300, 2_860_000),
+    ("LU", 2_586, 660_000),
+    ("LV", 64_589, 1_880_000),
+    ("LY", 1_759_540, 6_900_000),
+    ("MA", 446_550, 37_800_000),
+    ("MC", 2, 36_000),
+    ("MD", 33_846, 2_500_000),
+    ("ME", 13_812, 620_000),
+    ("MG", 587_041, 30_300_000),
+    ("MH", 181, 42_000),
+    ("MK", 25_713, 1_830_000),
+    ("ML", 1_240_192, 23_300_000),
+    ("MM", 676_578, 54_600_000),
+    ("MN", 1_564_110, 3_450_000),
+    ("MO", 33, 700_000),
+    ("MP", 464, 50_000),
+    ("MQ", 1_128, 350_000),
+    ("MR", 1_030_700, 4_860_000),
+    ("MT", 316, 540_000),
+    ("MU", 2_040, 1_260_000),
+    ("MV", 300, 520_000),
+    ("MW", 118_484, 20_900_000),
+    ("MX", 1_964_375, 128_500_000),
+    ("MY", 330_803, 34_300_000),
+    ("MZ", 801_590, 33_900_000),
+    ("NA", 825_615, 2_600_000),
+    ("NC", 18_575, 270_000),
+    ("NE", 1_267_000, 27_200_000),
+    ("NG", 923_768, 223_800_000),
+    ("NI", 130_373, 7_000_000),
+    ("NL", 41_850, 17_900_000),
+    ("NO", 323_802, 5_500_000),
+    ("NP", 147_181, 30_900_000),
+    ("NR", 21, 12_000),
+    ("NZ", 268_021, 5_220_000),
+    ("OM", 309_500, 4_640_000),
+    ("PA", 75_417, 4_470_000),
+    ("PE", 1_285_216, 34_400_000),
+    ("PF", 4_167, 280_000),
+    ("PG", 462_840, 10_300_000),
+    ("PH", 300_000, 117_300_000),
+    ("PK", 881_913, 240_500_000),
+    ("PL", 312_696, 36_800_000),
+    ("PM", 242, 6_000),
+    ("PN", 47, 50),
+    ("PR", 9_104, 3_210_000),
+    ("PS", 6_020, 5_400_000),
+    ("PT", 92_212, 10_500_000),
+    ("PW", 459, 18_000),
+    ("PY", 406_752, 6_860_000),
+    ("QA", 11_586, 2_720_000),
+    ("RE", 2_511, 870_000),
+    ("RO", 238_397, 19_000_000),
+    ("RS", 77_474, 6_650_000),
+    ("RU", 17_098_246, 144_000_000),
+    ("RW", 26_338, 14_100_000),
+    ("SA", 2_149_690, 36_900_000),
+    ("SB", 28_896, 740_000),
+    ("SC", 459, 120_000),
+    ("SD", 1_861_484, 48_100_000),
+    ("SE", 450_295, 10_500_000),
+    ("SG", 728, 5_920_000),
+    ("SI", 20_273, 2_120_000),
+    ("SJ", 61_399, 2_500),
+    ("SK", 49_035, 5_430_000),
+    ("SL", 71_740, 8_800_000),
+    ("SM", 61, 34_000),
+    ("SN", 196_722, 17_800_000),
+    ("SO", 637_657, 18_100_000),
+    ("SR", 163_820, 620_000),
+    ("SS", 619_745, 11_100_000),
+    ("ST", 964, 230_000),
+    ("SV", 21_041, 6_360_000),
+    ("SY", 185_180, 23_200_000),
+    ("SZ", 17_364, 1_210_000),
+    ("TC", 948, 46_000),
+    ("TD", 1_284_000, 18_300_000),
+    ("TG", 56_785, 9_100_000),
+    ("TH", 513_120, 71_800_000),
+    ("TJ", 143_100, 10_100_000),
+    ("TL", 14_874, 1_360_000),
+    ("TM", 488_100, 6_500_000),
+    ("TN", 163_610, 12_500_000),
+    ("TO", 747, 107_000),
+    ("TR", 783_562, 85_300_000),
+    ("TT", 5_128, 1_530_000),
+    ("TV", 26, 11_000),
+    ("TW", 36_193, 23_900_000),
+    ("TZ", 947_303, 67_400_000),
+    ("UA", 603_550, 37_000_000),
+    ("UG", 241_550, 48_600_000),
+    ("US", 9_833_520, 334_900_000),
+    ("UY", 176_215, 3_420_000),
+    ("UZ", 448_978, 35_600_000),
+    ("VA", 1, 800),
+    ("VC", 389, 104_000),
+    ("VE", 916_445, 28_800_000),
+    ("VG", 151, 31_000),
+    ("VI", 347, 99_000),
+    ("VN", 331_212, 98_900_000),
+    ("VU", 12_189, 330_000),
+    ("WS", 2_842, 225_000),
+    ("XK", 10_887, 1_760_000),
+    ("YE", 527_968, 34_400_000),
+    ("YT", 374, 320_000),
+    ("ZA", 1_221_037, 60_400_000),
+    ("ZM", 752_612, 20_600_000),
+    ("ZW", 390_757, 16_700_000),
+];
+
+fn lookup(country: &str) -> Option<&'static (&'static str, u64, u64)> {
+    let country = country.to_ascii_uppercase();
+    COUNTRY_STATS
+        .binary_search_by(|(code, _, _)| (*code).cmp(country.as_str()))
+        .ok()
+        .map(|i| &COUNTRY_STATS[i])
+}
+
+/// Total area of a country in km², if known.
+pub fn country_area_km2(country: &str) -> Option<u64> {
+    lookup(country).map(|(_, area, _)| *area)
+}
+
+/// ISO 3166-1 alpha-2 codes of every country with known statistics.
+pub fn country_codes() -> impl Iterator<Item = &'static str> {
+    COUNTRY_STATS.iter().map(|(code, _, _)| *code)
+}
+
+/// Population of a country, if known.
+pub fn country_population(country: &str) -> Option<u64> {
+    lookup(country).map(|(_, _, population)| *population)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_is_sorted_and_well_formed() {
+        for pair in COUNTRY_STATS.windows(2) {
+            assert!(pair[0].0 < pair[1].0, "{} must come before {}", pair[0].0, pair[1].0);
+        }
+        for (code, area, population) in COUNTRY_STATS {
+            assert_eq!(code.len(), 2);
+            assert!(code.chars().all(|c| c.is_ascii_uppercase()));
+            assert!(*area > 0 && *population > 0, "{code} needs positive stats");
+        }
+    }
+
+    #[test]
+    fn test_lookup() {
+        assert_eq!(country_area_km2("FR"), Some(551_695));
+        assert_eq!(country_population("fr"), Some(68_200_000));
+        assert_eq!(country_area_km2("ZZ"), None);
+        assert!(country_area_km2("RU") > country_area_km2("US"));
+        assert!(country_population("IN") > country_population("US"));
+    }
+}
@@ -0,0 +1,108 @@
+//! Location difficulty derived from gameplay.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::{ScoringConfig, calculate_score};
+
+/// Highest difficulty rating (nobody scores any points).
+pub const MAX_DIFFICULTY: i16 = 100;
+
+/// Rate a location from the median distance of its past guesses.
+///
+/// The rating is the share of points (0-100) the median guess loses on the
+/// default scoring curve, so it is comparable across maps with custom curves.
+pub fn difficulty_rating(median_distance_meters: f64) -> i16 {
+    let config = ScoringConfig::default();
+    let score = calculate_score(median_distance_meters.max(0.0), &config);
+    let lost = 1.0 - score as f64 / config.max_points as f64;
+    (lost * MAX_DIFFICULTY as f64).round() as i16
+}
+
+/// A range of difficulty ratings maps and searches can filter by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DifficultyBand {
+    Easy,
+    Medium,
+    Hard,
+    Extreme,
+}
+
+impl DifficultyBand {
+    /// Every band, easiest first.
+    pub const ALL: [DifficultyBand; 4] = [Self::Easy, Self::Medium, Self::Hard, Self::Extreme];
+
+    /// Inclusive range of ratings in this band.
+    pub fn range(self) -> (i16, i16) {
+        match self {
+            Self::Easy => (0, 24),
+            Self::Medium => (25, 49),
+            Self::Hard => (50, 74),
+            Self::Extreme => (75, MAX_DIFFICULTY),
+        }
+    }
+
+    /// The band a rating falls in.
+    pub fn for_rating(rating: i16) -> Self {
+        Self::ALL.into_iter().find(|band| rating <= band.range().1).unwrap_or(Self::Extreme)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Easy => "easy",
+            Self::Medium => "medium",
+            Self::Hard => "hard",
+            Self::Extreme => "extreme",
+        }
+    }
+}
+
+impl fmt::Display for DifficultyBand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DifficultyBand {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|band| band.as_str() == s)
+            .ok_or_else(|| format!("Invalid difficulty: {s}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_difficulty_rating() {
+        assert_eq!(difficulty_rating(0.0), 0);
+        assert_eq!(difficulty_rating(-1.0), 0);
+        assert_eq!(difficulty_rating(5_000_000.0), MAX_DIFFICULTY);
+        assert_eq!(difficulty_rating(20_000_000.0), MAX_DIFFICULTY);
+
+        let near = difficulty_rating(500_000.0);
+        let far = difficulty_rating(2_500_000.0);
+        assert!(near < far);
+        assert_eq!(DifficultyBand::for_rating(near), DifficultyBand::Easy);
+        assert_eq!(DifficultyBand::for_rating(far), DifficultyBand::Medium);
+    }
+
+    #[test]
+    fn test_bands_cover_all_ratings() {
+        for rating in 0..=MAX_DIFFICULTY {
+            let (min, max) = DifficultyBand::for_rating(rating).range();
+            assert!((min..=max).contains(&rating));
+        }
+        for band in DifficultyBand::ALL {
+            assert_eq!(band.as_str().parse::<DifficultyBand>(), Ok(band));
+        }
+    }
+}
@@ -3,12 +3,20 @@
 //! This module defines the core types for managing pre-validated Street View locations
 //! and the trait for selecting random locations during gameplay.
 
+mod countries;
+mod difficulty;
+mod region;
 mod spread;
+mod tags;
 mod types;
 
-pub use spread::{SpreadSelection, select_spread_candidate};
+pub use countries::{country_area_km2, country_codes, country_population};
+pub use difficulty::{DifficultyBand, MAX_DIFFICULTY, difficulty_rating};
+pub use region::{MAX_REGION_POLYGONS, MAX_REGION_VERTICES, MapRegion, Polygon, RegionError, Ring};
+pub use spread::{SpreadSelection, select_ranked_candidate, select_spread_candidate};
+pub use tags::{MAX_TAG_LENGTH, normalize_tags, parse_tag_list};
 pub use types::{
     CountryDistribution, DEFAULT_MIN_SPREAD_DISTANCE_KM, GameLocation, Location, LocationError,
-    LocationProvider, LocationSource, LocationValidationStatus, Map, MapRules, MapVisibility,
-    ReviewStatus, SelectionConstraints,
+    LocationProvider, LocationSource, LocationValidationStatus, Map, MapLocationSource, MapRules,
+    MapRulesError, MapVisibility, ReviewStatus, SelectionConstraints,
 };
@@ -0,0 +1,193 @@
+//! Polygon map regions.
+//!
+//! A map can restrict its locations to an arbitrary area ("Scandinavian
+//! coastline") instead of, or in addition to, a list of countries. Regions are
+//! stored as GeoJSON `Polygon` or `MultiPolygon` geometries with `[lng, lat]`
+//! positions.
+
+use serde::{Deserialize, Serialize};
+
+/// Upper bound on the total number of vertices in a region.
+pub const MAX_REGION_VERTICES: usize = 10_000;
+
+/// Upper bound on the number of polygons in a `MultiPolygon` region.
+pub const MAX_REGION_POLYGONS: usize = 100;
+
+/// A polygon ring: a closed list of `[lng, lat]` positions.
+pub type Ring = Vec<[f64; 2]>;
+
+/// A polygon: an outer ring followed by zero or more holes.
+pub type Polygon = Vec<Ring>;
+
+/// A GeoJSON polygon geometry describing a map's playable area.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum MapRegion {
+    /// A single polygon, optionally with holes
+    Polygon { coordinates: Polygon },
+    /// Several disjoint polygons (e.g. a mainland and its islands)
+    MultiPolygon { coordinates: Vec<Polygon> },
+}
+
+/// Reasons a region geometry is rejected.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum RegionError {
+    #[error("region must contain at least one polygon")]
+    Empty,
+    #[error("region has too many polygons (max {MAX_REGION_POLYGONS})")]
+    TooManyPolygons,
+    #[error("region has too many vertices (max {MAX_REGION_VERTICES})")]
+    TooManyVertices,
+    #[error("polygon {0} has no outer ring")]
+    MissingOuterRing(usize),
+    #[error("ring in polygon {0} needs at least 4 positions")]
+    RingTooShort(usize),
+    #[error("ring in polygon {0} is not closed (first and last positions differ)")]
+    RingNotClosed(usize),
+    #[error("polygon {0} has a position outside [-180, 180] x [-90, 90]")]
+    OutOfRange(usize),
+}
+
+impl MapRegion {
+    /// All polygons in the region.
+    pub fn polygons(&self) -> &[Polygon] {
+        match self {
+            MapRegion::Polygon { coordinates } => std::slice::from_ref(coordinates),
+            MapRegion::MultiPolygon { coordinates } => coordinates,
+        }
+    }
+
+    /// Total number of positions across all rings.
+    pub fn vertex_count(&self) -> usize {
+        self.polygons().iter().flatten().map(Vec::len).sum()
+    }
+
+    /// Check the geometry is well formed and within size limits.
+    pub fn validate(&self) -> Result<(), RegionError> {
+        let polygons = self.polygons();
+        if polygons.is_empty() {
+            return Err(RegionError::Empty);
+        }
+        if polygons.len() > MAX_REGION_POLYGONS {
+            return Err(RegionError::TooManyPolygons);
+        }
+        if self.vertex_count() > MAX_REGION_VERTICES {
+            return Err(RegionError::TooManyVertices);
+        }
+
+        for (i, polygon) in polygons.iter().enumerate() {
+            if polygon.is_empty() {
+                return Err(RegionError::MissingOuterRing(i));
+            }
+            for ring in polygon {
+                if ring.len() < 4 {
+                    return Err(RegionError::RingTooShort(i));
+                }
+                if ring.first() != ring.last() {
+                    return Err(RegionError::RingNotClosed(i));
+                }
+                let in_range = ring.iter().all(|[lng, lat]| {
+                    (-180.0..=180.0).contains(lng) && (-90.0..=90.0).contains(lat)
+                });
+                if !in_range {
+                    return Err(RegionError::OutOfRange(i));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether a point lies inside the region (inside an outer ring and
+    /// outside all of that polygon's holes).
+    ///
+    /// Uses planar ray casting on lng/lat, which matches how PostGIS treats
+    /// `geometry(…, 4326)` values.
+    pub fn contains(&self, lat: f64, lng: f64) -> bool {
+        self.polygons().iter().any(|polygon| match polygon.split_first() {
+            Some((outer, holes)) => {
+                ring_contains(outer, lat, lng)
+                    && !holes.iter().any(|hole| ring_contains(hole, lat, lng))
+            }
+            None => false,
+        })
+    }
+}
+
+/// Even-odd ray casting test for a single ring.
+fn ring_contains(ring: &[[f64; 2]], lat: f64, lng: f64) -> bool {
+    let mut inside = false;
+    let mut j = ring.len().wrapping_sub(1);
+    for i in 0..ring.len() {
+        let [xi, yi] = ring[i];
+        let [xj, yj] = ring[j];
+        if (yi > lat) != (yj > lat) && lng < (xj - xi) * (lat - yi) / (yj - yi) + xi {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(min: f64, max: f64) -> Ring {
+        vec![[min, min], [max, min], [max, max], [min, max], [min, min]]
+    }
+
+    #[test]
+    fn test_deserialize_geojson() {
+        let json = r#"{"type":"Polygon","coordinates":[[[0,0],[10,0],[10,10],[0,10],[0,0]]]}"#;
+        let region: MapRegion = serde_json::from_str(json).unwrap();
+        assert_eq!(region, MapRegion::Polygon { coordinates: vec![square(0.0, 10.0)] });
+        assert_eq!(region.vertex_count(), 5);
+
+        let json = r#"{"type":"MultiPolygon","coordinates":[[[[0,0],[1,0],[1,1],[0,0]]]]}"#;
+        let region: MapRegion = serde_json::from_str(json).unwrap();
+        assert_eq!(region.polygons().len(), 1);
+    }
+
+    #[test]
+    fn test_validate() {
+        let ok = MapRegion::Polygon { coordinates: vec![square(0.0, 10.0)] };
+        assert_eq!(ok.validate(), Ok(()));
+
+        let empty = MapRegion::MultiPolygon { coordinates: vec![] };
+        assert_eq!(empty.validate(), Err(RegionError::Empty));
+
+        let open = MapRegion::Polygon {
+            coordinates: vec![vec![[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]]],
+        };
+        assert_eq!(open.validate(), Err(RegionError::RingNotClosed(0)));
+
+        let short = MapRegion::Polygon { coordinates: vec![vec![[0.0, 0.0], [0.0, 0.0]]] };
+        assert_eq!(short.validate(), Err(RegionError::RingTooShort(0)));
+
+        let out_of_range = MapRegion::Polygon { coordinates: vec![square(0.0, 200.0)] };
+        assert_eq!(out_of_range.validate(), Err(RegionError::OutOfRange(0)));
+
+        let huge = MapRegion::Polygon {
+            coordinates: vec![(0..=MAX_REGION_VERTICES).map(|_| [0.0, 0.0]).collect()],
+        };
+        assert_eq!(huge.validate(), Err(RegionError::TooManyVertices));
+    }
+
+    #[test]
+    fn test_contains_respects_holes() {
+        let region = MapRegion::Polygon { coordinates: vec![square(0.0, 10.0), square(4.0, 6.0)] };
+        assert!(region.contains(2.0, 2.0));
+        assert!(!region.contains(5.0, 5.0));
+        assert!(!region.contains(11.0, 5.0));
+    }
+
+    #[test]
+    fn test_contains_multipolygon() {
+        let region = MapRegion::MultiPolygon {
+            coordinates: vec![vec![square(0.0, 1.0)], vec![square(20.0, 21.0)]],
+        };
+        assert!(region.contains(0.5, 0.5));
+        assert!(region.contains(20.5, 20.5));
+        assert!(!region.contains(10.0, 10.0));
+    }
+}
@@ -12,6 +12,9 @@ const SHORTLIST_SCORE_RATIO: f64 = 0.9;
 /// Limit randomness to a small pool of top spread candidates.
 const MAX_SHORTLIST_CANDIDATES: usize = 4;
 
+/// Score multiplier for candidates in the same country as the previous round.
+const COUNTRY_REPEAT_PENALTY: f64 = 0.5;
+
 /// Result of selecting a spread-ranked candidate.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct SpreadSelection {
@@ -34,11 +37,29 @@ pub fn select_spread_candidate(
     candidates: &[(f64, f64)],
     constraints: &SelectionConstraints,
 ) -> Option<SpreadSelection> {
-    if candidates.is_empty() || constraints.previous_locations.is_empty() {
+    if constraints.previous_locations.is_empty() {
+        return None;
+    }
+    select_ranked_candidate(candidates, &[], constraints)
+}
+
+/// Select a candidate by spread, ranking candidates in `avoid_country` lower.
+///
+/// `countries` holds each candidate's country code, in the same order as
+/// `candidates` (missing entries count as unknown). Without previous round
+/// locations every candidate starts from the same score, so only the country
+/// penalty applies.
+pub fn select_ranked_candidate(
+    candidates: &[(f64, f64)],
+    countries: &[Option<&str>],
+    constraints: &SelectionConstraints,
+) -> Option<SpreadSelection> {
+    if candidates.is_empty() || !constraints.uses_candidate_ranking() {
         return None;
     }
 
-    let mut scored: Vec<(usize, f64, bool)> = candidates
+    // (index, spread distance, ranking score, meets hard minimum)
+    let mut scored: Vec<(usize, f64, f64, bool)> = candidates
         .iter()
         .enumerate()
         .map(|(idx, (lat, lng))| {
@@ -48,24 +69,32 @@ pub fn select_spread_candidate(
             let meets_min_distance = !constraints.has_hard_min_distance()
                 || spread_score >= constraints.min_distance_meters;
 
-            (idx, spread_score, meets_min_distance)
+            let base_score = if constraints.uses_spread_ranking() { spread_score } else { 1.0 };
+            let repeats_country = match (&constraints.avoid_country, countries.get(idx)) {
+                (Some(avoid), Some(Some(country))) => avoid.eq_ignore_ascii_case(country),
+                _ => false,
+            };
+            let score =
+                if repeats_country { base_score * COUNTRY_REPEAT_PENALTY } else { base_score };
+
+            (idx, spread_score, score, meets_min_distance)
         })
         .collect();
 
-    if constraints.has_hard_min_distance() && scored.iter().any(|(_, _, ok)| *ok) {
-        scored.retain(|(_, _, ok)| *ok);
+    if constraints.has_hard_min_distance() && scored.iter().any(|(_, _, _, ok)| *ok) {
+        scored.retain(|(_, _, _, ok)| *ok);
     }
 
-    scored.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    scored.sort_by(|a, b| b.2.total_cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
 
-    let best_score = scored.first()?.1;
+    let best_score = scored.first()?.2;
     let shortlist_threshold = best_score * SHORTLIST_SCORE_RATIO;
 
     let mut shortlist: Vec<(usize, f64)> = scored
         .iter()
-        .filter(|(_, score, _)| *score >= shortlist_threshold)
+        .filter(|(_, _, score, _)| *score >= shortlist_threshold)
         .take(MAX_SHORTLIST_CANDIDATES)
-        .map(|(idx, score, _)| (*idx, *score))
+        .map(|(idx, spread, _, _)| (*idx, *spread))
         .collect();
 
     if shortlist.is_empty() {
@@ -153,4 +182,26 @@ mod tests {
         assert!(seen.contains(&1));
         assert!(!seen.contains(&2));
     }
+
+    #[test]
+    fn test_select_ranked_candidate_penalizes_repeated_country() {
+        let constraints = SelectionConstraints::with_previous_locations(vec![(0.0, 0.0)])
+            .with_history(Vec::new(), Some("FR".to_string()));
+        // The French candidate is further away, but the penalty halves its score
+        let candidates = [(0.0, 10.0), (0.0, 8.0)];
+        let countries = [Some("fr"), Some("DE")];
+
+        for _ in 0..50 {
+            let selection = select_ranked_candidate(&candidates, &countries, &constraints).unwrap();
+            assert_eq!(selection.selected_index, 1);
+        }
+
+        // Without previous locations only the penalty decides
+        let constraints = SelectionConstraints::none().with_history(Vec::new(), Some("FR".into()));
+        assert!(select_spread_candidate(&candidates, &constraints).is_none());
+        for _ in 0..50 {
+            let selection = select_ranked_candidate(&candidates, &countries, &constraints).unwrap();
+            assert_eq!(selection.selected_index, 1);
+        }
+    }
 }
@@ -0,0 +1,51 @@
+//! Free-form location tags (from Vali) used by map builder searches.
+
+/// Longest tag kept; longer ones are dropped.
+pub const MAX_TAG_LENGTH: usize = 64;
+
+/// Normalize tags for storage and search: trimmed, lowercase, inner
+/// whitespace replaced with `-`, without empty, overlong or duplicate tags.
+/// Order is preserved.
+pub fn normalize_tags<I, S>(tags: I) -> Vec<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.as_ref().split_whitespace().collect::<Vec<_>>().join("-").to_lowercase();
+        if tag.is_empty() || tag.chars().count() > MAX_TAG_LENGTH || normalized.contains(&tag) {
+            continue;
+        }
+        normalized.push(tag);
+    }
+    normalized
+}
+
+/// Parse a comma-separated tag list (as given in search queries).
+pub fn parse_tag_list(list: &str) -> Vec<String> {
+    normalize_tags(list.split(','))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_tags() {
+        let tags = normalize_tags([" Snow ", "desert", "SNOW", "", "toll booth", "IsScout-No"]);
+        assert_eq!(tags, vec!["snow", "desert", "toll-booth", "isscout-no"]);
+    }
+
+    #[test]
+    fn test_normalize_drops_overlong_tags() {
+        let long = "x".repeat(MAX_TAG_LENGTH + 1);
+        assert_eq!(normalize_tags([long.as_str(), "ok"]), vec!["ok"]);
+    }
+
+    #[test]
+    fn test_parse_tag_list() {
+        assert_eq!(parse_tag_list("snow, Desert,,snow"), vec!["snow", "desert"]);
+        assert!(parse_tag_list(" , ").is_empty());
+    }
+}
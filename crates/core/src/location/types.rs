@@ -7,6 +7,12 @@ use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use super::countries::{country_area_km2, country_population};
+use super::{DifficultyBand, MapRegion, RegionError};
+use crate::game::{ScoringConfig, ScoringError};
+use crate::geo::countries::{CountryCode, CountryError};
+use crate::streetview::ImageryProvider;
+
 /// Errors that can occur during location operations.
 #[derive(Error, Debug)]
 pub enum LocationError {
@@ -168,6 +174,38 @@ impl std::str::FromStr for MapVisibility {
     }
 }
 
+/// Where a map's locations are served from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MapLocationSource {
+    /// PostgreSQL `locations` table (map_locations list or polygon region)
+    #[default]
+    Database,
+    /// Pre-built location packs in R2 (filtered by country/year/scout buckets)
+    Pack,
+}
+
+impl std::fmt::Display for MapLocationSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MapLocationSource::Database => write!(f, "database"),
+            MapLocationSource::Pack => write!(f, "pack"),
+        }
+    }
+}
+
+impl std::str::FromStr for MapLocationSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "database" => Ok(MapLocationSource::Database),
+            "pack" => Ok(MapLocationSource::Pack),
+            _ => Err(format!("Unknown map location source: {s}")),
+        }
+    }
+}
+
 impl std::fmt::Display for ReviewStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -210,8 +248,8 @@ pub struct Location {
     pub subdivision_code: Option<String>,
     /// Date the Street View imagery was captured
     pub capture_date: Option<NaiveDate>,
-    /// Provider of the Street View imagery
-    pub provider: String,
+    /// Provider of the imagery
+    pub provider: ImageryProvider,
     /// Whether this location is active
     pub active: bool,
     /// Last time this location was validated
@@ -238,6 +276,8 @@ pub struct Location {
     pub elevation: Option<i32>,
     /// Default heading for panorama
     pub heading: Option<f64>,
+    /// Normalized Vali tags (e.g. "snow", "tollbooth")
+    pub tags: Vec<String>,
 
     // --- Failure tracking ---
     /// Number of times reported as broken
@@ -252,6 +292,14 @@ pub struct Location {
     pub reviewed_at: Option<DateTime<Utc>>,
     /// Who reviewed it
     pub reviewed_by: Option<String>,
+
+    // --- Gameplay difficulty ---
+    /// Difficulty rating (0-100) from past guesses; `None` until rated
+    pub difficulty: Option<i16>,
+    /// Median distance of past guesses in meters
+    pub median_guess_distance_meters: Option<f64>,
+    /// Median score of past guesses
+    pub median_guess_score: Option<f64>,
 }
 
 /// A simplified location for use during gameplay.
@@ -270,6 +318,9 @@ pub struct GameLocation {
     pub country_code: Option<String>,
     /// Default heading/direction for the panorama (degrees, 0-360)
     pub heading: Option<f64>,
+    /// Provider of the imagery, so clients pick the right viewer
+    #[serde(default)]
+    pub provider: ImageryProvider,
 }
 
 impl From<Location> for GameLocation {
@@ -281,6 +332,7 @@ impl From<Location> for GameLocation {
             lng: loc.lng,
             country_code: loc.country_code,
             heading: loc.heading,
+            provider: loc.provider,
         }
     }
 }
@@ -309,6 +361,34 @@ pub enum CountryDistribution {
         /// Country code -> relative weight (e.g., {"US": 100, "FR": 50, "JP": 50})
         weights: std::collections::HashMap<String, u32>,
     },
+
+    /// Each country weighted by its land area.
+    /// Good for: world maps that should feel geographically even.
+    Area,
+
+    /// Each country weighted by its population.
+    /// Good for: world maps that follow where people live.
+    Population,
+}
+
+impl CountryDistribution {
+    /// Relative weight of a country under strategies that do not depend on
+    /// coverage. Returns `None` for `Proportional`, where the weight is the
+    /// country's number of eligible locations.
+    ///
+    /// Countries missing from the area/population tables get weight 1 so
+    /// small territories stay reachable.
+    pub fn static_weight(&self, country: &str) -> Option<u64> {
+        match self {
+            CountryDistribution::Proportional => None,
+            CountryDistribution::Equal => Some(1),
+            CountryDistribution::Weighted { weights } => {
+                Some(weights.get(country).map_or(0, |&w| w as u64))
+            }
+            CountryDistribution::Area => Some(country_area_km2(country).unwrap_or(1)),
+            CountryDistribution::Population => Some(country_population(country).unwrap_or(1)),
+        }
+    }
 }
 
 /// Suggested hard minimum spread distance between rounds in kilometers.
@@ -338,13 +418,93 @@ pub struct MapRules {
     /// to disable the hard minimum explicitly.
     #[serde(default)]
     pub min_spread_distance_km: Option<f64>,
+    /// Optional polygon boundary; only locations inside it are selected.
+    ///
+    /// Combines with the other rules (a location must also match the country
+    /// list and year range when those are set).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<MapRegion>,
+    /// Only select locations from this imagery provider
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<ImageryProvider>,
+    /// Custom scoring curve; unset uses the default curve
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scoring: Option<ScoringConfig>,
+    /// Only select locations rated in this difficulty band.
+    ///
+    /// Unrated locations (too few guesses so far) are excluded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<DifficultyBand>,
+}
+
+/// Reasons map rules are rejected.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum MapRulesError {
+    #[error(transparent)]
+    Country(#[from] CountryError),
+    #[error("Invalid country code '{0}' in weights")]
+    WeightCountry(String),
+    #[error("min_year must not be after max_year")]
+    YearRange,
+    #[error("min_spread_distance_km must not be negative")]
+    SpreadDistance,
+    #[error(transparent)]
+    Scoring(#[from] ScoringError),
+    #[error(transparent)]
+    Region(#[from] RegionError),
 }
 
 impl MapRules {
+    /// Check the rules are consistent and reference known countries.
+    pub fn validate(&self) -> Result<(), MapRulesError> {
+        for country in &self.countries {
+            CountryCode::parse(country)?;
+        }
+        if let CountryDistribution::Weighted { weights } = &self.country_distribution
+            && let Some(code) = weights.keys().find(|code| CountryCode::parse(code).is_err())
+        {
+            return Err(MapRulesError::WeightCountry(code.clone()));
+        }
+        if let (Some(min), Some(max)) = (self.min_year, self.max_year)
+            && min > max
+        {
+            return Err(MapRulesError::YearRange);
+        }
+        if self.min_spread_distance_km.is_some_and(|d| !d.is_finite() || d < 0.0) {
+            return Err(MapRulesError::SpreadDistance);
+        }
+        if let Some(scoring) = &self.scoring {
+            scoring.validate()?;
+        }
+        if let Some(region) = &self.region {
+            region.validate()?;
+        }
+        Ok(())
+    }
+
+    /// Normalize country codes (in the list and in weights) to upper case.
+    /// Call after [`validate`](Self::validate).
+    pub fn normalize(&mut self) {
+        for country in &mut self.countries {
+            *country = country.trim().to_ascii_uppercase();
+        }
+        if let CountryDistribution::Weighted { weights } = &mut self.country_distribution {
+            *weights = std::mem::take(weights)
+                .into_iter()
+                .map(|(code, weight)| (code.trim().to_ascii_uppercase(), weight))
+                .collect();
+        }
+    }
+
     /// Get the configured hard minimum spread distance, if any.
     pub fn hard_min_spread_distance_km(&self) -> Option<f64> {
         self.min_spread_distance_km.filter(|distance| *distance > 0.0)
     }
+
+    /// Get the scoring curve games on this map use.
+    pub fn scoring_config(&self) -> ScoringConfig {
+        self.scoring.clone().unwrap_or_default()
+    }
 }
 
 /// A map definition (playable region).
@@ -370,6 +530,17 @@ pub struct Map {
     pub visibility: MapVisibility,
     /// Denormalized count of locations in this map
     pub location_count: i32,
+    /// Denormalized count of users who liked this map
+    pub like_count: i32,
+    /// Finished games played on this map, counted once per player
+    pub play_count: i32,
+    /// Sum of final player scores across all plays
+    pub score_sum: i64,
+    /// Where locations are served from; `None` uses the server default
+    pub source: Option<MapLocationSource>,
+    /// Organization that owns this map (`None` for the public instance)
+    #[serde(default)]
+    pub org_id: Option<String>,
     /// When this map was created
     pub created_at: DateTime<Utc>,
     /// When this map was last updated
@@ -401,6 +572,21 @@ impl Map {
     pub fn is_system_map(&self) -> bool {
         self.creator_id.is_none()
     }
+
+    /// Check if this map can be used in an organization (`None` for the
+    /// public instance). System maps of the public instance are shared with
+    /// every organization.
+    pub fn is_available_in(&self, org_id: Option<&str>) -> bool {
+        match self.org_id.as_deref() {
+            Some(owner) => org_id == Some(owner),
+            None => org_id.is_none() || self.is_system_map(),
+        }
+    }
+
+    /// Average final score per play, if the map has been played.
+    pub fn average_score(&self) -> Option<f64> {
+        (self.play_count > 0).then(|| self.score_sum as f64 / self.play_count as f64)
+    }
 }
 
 /// Constraints for location selection beyond basic exclusion.
@@ -413,6 +599,14 @@ pub struct SelectionConstraints {
     /// Minimum distance in meters from all previous locations.
     /// Set to 0 to disable distance checking.
     pub min_distance_meters: f64,
+
+    /// Panorama IDs the players have seen in recent games.
+    /// These are never selected again.
+    pub exclude_panoramas: Vec<String>,
+
+    /// Country of the players' previous round.
+    /// Candidates in this country are ranked lower, but not excluded.
+    pub avoid_country: Option<String>,
 }
 
 impl SelectionConstraints {
@@ -423,12 +617,16 @@ impl SelectionConstraints {
 
     /// Create constraints that use relative spread ranking only.
     pub fn with_previous_locations(previous_locations: Vec<(f64, f64)>) -> Self {
-        Self { previous_locations, min_distance_meters: 0.0 }
+        Self { previous_locations, ..Self::default() }
     }
 
     /// Create constraints with minimum distance from previous locations.
     pub fn with_min_distance(previous_locations: Vec<(f64, f64)>, min_distance_km: f64) -> Self {
-        Self { previous_locations, min_distance_meters: min_distance_km * 1000.0 }
+        Self {
+            previous_locations,
+            min_distance_meters: min_distance_km * 1000.0,
+            ..Self::default()
+        }
     }
 
     /// Create constraints with an optional hard minimum distance.
@@ -442,11 +640,35 @@ impl SelectionConstraints {
         }
     }
 
+    /// Add the players' recent history: panoramas to skip and the country
+    /// of their previous round.
+    pub fn with_history(
+        mut self,
+        exclude_panoramas: Vec<String>,
+        avoid_country: Option<String>,
+    ) -> Self {
+        self.exclude_panoramas = exclude_panoramas;
+        self.avoid_country = avoid_country;
+        self
+    }
+
+    /// The same constraints without the players' recent panoramas.
+    ///
+    /// Used to retry when history excludes every location of a small map.
+    pub fn without_recent_panoramas(&self) -> Self {
+        Self { exclude_panoramas: Vec::new(), ..self.clone() }
+    }
+
     /// Whether previous locations exist and spread ranking should be applied.
     pub fn uses_spread_ranking(&self) -> bool {
         !self.previous_locations.is_empty()
     }
 
+    /// Whether candidates should be ranked at all, by spread or by country.
+    pub fn uses_candidate_ranking(&self) -> bool {
+        self.uses_spread_ranking() || self.avoid_country.is_some()
+    }
+
     /// Whether a hard minimum spread distance is configured.
     pub fn has_hard_min_distance(&self) -> bool {
         self.min_distance_meters > 0.0
@@ -512,6 +734,17 @@ pub trait LocationProvider: Send + Sync {
         &'a self,
         location_id: &'a str,
     ) -> Pin<Box<dyn Future<Output = Result<(), LocationError>> + Send + 'a>>;
+
+    /// Check that a game could start: the default map loads and yields a
+    /// location. Used by readiness checks.
+    fn self_test<'a>(
+        &'a self,
+    ) -> Pin<Box<dyn Future<Output = Result<(), LocationError>> + Send + 'a>> {
+        Box::pin(async move {
+            let map = self.get_default_map().await?;
+            self.select_location(&map.id, &[]).await.map(|_| ())
+        })
+    }
 }
 
 #[cfg(test)]
@@ -545,6 +778,26 @@ mod tests {
         assert_eq!(rules.hard_min_spread_distance_km(), None);
     }
 
+    #[test]
+    fn test_country_distribution_static_weight() {
+        assert_eq!(CountryDistribution::Proportional.static_weight("US"), None);
+        assert_eq!(CountryDistribution::Equal.static_weight("US"), Some(1));
+
+        let weights = [("US".to_string(), 5)].into_iter().collect();
+        let weighted = CountryDistribution::Weighted { weights };
+        assert_eq!(weighted.static_weight("US"), Some(5));
+        assert_eq!(weighted.static_weight("FR"), Some(0));
+
+        let area = CountryDistribution::Area;
+        assert!(area.static_weight("CA") > area.static_weight("FR"));
+        assert_eq!(area.static_weight("ZZ"), Some(1));
+        let population = CountryDistribution::Population;
+        assert!(population.static_weight("FR") > population.static_weight("CA"));
+
+        let json = serde_json::to_string(&CountryDistribution::Population).unwrap();
+        assert_eq!(json, r#"{"type":"population"}"#);
+    }
+
     #[test]
     fn test_map_rules_min_spread_distance() {
         // Test custom spread distance
@@ -556,6 +809,21 @@ mod tests {
         assert_eq!(rules.hard_min_spread_distance_km(), None);
     }
 
+    #[test]
+    fn test_map_rules_validate_and_normalize() {
+        let mut rules =
+            MapRules { countries: vec![" se".into(), "No".into()], ..Default::default() };
+        assert!(rules.validate().is_ok());
+        rules.normalize();
+        assert_eq!(rules.countries, vec!["SE", "NO"]);
+
+        let rules = MapRules { countries: vec!["XX".into()], ..Default::default() };
+        assert!(matches!(rules.validate(), Err(MapRulesError::Country(_))));
+
+        let rules = MapRules { min_year: Some(2020), max_year: Some(2010), ..Default::default() };
+        assert!(matches!(rules.validate(), Err(MapRulesError::YearRange)));
+    }
+
     #[test]
     fn test_selection_constraints_with_optional_min_distance() {
         let previous_locations = vec![(1.0, 2.0)];
@@ -0,0 +1,504 @@
+//! Display-name and chat moderation.
+//!
+//! Names are checked after folding them to a plain lowercase "skeleton":
+//! look-alike letters from other scripts, fullwidth and styled letters, and
+//! accents are mapped to ASCII, digits and symbols read as the letters they
+//! commonly stand in for, and separators are dropped. Offensive terms in the
+//! skeleton reject the name. Names that impersonate staff, advertise links,
+//! or mix look-alike letters into Latin text are accepted but flagged so a
+//! moderator can review them.
+//!
+//! Chat messages are checked word by word against the same offensive terms;
+//! matching words are masked rather than rejecting the message.
+
+/// Shortest accepted display name, in characters.
+pub const MIN_DISPLAY_NAME_CHARS: usize = 3;
+
+/// Longest accepted display name, in characters.
+pub const MAX_DISPLAY_NAME_CHARS: usize = 50;
+
+/// Combining marks allowed in one name (enough for real accents, not for
+/// stacked "zalgo" text).
+const MAX_COMBINING_MARKS: usize = 8;
+
+/// Offensive terms rejected anywhere in the skeleton, even inside words.
+const BLOCKED_TERMS: &[&str] = &[
+    "bitch", "cunt", "faggot", "fuck", "hitler", "kkk", "motherf", "nigg", "porn", "pussy",
+    "retard", "shit", "slut", "twat", "whore",
+];
+
+/// Offensive words rejected only when they stand alone, because they also
+/// occur inside harmless words ("class", "Dickens", "therapist").
+const BLOCKED_WORDS: &[&str] =
+    &["anal", "ass", "cock", "cum", "dick", "fag", "nazi", "piss", "rape", "sex", "tits"];
+
+/// Terms that suggest staff impersonation.
+const RESERVED_TERMS: &[&str] = &["admin", "dguess", "moderator", "official", "staff", "support"];
+
+/// Short reserved words, only matched when they stand alone.
+const RESERVED_WORDS: &[&str] = &["dev", "mod", "system"];
+
+/// Fragments of links and handles for other sites.
+const LINK_MARKERS: &[&str] = &["http", "www.", ".com", ".gg", ".net", ".org", ".io", "discord"];
+
+/// Why a name was rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum NameError {
+    /// Outside the allowed length
+    #[error(
+        "Display name must be between {MIN_DISPLAY_NAME_CHARS} and {MAX_DISPLAY_NAME_CHARS} characters"
+    )]
+    Length,
+    /// Control, invisible or excessive combining characters
+    #[error("Display name contains invalid characters")]
+    InvalidCharacters,
+    /// Contains an offensive term
+    #[error("This display name is not allowed")]
+    Offensive,
+}
+
+impl NameError {
+    /// Stable error code for API responses.
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::Length | Self::InvalidCharacters => "INVALID_DISPLAY_NAME",
+            Self::Offensive => "DISPLAY_NAME_NOT_ALLOWED",
+        }
+    }
+}
+
+/// Why an accepted name should be reviewed by a moderator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlagReason {
+    /// Looks like a staff or official account
+    Impersonation,
+    /// Contains a link or a handle for another site
+    Link,
+    /// Mixes look-alike letters from other scripts into Latin text
+    Confusables,
+    /// Reported as abusive by another player
+    Reported,
+}
+
+impl FlagReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Impersonation => "impersonation",
+            Self::Link => "link",
+            Self::Confusables => "confusables",
+            Self::Reported => "reported",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "impersonation" => Some(Self::Impersonation),
+            "link" => Some(Self::Link),
+            "confusables" => Some(Self::Confusables),
+            "reported" => Some(Self::Reported),
+            _ => None,
+        }
+    }
+}
+
+/// An accepted display name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckedName {
+    /// The name with surrounding whitespace removed
+    pub name: String,
+    /// Set when the name should be reviewed
+    pub flag: Option<FlagReason>,
+}
+
+/// Validate a display name typed by a user.
+///
+/// Returns the trimmed name and whether it should be flagged for review.
+pub fn check_display_name(input: &str) -> Result<CheckedName, NameError> {
+    let name = input.trim();
+
+    let length = name.chars().count();
+    if !(MIN_DISPLAY_NAME_CHARS..=MAX_DISPLAY_NAME_CHARS).contains(&length) {
+        return Err(NameError::Length);
+    }
+    if name.chars().any(|c| c.is_control() || is_invisible(c))
+        || name.chars().filter(|&c| is_combining_mark(c)).count() > MAX_COMBINING_MARKS
+    {
+        return Err(NameError::InvalidCharacters);
+    }
+
+    let skeleton = Skeleton::new(name);
+    if skeleton.contains_any(BLOCKED_TERMS) || skeleton.has_any_word(BLOCKED_WORDS) {
+        return Err(NameError::Offensive);
+    }
+
+    let lower = name.to_lowercase();
+    let flag = if skeleton.contains_any(RESERVED_TERMS) || skeleton.has_any_word(RESERVED_WORDS) {
+        Some(FlagReason::Impersonation)
+    } else if LINK_MARKERS.iter().any(|marker| lower.contains(marker)) {
+        Some(FlagReason::Link)
+    } else if skeleton.mixes_confusables {
+        Some(FlagReason::Confusables)
+    } else {
+        None
+    };
+
+    Ok(CheckedName { name: name.to_string(), flag })
+}
+
+/// A chat message after filtering.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilteredChat {
+    /// The message with offensive words masked
+    pub text: String,
+    /// Whether any word was masked
+    pub flagged: bool,
+}
+
+/// Mask offensive words in a chat message.
+///
+/// Each whitespace-separated word is checked on its own, so terms split
+/// across words ("f u c k") get through; the point is to catch the common
+/// case and feed a report, not to be airtight.
+pub fn filter_chat_message(input: &str) -> FilteredChat {
+    let mut text = String::with_capacity(input.len());
+    let mut flagged = false;
+    let mut rest = input;
+    while !rest.is_empty() {
+        let word_end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let (word, after) = rest.split_at(word_end);
+        if !word.is_empty() {
+            let skeleton = Skeleton::new(word);
+            if skeleton.contains_any(BLOCKED_TERMS) || skeleton.has_any_word(BLOCKED_WORDS) {
+                flagged = true;
+                text.extend(std::iter::repeat_n('*', word.chars().count()));
+            } else {
+                text.push_str(word);
+            }
+        }
+        let space_end = after.find(|c: char| !c.is_whitespace()).unwrap_or(after.len());
+        text.push_str(&after[..space_end]);
+        rest = &after[space_end..];
+    }
+    FilteredChat { text, flagged }
+}
+
+/// Fold text to lowercase ASCII letters, undoing look-alikes and
+/// letter-for-symbol substitutions. Characters that aren't letters after
+/// folding become spaces.
+pub fn normalize(input: &str) -> String {
+    input
+        .chars()
+        .filter(|&c| !is_invisible(c) && !is_combining_mark(c))
+        .flat_map(char::to_lowercase)
+        .map(|c| fold_char(c).map(|(letter, _)| letter).unwrap_or(' '))
+        .collect()
+}
+
+/// Normalized forms of a name used for matching.
+struct Skeleton {
+    /// All letters run together, so "f.u.c.k" reads as "fuck"
+    compact: String,
+    /// `compact` with repeated letters collapsed ("fuuuck" reads as "fuck")
+    collapsed: String,
+    /// Letters split at separators
+    words: Vec<String>,
+    /// Whether Latin letters are mixed with look-alikes from other scripts
+    mixes_confusables: bool,
+}
+
+impl Skeleton {
+    fn new(name: &str) -> Self {
+        let mut ascii_letters = false;
+        let mut confusables = false;
+        for c in name.chars().flat_map(char::to_lowercase) {
+            match fold_char(c) {
+                Some((_, true)) => confusables = true,
+                Some(_) if c.is_ascii_alphabetic() => ascii_letters = true,
+                _ => {}
+            }
+        }
+
+        let normalized = normalize(name);
+        let words: Vec<String> = normalized.split_whitespace().map(str::to_string).collect();
+        let compact = words.concat();
+        let collapsed = collapse_repeats(&compact);
+        Self { compact, collapsed, words, mixes_confusables: ascii_letters && confusables }
+    }
+
+    fn contains_any(&self, terms: &[&str]) -> bool {
+        terms.iter().any(|term| {
+            self.compact.contains(term)
+                // Terms with doubled letters would match too much once collapsed
+                || (collapse_repeats(term) == *term && self.collapsed.contains(term))
+        })
+    }
+
+    fn has_any_word(&self, words: &[&str]) -> bool {
+        self.words.iter().any(|word| {
+            let collapsed = collapse_repeats(word);
+            words.iter().any(|w| word == w || collapsed == *w)
+        })
+    }
+}
+
+/// Replace runs of the same letter with a single one.
+fn collapse_repeats(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if !out.ends_with(c) {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Map a lowercase character to the ASCII letter it reads as. The flag is
+/// set for look-alike letters from other scripts and styled forms.
+fn fold_char(c: char) -> Option<(char, bool)> {
+    if c.is_ascii_lowercase() {
+        return Some((c, false));
+    }
+    let leet = match c {
+        '0' => Some('o'),
+        '1' | '!' | '|' => Some('i'),
+        '3' => Some('e'),
+        '4' | '@' => Some('a'),
+        '5' | '$' => Some('s'),
+        '6' | '9' => Some('g'),
+        '7' | '+' => Some('t'),
+        '8' => Some('b'),
+        _ => None,
+    };
+    if let Some(letter) = leet {
+        return Some((letter, false));
+    }
+    let accented = match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' => Some('a'),
+        'ç' | 'ć' | 'č' => Some('c'),
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ę' => Some('e'),
+        'ì' | 'í' | 'î' | 'ï' | 'ı' => Some('i'),
+        'ñ' | 'ń' => Some('n'),
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' => Some('o'),
+        'ś' | 'š' => Some('s'),
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' => Some('u'),
+        'ý' | 'ÿ' => Some('y'),
+        'ž' | 'ź' | 'ż' => Some('z'),
+        _ => None,
+    };
+    if let Some(letter) = accented {
+        return Some((letter, false));
+    }
+    confusable(c).map(|letter| (letter, true))
+}
+
+/// ASCII letter a look-alike or styled character stands for.
+fn confusable(c: char) -> Option<char> {
+    let code = c as u32;
+    // Fullwidth Latin
+    if ('ａ'..='ｚ').contains(&c) {
+        return char::from_u32(code - 'ａ' as u32 + 'a' as u32);
+    }
+    // Circled Latin
+    if ('ⓐ'..='ⓩ').contains(&c) {
+        return char::from_u32(code - 'ⓐ' as u32 + 'a' as u32);
+    }
+    // Mathematical bold, italic, script, ... Latin: repeating blocks of 52
+    if (0x1D400..=0x1D6A3).contains(&code) {
+        let offset = (code - 0x1D400) % 52;
+        return char::from_u32('a' as u32 + offset % 26);
+    }
+    let letter = match c {
+        // Cyrillic
+        'а' => 'a',
+        'в' | 'ь' => 'b',
+        'с' => 'c',
+        'ԁ' => 'd',
+        'е' | 'ё' => 'e',
+        'һ' | 'н' => 'h',
+        'і' | 'ї' => 'i',
+        'ј' => 'j',
+        'к' => 'k',
+        'м' => 'm',
+        'п' => 'n',
+        'о' => 'o',
+        'р' => 'p',
+        'ԛ' => 'q',
+        'г' => 'r',
+        'ѕ' => 's',
+        'т' => 't',
+        'ц' | 'џ' => 'u',
+        'ѵ' => 'v',
+        'ш' | 'щ' | 'ԝ' => 'w',
+        'х' => 'x',
+        'у' => 'y',
+        // Greek
+        'α' => 'a',
+        'β' => 'b',
+        'ε' => 'e',
+        'η' => 'n',
+        'ι' => 'i',
+        'κ' => 'k',
+        'ν' => 'v',
+        'ο' => 'o',
+        'ρ' => 'p',
+        'τ' => 't',
+        'υ' => 'u',
+        'χ' => 'x',
+        'γ' => 'y',
+        'ζ' => 'z',
+        // Latin extensions and small capitals
+        'ɡ' => 'g',
+        'ɑ' => 'a',
+        'ʀ' => 'r',
+        'ᴀ' => 'a',
+        'ʙ' => 'b',
+        'ᴄ' => 'c',
+        'ᴅ' => 'd',
+        'ᴇ' => 'e',
+        'ɢ' => 'g',
+        'ʜ' => 'h',
+        'ɪ' => 'i',
+        'ᴊ' => 'j',
+        'ᴋ' => 'k',
+        'ʟ' => 'l',
+        'ᴍ' => 'm',
+        'ɴ' => 'n',
+        'ᴏ' => 'o',
+        'ᴘ' => 'p',
+        'ꜱ' => 's',
+        'ᴛ' => 't',
+        'ᴜ' => 'u',
+        'ᴠ' => 'v',
+        'ᴡ' => 'w',
+        'ʏ' => 'y',
+        'ᴢ' => 'z',
+        _ => return None,
+    };
+    Some(letter)
+}
+
+/// Zero-width and other invisible formatting characters.
+fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00AD}'
+            | '\u{034F}'
+            | '\u{115F}'
+            | '\u{1160}'
+            | '\u{180E}'
+            | '\u{200B}'..='\u{200F}'
+            | '\u{202A}'..='\u{202E}'
+            | '\u{2060}'..='\u{2064}'
+            | '\u{2066}'..='\u{206F}'
+            | '\u{3164}'
+            | '\u{FE00}'..='\u{FE0F}'
+            | '\u{FEFF}'
+            | '\u{FFA0}'
+    )
+}
+
+/// Combining diacritical marks.
+fn is_combining_mark(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0300}'..='\u{036F}'
+            | '\u{1AB0}'..='\u{1AFF}'
+            | '\u{1DC0}'..='\u{1DFF}'
+            | '\u{20D0}'..='\u{20FF}'
+            | '\u{FE20}'..='\u{FE2F}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flag(name: &str) -> Option<FlagReason> {
+        check_display_name(name).unwrap().flag
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("H3ll0 W0rld"), "hello world");
+        assert_eq!(normalize("ＦＵＬＬ"), "full");
+        assert_eq!(normalize("рауpal"), "paypal");
+        assert_eq!(normalize("𝐛𝐨𝐥𝐝"), "bold");
+        assert_eq!(normalize("Zoë_Ünal"), "zoe unal");
+        assert_eq!(normalize("a\u{200B}b"), "ab");
+    }
+
+    #[test]
+    fn test_clean_names() {
+        for name in ["Swift Explorer", "Zoë", "Grass Hopper", "Classic Dickens", "Therapist"] {
+            let checked = check_display_name(name);
+            assert!(checked.is_ok(), "{name} should be allowed");
+        }
+        assert_eq!(
+            check_display_name("  Björn  "),
+            Ok(CheckedName { name: "Björn".to_string(), flag: None })
+        );
+        assert_eq!(flag("Александр"), None);
+    }
+
+    #[test]
+    fn test_length_and_characters() {
+        assert_eq!(check_display_name("ab"), Err(NameError::Length));
+        assert_eq!(check_display_name(&"x".repeat(51)), Err(NameError::Length));
+        assert_eq!(check_display_name("ééé").map(|c| c.name), Ok("ééé".to_string()));
+        assert_eq!(check_display_name("bad\u{200B}name"), Err(NameError::InvalidCharacters));
+        assert_eq!(check_display_name("tab\tname"), Err(NameError::InvalidCharacters));
+        assert_eq!(
+            check_display_name(&format!("z{}", "\u{0301}".repeat(20))),
+            Err(NameError::InvalidCharacters)
+        );
+    }
+
+    #[test]
+    fn test_offensive_names() {
+        for name in
+            ["fuck", "Sh1tHead", "f.u.c.k", "FUUUUCK", "ｆｕｃｋ", "fuсk", "ѕhit", "big ass"]
+        {
+            assert_eq!(check_display_name(name), Err(NameError::Offensive), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_flagged_names() {
+        assert_eq!(flag("Admin Bob"), Some(FlagReason::Impersonation));
+        assert_eq!(flag("DGuesser Official"), Some(FlagReason::Impersonation));
+        assert_eq!(flag("4dm1n"), Some(FlagReason::Impersonation));
+        assert_eq!(flag("the mod"), Some(FlagReason::Impersonation));
+        assert_eq!(flag("Modern Mapper"), None);
+        assert_eq!(flag("join discord.gg/x"), Some(FlagReason::Link));
+        assert_eq!(flag("Pаul"), Some(FlagReason::Confusables));
+    }
+
+    #[test]
+    fn test_filter_chat_message() {
+        let clean = filter_chat_message("gg wp, nice guess in Essex!");
+        assert_eq!(
+            clean,
+            FilteredChat { text: "gg wp, nice guess in Essex!".into(), flagged: false }
+        );
+
+        let filtered = filter_chat_message("you  sh1t\nplayer");
+        assert!(filtered.flagged);
+        assert_eq!(filtered.text, "you  ****\nplayer");
+
+        assert_eq!(filter_chat_message("what an ass").text, "what an ***");
+        assert_eq!(filter_chat_message("ｆｕｃｋ off").text, "**** off");
+        assert!(!filter_chat_message("classic pass").flagged);
+    }
+
+    #[test]
+    fn test_flag_reason_round_trip() {
+        for reason in [
+            FlagReason::Impersonation,
+            FlagReason::Link,
+            FlagReason::Confusables,
+            FlagReason::Reported,
+        ] {
+            assert_eq!(FlagReason::parse(reason.as_str()), Some(reason));
+        }
+    }
+}
@@ -0,0 +1,177 @@
+//! Player reports.
+//!
+//! Players report each other for cheating or abuse. Reports start open, may
+//! be taken into review, and end actioned or dismissed. A closed report can
+//! be reopened if it was closed by mistake.
+
+use serde::{Deserialize, Serialize};
+
+/// Longest accepted chat excerpt, in characters.
+pub const MAX_CHAT_EXCERPT_CHARS: usize = 500;
+
+/// Longest accepted reporter or moderator note, in characters.
+pub const MAX_REPORT_NOTE_CHARS: usize = 1000;
+
+/// What a player is reported for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportCategory {
+    /// Using outside help or tools during a game
+    Cheating,
+    /// Offensive or impersonating display name
+    AbusiveName,
+    /// Harassment or slurs in chat
+    AbusiveChat,
+    /// Deliberately ruining games for others
+    Griefing,
+    /// Anything else; needs a note
+    Other,
+}
+
+impl ReportCategory {
+    pub const ALL: [Self; 5] =
+        [Self::Cheating, Self::AbusiveName, Self::AbusiveChat, Self::Griefing, Self::Other];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Cheating => "cheating",
+            Self::AbusiveName => "abusive_name",
+            Self::AbusiveChat => "abusive_chat",
+            Self::Griefing => "griefing",
+            Self::Other => "other",
+        }
+    }
+
+    /// Whether the report must name the game it happened in.
+    pub fn requires_game(self) -> bool {
+        matches!(self, Self::Cheating | Self::Griefing)
+    }
+
+    /// Whether the report must quote the chat it is about.
+    pub fn requires_chat_excerpt(self) -> bool {
+        matches!(self, Self::AbusiveChat)
+    }
+}
+
+impl std::fmt::Display for ReportCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ReportCategory {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|category| category.as_str() == s)
+            .ok_or_else(|| format!("Unknown report category: {s}"))
+    }
+}
+
+/// Where a report is in the moderation workflow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportStatus {
+    /// Waiting for a moderator
+    #[default]
+    Open,
+    /// A moderator is looking into it
+    Reviewing,
+    /// Action was taken against the reported player
+    Actioned,
+    /// No action needed
+    Dismissed,
+}
+
+impl ReportStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Reviewing => "reviewing",
+            Self::Actioned => "actioned",
+            Self::Dismissed => "dismissed",
+        }
+    }
+
+    /// Whether the report still needs a decision.
+    pub fn is_open(self) -> bool {
+        matches!(self, Self::Open | Self::Reviewing)
+    }
+
+    /// Whether a moderator may move a report from this status to `next`.
+    pub fn can_transition_to(self, next: Self) -> bool {
+        match (self, next) {
+            (Self::Open, Self::Reviewing) => true,
+            // Closing straight from open is fine for obvious cases
+            (Self::Open | Self::Reviewing, Self::Actioned | Self::Dismissed) => true,
+            (Self::Reviewing, Self::Open) => true,
+            // Reopen a report closed by mistake
+            (Self::Actioned | Self::Dismissed, Self::Open) => true,
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Display for ReportStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ReportStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "open" => Ok(Self::Open),
+            "reviewing" => Ok(Self::Reviewing),
+            "actioned" => Ok(Self::Actioned),
+            "dismissed" => Ok(Self::Dismissed),
+            _ => Err(format!("Unknown report status: {s}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_category_round_trip() {
+        for category in ReportCategory::ALL {
+            assert_eq!(category.as_str().parse::<ReportCategory>(), Ok(category));
+        }
+        assert!("spam".parse::<ReportCategory>().is_err());
+    }
+
+    #[test]
+    fn test_category_requirements() {
+        assert!(ReportCategory::Cheating.requires_game());
+        assert!(!ReportCategory::AbusiveName.requires_game());
+        assert!(ReportCategory::AbusiveChat.requires_chat_excerpt());
+        assert!(!ReportCategory::Other.requires_chat_excerpt());
+    }
+
+    #[test]
+    fn test_status_transitions() {
+        use ReportStatus::*;
+        assert!(Open.can_transition_to(Reviewing));
+        assert!(Open.can_transition_to(Dismissed));
+        assert!(Reviewing.can_transition_to(Actioned));
+        assert!(Reviewing.can_transition_to(Open));
+        assert!(Actioned.can_transition_to(Open));
+        assert!(!Actioned.can_transition_to(Dismissed));
+        assert!(!Dismissed.can_transition_to(Reviewing));
+        assert!(!Open.can_transition_to(Open));
+    }
+
+    #[test]
+    fn test_status_serde() {
+        assert_eq!(serde_json::to_string(&ReportStatus::Reviewing).unwrap(), "\"reviewing\"");
+        assert_eq!("actioned".parse::<ReportStatus>(), Ok(ReportStatus::Actioned));
+        assert!(ReportStatus::Open.is_open());
+        assert!(!ReportStatus::Dismissed.is_open());
+    }
+}
@@ -1,8 +1,25 @@
 //! Street View URL parsing utilities.
 //!
-//! This module provides utilities for parsing Google Maps Street View URLs
-//! to extract coordinates, panorama IDs, and other metadata.
+//! This module parses the URL shapes Google Maps uses for Street View links
+//! to extract coordinates, panorama IDs, and view parameters:
+//!
+//! - Map URLs: `https://www.google.com/maps/@48.8584,2.2945,3a,75y,90h,95t/data=!3m6!1e1!3m4!1s{pano}!2e0`
+//! - Maps URLs API: `https://www.google.com/maps/@?api=1&map_action=pano&viewpoint=48.8584,2.2945&pano={pano}`
+//! - Legacy links: `https://maps.google.com/maps?layer=c&cbll=48.8584,2.2945&cbp=12,90,,0,5&panoid={pano}`
+//! - Embeds: `https://www.google.com/maps/embed?pb=!4v1!6m8!1m7!1s{pano}!2m2!1d48.8584!2d2.2945!3f90!4f0!5f0.78`
+//!   and `https://www.google.com/maps/embed/v1/streetview?location=48.8584,2.2945&heading=90`
+//!
+//! Query parameters may come in any order, and any Google country domain is
+//! accepted. Short links (`maps.app.goo.gl`, `goo.gl/maps`) carry no location
+//! themselves; they fail with [`StreetViewUrlError::UnresolvedShortLink`] so
+//! the caller can follow the redirect and parse the target instead.
+//!
+//! It also defines [`ImageryProvider`], the street-level imagery services a
+//! location can come from, and how each identifies its images, and
+//! [`QuotaBreaker`], which pauses metadata API calls once the quota is spent.
 
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 /// Errors that can occur during Street View URL parsing.
@@ -22,6 +39,9 @@ pub enum StreetViewUrlError {
 
     #[error("Not a Street View URL")]
     NotStreetViewUrl,
+
+    #[error("Short link must be resolved before parsing")]
+    UnresolvedShortLink,
 }
 
 /// Parsed information from a Street View URL.
@@ -43,11 +63,9 @@ pub struct StreetViewUrlInfo {
 
 /// Parse a Google Maps Street View URL to extract location information.
 ///
-/// Supports various URL formats:
-/// - `https://www.google.com/maps/@48.8584,2.2945,3a,75y,90t/data=...`
-/// - `https://www.google.com/maps/@48.8584,2.2945,17z/data=!3m1!4b1!4m5...`
-/// - `https://maps.google.com/...`
-/// - Short URLs with coordinates embedded
+/// See the [module docs](self) for the supported URL shapes. Coordinates are
+/// taken from explicit query parameters first, then embed data, then the
+/// `@lat,lng` path segment, then place data.
 ///
 /// # Examples
 ///
@@ -60,195 +78,580 @@ pub struct StreetViewUrlInfo {
 /// assert!((info.lng - 2.2945).abs() < 0.0001);
 /// ```
 pub fn parse_streetview_url(url: &str) -> Result<StreetViewUrlInfo, StreetViewUrlError> {
-    let url = url.trim();
+    let parts = UrlParts::split(url.trim());
 
-    // Check if it's a Google Maps URL
-    if !url.contains("google.com/maps") && !url.contains("maps.google.com") {
+    if parts.is_short_link() {
+        return Err(StreetViewUrlError::UnresolvedShortLink);
+    }
+    if !parts.is_google_maps() {
         return Err(StreetViewUrlError::NotStreetViewUrl);
     }
 
-    // Try to extract coordinates from the @ symbol pattern
-    // Format: @lat,lng,... or @lat,lng
-    let coords = extract_coordinates_from_at(url)?;
+    let params = QueryParams::parse(parts.query);
+    let data = DataTokens::parse(parts.data_block().unwrap_or_default());
+    let embed = DataTokens::parse(params.get("pb").unwrap_or_default());
+    let view = parts.at_segment().map(AtSegment::parse);
 
-    // Try to extract panorama ID from data parameter
-    let panorama_id = extract_panorama_id(url);
+    let (lat, lng) = if let Some(pair) = params.first(&["viewpoint", "cbll", "location"]) {
+        parse_coordinate_pair(pair)?
+    } else if let Some(coords) = embed.streetview_coordinates() {
+        validate_coordinates(coords)?
+    } else if let Some(view) = &view
+        && view.has_coordinates()
+    {
+        view.coordinates()?
+    } else if let Some(coords) = data.place_coordinates() {
+        validate_coordinates(coords)?
+    } else {
+        return Err(StreetViewUrlError::MissingCoordinates);
+    };
+
+    let panorama_id = params
+        .first(&["pano", "panoid"])
+        .filter(|pano| is_panorama_id(pano))
+        .map(str::to_string)
+        .or_else(|| embed.panorama_id())
+        .or_else(|| data.panorama_id());
 
-    // Try to extract view parameters (heading, fov, pitch)
-    let (heading, fov, pitch) = extract_view_params(url);
+    let heading = params
+        .number("heading")
+        .or_else(|| view.as_ref().and_then(|v| v.heading))
+        .or_else(|| embed.number(3, 'f'))
+        .or_else(|| params.cbp_heading());
+    let pitch = params
+        .number("pitch")
+        .or_else(|| view.as_ref().and_then(|v| v.pitch))
+        .or_else(|| embed.number(4, 'f'));
+    let fov = params.number("fov").or_else(|| view.as_ref().and_then(|v| v.fov));
+
+    Ok(StreetViewUrlInfo { lat, lng, panorama_id, heading, fov, pitch })
+}
 
-    Ok(StreetViewUrlInfo { lat: coords.0, lng: coords.1, panorama_id, heading, fov, pitch })
+/// Whether a URL is a Google Maps short link, which redirects to a full URL.
+pub fn is_short_link(url: &str) -> bool {
+    UrlParts::split(url.trim()).is_short_link()
 }
 
-/// Extract coordinates from the @lat,lng pattern in a URL.
-fn extract_coordinates_from_at(url: &str) -> Result<(f64, f64), StreetViewUrlError> {
-    // Find the @ symbol and extract coordinates
-    let at_pos = url.find('@').ok_or(StreetViewUrlError::MissingCoordinates)?;
+/// Whether a URL points at Google Maps on any Google country domain.
+pub fn is_google_maps_url(url: &str) -> bool {
+    UrlParts::split(url.trim()).is_google_maps()
+}
 
-    let after_at = &url[at_pos + 1..];
+/// Parse multiple Street View URLs, returning successful parses and errors.
+pub fn parse_streetview_urls(urls: &[&str]) -> Vec<Result<StreetViewUrlInfo, StreetViewUrlError>> {
+    urls.iter().map(|url| parse_streetview_url(url)).collect()
+}
 
-    // Split by comma and take first two parts
-    let parts: Vec<&str> = after_at.split(',').collect();
-    if parts.len() < 2 {
-        return Err(StreetViewUrlError::MissingCoordinates);
+/// Validate a batch of URLs and return only the valid ones with their info.
+pub fn validate_streetview_urls<'a>(urls: &'a [&'a str]) -> Vec<(&'a str, StreetViewUrlInfo)> {
+    urls.iter().filter_map(|url| parse_streetview_url(url).ok().map(|info| (*url, info))).collect()
+}
+
+// =============================================================================
+// Imagery providers
+// =============================================================================
+
+/// Maximum length of a stored image ID
+const MAX_IMAGE_ID_LEN: usize = 100;
+
+/// Errors from validating a location against its imagery provider.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ImageryError {
+    #[error("Unknown imagery provider: {0}")]
+    UnknownProvider(String),
+
+    #[error("{} locations need an image ID", .0.display_name())]
+    MissingImageId(ImageryProvider),
+
+    #[error("Invalid {} image ID", .0.display_name())]
+    InvalidImageId(ImageryProvider),
+}
+
+/// Street-level imagery service a location is shown with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageryProvider {
+    /// Google Street View; images are panorama IDs
+    #[default]
+    #[serde(rename = "google_streetview")]
+    GoogleStreetView,
+    /// Mapillary; images are numeric image keys
+    Mapillary,
+    /// Bing Maps Streetside; shown by coordinates
+    BingStreetside,
+    /// Generated development data without real imagery
+    Sample,
+}
+
+impl ImageryProvider {
+    /// All providers, in display order.
+    pub const ALL: [Self; 4] =
+        [Self::GoogleStreetView, Self::Mapillary, Self::BingStreetside, Self::Sample];
+
+    /// Stored and serialized name.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::GoogleStreetView => "google_streetview",
+            Self::Mapillary => "mapillary",
+            Self::BingStreetside => "bing_streetside",
+            Self::Sample => "sample",
+        }
     }
 
-    // Parse latitude (first part)
-    let lat_str = parts[0].trim();
-    let lat: f64 =
-        lat_str.parse().map_err(|_| StreetViewUrlError::InvalidLatitude(lat_str.to_string()))?;
+    /// Human-readable name.
+    pub fn display_name(self) -> &'static str {
+        match self {
+            Self::GoogleStreetView => "Google Street View",
+            Self::Mapillary => "Mapillary",
+            Self::BingStreetside => "Bing Streetside",
+            Self::Sample => "Sample",
+        }
+    }
 
-    // Validate latitude range
-    if !(-90.0..=90.0).contains(&lat) {
-        return Err(StreetViewUrlError::InvalidLatitude(format!(
-            "{lat} is out of range [-90, 90]"
-        )));
+    /// Whether the viewer needs an image ID. Providers shown by coordinates
+    /// (and Google, which falls back to the nearest panorama) do not.
+    pub fn requires_image_id(self) -> bool {
+        matches!(self, Self::Mapillary)
     }
 
-    // Parse longitude (second part, may have suffix like 'z' or 'a')
-    let lng_part = parts[1];
-    // Remove any letter suffix (like 'z', 'a', 'y', 't', etc.)
-    let lng_str: String =
-        lng_part.chars().take_while(|c| c.is_ascii_digit() || *c == '.' || *c == '-').collect();
+    /// Whether images can be checked against the Street View metadata API.
+    pub fn supports_metadata_checks(self) -> bool {
+        matches!(self, Self::GoogleStreetView)
+    }
 
-    let lng: f64 =
-        lng_str.parse().map_err(|_| StreetViewUrlError::InvalidLongitude(lng_part.to_string()))?;
+    /// Whether location packs can hold this provider's locations. Packs are
+    /// built from Google coverage only.
+    pub fn supports_packs(self) -> bool {
+        matches!(self, Self::GoogleStreetView)
+    }
 
-    // Validate longitude range
-    if !(-180.0..=180.0).contains(&lng) {
-        return Err(StreetViewUrlError::InvalidLongitude(format!(
-            "{lng} is out of range [-180, 180]"
-        )));
+    /// Check that an image ID has the shape this provider uses.
+    ///
+    /// Google and Bing IDs are opaque; locations imported without a panorama
+    /// get a generated coordinate ID, so only length and whitespace are
+    /// checked. Mapillary image keys are numeric.
+    pub fn validate_image_id(self, image_id: Option<&str>) -> Result<(), ImageryError> {
+        let Some(image_id) = image_id else {
+            return if self.requires_image_id() {
+                Err(ImageryError::MissingImageId(self))
+            } else {
+                Ok(())
+            };
+        };
+
+        let valid = match self {
+            Self::Mapillary => {
+                image_id.len() <= 20
+                    && !image_id.is_empty()
+                    && image_id.bytes().all(|b| b.is_ascii_digit())
+            }
+            Self::GoogleStreetView | Self::BingStreetside | Self::Sample => {
+                !image_id.is_empty()
+                    && image_id.len() <= MAX_IMAGE_ID_LEN
+                    && !image_id.chars().any(char::is_whitespace)
+            }
+        };
+        if valid { Ok(()) } else { Err(ImageryError::InvalidImageId(self)) }
     }
+}
 
-    Ok((lat, lng))
+impl std::fmt::Display for ImageryProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
-/// Simple percent-decode for URL parameters.
-fn percent_decode(s: &str) -> String {
-    let mut result = String::with_capacity(s.len());
-    let mut chars = s.chars().peekable();
-
-    while let Some(c) = chars.next() {
-        if c == '%' {
-            let hex: String = chars.by_ref().take(2).collect();
-            if hex.len() == 2
-                && let Ok(byte) = u8::from_str_radix(&hex, 16)
-            {
-                result.push(byte as char);
-                continue;
-            }
-            result.push('%');
-            result.push_str(&hex);
-        } else if c == '+' {
-            result.push(' ');
-        } else {
-            result.push(c);
-        }
+impl std::str::FromStr for ImageryProvider {
+    type Err = ImageryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|provider| provider.as_str() == s)
+            .ok_or_else(|| ImageryError::UnknownProvider(s.to_string()))
     }
+}
+
+// =============================================================================
+// Metadata API quota
+// =============================================================================
+
+/// Metadata API status returned once the daily or per-minute quota is spent.
+pub const OVER_QUERY_LIMIT: &str = "OVER_QUERY_LIMIT";
+
+/// Redis key where processes share the [`QuotaBreaker`]
+pub const QUOTA_REDIS_KEY: &str = "streetview:quota";
+
+/// Pause after the first `OVER_QUERY_LIMIT`; doubled for each further one
+const QUOTA_BASE_BACKOFF_SECS: i64 = 10;
 
-    result
+/// Longest pause between probes of an exhausted quota
+const QUOTA_MAX_BACKOFF_SECS: i64 = 60 * 60;
+
+/// Whether the metadata API may be called, according to a [`QuotaBreaker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaState {
+    /// Quota available
+    Closed,
+    /// Quota exhausted; no requests before `until`
+    Open { until: DateTime<Utc> },
+    /// Backoff expired; the next request probes whether quota is back
+    HalfOpen,
 }
 
-/// Extract panorama ID from the data parameter.
-/// Panorama IDs are typically in the format: !1s{panorama_id}!2e...
-fn extract_panorama_id(url: &str) -> Option<String> {
-    // Look for !1s pattern followed by the panorama ID
-    if let Some(start) = url.find("!1s") {
-        let after_marker = &url[start + 3..];
-        // Find the end of the panorama ID (next ! or end of string)
-        let end = after_marker.find('!').unwrap_or(after_marker.len());
-        let pano_id = &after_marker[..end];
-
-        // Panorama IDs are typically alphanumeric with some special chars
-        if !pano_id.is_empty() && pano_id.len() < 100 {
-            // URL decode if necessary
-            let decoded = percent_decode(pano_id);
-            return Some(decoded);
+/// Circuit breaker for the Street View metadata API quota.
+///
+/// Every caller of the metadata API (seeder, URL importer, health checker)
+/// should check [`QuotaBreaker::state`] before a request and report
+/// `OVER_QUERY_LIMIT` responses through [`QuotaBreaker::trip`]. The pause
+/// doubles with each consecutive trip up to an hour, plus up to 25% jitter so
+/// separate processes don't all probe at once. The state is serializable so
+/// it can be shared between processes.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QuotaBreaker {
+    /// `OVER_QUERY_LIMIT` responses since the last successful request
+    pub consecutive_trips: u32,
+    /// No requests are sent before this time
+    pub open_until: Option<DateTime<Utc>>,
+    /// When the quota was last reported exhausted
+    pub last_tripped_at: Option<DateTime<Utc>>,
+}
+
+impl QuotaBreaker {
+    /// Current state of the breaker.
+    pub fn state(&self, now: DateTime<Utc>) -> QuotaState {
+        match self.open_until {
+            Some(until) if now < until => QuotaState::Open { until },
+            Some(_) => QuotaState::HalfOpen,
+            None => QuotaState::Closed,
         }
     }
 
-    // Alternative format: pano= parameter
-    if let Some(start) = url.find("pano=") {
-        let after_marker = &url[start + 5..];
-        let end = after_marker.find('&').unwrap_or(after_marker.len());
-        let pano_id = &after_marker[..end];
+    /// Record an `OVER_QUERY_LIMIT` response, returning when requests may
+    /// resume.
+    pub fn trip(&mut self, now: DateTime<Utc>) -> DateTime<Utc> {
+        use rand::RngExt;
 
-        if !pano_id.is_empty() {
-            let decoded = percent_decode(pano_id);
-            return Some(decoded);
-        }
+        self.consecutive_trips = self.consecutive_trips.saturating_add(1);
+        let backoff = quota_backoff_secs(self.consecutive_trips);
+        let jitter = rand::rng().random_range(0..=backoff / 4);
+        let until = now + chrono::Duration::seconds(backoff + jitter);
+
+        // Concurrent lookups may trip together; keep the latest resume time
+        self.open_until = Some(self.open_until.map_or(until, |current| current.max(until)));
+        self.last_tripped_at = Some(now);
+        self.open_until.unwrap_or(until)
     }
 
-    None
+    /// Record a request the API answered normally. Returns whether the
+    /// breaker changed, i.e. the quota is back.
+    pub fn record_success(&mut self) -> bool {
+        let changed = self.consecutive_trips > 0 || self.open_until.is_some();
+        self.consecutive_trips = 0;
+        self.open_until = None;
+        changed
+    }
 }
 
-/// Extract view parameters (heading, fov, pitch) from URL.
-/// Format: ...3a,75y,90t... where:
-/// - 3a = ??? (some kind of mode marker)
-/// - 75y = field of view (75 degrees)
-/// - 90t = pitch (tilt)
-/// - h= heading
-fn extract_view_params(url: &str) -> (Option<f64>, Option<f64>, Option<f64>) {
-    let mut heading = None;
-    let mut fov = None;
-    let mut pitch = None;
-
-    // Look for parameters in the URL path after @
-    if let Some(at_pos) = url.find('@') {
-        let after_at = &url[at_pos + 1..];
-        let parts: Vec<&str> =
-            after_at.split(&['/', '?', '!'][..]).next().unwrap_or("").split(',').collect();
-
-        for part in parts.iter().skip(2) {
-            // Skip lat,lng
-            if part.ends_with('y') {
-                // FOV parameter
-                if let Ok(v) = part.trim_end_matches('y').parse::<f64>() {
-                    fov = Some(v);
-                }
-            } else if part.ends_with('t') {
-                // Pitch/tilt parameter
-                if let Ok(v) = part.trim_end_matches('t').parse::<f64>() {
-                    pitch = Some(v);
-                }
-            } else if part.ends_with('h') {
-                // Heading parameter
-                if let Ok(v) = part.trim_end_matches('h').parse::<f64>() {
-                    heading = Some(v);
-                }
+/// Pause in seconds after `trips` consecutive `OVER_QUERY_LIMIT` responses,
+/// before jitter.
+fn quota_backoff_secs(trips: u32) -> i64 {
+    let doublings = trips.saturating_sub(1).min(16);
+    (QUOTA_BASE_BACKOFF_SECS << doublings).min(QUOTA_MAX_BACKOFF_SECS)
+}
+
+// =============================================================================
+// URL structure
+// =============================================================================
+
+/// A URL split into host, path, and query string.
+struct UrlParts<'a> {
+    /// Lower-cased host without port
+    host: String,
+    path: &'a str,
+    query: &'a str,
+}
+
+impl<'a> UrlParts<'a> {
+    /// Split a URL. The scheme is optional, so pasted `www.google.com/...`
+    /// links work too.
+    fn split(url: &'a str) -> Self {
+        let rest = url
+            .strip_prefix("https://")
+            .or_else(|| url.strip_prefix("http://"))
+            .or_else(|| url.strip_prefix("//"))
+            .unwrap_or(url);
+        let rest = rest.split('#').next().unwrap_or_default();
+
+        let host_end = rest.find(['/', '?']).unwrap_or(rest.len());
+        let host = rest[..host_end].split(':').next().unwrap_or_default().to_ascii_lowercase();
+
+        let after_host = &rest[host_end..];
+        let (path, query) = after_host.split_once('?').unwrap_or((after_host, ""));
+
+        Self { host, path, query }
+    }
+
+    fn is_short_link(&self) -> bool {
+        self.host == "maps.app.goo.gl" || (self.host == "goo.gl" && self.path.starts_with("/maps"))
+    }
+
+    /// Whether this is a Maps URL on any Google country domain
+    /// (`google.fr`, `google.co.uk`, `maps.google.com.au`, ...).
+    fn is_google_maps(&self) -> bool {
+        let host = self.host.strip_prefix("www.").unwrap_or(&self.host);
+        let (maps_subdomain, host) = match host.strip_prefix("maps.") {
+            Some(rest) => (true, rest),
+            None => (false, host),
+        };
+        let Some(suffix) = host.strip_prefix("google.") else {
+            return false;
+        };
+        let valid_suffix = suffix.split('.').count() <= 2
+            && suffix.split('.').all(|label| {
+                (2..=3).contains(&label.len()) && label.bytes().all(|b| b.is_ascii_lowercase())
+            });
+
+        valid_suffix && (maps_subdomain || self.path == "/maps" || self.path.starts_with("/maps/"))
+    }
+
+    /// The `lat,lng,...` view segment following `@` in the path.
+    fn at_segment(&self) -> Option<&'a str> {
+        let at = self.path.find('@')?;
+        let segment = &self.path[at + 1..];
+        Some(segment.split('/').next().unwrap_or_default())
+    }
+
+    /// The `!`-separated data block of a `/data=...` path segment.
+    fn data_block(&self) -> Option<&'a str> {
+        self.path.split('/').find_map(|segment| segment.strip_prefix("data="))
+    }
+}
+
+/// Percent-decoded query parameters, in order.
+struct QueryParams(Vec<(String, String)>);
+
+impl QueryParams {
+    fn parse(query: &str) -> Self {
+        let params = query
+            .split('&')
+            .filter_map(|pair| {
+                let (key, value) = pair.split_once('=')?;
+                // Links copied out of HTML sometimes keep `&amp;` separators
+                let key = key.strip_prefix("amp;").unwrap_or(key);
+                Some((percent_decode(key).to_ascii_lowercase(), percent_decode(value)))
+            })
+            .collect();
+        Self(params)
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.0.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    /// The value of the first of `names` present and non-empty.
+    fn first(&self, names: &[&str]) -> Option<&str> {
+        names.iter().filter_map(|name| self.get(name)).find(|value| !value.trim().is_empty())
+    }
+
+    fn number(&self, name: &str) -> Option<f64> {
+        self.get(name).and_then(|value| parse_finite(value.trim()))
+    }
+
+    /// Heading from a legacy `cbp=1,{heading},,{zoom},{pitch}` parameter.
+    fn cbp_heading(&self) -> Option<f64> {
+        self.get("cbp")?.split(',').nth(1).and_then(|value| parse_finite(value.trim()))
+    }
+}
+
+/// The `@lat,lng,3a,75y,90h,95t` segment of a map URL.
+struct AtSegment<'a> {
+    lat: Option<&'a str>,
+    lng: Option<&'a str>,
+    heading: Option<f64>,
+    fov: Option<f64>,
+    pitch: Option<f64>,
+}
+
+impl<'a> AtSegment<'a> {
+    fn parse(segment: &'a str) -> Self {
+        let mut parts = segment.split(',');
+        let lat = parts.next().filter(|s| !s.is_empty());
+        let lng = parts.next().filter(|s| !s.is_empty());
+
+        let mut view = Self { lat, lng, heading: None, fov: None, pitch: None };
+        for part in parts {
+            let Some(suffix) = part.chars().last() else {
+                continue;
+            };
+            let value = parse_finite(&part[..part.len() - suffix.len_utf8()]);
+            match suffix {
+                'h' => view.heading = value,
+                'y' => view.fov = value,
+                't' => view.pitch = value,
+                _ => {}
             }
         }
+        view
     }
 
-    // Also check for explicit h= parameter in query string
-    if heading.is_none()
-        && let Some(start) = url.find("h=")
-    {
-        let after = &url[start + 2..];
-        let end = after.find(&['&', '!'][..]).unwrap_or(after.len());
-        if let Ok(v) = after[..end].parse::<f64>() {
-            heading = Some(v);
-        }
+    fn has_coordinates(&self) -> bool {
+        self.lat.is_some()
     }
 
-    (heading, fov, pitch)
+    fn coordinates(&self) -> Result<(f64, f64), StreetViewUrlError> {
+        let (Some(lat), Some(lng)) = (self.lat, self.lng) else {
+            return Err(StreetViewUrlError::MissingCoordinates);
+        };
+        // Map URLs put the zoom or mode right after the longitude (`17z`, `3a`)
+        // when there is no comma between them
+        let lat = parse_finite(lat.trim())
+            .ok_or_else(|| StreetViewUrlError::InvalidLatitude(lat.to_string()))?;
+        let lng_digits: String =
+            lng.chars().take_while(|c| c.is_ascii_digit() || *c == '.' || *c == '-').collect();
+        let lng = parse_finite(&lng_digits)
+            .ok_or_else(|| StreetViewUrlError::InvalidLongitude(lng.to_string()))?;
+        validate_coordinates((lat, lng))
+    }
 }
 
-/// Parse multiple Street View URLs, returning successful parses and errors.
-pub fn parse_streetview_urls(urls: &[&str]) -> Vec<Result<StreetViewUrlInfo, StreetViewUrlError>> {
-    urls.iter().map(|url| parse_streetview_url(url)).collect()
+/// Tokens of a `!`-separated protobuf-style data string, such as a map URL
+/// `data=` block or an embed `pb` parameter. Each token is a field number, a
+/// type letter, and a value: `!1d48.8584` is field 1, type `d`, `48.8584`.
+struct DataTokens<'a>(Vec<(u32, char, &'a str)>);
+
+impl<'a> DataTokens<'a> {
+    fn parse(data: &'a str) -> Self {
+        let tokens = data
+            .split('!')
+            .filter_map(|token| {
+                let digits = token.bytes().take_while(u8::is_ascii_digit).count();
+                let field = token[..digits].parse().ok()?;
+                let kind = token[digits..].chars().next()?;
+                Some((field, kind, &token[digits + kind.len_utf8()..]))
+            })
+            .collect();
+        Self(tokens)
+    }
+
+    fn number(&self, field: u32, kind: char) -> Option<f64> {
+        self.0
+            .iter()
+            .find(|(f, k, _)| *f == field && *k == kind)
+            .and_then(|(_, _, value)| parse_finite(value))
+    }
+
+    /// The first `1s` value that looks like a panorama ID. Place data also
+    /// uses `1s` for feature IDs (`0x...:0x...`), which are skipped.
+    fn panorama_id(&self) -> Option<String> {
+        self.0
+            .iter()
+            .filter(|(field, kind, _)| *field == 1 && *kind == 's')
+            .map(|(_, _, value)| percent_decode(value))
+            .find(|value| is_panorama_id(value))
+    }
+
+    /// Street View embed position: `!2m2!1d{lat}!2d{lng}`.
+    fn streetview_coordinates(&self) -> Option<(f64, f64)> {
+        self.0.windows(3).find_map(|w| match w {
+            [(2, 'm', "2"), (1, 'd', lat), (2, 'd', lng)] => {
+                Some((parse_finite(lat)?, parse_finite(lng)?))
+            }
+            _ => None,
+        })
+    }
+
+    /// Place position in map URL data: `!3d{lat}!4d{lng}`.
+    fn place_coordinates(&self) -> Option<(f64, f64)> {
+        self.0.windows(2).find_map(|w| match w {
+            [(3, 'd', lat), (4, 'd', lng)] => Some((parse_finite(lat)?, parse_finite(lng)?)),
+            _ => None,
+        })
+    }
 }
 
-/// Validate a batch of URLs and return only the valid ones with their info.
-pub fn validate_streetview_urls<'a>(urls: &'a [&'a str]) -> Vec<(&'a str, StreetViewUrlInfo)> {
-    urls.iter().filter_map(|url| parse_streetview_url(url).ok().map(|info| (*url, info))).collect()
+// =============================================================================
+// Values
+// =============================================================================
+
+/// Parse a `lat,lng` pair.
+fn parse_coordinate_pair(pair: &str) -> Result<(f64, f64), StreetViewUrlError> {
+    let (lat, lng) = pair.split_once(',').ok_or(StreetViewUrlError::MissingCoordinates)?;
+    let lat = parse_finite(lat.trim())
+        .ok_or_else(|| StreetViewUrlError::InvalidLatitude(lat.to_string()))?;
+    let lng = parse_finite(lng.trim())
+        .ok_or_else(|| StreetViewUrlError::InvalidLongitude(lng.to_string()))?;
+    validate_coordinates((lat, lng))
+}
+
+/// Check coordinates are within range.
+fn validate_coordinates((lat, lng): (f64, f64)) -> Result<(f64, f64), StreetViewUrlError> {
+    if !(-90.0..=90.0).contains(&lat) {
+        return Err(StreetViewUrlError::InvalidLatitude(format!(
+            "{lat} is out of range [-90, 90]"
+        )));
+    }
+    if !(-180.0..=180.0).contains(&lng) {
+        return Err(StreetViewUrlError::InvalidLongitude(format!(
+            "{lng} is out of range [-180, 180]"
+        )));
+    }
+    Ok((lat, lng))
+}
+
+/// Parse a finite number, rejecting `NaN` and infinities.
+fn parse_finite(s: &str) -> Option<f64> {
+    s.parse::<f64>().ok().filter(|v| v.is_finite())
+}
+
+/// Whether a value can be a panorama ID: Street View (`CAoSLEFGMVFpcE...`)
+/// and photo sphere (`AF1QipN...`) IDs are URL-safe tokens, unlike the
+/// `0x...:0x...` feature IDs that share their data field.
+fn is_panorama_id(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() < 100
+        && value.bytes().all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_'))
+}
+
+/// Percent-decode a URL component, treating `+` as a space.
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 3;
+                        continue;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            b'+' => decoded.push(b' '),
+            b => decoded.push(b),
+        }
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const PANO: &str = "CAoSLEFGMVFpcE1fQ2xN";
+
+    fn assert_coords(info: &StreetViewUrlInfo, lat: f64, lng: f64) {
+        assert!((info.lat - lat).abs() < 0.0001, "lat {} != {}", info.lat, lat);
+        assert!((info.lng - lng).abs() < 0.0001, "lng {} != {}", info.lng, lng);
+    }
+
     #[test]
     fn test_parse_basic_url() {
         let url = "https://www.google.com/maps/@48.8584,2.2945,3a,75y,90t/data=!3m7";
@@ -352,4 +755,434 @@ mod tests {
 
         assert_eq!(result.panorama_id, Some("ABCD1234".to_string()));
     }
+
+    // -------------------------------------------------------------------------
+    // Map URLs
+    // -------------------------------------------------------------------------
+
+    #[test]
+    fn test_full_map_url() {
+        let url = format!(
+            "https://www.google.com/maps/@48.8583701,2.2944813,3a,75y,90h,95t/data=!3m6!1e1!3m4!1s{PANO}!2e0!7i16384!8i8192?entry=ttu"
+        );
+        let result = parse_streetview_url(&url).unwrap();
+
+        assert_coords(&result, 48.8583701, 2.2944813);
+        assert_eq!(result.panorama_id.as_deref(), Some(PANO));
+        assert_eq!(result.heading, Some(90.0));
+        assert_eq!(result.fov, Some(75.0));
+        assert_eq!(result.pitch, Some(95.0));
+    }
+
+    #[test]
+    fn test_heading_without_fov() {
+        let url = "https://www.google.com/maps/@48.8584,2.2945,3a,15.5y,271.03h,88.4t";
+        let result = parse_streetview_url(url).unwrap();
+
+        assert_eq!(result.heading, Some(271.03));
+        assert_eq!(result.fov, Some(15.5));
+        assert_eq!(result.pitch, Some(88.4));
+    }
+
+    #[test]
+    fn test_country_domains() {
+        for host in [
+            "www.google.fr",
+            "google.de",
+            "www.google.co.uk",
+            "www.google.com.au",
+            "maps.google.com",
+            "maps.google.co.jp",
+        ] {
+            let url = format!("https://{host}/maps/@48.8584,2.2945,3a");
+            let result = parse_streetview_url(&url).unwrap_or_else(|e| panic!("{host}: {e}"));
+            assert_coords(&result, 48.8584, 2.2945);
+        }
+    }
+
+    #[test]
+    fn test_lookalike_domains_rejected() {
+        for url in [
+            "https://google.com.evil.example/maps/@48.8584,2.2945",
+            "https://notgoogle.com/maps/@48.8584,2.2945",
+            "https://www.google.com/search?q=@48.8584,2.2945",
+            "https://evil.example/?u=google.com/maps/@48.8584,2.2945",
+        ] {
+            assert_eq!(
+                parse_streetview_url(url),
+                Err(StreetViewUrlError::NotStreetViewUrl),
+                "{url}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_without_scheme_and_with_whitespace() {
+        let result = parse_streetview_url("  www.google.com/maps/@48.8584,2.2945,3a\n").unwrap();
+        assert_coords(&result, 48.8584, 2.2945);
+
+        let result = parse_streetview_url("http://google.com/maps/@48.8584,2.2945,17z").unwrap();
+        assert_coords(&result, 48.8584, 2.2945);
+    }
+
+    #[test]
+    fn test_uppercase_host() {
+        let result =
+            parse_streetview_url("https://WWW.GOOGLE.COM/maps/@48.8584,2.2945,3a").unwrap();
+        assert_coords(&result, 48.8584, 2.2945);
+    }
+
+    #[test]
+    fn test_place_url_with_streetview_at_segment() {
+        let url = format!(
+            "https://www.google.com/maps/place/Eiffel+Tower/@48.8583701,2.2944813,3a,75y,90h,90t/data=!3m8!1e1!3m6!1s{PANO}!2e0!4m5!3m4!1s0x47e66e2964e34e2d:0x8ddca9ee380ef7e0!8m2!3d48.8583701!4d2.2944813"
+        );
+        let result = parse_streetview_url(&url).unwrap();
+
+        assert_coords(&result, 48.8583701, 2.2944813);
+        assert_eq!(result.panorama_id.as_deref(), Some(PANO));
+    }
+
+    #[test]
+    fn test_place_coordinates_fallback() {
+        let url = "https://www.google.com/maps/place/Eiffel+Tower/data=!4m6!3m5!1s0x47e66e2964e34e2d:0x8ddca9ee380ef7e0!8m2!3d48.8583701!4d2.2944813";
+        let result = parse_streetview_url(url).unwrap();
+
+        assert_coords(&result, 48.8583701, 2.2944813);
+        // Feature IDs are not panorama IDs
+        assert_eq!(result.panorama_id, None);
+    }
+
+    #[test]
+    fn test_percent_encoded_panorama_id() {
+        let url = "https://www.google.com/maps/@48.8584,2.2945,3a/data=!3m4!1e1!3m2!1sAF1QipN%2DabC_12!2e10";
+        let result = parse_streetview_url(url).unwrap();
+
+        assert_eq!(result.panorama_id.as_deref(), Some("AF1QipN-abC_12"));
+    }
+
+    #[test]
+    fn test_panorama_id_at_end_of_data() {
+        let url =
+            format!("https://www.google.com/maps/@48.8584,2.2945,3a/data=!3m4!1e1!3m2!1s{PANO}");
+        let result = parse_streetview_url(&url).unwrap();
+
+        assert_eq!(result.panorama_id.as_deref(), Some(PANO));
+    }
+
+    #[test]
+    fn test_missing_longitude() {
+        let url = "https://www.google.com/maps/@48.8584";
+        assert_eq!(parse_streetview_url(url), Err(StreetViewUrlError::MissingCoordinates));
+    }
+
+    #[test]
+    fn test_invalid_latitude_text() {
+        let url = "https://www.google.com/maps/@abc,2.2945,3a";
+        assert!(matches!(parse_streetview_url(url), Err(StreetViewUrlError::InvalidLatitude(_))));
+    }
+
+    #[test]
+    fn test_non_finite_coordinates_rejected() {
+        let url = "https://www.google.com/maps/@NaN,2.2945,3a";
+        assert!(matches!(parse_streetview_url(url), Err(StreetViewUrlError::InvalidLatitude(_))));
+
+        let url = "https://www.google.com/maps/@?api=1&map_action=pano&viewpoint=48.8,inf";
+        assert!(matches!(parse_streetview_url(url), Err(StreetViewUrlError::InvalidLongitude(_))));
+    }
+
+    #[test]
+    fn test_boundary_coordinates() {
+        let result = parse_streetview_url("https://www.google.com/maps/@-90,180,3a").unwrap();
+        assert_coords(&result, -90.0, 180.0);
+
+        let result = parse_streetview_url("https://www.google.com/maps/@90,-180,3a").unwrap();
+        assert_coords(&result, 90.0, -180.0);
+    }
+
+    // -------------------------------------------------------------------------
+    // Maps URLs API (pano action)
+    // -------------------------------------------------------------------------
+
+    #[test]
+    fn test_pano_action_url() {
+        let url = format!(
+            "https://www.google.com/maps/@?api=1&map_action=pano&viewpoint=48.8584,2.2945&heading=-45&pitch=38&fov=80&pano={PANO}"
+        );
+        let result = parse_streetview_url(&url).unwrap();
+
+        assert_coords(&result, 48.8584, 2.2945);
+        assert_eq!(result.panorama_id.as_deref(), Some(PANO));
+        assert_eq!(result.heading, Some(-45.0));
+        assert_eq!(result.pitch, Some(38.0));
+        assert_eq!(result.fov, Some(80.0));
+    }
+
+    #[test]
+    fn test_pano_action_parameter_order() {
+        let orders = [
+            format!("api=1&map_action=pano&viewpoint=48.8584,2.2945&pano={PANO}&heading=90"),
+            format!("pano={PANO}&heading=90&viewpoint=48.8584,2.2945&map_action=pano&api=1"),
+            format!("heading=90&api=1&pano={PANO}&map_action=pano&viewpoint=48.8584,2.2945"),
+        ];
+        for query in orders {
+            let url = format!("https://www.google.com/maps/@?{query}");
+            let result = parse_streetview_url(&url).unwrap_or_else(|e| panic!("{query}: {e}"));
+
+            assert_coords(&result, 48.8584, 2.2945);
+            assert_eq!(result.panorama_id.as_deref(), Some(PANO), "{query}");
+            assert_eq!(result.heading, Some(90.0), "{query}");
+        }
+    }
+
+    #[test]
+    fn test_encoded_viewpoint() {
+        let url = "https://www.google.com/maps/@?api=1&map_action=pano&viewpoint=48.8584%2C2.2945";
+        let result = parse_streetview_url(url).unwrap();
+
+        assert_coords(&result, 48.8584, 2.2945);
+    }
+
+    #[test]
+    fn test_pano_action_without_viewpoint() {
+        let url = format!("https://www.google.com/maps/@?api=1&map_action=pano&pano={PANO}");
+        assert_eq!(parse_streetview_url(&url), Err(StreetViewUrlError::MissingCoordinates));
+    }
+
+    #[test]
+    fn test_query_param_names_do_not_overlap() {
+        // `path=` ends in `h=` but is not a heading
+        let url =
+            "https://www.google.com/maps/@?api=1&map_action=pano&path=90&viewpoint=48.8584,2.2945";
+        let result = parse_streetview_url(url).unwrap();
+
+        assert_eq!(result.heading, None);
+    }
+
+    // -------------------------------------------------------------------------
+    // Legacy links
+    // -------------------------------------------------------------------------
+
+    #[test]
+    fn test_legacy_cbll_url() {
+        let url = format!(
+            "https://maps.google.com/maps?q=&layer=c&cbll=48.8584,2.2945&cbp=12,137.5,,0,5&panoid={PANO}"
+        );
+        let result = parse_streetview_url(&url).unwrap();
+
+        assert_coords(&result, 48.8584, 2.2945);
+        assert_eq!(result.panorama_id.as_deref(), Some(PANO));
+        assert_eq!(result.heading, Some(137.5));
+    }
+
+    #[test]
+    fn test_legacy_url_on_root_path() {
+        let url = "http://maps.google.co.uk/?cbll=51.5007,-0.1246&layer=c";
+        let result = parse_streetview_url(url).unwrap();
+
+        assert_coords(&result, 51.5007, -0.1246);
+    }
+
+    #[test]
+    fn test_legacy_html_escaped_ampersands() {
+        let url = "https://maps.google.com/maps?layer=c&amp;cbll=48.8584,2.2945&amp;cbp=12,90,,0,5";
+        let result = parse_streetview_url(url).unwrap();
+
+        assert_coords(&result, 48.8584, 2.2945);
+        assert_eq!(result.heading, Some(90.0));
+    }
+
+    // -------------------------------------------------------------------------
+    // Embeds
+    // -------------------------------------------------------------------------
+
+    #[test]
+    fn test_embed_pb_url() {
+        let url = format!(
+            "https://www.google.com/maps/embed?pb=!4v1700000000000!6m8!1m7!1s{PANO}!2m2!1d48.8583701!2d2.2944813!3f210.5!4f-3.2!5f0.7820865974627469"
+        );
+        let result = parse_streetview_url(&url).unwrap();
+
+        assert_coords(&result, 48.8583701, 2.2944813);
+        assert_eq!(result.panorama_id.as_deref(), Some(PANO));
+        assert_eq!(result.heading, Some(210.5));
+        assert_eq!(result.pitch, Some(-3.2));
+    }
+
+    #[test]
+    fn test_embed_pb_after_other_params() {
+        let url = format!(
+            "https://www.google.com/maps/embed?hl=en&pb=!4v1!6m8!1m7!1s{PANO}!2m2!1d-33.8568!2d151.2153!3f0!4f0!5f0.8&width=600"
+        );
+        let result = parse_streetview_url(&url).unwrap();
+
+        assert_coords(&result, -33.8568, 151.2153);
+        assert_eq!(result.panorama_id.as_deref(), Some(PANO));
+    }
+
+    #[test]
+    fn test_map_embed_without_streetview_rejected() {
+        // Regular map embeds put the viewport span in !1d; no coordinates here
+        let url =
+            "https://www.google.com/maps/embed?pb=!1m18!1m12!1m3!1d2624.99!2d2.2922926!4f13.1";
+        assert_eq!(parse_streetview_url(url), Err(StreetViewUrlError::MissingCoordinates));
+    }
+
+    #[test]
+    fn test_embed_api_url() {
+        let url = "https://www.google.com/maps/embed/v1/streetview?key=KEY&location=46.414382,10.013988&heading=210&pitch=10&fov=35";
+        let result = parse_streetview_url(url).unwrap();
+
+        assert_coords(&result, 46.414382, 10.013988);
+        assert_eq!(result.heading, Some(210.0));
+        assert_eq!(result.pitch, Some(10.0));
+        assert_eq!(result.fov, Some(35.0));
+        assert_eq!(result.panorama_id, None);
+    }
+
+    #[test]
+    fn test_embed_api_url_with_pano() {
+        let url = format!(
+            "https://www.google.com/maps/embed/v1/streetview?pano={PANO}&location=46.414382,10.013988&key=KEY"
+        );
+        let result = parse_streetview_url(&url).unwrap();
+
+        assert_eq!(result.panorama_id.as_deref(), Some(PANO));
+    }
+
+    // -------------------------------------------------------------------------
+    // Short links
+    // -------------------------------------------------------------------------
+
+    #[test]
+    fn test_short_links() {
+        for url in [
+            "https://maps.app.goo.gl/uMqAbcDeFgH12345",
+            "https://goo.gl/maps/AbCdEf123",
+            "maps.app.goo.gl/uMqAbcDeFgH12345",
+        ] {
+            assert!(is_short_link(url), "{url}");
+            assert_eq!(
+                parse_streetview_url(url),
+                Err(StreetViewUrlError::UnresolvedShortLink),
+                "{url}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_not_short_links() {
+        assert!(!is_short_link("https://goo.gl/AbCdEf123"));
+        assert!(!is_short_link("https://www.google.com/maps/@48.8584,2.2945,3a"));
+        assert!(!is_short_link("https://maps.app.goo.gl.evil.example/abc"));
+    }
+
+    // -------------------------------------------------------------------------
+    // Helpers
+    // -------------------------------------------------------------------------
+
+    #[test]
+    fn test_is_google_maps_url() {
+        assert!(is_google_maps_url("https://www.google.com/maps/@48.8584,2.2945,3a"));
+        assert!(is_google_maps_url("https://maps.google.co.uk/?cbll=48.8584,2.2945"));
+        assert!(!is_google_maps_url("https://www.google.com/search?q=maps"));
+        assert!(!is_google_maps_url("https://google.com.aa.bb/maps/"));
+        assert!(!is_google_maps_url("https://maps.app.goo.gl/AbCdEf123"));
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("a%2Cb"), "a,b");
+        assert_eq!(percent_decode("a+b"), "a b");
+        assert_eq!(percent_decode("%E2%9C%93"), "\u{2713}");
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%zz"), "%zz");
+        assert_eq!(percent_decode("%2"), "%2");
+    }
+
+    #[test]
+    fn test_is_panorama_id() {
+        assert!(is_panorama_id(PANO));
+        assert!(is_panorama_id("AF1QipN-abC_12"));
+        assert!(!is_panorama_id(""));
+        assert!(!is_panorama_id("0x47e66e2964e34e2d:0x8ddca9ee380ef7e0"));
+        assert!(!is_panorama_id("has space"));
+        assert!(!is_panorama_id(&"a".repeat(100)));
+    }
+
+    #[test]
+    fn test_imagery_provider_names_round_trip() {
+        for provider in ImageryProvider::ALL {
+            assert_eq!(provider.as_str().parse::<ImageryProvider>(), Ok(provider));
+            let json = serde_json::to_string(&provider).unwrap();
+            assert_eq!(json, format!("\"{}\"", provider.as_str()));
+        }
+        assert_eq!(ImageryProvider::default(), ImageryProvider::GoogleStreetView);
+        assert!("street_view".parse::<ImageryProvider>().is_err());
+    }
+
+    #[test]
+    fn test_imagery_provider_image_ids() {
+        let google = ImageryProvider::GoogleStreetView;
+        assert!(google.validate_image_id(Some("CAoSLEFGMVFpcE")).is_ok());
+        assert!(google.validate_image_id(Some("url_48.858400_2.294500")).is_ok());
+        assert!(google.validate_image_id(None).is_ok());
+        assert!(google.validate_image_id(Some("has space")).is_err());
+        assert!(google.validate_image_id(Some(&"a".repeat(101))).is_err());
+
+        let mapillary = ImageryProvider::Mapillary;
+        assert!(mapillary.validate_image_id(Some("498763468214164")).is_ok());
+        assert_eq!(
+            mapillary.validate_image_id(None),
+            Err(ImageryError::MissingImageId(ImageryProvider::Mapillary))
+        );
+        assert_eq!(
+            mapillary.validate_image_id(Some("CAoSLEFGMVFpcE")),
+            Err(ImageryError::InvalidImageId(ImageryProvider::Mapillary))
+        );
+
+        assert!(ImageryProvider::BingStreetside.validate_image_id(None).is_ok());
+    }
+
+    // -------------------------------------------------------------------------
+    // Quota breaker
+    // -------------------------------------------------------------------------
+
+    #[test]
+    fn test_quota_breaker_trips_and_recovers() {
+        let now = Utc::now();
+        let mut breaker = QuotaBreaker::default();
+        assert_eq!(breaker.state(now), QuotaState::Closed);
+        assert!(!breaker.record_success());
+
+        let until = breaker.trip(now);
+        let pause = (until - now).num_seconds();
+        assert!((10..=12).contains(&pause), "pause was {pause}s");
+        assert_eq!(breaker.state(now), QuotaState::Open { until });
+        assert_eq!(breaker.state(until), QuotaState::HalfOpen);
+        assert_eq!(breaker.last_tripped_at, Some(now));
+
+        assert!(breaker.record_success());
+        assert_eq!(breaker.state(now), QuotaState::Closed);
+        assert_eq!(breaker.consecutive_trips, 0);
+    }
+
+    #[test]
+    fn test_quota_backoff_doubles_up_to_cap() {
+        assert_eq!(quota_backoff_secs(1), 10);
+        assert_eq!(quota_backoff_secs(2), 20);
+        assert_eq!(quota_backoff_secs(3), 40);
+        assert_eq!(quota_backoff_secs(10), 3600);
+        assert_eq!(quota_backoff_secs(u32::MAX), 3600);
+    }
+
+    #[test]
+    fn test_quota_breaker_keeps_latest_resume_time() {
+        let now = Utc::now();
+        let mut breaker = QuotaBreaker::default();
+        let first = breaker.trip(now);
+        let second = breaker.trip(now - chrono::Duration::seconds(600));
+        assert_eq!(second, first);
+        assert_eq!(breaker.consecutive_trips, 2);
+    }
 }
@@ -0,0 +1,308 @@
+//! Game and player analytics rollups
+//!
+//! The rollup tables are rebuilt from `guesses`, `games`, and `users` one
+//! range of days at a time; dashboard reads only touch the rollups.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::FromRow;
+
+use crate::DbPool;
+
+/// Guess percentiles stored per day: p10, p25, p50, p75, p90.
+pub const PERCENTILES: [f64; 5] = [0.1, 0.25, 0.5, 0.75, 0.9];
+
+/// Player activity for one day.
+#[derive(Debug, Clone, FromRow)]
+pub struct DailyActivity {
+    pub day: NaiveDate,
+    pub active_users: i32,
+    pub new_users: i32,
+    pub guesses: i32,
+    /// Guess score at each of [`PERCENTILES`]
+    pub score_percentiles: Option<Vec<f64>>,
+    /// Guess distance (km) at each of [`PERCENTILES`]
+    pub distance_percentiles: Option<Vec<f64>>,
+}
+
+/// Games of one mode on one day.
+#[derive(Debug, Clone, FromRow)]
+pub struct DailyGames {
+    pub day: NaiveDate,
+    pub mode: String,
+    pub games_created: i32,
+    pub games_finished: i32,
+    pub duration_secs_total: i64,
+}
+
+/// Guesses on rounds in one country over a period.
+#[derive(Debug, Clone, FromRow)]
+pub struct CountryGuesses {
+    pub country_code: String,
+    pub guesses: i64,
+    pub score_total: i64,
+    pub distance_km_total: f64,
+}
+
+/// Active users in one week after signing up, for one signup cohort.
+#[derive(Debug, Clone, FromRow)]
+pub struct RetentionRow {
+    pub cohort_week: NaiveDate,
+    pub week_offset: i16,
+    pub cohort_size: i32,
+    pub retained_users: i32,
+}
+
+/// Recompute the daily activity and game rollups for `from..=to`.
+pub async fn refresh_daily(
+    pool: &DbPool,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        r#"
+        WITH days AS (
+            SELECT generate_series($1::date, $2::date, INTERVAL '1 day')::date AS day
+        ),
+        g AS (
+            SELECT (submitted_at AT TIME ZONE 'UTC')::date AS day,
+                   COUNT(DISTINCT user_id) AS active_users,
+                   COUNT(*) AS guesses,
+                   percentile_cont($3::float8[]) WITHIN GROUP (ORDER BY score) AS scores,
+                   percentile_cont($3::float8[])
+                       WITHIN GROUP (ORDER BY distance_meters / 1000.0) AS distances
+            FROM guesses
+            WHERE submitted_at >= $1::date::timestamp AT TIME ZONE 'UTC'
+              AND submitted_at < ($2::date + 1)::timestamp AT TIME ZONE 'UTC'
+            GROUP BY 1
+        ),
+        u AS (
+            SELECT (created_at AT TIME ZONE 'UTC')::date AS day, COUNT(*) AS new_users
+            FROM users
+            WHERE created_at >= $1::date::timestamp AT TIME ZONE 'UTC'
+              AND created_at < ($2::date + 1)::timestamp AT TIME ZONE 'UTC'
+            GROUP BY 1
+        )
+        INSERT INTO analytics_daily (
+            day, active_users, new_users, guesses, score_percentiles, distance_percentiles
+        )
+        SELECT days.day, COALESCE(g.active_users, 0), COALESCE(u.new_users, 0),
+               COALESCE(g.guesses, 0), g.scores, g.distances
+        FROM days
+        LEFT JOIN g USING (day)
+        LEFT JOIN u USING (day)
+        ON CONFLICT (day) DO UPDATE
+        SET active_users = EXCLUDED.active_users,
+            new_users = EXCLUDED.new_users,
+            guesses = EXCLUDED.guesses,
+            score_percentiles = EXCLUDED.score_percentiles,
+            distance_percentiles = EXCLUDED.distance_percentiles,
+            updated_at = NOW()
+        "#,
+    )
+    .bind(from)
+    .bind(to)
+    .bind(PERCENTILES.as_slice())
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("DELETE FROM analytics_daily_games WHERE day BETWEEN $1 AND $2")
+        .bind(from)
+        .bind(to)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        r#"
+        WITH created AS (
+            SELECT (created_at AT TIME ZONE 'UTC')::date AS day, mode::text AS mode, COUNT(*) AS n
+            FROM games
+            WHERE created_at >= $1::date::timestamp AT TIME ZONE 'UTC'
+              AND created_at < ($2::date + 1)::timestamp AT TIME ZONE 'UTC'
+            GROUP BY 1, 2
+        ),
+        finished AS (
+            SELECT (ended_at AT TIME ZONE 'UTC')::date AS day, mode::text AS mode, COUNT(*) AS n,
+                   COALESCE(SUM(EXTRACT(EPOCH FROM ended_at - COALESCE(started_at, created_at))), 0)
+                       ::bigint AS secs
+            FROM games
+            WHERE status = 'finished'
+              AND ended_at >= $1::date::timestamp AT TIME ZONE 'UTC'
+              AND ended_at < ($2::date + 1)::timestamp AT TIME ZONE 'UTC'
+            GROUP BY 1, 2
+        )
+        INSERT INTO analytics_daily_games (
+            day, mode, games_created, games_finished, duration_secs_total
+        )
+        SELECT COALESCE(c.day, f.day), COALESCE(c.mode, f.mode),
+               COALESCE(c.n, 0), COALESCE(f.n, 0), COALESCE(f.secs, 0)
+        FROM created c
+        FULL JOIN finished f ON f.day = c.day AND f.mode = c.mode
+        "#,
+    )
+    .bind(from)
+    .bind(to)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("DELETE FROM analytics_daily_countries WHERE day BETWEEN $1 AND $2")
+        .bind(from)
+        .bind(to)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO analytics_daily_countries (
+            day, country_code, guesses, score_total, distance_km_total
+        )
+        SELECT (g.submitted_at AT TIME ZONE 'UTC')::date, UPPER(r.country_code), COUNT(*),
+               SUM(g.score), SUM(g.distance_meters / 1000.0)
+        FROM guesses g
+        JOIN rounds r ON r.id = g.round_id
+        WHERE r.country_code IS NOT NULL
+          AND g.submitted_at >= $1::date::timestamp AT TIME ZONE 'UTC'
+          AND g.submitted_at < ($2::date + 1)::timestamp AT TIME ZONE 'UTC'
+        GROUP BY 1, 2
+        "#,
+    )
+    .bind(from)
+    .bind(to)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await
+}
+
+/// Recompute retention for the signup cohorts of the last `weeks` weeks,
+/// tracking each cohort for at most `max_offset` weeks after signup.
+pub async fn refresh_retention(
+    pool: &DbPool,
+    weeks: i32,
+    max_offset: i32,
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let since: NaiveDate = sqlx::query_scalar(
+        "SELECT date_trunc('week', (NOW() AT TIME ZONE 'UTC') - make_interval(weeks => $1))::date",
+    )
+    .bind(weeks)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query("DELETE FROM analytics_retention WHERE cohort_week >= $1")
+        .bind(since)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        r#"
+        WITH cohorts AS (
+            SELECT id, date_trunc('week', created_at AT TIME ZONE 'UTC')::date AS cohort_week
+            FROM users
+            WHERE created_at >= $1::date::timestamp AT TIME ZONE 'UTC'
+        ),
+        sizes AS (
+            SELECT cohort_week, COUNT(*) AS size FROM cohorts GROUP BY cohort_week
+        ),
+        retained AS (
+            SELECT c.cohort_week,
+                   (date_trunc('week', g.submitted_at AT TIME ZONE 'UTC')::date - c.cohort_week) / 7
+                       AS week_offset,
+                   COUNT(DISTINCT g.user_id) AS users
+            FROM cohorts c
+            JOIN guesses g ON g.user_id = c.id
+            WHERE g.submitted_at >= $1::date::timestamp AT TIME ZONE 'UTC'
+            GROUP BY 1, 2
+        )
+        INSERT INTO analytics_retention (cohort_week, week_offset, cohort_size, retained_users)
+        SELECT s.cohort_week, o.week_offset, s.size, COALESCE(r.users, 0)
+        FROM sizes s
+        CROSS JOIN LATERAL generate_series(
+            0,
+            LEAST($2, (date_trunc('week', NOW() AT TIME ZONE 'UTC')::date - s.cohort_week) / 7)
+        ) AS o(week_offset)
+        LEFT JOIN retained r ON r.cohort_week = s.cohort_week AND r.week_offset = o.week_offset
+        "#,
+    )
+    .bind(since)
+    .bind(max_offset)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await
+}
+
+/// Latest day with a rollup, if any rollup has been computed.
+pub async fn last_rolled_up_day(pool: &DbPool) -> Result<Option<NaiveDate>, sqlx::Error> {
+    sqlx::query_scalar("SELECT MAX(day) FROM analytics_daily").fetch_one(pool).await
+}
+
+/// When the rollups were last refreshed.
+pub async fn last_refreshed_at(pool: &DbPool) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+    sqlx::query_scalar("SELECT MAX(updated_at) FROM analytics_daily").fetch_one(pool).await
+}
+
+/// Daily activity for the last `days` days, oldest first.
+pub async fn daily_activity(pool: &DbPool, days: i32) -> Result<Vec<DailyActivity>, sqlx::Error> {
+    sqlx::query_as::<_, DailyActivity>(
+        r#"
+        SELECT day, active_users, new_users, guesses, score_percentiles, distance_percentiles
+        FROM analytics_daily
+        WHERE day > (NOW() AT TIME ZONE 'UTC')::date - $1
+        ORDER BY day
+        "#,
+    )
+    .bind(days)
+    .fetch_all(pool)
+    .await
+}
+
+/// Games per day and mode for the last `days` days, oldest first.
+pub async fn daily_games(pool: &DbPool, days: i32) -> Result<Vec<DailyGames>, sqlx::Error> {
+    sqlx::query_as::<_, DailyGames>(
+        r#"
+        SELECT day, mode, games_created, games_finished, duration_secs_total
+        FROM analytics_daily_games
+        WHERE day > (NOW() AT TIME ZONE 'UTC')::date - $1
+        ORDER BY day, mode
+        "#,
+    )
+    .bind(days)
+    .fetch_all(pool)
+    .await
+}
+
+/// Guesses per round country over the last `days` days, most guessed first.
+pub async fn country_guesses(pool: &DbPool, days: i32) -> Result<Vec<CountryGuesses>, sqlx::Error> {
+    sqlx::query_as::<_, CountryGuesses>(
+        r#"
+        SELECT country_code, SUM(guesses)::bigint AS guesses,
+               SUM(score_total)::bigint AS score_total,
+               SUM(distance_km_total) AS distance_km_total
+        FROM analytics_daily_countries
+        WHERE day > (NOW() AT TIME ZONE 'UTC')::date - $1
+        GROUP BY country_code
+        ORDER BY guesses DESC, country_code
+        "#,
+    )
+    .bind(days)
+    .fetch_all(pool)
+    .await
+}
+
+/// Retention of the last `weeks` signup cohorts, oldest cohort first.
+pub async fn retention(pool: &DbPool, weeks: i32) -> Result<Vec<RetentionRow>, sqlx::Error> {
+    sqlx::query_as::<_, RetentionRow>(
+        r#"
+        SELECT cohort_week, week_offset, cohort_size, retained_users
+        FROM analytics_retention
+        WHERE cohort_week >= date_trunc('week', (NOW() AT TIME ZONE 'UTC') - make_interval(weeks => $1))::date
+        ORDER BY cohort_week, week_offset
+        "#,
+    )
+    .bind(weeks)
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,40 @@
+//! Cheating signal database queries
+
+use dguesser_core::game::CheatSignalKind;
+
+use crate::DbPool;
+
+/// Record a cheating signal for a user.
+pub async fn record_signal(
+    pool: &DbPool,
+    user_id: &str,
+    game_id: &str,
+    round_id: Option<&str>,
+    kind: CheatSignalKind,
+    details: serde_json::Value,
+) -> Result<String, sqlx::Error> {
+    let id = dguesser_core::generate_cheat_signal_id();
+    sqlx::query(
+        r#"
+        INSERT INTO cheat_signals (id, user_id, game_id, round_id, kind, details)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        "#,
+    )
+    .bind(&id)
+    .bind(user_id)
+    .bind(game_id)
+    .bind(round_id)
+    .bind(kind.as_str())
+    .bind(details)
+    .execute(pool)
+    .await?;
+    Ok(id)
+}
+
+/// Count the cheating signals recorded for a user.
+pub async fn count_for_user(pool: &DbPool, user_id: &str) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar("SELECT COUNT(*) FROM cheat_signals WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+}
@@ -0,0 +1,65 @@
+//! Admin audit log queries
+//!
+//! Records sensitive admin actions (such as signing in as a user) with who
+//! did them and to whom.
+
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgConnection};
+
+use crate::DbPool;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct AuditLogEntry {
+    pub id: i64,
+    pub actor_id: Option<String>,
+    pub action: String,
+    pub target_user_id: Option<String>,
+    pub details: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Record an admin action.
+pub async fn record(
+    conn: &mut PgConnection,
+    actor_id: &str,
+    action: &str,
+    target_user_id: Option<&str>,
+    details: serde_json::Value,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO admin_audit_log (actor_id, action, target_user_id, details)
+        VALUES ($1, $2, $3, $4)
+        "#,
+    )
+    .bind(actor_id)
+    .bind(action)
+    .bind(target_user_id)
+    .bind(details)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// List entries, newest first, optionally only those about one user.
+pub async fn list(
+    pool: &DbPool,
+    target_user_id: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<AuditLogEntry>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT id, actor_id, action, target_user_id, details, created_at
+        FROM admin_audit_log
+        WHERE $1::text IS NULL OR target_user_id = $1
+        ORDER BY created_at DESC, id DESC
+        LIMIT $2 OFFSET $3
+        "#,
+    )
+    .bind(target_user_id)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,177 @@
+//! Asynchronous challenge queries
+//!
+//! Challenges are games in `challenge` mode with all rounds created up front.
+//! Per-player progress lives in `player_rounds`.
+
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+use crate::DbPool;
+use crate::games::GameStatus;
+
+/// A player's progress on one round of a challenge
+#[derive(Debug, Clone, FromRow)]
+pub struct PlayerRound {
+    pub round_id: String, // rnd_XXXXXXXXXXXX
+    pub round_number: i16,
+    pub user_id: String, // usr_XXXXXXXXXXXX
+    pub started_at: DateTime<Utc>,
+    /// Set once the player guessed or ran out of time
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// A challenge from one player's point of view
+#[derive(Debug, Clone, FromRow)]
+pub struct ChallengeSummary {
+    pub game_id: String,
+    pub status: GameStatus,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub settings: serde_json::Value,
+    pub score: i32,
+    /// Rounds the player has guessed on or run out of time in
+    pub rounds_played: i64,
+    pub opponent_id: String,
+    pub opponent_display_name: String,
+    pub opponent_avatar_url: Option<String>,
+    pub opponent_score: i32,
+    pub opponent_rounds_played: i64,
+}
+
+/// Record that a player started a round
+pub async fn start_player_round(
+    pool: &DbPool,
+    round_id: &str,
+    user_id: &str,
+) -> Result<PlayerRound, sqlx::Error> {
+    sqlx::query_as::<_, PlayerRound>(
+        r#"
+        WITH inserted AS (
+            INSERT INTO player_rounds (round_id, user_id)
+            VALUES ($1, $2)
+            RETURNING round_id, user_id, started_at, completed_at
+        )
+        SELECT i.round_id, r.round_number, i.user_id, i.started_at, i.completed_at
+        FROM inserted i
+        JOIN rounds r ON r.id = i.round_id
+        "#,
+    )
+    .bind(round_id)
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+}
+
+/// Mark a player's round as finished
+pub async fn complete_player_round(
+    pool: &DbPool,
+    round_id: &str,
+    user_id: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE player_rounds SET completed_at = NOW()
+        WHERE round_id = $1 AND user_id = $2 AND completed_at IS NULL
+        "#,
+    )
+    .bind(round_id)
+    .bind(user_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Get every player's round progress in a challenge, ordered by round
+pub async fn get_player_rounds(
+    pool: &DbPool,
+    game_id: &str,
+) -> Result<Vec<PlayerRound>, sqlx::Error> {
+    sqlx::query_as::<_, PlayerRound>(
+        r#"
+        SELECT pr.round_id, r.round_number, pr.user_id, pr.started_at, pr.completed_at
+        FROM player_rounds pr
+        JOIN rounds r ON r.id = pr.round_id
+        WHERE r.game_id = $1
+        ORDER BY r.round_number ASC
+        "#,
+    )
+    .bind(game_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// List a user's active challenges and those that ended in the last
+/// `recent_days` days, newest first
+pub async fn list_for_user(
+    pool: &DbPool,
+    user_id: &str,
+    recent_days: i32,
+    limit: i64,
+) -> Result<Vec<ChallengeSummary>, sqlx::Error> {
+    sqlx::query_as::<_, ChallengeSummary>(
+        r#"
+        WITH played AS (
+            SELECT r.game_id, pr.user_id, COUNT(*) AS rounds_played
+            FROM player_rounds pr
+            JOIN rounds r ON r.id = pr.round_id
+            WHERE pr.completed_at IS NOT NULL
+               OR pr.started_at + r.time_limit_ms * INTERVAL '1 millisecond' <= NOW()
+            GROUP BY r.game_id, pr.user_id
+        )
+        SELECT g.id AS game_id, g.status, g.created_by, g.created_at, g.ended_at, g.settings,
+               me.score_total AS score,
+               COALESCE(mp.rounds_played, 0) AS rounds_played,
+               opp.user_id AS opponent_id,
+               u.display_name AS opponent_display_name,
+               u.avatar_url AS opponent_avatar_url,
+               opp.score_total AS opponent_score,
+               COALESCE(op.rounds_played, 0) AS opponent_rounds_played
+        FROM games g
+        JOIN game_players me ON me.game_id = g.id AND me.user_id = $1
+        JOIN game_players opp ON opp.game_id = g.id AND opp.user_id <> $1
+        JOIN users u ON u.id = opp.user_id
+        LEFT JOIN played mp ON mp.game_id = g.id AND mp.user_id = me.user_id
+        LEFT JOIN played op ON op.game_id = g.id AND op.user_id = opp.user_id
+        WHERE g.mode = 'challenge'
+          AND (g.status = 'active' OR g.ended_at > NOW() - make_interval(days => $2))
+        ORDER BY g.created_at DESC
+        LIMIT $3
+        "#,
+    )
+    .bind(user_id)
+    .bind(recent_days)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+/// Count a user's active challenges
+pub async fn count_active(pool: &DbPool, user_id: &str) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*)
+        FROM games g
+        JOIN game_players gp ON gp.game_id = g.id
+        WHERE gp.user_id = $1 AND g.mode = 'challenge' AND g.status = 'active'
+        "#,
+    )
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+}
+
+/// Abandon active challenges created more than `days` days ago
+pub async fn abandon_expired(pool: &DbPool, days: i32) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE games SET status = 'abandoned', ended_at = NOW()
+        WHERE mode = 'challenge' AND status = 'active'
+          AND created_at < NOW() - make_interval(days => $1)
+        "#,
+    )
+    .bind(days)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,104 @@
+//! Client error report queries
+//!
+//! The frontend reports errors it cannot recover from (a panorama that did
+//! not load, a socket that dropped). Panorama failures are linked to their
+//! location so they count toward its failure counter.
+
+use crate::DbPool;
+
+/// Error kind for a panorama that failed to load
+pub const PANO_LOAD_FAILED: &str = "pano_load_failed";
+
+/// A client error report to store
+#[derive(Debug, Clone)]
+pub struct NewClientError<'a> {
+    pub kind: &'a str,
+    pub message: Option<&'a str>,
+    pub user_id: Option<&'a str>,
+    pub location_id: Option<&'a str>,
+    /// Used to find the location when the client only knows the panorama
+    pub panorama_id: Option<&'a str>,
+    pub game_id: Option<&'a str>,
+    pub page: Option<&'a str>,
+    pub user_agent: Option<&'a str>,
+    pub context: serde_json::Value,
+}
+
+/// Store a client error report. Unknown location and game IDs are dropped.
+///
+/// Returns the ID of the location the report was linked to.
+pub async fn insert(
+    pool: &DbPool,
+    error: &NewClientError<'_>,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        INSERT INTO client_errors (
+            kind, message, user_id, location_id, game_id, page, user_agent, context
+        )
+        VALUES (
+            $1, $2, $3,
+            COALESCE(
+                (SELECT id FROM locations WHERE id = $4),
+                (SELECT id FROM locations WHERE panorama_id = $5)
+            ),
+            (SELECT id FROM games WHERE id = $6),
+            $7, $8, $9
+        )
+        RETURNING location_id
+        "#,
+    )
+    .bind(error.kind)
+    .bind(error.message)
+    .bind(error.user_id)
+    .bind(error.location_id)
+    .bind(error.panorama_id)
+    .bind(error.game_id)
+    .bind(error.page)
+    .bind(error.user_agent)
+    .bind(&error.context)
+    .fetch_one(pool)
+    .await
+}
+
+/// Count a panorama failure toward a location's failure counter.
+///
+/// Each user counts once per location per day, so one player retrying a
+/// broken panorama does not flag it on their own. Returns `true` if the
+/// counter was incremented.
+pub async fn record_pano_failure(
+    pool: &DbPool,
+    location_id: &str,
+    user_id: &str,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE locations
+        SET failure_count = COALESCE(failure_count, 0) + 1,
+            last_failure_reason = $3
+        WHERE id = $1
+          AND (
+              SELECT COUNT(*) FROM client_errors
+              WHERE location_id = $1 AND user_id = $2 AND kind = $3
+                AND created_at > NOW() - INTERVAL '1 day'
+          ) = 1
+        "#,
+    )
+    .bind(location_id)
+    .bind(user_id)
+    .bind(PANO_LOAD_FAILED)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Delete reports older than `days` days. Returns the number deleted.
+pub async fn delete_older_than(pool: &DbPool, days: i32) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        "DELETE FROM client_errors WHERE created_at < NOW() - make_interval(days => $1)",
+    )
+    .bind(days)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,284 @@
+//! Password credential and auth token database queries
+
+use chrono::{DateTime, Duration, Utc};
+use sqlx::FromRow;
+
+use crate::DbPool;
+use crate::users::User;
+
+/// Purpose of a single-use auth token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthTokenPurpose {
+    /// Confirms ownership of an email address
+    EmailVerification,
+    /// Allows setting a new password without the old one
+    PasswordReset,
+}
+
+impl AuthTokenPurpose {
+    /// Database representation.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuthTokenPurpose::EmailVerification => "email_verification",
+            AuthTokenPurpose::PasswordReset => "password_reset",
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct AuthToken {
+    pub token_hash: String,
+    pub user_id: String, // usr_XXXXXXXXXXXX
+    pub purpose: String,
+    pub email: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub consumed_at: Option<DateTime<Utc>>,
+}
+
+/// A user together with their password hash, for sign-in.
+#[derive(Debug, Clone)]
+pub struct PasswordLogin {
+    pub user: User,
+    pub password_hash: String,
+}
+
+const USER_COLUMNS: &str = r#"u.id, u.kind, u.role, u.username, u.email, u.email_verified,
+    u.display_name, u.avatar_url, u.created_at, u.updated_at, u.last_seen_at,
+    u.games_played, u.total_score, u.best_score, u.deleted_at, u.leaderboard_public"#;
+
+/// Look up a user and their password hash by (normalized) email.
+pub async fn get_login_by_email(
+    pool: &DbPool,
+    email: &str,
+) -> Result<Option<PasswordLogin>, sqlx::Error> {
+    let query = format!(
+        r#"
+        SELECT {USER_COLUMNS}, pc.password_hash
+        FROM users u
+        INNER JOIN password_credentials pc ON pc.user_id = u.id
+        WHERE LOWER(u.email) = $1 AND u.deleted_at IS NULL
+        "#
+    );
+
+    let row = sqlx::query(&query).bind(email).fetch_optional(pool).await?;
+
+    row.map(|row| {
+        use sqlx::Row;
+        Ok(PasswordLogin {
+            user: User::from_row(&row)?,
+            password_hash: row.try_get("password_hash")?,
+        })
+    })
+    .transpose()
+}
+
+/// Look up a user by (normalized) email regardless of login method.
+pub async fn get_user_by_email_ci(pool: &DbPool, email: &str) -> Result<Option<User>, sqlx::Error> {
+    let query = format!(
+        "SELECT {USER_COLUMNS} FROM users u WHERE LOWER(u.email) = $1 AND u.deleted_at IS NULL"
+    );
+    sqlx::query_as::<_, User>(&query).bind(email).fetch_optional(pool).await
+}
+
+/// Check whether a user has a password set.
+pub async fn has_password(pool: &DbPool, user_id: &str) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM password_credentials WHERE user_id = $1)")
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+}
+
+/// Set (or replace) a user's password hash.
+pub async fn set_password(
+    pool: &DbPool,
+    user_id: &str,
+    password_hash: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO password_credentials (user_id, password_hash)
+        VALUES ($1, $2)
+        ON CONFLICT (user_id) DO UPDATE SET password_hash = EXCLUDED.password_hash
+        "#,
+    )
+    .bind(user_id)
+    .bind(password_hash)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Create a new authenticated user with an unverified email and a password.
+pub async fn create_user_with_password(
+    pool: &DbPool,
+    display_name: &str,
+    email: &str,
+    password_hash: &str,
+) -> Result<User, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let user = sqlx::query_as::<_, User>(
+        r#"
+        INSERT INTO users (id, kind, display_name, email, email_verified)
+        VALUES ($1, 'authenticated', $2, $3, FALSE)
+        RETURNING id, kind, role, username, email, email_verified,
+                  display_name, avatar_url, created_at, updated_at, last_seen_at,
+                  games_played, total_score, best_score, deleted_at, leaderboard_public
+        "#,
+    )
+    .bind(dguesser_core::generate_user_id())
+    .bind(display_name)
+    .bind(email)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query("INSERT INTO password_credentials (user_id, password_hash) VALUES ($1, $2)")
+        .bind(&user.id)
+        .bind(password_hash)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(user)
+}
+
+/// Upgrade a guest in place to an authenticated user with an unverified email
+/// and a password. The user ID is unchanged, so all history stays attached.
+pub async fn upgrade_guest_with_password(
+    pool: &DbPool,
+    user_id: &str,
+    display_name: Option<&str>,
+    email: &str,
+    password_hash: &str,
+) -> Result<User, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let user = sqlx::query_as::<_, User>(
+        r#"
+        UPDATE users
+        SET kind = 'authenticated',
+            email = $2,
+            email_verified = FALSE,
+            display_name = COALESCE($3, display_name)
+        WHERE id = $1 AND kind = 'guest' AND deleted_at IS NULL
+        RETURNING id, kind, role, username, email, email_verified,
+                  display_name, avatar_url, created_at, updated_at, last_seen_at,
+                  games_played, total_score, best_score, deleted_at, leaderboard_public
+        "#,
+    )
+    .bind(user_id)
+    .bind(email)
+    .bind(display_name)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(sqlx::Error::RowNotFound)?;
+
+    sqlx::query("INSERT INTO password_credentials (user_id, password_hash) VALUES ($1, $2)")
+        .bind(user_id)
+        .bind(password_hash)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(user)
+}
+
+/// Mark a user's email as verified if it still matches the verified address.
+pub async fn mark_email_verified(
+    pool: &DbPool,
+    user_id: &str,
+    email: &str,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE users SET email_verified = TRUE
+        WHERE id = $1 AND LOWER(email) = LOWER($2) AND deleted_at IS NULL
+        "#,
+    )
+    .bind(user_id)
+    .bind(email)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Store a new single-use token, invalidating earlier unused tokens of the same
+/// purpose for the user.
+pub async fn create_token(
+    pool: &DbPool,
+    token_hash: &str,
+    user_id: &str,
+    purpose: AuthTokenPurpose,
+    email: Option<&str>,
+    ttl_hours: i64,
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        r#"
+        UPDATE auth_tokens SET consumed_at = NOW()
+        WHERE user_id = $1 AND purpose = $2 AND consumed_at IS NULL
+        "#,
+    )
+    .bind(user_id)
+    .bind(purpose.as_str())
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO auth_tokens (token_hash, user_id, purpose, email, expires_at)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+    )
+    .bind(token_hash)
+    .bind(user_id)
+    .bind(purpose.as_str())
+    .bind(email)
+    .bind(Utc::now() + Duration::hours(ttl_hours))
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Atomically consume a valid (unexpired, unused) token.
+///
+/// Returns `None` if the token does not exist, has the wrong purpose, has
+/// expired, or was already used.
+pub async fn consume_token(
+    pool: &DbPool,
+    token_hash: &str,
+    purpose: AuthTokenPurpose,
+) -> Result<Option<AuthToken>, sqlx::Error> {
+    sqlx::query_as::<_, AuthToken>(
+        r#"
+        UPDATE auth_tokens SET consumed_at = NOW()
+        WHERE token_hash = $1
+          AND purpose = $2
+          AND consumed_at IS NULL
+          AND expires_at > NOW()
+        RETURNING token_hash, user_id, purpose, email, created_at, expires_at, consumed_at
+        "#,
+    )
+    .bind(token_hash)
+    .bind(purpose.as_str())
+    .fetch_optional(pool)
+    .await
+}
+
+/// Delete expired or consumed tokens older than a day (call periodically).
+pub async fn cleanup_tokens(pool: &DbPool) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM auth_tokens
+        WHERE expires_at < NOW() - INTERVAL '1 day'
+           OR consumed_at < NOW() - INTERVAL '1 day'
+        "#,
+    )
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
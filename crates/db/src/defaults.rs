@@ -0,0 +1,69 @@
+//! Default data a fresh database needs
+//!
+//! The initial migrations seed the default maps, but databases restored
+//! without them (or with them deleted) still need a world map and an admin.
+
+use crate::DbPool;
+use crate::users::User;
+
+/// ID of the default world map
+pub const WORLD_MAP_ID: &str = "map_Wor1dG1oba1X";
+
+/// Slug of the default world map
+pub const WORLD_MAP_SLUG: &str = "world";
+
+/// Create the world map unless a map with its ID or slug exists. It becomes
+/// the default map when there is no active default. Returns whether it was
+/// created.
+pub async fn ensure_world_map(pool: &DbPool) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO maps (id, slug, name, description, rules, is_default)
+        VALUES (
+            $1, $2, 'World', 'Locations from around the world', '{}',
+            NOT EXISTS (SELECT 1 FROM maps WHERE is_default = TRUE AND active = TRUE)
+        )
+        ON CONFLICT DO NOTHING
+        "#,
+    )
+    .bind(WORLD_MAP_ID)
+    .bind(WORLD_MAP_SLUG)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Make the user with `email` an admin, creating a verified account if there
+/// is none. `password_hash` is set on a created account, or replaces the
+/// password of an existing one. Returns the user and whether it was created.
+pub async fn ensure_admin(
+    pool: &DbPool,
+    email: &str,
+    display_name: &str,
+    password_hash: Option<&str>,
+) -> Result<(User, bool), sqlx::Error> {
+    let existing = crate::credentials::get_user_by_email_ci(pool, email).await?;
+    let created = existing.is_none();
+    let user = match existing {
+        Some(user) => user,
+        None => crate::users::create_authenticated(pool, display_name, Some(email), None).await?,
+    };
+
+    if let Some(password_hash) = password_hash {
+        crate::credentials::set_password(pool, &user.id, password_hash).await?;
+    }
+
+    let user = sqlx::query_as::<_, User>(
+        r#"
+        UPDATE users SET role = 'admin', email_verified = TRUE
+        WHERE id = $1
+        RETURNING id, kind, role, username, email, email_verified,
+                  display_name, avatar_url, created_at, updated_at, last_seen_at,
+                  games_played, total_score, best_score, deleted_at, leaderboard_public
+        "#,
+    )
+    .bind(&user.id)
+    .fetch_one(pool)
+    .await?;
+    Ok((user, created))
+}
@@ -0,0 +1,157 @@
+//! User device database queries
+
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+use crate::DbPool;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct UserDevice {
+    pub id: String,      // dev_XXXXXXXXXXXX
+    pub user_id: String, // usr_XXXXXXXXXXXX
+    pub device_key: String,
+    pub browser: Option<String>,
+    pub os: Option<String>,
+    pub device_type: String,
+    pub last_country: Option<String>,
+    pub trusted: bool,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+/// Device details parsed from a sign-in request.
+#[derive(Debug, Clone, Copy)]
+pub struct DeviceSighting<'a> {
+    pub device_key: &'a str,
+    pub browser: Option<&'a str>,
+    pub os: Option<&'a str>,
+    pub device_type: &'a str,
+    pub country: Option<&'a str>,
+}
+
+/// Result of recording a device sign-in.
+#[derive(Debug, Clone)]
+pub struct RecordedDevice {
+    pub device: UserDevice,
+    /// The device had not been seen for this user before
+    pub is_new: bool,
+    /// The user had other devices before this one
+    pub had_other_devices: bool,
+}
+
+/// Record that a user signed in from a device, creating it if unseen.
+pub async fn record_sighting(
+    pool: &DbPool,
+    user_id: &str,
+    sighting: &DeviceSighting<'_>,
+) -> Result<RecordedDevice, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let had_other_devices: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM user_devices WHERE user_id = $1 AND device_key != $2)",
+    )
+    .bind(user_id)
+    .bind(sighting.device_key)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let row = sqlx::query(
+        r#"
+        INSERT INTO user_devices (id, user_id, device_key, browser, os, device_type, last_country)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (user_id, device_key) DO UPDATE
+            SET last_seen_at = NOW(),
+                last_country = COALESCE(EXCLUDED.last_country, user_devices.last_country)
+        RETURNING id, user_id, device_key, browser, os, device_type, last_country, trusted,
+                  first_seen_at, last_seen_at, (xmax = 0) AS inserted
+        "#,
+    )
+    .bind(dguesser_core::generate_device_id())
+    .bind(user_id)
+    .bind(sighting.device_key)
+    .bind(sighting.browser)
+    .bind(sighting.os)
+    .bind(sighting.device_type)
+    .bind(sighting.country)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    use sqlx::Row;
+    Ok(RecordedDevice {
+        device: UserDevice::from_row(&row)?,
+        is_new: row.try_get("inserted")?,
+        had_other_devices,
+    })
+}
+
+/// List a user's devices, most recently used first.
+pub async fn list_for_user(pool: &DbPool, user_id: &str) -> Result<Vec<UserDevice>, sqlx::Error> {
+    sqlx::query_as::<_, UserDevice>(
+        r#"
+        SELECT id, user_id, device_key, browser, os, device_type, last_country, trusted,
+               first_seen_at, last_seen_at
+        FROM user_devices
+        WHERE user_id = $1
+        ORDER BY last_seen_at DESC
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Mark a device as trusted or untrusted. Returns false if the user has no
+/// such device.
+pub async fn set_trusted(
+    pool: &DbPool,
+    user_id: &str,
+    device_id: &str,
+    trusted: bool,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("UPDATE user_devices SET trusted = $3 WHERE id = $1 AND user_id = $2")
+        .bind(device_id)
+        .bind(user_id)
+        .bind(trusted)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Forget a device: revoke its sessions and delete it, so the next sign-in
+/// from it counts as a new device. Returns the number of sessions revoked,
+/// or `None` if the user has no such device.
+pub async fn forget(
+    pool: &DbPool,
+    user_id: &str,
+    device_id: &str,
+) -> Result<Option<u64>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let revoked = sqlx::query(
+        r#"
+        UPDATE sessions SET revoked_at = NOW()
+        WHERE user_id = $1 AND device_id = $2 AND revoked_at IS NULL
+        "#,
+    )
+    .bind(user_id)
+    .bind(device_id)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    let deleted = sqlx::query("DELETE FROM user_devices WHERE id = $1 AND user_id = $2")
+        .bind(device_id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    if deleted == 0 {
+        return Ok(None);
+    }
+
+    tx.commit().await?;
+    Ok(Some(revoked))
+}
@@ -0,0 +1,214 @@
+//! Email outbox and email preference database queries
+
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+use crate::DbPool;
+
+/// How long a claimed email may stay in `sending` before it is considered
+/// abandoned (e.g. the worker crashed) and becomes eligible again.
+const SENDING_LEASE_SECS: i64 = 600;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct QueuedEmail {
+    pub id: String,              // eml_XXXXXXXXXXXX
+    pub user_id: Option<String>, // usr_XXXXXXXXXXXX
+    pub template: String,
+    pub to_address: String,
+    pub subject: String,
+    pub text_body: String,
+    pub html_body: String,
+    pub status: String,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub sent_at: Option<DateTime<Utc>>,
+}
+
+/// A rendered email ready to be queued.
+#[derive(Debug, Clone)]
+pub struct NewEmail<'a> {
+    pub user_id: Option<&'a str>,
+    pub template: &'a str,
+    pub to_address: &'a str,
+    pub subject: &'a str,
+    pub text_body: &'a str,
+    pub html_body: &'a str,
+}
+
+/// Weekly activity summary for a digest recipient.
+#[derive(Debug, Clone, FromRow)]
+pub struct DigestRecipient {
+    pub user_id: String,
+    pub email: String,
+    pub display_name: String,
+    pub games_played: i64,
+    pub total_score: i64,
+    pub best_score: i32,
+}
+
+/// Add an email to the outbox. Returns the new email ID.
+pub async fn enqueue(pool: &DbPool, email: &NewEmail<'_>) -> Result<String, sqlx::Error> {
+    let id = dguesser_core::generate_email_id();
+
+    sqlx::query(
+        r#"
+        INSERT INTO email_outbox (id, user_id, template, to_address, subject, text_body, html_body)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        "#,
+    )
+    .bind(&id)
+    .bind(email.user_id)
+    .bind(email.template)
+    .bind(email.to_address)
+    .bind(email.subject)
+    .bind(email.text_body)
+    .bind(email.html_body)
+    .execute(pool)
+    .await?;
+
+    Ok(id)
+}
+
+/// Claim up to `limit` due emails for delivery.
+///
+/// Claimed rows move to `sending` with their attempt count incremented, and
+/// hold a lease so concurrent workers skip them. Rows whose lease expired
+/// (worker died mid-send) are claimed again.
+pub async fn claim_due(pool: &DbPool, limit: i64) -> Result<Vec<QueuedEmail>, sqlx::Error> {
+    sqlx::query_as::<_, QueuedEmail>(
+        r#"
+        UPDATE email_outbox
+        SET status = 'sending',
+            attempts = attempts + 1,
+            next_attempt_at = NOW() + make_interval(secs => $2)
+        WHERE id IN (
+            SELECT id FROM email_outbox
+            WHERE status IN ('pending', 'sending') AND next_attempt_at <= NOW()
+            ORDER BY next_attempt_at
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING id, user_id, template, to_address, subject, text_body, html_body,
+                  status, attempts, max_attempts, next_attempt_at, last_error,
+                  created_at, sent_at
+        "#,
+    )
+    .bind(limit)
+    .bind(SENDING_LEASE_SECS as f64)
+    .fetch_all(pool)
+    .await
+}
+
+/// Mark an email as delivered.
+pub async fn mark_sent(pool: &DbPool, id: &str) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE email_outbox SET status = 'sent', sent_at = NOW(), last_error = NULL WHERE id = $1",
+    )
+    .bind(id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Record a failed delivery attempt.
+///
+/// With `retry_at` the email goes back to `pending`; without it the email is
+/// given up on and marked `failed`.
+pub async fn mark_failed(
+    pool: &DbPool,
+    id: &str,
+    error: &str,
+    retry_at: Option<DateTime<Utc>>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE email_outbox
+        SET status = CASE WHEN $3::timestamptz IS NULL THEN 'failed' ELSE 'pending' END,
+            next_attempt_at = COALESCE($3, next_attempt_at),
+            last_error = $2
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .bind(error)
+    .bind(retry_at)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Delete sent or failed emails older than `days` (call periodically).
+pub async fn cleanup_finished(pool: &DbPool, days: i32) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM email_outbox
+        WHERE status IN ('sent', 'failed')
+          AND created_at < NOW() - make_interval(days => $1)
+        "#,
+    )
+    .bind(days)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Whether the user has opted into the weekly stats digest.
+pub async fn get_weekly_digest(pool: &DbPool, user_id: &str) -> Result<bool, sqlx::Error> {
+    let enabled: Option<bool> =
+        sqlx::query_scalar("SELECT weekly_digest FROM email_preferences WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
+    Ok(enabled.unwrap_or(false))
+}
+
+/// Opt in to or out of the weekly stats digest.
+pub async fn set_weekly_digest(
+    pool: &DbPool,
+    user_id: &str,
+    enabled: bool,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO email_preferences (user_id, weekly_digest)
+        VALUES ($1, $2)
+        ON CONFLICT (user_id) DO UPDATE SET weekly_digest = EXCLUDED.weekly_digest
+        "#,
+    )
+    .bind(user_id)
+    .bind(enabled)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Opted-in users with a verified email who finished at least one game in the
+/// last seven days, with their activity for that week.
+pub async fn get_digest_recipients(pool: &DbPool) -> Result<Vec<DigestRecipient>, sqlx::Error> {
+    sqlx::query_as::<_, DigestRecipient>(
+        r#"
+        SELECT u.id AS user_id,
+               u.email AS email,
+               u.display_name,
+               COUNT(g.id) AS games_played,
+               COALESCE(SUM(gp.score_total), 0)::BIGINT AS total_score,
+               COALESCE(MAX(gp.score_total), 0) AS best_score
+        FROM users u
+        INNER JOIN email_preferences ep ON ep.user_id = u.id AND ep.weekly_digest
+        INNER JOIN game_players gp ON gp.user_id = u.id
+        INNER JOIN games g ON g.id = gp.game_id
+        WHERE u.email IS NOT NULL
+          AND u.email_verified
+          AND u.deleted_at IS NULL
+          AND g.status = 'finished'
+          AND g.mode <> 'streak'
+          AND g.ended_at >= NOW() - INTERVAL '7 days'
+        GROUP BY u.id, u.email, u.display_name
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,279 @@
+//! Featured map schedule queries
+//!
+//! A featured map is a system map that is the default map for a scheduled
+//! period. [`run_schedule`] applies the schedule: it restores the previous
+//! default when a feature ends and makes the next map the default when its
+//! feature starts.
+
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgConnection};
+
+use crate::DbPool;
+
+/// A scheduled, running, or finished feature.
+#[derive(Debug, Clone, FromRow)]
+pub struct FeaturedMap {
+    pub id: i64,
+    pub map_id: String,
+    pub map_slug: String,
+    pub map_name: String,
+    /// Event name shown alongside the map
+    pub title: Option<String>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// When the map was made the default
+    pub activated_at: Option<DateTime<Utc>>,
+    /// When the feature ended, or was skipped because the map was unavailable
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// Changes made by one [`run_schedule`] call.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScheduleChanges {
+    /// Features whose map was made the default
+    pub started: u64,
+    /// Running features that ended
+    pub ended: u64,
+    /// Features dropped because their map was inactive or they ended before
+    /// starting
+    pub skipped: u64,
+}
+
+const FEATURED_COLUMNS: &str = r#"
+    f.id, f.map_id, m.slug AS map_slug, m.name AS map_name, f.title, f.starts_at,
+    f.ends_at, f.created_by, f.created_at, f.activated_at, f.finished_at
+"#;
+
+/// Schedule a map to be featured from `starts_at` until `ends_at`.
+pub async fn create(
+    pool: &DbPool,
+    map_id: &str,
+    title: Option<&str>,
+    starts_at: DateTime<Utc>,
+    ends_at: DateTime<Utc>,
+    created_by: &str,
+) -> Result<FeaturedMap, sqlx::Error> {
+    sqlx::query_as(&format!(
+        r#"
+        WITH f AS (
+            INSERT INTO featured_maps (map_id, title, starts_at, ends_at, created_by)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+        )
+        SELECT {FEATURED_COLUMNS}
+        FROM f
+        JOIN maps m ON m.id = f.map_id
+        "#
+    ))
+    .bind(map_id)
+    .bind(title)
+    .bind(starts_at)
+    .bind(ends_at)
+    .bind(created_by)
+    .fetch_one(pool)
+    .await
+}
+
+/// Find an unfinished feature overlapping `starts_at..ends_at`.
+pub async fn find_overlapping(
+    pool: &DbPool,
+    starts_at: DateTime<Utc>,
+    ends_at: DateTime<Utc>,
+) -> Result<Option<FeaturedMap>, sqlx::Error> {
+    sqlx::query_as(&format!(
+        r#"
+        SELECT {FEATURED_COLUMNS}
+        FROM featured_maps f
+        JOIN maps m ON m.id = f.map_id
+        WHERE f.finished_at IS NULL AND f.starts_at < $2 AND f.ends_at > $1
+        ORDER BY f.starts_at
+        LIMIT 1
+        "#
+    ))
+    .bind(starts_at)
+    .bind(ends_at)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Unfinished features and those finished in the last `history_days` days,
+/// in start order.
+pub async fn list(pool: &DbPool, history_days: i32) -> Result<Vec<FeaturedMap>, sqlx::Error> {
+    sqlx::query_as(&format!(
+        r#"
+        SELECT {FEATURED_COLUMNS}
+        FROM featured_maps f
+        JOIN maps m ON m.id = f.map_id
+        WHERE f.finished_at IS NULL
+           OR f.finished_at >= NOW() - make_interval(days => $1)
+        ORDER BY f.starts_at
+        "#
+    ))
+    .bind(history_days)
+    .fetch_all(pool)
+    .await
+}
+
+/// The running feature, if any.
+pub async fn current(pool: &DbPool) -> Result<Option<FeaturedMap>, sqlx::Error> {
+    sqlx::query_as(&format!(
+        r#"
+        SELECT {FEATURED_COLUMNS}
+        FROM featured_maps f
+        JOIN maps m ON m.id = f.map_id
+        WHERE f.activated_at IS NOT NULL AND f.finished_at IS NULL
+        ORDER BY f.activated_at DESC
+        LIMIT 1
+        "#
+    ))
+    .fetch_optional(pool)
+    .await
+}
+
+/// The next `limit` features that have not started yet.
+pub async fn upcoming(pool: &DbPool, limit: i64) -> Result<Vec<FeaturedMap>, sqlx::Error> {
+    sqlx::query_as(&format!(
+        r#"
+        SELECT {FEATURED_COLUMNS}
+        FROM featured_maps f
+        JOIN maps m ON m.id = f.map_id
+        WHERE f.activated_at IS NULL AND f.finished_at IS NULL AND m.active = TRUE
+        ORDER BY f.starts_at
+        LIMIT $1
+        "#
+    ))
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+/// Cancel a feature: one that has not started is deleted, a running one is
+/// ended now (the next [`run_schedule`] restores the previous default).
+/// Returns `false` if there is no such unfinished feature.
+pub async fn cancel(pool: &DbPool, id: i64) -> Result<bool, sqlx::Error> {
+    let deleted = sqlx::query("DELETE FROM featured_maps WHERE id = $1 AND activated_at IS NULL")
+        .bind(id)
+        .execute(pool)
+        .await?;
+    if deleted.rows_affected() > 0 {
+        return Ok(true);
+    }
+
+    let ended = sqlx::query(
+        r#"
+        UPDATE featured_maps
+        SET ends_at = NOW()
+        WHERE id = $1 AND activated_at IS NOT NULL AND finished_at IS NULL
+        "#,
+    )
+    .bind(id)
+    .execute(pool)
+    .await?;
+    Ok(ended.rows_affected() > 0)
+}
+
+/// Apply the schedule: end features whose period is over, then start the
+/// feature whose period has begun.
+///
+/// Safe to run concurrently; each feature is started and ended once.
+pub async fn run_schedule(pool: &DbPool) -> Result<ScheduleChanges, sqlx::Error> {
+    let mut changes = ScheduleChanges::default();
+    let mut tx = pool.begin().await?;
+
+    let finished: Vec<(String, Option<DateTime<Utc>>, Option<String>)> = sqlx::query_as(
+        r#"
+        UPDATE featured_maps
+        SET finished_at = NOW()
+        WHERE finished_at IS NULL AND ends_at <= NOW()
+        RETURNING map_id, activated_at, previous_default_map_id
+        "#,
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    for (map_id, activated_at, previous_default) in finished {
+        if activated_at.is_none() {
+            changes.skipped += 1;
+            continue;
+        }
+        changes.ended += 1;
+
+        // Leave the default alone if an admin changed it during the feature
+        let still_default: bool =
+            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM maps WHERE id = $1 AND is_default)")
+                .bind(&map_id)
+                .fetch_one(&mut *tx)
+                .await?;
+        if let Some(previous) = previous_default.filter(|_| still_default) {
+            make_default(&mut tx, &previous).await?;
+        }
+    }
+
+    loop {
+        let due: Option<(i64, String)> = sqlx::query_as(
+            r#"
+            SELECT id, map_id
+            FROM featured_maps
+            WHERE activated_at IS NULL AND finished_at IS NULL AND starts_at <= NOW()
+            ORDER BY starts_at
+            LIMIT 1
+            FOR UPDATE SKIP LOCKED
+            "#,
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some((id, map_id)) = due else {
+            break;
+        };
+
+        let previous_default: Option<String> =
+            sqlx::query_scalar("SELECT id FROM maps WHERE is_default LIMIT 1")
+                .fetch_optional(&mut *tx)
+                .await?;
+
+        if make_default(&mut tx, &map_id).await? {
+            changes.started += 1;
+            sqlx::query(
+                r#"
+                UPDATE featured_maps
+                SET activated_at = NOW(), previous_default_map_id = $2
+                WHERE id = $1
+                "#,
+            )
+            .bind(id)
+            .bind(previous_default.filter(|previous| *previous != map_id))
+            .execute(&mut *tx)
+            .await?;
+        } else {
+            changes.skipped += 1;
+            sqlx::query("UPDATE featured_maps SET finished_at = NOW() WHERE id = $1")
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        }
+    }
+
+    tx.commit().await?;
+    Ok(changes)
+}
+
+/// Make an active system map the only default map. Returns `false` if there
+/// is no such map.
+async fn make_default(conn: &mut PgConnection, map_id: &str) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE maps
+        SET is_default = (id = $1), updated_at = NOW()
+        WHERE (is_default = TRUE OR id = $1)
+          AND EXISTS (
+              SELECT 1 FROM maps WHERE id = $1 AND creator_id IS NULL AND active = TRUE
+          )
+        "#,
+    )
+    .bind(map_id)
+    .execute(conn)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
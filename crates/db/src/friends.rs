@@ -0,0 +1,188 @@
+//! Friend database queries
+
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+use crate::DbPool;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct Friendship {
+    pub requester_id: String,
+    pub addressee_id: String,
+    /// 'pending' or 'accepted'
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub accepted_at: Option<DateTime<Utc>>,
+}
+
+impl Friendship {
+    pub fn is_accepted(&self) -> bool {
+        self.status == "accepted"
+    }
+}
+
+/// The other user of a friendship or friend request
+#[derive(Debug, Clone, FromRow)]
+pub struct FriendUser {
+    pub user_id: String,
+    pub username: Option<String>,
+    pub display_name: String,
+    pub avatar_url: Option<String>,
+    /// When the request was sent, or accepted for friends
+    pub since: DateTime<Utc>,
+}
+
+/// Get the friendship or pending request between two users, in either direction
+pub async fn get_friendship(
+    pool: &DbPool,
+    user_a: &str,
+    user_b: &str,
+) -> Result<Option<Friendship>, sqlx::Error> {
+    sqlx::query_as::<_, Friendship>(
+        r#"
+        SELECT requester_id, addressee_id, status, created_at, accepted_at
+        FROM friendships
+        WHERE (requester_id = $1 AND addressee_id = $2)
+           OR (requester_id = $2 AND addressee_id = $1)
+        "#,
+    )
+    .bind(user_a)
+    .bind(user_b)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Create a pending friend request
+pub async fn create_request(
+    pool: &DbPool,
+    requester_id: &str,
+    addressee_id: &str,
+) -> Result<Friendship, sqlx::Error> {
+    sqlx::query_as::<_, Friendship>(
+        r#"
+        INSERT INTO friendships (requester_id, addressee_id)
+        VALUES ($1, $2)
+        RETURNING requester_id, addressee_id, status, created_at, accepted_at
+        "#,
+    )
+    .bind(requester_id)
+    .bind(addressee_id)
+    .fetch_one(pool)
+    .await
+}
+
+/// Accept a pending request from `requester_id` to `addressee_id`
+pub async fn accept_request(
+    pool: &DbPool,
+    requester_id: &str,
+    addressee_id: &str,
+) -> Result<Option<Friendship>, sqlx::Error> {
+    sqlx::query_as::<_, Friendship>(
+        r#"
+        UPDATE friendships
+        SET status = 'accepted', accepted_at = NOW()
+        WHERE requester_id = $1 AND addressee_id = $2 AND status = 'pending'
+        RETURNING requester_id, addressee_id, status, created_at, accepted_at
+        "#,
+    )
+    .bind(requester_id)
+    .bind(addressee_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Delete the friendship or pending request between two users.
+/// Returns whether anything was deleted.
+pub async fn delete_friendship(
+    pool: &DbPool,
+    user_a: &str,
+    user_b: &str,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM friendships
+        WHERE (requester_id = $1 AND addressee_id = $2)
+           OR (requester_id = $2 AND addressee_id = $1)
+        "#,
+    )
+    .bind(user_a)
+    .bind(user_b)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// List a user's friends, ordered by display name
+pub async fn list_friends(pool: &DbPool, user_id: &str) -> Result<Vec<FriendUser>, sqlx::Error> {
+    sqlx::query_as::<_, FriendUser>(
+        r#"
+        SELECT u.id AS user_id, u.username, u.display_name, u.avatar_url,
+               COALESCE(f.accepted_at, f.created_at) AS since
+        FROM friendships f
+        INNER JOIN users u
+            ON u.id = CASE WHEN f.requester_id = $1 THEN f.addressee_id ELSE f.requester_id END
+        WHERE (f.requester_id = $1 OR f.addressee_id = $1) AND f.status = 'accepted'
+        ORDER BY LOWER(u.display_name), u.id
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// List pending requests sent to a user, newest first
+pub async fn list_incoming_requests(
+    pool: &DbPool,
+    user_id: &str,
+) -> Result<Vec<FriendUser>, sqlx::Error> {
+    sqlx::query_as::<_, FriendUser>(
+        r#"
+        SELECT u.id AS user_id, u.username, u.display_name, u.avatar_url, f.created_at AS since
+        FROM friendships f
+        INNER JOIN users u ON u.id = f.requester_id
+        WHERE f.addressee_id = $1 AND f.status = 'pending'
+        ORDER BY f.created_at DESC
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// List pending requests a user has sent, newest first
+pub async fn list_outgoing_requests(
+    pool: &DbPool,
+    user_id: &str,
+) -> Result<Vec<FriendUser>, sqlx::Error> {
+    sqlx::query_as::<_, FriendUser>(
+        r#"
+        SELECT u.id AS user_id, u.username, u.display_name, u.avatar_url, f.created_at AS since
+        FROM friendships f
+        INNER JOIN users u ON u.id = f.addressee_id
+        WHERE f.requester_id = $1 AND f.status = 'pending'
+        ORDER BY f.created_at DESC
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// IDs of a user's friends
+pub async fn list_friend_ids(pool: &DbPool, user_id: &str) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT CASE WHEN requester_id = $1 THEN addressee_id ELSE requester_id END
+        FROM friendships
+        WHERE (requester_id = $1 OR addressee_id = $1) AND status = 'accepted'
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Check whether two users are friends
+pub async fn are_friends(pool: &DbPool, user_a: &str, user_b: &str) -> Result<bool, sqlx::Error> {
+    Ok(get_friendship(pool, user_a, user_b).await?.is_some_and(|f| f.is_accepted()))
+}
@@ -0,0 +1,241 @@
+//! Game archival queries
+//!
+//! Old finished games keep their `games` and `game_players` rows; their
+//! rounds, guesses, hints, challenge progress and rerolls are exported as
+//! JSON rows, stored elsewhere and deleted. [`restore_game`] puts them back.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgConnection};
+
+use crate::DbPool;
+
+/// A game due for archival
+#[derive(Debug, Clone, FromRow)]
+pub struct ArchiveCandidate {
+    pub id: String,
+    pub ended_at: DateTime<Utc>,
+}
+
+/// Where a game's detail rows live
+#[derive(Debug, Clone, FromRow)]
+pub struct ArchiveStatus {
+    /// Set while the detail rows are only in the archive
+    pub archived_at: Option<DateTime<Utc>>,
+    pub archive_key: Option<String>,
+}
+
+/// A game's detail rows, one JSON object per row
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GameDetailRows {
+    pub rounds: Vec<serde_json::Value>,
+    pub guesses: Vec<serde_json::Value>,
+    pub player_rounds: Vec<serde_json::Value>,
+    pub round_rerolls: Vec<serde_json::Value>,
+    pub round_hints: Vec<serde_json::Value>,
+}
+
+/// Lock the next game that ended before `ended_before` and has not been
+/// archived. Games restored after `rehydrated_before` are skipped so they can
+/// be viewed for a while. The lock is held until the caller's transaction
+/// ends; games locked by other archivers are skipped.
+pub async fn lock_next_candidate(
+    conn: &mut PgConnection,
+    ended_before: DateTime<Utc>,
+    rehydrated_before: DateTime<Utc>,
+) -> Result<Option<ArchiveCandidate>, sqlx::Error> {
+    sqlx::query_as::<_, ArchiveCandidate>(
+        r#"
+        SELECT id, ended_at
+        FROM games
+        WHERE archived_at IS NULL
+          AND status IN ('finished', 'abandoned')
+          AND ended_at < $1
+          AND (rehydrated_at IS NULL OR rehydrated_at < $2)
+        ORDER BY ended_at
+        LIMIT 1
+        FOR UPDATE SKIP LOCKED
+        "#,
+    )
+    .bind(ended_before)
+    .bind(rehydrated_before)
+    .fetch_optional(conn)
+    .await
+}
+
+/// Export a game's detail rows.
+pub async fn export_game(
+    conn: &mut PgConnection,
+    game_id: &str,
+) -> Result<GameDetailRows, sqlx::Error> {
+    let rounds = sqlx::query_scalar(
+        "SELECT to_jsonb(r) FROM rounds r WHERE r.game_id = $1 ORDER BY r.round_number",
+    )
+    .bind(game_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let guesses = sqlx::query_scalar(
+        r#"
+        SELECT to_jsonb(g)
+        FROM guesses g
+        INNER JOIN rounds r ON r.id = g.round_id
+        WHERE r.game_id = $1
+        ORDER BY r.round_number, g.submitted_at
+        "#,
+    )
+    .bind(game_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let player_rounds = sqlx::query_scalar(
+        r#"
+        SELECT to_jsonb(pr)
+        FROM player_rounds pr
+        INNER JOIN rounds r ON r.id = pr.round_id
+        WHERE r.game_id = $1
+        ORDER BY r.round_number, pr.started_at
+        "#,
+    )
+    .bind(game_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let round_rerolls = sqlx::query_scalar(
+        "SELECT to_jsonb(rr) FROM round_rerolls rr WHERE rr.game_id = $1 ORDER BY rr.id",
+    )
+    .bind(game_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let round_hints = sqlx::query_scalar(
+        r#"
+        SELECT to_jsonb(rh)
+        FROM round_hints rh
+        INNER JOIN rounds r ON r.id = rh.round_id
+        WHERE r.game_id = $1
+        ORDER BY r.round_number, rh.created_at
+        "#,
+    )
+    .bind(game_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(GameDetailRows { rounds, guesses, player_rounds, round_rerolls, round_hints })
+}
+
+/// Delete a game's detail rows and record where the archive is.
+pub async fn mark_archived(
+    conn: &mut PgConnection,
+    game_id: &str,
+    archive_key: &str,
+) -> Result<(), sqlx::Error> {
+    // Guesses, hints, challenge progress and rerolls go with their rounds
+    sqlx::query("DELETE FROM rounds WHERE game_id = $1").bind(game_id).execute(&mut *conn).await?;
+
+    sqlx::query(
+        "UPDATE games SET archived_at = NOW(), archive_key = $2, rehydrated_at = NULL WHERE id = $1",
+    )
+    .bind(game_id)
+    .bind(archive_key)
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+/// Get whether a game is archived and where.
+pub async fn get_status(
+    pool: &DbPool,
+    game_id: &str,
+) -> Result<Option<ArchiveStatus>, sqlx::Error> {
+    sqlx::query_as::<_, ArchiveStatus>("SELECT archived_at, archive_key FROM games WHERE id = $1")
+        .bind(game_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Put an archived game's detail rows back.
+///
+/// Rows of users deleted since the game was archived are left out. Returns
+/// false if the game is not archived (another request restored it first).
+pub async fn restore_game(
+    conn: &mut PgConnection,
+    game_id: &str,
+    rows: &GameDetailRows,
+) -> Result<bool, sqlx::Error> {
+    let claimed = sqlx::query(
+        r#"
+        UPDATE games SET archived_at = NULL, rehydrated_at = NOW()
+        WHERE id = $1 AND archived_at IS NOT NULL
+        "#,
+    )
+    .bind(game_id)
+    .execute(&mut *conn)
+    .await?;
+    if claimed.rows_affected() == 0 {
+        return Ok(false);
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO rounds
+        SELECT * FROM jsonb_populate_recordset(NULL::rounds, $1) WHERE game_id = $2
+        "#,
+    )
+    .bind(serde_json::Value::from(rows.rounds.clone()))
+    .bind(game_id)
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO guesses
+        SELECT g.* FROM jsonb_populate_recordset(NULL::guesses, $1) g
+        WHERE EXISTS (SELECT 1 FROM users u WHERE u.id = g.user_id)
+        "#,
+    )
+    .bind(serde_json::Value::from(rows.guesses.clone()))
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO player_rounds
+        SELECT pr.* FROM jsonb_populate_recordset(NULL::player_rounds, $1) pr
+        WHERE EXISTS (SELECT 1 FROM users u WHERE u.id = pr.user_id)
+        "#,
+    )
+    .bind(serde_json::Value::from(rows.player_rounds.clone()))
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO round_rerolls
+        SELECT (jsonb_populate_record(
+            NULL::round_rerolls,
+            rr || CASE
+                WHEN EXISTS (SELECT 1 FROM users u WHERE u.id = rr->>'rerolled_by') THEN '{}'
+                ELSE '{"rerolled_by": null}'
+            END::jsonb
+        )).*
+        FROM jsonb_array_elements($1) AS rr
+        "#,
+    )
+    .bind(serde_json::Value::from(rows.round_rerolls.clone()))
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO round_hints
+        SELECT rh.* FROM jsonb_populate_recordset(NULL::round_hints, $1) rh
+        WHERE EXISTS (SELECT 1 FROM users u WHERE u.id = rh.user_id)
+        "#,
+    )
+    .bind(serde_json::Value::from(rows.round_hints.clone()))
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(true)
+}
@@ -0,0 +1,176 @@
+//! Game invite database queries
+//!
+//! Invites are looked up by the SHA-256 hash of their token; the raw token is
+//! only ever shown to the host who created it.
+
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+use crate::DbPool;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct GameInvite {
+    pub id: i64,
+    pub game_id: String,
+    pub created_by: Option<String>,
+    pub max_uses: i32,
+    pub uses: i32,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl GameInvite {
+    /// Whether the invite can still be redeemed by a new user.
+    pub fn is_usable(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at > now && self.uses < self.max_uses
+    }
+}
+
+/// Why an invite could not be redeemed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedeemError {
+    /// No invite has this token
+    NotFound,
+    /// The invite was revoked, has expired, or has no uses left
+    Unusable,
+}
+
+const INVITE_COLUMNS: &str =
+    "id, game_id, created_by, max_uses, uses, expires_at, revoked_at, created_at";
+
+/// Create an invite for a game.
+pub async fn create(
+    pool: &DbPool,
+    game_id: &str,
+    token_hash: &str,
+    created_by: &str,
+    max_uses: i32,
+    expires_at: DateTime<Utc>,
+) -> Result<GameInvite, sqlx::Error> {
+    sqlx::query_as(&format!(
+        r#"
+        INSERT INTO game_invites (game_id, token_hash, created_by, max_uses, expires_at)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING {INVITE_COLUMNS}
+        "#
+    ))
+    .bind(game_id)
+    .bind(token_hash)
+    .bind(created_by)
+    .bind(max_uses)
+    .bind(expires_at)
+    .fetch_one(pool)
+    .await
+}
+
+/// Count a game's invites that can still be redeemed.
+pub async fn count_usable(pool: &DbPool, game_id: &str) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*) FROM game_invites
+        WHERE game_id = $1 AND revoked_at IS NULL AND expires_at > NOW() AND uses < max_uses
+        "#,
+    )
+    .bind(game_id)
+    .fetch_one(pool)
+    .await
+}
+
+/// List a game's invites, newest first.
+pub async fn list_for_game(pool: &DbPool, game_id: &str) -> Result<Vec<GameInvite>, sqlx::Error> {
+    sqlx::query_as(&format!(
+        r#"
+        SELECT {INVITE_COLUMNS}
+        FROM game_invites
+        WHERE game_id = $1
+        ORDER BY created_at DESC, id DESC
+        "#
+    ))
+    .bind(game_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Get an invite by token hash.
+pub async fn get_by_token_hash(
+    pool: &DbPool,
+    token_hash: &str,
+) -> Result<Option<GameInvite>, sqlx::Error> {
+    sqlx::query_as(&format!("SELECT {INVITE_COLUMNS} FROM game_invites WHERE token_hash = $1"))
+        .bind(token_hash)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Revoke an invite of a game. Returns `false` if the game has no such
+/// unrevoked invite.
+pub async fn revoke(pool: &DbPool, game_id: &str, invite_id: i64) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE game_invites SET revoked_at = NOW()
+        WHERE id = $1 AND game_id = $2 AND revoked_at IS NULL
+        "#,
+    )
+    .bind(invite_id)
+    .bind(game_id)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Redeem an invite for a user.
+///
+/// Uses up one redemption unless the user already redeemed this invite, in
+/// which case it succeeds again as long as it has not been revoked or
+/// expired.
+pub async fn redeem(
+    pool: &DbPool,
+    token_hash: &str,
+    user_id: &str,
+) -> Result<Result<GameInvite, RedeemError>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let invite: Option<GameInvite> = sqlx::query_as(&format!(
+        "SELECT {INVITE_COLUMNS} FROM game_invites WHERE token_hash = $1 FOR UPDATE"
+    ))
+    .bind(token_hash)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some(mut invite) = invite else {
+        return Ok(Err(RedeemError::NotFound));
+    };
+
+    let now = Utc::now();
+    if invite.revoked_at.is_some() || invite.expires_at <= now {
+        return Ok(Err(RedeemError::Unusable));
+    }
+
+    let first_redemption = sqlx::query(
+        r#"
+        INSERT INTO game_invite_redemptions (invite_id, user_id)
+        VALUES ($1, $2)
+        ON CONFLICT DO NOTHING
+        "#,
+    )
+    .bind(invite.id)
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected()
+        > 0;
+
+    if first_redemption {
+        if invite.uses >= invite.max_uses {
+            return Ok(Err(RedeemError::Unusable));
+        }
+        sqlx::query("UPDATE game_invites SET uses = uses + 1 WHERE id = $1")
+            .bind(invite.id)
+            .execute(&mut *tx)
+            .await?;
+        invite.uses += 1;
+    }
+
+    tx.commit().await?;
+    Ok(Ok(invite))
+}
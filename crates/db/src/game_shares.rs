@@ -0,0 +1,126 @@
+//! Game share link database queries
+//!
+//! Share links are looked up by the SHA-256 hash of their token; the raw
+//! token is only ever shown to the player who created the link.
+
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+use crate::DbPool;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct GameShareLink {
+    pub id: i64,
+    pub game_id: String,
+    pub created_by: Option<String>,
+    pub views: i32,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl GameShareLink {
+    /// Whether the link still opens the replay.
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at > now
+    }
+}
+
+const SHARE_COLUMNS: &str = "id, game_id, created_by, views, expires_at, revoked_at, created_at";
+
+/// Create a share link for a game.
+pub async fn create(
+    pool: &DbPool,
+    game_id: &str,
+    token_hash: &str,
+    created_by: &str,
+    expires_at: DateTime<Utc>,
+) -> Result<GameShareLink, sqlx::Error> {
+    sqlx::query_as(&format!(
+        r#"
+        INSERT INTO game_share_links (game_id, token_hash, created_by, expires_at)
+        VALUES ($1, $2, $3, $4)
+        RETURNING {SHARE_COLUMNS}
+        "#
+    ))
+    .bind(game_id)
+    .bind(token_hash)
+    .bind(created_by)
+    .bind(expires_at)
+    .fetch_one(pool)
+    .await
+}
+
+/// Count a user's share links for a game that still work.
+pub async fn count_active(
+    pool: &DbPool,
+    game_id: &str,
+    created_by: &str,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*) FROM game_share_links
+        WHERE game_id = $1 AND created_by = $2 AND revoked_at IS NULL AND expires_at > NOW()
+        "#,
+    )
+    .bind(game_id)
+    .bind(created_by)
+    .fetch_one(pool)
+    .await
+}
+
+/// List a user's share links for a game, newest first.
+pub async fn list_for_game(
+    pool: &DbPool,
+    game_id: &str,
+    created_by: &str,
+) -> Result<Vec<GameShareLink>, sqlx::Error> {
+    sqlx::query_as(&format!(
+        r#"
+        SELECT {SHARE_COLUMNS}
+        FROM game_share_links
+        WHERE game_id = $1 AND created_by = $2
+        ORDER BY created_at DESC, id DESC
+        "#
+    ))
+    .bind(game_id)
+    .bind(created_by)
+    .fetch_all(pool)
+    .await
+}
+
+/// Look up a working link by token hash and count the view.
+pub async fn open(pool: &DbPool, token_hash: &str) -> Result<Option<GameShareLink>, sqlx::Error> {
+    sqlx::query_as(&format!(
+        r#"
+        UPDATE game_share_links SET views = views + 1
+        WHERE token_hash = $1 AND revoked_at IS NULL AND expires_at > NOW()
+        RETURNING {SHARE_COLUMNS}
+        "#
+    ))
+    .bind(token_hash)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Revoke one of a user's share links for a game. Returns `false` if there
+/// is no such unrevoked link.
+pub async fn revoke(
+    pool: &DbPool,
+    game_id: &str,
+    share_id: i64,
+    created_by: &str,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE game_share_links SET revoked_at = NOW()
+        WHERE id = $1 AND game_id = $2 AND created_by = $3 AND revoked_at IS NULL
+        "#,
+    )
+    .bind(share_id)
+    .bind(game_id)
+    .bind(created_by)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
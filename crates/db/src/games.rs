@@ -1,7 +1,8 @@
 //! Game database queries
 
 use chrono::{DateTime, Utc};
-use sqlx::FromRow;
+use dguesser_core::streetview::ImageryProvider;
+use sqlx::{Acquire, FromRow, PgConnection, PgExecutor};
 
 use crate::DbPool;
 
@@ -12,6 +13,14 @@ pub enum GameMode {
     Solo,
     Multiplayer,
     Challenge,
+    Streak,
+}
+
+impl GameMode {
+    /// Whether the game is played by a single player through the REST API
+    pub fn is_single_player(self) -> bool {
+        matches!(self, GameMode::Solo | GameMode::Streak)
+    }
 }
 
 impl std::fmt::Display for GameMode {
@@ -20,6 +29,7 @@ impl std::fmt::Display for GameMode {
             GameMode::Solo => write!(f, "solo"),
             GameMode::Multiplayer => write!(f, "multiplayer"),
             GameMode::Challenge => write!(f, "challenge"),
+            GameMode::Streak => write!(f, "streak"),
         }
     }
 }
@@ -68,6 +78,8 @@ pub struct GamePlayer {
     pub is_host: bool,
     pub score_total: i32,
     pub final_rank: Option<i32>,
+    /// Multiplier applied to round scores
+    pub handicap: f64,
 }
 
 #[derive(Debug, Clone, FromRow)]
@@ -80,6 +92,8 @@ pub struct Round {
     pub panorama_id: Option<String>,
     pub location_id: Option<String>, // loc_XXXXXXXXXXXX (for reporting)
     pub heading: Option<f64>,        // Default heading for panorama
+    pub provider: String,            // Imagery provider (google_streetview, mapillary, ...)
+    pub country_code: Option<String>,
     pub started_at: Option<DateTime<Utc>>,
     pub ended_at: Option<DateTime<Utc>>,
     pub time_limit_ms: Option<i32>,
@@ -96,40 +110,151 @@ pub struct Guess {
     pub score: i32,
     pub submitted_at: DateTime<Utc>,
     pub time_taken_ms: Option<i32>,
+    /// Country the player named (streak games)
+    pub country_code: Option<String>,
+}
+
+/// A solo round result and its signature, for checking the score chain
+#[derive(Debug, Clone, FromRow)]
+pub struct ScoreLink {
+    pub round_number: i16,
+    pub score: i32,
+    /// Signature chaining this result to the previous round's
+    pub score_mac: Option<String>,
 }
 
 // =============================================================================
 // Game CRUD operations
 // =============================================================================
 
-/// Create a new game
+/// How a game's join code is chosen
+#[derive(Debug, Clone, Copy)]
+pub enum JoinCodeChoice<'a> {
+    /// No join code (solo, streak and challenge games)
+    None,
+    /// A random code of this length, regenerated on collision
+    Random(usize),
+    /// A code picked by the host; a collision is returned as a unique violation
+    Vanity(&'a str),
+}
+
+impl JoinCodeChoice<'_> {
+    fn code(&self) -> Option<String> {
+        match self {
+            JoinCodeChoice::None => None,
+            JoinCodeChoice::Random(length) => {
+                Some(dguesser_core::join_code::generate_join_code(*length))
+            }
+            JoinCodeChoice::Vanity(code) => Some(code.to_string()),
+        }
+    }
+}
+
+/// Attempts at finding an unused random join code before giving up
+const JOIN_CODE_ATTEMPTS: usize = 5;
+
+/// Whether an error is a clash with another game's join code
+fn is_join_code_collision(err: &sqlx::Error) -> bool {
+    matches!(err, sqlx::Error::Database(db) if db.constraint() == Some("games_join_code_unique"))
+}
+
+/// Create a new game, in an organization or (with no `org_id`) the public
+/// instance
 pub async fn create_game(
     pool: &DbPool,
     mode: GameMode,
     created_by: &str,
-    join_code: Option<&str>,
+    join_code: JoinCodeChoice<'_>,
     settings: serde_json::Value,
+    org_id: Option<&str>,
 ) -> Result<Game, sqlx::Error> {
     let id = dguesser_core::generate_game_id();
 
-    sqlx::query_as!(
-        Game,
+    let mut attempt = 1;
+    loop {
+        let code = join_code.code();
+        let result = sqlx::query_as::<_, Game>(
+            r#"
+        INSERT INTO games (id, mode, created_by, join_code, settings, org_id)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING id, mode, status, join_code, created_by, created_at, started_at, ended_at,
+                  settings, total_score
+        "#,
+        )
+        .bind(&id)
+        .bind(mode)
+        .bind(created_by)
+        .bind(code)
+        .bind(&settings)
+        .bind(org_id)
+        .fetch_one(pool)
+        .await;
+
+        match result {
+            Err(e)
+                if matches!(join_code, JoinCodeChoice::Random(_))
+                    && attempt < JOIN_CODE_ATTEMPTS
+                    && is_join_code_collision(&e) =>
+            {
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Count the multiplayer lobbies a user created that have not started yet
+pub async fn count_open_lobbies(pool: &DbPool, user_id: &str) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(
         r#"
-        INSERT INTO games (id, mode, created_by, join_code, settings)
-        VALUES ($1, $2, $3, $4, $5)
-        RETURNING id, mode as "mode: GameMode", status as "status: GameStatus",
-                  join_code, created_by, created_at, started_at, ended_at, settings, total_score
+        SELECT COUNT(*) FROM games
+        WHERE created_by = $1 AND mode = 'multiplayer' AND status = 'lobby'
         "#,
-        id,
-        mode as GameMode,
-        created_by,
-        join_code,
-        settings
     )
+    .bind(user_id)
     .fetch_one(pool)
     .await
 }
 
+/// Replace the join code of a game still in its lobby.
+///
+/// Returns the new code, or `None` if the game isn't in the lobby. Each
+/// attempt runs in a savepoint, so a collision doesn't abort a surrounding
+/// transaction.
+pub async fn rotate_join_code(
+    conn: &mut PgConnection,
+    game_id: &str,
+    join_code: JoinCodeChoice<'_>,
+) -> Result<Option<String>, sqlx::Error> {
+    let mut attempt = 1;
+    loop {
+        let mut savepoint = conn.begin().await?;
+        let result = sqlx::query_scalar(
+            "UPDATE games SET join_code = $2 WHERE id = $1 AND status = 'lobby' RETURNING join_code",
+        )
+        .bind(game_id)
+        .bind(join_code.code())
+        .fetch_optional(&mut *savepoint)
+        .await;
+
+        match result {
+            Err(e)
+                if matches!(join_code, JoinCodeChoice::Random(_))
+                    && attempt < JOIN_CODE_ATTEMPTS
+                    && is_join_code_collision(&e) =>
+            {
+                savepoint.rollback().await?;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+            Ok(code) => {
+                savepoint.commit().await?;
+                return Ok(code.flatten());
+            }
+        }
+    }
+}
+
 /// Get game by ID
 pub async fn get_game_by_id(pool: &DbPool, id: &str) -> Result<Option<Game>, sqlx::Error> {
     sqlx::query_as!(
@@ -221,12 +346,12 @@ pub async fn set_game_total_score(
 
 /// Update game settings (only valid in lobby)
 pub async fn update_game_settings(
-    pool: &DbPool,
+    executor: impl PgExecutor<'_>,
     game_id: &str,
     settings: serde_json::Value,
 ) -> Result<(), sqlx::Error> {
     sqlx::query!("UPDATE games SET settings = $2 WHERE id = $1", game_id, settings)
-        .execute(pool)
+        .execute(executor)
         .await?;
     Ok(())
 }
@@ -265,7 +390,7 @@ pub async fn add_player(
         r#"
         INSERT INTO game_players (game_id, user_id, is_host)
         VALUES ($1, $2, $3)
-        RETURNING game_id, user_id, joined_at, left_at, is_host, score_total, final_rank
+        RETURNING game_id, user_id, joined_at, left_at, is_host, score_total, final_rank, handicap
         "#,
         game_id,
         user_id,
@@ -292,7 +417,7 @@ pub async fn get_players(pool: &DbPool, game_id: &str) -> Result<Vec<GamePlayer>
     sqlx::query_as!(
         GamePlayer,
         r#"
-        SELECT game_id, user_id, joined_at, left_at, is_host, score_total, final_rank
+        SELECT game_id, user_id, joined_at, left_at, is_host, score_total, final_rank, handicap
         FROM game_players
         WHERE game_id = $1 AND left_at IS NULL
         ORDER BY joined_at ASC
@@ -303,6 +428,24 @@ pub async fn get_players(pool: &DbPool, game_id: &str) -> Result<Vec<GamePlayer>
     .await
 }
 
+/// Set a player's score multiplier
+pub async fn set_player_handicap(
+    pool: &DbPool,
+    game_id: &str,
+    user_id: &str,
+    handicap: f64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "UPDATE game_players SET handicap = $3 WHERE game_id = $1 AND user_id = $2",
+        game_id,
+        user_id,
+        handicap
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
 /// Update player's score and return the new total
 pub async fn update_player_score(
     pool: &DbPool,
@@ -323,24 +466,100 @@ pub async fn update_player_score(
 
 /// Set final rankings for all players in a game
 pub async fn set_final_rankings(pool: &DbPool, game_id: &str) -> Result<(), sqlx::Error> {
-    sqlx::query!(
+    // The teacher of a classroom game is not ranked
+    sqlx::query(
         r#"
         UPDATE game_players gp
         SET final_rank = ranked.rank
         FROM (
-            SELECT user_id, RANK() OVER (ORDER BY score_total DESC)::int as rank
-            FROM game_players
-            WHERE game_id = $1 AND left_at IS NULL
+            SELECT p.user_id, RANK() OVER (ORDER BY p.score_total DESC)::int as rank
+            FROM game_players p
+            INNER JOIN games g ON g.id = p.game_id
+            WHERE p.game_id = $1 AND p.left_at IS NULL
+              AND NOT (p.is_host AND COALESCE((g.settings->>'classroom')::boolean, false))
         ) ranked
         WHERE gp.game_id = $1 AND gp.user_id = ranked.user_id
         "#,
-        game_id
     )
+    .bind(game_id)
     .execute(pool)
     .await?;
     Ok(())
 }
 
+/// One student's result for one round of a classroom game
+#[derive(Debug, Clone, FromRow)]
+pub struct ClassroomExportRow {
+    pub user_id: String,
+    pub display_name: String,
+    pub round_number: i16,
+    pub guess_lat: Option<f64>,
+    pub guess_lng: Option<f64>,
+    pub distance_meters: Option<f64>,
+    pub score: Option<i32>,
+    pub time_taken_ms: Option<i32>,
+    pub score_total: i32,
+    pub final_rank: Option<i32>,
+}
+
+/// Get every student's result for every round of a classroom game, ordered
+/// by student name and round. Rounds a student did not guess in have no
+/// guess columns.
+pub async fn get_classroom_export(
+    pool: &DbPool,
+    game_id: &str,
+) -> Result<Vec<ClassroomExportRow>, sqlx::Error> {
+    sqlx::query_as::<_, ClassroomExportRow>(
+        r#"
+        SELECT gp.user_id, u.display_name, r.round_number,
+               gs.guess_lat, gs.guess_lng, gs.distance_meters, gs.score, gs.time_taken_ms,
+               gp.score_total, gp.final_rank
+        FROM game_players gp
+        INNER JOIN users u ON u.id = gp.user_id
+        INNER JOIN rounds r ON r.game_id = gp.game_id
+        LEFT JOIN guesses gs ON gs.round_id = r.id AND gs.user_id = gp.user_id
+        WHERE gp.game_id = $1 AND NOT gp.is_host
+        ORDER BY u.display_name, gp.user_id, r.round_number
+        "#,
+    )
+    .bind(game_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// One guess in a game, with the round it was made in
+#[derive(Debug, Clone, FromRow)]
+pub struct SummaryGuessRow {
+    pub round_number: i16,
+    pub user_id: String,
+    pub guess_lat: f64,
+    pub guess_lng: f64,
+    pub distance_meters: f64,
+    pub score: i32,
+    pub time_taken_ms: Option<i32>,
+}
+
+/// Get every guess made in a game in one query, ordered by round and then
+/// by score, best first
+pub async fn get_game_summary_guesses(
+    pool: &DbPool,
+    game_id: &str,
+) -> Result<Vec<SummaryGuessRow>, sqlx::Error> {
+    sqlx::query_as::<_, SummaryGuessRow>(
+        r#"
+        SELECT r.round_number, gs.user_id, gs.guess_lat, gs.guess_lng, gs.distance_meters,
+               gs.score, gs.time_taken_ms
+        FROM guesses gs
+        INNER JOIN rounds r ON r.id = gs.round_id
+        WHERE r.game_id = $1
+        ORDER BY r.round_number, gs.score DESC, gs.distance_meters
+        "#,
+    )
+    .bind(game_id)
+    .fetch_all(pool)
+    .await
+}
+
 /// Get player count for a game
 pub async fn get_player_count(pool: &DbPool, game_id: &str) -> Result<i64, sqlx::Error> {
     let result = sqlx::query_scalar!(
@@ -368,6 +587,21 @@ pub async fn is_player_in_game(
     Ok(count.unwrap_or(0) > 0)
 }
 
+/// Check if a user ever played in a game, including players who left
+pub async fn was_player_in_game(
+    pool: &DbPool,
+    game_id: &str,
+    user_id: &str,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM game_players WHERE game_id = $1 AND user_id = $2)",
+    )
+    .bind(game_id)
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+}
+
 /// Check whether a user is currently attached to a live multiplayer game.
 ///
 /// This is used to avoid identity merges while realtime game actors may still be
@@ -396,6 +630,63 @@ pub async fn has_live_multiplayer_membership(
     Ok(exists)
 }
 
+/// Abandon lobby and active games of the given modes with no activity for
+/// `idle_hours` hours, releasing their join codes. Returns the abandoned IDs.
+///
+/// Activity is the latest of creation, start, a player joining, a round
+/// starting or a guess.
+pub async fn abandon_stale(
+    pool: &DbPool,
+    modes: &[GameMode],
+    idle_hours: i32,
+) -> Result<Vec<String>, sqlx::Error> {
+    let modes: Vec<String> = modes.iter().map(ToString::to_string).collect();
+    sqlx::query_scalar(
+        r#"
+        UPDATE games g SET status = 'abandoned', ended_at = NOW(), join_code = NULL
+        WHERE g.status IN ('lobby', 'active')
+          AND g.mode::text = ANY($1)
+          AND GREATEST(
+                g.created_at,
+                g.started_at,
+                (SELECT MAX(gp.joined_at) FROM game_players gp WHERE gp.game_id = g.id),
+                (SELECT MAX(r.started_at) FROM rounds r WHERE r.game_id = g.id),
+                (SELECT MAX(gu.submitted_at)
+                 FROM guesses gu JOIN rounds r ON r.id = gu.round_id
+                 WHERE r.game_id = g.id)
+              ) < NOW() - make_interval(hours => $2)
+        RETURNING g.id
+        "#,
+    )
+    .bind(&modes)
+    .bind(idle_hours)
+    .fetch_all(pool)
+    .await
+}
+
+/// Abandon a lobby or active game, releasing its join code. Returns false
+/// if the game had already ended.
+pub async fn abandon(pool: &DbPool, game_id: &str) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        "UPDATE games SET status = 'abandoned', ended_at = NOW(), join_code = NULL \
+         WHERE id = $1 AND status IN ('lobby', 'active')",
+    )
+    .bind(game_id)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Of the given game IDs, those that are no longer in lobby or active
+pub async fn filter_ended(pool: &DbPool, game_ids: &[String]) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT id FROM games WHERE id = ANY($1) AND status NOT IN ('lobby', 'active')",
+    )
+    .bind(game_ids)
+    .fetch_all(pool)
+    .await
+}
+
 // =============================================================================
 // Round operations
 // =============================================================================
@@ -411,6 +702,8 @@ pub async fn create_round(
     panorama_id: Option<&str>,
     location_id: Option<&str>,
     heading: Option<f64>,
+    provider: ImageryProvider,
+    country_code: Option<&str>,
     time_limit_ms: Option<i32>,
 ) -> Result<Round, sqlx::Error> {
     let id = dguesser_core::generate_round_id();
@@ -418,10 +711,10 @@ pub async fn create_round(
     sqlx::query_as!(
         Round,
         r#"
-        INSERT INTO rounds (id, game_id, round_number, location_lat, location_lng, panorama_id, location_id, heading, time_limit_ms)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        INSERT INTO rounds (id, game_id, round_number, location_lat, location_lng, panorama_id, location_id, heading, provider, country_code, time_limit_ms)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
         RETURNING id, game_id, round_number, location_lat, location_lng, panorama_id, location_id,
-                  heading, started_at, ended_at, time_limit_ms
+                  heading, provider, country_code, started_at, ended_at, time_limit_ms
         "#,
         id,
         game_id,
@@ -431,6 +724,8 @@ pub async fn create_round(
         panorama_id,
         location_id,
         heading,
+        provider.as_str(),
+        country_code,
         time_limit_ms
     )
     .fetch_one(pool)
@@ -443,7 +738,7 @@ pub async fn get_round_by_id(pool: &DbPool, id: &str) -> Result<Option<Round>, s
         Round,
         r#"
         SELECT id, game_id, round_number, location_lat, location_lng, panorama_id, location_id,
-               heading, started_at, ended_at, time_limit_ms
+               heading, provider, country_code, started_at, ended_at, time_limit_ms
         FROM rounds WHERE id = $1
         "#,
         id
@@ -458,7 +753,7 @@ pub async fn get_rounds_for_game(pool: &DbPool, game_id: &str) -> Result<Vec<Rou
         Round,
         r#"
         SELECT id, game_id, round_number, location_lat, location_lng, panorama_id, location_id,
-               heading, started_at, ended_at, time_limit_ms
+               heading, provider, country_code, started_at, ended_at, time_limit_ms
         FROM rounds WHERE game_id = $1
         ORDER BY round_number ASC
         "#,
@@ -474,7 +769,7 @@ pub async fn get_current_round(pool: &DbPool, game_id: &str) -> Result<Option<Ro
         Round,
         r#"
         SELECT id, game_id, round_number, location_lat, location_lng, panorama_id, location_id,
-               heading, started_at, ended_at, time_limit_ms
+               heading, provider, country_code, started_at, ended_at, time_limit_ms
         FROM rounds WHERE game_id = $1
         ORDER BY round_number DESC
         LIMIT 1
@@ -516,15 +811,18 @@ pub async fn create_guess(
     distance_meters: f64,
     score: i32,
     time_taken_ms: Option<i32>,
+    reported_panorama_id: Option<&str>,
+    country_code: Option<&str>,
 ) -> Result<Guess, sqlx::Error> {
     let id = dguesser_core::generate_guess_id();
 
     sqlx::query_as!(
         Guess,
         r#"
-        INSERT INTO guesses (id, round_id, user_id, guess_lat, guess_lng, distance_meters, score, time_taken_ms)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-        RETURNING id, round_id, user_id, guess_lat, guess_lng, distance_meters, score, submitted_at, time_taken_ms
+        INSERT INTO guesses (id, round_id, user_id, guess_lat, guess_lng, distance_meters, score, time_taken_ms, reported_panorama_id, country_code)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        RETURNING id, round_id, user_id, guess_lat, guess_lng, distance_meters, score, submitted_at, time_taken_ms,
+               country_code
         "#,
         id,
         round_id,
@@ -533,12 +831,119 @@ pub async fn create_guess(
         guess_lng,
         distance_meters,
         score,
-        time_taken_ms
+        time_taken_ms,
+        reported_panorama_id,
+        country_code
     )
     .fetch_one(pool)
     .await
 }
 
+/// Store the score chain signature of a guess.
+pub async fn set_guess_score_mac(
+    pool: &DbPool,
+    guess_id: &str,
+    score_mac: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE guesses SET score_mac = $2 WHERE id = $1")
+        .bind(guess_id)
+        .bind(score_mac)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Get a player's round results in a game, oldest round first.
+pub async fn get_score_chain(
+    pool: &DbPool,
+    game_id: &str,
+    user_id: &str,
+) -> Result<Vec<ScoreLink>, sqlx::Error> {
+    sqlx::query_as::<_, ScoreLink>(
+        r#"
+        SELECT r.round_number, g.score, g.score_mac
+        FROM guesses g
+        INNER JOIN rounds r ON r.id = g.round_id
+        WHERE r.game_id = $1 AND g.user_id = $2
+        ORDER BY r.round_number, g.submitted_at, g.id
+        "#,
+    )
+    .bind(game_id)
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// A guess to insert with [`record_guesses`]
+#[derive(Debug, Clone)]
+pub struct NewGuess {
+    pub round_id: String,
+    pub user_id: String,
+    pub guess_lat: f64,
+    pub guess_lng: f64,
+    pub distance_meters: f64,
+    pub score: i32,
+    pub time_taken_ms: Option<i32>,
+    pub reported_panorama_id: Option<String>,
+    pub submitted_at: DateTime<Utc>,
+}
+
+/// Insert a batch of guesses and add points to player totals, in one
+/// transaction and two statements however many guesses there are.
+///
+/// A guess that already exists for its round and player is skipped.
+pub async fn record_guesses(
+    pool: &DbPool,
+    game_id: &str,
+    guesses: &[NewGuess],
+    points: &[(String, i32)],
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    if !guesses.is_empty() {
+        let ids: Vec<String> = guesses.iter().map(|_| dguesser_core::generate_guess_id()).collect();
+        sqlx::query(
+            r#"
+            INSERT INTO guesses (id, round_id, user_id, guess_lat, guess_lng, distance_meters,
+                                 score, time_taken_ms, reported_panorama_id, submitted_at)
+            SELECT * FROM UNNEST($1::text[], $2::text[], $3::text[], $4::float8[], $5::float8[],
+                                 $6::float8[], $7::int4[], $8::int4[], $9::text[], $10::timestamptz[])
+            ON CONFLICT (round_id, user_id) DO NOTHING
+            "#,
+        )
+        .bind(ids)
+        .bind(guesses.iter().map(|g| g.round_id.as_str()).collect::<Vec<_>>())
+        .bind(guesses.iter().map(|g| g.user_id.as_str()).collect::<Vec<_>>())
+        .bind(guesses.iter().map(|g| g.guess_lat).collect::<Vec<_>>())
+        .bind(guesses.iter().map(|g| g.guess_lng).collect::<Vec<_>>())
+        .bind(guesses.iter().map(|g| g.distance_meters).collect::<Vec<_>>())
+        .bind(guesses.iter().map(|g| g.score).collect::<Vec<_>>())
+        .bind(guesses.iter().map(|g| g.time_taken_ms).collect::<Vec<_>>())
+        .bind(guesses.iter().map(|g| g.reported_panorama_id.as_deref()).collect::<Vec<_>>())
+        .bind(guesses.iter().map(|g| g.submitted_at).collect::<Vec<_>>())
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    if !points.is_empty() {
+        sqlx::query(
+            r#"
+            UPDATE game_players gp
+            SET score_total = gp.score_total + batch.points
+            FROM UNNEST($2::text[], $3::int4[]) AS batch(user_id, points)
+            WHERE gp.game_id = $1 AND gp.user_id = batch.user_id
+            "#,
+        )
+        .bind(game_id)
+        .bind(points.iter().map(|(user_id, _)| user_id.as_str()).collect::<Vec<_>>())
+        .bind(points.iter().map(|(_, points)| *points).collect::<Vec<_>>())
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await
+}
+
 /// Get guess by round and user
 pub async fn get_guess(
     pool: &DbPool,
@@ -548,7 +953,8 @@ pub async fn get_guess(
     sqlx::query_as!(
         Guess,
         r#"
-        SELECT id, round_id, user_id, guess_lat, guess_lng, distance_meters, score, submitted_at, time_taken_ms
+        SELECT id, round_id, user_id, guess_lat, guess_lng, distance_meters, score, submitted_at, time_taken_ms,
+               country_code
         FROM guesses WHERE round_id = $1 AND user_id = $2
         "#,
         round_id,
@@ -566,7 +972,8 @@ pub async fn get_guesses_for_round(
     sqlx::query_as!(
         Guess,
         r#"
-        SELECT id, round_id, user_id, guess_lat, guess_lng, distance_meters, score, submitted_at, time_taken_ms
+        SELECT id, round_id, user_id, guess_lat, guess_lng, distance_meters, score, submitted_at, time_taken_ms,
+               country_code
         FROM guesses WHERE round_id = $1
         ORDER BY score DESC
         "#,
@@ -601,7 +1008,8 @@ pub async fn get_user_guesses(
     sqlx::query_as!(
         Guess,
         r#"
-        SELECT id, round_id, user_id, guess_lat, guess_lng, distance_meters, score, submitted_at, time_taken_ms
+        SELECT id, round_id, user_id, guess_lat, guess_lng, distance_meters, score, submitted_at, time_taken_ms,
+               country_code
         FROM guesses WHERE user_id = $1
         ORDER BY submitted_at DESC
         LIMIT $2
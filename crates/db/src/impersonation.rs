@@ -0,0 +1,162 @@
+//! Impersonation session queries
+//!
+//! An impersonation session is a regular session for the impersonated user
+//! with a row in `impersonation_sessions` naming the admin behind it. Starting
+//! and ending one is recorded in the admin audit log.
+
+use chrono::{DateTime, Duration, Utc};
+use ipnetwork::IpNetwork;
+use sqlx::FromRow;
+
+use crate::DbPool;
+use crate::audit_log;
+
+/// Audit log action for starting impersonation
+pub const ACTION_START: &str = "impersonation.start";
+/// Audit log action for ending impersonation
+pub const ACTION_END: &str = "impersonation.end";
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ImpersonationSession {
+    pub session_id: String,
+    pub admin_id: String,
+    /// The admin's own session, restored when impersonation ends
+    pub admin_session_id: Option<String>,
+    pub user_id: String,
+    pub read_only: bool,
+    pub reason: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+}
+
+/// An impersonation session to start
+#[derive(Debug, Clone)]
+pub struct NewImpersonation<'a> {
+    pub admin_id: &'a str,
+    pub admin_session_id: &'a str,
+    pub user_id: &'a str,
+    pub read_only: bool,
+    pub reason: &'a str,
+    pub ttl: Duration,
+    pub ip: Option<&'a str>,
+    pub user_agent: Option<&'a str>,
+}
+
+const IMPERSONATION_COLUMNS: &str = "session_id, admin_id, admin_session_id, user_id, read_only, \
+                                     reason, created_at, expires_at, ended_at";
+
+/// Create a session for the user and record who is behind it.
+pub async fn start(
+    pool: &DbPool,
+    new: &NewImpersonation<'_>,
+) -> Result<ImpersonationSession, sqlx::Error> {
+    let session_id = dguesser_core::generate_session_id();
+    let expires_at = Utc::now() + new.ttl;
+    let ip_network: Option<IpNetwork> = new.ip.and_then(|s| s.parse().ok());
+
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO sessions (id, user_id, expires_at, ip_address, user_agent)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+    )
+    .bind(&session_id)
+    .bind(new.user_id)
+    .bind(expires_at)
+    .bind(ip_network)
+    .bind(new.user_agent)
+    .execute(&mut *tx)
+    .await?;
+
+    let session: ImpersonationSession = sqlx::query_as(&format!(
+        r#"
+        INSERT INTO impersonation_sessions (
+            session_id, admin_id, admin_session_id, user_id, read_only, reason, expires_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING {IMPERSONATION_COLUMNS}
+        "#
+    ))
+    .bind(&session_id)
+    .bind(new.admin_id)
+    .bind(new.admin_session_id)
+    .bind(new.user_id)
+    .bind(new.read_only)
+    .bind(new.reason)
+    .bind(expires_at)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    audit_log::record(
+        &mut tx,
+        new.admin_id,
+        ACTION_START,
+        Some(new.user_id),
+        serde_json::json!({
+            "read_only": new.read_only,
+            "reason": new.reason,
+            "expires_at": expires_at,
+        }),
+    )
+    .await?;
+
+    tx.commit().await?;
+    Ok(session)
+}
+
+/// Get the impersonation behind a session, if any.
+pub async fn get_by_session(
+    pool: &DbPool,
+    session_id: &str,
+) -> Result<Option<ImpersonationSession>, sqlx::Error> {
+    sqlx::query_as(&format!(
+        "SELECT {IMPERSONATION_COLUMNS} FROM impersonation_sessions WHERE session_id = $1"
+    ))
+    .bind(session_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// End an impersonation session: revoke it and record that it ended.
+///
+/// Returns `None` if the session is not an active impersonation.
+pub async fn end(
+    pool: &DbPool,
+    session_id: &str,
+) -> Result<Option<ImpersonationSession>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let session: Option<ImpersonationSession> = sqlx::query_as(&format!(
+        r#"
+        UPDATE impersonation_sessions SET ended_at = NOW()
+        WHERE session_id = $1 AND ended_at IS NULL
+        RETURNING {IMPERSONATION_COLUMNS}
+        "#
+    ))
+    .bind(session_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some(session) = session else {
+        return Ok(None);
+    };
+
+    sqlx::query("UPDATE sessions SET revoked_at = NOW() WHERE id = $1 AND revoked_at IS NULL")
+        .bind(session_id)
+        .execute(&mut *tx)
+        .await?;
+
+    audit_log::record(
+        &mut tx,
+        &session.admin_id,
+        ACTION_END,
+        Some(&session.user_id),
+        serde_json::json!({ "started_at": session.created_at }),
+    )
+    .await?;
+
+    tx.commit().await?;
+    Ok(Some(session))
+}
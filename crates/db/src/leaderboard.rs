@@ -1,11 +1,29 @@
 //! Leaderboard database queries
+//!
+//! Leaderboards read from `leaderboard_rollups`, which holds per-player totals
+//! for each day, ISO week and all time, per organization, map and mode.
+//! [`record_game`] adds a finished game to the rollups.
 
-use chrono::{DateTime, Duration, Utc};
+use chrono::{Datelike, Duration, NaiveDate, Utc};
+use sqlx::FromRow;
 
 use crate::DbPool;
+use crate::games::GameMode;
+
+/// Rollup key for every map, or every non-streak mode
+const ALL: &str = "*";
+
+/// Rollup key of the public instance (games outside any organization)
+const PUBLIC_ORG: &str = "";
+
+/// Day buckets summed for the monthly leaderboard
+const MONTHLY_DAYS: i64 = 30;
+
+/// Games needed to appear on the average score leaderboard
+const MIN_GAMES_FOR_AVERAGE: i64 = 3;
 
 /// Leaderboard entry from database query
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, FromRow)]
 pub struct LeaderboardRow {
     pub user_id: String,
     pub display_name: String,
@@ -16,592 +34,290 @@ pub struct LeaderboardRow {
     pub leaderboard_public: bool,
 }
 
-/// Get top players by total score (all-time)
-pub async fn get_by_total_score(
-    pool: &DbPool,
-    limit: i64,
-    offset: i64,
-) -> Result<Vec<LeaderboardRow>, sqlx::Error> {
-    let rows = sqlx::query!(
-        r#"
-        SELECT 
-            id as user_id,
-            display_name,
-            avatar_url,
-            total_score as score,
-            games_played as games_count,
-            leaderboard_public
-        FROM users
-        WHERE games_played > 0 AND deleted_at IS NULL
-        ORDER BY total_score DESC, games_played DESC
-        LIMIT $1 OFFSET $2
-        "#,
-        limit,
-        offset
-    )
-    .fetch_all(pool)
-    .await?;
-
-    Ok(rows
-        .into_iter()
-        .map(|r| LeaderboardRow {
-            user_id: r.user_id,
-            display_name: r.display_name,
-            avatar_url: r.avatar_url,
-            score: r.score,
-            games_count: r.games_count as i64,
-            leaderboard_public: r.leaderboard_public,
-        })
-        .collect())
+/// What a leaderboard ranks players by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Metric {
+    TotalScore,
+    BestScore,
+    GamesPlayed,
+    /// Average score per game, for players with at least 3 games
+    AverageScore,
+    /// Longest country streak, from streak games only
+    Streak,
 }
 
-/// Get top players by best single game score (all-time)
-pub async fn get_by_best_score(
-    pool: &DbPool,
-    limit: i64,
-    offset: i64,
-) -> Result<Vec<LeaderboardRow>, sqlx::Error> {
-    let rows = sqlx::query!(
-        r#"
-        SELECT 
-            id as user_id,
-            display_name,
-            avatar_url,
-            best_score as score,
-            games_played as games_count,
-            leaderboard_public
-        FROM users
-        WHERE games_played > 0 AND deleted_at IS NULL
-        ORDER BY best_score DESC, games_played DESC
-        LIMIT $1 OFFSET $2
-        "#,
-        limit,
-        offset
-    )
-    .fetch_all(pool)
-    .await?;
-
-    Ok(rows
-        .into_iter()
-        .map(|r| LeaderboardRow {
-            user_id: r.user_id,
-            display_name: r.display_name,
-            avatar_url: r.avatar_url,
-            score: r.score as i64,
-            games_count: r.games_count as i64,
-            leaderboard_public: r.leaderboard_public,
-        })
-        .collect())
+impl Metric {
+    /// Score column, rank order and extra condition for this metric.
+    fn sql(self) -> (&'static str, &'static str, &'static str) {
+        match self {
+            Self::TotalScore => {
+                ("total_score", "total_score DESC, games_played DESC", "total_score > 0")
+            }
+            Self::BestScore => {
+                ("best_score", "best_score DESC, games_played DESC", "best_score > 0")
+            }
+            Self::GamesPlayed => {
+                ("games_played", "games_played DESC, total_score DESC", "games_played > 0")
+            }
+            Self::AverageScore => (
+                "ROUND(total_score::numeric / games_played)::bigint",
+                "total_score::numeric / games_played DESC, games_played DESC",
+                "games_played >= $6",
+            ),
+            Self::Streak => ("best_score", "best_score DESC, games_played ASC", "best_score > 0"),
+        }
+    }
 }
 
-/// Get top players by games played (all-time)
-pub async fn get_by_games_played(
-    pool: &DbPool,
-    limit: i64,
-    offset: i64,
-) -> Result<Vec<LeaderboardRow>, sqlx::Error> {
-    let rows = sqlx::query!(
-        r#"
-        SELECT 
-            id as user_id,
-            display_name,
-            avatar_url,
-            games_played as score,
-            games_played as games_count,
-            leaderboard_public
-        FROM users
-        WHERE games_played > 0 AND deleted_at IS NULL
-        ORDER BY games_played DESC, total_score DESC
-        LIMIT $1 OFFSET $2
-        "#,
-        limit,
-        offset
-    )
-    .fetch_all(pool)
-    .await?;
+/// Time window of a leaderboard
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Period {
+    #[default]
+    AllTime,
+    /// Today (UTC)
+    Daily,
+    /// This ISO week (UTC, from Monday)
+    Weekly,
+    /// The last 30 days, today included
+    Monthly,
+}
 
-    Ok(rows
-        .into_iter()
-        .map(|r| LeaderboardRow {
-            user_id: r.user_id,
-            display_name: r.display_name,
-            avatar_url: r.avatar_url,
-            score: r.score as i64,
-            games_count: r.games_count as i64,
-            leaderboard_public: r.leaderboard_public,
-        })
-        .collect())
+impl Period {
+    /// Rollup bucket and first bucket start read for this period on `today`.
+    pub fn buckets(self, today: NaiveDate) -> (&'static str, NaiveDate) {
+        match self {
+            Self::AllTime => ("all", NaiveDate::default()),
+            Self::Daily => ("day", today),
+            Self::Weekly => {
+                ("week", today - Duration::days(today.weekday().num_days_from_monday().into()))
+            }
+            Self::Monthly => ("day", today - Duration::days(MONTHLY_DAYS - 1)),
+        }
+    }
 }
 
-/// Get top players by average score (all-time, minimum 3 games)
-pub async fn get_by_average_score(
-    pool: &DbPool,
-    limit: i64,
-    offset: i64,
-) -> Result<Vec<LeaderboardRow>, sqlx::Error> {
-    let rows = sqlx::query!(
-        r#"
-        SELECT 
-            id as user_id,
-            display_name,
-            avatar_url,
-            ROUND(total_score::numeric / games_played, 0)::bigint as "score!: i64",
-            games_played as games_count,
-            leaderboard_public
-        FROM users
-        WHERE games_played >= 3 AND deleted_at IS NULL
-        ORDER BY (total_score::numeric / games_played) DESC, games_played DESC
-        LIMIT $1 OFFSET $2
-        "#,
-        limit,
-        offset
-    )
-    .fetch_all(pool)
-    .await?;
+/// Which players and games a leaderboard covers (all time, every map and mode
+/// by default)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Scope {
+    pub period: Period,
+    /// Only games on this map
+    pub map_id: Option<String>,
+    /// Only games of this mode (streak leaderboards always read streak games)
+    pub mode: Option<GameMode>,
+    /// Games of this organization instead of the public instance
+    pub org_id: Option<String>,
+}
 
-    Ok(rows
-        .into_iter()
-        .map(|r| LeaderboardRow {
-            user_id: r.user_id,
-            display_name: r.display_name,
-            avatar_url: r.avatar_url,
-            score: r.score,
-            games_count: r.games_count as i64,
-            leaderboard_public: r.leaderboard_public,
-        })
-        .collect())
+impl Scope {
+    fn keys(&self, metric: Metric) -> (&'static str, NaiveDate, String, String, &str) {
+        let (bucket, since) = self.period.buckets(Utc::now().date_naive());
+        let map_id = self.map_id.clone().unwrap_or_else(|| ALL.to_string());
+        let mode = match (metric, self.mode) {
+            (Metric::Streak, _) => GameMode::Streak.to_string(),
+            (_, Some(mode)) => mode.to_string(),
+            (_, None) => ALL.to_string(),
+        };
+        (bucket, since, map_id, mode, self.org_id.as_deref().unwrap_or(PUBLIC_ORG))
+    }
 }
 
-/// Get top players by total score within a time period
-pub async fn get_by_total_score_period(
-    pool: &DbPool,
-    since: DateTime<Utc>,
-    limit: i64,
-    offset: i64,
-) -> Result<Vec<LeaderboardRow>, sqlx::Error> {
-    let rows = sqlx::query!(
-        r#"
-        SELECT 
-            u.id as user_id,
-            u.display_name,
-            u.avatar_url,
-            u.leaderboard_public,
-            COALESCE(SUM(gp.score_total), 0)::bigint as "score!: i64",
-            COUNT(DISTINCT gp.game_id)::bigint as "games_count!: i64"
-        FROM users u
-        INNER JOIN game_players gp ON gp.user_id = u.id
-        INNER JOIN games g ON g.id = gp.game_id
-        WHERE g.status = 'finished' AND g.ended_at >= $1 AND u.deleted_at IS NULL
-        GROUP BY u.id, u.display_name, u.avatar_url, u.leaderboard_public
-        HAVING SUM(gp.score_total) > 0
-        ORDER BY SUM(gp.score_total) DESC, COUNT(DISTINCT gp.game_id) DESC
-        LIMIT $2 OFFSET $3
-        "#,
-        since,
-        limit,
-        offset
+/// Players' totals within a scope; `$1`-`$5` are the rollup keys
+const SCOPED: &str = r#"
+    scoped AS (
+        SELECT user_id,
+               SUM(total_score)::bigint AS total_score,
+               MAX(best_score)::bigint AS best_score,
+               SUM(games_played)::bigint AS games_played
+        FROM leaderboard_rollups
+        WHERE bucket = $1 AND bucket_start >= $2 AND map_id = $3 AND mode = $4
+          AND org_id = $5
+        GROUP BY user_id
     )
-    .fetch_all(pool)
-    .await?;
+"#;
 
-    Ok(rows
-        .into_iter()
-        .map(|r| LeaderboardRow {
-            user_id: r.user_id,
-            display_name: r.display_name,
-            avatar_url: r.avatar_url,
-            score: r.score,
-            games_count: r.games_count,
-            leaderboard_public: r.leaderboard_public,
-        })
-        .collect())
-}
+/// Add a finished game to the leaderboard rollups.
+///
+/// Each game is only counted once; recording it again does nothing. Returns
+/// whether the game was recorded.
+pub async fn record_game(pool: &DbPool, game_id: &str) -> Result<bool, sqlx::Error> {
+    let mut tx = pool.begin().await?;
 
-/// Get top players by best game within a time period
-pub async fn get_by_best_score_period(
-    pool: &DbPool,
-    since: DateTime<Utc>,
-    limit: i64,
-    offset: i64,
-) -> Result<Vec<LeaderboardRow>, sqlx::Error> {
-    let rows = sqlx::query!(
+    let claimed = sqlx::query(
         r#"
-        SELECT 
-            u.id as user_id,
-            u.display_name,
-            u.avatar_url,
-            u.leaderboard_public,
-            COALESCE(MAX(gp.score_total), 0)::bigint as "score!: i64",
-            COUNT(DISTINCT gp.game_id)::bigint as "games_count!: i64"
-        FROM users u
-        INNER JOIN game_players gp ON gp.user_id = u.id
-        INNER JOIN games g ON g.id = gp.game_id
-        WHERE g.status = 'finished' AND g.ended_at >= $1 AND u.deleted_at IS NULL
-        GROUP BY u.id, u.display_name, u.avatar_url, u.leaderboard_public
-        HAVING MAX(gp.score_total) > 0
-        ORDER BY MAX(gp.score_total) DESC, COUNT(DISTINCT gp.game_id) DESC
-        LIMIT $2 OFFSET $3
+        UPDATE games SET leaderboard_recorded_at = NOW()
+        WHERE id = $1 AND status = 'finished' AND leaderboard_recorded_at IS NULL
         "#,
-        since,
-        limit,
-        offset
     )
-    .fetch_all(pool)
+    .bind(game_id)
+    .execute(&mut *tx)
     .await?;
+    if claimed.rows_affected() == 0 {
+        return Ok(false);
+    }
 
-    Ok(rows
-        .into_iter()
-        .map(|r| LeaderboardRow {
-            user_id: r.user_id,
-            display_name: r.display_name,
-            avatar_url: r.avatar_url,
-            score: r.score,
-            games_count: r.games_count,
-            leaderboard_public: r.leaderboard_public,
-        })
-        .collect())
-}
-
-/// Get top players by games played within a time period
-pub async fn get_by_games_played_period(
-    pool: &DbPool,
-    since: DateTime<Utc>,
-    limit: i64,
-    offset: i64,
-) -> Result<Vec<LeaderboardRow>, sqlx::Error> {
-    let rows = sqlx::query!(
+    sqlx::query(
         r#"
-        SELECT 
-            u.id as user_id,
-            u.display_name,
-            u.avatar_url,
-            u.leaderboard_public,
-            COUNT(DISTINCT gp.game_id)::bigint as "score!: i64",
-            COUNT(DISTINCT gp.game_id)::bigint as "games_count!: i64"
-        FROM users u
-        INNER JOIN game_players gp ON gp.user_id = u.id
-        INNER JOIN games g ON g.id = gp.game_id
-        WHERE g.status = 'finished' AND g.ended_at >= $1 AND u.deleted_at IS NULL
-        GROUP BY u.id, u.display_name, u.avatar_url, u.leaderboard_public
-        HAVING COUNT(DISTINCT gp.game_id) > 0
-        ORDER BY COUNT(DISTINCT gp.game_id) DESC, SUM(gp.score_total) DESC
-        LIMIT $2 OFFSET $3
+        INSERT INTO leaderboard_rollups
+            (org_id, bucket, bucket_start, map_id, mode, user_id,
+             total_score, best_score, games_played)
+        SELECT COALESCE(g.org_id, ''), b.bucket, b.bucket_start, m.map_id, md.mode, gp.user_id,
+               gp.score_total, gp.score_total, 1
+        FROM games g
+        INNER JOIN game_players gp ON gp.game_id = g.id
+        CROSS JOIN LATERAL (VALUES
+            ('day', (COALESCE(g.ended_at, NOW()) AT TIME ZONE 'UTC')::date),
+            ('week', date_trunc('week', COALESCE(g.ended_at, NOW()) AT TIME ZONE 'UTC')::date),
+            ('all', DATE '1970-01-01')
+        ) AS b(bucket, bucket_start)
+        CROSS JOIN LATERAL (VALUES (COALESCE(g.settings->>'map_id', '')), ('*')) AS m(map_id)
+        CROSS JOIN LATERAL (VALUES
+            (g.mode::text),
+            (CASE WHEN g.mode = 'streak' THEN NULL ELSE '*' END)
+        ) AS md(mode)
+        WHERE g.id = $1 AND md.mode IS NOT NULL
+          AND NOT (gp.is_host AND COALESCE((g.settings->>'classroom')::boolean, false))
+        ON CONFLICT (org_id, bucket, bucket_start, map_id, mode, user_id) DO UPDATE
+        SET total_score = leaderboard_rollups.total_score + EXCLUDED.total_score,
+            best_score = GREATEST(leaderboard_rollups.best_score, EXCLUDED.best_score),
+            games_played = leaderboard_rollups.games_played + 1,
+            updated_at = NOW()
         "#,
-        since,
-        limit,
-        offset
     )
-    .fetch_all(pool)
+    .bind(game_id)
+    .execute(&mut *tx)
     .await?;
 
-    Ok(rows
-        .into_iter()
-        .map(|r| LeaderboardRow {
-            user_id: r.user_id,
-            display_name: r.display_name,
-            avatar_url: r.avatar_url,
-            score: r.score,
-            games_count: r.games_count,
-            leaderboard_public: r.leaderboard_public,
-        })
-        .collect())
+    tx.commit().await?;
+    Ok(true)
 }
 
-/// Get top players by average score within a time period (minimum 3 games)
-pub async fn get_by_average_score_period(
+/// Get the top players of a leaderboard
+pub async fn get_top(
     pool: &DbPool,
-    since: DateTime<Utc>,
+    metric: Metric,
+    scope: &Scope,
     limit: i64,
     offset: i64,
 ) -> Result<Vec<LeaderboardRow>, sqlx::Error> {
-    let rows = sqlx::query!(
+    let (bucket, since, map_id, mode, org_id) = scope.keys(metric);
+    let (score, order, condition) = metric.sql();
+
+    sqlx::query_as::<_, LeaderboardRow>(&format!(
         r#"
-        SELECT 
-            u.id as user_id,
-            u.display_name,
-            u.avatar_url,
-            u.leaderboard_public,
-            ROUND(COALESCE(SUM(gp.score_total), 0)::numeric / NULLIF(COUNT(DISTINCT gp.game_id), 0), 0)::bigint as "score!: i64",
-            COUNT(DISTINCT gp.game_id)::bigint as "games_count!: i64"
-        FROM users u
-        INNER JOIN game_players gp ON gp.user_id = u.id
-        INNER JOIN games g ON g.id = gp.game_id
-        WHERE g.status = 'finished' AND g.ended_at >= $1 AND u.deleted_at IS NULL
-        GROUP BY u.id, u.display_name, u.avatar_url, u.leaderboard_public
-        HAVING COUNT(DISTINCT gp.game_id) >= 3
-        ORDER BY (SUM(gp.score_total)::numeric / NULLIF(COUNT(DISTINCT gp.game_id), 0)) DESC, COUNT(DISTINCT gp.game_id) DESC
-        LIMIT $2 OFFSET $3
-        "#,
-        since,
-        limit,
-        offset
-    )
+        WITH {SCOPED}
+        SELECT u.id AS user_id, u.display_name, u.avatar_url, u.leaderboard_public,
+               {score} AS score, s.games_played AS games_count
+        FROM scoped s
+        INNER JOIN users u ON u.id = s.user_id AND u.deleted_at IS NULL
+        WHERE {condition}
+        ORDER BY {order}, u.id
+        LIMIT $7 OFFSET $8
+        "#
+    ))
+    .bind(bucket)
+    .bind(since)
+    .bind(map_id)
+    .bind(mode)
+    .bind(org_id)
+    .bind(MIN_GAMES_FOR_AVERAGE)
+    .bind(limit)
+    .bind(offset)
     .fetch_all(pool)
-    .await?;
-
-    Ok(rows
-        .into_iter()
-        .map(|r| LeaderboardRow {
-            user_id: r.user_id,
-            display_name: r.display_name,
-            avatar_url: r.avatar_url,
-            score: r.score,
-            games_count: r.games_count,
-            leaderboard_public: r.leaderboard_public,
-        })
-        .collect())
-}
-
-/// Count total players on leaderboard (all-time)
-pub async fn count_ranked_players(pool: &DbPool) -> Result<i64, sqlx::Error> {
-    let result = sqlx::query_scalar!(
-        r#"SELECT COUNT(*)::bigint as "count!" FROM users WHERE games_played > 0 AND deleted_at IS NULL"#
-    )
-    .fetch_one(pool)
-    .await?;
-    Ok(result)
+    .await
 }
 
-/// Count players on leaderboard within a time period
-pub async fn count_ranked_players_period(
+/// Count players on a leaderboard
+pub async fn count_ranked(
     pool: &DbPool,
-    since: DateTime<Utc>,
+    metric: Metric,
+    scope: &Scope,
 ) -> Result<i64, sqlx::Error> {
-    let result = sqlx::query_scalar!(
-        r#"
-        SELECT COUNT(DISTINCT gp.user_id)::bigint as "count!"
-        FROM game_players gp
-        INNER JOIN games g ON g.id = gp.game_id
-        INNER JOIN users u ON gp.user_id = u.id AND u.deleted_at IS NULL
-        WHERE g.status = 'finished' AND g.ended_at >= $1
-        "#,
-        since
-    )
-    .fetch_one(pool)
-    .await?;
-    Ok(result)
-}
+    let (bucket, since, map_id, mode, org_id) = scope.keys(metric);
+    let (_, _, condition) = metric.sql();
 
-/// Get a user's rank by total score (all-time)
-pub async fn get_user_rank_total_score(
-    pool: &DbPool,
-    user_id: &str,
-) -> Result<Option<i64>, sqlx::Error> {
-    let result = sqlx::query_scalar!(
+    sqlx::query_scalar(&format!(
         r#"
-        SELECT rank::bigint as "rank"
-        FROM (
-            SELECT id, RANK() OVER (ORDER BY total_score DESC) as rank
-            FROM users
-            WHERE games_played > 0 AND deleted_at IS NULL
-        ) ranked
-        WHERE id = $1
-        "#,
-        user_id
-    )
-    .fetch_optional(pool)
-    .await?;
-    Ok(result.flatten())
+        WITH {SCOPED}
+        SELECT COUNT(*)::bigint
+        FROM scoped s
+        INNER JOIN users u ON u.id = s.user_id AND u.deleted_at IS NULL
+        WHERE {condition}
+        "#
+    ))
+    .bind(bucket)
+    .bind(since)
+    .bind(map_id)
+    .bind(mode)
+    .bind(org_id)
+    .bind(MIN_GAMES_FOR_AVERAGE)
+    .fetch_one(pool)
+    .await
 }
 
-/// Get a user's rank by best score (all-time)
-pub async fn get_user_rank_best_score(
+/// Get a user's rank and score on a leaderboard
+pub async fn get_user_rank(
     pool: &DbPool,
+    metric: Metric,
+    scope: &Scope,
     user_id: &str,
-) -> Result<Option<i64>, sqlx::Error> {
-    let result = sqlx::query_scalar!(
-        r#"
-        SELECT rank::bigint as "rank"
-        FROM (
-            SELECT id, RANK() OVER (ORDER BY best_score DESC) as rank
-            FROM users
-            WHERE games_played > 0 AND deleted_at IS NULL
-        ) ranked
-        WHERE id = $1
-        "#,
-        user_id
-    )
-    .fetch_optional(pool)
-    .await?;
-    Ok(result.flatten())
-}
+) -> Result<Option<(i64, i64)>, sqlx::Error> {
+    let (bucket, since, map_id, mode, org_id) = scope.keys(metric);
+    let (score, order, condition) = metric.sql();
 
-/// Get a user's rank by games played (all-time)
-pub async fn get_user_rank_games_played(
-    pool: &DbPool,
-    user_id: &str,
-) -> Result<Option<i64>, sqlx::Error> {
-    let result = sqlx::query_scalar!(
+    sqlx::query_as::<_, (i64, i64)>(&format!(
         r#"
-        SELECT rank::bigint as "rank"
+        WITH {SCOPED}
+        SELECT rank, score
         FROM (
-            SELECT id, RANK() OVER (ORDER BY games_played DESC) as rank
-            FROM users
-            WHERE games_played > 0 AND deleted_at IS NULL
+            SELECT s.user_id, {score} AS score, RANK() OVER (ORDER BY {order})::bigint AS rank
+            FROM scoped s
+            INNER JOIN users u ON u.id = s.user_id AND u.deleted_at IS NULL
+            WHERE {condition}
         ) ranked
-        WHERE id = $1
-        "#,
-        user_id
-    )
+        WHERE user_id = $7
+        "#
+    ))
+    .bind(bucket)
+    .bind(since)
+    .bind(map_id)
+    .bind(mode)
+    .bind(org_id)
+    .bind(MIN_GAMES_FOR_AVERAGE)
+    .bind(user_id)
     .fetch_optional(pool)
-    .await?;
-    Ok(result.flatten())
+    .await
 }
 
-/// Get a user's rank by average score (all-time)
-pub async fn get_user_rank_average_score(
-    pool: &DbPool,
-    user_id: &str,
-) -> Result<Option<i64>, sqlx::Error> {
-    let result = sqlx::query_scalar!(
-        r#"
-        SELECT rank::bigint as "rank"
-        FROM (
-            SELECT id, RANK() OVER (ORDER BY (total_score::numeric / NULLIF(games_played, 0)) DESC) as rank
-            FROM users
-            WHERE games_played >= 3 AND deleted_at IS NULL
-        ) ranked
-        WHERE id = $1
-        "#,
-        user_id
-    )
-    .fetch_optional(pool)
-    .await?;
-    Ok(result.flatten())
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-/// Get a user's rank by total score within a time period
-pub async fn get_user_rank_total_score_period(
-    pool: &DbPool,
-    user_id: &str,
-    since: DateTime<Utc>,
-) -> Result<Option<(i64, i64)>, sqlx::Error> {
-    let result = sqlx::query!(
-        r#"
-        SELECT rank::bigint as "rank!", score::bigint as "score!"
-        FROM (
-            SELECT 
-                u.id,
-                COALESCE(SUM(gp.score_total), 0) as score,
-                RANK() OVER (ORDER BY COALESCE(SUM(gp.score_total), 0) DESC) as rank
-            FROM users u
-            INNER JOIN game_players gp ON gp.user_id = u.id
-            INNER JOIN games g ON g.id = gp.game_id
-            WHERE g.status = 'finished' AND g.ended_at >= $1 AND u.deleted_at IS NULL
-            GROUP BY u.id
-            HAVING SUM(gp.score_total) > 0
-        ) ranked
-        WHERE id = $2
-        "#,
-        since,
-        user_id
-    )
-    .fetch_optional(pool)
-    .await?;
-    Ok(result.map(|r| (r.rank, r.score)))
-}
+    #[test]
+    fn test_period_buckets() {
+        // A Thursday
+        let today = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap();
 
-/// Get a user's rank by best score within a time period
-pub async fn get_user_rank_best_score_period(
-    pool: &DbPool,
-    user_id: &str,
-    since: DateTime<Utc>,
-) -> Result<Option<(i64, i64)>, sqlx::Error> {
-    let result = sqlx::query!(
-        r#"
-        SELECT rank::bigint as "rank!", score::bigint as "score!"
-        FROM (
-            SELECT 
-                u.id,
-                COALESCE(MAX(gp.score_total), 0) as score,
-                RANK() OVER (ORDER BY COALESCE(MAX(gp.score_total), 0) DESC) as rank
-            FROM users u
-            INNER JOIN game_players gp ON gp.user_id = u.id
-            INNER JOIN games g ON g.id = gp.game_id
-            WHERE g.status = 'finished' AND g.ended_at >= $1 AND u.deleted_at IS NULL
-            GROUP BY u.id
-            HAVING MAX(gp.score_total) > 0
-        ) ranked
-        WHERE id = $2
-        "#,
-        since,
-        user_id
-    )
-    .fetch_optional(pool)
-    .await?;
-    Ok(result.map(|r| (r.rank, r.score)))
-}
+        assert_eq!(Period::AllTime.buckets(today), ("all", NaiveDate::default()));
+        assert_eq!(Period::Daily.buckets(today), ("day", today));
+        assert_eq!(
+            Period::Weekly.buckets(today),
+            ("week", NaiveDate::from_ymd_opt(2026, 10, 12).unwrap())
+        );
+        assert_eq!(
+            Period::Monthly.buckets(today),
+            ("day", NaiveDate::from_ymd_opt(2026, 9, 16).unwrap())
+        );
+    }
 
-/// Get a user's rank by games played within a time period
-pub async fn get_user_rank_games_played_period(
-    pool: &DbPool,
-    user_id: &str,
-    since: DateTime<Utc>,
-) -> Result<Option<(i64, i64)>, sqlx::Error> {
-    let result = sqlx::query!(
-        r#"
-        SELECT rank::bigint as "rank!", games_count::bigint as "score!"
-        FROM (
-            SELECT 
-                u.id,
-                COUNT(DISTINCT gp.game_id) as games_count,
-                RANK() OVER (ORDER BY COUNT(DISTINCT gp.game_id) DESC) as rank
-            FROM users u
-            INNER JOIN game_players gp ON gp.user_id = u.id
-            INNER JOIN games g ON g.id = gp.game_id
-            WHERE g.status = 'finished' AND g.ended_at >= $1 AND u.deleted_at IS NULL
-            GROUP BY u.id
-            HAVING COUNT(DISTINCT gp.game_id) > 0
-        ) ranked
-        WHERE id = $2
-        "#,
-        since,
-        user_id
-    )
-    .fetch_optional(pool)
-    .await?;
-    Ok(result.map(|r| (r.rank, r.score)))
-}
+    #[test]
+    fn test_streaks_always_read_streak_games() {
+        let scope = Scope { mode: Some(GameMode::Solo), ..Default::default() };
 
-/// Get a user's rank by average score within a time period (minimum 3 games)
-pub async fn get_user_rank_average_score_period(
-    pool: &DbPool,
-    user_id: &str,
-    since: DateTime<Utc>,
-) -> Result<Option<(i64, i64)>, sqlx::Error> {
-    let result = sqlx::query!(
-        r#"
-        SELECT rank::bigint as "rank!", avg_score::bigint as "score!"
-        FROM (
-            SELECT 
-                u.id,
-                ROUND(COALESCE(SUM(gp.score_total), 0)::numeric / NULLIF(COUNT(DISTINCT gp.game_id), 0), 0) as avg_score,
-                RANK() OVER (
-                    ORDER BY (SUM(gp.score_total)::numeric / NULLIF(COUNT(DISTINCT gp.game_id), 0)) DESC
-                ) as rank
-            FROM users u
-            INNER JOIN game_players gp ON gp.user_id = u.id
-            INNER JOIN games g ON g.id = gp.game_id
-            WHERE g.status = 'finished' AND g.ended_at >= $1 AND u.deleted_at IS NULL
-            GROUP BY u.id
-            HAVING COUNT(DISTINCT gp.game_id) >= 3
-        ) ranked
-        WHERE id = $2
-        "#,
-        since,
-        user_id
-    )
-    .fetch_optional(pool)
-    .await?;
-    Ok(result.map(|r| (r.rank, r.score)))
-}
+        let (_, _, map_id, mode, org_id) = scope.keys(Metric::Streak);
+        assert_eq!((map_id.as_str(), mode.as_str(), org_id), ("*", "streak", ""));
 
-/// Helper to get the start of a time period
-pub fn period_start(period: &str) -> Option<DateTime<Utc>> {
-    match period {
-        "daily" => Some(Utc::now() - Duration::days(1)),
-        "weekly" => Some(Utc::now() - Duration::weeks(1)),
-        "monthly" => Some(Utc::now() - Duration::days(30)),
-        _ => None, // "all_time" and any other value
+        let (_, _, _, mode, _) = scope.keys(Metric::TotalScore);
+        assert_eq!(mode, "solo");
     }
 }
@@ -2,20 +2,51 @@
 //!
 //! This crate provides database connection pooling and query functions.
 
+pub mod analytics;
+pub mod anti_cheat;
+pub mod audit_log;
+pub mod challenges;
+pub mod client_errors;
+pub mod credentials;
+pub mod defaults;
+pub mod devices;
+pub mod emails;
+pub mod featured_maps;
+pub mod friends;
+pub mod game_archive;
+pub mod game_invites;
+pub mod game_shares;
 pub mod games;
+pub mod impersonation;
 pub mod leaderboard;
+pub mod location_health;
+pub mod location_notes;
+pub mod location_stats;
 pub mod locations;
+pub mod map_versions;
+pub mod name_flags;
 pub mod oauth;
+pub mod organizations;
 pub mod parties;
+pub mod player_reports;
 pub mod pool;
+pub mod profiles;
+pub mod push;
+pub mod review_claims;
+pub mod round_hints;
+pub mod round_rerolls;
+pub mod schema;
 pub mod sessions;
+pub mod settings_presets;
+pub mod socket_outbox;
 pub mod users;
 
-pub use games::{Game, GameMode, GamePlayer, GameStatus, Guess, Round};
+pub use devices::UserDevice;
+pub use games::{Game, GameMode, GamePlayer, GameStatus, Guess, JoinCodeChoice, Round};
 pub use leaderboard::LeaderboardRow;
 pub use locations::LocationRepository;
-pub use oauth::OAuthAccount;
+pub use oauth::{OAuthAccount, UnlinkOutcome};
 pub use parties::{Party, PartyMember};
-pub use pool::{DbPool, create_pool};
+pub use pool::{DbPool, DbPools, create_pool};
 pub use sessions::Session;
-pub use users::{User, UserKind, UserRole};
+pub use users::{User, UserKind, UserLoader, UserRole};
@@ -0,0 +1,401 @@
+//! Location health check database queries
+
+use chrono::{DateTime, NaiveDate, Utc};
+use dguesser_core::streetview::ImageryProvider;
+use sqlx::FromRow;
+
+use crate::DbPool;
+
+/// An active location due for a health check.
+#[derive(Debug, Clone, FromRow)]
+pub struct HealthCheckTarget {
+    pub id: String, // loc_XXXXXXXXXXXX
+    pub panorama_id: String,
+}
+
+/// Summary of one health check run.
+#[derive(Debug, Clone, FromRow)]
+pub struct HealthCheckRun {
+    pub id: String, // lhc_XXXXXXXXXXXX
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub checked: i32,
+    pub healthy: i32,
+    pub dead: i32,
+    pub errors: i32,
+    pub revived: i32,
+}
+
+/// Outcome counts for a run, as recorded by the checker.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HealthCheckCounts {
+    pub checked: i32,
+    pub healthy: i32,
+    pub dead: i32,
+    pub errors: i32,
+    /// Inactive locations whose panorama exists again
+    pub revived: i32,
+}
+
+/// Health check totals for one day.
+#[derive(Debug, Clone, FromRow)]
+pub struct HealthCheckDay {
+    pub day: NaiveDate,
+    pub runs: i64,
+    pub checked: i64,
+    pub healthy: i64,
+    pub dead: i64,
+    pub errors: i64,
+    pub revived: i64,
+}
+
+/// A pending proposal to bring an inactive location back.
+#[derive(Debug, Clone, FromRow)]
+pub struct ReapprovalProposal {
+    pub location_id: String, // loc_XXXXXXXXXXXX
+    pub panorama_id: String,
+    pub lat: f64,
+    pub lng: f64,
+    pub country_code: Option<String>,
+    /// Review status when the proposal was made
+    pub previous_status: Option<String>,
+    /// Failure reason when the proposal was made
+    pub previous_reason: Option<String>,
+    pub proposed_at: DateTime<Utc>,
+}
+
+/// Pick up to `limit` active locations whose provider supports metadata
+/// checks, least recently validated first (never-validated locations before
+/// all others).
+pub async fn sample_targets(
+    pool: &DbPool,
+    limit: i64,
+) -> Result<Vec<HealthCheckTarget>, sqlx::Error> {
+    let providers: Vec<&str> = ImageryProvider::ALL
+        .into_iter()
+        .filter(|provider| provider.supports_metadata_checks())
+        .map(ImageryProvider::as_str)
+        .collect();
+
+    sqlx::query_as::<_, HealthCheckTarget>(
+        r#"
+        SELECT id, panorama_id
+        FROM locations
+        WHERE active = TRUE AND provider = ANY($2)
+        ORDER BY last_validated_at ASC NULLS FIRST
+        LIMIT $1
+        "#,
+    )
+    .bind(limit)
+    .bind(&providers)
+    .fetch_all(pool)
+    .await
+}
+
+/// Pick up to `limit` inactive locations to recheck: dead, rejected or
+/// flagged ones last validated over `recheck_days` ago, skipping those with
+/// a pending proposal or one dismissed within the last `recheck_days`.
+pub async fn sample_recheck_targets(
+    pool: &DbPool,
+    limit: i64,
+    recheck_days: i32,
+) -> Result<Vec<HealthCheckTarget>, sqlx::Error> {
+    let providers: Vec<&str> = ImageryProvider::ALL
+        .into_iter()
+        .filter(|provider| provider.supports_metadata_checks())
+        .map(ImageryProvider::as_str)
+        .collect();
+
+    sqlx::query_as::<_, HealthCheckTarget>(
+        r#"
+        SELECT l.id, l.panorama_id
+        FROM locations l
+        LEFT JOIN location_reapprovals r ON r.location_id = l.id
+        WHERE l.active = FALSE
+          AND l.provider = ANY($2)
+          AND (l.validation_status = 'zero_results' OR l.review_status IN ('rejected', 'flagged'))
+          AND (l.last_validated_at IS NULL
+               OR l.last_validated_at < NOW() - make_interval(days => $3))
+          AND (r.location_id IS NULL
+               OR (r.status = 'dismissed' AND r.decided_at < NOW() - make_interval(days => $3))
+               OR r.status = 'accepted')
+        ORDER BY l.last_validated_at ASC NULLS FIRST
+        LIMIT $1
+        "#,
+    )
+    .bind(limit)
+    .bind(&providers)
+    .bind(recheck_days)
+    .fetch_all(pool)
+    .await
+}
+
+/// Record that inactive locations were rechecked and are still dead.
+pub async fn mark_rechecked(pool: &DbPool, location_ids: &[String]) -> Result<u64, sqlx::Error> {
+    if location_ids.is_empty() {
+        return Ok(0);
+    }
+    let result = sqlx::query("UPDATE locations SET last_validated_at = NOW() WHERE id = ANY($1)")
+        .bind(location_ids)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected())
+}
+
+/// Propose bringing back an inactive location whose panorama exists again.
+/// The location itself stays inactive until an admin accepts.
+pub async fn propose_reapproval(pool: &DbPool, location_id: &str) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        WITH checked AS (
+            UPDATE locations SET last_validated_at = NOW()
+            WHERE id = $1
+            RETURNING id, review_status, last_failure_reason
+        )
+        INSERT INTO location_reapprovals (location_id, previous_status, previous_reason)
+        SELECT id, review_status, last_failure_reason FROM checked
+        ON CONFLICT (location_id) DO UPDATE
+            SET status = 'proposed',
+                previous_status = EXCLUDED.previous_status,
+                previous_reason = EXCLUDED.previous_reason,
+                proposed_at = NOW(),
+                decided_by = NULL,
+                decided_at = NULL
+        "#,
+    )
+    .bind(location_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Pending re-approval proposals, oldest first, with the total count.
+pub async fn list_reapprovals(
+    pool: &DbPool,
+    limit: i64,
+    offset: i64,
+) -> Result<(Vec<ReapprovalProposal>, i64), sqlx::Error> {
+    let rows = sqlx::query_as::<_, ReapprovalProposal>(
+        r#"
+        SELECT r.location_id, l.panorama_id, l.lat, l.lng, l.country_code,
+               r.previous_status, r.previous_reason, r.proposed_at
+        FROM location_reapprovals r
+        JOIN locations l ON l.id = r.location_id
+        WHERE r.status = 'proposed'
+        ORDER BY r.proposed_at ASC
+        LIMIT $1 OFFSET $2
+        "#,
+    )
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+
+    let total: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM location_reapprovals WHERE status = 'proposed'")
+            .fetch_one(pool)
+            .await?;
+
+    Ok((rows, total))
+}
+
+/// Accept a pending proposal: reactivate the location and approve it.
+/// Returns whether there was a pending proposal.
+pub async fn accept_reapproval(
+    pool: &DbPool,
+    location_id: &str,
+    reviewer_id: &str,
+) -> Result<bool, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let result = sqlx::query(
+        r#"
+        UPDATE location_reapprovals
+        SET status = 'accepted', decided_by = $2, decided_at = NOW()
+        WHERE location_id = $1 AND status = 'proposed'
+        "#,
+    )
+    .bind(location_id)
+    .bind(reviewer_id)
+    .execute(&mut *tx)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Ok(false);
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE locations
+        SET active = TRUE,
+            validation_status = 'ok',
+            review_status = 'approved',
+            reviewed_at = NOW(),
+            reviewed_by = $2,
+            failure_count = 0,
+            last_failure_reason = NULL
+        WHERE id = $1
+        "#,
+    )
+    .bind(location_id)
+    .bind(reviewer_id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(true)
+}
+
+/// Dismiss a pending proposal, leaving the location inactive. It won't be
+/// proposed again until the recheck interval has passed.
+pub async fn dismiss_reapproval(
+    pool: &DbPool,
+    location_id: &str,
+    reviewer_id: &str,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE location_reapprovals
+        SET status = 'dismissed', decided_by = $2, decided_at = NOW()
+        WHERE location_id = $1 AND status = 'proposed'
+        "#,
+    )
+    .bind(location_id)
+    .bind(reviewer_id)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Record that these locations' panoramas were confirmed to exist.
+pub async fn mark_healthy(pool: &DbPool, location_ids: &[String]) -> Result<u64, sqlx::Error> {
+    if location_ids.is_empty() {
+        return Ok(0);
+    }
+    let result = sqlx::query(
+        r#"
+        UPDATE locations
+        SET validation_status = 'ok', last_validated_at = NOW()
+        WHERE id = ANY($1)
+        "#,
+    )
+    .bind(location_ids)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Record that a location's panorama no longer exists and deactivate it.
+pub async fn mark_dead(pool: &DbPool, location_id: &str, reason: &str) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE locations
+        SET active = FALSE,
+            validation_status = 'zero_results',
+            last_validated_at = NOW(),
+            last_failure_reason = $2
+        WHERE id = $1
+        "#,
+    )
+    .bind(location_id)
+    .bind(reason)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Deactivate every active location showing a panorama that no longer
+/// exists. Returns the IDs of the deactivated locations.
+pub async fn mark_dead_by_panorama(
+    pool: &DbPool,
+    panorama_id: &str,
+    reason: &str,
+) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        UPDATE locations
+        SET active = FALSE,
+            validation_status = 'zero_results',
+            last_validated_at = NOW(),
+            last_failure_reason = $2
+        WHERE panorama_id = $1 AND active
+        RETURNING id
+        "#,
+    )
+    .bind(panorama_id)
+    .bind(reason)
+    .fetch_all(pool)
+    .await
+}
+
+/// Store the summary of a finished run. Returns the new run ID.
+pub async fn record_run(
+    pool: &DbPool,
+    started_at: DateTime<Utc>,
+    counts: HealthCheckCounts,
+) -> Result<String, sqlx::Error> {
+    let id = dguesser_core::generate_health_check_id();
+
+    sqlx::query(
+        r#"
+        INSERT INTO location_health_checks (id, started_at, checked, healthy, dead, errors, revived)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        "#,
+    )
+    .bind(&id)
+    .bind(started_at)
+    .bind(counts.checked)
+    .bind(counts.healthy)
+    .bind(counts.dead)
+    .bind(counts.errors)
+    .bind(counts.revived)
+    .execute(pool)
+    .await?;
+
+    Ok(id)
+}
+
+/// The most recent run, if any.
+pub async fn latest_run(pool: &DbPool) -> Result<Option<HealthCheckRun>, sqlx::Error> {
+    sqlx::query_as::<_, HealthCheckRun>(
+        r#"
+        SELECT id, started_at, finished_at, checked, healthy, dead, errors, revived
+        FROM location_health_checks
+        ORDER BY started_at DESC
+        LIMIT 1
+        "#,
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+/// Per-day totals for the last `days` days, oldest first.
+pub async fn daily_totals(pool: &DbPool, days: i32) -> Result<Vec<HealthCheckDay>, sqlx::Error> {
+    sqlx::query_as::<_, HealthCheckDay>(
+        r#"
+        SELECT (started_at AT TIME ZONE 'UTC')::date AS day,
+               COUNT(*) AS runs,
+               COALESCE(SUM(checked), 0)::BIGINT AS checked,
+               COALESCE(SUM(healthy), 0)::BIGINT AS healthy,
+               COALESCE(SUM(dead), 0)::BIGINT AS dead,
+               COALESCE(SUM(errors), 0)::BIGINT AS errors,
+               COALESCE(SUM(revived), 0)::BIGINT AS revived
+        FROM location_health_checks
+        WHERE started_at >= NOW() - make_interval(days => $1)
+        GROUP BY day
+        ORDER BY day
+        "#,
+    )
+    .bind(days)
+    .fetch_all(pool)
+    .await
+}
+
+/// Delete runs older than `days` (call periodically).
+pub async fn cleanup_runs(pool: &DbPool, days: i32) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        "DELETE FROM location_health_checks WHERE started_at < NOW() - make_interval(days => $1)",
+    )
+    .bind(days)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
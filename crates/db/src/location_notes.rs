@@ -0,0 +1,158 @@
+//! Internal location note queries
+//!
+//! Admins and map curators attach notes and tags to locations for each
+//! other; players never see them. Notes are append-only apart from deletion.
+
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+use crate::DbPool;
+use crate::locations::contains_pattern;
+
+/// A note with its author's current display name
+#[derive(Debug, Clone, FromRow)]
+pub struct LocationNote {
+    pub id: i64,
+    pub location_id: String,       // loc_XXXXXXXXXXXX
+    pub author_id: Option<String>, // usr_XXXXXXXXXXXX
+    pub author_name: Option<String>,
+    /// Map a curator wrote the note from; `None` for admin notes
+    pub map_id: Option<String>,
+    pub body: String,
+    pub tags: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Filters for [`search`]; `None` matches everything.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoteFilter<'a> {
+    /// Substring of the note body, case-insensitive
+    pub text: Option<&'a str>,
+    /// Normalized tag the note must have
+    pub tag: Option<&'a str>,
+    pub location_id: Option<&'a str>,
+    pub author_id: Option<&'a str>,
+}
+
+const NOTE_COLUMNS: &str = "n.id, n.location_id, n.author_id, u.display_name AS author_name, \
+                            n.map_id, n.body, n.tags, n.created_at";
+
+/// Add a note to a location.
+pub async fn create(
+    pool: &DbPool,
+    location_id: &str,
+    author_id: &str,
+    map_id: Option<&str>,
+    body: &str,
+    tags: &[String],
+) -> Result<LocationNote, sqlx::Error> {
+    sqlx::query_as::<_, LocationNote>(&format!(
+        r#"
+        WITH n AS (
+            INSERT INTO location_notes (location_id, author_id, map_id, body, tags)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+        )
+        SELECT {NOTE_COLUMNS} FROM n LEFT JOIN users u ON u.id = n.author_id
+        "#
+    ))
+    .bind(location_id)
+    .bind(author_id)
+    .bind(map_id)
+    .bind(body)
+    .bind(tags)
+    .fetch_one(pool)
+    .await
+}
+
+/// Get a note.
+pub async fn get(pool: &DbPool, id: i64) -> Result<Option<LocationNote>, sqlx::Error> {
+    sqlx::query_as::<_, LocationNote>(&format!(
+        "SELECT {NOTE_COLUMNS} FROM location_notes n \
+         LEFT JOIN users u ON u.id = n.author_id WHERE n.id = $1"
+    ))
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Notes on several locations, newest first.
+pub async fn list_for_locations(
+    pool: &DbPool,
+    location_ids: &[String],
+) -> Result<Vec<LocationNote>, sqlx::Error> {
+    if location_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    sqlx::query_as::<_, LocationNote>(&format!(
+        "SELECT {NOTE_COLUMNS} FROM location_notes n \
+         LEFT JOIN users u ON u.id = n.author_id \
+         WHERE n.location_id = ANY($1) ORDER BY n.created_at DESC, n.id DESC"
+    ))
+    .bind(location_ids)
+    .fetch_all(pool)
+    .await
+}
+
+/// Notes on one location, newest first.
+pub async fn list_for_location(
+    pool: &DbPool,
+    location_id: &str,
+) -> Result<Vec<LocationNote>, sqlx::Error> {
+    list_for_locations(pool, &[location_id.to_string()]).await
+}
+
+/// Search notes, newest first, with the total count.
+pub async fn search(
+    pool: &DbPool,
+    filter: NoteFilter<'_>,
+    limit: i64,
+    offset: i64,
+) -> Result<(Vec<LocationNote>, i64), sqlx::Error> {
+    let pattern = filter.text.map(contains_pattern);
+    let rows = sqlx::query_as::<_, LocationNote>(&format!(
+        r#"
+        SELECT {NOTE_COLUMNS} FROM location_notes n
+        LEFT JOIN users u ON u.id = n.author_id
+        WHERE ($1::TEXT IS NULL OR n.body ILIKE $1)
+          AND ($2::TEXT IS NULL OR $2 = ANY(n.tags))
+          AND ($3::TEXT IS NULL OR n.location_id = $3)
+          AND ($4::TEXT IS NULL OR n.author_id = $4)
+        ORDER BY n.created_at DESC, n.id DESC
+        LIMIT $5 OFFSET $6
+        "#
+    ))
+    .bind(pattern.as_deref())
+    .bind(filter.tag)
+    .bind(filter.location_id)
+    .bind(filter.author_id)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+
+    let total: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*) FROM location_notes n
+        WHERE ($1::TEXT IS NULL OR n.body ILIKE $1)
+          AND ($2::TEXT IS NULL OR $2 = ANY(n.tags))
+          AND ($3::TEXT IS NULL OR n.location_id = $3)
+          AND ($4::TEXT IS NULL OR n.author_id = $4)
+        "#,
+    )
+    .bind(pattern.as_deref())
+    .bind(filter.tag)
+    .bind(filter.location_id)
+    .bind(filter.author_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok((rows, total))
+}
+
+/// Delete a note. Returns whether it existed.
+pub async fn delete(pool: &DbPool, id: i64) -> Result<bool, sqlx::Error> {
+    let result =
+        sqlx::query("DELETE FROM location_notes WHERE id = $1").bind(id).execute(pool).await?;
+    Ok(result.rows_affected() > 0)
+}
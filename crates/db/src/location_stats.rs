@@ -0,0 +1,146 @@
+//! Per-location guess statistics
+//!
+//! `location_guess_stats` is rebuilt from `guesses` for the locations guessed
+//! since the last refresh; round results only read single rows. Database
+//! locations also carry a difficulty rating derived from these statistics.
+
+use chrono::{DateTime, Utc};
+use dguesser_core::game::LocationGuessStats;
+use dguesser_core::game::location_stats::{MIN_GUESSES_FOR_STATS, distance_quantile_fractions};
+use dguesser_core::location::difficulty_rating;
+use sqlx::FromRow;
+
+use crate::DbPool;
+
+/// Guess statistics for one location.
+#[derive(Debug, Clone, FromRow)]
+pub struct LocationGuessStatsRow {
+    pub location_id: String,
+    pub guesses: i32,
+    pub median_distance_meters: f64,
+    pub median_score: f64,
+    pub distance_quantiles: Vec<f64>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<LocationGuessStatsRow> for LocationGuessStats {
+    fn from(row: LocationGuessStatsRow) -> Self {
+        Self {
+            guesses: row.guesses.max(0) as u32,
+            median_distance_meters: row.median_distance_meters,
+            distance_quantiles: row.distance_quantiles,
+        }
+    }
+}
+
+/// When the statistics were last refreshed, if ever.
+pub async fn last_refreshed_at(pool: &DbPool) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+    sqlx::query_scalar("SELECT MAX(updated_at) FROM location_guess_stats").fetch_one(pool).await
+}
+
+/// Recompute the statistics of every location guessed at or after `since`
+/// (all locations when `None`). Returns the number of locations refreshed.
+pub async fn refresh(pool: &DbPool, since: Option<DateTime<Utc>>) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        WITH touched AS (
+            SELECT DISTINCT r.location_id
+            FROM guesses g
+            JOIN rounds r ON r.id = g.round_id
+            WHERE r.location_id IS NOT NULL
+              AND ($1::timestamptz IS NULL OR g.submitted_at >= $1)
+        )
+        INSERT INTO location_guess_stats (
+            location_id, guesses, median_distance_meters, median_score, distance_quantiles
+        )
+        SELECT r.location_id,
+               COUNT(*),
+               percentile_cont(0.5) WITHIN GROUP (ORDER BY g.distance_meters),
+               percentile_cont(0.5) WITHIN GROUP (ORDER BY g.score),
+               percentile_cont($2::float8[]) WITHIN GROUP (ORDER BY g.distance_meters)
+        FROM guesses g
+        JOIN rounds r ON r.id = g.round_id
+        JOIN touched t ON t.location_id = r.location_id
+        WHERE g.distance_meters >= 0
+        GROUP BY r.location_id
+        ON CONFLICT (location_id) DO UPDATE
+        SET guesses = EXCLUDED.guesses,
+            median_distance_meters = EXCLUDED.median_distance_meters,
+            median_score = EXCLUDED.median_score,
+            distance_quantiles = EXCLUDED.distance_quantiles,
+            updated_at = NOW()
+        "#,
+    )
+    .bind(since)
+    .bind(distance_quantile_fractions())
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Get the statistics for one location.
+pub async fn get_for_location(
+    pool: &DbPool,
+    location_id: &str,
+) -> Result<Option<LocationGuessStatsRow>, sqlx::Error> {
+    sqlx::query_as::<_, LocationGuessStatsRow>(
+        r#"
+        SELECT location_id, guesses, median_distance_meters, median_score, distance_quantiles,
+               updated_at
+        FROM location_guess_stats
+        WHERE location_id = $1
+        "#,
+    )
+    .bind(location_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Rate the database locations whose statistics changed at or after `since`
+/// (all when `None`). Returns the number of locations rated.
+pub async fn refresh_difficulty(
+    pool: &DbPool,
+    since: Option<DateTime<Utc>>,
+) -> Result<u64, sqlx::Error> {
+    let rows = sqlx::query_as::<_, LocationGuessStatsRow>(
+        r#"
+        SELECT location_id, guesses, median_distance_meters, median_score, distance_quantiles,
+               updated_at
+        FROM location_guess_stats
+        WHERE guesses >= $1 AND ($2::timestamptz IS NULL OR updated_at >= $2)
+        "#,
+    )
+    .bind(MIN_GUESSES_FOR_STATS as i32)
+    .bind(since)
+    .fetch_all(pool)
+    .await?;
+
+    if rows.is_empty() {
+        return Ok(0);
+    }
+
+    let ids: Vec<&str> = rows.iter().map(|r| r.location_id.as_str()).collect();
+    let ratings: Vec<i16> =
+        rows.iter().map(|r| difficulty_rating(r.median_distance_meters)).collect();
+    let distances: Vec<f64> = rows.iter().map(|r| r.median_distance_meters).collect();
+    let scores: Vec<f64> = rows.iter().map(|r| r.median_score).collect();
+
+    let result = sqlx::query(
+        r#"
+        UPDATE locations l
+        SET difficulty = d.difficulty,
+            median_guess_distance_meters = d.distance,
+            median_guess_score = d.score
+        FROM UNNEST($1::text[], $2::int2[], $3::float8[], $4::float8[])
+            AS d(id, difficulty, distance, score)
+        WHERE l.id = d.id
+        "#,
+    )
+    .bind(&ids)
+    .bind(&ratings)
+    .bind(&distances)
+    .bind(&scores)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
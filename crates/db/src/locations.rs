@@ -6,12 +6,14 @@ use std::pin::Pin;
 use std::sync::Arc;
 
 use chrono::{DateTime, NaiveDate, Utc};
+use dguesser_core::game::ScoringConfig;
 use dguesser_core::location::{
-    CountryDistribution, GameLocation, Location, LocationError, LocationProvider, LocationSource,
-    LocationValidationStatus, Map, MapRules, MapVisibility, ReviewStatus, SelectionConstraints,
-    select_spread_candidate,
+    CountryDistribution, DifficultyBand, GameLocation, Location, LocationError, LocationProvider,
+    LocationSource, LocationValidationStatus, Map, MapLocationSource, MapRegion, MapRules,
+    MapVisibility, ReviewStatus, SelectionConstraints, select_ranked_candidate,
 };
-use sqlx::FromRow;
+use dguesser_core::streetview::ImageryProvider;
+use sqlx::{FromRow, Postgres, QueryBuilder};
 
 use rand::RngExt;
 
@@ -44,6 +46,7 @@ struct LocationRow {
     roads_100: Option<i32>,
     elevation: Option<i32>,
     heading: Option<f64>,
+    tags: Vec<String>,
     // Failure tracking
     failure_count: Option<i32>,
     last_failure_reason: Option<String>,
@@ -51,6 +54,10 @@ struct LocationRow {
     review_status: Option<String>,
     reviewed_at: Option<DateTime<Utc>>,
     reviewed_by: Option<String>,
+    // Gameplay difficulty
+    difficulty: Option<i16>,
+    median_guess_distance_meters: Option<f64>,
+    median_guess_score: Option<f64>,
 }
 
 impl TryFrom<LocationRow> for Location {
@@ -72,6 +79,8 @@ impl TryFrom<LocationRow> for Location {
             .parse::<ReviewStatus>()
             .unwrap_or_default();
 
+        let provider = row.provider.parse::<ImageryProvider>().unwrap_or_default();
+
         Ok(Location {
             id: row.id,
             panorama_id: row.panorama_id,
@@ -80,7 +89,7 @@ impl TryFrom<LocationRow> for Location {
             country_code: row.country_code,
             subdivision_code: row.subdivision_code,
             capture_date: row.capture_date,
-            provider: row.provider,
+            provider,
             active: row.active,
             last_validated_at: row.last_validated_at,
             validation_status,
@@ -94,6 +103,7 @@ impl TryFrom<LocationRow> for Location {
             roads_100: row.roads_100,
             elevation: row.elevation,
             heading: row.heading,
+            tags: row.tags,
             // Failure tracking
             failure_count: row.failure_count.unwrap_or(0),
             last_failure_reason: row.last_failure_reason,
@@ -101,6 +111,10 @@ impl TryFrom<LocationRow> for Location {
             review_status,
             reviewed_at: row.reviewed_at,
             reviewed_by: row.reviewed_by,
+            // Gameplay difficulty
+            difficulty: row.difficulty,
+            median_guess_distance_meters: row.median_guess_distance_meters,
+            median_guess_score: row.median_guess_score,
         })
     }
 }
@@ -117,6 +131,11 @@ struct MapRow {
     creator_id: Option<String>,
     visibility: String,
     location_count: i32,
+    like_count: i32,
+    play_count: i32,
+    score_sum: i64,
+    source: Option<String>,
+    org_id: Option<String>,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
 }
@@ -133,6 +152,12 @@ impl TryFrom<MapRow> for Map {
             .parse::<MapVisibility>()
             .map_err(|e| LocationError::Database(format!("Invalid map visibility: {e}")))?;
 
+        let source = row
+            .source
+            .map(|s| s.parse::<MapLocationSource>())
+            .transpose()
+            .map_err(|e| LocationError::Database(format!("Invalid map source: {e}")))?;
+
         Ok(Map {
             id: row.id,
             slug: row.slug,
@@ -144,6 +169,11 @@ impl TryFrom<MapRow> for Map {
             creator_id: row.creator_id,
             visibility,
             location_count: row.location_count,
+            like_count: row.like_count,
+            play_count: row.play_count,
+            score_sum: row.score_sum,
+            source,
+            org_id: row.org_id,
             created_at: row.created_at,
             updated_at: row.updated_at,
         })
@@ -159,6 +189,7 @@ struct GameLocationRow {
     lng: f64,
     country_code: Option<String>,
     heading: Option<f64>,
+    provider: String,
 }
 
 impl From<GameLocationRow> for GameLocation {
@@ -170,6 +201,7 @@ impl From<GameLocationRow> for GameLocation {
             lng: row.lng,
             country_code: row.country_code,
             heading: row.heading,
+            provider: row.provider.parse().unwrap_or_default(),
         }
     }
 }
@@ -202,7 +234,7 @@ impl LocationProvider for LocationRepository {
         map_id: &'a str,
         exclude_ids: &'a [String],
     ) -> Pin<Box<dyn Future<Output = Result<GameLocation, LocationError>> + Send + 'a>> {
-        Box::pin(async move { select_random_location(&self.pool, map_id, exclude_ids).await })
+        Box::pin(async move { select_random_location(&self.pool, map_id, exclude_ids, &[]).await })
     }
 
     fn get_map<'a>(
@@ -272,27 +304,70 @@ fn build_location_filter_clause(rules: &MapRules) -> String {
         conditions.push("(l.is_scout IS NULL OR l.is_scout = FALSE)".to_string());
     }
 
+    if let Some(provider) = rules.provider {
+        conditions.push(format!("l.provider = '{}'", provider.as_str()));
+    }
+
+    if let Some(band) = rules.difficulty {
+        let (min, max) = band.range();
+        conditions.push(format!("l.difficulty BETWEEN {min} AND {max}"));
+    }
+
     // Only select approved locations
     conditions.push("(l.review_status IS NULL OR l.review_status = 'approved')".to_string());
 
     if conditions.is_empty() { String::new() } else { format!(" AND {}", conditions.join(" AND ")) }
 }
 
+/// SQL that yields a map's candidate locations as `l`, with the map ID bound
+/// to `$1`, and the column to seek on for random selection.
+///
+/// Maps with a polygon region draw from every active location inside it (and
+/// inside the map's country list, when it has one); other maps draw from their
+/// `map_locations` list.
+struct CandidateSource {
+    from: &'static str,
+    random_key: &'static str,
+}
+
+impl CandidateSource {
+    fn for_rules(rules: &MapRules) -> Self {
+        if rules.region.is_some() {
+            Self {
+                from: r#"locations l
+                JOIN maps m ON m.id = $1
+                    AND ST_Intersects(l.geom, m.region)
+                    AND (jsonb_array_length(COALESCE(m.rules->'countries', '[]')) = 0
+                         OR m.rules->'countries' ? l.country_code)"#,
+                random_key: "l.random_key",
+            }
+        } else {
+            Self {
+                from: "locations l JOIN map_locations ml ON l.id = ml.location_id AND ml.map_id = $1",
+                random_key: "ml.random_key",
+            }
+        }
+    }
+}
+
 /// Select a random location from a map using the seek-then-wrap algorithm.
 /// This is O(log n) instead of O(n) for ORDER BY random().
-/// Respects map rules for min_year, max_year, outdoor_only, and country_distribution.
+/// Respects map rules for min_year, max_year, outdoor_only, country_distribution,
+/// and region.
 async fn select_random_location(
     pool: &DbPool,
     map_id_or_slug: &str,
     exclude_ids: &[String],
+    exclude_panoramas: &[String],
 ) -> Result<GameLocation, LocationError> {
     // First, resolve the map ID and get rules
     let map = get_map_by_id_or_slug(pool, map_id_or_slug).await?;
     let map_id = &map.id;
     let filter_clause = build_location_filter_clause(&map.rules);
+    let source = CandidateSource::for_rules(&map.rules);
 
     let available_countries =
-        load_available_countries(pool, map_id, &filter_clause, &map.rules).await?;
+        load_available_countries(pool, map_id, &source, &filter_clause, &map.rules).await?;
     if !matches!(&map.rules.country_distribution, CountryDistribution::Proportional)
         && available_countries.is_none()
     {
@@ -309,19 +384,19 @@ async fn select_random_location(
     // Build the query with dynamic filters - country filter uses parameterized query ($4)
     let query = format!(
         r#"
-        SELECT l.id, l.panorama_id, l.lat, l.lng, l.country_code, l.heading
-        FROM locations l
-        JOIN map_locations ml ON l.id = ml.location_id
-        WHERE ml.map_id = $1
-          AND l.active = TRUE
-          AND ml.random_key >= $2
+        SELECT l.id, l.panorama_id, l.lat, l.lng, l.country_code, l.heading, l.provider
+        FROM {from}
+        WHERE l.active = TRUE
+          AND {random_key} >= $2
           AND l.id != ALL($3)
+          AND l.panorama_id != ALL($5)
           AND ($4::text IS NULL OR l.country_code = $4)
-          {}
-        ORDER BY ml.random_key
+          {filter_clause}
+        ORDER BY {random_key}
         LIMIT 1
         "#,
-        filter_clause
+        from = source.from,
+        random_key = source.random_key,
     );
 
     // Try to find a location with random_key >= our random value
@@ -330,6 +405,7 @@ async fn select_random_location(
         .bind(random_key)
         .bind(exclude_ids)
         .bind(selected_country.as_deref())
+        .bind(exclude_panoramas)
         .fetch_optional(pool)
         .await
         .map_err(|e| LocationError::Database(e.to_string()))?;
@@ -340,24 +416,25 @@ async fn select_random_location(
         None => {
             let wrap_query = format!(
                 r#"
-                SELECT l.id, l.panorama_id, l.lat, l.lng, l.country_code, l.heading
-                FROM locations l
-                JOIN map_locations ml ON l.id = ml.location_id
-                WHERE ml.map_id = $1
-                  AND l.active = TRUE
+                SELECT l.id, l.panorama_id, l.lat, l.lng, l.country_code, l.heading, l.provider
+                FROM {from}
+                WHERE l.active = TRUE
                   AND l.id != ALL($2)
+                  AND l.panorama_id != ALL($4)
                   AND ($3::text IS NULL OR l.country_code = $3)
-                  {}
-                ORDER BY ml.random_key
+                  {filter_clause}
+                ORDER BY {random_key}
                 LIMIT 1
                 "#,
-                filter_clause
+                from = source.from,
+                random_key = source.random_key,
             );
 
             sqlx::query_as::<_, GameLocationRow>(&wrap_query)
                 .bind(map_id)
                 .bind(exclude_ids)
                 .bind(selected_country.as_deref())
+                .bind(exclude_panoramas)
                 .fetch_optional(pool)
                 .await
                 .map_err(|e| LocationError::Database(e.to_string()))?
@@ -372,19 +449,18 @@ async fn select_random_location(
 async fn get_map_countries(
     pool: &DbPool,
     map_id: &str,
+    source: &CandidateSource,
     filter_clause: &str,
 ) -> Result<Vec<String>, LocationError> {
     let query = format!(
         r#"
         SELECT DISTINCT l.country_code
-        FROM locations l
-        JOIN map_locations ml ON l.id = ml.location_id
-        WHERE ml.map_id = $1
-          AND l.active = TRUE
+        FROM {from}
+        WHERE l.active = TRUE
           AND l.country_code IS NOT NULL
-          {}
+          {filter_clause}
         "#,
-        filter_clause
+        from = source.from,
     );
 
     let rows: Vec<(String,)> = sqlx::query_as(&query)
@@ -399,13 +475,14 @@ async fn get_map_countries(
 async fn load_available_countries(
     pool: &DbPool,
     map_id: &str,
+    source: &CandidateSource,
     filter_clause: &str,
     rules: &MapRules,
 ) -> Result<Option<Vec<String>>, LocationError> {
     match &rules.country_distribution {
         CountryDistribution::Proportional => Ok(None),
-        CountryDistribution::Equal | CountryDistribution::Weighted { .. } => {
-            let countries = get_map_countries(pool, map_id, filter_clause).await?;
+        _ => {
+            let countries = get_map_countries(pool, map_id, source, filter_clause).await?;
             if countries.is_empty() { Ok(None) } else { Ok(Some(countries)) }
         }
     }
@@ -417,53 +494,59 @@ fn select_country_for_distribution(
 ) -> Option<String> {
     let countries = countries.filter(|countries| !countries.is_empty())?;
 
-    match distribution {
-        CountryDistribution::Proportional => None,
-        CountryDistribution::Equal => {
-            let idx = rand::random_range(0..countries.len());
-            Some(countries[idx].clone())
-        }
-        CountryDistribution::Weighted { weights } => {
-            let weighted: Vec<(&str, u32)> =
-                countries.iter().filter_map(|c| weights.get(c).map(|&w| (c.as_str(), w))).collect();
-
-            if weighted.is_empty() {
-                let idx = rand::random_range(0..countries.len());
-                Some(countries[idx].clone())
-            } else {
-                let total: u64 = weighted.iter().map(|(_, w)| *w as u64).sum();
-                let target = rand::random_range(0..total);
-                let mut cumulative = 0u64;
-                let mut selected = weighted[0].0;
-
-                for (country, weight) in &weighted {
-                    cumulative += *weight as u64;
-                    if cumulative > target {
-                        selected = country;
-                        break;
-                    }
-                }
-
-                Some(selected.to_string())
-            }
+    // Proportional selection is left to the per-row random key
+    if matches!(distribution, CountryDistribution::Proportional) {
+        return None;
+    }
+
+    let weighted: Vec<(&str, u64)> = countries
+        .iter()
+        .filter_map(|c| {
+            let weight = distribution.static_weight(c)?;
+            (weight > 0).then_some((c.as_str(), weight))
+        })
+        .collect();
+
+    // No country has weight (e.g. custom weights for none of them): pick uniformly
+    if weighted.is_empty() {
+        let idx = rand::random_range(0..countries.len());
+        return Some(countries[idx].clone());
+    }
+
+    let total: u64 = weighted.iter().map(|(_, w)| w).sum();
+    let target = rand::random_range(0..total);
+    let mut cumulative = 0u64;
+    let mut selected = weighted[0].0;
+
+    for (country, weight) in &weighted {
+        cumulative += weight;
+        if cumulative > target {
+            selected = country;
+            break;
         }
     }
+
+    Some(selected.to_string())
 }
 
 /// Select a random location with distance constraints.
 ///
 /// Attempts to find a location that is at least `min_distance_meters` away
 /// from all previous locations. Falls back to any valid location if
-/// the constraint cannot be satisfied after multiple attempts.
+/// the constraint cannot be satisfied after multiple attempts. Recently seen
+/// panoramas are always excluded, and candidates in `avoid_country` are
+/// ranked lower.
 async fn select_random_location_with_constraints(
     pool: &DbPool,
     map_id_or_slug: &str,
     exclude_ids: &[String],
     constraints: &SelectionConstraints,
 ) -> Result<GameLocation, LocationError> {
-    // If there are no previous round locations, use simple selection.
-    if !constraints.uses_spread_ranking() {
-        return select_random_location(pool, map_id_or_slug, exclude_ids).await;
+    let exclude_panoramas = constraints.exclude_panoramas.as_slice();
+
+    // If there is nothing to rank candidates by, use simple selection.
+    if !constraints.uses_candidate_ranking() {
+        return select_random_location(pool, map_id_or_slug, exclude_ids, exclude_panoramas).await;
     }
 
     const MAX_ATTEMPTS: u32 = 10;
@@ -473,9 +556,10 @@ async fn select_random_location_with_constraints(
     let map = get_map_by_id_or_slug(pool, map_id_or_slug).await?;
     let map_id = &map.id;
     let filter_clause = build_location_filter_clause(&map.rules);
+    let source = CandidateSource::for_rules(&map.rules);
 
     let available_countries =
-        load_available_countries(pool, map_id, &filter_clause, &map.rules).await?;
+        load_available_countries(pool, map_id, &source, &filter_clause, &map.rules).await?;
     if !matches!(&map.rules.country_distribution, CountryDistribution::Proportional)
         && available_countries.is_none()
     {
@@ -494,24 +578,24 @@ async fn select_random_location_with_constraints(
         // Fetch multiple candidates - uses parameterized query for country filter
         let query = format!(
             r#"
-            SELECT l.id, l.panorama_id, l.lat, l.lng, l.country_code, l.heading
-            FROM locations l
-            JOIN map_locations ml ON l.id = ml.location_id
-            WHERE ml.map_id = $1
-              AND l.active = TRUE
+            SELECT l.id, l.panorama_id, l.lat, l.lng, l.country_code, l.heading, l.provider
+            FROM {from}
+            WHERE l.active = TRUE
               AND l.id != ALL($2)
+              AND l.panorama_id != ALL($4)
               AND ($3::text IS NULL OR l.country_code = $3)
-              {}
+              {filter_clause}
             ORDER BY random()
-            LIMIT {}
+            LIMIT {CANDIDATES_PER_ATTEMPT}
             "#,
-            filter_clause, CANDIDATES_PER_ATTEMPT
+            from = source.from,
         );
 
         let batch: Vec<GameLocationRow> = sqlx::query_as(&query)
             .bind(map_id)
             .bind(exclude_ids)
             .bind(selected_country.as_deref())
+            .bind(exclude_panoramas)
             .fetch_all(pool)
             .await
             .map_err(|e| LocationError::Database(e.to_string()))?;
@@ -540,13 +624,16 @@ async fn select_random_location_with_constraints(
             previous_count = constraints.previous_locations.len(),
             "No spread-ranking candidates found, falling back to basic selection"
         );
-        return select_random_location(pool, map_id_or_slug, exclude_ids).await;
+        return select_random_location(pool, map_id_or_slug, exclude_ids, exclude_panoramas).await;
     }
 
     let candidate_coords: Vec<(f64, f64)> =
         candidates.iter().map(|candidate| (candidate.lat, candidate.lng)).collect();
-    let selection = select_spread_candidate(&candidate_coords, constraints)
-        .ok_or_else(|| LocationError::NoLocationsAvailable(map_id_or_slug.to_string()))?;
+    let candidate_countries: Vec<Option<&str>> =
+        candidates.iter().map(|candidate| candidate.country_code.as_deref()).collect();
+    let selection =
+        select_ranked_candidate(&candidate_coords, &candidate_countries, constraints)
+            .ok_or_else(|| LocationError::NoLocationsAvailable(map_id_or_slug.to_string()))?;
     let chosen = candidates.swap_remove(selection.selected_index);
 
     if constraints.has_hard_min_distance() && !selection.met_min_distance {
@@ -573,11 +660,15 @@ async fn select_random_location_with_constraints(
 /// All columns to select for a Map row.
 const MAP_COLUMNS: &str = r#"
     id, slug, name, description, rules, is_default, active,
-    creator_id, visibility, location_count, created_at, updated_at
+    creator_id, visibility, location_count, like_count, play_count, score_sum,
+    source, org_id, created_at, updated_at
 "#;
 
 /// Get a map by ID or slug.
-async fn get_map_by_id_or_slug(pool: &DbPool, map_id_or_slug: &str) -> Result<Map, LocationError> {
+pub(crate) async fn get_map_by_id_or_slug(
+    pool: &DbPool,
+    map_id_or_slug: &str,
+) -> Result<Map, LocationError> {
     let row = sqlx::query_as::<_, MapRow>(&format!(
         r#"
         SELECT {MAP_COLUMNS}
@@ -619,20 +710,18 @@ async fn get_location_count_for_map(
 ) -> Result<i64, LocationError> {
     let map = get_map_by_id_or_slug(pool, map_id_or_slug).await?;
 
-    let count = sqlx::query_scalar!(
-        r#"
-        SELECT COUNT(*)
-        FROM map_locations ml
-        JOIN locations l ON l.id = ml.location_id
-        WHERE ml.map_id = $1 AND l.active = TRUE
-        "#,
-        map.id
-    )
+    let source = CandidateSource::for_rules(&map.rules);
+
+    let count: i64 = sqlx::query_scalar(&format!(
+        "SELECT COUNT(*) FROM {from} WHERE l.active = TRUE",
+        from = source.from
+    ))
+    .bind(&map.id)
     .fetch_one(pool)
     .await
     .map_err(|e| LocationError::Database(e.to_string()))?;
 
-    Ok(count.unwrap_or(0))
+    Ok(count)
 }
 
 /// Mark a location as failed (deactivate it).
@@ -663,9 +752,59 @@ const LOCATION_COLUMNS: &str = r#"
     id, panorama_id, lat, lng, country_code, subdivision_code, capture_date, provider,
     active, last_validated_at, validation_status, created_at,
     source, surface, arrow_count, is_scout, buildings_100, roads_100, elevation, heading,
-    failure_count, last_failure_reason, review_status, reviewed_at, reviewed_by
+    failure_count, last_failure_reason, review_status, reviewed_at, reviewed_by,
+    difficulty, median_guess_distance_meters, median_guess_score, tags
 "#;
 
+/// Location fields needed to build R2 location packs.
+#[derive(Debug, Clone, FromRow)]
+pub struct PackSourceLocation {
+    pub id: String,
+    pub panorama_id: String,
+    pub lat: f64,
+    pub lng: f64,
+    pub country_code: String,
+    pub subdivision_code: Option<String>,
+    pub capture_date: Option<NaiveDate>,
+    pub is_scout: Option<bool>,
+    pub heading: Option<f64>,
+    pub surface: Option<String>,
+    pub arrow_count: Option<i32>,
+    pub buildings_100: Option<i32>,
+    pub roads_100: Option<i32>,
+    pub elevation: Option<i32>,
+}
+
+/// Page through playable locations for pack building, ordered by ID.
+///
+/// Only active, validated, non-rejected locations with a country are
+/// returned. Pass the last ID of the previous page as `after_id`.
+pub async fn list_pack_source_locations(
+    pool: &DbPool,
+    after_id: Option<&str>,
+    limit: i64,
+) -> Result<Vec<PackSourceLocation>, LocationError> {
+    sqlx::query_as::<_, PackSourceLocation>(
+        r#"
+        SELECT id, panorama_id, lat, lng, country_code, subdivision_code, capture_date,
+               is_scout, heading, surface, arrow_count, buildings_100, roads_100, elevation
+        FROM locations
+        WHERE active = TRUE
+          AND validation_status = 'ok'
+          AND (review_status IS NULL OR review_status = 'approved')
+          AND country_code IS NOT NULL
+          AND ($1::text IS NULL OR id > $1)
+        ORDER BY id
+        LIMIT $2
+        "#,
+    )
+    .bind(after_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| LocationError::Database(e.to_string()))
+}
+
 /// Parameters for creating a new location.
 #[derive(Debug, Clone, Default)]
 pub struct CreateLocationParams {
@@ -675,7 +814,7 @@ pub struct CreateLocationParams {
     pub country_code: Option<String>,
     pub subdivision_code: Option<String>,
     pub capture_date: Option<NaiveDate>,
-    pub provider: String,
+    pub provider: ImageryProvider,
     pub source: String,
     pub surface: Option<String>,
     pub arrow_count: Option<i32>,
@@ -685,6 +824,8 @@ pub struct CreateLocationParams {
     pub elevation: Option<i32>,
     pub heading: Option<f64>,
     pub review_status: String,
+    /// Normalized tags (see [`dguesser_core::location::normalize_tags`])
+    pub tags: Vec<String>,
 }
 
 /// Create a new location with full metadata.
@@ -699,9 +840,9 @@ pub async fn create_location_full(
         INSERT INTO locations (
             id, panorama_id, lat, lng, country_code, subdivision_code, capture_date, provider,
             source, surface, arrow_count, is_scout, buildings_100, roads_100, elevation, heading,
-            review_status
+            review_status, tags
         )
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)
         RETURNING {LOCATION_COLUMNS}
         "#
     ))
@@ -712,7 +853,7 @@ pub async fn create_location_full(
     .bind(&params.country_code)
     .bind(&params.subdivision_code)
     .bind(params.capture_date)
-    .bind(&params.provider)
+    .bind(params.provider.as_str())
     .bind(&params.source)
     .bind(&params.surface)
     .bind(params.arrow_count)
@@ -722,6 +863,7 @@ pub async fn create_location_full(
     .bind(params.elevation)
     .bind(params.heading)
     .bind(&params.review_status)
+    .bind(&params.tags)
     .fetch_one(pool)
     .await
     .map_err(|e| LocationError::Database(e.to_string()))?;
@@ -739,7 +881,7 @@ pub async fn create_location(
     country_code: Option<&str>,
     subdivision_code: Option<&str>,
     capture_date: Option<NaiveDate>,
-    provider: &str,
+    provider: ImageryProvider,
 ) -> Result<Location, LocationError> {
     let params = CreateLocationParams {
         panorama_id: panorama_id.to_string(),
@@ -748,8 +890,12 @@ pub async fn create_location(
         country_code: country_code.map(String::from),
         subdivision_code: subdivision_code.map(String::from),
         capture_date,
-        provider: provider.to_string(),
-        source: if provider == "sample" { "sample".to_string() } else { "imported".to_string() },
+        provider,
+        source: if provider == ImageryProvider::Sample {
+            "sample".to_string()
+        } else {
+            "imported".to_string()
+        },
         review_status: "approved".to_string(),
         ..Default::default()
     };
@@ -757,6 +903,98 @@ pub async fn create_location(
     create_location_full(pool, &params).await
 }
 
+/// Outcome of a batch location insert.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LocationBatchResult {
+    /// New locations created (and added to the map)
+    pub inserted: usize,
+    /// Locations skipped because their panorama already exists
+    pub skipped: usize,
+}
+
+/// Create many locations with one multi-row insert and add the new ones to a
+/// map, in a single transaction. Panoramas that already exist are skipped
+/// and left out of the map, as with [`create_location_full`].
+pub async fn create_locations_batch(
+    pool: &DbPool,
+    map_id: &str,
+    locations: &[CreateLocationParams],
+) -> Result<LocationBatchResult, LocationError> {
+    if locations.is_empty() {
+        return Ok(LocationBatchResult::default());
+    }
+
+    let ids: Vec<String> =
+        locations.iter().map(|_| dguesser_core::generate_location_id()).collect();
+
+    let mut tx = pool.begin().await.map_err(|e| LocationError::Database(e.to_string()))?;
+
+    let inserted: Vec<String> = sqlx::query_scalar(
+        r#"
+        INSERT INTO locations (
+            id, panorama_id, lat, lng, country_code, subdivision_code, capture_date, provider,
+            source, surface, arrow_count, is_scout, buildings_100, roads_100, elevation, heading,
+            review_status, tags
+        )
+        -- Tag lists are passed as JSON since Postgres arrays can't be ragged
+        SELECT u.id, u.panorama_id, u.lat, u.lng, u.country_code, u.subdivision_code,
+               u.capture_date, u.provider, u.source, u.surface, u.arrow_count, u.is_scout,
+               u.buildings_100, u.roads_100, u.elevation, u.heading, u.review_status,
+               ARRAY(SELECT jsonb_array_elements_text(u.tags))
+        FROM UNNEST(
+            $1::varchar[], $2::varchar[], $3::float8[], $4::float8[], $5::varchar[],
+            $6::varchar[], $7::date[], $8::varchar[], $9::varchar[], $10::varchar[],
+            $11::int4[], $12::bool[], $13::int4[], $14::int4[], $15::int4[], $16::float8[],
+            $17::varchar[], $18::jsonb[]
+        ) AS u(
+            id, panorama_id, lat, lng, country_code, subdivision_code, capture_date, provider,
+            source, surface, arrow_count, is_scout, buildings_100, roads_100, elevation, heading,
+            review_status, tags
+        )
+        ON CONFLICT (panorama_id) DO NOTHING
+        RETURNING id
+        "#,
+    )
+    .bind(&ids)
+    .bind(locations.iter().map(|l| l.panorama_id.clone()).collect::<Vec<_>>())
+    .bind(locations.iter().map(|l| l.lat).collect::<Vec<_>>())
+    .bind(locations.iter().map(|l| l.lng).collect::<Vec<_>>())
+    .bind(locations.iter().map(|l| l.country_code.clone()).collect::<Vec<_>>())
+    .bind(locations.iter().map(|l| l.subdivision_code.clone()).collect::<Vec<_>>())
+    .bind(locations.iter().map(|l| l.capture_date).collect::<Vec<_>>())
+    .bind(locations.iter().map(|l| l.provider.as_str()).collect::<Vec<_>>())
+    .bind(locations.iter().map(|l| l.source.clone()).collect::<Vec<_>>())
+    .bind(locations.iter().map(|l| l.surface.clone()).collect::<Vec<_>>())
+    .bind(locations.iter().map(|l| l.arrow_count).collect::<Vec<_>>())
+    .bind(locations.iter().map(|l| l.is_scout).collect::<Vec<_>>())
+    .bind(locations.iter().map(|l| l.buildings_100).collect::<Vec<_>>())
+    .bind(locations.iter().map(|l| l.roads_100).collect::<Vec<_>>())
+    .bind(locations.iter().map(|l| l.elevation).collect::<Vec<_>>())
+    .bind(locations.iter().map(|l| l.heading).collect::<Vec<_>>())
+    .bind(locations.iter().map(|l| l.review_status.clone()).collect::<Vec<_>>())
+    .bind(locations.iter().map(|l| sqlx::types::Json(&l.tags)).collect::<Vec<_>>())
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| LocationError::Database(e.to_string()))?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO map_locations (map_id, location_id)
+        SELECT $1, unnest($2::varchar[])
+        ON CONFLICT (map_id, location_id) DO NOTHING
+        "#,
+    )
+    .bind(map_id)
+    .bind(&inserted)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| LocationError::Database(e.to_string()))?;
+
+    tx.commit().await.map_err(|e| LocationError::Database(e.to_string()))?;
+
+    Ok(LocationBatchResult { inserted: inserted.len(), skipped: locations.len() - inserted.len() })
+}
+
 /// Add a location to a map.
 pub async fn add_location_to_map(
     pool: &DbPool,
@@ -1106,6 +1344,102 @@ pub async fn get_location_stats(pool: &DbPool) -> Result<LocationStats, Location
     })
 }
 
+/// Coarsest and finest geohash precision accepted for heatmaps.
+pub const HEATMAP_PRECISION_RANGE: std::ops::RangeInclusive<i32> = 1..=6;
+
+/// One heatmap bin: a geohash cell and the number of points inside it.
+#[derive(Debug, Clone, FromRow, serde::Serialize, serde::Deserialize)]
+pub struct HeatmapCell {
+    /// Geohash of the cell
+    pub geohash: String,
+    /// Latitude of the cell center
+    pub lat: f64,
+    /// Longitude of the cell center
+    pub lng: f64,
+    /// Points in the cell
+    pub count: i64,
+}
+
+/// Bin a map's playable locations into geohash cells.
+///
+/// Applies the same map rules as location selection, so the result shows the
+/// coverage a game can actually draw from.
+pub async fn get_map_location_heatmap(
+    pool: &DbPool,
+    map: &Map,
+    precision: i32,
+) -> Result<Vec<HeatmapCell>, LocationError> {
+    let precision =
+        precision.clamp(*HEATMAP_PRECISION_RANGE.start(), *HEATMAP_PRECISION_RANGE.end());
+    let filter_clause = build_location_filter_clause(&map.rules);
+    let source = CandidateSource::for_rules(&map.rules);
+
+    let query = format!(
+        r#"
+        SELECT geohash,
+               ST_Y(ST_PointFromGeoHash(geohash)) AS lat,
+               ST_X(ST_PointFromGeoHash(geohash)) AS lng,
+               count
+        FROM (
+            SELECT ST_GeoHash(l.geom, $2) AS geohash, COUNT(*) AS count
+            FROM {from}
+            WHERE l.active = TRUE
+              {filter_clause}
+            GROUP BY 1
+        ) cells
+        ORDER BY count DESC
+        "#,
+        from = source.from,
+    );
+
+    sqlx::query_as::<_, HeatmapCell>(&query)
+        .bind(&map.id)
+        .bind(precision)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| LocationError::Database(e.to_string()))
+}
+
+/// Bin where players guessed in games played on a map into geohash cells.
+///
+/// Rounds that ran out of time without a guess are not counted.
+pub async fn get_map_guess_heatmap(
+    pool: &DbPool,
+    map: &Map,
+    precision: i32,
+) -> Result<Vec<HeatmapCell>, LocationError> {
+    let precision =
+        precision.clamp(*HEATMAP_PRECISION_RANGE.start(), *HEATMAP_PRECISION_RANGE.end());
+
+    // Games store whichever map reference they were created with: ID or slug
+    sqlx::query_as::<_, HeatmapCell>(
+        r#"
+        SELECT geohash,
+               ST_Y(ST_PointFromGeoHash(geohash)) AS lat,
+               ST_X(ST_PointFromGeoHash(geohash)) AS lng,
+               count
+        FROM (
+            SELECT ST_GeoHash(ST_SetSRID(ST_MakePoint(gs.guess_lng, gs.guess_lat), 4326), $3)
+                       AS geohash,
+                   COUNT(*) AS count
+            FROM guesses gs
+            JOIN rounds r ON r.id = gs.round_id
+            JOIN games g ON g.id = r.game_id
+            WHERE g.settings->>'map_id' IN ($1, $2)
+              AND gs.distance_meters >= 0
+            GROUP BY 1
+        ) cells
+        ORDER BY count DESC
+        "#,
+    )
+    .bind(&map.id)
+    .bind(&map.slug)
+    .bind(precision)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| LocationError::Database(e.to_string()))
+}
+
 // =============================================================================
 // Admin Operations
 // =============================================================================
@@ -1159,46 +1493,79 @@ pub async fn get_reports_for_location(
     Ok(rows)
 }
 
+/// Which review claims to show in the review queue.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum QueueAssignment<'a> {
+    /// Every queued location
+    #[default]
+    Any,
+    /// Only locations nobody has an active claim on
+    Unclaimed,
+    /// Only locations this reviewer has an active claim on
+    ClaimedBy(&'a str),
+}
+
+/// Append the WHERE clause for review queue filters to a query over
+/// `locations`, binding every filter value.
+fn push_review_queue_filters<'a>(
+    builder: &mut QueryBuilder<'a, Postgres>,
+    status_filter: Option<&str>,
+    assignment: QueueAssignment<'a>,
+) {
+    builder.push(match status_filter {
+        Some("pending") => " WHERE review_status = 'pending'",
+        Some("flagged") => " WHERE review_status = 'flagged'",
+        _ => " WHERE (review_status IN ('pending', 'flagged') OR failure_count >= 2)",
+    });
+
+    const ACTIVE_CLAIM: &str = "SELECT 1 FROM location_review_claims c \
+                                WHERE c.location_id = locations.id AND c.expires_at > NOW()";
+    match assignment {
+        QueueAssignment::Any => {}
+        QueueAssignment::Unclaimed => {
+            builder.push(format_args!(" AND NOT EXISTS ({ACTIVE_CLAIM})"));
+        }
+        QueueAssignment::ClaimedBy(reviewer_id) => {
+            builder
+                .push(format_args!(" AND EXISTS ({ACTIVE_CLAIM} AND c.reviewer_id = "))
+                .push_bind(reviewer_id)
+                .push(")");
+        }
+    }
+}
+
 /// Get paginated review queue.
 pub async fn get_review_queue_paginated(
     pool: &DbPool,
     page: i64,
     per_page: i64,
     status_filter: Option<&str>,
+    assignment: QueueAssignment<'_>,
 ) -> Result<(Vec<Location>, i64), LocationError> {
     let offset = (page - 1) * per_page;
 
-    // Build the WHERE clause based on status filter
-    let status_clause = match status_filter {
-        Some("pending") => "review_status = 'pending'",
-        Some("flagged") => "review_status = 'flagged'",
-        _ => "(review_status IN ('pending', 'flagged') OR failure_count >= 2)",
-    };
-
     // Get total count
-    let count_query = format!("SELECT COUNT(*)::bigint FROM locations WHERE {}", status_clause);
-    let total: i64 = sqlx::query_scalar::<_, i64>(&count_query)
+    let mut count_query = QueryBuilder::new("SELECT COUNT(*)::bigint FROM locations");
+    push_review_queue_filters(&mut count_query, status_filter, assignment);
+    let total: i64 = count_query
+        .build_query_scalar()
         .fetch_one(pool)
         .await
         .map_err(|e| LocationError::Database(e.to_string()))?;
 
     // Get paginated results
-    let rows = sqlx::query_as::<_, LocationRow>(&format!(
-        r#"
-        SELECT {LOCATION_COLUMNS}
-        FROM locations
-        WHERE {status_clause}
-        ORDER BY failure_count DESC, created_at ASC
-        LIMIT $1 OFFSET $2
-        "#,
-        LOCATION_COLUMNS = LOCATION_COLUMNS,
-        status_clause = status_clause
-    ))
-    .bind(per_page)
-    .bind(offset)
-    .fetch_all(pool)
-    .await
-    .map_err(|e| LocationError::Database(e.to_string()))?;
+    let mut data_query = QueryBuilder::new(format!("SELECT {LOCATION_COLUMNS} FROM locations"));
+    push_review_queue_filters(&mut data_query, status_filter, assignment);
+    data_query
+        .push(" ORDER BY failure_count DESC, created_at ASC LIMIT ")
+        .push_bind(per_page)
+        .push(" OFFSET ")
+        .push_bind(offset);
+    let rows = data_query
+        .build_query_as::<LocationRow>()
+        .fetch_all(pool)
+        .await
+        .map_err(|e| LocationError::Database(e.to_string()))?;
 
     let locations: Result<Vec<Location>, _> = rows.into_iter().map(|r| r.try_into()).collect();
     Ok((locations?, total))
@@ -1221,6 +1588,24 @@ pub async fn get_report_count_for_location(
     Ok(count)
 }
 
+/// Append the WHERE clause for report filters to a query over
+/// `location_reports r JOIN locations l`, binding every filter value.
+fn push_report_filters<'a>(
+    builder: &mut QueryBuilder<'a, Postgres>,
+    reason_filter: Option<&'a str>,
+    location_status_filter: Option<&'a str>,
+) {
+    builder.push(" WHERE TRUE");
+
+    if let Some(reason) = reason_filter {
+        builder.push(" AND r.reason = ").push_bind(reason);
+    }
+
+    if let Some(status) = location_status_filter {
+        builder.push(" AND COALESCE(l.review_status, 'approved') = ").push_bind(status);
+    }
+}
+
 /// Get paginated reports with location info.
 pub async fn get_reports_paginated(
     pool: &DbPool,
@@ -1231,26 +1616,19 @@ pub async fn get_reports_paginated(
 ) -> Result<(Vec<LocationReportWithLocationRow>, i64), LocationError> {
     let offset = (page - 1) * per_page;
 
-    // Get total count - uses parameterized queries to prevent SQL injection
-    let total: i64 = sqlx::query_scalar(
-        r#"
-        SELECT COUNT(*)::bigint
-        FROM location_reports r
-        JOIN locations l ON r.location_id = l.id
-        WHERE ($1::text IS NULL OR r.reason = $1)
-          AND ($2::text IS NULL OR COALESCE(l.review_status, 'approved') = $2)
-        "#,
-    )
-    .bind(reason_filter)
-    .bind(location_status_filter)
-    .fetch_one(pool)
-    .await
-    .map_err(|e| LocationError::Database(e.to_string()))?;
+    let mut count_query = QueryBuilder::new(
+        "SELECT COUNT(*)::bigint FROM location_reports r JOIN locations l ON r.location_id = l.id",
+    );
+    push_report_filters(&mut count_query, reason_filter, location_status_filter);
+    let total: i64 = count_query
+        .build_query_scalar()
+        .fetch_one(pool)
+        .await
+        .map_err(|e| LocationError::Database(e.to_string()))?;
 
-    // Get paginated results - uses parameterized queries
-    let rows = sqlx::query_as::<_, LocationReportWithLocationRow>(
+    let mut data_query = QueryBuilder::new(
         r#"
-        SELECT 
+        SELECT
             r.id,
             r.location_id,
             l.panorama_id,
@@ -1264,19 +1642,20 @@ pub async fn get_reports_paginated(
             l.review_status as location_review_status
         FROM location_reports r
         JOIN locations l ON r.location_id = l.id
-        WHERE ($1::text IS NULL OR r.reason = $1)
-          AND ($2::text IS NULL OR COALESCE(l.review_status, 'approved') = $2)
-        ORDER BY r.created_at DESC
-        LIMIT $3 OFFSET $4
         "#,
-    )
-    .bind(reason_filter)
-    .bind(location_status_filter)
-    .bind(per_page)
-    .bind(offset)
-    .fetch_all(pool)
-    .await
-    .map_err(|e| LocationError::Database(e.to_string()))?;
+    );
+    push_report_filters(&mut data_query, reason_filter, location_status_filter);
+    data_query
+        .push(" ORDER BY r.created_at DESC LIMIT ")
+        .push_bind(per_page)
+        .push(" OFFSET ")
+        .push_bind(offset);
+
+    let rows = data_query
+        .build_query_as::<LocationReportWithLocationRow>()
+        .fetch_all(pool)
+        .await
+        .map_err(|e| LocationError::Database(e.to_string()))?;
 
     Ok((rows, total))
 }
@@ -1352,67 +1731,295 @@ pub async fn list_maps(pool: &DbPool) -> Result<Vec<Map>, LocationError> {
     rows.into_iter().map(|r| r.try_into()).collect()
 }
 
-// =============================================================================
-// User-Created Maps
-// =============================================================================
+/// List every system map, inactive ones included.
+pub async fn list_system_maps(pool: &DbPool) -> Result<Vec<Map>, LocationError> {
+    let rows = sqlx::query_as::<_, MapRow>(&format!(
+        r#"
+        SELECT {MAP_COLUMNS}
+        FROM maps
+        WHERE creator_id IS NULL
+        ORDER BY is_default DESC, active DESC, name ASC
+        "#
+    ))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| LocationError::Database(e.to_string()))?;
 
-/// Parameters for creating a user map.
-#[derive(Debug, Clone)]
-pub struct CreateUserMapParams {
-    pub slug: String,
-    pub name: String,
-    pub description: Option<String>,
-    pub visibility: MapVisibility,
+    rows.into_iter().map(|r| r.try_into()).collect()
 }
 
-/// Create a new user-owned map.
-pub async fn create_user_map(
+/// Get a system map by ID, active or not.
+pub async fn get_system_map(pool: &DbPool, map_id: &str) -> Result<Option<Map>, LocationError> {
+    let row = sqlx::query_as::<_, MapRow>(&format!(
+        r#"
+        SELECT {MAP_COLUMNS}
+        FROM maps
+        WHERE id = $1 AND creator_id IS NULL
+        "#
+    ))
+    .bind(map_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| LocationError::Database(e.to_string()))?;
+
+    row.map(|r| r.try_into()).transpose()
+}
+
+/// Edit a system map. `None` leaves a field unchanged; an empty description
+/// removes it. Returns `None` if the map does not exist.
+pub async fn update_system_map(
     pool: &DbPool,
-    creator_id: &str,
-    params: &CreateUserMapParams,
-) -> Result<Map, LocationError> {
-    let id = dguesser_core::generate_map_id();
-    let rules_json = serde_json::to_value(MapRules::default())
+    map_id: &str,
+    name: Option<&str>,
+    description: Option<&str>,
+    rules: Option<&MapRules>,
+) -> Result<Option<Map>, LocationError> {
+    let rules_json = rules
+        .map(serde_json::to_value)
+        .transpose()
         .map_err(|e| LocationError::Database(e.to_string()))?;
 
     let row = sqlx::query_as::<_, MapRow>(&format!(
         r#"
-        INSERT INTO maps (id, slug, name, description, rules, creator_id, visibility)
-        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        UPDATE maps
+        SET name = COALESCE($2, name),
+            description = CASE WHEN $3::text IS NULL THEN description ELSE NULLIF($3, '') END,
+            rules = COALESCE($4, rules),
+            updated_at = NOW()
+        WHERE id = $1 AND creator_id IS NULL
         RETURNING {MAP_COLUMNS}
         "#
     ))
-    .bind(&id)
-    .bind(&params.slug)
-    .bind(&params.name)
-    .bind(&params.description)
-    .bind(&rules_json)
-    .bind(creator_id)
-    .bind(params.visibility.to_string())
-    .fetch_one(pool)
+    .bind(map_id)
+    .bind(name)
+    .bind(description)
+    .bind(rules_json)
+    .fetch_optional(pool)
     .await
     .map_err(|e| LocationError::Database(e.to_string()))?;
 
-    row.try_into()
+    row.map(|r| r.try_into()).transpose()
 }
 
-/// List maps visible to a user (public maps + their own maps).
-pub async fn list_visible_maps(
-    pool: &DbPool,
-    user_id: Option<&str>,
-) -> Result<Vec<Map>, LocationError> {
-    let rows = match user_id {
-        Some(uid) => {
-            sqlx::query_as::<_, MapRow>(&format!(
-                r#"
-                SELECT {MAP_COLUMNS}
-                FROM maps
-                WHERE active = TRUE
-                  AND (visibility = 'public' OR creator_id = $1)
-                ORDER BY is_default DESC, created_at DESC
-                "#
-            ))
+/// Make an active system map the default, clearing the flag on every other
+/// map. Returns `None` if there is no such map.
+pub async fn set_default_map(pool: &DbPool, map_id: &str) -> Result<Option<Map>, LocationError> {
+    let mut tx = pool.begin().await.map_err(|e| LocationError::Database(e.to_string()))?;
+
+    let row = sqlx::query_as::<_, MapRow>(&format!(
+        r#"
+        UPDATE maps
+        SET is_default = TRUE, updated_at = NOW()
+        WHERE id = $1 AND creator_id IS NULL AND active = TRUE
+        RETURNING {MAP_COLUMNS}
+        "#
+    ))
+    .bind(map_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| LocationError::Database(e.to_string()))?;
+
+    let Some(row) = row else {
+        return Ok(None);
+    };
+
+    sqlx::query(
+        r#"
+        UPDATE maps
+        SET is_default = FALSE, updated_at = NOW()
+        WHERE is_default = TRUE AND id <> $1
+        "#,
+    )
+    .bind(map_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| LocationError::Database(e.to_string()))?;
+
+    tx.commit().await.map_err(|e| LocationError::Database(e.to_string()))?;
+
+    row.try_into().map(Some)
+}
+
+/// Activate or deactivate a system map. Returns `None` if the map does not
+/// exist.
+pub async fn set_system_map_active(
+    pool: &DbPool,
+    map_id: &str,
+    active: bool,
+) -> Result<Option<Map>, LocationError> {
+    let row = sqlx::query_as::<_, MapRow>(&format!(
+        r#"
+        UPDATE maps
+        SET active = $2, updated_at = NOW()
+        WHERE id = $1 AND creator_id IS NULL
+        RETURNING {MAP_COLUMNS}
+        "#
+    ))
+    .bind(map_id)
+    .bind(active)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| LocationError::Database(e.to_string()))?;
+
+    row.map(|r| r.try_into()).transpose()
+}
+
+// =============================================================================
+// User-Created Maps
+// =============================================================================
+
+/// Parameters for creating a user map.
+#[derive(Debug, Clone)]
+pub struct CreateUserMapParams {
+    pub slug: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub visibility: MapVisibility,
+    pub region: Option<MapRegion>,
+    /// Restrict the map to one imagery provider
+    pub provider: Option<ImageryProvider>,
+    /// Custom scoring curve
+    pub scoring: Option<ScoringConfig>,
+    /// Restrict the map to one difficulty band
+    pub difficulty: Option<DifficultyBand>,
+    /// Organization the map is created in
+    pub org_id: Option<String>,
+}
+
+/// SQL converting a GeoJSON polygon parameter into the `maps.region` column
+/// type. Invalid rings (self-intersections) are repaired rather than rejected.
+pub(crate) fn region_geometry_sql(param: &str) -> String {
+    format!(
+        "ST_Multi(ST_CollectionExtract(\
+         ST_MakeValid(ST_SetSRID(ST_GeomFromGeoJSON(({param})::text), 4326)), 3))"
+    )
+}
+
+/// Recompute the denormalized location count of a map from its candidate
+/// source. Needed whenever a map's region changes.
+pub(crate) async fn refresh_location_count(pool: &DbPool, map: &Map) -> Result<Map, LocationError> {
+    let source = CandidateSource::for_rules(&map.rules);
+    let row = sqlx::query_as::<_, MapRow>(&format!(
+        r#"
+        UPDATE maps
+        SET location_count = (SELECT COUNT(*) FROM {from} WHERE l.active = TRUE)
+        WHERE id = $1
+        RETURNING {MAP_COLUMNS}
+        "#,
+        from = source.from,
+    ))
+    .bind(&map.id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| LocationError::Database(e.to_string()))?;
+
+    row.try_into()
+}
+
+/// Create a new user-owned map.
+pub async fn create_user_map(
+    pool: &DbPool,
+    creator_id: &str,
+    params: &CreateUserMapParams,
+) -> Result<Map, LocationError> {
+    let id = dguesser_core::generate_map_id();
+    let rules = MapRules {
+        region: params.region.clone(),
+        provider: params.provider,
+        scoring: params.scoring.clone(),
+        difficulty: params.difficulty,
+        ..Default::default()
+    };
+    let rules_json =
+        serde_json::to_value(&rules).map_err(|e| LocationError::Database(e.to_string()))?;
+    let region = region_geometry_sql("$5::jsonb->'region'");
+
+    let row = sqlx::query_as::<_, MapRow>(&format!(
+        r#"
+        INSERT INTO maps
+            (id, slug, name, description, rules, creator_id, visibility, region, org_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, {region}, $8)
+        RETURNING {MAP_COLUMNS}
+        "#
+    ))
+    .bind(&id)
+    .bind(&params.slug)
+    .bind(&params.name)
+    .bind(&params.description)
+    .bind(&rules_json)
+    .bind(creator_id)
+    .bind(params.visibility.to_string())
+    .bind(&params.org_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| LocationError::Database(e.to_string()))?;
+
+    let map: Map = row.try_into()?;
+    if map.rules.region.is_some() { refresh_location_count(pool, &map).await } else { Ok(map) }
+}
+
+/// Sort order for map listings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MapSort {
+    /// Default map first, then newest first
+    #[default]
+    Newest,
+    /// Most played first, ties broken by likes
+    Popular,
+    /// Most liked first
+    MostLiked,
+}
+
+impl MapSort {
+    fn order_clause(self) -> &'static str {
+        match self {
+            Self::Newest => "is_default DESC, created_at DESC",
+            Self::Popular => "play_count DESC, like_count DESC, created_at DESC",
+            Self::MostLiked => "like_count DESC, play_count DESC, created_at DESC",
+        }
+    }
+}
+
+/// SQL condition on a map's `org_id` matching [`Map::is_available_in`] for
+/// the organization in parameter `param`
+fn org_filter(param: &str) -> String {
+    format!(
+        "(org_id = {param} OR (org_id IS NULL AND ({param}::text IS NULL OR creator_id IS NULL)))"
+    )
+}
+
+/// List maps visible to a user (public maps, their own maps, and maps they
+/// edit) in an organization, or the public instance.
+pub async fn list_visible_maps(
+    pool: &DbPool,
+    user_id: Option<&str>,
+    sort: MapSort,
+    org_id: Option<&str>,
+) -> Result<Vec<Map>, LocationError> {
+    let order = sort.order_clause();
+    let rows = match user_id {
+        Some(uid) => {
+            sqlx::query_as::<_, MapRow>(&format!(
+                r#"
+                SELECT {MAP_COLUMNS}
+                FROM maps
+                WHERE active = TRUE
+                  AND (
+                    visibility = 'public'
+                    OR creator_id = $1
+                    OR id IN (
+                        SELECT map_id FROM map_collaborators
+                        WHERE user_id = $1 AND accepted_at IS NOT NULL
+                    )
+                  )
+                  AND {org}
+                ORDER BY {order}
+                "#,
+                org = org_filter("$2"),
+            ))
             .bind(uid)
+            .bind(org_id)
             .fetch_all(pool)
             .await
         }
@@ -1421,10 +2028,12 @@ pub async fn list_visible_maps(
                 r#"
                 SELECT {MAP_COLUMNS}
                 FROM maps
-                WHERE active = TRUE AND visibility = 'public'
-                ORDER BY is_default DESC, created_at DESC
-                "#
+                WHERE active = TRUE AND visibility = 'public' AND {org}
+                ORDER BY {order}
+                "#,
+                org = org_filter("$1"),
             ))
+            .bind(org_id)
             .fetch_all(pool)
             .await
         }
@@ -1452,11 +2061,14 @@ pub async fn list_user_maps(pool: &DbPool, user_id: &str) -> Result<Vec<Map>, Lo
     rows.into_iter().map(|r| r.try_into()).collect()
 }
 
-/// Get a map by ID if visible to the user.
+/// Get a map by ID if visible to the user in an organization (or the public
+/// instance). Private maps are visible to their collaborators, including
+/// users with a pending invitation.
 pub async fn get_map_if_visible(
     pool: &DbPool,
     map_id: &str,
     user_id: Option<&str>,
+    org_id: Option<&str>,
 ) -> Result<Option<Map>, LocationError> {
     let row = sqlx::query_as::<_, MapRow>(&format!(
         r#"
@@ -1470,12 +2082,21 @@ pub async fn get_map_if_visible(
     .await
     .map_err(|e| LocationError::Database(e.to_string()))?;
 
-    match row {
-        Some(r) => {
-            let map: Map = r.try_into()?;
-            if map.is_visible_to(user_id) { Ok(Some(map)) } else { Ok(None) }
-        }
-        None => Ok(None),
+    let Some(row) = row else {
+        return Ok(None);
+    };
+    let map: Map = row.try_into()?;
+    if !map.is_available_in(org_id) {
+        return Ok(None);
+    }
+    if map.is_visible_to(user_id) {
+        return Ok(Some(map));
+    }
+
+    // Invited users can see private maps, so they can look before accepting
+    match user_id {
+        Some(uid) if get_map_collaborator(pool, &map.id, uid).await?.is_some() => Ok(Some(map)),
+        _ => Ok(None),
     }
 }
 
@@ -1485,6 +2106,9 @@ pub struct UpdateMapParams {
     pub name: Option<String>,
     pub description: Option<Option<String>>, // None = don't change, Some(None) = clear, Some(Some(x)) = set
     pub visibility: Option<MapVisibility>,
+    pub region: Option<Option<MapRegion>>, // None = don't change, Some(None) = clear, Some(Some(x)) = set
+    pub scoring: Option<Option<ScoringConfig>>, // None = don't change, Some(None) = clear, Some(Some(x)) = set
+    pub difficulty: Option<Option<DifficultyBand>>, // None = don't change, Some(None) = clear, Some(Some(x)) = set
 }
 
 /// Update a map's metadata (owner only).
@@ -1507,6 +2131,36 @@ pub async fn update_map(
     }
     if params.visibility.is_some() {
         updates.push(format!("visibility = ${bind_idx}"));
+        bind_idx += 1;
+    }
+    // Rules can only be assigned once, so changes to them are nested
+    let mut rules = "rules".to_string();
+    if params.region.is_some() {
+        // A NULL parameter clears the region
+        rules = format!(
+            "CASE WHEN ${bind_idx}::jsonb IS NULL THEN {rules} - 'region' \
+             ELSE jsonb_set({rules}, '{{region}}', ${bind_idx}::jsonb) END"
+        );
+        updates.push(format!("region = {}", region_geometry_sql(&format!("${bind_idx}::jsonb"))));
+        bind_idx += 1;
+    }
+    if params.scoring.is_some() {
+        // A NULL parameter restores the default curve
+        rules = format!(
+            "CASE WHEN ${bind_idx}::jsonb IS NULL THEN {rules} - 'scoring' \
+             ELSE jsonb_set({rules}, '{{scoring}}', ${bind_idx}::jsonb) END"
+        );
+        bind_idx += 1;
+    }
+    if params.difficulty.is_some() {
+        // A NULL parameter allows every difficulty
+        rules = format!(
+            "CASE WHEN ${bind_idx}::jsonb IS NULL THEN {rules} - 'difficulty' \
+             ELSE jsonb_set({rules}, '{{difficulty}}', ${bind_idx}::jsonb) END"
+        );
+    }
+    if rules != "rules" {
+        updates.push(format!("rules = {rules}"));
     }
 
     if updates.is_empty() {
@@ -1535,6 +2189,25 @@ pub async fn update_map(
     if let Some(ref vis) = params.visibility {
         q = q.bind(vis.to_string());
     }
+    if let Some(ref region) = params.region {
+        let region_json = region
+            .as_ref()
+            .map(serde_json::to_value)
+            .transpose()
+            .map_err(|e| LocationError::Database(e.to_string()))?;
+        q = q.bind(region_json);
+    }
+    if let Some(ref scoring) = params.scoring {
+        let scoring_json = scoring
+            .as_ref()
+            .map(serde_json::to_value)
+            .transpose()
+            .map_err(|e| LocationError::Database(e.to_string()))?;
+        q = q.bind(scoring_json);
+    }
+    if let Some(ref difficulty) = params.difficulty {
+        q = q.bind(difficulty.map(|band| serde_json::Value::from(band.as_str())));
+    }
 
     let row = q
         .fetch_optional(pool)
@@ -1542,7 +2215,8 @@ pub async fn update_map(
         .map_err(|e| LocationError::Database(e.to_string()))?
         .ok_or_else(|| LocationError::MapNotFound(map_id.to_string()))?;
 
-    row.try_into()
+    let map: Map = row.try_into()?;
+    if params.region.is_some() { refresh_location_count(pool, &map).await } else { Ok(map) }
 }
 
 /// Soft-delete a map (set active = false).
@@ -1610,13 +2284,30 @@ pub async fn remove_location_from_map(
     Ok(result.rows_affected() > 0)
 }
 
+/// Check whether a location is in a map.
+pub async fn is_location_in_map(
+    pool: &DbPool,
+    map_id: &str,
+    location_id: &str,
+) -> Result<bool, LocationError> {
+    sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM map_locations WHERE map_id = $1 AND location_id = $2)",
+    )
+    .bind(map_id)
+    .bind(location_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| LocationError::Database(e.to_string()))
+}
+
 /// All columns to select for a full Location row, qualified with table alias 'l'.
 /// Use this when JOINing with other tables that have overlapping column names.
 const LOCATION_COLUMNS_ALIASED: &str = r#"
     l.id, l.panorama_id, l.lat, l.lng, l.country_code, l.subdivision_code, l.capture_date, l.provider,
     l.active, l.last_validated_at, l.validation_status, l.created_at,
     l.source, l.surface, l.arrow_count, l.is_scout, l.buildings_100, l.roads_100, l.elevation, l.heading,
-    l.failure_count, l.last_failure_reason, l.review_status, l.reviewed_at, l.reviewed_by
+    l.failure_count, l.last_failure_reason, l.review_status, l.reviewed_at, l.reviewed_by,
+    l.difficulty, l.median_guess_distance_meters, l.median_guess_score
 "#;
 
 /// Get paginated locations for a map.
@@ -1646,6 +2337,37 @@ pub async fn get_map_locations(
     rows.into_iter().map(|r| r.try_into()).collect()
 }
 
+/// Get a page of a map's active locations ordered by ID, for walking every
+/// location with keyset pagination. Region maps return the locations inside
+/// their region.
+pub async fn get_map_locations_after(
+    pool: &DbPool,
+    map: &Map,
+    after_id: Option<&str>,
+    limit: i64,
+) -> Result<Vec<Location>, LocationError> {
+    let source = CandidateSource::for_rules(&map.rules);
+    let rows = sqlx::query_as::<_, LocationRow>(&format!(
+        r#"
+        SELECT {LOCATION_COLUMNS_ALIASED}
+        FROM {from}
+        WHERE l.active = TRUE
+          AND ($2::text IS NULL OR l.id > $2)
+        ORDER BY l.id
+        LIMIT $3
+        "#,
+        from = source.from,
+    ))
+    .bind(&map.id)
+    .bind(after_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| LocationError::Database(e.to_string()))?;
+
+    rows.into_iter().map(|r| r.try_into()).collect()
+}
+
 /// Check if a map slug is available.
 pub async fn is_map_slug_available(pool: &DbPool, slug: &str) -> Result<bool, LocationError> {
     let exists = sqlx::query_scalar!(
@@ -1676,6 +2398,372 @@ pub async fn get_user_map_count(pool: &DbPool, user_id: &str) -> Result<i64, Loc
     Ok(count.unwrap_or(0))
 }
 
+// =============================================================================
+// Map Likes and Plays
+// =============================================================================
+
+/// Like a map. Returns the map's new like count, or `None` if the map does
+/// not exist. Liking an already liked map is a no-op.
+pub async fn like_map(
+    pool: &DbPool,
+    user_id: &str,
+    map_id: &str,
+) -> Result<Option<i32>, LocationError> {
+    sqlx::query_scalar::<_, i32>(
+        r#"
+        WITH inserted AS (
+            INSERT INTO map_likes (user_id, map_id)
+            SELECT $1, id FROM maps WHERE id = $2 AND active = TRUE
+            ON CONFLICT DO NOTHING
+            RETURNING map_id
+        )
+        UPDATE maps
+        SET like_count = like_count + (SELECT COUNT(*) FROM inserted)
+        WHERE id = $2 AND active = TRUE
+        RETURNING like_count
+        "#,
+    )
+    .bind(user_id)
+    .bind(map_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| LocationError::Database(e.to_string()))
+}
+
+/// Remove a like from a map. Returns the map's new like count, or `None` if
+/// the map does not exist. Unliking a map that was not liked is a no-op.
+pub async fn unlike_map(
+    pool: &DbPool,
+    user_id: &str,
+    map_id: &str,
+) -> Result<Option<i32>, LocationError> {
+    sqlx::query_scalar::<_, i32>(
+        r#"
+        WITH deleted AS (
+            DELETE FROM map_likes
+            WHERE user_id = $1 AND map_id = $2
+            RETURNING map_id
+        )
+        UPDATE maps
+        SET like_count = GREATEST(like_count - (SELECT COUNT(*) FROM deleted), 0)
+        WHERE id = $2 AND active = TRUE
+        RETURNING like_count
+        "#,
+    )
+    .bind(user_id)
+    .bind(map_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| LocationError::Database(e.to_string()))
+}
+
+/// Get the IDs of all maps a user has liked.
+pub async fn get_liked_map_ids(
+    pool: &DbPool,
+    user_id: &str,
+) -> Result<HashSet<String>, LocationError> {
+    let ids = sqlx::query_scalar!("SELECT map_id FROM map_likes WHERE user_id = $1", user_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| LocationError::Database(e.to_string()))?;
+
+    Ok(ids.into_iter().collect())
+}
+
+/// Check whether a user has liked a map.
+pub async fn has_liked_map(
+    pool: &DbPool,
+    user_id: &str,
+    map_id: &str,
+) -> Result<bool, LocationError> {
+    let liked = sqlx::query_scalar!(
+        "SELECT EXISTS(SELECT 1 FROM map_likes WHERE user_id = $1 AND map_id = $2)",
+        user_id,
+        map_id
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| LocationError::Database(e.to_string()))?;
+
+    Ok(liked.unwrap_or(false))
+}
+
+/// List the maps a user has liked that they can still see, most recently
+/// liked first.
+pub async fn list_liked_maps(pool: &DbPool, user_id: &str) -> Result<Vec<Map>, LocationError> {
+    let rows = sqlx::query_as::<_, MapRow>(&format!(
+        r#"
+        SELECT {MAP_COLUMNS}
+        FROM (
+            SELECT m.*, ml.created_at AS liked_at
+            FROM maps m
+            JOIN map_likes ml ON ml.map_id = m.id AND ml.user_id = $1
+        ) liked
+        WHERE active = TRUE
+          AND (visibility IN ('public', 'unlisted') OR creator_id = $1)
+        ORDER BY liked_at DESC
+        "#
+    ))
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| LocationError::Database(e.to_string()))?;
+
+    rows.into_iter().map(|r| r.try_into()).collect()
+}
+
+/// Record finished plays of a map, one per player final score.
+///
+/// Games store the map reference they were created with, which may be an ID
+/// or a slug.
+pub async fn record_map_plays(
+    pool: &DbPool,
+    map_id_or_slug: &str,
+    scores: &[i32],
+) -> Result<(), LocationError> {
+    if scores.is_empty() {
+        return Ok(());
+    }
+    let plays = scores.len() as i32;
+    let score_sum: i64 = scores.iter().map(|&s| s as i64).sum();
+
+    sqlx::query!(
+        r#"
+        UPDATE maps
+        SET play_count = play_count + $2, score_sum = score_sum + $3
+        WHERE id = $1 OR slug = $1
+        "#,
+        map_id_or_slug,
+        plays,
+        score_sum
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| LocationError::Database(e.to_string()))?;
+
+    Ok(())
+}
+
+// =============================================================================
+// Map Collaborators
+// =============================================================================
+
+/// A user invited to edit a map.
+#[derive(Debug, Clone, FromRow)]
+pub struct MapCollaborator {
+    pub map_id: String,
+    pub user_id: String,
+    pub username: Option<String>,
+    pub display_name: String,
+    pub avatar_url: Option<String>,
+    pub invited_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// `None` while the invitation is pending
+    pub accepted_at: Option<DateTime<Utc>>,
+}
+
+impl MapCollaborator {
+    /// Whether the invitation has been accepted.
+    pub fn is_accepted(&self) -> bool {
+        self.accepted_at.is_some()
+    }
+}
+
+/// A pending invitation to edit a map, as seen by the invitee.
+#[derive(Debug, Clone, FromRow)]
+pub struct MapInvitation {
+    pub map_id: String,
+    pub map_slug: String,
+    pub map_name: String,
+    pub invited_by: Option<String>,
+    pub inviter_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Invite a user to edit a map. Returns `false` if the user was already
+/// invited or is already a collaborator.
+pub async fn invite_map_collaborator(
+    pool: &DbPool,
+    map_id: &str,
+    user_id: &str,
+    invited_by: &str,
+) -> Result<bool, LocationError> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO map_collaborators (map_id, user_id, invited_by)
+        VALUES ($1, $2, $3)
+        ON CONFLICT DO NOTHING
+        "#,
+        map_id,
+        user_id,
+        invited_by
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| LocationError::Database(e.to_string()))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Accept a pending invitation. Returns `false` if there was none.
+pub async fn accept_map_invitation(
+    pool: &DbPool,
+    map_id: &str,
+    user_id: &str,
+) -> Result<bool, LocationError> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE map_collaborators
+        SET accepted_at = NOW()
+        WHERE map_id = $1 AND user_id = $2 AND accepted_at IS NULL
+        "#,
+        map_id,
+        user_id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| LocationError::Database(e.to_string()))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Remove a collaborator or decline an invitation. Returns `false` if the
+/// user was not invited.
+pub async fn remove_map_collaborator(
+    pool: &DbPool,
+    map_id: &str,
+    user_id: &str,
+) -> Result<bool, LocationError> {
+    let result = sqlx::query!(
+        "DELETE FROM map_collaborators WHERE map_id = $1 AND user_id = $2",
+        map_id,
+        user_id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| LocationError::Database(e.to_string()))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// List a map's collaborators and pending invitations, oldest first.
+pub async fn list_map_collaborators(
+    pool: &DbPool,
+    map_id: &str,
+) -> Result<Vec<MapCollaborator>, LocationError> {
+    sqlx::query_as!(
+        MapCollaborator,
+        r#"
+        SELECT mc.map_id, mc.user_id, u.username, u.display_name, u.avatar_url,
+               mc.invited_by, mc.created_at, mc.accepted_at
+        FROM map_collaborators mc
+        JOIN users u ON u.id = mc.user_id
+        WHERE mc.map_id = $1
+        ORDER BY mc.created_at
+        "#,
+        map_id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| LocationError::Database(e.to_string()))
+}
+
+/// Get a user's collaborator entry for a map, pending or accepted.
+pub async fn get_map_collaborator(
+    pool: &DbPool,
+    map_id: &str,
+    user_id: &str,
+) -> Result<Option<MapCollaborator>, LocationError> {
+    sqlx::query_as!(
+        MapCollaborator,
+        r#"
+        SELECT mc.map_id, mc.user_id, u.username, u.display_name, u.avatar_url,
+               mc.invited_by, mc.created_at, mc.accepted_at
+        FROM map_collaborators mc
+        JOIN users u ON u.id = mc.user_id
+        WHERE mc.map_id = $1 AND mc.user_id = $2
+        "#,
+        map_id,
+        user_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| LocationError::Database(e.to_string()))
+}
+
+/// Count a map's collaborators, including pending invitations.
+pub async fn count_map_collaborators(pool: &DbPool, map_id: &str) -> Result<i64, LocationError> {
+    let count =
+        sqlx::query_scalar!("SELECT COUNT(*) FROM map_collaborators WHERE map_id = $1", map_id)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| LocationError::Database(e.to_string()))?;
+
+    Ok(count.unwrap_or(0))
+}
+
+/// Check whether a user is an accepted editor of a map.
+pub async fn is_map_editor(
+    pool: &DbPool,
+    map_id: &str,
+    user_id: &str,
+) -> Result<bool, LocationError> {
+    let is_editor = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM map_collaborators
+            WHERE map_id = $1 AND user_id = $2 AND accepted_at IS NOT NULL
+        )
+        "#,
+        map_id,
+        user_id
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| LocationError::Database(e.to_string()))?;
+
+    Ok(is_editor.unwrap_or(false))
+}
+
+/// Get the IDs of all maps a user is an accepted editor of.
+pub async fn get_editable_map_ids(
+    pool: &DbPool,
+    user_id: &str,
+) -> Result<HashSet<String>, LocationError> {
+    let ids = sqlx::query_scalar!(
+        "SELECT map_id FROM map_collaborators WHERE user_id = $1 AND accepted_at IS NOT NULL",
+        user_id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| LocationError::Database(e.to_string()))?;
+
+    Ok(ids.into_iter().collect())
+}
+
+/// List a user's pending invitations to active maps, newest first.
+pub async fn list_map_invitations(
+    pool: &DbPool,
+    user_id: &str,
+) -> Result<Vec<MapInvitation>, LocationError> {
+    sqlx::query_as!(
+        MapInvitation,
+        r#"
+        SELECT mc.map_id, m.slug AS map_slug, m.name AS map_name, mc.invited_by,
+               inviter.display_name AS "inviter_name?", mc.created_at
+        FROM map_collaborators mc
+        JOIN maps m ON m.id = mc.map_id
+        LEFT JOIN users inviter ON inviter.id = mc.invited_by
+        WHERE mc.user_id = $1 AND mc.accepted_at IS NULL AND m.active = TRUE
+        ORDER BY mc.created_at DESC
+        "#,
+        user_id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| LocationError::Database(e.to_string()))
+}
+
 // =============================================================================
 // Location Search for Map Builder
 // =============================================================================
@@ -1695,86 +2783,88 @@ pub struct LocationSearchFilters {
     pub outdoor_only: bool,
     /// Exclude locations already in this map
     pub exclude_map_id: Option<String>,
+    /// Only locations rated in this difficulty band
+    pub difficulty: Option<DifficultyBand>,
+    /// Only locations with all of these (normalized) tags
+    pub tags: Vec<String>,
+    /// Free text matched against tags, country, subdivision and surface
+    pub text: Option<String>,
 }
 
-/// Helper enum for dynamic bind parameter values in search queries.
-enum FilterBindValue {
-    Text(String),
-    Int(i32),
+/// Text free-text searches match, as indexed by the location_tags migration.
+const LOCATION_SEARCH_TEXT: &str =
+    "location_search_text(l.tags, l.country_code, l.subdivision_code, l.surface)";
+
+/// `ILIKE` pattern matching a substring, with wildcards in it escaped.
+pub(crate) fn contains_pattern(text: &str) -> String {
+    let escaped = text.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+    format!("%{}%", escaped.to_lowercase())
 }
 
-/// Build WHERE clause and bind values from search filters.
+/// Append the WHERE clause for search filters to a query over `locations l`.
 ///
-/// `param_start` is the first `$N` index to use (e.g., 1 for count queries,
-/// 3 for data queries where $1/$2 are limit/offset).
-/// Returns `(where_clause, bind_values)`.
-fn build_search_filter_clause(
-    filters: &LocationSearchFilters,
-    param_start: usize,
-) -> (String, Vec<FilterBindValue>) {
-    let mut conditions = vec![
-        "l.active = TRUE".to_string(),
-        "(l.review_status IS NULL OR l.review_status = 'approved')".to_string(),
-    ];
-    let mut bind_values: Vec<FilterBindValue> = Vec::new();
-    let mut idx = param_start;
+/// Every user-supplied value is pushed as a bind parameter.
+fn push_search_filters<'a>(
+    builder: &mut QueryBuilder<'a, Postgres>,
+    filters: &'a LocationSearchFilters,
+) {
+    builder.push(
+        " WHERE l.active = TRUE AND (l.review_status IS NULL OR l.review_status = 'approved')",
+    );
 
-    if let Some(ref country) = filters.country_code {
-        conditions.push(format!("l.country_code = ${idx}"));
-        bind_values.push(FilterBindValue::Text(country.clone()));
-        idx += 1;
+    if let Some(country) = &filters.country_code {
+        builder.push(" AND l.country_code = ").push_bind(country.as_str());
     }
 
-    if let Some(ref subdivision) = filters.subdivision_code {
-        conditions.push(format!("l.subdivision_code = ${idx}"));
-        bind_values.push(FilterBindValue::Text(subdivision.clone()));
-        idx += 1;
+    if let Some(subdivision) = &filters.subdivision_code {
+        builder.push(" AND l.subdivision_code = ").push_bind(subdivision.as_str());
     }
 
     if let Some(min_year) = filters.min_year {
-        conditions.push(format!(
-            "(l.capture_date IS NULL OR EXTRACT(YEAR FROM l.capture_date) >= ${idx})"
-        ));
-        bind_values.push(FilterBindValue::Int(min_year));
-        idx += 1;
+        builder
+            .push(" AND (l.capture_date IS NULL OR EXTRACT(YEAR FROM l.capture_date) >= ")
+            .push_bind(min_year)
+            .push(")");
     }
 
     if let Some(max_year) = filters.max_year {
-        conditions.push(format!(
-            "(l.capture_date IS NULL OR EXTRACT(YEAR FROM l.capture_date) <= ${idx})"
-        ));
-        bind_values.push(FilterBindValue::Int(max_year));
-        idx += 1;
+        builder
+            .push(" AND (l.capture_date IS NULL OR EXTRACT(YEAR FROM l.capture_date) <= ")
+            .push_bind(max_year)
+            .push(")");
     }
 
     if filters.outdoor_only {
-        conditions.push("(l.is_scout IS NULL OR l.is_scout = FALSE)".to_string());
+        builder.push(" AND (l.is_scout IS NULL OR l.is_scout = FALSE)");
     }
 
-    if let Some(ref map_id) = filters.exclude_map_id {
-        conditions.push(format!(
-            "NOT EXISTS (SELECT 1 FROM map_locations ml WHERE ml.location_id = l.id AND ml.map_id = ${idx})"
-        ));
-        bind_values.push(FilterBindValue::Text(map_id.clone()));
-        idx += 1;
+    if let Some(map_id) = &filters.exclude_map_id {
+        builder
+            .push(" AND NOT EXISTS (SELECT 1 FROM map_locations ml WHERE ml.location_id = l.id AND ml.map_id = ")
+            .push_bind(map_id.as_str())
+            .push(")");
     }
 
-    let _ = idx;
-    (conditions.join(" AND "), bind_values)
-}
+    if let Some(band) = filters.difficulty {
+        let (min, max) = band.range();
+        builder.push(" AND l.difficulty BETWEEN ").push_bind(min).push(" AND ").push_bind(max);
+    }
 
-/// Helper: bind `FilterBindValue`s to a sqlx query in order.
-fn bind_filter_values<'q, O>(
-    mut query: sqlx::query::QueryAs<'q, sqlx::Postgres, O, sqlx::postgres::PgArguments>,
-    values: &'q [FilterBindValue],
-) -> sqlx::query::QueryAs<'q, sqlx::Postgres, O, sqlx::postgres::PgArguments> {
-    for val in values {
-        query = match val {
-            FilterBindValue::Text(s) => query.bind(s.as_str()),
-            FilterBindValue::Int(i) => query.bind(*i),
-        };
+    if !filters.tags.is_empty() {
+        builder.push(" AND l.tags @> ").push_bind(filters.tags.as_slice());
+    }
+
+    if let Some(text) = filters.text.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+        // Whole words through the full-text index, partial words ("toll" for
+        // "tollbooth") through the trigram index
+        builder
+            .push(format!(" AND (to_tsvector('simple', {LOCATION_SEARCH_TEXT}) @@ "))
+            .push("websearch_to_tsquery('simple', ")
+            .push_bind(text)
+            .push(format!(") OR {LOCATION_SEARCH_TEXT} ILIKE "))
+            .push_bind(contains_pattern(text))
+            .push(")");
     }
-    query
 }
 
 /// Search locations with filters for the map builder.
@@ -1786,38 +2876,80 @@ pub async fn search_locations(
     limit: i64,
     offset: i64,
 ) -> Result<(Vec<Location>, i64), LocationError> {
-    // Count query: filter params start at $1
-    let (count_where, count_binds) = build_search_filter_clause(filters, 1);
-    let count_sql = format!("SELECT COUNT(*)::bigint FROM locations l WHERE {count_where}");
-    let mut count_q = sqlx::query_scalar::<_, i64>(&count_sql);
-    for val in &count_binds {
-        count_q = match val {
-            FilterBindValue::Text(s) => count_q.bind(s.as_str()),
-            FilterBindValue::Int(i) => count_q.bind(*i),
-        };
-    }
-    let total: i64 =
-        count_q.fetch_one(pool).await.map_err(|e| LocationError::Database(e.to_string()))?;
+    let mut count_query = QueryBuilder::new("SELECT COUNT(*)::bigint FROM locations l");
+    push_search_filters(&mut count_query, filters);
+    let total: i64 = count_query
+        .build_query_scalar()
+        .fetch_one(pool)
+        .await
+        .map_err(|e| LocationError::Database(e.to_string()))?;
+
+    let mut data_query = QueryBuilder::new(format!("SELECT {LOCATION_COLUMNS} FROM locations l"));
+    push_search_filters(&mut data_query, filters);
+    data_query
+        .push(" ORDER BY l.country_code ASC, l.created_at DESC LIMIT ")
+        .push_bind(limit)
+        .push(" OFFSET ")
+        .push_bind(offset);
+
+    let rows = data_query
+        .build_query_as::<LocationRow>()
+        .fetch_all(pool)
+        .await
+        .map_err(|e| LocationError::Database(e.to_string()))?;
+
+    let locations: Result<Vec<Location>, _> = rows.into_iter().map(|r| r.try_into()).collect();
+    Ok((locations?, total))
+}
+
+/// Count the active locations a draft of map rules would draw from, per
+/// country (most locations first; `None` for locations without a country).
+///
+/// Applies the same filters as location selection. With `map_id` and no
+/// region, counts that map's location list; otherwise counts every location,
+/// inside the region and country list when the rules set them.
+pub async fn count_locations_by_rules(
+    pool: &DbPool,
+    rules: &MapRules,
+    map_id: Option<&str>,
+) -> Result<Vec<(Option<String>, i64)>, LocationError> {
+    let filter_clause = build_location_filter_clause(rules);
+    let map_id = map_id.filter(|_| rules.region.is_none());
+    let region = rules
+        .region
+        .as_ref()
+        .map(serde_json::to_value)
+        .transpose()
+        .map_err(|e| LocationError::Database(e.to_string()))?;
+    // Map location lists are not filtered by country, as in selection
+    let countries: &[String] = if map_id.is_some() { &[] } else { &rules.countries };
 
-    // Data query: $1 = limit, $2 = offset, filter params start at $3
-    let (data_where, data_binds) = build_search_filter_clause(filters, 3);
-    let data_sql = format!(
+    let query = format!(
         r#"
-        SELECT {LOCATION_COLUMNS}
+        SELECT l.country_code, COUNT(*) AS count
         FROM locations l
-        WHERE {data_where}
-        ORDER BY l.country_code ASC, l.created_at DESC
-        LIMIT $1 OFFSET $2
-        "#
+        WHERE l.active = TRUE
+          AND ($1::text IS NULL OR EXISTS (
+              SELECT 1 FROM map_locations ml WHERE ml.map_id = $1 AND ml.location_id = l.id
+          ))
+          AND (cardinality($2::text[]) = 0 OR l.country_code = ANY($2))
+          AND ($3::jsonb IS NULL OR ST_Intersects(l.geom, (SELECT {region})))
+          {filter_clause}
+        GROUP BY l.country_code
+        ORDER BY count DESC, l.country_code
+        "#,
+        region = region_geometry_sql("$3"),
     );
 
-    let base_q = sqlx::query_as::<_, LocationRow>(&data_sql).bind(limit).bind(offset);
-    let data_q = bind_filter_values(base_q, &data_binds);
-
-    let rows = data_q.fetch_all(pool).await.map_err(|e| LocationError::Database(e.to_string()))?;
+    let rows: Vec<(Option<String>, i64)> = sqlx::query_as(&query)
+        .bind(map_id)
+        .bind(countries)
+        .bind(region)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| LocationError::Database(e.to_string()))?;
 
-    let locations: Result<Vec<Location>, _> = rows.into_iter().map(|r| r.try_into()).collect();
-    Ok((locations?, total))
+    Ok(rows)
 }
 
 /// Get available countries for location filtering.
@@ -1867,3 +2999,112 @@ pub async fn get_available_subdivisions(
         .filter_map(|r| r.subdivision_code.map(|s| (s, r.count.unwrap_or(0))))
         .collect())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INJECTION: &str = "US' OR '1'='1'; DROP TABLE locations; --";
+
+    #[test]
+    fn test_search_filters_bind_text_values() {
+        let filters = LocationSearchFilters {
+            country_code: Some(INJECTION.to_string()),
+            subdivision_code: Some(INJECTION.to_string()),
+            exclude_map_id: Some(INJECTION.to_string()),
+            ..Default::default()
+        };
+        let mut query = QueryBuilder::new("SELECT COUNT(*) FROM locations l");
+        push_search_filters(&mut query, &filters);
+
+        let sql = query.sql();
+        assert!(!sql.contains(INJECTION));
+        assert!(!sql.contains("DROP TABLE"));
+        assert!(sql.contains("l.country_code = $1"));
+        assert!(sql.contains("l.subdivision_code = $2"));
+        assert!(sql.contains("ml.map_id = $3"));
+    }
+
+    #[test]
+    fn test_search_filters_number_placeholders_in_order() {
+        let filters = LocationSearchFilters {
+            country_code: Some("US".to_string()),
+            min_year: Some(2015),
+            max_year: Some(2020),
+            outdoor_only: true,
+            difficulty: Some(DifficultyBand::Hard),
+            ..Default::default()
+        };
+        let mut query = QueryBuilder::new("SELECT COUNT(*) FROM locations l");
+        push_search_filters(&mut query, &filters);
+
+        let sql = query.sql();
+        assert!(sql.contains("l.country_code = $1"));
+        assert!(sql.contains(">= $2"));
+        assert!(sql.contains("<= $3"));
+        assert!(sql.contains("l.difficulty BETWEEN $4 AND $5"));
+        assert!(sql.contains("l.is_scout = FALSE"));
+    }
+
+    #[test]
+    fn test_search_filters_without_values_have_no_placeholders() {
+        let filters = LocationSearchFilters::default();
+        let mut query = QueryBuilder::new("SELECT COUNT(*) FROM locations l");
+        push_search_filters(&mut query, &filters);
+
+        assert!(!query.sql().contains('$'));
+    }
+
+    #[test]
+    fn test_search_filters_bind_tags_and_text() {
+        let filters = LocationSearchFilters {
+            tags: vec!["snow".to_string(), INJECTION.to_string()],
+            text: Some(INJECTION.to_string()),
+            ..Default::default()
+        };
+        let mut query = QueryBuilder::new("SELECT COUNT(*) FROM locations l");
+        push_search_filters(&mut query, &filters);
+
+        let sql = query.sql();
+        assert!(!sql.contains(INJECTION));
+        assert!(sql.contains("l.tags @> $1"));
+        assert!(sql.contains("websearch_to_tsquery('simple', $2)"));
+        assert!(sql.contains("ILIKE $3"));
+    }
+
+    #[test]
+    fn test_blank_text_is_ignored() {
+        let filters = LocationSearchFilters { text: Some("   ".to_string()), ..Default::default() };
+        let mut query = QueryBuilder::new("SELECT COUNT(*) FROM locations l");
+        push_search_filters(&mut query, &filters);
+
+        assert!(!query.sql().contains("ILIKE"));
+    }
+
+    #[test]
+    fn test_contains_pattern_escapes_wildcards() {
+        assert_eq!(contains_pattern("Toll"), "%toll%");
+        assert_eq!(contains_pattern("100%_x\\"), "%100\\%\\_x\\\\%");
+    }
+
+    #[test]
+    fn test_report_filters_bind_text_values() {
+        let mut query = QueryBuilder::new("SELECT COUNT(*) FROM location_reports r");
+        push_report_filters(&mut query, Some(INJECTION), Some(INJECTION));
+
+        let sql = query.sql();
+        assert!(!sql.contains(INJECTION));
+        assert!(sql.contains("r.reason = $1"));
+        assert!(sql.contains("COALESCE(l.review_status, 'approved') = $2"));
+    }
+
+    #[test]
+    fn test_report_filters_are_optional() {
+        let mut query = QueryBuilder::new("SELECT COUNT(*) FROM location_reports r");
+        push_report_filters(&mut query, None, Some("flagged"));
+
+        let sql = query.sql();
+        assert!(!sql.contains("r.reason"));
+        assert!(sql.contains("COALESCE(l.review_status, 'approved') = $1"));
+    }
+}
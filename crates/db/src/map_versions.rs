@@ -0,0 +1,571 @@
+//! Map version history database queries
+//!
+//! Location and rule changes go through this module so each one is recorded
+//! in `map_versions` in the same transaction that applies it. Versions store
+//! diffs, so restoring a version undoes every later version in reverse.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use dguesser_core::location::{LocationError, MapRules};
+use sqlx::{FromRow, PgConnection};
+
+use crate::DbPool;
+use crate::locations::{get_map_by_id_or_slug, refresh_location_count, region_geometry_sql};
+
+/// What kind of change a version records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapVersionKind {
+    /// State before the first recorded change
+    Initial,
+    /// Locations added or removed
+    Locations,
+    /// Rules edited
+    Rules,
+    /// Locations added by a file import
+    Import,
+    /// An earlier version restored
+    Restore,
+}
+
+impl MapVersionKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Initial => "initial",
+            Self::Locations => "locations",
+            Self::Rules => "rules",
+            Self::Import => "import",
+            Self::Restore => "restore",
+        }
+    }
+}
+
+impl fmt::Display for MapVersionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for MapVersionKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "initial" => Ok(Self::Initial),
+            "locations" => Ok(Self::Locations),
+            "rules" => Ok(Self::Rules),
+            "import" => Ok(Self::Import),
+            "restore" => Ok(Self::Restore),
+            _ => Err(format!("Invalid map version kind: {}", s)),
+        }
+    }
+}
+
+/// One entry in a map's version history.
+#[derive(Debug, Clone)]
+pub struct MapVersion {
+    pub map_id: String,
+    pub version: i32,
+    pub kind: MapVersionKind,
+    pub user_id: Option<String>,
+    pub added_location_ids: Vec<String>,
+    pub removed_location_ids: Vec<String>,
+    /// Map rules after this version
+    pub rules: serde_json::Value,
+    /// Version restored, for restore entries
+    pub restored_from: Option<i32>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A version without its location diff, for history listings.
+#[derive(Debug, Clone)]
+pub struct MapVersionSummary {
+    pub version: i32,
+    pub kind: MapVersionKind,
+    pub user_id: Option<String>,
+    pub added_count: i32,
+    pub removed_count: i32,
+    pub rules: serde_json::Value,
+    pub restored_from: Option<i32>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, FromRow)]
+struct MapVersionRow {
+    map_id: String,
+    version: i32,
+    kind: String,
+    user_id: Option<String>,
+    added_location_ids: Vec<String>,
+    removed_location_ids: Vec<String>,
+    rules: serde_json::Value,
+    restored_from: Option<i32>,
+    created_at: DateTime<Utc>,
+}
+
+impl TryFrom<MapVersionRow> for MapVersion {
+    type Error = LocationError;
+
+    fn try_from(row: MapVersionRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            map_id: row.map_id,
+            version: row.version,
+            kind: row.kind.parse().map_err(LocationError::Database)?,
+            user_id: row.user_id,
+            added_location_ids: row.added_location_ids,
+            removed_location_ids: row.removed_location_ids,
+            rules: row.rules,
+            restored_from: row.restored_from,
+            created_at: row.created_at,
+        })
+    }
+}
+
+#[derive(Debug, FromRow)]
+struct MapVersionSummaryRow {
+    version: i32,
+    kind: String,
+    user_id: Option<String>,
+    added_count: i32,
+    removed_count: i32,
+    rules: serde_json::Value,
+    restored_from: Option<i32>,
+    created_at: DateTime<Utc>,
+}
+
+impl TryFrom<MapVersionSummaryRow> for MapVersionSummary {
+    type Error = LocationError;
+
+    fn try_from(row: MapVersionSummaryRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            version: row.version,
+            kind: row.kind.parse().map_err(LocationError::Database)?,
+            user_id: row.user_id,
+            added_count: row.added_count,
+            removed_count: row.removed_count,
+            rules: row.rules,
+            restored_from: row.restored_from,
+            created_at: row.created_at,
+        })
+    }
+}
+
+fn db_error(e: sqlx::Error) -> LocationError {
+    LocationError::Database(e.to_string())
+}
+
+// =============================================================================
+// Recording
+// =============================================================================
+
+/// Lock a map row for the rest of the transaction and return its rules.
+/// Serializes version numbering for the map.
+async fn lock_map(
+    conn: &mut PgConnection,
+    map_id: &str,
+) -> Result<serde_json::Value, LocationError> {
+    sqlx::query_scalar::<_, serde_json::Value>(
+        "SELECT rules FROM maps WHERE id = $1 AND active = TRUE FOR UPDATE",
+    )
+    .bind(map_id)
+    .fetch_optional(conn)
+    .await
+    .map_err(db_error)?
+    .ok_or_else(|| LocationError::MapNotFound(map_id.to_string()))
+}
+
+/// A change to record as a new version.
+struct Change<'a> {
+    kind: MapVersionKind,
+    user_id: &'a str,
+    added: &'a [String],
+    removed: &'a [String],
+    rules_before: &'a serde_json::Value,
+    rules_after: &'a serde_json::Value,
+    restored_from: Option<i32>,
+}
+
+/// Append a version for a change. The map row must be locked. Maps without
+/// history first get an initial version holding the state before the change,
+/// so the first change can be undone too.
+async fn record(
+    conn: &mut PgConnection,
+    map_id: &str,
+    change: Change<'_>,
+) -> Result<i32, LocationError> {
+    let latest = sqlx::query_scalar::<_, Option<i32>>(
+        "SELECT MAX(version) FROM map_versions WHERE map_id = $1",
+    )
+    .bind(map_id)
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(db_error)?;
+
+    let version = match latest {
+        Some(latest) => latest + 1,
+        None => {
+            sqlx::query(
+                r#"
+                INSERT INTO map_versions (map_id, version, kind, rules)
+                VALUES ($1, 1, $2, $3)
+                "#,
+            )
+            .bind(map_id)
+            .bind(MapVersionKind::Initial.as_str())
+            .bind(change.rules_before)
+            .execute(&mut *conn)
+            .await
+            .map_err(db_error)?;
+            2
+        }
+    };
+
+    sqlx::query(
+        r#"
+        INSERT INTO map_versions (
+            map_id, version, kind, user_id, added_location_ids, removed_location_ids,
+            rules, restored_from
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        "#,
+    )
+    .bind(map_id)
+    .bind(version)
+    .bind(change.kind.as_str())
+    .bind(change.user_id)
+    .bind(change.added)
+    .bind(change.removed)
+    .bind(change.rules_after)
+    .bind(change.restored_from)
+    .execute(&mut *conn)
+    .await
+    .map_err(db_error)?;
+
+    Ok(version)
+}
+
+/// Link locations to a map, skipping ones already in it. Returns the IDs
+/// actually added.
+async fn insert_map_locations(
+    conn: &mut PgConnection,
+    map_id: &str,
+    location_ids: &[String],
+) -> Result<Vec<String>, LocationError> {
+    sqlx::query_scalar::<_, String>(
+        r#"
+        INSERT INTO map_locations (map_id, location_id)
+        SELECT $1, id FROM locations WHERE id = ANY($2)
+        ON CONFLICT (map_id, location_id) DO NOTHING
+        RETURNING location_id
+        "#,
+    )
+    .bind(map_id)
+    .bind(location_ids)
+    .fetch_all(conn)
+    .await
+    .map_err(db_error)
+}
+
+/// Unlink locations from a map. Returns the IDs actually removed.
+async fn delete_map_locations(
+    conn: &mut PgConnection,
+    map_id: &str,
+    location_ids: &[String],
+) -> Result<Vec<String>, LocationError> {
+    sqlx::query_scalar::<_, String>(
+        r#"
+        DELETE FROM map_locations
+        WHERE map_id = $1 AND location_id = ANY($2)
+        RETURNING location_id
+        "#,
+    )
+    .bind(map_id)
+    .bind(location_ids)
+    .fetch_all(conn)
+    .await
+    .map_err(db_error)
+}
+
+/// Add locations to a map and record the change. Locations already in the
+/// map are skipped; returns the number added.
+pub async fn add_locations(
+    pool: &DbPool,
+    map_id: &str,
+    user_id: &str,
+    location_ids: &[String],
+    kind: MapVersionKind,
+) -> Result<usize, LocationError> {
+    if location_ids.is_empty() {
+        return Ok(0);
+    }
+
+    let mut tx = pool.begin().await.map_err(db_error)?;
+    let rules = lock_map(&mut tx, map_id).await?;
+    let added = insert_map_locations(&mut tx, map_id, location_ids).await?;
+
+    if !added.is_empty() {
+        let change = Change {
+            kind,
+            user_id,
+            added: &added,
+            removed: &[],
+            rules_before: &rules,
+            rules_after: &rules,
+            restored_from: None,
+        };
+        record(&mut tx, map_id, change).await?;
+    }
+    tx.commit().await.map_err(db_error)?;
+
+    Ok(added.len())
+}
+
+/// Remove locations from a map and record the change. Returns the number
+/// removed.
+pub async fn remove_locations(
+    pool: &DbPool,
+    map_id: &str,
+    user_id: &str,
+    location_ids: &[String],
+) -> Result<usize, LocationError> {
+    if location_ids.is_empty() {
+        return Ok(0);
+    }
+
+    let mut tx = pool.begin().await.map_err(db_error)?;
+    let rules = lock_map(&mut tx, map_id).await?;
+    let removed = delete_map_locations(&mut tx, map_id, location_ids).await?;
+
+    if !removed.is_empty() {
+        let change = Change {
+            kind: MapVersionKind::Locations,
+            user_id,
+            added: &[],
+            removed: &removed,
+            rules_before: &rules,
+            rules_after: &rules,
+            restored_from: None,
+        };
+        record(&mut tx, map_id, change).await?;
+    }
+    tx.commit().await.map_err(db_error)?;
+
+    Ok(removed.len())
+}
+
+/// Record an edit of a map's rules, given the rules before the edit. Does
+/// nothing if the rules did not change.
+pub async fn record_rules_change(
+    pool: &DbPool,
+    map_id: &str,
+    user_id: &str,
+    rules_before: &MapRules,
+) -> Result<(), LocationError> {
+    let before =
+        serde_json::to_value(rules_before).map_err(|e| LocationError::Database(e.to_string()))?;
+
+    let mut tx = pool.begin().await.map_err(db_error)?;
+    let after = lock_map(&mut tx, map_id).await?;
+
+    if after != before {
+        let change = Change {
+            kind: MapVersionKind::Rules,
+            user_id,
+            added: &[],
+            removed: &[],
+            rules_before: &before,
+            rules_after: &after,
+            restored_from: None,
+        };
+        record(&mut tx, map_id, change).await?;
+    }
+    tx.commit().await.map_err(db_error)?;
+
+    Ok(())
+}
+
+// =============================================================================
+// History
+// =============================================================================
+
+/// List a map's versions, newest first.
+pub async fn list_versions(
+    pool: &DbPool,
+    map_id: &str,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<MapVersionSummary>, LocationError> {
+    let rows = sqlx::query_as::<_, MapVersionSummaryRow>(
+        r#"
+        SELECT version, kind, user_id,
+               COALESCE(cardinality(added_location_ids), 0) AS added_count,
+               COALESCE(cardinality(removed_location_ids), 0) AS removed_count,
+               rules, restored_from, created_at
+        FROM map_versions
+        WHERE map_id = $1
+        ORDER BY version DESC
+        LIMIT $2 OFFSET $3
+        "#,
+    )
+    .bind(map_id)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+    .map_err(db_error)?;
+
+    rows.into_iter().map(|r| r.try_into()).collect()
+}
+
+/// Count a map's versions.
+pub async fn count_versions(pool: &DbPool, map_id: &str) -> Result<i64, LocationError> {
+    sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM map_versions WHERE map_id = $1")
+        .bind(map_id)
+        .fetch_one(pool)
+        .await
+        .map_err(db_error)
+}
+
+/// Get one version with its full location diff.
+pub async fn get_version(
+    pool: &DbPool,
+    map_id: &str,
+    version: i32,
+) -> Result<Option<MapVersion>, LocationError> {
+    let row = sqlx::query_as::<_, MapVersionRow>(
+        r#"
+        SELECT map_id, version, kind, user_id, added_location_ids, removed_location_ids,
+               rules, restored_from, created_at
+        FROM map_versions
+        WHERE map_id = $1 AND version = $2
+        "#,
+    )
+    .bind(map_id)
+    .bind(version)
+    .fetch_optional(pool)
+    .await
+    .map_err(db_error)?;
+
+    row.map(TryInto::try_into).transpose()
+}
+
+// =============================================================================
+// Restore
+// =============================================================================
+
+/// Net location changes that undo a run of versions.
+///
+/// Takes the diffs newest first. Undoing a version removes what it added and
+/// re-adds what it removed; when several versions touch a location, the
+/// oldest one decides whether it was in the map before them.
+fn undo_diffs<'a>(
+    newest_first: impl IntoIterator<Item = (&'a [String], &'a [String])>,
+) -> (Vec<String>, Vec<String>) {
+    let mut present: HashMap<&str, bool> = HashMap::new();
+    for (added, removed) in newest_first {
+        for id in added {
+            present.insert(id, false);
+        }
+        for id in removed {
+            present.insert(id, true);
+        }
+    }
+
+    let mut to_add = Vec::new();
+    let mut to_remove = Vec::new();
+    for (id, present) in present {
+        if present {
+            to_add.push(id.to_string());
+        } else {
+            to_remove.push(id.to_string());
+        }
+    }
+    to_add.sort();
+    to_remove.sort();
+    (to_add, to_remove)
+}
+
+/// Restore a map to how it was right after `version`, recording the restore
+/// as a new version. Returns `None` if the version does not exist.
+///
+/// Locations deleted from the database since cannot be re-added and are
+/// skipped.
+pub async fn restore_version(
+    pool: &DbPool,
+    map_id: &str,
+    version: i32,
+    user_id: &str,
+) -> Result<Option<MapVersion>, LocationError> {
+    let mut tx = pool.begin().await.map_err(db_error)?;
+    let current_rules = lock_map(&mut tx, map_id).await?;
+
+    let target_rules = sqlx::query_scalar::<_, serde_json::Value>(
+        "SELECT rules FROM map_versions WHERE map_id = $1 AND version = $2",
+    )
+    .bind(map_id)
+    .bind(version)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(db_error)?;
+    let Some(target_rules) = target_rules else {
+        return Ok(None);
+    };
+
+    let later = sqlx::query_as::<_, (Vec<String>, Vec<String>)>(
+        r#"
+        SELECT added_location_ids, removed_location_ids
+        FROM map_versions
+        WHERE map_id = $1 AND version > $2
+        ORDER BY version DESC
+        "#,
+    )
+    .bind(map_id)
+    .bind(version)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(db_error)?;
+
+    let (to_add, to_remove) = undo_diffs(later.iter().map(|(a, r)| (a.as_slice(), r.as_slice())));
+    let removed = delete_map_locations(&mut tx, map_id, &to_remove).await?;
+    let added = insert_map_locations(&mut tx, map_id, &to_add).await?;
+
+    let rules_changed = target_rules != current_rules;
+    if rules_changed {
+        sqlx::query(&format!(
+            r#"
+            UPDATE maps
+            SET rules = $2,
+                region = CASE WHEN $2 ? 'region' THEN {region} END,
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+            region = region_geometry_sql("$2->'region'"),
+        ))
+        .bind(map_id)
+        .bind(&target_rules)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+    }
+
+    let change = Change {
+        kind: MapVersionKind::Restore,
+        user_id,
+        added: &added,
+        removed: &removed,
+        rules_before: &current_rules,
+        rules_after: &target_rules,
+        restored_from: Some(version),
+    };
+    let new_version = record(&mut tx, map_id, change).await?;
+    tx.commit().await.map_err(db_error)?;
+
+    if rules_changed {
+        let map = get_map_by_id_or_slug(pool, map_id).await?;
+        refresh_location_count(pool, &map).await?;
+    }
+
+    get_version(pool, map_id, new_version).await
+}
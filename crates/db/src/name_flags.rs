@@ -0,0 +1,137 @@
+//! Flagged display name queries
+//!
+//! Display names that pass validation but look suspicious are flagged for a
+//! moderator. A user has at most one pending flag; flagging again replaces
+//! it with the newer name.
+
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+use crate::DbPool;
+
+/// A flagged display name with the user's current name
+#[derive(Debug, Clone, FromRow)]
+pub struct NameFlag {
+    pub id: String,      // nfl_XXXXXXXXXXXX
+    pub user_id: String, // usr_XXXXXXXXXXXX
+    /// The name that was flagged
+    pub display_name: String,
+    /// The user's name now
+    pub current_display_name: String,
+    pub reason: String,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub resolved_by: Option<String>,
+}
+
+const FLAG_COLUMNS: &str = "f.id, f.user_id, f.display_name, u.display_name AS \
+                            current_display_name, f.reason, f.status, f.created_at, \
+                            f.resolved_at, f.resolved_by";
+
+/// Flag a user's display name, replacing any pending flag.
+pub async fn flag(
+    pool: &DbPool,
+    user_id: &str,
+    display_name: &str,
+    reason: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO name_flags (id, user_id, display_name, reason)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (user_id) WHERE status = 'pending' DO UPDATE SET
+            display_name = EXCLUDED.display_name,
+            reason = EXCLUDED.reason,
+            created_at = NOW()
+        "#,
+    )
+    .bind(dguesser_core::generate_name_flag_id())
+    .bind(user_id)
+    .bind(display_name)
+    .bind(reason)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Flag a user's current display name because a player reported it. An
+/// existing pending flag is kept as it is.
+pub async fn flag_reported(pool: &DbPool, user_id: &str) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO name_flags (id, user_id, display_name, reason)
+        SELECT $1, id, display_name, 'reported' FROM users WHERE id = $2
+        ON CONFLICT (user_id) WHERE status = 'pending' DO NOTHING
+        "#,
+    )
+    .bind(dguesser_core::generate_name_flag_id())
+    .bind(user_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Drop a user's pending flag, e.g. after they picked an unflagged name.
+pub async fn clear_pending(pool: &DbPool, user_id: &str) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM name_flags WHERE user_id = $1 AND status = 'pending'")
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// List pending flags, oldest first, with the total count.
+pub async fn list_pending(
+    pool: &DbPool,
+    limit: i64,
+    offset: i64,
+) -> Result<(Vec<NameFlag>, i64), sqlx::Error> {
+    let flags = sqlx::query_as::<_, NameFlag>(&format!(
+        r#"
+        SELECT {FLAG_COLUMNS}
+        FROM name_flags f
+        JOIN users u ON u.id = f.user_id
+        WHERE f.status = 'pending' AND u.deleted_at IS NULL
+        ORDER BY f.created_at, f.id
+        LIMIT $1 OFFSET $2
+        "#
+    ))
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+
+    let total: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*)
+        FROM name_flags f
+        JOIN users u ON u.id = f.user_id
+        WHERE f.status = 'pending' AND u.deleted_at IS NULL
+        "#,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok((flags, total))
+}
+
+/// Dismiss a pending flag. Returns `None` if there is no such pending flag.
+pub async fn dismiss(
+    pool: &DbPool,
+    flag_id: &str,
+    moderator_id: &str,
+) -> Result<Option<NameFlag>, sqlx::Error> {
+    sqlx::query_as::<_, NameFlag>(&format!(
+        r#"
+        UPDATE name_flags f SET status = 'dismissed', resolved_at = NOW(), resolved_by = $2
+        FROM users u
+        WHERE f.id = $1 AND f.status = 'pending' AND u.id = f.user_id
+        RETURNING {FLAG_COLUMNS}
+        "#
+    ))
+    .bind(flag_id)
+    .bind(moderator_id)
+    .fetch_optional(pool)
+    .await
+}
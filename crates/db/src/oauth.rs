@@ -139,3 +139,63 @@ pub async fn count_linked_providers(pool: &DbPool, user_id: &str) -> Result<i64,
             .await?;
     Ok(count.unwrap_or(0))
 }
+
+/// Outcome of a guarded unlink attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnlinkOutcome {
+    /// The provider was unlinked
+    Unlinked,
+    /// The user has no account linked for this provider
+    NotLinked,
+    /// The provider is the user's only remaining login method (no other
+    /// provider and no password)
+    LastLoginMethod,
+}
+
+/// Unlink an OAuth provider unless it is the user's last remaining login method.
+///
+/// The user row is locked for the duration of the check so that two concurrent
+/// unlink requests cannot both remove a provider and leave the account without
+/// any way to sign in.
+pub async fn unlink_account_if_not_last(
+    pool: &DbPool,
+    user_id: &str,
+    provider: &str,
+) -> Result<UnlinkOutcome, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("SELECT id FROM users WHERE id = $1 FOR UPDATE")
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(sqlx::Error::RowNotFound)?;
+
+    let linked: Vec<String> =
+        sqlx::query_scalar("SELECT provider FROM oauth_accounts WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_all(&mut *tx)
+            .await?;
+
+    if !linked.iter().any(|p| p == provider) {
+        return Ok(UnlinkOutcome::NotLinked);
+    }
+
+    let has_password: bool =
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM password_credentials WHERE user_id = $1)")
+            .bind(user_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+    if linked.len() <= 1 && !has_password {
+        return Ok(UnlinkOutcome::LastLoginMethod);
+    }
+
+    sqlx::query("DELETE FROM oauth_accounts WHERE user_id = $1 AND provider = $2")
+        .bind(user_id)
+        .bind(provider)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(UnlinkOutcome::Unlinked)
+}
@@ -0,0 +1,256 @@
+//! Organization queries
+//!
+//! Organizations are separate instances of the game (a classroom, a company)
+//! served from the same deployment. Maps, games and leaderboard rollups carry
+//! the `org_id` they belong to; users take part through a membership.
+
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+use crate::DbPool;
+
+/// Role of a user within an organization
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OrgRole {
+    #[default]
+    Member,
+    /// Manages the organization's members and branding
+    Admin,
+}
+
+impl OrgRole {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Member => "member",
+            Self::Admin => "admin",
+        }
+    }
+}
+
+impl TryFrom<String> for OrgRole {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "member" => Ok(Self::Member),
+            "admin" => Ok(Self::Admin),
+            _ => Err(format!("Invalid organization role: {value}")),
+        }
+    }
+}
+
+/// An organization
+#[derive(Debug, Clone, FromRow)]
+pub struct Organization {
+    pub id: String, // org_XXXXXXXXXXXX
+    pub slug: String,
+    pub name: String,
+    /// Custom domain the organization is served from
+    pub domain: Option<String>,
+    /// Logo and colors, as set by the organization's admins
+    pub branding: serde_json::Value,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A member of an organization
+#[derive(Debug, Clone, FromRow)]
+pub struct OrgMember {
+    pub user_id: String,
+    pub display_name: String,
+    pub avatar_url: Option<String>,
+    #[sqlx(try_from = "String")]
+    pub role: OrgRole,
+    pub joined_at: DateTime<Utc>,
+}
+
+const ORG_COLUMNS: &str = "id, slug, name, domain, branding, active, created_at, updated_at";
+
+/// Create an organization.
+pub async fn create(
+    pool: &DbPool,
+    slug: &str,
+    name: &str,
+    domain: Option<&str>,
+) -> Result<Organization, sqlx::Error> {
+    sqlx::query_as::<_, Organization>(&format!(
+        r#"
+        INSERT INTO organizations (id, slug, name, domain)
+        VALUES ($1, $2, $3, $4)
+        RETURNING {ORG_COLUMNS}
+        "#
+    ))
+    .bind(dguesser_core::generate_org_id())
+    .bind(slug)
+    .bind(name)
+    .bind(domain)
+    .fetch_one(pool)
+    .await
+}
+
+/// List every organization, inactive ones included.
+pub async fn list(pool: &DbPool) -> Result<Vec<Organization>, sqlx::Error> {
+    sqlx::query_as::<_, Organization>(&format!(
+        "SELECT {ORG_COLUMNS} FROM organizations ORDER BY name, id"
+    ))
+    .fetch_all(pool)
+    .await
+}
+
+/// Get an active organization by slug.
+pub async fn get_by_slug(pool: &DbPool, slug: &str) -> Result<Option<Organization>, sqlx::Error> {
+    sqlx::query_as::<_, Organization>(&format!(
+        "SELECT {ORG_COLUMNS} FROM organizations WHERE slug = $1 AND active = TRUE"
+    ))
+    .bind(slug)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Get an active organization by its custom domain.
+pub async fn get_by_domain(
+    pool: &DbPool,
+    domain: &str,
+) -> Result<Option<Organization>, sqlx::Error> {
+    sqlx::query_as::<_, Organization>(&format!(
+        "SELECT {ORG_COLUMNS} FROM organizations WHERE domain = $1 AND active = TRUE"
+    ))
+    .bind(domain)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Check whether a slug or domain is taken by another organization.
+pub async fn is_taken(
+    pool: &DbPool,
+    slug: &str,
+    domain: Option<&str>,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM organizations WHERE slug = $1 OR domain = $2)")
+        .bind(slug)
+        .bind(domain)
+        .fetch_one(pool)
+        .await
+}
+
+/// Change an organization's name or branding. `None` leaves a field
+/// unchanged. Returns `None` if the organization does not exist.
+pub async fn update(
+    pool: &DbPool,
+    org_id: &str,
+    name: Option<&str>,
+    branding: Option<&serde_json::Value>,
+) -> Result<Option<Organization>, sqlx::Error> {
+    sqlx::query_as::<_, Organization>(&format!(
+        r#"
+        UPDATE organizations
+        SET name = COALESCE($2, name), branding = COALESCE($3, branding), updated_at = NOW()
+        WHERE id = $1
+        RETURNING {ORG_COLUMNS}
+        "#
+    ))
+    .bind(org_id)
+    .bind(name)
+    .bind(branding)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Activate or deactivate an organization. Returns `None` if it does not
+/// exist.
+pub async fn set_active(
+    pool: &DbPool,
+    org_id: &str,
+    active: bool,
+) -> Result<Option<Organization>, sqlx::Error> {
+    sqlx::query_as::<_, Organization>(&format!(
+        r#"
+        UPDATE organizations SET active = $2, updated_at = NOW()
+        WHERE id = $1
+        RETURNING {ORG_COLUMNS}
+        "#
+    ))
+    .bind(org_id)
+    .bind(active)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Get a user's role in an organization, if they are a member.
+pub async fn get_role(
+    pool: &DbPool,
+    org_id: &str,
+    user_id: &str,
+) -> Result<Option<OrgRole>, sqlx::Error> {
+    let role: Option<String> = sqlx::query_scalar(
+        "SELECT role FROM organization_members WHERE org_id = $1 AND user_id = $2",
+    )
+    .bind(org_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(role.and_then(|role| OrgRole::try_from(role).ok()))
+}
+
+/// List an organization's members, admins first.
+pub async fn list_members(pool: &DbPool, org_id: &str) -> Result<Vec<OrgMember>, sqlx::Error> {
+    sqlx::query_as::<_, OrgMember>(
+        r#"
+        SELECT m.user_id, u.display_name, u.avatar_url, m.role, m.joined_at
+        FROM organization_members m
+        INNER JOIN users u ON u.id = m.user_id AND u.deleted_at IS NULL
+        WHERE m.org_id = $1
+        ORDER BY m.role = 'admin' DESC, m.joined_at, m.user_id
+        "#,
+    )
+    .bind(org_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Add a user to an organization, or change the role of a member.
+pub async fn upsert_member(
+    pool: &DbPool,
+    org_id: &str,
+    user_id: &str,
+    role: OrgRole,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO organization_members (org_id, user_id, role)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (org_id, user_id) DO UPDATE SET role = EXCLUDED.role
+        "#,
+    )
+    .bind(org_id)
+    .bind(user_id)
+    .bind(role.as_str())
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Remove a member from an organization. Returns whether they were a member.
+pub async fn remove_member(
+    pool: &DbPool,
+    org_id: &str,
+    user_id: &str,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM organization_members WHERE org_id = $1 AND user_id = $2")
+        .bind(org_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Count an organization's admins.
+pub async fn count_admins(pool: &DbPool, org_id: &str) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM organization_members WHERE org_id = $1 AND role = 'admin'",
+    )
+    .bind(org_id)
+    .fetch_one(pool)
+    .await
+}
@@ -22,6 +22,21 @@ pub struct PartyMember {
     pub user_id: String,
     pub joined_at: DateTime<Utc>,
     pub left_at: Option<DateTime<Utc>>,
+    /// Muted in chat by the host
+    pub chat_muted: bool,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct PartyMessage {
+    pub id: i64,
+    pub party_id: String,
+    pub user_id: String,
+    /// Sender's current display name
+    pub display_name: String,
+    pub content: String,
+    /// Text before the chat filter masked it, if it did
+    pub original_content: Option<String>,
+    pub created_at: DateTime<Utc>,
 }
 
 // =============================================================================
@@ -143,7 +158,7 @@ pub async fn add_party_member(
         VALUES ($1, $2)
         ON CONFLICT (party_id, user_id)
         DO UPDATE SET left_at = NULL, joined_at = NOW()
-        RETURNING party_id, user_id, joined_at, left_at
+        RETURNING party_id, user_id, joined_at, left_at, chat_muted
         "#,
     )
     .bind(party_id)
@@ -173,7 +188,7 @@ pub async fn get_party_members(
 ) -> Result<Vec<PartyMember>, sqlx::Error> {
     sqlx::query_as::<_, PartyMember>(
         r#"
-        SELECT party_id, user_id, joined_at, left_at
+        SELECT party_id, user_id, joined_at, left_at, chat_muted
         FROM party_members
         WHERE party_id = $1 AND left_at IS NULL
         ORDER BY joined_at ASC
@@ -215,6 +230,118 @@ pub async fn get_party_member_count(pool: &DbPool, party_id: &str) -> Result<i64
     Ok(row.0)
 }
 
+// =============================================================================
+// Party chat
+// =============================================================================
+
+/// Store a chat message. `original_content` is the text before the chat
+/// filter masked it.
+pub async fn add_party_message(
+    pool: &DbPool,
+    party_id: &str,
+    user_id: &str,
+    content: &str,
+    original_content: Option<&str>,
+) -> Result<PartyMessage, sqlx::Error> {
+    sqlx::query_as::<_, PartyMessage>(
+        r#"
+        WITH inserted AS (
+            INSERT INTO party_messages (party_id, user_id, content, original_content)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, party_id, user_id, content, original_content, created_at
+        )
+        SELECT i.id, i.party_id, i.user_id, u.display_name, i.content, i.original_content,
+               i.created_at
+        FROM inserted i
+        INNER JOIN users u ON u.id = i.user_id
+        "#,
+    )
+    .bind(party_id)
+    .bind(user_id)
+    .bind(content)
+    .bind(original_content)
+    .fetch_one(pool)
+    .await
+}
+
+/// Get the most recent chat messages of a party, oldest first
+pub async fn get_recent_party_messages(
+    pool: &DbPool,
+    party_id: &str,
+    limit: i64,
+) -> Result<Vec<PartyMessage>, sqlx::Error> {
+    sqlx::query_as::<_, PartyMessage>(
+        r#"
+        SELECT m.id, m.party_id, m.user_id, u.display_name, m.content, m.original_content,
+               m.created_at
+        FROM (
+            SELECT id, party_id, user_id, content, original_content, created_at
+            FROM party_messages
+            WHERE party_id = $1
+            ORDER BY id DESC
+            LIMIT $2
+        ) m
+        INNER JOIN users u ON u.id = m.user_id
+        ORDER BY m.id ASC
+        "#,
+    )
+    .bind(party_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+/// Get the chat sent in a game's party while the game ran, oldest first.
+/// Games without a party have no chat.
+pub async fn get_game_chat(pool: &DbPool, game_id: &str) -> Result<Vec<PartyMessage>, sqlx::Error> {
+    sqlx::query_as::<_, PartyMessage>(
+        r#"
+        SELECT m.id, m.party_id, m.user_id, u.display_name, m.content, m.original_content,
+               m.created_at
+        FROM games g
+        INNER JOIN party_messages m ON m.party_id = g.party_id
+        INNER JOIN users u ON u.id = m.user_id
+        WHERE g.id = $1
+          AND m.created_at >= COALESCE(g.started_at, g.created_at)
+          AND m.created_at <= COALESCE(g.ended_at, NOW())
+        ORDER BY m.id ASC
+        "#,
+    )
+    .bind(game_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Delete chat messages older than `days`
+pub async fn delete_party_messages_older_than(
+    pool: &DbPool,
+    days: i32,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        "DELETE FROM party_messages WHERE created_at < NOW() - make_interval(days => $1)",
+    )
+    .bind(days)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Mute or unmute a member in the party chat
+pub async fn set_member_chat_muted(
+    pool: &DbPool,
+    party_id: &str,
+    user_id: &str,
+    muted: bool,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE party_members SET chat_muted = $3 WHERE party_id = $1 AND user_id = $2")
+        .bind(party_id)
+        .bind(user_id)
+        .bind(muted)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
 // =============================================================================
 // Party-game linking
 // =============================================================================
@@ -0,0 +1,235 @@
+//! Player report queries
+//!
+//! Reports about players move through the workflow in
+//! `dguesser_core::reports`. Listings carry a summary of other signals
+//! against the reported player so moderators see repeat offenders.
+
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+use crate::DbPool;
+
+/// A report about a player
+#[derive(Debug, Clone, FromRow)]
+pub struct PlayerReport {
+    pub id: String,                  // prp_XXXXXXXXXXXX
+    pub reporter_id: Option<String>, // usr_XXXXXXXXXXXX
+    pub reported_user_id: String,    // usr_XXXXXXXXXXXX
+    pub category: String,
+    pub game_id: Option<String>,
+    pub chat_excerpt: Option<String>,
+    pub notes: Option<String>,
+    pub status: String,
+    pub resolution_note: Option<String>,
+    pub reviewed_by: Option<String>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A report with names and the reported player's other signals
+#[derive(Debug, Clone, FromRow)]
+pub struct PlayerReportRow {
+    #[sqlx(flatten)]
+    pub report: PlayerReport,
+    pub reporter_name: Option<String>,
+    pub reported_name: String,
+    /// Open reports against the reported player, this one included
+    pub open_reports: i64,
+    /// All reports against the reported player
+    pub total_reports: i64,
+    /// Cheating signals recorded for the reported player
+    pub cheat_signals: i64,
+    /// Whether the reported player's name is waiting for review
+    pub name_flagged: bool,
+}
+
+/// A new report
+#[derive(Debug, Clone, Copy)]
+pub struct NewPlayerReport<'a> {
+    pub reporter_id: &'a str,
+    pub reported_user_id: &'a str,
+    pub category: &'a str,
+    pub game_id: Option<&'a str>,
+    pub chat_excerpt: Option<&'a str>,
+    pub notes: Option<&'a str>,
+}
+
+/// Filters for the admin report queue
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReportFilter<'a> {
+    pub status: Option<&'a str>,
+    pub category: Option<&'a str>,
+    pub reported_user_id: Option<&'a str>,
+}
+
+const REPORT_COLUMNS: &str = "r.id, r.reporter_id, r.reported_user_id, r.category, r.game_id, \
+                              r.chat_excerpt, r.notes, r.status, r.resolution_note, \
+                              r.reviewed_by, r.reviewed_at, r.created_at, r.updated_at";
+
+/// Store a report. Fails with a unique violation if the reporter already has
+/// an open report about the player in this category.
+pub async fn create(
+    pool: &DbPool,
+    report: NewPlayerReport<'_>,
+) -> Result<PlayerReport, sqlx::Error> {
+    sqlx::query_as::<_, PlayerReport>(&format!(
+        r#"
+        INSERT INTO player_reports AS r
+            (id, reporter_id, reported_user_id, category, game_id, chat_excerpt, notes)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING {REPORT_COLUMNS}
+        "#
+    ))
+    .bind(dguesser_core::generate_player_report_id())
+    .bind(report.reporter_id)
+    .bind(report.reported_user_id)
+    .bind(report.category)
+    .bind(report.game_id)
+    .bind(report.chat_excerpt)
+    .bind(report.notes)
+    .fetch_one(pool)
+    .await
+}
+
+/// File a report from the chat filter, which has no reporter. Skipped when
+/// the user already has an open filter report; returns whether one was filed.
+pub async fn create_from_chat_filter(
+    pool: &DbPool,
+    reported_user_id: &str,
+    game_id: Option<&str>,
+    chat_excerpt: &str,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO player_reports (id, reported_user_id, category, game_id, chat_excerpt, notes)
+        SELECT $1, $2, 'abusive_chat', $3, $4, 'Filed automatically by the chat filter'
+        WHERE NOT EXISTS (
+            SELECT 1 FROM player_reports
+            WHERE reporter_id IS NULL
+              AND reported_user_id = $2
+              AND category = 'abusive_chat'
+              AND status IN ('open', 'reviewing')
+        )
+        "#,
+    )
+    .bind(dguesser_core::generate_player_report_id())
+    .bind(reported_user_id)
+    .bind(game_id)
+    .bind(chat_excerpt)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Count reports a user filed since `since`.
+pub async fn count_by_reporter_since(
+    pool: &DbPool,
+    reporter_id: &str,
+    since: DateTime<Utc>,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM player_reports WHERE reporter_id = $1 AND created_at >= $2",
+    )
+    .bind(reporter_id)
+    .bind(since)
+    .fetch_one(pool)
+    .await
+}
+
+/// Get a report.
+pub async fn get(pool: &DbPool, id: &str) -> Result<Option<PlayerReport>, sqlx::Error> {
+    sqlx::query_as::<_, PlayerReport>(&format!(
+        "SELECT {REPORT_COLUMNS} FROM player_reports r WHERE r.id = $1"
+    ))
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// List reports, oldest first, with the total count.
+pub async fn list(
+    pool: &DbPool,
+    filter: ReportFilter<'_>,
+    limit: i64,
+    offset: i64,
+) -> Result<(Vec<PlayerReportRow>, i64), sqlx::Error> {
+    let rows = sqlx::query_as::<_, PlayerReportRow>(&format!(
+        r#"
+        SELECT {REPORT_COLUMNS},
+            reporter.display_name AS reporter_name,
+            reported.display_name AS reported_name,
+            (SELECT COUNT(*) FROM player_reports o
+                WHERE o.reported_user_id = r.reported_user_id
+                  AND o.status IN ('open', 'reviewing')) AS open_reports,
+            (SELECT COUNT(*) FROM player_reports o
+                WHERE o.reported_user_id = r.reported_user_id) AS total_reports,
+            (SELECT COUNT(*) FROM cheat_signals c
+                WHERE c.user_id = r.reported_user_id) AS cheat_signals,
+            EXISTS(SELECT 1 FROM name_flags f
+                WHERE f.user_id = r.reported_user_id AND f.status = 'pending') AS name_flagged
+        FROM player_reports r
+        JOIN users reported ON reported.id = r.reported_user_id
+        LEFT JOIN users reporter ON reporter.id = r.reporter_id
+        WHERE ($1::TEXT IS NULL OR r.status = $1)
+          AND ($2::TEXT IS NULL OR r.category = $2)
+          AND ($3::TEXT IS NULL OR r.reported_user_id = $3)
+        ORDER BY r.created_at, r.id
+        LIMIT $4 OFFSET $5
+        "#
+    ))
+    .bind(filter.status)
+    .bind(filter.category)
+    .bind(filter.reported_user_id)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+
+    let total: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*) FROM player_reports r
+        WHERE ($1::TEXT IS NULL OR r.status = $1)
+          AND ($2::TEXT IS NULL OR r.category = $2)
+          AND ($3::TEXT IS NULL OR r.reported_user_id = $3)
+        "#,
+    )
+    .bind(filter.status)
+    .bind(filter.category)
+    .bind(filter.reported_user_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok((rows, total))
+}
+
+/// Move a report from status `from` to `to`. Returns `None` if the report
+/// is no longer in `from` (another moderator got there first).
+pub async fn update_status(
+    pool: &DbPool,
+    id: &str,
+    from: &str,
+    to: &str,
+    resolution_note: Option<&str>,
+    moderator_id: &str,
+) -> Result<Option<PlayerReport>, sqlx::Error> {
+    sqlx::query_as::<_, PlayerReport>(&format!(
+        r#"
+        UPDATE player_reports r SET
+            status = $3,
+            resolution_note = COALESCE($4, r.resolution_note),
+            reviewed_by = $5,
+            reviewed_at = NOW(),
+            updated_at = NOW()
+        WHERE r.id = $1 AND r.status = $2
+        RETURNING {REPORT_COLUMNS}
+        "#
+    ))
+    .bind(id)
+    .bind(from)
+    .bind(to)
+    .bind(resolution_note)
+    .bind(moderator_id)
+    .fetch_optional(pool)
+    .await
+}
@@ -1,8 +1,11 @@
 //! Database connection pool setup
 
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
 use sqlx::PgPool;
 use sqlx::postgres::PgPoolOptions;
-use std::time::Duration;
 
 /// Type alias for the PostgreSQL connection pool
 pub type DbPool = PgPool;
@@ -17,3 +20,102 @@ pub async fn create_pool(database_url: &str) -> Result<DbPool, sqlx::Error> {
         .connect(database_url)
         .await
 }
+
+/// Primary pool plus an optional read replica
+///
+/// Writes and reads that must see them always go to the primary. Heavy read
+/// paths (leaderboards, history, admin lists, stats) use [`DbPools::reader`],
+/// which falls back to the primary while the replica is lagging or
+/// unreachable.
+#[derive(Clone)]
+pub struct DbPools {
+    primary: DbPool,
+    replica: Option<Arc<Replica>>,
+}
+
+struct Replica {
+    pool: DbPool,
+    max_lag: Duration,
+    /// Whether the last lag check was within `max_lag`
+    healthy: AtomicBool,
+}
+
+impl DbPools {
+    /// Connect to the primary and, if a URL is given, the read replica
+    pub async fn connect(
+        primary_url: &str,
+        replica_url: Option<&str>,
+        max_lag: Duration,
+    ) -> Result<Self, sqlx::Error> {
+        let primary = create_pool(primary_url).await?;
+        let replica = match replica_url {
+            Some(url) => Some(Arc::new(Replica {
+                pool: create_pool(url).await?,
+                max_lag,
+                healthy: AtomicBool::new(true),
+            })),
+            None => None,
+        };
+        Ok(Self { primary, replica })
+    }
+
+    /// Pool for writes and read-your-writes queries
+    pub fn primary(&self) -> &DbPool {
+        &self.primary
+    }
+
+    /// Pool for heavy reads that tolerate slightly stale data
+    pub fn reader(&self) -> &DbPool {
+        match &self.replica {
+            Some(replica) if replica.healthy.load(Ordering::Relaxed) => &replica.pool,
+            _ => &self.primary,
+        }
+    }
+
+    /// Whether a replica is configured
+    pub fn has_replica(&self) -> bool {
+        self.replica.is_some()
+    }
+
+    /// Whether reads are currently routed to the replica
+    pub fn replica_in_use(&self) -> bool {
+        self.replica.as_ref().is_some_and(|replica| replica.healthy.load(Ordering::Relaxed))
+    }
+
+    /// Measure replication lag and route reads accordingly
+    ///
+    /// Returns the measured lag, or `None` without a replica. A replica that
+    /// can't be queried is treated as lagging.
+    pub async fn check_replica_lag(&self) -> Option<Result<Duration, sqlx::Error>> {
+        let replica = self.replica.as_ref()?;
+        let lag = replica_lag(&replica.pool).await;
+        let healthy = matches!(&lag, Ok(lag) if *lag <= replica.max_lag);
+        let was_healthy = replica.healthy.swap(healthy, Ordering::Relaxed);
+        if was_healthy != healthy {
+            if healthy {
+                tracing::info!("Read replica caught up, routing reads to it again");
+            } else {
+                tracing::warn!("Read replica lagging or unreachable, routing reads to primary");
+            }
+        }
+        Some(lag)
+    }
+}
+
+/// Replication lag of a standby. Zero when it has replayed everything it
+/// received, so an idle primary doesn't look like lag.
+async fn replica_lag(pool: &DbPool) -> Result<Duration, sqlx::Error> {
+    let seconds: Option<f64> = sqlx::query_scalar(
+        r#"
+        SELECT CASE
+            WHEN NOT pg_is_in_recovery() THEN 0
+            WHEN pg_last_wal_receive_lsn() = pg_last_wal_replay_lsn() THEN 0
+            ELSE EXTRACT(EPOCH FROM (NOW() - pg_last_xact_replay_timestamp()))
+        END::float8
+        "#,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(Duration::from_secs_f64(seconds.unwrap_or(0.0).max(0.0)))
+}
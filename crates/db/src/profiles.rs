@@ -0,0 +1,311 @@
+//! User profile customization queries
+
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+use crate::DbPool;
+
+/// Customizable profile fields (all empty until the user sets them)
+#[derive(Debug, Clone, Default, FromRow)]
+pub struct UserProfile {
+    pub user_id: String,
+    pub bio: Option<String>,
+    /// ISO 3166-1 alpha-2 home country
+    pub country_code: Option<String>,
+    /// `#rrggbb`
+    pub profile_color: Option<String>,
+    /// Object storage key of an uploaded avatar
+    pub avatar_key: Option<String>,
+    /// Set by moderators; the user can't edit the profile while locked
+    pub locked: bool,
+    pub moderated_at: Option<DateTime<Utc>>,
+    pub moderated_by: Option<String>,
+    /// Who sees the user's presence: "everyone", "friends" or "nobody"
+    /// (empty when never customized, meaning "friends")
+    pub presence_visibility: String,
+}
+
+/// Profile fields to change. `None` leaves a field alone, `Some(None)`
+/// clears it.
+#[derive(Debug, Clone, Default)]
+pub struct ProfileUpdate<'a> {
+    pub bio: Option<Option<&'a str>>,
+    pub country_code: Option<Option<&'a str>>,
+    pub profile_color: Option<Option<&'a str>>,
+    pub presence_visibility: Option<&'a str>,
+}
+
+const PROFILE_COLUMNS: &str = "user_id, bio, country_code, profile_color, avatar_key, locked, \
+                               moderated_at, moderated_by, presence_visibility";
+
+/// Get a user's profile fields (defaults when never customized)
+pub async fn get(pool: &DbPool, user_id: &str) -> Result<UserProfile, sqlx::Error> {
+    let profile = sqlx::query_as::<_, UserProfile>(&format!(
+        "SELECT {PROFILE_COLUMNS} FROM user_profiles WHERE user_id = $1"
+    ))
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(profile
+        .unwrap_or_else(|| UserProfile { user_id: user_id.to_string(), ..Default::default() }))
+}
+
+/// Update bio, country, color and presence visibility
+pub async fn update(
+    pool: &DbPool,
+    user_id: &str,
+    update: &ProfileUpdate<'_>,
+) -> Result<UserProfile, sqlx::Error> {
+    sqlx::query_as::<_, UserProfile>(&format!(
+        r#"
+        INSERT INTO user_profiles (user_id, bio, country_code, profile_color, presence_visibility)
+        VALUES ($1, $3, $5, $7, COALESCE($8, 'friends'))
+        ON CONFLICT (user_id) DO UPDATE SET
+            bio = CASE WHEN $2 THEN EXCLUDED.bio ELSE user_profiles.bio END,
+            country_code = CASE WHEN $4 THEN EXCLUDED.country_code
+                                ELSE user_profiles.country_code END,
+            profile_color = CASE WHEN $6 THEN EXCLUDED.profile_color
+                                 ELSE user_profiles.profile_color END,
+            presence_visibility = COALESCE($8, user_profiles.presence_visibility),
+            updated_at = NOW()
+        RETURNING {PROFILE_COLUMNS}
+        "#
+    ))
+    .bind(user_id)
+    .bind(update.bio.is_some())
+    .bind(update.bio.flatten())
+    .bind(update.country_code.is_some())
+    .bind(update.country_code.flatten())
+    .bind(update.profile_color.is_some())
+    .bind(update.profile_color.flatten())
+    .bind(update.presence_visibility)
+    .fetch_one(pool)
+    .await
+}
+
+/// Get the presence visibility of several users, as (user_id, visibility)
+/// pairs. Users who never changed it are missing and default to "friends".
+pub async fn get_presence_visibilities(
+    pool: &DbPool,
+    user_ids: &[String],
+) -> Result<Vec<(String, String)>, sqlx::Error> {
+    if user_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    sqlx::query_as::<_, (String, String)>(
+        "SELECT user_id, presence_visibility FROM user_profiles WHERE user_id = ANY($1)",
+    )
+    .bind(user_ids)
+    .fetch_all(pool)
+    .await
+}
+
+/// Aggregate guess stats shown on public profiles
+#[derive(Debug, Clone, Default, FromRow)]
+pub struct GuessStats {
+    /// Rounds the user submitted a guess in
+    pub rounds_played: i64,
+    /// Mean guess distance, excluding timed-out rounds
+    pub average_distance_meters: Option<f64>,
+}
+
+/// Get a user's guess stats across finished games
+pub async fn get_guess_stats(pool: &DbPool, user_id: &str) -> Result<GuessStats, sqlx::Error> {
+    sqlx::query_as::<_, GuessStats>(
+        r#"
+        SELECT
+            COUNT(*) FILTER (WHERE gu.distance_meters >= 0) as rounds_played,
+            AVG(gu.distance_meters) FILTER (WHERE gu.distance_meters >= 0)
+                as average_distance_meters
+        FROM guesses gu
+        INNER JOIN rounds r ON r.id = gu.round_id
+        INNER JOIN games g ON g.id = r.game_id
+        WHERE gu.user_id = $1 AND g.status = 'finished' AND g.mode <> 'streak'
+        "#,
+    )
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+}
+
+/// Point the user's avatar at an uploaded object, returning the key of the
+/// previously uploaded avatar (if any) so it can be deleted
+pub async fn set_uploaded_avatar(
+    pool: &DbPool,
+    user_id: &str,
+    avatar_key: Option<&str>,
+    avatar_url: Option<&str>,
+) -> Result<Option<String>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let previous: Option<Option<String>> =
+        sqlx::query_scalar("SELECT avatar_key FROM user_profiles WHERE user_id = $1 FOR UPDATE")
+            .bind(user_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO user_profiles (user_id, avatar_key)
+        VALUES ($1, $2)
+        ON CONFLICT (user_id) DO UPDATE SET avatar_key = EXCLUDED.avatar_key, updated_at = NOW()
+        "#,
+    )
+    .bind(user_id)
+    .bind(avatar_key)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("UPDATE users SET avatar_url = $2 WHERE id = $1 AND deleted_at IS NULL")
+        .bind(user_id)
+        .bind(avatar_url)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(previous.flatten())
+}
+
+/// Profile content a moderator can reset
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProfileReset {
+    pub avatar: bool,
+    pub bio: bool,
+    pub display_name: bool,
+    pub country: bool,
+    pub color: bool,
+}
+
+/// Reset profile content and record the moderation, optionally changing the
+/// lock. Returns the profile before the reset (so an uploaded avatar can be
+/// deleted), or `None` if the user doesn't exist.
+pub async fn moderate(
+    pool: &DbPool,
+    user_id: &str,
+    moderator_id: &str,
+    reset: ProfileReset,
+    locked: Option<bool>,
+) -> Result<Option<UserProfile>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let exists: Option<String> =
+        sqlx::query_scalar("SELECT id FROM users WHERE id = $1 AND deleted_at IS NULL FOR UPDATE")
+            .bind(user_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+    if exists.is_none() {
+        return Ok(None);
+    }
+
+    let previous = sqlx::query_as::<_, UserProfile>(&format!(
+        "SELECT {PROFILE_COLUMNS} FROM user_profiles WHERE user_id = $1"
+    ))
+    .bind(user_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .unwrap_or_else(|| UserProfile { user_id: user_id.to_string(), ..Default::default() });
+
+    sqlx::query(
+        r#"
+        INSERT INTO user_profiles (user_id, locked, moderated_at, moderated_by)
+        VALUES ($1, COALESCE($6, FALSE), NOW(), $7)
+        ON CONFLICT (user_id) DO UPDATE SET
+            avatar_key = CASE WHEN $2 THEN NULL ELSE user_profiles.avatar_key END,
+            bio = CASE WHEN $3 THEN NULL ELSE user_profiles.bio END,
+            country_code = CASE WHEN $4 THEN NULL ELSE user_profiles.country_code END,
+            profile_color = CASE WHEN $5 THEN NULL ELSE user_profiles.profile_color END,
+            locked = COALESCE($6, user_profiles.locked),
+            moderated_at = NOW(),
+            moderated_by = $7,
+            updated_at = NOW()
+        "#,
+    )
+    .bind(user_id)
+    .bind(reset.avatar)
+    .bind(reset.bio)
+    .bind(reset.country)
+    .bind(reset.color)
+    .bind(locked)
+    .bind(moderator_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE users SET
+            avatar_url = CASE WHEN $2 THEN NULL ELSE avatar_url END,
+            display_name = CASE WHEN $3 THEN 'Player ' || RIGHT(id, 4) ELSE display_name END
+        WHERE id = $1
+        "#,
+    )
+    .bind(user_id)
+    .bind(reset.avatar)
+    .bind(reset.display_name)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(Some(previous))
+}
+
+/// Set a user's display name on a moderator's behalf (a generic name when
+/// `display_name` is `None`), record the moderation, optionally change the
+/// lock, and resolve any pending name flag. Returns the new name and lock
+/// state, or `None` if the user doesn't exist.
+pub async fn force_rename(
+    pool: &DbPool,
+    user_id: &str,
+    moderator_id: &str,
+    display_name: Option<&str>,
+    locked: Option<bool>,
+) -> Result<Option<(String, bool)>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let name: Option<String> = sqlx::query_scalar(
+        r#"
+        UPDATE users SET display_name = COALESCE($2, 'Player ' || RIGHT(id, 4))
+        WHERE id = $1 AND deleted_at IS NULL
+        RETURNING display_name
+        "#,
+    )
+    .bind(user_id)
+    .bind(display_name)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some(name) = name else {
+        return Ok(None);
+    };
+
+    let locked: bool = sqlx::query_scalar(
+        r#"
+        INSERT INTO user_profiles (user_id, locked, moderated_at, moderated_by)
+        VALUES ($1, COALESCE($2, FALSE), NOW(), $3)
+        ON CONFLICT (user_id) DO UPDATE SET
+            locked = COALESCE($2, user_profiles.locked),
+            moderated_at = NOW(),
+            moderated_by = $3,
+            updated_at = NOW()
+        RETURNING locked
+        "#,
+    )
+    .bind(user_id)
+    .bind(locked)
+    .bind(moderator_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE name_flags SET status = 'renamed', resolved_at = NOW(), resolved_by = $2
+        WHERE user_id = $1 AND status = 'pending'
+        "#,
+    )
+    .bind(user_id)
+    .bind(moderator_id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(Some((name, locked)))
+}
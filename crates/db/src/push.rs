@@ -0,0 +1,330 @@
+//! Web Push subscription, outbox, and notification preference queries
+
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+use crate::DbPool;
+
+/// How long a claimed notification may stay in `sending` before it is
+/// considered abandoned (e.g. the worker crashed) and becomes eligible again.
+const SENDING_LEASE_SECS: i64 = 300;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct PushSubscription {
+    pub id: String,      // psb_XXXXXXXXXXXX
+    pub user_id: String, // usr_XXXXXXXXXXXX
+    pub endpoint: String,
+    pub p256dh: String,
+    pub auth: String,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// A queued notification joined with the subscription it is addressed to.
+#[derive(Debug, Clone, FromRow)]
+pub struct QueuedPush {
+    pub id: i64,
+    pub subscription_id: String,
+    pub kind: String,
+    pub payload: serde_json::Value,
+    pub ttl_secs: i32,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub created_at: DateTime<Utc>,
+    pub endpoint: String,
+    pub p256dh: String,
+    pub auth: String,
+}
+
+/// Which notifications a user wants. Every kind is on by default.
+#[derive(Debug, Clone, FromRow)]
+pub struct NotificationPreferences {
+    pub challenge_turns: bool,
+    pub friend_invites: bool,
+    pub daily_challenge: bool,
+}
+
+impl Default for NotificationPreferences {
+    fn default() -> Self {
+        Self { challenge_turns: true, friend_invites: true, daily_challenge: true }
+    }
+}
+
+/// Register a subscription, or move an existing endpoint to this user.
+pub async fn upsert_subscription(
+    pool: &DbPool,
+    user_id: &str,
+    endpoint: &str,
+    p256dh: &str,
+    auth: &str,
+    user_agent: Option<&str>,
+) -> Result<PushSubscription, sqlx::Error> {
+    let id = dguesser_core::generate_push_subscription_id();
+
+    sqlx::query_as::<_, PushSubscription>(
+        r#"
+        INSERT INTO push_subscriptions (id, user_id, endpoint, p256dh, auth, user_agent)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (endpoint) DO UPDATE
+        SET user_id = EXCLUDED.user_id,
+            p256dh = EXCLUDED.p256dh,
+            auth = EXCLUDED.auth,
+            user_agent = EXCLUDED.user_agent
+        RETURNING id, user_id, endpoint, p256dh, auth, user_agent, created_at, last_used_at
+        "#,
+    )
+    .bind(&id)
+    .bind(user_id)
+    .bind(endpoint)
+    .bind(p256dh)
+    .bind(auth)
+    .bind(user_agent)
+    .fetch_one(pool)
+    .await
+}
+
+/// Delete all but the newest `keep` subscriptions of a user.
+pub async fn prune_subscriptions(
+    pool: &DbPool,
+    user_id: &str,
+    keep: i64,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM push_subscriptions
+        WHERE user_id = $1 AND id NOT IN (
+            SELECT id FROM push_subscriptions
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2
+        )
+        "#,
+    )
+    .bind(user_id)
+    .bind(keep)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Remove a user's subscription by endpoint. Returns whether it existed.
+pub async fn delete_subscription(
+    pool: &DbPool,
+    user_id: &str,
+    endpoint: &str,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM push_subscriptions WHERE user_id = $1 AND endpoint = $2")
+        .bind(user_id)
+        .bind(endpoint)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Remove a subscription the push service reported as gone.
+pub async fn delete_subscription_by_id(pool: &DbPool, id: &str) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM push_subscriptions WHERE id = $1").bind(id).execute(pool).await?;
+    Ok(())
+}
+
+/// Queue a notification for every subscription of a user who allows `kind`.
+/// Returns how many notifications were queued.
+pub async fn enqueue_for_user(
+    pool: &DbPool,
+    user_id: &str,
+    kind: &str,
+    payload: &serde_json::Value,
+    ttl_secs: i32,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO push_outbox (subscription_id, kind, payload, ttl_secs)
+        SELECT s.id, $2, $3, $4
+        FROM push_subscriptions s
+        LEFT JOIN notification_preferences p ON p.user_id = s.user_id
+        WHERE s.user_id = $1
+          AND CASE $2
+                WHEN 'challenge_turn' THEN COALESCE(p.challenge_turns, TRUE)
+                WHEN 'friend_invite' THEN COALESCE(p.friend_invites, TRUE)
+                WHEN 'daily_challenge' THEN COALESCE(p.daily_challenge, TRUE)
+                ELSE FALSE
+              END
+        "#,
+    )
+    .bind(user_id)
+    .bind(kind)
+    .bind(payload)
+    .bind(ttl_secs)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Queue a notification for every subscription whose user allows `kind`.
+/// Returns how many notifications were queued.
+pub async fn enqueue_for_all(
+    pool: &DbPool,
+    kind: &str,
+    payload: &serde_json::Value,
+    ttl_secs: i32,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO push_outbox (subscription_id, kind, payload, ttl_secs)
+        SELECT s.id, $1, $2, $3
+        FROM push_subscriptions s
+        LEFT JOIN notification_preferences p ON p.user_id = s.user_id
+        WHERE CASE $1
+                WHEN 'challenge_turn' THEN COALESCE(p.challenge_turns, TRUE)
+                WHEN 'friend_invite' THEN COALESCE(p.friend_invites, TRUE)
+                WHEN 'daily_challenge' THEN COALESCE(p.daily_challenge, TRUE)
+                ELSE FALSE
+              END
+        "#,
+    )
+    .bind(kind)
+    .bind(payload)
+    .bind(ttl_secs)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Claim up to `limit` due notifications for delivery.
+///
+/// Works like [`crate::emails::claim_due`]: claimed rows move to `sending`
+/// under a lease so concurrent workers skip them.
+pub async fn claim_due(pool: &DbPool, limit: i64) -> Result<Vec<QueuedPush>, sqlx::Error> {
+    sqlx::query_as::<_, QueuedPush>(
+        r#"
+        WITH claimed AS (
+            UPDATE push_outbox
+            SET status = 'sending',
+                attempts = attempts + 1,
+                next_attempt_at = NOW() + make_interval(secs => $2)
+            WHERE id IN (
+                SELECT id FROM push_outbox
+                WHERE status IN ('pending', 'sending') AND next_attempt_at <= NOW()
+                ORDER BY next_attempt_at
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, subscription_id, kind, payload, ttl_secs, attempts, max_attempts,
+                      created_at
+        )
+        SELECT c.id, c.subscription_id, c.kind, c.payload, c.ttl_secs, c.attempts,
+               c.max_attempts, c.created_at, s.endpoint, s.p256dh, s.auth
+        FROM claimed c
+        INNER JOIN push_subscriptions s ON s.id = c.subscription_id
+        "#,
+    )
+    .bind(limit)
+    .bind(SENDING_LEASE_SECS as f64)
+    .fetch_all(pool)
+    .await
+}
+
+/// Mark a notification as delivered.
+pub async fn mark_sent(pool: &DbPool, id: i64, subscription_id: &str) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE push_outbox SET status = 'sent', sent_at = NOW(), last_error = NULL WHERE id = $1",
+    )
+    .bind(id)
+    .execute(pool)
+    .await?;
+    sqlx::query("UPDATE push_subscriptions SET last_used_at = NOW() WHERE id = $1")
+        .bind(subscription_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Record a failed delivery attempt.
+///
+/// With `retry_at` the notification goes back to `pending`; without it the
+/// notification is given up on and marked `failed`.
+pub async fn mark_failed(
+    pool: &DbPool,
+    id: i64,
+    error: &str,
+    retry_at: Option<DateTime<Utc>>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE push_outbox
+        SET status = CASE WHEN $3::timestamptz IS NULL THEN 'failed' ELSE 'pending' END,
+            next_attempt_at = COALESCE($3, next_attempt_at),
+            last_error = $2
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .bind(error)
+    .bind(retry_at)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Delete sent or failed notifications older than `days` (call periodically).
+pub async fn cleanup_finished(pool: &DbPool, days: i32) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM push_outbox
+        WHERE status IN ('sent', 'failed')
+          AND created_at < NOW() - make_interval(days => $1)
+        "#,
+    )
+    .bind(days)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Get a user's notification preferences, or the defaults if never set.
+pub async fn get_preferences(
+    pool: &DbPool,
+    user_id: &str,
+) -> Result<NotificationPreferences, sqlx::Error> {
+    let prefs = sqlx::query_as::<_, NotificationPreferences>(
+        r#"
+        SELECT challenge_turns, friend_invites, daily_challenge
+        FROM notification_preferences
+        WHERE user_id = $1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(prefs.unwrap_or_default())
+}
+
+/// Update a user's notification preferences; `None` leaves a setting as is.
+pub async fn update_preferences(
+    pool: &DbPool,
+    user_id: &str,
+    challenge_turns: Option<bool>,
+    friend_invites: Option<bool>,
+    daily_challenge: Option<bool>,
+) -> Result<NotificationPreferences, sqlx::Error> {
+    sqlx::query_as::<_, NotificationPreferences>(
+        r#"
+        INSERT INTO notification_preferences (
+            user_id, challenge_turns, friend_invites, daily_challenge
+        )
+        VALUES ($1, COALESCE($2, TRUE), COALESCE($3, TRUE), COALESCE($4, TRUE))
+        ON CONFLICT (user_id) DO UPDATE
+        SET challenge_turns = COALESCE($2, notification_preferences.challenge_turns),
+            friend_invites = COALESCE($3, notification_preferences.friend_invites),
+            daily_challenge = COALESCE($4, notification_preferences.daily_challenge)
+        RETURNING challenge_turns, friend_invites, daily_challenge
+        "#,
+    )
+    .bind(user_id)
+    .bind(challenge_turns)
+    .bind(friend_invites)
+    .bind(daily_challenge)
+    .fetch_one(pool)
+    .await
+}
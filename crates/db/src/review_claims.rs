@@ -0,0 +1,203 @@
+//! Review queue assignment queries
+//!
+//! A reviewer claims a batch of queued locations, which hides them from
+//! other reviewers' batches until the claim expires or the reviewer decides
+//! on the location. Every decision is logged for per-reviewer throughput.
+
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+use crate::DbPool;
+
+/// An active claim on a queued location
+#[derive(Debug, Clone, FromRow)]
+pub struct ReviewClaim {
+    pub location_id: String, // loc_XXXXXXXXXXXX
+    pub reviewer_id: String, // usr_XXXXXXXXXXXX
+    pub reviewer_name: Option<String>,
+    pub claimed_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Review throughput for one reviewer
+#[derive(Debug, Clone, FromRow)]
+pub struct ReviewerStats {
+    pub reviewer_id: String,
+    pub reviewer_name: Option<String>,
+    pub reviewed: i64,
+    pub approved: i64,
+    pub rejected: i64,
+    pub flagged: i64,
+    /// Locations the reviewer currently has claimed
+    pub in_progress: i64,
+    /// Mean time from claim to decision, over claimed decisions only
+    pub avg_review_secs: Option<f64>,
+}
+
+const CLAIM_COLUMNS: &str = "c.location_id, c.reviewer_id, u.display_name AS reviewer_name, \
+                             c.claimed_at, c.expires_at";
+
+/// Claim up to `count` unclaimed locations from the front of the review
+/// queue for `minutes`. Returns the claimed location IDs in queue order.
+///
+/// Rows another reviewer is claiming at the same moment are skipped, so two
+/// concurrent claims never hand out the same location.
+pub async fn claim_batch(
+    pool: &DbPool,
+    reviewer_id: &str,
+    count: i64,
+    minutes: i32,
+) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        WITH picked AS (
+            SELECT l.id, l.failure_count, l.created_at
+            FROM locations l
+            WHERE (l.review_status IN ('pending', 'flagged') OR l.failure_count >= 2)
+              AND NOT EXISTS (
+                  SELECT 1 FROM location_review_claims c
+                  WHERE c.location_id = l.id AND c.expires_at > NOW()
+              )
+            ORDER BY l.failure_count DESC, l.created_at ASC
+            LIMIT $2
+            FOR UPDATE OF l SKIP LOCKED
+        ),
+        claimed AS (
+            INSERT INTO location_review_claims (location_id, reviewer_id, claimed_at, expires_at)
+            SELECT id, $1, NOW(), NOW() + make_interval(mins => $3) FROM picked
+            ON CONFLICT (location_id) DO UPDATE
+                SET reviewer_id = EXCLUDED.reviewer_id,
+                    claimed_at = EXCLUDED.claimed_at,
+                    expires_at = EXCLUDED.expires_at
+                WHERE location_review_claims.expires_at <= NOW()
+            RETURNING location_id
+        )
+        SELECT p.id FROM picked p JOIN claimed c ON c.location_id = p.id
+        ORDER BY p.failure_count DESC, p.created_at ASC
+        "#,
+    )
+    .bind(reviewer_id)
+    .bind(count)
+    .bind(minutes)
+    .fetch_all(pool)
+    .await
+}
+
+/// Number of locations a reviewer currently has claimed.
+pub async fn count_active(pool: &DbPool, reviewer_id: &str) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM location_review_claims WHERE reviewer_id = $1 AND expires_at > NOW()",
+    )
+    .bind(reviewer_id)
+    .fetch_one(pool)
+    .await
+}
+
+/// Active claims on any of the given locations.
+pub async fn active_for_locations(
+    pool: &DbPool,
+    location_ids: &[String],
+) -> Result<Vec<ReviewClaim>, sqlx::Error> {
+    if location_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    sqlx::query_as::<_, ReviewClaim>(&format!(
+        "SELECT {CLAIM_COLUMNS} FROM location_review_claims c \
+         LEFT JOIN users u ON u.id = c.reviewer_id \
+         WHERE c.location_id = ANY($1) AND c.expires_at > NOW()"
+    ))
+    .bind(location_ids)
+    .fetch_all(pool)
+    .await
+}
+
+/// The active claim on a location, if any.
+pub async fn get_active(
+    pool: &DbPool,
+    location_id: &str,
+) -> Result<Option<ReviewClaim>, sqlx::Error> {
+    Ok(active_for_locations(pool, &[location_id.to_string()]).await?.pop())
+}
+
+/// Drop all of a reviewer's claims. Returns how many were active.
+pub async fn release_all(pool: &DbPool, reviewer_id: &str) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        "DELETE FROM location_review_claims WHERE reviewer_id = $1 AND expires_at > NOW()",
+    )
+    .bind(reviewer_id)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Log a review decision and drop any claim on the location. The decision
+/// keeps the reviewer's claim time so throughput can measure review time.
+pub async fn record_decision(
+    pool: &DbPool,
+    location_id: &str,
+    reviewer_id: &str,
+    status: &str,
+    notes: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        WITH released AS (
+            DELETE FROM location_review_claims WHERE location_id = $1
+            RETURNING reviewer_id, claimed_at
+        )
+        INSERT INTO location_review_decisions (location_id, reviewer_id, status, notes, claimed_at)
+        VALUES ($1, $2, $3, $4, (SELECT claimed_at FROM released WHERE reviewer_id = $2))
+        "#,
+    )
+    .bind(location_id)
+    .bind(reviewer_id)
+    .bind(status)
+    .bind(notes)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Throughput per reviewer for decisions since `since`, including reviewers
+/// who only have locations in progress. Busiest reviewers first.
+pub async fn reviewer_stats(
+    pool: &DbPool,
+    since: DateTime<Utc>,
+) -> Result<Vec<ReviewerStats>, sqlx::Error> {
+    sqlx::query_as::<_, ReviewerStats>(
+        r#"
+        WITH decided AS (
+            SELECT reviewer_id,
+                   COUNT(*) AS reviewed,
+                   COUNT(*) FILTER (WHERE status = 'approved') AS approved,
+                   COUNT(*) FILTER (WHERE status = 'rejected') AS rejected,
+                   COUNT(*) FILTER (WHERE status = 'flagged') AS flagged,
+                   AVG(EXTRACT(EPOCH FROM decided_at - claimed_at))::FLOAT8 AS avg_review_secs
+            FROM location_review_decisions
+            WHERE decided_at >= $1 AND reviewer_id IS NOT NULL
+            GROUP BY reviewer_id
+        ),
+        claimed AS (
+            SELECT reviewer_id, COUNT(*) AS in_progress
+            FROM location_review_claims
+            WHERE expires_at > NOW()
+            GROUP BY reviewer_id
+        )
+        SELECT COALESCE(d.reviewer_id, c.reviewer_id) AS reviewer_id,
+               u.display_name AS reviewer_name,
+               COALESCE(d.reviewed, 0) AS reviewed,
+               COALESCE(d.approved, 0) AS approved,
+               COALESCE(d.rejected, 0) AS rejected,
+               COALESCE(d.flagged, 0) AS flagged,
+               COALESCE(c.in_progress, 0) AS in_progress,
+               d.avg_review_secs
+        FROM decided d
+        FULL OUTER JOIN claimed c ON c.reviewer_id = d.reviewer_id
+        LEFT JOIN users u ON u.id = COALESCE(d.reviewer_id, c.reviewer_id)
+        ORDER BY reviewed DESC, in_progress DESC
+        "#,
+    )
+    .bind(since)
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,58 @@
+//! Round hint queries
+//!
+//! Hints a player took during a round, kept so singleplayer rounds can be
+//! rebuilt with them and finished games show what each player was told.
+
+use std::collections::HashMap;
+
+use dguesser_core::game::Hint;
+
+use crate::DbPool;
+
+/// Record a hint a player took. Taking the same kind twice in a round keeps
+/// the first.
+pub async fn record_hint(
+    pool: &DbPool,
+    round_id: &str,
+    user_id: &str,
+    hint: &Hint,
+    cost: u32,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO round_hints (round_id, user_id, kind, hint, cost)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (round_id, user_id, kind) DO NOTHING
+        "#,
+    )
+    .bind(round_id)
+    .bind(user_id)
+    .bind(hint.kind().as_str())
+    .bind(serde_json::to_value(hint).unwrap_or_default())
+    .bind(cost as i32)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Get the hints taken in a round, per player in the order they were taken.
+/// Rows that no longer parse are skipped.
+pub async fn get_hints_for_round(
+    pool: &DbPool,
+    round_id: &str,
+) -> Result<HashMap<String, Vec<Hint>>, sqlx::Error> {
+    let rows: Vec<(String, serde_json::Value)> = sqlx::query_as(
+        "SELECT user_id, hint FROM round_hints WHERE round_id = $1 ORDER BY created_at, kind",
+    )
+    .bind(round_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut hints: HashMap<String, Vec<Hint>> = HashMap::new();
+    for (user_id, hint) in rows {
+        if let Ok(hint) = serde_json::from_value(hint) {
+            hints.entry(user_id).or_default().push(hint);
+        }
+    }
+    Ok(hints)
+}
@@ -0,0 +1,101 @@
+//! Round reroll queries
+//!
+//! A host can replace the location of a round that just started, e.g. when
+//! the panorama does not load. The round keeps its ID and number; the old
+//! location is kept in `round_rerolls` and the round's guesses are discarded.
+
+use dguesser_core::game::LocationData;
+
+use crate::DbPool;
+
+/// Replace a round's location and restart its timer.
+///
+/// Records the old location, deletes the round's guesses and takes the points
+/// they scored back off the players' totals. `refunds` holds the points (after
+/// handicap) each player got for their discarded guess.
+pub async fn reroll_round(
+    pool: &DbPool,
+    game_id: &str,
+    round_id: &str,
+    rerolled_by: &str,
+    location: &LocationData,
+    time_limit_ms: Option<i32>,
+    refunds: &[(String, i32)],
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let discarded = sqlx::query("DELETE FROM guesses WHERE round_id = $1")
+        .bind(round_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    // Hints were about the old location
+    sqlx::query("DELETE FROM round_hints WHERE round_id = $1")
+        .bind(round_id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO round_rerolls (
+            game_id, round_id, round_number, rerolled_by, previous_lat, previous_lng,
+            previous_panorama_id, previous_location_id, discarded_guesses
+        )
+        SELECT game_id, id, round_number, $2, location_lat, location_lng,
+               panorama_id, location_id, $3
+        FROM rounds
+        WHERE id = $1
+        "#,
+    )
+    .bind(round_id)
+    .bind(rerolled_by)
+    .bind(discarded as i32)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE rounds
+        SET location_lat = $2, location_lng = $3, panorama_id = $4, location_id = $5,
+            heading = $6, provider = $7, country_code = $8, time_limit_ms = $9,
+            started_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(round_id)
+    .bind(location.lat)
+    .bind(location.lng)
+    .bind(location.panorama_id.as_deref())
+    .bind(location.location_id.as_deref())
+    .bind(location.heading)
+    .bind(location.provider.as_str())
+    .bind(location.country_code.as_deref())
+    .bind(time_limit_ms)
+    .execute(&mut *tx)
+    .await?;
+
+    for (user_id, points) in refunds {
+        sqlx::query(
+            r#"
+            UPDATE game_players SET score_total = score_total - $3
+            WHERE game_id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(game_id)
+        .bind(user_id)
+        .bind(points)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await
+}
+
+/// Number of rerolls used in a game.
+pub async fn count_for_game(pool: &DbPool, game_id: &str) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar("SELECT COUNT(*) FROM round_rerolls WHERE game_id = $1")
+        .bind(game_id)
+        .fetch_one(pool)
+        .await
+}
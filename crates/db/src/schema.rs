@@ -0,0 +1,132 @@
+//! Schema migrations
+//!
+//! The migrations in the workspace `migrations/` directory are embedded here
+//! so every binary applies and checks the same set.
+
+use std::collections::HashMap;
+
+use sqlx::migrate::{AppliedMigration, Migrate, MigrateError, Migration, Migrator};
+
+use crate::DbPool;
+
+/// Migrations embedded from the workspace `migrations/` directory
+pub static MIGRATOR: Migrator = sqlx::migrate!("../../migrations");
+
+/// How the database schema compares to the embedded migrations
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemaReport {
+    /// Applied migrations
+    pub applied: usize,
+    /// Migrations not applied yet, as (version, description)
+    pub pending: Vec<(i64, String)>,
+    /// Applied migrations whose file changed since
+    pub modified: Vec<i64>,
+    /// Applied migrations this build doesn't know about (a newer build ran)
+    pub unknown: Vec<i64>,
+    /// A migration that failed part way
+    pub dirty: Option<i64>,
+}
+
+impl SchemaReport {
+    /// Whether the schema matches the embedded migrations exactly
+    pub fn is_current(&self) -> bool {
+        self.pending.is_empty()
+            && self.modified.is_empty()
+            && self.unknown.is_empty()
+            && self.dirty.is_none()
+    }
+
+    /// Compare applied migrations with the expected ones.
+    fn compare(expected: &[Migration], applied: &[AppliedMigration], dirty: Option<i64>) -> Self {
+        let expected: Vec<&Migration> =
+            expected.iter().filter(|m| m.migration_type.is_up_migration()).collect();
+        let applied_checksums: HashMap<i64, &[u8]> =
+            applied.iter().map(|m| (m.version, m.checksum.as_ref())).collect();
+
+        let mut report = Self { applied: applied.len(), dirty, ..Self::default() };
+        for migration in &expected {
+            match applied_checksums.get(&migration.version) {
+                None => report.pending.push((migration.version, migration.description.to_string())),
+                Some(checksum) if *checksum != migration.checksum.as_ref() => {
+                    report.modified.push(migration.version)
+                }
+                Some(_) => {}
+            }
+        }
+        report.unknown = applied
+            .iter()
+            .map(|m| m.version)
+            .filter(|version| !expected.iter().any(|m| m.version == *version))
+            .collect();
+        report
+    }
+}
+
+/// Apply pending migrations.
+pub async fn migrate(pool: &DbPool) -> Result<(), MigrateError> {
+    MIGRATOR.run(pool).await
+}
+
+/// Compare the database schema with the embedded migrations without changing
+/// anything.
+pub async fn check(pool: &DbPool) -> Result<SchemaReport, MigrateError> {
+    let has_table: bool = sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+        .fetch_one(pool)
+        .await?;
+    if !has_table {
+        return Ok(SchemaReport::compare(MIGRATOR.migrations.as_ref(), &[], None));
+    }
+
+    let mut conn = pool.acquire().await?;
+    let dirty = conn.dirty_version().await?;
+    let applied = conn.list_applied_migrations().await?;
+    Ok(SchemaReport::compare(MIGRATOR.migrations.as_ref(), &applied, dirty))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use sqlx::migrate::MigrationType;
+
+    use super::*;
+
+    fn migration(version: i64, sql: &'static str) -> Migration {
+        Migration::new(
+            version,
+            Cow::Borrowed("test"),
+            MigrationType::Simple,
+            Cow::Borrowed(sql),
+            false,
+        )
+    }
+
+    fn applied(migration: &Migration) -> AppliedMigration {
+        AppliedMigration { version: migration.version, checksum: migration.checksum.clone() }
+    }
+
+    #[test]
+    fn test_compare_reports_every_difference() {
+        let expected =
+            [migration(1, "SELECT 1"), migration(2, "SELECT 2"), migration(3, "SELECT 3")];
+        let changed =
+            AppliedMigration { version: 2, checksum: migration(2, "SELECT 'edited'").checksum };
+        let applied = [applied(&expected[0]), changed, applied(&migration(9, "SELECT 9"))];
+
+        let report = SchemaReport::compare(&expected, &applied, None);
+        assert_eq!(report.applied, 3);
+        assert_eq!(report.pending, vec![(3, "test".to_string())]);
+        assert_eq!(report.modified, vec![2]);
+        assert_eq!(report.unknown, vec![9]);
+        assert!(!report.is_current());
+    }
+
+    #[test]
+    fn test_compare_current_schema() {
+        let expected = [migration(1, "SELECT 1"), migration(2, "SELECT 2")];
+        let applied: Vec<_> = expected.iter().map(applied).collect();
+
+        assert!(SchemaReport::compare(&expected, &applied, None).is_current());
+        assert!(!SchemaReport::compare(&expected, &applied, Some(2)).is_current());
+    }
+}
@@ -17,6 +17,8 @@ pub struct Session {
     pub user_agent: Option<String>,
     pub revoked_at: Option<DateTime<Utc>>,
     pub rotated_from: Option<String>,
+    pub device_id: Option<String>,   // dev_XXXXXXXXXXXX
+    pub geo_country: Option<String>, // ISO 3166-1 alpha-2
 }
 
 /// Create a new session
@@ -26,6 +28,8 @@ pub async fn create(
     ttl_hours: i64,
     ip: Option<&str>,
     user_agent: Option<&str>,
+    device_id: Option<&str>,
+    geo_country: Option<&str>,
 ) -> Result<Session, sqlx::Error> {
     let session_id = dguesser_core::generate_session_id();
     let expires_at = Utc::now() + Duration::hours(ttl_hours);
@@ -34,16 +38,20 @@ pub async fn create(
     sqlx::query_as!(
         Session,
         r#"
-        INSERT INTO sessions (id, user_id, expires_at, ip_address, user_agent)
-        VALUES ($1, $2, $3, $4, $5)
+        INSERT INTO sessions (id, user_id, expires_at, ip_address, user_agent,
+                              device_id, geo_country)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
         RETURNING id, user_id, created_at, expires_at, last_accessed_at, 
-                  ip_address::text, user_agent, revoked_at, rotated_from
+                  ip_address::text, user_agent, revoked_at, rotated_from,
+                  device_id, geo_country
         "#,
         session_id,
         user_id,
         expires_at,
         ip_network,
-        user_agent
+        user_agent,
+        device_id,
+        geo_country
     )
     .fetch_one(pool)
     .await
@@ -55,7 +63,8 @@ pub async fn get_valid(pool: &DbPool, session_id: &str) -> Result<Option<Session
         Session,
         r#"
         SELECT id, user_id, created_at, expires_at, last_accessed_at,
-               ip_address::text, user_agent, revoked_at, rotated_from
+               ip_address::text, user_agent, revoked_at, rotated_from,
+               device_id, geo_country
         FROM sessions
         WHERE id = $1
           AND expires_at > NOW()
@@ -105,6 +114,43 @@ pub async fn revoke_all_except(
     Ok(result.rows_affected())
 }
 
+/// Revoke all sessions for a user except the current one and those on
+/// devices the user marked as trusted
+pub async fn revoke_all_except_trusted(
+    pool: &DbPool,
+    user_id: &str,
+    keep_session_id: &str,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE sessions s
+        SET revoked_at = NOW()
+        WHERE s.user_id = $1
+          AND s.id != $2
+          AND s.revoked_at IS NULL
+          AND NOT EXISTS (
+              SELECT 1 FROM user_devices d WHERE d.id = s.device_id AND d.trusted
+          )
+        "#,
+    )
+    .bind(user_id)
+    .bind(keep_session_id)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Revoke every active session for a user
+pub async fn revoke_all_for_user(pool: &DbPool, user_id: &str) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        "UPDATE sessions SET revoked_at = NOW() WHERE user_id = $1 AND revoked_at IS NULL",
+    )
+    .bind(user_id)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
 /// Clean up expired sessions (call periodically)
 pub async fn cleanup_expired(pool: &DbPool) -> Result<u64, sqlx::Error> {
     let result =
@@ -128,7 +174,8 @@ pub async fn rotate(
         Session,
         r#"
         SELECT id, user_id, created_at, expires_at, last_accessed_at,
-               ip_address::text, user_agent, revoked_at, rotated_from
+               ip_address::text, user_agent, revoked_at, rotated_from,
+               device_id, geo_country
         FROM sessions
         WHERE id = $1
           AND expires_at > NOW()
@@ -153,17 +200,21 @@ pub async fn rotate(
     let new_session = sqlx::query_as!(
         Session,
         r#"
-        INSERT INTO sessions (id, user_id, expires_at, ip_address, user_agent, rotated_from)
-        VALUES ($1, $2, $3, $4, $5, $6)
+        INSERT INTO sessions (id, user_id, expires_at, ip_address, user_agent, rotated_from,
+                              device_id, geo_country)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
         RETURNING id, user_id, created_at, expires_at, last_accessed_at,
-                  ip_address::text, user_agent, revoked_at, rotated_from
+                  ip_address::text, user_agent, revoked_at, rotated_from,
+                  device_id, geo_country
         "#,
         new_session_id,
         old.user_id,
         expires_at,
         ip_network,
         old.user_agent.as_deref(),
-        old_session_id
+        old_session_id,
+        old.device_id.as_deref(),
+        old.geo_country.as_deref()
     )
     .fetch_one(&mut *tx)
     .await?;
@@ -172,13 +223,100 @@ pub async fn rotate(
     Ok(new_session)
 }
 
+/// Renew a session by issuing a successor with a fresh TTL.
+///
+/// Unlike [`rotate`], the old session is not revoked outright: its expiry is
+/// cut to `overlap_secs` from now so concurrent requests still carrying the old
+/// token keep working until the browser picks up the new cookie.
+///
+/// Returns `None` if the old session is no longer valid or was already renewed
+/// by a racing request.
+pub async fn renew(
+    pool: &DbPool,
+    old_session_id: &str,
+    ttl_hours: i64,
+    overlap_secs: i64,
+) -> Result<Option<Session>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    // Lock the old session so racing renewals serialize on it
+    let Some(old) = sqlx::query_as!(
+        Session,
+        r#"
+        SELECT id, user_id, created_at, expires_at, last_accessed_at,
+               ip_address::text, user_agent, revoked_at, rotated_from,
+               device_id, geo_country
+        FROM sessions
+        WHERE id = $1
+          AND expires_at > NOW()
+          AND revoked_at IS NULL
+        FOR UPDATE
+        "#,
+        old_session_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    let already_renewed = sqlx::query_scalar!(
+        r#"SELECT EXISTS(SELECT 1 FROM sessions WHERE rotated_from = $1) AS "exists!""#,
+        old_session_id
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+    if already_renewed {
+        return Ok(None);
+    }
+
+    let overlap_until = Utc::now() + Duration::seconds(overlap_secs);
+    sqlx::query!(
+        "UPDATE sessions SET expires_at = LEAST(expires_at, $2) WHERE id = $1",
+        old_session_id,
+        overlap_until
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    let new_session_id = dguesser_core::generate_session_id();
+    let expires_at = Utc::now() + Duration::hours(ttl_hours);
+    let ip_network: Option<IpNetwork> = old.ip_address.as_deref().and_then(|s| s.parse().ok());
+
+    let new_session = sqlx::query_as!(
+        Session,
+        r#"
+        INSERT INTO sessions (id, user_id, expires_at, ip_address, user_agent, rotated_from,
+                              device_id, geo_country)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING id, user_id, created_at, expires_at, last_accessed_at,
+                  ip_address::text, user_agent, revoked_at, rotated_from,
+                  device_id, geo_country
+        "#,
+        new_session_id,
+        old.user_id,
+        expires_at,
+        ip_network,
+        old.user_agent.as_deref(),
+        old_session_id,
+        old.device_id.as_deref(),
+        old.geo_country.as_deref()
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(Some(new_session))
+}
+
 /// Get all active sessions for a user
 pub async fn get_user_sessions(pool: &DbPool, user_id: &str) -> Result<Vec<Session>, sqlx::Error> {
     sqlx::query_as!(
         Session,
         r#"
         SELECT id, user_id, created_at, expires_at, last_accessed_at,
-               ip_address::text, user_agent, revoked_at, rotated_from
+               ip_address::text, user_agent, revoked_at, rotated_from,
+               device_id, geo_country
         FROM sessions
         WHERE user_id = $1
           AND expires_at > NOW()
@@ -0,0 +1,115 @@
+//! Saved settings preset queries
+//!
+//! Users save named game settings to reuse when creating games. The settings
+//! are stored as the core `GameSettings` JSON.
+
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+use crate::DbPool;
+
+/// A user's saved settings preset
+#[derive(Debug, Clone, FromRow)]
+pub struct SettingsPreset {
+    pub id: String,      // prs_XXXXXXXXXXXX
+    pub user_id: String, // usr_XXXXXXXXXXXX
+    pub name: String,
+    pub settings: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+const PRESET_COLUMNS: &str = "id, user_id, name, settings, created_at, updated_at";
+
+/// Save a new preset for a user.
+pub async fn create(
+    pool: &DbPool,
+    user_id: &str,
+    name: &str,
+    settings: &serde_json::Value,
+) -> Result<SettingsPreset, sqlx::Error> {
+    sqlx::query_as::<_, SettingsPreset>(&format!(
+        r#"
+        INSERT INTO settings_presets (id, user_id, name, settings)
+        VALUES ($1, $2, $3, $4)
+        RETURNING {PRESET_COLUMNS}
+        "#
+    ))
+    .bind(dguesser_core::generate_preset_id())
+    .bind(user_id)
+    .bind(name)
+    .bind(settings)
+    .fetch_one(pool)
+    .await
+}
+
+/// List a user's presets, oldest first.
+pub async fn list_for_user(
+    pool: &DbPool,
+    user_id: &str,
+) -> Result<Vec<SettingsPreset>, sqlx::Error> {
+    sqlx::query_as::<_, SettingsPreset>(&format!(
+        "SELECT {PRESET_COLUMNS} FROM settings_presets WHERE user_id = $1 ORDER BY created_at, id"
+    ))
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Get one of a user's presets.
+pub async fn get_for_user(
+    pool: &DbPool,
+    id: &str,
+    user_id: &str,
+) -> Result<Option<SettingsPreset>, sqlx::Error> {
+    sqlx::query_as::<_, SettingsPreset>(&format!(
+        "SELECT {PRESET_COLUMNS} FROM settings_presets WHERE id = $1 AND user_id = $2"
+    ))
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Count a user's presets.
+pub async fn count_for_user(pool: &DbPool, user_id: &str) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar("SELECT COUNT(*) FROM settings_presets WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+}
+
+/// Rename a preset and/or replace its settings. Returns `None` when the user
+/// has no preset with this ID.
+pub async fn update(
+    pool: &DbPool,
+    id: &str,
+    user_id: &str,
+    name: Option<&str>,
+    settings: Option<&serde_json::Value>,
+) -> Result<Option<SettingsPreset>, sqlx::Error> {
+    sqlx::query_as::<_, SettingsPreset>(&format!(
+        r#"
+        UPDATE settings_presets
+        SET name = COALESCE($3, name), settings = COALESCE($4, settings), updated_at = NOW()
+        WHERE id = $1 AND user_id = $2
+        RETURNING {PRESET_COLUMNS}
+        "#
+    ))
+    .bind(id)
+    .bind(user_id)
+    .bind(name)
+    .bind(settings)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Delete a preset. Returns whether the user had a preset with this ID.
+pub async fn delete(pool: &DbPool, id: &str, user_id: &str) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM settings_presets WHERE id = $1 AND user_id = $2")
+        .bind(id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
@@ -0,0 +1,130 @@
+//! Socket.IO event outbox queries
+//!
+//! Events are written in the transaction that changes the game and published
+//! to Redis by a relay, which gives at-least-once delivery.
+
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgConnection};
+
+use crate::DbPool;
+
+/// How long a claimed event may stay in `sending` before it is considered
+/// abandoned (the relay crashed mid-publish) and becomes eligible again.
+const SENDING_LEASE_SECS: i64 = 30;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct QueuedSocketEvent {
+    pub id: i64,
+    pub room: String,
+    pub event: String,
+    pub payload: serde_json::Value,
+    pub attempts: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Add an event to the outbox. Call inside the transaction of the change the
+/// event announces. Returns the event ID.
+pub async fn enqueue(
+    conn: &mut PgConnection,
+    room: &str,
+    event: &str,
+    payload: serde_json::Value,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(
+        "INSERT INTO socket_event_outbox (room, event, payload) VALUES ($1, $2, $3) RETURNING id",
+    )
+    .bind(room)
+    .bind(event)
+    .bind(payload)
+    .fetch_one(conn)
+    .await
+}
+
+/// Claim up to `limit` due events, oldest first.
+///
+/// Claimed rows move to `sending` with their attempt count incremented, and
+/// hold a lease so concurrent relays skip them.
+pub async fn claim_due(pool: &DbPool, limit: i64) -> Result<Vec<QueuedSocketEvent>, sqlx::Error> {
+    sqlx::query_as::<_, QueuedSocketEvent>(
+        r#"
+        UPDATE socket_event_outbox
+        SET status = 'sending',
+            attempts = attempts + 1,
+            next_attempt_at = NOW() + make_interval(secs => $2)
+        WHERE id IN (
+            SELECT id FROM socket_event_outbox
+            WHERE status IN ('pending', 'sending') AND next_attempt_at <= NOW()
+            ORDER BY id
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING id, room, event, payload, attempts, created_at
+        "#,
+    )
+    .bind(limit)
+    .bind(SENDING_LEASE_SECS as f64)
+    .fetch_all(pool)
+    .await
+    .map(|mut events| {
+        // RETURNING does not keep the subquery's order
+        events.sort_by_key(|event| event.id);
+        events
+    })
+}
+
+/// Mark events as published.
+pub async fn mark_delivered(pool: &DbPool, ids: &[i64]) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE socket_event_outbox
+        SET status = 'delivered', delivered_at = NOW(), last_error = NULL
+        WHERE id = ANY($1)
+        "#,
+    )
+    .bind(ids)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Record a failed publish for events.
+///
+/// With `retry_at` the events go back to `pending`; without it they are given
+/// up on and marked `failed`.
+pub async fn mark_failed(
+    pool: &DbPool,
+    ids: &[i64],
+    error: &str,
+    retry_at: Option<DateTime<Utc>>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE socket_event_outbox
+        SET status = CASE WHEN $3::timestamptz IS NULL THEN 'failed' ELSE 'pending' END,
+            next_attempt_at = COALESCE($3, next_attempt_at),
+            last_error = $2
+        WHERE id = ANY($1)
+        "#,
+    )
+    .bind(ids)
+    .bind(error)
+    .bind(retry_at)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Delete delivered or failed events older than `days` (call periodically).
+pub async fn cleanup_finished(pool: &DbPool, days: i32) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM socket_event_outbox
+        WHERE status IN ('delivered', 'failed')
+          AND created_at < NOW() - make_interval(days => $1)
+        "#,
+    )
+    .bind(days)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,398 @@
+//! Simulated players
+//!
+//! A [`Bot`] signs in as a guest through the API, connects to the realtime
+//! server and plays like a person would: it waits a while after each round
+//! starts, then guesses somewhere near (or far from) the location. One bot per
+//! game hosts it; the others join with its join code.
+
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result, anyhow, bail};
+use dguesser_protocol::socket::events::{client, server};
+use dguesser_protocol::socket::payloads::{ErrorPayload, PlayerGuessedPayload, RoundStartPayload};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::metrics::{Metrics, op};
+use crate::socket::{Event, SocketClient};
+
+/// Where the servers are
+#[derive(Debug, Clone)]
+pub struct Target {
+    pub api_url: String,
+    pub realtime_url: String,
+    /// Name of the session cookie set by the API
+    pub cookie_name: String,
+}
+
+/// How the simulated games are played
+#[derive(Debug, Clone)]
+pub struct GameConfig {
+    /// Players per game, including the host
+    pub players: usize,
+    pub rounds: u8,
+    pub time_limit_secs: u32,
+    pub map_id: Option<String>,
+    /// Delay between a round starting and a bot guessing, in milliseconds
+    pub guess_delay_ms: Range<u64>,
+    /// Longest wait for any single server reply
+    pub reply_timeout: Duration,
+}
+
+#[derive(Deserialize)]
+struct CreateGameResponse {
+    id: String,
+    join_code: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AuthResponse {
+    user_id: Option<String>,
+    error: Option<String>,
+}
+
+/// A simulated player with a connected socket
+pub struct Bot {
+    session_id: String,
+    user_id: String,
+    socket: SocketClient,
+}
+
+impl Bot {
+    /// Sign in as a new guest and connect to the realtime server.
+    pub async fn sign_in(
+        http: &reqwest::Client,
+        target: &Target,
+        metrics: &Metrics,
+    ) -> Result<Self> {
+        let session_id = timed(metrics, op::GUEST, guest_session(http, target)).await?;
+
+        let cookie = format!("{}={}", target.cookie_name, session_id);
+        let socket = timed(metrics, op::CONNECT, async {
+            SocketClient::connect(&target.realtime_url, Some(&cookie)).await.map_err(Into::into)
+        })
+        .await?;
+
+        let mut bot = Self { session_id, user_id: String::new(), socket };
+        let start = Instant::now();
+        let result = async {
+            bot.socket.emit("auth", &json!({ "session_id": bot.session_id })).await?;
+            let reply =
+                bot.wait_for(&["auth:success", "auth:error"], Duration::from_secs(10)).await?;
+            let auth: AuthResponse = serde_json::from_value(reply.data)?;
+            auth.user_id.ok_or_else(|| anyhow!(auth.error.unwrap_or_else(|| "auth failed".into())))
+        }
+        .await;
+        match result {
+            Ok(user_id) => {
+                metrics.record(op::AUTH, start.elapsed());
+                bot.user_id = user_id;
+                Ok(bot)
+            }
+            Err(e) => {
+                metrics.record_error(op::AUTH, &e);
+                Err(e)
+            }
+        }
+    }
+
+    /// Create a multiplayer game as this bot. Returns the game ID and join
+    /// code.
+    pub async fn create_game(
+        &self,
+        http: &reqwest::Client,
+        target: &Target,
+        config: &GameConfig,
+        metrics: &Metrics,
+    ) -> Result<(String, String)> {
+        timed(metrics, op::CREATE_GAME, async {
+            let game: CreateGameResponse = http
+                .post(format!("{}/api/v1/games", target.api_url))
+                .header("cookie", self.cookie(target))
+                .json(&json!({
+                    "mode": "multiplayer",
+                    "rounds": config.rounds,
+                    "time_limit_seconds": config.time_limit_secs,
+                    "map_id": config.map_id,
+                }))
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+            let code = game.join_code.context("multiplayer game has no join code")?;
+            Ok((game.id, code))
+        })
+        .await
+    }
+
+    /// Join a game by its join code through the API.
+    pub async fn join_by_code(
+        &self,
+        http: &reqwest::Client,
+        target: &Target,
+        code: &str,
+        metrics: &Metrics,
+    ) -> Result<()> {
+        timed(metrics, op::JOIN_CODE, async {
+            http.post(format!("{}/api/v1/games/join", target.api_url))
+                .header("cookie", self.cookie(target))
+                .json(&json!({ "code": code }))
+                .send()
+                .await?
+                .error_for_status()?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Join the game's room on the realtime server.
+    pub async fn join_room(
+        &mut self,
+        game_id: &str,
+        config: &GameConfig,
+        metrics: &Metrics,
+    ) -> Result<()> {
+        let start = Instant::now();
+        let result = async {
+            self.socket.emit(client::JOIN_GAME, &json!({ "game_id": game_id })).await?;
+            self.wait_for(&[server::GAME_STATE], config.reply_timeout).await
+        }
+        .await;
+        record(metrics, op::JOIN, start, result.map(|_| ()))
+    }
+
+    /// Start the game as its host and wait for the first round.
+    pub async fn start(
+        &mut self,
+        game_id: &str,
+        config: &GameConfig,
+        metrics: &Metrics,
+    ) -> Result<RoundStartPayload> {
+        let start = Instant::now();
+        let result = async {
+            self.socket.emit(client::START_GAME, &json!({ "game_id": game_id })).await?;
+            let event = self.wait_for(&[server::ROUND_START], config.reply_timeout).await?;
+            Ok(serde_json::from_value::<RoundStartPayload>(event.data)?)
+        }
+        .await;
+        match result {
+            Ok(round) => {
+                metrics.record(op::START, start.elapsed());
+                Ok(round)
+            }
+            Err(e) => {
+                metrics.record_error(op::START, &e);
+                Err(e)
+            }
+        }
+    }
+
+    /// Play until the game ends. `first_round` is a round start this bot
+    /// already received.
+    pub async fn play(
+        mut self,
+        game_id: String,
+        config: Arc<GameConfig>,
+        metrics: Arc<Metrics>,
+        first_round: Option<RoundStartPayload>,
+    ) -> Result<()> {
+        let mut next_guess = first_round.map(|round| PlannedGuess::new(round, &config, &metrics));
+        // When the unconfirmed guess was sent
+        let mut pending_guess: Option<Instant> = None;
+        let idle_timeout =
+            Duration::from_secs(u64::from(config.time_limit_secs)) + config.reply_timeout * 2;
+
+        loop {
+            let guess_at = next_guess.as_ref().map(|guess| guess.at);
+            let event = tokio::select! {
+                _ = sleep_until(guess_at), if guess_at.is_some() => {
+                    let guess = next_guess.take().expect("guess is planned");
+                    let payload = json!({
+                        "game_id": game_id,
+                        "lat": guess.lat,
+                        "lng": guess.lng,
+                        "time_taken_ms": guess.round_received.elapsed().as_millis() as u64,
+                        "panorama_id": guess.panorama_id,
+                    });
+                    if let Err(e) = self.socket.emit(client::SUBMIT_GUESS, &payload).await {
+                        metrics.record_error(op::GUESS, &e);
+                        return Err(e.into());
+                    }
+                    pending_guess = Some(Instant::now());
+                    continue;
+                }
+                event = tokio::time::timeout(idle_timeout, self.socket.next_event()) => {
+                    event.map_err(|_| anyhow!("no event from the server for {idle_timeout:?}"))??
+                }
+            };
+
+            match event.name.as_str() {
+                server::ROUND_START => {
+                    let round: RoundStartPayload = serde_json::from_value(event.data)?;
+                    next_guess = Some(PlannedGuess::new(round, &config, &metrics));
+                }
+                server::PLAYER_GUESSED => {
+                    let guessed: PlayerGuessedPayload = serde_json::from_value(event.data)?;
+                    if guessed.user_id == self.user_id
+                        && let Some(sent) = pending_guess.take()
+                    {
+                        metrics.record(op::GUESS, sent.elapsed());
+                    }
+                }
+                server::ROUND_END => {
+                    next_guess = None;
+                    if pending_guess.take().is_some() {
+                        metrics
+                            .record_error(op::GUESS, "round ended before the guess was confirmed");
+                    }
+                }
+                server::ERROR => {
+                    let error: ErrorPayload = serde_json::from_value(event.data)?;
+                    if pending_guess.take().is_some() {
+                        metrics.record_error(op::GUESS, &error.code);
+                    } else {
+                        tracing::debug!(code = %error.code, message = %error.message, "Server error");
+                    }
+                }
+                server::GAME_END => break,
+                server::GAME_ABANDONED => bail!("game abandoned"),
+                _ => {}
+            }
+        }
+
+        self.socket.close().await;
+        Ok(())
+    }
+
+    /// Wait for one of `names`, skipping other events. An `error` event
+    /// fails the wait.
+    async fn wait_for(&mut self, names: &[&str], timeout: Duration) -> Result<Event> {
+        tokio::time::timeout(timeout, async {
+            loop {
+                let event = self.socket.next_event().await?;
+                if names.contains(&event.name.as_str()) {
+                    return Ok(event);
+                }
+                if event.name == server::ERROR {
+                    let error: ErrorPayload = serde_json::from_value(event.data)?;
+                    bail!("{}", error.code);
+                }
+            }
+        })
+        .await
+        .map_err(|_| anyhow!("timed out waiting for {}", names.join("/")))?
+    }
+
+    fn cookie(&self, target: &Target) -> String {
+        format!("{}={}", target.cookie_name, self.session_id)
+    }
+}
+
+/// When and where a bot guesses in a round
+struct PlannedGuess {
+    at: Instant,
+    lat: f64,
+    lng: f64,
+    panorama_id: Option<String>,
+    round_received: Instant,
+}
+
+impl PlannedGuess {
+    /// Plan a guess for a round that just started, recording how late the
+    /// round start arrived.
+    fn new(round: RoundStartPayload, config: &GameConfig, metrics: &Metrics) -> Self {
+        let delivery_ms = (now_ms() - round.started_at).max(0) as u64;
+        metrics.record(op::ROUND_START_DELIVERY, Duration::from_millis(delivery_ms));
+
+        // Leave time for the guess to arrive before the round closes
+        let max_delay_ms =
+            round.time_limit_ms.map_or(u64::MAX, |limit| u64::from(limit) * 9 / 10).max(1);
+        let delay_ms = random_in(&config.guess_delay_ms).min(max_delay_ms);
+        let (lat, lng) = scatter(round.location.lat, round.location.lng);
+
+        let now = Instant::now();
+        Self {
+            at: now + Duration::from_millis(delay_ms),
+            lat,
+            lng,
+            panorama_id: round.location.panorama_id,
+            round_received: now,
+        }
+    }
+}
+
+/// A guess somewhere around a location: usually close, sometimes on the
+/// wrong continent
+fn scatter(lat: f64, lng: f64) -> (f64, f64) {
+    let spread = if rand::random_bool(0.2) { 60.0 } else { 3.0 };
+    let lat = (lat + rand::random_range(-spread..spread)).clamp(-85.0, 85.0);
+    let lng = lng + rand::random_range(-spread..spread);
+    let lng = if lng > 180.0 {
+        lng - 360.0
+    } else if lng < -180.0 {
+        lng + 360.0
+    } else {
+        lng
+    };
+    (lat, lng)
+}
+
+fn random_in(range: &Range<u64>) -> u64 {
+    if range.is_empty() { range.start } else { rand::random_range(range.clone()) }
+}
+
+/// Create a guest session, returning its ID from the session cookie.
+async fn guest_session(http: &reqwest::Client, target: &Target) -> Result<String> {
+    let response = http
+        .post(format!("{}/api/v1/auth/guest", target.api_url))
+        .send()
+        .await?
+        .error_for_status()?;
+    response
+        .headers()
+        .get_all("set-cookie")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .find_map(|cookie| {
+            let (name, value) = cookie.split(';').next()?.split_once('=')?;
+            (name.trim() == target.cookie_name && !value.is_empty()).then(|| value.to_string())
+        })
+        .with_context(|| format!("no {} cookie in the guest response", target.cookie_name))
+}
+
+/// Time an operation and record its latency or error.
+async fn timed<T>(
+    metrics: &Metrics,
+    op: &'static str,
+    operation: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    let start = Instant::now();
+    let result = operation.await;
+    match &result {
+        Ok(_) => metrics.record(op, start.elapsed()),
+        Err(e) => metrics.record_error(op, e),
+    }
+    result
+}
+
+fn record(metrics: &Metrics, op: &'static str, start: Instant, result: Result<()>) -> Result<()> {
+    match &result {
+        Ok(()) => metrics.record(op, start.elapsed()),
+        Err(e) => metrics.record_error(op, e),
+    }
+    result
+}
+
+async fn sleep_until(at: Option<Instant>) {
+    if let Some(at) = at {
+        tokio::time::sleep_until(at.into()).await;
+    }
+}
+
+/// Milliseconds since the Unix epoch
+fn now_ms() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as i64)
+}
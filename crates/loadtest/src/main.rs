@@ -0,0 +1,196 @@
+//! Load test harness
+//!
+//! Plays simulated multiplayer games against a running API and realtime
+//! server and reports latency percentiles and error rates per operation.
+//! Each game has one host bot that creates it and starts it once everyone
+//! joined; every bot guesses after a random delay in each round.
+//!
+//! ```sh
+//! cargo run --release -p dguesser-loadtest -- \
+//!     --api-url http://localhost:3001 --realtime-url http://localhost:3002 \
+//!     --games 50 --players-per-game 4 --rounds 3 --time-limit 30
+//! ```
+//!
+//! The realtime server allows 10 `auth` events per minute per IP, so a run
+//! from one machine with more than a handful of bots needs a higher
+//! `socket_rate_limit_multiplier` (set through `POST /api/v1/admin/reload`).
+//! Guest sign-ups are rate limited by the API the same way.
+//!
+//! The process exits with status 1 when the overall error rate exceeds
+//! `--max-error-rate`, so it can gate a CI job.
+
+mod bot;
+mod metrics;
+mod socket;
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use futures::future::join_all;
+use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitExt};
+
+use crate::bot::{Bot, GameConfig, Target};
+use crate::metrics::{Metrics, op};
+
+#[derive(Debug, Parser)]
+#[command(name = "dguesser_loadtest", about = "Play simulated games against a dguesser deployment")]
+struct Args {
+    /// API base URL
+    #[arg(long, env = "LOADTEST_API_URL", default_value = "http://localhost:3001")]
+    api_url: String,
+
+    /// Realtime server base URL
+    #[arg(long, env = "LOADTEST_REALTIME_URL", default_value = "http://localhost:3002")]
+    realtime_url: String,
+
+    /// Number of games to play
+    #[arg(long, default_value_t = 10)]
+    games: usize,
+
+    /// Bots per game, including the host
+    #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u16).range(1..=50))]
+    players_per_game: u16,
+
+    /// Rounds per game
+    #[arg(long, default_value_t = 3, value_parser = clap::value_parser!(u8).range(1..=20))]
+    rounds: u8,
+
+    /// Round time limit in seconds
+    #[arg(long, default_value_t = 30)]
+    time_limit: u32,
+
+    /// Map to play on (the server default when unset)
+    #[arg(long)]
+    map_id: Option<String>,
+
+    /// Shortest delay between a round starting and a bot guessing, in seconds
+    #[arg(long, default_value_t = 3.0)]
+    guess_delay_min: f64,
+
+    /// Longest delay between a round starting and a bot guessing, in seconds
+    #[arg(long, default_value_t = 20.0)]
+    guess_delay_max: f64,
+
+    /// Spread game starts over this many seconds
+    #[arg(long, default_value_t = 10)]
+    ramp_up: u64,
+
+    /// Longest wait for a single server reply, in seconds
+    #[arg(long, default_value_t = 15)]
+    reply_timeout: u64,
+
+    /// Session cookie name set by the API
+    #[arg(long, default_value = "dguesser_sid")]
+    cookie_name: String,
+
+    /// Fail when more than this share of operations failed
+    #[arg(long, default_value_t = 0.01)]
+    max_error_rate: f64,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::registry()
+        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| "dguesser_loadtest=info".into()))
+        .with(tracing_subscriber::fmt::layer())
+        .init();
+
+    let args = Args::parse();
+    let target = Arc::new(Target {
+        api_url: args.api_url.trim_end_matches('/').to_string(),
+        realtime_url: args.realtime_url.trim_end_matches('/').to_string(),
+        cookie_name: args.cookie_name.clone(),
+    });
+    let config = Arc::new(GameConfig {
+        players: usize::from(args.players_per_game),
+        rounds: args.rounds,
+        time_limit_secs: args.time_limit,
+        map_id: args.map_id.clone(),
+        guess_delay_ms: (args.guess_delay_min.max(0.0) * 1000.0) as u64
+            ..(args.guess_delay_max.max(args.guess_delay_min) * 1000.0) as u64,
+        reply_timeout: Duration::from_secs(args.reply_timeout),
+    });
+    let metrics = Arc::new(Metrics::default());
+    let http = reqwest::Client::builder()
+        .timeout(config.reply_timeout)
+        .build()
+        .context("failed to build HTTP client")?;
+
+    tracing::info!(
+        games = args.games,
+        players_per_game = args.players_per_game,
+        rounds = args.rounds,
+        "Starting load test"
+    );
+    let started = Instant::now();
+
+    let stagger = Duration::from_secs(args.ramp_up) / args.games.max(1) as u32;
+    let games = (0..args.games).map(|index| {
+        let (http, target, config, metrics) =
+            (http.clone(), target.clone(), config.clone(), metrics.clone());
+        tokio::spawn(async move {
+            tokio::time::sleep(stagger * index as u32).await;
+            let start = Instant::now();
+            match play_game(&http, &target, config, metrics.clone()).await {
+                Ok(()) => metrics.record(op::GAME, start.elapsed()),
+                Err(e) => {
+                    tracing::warn!(game = index, error = %e, "Game failed");
+                    metrics.record_error(op::GAME, &e);
+                }
+            }
+        })
+    });
+    join_all(games).await;
+
+    let report = metrics.report();
+    println!("\n{report}");
+    println!("finished in {:.1}s", started.elapsed().as_secs_f64());
+
+    if report.error_rate() > args.max_error_rate {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Play one game from creation to the end with `config.players` bots.
+async fn play_game(
+    http: &reqwest::Client,
+    target: &Target,
+    config: Arc<GameConfig>,
+    metrics: Arc<Metrics>,
+) -> Result<()> {
+    let mut host = Bot::sign_in(http, target, &metrics).await?;
+    let (game_id, code) = host.create_game(http, target, &config, &metrics).await?;
+    host.join_room(&game_id, &config, &metrics).await?;
+
+    let guests = join_all((1..config.players).map(|_| async {
+        let mut bot = Bot::sign_in(http, target, &metrics).await?;
+        bot.join_by_code(http, target, &code, &metrics).await?;
+        bot.join_room(&game_id, &config, &metrics).await?;
+        anyhow::Ok(bot)
+    }))
+    .await
+    .into_iter()
+    .collect::<Result<Vec<_>>>()?;
+
+    let first_round = host.start(&game_id, &config, &metrics).await?;
+
+    let mut players = vec![tokio::spawn(host.play(
+        game_id.clone(),
+        config.clone(),
+        metrics.clone(),
+        Some(first_round),
+    ))];
+    players.extend(
+        guests.into_iter().map(|bot| {
+            tokio::spawn(bot.play(game_id.clone(), config.clone(), metrics.clone(), None))
+        }),
+    );
+
+    for result in join_all(players).await {
+        result.context("player task panicked")??;
+    }
+    Ok(())
+}
@@ -0,0 +1,191 @@
+//! Latency and error collection
+//!
+//! Bots record how long each operation took and which operations failed;
+//! [`Report`] summarizes them as percentiles and error rates.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Operations timed by the bots
+pub mod op {
+    pub const GUEST: &str = "http:guest";
+    pub const CREATE_GAME: &str = "http:create_game";
+    pub const JOIN_CODE: &str = "http:join_code";
+    pub const CONNECT: &str = "socket:connect";
+    pub const AUTH: &str = "socket:auth";
+    pub const JOIN: &str = "socket:join";
+    pub const START: &str = "socket:start";
+    pub const GUESS: &str = "socket:guess";
+    /// Server timestamp of a round start until the bot received it
+    pub const ROUND_START_DELIVERY: &str = "socket:round_start_delivery";
+    pub const GAME: &str = "game";
+}
+
+#[derive(Debug, Default)]
+struct Samples {
+    latencies_ms: Vec<f64>,
+    errors: BTreeMap<String, u64>,
+}
+
+/// Shared metrics collector
+#[derive(Debug, Default)]
+pub struct Metrics {
+    ops: Mutex<BTreeMap<&'static str, Samples>>,
+}
+
+impl Metrics {
+    /// Record a successful operation.
+    pub fn record(&self, op: &'static str, latency: Duration) {
+        self.ops
+            .lock()
+            .unwrap()
+            .entry(op)
+            .or_default()
+            .latencies_ms
+            .push(latency.as_secs_f64() * 1000.0);
+    }
+
+    /// Record a failed operation.
+    pub fn record_error(&self, op: &'static str, error: impl fmt::Display) {
+        let error = error.to_string();
+        tracing::debug!(op, error = %error, "Operation failed");
+        *self.ops.lock().unwrap().entry(op).or_default().errors.entry(error).or_default() += 1;
+    }
+
+    /// Summarize everything recorded so far.
+    pub fn report(&self) -> Report {
+        let ops = self.ops.lock().unwrap();
+        Report {
+            ops: ops
+                .iter()
+                .map(|(op, samples)| {
+                    let mut latencies = samples.latencies_ms.clone();
+                    latencies.sort_by(f64::total_cmp);
+                    OpSummary {
+                        op,
+                        ok: latencies.len() as u64,
+                        errors: samples.errors.values().sum(),
+                        p50_ms: percentile(&latencies, 50.0),
+                        p95_ms: percentile(&latencies, 95.0),
+                        p99_ms: percentile(&latencies, 99.0),
+                        max_ms: latencies.last().copied(),
+                        top_error: samples
+                            .errors
+                            .iter()
+                            .max_by_key(|(_, count)| **count)
+                            .map(|(error, _)| error.clone()),
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[f64], p: f64) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+/// Summary of one operation
+#[derive(Debug)]
+pub struct OpSummary {
+    pub op: &'static str,
+    pub ok: u64,
+    pub errors: u64,
+    pub p50_ms: Option<f64>,
+    pub p95_ms: Option<f64>,
+    pub p99_ms: Option<f64>,
+    pub max_ms: Option<f64>,
+    /// Most frequent error
+    pub top_error: Option<String>,
+}
+
+impl OpSummary {
+    /// Share of attempts that failed
+    pub fn error_rate(&self) -> f64 {
+        let total = self.ok + self.errors;
+        if total == 0 { 0.0 } else { self.errors as f64 / total as f64 }
+    }
+}
+
+/// Summary of a run
+#[derive(Debug)]
+pub struct Report {
+    pub ops: Vec<OpSummary>,
+}
+
+impl Report {
+    /// Share of all attempts that failed
+    pub fn error_rate(&self) -> f64 {
+        let (ok, errors) =
+            self.ops.iter().fold((0, 0), |(ok, errors), op| (ok + op.ok, errors + op.errors));
+        if ok + errors == 0 { 0.0 } else { errors as f64 / (ok + errors) as f64 }
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ms = |v: Option<f64>| v.map_or_else(|| "-".to_string(), |v| format!("{v:.1}"));
+        writeln!(
+            f,
+            "{:<30} {:>8} {:>7} {:>7} {:>9} {:>9} {:>9} {:>9}",
+            "operation", "ok", "errors", "err %", "p50 ms", "p95 ms", "p99 ms", "max ms"
+        )?;
+        for op in &self.ops {
+            writeln!(
+                f,
+                "{:<30} {:>8} {:>7} {:>6.2}% {:>9} {:>9} {:>9} {:>9}",
+                op.op,
+                op.ok,
+                op.errors,
+                op.error_rate() * 100.0,
+                ms(op.p50_ms),
+                ms(op.p95_ms),
+                ms(op.p99_ms),
+                ms(op.max_ms)
+            )?;
+        }
+        for op in self.ops.iter().filter(|op| op.top_error.is_some()) {
+            writeln!(f, "  {}: {}", op.op, op.top_error.as_deref().unwrap_or_default())?;
+        }
+        write!(f, "overall error rate: {:.2}%", self.error_rate() * 100.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile() {
+        let values: Vec<f64> = (1..=100).map(f64::from).collect();
+        assert_eq!(percentile(&values, 50.0), Some(50.0));
+        assert_eq!(percentile(&values, 95.0), Some(95.0));
+        assert_eq!(percentile(&values, 99.0), Some(99.0));
+        assert_eq!(percentile(&[7.0], 99.0), Some(7.0));
+        assert_eq!(percentile(&[], 50.0), None);
+    }
+
+    #[test]
+    fn test_report_error_rate() {
+        let metrics = Metrics::default();
+        metrics.record(op::GUESS, Duration::from_millis(10));
+        metrics.record(op::GUESS, Duration::from_millis(30));
+        metrics.record(op::GUESS, Duration::from_millis(20));
+        metrics.record_error(op::GUESS, "RATE_LIMITED");
+
+        let report = metrics.report();
+        let guess = &report.ops[0];
+        assert_eq!((guess.ok, guess.errors), (3, 1));
+        assert_eq!(guess.p50_ms, Some(20.0));
+        assert_eq!(guess.max_ms, Some(30.0));
+        assert_eq!(guess.top_error.as_deref(), Some("RATE_LIMITED"));
+        assert!((report.error_rate() - 0.25).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,221 @@
+//! Minimal Socket.IO client
+//!
+//! Speaks just enough of Engine.IO v4 and Socket.IO v5 over a WebSocket to
+//! play a game: connecting to the default namespace, emitting events,
+//! receiving events and answering the server's pings. Acknowledgements,
+//! binary packets and other namespaces are not supported.
+
+use futures::{SinkExt, StreamExt};
+use serde::Serialize;
+use serde_json::Value;
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::tungstenite::{self, Message};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+#[derive(Debug, thiserror::Error)]
+pub enum SocketError {
+    #[error("websocket error: {0}")]
+    WebSocket(#[from] tungstenite::Error),
+    #[error("invalid packet: {0}")]
+    InvalidPacket(String),
+    #[error("connection refused: {0}")]
+    ConnectError(String),
+    #[error("connection closed")]
+    Closed,
+}
+
+/// An event received from the server
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub name: String,
+    pub data: Value,
+}
+
+/// A decoded Engine.IO/Socket.IO packet
+#[derive(Debug, PartialEq)]
+enum Packet {
+    /// Engine.IO handshake
+    Open,
+    Ping,
+    /// Connected to the namespace
+    Connect,
+    ConnectError(String),
+    Event(Event),
+    Disconnect,
+    /// Anything this client does not handle
+    Other,
+}
+
+/// A connected Socket.IO client
+pub struct SocketClient {
+    ws: WebSocketStream<MaybeTlsStream<TcpStream>>,
+}
+
+impl SocketClient {
+    /// Connect to a realtime server (`http(s)://host[:port]`), sending
+    /// `cookie` with the handshake, and join the default namespace.
+    pub async fn connect(base_url: &str, cookie: Option<&str>) -> Result<Self, SocketError> {
+        let url = socket_url(base_url);
+        let mut request = url.as_str().into_client_request()?;
+        if let Some(cookie) = cookie {
+            let value = HeaderValue::from_str(cookie)
+                .map_err(|_| SocketError::InvalidPacket("invalid cookie".to_string()))?;
+            request.headers_mut().insert("cookie", value);
+        }
+        let (ws, _) = tokio_tungstenite::connect_async(request).await?;
+        let mut client = Self { ws };
+
+        loop {
+            match client.next_packet().await? {
+                Packet::Open => client.send("40").await?,
+                Packet::Connect => return Ok(client),
+                Packet::ConnectError(message) => return Err(SocketError::ConnectError(message)),
+                Packet::Disconnect => return Err(SocketError::Closed),
+                Packet::Ping | Packet::Event(_) | Packet::Other => {}
+            }
+        }
+    }
+
+    /// Emit an event to the server.
+    pub async fn emit(&mut self, event: &str, data: &impl Serialize) -> Result<(), SocketError> {
+        let data =
+            serde_json::to_value(data).map_err(|e| SocketError::InvalidPacket(e.to_string()))?;
+        let payload = Value::Array(vec![Value::String(event.to_string()), data]);
+        self.send(&format!("42{payload}")).await
+    }
+
+    /// Wait for the next event, answering pings on the way.
+    pub async fn next_event(&mut self) -> Result<Event, SocketError> {
+        loop {
+            match self.next_packet().await? {
+                Packet::Event(event) => return Ok(event),
+                Packet::Disconnect => return Err(SocketError::Closed),
+                Packet::Open | Packet::Ping | Packet::Connect | Packet::Other => {}
+                Packet::ConnectError(message) => return Err(SocketError::ConnectError(message)),
+            }
+        }
+    }
+
+    /// Leave the namespace and close the connection.
+    pub async fn close(mut self) {
+        let _ = self.send("41").await;
+        let _ = self.ws.close(None).await;
+    }
+
+    async fn send(&mut self, text: &str) -> Result<(), SocketError> {
+        self.ws.send(Message::text(text)).await?;
+        Ok(())
+    }
+
+    async fn next_packet(&mut self) -> Result<Packet, SocketError> {
+        loop {
+            let message = self.ws.next().await.ok_or(SocketError::Closed)??;
+            let text = match message {
+                Message::Text(text) => text,
+                Message::Close(_) => return Err(SocketError::Closed),
+                _ => continue,
+            };
+            let packet = parse_packet(text.as_str())?;
+            if packet == Packet::Ping {
+                self.send("3").await?;
+            }
+            return Ok(packet);
+        }
+    }
+}
+
+/// The WebSocket URL of a server's Socket.IO endpoint
+fn socket_url(base_url: &str) -> String {
+    let base_url = base_url.trim_end_matches('/');
+    let base_url = match base_url.split_once("://") {
+        Some(("https", rest)) => format!("wss://{rest}"),
+        Some(("http", rest)) => format!("ws://{rest}"),
+        Some(_) => base_url.to_string(),
+        None => format!("ws://{base_url}"),
+    };
+    format!("{base_url}/socket.io/?EIO=4&transport=websocket")
+}
+
+/// Decode a text frame.
+fn parse_packet(text: &str) -> Result<Packet, SocketError> {
+    let invalid = || SocketError::InvalidPacket(text.chars().take(100).collect());
+    let mut chars = text.chars();
+    match chars.next().ok_or_else(invalid)? {
+        '0' => return Ok(Packet::Open),
+        '1' => return Ok(Packet::Disconnect),
+        '2' => return Ok(Packet::Ping),
+        '4' => {}
+        _ => return Ok(Packet::Other),
+    }
+
+    // Socket.IO packet inside an Engine.IO message
+    let body = chars.as_str();
+    let (kind, rest) = body.split_at(body.len().min(1));
+    match kind {
+        "0" => Ok(Packet::Connect),
+        "1" => Ok(Packet::Disconnect),
+        "2" => {
+            let json = rest.find('[').map(|start| &rest[start..]).ok_or_else(invalid)?;
+            let mut items = match serde_json::from_str::<Value>(json) {
+                Ok(Value::Array(items)) if !items.is_empty() => items,
+                _ => return Err(invalid()),
+            };
+            let data = if items.len() > 1 { items.swap_remove(1) } else { Value::Null };
+            let Value::String(name) = items.swap_remove(0) else {
+                return Err(invalid());
+            };
+            Ok(Packet::Event(Event { name, data }))
+        }
+        "4" => {
+            let message = serde_json::from_str::<Value>(rest)
+                .ok()
+                .and_then(|v| v.get("message").and_then(Value::as_str).map(str::to_string))
+                .unwrap_or_else(|| rest.to_string());
+            Ok(Packet::ConnectError(message))
+        }
+        _ => Ok(Packet::Other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_socket_url() {
+        assert_eq!(
+            socket_url("http://localhost:3002/"),
+            "ws://localhost:3002/socket.io/?EIO=4&transport=websocket"
+        );
+        assert_eq!(
+            socket_url("https://rt.example.com"),
+            "wss://rt.example.com/socket.io/?EIO=4&transport=websocket"
+        );
+    }
+
+    #[test]
+    fn test_parse_packet() {
+        assert_eq!(parse_packet(r#"0{"sid":"abc"}"#).unwrap(), Packet::Open);
+        assert_eq!(parse_packet("2").unwrap(), Packet::Ping);
+        assert_eq!(parse_packet(r#"40{"sid":"xyz"}"#).unwrap(), Packet::Connect);
+        assert_eq!(
+            parse_packet(r#"44{"message":"nope"}"#).unwrap(),
+            Packet::ConnectError("nope".to_string())
+        );
+        assert_eq!(
+            parse_packet(r#"42["round:start",{"round_number":1}]"#).unwrap(),
+            Packet::Event(Event {
+                name: "round:start".to_string(),
+                data: serde_json::json!({ "round_number": 1 }),
+            })
+        );
+        assert_eq!(
+            parse_packet(r#"42["game:paused"]"#).unwrap(),
+            Packet::Event(Event { name: "game:paused".to_string(), data: Value::Null })
+        );
+        assert!(parse_packet("42{}").is_err());
+        assert!(parse_packet("").is_err());
+    }
+}
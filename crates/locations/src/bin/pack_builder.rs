@@ -1,10 +1,19 @@
-//! Pack Builder - Convert Vali JSON locations to R2 pack format.
+//! Pack Builder - Convert Vali JSON or Postgres locations to R2 pack format.
 //!
 //! Usage:
 //! ```bash
 //! # Build packs from Vali JSON files
 //! pack-builder build --input ./vali-output/ --output ./packs/ --version v2026-01
 //!
+//! # Build packs from the validated locations in Postgres and upload them
+//! pack-builder build-db --output ./packs/ --version v2026-02 --upload dguesser-r2:dguesser-cdn
+//!
+//! # Upload an already built version (uses rclone, see docs/R2-CDN-SETUP.md)
+//! pack-builder upload --path ./packs/v2026-02/ --remote dguesser-r2:dguesser-cdn
+//!
+//! # Exclude bad panoramas from a published version without rebuilding it
+//! pack-builder tombstone --path ./packs/v2026-02/ --upload dguesser-r2:dguesser-cdn PANO_ID...
+//!
 //! # Validate existing packs
 //! pack-builder validate --path ./packs/v2026-01/
 //!
@@ -13,19 +22,23 @@
 //! ```
 
 use std::collections::HashMap;
-use std::io::Write;
-use std::path::PathBuf;
+use std::io::{BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
 use anyhow::{Context, Result};
-use chrono::NaiveDate;
+use chrono::{Datelike, NaiveDate};
 use clap::{Parser, Subcommand};
 use indicatif::{ProgressBar, ProgressStyle};
 use serde::Deserialize;
 
+use dguesser_db::locations::{PackSourceLocation, list_pack_source_locations};
 use dguesser_locations::bucket::{BucketKey, ScoutBucket, YearBucket};
+use dguesser_locations::builder::{BuildSummary, PackBuilder};
 use dguesser_locations::index::CountryIndex;
 use dguesser_locations::manifest::Manifest;
 use dguesser_locations::pack::{PackRecord, RECORD_SIZE};
+use dguesser_locations::tombstone::CountryTombstones;
 
 // =============================================================================
 // CLI
@@ -33,7 +46,7 @@ use dguesser_locations::pack::{PackRecord, RECORD_SIZE};
 
 #[derive(Parser)]
 #[command(name = "pack-builder")]
-#[command(about = "Build R2 location packs from Vali JSON files")]
+#[command(about = "Build R2 location packs from Vali JSON files or Postgres")]
 struct Cli {
     #[command(subcommand)]
     command: Commands,
@@ -70,6 +83,71 @@ enum Commands {
         /// Dry run - show what would be built without writing files
         #[arg(long)]
         dry_run: bool,
+
+        /// Upload the built version to this rclone remote (e.g. "dguesser-r2:dguesser-cdn")
+        #[arg(long)]
+        upload: Option<String>,
+    },
+
+    /// Build packs from validated locations in Postgres
+    BuildDb {
+        /// Database URL
+        #[arg(long, env = "DATABASE_URL")]
+        database_url: String,
+
+        /// Output directory for pack files
+        #[arg(short, long)]
+        output: PathBuf,
+
+        /// Dataset version (e.g., "v2026-02"); must not exist on the remote yet
+        #[arg(short, long)]
+        version: String,
+
+        /// Minimum capture year (filter out older coverage)
+        #[arg(long)]
+        min_year: Option<i32>,
+
+        /// Maximum capture year
+        #[arg(long)]
+        max_year: Option<i32>,
+
+        /// Filter out trekker/scout coverage
+        #[arg(long)]
+        outdoor_only: bool,
+
+        /// Upload the built version to this rclone remote (e.g. "dguesser-r2:dguesser-cdn")
+        #[arg(long)]
+        upload: Option<String>,
+    },
+
+    /// Upload a built version to R2 under its version prefix
+    Upload {
+        /// Path to version directory (e.g., ./packs/v2026-02/)
+        #[arg(short, long)]
+        path: PathBuf,
+
+        /// rclone remote and bucket (e.g. "dguesser-r2:dguesser-cdn")
+        #[arg(short, long)]
+        remote: String,
+    },
+
+    /// Exclude panoramas from a built version by writing tombstone files
+    Tombstone {
+        /// Path to version directory (e.g., ./packs/v2026-02/)
+        #[arg(short, long)]
+        path: PathBuf,
+
+        /// Panorama IDs to exclude
+        #[arg(required_unless_present = "file")]
+        pano_ids: Vec<String>,
+
+        /// File with one panorama ID per line ('#' starts a comment)
+        #[arg(short, long)]
+        file: Option<PathBuf>,
+
+        /// Upload changed tombstone files to this rclone remote (e.g. "dguesser-r2:dguesser-cdn")
+        #[arg(long)]
+        upload: Option<String>,
     },
 
     /// Validate existing packs
@@ -347,43 +425,17 @@ impl ValiLocation {
         }
 
         // Convert year/month to days since epoch
-        let capture_days = match (self.year, self.month) {
-            (Some(y), Some(m)) => {
-                let days = NaiveDate::from_ymd_opt(y, m as u32, 15).map(|d| days_since_epoch(d));
-                match days {
-                    Some(d) if d < 0 || d > 65535 => {
-                        tracing::warn!(
-                            year = y,
-                            month = m,
-                            days = d,
-                            "Capture date out of u16 range"
-                        );
-                        None
-                    }
-                    Some(d) => Some(d as u16),
-                    None => None,
-                }
-            }
-            (Some(y), None) => {
-                let days = NaiveDate::from_ymd_opt(y, 6, 15).map(|d| days_since_epoch(d));
-                match days {
-                    Some(d) if d < 0 || d > 65535 => {
-                        tracing::warn!(year = y, days = d, "Capture date out of u16 range");
-                        None
-                    }
-                    Some(d) => Some(d as u16),
-                    None => None,
-                }
-            }
-            _ => None,
-        };
+        let capture = self
+            .year
+            .and_then(|y| NaiveDate::from_ymd_opt(y, self.month.unwrap_or(6) as u32, 15))
+            .and_then(capture_days);
 
         PackRecord::new(
             pano_id,
             self.lat,
             self.lng,
             self.subdivision_code.clone(),
-            capture_days,
+            capture,
             self.is_scout.unwrap_or(false),
             self.heading,
             self.surface.clone(),
@@ -409,29 +461,7 @@ impl ValiLocation {
         max_year: Option<i32>,
         outdoor_only: bool,
     ) -> bool {
-        // Filter by year
-        if let Some(min) = min_year {
-            if let Some(year) = self.year {
-                if year < min {
-                    return true;
-                }
-            }
-        }
-
-        if let Some(max) = max_year {
-            if let Some(year) = self.year {
-                if year > max {
-                    return true;
-                }
-            }
-        }
-
-        // Filter trekker/scout coverage
-        if outdoor_only && self.is_scout.unwrap_or(false) {
-            return true;
-        }
-
-        false
+        !passes_filters(self.year, self.is_scout.unwrap_or(false), min_year, max_year, outdoor_only)
     }
 }
 
@@ -440,6 +470,63 @@ fn days_since_epoch(date: NaiveDate) -> i64 {
     (date - epoch).num_days()
 }
 
+/// Capture date as pack days since epoch, if it fits in the record field.
+fn capture_days(date: NaiveDate) -> Option<u16> {
+    let days = days_since_epoch(date);
+    match u16::try_from(days) {
+        Ok(d) => Some(d),
+        Err(_) => {
+            tracing::warn!(date = %date, days, "Capture date out of u16 range");
+            None
+        }
+    }
+}
+
+/// Convert a Postgres location into its bucket and pack record.
+fn db_pack_record(loc: &PackSourceLocation) -> (BucketKey, PackRecord) {
+    let is_scout = loc.is_scout.unwrap_or(false);
+    let key = BucketKey::new(
+        YearBucket::from_year(loc.capture_date.map(|d| d.year())),
+        ScoutBucket::from_is_scout(is_scout),
+    );
+    let record = PackRecord::new(
+        loc.panorama_id.clone(),
+        loc.lat,
+        loc.lng,
+        loc.subdivision_code.clone(),
+        loc.capture_date.and_then(capture_days),
+        is_scout,
+        loc.heading,
+        loc.surface.clone(),
+        loc.arrow_count.map(|c| c.clamp(0, 254) as u8),
+        loc.buildings_100.map(|c| c.clamp(0, 65534) as u16),
+        loc.roads_100.map(|c| c.clamp(0, 65534) as u16),
+        loc.elevation.map(|e| e.clamp(-32766, 32766) as i16),
+    );
+    (key, record)
+}
+
+/// Whether a capture year/scout flag passes the build filters.
+fn passes_filters(
+    year: Option<i32>,
+    is_scout: bool,
+    min_year: Option<i32>,
+    max_year: Option<i32>,
+    outdoor_only: bool,
+) -> bool {
+    if let (Some(min), Some(year)) = (min_year, year)
+        && year < min
+    {
+        return false;
+    }
+    if let (Some(max), Some(year)) = (max_year, year)
+        && year > max
+    {
+        return false;
+    }
+    !(outdoor_only && is_scout)
+}
+
 /// Parse Vali JSON in either detailed format or generate output format.
 fn parse_vali_json(content: &str) -> Result<Vec<ValiLocation>> {
     // Try the generate output format FIRST (with tags) since it's most common from `vali generate`
@@ -470,15 +557,17 @@ fn parse_vali_json(content: &str) -> Result<Vec<ValiLocation>> {
 // Build Command
 // =============================================================================
 
+/// Build packs from Vali JSON. Returns the version directory unless this is
+/// a dry run.
 fn build_packs(
     input: &PathBuf,
-    output: &PathBuf,
+    output: &Path,
     version: &str,
     min_year: Option<i32>,
     max_year: Option<i32>,
     outdoor_only: bool,
     dry_run: bool,
-) -> Result<()> {
+) -> Result<Option<PathBuf>> {
     // Determine if input is a file or directory
     let is_single_file = input.is_file();
 
@@ -532,135 +621,263 @@ fn build_packs(
         }
     }
 
-    // Create output directory structure
-    let version_dir = output.join(version);
-    let countries_dir = version_dir.join("countries");
-
-    if !dry_run {
-        std::fs::create_dir_all(&countries_dir)?;
-    }
-
-    // Track stats for manifest
-    let mut manifest = Manifest::new(version);
-    let mut total_locations = 0u64;
+    let mut builder = PackBuilder::new(version);
     let mut total_filtered = 0u64;
 
-    // Sort countries for deterministic output
-    let mut countries: Vec<_> = country_locations.keys().cloned().collect();
-    countries.sort();
-
-    // Progress bar
-    let pb = ProgressBar::new(countries.len() as u64);
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} {msg}")
-            .unwrap()
-            .progress_chars("#>-"),
-    );
-
-    // Process each country
-    for country_code in countries {
-        pb.set_message(country_code.clone());
-
-        let locations = country_locations.remove(&country_code).unwrap_or_default();
-        let raw_count = locations.len();
-
-        // Group by bucket and filter
-        let mut buckets: HashMap<BucketKey, Vec<PackRecord>> = HashMap::new();
-
+    for (country_code, locations) in country_locations {
         for loc in locations {
             if loc.should_filter(min_year, max_year, outdoor_only) {
                 total_filtered += 1;
                 continue;
             }
-
-            let key = loc.bucket_key();
-            let record = loc.to_pack_record();
-            buckets.entry(key).or_default().push(record);
+            builder.add(&country_code, loc.bucket_key(), loc.to_pack_record());
         }
+    }
 
-        // Create country index
-        let mut country_index = CountryIndex::new(&country_code, version);
-        let country_dir = countries_dir.join(&country_code);
+    if dry_run {
+        println!("\n=== Build Summary (dry run - no files written) ===\n");
+        println!("  Version: {}", version);
+        println!("  Total locations: {}", builder.len());
+        println!("  Filtered out: {}", total_filtered);
+        println!();
+        return Ok(None);
+    }
 
-        if !dry_run {
-            std::fs::create_dir_all(&country_dir)?;
-        }
+    let summary = builder.write(output)?;
+    print_build_summary(version, &summary, total_filtered);
+    Ok(Some(summary.version_dir))
+}
+
+fn print_build_summary(version: &str, summary: &BuildSummary, filtered: u64) {
+    println!("\n=== Build Summary ===\n");
+    println!("  Version: {}", version);
+    println!("  Countries: {}", summary.countries);
+    println!("  Total locations: {}", summary.records);
+    println!("  Filtered out: {}", filtered);
+    println!("  Duplicate panoramas: {}", summary.duplicates);
+    println!("  Output: {}", summary.version_dir.display());
+    println!();
+}
+
+// =============================================================================
+// Build From Database Command
+// =============================================================================
 
-        let mut country_total = 0u64;
+/// Locations fetched from Postgres per page
+const DB_PAGE_SIZE: i64 = 10_000;
+
+async fn build_packs_from_db(
+    database_url: &str,
+    output: &Path,
+    version: &str,
+    min_year: Option<i32>,
+    max_year: Option<i32>,
+    outdoor_only: bool,
+) -> Result<PathBuf> {
+    let pool = dguesser_db::create_pool(database_url).await?;
 
-        // Write pack files for each bucket
-        for (key, mut records) in buckets {
-            let count = records.len() as u64;
-            if count == 0 {
+    let mut builder = PackBuilder::new(version);
+    let mut total_filtered = 0u64;
+    let mut after_id: Option<String> = None;
+
+    let pb = ProgressBar::new_spinner();
+    pb.set_style(ProgressStyle::default_spinner().template("{spinner:.green} {msg}").unwrap());
+
+    loop {
+        let page = list_pack_source_locations(&pool, after_id.as_deref(), DB_PAGE_SIZE).await?;
+        let Some(last) = page.last() else {
+            break;
+        };
+        after_id = Some(last.id.clone());
+
+        for loc in &page {
+            let year = loc.capture_date.map(|d| d.year());
+            if !passes_filters(
+                year,
+                loc.is_scout.unwrap_or(false),
+                min_year,
+                max_year,
+                outdoor_only,
+            ) {
+                total_filtered += 1;
                 continue;
             }
+            let (key, record) = db_pack_record(loc);
+            builder.add(&loc.country_code, key, record);
+        }
+        pb.set_message(format!("Loaded {} locations", builder.len()));
+    }
+    pb.finish_and_clear();
 
-            // Shuffle records for randomness
-            use rand::prelude::IndexedMutRandom;
-            let mut rng = rand::rng();
-            records.shuffle(&mut rng);
+    if builder.is_empty() {
+        anyhow::bail!("No playable locations found in the database");
+    }
 
-            // Write pack file
-            let pack_name = format!("{}_{}.pack", country_code, key.file_suffix());
-            let pack_path = country_dir.join(&pack_name);
+    let summary = builder.write(output)?;
+    print_build_summary(version, &summary, total_filtered);
+    Ok(summary.version_dir)
+}
 
-            if !dry_run {
-                let mut file = std::fs::File::create(&pack_path)?;
-                for record in &records {
-                    file.write_all(&record.encode())?;
-                }
-            }
+// =============================================================================
+// Upload Command
+// =============================================================================
 
-            country_index.add_bucket(key, count);
-            country_total += count;
-        }
+/// Upload a version directory to `{remote}/{version}` with rclone.
+///
+/// Versions are immutable: the upload is refused if the prefix already has
+/// objects. The manifest goes up last so servers pointed at the new version
+/// never see it before all packs and indexes are in place.
+fn upload_packs(version_dir: &Path, remote: &str) -> Result<()> {
+    let manifest_path = version_dir.join("manifest.json");
+    let manifest: Manifest = serde_json::from_str(
+        &std::fs::read_to_string(&manifest_path)
+            .with_context(|| format!("Cannot read manifest at {}", manifest_path.display()))?,
+    )?;
+    let target = format!("{}/{}", remote.trim_end_matches('/'), manifest.version);
+
+    let existing = Command::new("rclone")
+        .args(["lsf", "--max-depth", "1", &target])
+        .output()
+        .context("Failed to run rclone (is it installed and configured?)")?;
+    if existing.status.success() && !existing.stdout.is_empty() {
+        anyhow::bail!("{} already exists; build under a new version instead", target);
+    }
 
-        total_locations += country_total;
+    println!("Uploading {} to {}", version_dir.display(), target);
+    let dir = version_dir.to_string_lossy();
+    run_rclone(&["copy", &dir, &target, "--exclude", "manifest.json", "--transfers", "16"])?;
+    run_rclone(&["copyto", &manifest_path.to_string_lossy(), &format!("{target}/manifest.json")])?;
 
-        // Write country index
-        if !dry_run && country_total > 0 {
-            let index_path = country_dir.join("index.json");
-            let index_json = serde_json::to_string_pretty(&country_index)?;
-            std::fs::write(index_path, index_json)?;
-        }
+    println!("\nUploaded {} ({} locations)", manifest.version, manifest.total_count);
+    println!("Point servers at it with LOCATION_R2_VERSION={}\n", manifest.version);
+    Ok(())
+}
 
-        // Add to manifest
-        if country_total > 0 {
-            manifest.add_country(&country_code, country_total, None);
-        }
+fn run_rclone(args: &[&str]) -> Result<()> {
+    let status = Command::new("rclone")
+        .args(args)
+        .arg("--progress")
+        .status()
+        .context("Failed to run rclone (is it installed and configured?)")?;
+    if !status.success() {
+        anyhow::bail!("rclone {} failed with {}", args[0], status);
+    }
+    Ok(())
+}
 
-        pb.inc(1);
-        tracing::info!(
-            country = %country_code,
-            raw = raw_count,
-            processed = country_total,
-            "Processed country"
+// =============================================================================
+// Tombstone Command
+// =============================================================================
+
+/// Cache lifetime for uploaded tombstone files, so CDN edges pick up new
+/// exclusions quickly.
+const TOMBSTONE_CACHE_CONTROL: &str = "Cache-Control: public, max-age=60";
+
+/// Read panorama IDs from the command line and an optional list file.
+fn collect_pano_ids(pano_ids: Vec<String>, file: Option<&Path>) -> Result<Vec<String>> {
+    let mut ids = pano_ids;
+    if let Some(file) = file {
+        let content = std::fs::read_to_string(file)
+            .with_context(|| format!("Cannot read {}", file.display()))?;
+        ids.extend(
+            content
+                .lines()
+                .map(|line| line.split('#').next().unwrap_or("").trim())
+                .filter(|line| !line.is_empty())
+                .map(str::to_string),
         );
     }
+    Ok(ids)
+}
 
-    pb.finish_with_message("Done");
+/// Find the given panoramas in a version's packs and add them to the
+/// per-country tombstone files. Returns the tombstone files that changed.
+fn tombstone_packs(path: &Path, pano_ids: &[String]) -> Result<Vec<PathBuf>> {
+    let manifest_path = path.join("manifest.json");
+    let manifest: Manifest = serde_json::from_str(
+        &std::fs::read_to_string(&manifest_path)
+            .with_context(|| format!("Cannot read manifest at {}", manifest_path.display()))?,
+    )?;
 
-    // Write manifest
-    if !dry_run {
-        let manifest_path = version_dir.join("manifest.json");
-        let manifest_json = serde_json::to_string_pretty(&manifest)?;
-        std::fs::write(manifest_path, manifest_json)?;
+    let mut remaining: HashMap<u64, &str> =
+        pano_ids.iter().map(|id| (PackRecord::hash_pano_id(id), id.as_str())).collect();
+    let mut changed = Vec::new();
+
+    let mut countries: Vec<_> = manifest.country_codes();
+    countries.sort_unstable();
+
+    for country in countries {
+        if remaining.is_empty() {
+            break;
+        }
+        let country_dir = path.join("countries").join(country);
+        let index: CountryIndex =
+            serde_json::from_str(&std::fs::read_to_string(country_dir.join("index.json"))?)?;
+
+        let tombstone_path = country_dir.join("tombstones.json");
+        let mut tombstones = match std::fs::read_to_string(&tombstone_path) {
+            Ok(content) => serde_json::from_str(&content)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                CountryTombstones::new(country, &manifest.version)
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let mut added = 0;
+
+        for info in index.buckets.values() {
+            let mut pack = BufReader::new(std::fs::File::open(country_dir.join(&info.object))?);
+            let mut buf = [0u8; RECORD_SIZE];
+            for _ in 0..info.count {
+                pack.read_exact(&mut buf)?;
+                let record = PackRecord::decode(&buf)?;
+                if let Some(pano_id) = remaining.remove(&record.id_hash) {
+                    if tombstones.add(&info.object, record.id_hash) {
+                        added += 1;
+                    }
+                    println!("  {} -> {}/{}", pano_id, country, info.object);
+                }
+            }
+        }
+
+        if added > 0 {
+            std::fs::write(&tombstone_path, serde_json::to_vec_pretty(&tombstones)?)?;
+            changed.push(tombstone_path);
+        }
     }
 
-    // Summary
-    println!("\n=== Build Summary ===\n");
-    println!("  Version: {}", version);
-    println!("  Countries: {}", manifest.countries.len());
-    println!("  Total locations: {}", total_locations);
-    println!("  Filtered out: {}", total_filtered);
-    if !dry_run {
-        println!("  Output: {}", version_dir.display());
-    } else {
-        println!("  (dry run - no files written)");
+    let mut missing: Vec<_> = remaining.into_values().collect();
+    missing.sort_unstable();
+    for pano_id in &missing {
+        println!("WARNING: {} is not in version {}", pano_id, manifest.version);
     }
-    println!();
 
+    println!(
+        "\nTombstoned {} of {} panoramas in {} countries\n",
+        pano_ids.len() - missing.len(),
+        pano_ids.len(),
+        changed.len()
+    );
+    Ok(changed)
+}
+
+/// Upload changed tombstone files to `{remote}/{version}/countries/{CC}/`.
+///
+/// These are the only files replaced inside a published version.
+fn upload_tombstones(version_dir: &Path, files: &[PathBuf], remote: &str) -> Result<()> {
+    let manifest: Manifest =
+        serde_json::from_str(&std::fs::read_to_string(version_dir.join("manifest.json"))?)?;
+    let target = format!("{}/{}", remote.trim_end_matches('/'), manifest.version);
+
+    for file in files {
+        let relative = file.strip_prefix(version_dir)?.to_string_lossy().replace('\\', "/");
+        run_rclone(&[
+            "copyto",
+            &file.to_string_lossy(),
+            &format!("{target}/{relative}"),
+            "--header-upload",
+            TOMBSTONE_CACHE_CONTROL,
+        ])?;
+    }
     Ok(())
 }
 
@@ -719,6 +936,19 @@ fn validate_packs(path: &PathBuf) -> Result<()> {
             }
         }
 
+        // Tombstones must point at packs listed in the index
+        let tombstone_path = country_dir.join("tombstones.json");
+        if tombstone_path.exists() {
+            let tombstones: CountryTombstones =
+                serde_json::from_str(&std::fs::read_to_string(&tombstone_path)?)?;
+            for pack in tombstones.packs.keys() {
+                if !index.buckets.values().any(|info| &info.object == pack) {
+                    println!("WARNING: Tombstones for unknown pack {} in {}", pack, country);
+                    warnings += 1;
+                }
+            }
+        }
+
         // Check total count matches manifest
         if index.total_count() != summary.count {
             println!(
@@ -886,7 +1116,7 @@ fn generate_sample_packs(
             .collect();
 
         // Shuffle records
-        use rand::prelude::IndexedMutRandom;
+        use rand::seq::SliceRandom;
         records.shuffle(&mut rng);
 
         // Distribute across a couple of buckets
@@ -955,7 +1185,8 @@ fn generate_sample_packs(
 // Main
 // =============================================================================
 
-fn main() -> Result<()> {
+#[tokio::main]
+async fn main() -> Result<()> {
     // Load .env
     dotenvy::dotenv().ok();
 
@@ -970,8 +1201,53 @@ fn main() -> Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Build { input, output, version, min_year, max_year, outdoor_only, dry_run } => {
-            build_packs(&input, &output, &version, min_year, max_year, outdoor_only, dry_run)?;
+        Commands::Build {
+            input,
+            output,
+            version,
+            min_year,
+            max_year,
+            outdoor_only,
+            dry_run,
+            upload,
+        } => {
+            let version_dir =
+                build_packs(&input, &output, &version, min_year, max_year, outdoor_only, dry_run)?;
+            if let (Some(version_dir), Some(remote)) = (version_dir, upload) {
+                upload_packs(&version_dir, &remote)?;
+            }
+        }
+        Commands::BuildDb {
+            database_url,
+            output,
+            version,
+            min_year,
+            max_year,
+            outdoor_only,
+            upload,
+        } => {
+            let version_dir = build_packs_from_db(
+                &database_url,
+                &output,
+                &version,
+                min_year,
+                max_year,
+                outdoor_only,
+            )
+            .await?;
+            if let Some(remote) = upload {
+                upload_packs(&version_dir, &remote)?;
+            }
+        }
+        Commands::Upload { path, remote } => {
+            upload_packs(&path, &remote)?;
+        }
+        Commands::Tombstone { path, pano_ids, file, upload } => {
+            let pano_ids = collect_pano_ids(pano_ids, file.as_deref())?;
+            let changed = tombstone_packs(&path, &pano_ids)?;
+            if let Some(remote) = upload {
+                upload_tombstones(&path, &changed, &remote)?;
+            }
         }
         Commands::Validate { path } => {
             validate_packs(&path)?;
@@ -0,0 +1,194 @@
+//! Pack building: group records into country/bucket packs and write a
+//! complete dataset version (pack files, country indexes, manifest).
+//!
+//! Output layout, matching what [`FileReader`](crate::reader::FileReader) and
+//! [`HttpReader`](crate::reader::HttpReader) read:
+//!
+//! ```text
+//! {output}/{version}/manifest.json
+//! {output}/{version}/countries/{CC}/index.json
+//! {output}/{version}/countries/{CC}/{CC}_{B}_{S}.pack
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use rand::seq::SliceRandom;
+
+use crate::bucket::BucketKey;
+use crate::error::LocationPackError;
+use crate::index::CountryIndex;
+use crate::manifest::Manifest;
+use crate::pack::PackRecord;
+
+/// Totals for a finished build.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BuildSummary {
+    /// Directory the version was written to
+    pub version_dir: PathBuf,
+    /// Countries with at least one record
+    pub countries: usize,
+    /// Records written
+    pub records: u64,
+    /// Records dropped because their panorama ID was already added
+    pub duplicates: u64,
+}
+
+/// Accumulates records and writes them out as one dataset version.
+#[derive(Debug)]
+pub struct PackBuilder {
+    version: String,
+    countries: HashMap<String, HashMap<BucketKey, Vec<PackRecord>>>,
+    seen: HashSet<u64>,
+    duplicates: u64,
+}
+
+impl PackBuilder {
+    /// Start an empty dataset version.
+    pub fn new(version: &str) -> Self {
+        Self {
+            version: version.to_string(),
+            countries: HashMap::new(),
+            seen: HashSet::new(),
+            duplicates: 0,
+        }
+    }
+
+    /// Add a record to a country's bucket. Returns false (and drops the
+    /// record) if the same panorama was already added.
+    pub fn add(&mut self, country: &str, key: BucketKey, record: PackRecord) -> bool {
+        if !self.seen.insert(record.id_hash) {
+            self.duplicates += 1;
+            return false;
+        }
+        self.countries
+            .entry(country.to_uppercase())
+            .or_default()
+            .entry(key)
+            .or_default()
+            .push(record);
+        true
+    }
+
+    /// Number of records added so far.
+    pub fn len(&self) -> u64 {
+        self.countries.values().flat_map(|b| b.values()).map(|r| r.len() as u64).sum()
+    }
+
+    /// Whether no records have been added.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Shuffle every bucket and write the dataset under `output/{version}`.
+    ///
+    /// The manifest is written last, so readers never see a version whose
+    /// manifest references packs that are not there yet.
+    pub fn write(self, output: &Path) -> Result<BuildSummary, LocationPackError> {
+        let version_dir = output.join(&self.version);
+        let countries_dir = version_dir.join("countries");
+        std::fs::create_dir_all(&countries_dir)?;
+
+        let mut manifest = Manifest::new(&self.version);
+        let mut rng = rand::rng();
+        let mut records_written = 0u64;
+
+        let mut countries: Vec<_> = self.countries.into_iter().collect();
+        countries.sort_by(|a, b| a.0.cmp(&b.0));
+
+        for (country, buckets) in countries {
+            let country_dir = countries_dir.join(&country);
+            std::fs::create_dir_all(&country_dir)?;
+
+            let mut index = CountryIndex::new(&country, &self.version);
+            for (key, mut records) in buckets {
+                if records.is_empty() {
+                    continue;
+                }
+                // Packs are read at random offsets, so they must be pre-shuffled
+                records.shuffle(&mut rng);
+
+                let pack_name = format!("{}_{}.pack", country, key.file_suffix());
+                let mut file = BufWriter::new(std::fs::File::create(country_dir.join(pack_name))?);
+                for record in &records {
+                    file.write_all(&record.encode())?;
+                }
+                file.flush()?;
+
+                index.add_bucket(key, records.len() as u64);
+            }
+
+            let total = index.total_count();
+            std::fs::write(country_dir.join("index.json"), serde_json::to_vec_pretty(&index)?)?;
+            manifest.add_country(&country, total, None);
+            records_written += total;
+        }
+
+        std::fs::write(version_dir.join("manifest.json"), serde_json::to_vec_pretty(&manifest)?)?;
+
+        Ok(BuildSummary {
+            version_dir,
+            countries: manifest.countries.len(),
+            records: records_written,
+            duplicates: self.duplicates,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bucket::{ScoutBucket, YearBucket};
+    use crate::pack::RECORD_SIZE;
+
+    fn record(pano_id: &str) -> PackRecord {
+        PackRecord::new(
+            pano_id.to_string(),
+            48.85,
+            2.35,
+            None,
+            Some(19000),
+            false,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_write_dataset() {
+        let dir = tempfile::tempdir().unwrap();
+        let outdoor = BucketKey::new(YearBucket::B6, ScoutBucket::S0);
+        let scout = BucketKey::new(YearBucket::B6, ScoutBucket::S1);
+
+        let mut builder = PackBuilder::new("v-test");
+        assert!(builder.add("fr", outdoor, record("a")));
+        assert!(builder.add("FR", outdoor, record("b")));
+        assert!(builder.add("FR", scout, record("c")));
+        assert!(!builder.add("FR", outdoor, record("a")));
+        assert_eq!(builder.len(), 3);
+
+        let summary = builder.write(dir.path()).unwrap();
+        assert_eq!(summary.countries, 1);
+        assert_eq!(summary.records, 3);
+        assert_eq!(summary.duplicates, 1);
+
+        let manifest: Manifest = serde_json::from_slice(
+            &std::fs::read(summary.version_dir.join("manifest.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(manifest.total_count, 3);
+
+        let pack = summary.version_dir.join("countries/FR/FR_B6_S0.pack");
+        assert_eq!(std::fs::metadata(pack).unwrap().len(), 2 * RECORD_SIZE as u64);
+        let index: CountryIndex = serde_json::from_slice(
+            &std::fs::read(summary.version_dir.join("countries/FR/index.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(index.total_count(), 3);
+    }
+}
@@ -1,12 +1,26 @@
-//! LRU cache for disabled location hashes.
+//! In-memory caches for the pack provider.
 //!
-//! This module provides an in-memory cache of disabled location hashes
-//! to avoid database lookups on every selection.
+//! - [`DisabledCache`]: disabled location hashes, to avoid database lookups
+//!   on every selection
+//! - [`RangeCache`] / [`CachedReader`]: an LRU of pack byte ranges and
+//!   country indexes in front of any [`RangeReader`], with an optional
+//!   shared Redis tier, to cut storage requests for popular maps
 
 use std::collections::HashSet;
-use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
 
+use bytes::{Bytes, BytesMut};
 use lru::LruCache;
+use serde::Serialize;
+
+use crate::error::LocationPackError;
+use crate::index::CountryIndex;
+use crate::manifest::Manifest;
+use crate::pack::RECORD_SIZE;
+use crate::reader::{RangeReader, async_trait};
+use crate::tombstone::CountryTombstones;
 
 /// Cache for tracking disabled location hashes.
 ///
@@ -165,6 +179,370 @@ impl Default for DisabledCache {
     }
 }
 
+/// Configuration for the range cache.
+#[derive(Debug, Clone)]
+pub struct RangeCacheConfig {
+    /// Memory budget for cached pack blocks, in bytes.
+    pub max_bytes: usize,
+    /// Records per cached block. Reads are widened to whole blocks so
+    /// nearby random reads share cache entries.
+    pub block_records: usize,
+    /// Maximum number of country indexes to keep.
+    pub max_indexes: usize,
+    /// Expiry for blocks stored in the Redis tier.
+    pub redis_ttl: Duration,
+}
+
+impl Default for RangeCacheConfig {
+    fn default() -> Self {
+        Self {
+            max_bytes: 64 * 1024 * 1024,
+            block_records: 64,
+            max_indexes: 256,
+            redis_ttl: Duration::from_secs(3600),
+        }
+    }
+}
+
+/// Snapshot of range cache counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct RangeCacheStats {
+    /// Block reads served from memory.
+    pub block_hits: u64,
+    /// Block reads that missed memory.
+    pub block_misses: u64,
+    /// Memory misses served from the Redis tier.
+    pub redis_hits: u64,
+    /// Country index reads served from memory.
+    pub index_hits: u64,
+    /// Country index reads that went to storage.
+    pub index_misses: u64,
+    /// Blocks currently in memory.
+    pub blocks: u64,
+    /// Bytes currently in memory.
+    pub bytes: u64,
+}
+
+/// Identifies one cached block of a pack file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct BlockKey {
+    pack: String,
+    block: u64,
+}
+
+/// LRU of blocks bounded by total size rather than entry count.
+struct BlockLru {
+    entries: LruCache<BlockKey, Bytes>,
+    bytes: usize,
+    max_bytes: usize,
+}
+
+impl BlockLru {
+    fn get(&mut self, key: &BlockKey) -> Option<Bytes> {
+        self.entries.get(key).cloned()
+    }
+
+    fn put(&mut self, key: BlockKey, data: Bytes) {
+        if data.len() > self.max_bytes {
+            return;
+        }
+        self.bytes += data.len();
+        if let Some(old) = self.entries.put(key, data) {
+            self.bytes -= old.len();
+        }
+        while self.bytes > self.max_bytes {
+            match self.entries.pop_lru() {
+                Some((_, evicted)) => self.bytes -= evicted.len(),
+                None => break,
+            }
+        }
+    }
+}
+
+/// Shared Redis tier: blocks evicted from (or not yet in) one instance's
+/// memory can still be served without hitting storage.
+#[cfg(feature = "redis")]
+struct RedisTier {
+    conn: dguesser_redis::RedisConnection,
+    key_prefix: String,
+}
+
+#[derive(Default)]
+struct RangeCacheCounters {
+    block_hits: AtomicU64,
+    block_misses: AtomicU64,
+    redis_hits: AtomicU64,
+    index_hits: AtomicU64,
+    index_misses: AtomicU64,
+}
+
+/// LRU cache of pack blocks and country indexes.
+///
+/// Shared between a [`CachedReader`] and whoever reports its statistics.
+pub struct RangeCache {
+    config: RangeCacheConfig,
+    blocks: Mutex<BlockLru>,
+    indexes: Mutex<LruCache<String, Arc<CountryIndex>>>,
+    counters: RangeCacheCounters,
+    #[cfg(feature = "redis")]
+    redis: Option<RedisTier>,
+}
+
+impl RangeCache {
+    /// Create a memory-only cache.
+    pub fn new(config: RangeCacheConfig) -> Self {
+        let max_indexes =
+            std::num::NonZeroUsize::new(config.max_indexes.max(1)).expect("max_indexes is >= 1");
+        Self {
+            blocks: Mutex::new(BlockLru {
+                entries: LruCache::unbounded(),
+                bytes: 0,
+                max_bytes: config.max_bytes,
+            }),
+            indexes: Mutex::new(LruCache::new(max_indexes)),
+            counters: RangeCacheCounters::default(),
+            config,
+            #[cfg(feature = "redis")]
+            redis: None,
+        }
+    }
+
+    /// Add a Redis tier shared by all instances.
+    ///
+    /// `key_prefix` must identify the dataset version, since blocks of
+    /// different versions share pack names.
+    #[cfg(feature = "redis")]
+    pub fn with_redis(mut self, conn: dguesser_redis::RedisConnection, key_prefix: &str) -> Self {
+        self.redis = Some(RedisTier { conn, key_prefix: key_prefix.to_string() });
+        self
+    }
+
+    /// Size of one block in bytes.
+    fn block_size(&self) -> u64 {
+        (self.config.block_records.max(1) * RECORD_SIZE) as u64
+    }
+
+    /// Current counters.
+    pub fn stats(&self) -> RangeCacheStats {
+        let blocks = self.blocks.lock().unwrap();
+        RangeCacheStats {
+            block_hits: self.counters.block_hits.load(Ordering::Relaxed),
+            block_misses: self.counters.block_misses.load(Ordering::Relaxed),
+            redis_hits: self.counters.redis_hits.load(Ordering::Relaxed),
+            index_hits: self.counters.index_hits.load(Ordering::Relaxed),
+            index_misses: self.counters.index_misses.load(Ordering::Relaxed),
+            blocks: blocks.entries.len() as u64,
+            bytes: blocks.bytes as u64,
+        }
+    }
+
+    /// Drop all cached blocks and indexes (counters are kept).
+    pub fn clear(&self) {
+        let mut blocks = self.blocks.lock().unwrap();
+        blocks.entries.clear();
+        blocks.bytes = 0;
+        self.indexes.lock().unwrap().clear();
+    }
+
+    fn get_index(&self, country: &str) -> Option<Arc<CountryIndex>> {
+        let index = self.indexes.lock().unwrap().get(country).cloned();
+        let counter =
+            if index.is_some() { &self.counters.index_hits } else { &self.counters.index_misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        index
+    }
+
+    fn put_index(&self, country: &str, index: Arc<CountryIndex>) {
+        self.indexes.lock().unwrap().put(country.to_string(), index);
+    }
+
+    /// Look a block up in memory, then in Redis.
+    async fn get_block(&self, key: &BlockKey) -> Option<Bytes> {
+        if let Some(data) = self.blocks.lock().unwrap().get(key) {
+            self.counters.block_hits.fetch_add(1, Ordering::Relaxed);
+            return Some(data);
+        }
+        self.counters.block_misses.fetch_add(1, Ordering::Relaxed);
+
+        #[cfg(feature = "redis")]
+        if let Some(data) = self.redis_get(key).await {
+            self.counters.redis_hits.fetch_add(1, Ordering::Relaxed);
+            self.blocks.lock().unwrap().put(key.clone(), data.clone());
+            return Some(data);
+        }
+
+        None
+    }
+
+    /// Store a block fetched from storage.
+    async fn put_block(&self, key: BlockKey, data: Bytes) {
+        #[cfg(feature = "redis")]
+        self.redis_set(&key, &data).await;
+
+        self.blocks.lock().unwrap().put(key, data);
+    }
+
+    #[cfg(feature = "redis")]
+    fn redis_key(tier: &RedisTier, key: &BlockKey) -> String {
+        format!("{}:{}:{}", tier.key_prefix, key.pack, key.block)
+    }
+
+    #[cfg(feature = "redis")]
+    async fn redis_get(&self, key: &BlockKey) -> Option<Bytes> {
+        let tier = self.redis.as_ref()?;
+        let mut conn = tier.conn.clone();
+        match redis::cmd("GET")
+            .arg(Self::redis_key(tier, key))
+            .query_async::<Option<Vec<u8>>>(&mut conn)
+            .await
+        {
+            Ok(data) => data.map(Bytes::from),
+            Err(e) => {
+                tracing::debug!(error = %e, "Range cache Redis read failed");
+                None
+            }
+        }
+    }
+
+    #[cfg(feature = "redis")]
+    async fn redis_set(&self, key: &BlockKey, data: &Bytes) {
+        let Some(tier) = self.redis.as_ref() else {
+            return;
+        };
+        let mut conn = tier.conn.clone();
+        let result = redis::cmd("SET")
+            .arg(Self::redis_key(tier, key))
+            .arg(data.as_ref())
+            .arg("EX")
+            .arg(self.config.redis_ttl.as_secs().max(1))
+            .query_async::<()>(&mut conn)
+            .await;
+        if let Err(e) = result {
+            tracing::debug!(error = %e, "Range cache Redis write failed");
+        }
+    }
+}
+
+/// [`RangeReader`] that serves pack reads and country indexes from a
+/// [`RangeCache`].
+///
+/// Reads are widened to whole blocks (clamped to the pack size from the
+/// country index). Manifests and tombstones are always read through, since
+/// they must stay fresh.
+pub struct CachedReader<R: RangeReader> {
+    inner: R,
+    cache: Arc<RangeCache>,
+}
+
+impl<R: RangeReader> CachedReader<R> {
+    /// Wrap a reader with a cache.
+    pub fn new(inner: R, cache: Arc<RangeCache>) -> Self {
+        Self { inner, cache }
+    }
+
+    /// The cache backing this reader.
+    pub fn cache(&self) -> &Arc<RangeCache> {
+        &self.cache
+    }
+
+    async fn index(&self, country: &str) -> Result<Arc<CountryIndex>, LocationPackError> {
+        if let Some(index) = self.cache.get_index(country) {
+            return Ok(index);
+        }
+        let index = Arc::new(self.inner.read_country_index(country).await?);
+        self.cache.put_index(country, Arc::clone(&index));
+        Ok(index)
+    }
+
+    /// Read one block, from the cache or from storage.
+    async fn block(
+        &self,
+        country: &str,
+        pack_name: &str,
+        block: u64,
+        pack_size: u64,
+    ) -> Result<Bytes, LocationPackError> {
+        let key = BlockKey { pack: pack_name.to_string(), block };
+        if let Some(data) = self.cache.get_block(&key).await {
+            return Ok(data);
+        }
+
+        let block_size = self.cache.block_size();
+        let start = block * block_size;
+        let length = block_size.min(pack_size - start);
+        let data = self.inner.read_pack_range(country, pack_name, start, length).await?;
+        self.cache.put_block(key, data.clone()).await;
+        Ok(data)
+    }
+}
+
+#[async_trait]
+impl<R: RangeReader> RangeReader for CachedReader<R> {
+    async fn read_manifest(&self) -> Result<Manifest, LocationPackError> {
+        self.inner.read_manifest().await
+    }
+
+    async fn check_available(&self) -> Result<(), LocationPackError> {
+        self.inner.check_available().await
+    }
+
+    async fn read_country_index(&self, country: &str) -> Result<CountryIndex, LocationPackError> {
+        Ok(self.index(country).await?.as_ref().clone())
+    }
+
+    async fn read_pack_range(
+        &self,
+        country: &str,
+        pack_name: &str,
+        offset: u64,
+        length: u64,
+    ) -> Result<Bytes, LocationPackError> {
+        if length == 0 {
+            return Ok(Bytes::new());
+        }
+
+        let pack_size = self
+            .index(country)
+            .await?
+            .buckets
+            .values()
+            .find(|info| info.object == pack_name)
+            .map(|info| info.file_size());
+
+        // Unknown packs and out-of-range reads go straight to storage
+        let Some(pack_size) = pack_size.filter(|&size| offset + length <= size) else {
+            return self.inner.read_pack_range(country, pack_name, offset, length).await;
+        };
+
+        let block_size = self.cache.block_size();
+        let first = offset / block_size;
+        let last = (offset + length - 1) / block_size;
+
+        if first == last {
+            let data = self.block(country, pack_name, first, pack_size).await?;
+            let start = (offset - first * block_size) as usize;
+            return Ok(data.slice(start..start + length as usize));
+        }
+
+        let mut out = BytesMut::with_capacity(length as usize);
+        for block in first..=last {
+            let data = self.block(country, pack_name, block, pack_size).await?;
+            let block_start = block * block_size;
+            let from = offset.saturating_sub(block_start) as usize;
+            let to = ((offset + length - block_start) as usize).min(data.len());
+            out.extend_from_slice(&data[from..to]);
+        }
+        Ok(out.freeze())
+    }
+
+    async fn read_tombstones(
+        &self,
+        country: &str,
+    ) -> Result<Option<CountryTombstones>, LocationPackError> {
+        self.inner.read_tombstones(country).await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -240,3 +618,117 @@ mod tests {
         assert_eq!(cache.disabled_count(), 5);
     }
 }
+
+#[cfg(test)]
+mod range_tests {
+    use super::*;
+    use crate::bucket::{BucketKey, ScoutBucket, YearBucket};
+    use std::sync::atomic::AtomicUsize;
+
+    /// Reader over one in-memory pack of 100 records whose bytes are their
+    /// offsets modulo 251, counting pack reads.
+    struct CountingReader {
+        pack: Vec<u8>,
+        reads: AtomicUsize,
+    }
+
+    impl CountingReader {
+        fn new() -> Self {
+            let pack = (0..100 * RECORD_SIZE).map(|i| (i % 251) as u8).collect();
+            Self { pack, reads: AtomicUsize::new(0) }
+        }
+    }
+
+    #[async_trait]
+    impl RangeReader for CountingReader {
+        async fn read_manifest(&self) -> Result<Manifest, LocationPackError> {
+            Ok(Manifest::new("v-test"))
+        }
+
+        async fn read_country_index(
+            &self,
+            country: &str,
+        ) -> Result<CountryIndex, LocationPackError> {
+            let mut index = CountryIndex::new(country, "v-test");
+            index.add_bucket(BucketKey::new(YearBucket::B4, ScoutBucket::S0), 100);
+            Ok(index)
+        }
+
+        async fn read_pack_range(
+            &self,
+            _country: &str,
+            _pack_name: &str,
+            offset: u64,
+            length: u64,
+        ) -> Result<Bytes, LocationPackError> {
+            self.reads.fetch_add(1, Ordering::Relaxed);
+            let end = (offset + length) as usize;
+            if end > self.pack.len() {
+                return Err(LocationPackError::Storage("read past end".to_string()));
+            }
+            Ok(Bytes::copy_from_slice(&self.pack[offset as usize..end]))
+        }
+    }
+
+    fn cached(max_bytes: usize) -> CachedReader<CountingReader> {
+        let config = RangeCacheConfig { max_bytes, block_records: 16, ..Default::default() };
+        CachedReader::new(CountingReader::new(), Arc::new(RangeCache::new(config)))
+    }
+
+    #[tokio::test]
+    async fn test_reads_match_storage() {
+        let reader = cached(1024 * 1024);
+        let expected = CountingReader::new();
+
+        // Within one block, across blocks, and up to the end of the pack
+        for (offset, length) in [(0, 192), (10 * 192, 16 * 192), (90 * 192, 10 * 192)] {
+            let data = reader.read_pack_range("US", "US_B4_S0.pack", offset, length).await.unwrap();
+            let direct =
+                expected.read_pack_range("US", "US_B4_S0.pack", offset, length).await.unwrap();
+            assert_eq!(data, direct, "offset {offset} length {length}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_repeated_reads_hit_cache() {
+        let reader = cached(1024 * 1024);
+
+        reader.read_pack_range("US", "US_B4_S0.pack", 0, 4 * 192).await.unwrap();
+        reader.read_pack_range("US", "US_B4_S0.pack", 8 * 192, 4 * 192).await.unwrap();
+        assert_eq!(reader.inner.reads.load(Ordering::Relaxed), 1);
+
+        let stats = reader.cache().stats();
+        assert_eq!(stats.block_hits, 1);
+        assert_eq!(stats.block_misses, 1);
+        assert_eq!(stats.index_misses, 1);
+        assert_eq!(stats.index_hits, 1);
+        assert_eq!(stats.blocks, 1);
+        assert_eq!(stats.bytes, 16 * 192);
+    }
+
+    #[tokio::test]
+    async fn test_evicts_to_budget() {
+        // Room for two 16-record blocks
+        let reader = cached(2 * 16 * 192);
+
+        for block in 0..4u64 {
+            reader.read_pack_range("US", "US_B4_S0.pack", block * 16 * 192, 192).await.unwrap();
+        }
+        let stats = reader.cache().stats();
+        assert_eq!(stats.blocks, 2);
+        assert!(stats.bytes <= 2 * 16 * 192);
+
+        // Block 0 was evicted, block 3 is still cached
+        reader.read_pack_range("US", "US_B4_S0.pack", 3 * 16 * 192, 192).await.unwrap();
+        reader.read_pack_range("US", "US_B4_S0.pack", 0, 192).await.unwrap();
+        assert_eq!(reader.inner.reads.load(Ordering::Relaxed), 5);
+    }
+
+    #[tokio::test]
+    async fn test_out_of_range_reads_pass_through() {
+        let reader = cached(1024 * 1024);
+        let result = reader.read_pack_range("US", "US_B4_S0.pack", 99 * 192, 2 * 192).await;
+        assert!(result.is_err());
+        assert_eq!(reader.cache().stats().blocks, 0);
+    }
+}
@@ -0,0 +1,116 @@
+//! Readiness checks for location serving.
+//!
+//! [`LocationHealth`] checks that pack storage is reachable (a HEAD on the
+//! manifest) and that the location provider can select a location from the
+//! default map. Results are reused for [`CHECK_TTL`] so that frequent probes
+//! don't turn into storage traffic.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use dguesser_core::location::LocationProvider;
+
+use crate::reader::RangeReader;
+
+/// How long check results are reused
+pub const CHECK_TTL: Duration = Duration::from_secs(30);
+
+/// Result of one check: latency in milliseconds, or what went wrong
+pub type CheckOutcome = Result<u64, String>;
+
+/// Results of the location checks
+#[derive(Debug, Clone)]
+pub struct LocationChecks {
+    /// Selecting a location from the default map
+    pub provider: CheckOutcome,
+    /// Pack storage reachability (`None` when packs are not used)
+    pub storage: Option<CheckOutcome>,
+}
+
+impl LocationChecks {
+    /// Whether games can start
+    pub fn is_ready(&self) -> bool {
+        self.provider.is_ok() && self.storage.as_ref().is_none_or(Result::is_ok)
+    }
+}
+
+/// Checks a location provider and the pack storage behind it.
+pub struct LocationHealth {
+    provider: Arc<dyn LocationProvider>,
+    storage: Option<Arc<dyn RangeReader>>,
+    last: Mutex<Option<(Instant, LocationChecks)>>,
+}
+
+impl LocationHealth {
+    /// Check `provider`, and `storage` if it serves location packs.
+    pub fn new(provider: Arc<dyn LocationProvider>, storage: Option<Arc<dyn RangeReader>>) -> Self {
+        Self { provider, storage, last: Mutex::new(None) }
+    }
+
+    /// Run the checks, or return results less than [`CHECK_TTL`] old.
+    pub async fn check(&self) -> LocationChecks {
+        if let Some((at, checks)) = self.last.lock().unwrap().as_ref()
+            && at.elapsed() < CHECK_TTL
+        {
+            return checks.clone();
+        }
+
+        let (provider, storage) = tokio::join!(self.check_provider(), self.check_storage());
+        let checks = LocationChecks { provider, storage };
+        *self.last.lock().unwrap() = Some((Instant::now(), checks.clone()));
+        checks
+    }
+
+    async fn check_provider(&self) -> CheckOutcome {
+        let start = Instant::now();
+        match self.provider.self_test().await {
+            Ok(()) => Ok(start.elapsed().as_millis() as u64),
+            Err(e) => {
+                tracing::error!(error = %e, "Location provider self-test failed");
+                Err(e.to_string())
+            }
+        }
+    }
+
+    async fn check_storage(&self) -> Option<CheckOutcome> {
+        let storage = self.storage.as_ref()?;
+        let start = Instant::now();
+        Some(match storage.check_available().await {
+            Ok(()) => Ok(start.elapsed().as_millis() as u64),
+            Err(e) => {
+                tracing::error!(error = %e, "Pack storage health check failed");
+                Err("Pack manifest unreachable".to_string())
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::PackProvider;
+    use crate::reader::FileReader;
+
+    #[tokio::test]
+    async fn test_missing_packs_are_not_ready() {
+        let reader = || FileReader::new("/nonexistent-location-packs", "v0");
+        let health = LocationHealth::new(
+            Arc::new(PackProvider::with_reader(reader())),
+            Some(Arc::new(reader())),
+        );
+
+        let checks = health.check().await;
+        assert!(checks.provider.is_err());
+        assert!(matches!(checks.storage, Some(Err(_))));
+        assert!(!checks.is_ready());
+    }
+
+    #[test]
+    fn test_ready_without_storage() {
+        let checks = LocationChecks { provider: Ok(3), storage: None };
+        assert!(checks.is_ready());
+
+        let checks = LocationChecks { provider: Ok(3), storage: Some(Err("down".to_string())) };
+        assert!(!checks.is_ready());
+    }
+}
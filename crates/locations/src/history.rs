@@ -0,0 +1,161 @@
+//! Per-player history of recently served locations, stored in Redis.
+//!
+//! Both the API (solo games) and the realtime server (multiplayer games)
+//! record every location they serve here, so a player does not see the same
+//! panorama again within their last N games, whichever server ran them.
+//!
+//! Keys per user:
+//!
+//! ```text
+//! locations:recent:{user_id}:games            LIST of game IDs, newest first
+//! locations:recent:{user_id}:game:{game_id}   SET of panorama IDs served in that game
+//! locations:recent:{user_id}:country          country code of the last served location
+//! ```
+
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use dguesser_core::location::{GameLocation, SelectionConstraints};
+use dguesser_redis::RedisConnection;
+
+/// Keys expire after this long without a new game.
+const HISTORY_TTL: Duration = Duration::from_secs(30 * 24 * 3600);
+
+/// Locations a group of players has recently been served.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecentHistory {
+    /// Panorama IDs from the players' recent games.
+    pub panoramas: Vec<String>,
+    /// Country of the most recently served location, if known.
+    pub last_country: Option<String>,
+}
+
+impl RecentHistory {
+    /// Add this history to selection constraints.
+    pub fn apply(self, constraints: SelectionConstraints) -> SelectionConstraints {
+        constraints.with_history(self.panoramas, self.last_country)
+    }
+}
+
+/// Redis-backed store of recently served locations per player.
+///
+/// Failures are logged and treated as an empty history: anti-repetition is
+/// best-effort and must never block a game.
+#[derive(Clone)]
+pub struct RecentLocations {
+    conn: RedisConnection,
+    window_games: usize,
+}
+
+impl RecentLocations {
+    /// Create a store that remembers each player's last `window_games` games.
+    pub fn new(conn: RedisConnection, window_games: usize) -> Self {
+        Self { conn, window_games: window_games.max(1) }
+    }
+
+    fn games_key(user_id: &str) -> String {
+        format!("locations:recent:{user_id}:games")
+    }
+
+    fn game_key(user_id: &str, game_id: &str) -> String {
+        format!("locations:recent:{user_id}:game:{game_id}")
+    }
+
+    fn country_key(user_id: &str) -> String {
+        format!("locations:recent:{user_id}:country")
+    }
+
+    /// Load the combined recent history of a group of players.
+    pub async fn load(&self, user_ids: &[String]) -> RecentHistory {
+        if user_ids.is_empty() {
+            return RecentHistory::default();
+        }
+        match self.try_load(user_ids).await {
+            Ok(history) => history,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to load recent location history");
+                RecentHistory::default()
+            }
+        }
+    }
+
+    async fn try_load(&self, user_ids: &[String]) -> Result<RecentHistory, redis::RedisError> {
+        let mut conn = self.conn.clone();
+
+        let mut pipe = redis::pipe();
+        for user_id in user_ids {
+            pipe.cmd("LRANGE").arg(Self::games_key(user_id)).arg(0).arg(self.window_games - 1);
+        }
+        for user_id in user_ids {
+            pipe.cmd("GET").arg(Self::country_key(user_id));
+        }
+        let (games, countries): (Vec<Vec<String>>, Vec<Option<String>>) = {
+            let mut replies: Vec<redis::Value> = pipe.query_async(&mut conn).await?;
+            let countries = replies.split_off(user_ids.len());
+            (
+                replies.into_iter().map(redis::from_redis_value).collect::<Result<_, _>>()?,
+                countries.into_iter().map(redis::from_redis_value).collect::<Result<_, _>>()?,
+            )
+        };
+
+        let game_keys: Vec<String> = user_ids
+            .iter()
+            .zip(&games)
+            .flat_map(|(user_id, games)| games.iter().map(|g| Self::game_key(user_id, g)))
+            .collect();
+
+        // One SMEMBERS per game rather than SUNION, which Redis Cluster
+        // rejects when the games live in different slots
+        let panoramas: BTreeSet<String> = if game_keys.is_empty() {
+            BTreeSet::new()
+        } else {
+            let mut pipe = redis::pipe();
+            for key in &game_keys {
+                pipe.cmd("SMEMBERS").arg(key);
+            }
+            let sets: Vec<Vec<String>> = pipe.query_async(&mut conn).await?;
+            sets.into_iter().flatten().collect()
+        };
+
+        Ok(RecentHistory {
+            panoramas: panoramas.into_iter().collect(),
+            last_country: countries.into_iter().flatten().next(),
+        })
+    }
+
+    /// Record a location served to a group of players in a game.
+    pub async fn record(&self, user_ids: &[String], game_id: &str, location: &GameLocation) {
+        if user_ids.is_empty() || location.panorama_id.is_empty() {
+            return;
+        }
+
+        let ttl = HISTORY_TTL.as_secs();
+        let mut pipe = redis::pipe();
+        for user_id in user_ids {
+            let games_key = Self::games_key(user_id);
+            let game_key = Self::game_key(user_id, game_id);
+
+            // Move this game to the front of the list, keeping it unique
+            pipe.cmd("LREM").arg(&games_key).arg(0).arg(game_id).ignore();
+            pipe.cmd("LPUSH").arg(&games_key).arg(game_id).ignore();
+            pipe.cmd("LTRIM").arg(&games_key).arg(0).arg(self.window_games - 1).ignore();
+            pipe.cmd("EXPIRE").arg(&games_key).arg(ttl).ignore();
+
+            pipe.cmd("SADD").arg(&game_key).arg(&location.panorama_id).ignore();
+            pipe.cmd("EXPIRE").arg(&game_key).arg(ttl).ignore();
+
+            match &location.country_code {
+                Some(country) => {
+                    pipe.cmd("SET").arg(Self::country_key(user_id)).arg(country).arg("EX").arg(ttl)
+                }
+                None => pipe.cmd("DEL").arg(Self::country_key(user_id)),
+            }
+            .ignore();
+        }
+
+        let mut conn = self.conn.clone();
+        if let Err(e) = pipe.query_async::<()>(&mut conn).await {
+            tracing::warn!(game_id, error = %e, "Failed to record recent location history");
+        }
+    }
+}
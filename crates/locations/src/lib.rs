@@ -26,19 +26,31 @@
 //! ```
 
 pub mod bucket;
+pub mod builder;
 pub mod cache;
 pub mod error;
+pub mod health;
+#[cfg(feature = "redis")]
+pub mod history;
 pub mod index;
 pub mod manifest;
 pub mod pack;
 pub mod provider;
 pub mod reader;
+pub mod routed;
+pub mod tombstone;
 
 pub use bucket::{ScoutBucket, YearBucket};
-pub use cache::DisabledCache;
+pub use builder::{BuildSummary, PackBuilder};
+pub use cache::{CachedReader, DisabledCache, RangeCache, RangeCacheConfig, RangeCacheStats};
 pub use error::LocationPackError;
+pub use health::{LocationChecks, LocationHealth};
+#[cfg(feature = "redis")]
+pub use history::{RecentHistory, RecentLocations};
 pub use index::CountryIndex;
 pub use manifest::Manifest;
 pub use pack::{PackRecord, RECORD_SIZE};
 pub use provider::{PackProvider, PackProviderConfig};
 pub use reader::{FileReader, HttpReader, RangeReader};
+pub use routed::RoutedProvider;
+pub use tombstone::CountryTombstones;
@@ -5,6 +5,7 @@
 
 use crate::error::LocationPackError;
 use dguesser_core::location::GameLocation;
+use dguesser_core::streetview::ImageryProvider;
 use xxhash_rust::xxh3::xxh3_64;
 
 /// Fixed record size in bytes.
@@ -139,6 +140,7 @@ impl PackRecord {
             lng: self.lng,
             country_code: Some(country_code.to_string()),
             heading: self.heading,
+            provider: ImageryProvider::GoogleStreetView,
         }
     }
 
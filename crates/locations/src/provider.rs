@@ -6,12 +6,13 @@ use std::collections::{HashMap, HashSet};
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use tokio::sync::RwLock;
 
 use dguesser_core::location::{
-    CountryDistribution, GameLocation, LocationError, LocationProvider, Map, MapRules,
-    SelectionConstraints, select_spread_candidate,
+    GameLocation, LocationError, LocationProvider, Map, MapRules, SelectionConstraints,
+    select_ranked_candidate,
 };
 
 use crate::bucket::BucketKey;
@@ -21,6 +22,7 @@ use crate::index::{BucketInfo, CountryIndex};
 use crate::manifest::Manifest;
 use crate::pack::{PackRecord, RECORD_SIZE};
 use crate::reader::RangeReader;
+use crate::tombstone::CountryTombstones;
 
 /// Number of records to fetch per Range request.
 const BATCH_SIZE: usize = 16;
@@ -35,14 +37,26 @@ pub struct PackProviderConfig {
     pub cache_indexes: bool,
     /// Maximum number of disabled hashes to keep in memory.
     pub max_disabled_cache: usize,
+    /// How long a country's tombstone list is used before it is re-fetched.
+    pub tombstone_refresh: Duration,
 }
 
 impl Default for PackProviderConfig {
     fn default() -> Self {
-        Self { cache_indexes: true, max_disabled_cache: 200_000 }
+        Self {
+            cache_indexes: true,
+            max_disabled_cache: 200_000,
+            tombstone_refresh: Duration::from_secs(300),
+        }
     }
 }
 
+/// A country's tombstones as of the last fetch (`None` if it has no file).
+struct CachedTombstones {
+    fetched_at: Instant,
+    tombstones: Option<Arc<CountryTombstones>>,
+}
+
 /// Pack-based location provider that reads from R2/file storage.
 pub struct PackProvider<R: RangeReader> {
     reader: Arc<R>,
@@ -51,6 +65,8 @@ pub struct PackProvider<R: RangeReader> {
     manifest: RwLock<Option<Arc<Manifest>>>,
     /// Cached country indexes.
     indexes: RwLock<HashMap<String, Arc<CountryIndex>>>,
+    /// Cached tombstone lists, refreshed after `tombstone_refresh`.
+    tombstones: RwLock<HashMap<String, CachedTombstones>>,
     /// Disabled location cache.
     disabled_cache: DisabledCache,
     /// Map definitions (loaded from config, not from packs).
@@ -67,6 +83,7 @@ impl<R: RangeReader> PackProvider<R> {
             config,
             manifest: RwLock::new(None),
             indexes: RwLock::new(HashMap::new()),
+            tombstones: RwLock::new(HashMap::new()),
             disabled_cache,
             maps: RwLock::new(HashMap::new()),
         }
@@ -137,6 +154,43 @@ impl<R: RangeReader> PackProvider<R> {
         Ok(index)
     }
 
+    /// Get a country's tombstones, re-fetching them once the cached list is
+    /// older than `tombstone_refresh`.
+    ///
+    /// A failed fetch keeps the previous list, so a storage hiccup never
+    /// blocks selection.
+    pub async fn country_tombstones(&self, country: &str) -> Option<Arc<CountryTombstones>> {
+        {
+            let cached = self.tombstones.read().await;
+            if let Some(entry) = cached.get(country)
+                && entry.fetched_at.elapsed() < self.config.tombstone_refresh
+            {
+                return entry.tombstones.clone();
+            }
+        }
+
+        let tombstones = match self.reader.read_tombstones(country).await {
+            Ok(tombstones) => tombstones.map(Arc::new),
+            Err(e) => {
+                tracing::warn!(
+                    country = %country,
+                    error = %e,
+                    "Failed to refresh tombstones, keeping previous list"
+                );
+                let cached = self.tombstones.read().await;
+                cached.get(country).and_then(|entry| entry.tombstones.clone())
+            }
+        };
+
+        let mut cached = self.tombstones.write().await;
+        cached.insert(
+            country.to_string(),
+            CachedTombstones { fetched_at: Instant::now(), tombstones: tombstones.clone() },
+        );
+
+        tombstones
+    }
+
     /// Pre-warm the cache by loading the manifest and all country indexes.
     ///
     /// This should be called at server startup (in a background task) to ensure
@@ -158,6 +212,10 @@ impl<R: RangeReader> PackProvider<R> {
         let results = futures::future::join_all(futures).await;
 
         let (ok, failed): (Vec<_>, Vec<_>) = results.into_iter().partition(Result::is_ok);
+
+        // Tombstones are small and fetched alongside the indexes
+        let futures: Vec<_> = countries.iter().map(|c| self.country_tombstones(c)).collect();
+        futures::future::join_all(futures).await;
         let failed_count = failed.len();
 
         if failed_count > 0 {
@@ -180,7 +238,7 @@ impl<R: RangeReader> PackProvider<R> {
     /// Select random locations matching the given rules.
     ///
     /// # Arguments
-    /// * `rules` - Map rules for filtering (countries, year range, outdoor_only)
+    /// * `rules` - Map rules for filtering (countries, year range, outdoor_only, region)
     /// * `exclude_hashes` - Location hashes to exclude (already used in this game)
     /// * `count` - Number of locations to select
     pub async fn select_locations(
@@ -232,24 +290,13 @@ impl<R: RangeReader> PackProvider<R> {
             return Err(LocationPackError::NoEligibleBuckets);
         }
 
-        // Calculate country weights based on distribution strategy
-        let country_weights: Vec<(&str, u64)> = match &rules.country_distribution {
-            CountryDistribution::Proportional => {
-                // Weight by total location count (original behavior)
-                country_totals.iter().map(|(&c, &t)| (c, t)).collect()
-            }
-            CountryDistribution::Equal => {
-                // Equal weight for all countries
-                country_buckets.keys().map(|&c| (c, 1u64)).collect()
-            }
-            CountryDistribution::Weighted { weights } => {
-                // Custom weights
-                country_buckets
-                    .keys()
-                    .filter_map(|&c| weights.get(c).map(|&w| (c, w as u64)))
-                    .collect()
-            }
-        };
+        // Calculate country weights based on distribution strategy; proportional
+        // weights each country by its eligible location count
+        let country_weights: Vec<(&str, u64)> = country_totals
+            .iter()
+            .map(|(&c, &total)| (c, rules.country_distribution.static_weight(c).unwrap_or(total)))
+            .filter(|(_, weight)| *weight > 0)
+            .collect();
 
         let total_country_weight: u64 = country_weights.iter().map(|(_, w)| w).sum();
         if total_country_weight == 0 {
@@ -301,6 +348,7 @@ impl<R: RangeReader> PackProvider<R> {
 
             let records =
                 self.fetch_random_batch(country, bucket_info, bucket_count, rand_seed).await?;
+            let tombstones = self.country_tombstones(country).await;
 
             // Filter out excluded and disabled
             let exclude_set: std::collections::HashSet<u64> =
@@ -325,6 +373,17 @@ impl<R: RangeReader> PackProvider<R> {
                 if disabled.contains(&record.id_hash) {
                     continue;
                 }
+                if tombstones
+                    .as_ref()
+                    .is_some_and(|t| t.contains(&bucket_info.object, record.id_hash))
+                {
+                    continue;
+                }
+                if let Some(region) = &rules.region
+                    && !region.contains(record.lat, record.lng)
+                {
+                    continue;
+                }
 
                 results.push((country.to_string(), record));
             }
@@ -481,10 +540,12 @@ impl<R: RangeReader + 'static> LocationProvider for PackProvider<R> {
             let mut total = 0i64;
             for country in countries {
                 if let Ok(index) = self.country_index(country).await {
+                    let tombstones = self.country_tombstones(country).await;
                     let eligible =
                         index.eligible_buckets(rules.min_year, rules.max_year, rules.outdoor_only);
                     for (_, info) in eligible {
-                        total += info.count as i64;
+                        let removed = tombstones.as_ref().map_or(0, |t| t.count_for(&info.object));
+                        total += info.count.saturating_sub(removed) as i64;
                     }
                 }
             }
@@ -528,12 +589,15 @@ impl<R: RangeReader + 'static> LocationProvider for PackProvider<R> {
             let rules = map.rules.clone();
             drop(maps);
 
-            // Convert exclude_ids to hashes
-            let exclude_hashes: Vec<u64> =
-                exclude_ids.iter().map(|id| PackRecord::hash_pano_id(id)).collect();
+            // Convert exclude_ids and recently seen panoramas to hashes
+            let exclude_hashes: Vec<u64> = exclude_ids
+                .iter()
+                .chain(&constraints.exclude_panoramas)
+                .map(|id| PackRecord::hash_pano_id(id))
+                .collect();
 
-            // If there are no previous round locations, use simple selection.
-            if !constraints.uses_spread_ranking() {
+            // If there is nothing to rank candidates by, use simple selection.
+            if !constraints.uses_candidate_ranking() {
                 let results = self.select_locations(&rules, &exclude_hashes, 1).await?;
                 let (country, record) = results
                     .into_iter()
@@ -550,8 +614,18 @@ impl<R: RangeReader + 'static> LocationProvider for PackProvider<R> {
             let mut seen_hashes = HashSet::new();
 
             for attempt in 0..MAX_ATTEMPTS {
-                let results =
-                    self.select_locations(&rules, &exclude_hashes, CANDIDATES_PER_ATTEMPT).await?;
+                // Large exclusion lists can leave a batch empty; later
+                // attempts read other offsets
+                let results = match self
+                    .select_locations(&rules, &exclude_hashes, CANDIDATES_PER_ATTEMPT)
+                    .await
+                {
+                    Ok(results) => results,
+                    Err(e) => {
+                        tracing::debug!(attempt = attempt, error = %e, "Candidate batch failed");
+                        continue;
+                    }
+                };
 
                 let mut added_this_attempt = 0usize;
                 for (country, record) in results {
@@ -589,8 +663,11 @@ impl<R: RangeReader + 'static> LocationProvider for PackProvider<R> {
 
             let candidate_coords: Vec<(f64, f64)> =
                 candidates.iter().map(|(_, record)| (record.lat, record.lng)).collect();
-            let selection = select_spread_candidate(&candidate_coords, constraints)
-                .ok_or(LocationError::NoLocationsAvailable(map_id.to_string()))?;
+            let candidate_countries: Vec<Option<&str>> =
+                candidates.iter().map(|(country, _)| Some(country.as_str())).collect();
+            let selection =
+                select_ranked_candidate(&candidate_coords, &candidate_countries, constraints)
+                    .ok_or(LocationError::NoLocationsAvailable(map_id.to_string()))?;
             let (country, record) = candidates.swap_remove(selection.selected_index);
 
             if constraints.has_hard_min_distance() && !selection.met_min_distance {
@@ -621,11 +698,13 @@ mod tests {
     use super::*;
     use crate::reader::{RangeReader, async_trait};
     use bytes::Bytes;
+    use dguesser_core::location::CountryDistribution;
 
     struct MockReader {
         manifest: Manifest,
         indexes: HashMap<String, CountryIndex>,
         packs: HashMap<String, Vec<u8>>,
+        tombstones: HashMap<String, CountryTombstones>,
     }
 
     impl MockReader {
@@ -666,7 +745,7 @@ mod tests {
             let mut packs = HashMap::new();
             packs.insert("US_B4_S0.pack".to_string(), pack_data);
 
-            Self { manifest, indexes, packs }
+            Self { manifest, indexes, packs, tombstones: HashMap::new() }
         }
     }
 
@@ -701,6 +780,13 @@ mod tests {
             let end = (offset + length) as usize;
             Ok(Bytes::copy_from_slice(&pack[start..end.min(pack.len())]))
         }
+
+        async fn read_tombstones(
+            &self,
+            country: &str,
+        ) -> Result<Option<CountryTombstones>, LocationPackError> {
+            Ok(self.tombstones.get(country).cloned())
+        }
     }
 
     #[tokio::test]
@@ -739,6 +825,90 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_tombstoned_records_are_skipped() {
+        let mut reader = MockReader::new();
+        let mut tombstones = CountryTombstones::new("US", "v2026-01");
+        for i in 0..95 {
+            tombstones.add("US_B4_S0.pack", PackRecord::hash_pano_id(&format!("pano_{}", i)));
+        }
+        reader.tombstones.insert("US".to_string(), tombstones);
+        let provider = PackProvider::with_reader(reader);
+
+        let rules = MapRules { countries: vec!["US".to_string()], ..Default::default() };
+        for _ in 0..20 {
+            if let Ok(results) = provider.select_locations(&rules, &[], 5).await {
+                for (_, record) in results {
+                    let n: u32 = record.pano_id.trim_start_matches("pano_").parse().unwrap();
+                    assert!(n >= 95, "tombstoned record {} was selected", record.pano_id);
+                }
+            }
+        }
+
+        provider
+            .register_map(Map {
+                id: "map_us".to_string(),
+                slug: "us".to_string(),
+                name: "US".to_string(),
+                description: None,
+                rules,
+                is_default: false,
+                active: true,
+                creator_id: None,
+                visibility: dguesser_core::location::MapVisibility::Public,
+                location_count: 100,
+                like_count: 0,
+                play_count: 0,
+                score_sum: 0,
+                source: None,
+                org_id: None,
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+            })
+            .await;
+        assert_eq!(provider.get_location_count("map_us").await.unwrap(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_recent_panoramas_are_excluded() {
+        let provider = PackProvider::with_reader(MockReader::new());
+        let rules = MapRules { countries: vec!["US".to_string()], ..Default::default() };
+        provider
+            .register_map(Map {
+                id: "map_us".to_string(),
+                slug: "us".to_string(),
+                name: "US".to_string(),
+                description: None,
+                rules,
+                is_default: false,
+                active: true,
+                creator_id: None,
+                visibility: dguesser_core::location::MapVisibility::Public,
+                location_count: 100,
+                like_count: 0,
+                play_count: 0,
+                score_sum: 0,
+                source: None,
+                org_id: None,
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+            })
+            .await;
+
+        let recent: Vec<String> = (0..100).step_by(2).map(|i| format!("pano_{}", i)).collect();
+        let constraints = SelectionConstraints::with_previous_locations(vec![(0.0, 0.0)])
+            .with_history(recent, Some("US".to_string()));
+
+        for _ in 0..20 {
+            let location = provider
+                .select_location_with_constraints("map_us", &[], &constraints)
+                .await
+                .unwrap();
+            let n: u32 = location.panorama_id.trim_start_matches("pano_").parse().unwrap();
+            assert!(n % 2 == 1, "recently seen panorama {} was selected", location.panorama_id);
+        }
+    }
+
     /// Create a multi-country mock reader for testing distribution strategies.
     fn create_multi_country_reader() -> MockReader {
         use crate::bucket::{ScoutBucket, YearBucket};
@@ -825,7 +995,7 @@ mod tests {
         }
         packs.insert("AD_B4_S0.pack".to_string(), ad_pack);
 
-        MockReader { manifest, indexes, packs }
+        MockReader { manifest, indexes, packs, tombstones: HashMap::new() }
     }
 
     #[tokio::test]
@@ -874,6 +1044,33 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_area_country_distribution() {
+        let reader = create_multi_country_reader();
+        let provider = PackProvider::with_reader(reader);
+
+        // US has the most coverage here, but is ~18x larger than FR and ~21000x
+        // larger than AD, so it should dominate by area
+        let rules = MapRules {
+            countries: vec!["US".to_string(), "FR".to_string(), "AD".to_string()],
+            country_distribution: CountryDistribution::Area,
+            ..Default::default()
+        };
+
+        let mut country_counts: HashMap<String, usize> = HashMap::new();
+        for _ in 0..100 {
+            let results = provider.select_locations(&rules, &[], 1).await.unwrap();
+            for (country, _) in results {
+                *country_counts.entry(country).or_insert(0) += 1;
+            }
+        }
+
+        let us_count = country_counts.get("US").copied().unwrap_or(0);
+        let ad_count = country_counts.get("AD").copied().unwrap_or(0);
+        assert!(us_count >= 80, "US should dominate by area, got {}", us_count);
+        assert!(ad_count <= 5, "AD should almost never be picked by area, got {}", ad_count);
+    }
+
     #[tokio::test]
     async fn test_weighted_country_distribution() {
         let reader = create_multi_country_reader();
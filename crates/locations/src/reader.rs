@@ -12,6 +12,7 @@ use tokio::io::{AsyncReadExt, AsyncSeekExt};
 use crate::error::LocationPackError;
 use crate::index::CountryIndex;
 use crate::manifest::Manifest;
+use crate::tombstone::CountryTombstones;
 
 /// Validate a path component to prevent path traversal attacks.
 ///
@@ -54,6 +55,20 @@ pub trait RangeReader: Send + Sync {
         offset: u64,
         length: u64,
     ) -> Result<Bytes, LocationPackError>;
+
+    /// Read a country's tombstone file, or `None` if it has none.
+    async fn read_tombstones(
+        &self,
+        _country: &str,
+    ) -> Result<Option<CountryTombstones>, LocationPackError> {
+        Ok(None)
+    }
+
+    /// Check that the storage is reachable and has the manifest, without
+    /// reading it if possible.
+    async fn check_available(&self) -> Result<(), LocationPackError> {
+        self.read_manifest().await.map(|_| ())
+    }
 }
 
 /// HTTP-based reader for R2/S3 compatible storage.
@@ -100,6 +115,12 @@ impl RangeReader for HttpReader {
         Ok(manifest)
     }
 
+    async fn check_available(&self) -> Result<(), LocationPackError> {
+        let url = self.url("manifest.json");
+        self.client.head(&url).send().await?.error_for_status()?;
+        Ok(())
+    }
+
     async fn read_country_index(&self, country: &str) -> Result<CountryIndex, LocationPackError> {
         // Validate country code to prevent path traversal
         validate_path_component(country)?;
@@ -151,6 +172,24 @@ impl RangeReader for HttpReader {
 
         Ok(response.bytes().await?)
     }
+
+    async fn read_tombstones(
+        &self,
+        country: &str,
+    ) -> Result<Option<CountryTombstones>, LocationPackError> {
+        validate_path_component(country)?;
+
+        let url = self.url(&format!("countries/{}/tombstones.json", country));
+        tracing::debug!(url = %url, "Fetching tombstones");
+
+        let response = self.client.get(&url).send().await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let tombstones: CountryTombstones = response.error_for_status()?.json().await?;
+        Ok(Some(tombstones))
+    }
 }
 
 /// File-based reader for local development.
@@ -187,6 +226,11 @@ impl RangeReader for FileReader {
         Ok(manifest)
     }
 
+    async fn check_available(&self) -> Result<(), LocationPackError> {
+        tokio::fs::metadata(self.path("manifest.json")).await?;
+        Ok(())
+    }
+
     async fn read_country_index(&self, country: &str) -> Result<CountryIndex, LocationPackError> {
         // Validate country code to prevent path traversal
         validate_path_component(country)?;
@@ -233,6 +277,24 @@ impl RangeReader for FileReader {
 
         Ok(Bytes::from(buffer))
     }
+
+    async fn read_tombstones(
+        &self,
+        country: &str,
+    ) -> Result<Option<CountryTombstones>, LocationPackError> {
+        validate_path_component(country)?;
+
+        let path = self.path(&format!("countries/{}/tombstones.json", country));
+        tracing::debug!(path = ?path, "Reading tombstones");
+
+        let content = match tokio::fs::read_to_string(&path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(LocationPackError::Io(e)),
+        };
+
+        Ok(Some(serde_json::from_str(&content)?))
+    }
 }
 
 #[cfg(test)]
@@ -314,6 +376,23 @@ mod tests {
         assert!(data.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_file_reader_tombstones() {
+        let dir = setup_test_files().await;
+        let reader = FileReader::new(dir.path(), "v2026-01");
+
+        // No file means no tombstones
+        assert!(reader.read_tombstones("US").await.unwrap().is_none());
+
+        let mut tombstones = CountryTombstones::new("US", "v2026-01");
+        tombstones.add("US_B4_S0.pack", 42);
+        let path = dir.path().join("v2026-01/countries/US/tombstones.json");
+        tokio::fs::write(path, serde_json::to_string(&tombstones).unwrap()).await.unwrap();
+
+        let read = reader.read_tombstones("US").await.unwrap().unwrap();
+        assert!(read.contains("US_B4_S0.pack", 42));
+    }
+
     #[tokio::test]
     async fn test_path_traversal_prevention() {
         let dir = setup_test_files().await;
@@ -0,0 +1,334 @@
+//! Per-map routing between the database and pack location providers.
+//!
+//! Each map chooses where its locations come from through its `source` field
+//! (falling back to a server-wide default). Map definitions always live in
+//! the database; pack selection translates the map's rules into
+//! country/year/scout buckets. When pack selection fails (storage outage,
+//! missing country, no eligible buckets) the request falls back to the
+//! database provider so games keep working. Packs carry no difficulty
+//! ratings, so maps filtered by difficulty are always served from the
+//! database.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use dguesser_core::location::{
+    GameLocation, LocationError, LocationProvider, Map, MapLocationSource, SelectionConstraints,
+};
+
+use crate::provider::PackProvider;
+use crate::reader::RangeReader;
+
+/// Location provider that dispatches each map to the database or to packs.
+pub struct RoutedProvider<R: RangeReader> {
+    database: Arc<dyn LocationProvider>,
+    packs: Arc<PackProvider<R>>,
+    default_source: MapLocationSource,
+}
+
+impl<R: RangeReader + 'static> RoutedProvider<R> {
+    /// Create a routed provider.
+    ///
+    /// `database` resolves maps and serves database-backed maps;
+    /// `default_source` applies to maps without an explicit source.
+    pub fn new(
+        database: Arc<dyn LocationProvider>,
+        packs: Arc<PackProvider<R>>,
+        default_source: MapLocationSource,
+    ) -> Self {
+        Self { database, packs, default_source }
+    }
+
+    /// The source a map's locations are served from. Maps restricted to an
+    /// imagery provider the packs do not hold, or to a difficulty band,
+    /// always use the database.
+    fn source_for(&self, map: &Map) -> MapLocationSource {
+        if map.rules.provider.is_some_and(|provider| !provider.supports_packs())
+            || map.rules.difficulty.is_some()
+        {
+            return MapLocationSource::Database;
+        }
+        map.source.unwrap_or(self.default_source)
+    }
+
+    /// Resolve a map from the database and, for pack-backed maps, make sure
+    /// the pack provider has its current rules.
+    async fn resolve(&self, map_id: &str) -> Result<(Map, MapLocationSource), LocationError> {
+        let map = self.database.get_map(map_id).await?;
+        let source = self.source_for(&map);
+        if source == MapLocationSource::Pack {
+            self.packs.register_map(map.clone()).await;
+        }
+        Ok((map, source))
+    }
+
+    /// Run a pack operation, falling back to the database provider on error.
+    async fn with_fallback<'a, T>(
+        &'a self,
+        map: &Map,
+        operation: &'static str,
+        pack: impl Future<Output = Result<T, LocationError>> + 'a,
+        database: impl Future<Output = Result<T, LocationError>> + 'a,
+    ) -> Result<T, LocationError> {
+        match pack.await {
+            Ok(value) => Ok(value),
+            Err(e) => {
+                tracing::warn!(
+                    map_id = %map.id,
+                    operation,
+                    error = %e,
+                    "Pack location provider failed, falling back to database"
+                );
+                database.await
+            }
+        }
+    }
+}
+
+impl<R: RangeReader + 'static> LocationProvider for RoutedProvider<R> {
+    fn select_location<'a>(
+        &'a self,
+        map_id: &'a str,
+        exclude_ids: &'a [String],
+    ) -> Pin<Box<dyn Future<Output = Result<GameLocation, LocationError>> + Send + 'a>> {
+        Box::pin(async move {
+            let (map, source) = self.resolve(map_id).await?;
+            match source {
+                MapLocationSource::Database => {
+                    self.database.select_location(&map.id, exclude_ids).await
+                }
+                MapLocationSource::Pack => {
+                    self.with_fallback(
+                        &map,
+                        "select_location",
+                        self.packs.select_location(&map.id, exclude_ids),
+                        self.database.select_location(&map.id, exclude_ids),
+                    )
+                    .await
+                }
+            }
+        })
+    }
+
+    fn get_map<'a>(
+        &'a self,
+        map_id_or_slug: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<Map, LocationError>> + Send + 'a>> {
+        self.database.get_map(map_id_or_slug)
+    }
+
+    fn get_default_map<'a>(
+        &'a self,
+    ) -> Pin<Box<dyn Future<Output = Result<Map, LocationError>> + Send + 'a>> {
+        self.database.get_default_map()
+    }
+
+    fn get_location_count<'a>(
+        &'a self,
+        map_id: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<i64, LocationError>> + Send + 'a>> {
+        Box::pin(async move {
+            let (map, source) = self.resolve(map_id).await?;
+            match source {
+                MapLocationSource::Database => self.database.get_location_count(&map.id).await,
+                MapLocationSource::Pack => {
+                    self.with_fallback(
+                        &map,
+                        "get_location_count",
+                        self.packs.get_location_count(&map.id),
+                        self.database.get_location_count(&map.id),
+                    )
+                    .await
+                }
+            }
+        })
+    }
+
+    fn mark_location_failed<'a>(
+        &'a self,
+        location_id: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<(), LocationError>> + Send + 'a>> {
+        // Database rows have loc_ IDs; pack locations are identified by their
+        // r2_ pseudo-ID or panorama ID.
+        if location_id.starts_with("loc_") {
+            self.database.mark_location_failed(location_id)
+        } else {
+            self.packs.mark_location_failed(location_id)
+        }
+    }
+
+    fn select_location_with_constraints<'a>(
+        &'a self,
+        map_id: &'a str,
+        exclude_ids: &'a [String],
+        constraints: &'a SelectionConstraints,
+    ) -> Pin<Box<dyn Future<Output = Result<GameLocation, LocationError>> + Send + 'a>> {
+        Box::pin(async move {
+            let (map, source) = self.resolve(map_id).await?;
+            match source {
+                MapLocationSource::Database => {
+                    self.database
+                        .select_location_with_constraints(&map.id, exclude_ids, constraints)
+                        .await
+                }
+                MapLocationSource::Pack => {
+                    self.with_fallback(
+                        &map,
+                        "select_location_with_constraints",
+                        self.packs.select_location_with_constraints(
+                            &map.id,
+                            exclude_ids,
+                            constraints,
+                        ),
+                        self.database.select_location_with_constraints(
+                            &map.id,
+                            exclude_ids,
+                            constraints,
+                        ),
+                    )
+                    .await
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::FileReader;
+    use chrono::Utc;
+    use dguesser_core::location::{DifficultyBand, MapRules, MapVisibility};
+    use dguesser_core::streetview::ImageryProvider;
+
+    /// Database stand-in serving a single map and a single location.
+    struct StubDatabase {
+        source: Option<MapLocationSource>,
+    }
+
+    impl StubDatabase {
+        fn map(&self) -> Map {
+            Map {
+                id: "map_test".to_string(),
+                slug: "test".to_string(),
+                name: "Test".to_string(),
+                description: None,
+                rules: MapRules::default(),
+                is_default: true,
+                active: true,
+                creator_id: None,
+                visibility: MapVisibility::Public,
+                location_count: 1,
+                like_count: 0,
+                play_count: 0,
+                score_sum: 0,
+                source: self.source,
+                org_id: None,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            }
+        }
+    }
+
+    impl LocationProvider for StubDatabase {
+        fn select_location<'a>(
+            &'a self,
+            _map_id: &'a str,
+            _exclude_ids: &'a [String],
+        ) -> Pin<Box<dyn Future<Output = Result<GameLocation, LocationError>> + Send + 'a>>
+        {
+            Box::pin(async move {
+                Ok(GameLocation {
+                    id: "loc_db".to_string(),
+                    panorama_id: "pano_db".to_string(),
+                    lat: 0.0,
+                    lng: 0.0,
+                    country_code: None,
+                    heading: None,
+                    provider: ImageryProvider::GoogleStreetView,
+                })
+            })
+        }
+
+        fn get_map<'a>(
+            &'a self,
+            _map_id_or_slug: &'a str,
+        ) -> Pin<Box<dyn Future<Output = Result<Map, LocationError>> + Send + 'a>> {
+            Box::pin(async move { Ok(self.map()) })
+        }
+
+        fn get_default_map<'a>(
+            &'a self,
+        ) -> Pin<Box<dyn Future<Output = Result<Map, LocationError>> + Send + 'a>> {
+            Box::pin(async move { Ok(self.map()) })
+        }
+
+        fn get_location_count<'a>(
+            &'a self,
+            _map_id: &'a str,
+        ) -> Pin<Box<dyn Future<Output = Result<i64, LocationError>> + Send + 'a>> {
+            Box::pin(async move { Ok(1) })
+        }
+
+        fn mark_location_failed<'a>(
+            &'a self,
+            _location_id: &'a str,
+        ) -> Pin<Box<dyn Future<Output = Result<(), LocationError>> + Send + 'a>> {
+            Box::pin(async move { Ok(()) })
+        }
+    }
+
+    fn routed(
+        source: Option<MapLocationSource>,
+        default_source: MapLocationSource,
+    ) -> RoutedProvider<FileReader> {
+        // Points at a directory without packs, so every pack read fails
+        let reader = FileReader::new("/nonexistent-location-packs", "v0");
+        RoutedProvider::new(
+            Arc::new(StubDatabase { source }),
+            Arc::new(PackProvider::with_reader(reader)),
+            default_source,
+        )
+    }
+
+    #[test]
+    fn test_source_falls_back_to_default() {
+        let provider = routed(None, MapLocationSource::Pack);
+        let map = StubDatabase { source: None }.map();
+        assert_eq!(provider.source_for(&map), MapLocationSource::Pack);
+
+        let map = StubDatabase { source: Some(MapLocationSource::Database) }.map();
+        assert_eq!(provider.source_for(&map), MapLocationSource::Database);
+    }
+
+    #[test]
+    fn test_provider_without_packs_uses_database() {
+        let provider = routed(None, MapLocationSource::Pack);
+        let mut map = StubDatabase { source: Some(MapLocationSource::Pack) }.map();
+
+        map.rules.provider = Some(ImageryProvider::GoogleStreetView);
+        assert_eq!(provider.source_for(&map), MapLocationSource::Pack);
+
+        map.rules.provider = Some(ImageryProvider::Mapillary);
+        assert_eq!(provider.source_for(&map), MapLocationSource::Database);
+    }
+
+    #[test]
+    fn test_difficulty_uses_database() {
+        let provider = routed(None, MapLocationSource::Pack);
+        let mut map = StubDatabase { source: Some(MapLocationSource::Pack) }.map();
+
+        map.rules.difficulty = Some(DifficultyBand::Hard);
+        assert_eq!(provider.source_for(&map), MapLocationSource::Database);
+    }
+
+    #[tokio::test]
+    async fn test_pack_failure_falls_back_to_database() {
+        let provider = routed(Some(MapLocationSource::Pack), MapLocationSource::Database);
+
+        let location = provider.select_location("map_test", &[]).await.unwrap();
+        assert_eq!(location.id, "loc_db");
+        assert_eq!(provider.get_location_count("map_test").await.unwrap(), 1);
+    }
+}
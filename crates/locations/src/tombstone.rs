@@ -0,0 +1,94 @@
+//! Tombstones: records excluded from a published dataset version.
+//!
+//! Published versions are immutable, but panoramas keep disappearing after a
+//! build. Instead of rebuilding every pack, bad records are listed per pack in
+//! a small `countries/{CC}/tombstones.json` file next to the country index.
+//! The provider re-fetches it periodically and skips listed records.
+//!
+//! ```json
+//! {
+//!   "country": "US",
+//!   "version": "v2026-01",
+//!   "updated_at": "2026-02-03T10:00:00Z",
+//!   "packs": { "US_B4_S0.pack": [1234567890123456789] }
+//! }
+//! ```
+
+use std::collections::{BTreeSet, HashMap};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Tombstoned record hashes for one country, grouped by pack file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CountryTombstones {
+    /// ISO 3166-1 alpha-2 country code.
+    pub country: String,
+    /// Dataset version the hashes apply to.
+    pub version: String,
+    /// When the file was last changed.
+    pub updated_at: DateTime<Utc>,
+    /// Map of pack file name (e.g., "US_B4_S0.pack") to excluded record hashes.
+    #[serde(default)]
+    pub packs: HashMap<String, BTreeSet<u64>>,
+}
+
+impl CountryTombstones {
+    /// Create an empty tombstone list.
+    pub fn new(country: &str, version: &str) -> Self {
+        Self {
+            country: country.to_string(),
+            version: version.to_string(),
+            updated_at: Utc::now(),
+            packs: HashMap::new(),
+        }
+    }
+
+    /// Tombstone a record in a pack. Returns false if it already was.
+    pub fn add(&mut self, pack: &str, hash: u64) -> bool {
+        let added = self.packs.entry(pack.to_string()).or_default().insert(hash);
+        if added {
+            self.updated_at = Utc::now();
+        }
+        added
+    }
+
+    /// Check whether a record in a pack is tombstoned.
+    pub fn contains(&self, pack: &str, hash: u64) -> bool {
+        self.packs.get(pack).is_some_and(|hashes| hashes.contains(&hash))
+    }
+
+    /// Number of tombstoned records in a pack.
+    pub fn count_for(&self, pack: &str) -> u64 {
+        self.packs.get(pack).map_or(0, |hashes| hashes.len() as u64)
+    }
+
+    /// Number of tombstoned records across all packs.
+    pub fn total_count(&self) -> u64 {
+        self.packs.values().map(|hashes| hashes.len() as u64).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tombstones_roundtrip() {
+        let mut tombstones = CountryTombstones::new("US", "v2026-01");
+        assert!(tombstones.add("US_B4_S0.pack", 42));
+        assert!(tombstones.add("US_B4_S0.pack", u64::MAX));
+        assert!(!tombstones.add("US_B4_S0.pack", 42));
+        assert!(tombstones.add("US_B5_S0.pack", 7));
+
+        let json = serde_json::to_string(&tombstones).unwrap();
+        let parsed: CountryTombstones = serde_json::from_str(&json).unwrap();
+
+        assert!(parsed.contains("US_B4_S0.pack", 42));
+        assert!(parsed.contains("US_B4_S0.pack", u64::MAX));
+        assert!(!parsed.contains("US_B5_S0.pack", 42));
+        assert_eq!(parsed.count_for("US_B4_S0.pack"), 2);
+        assert_eq!(parsed.count_for("US_B6_S0.pack"), 0);
+        assert_eq!(parsed.total_count(), 3);
+    }
+}
@@ -0,0 +1,157 @@
+//! Mailer configuration from environment variables.
+
+use std::env;
+use std::sync::Arc;
+
+use crate::error::MailerError;
+use crate::mailer::{LogMailer, Mailer};
+use crate::resend::ResendMailer;
+use crate::smtp::SmtpMailer;
+
+/// Default sender used when `MAIL_FROM` is not set.
+const DEFAULT_FROM: &str = "DGuesser <noreply@dguesser.lol>";
+
+/// Which delivery backend to use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MailProvider {
+    /// Write emails to the log (development)
+    Log,
+    /// Send through an SMTP relay
+    Smtp,
+    /// Send through the Resend HTTP API
+    Resend,
+}
+
+impl MailProvider {
+    /// Parse from string (log, smtp, resend).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "log" | "console" | "none" => Some(Self::Log),
+            "smtp" => Some(Self::Smtp),
+            "resend" => Some(Self::Resend),
+            _ => None,
+        }
+    }
+}
+
+/// How the SMTP connection is secured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpSecurity {
+    /// Upgrade a plain connection with STARTTLS (port 587)
+    StartTls,
+    /// TLS from the first byte (port 465)
+    Tls,
+    /// No encryption (local catchers such as Mailpit only)
+    None,
+}
+
+/// SMTP relay settings.
+#[derive(Debug, Clone)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub security: SmtpSecurity,
+}
+
+/// Email delivery configuration.
+#[derive(Debug, Clone)]
+pub struct MailerConfig {
+    /// Selected provider
+    pub provider: MailProvider,
+    /// Sender address, e.g. `DGuesser <noreply@dguesser.lol>`
+    pub from: String,
+    /// SMTP settings (when `provider` is `Smtp`)
+    pub smtp: Option<SmtpConfig>,
+    /// Resend API key (when `provider` is `Resend`)
+    pub resend_api_key: Option<String>,
+}
+
+impl MailerConfig {
+    /// Create from environment variables.
+    ///
+    /// `MAIL_PROVIDER` selects the backend (default `log`). SMTP reads
+    /// `SMTP_HOST`, `SMTP_PORT`, `SMTP_USERNAME`, `SMTP_PASSWORD` and
+    /// `SMTP_SECURITY` (`starttls`, `tls` or `none`); Resend reads
+    /// `RESEND_API_KEY`.
+    pub fn from_env() -> Result<Self, MailerError> {
+        let provider = match env::var("MAIL_PROVIDER") {
+            Ok(value) => MailProvider::parse(&value)
+                .ok_or_else(|| MailerError::Config(format!("Unknown MAIL_PROVIDER: {value}")))?,
+            Err(_) => MailProvider::Log,
+        };
+
+        let from = env::var("MAIL_FROM").unwrap_or_else(|_| DEFAULT_FROM.to_string());
+
+        let smtp = env::var("SMTP_HOST").ok().map(|host| {
+            let security = match env::var("SMTP_SECURITY").as_deref() {
+                Ok("tls") => SmtpSecurity::Tls,
+                Ok("none") => SmtpSecurity::None,
+                _ => SmtpSecurity::StartTls,
+            };
+            let default_port = match security {
+                SmtpSecurity::Tls => 465,
+                SmtpSecurity::StartTls => 587,
+                SmtpSecurity::None => 25,
+            };
+            SmtpConfig {
+                host,
+                port: env::var("SMTP_PORT")
+                    .ok()
+                    .and_then(|p| p.parse().ok())
+                    .unwrap_or(default_port),
+                username: env::var("SMTP_USERNAME").ok(),
+                password: env::var("SMTP_PASSWORD").ok(),
+                security,
+            }
+        });
+
+        let resend_api_key = env::var("RESEND_API_KEY").ok().filter(|k| !k.is_empty());
+
+        Ok(Self { provider, from, smtp, resend_api_key })
+    }
+
+    /// Build the configured mailer.
+    pub fn build(&self) -> Result<Arc<dyn Mailer>, MailerError> {
+        match self.provider {
+            MailProvider::Log => Ok(Arc::new(LogMailer)),
+            MailProvider::Smtp => {
+                let smtp = self.smtp.as_ref().ok_or_else(|| {
+                    MailerError::Config("MAIL_PROVIDER=smtp requires SMTP_HOST".to_string())
+                })?;
+                Ok(Arc::new(SmtpMailer::new(smtp, &self.from)?))
+            }
+            MailProvider::Resend => {
+                let api_key = self.resend_api_key.as_deref().ok_or_else(|| {
+                    MailerError::Config("MAIL_PROVIDER=resend requires RESEND_API_KEY".to_string())
+                })?;
+                Ok(Arc::new(ResendMailer::new(api_key, &self.from)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_provider() {
+        assert_eq!(MailProvider::parse("SMTP"), Some(MailProvider::Smtp));
+        assert_eq!(MailProvider::parse("resend"), Some(MailProvider::Resend));
+        assert_eq!(MailProvider::parse("console"), Some(MailProvider::Log));
+        assert_eq!(MailProvider::parse("carrier-pigeon"), None);
+    }
+
+    #[test]
+    fn test_build_requires_provider_settings() {
+        let config = MailerConfig {
+            provider: MailProvider::Resend,
+            from: DEFAULT_FROM.to_string(),
+            smtp: None,
+            resend_api_key: None,
+        };
+        assert!(matches!(config.build(), Err(MailerError::Config(_))));
+    }
+}
@@ -0,0 +1,34 @@
+//! Weekly stats digest.
+
+use dguesser_db::DbPool;
+use dguesser_db::emails;
+
+use crate::error::MailerError;
+use crate::queue;
+use crate::templates::{self, DigestStats, Template};
+
+/// Queue a weekly digest for every opted-in user who played this week.
+///
+/// Returns the number of digests queued.
+pub async fn enqueue_weekly_digests(
+    pool: &DbPool,
+    frontend_url: &str,
+) -> Result<usize, MailerError> {
+    let recipients = emails::get_digest_recipients(pool).await?;
+    let play_link = format!("{frontend_url}/play");
+    let preferences_link = format!("{frontend_url}/account");
+
+    for recipient in &recipients {
+        let stats = DigestStats {
+            display_name: recipient.display_name.clone(),
+            games_played: recipient.games_played,
+            total_score: recipient.total_score,
+            best_score: recipient.best_score,
+        };
+        let message =
+            templates::weekly_digest(&recipient.email, &stats, &play_link, &preferences_link);
+        queue::enqueue(pool, Some(&recipient.user_id), Template::WeeklyDigest, &message).await?;
+    }
+
+    Ok(recipients.len())
+}
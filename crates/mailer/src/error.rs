@@ -0,0 +1,72 @@
+//! Error types for email delivery.
+
+use thiserror::Error;
+
+/// Errors that can occur while building or sending email.
+#[derive(Debug, Error)]
+pub enum MailerError {
+    /// Sender or recipient address could not be parsed
+    #[error("Invalid email address: {0}")]
+    Address(String),
+
+    /// Message could not be assembled
+    #[error("Failed to build message: {0}")]
+    Build(String),
+
+    /// Network or protocol failure talking to the provider
+    #[error("Transport error: {0}")]
+    Transport(String),
+
+    /// HTTP provider answered with an error status
+    #[error("Provider rejected message ({status}): {body}")]
+    Rejected { status: u16, body: String },
+
+    /// SMTP server permanently refused the message (5xx reply)
+    #[error("SMTP server rejected message: {0}")]
+    SmtpRejected(String),
+
+    /// Mailer is misconfigured
+    #[error("Configuration error: {0}")]
+    Config(String),
+
+    /// Outbox query failed
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+impl MailerError {
+    /// Whether retrying the same message can never succeed.
+    ///
+    /// Malformed messages and client errors from the provider are permanent;
+    /// transport failures, rate limiting and server errors are retried.
+    pub fn is_permanent(&self) -> bool {
+        match self {
+            MailerError::Address(_)
+            | MailerError::Build(_)
+            | MailerError::Config(_)
+            | MailerError::SmtpRejected(_) => true,
+            MailerError::Rejected { status, .. } => {
+                (400..500).contains(status) && *status != 408 && *status != 429
+            }
+            MailerError::Transport(_) | MailerError::Database(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_permanent_errors() {
+        assert!(MailerError::Address("nope".into()).is_permanent());
+        assert!(MailerError::Rejected { status: 422, body: String::new() }.is_permanent());
+    }
+
+    #[test]
+    fn test_retryable_errors() {
+        assert!(!MailerError::Transport("timeout".into()).is_permanent());
+        assert!(!MailerError::Rejected { status: 429, body: String::new() }.is_permanent());
+        assert!(!MailerError::Rejected { status: 503, body: String::new() }.is_permanent());
+    }
+}
@@ -0,0 +1,25 @@
+//! Transactional email for DGuesser.
+//!
+//! This crate provides:
+//! - A [`Mailer`] trait with SMTP, Resend and log-only implementations
+//! - Templates for account emails and the weekly stats digest
+//! - A Postgres-backed outbox with a retrying background delivery worker
+//!
+//! Callers render a template and [`queue::enqueue`] it; the worker started by
+//! [`queue::spawn_delivery_worker`] sends it through the configured mailer.
+
+pub mod config;
+pub mod digest;
+pub mod error;
+pub mod mailer;
+pub mod queue;
+pub mod resend;
+pub mod smtp;
+pub mod templates;
+
+pub use config::{MailProvider, MailerConfig, SmtpConfig, SmtpSecurity};
+pub use error::MailerError;
+pub use mailer::{EmailMessage, LogMailer, Mailer};
+pub use resend::ResendMailer;
+pub use smtp::SmtpMailer;
+pub use templates::{DigestStats, Template};
@@ -0,0 +1,54 @@
+//! The [`Mailer`] trait and the log-only implementation.
+
+use async_trait::async_trait;
+
+use crate::error::MailerError;
+
+/// A rendered email.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmailMessage {
+    /// Recipient address
+    pub to: String,
+    /// Subject line
+    pub subject: String,
+    /// Plain-text body
+    pub text: String,
+    /// HTML body
+    pub html: String,
+}
+
+/// A backend that can deliver email.
+///
+/// Implementations own their sender address and transport configuration.
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    /// Short provider name for logs (e.g. "smtp", "resend").
+    fn name(&self) -> &'static str;
+
+    /// Deliver a single message.
+    async fn send(&self, message: &EmailMessage) -> Result<(), MailerError>;
+}
+
+/// Mailer that writes messages to the log instead of sending them.
+///
+/// Intended for local development: verification and reset links show up in
+/// the API logs. Never use it in production, where bodies contain secrets.
+#[derive(Debug, Default, Clone)]
+pub struct LogMailer;
+
+#[async_trait]
+impl Mailer for LogMailer {
+    fn name(&self) -> &'static str {
+        "log"
+    }
+
+    async fn send(&self, message: &EmailMessage) -> Result<(), MailerError> {
+        tracing::info!(
+            to = %message.to,
+            subject = %message.subject,
+            body = %message.text,
+            "Email (log mailer, not delivered)"
+        );
+        Ok(())
+    }
+}
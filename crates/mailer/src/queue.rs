@@ -0,0 +1,163 @@
+//! Postgres-backed email outbox and delivery worker.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use dguesser_db::DbPool;
+use dguesser_db::emails::{self, NewEmail, QueuedEmail};
+
+use crate::error::MailerError;
+use crate::mailer::{EmailMessage, Mailer};
+use crate::templates::Template;
+
+/// How often the worker polls for due emails.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Maximum emails claimed per poll.
+const BATCH_SIZE: i64 = 20;
+
+/// First retry delay; doubles on every further attempt.
+const BASE_RETRY_SECS: i64 = 30;
+
+/// Upper bound for the retry delay.
+const MAX_RETRY_SECS: i64 = 6 * 60 * 60;
+
+/// Queue a rendered email for delivery. Returns the outbox ID.
+pub async fn enqueue(
+    pool: &DbPool,
+    user_id: Option<&str>,
+    template: Template,
+    message: &EmailMessage,
+) -> Result<String, MailerError> {
+    let id = emails::enqueue(
+        pool,
+        &NewEmail {
+            user_id,
+            template: template.as_str(),
+            to_address: &message.to,
+            subject: &message.subject,
+            text_body: &message.text,
+            html_body: &message.html,
+        },
+    )
+    .await?;
+
+    tracing::debug!(email_id = %id, template = template.as_str(), "Queued email");
+    Ok(id)
+}
+
+/// Delay before retrying after `attempts` failed attempts.
+pub fn retry_delay(attempts: i32) -> chrono::Duration {
+    let exponent = attempts.saturating_sub(1).clamp(0, 20) as u32;
+    let secs = BASE_RETRY_SECS.saturating_mul(1 << exponent).min(MAX_RETRY_SECS);
+    chrono::Duration::seconds(secs)
+}
+
+/// Claim and send one batch of due emails. Returns how many were claimed.
+pub async fn deliver_due(pool: &DbPool, mailer: &dyn Mailer) -> Result<usize, MailerError> {
+    let batch = emails::claim_due(pool, BATCH_SIZE).await?;
+    let claimed = batch.len();
+
+    for email in batch {
+        deliver_one(pool, mailer, email).await?;
+    }
+
+    Ok(claimed)
+}
+
+async fn deliver_one(
+    pool: &DbPool,
+    mailer: &dyn Mailer,
+    email: QueuedEmail,
+) -> Result<(), MailerError> {
+    let message = EmailMessage {
+        to: email.to_address,
+        subject: email.subject,
+        text: email.text_body,
+        html: email.html_body,
+    };
+
+    match mailer.send(&message).await {
+        Ok(()) => {
+            emails::mark_sent(pool, &email.id).await?;
+            tracing::info!(
+                email_id = %email.id,
+                template = %email.template,
+                provider = mailer.name(),
+                "Email delivered"
+            );
+        }
+        Err(err) => {
+            let give_up = err.is_permanent() || email.attempts >= email.max_attempts;
+            let retry_at = (!give_up).then(|| Utc::now() + retry_delay(email.attempts));
+            emails::mark_failed(pool, &email.id, &err.to_string(), retry_at).await?;
+
+            if give_up {
+                tracing::error!(
+                    email_id = %email.id,
+                    template = %email.template,
+                    attempts = email.attempts,
+                    error = %err,
+                    "Email delivery failed permanently"
+                );
+            } else {
+                tracing::warn!(
+                    email_id = %email.id,
+                    template = %email.template,
+                    attempts = email.attempts,
+                    error = %err,
+                    "Email delivery failed, will retry"
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Spawn a background task that delivers queued emails.
+///
+/// Safe to run on several instances at once: rows are claimed with
+/// `FOR UPDATE SKIP LOCKED`.
+pub fn spawn_delivery_worker(pool: DbPool, mailer: Arc<dyn Mailer>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            // Drain the backlog before sleeping again
+            loop {
+                match deliver_due(&pool, mailer.as_ref()).await {
+                    Ok(claimed) if claimed as i64 == BATCH_SIZE => continue,
+                    Ok(_) => break,
+                    Err(e) => {
+                        tracing::error!(error = %e, "Email delivery worker error");
+                        break;
+                    }
+                }
+            }
+        }
+    });
+
+    tracing::info!("Email delivery worker started");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_backs_off() {
+        assert_eq!(retry_delay(1).num_seconds(), 30);
+        assert_eq!(retry_delay(2).num_seconds(), 60);
+        assert_eq!(retry_delay(4).num_seconds(), 240);
+    }
+
+    #[test]
+    fn test_retry_delay_is_capped() {
+        assert_eq!(retry_delay(30).num_seconds(), MAX_RETRY_SECS);
+    }
+}
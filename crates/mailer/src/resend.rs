@@ -0,0 +1,71 @@
+//! Resend (https://resend.com) HTTP API mailer.
+
+use async_trait::async_trait;
+use serde::Serialize;
+
+use crate::error::MailerError;
+use crate::mailer::{EmailMessage, Mailer};
+
+const RESEND_API_URL: &str = "https://api.resend.com/emails";
+
+/// Mailer that delivers through the Resend HTTP API.
+pub struct ResendMailer {
+    client: reqwest::Client,
+    api_key: String,
+    from: String,
+}
+
+#[derive(Serialize)]
+struct SendEmailRequest<'a> {
+    from: &'a str,
+    to: [&'a str; 1],
+    subject: &'a str,
+    html: &'a str,
+    text: &'a str,
+}
+
+impl ResendMailer {
+    /// Create a new Resend mailer.
+    pub fn new(api_key: &str, from: &str) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(15))
+            .build()
+            .unwrap_or_default();
+
+        Self { client, api_key: api_key.to_string(), from: from.to_string() }
+    }
+}
+
+#[async_trait]
+impl Mailer for ResendMailer {
+    fn name(&self) -> &'static str {
+        "resend"
+    }
+
+    async fn send(&self, message: &EmailMessage) -> Result<(), MailerError> {
+        let body = SendEmailRequest {
+            from: &self.from,
+            to: [&message.to],
+            subject: &message.subject,
+            html: &message.html,
+            text: &message.text,
+        };
+
+        let response = self
+            .client
+            .post(RESEND_API_URL)
+            .bearer_auth(&self.api_key)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| MailerError::Transport(e.to_string()))?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+
+        let body = response.text().await.unwrap_or_default();
+        Err(MailerError::Rejected { status: status.as_u16(), body })
+    }
+}
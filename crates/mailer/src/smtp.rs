@@ -0,0 +1,74 @@
+//! SMTP relay mailer.
+
+use async_trait::async_trait;
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+use crate::config::{SmtpConfig, SmtpSecurity};
+use crate::error::MailerError;
+use crate::mailer::{EmailMessage, Mailer};
+
+/// Mailer that delivers through an SMTP relay.
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    /// Create a new SMTP mailer. Connections are pooled and opened lazily.
+    pub fn new(config: &SmtpConfig, from: &str) -> Result<Self, MailerError> {
+        let from: Mailbox = from.parse().map_err(|_| MailerError::Address(from.to_string()))?;
+
+        let builder = match config.security {
+            SmtpSecurity::StartTls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)
+                    .map_err(|e| MailerError::Config(e.to_string()))?
+            }
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host)
+                .map_err(|e| MailerError::Config(e.to_string()))?,
+            SmtpSecurity::None => {
+                AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host)
+            }
+        };
+
+        let mut builder = builder.port(config.port);
+        if let (Some(username), Some(password)) = (&config.username, &config.password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        Ok(Self { transport: builder.build(), from })
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    fn name(&self) -> &'static str {
+        "smtp"
+    }
+
+    async fn send(&self, message: &EmailMessage) -> Result<(), MailerError> {
+        let to: Mailbox =
+            message.to.parse().map_err(|_| MailerError::Address(message.to.clone()))?;
+
+        let email = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(&message.subject)
+            .multipart(MultiPart::alternative_plain_html(
+                message.text.clone(),
+                message.html.clone(),
+            ))
+            .map_err(|e| MailerError::Build(e.to_string()))?;
+
+        self.transport.send(email).await.map_err(|e| {
+            if e.is_permanent() {
+                MailerError::SmtpRejected(e.to_string())
+            } else {
+                MailerError::Transport(e.to_string())
+            }
+        })?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,253 @@
+//! Email templates.
+//!
+//! Each template renders both a plain-text and an HTML body. Templates are
+//! plain Rust functions so missing fields are compile errors; every
+//! interpolated value goes through [`escape_html`] in the HTML variant.
+
+use crate::mailer::EmailMessage;
+
+/// Template identifiers, stored with queued emails for observability.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Template {
+    /// Confirm ownership of an email address
+    EmailVerification,
+    /// Password reset link
+    PasswordReset,
+    /// Weekly stats digest
+    WeeklyDigest,
+    /// Sign-in from a device not seen before
+    NewDeviceLogin,
+}
+
+impl Template {
+    /// Database representation.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Template::EmailVerification => "email_verification",
+            Template::PasswordReset => "password_reset",
+            Template::WeeklyDigest => "weekly_digest",
+            Template::NewDeviceLogin => "new_device_login",
+        }
+    }
+}
+
+/// A user's activity for the weekly digest.
+#[derive(Debug, Clone)]
+pub struct DigestStats {
+    pub display_name: String,
+    pub games_played: i64,
+    pub total_score: i64,
+    pub best_score: i32,
+}
+
+/// Render the email verification message.
+pub fn email_verification(to: &str, link: &str) -> EmailMessage {
+    let text = format!(
+        "Welcome to DGuesser!\n\n\
+         Confirm your email address by opening this link:\n{link}\n\n\
+         The link expires in 24 hours. If you didn't create an account, you can ignore this email."
+    );
+    let html = layout(
+        "Confirm your email",
+        &format!(
+            "<p>Welcome to DGuesser!</p>\
+             <p>Confirm your email address to finish setting up your account.</p>\
+             {button}\
+             <p class=\"muted\">The link expires in 24 hours. If you didn't create an account, \
+             you can ignore this email.</p>",
+            button = button(link, "Verify email"),
+        ),
+    );
+
+    EmailMessage {
+        to: to.to_string(),
+        subject: "Verify your email address".to_string(),
+        text,
+        html,
+    }
+}
+
+/// Render the password reset message.
+pub fn password_reset(to: &str, link: &str) -> EmailMessage {
+    let text = format!(
+        "Someone requested a password reset for your DGuesser account.\n\n\
+         Set a new password here:\n{link}\n\n\
+         The link expires in 1 hour. If this wasn't you, you can ignore this email."
+    );
+    let html = layout(
+        "Reset your password",
+        &format!(
+            "<p>Someone requested a password reset for your DGuesser account.</p>\
+             {button}\
+             <p class=\"muted\">The link expires in 1 hour. If this wasn't you, \
+             you can ignore this email.</p>",
+            button = button(link, "Reset password"),
+        ),
+    );
+
+    EmailMessage { to: to.to_string(), subject: "Reset your password".to_string(), text, html }
+}
+
+/// Render the weekly stats digest.
+pub fn weekly_digest(
+    to: &str,
+    stats: &DigestStats,
+    play_link: &str,
+    preferences_link: &str,
+) -> EmailMessage {
+    let games = if stats.games_played == 1 { "game" } else { "games" };
+    let text = format!(
+        "Hi {name},\n\n\
+         Your week on DGuesser:\n\
+         - {played} {games} played\n\
+         - {total} total points\n\
+         - {best} best game\n\n\
+         Play again: {play_link}\n\n\
+         Don't want these emails? Turn them off: {preferences_link}",
+        name = stats.display_name,
+        played = stats.games_played,
+        total = stats.total_score,
+        best = stats.best_score,
+    );
+    let html = layout(
+        "Your week on DGuesser",
+        &format!(
+            "<p>Hi {name},</p>\
+             <table class=\"stats\"><tr>\
+             <td><strong>{played}</strong><br>{games} played</td>\
+             <td><strong>{total}</strong><br>total points</td>\
+             <td><strong>{best}</strong><br>best game</td>\
+             </tr></table>\
+             {button}\
+             <p class=\"muted\"><a href=\"{preferences}\">Turn off weekly emails</a></p>",
+            name = escape_html(&stats.display_name),
+            played = stats.games_played,
+            total = stats.total_score,
+            best = stats.best_score,
+            button = button(play_link, "Play again"),
+            preferences = escape_html(preferences_link),
+        ),
+    );
+
+    EmailMessage {
+        to: to.to_string(),
+        subject: format!("Your week on DGuesser: {} {games} played", stats.games_played),
+        text,
+        html,
+    }
+}
+
+/// Render the new-device sign-in alert.
+///
+/// `location` is an approximate place such as a country code, if known.
+pub fn new_device_login(
+    to: &str,
+    device: &str,
+    location: Option<&str>,
+    sessions_link: &str,
+) -> EmailMessage {
+    let location = location.unwrap_or("an unknown location");
+    let text = format!(
+        "Your DGuesser account was just signed in to from a new device:\n\n\
+         {device} ({location})\n\n\
+         If this was you, there's nothing to do. If not, reset your password and \
+         sign out of your other sessions here:\n{sessions_link}"
+    );
+    let html = layout(
+        "New sign-in to your account",
+        &format!(
+            "<p>Your DGuesser account was just signed in to from a new device:</p>\
+             <p><strong>{device}</strong> ({location})</p>\
+             <p>If this was you, there's nothing to do. If not, reset your password and \
+             sign out of your other sessions.</p>\
+             {button}",
+            device = escape_html(device),
+            location = escape_html(location),
+            button = button(sessions_link, "Review sessions"),
+        ),
+    );
+
+    EmailMessage { to: to.to_string(), subject: format!("New sign-in from {device}"), text, html }
+}
+
+fn button(link: &str, label: &str) -> String {
+    format!(
+        "<p><a class=\"button\" href=\"{href}\">{label}</a></p>\
+         <p class=\"muted\">Or paste this link into your browser:<br>{href}</p>",
+        href = escape_html(link),
+        label = escape_html(label),
+    )
+}
+
+fn layout(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>{title}</title>\
+         <style>\
+         body{{font-family:system-ui,sans-serif;color:#1f2937;max-width:560px;margin:0 auto;padding:24px}}\
+         .button{{display:inline-block;background:#16a34a;color:#fff;padding:10px 18px;\
+         border-radius:6px;text-decoration:none}}\
+         .muted{{color:#6b7280;font-size:13px}}\
+         .stats td{{padding:8px 16px;text-align:center}}\
+         </style></head><body><h1>{title}</h1>{body}</body></html>",
+        title = escape_html(title),
+    )
+}
+
+/// Escape text for safe interpolation into HTML content and attributes.
+pub fn escape_html(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(
+            escape_html("<b>\"Tom\" & 'Jerry'</b>"),
+            "&lt;b&gt;&quot;Tom&quot; &amp; &#39;Jerry&#39;&lt;/b&gt;"
+        );
+    }
+
+    #[test]
+    fn test_verification_contains_link() {
+        let message = email_verification("a@example.com", "https://dguesser.lol/v?token=abc&x=1");
+        assert_eq!(message.to, "a@example.com");
+        assert!(message.text.contains("https://dguesser.lol/v?token=abc&x=1"));
+        assert!(message.html.contains("https://dguesser.lol/v?token=abc&amp;x=1"));
+    }
+
+    #[test]
+    fn test_digest_escapes_display_name() {
+        let stats = DigestStats {
+            display_name: "<script>".to_string(),
+            games_played: 1,
+            total_score: 4200,
+            best_score: 4200,
+        };
+        let message = weekly_digest("a@example.com", &stats, "https://x/play", "https://x/account");
+        assert!(!message.html.contains("<script>"));
+        assert!(message.subject.contains("1 game played"));
+    }
+
+    #[test]
+    fn test_new_device_login_escapes_device() {
+        let message =
+            new_device_login("a@example.com", "<b>Chrome</b> on Windows", None, "https://x/s");
+        assert!(!message.html.contains("<b>Chrome</b>"));
+        assert!(message.text.contains("an unknown location"));
+        assert_eq!(message.subject, "New sign-in from <b>Chrome</b> on Windows");
+    }
+}
@@ -29,6 +29,144 @@ pub struct AdminStatsResponse {
     pub by_review_status: HashMap<String, i64>,
 }
 
+// =============================================================================
+// Location Health
+// =============================================================================
+
+/// Query parameters for location health trends
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct LocationHealthParams {
+    /// Days of history to include (default 30, max 90)
+    #[serde(default = "default_health_days")]
+    pub days: i32,
+}
+
+fn default_health_days() -> i32 {
+    30
+}
+
+/// Summary of one health check run
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct HealthCheckRunItem {
+    /// Run ID
+    #[schema(example = "lhc_V1StGXR8_Z5j")]
+    pub id: String,
+    /// When the run started
+    pub started_at: DateTime<Utc>,
+    /// When the run finished
+    pub finished_at: DateTime<Utc>,
+    /// Locations checked
+    pub checked: i32,
+    /// Panoramas that still exist
+    pub healthy: i32,
+    /// Panoramas that are gone (location deactivated)
+    pub dead: i32,
+    /// Lookups that failed and will be retried
+    pub errors: i32,
+    /// Inactive locations found alive again and proposed for re-approval
+    pub revived: i32,
+}
+
+/// Health check totals for one day
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct HealthTrendPoint {
+    /// Day (UTC)
+    pub date: NaiveDate,
+    /// Number of runs that day
+    pub runs: i64,
+    /// Locations checked
+    pub checked: i64,
+    /// Panoramas that still exist
+    pub healthy: i64,
+    /// Panoramas that are gone
+    pub dead: i64,
+    /// Lookups that failed
+    pub errors: i64,
+    /// Inactive locations proposed for re-approval
+    pub revived: i64,
+}
+
+/// Location coverage health response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct LocationHealthResponse {
+    /// Most recent run, if the checker has run
+    pub last_run: Option<HealthCheckRunItem>,
+    /// Daily totals, oldest first
+    pub daily: Vec<HealthTrendPoint>,
+}
+
+// =============================================================================
+// Pack Cache
+// =============================================================================
+
+/// Pack range cache counters for this API instance
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct PackCacheStatsResponse {
+    /// Whether the range cache is enabled
+    pub enabled: bool,
+    /// Block reads served from memory
+    pub block_hits: u64,
+    /// Block reads that missed memory
+    pub block_misses: u64,
+    /// Memory misses served from the shared Redis tier
+    pub redis_hits: u64,
+    /// Country index reads served from memory
+    pub index_hits: u64,
+    /// Country index reads that went to storage
+    pub index_misses: u64,
+    /// Share of block reads that avoided storage (0.0 - 1.0)
+    pub hit_rate: f64,
+    /// Blocks currently cached in memory
+    pub blocks: u64,
+    /// Bytes currently cached in memory
+    pub bytes: u64,
+}
+
+// =============================================================================
+// Street View Quota
+// =============================================================================
+
+/// Street View metadata API quota status, shared by all API instances
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct StreetViewQuotaResponse {
+    /// Whether a Maps API key is configured
+    pub configured: bool,
+    /// Breaker state: "closed" (quota available), "open" (lookups paused) or
+    /// "half_open" (next lookup probes the quota)
+    #[schema(example = "closed")]
+    pub state: String,
+    /// Lookups are paused until this time while open
+    pub paused_until: Option<DateTime<Utc>>,
+    /// Quota errors since the last successful lookup
+    pub consecutive_trips: u32,
+    /// When the quota was last reported exhausted
+    pub last_tripped_at: Option<DateTime<Utc>>,
+    /// Metadata requests sent today (UTC) by all instances; unknown without
+    /// Redis
+    pub requests_today: Option<u64>,
+}
+
+/// Map tiles served to one user on one day
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MapTileUsageEntry {
+    pub user_id: String,
+    pub display_name: String,
+    /// "streetview", "roadmap" or "satellite"
+    #[schema(example = "streetview")]
+    pub map_type: String,
+    pub tiles: u64,
+}
+
+/// Map tile usage per user for a day, for billing attribution
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MapTileUsageResponse {
+    pub date: NaiveDate,
+    /// Tiles served to all users
+    pub total_tiles: u64,
+    /// Usage per user and map type, most tiles first
+    pub users: Vec<MapTileUsageEntry>,
+}
+
 // =============================================================================
 // Review Queue
 // =============================================================================
@@ -78,6 +216,14 @@ pub struct ReviewQueueItem {
     pub review_status: String,
     /// When the location was created
     pub created_at: DateTime<Utc>,
+    /// Internal notes on the location, newest first
+    pub notes: Vec<LocationNoteItem>,
+    /// Reviewer with an active claim on the location
+    pub claimed_by: Option<String>,
+    /// Display name of the claiming reviewer
+    pub claimed_by_name: Option<String>,
+    /// When the active claim lapses
+    pub claim_expires_at: Option<DateTime<Utc>>,
 }
 
 /// Paginated review queue response
@@ -139,6 +285,12 @@ pub struct LocationDetailResponse {
     pub elevation: Option<i32>,
     /// Default heading
     pub heading: Option<f64>,
+    /// Difficulty rating (0-100) from past guesses; absent until rated
+    pub difficulty: Option<i16>,
+    /// Median distance of past guesses in meters
+    pub median_guess_distance_meters: Option<f64>,
+    /// Median score of past guesses
+    pub median_guess_score: Option<f64>,
     /// Failure count
     pub failure_count: i32,
     /// Last failure reason
@@ -153,6 +305,81 @@ pub struct LocationDetailResponse {
     pub created_at: DateTime<Utc>,
     /// Reports for this location
     pub reports: Vec<LocationReportItem>,
+    /// Internal notes on the location, newest first
+    pub notes: Vec<LocationNoteItem>,
+}
+
+// =============================================================================
+// Location Notes
+// =============================================================================
+
+/// An internal note on a location, written by an admin or a map curator
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct LocationNoteItem {
+    /// Note ID
+    pub id: i64,
+    /// Location the note is on
+    #[schema(example = "loc_V1StGXR8_Z5j")]
+    pub location_id: String,
+    /// Who wrote it (absent if their account was deleted)
+    pub author_id: Option<String>,
+    /// Author's display name
+    pub author_name: Option<String>,
+    /// Map a curator wrote the note from; absent for admin notes
+    pub map_id: Option<String>,
+    /// Note text
+    #[schema(example = "pano replaced 2025-03")]
+    pub body: String,
+    /// Normalized tags
+    #[schema(example = json!(["borderline-trekker"]))]
+    pub tags: Vec<String>,
+    /// When the note was written
+    pub created_at: DateTime<Utc>,
+}
+
+/// Add a note to a location
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateLocationNoteRequest {
+    /// Note text (1-2000 characters)
+    #[schema(example = "pano replaced 2025-03")]
+    pub body: String,
+    /// Tags (max 10); trimmed, lowercased and spaces replaced with `-`
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Query parameters for searching location notes
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct LocationNotesParams {
+    /// Text the note body contains
+    pub q: Option<String>,
+    /// Tag the note has
+    pub tag: Option<String>,
+    /// Only notes on this location
+    pub location_id: Option<String>,
+    /// Only notes by this user
+    pub author_id: Option<String>,
+    /// Page number (1-based)
+    #[serde(default = "default_page")]
+    pub page: i64,
+    /// Items per page (max 100)
+    #[serde(default = "default_per_page")]
+    pub per_page: i64,
+}
+
+/// Location notes page
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct LocationNotesResponse {
+    /// Notes, newest first
+    pub notes: Vec<LocationNoteItem>,
+    /// Total matching notes
+    pub total: i64,
+    /// Current page
+    pub page: i64,
+    /// Items per page
+    pub per_page: i64,
+    /// Total number of pages
+    pub total_pages: i64,
 }
 
 // =============================================================================
@@ -258,3 +485,910 @@ pub struct UpdateReviewStatusResponse {
     /// Whether the location is now active
     pub active: bool,
 }
+
+// =============================================================================
+// Re-approval
+// =============================================================================
+
+/// Query parameters for the re-approval queue
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct ReapprovalQueueParams {
+    /// Page number (1-based)
+    #[serde(default = "default_page")]
+    pub page: i64,
+    /// Items per page (max 100)
+    #[serde(default = "default_per_page")]
+    pub per_page: i64,
+}
+
+/// An inactive location whose panorama exists again
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReapprovalItem {
+    /// Location ID
+    #[schema(example = "loc_V1StGXR8_Z5j")]
+    pub location_id: String,
+    /// Panorama ID for preview
+    pub panorama_id: String,
+    /// Latitude
+    pub lat: f64,
+    /// Longitude
+    pub lng: f64,
+    /// Country code
+    pub country_code: Option<String>,
+    /// Review status when the location was found alive
+    pub previous_status: Option<String>,
+    /// Why the location was deactivated
+    pub previous_reason: Option<String>,
+    /// When the health checker proposed re-approval
+    pub proposed_at: DateTime<Utc>,
+}
+
+/// Paginated re-approval queue response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReapprovalQueueResponse {
+    /// Proposals, oldest first
+    pub locations: Vec<ReapprovalItem>,
+    /// Total pending proposals
+    pub total: i64,
+    /// Current page number
+    pub page: i64,
+    /// Items per page
+    pub per_page: i64,
+    /// Total number of pages
+    pub total_pages: i64,
+}
+
+/// Request to accept or dismiss a re-approval proposal
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DecideReapprovalRequest {
+    /// `true` reactivates and approves the location; `false` leaves it inactive
+    pub accept: bool,
+}
+
+// =============================================================================
+// Review Assignment
+// =============================================================================
+
+/// Request to claim a batch of locations from the review queue
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct ClaimReviewBatchRequest {
+    /// How many locations to claim (default 20)
+    #[schema(example = 20)]
+    pub count: Option<i64>,
+}
+
+/// Locations claimed for review
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ClaimReviewBatchResponse {
+    /// Newly claimed location IDs, in queue order
+    pub location_ids: Vec<String>,
+    /// Locations the reviewer now has claimed, including earlier claims
+    pub active_claims: i64,
+    /// When the new claims lapse; `None` if nothing was claimed
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Response after releasing the caller's claims
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReleaseReviewClaimsResponse {
+    /// Number of claims released
+    pub released: u64,
+}
+
+/// Query parameters for reviewer throughput
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReviewerStatsParams {
+    /// Days of decisions to include (default 7, max 90)
+    #[serde(default = "default_reviewer_stats_days")]
+    pub days: i32,
+}
+
+fn default_reviewer_stats_days() -> i32 {
+    7
+}
+
+/// Review throughput for one reviewer
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReviewerStatsItem {
+    /// Reviewer user ID
+    pub reviewer_id: String,
+    /// Reviewer display name
+    pub reviewer_name: Option<String>,
+    /// Review decisions made in the period
+    pub reviewed: i64,
+    /// Locations approved
+    pub approved: i64,
+    /// Locations rejected
+    pub rejected: i64,
+    /// Locations flagged for further review
+    pub flagged: i64,
+    /// Locations currently claimed and not yet decided
+    pub in_progress: i64,
+    /// Mean seconds from claim to decision, for claimed locations
+    pub avg_review_secs: Option<f64>,
+}
+
+/// Per-reviewer throughput response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReviewerStatsResponse {
+    /// Days of decisions included
+    pub days: i32,
+    /// Reviewers, busiest first
+    pub reviewers: Vec<ReviewerStatsItem>,
+}
+
+// =============================================================================
+// Profile Moderation
+// =============================================================================
+
+/// Request to reset parts of a user's profile and optionally lock it
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct ModerateProfileRequest {
+    /// Remove the avatar (uploaded images are deleted from storage)
+    #[serde(default)]
+    pub reset_avatar: bool,
+    /// Clear the bio
+    #[serde(default)]
+    pub reset_bio: bool,
+    /// Replace the display name with a generic one
+    #[serde(default)]
+    pub reset_display_name: bool,
+    /// Clear the home country
+    #[serde(default)]
+    pub reset_country: bool,
+    /// Clear the profile color
+    #[serde(default)]
+    pub reset_color: bool,
+    /// Lock (true) or unlock (false) the profile against edits; unchanged when absent
+    pub locked: Option<bool>,
+}
+
+/// Response after moderating a profile
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ModerateProfileResponse {
+    /// Success message
+    pub message: String,
+    /// The moderated user
+    pub user_id: String,
+    /// Whether the profile is now locked
+    pub locked: bool,
+}
+
+/// Request to rename a user
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct ForceRenameRequest {
+    /// New display name; a generic "Player XXXX" name when absent
+    pub display_name: Option<String>,
+    /// Lock (true) or unlock (false) the profile against edits; unchanged when absent
+    pub locked: Option<bool>,
+}
+
+/// Response after renaming a user
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ForceRenameResponse {
+    /// The renamed user
+    pub user_id: String,
+    /// The user's display name now
+    pub display_name: String,
+    /// Whether the profile is now locked
+    pub locked: bool,
+}
+
+/// Query parameters for flagged display names
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct NameFlagsParams {
+    /// Page number (1-based)
+    #[serde(default = "default_page")]
+    pub page: i64,
+    /// Items per page (max 100)
+    #[serde(default = "default_per_page")]
+    pub per_page: i64,
+}
+
+/// A display name flagged for review
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NameFlagItem {
+    /// Flag ID
+    #[schema(example = "nfl_V1StGXR8_Z5j")]
+    pub id: String,
+    /// Flagged user
+    pub user_id: String,
+    /// The name that was flagged
+    pub display_name: String,
+    /// The user's display name now
+    pub current_display_name: String,
+    /// "impersonation", "link", "confusables" or "reported"
+    #[schema(example = "impersonation")]
+    pub reason: String,
+    /// "pending", "dismissed" or "renamed"
+    #[schema(example = "pending")]
+    pub status: String,
+    /// When the name was flagged
+    pub created_at: DateTime<Utc>,
+}
+
+/// Pending flagged display names, oldest first
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NameFlagsResponse {
+    /// Flags on this page
+    pub flags: Vec<NameFlagItem>,
+    /// Total pending flags
+    pub total: i64,
+    /// Current page
+    pub page: i64,
+    /// Items per page
+    pub per_page: i64,
+    /// Total number of pages
+    pub total_pages: i64,
+}
+
+// =============================================================================
+// Player Reports
+// =============================================================================
+
+/// Query parameters for the player report queue
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct PlayerReportsParams {
+    /// Only reports in this status ("open", "reviewing", "actioned", "dismissed")
+    pub status: Option<String>,
+    /// Only reports in this category
+    pub category: Option<String>,
+    /// Only reports about this player
+    pub user_id: Option<String>,
+    /// Page number (1-based)
+    #[serde(default = "default_page")]
+    pub page: i64,
+    /// Items per page (max 100)
+    #[serde(default = "default_per_page")]
+    pub per_page: i64,
+}
+
+/// Other signals against a reported player
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReportedPlayerSignals {
+    /// Open reports against the player, this one included
+    pub open_reports: i64,
+    /// All reports against the player
+    pub total_reports: i64,
+    /// Cheating signals recorded for the player
+    pub cheat_signals: i64,
+    /// Whether the player's display name is waiting for review
+    pub name_flagged: bool,
+}
+
+/// A report about a player
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PlayerReportItem {
+    /// Report ID
+    #[schema(example = "prp_V1StGXR8_Z5j")]
+    pub id: String,
+    /// Who reported (absent if their account was deleted)
+    pub reporter_id: Option<String>,
+    /// Reporter's display name
+    pub reporter_name: Option<String>,
+    /// Reported player
+    pub reported_user_id: String,
+    /// Reported player's display name
+    pub reported_name: String,
+    /// Report category
+    #[schema(example = "cheating")]
+    pub category: String,
+    /// Game it happened in
+    pub game_id: Option<String>,
+    /// Quoted chat
+    pub chat_excerpt: Option<String>,
+    /// Reporter's notes
+    pub notes: Option<String>,
+    /// "open", "reviewing", "actioned" or "dismissed"
+    #[schema(example = "open")]
+    pub status: String,
+    /// Moderator's note on the outcome
+    pub resolution_note: Option<String>,
+    /// Moderator who last changed the status
+    pub reviewed_by: Option<String>,
+    /// When the status last changed
+    pub reviewed_at: Option<DateTime<Utc>>,
+    /// When the report was submitted
+    pub created_at: DateTime<Utc>,
+    /// Other signals against the reported player
+    pub signals: ReportedPlayerSignals,
+}
+
+/// Player report queue page
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PlayerReportsResponse {
+    /// Reports, oldest first
+    pub reports: Vec<PlayerReportItem>,
+    /// Total matching reports
+    pub total: i64,
+    /// Current page
+    pub page: i64,
+    /// Items per page
+    pub per_page: i64,
+    /// Total number of pages
+    pub total_pages: i64,
+}
+
+/// Move a player report through the workflow
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdatePlayerReportRequest {
+    /// New status: "open", "reviewing", "actioned" or "dismissed"
+    #[schema(example = "actioned")]
+    pub status: String,
+    /// Note on the outcome (max 1000 characters)
+    pub resolution_note: Option<String>,
+}
+
+/// Result of a status change
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdatePlayerReportResponse {
+    /// Report ID
+    pub id: String,
+    /// The report's status now
+    pub status: String,
+    /// When the status changed
+    pub reviewed_at: Option<DateTime<Utc>>,
+}
+
+// =============================================================================
+// Chat Transcripts
+// =============================================================================
+
+/// A chat message in a game transcript
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ChatTranscriptMessage {
+    /// Message ID, increasing in send order
+    pub id: i64,
+    /// Sender user ID
+    pub user_id: String,
+    /// Sender's current display name
+    pub display_name: String,
+    /// Text as other players saw it
+    pub content: String,
+    /// Text before the chat filter masked it, if it did
+    pub original_content: Option<String>,
+    /// When the message was sent
+    pub sent_at: DateTime<Utc>,
+}
+
+/// Chat sent in a game's party while the game ran
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GameChatTranscriptResponse {
+    /// Game ID
+    pub game_id: String,
+    /// Party the chat belongs to (absent for games without a party)
+    pub party_id: Option<String>,
+    /// Messages, oldest first. Messages older than the retention window are gone.
+    pub messages: Vec<ChatTranscriptMessage>,
+}
+
+// =============================================================================
+// Live Games
+// =============================================================================
+
+/// Redis key prefix of the state the realtime server caches for each running
+/// game (followed by the game ID)
+pub const GAME_STATE_KEY_PREFIX: &str = "dguesser:game:";
+
+/// A game a realtime server is running
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct LiveGameItem {
+    /// Game ID
+    pub game_id: String,
+    /// Actor phase (lobby, active, round_in_progress, between_rounds, finished)
+    pub status: String,
+    pub round_number: u8,
+    pub total_rounds: u8,
+    pub players: usize,
+    pub connected_players: usize,
+    /// When the current round's time runs out
+    pub round_ends_at: Option<DateTime<Utc>>,
+    /// Paused by a disconnected host or a teacher
+    pub paused: bool,
+}
+
+/// Games running on the realtime servers
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct LiveGamesResponse {
+    pub games: Vec<LiveGameItem>,
+}
+
+/// A player in a running game
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct LivePlayerState {
+    pub user_id: String,
+    pub display_name: String,
+    pub is_host: bool,
+    pub total_score: u32,
+    pub connected: bool,
+    /// When the player's reconnect grace period started
+    pub disconnected_at: Option<DateTime<Utc>>,
+    /// Median of the player's recent socket round-trip times
+    pub rtt_ms: Option<u32>,
+    /// Recent round-trip times, oldest first
+    pub rtt_samples: Vec<u32>,
+}
+
+/// The round in progress in a running game
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct LiveRoundState {
+    pub round_id: String,
+    pub round_number: u8,
+    pub location_id: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub time_limit_ms: Option<u32>,
+    /// When the round's time runs out (absent without a time limit)
+    pub ends_at: Option<DateTime<Utc>>,
+    /// Players who have guessed
+    pub guessed: Vec<String>,
+}
+
+/// Snapshot of a running game as last saved by its actor
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct LiveGameStateResponse {
+    pub game_id: String,
+    pub status: String,
+    pub round_number: u8,
+    pub total_rounds: u8,
+    /// Game settings
+    #[schema(value_type = Object)]
+    pub settings: serde_json::Value,
+    pub players: Vec<LivePlayerState>,
+    pub current_round: Option<LiveRoundState>,
+    /// When the between-rounds wait ends
+    pub between_rounds_ends_at: Option<DateTime<Utc>>,
+    /// When a time-attack game's time budget runs out
+    pub time_budget_ends_at: Option<DateTime<Utc>>,
+    /// When the game was paused for a disconnected host
+    pub paused_at: Option<DateTime<Utc>>,
+    pub teacher_paused: bool,
+    /// Players who voted to skip the between-rounds wait
+    pub skip_votes: Vec<String>,
+    pub rerolls_used: u8,
+}
+
+/// Request to end a running game
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct ForceEndGameRequest {
+    /// Shown to players in the room and kept in the audit log
+    pub reason: Option<String>,
+}
+
+/// Result of ending a running game
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ForceEndGameResponse {
+    pub game_id: String,
+    /// False if the game had already ended
+    pub ended: bool,
+}
+
+// =============================================================================
+// System Maps
+// =============================================================================
+
+/// A system map (a map with no creator)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SystemMapItem {
+    /// Map ID
+    #[schema(example = "map_Wor1dG1oba1X")]
+    pub id: String,
+    /// URL-friendly slug
+    #[schema(example = "world")]
+    pub slug: String,
+    /// Display name
+    pub name: String,
+    /// Description
+    pub description: Option<String>,
+    /// Location selection rules (countries, year range, outdoor only, ...)
+    #[schema(value_type = Object)]
+    pub rules: serde_json::Value,
+    /// Whether this is the default map
+    pub is_default: bool,
+    /// Whether the map can be played
+    pub active: bool,
+    /// Number of locations
+    pub location_count: i32,
+    /// Number of finished plays
+    pub play_count: i32,
+    /// When the map was created
+    pub created_at: DateTime<Utc>,
+    /// When the map was last updated
+    pub updated_at: DateTime<Utc>,
+}
+
+/// System maps list response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SystemMapsListResponse {
+    /// All system maps, inactive ones included
+    pub maps: Vec<SystemMapItem>,
+}
+
+/// Request to create a system map
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateSystemMapRequest {
+    /// URL-friendly slug (lowercase letters, digits, and hyphens)
+    #[schema(example = "modern-europe")]
+    pub slug: String,
+    /// Display name (3-100 characters)
+    #[schema(example = "Modern Europe")]
+    pub name: String,
+    /// Description (max 500 characters)
+    pub description: Option<String>,
+    /// Location selection rules; omitted rules match every location
+    #[serde(default)]
+    #[schema(value_type = Option<Object>)]
+    pub rules: Option<serde_json::Value>,
+    /// Make this the default map
+    #[serde(default)]
+    pub is_default: bool,
+}
+
+/// Request to edit a system map. Omitted fields are left unchanged.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateSystemMapRequest {
+    /// New display name (3-100 characters)
+    pub name: Option<String>,
+    /// New description; an empty string removes it
+    pub description: Option<String>,
+    /// New location selection rules, replacing the current ones
+    #[schema(value_type = Option<Object>)]
+    pub rules: Option<serde_json::Value>,
+}
+
+/// Request to activate or deactivate a system map
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SetMapActiveRequest {
+    /// Whether the map can be played
+    pub active: bool,
+}
+
+/// Request to feature a system map for a period
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ScheduleFeaturedMapRequest {
+    /// System map to make the default while featured
+    #[schema(example = "map_Wor1dG1oba1X")]
+    pub map_id: String,
+    /// Event name shown alongside the map (max 100 characters)
+    #[schema(example = "Island Week")]
+    pub title: Option<String>,
+    /// When the map becomes the default; a past time starts it right away
+    pub starts_at: DateTime<Utc>,
+    /// When the previous default map is restored
+    pub ends_at: DateTime<Utc>,
+}
+
+/// A scheduled, running, or finished feature
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ScheduledFeatureItem {
+    /// Feature ID
+    pub id: i64,
+    /// Featured map ID
+    pub map_id: String,
+    /// Featured map slug
+    pub map_slug: String,
+    /// Featured map name
+    pub map_name: String,
+    /// Event name
+    pub title: Option<String>,
+    /// Scheduled start
+    pub starts_at: DateTime<Utc>,
+    /// Scheduled end
+    pub ends_at: DateTime<Utc>,
+    /// Admin who scheduled the feature
+    pub created_by: Option<String>,
+    /// When the map was made the default
+    pub activated_at: Option<DateTime<Utc>>,
+    /// When the feature ended, or was skipped because the map was unavailable
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// Featured map schedule response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FeaturedMapScheduleResponse {
+    /// Pending and running features plus those finished in the last 30 days,
+    /// by start time
+    pub features: Vec<ScheduledFeatureItem>,
+}
+
+// =============================================================================
+// Analytics
+// =============================================================================
+
+/// Query parameters for daily analytics
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AnalyticsParams {
+    /// Days of history to include (default 30, max 365)
+    #[serde(default = "default_analytics_days")]
+    pub days: i32,
+}
+
+fn default_analytics_days() -> i32 {
+    30
+}
+
+/// Query parameters for retention cohorts
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RetentionParams {
+    /// Weekly signup cohorts to include (default 12, max 52)
+    #[serde(default = "default_retention_weeks")]
+    pub weeks: i32,
+}
+
+fn default_retention_weeks() -> i32 {
+    12
+}
+
+/// Games of one mode over a period
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ModeGameStats {
+    /// Game mode
+    #[schema(example = "solo")]
+    pub mode: String,
+    /// Games created
+    pub created: i64,
+    /// Games finished
+    pub finished: i64,
+    /// Average start-to-end time of finished games, in seconds
+    pub avg_duration_secs: Option<f64>,
+}
+
+/// Player and game activity for one day
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DailyActivityPoint {
+    /// Day (UTC)
+    pub date: NaiveDate,
+    /// Users who submitted at least one guess
+    pub active_users: i32,
+    /// Accounts created, guests included
+    pub new_users: i32,
+    /// Guesses submitted
+    pub guesses: i32,
+    /// Games by mode
+    pub games: Vec<ModeGameStats>,
+}
+
+/// Game and player activity response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ActivityAnalyticsResponse {
+    /// Daily activity, oldest first
+    pub daily: Vec<DailyActivityPoint>,
+    /// Games by mode over the whole period
+    pub totals_by_mode: Vec<ModeGameStats>,
+    /// When the rollups were last refreshed
+    pub refreshed_at: Option<DateTime<Utc>>,
+}
+
+/// Percentiles of a distribution
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Percentiles {
+    pub p10: f64,
+    pub p25: f64,
+    pub p50: f64,
+    pub p75: f64,
+    pub p90: f64,
+}
+
+/// Guess distribution for one day
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GuessDistributionPoint {
+    /// Day (UTC)
+    pub date: NaiveDate,
+    /// Guesses submitted
+    pub guesses: i32,
+    /// Guess score percentiles; absent without guesses
+    pub score: Option<Percentiles>,
+    /// Guess distance percentiles in km; absent without guesses
+    pub distance_km: Option<Percentiles>,
+}
+
+/// Guess distribution response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GuessAnalyticsResponse {
+    /// Daily distributions, oldest first
+    pub daily: Vec<GuessDistributionPoint>,
+}
+
+/// Retention of one weekly signup cohort
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RetentionCohort {
+    /// Monday of the signup week (UTC)
+    pub cohort_week: NaiveDate,
+    /// Users who signed up that week
+    pub size: i32,
+    /// Users active in each week since signup; index 0 is the signup week
+    pub retained_users: Vec<i32>,
+    /// `retained_users` as a share of the cohort (0.0 - 1.0)
+    pub retention: Vec<f64>,
+}
+
+/// Retention cohorts response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RetentionAnalyticsResponse {
+    /// Cohorts, oldest first
+    pub cohorts: Vec<RetentionCohort>,
+}
+
+/// Guesses on rounds in one country over a period
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CountryGuessStats {
+    /// ISO 3166-1 alpha-2 code of the round location
+    #[schema(example = "SE")]
+    pub country_code: String,
+    /// Guesses submitted
+    pub guesses: i64,
+    /// Average guess score
+    pub avg_score: f64,
+    /// Average guess distance in km
+    pub avg_distance_km: f64,
+}
+
+/// Guesses on rounds in one continent over a period
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ContinentGuessStats {
+    /// Continent, or "unknown" for country codes without metadata
+    #[schema(example = "europe")]
+    pub continent: String,
+    /// Guesses submitted
+    pub guesses: i64,
+    /// Average guess score
+    pub avg_score: f64,
+    /// Average guess distance in km
+    pub avg_distance_km: f64,
+    /// Countries in the continent, most guessed first
+    pub countries: Vec<CountryGuessStats>,
+}
+
+/// Guesses by continent response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ContinentAnalyticsResponse {
+    /// Continents, most guessed first
+    pub continents: Vec<ContinentGuessStats>,
+}
+
+// =============================================================================
+// Impersonation & Audit Log
+// =============================================================================
+
+/// Request to sign in as a user for support
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct StartImpersonationRequest {
+    /// Why the admin needs to sign in as the user (recorded in the audit log)
+    #[schema(example = "Ticket #1234: games missing from history")]
+    pub reason: String,
+    /// Only allow reads (default true)
+    #[serde(default = "default_read_only")]
+    pub read_only: bool,
+    /// Minutes until the session expires (5-60, default 15)
+    #[schema(example = 15)]
+    pub minutes: Option<i64>,
+}
+
+fn default_read_only() -> bool {
+    true
+}
+
+/// An impersonation session that was started
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ImpersonationResponse {
+    /// Impersonated user
+    pub user_id: String,
+    /// Whether only reads are allowed
+    pub read_only: bool,
+    /// When the session expires
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Query parameters for the audit log
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct AuditLogParams {
+    /// Only entries about this user
+    pub user_id: Option<String>,
+    /// Page number (1-based)
+    #[serde(default = "default_page")]
+    pub page: i64,
+    /// Items per page (max 100)
+    #[serde(default = "default_audit_per_page")]
+    pub per_page: i64,
+}
+
+fn default_audit_per_page() -> i64 {
+    50
+}
+
+/// An admin action
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AuditLogItem {
+    /// Entry ID
+    pub id: i64,
+    /// Admin who acted
+    pub actor_id: Option<String>,
+    /// What they did
+    #[schema(example = "impersonation.start")]
+    pub action: String,
+    /// User the action was about
+    pub target_user_id: Option<String>,
+    /// Action details
+    #[schema(value_type = Object)]
+    pub details: serde_json::Value,
+    /// When it happened
+    pub created_at: DateTime<Utc>,
+}
+
+/// Audit log page
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AuditLogResponse {
+    /// Entries, newest first
+    pub entries: Vec<AuditLogItem>,
+    /// Current page
+    pub page: i64,
+    /// Items per page
+    pub per_page: i64,
+}
+
+// =============================================================================
+// Runtime Config
+// =============================================================================
+
+/// Redis key holding the current [`StoredRuntimeSettings`]
+pub const RUNTIME_SETTINGS_KEY: &str = "config:runtime";
+
+/// Operational settings that can be changed without restarting the servers.
+/// Unset fields keep the value the server was started with.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct RuntimeSettings {
+    /// API rate limit multiplier for guests
+    pub rate_limit_guest_multiplier: Option<u32>,
+    /// API rate limit multiplier for registered users
+    pub rate_limit_registered_multiplier: Option<u32>,
+    /// API rate limit multiplier for admins
+    pub rate_limit_admin_multiplier: Option<u32>,
+    /// Origins allowed by CORS in addition to the frontend URL
+    #[schema(example = json!(["https://staging.dguesser.com"]))]
+    pub extra_cors_origins: Vec<String>,
+    /// Response cache lifetimes by cache namespace
+    pub cache_ttls: HashMap<String, CacheTtl>,
+    /// Seconds a party waits for a disconnected host before passing host on
+    pub party_host_grace_secs: Option<u64>,
+    /// Seconds a disconnected player has to reconnect, for new games that
+    /// don't come from a saved preset
+    pub reconnect_grace_secs: Option<u32>,
+    /// Multiplier applied to every socket event rate limit
+    pub socket_rate_limit_multiplier: Option<u32>,
+}
+
+/// Response cache lifetime
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct CacheTtl {
+    /// Entries younger than this are served without a refresh
+    pub fresh_secs: u64,
+    /// How much longer stale entries are served while being refreshed
+    pub stale_secs: u64,
+}
+
+/// Runtime settings as stored in Redis
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct StoredRuntimeSettings {
+    /// Incremented on every change; 0 means nothing was ever stored
+    pub version: u64,
+    pub settings: RuntimeSettings,
+    /// Admin who made the change
+    pub updated_by: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Request to reload the runtime config
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct ReloadConfigRequest {
+    /// New settings; omit to re-read the stored settings
+    pub settings: Option<RuntimeSettings>,
+}
+
+/// Runtime config after a reload
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReloadConfigResponse {
+    pub config: StoredRuntimeSettings,
+    /// Settings that changed on this instance
+    #[schema(example = json!(["extra_cors_origins"]))]
+    pub changed: Vec<String>,
+}
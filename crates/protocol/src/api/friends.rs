@@ -0,0 +1,89 @@
+//! Friend API DTOs
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::socket::presence::PresenceInfo;
+
+/// Send a friend request, identifying the other user by ID or username
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SendFriendRequest {
+    /// User ID (e.g., usr_V1StGXR8_Z5j)
+    #[schema(example = "usr_V1StGXR8_Z5j")]
+    pub user_id: Option<String>,
+    /// Username (e.g., coolplayer42)
+    #[schema(example = "coolplayer42")]
+    pub username: Option<String>,
+}
+
+/// State of a friendship after a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FriendshipStatus {
+    /// Waiting for the other user to accept
+    Pending,
+    /// The users are friends
+    Accepted,
+}
+
+/// Result of sending a friend request
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SendFriendRequestResponse {
+    /// User the request was sent to
+    pub user_id: String,
+    /// `accepted` when the other user had already sent a request
+    pub status: FriendshipStatus,
+}
+
+/// A friend
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FriendItem {
+    /// User ID
+    pub user_id: String,
+    /// Username
+    pub username: Option<String>,
+    /// Display name
+    pub display_name: String,
+    /// Avatar URL
+    pub avatar_url: Option<String>,
+    /// Whether the friend is connected to the realtime server (and shares
+    /// their presence)
+    pub online: bool,
+    /// What the friend is doing
+    #[serde(flatten)]
+    pub presence: PresenceInfo,
+    /// When the friendship started
+    pub since: DateTime<Utc>,
+}
+
+/// Friend list response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FriendsListResponse {
+    /// Friends, ordered by display name
+    pub friends: Vec<FriendItem>,
+}
+
+/// A pending friend request
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FriendRequestItem {
+    /// The other user's ID
+    pub user_id: String,
+    /// The other user's username
+    pub username: Option<String>,
+    /// The other user's display name
+    pub display_name: String,
+    /// The other user's avatar URL
+    pub avatar_url: Option<String>,
+    /// When the request was sent
+    pub sent_at: DateTime<Utc>,
+}
+
+/// Pending friend requests response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FriendRequestsResponse {
+    /// Requests other users sent to you, newest first
+    pub incoming: Vec<FriendRequestItem>,
+    /// Requests you sent, newest first
+    pub outgoing: Vec<FriendRequestItem>,
+}
@@ -99,6 +99,9 @@ pub struct PlayerGameStats {
     pub score: u32,
     /// Final rank (1 = first place)
     pub rank: Option<u8>,
+    /// Number of countries named correctly in a row (streak games only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub streak: Option<u32>,
 }
 
 /// Game result response
@@ -141,3 +144,87 @@ pub struct GuessResult {
     /// Score awarded
     pub score: u32,
 }
+
+/// Post-game breakdown of every round for every player
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GameSummaryResponse {
+    /// Game ID (e.g., gam_FybH2oF9Xaw8)
+    #[schema(example = "gam_FybH2oF9Xaw8")]
+    pub game_id: String,
+    /// Players, best first
+    pub players: Vec<PlayerGameStats>,
+    /// Rounds in order, with each player's guess
+    pub rounds: Vec<SummaryRound>,
+    /// Standout moments of the game
+    pub highlights: SummaryHighlights,
+}
+
+/// One round of a game summary
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SummaryRound {
+    /// Round number
+    pub round_number: u8,
+    /// Correct location latitude
+    pub location_lat: f64,
+    /// Correct location longitude
+    pub location_lng: f64,
+    /// Country of the correct location (ISO 3166-1 alpha-2)
+    #[schema(example = "FR")]
+    pub country_code: Option<String>,
+    /// Guesses, best first. Players who did not guess are left out.
+    pub guesses: Vec<SummaryGuess>,
+}
+
+/// One player's guess in a game summary
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SummaryGuess {
+    /// User ID (e.g., usr_V1StGXR8_Z5j)
+    #[schema(example = "usr_V1StGXR8_Z5j")]
+    pub user_id: String,
+    /// Guessed latitude
+    pub guess_lat: f64,
+    /// Guessed longitude
+    pub guess_lng: f64,
+    /// Distance from correct location in meters
+    pub distance_meters: f64,
+    /// Score awarded
+    pub score: u32,
+    /// How long the player took to guess, in milliseconds
+    pub time_taken_ms: Option<u32>,
+}
+
+/// Standout moments of a game. Each is absent when nobody guessed.
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct SummaryHighlights {
+    /// The highest-scoring guess (the closest one on a tie)
+    pub best_guess: Option<GuessHighlight>,
+    /// The round players were closest in on average
+    pub closest_round: Option<RoundHighlight>,
+    /// The guess furthest from its location
+    pub biggest_blunder: Option<GuessHighlight>,
+}
+
+/// A guess singled out in a game summary
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GuessHighlight {
+    /// Round the guess was made in
+    pub round_number: u8,
+    /// User ID (e.g., usr_V1StGXR8_Z5j)
+    #[schema(example = "usr_V1StGXR8_Z5j")]
+    pub user_id: String,
+    /// Display name
+    pub display_name: String,
+    /// Distance from correct location in meters
+    pub distance_meters: f64,
+    /// Score awarded
+    pub score: u32,
+}
+
+/// A round singled out in a game summary
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RoundHighlight {
+    /// Round number
+    pub round_number: u8,
+    /// Mean distance of the round's guesses in meters
+    pub average_distance_meters: f64,
+}
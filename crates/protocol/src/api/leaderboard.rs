@@ -16,6 +16,8 @@ pub enum LeaderboardType {
     GamesPlayed,
     /// Average score per game (minimum 3 games)
     AverageScore,
+    /// Longest country streak in a streak game
+    Streak,
 }
 
 impl std::fmt::Display for LeaderboardType {
@@ -25,6 +27,7 @@ impl std::fmt::Display for LeaderboardType {
             LeaderboardType::BestGame => write!(f, "best_game"),
             LeaderboardType::GamesPlayed => write!(f, "games_played"),
             LeaderboardType::AverageScore => write!(f, "average_score"),
+            LeaderboardType::Streak => write!(f, "streak"),
         }
     }
 }
@@ -36,9 +39,9 @@ pub enum TimePeriod {
     /// All time rankings
     #[default]
     AllTime,
-    /// Last 24 hours
+    /// Today (UTC)
     Daily,
-    /// Last 7 days
+    /// This week, from Monday (UTC)
     Weekly,
     /// Last 30 days
     Monthly,
@@ -55,6 +58,15 @@ impl std::fmt::Display for TimePeriod {
     }
 }
 
+/// Game mode a leaderboard is limited to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderboardMode {
+    Solo,
+    Multiplayer,
+    Challenge,
+}
+
 /// Leaderboard query parameters
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct LeaderboardQuery {
@@ -66,6 +78,13 @@ pub struct LeaderboardQuery {
     #[serde(default)]
     #[schema(example = "all_time")]
     pub period: TimePeriod,
+    /// Only count games on this map (default: all maps)
+    #[serde(default)]
+    #[schema(example = "world")]
+    pub map_id: Option<String>,
+    /// Only count games of this mode (default: all modes; ignored for streaks)
+    #[serde(default)]
+    pub mode: Option<LeaderboardMode>,
     /// Maximum entries to return (default: 50, max: 100)
     #[serde(default = "default_limit")]
     #[schema(example = 50)]
@@ -85,6 +104,8 @@ impl Default for LeaderboardQuery {
         Self {
             r#type: LeaderboardType::default(),
             period: TimePeriod::default(),
+            map_id: None,
+            mode: None,
             limit: default_limit(),
             offset: 0,
         }
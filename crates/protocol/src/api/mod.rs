@@ -2,8 +2,13 @@
 
 pub mod admin;
 pub mod auth;
+pub mod friends;
 pub mod game;
 pub mod leaderboard;
+pub mod notifications;
+pub mod orgs;
+pub mod reports;
 pub mod service;
 pub mod sessions;
+pub mod streetview;
 pub mod user;
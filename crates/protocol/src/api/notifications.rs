@@ -0,0 +1,54 @@
+//! Push notification API DTOs
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// VAPID public key browsers subscribe with
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PushPublicKeyResponse {
+    /// Pass as `applicationServerKey` to `PushManager.subscribe()` (base64url)
+    pub public_key: String,
+}
+
+/// Keys of a browser push subscription
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PushSubscriptionKeys {
+    /// Browser public key (base64url)
+    pub p256dh: String,
+    /// Auth secret (base64url)
+    pub auth: String,
+}
+
+/// Register a push subscription; the shape of `PushSubscription.toJSON()`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RegisterPushSubscriptionRequest {
+    /// Push service URL
+    pub endpoint: String,
+    pub keys: PushSubscriptionKeys,
+}
+
+/// Remove a push subscription
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RemovePushSubscriptionRequest {
+    /// Push service URL of the subscription
+    pub endpoint: String,
+}
+
+/// Which push notifications a user receives
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NotificationPreferencesResponse {
+    /// It is your turn in a challenge
+    pub challenge_turns: bool,
+    /// A friend invited you to a lobby
+    pub friend_invites: bool,
+    /// A new daily challenge is available
+    pub daily_challenge: bool,
+}
+
+/// Update notification preferences; omitted fields are unchanged
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct UpdateNotificationPreferencesRequest {
+    pub challenge_turns: Option<bool>,
+    pub friend_invites: Option<bool>,
+    pub daily_challenge: Option<bool>,
+}
@@ -0,0 +1,114 @@
+//! Organization API DTOs
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// How an organization's instance looks
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct OrgBranding {
+    /// Logo shown in place of the DGuesser logo
+    #[schema(example = "https://cdn.example.edu/logo.svg")]
+    pub logo_url: Option<String>,
+    /// Main color as `#rrggbb`
+    #[schema(example = "#1d4ed8")]
+    pub primary_color: Option<String>,
+    /// Secondary color as `#rrggbb`
+    pub accent_color: Option<String>,
+}
+
+/// An organization
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OrganizationInfo {
+    /// Organization ID
+    #[schema(example = "org_V1StGXR8_Z5j")]
+    pub id: String,
+    /// Subdomain and `X-Organization` header value
+    #[schema(example = "geo-101")]
+    pub slug: String,
+    /// Display name
+    pub name: String,
+    /// Custom domain the organization is served from
+    pub domain: Option<String>,
+    pub branding: OrgBranding,
+    /// Whether the organization can be used
+    pub active: bool,
+    /// When the organization was created
+    pub created_at: DateTime<Utc>,
+}
+
+/// Request to change an organization's name or branding. Omitted fields are
+/// left unchanged.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateOrganizationRequest {
+    /// New display name (3-100 characters)
+    pub name: Option<String>,
+    /// New branding, replacing the current one
+    pub branding: Option<OrgBranding>,
+}
+
+/// Role of a member within an organization
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OrgMemberRole {
+    #[default]
+    Member,
+    /// Manages members and branding
+    Admin,
+}
+
+/// A member of an organization
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OrgMemberItem {
+    pub user_id: String,
+    pub display_name: String,
+    pub avatar_url: Option<String>,
+    pub role: OrgMemberRole,
+    pub joined_at: DateTime<Utc>,
+}
+
+/// Members of an organization
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OrgMembersResponse {
+    /// Admins first, then by join date
+    pub members: Vec<OrgMemberItem>,
+}
+
+/// Request to add a member, or change a member's role
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AddOrgMemberRequest {
+    /// User to add
+    #[schema(example = "usr_V1StGXR8_Z5j")]
+    pub user_id: String,
+    #[serde(default)]
+    pub role: OrgMemberRole,
+}
+
+/// Request to create an organization
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateOrganizationRequest {
+    /// Subdomain (lowercase letters, digits, and hyphens)
+    #[schema(example = "geo-101")]
+    pub slug: String,
+    /// Display name (3-100 characters)
+    #[schema(example = "Geography 101")]
+    pub name: String,
+    /// Custom domain to serve the organization from
+    pub domain: Option<String>,
+    /// User to make the organization's first admin
+    pub admin_user_id: Option<String>,
+}
+
+/// All organizations
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OrganizationsListResponse {
+    /// Inactive organizations included
+    pub organizations: Vec<OrganizationInfo>,
+}
+
+/// Request to activate or deactivate an organization
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SetOrganizationActiveRequest {
+    /// Whether the organization can be used
+    pub active: bool,
+}
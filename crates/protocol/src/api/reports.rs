@@ -0,0 +1,36 @@
+//! Player report DTOs
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Report a player
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreatePlayerReportRequest {
+    /// Player being reported
+    #[schema(example = "usr_V1StGXR8_Z5j")]
+    pub user_id: String,
+    /// "cheating", "abusive_name", "abusive_chat", "griefing" or "other"
+    #[schema(example = "cheating")]
+    pub category: String,
+    /// Game it happened in (required for cheating and griefing)
+    #[schema(example = "gam_V1StGXR8_Z5j")]
+    pub game_id: Option<String>,
+    /// The offending chat messages (required for abusive chat, max 500 characters)
+    pub chat_excerpt: Option<String>,
+    /// What happened (required for "other", max 1000 characters)
+    pub notes: Option<String>,
+}
+
+/// A submitted report
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PlayerReportResponse {
+    /// Report ID
+    #[schema(example = "prp_V1StGXR8_Z5j")]
+    pub id: String,
+    /// "open" until a moderator picks it up
+    #[schema(example = "open")]
+    pub status: String,
+    /// When the report was submitted
+    pub created_at: DateTime<Utc>,
+}
@@ -3,6 +3,8 @@
 use serde::Serialize;
 use utoipa::ToSchema;
 
+use crate::version::ProtocolInfo;
+
 /// Service information response for root endpoint
 #[derive(Debug, Serialize, ToSchema)]
 pub struct ServiceInfo {
@@ -37,4 +39,7 @@ pub struct ServiceInfo {
     /// Seconds since service started
     #[schema(example = 3542)]
     pub uptime_seconds: u64,
+
+    /// Client protocol spoken by this build
+    pub protocol: ProtocolInfo,
 }
@@ -0,0 +1,55 @@
+//! Street View DTOs
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Metadata of a Street View panorama, looked up by the server
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct StreetViewMetadataResponse {
+    /// Panorama ID that was looked up
+    #[schema(example = "CAoSLEFGMVFpcE1fTmJ4")]
+    pub panorama_id: String,
+    /// "ok" if the panorama exists, "not_found" if it is gone
+    #[schema(example = "ok")]
+    pub status: String,
+    /// Panorama latitude, when it exists
+    pub lat: Option<f64>,
+    /// Panorama longitude, when it exists
+    pub lng: Option<f64>,
+    /// Capture month (first day of the month), when reported
+    pub capture_date: Option<NaiveDate>,
+}
+
+/// Request a map tile session
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateTileSessionRequest {
+    /// "streetview", "roadmap" or "satellite"
+    #[schema(example = "streetview")]
+    pub map_type: String,
+}
+
+/// A signed tile token for fetching tiles through the API
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TileSessionResponse {
+    /// Token to pass as `token` when fetching tiles
+    pub token: String,
+    /// When the token stops being accepted; request a new one before then
+    pub expires_at: DateTime<Utc>,
+    /// Map type the token serves
+    #[schema(example = "streetview")]
+    pub map_type: String,
+    /// Tile width in pixels
+    #[schema(example = 512)]
+    pub tile_width: u32,
+    /// Tile height in pixels
+    #[schema(example = 512)]
+    pub tile_height: u32,
+    /// "jpeg" or "png"
+    #[schema(example = "jpeg")]
+    pub image_format: String,
+    /// Tile path on the API host, with `{z}`, `{x}` and `{y}` placeholders
+    /// (and `{pano}` for Street View)
+    #[schema(example = "/api/v1/streetview/tiles/{z}/{x}/{y}?token=mt1...&pano={pano}")]
+    pub tile_url: String,
+}
@@ -0,0 +1,291 @@
+//! Protocol TypeScript generator - render the protocol DTOs for the frontend.
+//!
+//! Every REST DTO and socket payload registered in
+//! [`dguesser_protocol::schemas`] is written out as a TypeScript declaration,
+//! so the frontend imports them instead of keeping hand-written copies.
+//!
+//! Usage:
+//! ```bash
+//! # Regenerate frontend/src/lib/generated/protocol.ts
+//! cargo run -p dguesser-protocol --bin protocol-ts
+//!
+//! # Fail if the committed file no longer matches the DTOs (used in CI)
+//! cargo run -p dguesser-protocol --bin protocol-ts -- --check
+//!
+//! # Write to another path
+//! cargo run -p dguesser-protocol --bin protocol-ts -- --out ./protocol.ts
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use dguesser_protocol::schemas::{ApiSchemas, SocketSchemas};
+use serde_json::Value;
+use utoipa::OpenApi;
+
+/// Output path relative to the workspace root
+const DEFAULT_OUTPUT: &str = "frontend/src/lib/generated/protocol.ts";
+
+const HEADER: &str = "\
+// Generated by `cargo run -p dguesser-protocol --bin protocol-ts` from the DTOs
+// in crates/protocol. Do not edit by hand.
+";
+
+fn main() -> ExitCode {
+    let mut check = false;
+    let workspace =
+        Path::new(env!("CARGO_MANIFEST_DIR")).ancestors().nth(2).unwrap_or(Path::new("."));
+    let mut output = workspace.join(DEFAULT_OUTPUT);
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--check" => check = true,
+            "--out" => match args.next() {
+                Some(path) => output = PathBuf::from(path),
+                None => return usage("--out needs a path"),
+            },
+            other => return usage(&format!("unknown argument `{other}`")),
+        }
+    }
+
+    let schemas = match collect_schemas() {
+        Ok(schemas) => schemas,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let rendered = render(&schemas);
+
+    if check {
+        let current = std::fs::read_to_string(&output).unwrap_or_default();
+        if current != rendered {
+            eprintln!(
+                "{} is out of date, run `cargo run -p dguesser-protocol --bin protocol-ts`",
+                output.display()
+            );
+            return ExitCode::FAILURE;
+        }
+        println!("{} is up to date ({} types)", output.display(), schemas.len());
+        return ExitCode::SUCCESS;
+    }
+
+    if let Some(parent) = output.parent()
+        && let Err(e) = std::fs::create_dir_all(parent)
+    {
+        eprintln!("error: failed to create {}: {e}", parent.display());
+        return ExitCode::FAILURE;
+    }
+    if let Err(e) = std::fs::write(&output, rendered) {
+        eprintln!("error: failed to write {}: {e}", output.display());
+        return ExitCode::FAILURE;
+    }
+    println!("Wrote {} types to {}", schemas.len(), output.display());
+    ExitCode::SUCCESS
+}
+
+fn usage(problem: &str) -> ExitCode {
+    eprintln!("error: {problem}");
+    eprintln!("usage: protocol-ts [--check] [--out <path>]");
+    ExitCode::FAILURE
+}
+
+/// Merge the component schemas of all registries, keyed by type name.
+fn collect_schemas() -> Result<BTreeMap<String, Value>, String> {
+    let mut schemas = BTreeMap::new();
+
+    for doc in [ApiSchemas::openapi(), SocketSchemas::openapi()] {
+        let doc = serde_json::to_value(doc).map_err(|e| e.to_string())?;
+        let Some(components) = doc.pointer("/components/schemas").and_then(Value::as_object) else {
+            continue;
+        };
+        for (name, schema) in components {
+            match schemas.get(name) {
+                Some(existing) if existing != schema => {
+                    return Err(format!("two different types are named `{name}`"));
+                }
+                Some(_) => {}
+                None => {
+                    schemas.insert(name.clone(), schema.clone());
+                }
+            }
+        }
+    }
+
+    Ok(schemas)
+}
+
+/// Render all schemas as one TypeScript module.
+fn render(schemas: &BTreeMap<String, Value>) -> String {
+    let mut out = String::from(HEADER);
+
+    for (name, schema) in schemas {
+        out.push('\n');
+        push_doc(&mut out, schema, 0);
+        if is_interface(schema) {
+            out.push_str(&format!("export interface {name} {}\n", object_body(schema, 0)));
+        } else {
+            out.push_str(&format!("export type {name} = {};\n", ts_type(schema, 0)));
+        }
+    }
+
+    out
+}
+
+/// Plain objects become interfaces, everything else a type alias.
+fn is_interface(schema: &Value) -> bool {
+    schema.get("type").and_then(Value::as_str) == Some("object")
+        && schema.get("properties").and_then(Value::as_object).is_some_and(|p| !p.is_empty())
+}
+
+/// TypeScript type of a schema, with nested objects indented by `indent`.
+fn ts_type(schema: &Value, indent: usize) -> String {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        return reference.rsplit('/').next().unwrap_or(reference).to_string();
+    }
+    for key in ["oneOf", "anyOf"] {
+        if let Some(items) = schema.get(key).and_then(Value::as_array) {
+            return union(items.iter().map(|item| ts_type(item, indent)));
+        }
+    }
+    if let Some(items) = schema.get("allOf").and_then(Value::as_array) {
+        let parts: Vec<String> =
+            items.iter().map(|item| parenthesize(ts_type(item, indent))).collect();
+        return parts.join(" & ");
+    }
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        return union(values.iter().map(literal));
+    }
+
+    let types: Vec<&str> = match schema.get("type") {
+        Some(Value::String(ty)) => vec![ty.as_str()],
+        Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).collect(),
+        _ => return "unknown".to_string(),
+    };
+    union(types.into_iter().map(|ty| match ty {
+        "string" => "string".to_string(),
+        "integer" | "number" => "number".to_string(),
+        "boolean" => "boolean".to_string(),
+        "null" => "null".to_string(),
+        "array" => array_type(schema, indent),
+        _ => object_type(schema, indent),
+    }))
+}
+
+fn array_type(schema: &Value, indent: usize) -> String {
+    match schema.get("items") {
+        Some(items @ Value::Object(_)) => format!("{}[]", parenthesize(ts_type(items, indent))),
+        _ => "unknown[]".to_string(),
+    }
+}
+
+fn object_type(schema: &Value, indent: usize) -> String {
+    if schema.get("properties").and_then(Value::as_object).is_some_and(|p| !p.is_empty()) {
+        return object_body(schema, indent);
+    }
+    match schema.get("additionalProperties") {
+        Some(values @ Value::Object(_)) => format!("Record<string, {}>", ts_type(values, indent)),
+        _ => "Record<string, unknown>".to_string(),
+    }
+}
+
+/// `{ ... }` with one line per property; fields missing from `required` are
+/// optional.
+fn object_body(schema: &Value, indent: usize) -> String {
+    let required: BTreeSet<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|names| names.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    let empty = serde_json::Map::new();
+    let properties = schema.get("properties").and_then(Value::as_object).unwrap_or(&empty);
+
+    let pad = "  ".repeat(indent);
+    let mut out = String::from("{\n");
+    for (name, property) in properties {
+        push_doc(&mut out, property, indent + 1);
+        let optional = if required.contains(name.as_str()) { "" } else { "?" };
+        out.push_str(&format!(
+            "{pad}  {}{optional}: {};\n",
+            property_key(name),
+            ts_type(property, indent + 1)
+        ));
+    }
+    out.push_str(&pad);
+    out.push('}');
+    out
+}
+
+/// Write a schema's description as a JSDoc comment.
+fn push_doc(out: &mut String, schema: &Value, indent: usize) {
+    let Some(description) = schema.get("description").and_then(Value::as_str) else {
+        return;
+    };
+    let description = description.trim().replace("*/", "*\\/");
+    if description.is_empty() {
+        return;
+    }
+
+    let pad = "  ".repeat(indent);
+    if !description.contains('\n') {
+        out.push_str(&format!("{pad}/** {description} */\n"));
+        return;
+    }
+    out.push_str(&format!("{pad}/**\n"));
+    for line in description.lines() {
+        if line.trim().is_empty() {
+            out.push_str(&format!("{pad} *\n"));
+        } else {
+            out.push_str(&format!("{pad} * {}\n", line.trim_end()));
+        }
+    }
+    out.push_str(&format!("{pad} */\n"));
+}
+
+/// Join types with `|`, dropping duplicates.
+fn union(types: impl Iterator<Item = String>) -> String {
+    let mut seen = Vec::new();
+    for ty in types {
+        if !seen.contains(&ty) {
+            seen.push(ty);
+        }
+    }
+    match seen.len() {
+        0 => "never".to_string(),
+        _ => seen.join(" | "),
+    }
+}
+
+/// Wrap a union or intersection so it can be combined with other types.
+fn parenthesize(ty: String) -> String {
+    let mut depth = 0i32;
+    let mut compound = false;
+    for (i, c) in ty.char_indices() {
+        match c {
+            '{' | '(' | '<' | '[' => depth += 1,
+            '}' | ')' | '>' | ']' => depth -= 1,
+            ' ' if depth == 0 => {
+                compound |= ty[i..].starts_with(" | ") || ty[i..].starts_with(" & ");
+            }
+            _ => {}
+        }
+    }
+    if compound { format!("({ty})") } else { ty }
+}
+
+fn literal(value: &Value) -> String {
+    match value {
+        Value::String(_) | Value::Number(_) | Value::Bool(_) | Value::Null => value.to_string(),
+        _ => "unknown".to_string(),
+    }
+}
+
+fn property_key(name: &str) -> String {
+    let mut chars = name.chars();
+    let is_identifier =
+        chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if is_identifier { name.to_string() } else { Value::from(name).to_string() }
+}
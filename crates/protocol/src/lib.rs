@@ -3,4 +3,6 @@
 //! This crate contains shared DTOs for REST API and Socket.IO events.
 
 pub mod api;
+pub mod schemas;
 pub mod socket;
+pub mod version;
@@ -0,0 +1,252 @@
+//! Schema registries for code generation
+//!
+//! Every DTO of this crate derives `ToSchema`. These registries collect them
+//! into OpenAPI components so tools can work from one description of the
+//! protocol: the `protocol-ts` binary renders them as TypeScript for the
+//! frontend.
+
+use utoipa::OpenApi;
+
+use crate::api::{
+    admin, auth, friends, game, leaderboard, notifications, orgs, reports, service, sessions,
+    streetview, user,
+};
+use crate::socket::{payloads, presence};
+use crate::version;
+
+/// REST API request and response bodies
+#[derive(OpenApi)]
+#[openapi(components(schemas(
+    admin::AdminStatsResponse,
+    admin::LocationHealthParams,
+    admin::HealthCheckRunItem,
+    admin::HealthTrendPoint,
+    admin::LocationHealthResponse,
+    admin::PackCacheStatsResponse,
+    admin::StreetViewQuotaResponse,
+    admin::MapTileUsageEntry,
+    admin::MapTileUsageResponse,
+    admin::ReviewQueueParams,
+    admin::ReviewQueueItem,
+    admin::ReviewQueueResponse,
+    admin::LocationDetailResponse,
+    admin::LocationNoteItem,
+    admin::CreateLocationNoteRequest,
+    admin::LocationNotesParams,
+    admin::LocationNotesResponse,
+    admin::LocationReportItem,
+    admin::ReportsListResponse,
+    admin::LocationReportWithLocation,
+    admin::ReportsListParams,
+    admin::UpdateReviewStatusRequest,
+    admin::UpdateReviewStatusResponse,
+    admin::ReapprovalQueueParams,
+    admin::ReapprovalItem,
+    admin::ReapprovalQueueResponse,
+    admin::DecideReapprovalRequest,
+    admin::ClaimReviewBatchRequest,
+    admin::ClaimReviewBatchResponse,
+    admin::ReleaseReviewClaimsResponse,
+    admin::ReviewerStatsParams,
+    admin::ReviewerStatsItem,
+    admin::ReviewerStatsResponse,
+    admin::ModerateProfileRequest,
+    admin::ModerateProfileResponse,
+    admin::ForceRenameRequest,
+    admin::ForceRenameResponse,
+    admin::NameFlagsParams,
+    admin::NameFlagItem,
+    admin::NameFlagsResponse,
+    admin::PlayerReportsParams,
+    admin::ReportedPlayerSignals,
+    admin::PlayerReportItem,
+    admin::PlayerReportsResponse,
+    admin::UpdatePlayerReportRequest,
+    admin::UpdatePlayerReportResponse,
+    admin::ChatTranscriptMessage,
+    admin::GameChatTranscriptResponse,
+    admin::LiveGameItem,
+    admin::LiveGamesResponse,
+    admin::LivePlayerState,
+    admin::LiveRoundState,
+    admin::LiveGameStateResponse,
+    admin::ForceEndGameRequest,
+    admin::ForceEndGameResponse,
+    admin::SystemMapItem,
+    admin::SystemMapsListResponse,
+    admin::CreateSystemMapRequest,
+    admin::UpdateSystemMapRequest,
+    admin::SetMapActiveRequest,
+    admin::ScheduleFeaturedMapRequest,
+    admin::ScheduledFeatureItem,
+    admin::FeaturedMapScheduleResponse,
+    admin::AnalyticsParams,
+    admin::RetentionParams,
+    admin::ModeGameStats,
+    admin::DailyActivityPoint,
+    admin::ActivityAnalyticsResponse,
+    admin::Percentiles,
+    admin::GuessDistributionPoint,
+    admin::GuessAnalyticsResponse,
+    admin::RetentionCohort,
+    admin::RetentionAnalyticsResponse,
+    admin::CountryGuessStats,
+    admin::ContinentGuessStats,
+    admin::ContinentAnalyticsResponse,
+    admin::StartImpersonationRequest,
+    admin::ImpersonationResponse,
+    admin::AuditLogParams,
+    admin::AuditLogItem,
+    admin::AuditLogResponse,
+    admin::RuntimeSettings,
+    admin::CacheTtl,
+    admin::StoredRuntimeSettings,
+    admin::ReloadConfigRequest,
+    admin::ReloadConfigResponse,
+    auth::OAuthUrlResponse,
+    auth::MeResponse,
+    auth::GuestSessionResponse,
+    auth::OAuthCallbackParams,
+    auth::LogoutResponse,
+    friends::SendFriendRequest,
+    friends::FriendshipStatus,
+    friends::SendFriendRequestResponse,
+    friends::FriendItem,
+    friends::FriendsListResponse,
+    friends::FriendRequestItem,
+    friends::FriendRequestsResponse,
+    game::CreateGameRequest,
+    game::GameInfo,
+    game::GameSettingsResponse,
+    game::JoinGameRequest,
+    game::GameListResponse,
+    game::PlayerGameStats,
+    game::GameResultResponse,
+    game::RoundResultResponse,
+    game::GuessResult,
+    game::GameSummaryResponse,
+    game::SummaryRound,
+    game::SummaryGuess,
+    game::SummaryHighlights,
+    game::GuessHighlight,
+    game::RoundHighlight,
+    leaderboard::LeaderboardType,
+    leaderboard::TimePeriod,
+    leaderboard::LeaderboardMode,
+    leaderboard::LeaderboardQuery,
+    leaderboard::LeaderboardEntry,
+    leaderboard::LeaderboardResponse,
+    notifications::PushPublicKeyResponse,
+    notifications::PushSubscriptionKeys,
+    notifications::RegisterPushSubscriptionRequest,
+    notifications::RemovePushSubscriptionRequest,
+    notifications::NotificationPreferencesResponse,
+    notifications::UpdateNotificationPreferencesRequest,
+    orgs::OrgBranding,
+    orgs::OrganizationInfo,
+    orgs::UpdateOrganizationRequest,
+    orgs::OrgMemberRole,
+    orgs::OrgMemberItem,
+    orgs::OrgMembersResponse,
+    orgs::AddOrgMemberRequest,
+    orgs::CreateOrganizationRequest,
+    orgs::OrganizationsListResponse,
+    orgs::SetOrganizationActiveRequest,
+    reports::CreatePlayerReportRequest,
+    reports::PlayerReportResponse,
+    service::ServiceInfo,
+    sessions::SessionInfo,
+    sessions::SessionsListResponse,
+    sessions::RevokeSessionResponse,
+    streetview::StreetViewMetadataResponse,
+    streetview::CreateTileSessionRequest,
+    streetview::TileSessionResponse,
+    user::UserProfile,
+    user::CurrentUser,
+    user::UpdateProfileRequest,
+    version::ProtocolInfo,
+)))]
+pub struct ApiSchemas;
+
+/// Socket.IO event payloads
+#[derive(OpenApi)]
+#[openapi(components(schemas(
+    payloads::GameSettingsPayload,
+    payloads::MapRoundsPayload,
+    payloads::ReactPayload,
+    payloads::ReactionPayload,
+    payloads::TimeSyncRequest,
+    payloads::TimeSyncPayload,
+    payloads::TimePongPayload,
+    payloads::JoinGamePayload,
+    payloads::SubmitGuessPayload,
+    payloads::RoundStartPayload,
+    payloads::RoundRerolledPayload,
+    payloads::ClassroomGuessPayload,
+    payloads::HintRevealedPayload,
+    payloads::QuizChoice,
+    payloads::QuizBounds,
+    payloads::QuizQuestionPayload,
+    payloads::QuizScore,
+    payloads::QuizAnsweredPayload,
+    payloads::RoundLocation,
+    payloads::PlayerGuessedPayload,
+    payloads::RoundEndPayload,
+    payloads::GlobalGuessStats,
+    payloads::RoundResult,
+    payloads::GameEndPayload,
+    payloads::FinalStanding,
+    payloads::ErrorPayload,
+    payloads::PlayerInfo,
+    payloads::GameStatePayload,
+    payloads::PlayerJoinedPayload,
+    payloads::PlayerLeftPayload,
+    payloads::PlayerDisconnectedPayload,
+    payloads::PlayerReconnectedPayload,
+    payloads::PlayerTimeoutPayload,
+    payloads::GamePausedPayload,
+    payloads::GameResumedPayload,
+    payloads::ScoresUpdatePayload,
+    payloads::PlayerScoreInfo,
+    payloads::SettingsUpdatedPayload,
+    payloads::JoinCodeRotatedPayload,
+    payloads::HandicapUpdatedPayload,
+    payloads::SkipVoteUpdatePayload,
+    payloads::GameAbandonedPayload,
+    payloads::CreatePartyPayload,
+    payloads::JoinPartyPayload,
+    payloads::PartyStartGamePayload,
+    payloads::PartyUpdateSettingsPayload,
+    payloads::PartyKickPayload,
+    payloads::PartyMutePayload,
+    payloads::PartyDisbandPayload,
+    payloads::PartyCreatedPayload,
+    payloads::PartyMemberInfo,
+    payloads::PartyStatePayload,
+    payloads::PartyMemberJoinedPayload,
+    payloads::PartyMemberLeftPayload,
+    payloads::PartyGameStartingPayload,
+    payloads::PartyGameEndedPayload,
+    payloads::PartyDisbandedPayload,
+    payloads::PartyHostChangedPayload,
+    payloads::PartySettingsUpdatedPayload,
+    payloads::PartyKickedPayload,
+    payloads::PartyMemberMutedPayload,
+    payloads::PartyChatPayload,
+    payloads::PartyChatMessagePayload,
+    payloads::PartyChatHistoryPayload,
+    payloads::PartyErrorPayload,
+    payloads::FriendInvitePayload,
+    payloads::LobbyKind,
+    payloads::FriendInvitedPayload,
+    payloads::FriendInviteSentPayload,
+    payloads::FriendPresencePayload,
+    payloads::FriendRequestPayload,
+    payloads::TransitionPhase,
+    payloads::GameTransitioningPayload,
+    payloads::GameTransitionClearedPayload,
+    presence::PresenceStatus,
+    presence::PresenceInfo,
+    presence::PresenceVisibility,
+)))]
+pub struct SocketSchemas;
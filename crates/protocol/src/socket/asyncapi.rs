@@ -0,0 +1,339 @@
+//! AsyncAPI description of the socket protocol
+//!
+//! [`events`] lists every Socket.IO event with its direction and payload,
+//! and [`document`] renders the list as an AsyncAPI 3.0 document whose
+//! schemas come from the same `ToSchema` derives as the OpenAPI docs. The
+//! API serves it at `/asyncapi.json` next to the Scalar docs.
+
+use serde_json::{Map, Value, json};
+use utoipa::{OpenApi, ToSchema};
+
+use super::events::{client, friend, party, server};
+use super::payloads as p;
+use crate::schemas::SocketSchemas;
+use crate::version::PROTOCOL_VERSION;
+
+const DESCRIPTION: &str = "Socket.IO events of the DGuesser realtime server. \
+Each message is one event: its name is the Socket.IO event name and its payload \
+the event's single argument. Operations are described from the server's side: \
+`receive` operations are emitted by clients, `send` operations by the server. \
+Clients authenticate with an `auth` event carrying their `protocol_version` \
+before sending anything else.";
+
+/// Who emits an event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Client to server
+    FromClient,
+    /// Server to client
+    FromServer,
+}
+
+/// Payload of an event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Payload {
+    /// A protocol DTO, by schema name
+    Schema(String),
+    /// An object holding only this ID field, e.g. `{ "game_id": "gam_..." }`
+    Id(&'static str),
+    /// Not described by a protocol DTO
+    Untyped,
+}
+
+/// A documented socket event
+#[derive(Debug, Clone)]
+pub struct SocketEvent {
+    /// Socket.IO event name
+    pub name: &'static str,
+    pub direction: Direction,
+    /// One-line description
+    pub summary: &'static str,
+    pub payload: Payload,
+}
+
+fn from_client<T: ToSchema>(name: &'static str, summary: &'static str) -> SocketEvent {
+    let payload = Payload::Schema(T::name().into_owned());
+    SocketEvent { name, direction: Direction::FromClient, summary, payload }
+}
+
+fn from_server<T: ToSchema>(name: &'static str, summary: &'static str) -> SocketEvent {
+    let payload = Payload::Schema(T::name().into_owned());
+    SocketEvent { name, direction: Direction::FromServer, summary, payload }
+}
+
+fn client_event(name: &'static str, summary: &'static str, payload: Payload) -> SocketEvent {
+    SocketEvent { name, direction: Direction::FromClient, summary, payload }
+}
+
+/// Every socket event of the protocol
+pub fn events() -> Vec<SocketEvent> {
+    vec![
+        // Games (client -> server)
+        from_client::<p::JoinGamePayload>(client::JOIN_GAME, "Join a game lobby or running game"),
+        client_event(client::LEAVE_GAME, "Leave a game", Payload::Id("game_id")),
+        client_event(client::START_GAME, "Start the game (host only)", Payload::Id("game_id")),
+        client_event(
+            client::UPDATE_SETTINGS,
+            "Change the lobby settings (host only)",
+            Payload::Untyped,
+        ),
+        client_event(
+            client::SET_HANDICAP,
+            "Change a player's score multiplier (host only)",
+            Payload::Untyped,
+        ),
+        from_client::<p::SubmitGuessPayload>(client::SUBMIT_GUESS, "Submit a guess"),
+        client_event(client::READY, "Mark yourself ready in the lobby", Payload::Id("game_id")),
+        client_event(
+            client::SKIP_WAIT,
+            "Skip the between-rounds wait (host only)",
+            Payload::Id("game_id"),
+        ),
+        client_event(
+            client::VOTE_SKIP,
+            "Vote to skip the between-rounds wait",
+            Payload::Id("game_id"),
+        ),
+        from_client::<p::ReactPayload>(client::REACT, "Send an emoji reaction"),
+        client_event(
+            client::REROLL,
+            "Replace the current round's location (host only)",
+            Payload::Id("game_id"),
+        ),
+        client_event(
+            client::PAUSE,
+            "Pause a classroom game (teacher only)",
+            Payload::Id("game_id"),
+        ),
+        client_event(
+            client::RESUME,
+            "Resume a classroom game (teacher only)",
+            Payload::Id("game_id"),
+        ),
+        client_event(client::REQUEST_HINT, "Ask for a hint on the current round", Payload::Untyped),
+        from_client::<p::TimeSyncRequest>(client::TIME_SYNC, "Ask for the server time"),
+        from_client::<p::TimePongPayload>(client::TIME_PONG, "Echo a `time:sync` reply"),
+        // Games (server -> client)
+        from_server::<p::GameStatePayload>(server::GAME_STATE, "Full game state, sent on join"),
+        from_server::<p::RoundStartPayload>(server::ROUND_START, "A round started"),
+        from_server::<p::RoundEndPayload>(server::ROUND_END, "A round ended, with results"),
+        from_server::<p::PlayerJoinedPayload>(server::PLAYER_JOINED, "A player joined"),
+        from_server::<p::PlayerLeftPayload>(server::PLAYER_LEFT, "A player left"),
+        from_server::<p::PlayerGuessedPayload>(server::PLAYER_GUESSED, "A player guessed"),
+        from_server::<p::GameEndPayload>(server::GAME_END, "The game ended, with final standings"),
+        from_server::<p::ErrorPayload>(server::ERROR, "A request failed"),
+        from_server::<p::PlayerDisconnectedPayload>(
+            server::PLAYER_DISCONNECTED,
+            "A player disconnected; their grace period started",
+        ),
+        from_server::<p::PlayerReconnectedPayload>(
+            server::PLAYER_RECONNECTED,
+            "A player reconnected within their grace period",
+        ),
+        from_server::<p::PlayerTimeoutPayload>(
+            server::PLAYER_TIMEOUT,
+            "A player's grace period expired",
+        ),
+        from_server::<p::GamePausedPayload>(server::GAME_PAUSED, "The game was paused"),
+        from_server::<p::GameResumedPayload>(server::GAME_RESUMED, "The game resumed"),
+        from_server::<p::ScoresUpdatePayload>(server::SCORES_UPDATE, "Live scoreboard update"),
+        from_server::<p::SettingsUpdatedPayload>(
+            server::SETTINGS_UPDATED,
+            "The lobby settings changed",
+        ),
+        from_server::<p::HandicapUpdatedPayload>(
+            server::HANDICAP_UPDATED,
+            "A player's score multiplier changed",
+        ),
+        from_server::<p::JoinCodeRotatedPayload>(
+            server::JOIN_CODE_ROTATED,
+            "The host replaced the lobby's join code",
+        ),
+        from_server::<p::GameAbandonedPayload>(server::GAME_ABANDONED, "The game was abandoned"),
+        from_server::<p::SkipVoteUpdatePayload>(
+            server::SKIP_VOTE_UPDATE,
+            "Votes to skip the between-rounds wait changed",
+        ),
+        from_server::<p::GameTransitioningPayload>(
+            server::GAME_TRANSITIONING,
+            "The game is moving to its next phase",
+        ),
+        from_server::<p::GameTransitionClearedPayload>(
+            server::GAME_TRANSITION_CLEARED,
+            "A transition was cancelled; clear any loading state",
+        ),
+        from_server::<p::ReactionPayload>(server::REACTION, "A player sent an emoji reaction"),
+        from_server::<p::RoundRerolledPayload>(
+            server::ROUND_REROLLED,
+            "The host rerolled the current round; a `round:start` follows",
+        ),
+        from_server::<p::ClassroomGuessPayload>(
+            server::CLASSROOM_GUESS,
+            "A student guessed (sent to the teacher only)",
+        ),
+        from_server::<p::HintRevealedPayload>(
+            server::HINT_REVEALED,
+            "A hint the player asked for (sent to that player only)",
+        ),
+        from_server::<p::QuizQuestionPayload>(
+            server::QUIZ_QUESTION,
+            "A lobby warm-up quiz question was asked",
+        ),
+        from_server::<p::QuizAnsweredPayload>(
+            server::QUIZ_ANSWERED,
+            "A player answered the warm-up quiz question",
+        ),
+        from_server::<p::TimeSyncPayload>(server::TIME_SYNC, "Reply to a clock sync request"),
+        // Parties (client -> server)
+        from_client::<p::CreatePartyPayload>(client::CREATE_PARTY, "Create a party"),
+        from_client::<p::JoinPartyPayload>(client::JOIN_PARTY, "Join a party"),
+        client_event(client::LEAVE_PARTY, "Leave a party", Payload::Id("party_id")),
+        from_client::<p::PartyStartGamePayload>(
+            client::PARTY_START_GAME,
+            "Start a game with the party (host only)",
+        ),
+        from_client::<p::PartyUpdateSettingsPayload>(
+            client::PARTY_UPDATE_SETTINGS,
+            "Change the party's game settings (host only)",
+        ),
+        from_client::<p::PartyKickPayload>(client::PARTY_KICK, "Kick a member (host only)"),
+        from_client::<p::PartyMutePayload>(
+            client::PARTY_MUTE,
+            "Mute or unmute a member in chat (host only)",
+        ),
+        from_client::<p::PartyDisbandPayload>(
+            client::DISBAND_PARTY,
+            "Disband the party (host only)",
+        ),
+        from_client::<p::PartyChatPayload>(client::PARTY_CHAT, "Send a chat message"),
+        // Parties (server -> client)
+        from_server::<p::PartyCreatedPayload>(party::PARTY_CREATED, "Your party was created"),
+        from_server::<p::PartyStatePayload>(
+            party::PARTY_STATE,
+            "Full party state, sent on join or reconnect",
+        ),
+        from_server::<p::PartyMemberJoinedPayload>(party::MEMBER_JOINED, "A member joined"),
+        from_server::<p::PartyMemberLeftPayload>(party::MEMBER_LEFT, "A member left"),
+        from_server::<p::PartyGameStartingPayload>(
+            party::GAME_STARTING,
+            "A game is starting from the party",
+        ),
+        from_server::<p::PartyGameEndedPayload>(
+            party::GAME_ENDED,
+            "The game ended; back to the party lobby",
+        ),
+        from_server::<p::PartyDisbandedPayload>(party::DISBANDED, "The party was disbanded"),
+        from_server::<p::PartyHostChangedPayload>(party::HOST_CHANGED, "The party host changed"),
+        from_server::<p::PartySettingsUpdatedPayload>(
+            party::SETTINGS_UPDATED,
+            "The party's game settings changed",
+        ),
+        from_server::<p::PartyKickedPayload>(party::KICKED, "A member was kicked"),
+        from_server::<p::PartyMemberMutedPayload>(
+            party::MEMBER_MUTED,
+            "The host muted or unmuted a member in chat",
+        ),
+        from_server::<p::PartyChatMessagePayload>(party::CHAT_MESSAGE, "A chat message"),
+        from_server::<p::PartyChatHistoryPayload>(
+            party::CHAT_HISTORY,
+            "Recent chat messages, sent on join or reconnect",
+        ),
+        from_server::<p::PartyErrorPayload>(party::ERROR, "A party request failed"),
+        // Friends
+        from_client::<p::FriendInvitePayload>(
+            client::FRIEND_INVITE,
+            "Invite a friend to your party or game lobby",
+        ),
+        from_server::<p::FriendPresencePayload>(friend::PRESENCE, "A friend's presence changed"),
+        from_server::<p::FriendInvitedPayload>(friend::INVITED, "A friend invited you to a lobby"),
+        from_server::<p::FriendInviteSentPayload>(friend::INVITE_SENT, "Your invite was delivered"),
+        from_server::<p::FriendRequestPayload>(
+            friend::REQUEST_RECEIVED,
+            "Someone sent you a friend request",
+        ),
+        from_server::<p::FriendRequestPayload>(
+            friend::REQUEST_ACCEPTED,
+            "Someone accepted your friend request",
+        ),
+        from_server::<p::ErrorPayload>(friend::ERROR, "A friend request failed"),
+    ]
+}
+
+/// Component key of an event's message, e.g. `server.round.start`
+fn message_id(event: &SocketEvent) -> String {
+    let side = match event.direction {
+        Direction::FromClient => "client",
+        Direction::FromServer => "server",
+    };
+    format!("{side}.{}", event.name.replace(':', "."))
+}
+
+/// Render the events as an AsyncAPI 3.0 document.
+pub fn document() -> Value {
+    let mut messages = Map::new();
+    let mut channel_messages = Map::new();
+    let mut operations = Map::new();
+
+    for event in events() {
+        let id = message_id(&event);
+
+        let mut message = json!({ "name": event.name, "summary": event.summary });
+        match &event.payload {
+            Payload::Schema(name) => {
+                message["payload"] = json!({ "$ref": format!("#/components/schemas/{name}") });
+            }
+            Payload::Id(field) => {
+                message["payload"] = json!({
+                    "type": "object",
+                    "required": [field],
+                    "properties": { *field: { "type": "string" } },
+                });
+            }
+            Payload::Untyped => {}
+        }
+
+        let action = match event.direction {
+            Direction::FromClient => "receive",
+            Direction::FromServer => "send",
+        };
+        operations.insert(
+            id.clone(),
+            json!({
+                "action": action,
+                "channel": { "$ref": "#/channels/socket" },
+                "summary": event.summary,
+                "messages": [{ "$ref": format!("#/channels/socket/messages/{id}") }],
+            }),
+        );
+        channel_messages
+            .insert(id.clone(), json!({ "$ref": format!("#/components/messages/{id}") }));
+        messages.insert(id, message);
+    }
+
+    let schemas = serde_json::to_value(SocketSchemas::openapi())
+        .ok()
+        .and_then(|doc| doc.pointer("/components/schemas").cloned())
+        .unwrap_or_else(|| json!({}));
+
+    json!({
+        "asyncapi": "3.0.0",
+        "info": {
+            "title": "DGuesser realtime",
+            "version": PROTOCOL_VERSION.to_string(),
+            "description": DESCRIPTION,
+        },
+        "defaultContentType": "application/json",
+        "channels": {
+            "socket": {
+                "address": "/",
+                "title": "Socket.IO",
+                "messages": channel_messages,
+            },
+        },
+        "operations": operations,
+        "components": {
+            "messages": messages,
+            "schemas": schemas,
+        },
+    })
+}
@@ -16,10 +16,18 @@ pub mod server {
     pub const PLAYER_RECONNECTED: &str = "player:reconnected";
     /// Player timed out (grace period expired)
     pub const PLAYER_TIMEOUT: &str = "player:timeout";
+    /// Game paused while the host is disconnected
+    pub const GAME_PAUSED: &str = "game:paused";
+    /// Game resumed after a pause
+    pub const GAME_RESUMED: &str = "game:resumed";
     /// Live scoreboard update (during gameplay)
     pub const SCORES_UPDATE: &str = "scores:update";
     /// Game settings updated (in lobby)
     pub const SETTINGS_UPDATED: &str = "game:settings_updated";
+    /// A player's score multiplier changed (in lobby)
+    pub const HANDICAP_UPDATED: &str = "player:handicap_updated";
+    /// The host replaced the lobby's join code
+    pub const JOIN_CODE_ROTATED: &str = "game:code_rotated";
     /// Game abandoned (all players disconnected for too long)
     pub const GAME_ABANDONED: &str = "game:abandoned";
     /// Skip vote update (broadcast current vote count)
@@ -28,6 +36,20 @@ pub mod server {
     pub const GAME_TRANSITIONING: &str = "game:transitioning";
     /// Game transition was cancelled (e.g. DB write failed). Clients clear loading UI.
     pub const GAME_TRANSITION_CLEARED: &str = "game:transition_cleared";
+    /// A player sent an emoji reaction
+    pub const REACTION: &str = "game:reaction";
+    /// The host rerolled the current round (a `round:start` follows)
+    pub const ROUND_REROLLED: &str = "round:rerolled";
+    /// A student guessed in a classroom game (sent to the teacher only)
+    pub const CLASSROOM_GUESS: &str = "classroom:guess";
+    /// A hint the player asked for (sent to that player only)
+    pub const HINT_REVEALED: &str = "hint:revealed";
+    /// A lobby warm-up quiz question was asked
+    pub const QUIZ_QUESTION: &str = "quiz:question";
+    /// A player answered the warm-up quiz question
+    pub const QUIZ_ANSWERED: &str = "quiz:answered";
+    /// Reply to a clock sync request, carrying the server time
+    pub const TIME_SYNC: &str = "time:sync";
 }
 
 /// Socket.IO event names (client -> server)
@@ -35,12 +57,30 @@ pub mod client {
     pub const JOIN_GAME: &str = "game:join";
     pub const LEAVE_GAME: &str = "game:leave";
     pub const START_GAME: &str = "game:start";
+    /// Host changes the lobby settings
+    pub const UPDATE_SETTINGS: &str = "game:update_settings";
+    /// Host changes a player's score multiplier
+    pub const SET_HANDICAP: &str = "player:set_handicap";
     pub const SUBMIT_GUESS: &str = "guess:submit";
     pub const READY: &str = "player:ready";
     /// Host force-skips the between-rounds wait
     pub const SKIP_WAIT: &str = "round:skip";
     /// Player votes to skip the between-rounds wait
     pub const VOTE_SKIP: &str = "round:vote_skip";
+    /// Player sends an emoji reaction
+    pub const REACT: &str = "game:react";
+    /// Host replaces the current round's location
+    pub const REROLL: &str = "game:reroll";
+    /// Teacher pauses a classroom game
+    pub const PAUSE: &str = "classroom:pause";
+    /// Teacher resumes a classroom game
+    pub const RESUME: &str = "classroom:resume";
+    /// Player asks for a hint on the current round
+    pub const REQUEST_HINT: &str = "hint:request";
+    /// Ask for the server time to sync the client clock
+    pub const TIME_SYNC: &str = "time:sync";
+    /// Echo a `time:sync` reply so the server can measure round-trip time
+    pub const TIME_PONG: &str = "time:pong";
 
     // Party events
     pub const CREATE_PARTY: &str = "party:create";
@@ -49,7 +89,13 @@ pub mod client {
     pub const PARTY_START_GAME: &str = "party:start_game";
     pub const PARTY_UPDATE_SETTINGS: &str = "party:update_settings";
     pub const PARTY_KICK: &str = "party:kick";
+    /// Host mutes or unmutes a member in chat
+    pub const PARTY_MUTE: &str = "party:mute";
     pub const DISBAND_PARTY: &str = "party:disband";
+    pub const PARTY_CHAT: &str = "party:chat";
+
+    // Friend events
+    pub const FRIEND_INVITE: &str = "friend:invite";
 }
 
 /// Socket.IO event names for party system (server -> client)
@@ -64,5 +110,24 @@ pub mod party {
     pub const HOST_CHANGED: &str = "party:host_changed";
     pub const SETTINGS_UPDATED: &str = "party:settings_updated";
     pub const KICKED: &str = "party:kicked";
+    /// The host muted or unmuted a member in chat
+    pub const MEMBER_MUTED: &str = "party:member_muted";
+    pub const CHAT_MESSAGE: &str = "party:chat_message";
+    pub const CHAT_HISTORY: &str = "party:chat_history";
     pub const ERROR: &str = "party:error";
 }
+
+/// Socket.IO event names for friends (server -> client)
+pub mod friend {
+    /// A friend came online or went offline
+    pub const PRESENCE: &str = "friend:presence";
+    /// A friend invited you to their party or game lobby
+    pub const INVITED: &str = "friend:invited";
+    /// Your invite was delivered
+    pub const INVITE_SENT: &str = "friend:invite_sent";
+    /// Someone sent you a friend request
+    pub const REQUEST_RECEIVED: &str = "friend:request_received";
+    /// Someone accepted your friend request
+    pub const REQUEST_ACCEPTED: &str = "friend:request_accepted";
+    pub const ERROR: &str = "friend:error";
+}
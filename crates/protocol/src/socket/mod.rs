@@ -1,4 +1,6 @@
 //! Socket.IO event definitions
 
+pub mod asyncapi;
 pub mod events;
 pub mod payloads;
+pub mod presence;
@@ -3,6 +3,8 @@
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
+use super::presence::PresenceInfo;
+
 /// Game settings payload for socket events
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct GameSettingsPayload {
@@ -24,6 +26,122 @@ pub struct GameSettingsPayload {
     /// Whether rotation/compass is allowed
     #[schema(example = true)]
     pub rotation_allowed: bool,
+    /// Time-attack budget shared by all rounds in seconds (0 = off)
+    #[serde(default)]
+    #[schema(example = 0)]
+    pub time_budget_seconds: u32,
+    /// Seconds a disconnected player has to reconnect
+    #[serde(default = "default_reconnect_grace_seconds")]
+    #[schema(example = 30)]
+    pub reconnect_grace_seconds: u32,
+    /// What happens when a player disconnects: remove_player,
+    /// keep_scoring_zero or pause_if_host
+    #[serde(default = "default_disconnect_policy")]
+    #[schema(example = "keep_scoring_zero")]
+    pub disconnect_policy: String,
+    /// Whether players can send emoji reactions
+    #[serde(default = "default_reactions_enabled")]
+    #[schema(example = true)]
+    pub reactions_enabled: bool,
+    /// Whether players can join after the game has started
+    #[serde(default)]
+    pub allow_late_join: bool,
+    /// Maps the rounds are played on, in round order (empty = every round
+    /// on `map_id`)
+    #[serde(default)]
+    pub rounds_plan: Vec<MapRoundsPayload>,
+    /// Classroom game: the host is a teacher who sees guesses live and
+    /// can pause, but does not play
+    #[serde(default)]
+    pub classroom: bool,
+    /// Whether players can take hints during a round
+    #[serde(default)]
+    pub hints_enabled: bool,
+    /// Score points each hint costs
+    #[serde(default = "default_hint_cost")]
+    #[schema(example = 500)]
+    pub hint_cost: u32,
+}
+
+/// A run of consecutive rounds played on one map
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MapRoundsPayload {
+    /// Map/region identifier
+    #[schema(example = "japan")]
+    pub map_id: String,
+    /// Number of consecutive rounds played on the map
+    #[schema(example = 2)]
+    pub rounds: u8,
+}
+
+fn default_reconnect_grace_seconds() -> u32 {
+    30
+}
+
+fn default_disconnect_policy() -> String {
+    "keep_scoring_zero".to_string()
+}
+
+fn default_hint_cost() -> u32 {
+    500
+}
+
+fn default_reactions_enabled() -> bool {
+    true
+}
+
+/// Reactions players can send during a game
+pub const REACTIONS: &[&str] =
+    &["thumbs_up", "laugh", "wow", "fire", "clap", "thinking", "sad", "party"];
+
+/// Client sending a reaction
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReactPayload {
+    /// Game ID
+    #[schema(example = "gam_FybH2oF9Xaw8")]
+    pub game_id: String,
+    /// One of [`REACTIONS`]
+    #[schema(example = "fire")]
+    pub reaction: String,
+}
+
+/// Server broadcast: a player reacted. Reactions are not stored.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReactionPayload {
+    /// Player who reacted
+    #[schema(example = "usr_V1StGXR8_Z5j")]
+    pub user_id: String,
+    /// One of [`REACTIONS`]
+    #[schema(example = "fire")]
+    pub reaction: String,
+}
+
+/// Client asking for the server time
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TimeSyncRequest {
+    /// Client clock when the request was sent (unix ms)
+    pub client_time: i64,
+}
+
+/// Server reply to a clock sync request. The client estimates its clock
+/// offset as `server_time + rtt / 2 - now`, where `rtt` is measured from
+/// `client_time`, then echoes `server_time` back in a `time:pong`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TimeSyncPayload {
+    /// The request's `client_time`, echoed back
+    pub client_time: i64,
+    /// Server clock when the reply was sent (unix ms)
+    pub server_time: i64,
+}
+
+/// Client echo of a `time:sync` reply, used to measure round-trip time
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TimePongPayload {
+    /// The `server_time` of the reply being answered
+    pub server_time: i64,
+    /// Game the player is in, if any, to record the round-trip time against
+    #[schema(example = "gam_FybH2oF9Xaw8")]
+    pub game_id: Option<String>,
 }
 
 /// Client request to join a game
@@ -37,6 +155,9 @@ pub struct JoinGamePayload {
 /// Client submitting a guess
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SubmitGuessPayload {
+    /// Game ID (e.g., gam_FybH2oF9Xaw8)
+    #[schema(example = "gam_FybH2oF9Xaw8")]
+    pub game_id: String,
     /// Guessed latitude
     #[schema(example = 51.5074)]
     pub lat: f64,
@@ -45,6 +166,10 @@ pub struct SubmitGuessPayload {
     pub lng: f64,
     /// Time taken to submit guess in milliseconds
     pub time_taken_ms: Option<u32>,
+    /// Panorama the client was viewing when it guessed.
+    /// Must match the round's panorama in no-move modes.
+    #[serde(default)]
+    pub panorama_id: Option<String>,
 }
 
 /// Server broadcast: round started
@@ -64,6 +189,131 @@ pub struct RoundStartPayload {
     pub started_at: i64,
 }
 
+/// Server broadcast: the host rerolled the current round. Guesses on the
+/// old location were discarded; a `round:start` with the new location follows.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RoundRerolledPayload {
+    /// Round that was rerolled
+    #[schema(example = 2)]
+    pub round_number: u8,
+    /// Host who rerolled the round
+    #[schema(example = "usr_V1StGXR8_Z5j")]
+    pub rerolled_by: String,
+    /// Players whose guesses were discarded
+    pub discarded_guesses: Vec<String>,
+    /// Rerolls the host has left in this game
+    #[schema(example = 1)]
+    pub rerolls_left: u8,
+}
+
+/// Server to teacher: a student's guess in a classroom game, sent as soon
+/// as it is made
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ClassroomGuessPayload {
+    /// Round the guess was made in
+    #[schema(example = 2)]
+    pub round_number: u8,
+    /// User ID of the student (e.g., usr_V1StGXR8_Z5j)
+    #[schema(example = "usr_V1StGXR8_Z5j")]
+    pub user_id: String,
+    /// Display name of the student
+    #[schema(example = "CoolPlayer42")]
+    pub display_name: String,
+    /// Guessed latitude
+    pub lat: f64,
+    /// Guessed longitude
+    pub lng: f64,
+    /// Distance from correct location in meters
+    pub distance_meters: f64,
+    /// Score for this guess
+    pub score: u32,
+    /// How long the student took to guess, in milliseconds
+    pub time_taken_ms: Option<u32>,
+}
+
+/// Server to player: a hint revealed for the current round
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct HintRevealedPayload {
+    /// Round the hint is for
+    #[schema(example = 2)]
+    pub round_number: u8,
+    /// Hint kind (continent, country_letter, direction)
+    #[schema(example = "continent")]
+    pub kind: String,
+    /// What the hint reveals: a continent ID, a letter or a compass point
+    #[schema(example = "europe")]
+    pub value: String,
+    /// Points taken off this round's score (0 when the hint was already taken)
+    #[schema(example = 500)]
+    pub cost: u32,
+}
+
+/// A country the player can pick in the warm-up quiz
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct QuizChoice {
+    /// ISO 3166-1 alpha-2 code
+    #[schema(example = "SE")]
+    pub code: String,
+    /// English country name
+    #[schema(example = "Sweden")]
+    pub name: String,
+}
+
+/// Bounding box shown by a `bounds` quiz question
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct QuizBounds {
+    pub min_lat: f64,
+    pub min_lng: f64,
+    pub max_lat: f64,
+    pub max_lng: f64,
+}
+
+/// Server broadcast: a warm-up quiz question, without its answer
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct QuizQuestionPayload {
+    /// Question ID, sent back with the answer
+    #[schema(example = "q_1715000000000")]
+    pub question_id: String,
+    /// What the question shows (flag, bounds)
+    #[schema(example = "flag")]
+    pub prompt: String,
+    /// Flag emoji, for `flag` questions
+    pub flag: Option<String>,
+    /// Country bounding box, for `bounds` questions (`min_lng > max_lng`
+    /// when it crosses the antimeridian)
+    pub bounds: Option<QuizBounds>,
+    /// Countries to pick from
+    pub choices: Vec<QuizChoice>,
+    /// When answers stop being accepted (Unix ms)
+    #[schema(example = 1715000015000_i64)]
+    pub expires_at: i64,
+}
+
+/// A player's warm-up quiz score for the current lobby
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct QuizScore {
+    #[schema(example = "usr_V1StGXR8_Z5j")]
+    pub user_id: String,
+    #[schema(example = 350)]
+    pub score: u32,
+}
+
+/// Server broadcast: a player answered the warm-up quiz question. The
+/// answer itself is only returned to the player who answered.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct QuizAnsweredPayload {
+    pub question_id: String,
+    /// Player who answered
+    #[schema(example = "usr_V1StGXR8_Z5j")]
+    pub user_id: String,
+    pub correct: bool,
+    /// Points the answer earned
+    #[schema(example = 150)]
+    pub points: u32,
+    /// Lobby scores, highest first
+    pub scores: Vec<QuizScore>,
+}
+
 /// Location data for a round
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct RoundLocation {
@@ -78,6 +328,9 @@ pub struct RoundLocation {
     /// Optional heading/direction for Street View panorama (degrees, 0-360)
     #[schema(example = 180.0)]
     pub heading: Option<f64>,
+    /// Imagery provider the panorama comes from, which decides the viewer
+    #[schema(example = "google_streetview")]
+    pub provider: String,
 }
 
 /// Server broadcast: player guessed (without revealing location)
@@ -104,6 +357,20 @@ pub struct RoundEndPayload {
     /// Unix timestamp (ms) when the next round will auto-start (multiplayer only)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub next_round_at: Option<i64>,
+    /// How past players did on this location (absent until enough have guessed)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub global_stats: Option<GlobalGuessStats>,
+}
+
+/// Historical guesses on a location by all players
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GlobalGuessStats {
+    /// Number of past guesses aggregated
+    #[schema(example = 1520)]
+    pub guesses: u32,
+    /// Median distance of past guesses in meters
+    #[schema(example = 845000.0)]
+    pub median_distance_meters: f64,
 }
 
 /// Individual player result for a round
@@ -125,6 +392,10 @@ pub struct RoundResult {
     pub score: u32,
     /// Cumulative total score
     pub total_score: u32,
+    /// Percentage of past guesses on this location that were further away
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = 72.5)]
+    pub percentile: Option<f64>,
 }
 
 /// Server broadcast: game ended
@@ -151,6 +422,15 @@ pub struct FinalStanding {
     pub display_name: String,
     /// Total score
     pub total_score: u32,
+    /// Number of countries named correctly in a row (streak games only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = 12)]
+    pub streak: Option<u32>,
+    /// First round the player could play, if they joined after the game
+    /// started; earlier rounds scored 0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = 3)]
+    pub joined_round: Option<u8>,
 }
 
 /// Error payload
@@ -185,12 +465,25 @@ pub struct PlayerInfo {
     /// Unix timestamp (ms) when player disconnected (if disconnected)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub disconnected_at: Option<i64>,
+    /// Multiplier applied to the player's round scores
+    #[serde(default = "default_handicap")]
+    #[schema(example = 1.0)]
+    pub handicap: f64,
+    /// First round the player could play, if they joined after the game
+    /// started
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = 3)]
+    pub joined_round: Option<u8>,
 }
 
 fn default_connected() -> bool {
     true
 }
 
+fn default_handicap() -> f64 {
+    1.0
+}
+
 /// Full game state (sent when player joins)
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct GameStatePayload {
@@ -276,6 +569,29 @@ pub struct PlayerTimeoutPayload {
     pub display_name: String,
 }
 
+/// Game paused payload (host disconnected mid-game, or the teacher paused
+/// a classroom game)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GamePausedPayload {
+    /// User ID of the host (e.g., usr_V1StGXR8_Z5j)
+    #[schema(example = "usr_V1StGXR8_Z5j")]
+    pub user_id: String,
+    /// How long the game waits for the host before resuming, in
+    /// milliseconds; absent when paused until the teacher resumes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = 30000)]
+    pub grace_period_ms: Option<u32>,
+}
+
+/// Game resumed payload
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GameResumedPayload {
+    /// How long the game was paused in milliseconds; round timers are
+    /// extended by this much
+    #[schema(example = 12000)]
+    pub paused_ms: i64,
+}
+
 /// Live scoreboard update payload
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ScoresUpdatePayload {
@@ -300,12 +616,16 @@ pub struct PlayerScoreInfo {
     pub display_name: String,
     /// Avatar URL
     pub avatar_url: Option<String>,
-    /// Total score so far
+    /// Total score so far, with the handicap applied
     #[schema(example = 12500)]
     pub total_score: u32,
-    /// Score from the current round (0 if not yet guessed)
+    /// Score from the current round before the handicap (0 if not yet guessed)
     #[schema(example = 4500)]
     pub round_score: u32,
+    /// Multiplier applied to round scores before they are added to the total
+    #[serde(default = "default_handicap")]
+    #[schema(example = 1.25)]
+    pub handicap: f64,
     /// Whether the player has guessed this round
     #[schema(example = true)]
     pub has_guessed: bool,
@@ -327,6 +647,31 @@ pub struct SettingsUpdatedPayload {
     pub settings: GameSettingsPayload,
 }
 
+/// Join code rotated payload (broadcast to all players in lobby)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct JoinCodeRotatedPayload {
+    /// Game ID (e.g., gam_FybH2oF9Xaw8)
+    #[schema(example = "gam_FybH2oF9Xaw8")]
+    pub game_id: String,
+    /// New join code
+    #[schema(example = "XK4P9M")]
+    pub join_code: String,
+}
+
+/// Handicap updated payload (broadcast to all players in lobby)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct HandicapUpdatedPayload {
+    /// Game ID (e.g., gam_FybH2oF9Xaw8)
+    #[schema(example = "gam_FybH2oF9Xaw8")]
+    pub game_id: String,
+    /// User ID of the player (e.g., usr_V1StGXR8_Z5j)
+    #[schema(example = "usr_V1StGXR8_Z5j")]
+    pub user_id: String,
+    /// New multiplier applied to the player's round scores
+    #[schema(example = 1.25)]
+    pub handicap: f64,
+}
+
 /// Skip vote update payload (broadcast when a player votes to skip the between-rounds wait)
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SkipVoteUpdatePayload {
@@ -364,8 +709,8 @@ pub struct CreatePartyPayload {
 /// Client request to join a party
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct JoinPartyPayload {
-    /// Party join code
-    pub code: String,
+    /// Party ID
+    pub party_id: String,
 }
 
 /// Client request to start a game from the party
@@ -393,6 +738,17 @@ pub struct PartyKickPayload {
     pub user_id: String,
 }
 
+/// Client request to mute or unmute a member in chat (host only)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PartyMutePayload {
+    /// Party ID
+    pub party_id: String,
+    /// User ID of the member to mute
+    pub user_id: String,
+    /// `false` to unmute
+    pub muted: bool,
+}
+
 /// Client request to disband a party
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct PartyDisbandPayload {
@@ -420,6 +776,8 @@ pub struct PartyMemberInfo {
     pub avatar_url: Option<String>,
     /// Whether the member is currently connected
     pub connected: bool,
+    /// Whether the host muted the member in chat
+    pub chat_muted: bool,
 }
 
 /// Full party state (sent when member joins or reconnects)
@@ -496,6 +854,45 @@ pub struct PartyKickedPayload {
     pub user_id: String,
 }
 
+/// Server broadcast: the host muted or unmuted a member in chat
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PartyMemberMutedPayload {
+    pub user_id: String,
+    pub display_name: String,
+    pub muted: bool,
+}
+
+/// Client request to send a chat message to the party
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PartyChatPayload {
+    /// Party ID
+    pub party_id: String,
+    /// Message text (1-500 characters)
+    pub content: String,
+}
+
+/// Server broadcast: chat message sent to the party
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PartyChatMessagePayload {
+    /// Message ID, increasing in send order
+    pub id: i64,
+    /// Sender user ID
+    pub user_id: String,
+    /// Sender display name
+    pub display_name: String,
+    /// Message text
+    pub content: String,
+    /// Unix timestamp (ms) when the message was sent
+    pub sent_at: i64,
+}
+
+/// Server: recent chat messages, sent when a member joins or reconnects
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PartyChatHistoryPayload {
+    /// Messages, oldest first
+    pub messages: Vec<PartyChatMessagePayload>,
+}
+
 /// Party error payload
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct PartyErrorPayload {
@@ -503,6 +900,73 @@ pub struct PartyErrorPayload {
     pub message: String,
 }
 
+// =============================================================================
+// Friend payloads
+// =============================================================================
+
+/// Client request to invite a friend to a party or game lobby
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FriendInvitePayload {
+    /// Friend's user ID
+    pub user_id: String,
+    /// Party ID (pty_...) or game ID (gam_...) to invite them to
+    pub lobby_id: String,
+}
+
+/// Kind of lobby a friend was invited to
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LobbyKind {
+    Party,
+    Game,
+}
+
+/// Server: a friend invited you to their lobby
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FriendInvitedPayload {
+    /// Inviting friend's user ID
+    pub from_user_id: String,
+    /// Inviting friend's display name
+    pub from_display_name: String,
+    /// Whether the lobby is a party or a game
+    pub lobby_kind: LobbyKind,
+    /// Party or game ID
+    pub lobby_id: String,
+    /// Join code of the lobby
+    pub join_code: String,
+}
+
+/// Server: your invite was delivered
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FriendInviteSentPayload {
+    /// Invited friend's user ID
+    pub user_id: String,
+}
+
+/// Server broadcast to friends: a user came online, went offline, or
+/// joined or left a lobby or game
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FriendPresencePayload {
+    /// Friend's user ID
+    pub user_id: String,
+    /// Whether the friend is now online
+    pub online: bool,
+    /// What the friend is doing now
+    #[serde(flatten)]
+    pub presence: PresenceInfo,
+}
+
+/// Server: a friend request was received or accepted
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FriendRequestPayload {
+    /// The other user's ID
+    pub user_id: String,
+    /// The other user's display name
+    pub display_name: String,
+    /// The other user's avatar URL
+    pub avatar_url: Option<String>,
+}
+
 /// Phase of a broadcast game transition.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
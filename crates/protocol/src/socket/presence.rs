@@ -0,0 +1,128 @@
+//! Online presence
+//!
+//! The realtime server records each authenticated socket in a Redis sorted
+//! set per user, scored by the Unix time it was last seen, and refreshes the
+//! scores while sockets stay connected. A user is online while any entry is
+//! fresher than [`PRESENCE_TTL_SECS`], so sockets lost in a crash age out on
+//! their own.
+//!
+//! While a user is in a lobby or game, the game's actor also stores a
+//! [`PresenceActivity`] under [`activity_key`]. It expires with the sockets
+//! and only counts while the user is online.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// How long a socket counts as online without a refresh (seconds)
+pub const PRESENCE_TTL_SECS: i64 = 90;
+
+/// How often connected sockets are refreshed (seconds)
+pub const PRESENCE_REFRESH_SECS: u64 = 30;
+
+/// Redis key of a user's presence set
+pub fn presence_key(user_id: &str) -> String {
+    format!("presence:{user_id}")
+}
+
+/// Redis key of a user's current game activity (JSON [`PresenceActivity`])
+pub fn activity_key(user_id: &str) -> String {
+    format!("presence:{user_id}:activity")
+}
+
+/// What a user is currently doing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PresenceStatus {
+    #[default]
+    Offline,
+    Online,
+    InLobby,
+    InGame,
+}
+
+/// Game activity recorded while a user is in a lobby or game
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PresenceActivity {
+    /// `in_lobby` or `in_game`
+    pub status: PresenceStatus,
+    /// Game the user is in (never exposed to other users)
+    pub game_id: String,
+    /// Game mode, e.g. "multiplayer"
+    pub mode: String,
+    /// Name of the map being played
+    pub map_name: Option<String>,
+}
+
+/// Presence as shown to other users
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct PresenceInfo {
+    /// Current status
+    pub status: PresenceStatus,
+    /// Game mode while in a lobby or game
+    #[schema(example = "multiplayer")]
+    pub mode: Option<String>,
+    /// Map name while in a lobby or game
+    #[schema(example = "World")]
+    pub map_name: Option<String>,
+}
+
+impl PresenceInfo {
+    /// Presence of a user with live sockets and an optional activity
+    pub fn online(activity: Option<PresenceActivity>) -> Self {
+        match activity {
+            Some(activity) => Self {
+                status: activity.status,
+                mode: Some(activity.mode),
+                map_name: activity.map_name,
+            },
+            None => Self { status: PresenceStatus::Online, mode: None, map_name: None },
+        }
+    }
+
+    /// Whether the user is connected at all
+    pub fn is_online(&self) -> bool {
+        self.status != PresenceStatus::Offline
+    }
+}
+
+/// Who may see a user's presence
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PresenceVisibility {
+    /// Anyone, including signed-out visitors
+    Everyone,
+    /// Only friends
+    #[default]
+    Friends,
+    /// Nobody; the user always appears offline
+    Nobody,
+}
+
+impl PresenceVisibility {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Everyone => "everyone",
+            Self::Friends => "friends",
+            Self::Nobody => "nobody",
+        }
+    }
+
+    /// Parse a stored value, falling back to the default
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "everyone" => Self::Everyone,
+            "nobody" => Self::Nobody,
+            _ => Self::Friends,
+        }
+    }
+
+    /// Whether a viewer may see the presence
+    pub fn allows(self, is_self: bool, is_friend: bool) -> bool {
+        is_self
+            || match self {
+                Self::Everyone => true,
+                Self::Friends => is_friend,
+                Self::Nobody => false,
+            }
+    }
+}
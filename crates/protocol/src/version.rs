@@ -0,0 +1,92 @@
+//! Protocol version and capability negotiation
+//!
+//! Clients send the protocol version they speak when authenticating their
+//! socket. The server rejects versions older than [`MIN_PROTOCOL_VERSION`]
+//! and otherwise answers with the capabilities both sides support, so an
+//! older client keeps working without the features it does not know about.
+//! Clients that send no version are treated as version 1.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Protocol version this build speaks. Bump it when adding a capability or
+/// changing an event in a way older clients cannot handle.
+pub const PROTOCOL_VERSION: u32 = 2;
+
+/// Oldest protocol version the servers still accept
+pub const MIN_PROTOCOL_VERSION: u32 = 1;
+
+/// Version assumed for clients that send none
+pub const DEFAULT_CLIENT_VERSION: u32 = 1;
+
+/// Optional features a socket can use, negotiated at authentication
+pub mod capability {
+    /// Party chat (`party:chat`)
+    pub const CHAT: &str = "chat";
+    /// Emoji reactions in games (`game:react`)
+    pub const REACTIONS: &str = "reactions";
+    /// Clock sync and round-trip measurement (`time:sync`, `time:pong`)
+    pub const CLOCK_SYNC: &str = "clock_sync";
+}
+
+/// Every capability with the protocol version that introduced it
+const CAPABILITIES: &[(&str, u32)] =
+    &[(capability::CHAT, 1), (capability::REACTIONS, 1), (capability::CLOCK_SYNC, 2)];
+
+/// All capabilities of this build
+pub fn capabilities() -> Vec<&'static str> {
+    capabilities_for(PROTOCOL_VERSION)
+}
+
+/// Capabilities available to a client speaking `version`. Versions newer
+/// than this build get everything this build supports.
+pub fn capabilities_for(version: u32) -> Vec<&'static str> {
+    CAPABILITIES.iter().filter(|(_, since)| *since <= version).map(|(name, _)| *name).collect()
+}
+
+/// Outcome of checking a client's protocol version
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compatibility {
+    /// Same version as this build
+    Current,
+    /// Older or newer but supported; some capabilities may be missing
+    Degraded,
+    /// Older than [`MIN_PROTOCOL_VERSION`]; the client has to update
+    Unsupported,
+}
+
+/// Check a client's protocol version against this build.
+pub fn compatibility(client_version: u32) -> Compatibility {
+    if client_version < MIN_PROTOCOL_VERSION {
+        Compatibility::Unsupported
+    } else if client_version == PROTOCOL_VERSION {
+        Compatibility::Current
+    } else {
+        Compatibility::Degraded
+    }
+}
+
+/// Protocol a server speaks, advertised in its service info
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ProtocolInfo {
+    /// Protocol version of this build
+    #[schema(example = 2)]
+    pub version: u32,
+    /// Oldest client protocol version accepted
+    #[schema(example = 1)]
+    pub min_version: u32,
+    /// Capabilities of this build
+    #[schema(example = json!(["chat", "reactions", "clock_sync"]))]
+    pub capabilities: Vec<String>,
+}
+
+impl ProtocolInfo {
+    /// The protocol of this build
+    pub fn current() -> Self {
+        Self {
+            version: PROTOCOL_VERSION,
+            min_version: MIN_PROTOCOL_VERSION,
+            capabilities: capabilities().into_iter().map(String::from).collect(),
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! HTTP delivery to browser push services.
+
+use std::time::Duration;
+
+use crate::crypto;
+use crate::error::PushError;
+use crate::vapid::VapidSigner;
+
+/// Push service hosts subscriptions may point at. The server POSTs to
+/// whatever endpoint a browser registers, so anything else is refused.
+const ALLOWED_HOSTS: &[&str] =
+    &["fcm.googleapis.com", "push.services.mozilla.com", "push.apple.com", "notify.windows.com"];
+
+/// How urgently the push service should wake the device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Urgency {
+    Normal,
+    High,
+}
+
+impl Urgency {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Urgency::Normal => "normal",
+            Urgency::High => "high",
+        }
+    }
+}
+
+/// A push subscription as registered by a browser.
+#[derive(Debug, Clone, Copy)]
+pub struct Subscription<'a> {
+    pub endpoint: &'a str,
+    /// Browser public key (base64url)
+    pub p256dh: &'a str,
+    /// Auth secret (base64url)
+    pub auth: &'a str,
+}
+
+/// Sends encrypted, VAPID-signed messages to push services.
+pub struct WebPushClient {
+    client: reqwest::Client,
+    signer: VapidSigner,
+}
+
+impl WebPushClient {
+    /// Create a client signing with `signer`.
+    pub fn new(signer: VapidSigner) -> Self {
+        let client =
+            reqwest::Client::builder().timeout(Duration::from_secs(15)).build().unwrap_or_default();
+
+        Self { client, signer }
+    }
+
+    /// Public key browsers subscribe with.
+    pub fn public_key(&self) -> &str {
+        self.signer.public_key()
+    }
+
+    /// Encrypt `payload` for a subscription and send it.
+    ///
+    /// The push service keeps undelivered messages for up to `ttl_secs`.
+    pub async fn send(
+        &self,
+        subscription: Subscription<'_>,
+        payload: &[u8],
+        ttl_secs: u32,
+        urgency: Urgency,
+    ) -> Result<(), PushError> {
+        validate_endpoint(subscription.endpoint)?;
+        let body = crypto::encrypt(
+            &crypto::decode_key(subscription.p256dh)?,
+            &crypto::decode_key(subscription.auth)?,
+            payload,
+        )?;
+
+        let response = self
+            .client
+            .post(subscription.endpoint)
+            .header("Authorization", self.signer.authorization(subscription.endpoint)?)
+            .header("Content-Encoding", "aes128gcm")
+            .header("Content-Type", "application/octet-stream")
+            .header("TTL", ttl_secs.to_string())
+            .header("Urgency", urgency.as_str())
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| PushError::Transport(e.to_string()))?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+
+        let body = response.text().await.unwrap_or_default();
+        Err(PushError::Rejected { status: status.as_u16(), body })
+    }
+}
+
+/// Check an endpoint is an HTTPS URL on a known push service.
+pub fn validate_endpoint(endpoint: &str) -> Result<(), PushError> {
+    let invalid = |reason: &str| PushError::InvalidEndpoint(reason.to_string());
+
+    let url = reqwest::Url::parse(endpoint).map_err(|_| invalid("not a URL"))?;
+    if url.scheme() != "https" {
+        return Err(invalid("must use https"));
+    }
+    let host = url.host_str().ok_or_else(|| invalid("missing host"))?;
+    let allowed = ALLOWED_HOSTS.iter().any(|allowed| {
+        host == *allowed || host.strip_suffix(allowed).is_some_and(|sub| sub.ends_with('.'))
+    });
+    if !allowed {
+        return Err(invalid("unknown push service"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_endpoint() {
+        assert!(validate_endpoint("https://fcm.googleapis.com/fcm/send/abc").is_ok());
+        assert!(validate_endpoint("https://updates.push.services.mozilla.com/wpush/v2/x").is_ok());
+        assert!(validate_endpoint("https://web.push.apple.com/QGz").is_ok());
+        assert!(validate_endpoint("https://wns2-par02p.notify.windows.com/w/?token=x").is_ok());
+
+        assert!(validate_endpoint("http://fcm.googleapis.com/fcm/send/abc").is_err());
+        assert!(validate_endpoint("https://evilpush.apple.com/x").is_err());
+        assert!(validate_endpoint("https://fcm.googleapis.com.evil.example/x").is_err());
+        assert!(validate_endpoint("https://169.254.169.254/latest").is_err());
+        assert!(validate_endpoint("not a url").is_err());
+    }
+}
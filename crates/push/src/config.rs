@@ -0,0 +1,58 @@
+//! Push configuration from environment variables.
+
+use std::env;
+
+use crate::client::WebPushClient;
+use crate::error::PushError;
+use crate::vapid::VapidSigner;
+
+/// Contact sent to push services when `VAPID_SUBJECT` is not set.
+const DEFAULT_SUBJECT: &str = "mailto:support@dguesser.lol";
+
+/// VAPID application server identity.
+#[derive(Debug, Clone)]
+pub struct PushConfig {
+    /// Uncompressed P-256 public key (base64url)
+    pub vapid_public_key: String,
+    /// P-256 private key scalar (base64url)
+    pub vapid_private_key: String,
+    /// `mailto:` or `https:` contact for push service operators
+    pub subject: String,
+}
+
+impl PushConfig {
+    /// Create from environment variables.
+    ///
+    /// Reads `VAPID_PUBLIC_KEY`, `VAPID_PRIVATE_KEY` and `VAPID_SUBJECT`.
+    /// Returns `None` (push disabled) when neither key is set.
+    pub fn from_env() -> Result<Option<Self>, PushError> {
+        let public_key = env::var("VAPID_PUBLIC_KEY").ok().filter(|k| !k.is_empty());
+        let private_key = env::var("VAPID_PRIVATE_KEY").ok().filter(|k| !k.is_empty());
+
+        let (vapid_public_key, vapid_private_key) = match (public_key, private_key) {
+            (Some(public), Some(private)) => (public, private),
+            (None, None) => return Ok(None),
+            _ => {
+                return Err(PushError::Config(
+                    "VAPID_PUBLIC_KEY and VAPID_PRIVATE_KEY must be set together".to_string(),
+                ));
+            }
+        };
+
+        let subject = env::var("VAPID_SUBJECT").unwrap_or_else(|_| DEFAULT_SUBJECT.to_string());
+        if !subject.starts_with("mailto:") && !subject.starts_with("https:") {
+            return Err(PushError::Config(
+                "VAPID_SUBJECT must be a mailto: or https: URL".to_string(),
+            ));
+        }
+
+        Ok(Some(Self { vapid_public_key, vapid_private_key, subject }))
+    }
+
+    /// Build the push client, checking that the keys form a pair.
+    pub fn build(&self) -> Result<WebPushClient, PushError> {
+        let signer =
+            VapidSigner::new(&self.vapid_public_key, &self.vapid_private_key, &self.subject)?;
+        Ok(WebPushClient::new(signer))
+    }
+}
@@ -0,0 +1,205 @@
+//! Web Push payload encryption (RFC 8291, `aes128gcm` content coding).
+//!
+//! Every message uses a fresh ECDH key pair and salt, so the content key is
+//! never reused. Payloads are small enough to always fit in a single record.
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::{aead, agreement, hkdf};
+
+use crate::error::PushError;
+
+/// Record size written to the header.
+const RECORD_SIZE: u32 = 4096;
+
+/// Uncompressed P-256 public key length.
+const PUBLIC_KEY_LEN: usize = 65;
+
+/// Auth secret length.
+const AUTH_SECRET_LEN: usize = 16;
+
+/// Salt (16), record size (4), key ID length (1) and key ID.
+const HEADER_LEN: usize = 16 + 4 + 1 + PUBLIC_KEY_LEN;
+
+/// Largest plaintext that fits in the 4096 bytes push services must accept,
+/// after the header, the padding delimiter and the AES-GCM tag.
+pub const MAX_PAYLOAD_LEN: usize = 4096 - HEADER_LEN - 1 - 16;
+
+/// Decode a base64url key as browsers send it, with or without padding.
+pub fn decode_key(value: &str) -> Result<Vec<u8>, PushError> {
+    URL_SAFE_NO_PAD
+        .decode(value.trim_end_matches('='))
+        .map_err(|e| PushError::InvalidKey(e.to_string()))
+}
+
+/// Check a subscription's keys have the shape RFC 8291 requires.
+pub fn validate_subscription_keys(p256dh: &[u8], auth: &[u8]) -> Result<(), PushError> {
+    if p256dh.len() != PUBLIC_KEY_LEN || p256dh[0] != 0x04 {
+        return Err(PushError::InvalidKey("p256dh must be an uncompressed P-256 key".into()));
+    }
+    if auth.len() != AUTH_SECRET_LEN {
+        return Err(PushError::InvalidKey("auth secret must be 16 bytes".into()));
+    }
+    Ok(())
+}
+
+/// Encrypt a payload for a subscription's `p256dh` key and `auth` secret.
+///
+/// Returns the request body: the `aes128gcm` header followed by one record.
+pub fn encrypt(p256dh: &[u8], auth: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, PushError> {
+    validate_subscription_keys(p256dh, auth)?;
+    if plaintext.len() > MAX_PAYLOAD_LEN {
+        return Err(PushError::PayloadTooLarge(plaintext.len()));
+    }
+
+    let rng = SystemRandom::new();
+    let crypto_err = |_| PushError::Crypto("key generation failed".into());
+
+    let mut salt = [0u8; 16];
+    rng.fill(&mut salt).map_err(crypto_err)?;
+
+    let private_key = agreement::EphemeralPrivateKey::generate(&agreement::ECDH_P256, &rng)
+        .map_err(crypto_err)?;
+    let public_key = private_key.compute_public_key().map_err(crypto_err)?;
+    let peer = agreement::UnparsedPublicKey::new(&agreement::ECDH_P256, p256dh);
+    let ecdh_secret = agreement::agree_ephemeral(private_key, &peer, |secret| secret.to_vec())
+        .map_err(|_| PushError::InvalidKey("p256dh is not a valid P-256 point".into()))?;
+
+    let (cek, nonce) = derive_key_and_nonce(&ecdh_secret, auth, p256dh, public_key.as_ref(), &salt);
+
+    // A single record ends with the 0x02 delimiter and carries no padding
+    let mut record = Vec::with_capacity(plaintext.len() + 1 + aead::AES_128_GCM.tag_len());
+    record.extend_from_slice(plaintext);
+    record.push(0x02);
+
+    let key = aead::UnboundKey::new(&aead::AES_128_GCM, &cek)
+        .map_err(|_| PushError::Crypto("invalid content key".into()))?;
+    aead::LessSafeKey::new(key)
+        .seal_in_place_append_tag(
+            aead::Nonce::assume_unique_for_key(nonce),
+            aead::Aad::empty(),
+            &mut record,
+        )
+        .map_err(|_| PushError::Crypto("encryption failed".into()))?;
+
+    let mut body = Vec::with_capacity(HEADER_LEN + record.len());
+    body.extend_from_slice(&salt);
+    body.extend_from_slice(&RECORD_SIZE.to_be_bytes());
+    body.push(PUBLIC_KEY_LEN as u8);
+    body.extend_from_slice(public_key.as_ref());
+    body.extend_from_slice(&record);
+    Ok(body)
+}
+
+/// Derive the content encryption key and nonce (RFC 8291 section 3.4).
+fn derive_key_and_nonce(
+    ecdh_secret: &[u8],
+    auth_secret: &[u8],
+    ua_public: &[u8],
+    as_public: &[u8],
+    salt: &[u8],
+) -> ([u8; 16], [u8; 12]) {
+    let prk_key = hkdf::Salt::new(hkdf::HKDF_SHA256, auth_secret).extract(ecdh_secret);
+    let ikm: [u8; 32] = expand(&prk_key, &[b"WebPush: info\0", ua_public, as_public]);
+
+    let prk = hkdf::Salt::new(hkdf::HKDF_SHA256, salt).extract(&ikm);
+    let cek = expand(&prk, &[b"Content-Encoding: aes128gcm\0"]);
+    let nonce = expand(&prk, &[b"Content-Encoding: nonce\0"]);
+    (cek, nonce)
+}
+
+/// HKDF output length for [`expand`].
+struct OutputLen(usize);
+
+impl hkdf::KeyType for OutputLen {
+    fn len(&self) -> usize {
+        self.0
+    }
+}
+
+fn expand<const N: usize>(prk: &hkdf::Prk, info: &[&[u8]]) -> [u8; N] {
+    let mut out = [0u8; N];
+    prk.expand(info, OutputLen(N))
+        .and_then(|okm| okm.fill(&mut out))
+        .expect("HKDF-SHA256 output fits in 255 blocks");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn b64(value: &str) -> Vec<u8> {
+        decode_key(value).unwrap()
+    }
+
+    /// Test vector from RFC 8291 appendix A.
+    #[test]
+    fn test_derive_key_and_nonce_rfc8291() {
+        let (cek, nonce) = derive_key_and_nonce(
+            &b64("kyrL1jIIOHEzg3sM2ZWRHDRB62YACZhhSlknJ672kSs"),
+            &b64("BTBZMqHH6r4Tts7J_aSIgg"),
+            &b64(
+                "BCVxsr7N_eNgVRqvHtD0zTZsEc6-VV-JvLexhqUzORcxaOzi6-AYWXvTBHm4bjyPjs7Vd8pZGH6SRpkNtoIAiw4",
+            ),
+            &b64(
+                "BP4z9KsN6nGRTbVYI_c7VJSPQTBtkgcy27mlmlMoZIIgDll6e3vCYLocInmYWAmS6TlzAC8wEqKK6PBru3jl7A8",
+            ),
+            &b64("DGv6ra1nlYgDCS1FRnbzlw"),
+        );
+        assert_eq!(cek.to_vec(), b64("oIhVW04MRdy2XN9CiKLxTg"));
+        assert_eq!(nonce.to_vec(), b64("4h_95klXJ5E_qnoN"));
+    }
+
+    #[test]
+    fn test_encrypt_round_trip() {
+        let rng = SystemRandom::new();
+        let ua_private =
+            agreement::EphemeralPrivateKey::generate(&agreement::ECDH_P256, &rng).unwrap();
+        let ua_public = ua_private.compute_public_key().unwrap();
+        let auth = [7u8; 16];
+        let plaintext = b"When I grow up, I want to be a watermelon";
+
+        let body = encrypt(ua_public.as_ref(), &auth, plaintext).unwrap();
+        assert_eq!(body.len(), HEADER_LEN + plaintext.len() + 1 + 16);
+        assert_eq!(&body[16..20], &RECORD_SIZE.to_be_bytes());
+        assert_eq!(body[20] as usize, PUBLIC_KEY_LEN);
+
+        let (salt, rest) = body.split_at(16);
+        let as_public = &rest[5..5 + PUBLIC_KEY_LEN];
+        let peer = agreement::UnparsedPublicKey::new(&agreement::ECDH_P256, as_public);
+        let ecdh_secret =
+            agreement::agree_ephemeral(ua_private, &peer, |secret| secret.to_vec()).unwrap();
+        let (cek, nonce) =
+            derive_key_and_nonce(&ecdh_secret, &auth, ua_public.as_ref(), as_public, salt);
+
+        let key = aead::LessSafeKey::new(aead::UnboundKey::new(&aead::AES_128_GCM, &cek).unwrap());
+        let mut record = body[HEADER_LEN..].to_vec();
+        let opened = key
+            .open_in_place(
+                aead::Nonce::assume_unique_for_key(nonce),
+                aead::Aad::empty(),
+                &mut record,
+            )
+            .unwrap();
+        assert_eq!(opened.split_last(), Some((&0x02, &plaintext[..])));
+    }
+
+    #[test]
+    fn test_encrypt_rejects_bad_input() {
+        let key = [4u8; PUBLIC_KEY_LEN];
+        assert!(matches!(encrypt(&key[..33], &[0; 16], b"hi"), Err(PushError::InvalidKey(_))));
+        assert!(matches!(encrypt(&key, &[0; 8], b"hi"), Err(PushError::InvalidKey(_))));
+        assert!(matches!(
+            encrypt(&key, &[0; 16], &[0; MAX_PAYLOAD_LEN + 1]),
+            Err(PushError::PayloadTooLarge(_))
+        ));
+    }
+
+    #[test]
+    fn test_decode_key_accepts_padding() {
+        assert_eq!(decode_key("BTBZMqHH6r4Tts7J_aSIgg==").unwrap().len(), 16);
+        assert!(decode_key("not base64!").is_err());
+    }
+}
@@ -0,0 +1,81 @@
+//! Error types for push delivery.
+
+use thiserror::Error;
+
+/// Errors that can occur while building or sending a push notification.
+#[derive(Debug, Error)]
+pub enum PushError {
+    /// VAPID key or subscription key could not be decoded or used
+    #[error("Invalid key: {0}")]
+    InvalidKey(String),
+
+    /// Subscription endpoint is not an allowed push service URL
+    #[error("Invalid endpoint: {0}")]
+    InvalidEndpoint(String),
+
+    /// Payload is larger than push services accept
+    #[error("Payload too large ({0} bytes)")]
+    PayloadTooLarge(usize),
+
+    /// Encryption or signing failed
+    #[error("Crypto error: {0}")]
+    Crypto(String),
+
+    /// Network failure talking to the push service
+    #[error("Transport error: {0}")]
+    Transport(String),
+
+    /// Push service answered with an error status
+    #[error("Push service rejected message ({status}): {body}")]
+    Rejected { status: u16, body: String },
+
+    /// Push is misconfigured
+    #[error("Configuration error: {0}")]
+    Config(String),
+
+    /// Outbox query failed
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+impl PushError {
+    /// Whether the subscription no longer exists and should be deleted.
+    pub fn is_gone(&self) -> bool {
+        matches!(self, PushError::Rejected { status: 404 | 410, .. })
+    }
+
+    /// Whether retrying the same notification can never succeed.
+    pub fn is_permanent(&self) -> bool {
+        match self {
+            PushError::InvalidKey(_)
+            | PushError::InvalidEndpoint(_)
+            | PushError::PayloadTooLarge(_)
+            | PushError::Crypto(_)
+            | PushError::Config(_) => true,
+            PushError::Rejected { status, .. } => {
+                (400..500).contains(status) && *status != 408 && *status != 429
+            }
+            PushError::Transport(_) | PushError::Database(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gone_subscriptions() {
+        assert!(PushError::Rejected { status: 410, body: String::new() }.is_gone());
+        assert!(PushError::Rejected { status: 404, body: String::new() }.is_gone());
+        assert!(!PushError::Rejected { status: 400, body: String::new() }.is_gone());
+    }
+
+    #[test]
+    fn test_retryable_errors() {
+        assert!(!PushError::Transport("timeout".into()).is_permanent());
+        assert!(!PushError::Rejected { status: 429, body: String::new() }.is_permanent());
+        assert!(!PushError::Rejected { status: 503, body: String::new() }.is_permanent());
+        assert!(PushError::Rejected { status: 413, body: String::new() }.is_permanent());
+    }
+}
@@ -0,0 +1,24 @@
+//! Web Push notifications for DGuesser.
+//!
+//! This crate provides:
+//! - VAPID configuration and a [`WebPushClient`] that encrypts payloads
+//!   (RFC 8291) and signs requests (RFC 8292)
+//! - [`Notification`]s for challenge turns, friend invites and the daily
+//!   challenge, filtered by each user's notification preferences
+//! - A Postgres-backed outbox with a retrying background delivery worker
+//!
+//! Callers [`queue::notify`] a user; the worker started by
+//! [`queue::spawn_delivery_worker`] sends to each of their subscriptions.
+
+pub mod client;
+pub mod config;
+pub mod crypto;
+pub mod error;
+pub mod notification;
+pub mod queue;
+pub mod vapid;
+
+pub use client::WebPushClient;
+pub use config::PushConfig;
+pub use error::PushError;
+pub use notification::{Notification, NotificationKind};
@@ -0,0 +1,138 @@
+//! Notification kinds and their payloads.
+
+use serde::{Deserialize, Serialize};
+
+use crate::client::Urgency;
+
+/// What a notification is about. Each kind has its own user preference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    /// It is your turn in an asynchronous challenge
+    ChallengeTurn,
+    /// A friend invited you to their party or game lobby
+    FriendInvite,
+    /// A new daily challenge is available
+    DailyChallenge,
+}
+
+impl NotificationKind {
+    /// Stored outbox kind (matches the preference lookup in `dguesser_db::push`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationKind::ChallengeTurn => "challenge_turn",
+            NotificationKind::FriendInvite => "friend_invite",
+            NotificationKind::DailyChallenge => "daily_challenge",
+        }
+    }
+
+    /// Parse a stored outbox kind.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "challenge_turn" => Some(NotificationKind::ChallengeTurn),
+            "friend_invite" => Some(NotificationKind::FriendInvite),
+            "daily_challenge" => Some(NotificationKind::DailyChallenge),
+            _ => None,
+        }
+    }
+
+    /// How long the notification stays worth delivering (seconds).
+    pub fn ttl_secs(&self) -> i32 {
+        match self {
+            NotificationKind::ChallengeTurn => 24 * 60 * 60,
+            // Lobbies fill up or start quickly
+            NotificationKind::FriendInvite => 10 * 60,
+            NotificationKind::DailyChallenge => 12 * 60 * 60,
+        }
+    }
+
+    pub fn urgency(&self) -> Urgency {
+        match self {
+            NotificationKind::FriendInvite => Urgency::High,
+            NotificationKind::ChallengeTurn | NotificationKind::DailyChallenge => Urgency::Normal,
+        }
+    }
+}
+
+/// Payload the service worker turns into a system notification.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Notification {
+    pub kind: NotificationKind,
+    pub title: String,
+    pub body: String,
+    /// Frontend path opened when the notification is clicked
+    pub url: String,
+    /// Notifications with the same tag replace each other
+    pub tag: String,
+}
+
+impl Notification {
+    /// It is your turn in an asynchronous challenge against `opponent`.
+    pub fn challenge_turn(opponent: &str, game_id: &str) -> Self {
+        Self {
+            kind: NotificationKind::ChallengeTurn,
+            title: "Your turn".to_string(),
+            body: format!("{opponent} is waiting for you in your challenge."),
+            url: format!("/game/{game_id}"),
+            tag: format!("challenge:{game_id}"),
+        }
+    }
+
+    /// `opponent` finished your challenge and the results are in.
+    pub fn challenge_results(opponent: &str, game_id: &str) -> Self {
+        Self {
+            kind: NotificationKind::ChallengeTurn,
+            title: "Challenge results".to_string(),
+            body: format!("{opponent} finished your challenge. See who won!"),
+            url: format!("/game/{game_id}"),
+            tag: format!("challenge:{game_id}"),
+        }
+    }
+
+    /// `from` invited you to a lobby; `lobby_kind` is `party` or `game`.
+    pub fn friend_invite(from: &str, lobby_kind: &str, lobby_id: &str, join_code: &str) -> Self {
+        Self {
+            kind: NotificationKind::FriendInvite,
+            title: format!("{from} invited you to play"),
+            body: format!("Join their {lobby_kind} with code {join_code}."),
+            url: format!("/{lobby_kind}/{lobby_id}"),
+            tag: format!("invite:{lobby_id}"),
+        }
+    }
+
+    /// Today's daily challenge is available.
+    pub fn daily_challenge() -> Self {
+        Self {
+            kind: NotificationKind::DailyChallenge,
+            title: "Daily challenge".to_string(),
+            body: "Today's challenge is ready. How close can you get?".to_string(),
+            url: "/play".to_string(),
+            tag: "daily-challenge".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kind_round_trips() {
+        for kind in [
+            NotificationKind::ChallengeTurn,
+            NotificationKind::FriendInvite,
+            NotificationKind::DailyChallenge,
+        ] {
+            assert_eq!(NotificationKind::parse(kind.as_str()), Some(kind));
+            assert_eq!(serde_json::to_value(kind).unwrap(), kind.as_str());
+        }
+        assert_eq!(NotificationKind::parse("spam"), None);
+    }
+
+    #[test]
+    fn test_friend_invite_links_to_lobby() {
+        let n = Notification::friend_invite("Ada", "party", "pty_abc", "XK42QP");
+        assert_eq!(n.url, "/party/pty_abc");
+        assert_eq!(n.body, "Join their party with code XK42QP.");
+    }
+}
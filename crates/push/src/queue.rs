@@ -0,0 +1,187 @@
+//! Postgres-backed push outbox and delivery worker.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use dguesser_db::DbPool;
+use dguesser_db::push::{self, QueuedPush};
+
+use crate::client::{Subscription, Urgency, WebPushClient};
+use crate::error::PushError;
+use crate::notification::{Notification, NotificationKind};
+
+/// How often the worker polls for due notifications.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Maximum notifications claimed per poll.
+const BATCH_SIZE: i64 = 50;
+
+/// First retry delay; doubles on every further attempt.
+const BASE_RETRY_SECS: i64 = 15;
+
+/// Upper bound for the retry delay.
+const MAX_RETRY_SECS: i64 = 60 * 60;
+
+/// Queue a notification for all of a user's subscriptions, unless the user
+/// turned this kind off. Returns how many deliveries were queued.
+pub async fn notify(
+    pool: &DbPool,
+    user_id: &str,
+    notification: &Notification,
+) -> Result<u64, PushError> {
+    let payload = serde_json::to_value(notification).unwrap_or_default();
+    let queued = push::enqueue_for_user(
+        pool,
+        user_id,
+        notification.kind.as_str(),
+        &payload,
+        notification.kind.ttl_secs(),
+    )
+    .await?;
+
+    if queued > 0 {
+        tracing::debug!(user_id = %user_id, kind = notification.kind.as_str(), queued, "Queued push");
+    }
+    Ok(queued)
+}
+
+/// Queue a notification for every subscribed user who allows its kind.
+pub async fn broadcast(pool: &DbPool, notification: &Notification) -> Result<u64, PushError> {
+    let payload = serde_json::to_value(notification).unwrap_or_default();
+    let queued = push::enqueue_for_all(
+        pool,
+        notification.kind.as_str(),
+        &payload,
+        notification.kind.ttl_secs(),
+    )
+    .await?;
+
+    tracing::info!(kind = notification.kind.as_str(), queued, "Queued push broadcast");
+    Ok(queued)
+}
+
+/// Delay before retrying after `attempts` failed attempts.
+pub fn retry_delay(attempts: i32) -> chrono::Duration {
+    let exponent = attempts.saturating_sub(1).clamp(0, 20) as u32;
+    let secs = BASE_RETRY_SECS.saturating_mul(1 << exponent).min(MAX_RETRY_SECS);
+    chrono::Duration::seconds(secs)
+}
+
+/// Claim and send one batch of due notifications. Returns how many were claimed.
+pub async fn deliver_due(pool: &DbPool, client: &WebPushClient) -> Result<usize, PushError> {
+    let batch = push::claim_due(pool, BATCH_SIZE).await?;
+    let claimed = batch.len();
+
+    for queued in batch {
+        deliver_one(pool, client, queued).await?;
+    }
+
+    Ok(claimed)
+}
+
+async fn deliver_one(
+    pool: &DbPool,
+    client: &WebPushClient,
+    queued: QueuedPush,
+) -> Result<(), PushError> {
+    // Nothing is gained by showing a stale notification
+    let age_secs = (Utc::now() - queued.created_at).num_seconds();
+    let remaining_ttl = i64::from(queued.ttl_secs) - age_secs;
+    if remaining_ttl <= 0 {
+        push::mark_failed(pool, queued.id, "expired", None).await?;
+        return Ok(());
+    }
+
+    let urgency =
+        NotificationKind::parse(&queued.kind).map(|k| k.urgency()).unwrap_or(Urgency::Normal);
+    let subscription =
+        Subscription { endpoint: &queued.endpoint, p256dh: &queued.p256dh, auth: &queued.auth };
+    let payload = queued.payload.to_string();
+
+    match client.send(subscription, payload.as_bytes(), remaining_ttl as u32, urgency).await {
+        Ok(()) => {
+            push::mark_sent(pool, queued.id, &queued.subscription_id).await?;
+            tracing::debug!(push_id = queued.id, kind = %queued.kind, "Push delivered");
+        }
+        Err(err) if err.is_gone() => {
+            // Deleting the subscription also drops its queued notifications
+            push::delete_subscription_by_id(pool, &queued.subscription_id).await?;
+            tracing::info!(
+                subscription_id = %queued.subscription_id,
+                "Push subscription expired, removed"
+            );
+        }
+        Err(err) => {
+            let give_up = err.is_permanent() || queued.attempts >= queued.max_attempts;
+            let retry_at = (!give_up).then(|| Utc::now() + retry_delay(queued.attempts));
+            push::mark_failed(pool, queued.id, &err.to_string(), retry_at).await?;
+
+            if give_up {
+                tracing::warn!(
+                    push_id = queued.id,
+                    kind = %queued.kind,
+                    attempts = queued.attempts,
+                    error = %err,
+                    "Push delivery failed permanently"
+                );
+            } else {
+                tracing::debug!(
+                    push_id = queued.id,
+                    kind = %queued.kind,
+                    attempts = queued.attempts,
+                    error = %err,
+                    "Push delivery failed, will retry"
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Spawn a background task that delivers queued notifications.
+///
+/// Safe to run on several instances at once: rows are claimed with
+/// `FOR UPDATE SKIP LOCKED`.
+pub fn spawn_delivery_worker(pool: DbPool, client: Arc<WebPushClient>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            // Drain the backlog before sleeping again
+            loop {
+                match deliver_due(&pool, &client).await {
+                    Ok(claimed) if claimed as i64 == BATCH_SIZE => continue,
+                    Ok(_) => break,
+                    Err(e) => {
+                        tracing::error!(error = %e, "Push delivery worker error");
+                        break;
+                    }
+                }
+            }
+        }
+    });
+
+    tracing::info!("Push delivery worker started");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_backs_off() {
+        assert_eq!(retry_delay(1).num_seconds(), 15);
+        assert_eq!(retry_delay(2).num_seconds(), 30);
+        assert_eq!(retry_delay(3).num_seconds(), 60);
+    }
+
+    #[test]
+    fn test_retry_delay_is_capped() {
+        assert_eq!(retry_delay(30).num_seconds(), MAX_RETRY_SECS);
+    }
+}
@@ -0,0 +1,139 @@
+//! VAPID request signing (RFC 8292).
+//!
+//! Each request carries a short-lived ES256 JWT for the push service's
+//! origin, signed with the application server key that browsers were given
+//! when they subscribed.
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use chrono::Utc;
+use ring::rand::SystemRandom;
+use ring::signature::{ECDSA_P256_SHA256_FIXED_SIGNING, EcdsaKeyPair};
+use serde::Serialize;
+
+use crate::crypto::decode_key;
+use crate::error::PushError;
+
+/// Lifetime of a VAPID token (RFC 8292 allows at most 24 hours).
+const TOKEN_TTL_SECS: i64 = 12 * 60 * 60;
+
+/// Base64url of the fixed JWT header `{"typ":"JWT","alg":"ES256"}`.
+const JWT_HEADER: &str = "eyJ0eXAiOiJKV1QiLCJhbGciOiJFUzI1NiJ9";
+
+#[derive(Serialize)]
+struct Claims<'a> {
+    aud: &'a str,
+    exp: i64,
+    sub: &'a str,
+}
+
+/// Signs VAPID `Authorization` headers with the application server key.
+pub struct VapidSigner {
+    key_pair: EcdsaKeyPair,
+    public_key: String,
+    subject: String,
+    rng: SystemRandom,
+}
+
+impl VapidSigner {
+    /// Create a signer from base64url keys, as printed by
+    /// `npx web-push generate-vapid-keys`.
+    ///
+    /// `subject` is a `mailto:` or `https:` contact for the push services.
+    pub fn new(public_key: &str, private_key: &str, subject: &str) -> Result<Self, PushError> {
+        let rng = SystemRandom::new();
+        let key_pair = EcdsaKeyPair::from_private_key_and_public_key(
+            &ECDSA_P256_SHA256_FIXED_SIGNING,
+            &decode_key(private_key)?,
+            &decode_key(public_key)?,
+            &rng,
+        )
+        .map_err(|e| PushError::InvalidKey(format!("VAPID key pair rejected: {e}")))?;
+
+        Ok(Self {
+            key_pair,
+            public_key: public_key.trim_end_matches('=').to_string(),
+            subject: subject.to_string(),
+            rng,
+        })
+    }
+
+    /// Public key browsers pass as `applicationServerKey` (base64url).
+    pub fn public_key(&self) -> &str {
+        &self.public_key
+    }
+
+    /// `Authorization` header value for a request to `endpoint`.
+    pub fn authorization(&self, endpoint: &str) -> Result<String, PushError> {
+        let audience = audience(endpoint)?;
+        let claims = Claims {
+            aud: &audience,
+            exp: Utc::now().timestamp() + TOKEN_TTL_SECS,
+            sub: &self.subject,
+        };
+        let claims = serde_json::to_vec(&claims).map_err(|e| PushError::Crypto(e.to_string()))?;
+        let signing_input = format!("{JWT_HEADER}.{}", URL_SAFE_NO_PAD.encode(claims));
+
+        let signature = self
+            .key_pair
+            .sign(&self.rng, signing_input.as_bytes())
+            .map_err(|_| PushError::Crypto("VAPID signing failed".into()))?;
+
+        Ok(format!(
+            "vapid t={signing_input}.{}, k={}",
+            URL_SAFE_NO_PAD.encode(signature.as_ref()),
+            self.public_key
+        ))
+    }
+}
+
+/// The push service origin a token is valid for.
+fn audience(endpoint: &str) -> Result<String, PushError> {
+    let url =
+        reqwest::Url::parse(endpoint).map_err(|e| PushError::InvalidEndpoint(e.to_string()))?;
+    Ok(url.origin().ascii_serialization())
+}
+
+#[cfg(test)]
+mod tests {
+    use ring::signature::{ECDSA_P256_SHA256_FIXED, UnparsedPublicKey};
+
+    use super::*;
+
+    // Application server key pair from RFC 8291 appendix A
+    const PUBLIC_KEY: &str =
+        "BP4z9KsN6nGRTbVYI_c7VJSPQTBtkgcy27mlmlMoZIIgDll6e3vCYLocInmYWAmS6TlzAC8wEqKK6PBru3jl7A8";
+    const PRIVATE_KEY: &str = "yfWPiYE-n46HLnH0KqZOF1fJJU3MYrct3AELtAQ-oRw";
+
+    #[test]
+    fn test_jwt_header() {
+        assert_eq!(URL_SAFE_NO_PAD.encode(r#"{"typ":"JWT","alg":"ES256"}"#), JWT_HEADER);
+    }
+
+    #[test]
+    fn test_authorization_is_verifiable() {
+        let signer = VapidSigner::new(PUBLIC_KEY, PRIVATE_KEY, "mailto:ops@dguesser.lol").unwrap();
+        let header = signer.authorization("https://fcm.googleapis.com/fcm/send/abc").unwrap();
+
+        let (token, key) =
+            header.strip_prefix("vapid t=").and_then(|rest| rest.split_once(", k=")).unwrap();
+        assert_eq!(key, PUBLIC_KEY);
+
+        let (signing_input, signature) = token.rsplit_once('.').unwrap();
+        UnparsedPublicKey::new(&ECDSA_P256_SHA256_FIXED, decode_key(PUBLIC_KEY).unwrap())
+            .verify(signing_input.as_bytes(), &decode_key(signature).unwrap())
+            .unwrap();
+
+        let claims = signing_input.split_once('.').unwrap().1;
+        let claims: serde_json::Value =
+            serde_json::from_slice(&decode_key(claims).unwrap()).unwrap();
+        assert_eq!(claims["aud"], "https://fcm.googleapis.com");
+        assert_eq!(claims["sub"], "mailto:ops@dguesser.lol");
+    }
+
+    #[test]
+    fn test_rejects_mismatched_keys() {
+        let other_public = "BCVxsr7N_eNgVRqvHtD0zTZsEc6-VV-JvLexhqUzORcxaOzi6-AYWXvTBHm4bjyPjs7Vd8pZGH6SRpkNtoIAiw4";
+        assert!(VapidSigner::new(other_public, PRIVATE_KEY, "mailto:x@y.z").is_err());
+    }
+}
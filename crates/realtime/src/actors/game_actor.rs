@@ -8,26 +8,36 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
 
 use chrono::Utc;
 use dguesser_core::game::{
-    self, GameCommand as CoreCommand, GameEvent, GamePhase, GameState, LocationData, PlayerState,
-    RoundState, reduce,
+    self, CheatSignalKind, GameCommand as CoreCommand, GameEvent, GamePhase, GameState,
+    LocationData, LocationGuessStats, PlayerState, RoundState, reduce,
 };
-use dguesser_core::location::LocationProvider;
+use dguesser_core::location::{LocationError, LocationProvider};
 use dguesser_db::DbPool;
+use dguesser_db::games::NewGuess;
+use dguesser_locations::RecentLocations;
 use dguesser_protocol::socket::events;
 use dguesser_protocol::socket::payloads::{
-    FinalStanding, GameAbandonedPayload, GameEndPayload, GameSettingsPayload, GameStatePayload,
-    GameTransitionClearedPayload, GameTransitioningPayload, PlayerDisconnectedPayload,
-    PlayerGuessedPayload, PlayerInfo, PlayerJoinedPayload, PlayerLeftPayload,
-    PlayerReconnectedPayload, PlayerScoreInfo, PlayerTimeoutPayload, RoundEndPayload,
-    RoundLocation, RoundResult, RoundStartPayload, ScoresUpdatePayload, SettingsUpdatedPayload,
-    TransitionPhase,
+    ClassroomGuessPayload, FinalStanding, GameAbandonedPayload, GameEndPayload, GamePausedPayload,
+    GameResumedPayload, GameSettingsPayload, GameStatePayload, GameTransitionClearedPayload,
+    GameTransitioningPayload, GlobalGuessStats, HandicapUpdatedPayload, HintRevealedPayload,
+    PlayerDisconnectedPayload, PlayerGuessedPayload, PlayerInfo, PlayerJoinedPayload,
+    PlayerLeftPayload, PlayerReconnectedPayload, PlayerScoreInfo, PlayerTimeoutPayload,
+    ReactionPayload, RoundEndPayload, RoundLocation, RoundRerolledPayload, RoundResult,
+    RoundStartPayload, ScoresUpdatePayload, SettingsUpdatedPayload, TransitionPhase,
 };
+use dguesser_protocol::socket::presence::{PresenceActivity, PresenceStatus};
 use tokio::sync::mpsc;
 
+use super::guess_writes::GuessWriteBuffer;
+use super::latency::RttWindow;
+use super::rounds_plan_payload;
+use super::snapshot::GameSnapshot;
 use crate::emitter::BroadcastEmitter;
+use crate::presence::PresenceTracker;
 use crate::redis_state::{
     CachedGameState, CachedGuess, CachedPlayerState, CachedRoundState, RedisStateManager,
 };
@@ -55,6 +65,8 @@ pub struct GameActor {
     last_redis_save: Option<std::time::Instant>,
     /// Location provider for selecting game locations
     location_provider: Arc<dyn LocationProvider>,
+    /// Recently served locations per player, for repeat protection
+    recent_locations: Option<RecentLocations>,
     /// Channel to signal the AppState to remove this game from the HashMap
     cleanup_tx: Option<mpsc::Sender<String>>,
     /// Channel to notify a party when this game ends (party_id, game_id)
@@ -63,6 +75,16 @@ pub struct GameActor {
     /// yet superseded by `round:start` / `game:end`. Used to emit a compensating
     /// `game:transition_cleared` event if the follow-up work fails.
     pending_transition: Option<TransitionPhase>,
+    /// Records what players are doing for their friends
+    presence: Option<PresenceTracker>,
+    /// Name of the current map, cached by map ID for presence updates
+    map_name: Option<(String, Option<String>)>,
+    /// Guess writes waiting for the next batch
+    guess_writes: GuessWriteBuffer,
+    /// Recent round-trip times per player (user_id -> samples)
+    rtt: HashMap<String, RttWindow>,
+    /// Snapshot to start from instead of loading the game
+    initial_snapshot: Option<GameSnapshot>,
 }
 
 impl GameActor {
@@ -84,9 +106,15 @@ impl GameActor {
             redis_state: None,
             last_redis_save: None,
             location_provider,
+            recent_locations: None,
             cleanup_tx: None,
             party_notify_tx: None,
             pending_transition: None,
+            presence: None,
+            map_name: None,
+            guess_writes: GuessWriteBuffer::default(),
+            rtt: HashMap::new(),
+            initial_snapshot: None,
         }
     }
 
@@ -95,6 +123,11 @@ impl GameActor {
         self
     }
 
+    pub fn with_recent_locations(mut self, recent_locations: Option<RecentLocations>) -> Self {
+        self.recent_locations = recent_locations;
+        self
+    }
+
     pub fn with_cleanup(mut self, cleanup_tx: mpsc::Sender<String>) -> Self {
         self.cleanup_tx = Some(cleanup_tx);
         self
@@ -105,10 +138,23 @@ impl GameActor {
         self
     }
 
+    pub fn with_presence(mut self, presence: PresenceTracker) -> Self {
+        self.presence = Some(presence);
+        self
+    }
+
+    /// Start from an exported snapshot instead of the cached or stored game.
+    pub fn with_snapshot(mut self, snapshot: Option<GameSnapshot>) -> Self {
+        self.initial_snapshot = snapshot;
+        self
+    }
+
     /// Main run loop - processes commands from the channel
     pub async fn run(&mut self) {
-        // Load initial state from database
-        if let Err(e) = self.load_state().await {
+        // Load initial state from a snapshot, Redis or the database
+        if let Some(snapshot) = self.initial_snapshot.take() {
+            self.restore_snapshot(snapshot).await;
+        } else if let Err(e) = self.load_state().await {
             tracing::error!("Failed to load game state for {}: {}", self.game_id, e);
             return;
         }
@@ -129,8 +175,8 @@ impl GameActor {
                     let result = self.handle_start(&user_id).await;
                     let _ = respond.send(result);
                 }
-                GameCommand::Guess { user_id, lat, lng, time_ms, respond } => {
-                    let result = self.handle_guess(&user_id, lat, lng, time_ms).await;
+                GameCommand::Guess { user_id, lat, lng, time_ms, panorama_id, respond } => {
+                    let result = self.handle_guess(&user_id, lat, lng, time_ms, panorama_id).await;
                     let _ = respond.send(result);
                 }
                 GameCommand::Reconnect { user_id, socket_id } => {
@@ -140,6 +186,10 @@ impl GameActor {
                     let result = self.handle_update_settings(&user_id, settings).await;
                     let _ = respond.send(result);
                 }
+                GameCommand::SetHandicap { user_id, player_id, handicap, respond } => {
+                    let result = self.handle_set_handicap(&user_id, &player_id, handicap).await;
+                    let _ = respond.send(result);
+                }
                 GameCommand::SkipWait { user_id, respond } => {
                     let result = self.handle_skip_wait(&user_id).await;
                     let _ = respond.send(result);
@@ -148,6 +198,33 @@ impl GameActor {
                     let result = self.handle_vote_skip(&user_id).await;
                     let _ = respond.send(result);
                 }
+                GameCommand::React { user_id, reaction, respond } => {
+                    let result = self.handle_react(&user_id, reaction).await;
+                    let _ = respond.send(result);
+                }
+                GameCommand::RecordRtt { user_id, rtt_ms } => {
+                    self.handle_record_rtt(&user_id, rtt_ms).await;
+                }
+                GameCommand::DumpState { respond } => {
+                    let result = self.handle_dump_state().await;
+                    let _ = respond.send(result);
+                }
+                GameCommand::Reroll { user_id, respond } => {
+                    let result = self.handle_reroll(&user_id).await;
+                    let _ = respond.send(result);
+                }
+                GameCommand::Pause { user_id, respond } => {
+                    let result = self.handle_pause(&user_id).await;
+                    let _ = respond.send(result);
+                }
+                GameCommand::Resume { user_id, respond } => {
+                    let result = self.handle_resume(&user_id).await;
+                    let _ = respond.send(result);
+                }
+                GameCommand::RequestHint { user_id, kind, from, respond } => {
+                    let result = self.handle_request_hint(&user_id, kind, from).await;
+                    let _ = respond.send(result);
+                }
                 GameCommand::Tick => {
                     self.handle_tick().await;
                 }
@@ -157,6 +234,8 @@ impl GameActor {
             }
         }
 
+        self.guess_writes.flush_until_written(&self.db, &self.game_id).await;
+
         tracing::info!("Game actor {} shutting down", self.game_id);
     }
 
@@ -172,6 +251,12 @@ impl GameActor {
         {
             tracing::info!(game_id = %self.game_id, "Loaded game state from Redis cache");
             self.state = Some(Self::from_cached_state(&cached));
+            self.rtt = cached
+                .players
+                .iter()
+                .filter(|(_, p)| !p.rtt_samples.is_empty())
+                .map(|(uid, p)| (uid.clone(), RttWindow::from_samples(&p.rtt_samples)))
+                .collect();
             self.current_round_db_id = cached.current_round.map(|r| r.round_id);
             return Ok(());
         }
@@ -180,6 +265,25 @@ impl GameActor {
         self.load_state_from_db().await
     }
 
+    /// Take over an exported snapshot and cache it, so players reconnecting
+    /// to this server see the restored game.
+    async fn restore_snapshot(&mut self, snapshot: GameSnapshot) {
+        tracing::info!(
+            game_id = %self.game_id,
+            taken_at = %snapshot.taken_at,
+            "Restored game state from snapshot"
+        );
+        self.state = Some(snapshot.state);
+        self.socket_ids = snapshot.socket_ids;
+        self.current_round_db_id = snapshot.current_round_db_id;
+        self.rtt = snapshot
+            .rtt_samples
+            .iter()
+            .map(|(uid, samples)| (uid.clone(), RttWindow::from_samples(samples)))
+            .collect();
+        self.force_save_state_to_redis().await;
+    }
+
     /// Load game state from database
     async fn load_state_from_db(&mut self) -> Result<(), String> {
         let db_game = dguesser_db::games::get_game_by_id(&self.db, &self.game_id)
@@ -202,16 +306,20 @@ impl GameActor {
         };
 
         // Build player states
+        let users =
+            dguesser_db::UserLoader::load(&self.db, db_players.iter().map(|p| p.user_id.as_str()))
+                .await
+                .unwrap_or_default();
         let mut players = HashMap::new();
         for p in db_players {
-            let user = dguesser_db::users::get_by_id(&self.db, &p.user_id).await.ok().flatten();
+            let user = users.get(&p.user_id);
 
             players.insert(
                 p.user_id.clone(),
                 PlayerState::new(
                     p.user_id.clone(),
-                    user.as_ref().map(|u| u.display_name.clone()).unwrap_or_default(),
-                    user.as_ref().and_then(|u| u.avatar_url.clone()),
+                    user.map(|u| u.display_name.clone()).unwrap_or_default(),
+                    user.and_then(|u| u.avatar_url.clone()),
                     p.is_host,
                 ),
             );
@@ -219,6 +327,7 @@ impl GameActor {
             // Update total score from DB
             if let Some(player) = players.get_mut(&p.user_id) {
                 player.total_score = p.score_total as u32;
+                player.handicap = p.handicap;
             }
         }
 
@@ -227,12 +336,25 @@ impl GameActor {
             .await
             .unwrap_or_default();
         let round_number = rounds.len() as u8;
+        let rerolls_used = dguesser_db::round_rerolls::count_for_game(&self.db, &self.game_id)
+            .await
+            .unwrap_or_default();
 
         // Build core state
         let mut state = GameState::new(self.game_id.clone(), settings);
         state.phase = phase;
         state.players = players;
         state.round_number = round_number;
+        state.rerolls_used = rerolls_used.try_into().unwrap_or(u8::MAX);
+        state.started_at = db_game.started_at;
+        if let Some(started_at) = db_game.started_at
+            && state.settings.is_time_attack()
+        {
+            state.time_budget_ends_at = Some(
+                started_at.timestamp_millis()
+                    + i64::from(state.settings.time_budget_seconds) * 1000,
+            );
+        }
 
         self.state = Some(state);
         Ok(())
@@ -263,6 +385,8 @@ impl GameActor {
                     p.is_host,
                 );
                 player.total_score = p.total_score;
+                player.handicap = p.handicap;
+                player.joined_round = p.joined_round;
                 player.connected = false; // All players need to reconnect after restart
                 player.disconnected_at = p
                     .disconnect_time_ms
@@ -279,6 +403,7 @@ impl GameActor {
                 r.panorama_id.clone(),
                 r.location_id.clone(),
                 r.heading,
+                r.provider,
                 r.time_limit_ms,
                 chrono::DateTime::from_timestamp_millis(r.started_at_ms).unwrap_or_else(Utc::now),
             );
@@ -292,10 +417,13 @@ impl GameActor {
                         distance_meters: g.distance,
                         score: g.score,
                         time_taken_ms: None,
+                        reported_panorama_id: None,
+                        country_code: None,
                         submitted_at: Utc::now(), // Approximate
                     },
                 );
             }
+            round.hints = r.hints.clone();
             round
         });
 
@@ -306,6 +434,10 @@ impl GameActor {
         state.round_number = cached.round_number;
         state.between_rounds_ends_at = cached.between_rounds_ends_at;
         state.skip_votes = cached.skip_votes.iter().cloned().collect();
+        state.time_budget_ends_at = cached.time_budget_ends_at;
+        state.paused_at = cached.paused_at_ms.and_then(chrono::DateTime::from_timestamp_millis);
+        state.rerolls_used = cached.rerolls_used;
+        state.teacher_paused = cached.teacher_paused;
 
         state
     }
@@ -336,6 +468,10 @@ impl GameActor {
                         total_score: p.total_score,
                         connected: p.connected,
                         disconnect_time_ms: p.disconnected_at.map(|dt| dt.timestamp_millis()),
+                        handicap: p.handicap,
+                        joined_round: p.joined_round,
+                        rtt_ms: self.rtt.get(uid).and_then(RttWindow::median),
+                        rtt_samples: self.rtt.get(uid).map(RttWindow::samples).unwrap_or_default(),
                     },
                 )
             })
@@ -366,9 +502,11 @@ impl GameActor {
                 panorama_id: r.panorama_id.clone(),
                 location_id: r.location_id.clone(),
                 heading: r.heading,
+                provider: r.provider,
                 started_at_ms: r.started_at.timestamp_millis(),
                 time_limit_ms: r.time_limit_ms,
                 guesses,
+                hints: r.hints.clone(),
             }
         });
 
@@ -376,12 +514,16 @@ impl GameActor {
             game_id: state.game_id.clone(),
             status: status.to_string(),
             round_number: state.round_number,
-            total_rounds: state.settings.rounds,
+            total_rounds: state.total_rounds(),
             players,
             current_round,
             settings_json: serde_json::to_string(&state.settings).unwrap_or_default(),
             between_rounds_ends_at: state.between_rounds_ends_at,
             skip_votes: state.skip_votes.iter().cloned().collect(),
+            time_budget_ends_at: state.time_budget_ends_at,
+            paused_at_ms: state.paused_at.map(|dt| dt.timestamp_millis()),
+            rerolls_used: state.rerolls_used,
+            teacher_paused: state.teacher_paused,
         })
     }
 
@@ -445,8 +587,8 @@ impl GameActor {
             return self.handle_existing_player_join(user_id, socket_id).await;
         }
 
-        // New player - must be in lobby
-        if state.phase != GamePhase::Lobby {
+        // New player - must be in lobby, unless the game takes late joiners
+        if state.phase != GamePhase::Lobby && !state.accepts_late_join() {
             return Err("Cannot join game in progress".to_string());
         }
 
@@ -492,6 +634,8 @@ impl GameActor {
         // Save to Redis
         self.save_state_to_redis().await;
 
+        self.update_presence(&[user_id.to_string()]).await;
+
         Ok(())
     }
 
@@ -515,6 +659,7 @@ impl GameActor {
 
         // Send game state to reconnecting player
         self.send_game_state_to_socket(socket_id).await;
+        self.send_classroom_guesses(None).await;
 
         // Broadcast reconnection event if they were disconnected
         if was_disconnected {
@@ -524,6 +669,8 @@ impl GameActor {
         // Save to Redis
         self.save_state_to_redis().await;
 
+        self.update_presence(&[user_id.to_string()]).await;
+
         Ok(())
     }
 
@@ -560,6 +707,8 @@ impl GameActor {
 
         // Save to Redis
         self.save_state_to_redis().await;
+
+        self.clear_presence(&[user_id.to_string()]).await;
     }
 
     /// Handle game start
@@ -581,23 +730,34 @@ impl GameActor {
 
     /// Fallible inner start path; wrapped by `handle_start` for cleanup.
     async fn try_start(&mut self, user_id: &str) -> Result<(), String> {
-        let state = self.state.as_ref().ok_or("Game not initialized")?;
+        let mut state = self.state.clone().ok_or("Game not initialized")?;
         let now = Utc::now();
 
-        // MAP-004: Validate that the map has enough locations for the requested rounds
-        let map_id = &state.settings.map_id;
-        let location_count = self.location_provider.get_location_count(map_id).await.unwrap_or(0);
-        let validation = game::validate_location_count(state.settings.rounds, location_count);
-        if let Some(error_msg) = validation.error_message() {
-            return Err(error_msg);
+        // MAP-004: Validate that each map has enough locations for its rounds
+        for (map_id, rounds) in state.settings.rounds_per_map() {
+            let location_count =
+                self.location_provider.get_location_count(map_id).await.unwrap_or(0);
+            let validation = game::validate_location_count(rounds, location_count);
+            if let Some(error_msg) = validation.error_message() {
+                return Err(error_msg);
+            }
         }
+        let map_id = &state.settings.map_id;
+
+        // The map's scoring curve is fixed for the game when it starts
+        state.settings.scoring = self
+            .location_provider
+            .get_map(map_id)
+            .await
+            .map(|map| map.rules.scoring_config())
+            .unwrap_or_default();
 
         // Select first location
-        let location = self.select_location().await?;
+        let location = self.select_location(1, None).await?;
 
         // Apply start command
         let result = reduce(
-            state,
+            &state,
             CoreCommand::Start { user_id: user_id.to_string(), first_location: location.clone() },
             now,
         );
@@ -620,12 +780,15 @@ impl GameActor {
         .await
         .map_err(|e| e.to_string())?;
 
+        let settings_json = serde_json::to_value(&result.state.settings).unwrap_or_default();
+        if let Err(e) =
+            dguesser_db::games::update_game_settings(&self.db, &self.game_id, settings_json).await
+        {
+            tracing::warn!(error = %e, game_id = %self.game_id, "Failed to persist game scoring");
+        }
+
         // Create round in database
-        let time_limit_ms = if result.state.settings.time_limit_seconds > 0 {
-            Some(result.state.settings.time_limit_seconds * 1000)
-        } else {
-            None
-        };
+        let time_limit_ms = result.state.round_time_limit_ms(now);
 
         let db_round = match dguesser_db::games::create_round(
             &self.db,
@@ -636,6 +799,8 @@ impl GameActor {
             location.panorama_id.as_deref(),
             location.location_id.as_deref(),
             location.heading,
+            location.provider,
+            location.country_code.as_deref(),
             time_limit_ms.map(|t| t as i32),
         )
         .await
@@ -678,6 +843,9 @@ impl GameActor {
         // Save to Redis
         self.force_save_state_to_redis().await;
 
+        // Everyone in the lobby is now in a game
+        self.update_presence(&self.player_ids()).await;
+
         Ok(())
     }
 
@@ -688,6 +856,7 @@ impl GameActor {
         lat: f64,
         lng: f64,
         time_ms: Option<u32>,
+        panorama_id: Option<String>,
     ) -> Result<GuessResult, String> {
         let state = self.state.as_ref().ok_or("Game not initialized")?;
         let now = Utc::now();
@@ -700,64 +869,76 @@ impl GameActor {
                 lat,
                 lng,
                 time_taken_ms: time_ms,
+                reported_panorama_id: panorama_id.clone(),
+                country_code: None,
             },
             now,
         );
 
         if result.has_error() {
+            if result
+                .events
+                .iter()
+                .any(|e| matches!(e, GameEvent::Error { code, .. } if code == "PANORAMA_MISMATCH"))
+            {
+                let expected = state.current_round.as_ref().and_then(|r| r.panorama_id.clone());
+                self.record_panorama_mismatch(user_id, expected, panorama_id).await;
+            }
             return Err(self.extract_error_message(&result));
         }
 
-        // Get guess result from updated state
+        // Get guess result from updated state (a time-attack guess may have
+        // ended the round already)
         let guess = result
             .state
-            .current_round
-            .as_ref()
+            .latest_round()
             .and_then(|r| r.guesses.get(user_id))
             .ok_or("Guess not recorded")?;
 
         let distance = guess.distance_meters;
         let score = guess.score;
 
-        // Persist to database
-        if let Some(round_id) = &self.current_round_db_id
-            && let Err(e) = dguesser_db::games::create_guess(
-                &self.db,
-                round_id,
-                user_id,
-                lat,
-                lng,
-                distance,
-                score as i32,
-                time_ms.map(|t| t as i32),
-            )
-            .await
-        {
-            tracing::error!(error = %e, game_id = %self.game_id, "Failed to persist guess to DB");
-        }
-
-        // Update player score in database
-        if let Err(e) =
-            dguesser_db::games::update_player_score(&self.db, &self.game_id, user_id, score as i32)
-                .await
-        {
-            tracing::error!(error = %e, game_id = %self.game_id, "Failed to update player score in DB");
+        // Queue the writes; they go out in the next batch
+        let points =
+            state.players.get(user_id).map_or(score, |p| game::apply_handicap(score, p.handicap));
+        match &self.current_round_db_id {
+            Some(round_id) => self.guess_writes.push(
+                NewGuess {
+                    round_id: round_id.clone(),
+                    user_id: user_id.to_string(),
+                    guess_lat: lat,
+                    guess_lng: lng,
+                    distance_meters: distance,
+                    score: score as i32,
+                    time_taken_ms: time_ms.map(|t| t as i32),
+                    reported_panorama_id: panorama_id,
+                    submitted_at: now,
+                },
+                points as i32,
+            ),
+            None => self.guess_writes.add_points(user_id, points as i32),
         }
 
         // Check if all connected players have guessed (auto-end round)
         let connected_ids = result.state.connected_player_ids();
         let all_guessed =
             result.state.current_round.as_ref().is_some_and(|r| r.all_guessed(&connected_ids));
+        let round_ended = result.state.phase == GamePhase::BetweenRounds;
 
         // Update state and broadcast
         self.state = Some(result.state);
         self.broadcast_events(&result.events).await;
+        self.send_classroom_guesses(Some(user_id)).await;
 
         // Save to Redis
         self.save_state_to_redis().await;
 
-        // Auto-end round if all guessed
-        if all_guessed {
+        // Time attack ended the round on this guess; move straight on
+        if round_ended {
+            self.handle_round_ended().await;
+            self.advance_or_end_game(None).await;
+        } else if all_guessed {
+            // Auto-end round if all guessed
             tracing::info!("All players guessed in game {}, ending round", self.game_id);
             self.end_current_round().await.ok();
         }
@@ -765,6 +946,35 @@ impl GameActor {
         Ok(GuessResult { distance, score })
     }
 
+    /// Record a guess made away from the round's panorama in a no-move mode.
+    async fn record_panorama_mismatch(
+        &self,
+        user_id: &str,
+        expected: Option<String>,
+        reported: Option<String>,
+    ) {
+        tracing::warn!(
+            user_id,
+            game_id = %self.game_id,
+            ?expected,
+            ?reported,
+            "Rejected guess from wrong panorama"
+        );
+        let details = serde_json::json!({ "expected": expected, "reported": reported });
+        if let Err(e) = dguesser_db::anti_cheat::record_signal(
+            &self.db,
+            user_id,
+            &self.game_id,
+            self.current_round_db_id.as_deref(),
+            CheatSignalKind::PanoramaMismatch,
+            details,
+        )
+        .await
+        {
+            tracing::error!(error = %e, game_id = %self.game_id, "Failed to record cheat signal");
+        }
+    }
+
     /// Handle player reconnecting
     async fn handle_reconnect(&mut self, user_id: &str, socket_id: &str) {
         let Some(state) = self.state.as_ref() else { return };
@@ -788,10 +998,17 @@ impl GameActor {
         } else {
             self.state = Some(result.state);
         }
+        self.send_classroom_guesses(None).await;
+
+        self.update_presence(&[user_id.to_string()]).await;
     }
 
     /// Handle tick - check for timeouts and between-rounds expiry
     async fn handle_tick(&mut self) {
+        if self.guess_writes.is_due(Instant::now()) {
+            self.guess_writes.flush(&self.db, &self.game_id).await;
+        }
+
         let Some(state) = self.state.as_ref() else { return };
         let now = Utc::now();
 
@@ -818,7 +1035,7 @@ impl GameActor {
 
         // If round ended, handle round end logic
         if round_ended {
-            self.handle_round_ended_by_tick().await;
+            self.handle_round_ended().await;
         }
 
         // If between-rounds wait expired, advance to next round or end game
@@ -828,13 +1045,15 @@ impl GameActor {
         }
     }
 
-    /// Handle round ended by tick (timeout or all guessed)
+    /// Handle a round the reducer ended (timeout, all guessed, or a
+    /// time-attack guess)
     ///
     /// Persists the round end to DB and broadcasts results.
     /// The tick-based timer in `between_rounds_ends_at` handles
     /// the automatic advancement after the wait period.
-    async fn handle_round_ended_by_tick(&mut self) {
-        // End round in database
+    async fn handle_round_ended(&mut self) {
+        // End round in database, after its guesses
+        self.guess_writes.flush_until_written(&self.db, &self.game_id).await;
         if let Some(round_id) = &self.current_round_db_id
             && let Err(e) = dguesser_db::games::end_round(&self.db, round_id).await
         {
@@ -851,6 +1070,40 @@ impl GameActor {
         self.force_save_state_to_redis().await;
     }
 
+    /// Handle handicap change (host only, lobby only)
+    async fn handle_set_handicap(
+        &mut self,
+        user_id: &str,
+        player_id: &str,
+        handicap: f64,
+    ) -> Result<(), String> {
+        let state = self.state.as_ref().ok_or("Game not initialized")?;
+
+        let result = reduce(
+            state,
+            CoreCommand::SetHandicap {
+                user_id: user_id.to_string(),
+                player_id: player_id.to_string(),
+                handicap,
+            },
+            Utc::now(),
+        );
+
+        if result.has_error() {
+            return Err(self.extract_error_message(&result));
+        }
+
+        dguesser_db::games::set_player_handicap(&self.db, &self.game_id, player_id, handicap)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        self.state = Some(result.state);
+        self.broadcast_events(&result.events).await;
+        self.save_state_to_redis().await;
+
+        Ok(())
+    }
+
     /// Handle settings update (host only, lobby only)
     async fn handle_update_settings(
         &mut self,
@@ -887,6 +1140,9 @@ impl GameActor {
         // Save to Redis
         self.save_state_to_redis().await;
 
+        // The map or mode may have changed
+        self.update_presence(&self.player_ids()).await;
+
         Ok(())
     }
 
@@ -910,6 +1166,82 @@ impl GameActor {
         Ok(())
     }
 
+    /// Handle the teacher pausing a classroom game
+    async fn handle_pause(&mut self, user_id: &str) -> Result<(), String> {
+        let state = self.state.as_ref().ok_or("Game not initialized")?;
+        let now = Utc::now();
+
+        let result = reduce(state, CoreCommand::Pause { user_id: user_id.to_string() }, now);
+
+        if result.has_error() {
+            return Err(self.extract_error_message(&result));
+        }
+
+        self.state = Some(result.state);
+        self.broadcast_events(&result.events).await;
+        self.save_state_to_redis().await;
+
+        Ok(())
+    }
+
+    /// Handle the teacher resuming a classroom game
+    async fn handle_resume(&mut self, user_id: &str) -> Result<(), String> {
+        let state = self.state.as_ref().ok_or("Game not initialized")?;
+        let now = Utc::now();
+
+        let result = reduce(state, CoreCommand::Resume { user_id: user_id.to_string() }, now);
+
+        if result.has_error() {
+            return Err(self.extract_error_message(&result));
+        }
+
+        self.state = Some(result.state);
+        self.broadcast_events(&result.events).await;
+        self.save_state_to_redis().await;
+
+        Ok(())
+    }
+
+    /// Reveal a hint to the player who asked for it. The cost is stored with
+    /// the round so it comes off the player's guess, including after a restart.
+    async fn handle_request_hint(
+        &mut self,
+        user_id: &str,
+        kind: game::HintKind,
+        from: Option<(f64, f64)>,
+    ) -> Result<(), String> {
+        let state = self.state.as_ref().ok_or("Game not initialized")?;
+        let now = Utc::now();
+
+        let result = reduce(
+            state,
+            CoreCommand::RequestHint { user_id: user_id.to_string(), kind, from },
+            now,
+        );
+
+        if result.has_error() {
+            return Err(self.extract_error_message(&result));
+        }
+
+        for event in &result.events {
+            if let GameEvent::HintRevealed { hint, cost, .. } = event
+                && *cost > 0
+                && let Some(round_id) = &self.current_round_db_id
+                && let Err(e) =
+                    dguesser_db::round_hints::record_hint(&self.db, round_id, user_id, hint, *cost)
+                        .await
+            {
+                tracing::error!(error = %e, game_id = %self.game_id, "Failed to persist hint");
+            }
+        }
+
+        self.state = Some(result.state);
+        self.broadcast_events(&result.events).await;
+        self.save_state_to_redis().await;
+
+        Ok(())
+    }
+
     /// Handle a player voting to skip the between-rounds wait
     async fn handle_vote_skip(&mut self, user_id: &str) -> Result<(), String> {
         let state = self.state.as_ref().ok_or("Game not initialized")?;
@@ -934,6 +1266,128 @@ impl GameActor {
         Ok(())
     }
 
+    /// Broadcast a player's reaction to the room. Reactions are not stored.
+    async fn handle_react(&mut self, user_id: &str, reaction: String) -> Result<(), String> {
+        let state = self.state.as_ref().ok_or("Game not initialized")?;
+        if !state.players.contains_key(user_id) {
+            return Err("You are not in this game".to_string());
+        }
+        if !state.settings.reactions_enabled {
+            return Err("Reactions are disabled in this game".to_string());
+        }
+
+        let payload = ReactionPayload { user_id: user_id.to_string(), reaction };
+        self.emitter.emit_to_room(&self.game_id, events::server::REACTION, &payload).await.ok();
+
+        Ok(())
+    }
+
+    /// Write buffered guesses and the cached state, then export everything
+    /// the actor holds.
+    async fn handle_dump_state(&mut self) -> Result<GameSnapshot, String> {
+        self.guess_writes.flush_until_written(&self.db, &self.game_id).await;
+        self.force_save_state_to_redis().await;
+
+        let state = self.state.clone().ok_or("Game not initialized")?;
+        Ok(GameSnapshot {
+            game_id: self.game_id.clone(),
+            taken_at: Utc::now(),
+            state,
+            socket_ids: self.socket_ids.clone(),
+            current_round_db_id: self.current_round_db_id.clone(),
+            rtt_samples: self.rtt.iter().map(|(uid, w)| (uid.clone(), w.samples())).collect(),
+        })
+    }
+
+    /// Add a round-trip sample for a player, kept for latency comparisons.
+    async fn handle_record_rtt(&mut self, user_id: &str, rtt_ms: u32) {
+        let Some(state) = self.state.as_ref() else { return };
+        if !state.players.contains_key(user_id) {
+            return;
+        }
+
+        self.rtt.entry(user_id.to_string()).or_default().record(rtt_ms);
+        self.save_state_to_redis().await;
+    }
+
+    /// Replace the current round's location, e.g. when its panorama is broken.
+    ///
+    /// The reducer checks the host, the reroll window and the quota; the round
+    /// keeps its database row, which gets the new location and an audit entry.
+    async fn handle_reroll(&mut self, user_id: &str) -> Result<(), String> {
+        let state = self.state.as_ref().ok_or("Game not initialized")?;
+        let round = state.current_round.as_ref().ok_or("No round is currently in progress")?;
+        let round_number = round.round_number;
+        let current_panorama = round.panorama_id.clone();
+        let refunds: Vec<(String, i32)> = round
+            .guesses
+            .values()
+            .map(|guess| {
+                let points = state
+                    .players
+                    .get(&guess.user_id)
+                    .map_or(guess.score, |p| game::apply_handicap(guess.score, p.handicap));
+                (guess.user_id.clone(), points as i32)
+            })
+            .collect();
+
+        // Fail fast before picking a location the reducer would reject
+        let now = Utc::now();
+        let probe = reduce(
+            state,
+            CoreCommand::RerollRound {
+                user_id: user_id.to_string(),
+                new_location: LocationData::new(0.0, 0.0, None),
+            },
+            now,
+        );
+        if probe.has_error() {
+            return Err(self.extract_error_message(&probe));
+        }
+
+        let location = self.select_location(round_number, current_panorama.as_deref()).await?;
+
+        let state = self.state.as_ref().ok_or("Game not initialized")?;
+        let now = Utc::now();
+        let result = reduce(
+            state,
+            CoreCommand::RerollRound {
+                user_id: user_id.to_string(),
+                new_location: location.clone(),
+            },
+            now,
+        );
+        if result.has_error() {
+            return Err(self.extract_error_message(&result));
+        }
+
+        // The reroll refunds the round's points, so they must be written first
+        self.guess_writes.flush_until_written(&self.db, &self.game_id).await;
+        if let Some(round_id) = &self.current_round_db_id {
+            let time_limit_ms = result.state.round_time_limit_ms(now);
+            if let Err(e) = dguesser_db::round_rerolls::reroll_round(
+                &self.db,
+                &self.game_id,
+                round_id,
+                user_id,
+                &location,
+                time_limit_ms.map(|t| t as i32),
+                &refunds,
+            )
+            .await
+            {
+                tracing::error!(error = %e, game_id = %self.game_id, "Failed to persist round reroll");
+            }
+        }
+
+        self.state = Some(result.state);
+        self.broadcast_events(&result.events).await;
+        self.broadcast_scores_update().await;
+        self.force_save_state_to_redis().await;
+
+        Ok(())
+    }
+
     /// Advance to the next round or end the game.
     ///
     /// Called when the between-rounds wait is skipped or expires. Broadcasts a
@@ -941,7 +1395,7 @@ impl GameActor {
     /// a loading state while DB writes run. `initiated_by` is `None` for the
     /// tick-driven path (natural countdown expiry).
     async fn advance_or_end_game(&mut self, initiated_by: Option<&str>) {
-        let should_end = self.state.as_ref().is_some_and(|s| s.round_number >= s.settings.rounds);
+        let should_end = self.state.as_ref().is_some_and(|s| !s.can_start_round(Utc::now()));
 
         let phase =
             if should_end { TransitionPhase::EndingGame } else { TransitionPhase::AdvancingRound };
@@ -982,11 +1436,18 @@ impl GameActor {
     ///
     /// Uses `SelectionConstraints` to rank candidates by spread from previous
     /// round locations, with an optional hard minimum distance when configured.
-    async fn select_location(&self) -> Result<LocationData, String> {
+    /// Panoramas the players saw in recent games are skipped, and the country
+    /// of their previous round is ranked lower. `exclude_panorama` is skipped
+    /// even when repeats are allowed (a rerolled round's broken panorama).
+    async fn select_location(
+        &self,
+        round_number: u8,
+        exclude_panorama: Option<&str>,
+    ) -> Result<LocationData, String> {
         use dguesser_core::location::SelectionConstraints;
 
         let state = self.state.as_ref().ok_or("Game not initialized")?;
-        let map_id = &state.settings.map_id;
+        let map_id = state.settings.map_for_round(round_number);
 
         // Get previous round locations for distance constraints
         let previous_locations: Vec<(f64, f64)> =
@@ -1002,24 +1463,53 @@ impl GameActor {
 
         // Build constraints from previous locations. Relative spread applies by default,
         // and an explicit map rule can add a hard minimum distance floor.
-        let constraints = if previous_locations.is_empty() {
+        let mut constraints = if previous_locations.is_empty() {
             SelectionConstraints::none()
         } else {
             SelectionConstraints::with_optional_min_distance(previous_locations, min_distance_km)
         };
 
-        match self
+        let user_ids: Vec<String> = state.players.keys().cloned().collect();
+        if let Some(recent) = &self.recent_locations {
+            constraints = recent.load(&user_ids).await.apply(constraints);
+        }
+        if let Some(panorama_id) = exclude_panorama {
+            constraints.exclude_panoramas.push(panorama_id.to_string());
+        }
+
+        let mut selected = self
             .location_provider
             .select_location_with_constraints(map_id, &[], &constraints)
-            .await
+            .await;
+
+        // Small maps can run out of panoramas the players have not seen yet
+        if matches!(selected, Err(LocationError::NoLocationsAvailable(_)))
+            && !constraints.exclude_panoramas.is_empty()
         {
-            Ok(loc) => Ok(LocationData::full(
-                loc.lat,
-                loc.lng,
-                if loc.panorama_id.is_empty() { None } else { Some(loc.panorama_id) },
-                Some(loc.id),
-                loc.heading,
-            )),
+            tracing::debug!(map_id = %map_id, "All locations seen recently, allowing repeats");
+            let mut constraints = constraints.without_recent_panoramas();
+            constraints.exclude_panoramas.extend(exclude_panorama.map(str::to_string));
+            selected = self
+                .location_provider
+                .select_location_with_constraints(map_id, &[], &constraints)
+                .await;
+        }
+
+        match selected {
+            Ok(loc) => {
+                if let Some(recent) = &self.recent_locations {
+                    recent.record(&user_ids, &self.game_id, &loc).await;
+                }
+                Ok(LocationData::full(
+                    loc.lat,
+                    loc.lng,
+                    if loc.panorama_id.is_empty() { None } else { Some(loc.panorama_id) },
+                    Some(loc.id),
+                    loc.heading,
+                    loc.provider,
+                )
+                .with_country_code(loc.country_code))
+            }
             Err(e) => {
                 tracing::warn!(error = %e, map_id = %map_id, "Failed to select location, using random");
                 let (lat, lng) = generate_random_location();
@@ -1034,7 +1524,8 @@ impl GameActor {
         let now = Utc::now();
 
         // Select location
-        let location = self.select_location().await?;
+        let next_round = state.round_number + 1;
+        let location = self.select_location(next_round, None).await?;
 
         // Apply advance round command
         let result =
@@ -1046,11 +1537,7 @@ impl GameActor {
         }
 
         // Create round in database
-        let time_limit_ms = if result.state.settings.time_limit_seconds > 0 {
-            Some(result.state.settings.time_limit_seconds * 1000)
-        } else {
-            None
-        };
+        let time_limit_ms = result.state.round_time_limit_ms(now);
 
         let db_round = dguesser_db::games::create_round(
             &self.db,
@@ -1061,6 +1548,8 @@ impl GameActor {
             location.panorama_id.as_deref(),
             location.location_id.as_deref(),
             location.heading,
+            location.provider,
+            location.country_code.as_deref(),
             time_limit_ms.map(|t| t as i32),
         )
         .await
@@ -1096,7 +1585,8 @@ impl GameActor {
         // Apply end round command
         let result = reduce(state, CoreCommand::EndRound, now);
 
-        // End round in database
+        // End round in database, after its guesses
+        self.guess_writes.flush_until_written(&self.db, &self.game_id).await;
         if let Some(round_id) = &self.current_round_db_id
             && let Err(e) = dguesser_db::games::end_round(&self.db, round_id).await
         {
@@ -1126,7 +1616,8 @@ impl GameActor {
         // Apply end game command
         let result = reduce(state, CoreCommand::EndGame, now);
 
-        // Update database
+        // Update database; final rankings need every score written
+        self.guess_writes.flush_until_written(&self.db, &self.game_id).await;
         if let Err(e) = dguesser_db::games::update_game_status(
             &self.db,
             &self.game_id,
@@ -1141,8 +1632,14 @@ impl GameActor {
             tracing::error!(error = %e, game_id = %self.game_id, "Failed to set final rankings");
         }
 
-        // Update player stats
-        for player in result.state.players.values() {
+        // Update player stats; a classroom's teacher did not play
+        let players: Vec<_> = result
+            .state
+            .players
+            .values()
+            .filter(|p| !result.state.is_teacher(&p.user_id))
+            .collect();
+        for player in &players {
             if let Err(e) = dguesser_db::users::update_stats(
                 &self.db,
                 &player.user_id,
@@ -1158,6 +1655,20 @@ impl GameActor {
                 );
             }
         }
+        if let Err(e) = dguesser_db::leaderboard::record_game(&self.db, &self.game_id).await {
+            tracing::error!(error = %e, game_id = %self.game_id, "Failed to record game on leaderboards");
+        }
+
+        let scores: Vec<i32> = players.iter().map(|p| p.total_score as i32).collect();
+        if let Err(e) = dguesser_db::locations::record_map_plays(
+            &self.db,
+            &result.state.settings.map_id,
+            &scores,
+        )
+        .await
+        {
+            tracing::warn!(error = %e, game_id = %self.game_id, "Failed to record map plays");
+        }
 
         // Update state and broadcast
         self.state = Some(result.state);
@@ -1166,6 +1677,8 @@ impl GameActor {
         // Delete from Redis
         self.delete_state_from_redis().await;
 
+        self.clear_presence(&self.player_ids()).await;
+
         // Notify party (if this game belongs to one) so members return to lobby
         if let Some(notify_tx) = &self.party_notify_tx {
             match dguesser_db::parties::get_game_party_id(&self.db, &self.game_id).await {
@@ -1201,6 +1714,62 @@ impl GameActor {
         Ok(())
     }
 
+    // =========================================================================
+    // Presence
+    // =========================================================================
+
+    /// IDs of every player in the game
+    fn player_ids(&self) -> Vec<String> {
+        self.state.as_ref().map(|state| state.players.keys().cloned().collect()).unwrap_or_default()
+    }
+
+    /// Record that players are in this game's lobby or in the game itself.
+    async fn update_presence(&mut self, user_ids: &[String]) {
+        if self.presence.is_none() || user_ids.is_empty() {
+            return;
+        }
+        let map_name = self.current_map_name().await;
+        let (Some(presence), Some(state)) = (&self.presence, &self.state) else { return };
+
+        let status = match state.phase {
+            GamePhase::Lobby => PresenceStatus::InLobby,
+            GamePhase::Finished => return,
+            GamePhase::Active | GamePhase::RoundInProgress | GamePhase::BetweenRounds => {
+                PresenceStatus::InGame
+            }
+        };
+        let activity = PresenceActivity {
+            status,
+            game_id: self.game_id.clone(),
+            mode: if state.settings.streak { "streak" } else { "multiplayer" }.to_string(),
+            map_name,
+        };
+        for user_id in user_ids {
+            presence.set_activity(user_id, &activity).await;
+        }
+    }
+
+    /// Clear this game's activity for players who left or finished.
+    async fn clear_presence(&self, user_ids: &[String]) {
+        let Some(presence) = &self.presence else { return };
+        for user_id in user_ids {
+            presence.clear_activity(user_id, &self.game_id).await;
+        }
+    }
+
+    /// Name of the game's map, looked up once per map
+    async fn current_map_name(&mut self) -> Option<String> {
+        let map_id = &self.state.as_ref()?.settings.map_id;
+        if let Some((cached_id, name)) = &self.map_name
+            && cached_id == map_id
+        {
+            return name.clone();
+        }
+        let name = self.location_provider.get_map(map_id).await.ok().map(|map| map.name);
+        self.map_name = Some((map_id.clone(), name.clone()));
+        name
+    }
+
     // =========================================================================
     // Event Broadcasting
     // =========================================================================
@@ -1226,12 +1795,22 @@ impl GameActor {
                 GameEvent::PlayerTimedOut { user_id, display_name } => {
                     self.broadcast_player_timeout(user_id, display_name).await;
                 }
+                GameEvent::GamePaused { user_id, grace_period_ms } => {
+                    self.broadcast_game_paused(user_id, *grace_period_ms).await;
+                }
+                GameEvent::GameResumed { paused_ms } => {
+                    // Round timers moved; clients re-sync from the new deadline
+                    self.broadcast_game_resumed(*paused_ms).await;
+                }
                 GameEvent::GameStarted { .. } => {
                     // Handled via RoundStarted
                 }
                 GameEvent::RoundStarted { .. } => {
                     self.broadcast_round_start().await;
                 }
+                GameEvent::PlayerRoundStarted { .. } => {
+                    // Per-player games are played over the REST API
+                }
                 GameEvent::GuessSubmitted { user_id, display_name } => {
                     self.broadcast_player_guessed(user_id, display_name).await;
                     self.broadcast_scores_update().await;
@@ -1248,6 +1827,9 @@ impl GameActor {
                 GameEvent::SettingsUpdated { settings } => {
                     self.broadcast_settings_updated(settings).await;
                 }
+                GameEvent::HandicapUpdated { user_id, handicap } => {
+                    self.broadcast_handicap_updated(user_id, *handicap).await;
+                }
                 GameEvent::WaitSkipped => {
                     // Handled by the caller (advance_or_end_game)
                 }
@@ -1263,6 +1845,36 @@ impl GameActor {
                 GameEvent::GameAbandoned { reason } => {
                     self.broadcast_game_abandoned(reason).await;
                 }
+                GameEvent::RoundRerolled {
+                    round_number,
+                    rerolled_by,
+                    discarded_guesses,
+                    rerolls_left,
+                } => {
+                    let payload = RoundRerolledPayload {
+                        round_number: *round_number,
+                        rerolled_by: rerolled_by.clone(),
+                        discarded_guesses: discarded_guesses.clone(),
+                        rerolls_left: *rerolls_left,
+                    };
+                    self.emitter
+                        .emit_to_room(&self.game_id, events::server::ROUND_REROLLED, &payload)
+                        .await
+                        .ok();
+                }
+                GameEvent::HintRevealed { user_id, round_number, hint, cost } => {
+                    let Some(socket_id) = self.socket_ids.get(user_id) else { continue };
+                    let payload = HintRevealedPayload {
+                        round_number: *round_number,
+                        kind: hint.kind().as_str().to_string(),
+                        value: hint.value(),
+                        cost: *cost,
+                    };
+                    self.emitter
+                        .emit_to_socket(socket_id, events::server::HINT_REVEALED, &payload)
+                        .await
+                        .ok();
+                }
                 GameEvent::Error { .. } => {
                     // Errors are returned to the caller, not broadcast
                 }
@@ -1281,6 +1893,45 @@ impl GameActor {
             .unwrap_or_else(|| "Unknown error".to_string())
     }
 
+    /// Send the current round's guesses to the teacher of a classroom game:
+    /// one student's guess as it comes in, or all of them when the teacher
+    /// (re)joins mid-round
+    async fn send_classroom_guesses(&self, only: Option<&str>) {
+        let Some(state) = &self.state else { return };
+        if !state.settings.classroom {
+            return;
+        }
+        let Some(socket_id) = state.get_host().and_then(|host| self.socket_ids.get(&host.user_id))
+        else {
+            return;
+        };
+        let Some(round) = state.latest_round() else { return };
+
+        for guess in round.guesses.values() {
+            if only.is_some_and(|user_id| user_id != guess.user_id) {
+                continue;
+            }
+            let payload = ClassroomGuessPayload {
+                round_number: round.round_number,
+                user_id: guess.user_id.clone(),
+                display_name: state
+                    .players
+                    .get(&guess.user_id)
+                    .map(|p| p.display_name.clone())
+                    .unwrap_or_default(),
+                lat: guess.lat,
+                lng: guess.lng,
+                distance_meters: guess.distance_meters,
+                score: guess.score,
+                time_taken_ms: guess.time_taken_ms,
+            };
+            self.emitter
+                .emit_to_socket(socket_id, events::server::CLASSROOM_GUESS, &payload)
+                .await
+                .ok();
+        }
+    }
+
     /// Send current game state to a specific socket (via socket's personal room)
     async fn send_game_state_to_socket(&self, socket_id: &str) {
         let Some(state) = &self.state else { return };
@@ -1299,6 +1950,8 @@ impl GameActor {
                     .is_some_and(|r| r.guesses.contains_key(&p.user_id)),
                 connected: p.connected,
                 disconnected_at: p.disconnected_at.map(|dt| dt.timestamp_millis()),
+                handicap: p.handicap,
+                joined_round: p.joined_round,
             })
             .collect();
 
@@ -1317,6 +1970,7 @@ impl GameActor {
                 lng: r.location_lng,
                 panorama_id: r.panorama_id.clone(),
                 heading: r.heading,
+                provider: r.provider.to_string(),
             })
             .or_else(|| {
                 if state.phase == GamePhase::BetweenRounds {
@@ -1325,6 +1979,7 @@ impl GameActor {
                         lng: r.location_lng,
                         panorama_id: r.panorama_id.clone(),
                         heading: r.heading,
+                        provider: r.provider.to_string(),
                     })
                 } else {
                     None
@@ -1352,6 +2007,15 @@ impl GameActor {
             movement_allowed: state.settings.movement_allowed,
             zoom_allowed: state.settings.zoom_allowed,
             rotation_allowed: state.settings.rotation_allowed,
+            time_budget_seconds: state.settings.time_budget_seconds,
+            reconnect_grace_seconds: state.settings.reconnect_grace_seconds,
+            disconnect_policy: state.settings.disconnect_policy.to_string(),
+            reactions_enabled: state.settings.reactions_enabled,
+            allow_late_join: state.settings.allow_late_join,
+            rounds_plan: rounds_plan_payload(&state.settings.rounds_plan),
+            classroom: state.settings.classroom,
+            hints_enabled: state.settings.hints_enabled,
+            hint_cost: state.settings.hint_cost,
         };
 
         // Include between-rounds info when in BetweenRounds phase
@@ -1371,7 +2035,7 @@ impl GameActor {
             game_id: self.game_id.clone(),
             status: status.to_string(),
             current_round: state.round_number,
-            total_rounds: state.settings.rounds,
+            total_rounds: state.total_rounds(),
             settings,
             host_id,
             players,
@@ -1402,6 +2066,12 @@ impl GameActor {
                 has_guessed: false,
                 connected: true,
                 disconnected_at: None,
+                handicap: 1.0,
+                joined_round: self
+                    .state
+                    .as_ref()
+                    .and_then(|s| s.players.get(user_id))
+                    .and_then(|p| p.joined_round),
             },
         };
 
@@ -1466,6 +2136,20 @@ impl GameActor {
             .ok();
     }
 
+    /// Broadcast game paused
+    async fn broadcast_game_paused(&self, user_id: &str, grace_period_ms: Option<u32>) {
+        let payload = GamePausedPayload { user_id: user_id.to_string(), grace_period_ms };
+
+        self.emitter.emit_to_room(&self.game_id, events::server::GAME_PAUSED, &payload).await.ok();
+    }
+
+    /// Broadcast game resumed
+    async fn broadcast_game_resumed(&self, paused_ms: i64) {
+        let payload = GameResumedPayload { paused_ms };
+
+        self.emitter.emit_to_room(&self.game_id, events::server::GAME_RESUMED, &payload).await.ok();
+    }
+
     /// Broadcast player guessed
     async fn broadcast_player_guessed(&self, user_id: &str, display_name: &str) {
         let payload = PlayerGuessedPayload {
@@ -1552,12 +2236,13 @@ impl GameActor {
 
         let payload = RoundStartPayload {
             round_number: round.round_number,
-            total_rounds: state.settings.rounds,
+            total_rounds: state.total_rounds(),
             location: RoundLocation {
                 lat: round.location_lat,
                 lng: round.location_lng,
                 panorama_id: round.panorama_id.clone(),
                 heading: round.heading,
+                provider: round.provider.to_string(),
             },
             time_limit_ms: round.time_limit_ms,
             started_at: round.started_at.timestamp_millis(),
@@ -1573,6 +2258,8 @@ impl GameActor {
         // Get the last completed round
         let Some(round) = state.completed_rounds.last() else { return };
 
+        let stats = self.location_guess_stats(round.location_id.as_deref()).await;
+
         let results: Vec<RoundResult> = state
             .players
             .values()
@@ -1585,6 +2272,7 @@ impl GameActor {
                     distance_meters: g.distance_meters,
                     score: g.score,
                     total_score: p.total_score,
+                    percentile: stats.as_ref().and_then(|s| s.percentile_beaten(g.distance_meters)),
                 })
             })
             .collect();
@@ -1596,21 +2284,40 @@ impl GameActor {
                 lng: round.location_lng,
                 panorama_id: round.panorama_id.clone(),
                 heading: round.heading,
+                provider: round.provider.to_string(),
             },
             results,
             next_round_at: state.between_rounds_ends_at,
+            global_stats: stats.map(|s| GlobalGuessStats {
+                guesses: s.guesses,
+                median_distance_meters: s.median_distance_meters,
+            }),
         };
 
         self.emitter.emit_to_room(&self.game_id, events::server::ROUND_END, &payload).await.ok();
     }
 
+    /// Past guess statistics for the round's location, if there are enough.
+    ///
+    /// Read once per round from the hourly aggregate, never per guess.
+    async fn location_guess_stats(&self, location_id: Option<&str>) -> Option<LocationGuessStats> {
+        let location_id = location_id?;
+        match dguesser_db::location_stats::get_for_location(&self.db, location_id).await {
+            Ok(row) => row.map(LocationGuessStats::from).filter(LocationGuessStats::is_meaningful),
+            Err(e) => {
+                tracing::warn!(error = %e, game_id = %self.game_id, "Failed to load location stats");
+                None
+            }
+        }
+    }
+
     /// Broadcast game end
     async fn broadcast_game_end(&self) {
         let Some(state) = &self.state else { return };
 
         // Sort players by score
         let mut players: Vec<_> = state.players.values().collect();
-        players.sort_by(|a, b| b.total_score.cmp(&a.total_score));
+        players.sort_by_key(|p| std::cmp::Reverse(p.total_score));
 
         let final_standings: Vec<FinalStanding> = players
             .iter()
@@ -1620,6 +2327,8 @@ impl GameActor {
                 user_id: p.user_id.clone(),
                 display_name: p.display_name.clone(),
                 total_score: p.total_score,
+                streak: state.settings.streak.then_some(p.total_score),
+                joined_round: p.joined_round,
             })
             .collect();
 
@@ -1652,6 +2361,8 @@ impl GameActor {
         // Delete from Redis
         self.delete_state_from_redis().await;
 
+        self.clear_presence(&self.player_ids()).await;
+
         // Notify party (if this game belongs to one)
         if let Some(notify_tx) = &self.party_notify_tx
             && let Ok(Some(party_id)) =
@@ -1676,7 +2387,7 @@ impl GameActor {
         }
 
         let mut players: Vec<_> = state.players.values().collect();
-        players.sort_by(|a, b| b.total_score.cmp(&a.total_score));
+        players.sort_by_key(|p| std::cmp::Reverse(p.total_score));
 
         let scores: Vec<PlayerScoreInfo> = players
             .iter()
@@ -1697,6 +2408,7 @@ impl GameActor {
                     avatar_url: p.avatar_url.clone(),
                     total_score: p.total_score,
                     round_score,
+                    handicap: p.handicap,
                     has_guessed,
                     rank: (i + 1) as u8,
                     connected: p.connected,
@@ -1706,7 +2418,7 @@ impl GameActor {
 
         let payload = ScoresUpdatePayload {
             round_number: state.round_number,
-            total_rounds: state.settings.rounds,
+            total_rounds: state.total_rounds(),
             scores,
         };
 
@@ -1738,6 +2450,15 @@ impl GameActor {
                 movement_allowed: settings.movement_allowed,
                 zoom_allowed: settings.zoom_allowed,
                 rotation_allowed: settings.rotation_allowed,
+                time_budget_seconds: settings.time_budget_seconds,
+                reconnect_grace_seconds: settings.reconnect_grace_seconds,
+                disconnect_policy: settings.disconnect_policy.to_string(),
+                reactions_enabled: settings.reactions_enabled,
+                allow_late_join: settings.allow_late_join,
+                rounds_plan: rounds_plan_payload(&settings.rounds_plan),
+                classroom: settings.classroom,
+                hints_enabled: settings.hints_enabled,
+                hint_cost: settings.hint_cost,
             },
         };
 
@@ -1746,6 +2467,20 @@ impl GameActor {
             .await
             .ok();
     }
+
+    /// Broadcast a player's new score multiplier
+    async fn broadcast_handicap_updated(&self, user_id: &str, handicap: f64) {
+        let payload = HandicapUpdatedPayload {
+            game_id: self.game_id.clone(),
+            user_id: user_id.to_string(),
+            handicap,
+        };
+
+        self.emitter
+            .emit_to_room(&self.game_id, events::server::HANDICAP_UPDATED, &payload)
+            .await
+            .ok();
+    }
 }
 
 /// Generate a random location for a round (fallback)
@@ -0,0 +1,182 @@
+//! Write-behind buffer for guesses
+//!
+//! The game actor answers a guess from memory and queues the database writes
+//! here. Queued guesses and the points they add to each player's total are
+//! written in one batch on the next tick or when the round ends, instead of
+//! two writes per guess. A batch that fails to write stays buffered; where
+//! the game goes on to read the guesses back (a round or the game ending)
+//! the write is retried until it succeeds.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use dguesser_db::DbPool;
+use dguesser_db::games::NewGuess;
+
+/// Longest a guess waits in the buffer before the next tick writes it
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Buffered guesses that trigger a write without waiting for the interval
+const MAX_BUFFERED: usize = 64;
+
+/// First and longest wait between writes retried until they succeed
+const RETRY_MIN: Duration = Duration::from_millis(100);
+const RETRY_MAX: Duration = Duration::from_secs(5);
+
+/// Guesses and score changes waiting to be written
+#[derive(Debug, Default)]
+pub struct GuessWriteBuffer {
+    guesses: Vec<NewGuess>,
+    /// Points to add to each player's total
+    points: BTreeMap<String, i32>,
+    /// When the oldest buffered guess was queued
+    oldest: Option<Instant>,
+}
+
+impl GuessWriteBuffer {
+    /// Queue a guess and the points it adds to the player's total.
+    pub fn push(&mut self, guess: NewGuess, points: i32) {
+        self.add_points(&guess.user_id, points);
+        self.guesses.push(guess);
+    }
+
+    /// Queue points for a player's total without a guess row.
+    pub fn add_points(&mut self, user_id: &str, points: i32) {
+        *self.points.entry(user_id.to_string()).or_default() += points;
+        self.oldest.get_or_insert_with(Instant::now);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.guesses.is_empty() && self.points.is_empty()
+    }
+
+    /// Whether the buffer should be written now, short of a round ending.
+    pub fn is_due(&self, now: Instant) -> bool {
+        self.guesses.len() >= MAX_BUFFERED
+            || self.oldest.is_some_and(|oldest| now.duration_since(oldest) >= FLUSH_INTERVAL)
+    }
+
+    /// Write everything buffered. On failure the batch stays buffered and
+    /// the next flush writes it along with anything queued since. Returns
+    /// whether the buffer is now empty.
+    pub async fn flush(&mut self, db: &DbPool, game_id: &str) -> bool {
+        self.write_with(game_id, async |guesses, points| {
+            dguesser_db::games::record_guesses(db, game_id, guesses, points).await
+        })
+        .await
+    }
+
+    /// Write everything buffered, retrying until it is written.
+    pub async fn flush_until_written(&mut self, db: &DbPool, game_id: &str) {
+        let mut wait = RETRY_MIN;
+        while !self.flush(db, game_id).await {
+            tokio::time::sleep(wait).await;
+            wait = (wait * 2).min(RETRY_MAX);
+        }
+    }
+
+    async fn write_with<E: std::fmt::Display>(
+        &mut self,
+        game_id: &str,
+        write: impl AsyncFnOnce(&[NewGuess], &[(String, i32)]) -> Result<(), E>,
+    ) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+
+        let points: Vec<(String, i32)> =
+            self.points.iter().map(|(user_id, points)| (user_id.clone(), *points)).collect();
+        match write(&self.guesses, &points).await {
+            Ok(()) => {
+                tracing::debug!(game_id, guesses = self.guesses.len(), "Wrote buffered guesses");
+                *self = Self::default();
+                true
+            }
+            Err(e) => {
+                tracing::warn!(
+                    error = %e,
+                    game_id,
+                    guesses = self.guesses.len(),
+                    "Failed to persist guesses, will retry"
+                );
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn guess(round_id: &str, user_id: &str, score: i32) -> NewGuess {
+        NewGuess {
+            round_id: round_id.to_string(),
+            user_id: user_id.to_string(),
+            guess_lat: 0.0,
+            guess_lng: 0.0,
+            distance_meters: 0.0,
+            score,
+            time_taken_ms: None,
+            reported_panorama_id: None,
+            submitted_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_points_are_coalesced_per_player() {
+        let mut buffer = GuessWriteBuffer::default();
+        assert!(buffer.is_empty());
+
+        buffer.push(guess("rnd_1", "usr_a", 4000), 4000);
+        buffer.push(guess("rnd_1", "usr_b", 2500), 2000);
+        buffer.push(guess("rnd_2", "usr_a", 1000), 1000);
+
+        assert_eq!(buffer.guesses.len(), 3);
+        assert_eq!(buffer.points.get("usr_a"), Some(&5000));
+        assert_eq!(buffer.points.get("usr_b"), Some(&2000));
+    }
+
+    #[test]
+    fn test_due_after_interval_or_when_full() {
+        let mut buffer = GuessWriteBuffer::default();
+        let now = Instant::now();
+        assert!(!buffer.is_due(now));
+
+        buffer.push(guess("rnd_1", "usr_a", 100), 100);
+        let queued = buffer.oldest.unwrap();
+        assert!(!buffer.is_due(queued));
+        assert!(buffer.is_due(queued + FLUSH_INTERVAL));
+
+        let mut full = GuessWriteBuffer::default();
+        for i in 0..MAX_BUFFERED {
+            full.push(guess("rnd_1", &format!("usr_{i}"), 100), 100);
+        }
+        assert!(full.is_due(full.oldest.unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_failed_batch_is_written_by_the_next_flush() {
+        let mut buffer = GuessWriteBuffer::default();
+        buffer.push(guess("rnd_1", "usr_a", 4000), 4000);
+        buffer.push(guess("rnd_1", "usr_b", 2500), 2500);
+
+        let written = buffer.write_with("gam_1", async |_, _| Err("database unavailable")).await;
+        assert!(!written);
+        assert_eq!(buffer.guesses.len(), 2);
+
+        buffer.push(guess("rnd_2", "usr_a", 1000), 1000);
+        let mut batches = Vec::new();
+        let written = buffer
+            .write_with("gam_1", async |guesses, points| {
+                batches.push((guesses.len(), points.to_vec()));
+                Ok::<_, &str>(())
+            })
+            .await;
+        assert!(written);
+        assert!(buffer.is_empty());
+        assert_eq!(batches, [(3, vec![("usr_a".to_string(), 5000), ("usr_b".to_string(), 2500)])]);
+    }
+}
@@ -0,0 +1,72 @@
+//! Rolling round-trip times per player
+//!
+//! Clients answer clock syncs every so often (see [`crate::handlers::time`]),
+//! and each answer is one round-trip sample. The game actor keeps the most
+//! recent samples per player so latency can be compared across players when
+//! looking into whether a game was fair.
+
+use std::collections::VecDeque;
+
+/// Samples kept per player
+const WINDOW: usize = 10;
+
+/// The most recent round-trip samples of one player, oldest first
+#[derive(Debug, Clone, Default)]
+pub struct RttWindow {
+    samples: VecDeque<u32>,
+}
+
+impl RttWindow {
+    /// Restore a window from saved samples, keeping the newest.
+    pub fn from_samples(samples: &[u32]) -> Self {
+        let mut window = Self::default();
+        for &rtt_ms in samples {
+            window.record(rtt_ms);
+        }
+        window
+    }
+
+    pub fn record(&mut self, rtt_ms: u32) {
+        if self.samples.len() == WINDOW {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(rtt_ms);
+    }
+
+    pub fn samples(&self) -> Vec<u32> {
+        self.samples.iter().copied().collect()
+    }
+
+    /// Median of the window, which ignores the odd slow sample.
+    pub fn median(&self) -> Option<u32> {
+        let mut sorted = self.samples();
+        sorted.sort_unstable();
+        let mid = sorted.len() / 2;
+        match sorted.len() {
+            0 => None,
+            n if n % 2 == 1 => Some(sorted[mid]),
+            _ => Some(sorted[mid - 1] + (sorted[mid] - sorted[mid - 1]) / 2),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_median() {
+        assert_eq!(RttWindow::default().median(), None);
+        assert_eq!(RttWindow::from_samples(&[80]).median(), Some(80));
+        assert_eq!(RttWindow::from_samples(&[90, 40, 2000]).median(), Some(90));
+        assert_eq!(RttWindow::from_samples(&[40, 60]).median(), Some(50));
+    }
+
+    #[test]
+    fn test_window_keeps_newest() {
+        let samples: Vec<u32> = (1..=15).collect();
+        let window = RttWindow::from_samples(&samples);
+        assert_eq!(window.samples(), (6..=15).collect::<Vec<u32>>());
+        assert_eq!(window.median(), Some(10));
+    }
+}
@@ -1,7 +1,26 @@
 //! Game and party session actors
 
 mod game_actor;
+mod guess_writes;
+mod latency;
 mod party_actor;
+mod snapshot;
 
 pub use game_actor::GameActor;
 pub use party_actor::PartyActor;
+pub use snapshot::GameSnapshot;
+
+use dguesser_core::game::MapRounds;
+use dguesser_protocol::socket::payloads::MapRoundsPayload;
+
+/// Map playlist of a game, as sent to clients
+pub(crate) fn rounds_plan_payload(plan: &[MapRounds]) -> Vec<MapRoundsPayload> {
+    plan.iter()
+        .map(|run| MapRoundsPayload { map_id: run.map_id.clone(), rounds: run.rounds })
+        .collect()
+}
+
+/// Map playlist sent by a client
+pub(crate) fn rounds_plan_from_payload(plan: &[MapRoundsPayload]) -> Vec<MapRounds> {
+    plan.iter().map(|run| MapRounds { map_id: run.map_id.clone(), rounds: run.rounds }).collect()
+}
@@ -3,19 +3,24 @@
 //! The party is the persistent "lobby" that survives game endings.
 //! The host can start games, and all members automatically follow.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 
 use chrono::{DateTime, Utc};
 use dguesser_core::game::GameSettings;
+use dguesser_core::join_code::{DEFAULT_JOIN_CODE_LENGTH, generate_join_code};
+use dguesser_core::moderation::filter_chat_message;
 use dguesser_db::DbPool;
+use dguesser_db::parties::PartyMessage;
 use dguesser_protocol::socket::events;
 use dguesser_protocol::socket::payloads::{
-    GameSettingsPayload, PartyDisbandedPayload, PartyGameEndedPayload, PartyGameStartingPayload,
-    PartyHostChangedPayload, PartyMemberInfo, PartyMemberJoinedPayload, PartyMemberLeftPayload,
+    GameSettingsPayload, PartyChatHistoryPayload, PartyChatMessagePayload, PartyDisbandedPayload,
+    PartyGameEndedPayload, PartyGameStartingPayload, PartyHostChangedPayload, PartyMemberInfo,
+    PartyMemberJoinedPayload, PartyMemberLeftPayload, PartyMemberMutedPayload,
     PartySettingsUpdatedPayload, PartyStatePayload,
 };
 use tokio::sync::mpsc;
 
+use super::rounds_plan_payload;
 use crate::emitter::BroadcastEmitter;
 use crate::state::{AppState, PartyCommand};
 
@@ -25,6 +30,16 @@ const HOST_DISCONNECT_GRACE_SECS: u64 = 30;
 /// Maximum number of members in a party
 const MAX_PARTY_MEMBERS: usize = 8;
 
+/// Chat messages kept in memory and sent to joining members
+const CHAT_HISTORY_LIMIT: usize = 50;
+
+/// Maximum length of a chat message (characters)
+const MAX_CHAT_MESSAGE_CHARS: usize = 500;
+
+/// How often the roster is compared with the database (seconds), to pick up
+/// members who left through the REST API
+const ROSTER_SYNC_SECS: u64 = 10;
+
 /// Internal member state tracked by the actor
 #[derive(Debug, Clone)]
 struct MemberState {
@@ -34,6 +49,7 @@ struct MemberState {
     connected: bool,
     joined_at: DateTime<Utc>,
     disconnected_at: Option<std::time::Instant>,
+    chat_muted: bool,
 }
 
 /// Party actor that manages a persistent group of players.
@@ -53,6 +69,8 @@ pub struct PartyActor {
     app_state: Option<AppState>,
     host_disconnect_at: Option<std::time::Instant>,
     all_disconnected_at: Option<std::time::Instant>,
+    chat: VecDeque<PartyChatMessagePayload>,
+    last_roster_sync: std::time::Instant,
 }
 
 impl PartyActor {
@@ -81,6 +99,8 @@ impl PartyActor {
             app_state: None,
             host_disconnect_at: None,
             all_disconnected_at: None,
+            chat: VecDeque::with_capacity(CHAT_HISTORY_LIMIT),
+            last_roster_sync: std::time::Instant::now(),
         }
     }
 
@@ -95,7 +115,7 @@ impl PartyActor {
     }
 
     /// Hydrate actor state from DB (for restarts/recreation).
-    /// Loads active members and current game state.
+    /// Loads active members, current game state, and recent chat.
     pub async fn hydrate_from_db(&mut self) {
         // Load active members
         match dguesser_db::parties::get_party_members(&self.db, &self.party_id).await {
@@ -120,6 +140,7 @@ impl PartyActor {
                             connected: false, // Will reconnect via socket
                             joined_at: m.joined_at,
                             disconnected_at: Some(std::time::Instant::now()),
+                            chat_muted: m.chat_muted,
                         },
                     );
                 }
@@ -149,6 +170,24 @@ impl PartyActor {
                 );
             }
         }
+
+        // Load recent chat
+        match dguesser_db::parties::get_recent_party_messages(
+            &self.db,
+            &self.party_id,
+            CHAT_HISTORY_LIMIT as i64,
+        )
+        .await
+        {
+            Ok(messages) => self.chat = messages.into_iter().map(chat_message_payload).collect(),
+            Err(e) => {
+                tracing::error!(
+                    error = %e,
+                    party_id = %self.party_id,
+                    "Failed to hydrate party chat from DB"
+                );
+            }
+        }
     }
 
     /// Main run loop
@@ -187,6 +226,14 @@ impl PartyActor {
                     let result = self.handle_kick(&user_id, &target_user_id).await;
                     let _ = respond.send(result);
                 }
+                PartyCommand::Mute { user_id, target_user_id, muted, respond } => {
+                    let result = self.handle_mute(&user_id, &target_user_id, muted).await;
+                    let _ = respond.send(result);
+                }
+                PartyCommand::Chat { user_id, content, respond } => {
+                    let result = self.handle_chat(&user_id, &content).await;
+                    let _ = respond.send(result);
+                }
                 PartyCommand::Disband { user_id, respond } => {
                     let result = self.handle_disband(&user_id).await;
                     let ok = result.is_ok();
@@ -227,6 +274,7 @@ impl PartyActor {
         }
 
         let is_rejoin = self.members.contains_key(user_id);
+        let was_muted = self.members.get(user_id).is_some_and(|m| m.chat_muted);
 
         // Add/update member
         let member = MemberState {
@@ -236,6 +284,7 @@ impl PartyActor {
             connected: true,
             joined_at: Utc::now(),
             disconnected_at: None,
+            chat_muted: was_muted,
         };
         self.members.insert(user_id.to_string(), member);
         self.socket_ids.insert(user_id.to_string(), socket_id.to_string());
@@ -248,23 +297,29 @@ impl PartyActor {
             self.host_disconnect_at = None;
         }
 
-        // Persist to DB
-        if let Err(e) =
-            dguesser_db::parties::add_party_member(&self.db, &self.party_id, user_id).await
-        {
-            tracing::error!(error = %e, "Failed to persist party member");
-            // Rollback in-memory state
-            self.members.remove(user_id);
-            self.socket_ids.remove(user_id);
-            return Err("Failed to join party (database error)".to_string());
+        // Persist to DB; a mute survives leaving and rejoining
+        match dguesser_db::parties::add_party_member(&self.db, &self.party_id, user_id).await {
+            Ok(row) => {
+                if let Some(member) = self.members.get_mut(user_id) {
+                    member.chat_muted = row.chat_muted;
+                }
+            }
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to persist party member");
+                // Rollback in-memory state
+                self.members.remove(user_id);
+                self.socket_ids.remove(user_id);
+                return Err("Failed to join party (database error)".to_string());
+            }
         }
 
-        // Send full state to the joining member
+        // Send full state and recent chat to the joining member
         let state_payload = self.build_state_payload();
         let _ = self
             .emitter
             .emit_to_socket(socket_id, events::party::PARTY_STATE, &state_payload)
             .await;
+        self.send_chat_history(socket_id).await;
 
         // Broadcast member joined (to other members)
         if !is_rejoin {
@@ -274,6 +329,7 @@ impl PartyActor {
                     display_name: display_name.to_string(),
                     avatar_url: avatar_url.map(|s| s.to_string()),
                     connected: true,
+                    chat_muted: self.members.get(user_id).is_some_and(|m| m.chat_muted),
                 },
             };
             let _ = self
@@ -333,7 +389,7 @@ impl PartyActor {
                     party_id = %self.party_id,
                     host_id = %self.host_id,
                     "Host disconnected, starting {}s grace period",
-                    HOST_DISCONNECT_GRACE_SECS
+                    self.host_grace_secs()
                 );
             }
         }
@@ -374,12 +430,13 @@ impl PartyActor {
             );
         }
 
-        // Send full state to the reconnected member
+        // Send full state and recent chat to the reconnected member
         let state_payload = self.build_state_payload();
         let _ = self
             .emitter
             .emit_to_socket(socket_id, events::party::PARTY_STATE, &state_payload)
             .await;
+        self.send_chat_history(socket_id).await;
 
         // Broadcast updated member status to all members
         let state_payload = self.build_state_payload();
@@ -399,6 +456,13 @@ impl PartyActor {
             return Err("A game is already starting".to_string());
         }
 
+        // The game roster is built from the party, so drop anyone who left
+        // through the REST API since the last sync
+        self.sync_roster().await;
+        if user_id != self.host_id {
+            return Err("Only the host can start a game".to_string());
+        }
+
         // Must not already be in a game
         if self.current_game_id.is_some() {
             return Err("A game is already in progress".to_string());
@@ -420,7 +484,7 @@ impl PartyActor {
 
         // Generate game ID and join code
         let game_id = dguesser_core::generate_game_id();
-        let join_code = generate_join_code();
+        let join_code = generate_join_code(DEFAULT_JOIN_CODE_LENGTH);
 
         // Serialize settings
         let settings_json = serde_json::to_value(&self.settings).unwrap_or_default();
@@ -519,6 +583,15 @@ impl PartyActor {
                 movement_allowed: settings.movement_allowed,
                 zoom_allowed: settings.zoom_allowed,
                 rotation_allowed: settings.rotation_allowed,
+                time_budget_seconds: settings.time_budget_seconds,
+                reconnect_grace_seconds: settings.reconnect_grace_seconds,
+                disconnect_policy: settings.disconnect_policy.to_string(),
+                reactions_enabled: settings.reactions_enabled,
+                allow_late_join: settings.allow_late_join,
+                rounds_plan: rounds_plan_payload(&settings.rounds_plan),
+                classroom: settings.classroom,
+                hints_enabled: settings.hints_enabled,
+                hint_cost: settings.hint_cost,
             },
         };
         let _ = self
@@ -581,6 +654,122 @@ impl PartyActor {
         Ok(())
     }
 
+    async fn handle_mute(
+        &mut self,
+        user_id: &str,
+        target_user_id: &str,
+        muted: bool,
+    ) -> Result<(), String> {
+        if user_id != self.host_id {
+            return Err("Only the host can mute members".to_string());
+        }
+
+        if target_user_id == self.host_id {
+            return Err("Cannot mute yourself".to_string());
+        }
+
+        let Some(member) = self.members.get_mut(target_user_id) else {
+            return Err("Member not found in party".to_string());
+        };
+        if member.chat_muted == muted {
+            return Ok(());
+        }
+
+        dguesser_db::parties::set_member_chat_muted(
+            &self.db,
+            &self.party_id,
+            target_user_id,
+            muted,
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, party_id = %self.party_id, "Failed to persist chat mute");
+            "Failed to update mute".to_string()
+        })?;
+        member.chat_muted = muted;
+
+        let payload = PartyMemberMutedPayload {
+            user_id: target_user_id.to_string(),
+            display_name: member.display_name.clone(),
+            muted,
+        };
+        let _ =
+            self.emitter.emit_to_room(&self.party_id, events::party::MEMBER_MUTED, &payload).await;
+
+        tracing::info!(
+            party_id = %self.party_id,
+            target_user_id = %target_user_id,
+            muted,
+            "Party member chat mute changed"
+        );
+        Ok(())
+    }
+
+    async fn handle_chat(&mut self, user_id: &str, content: &str) -> Result<(), String> {
+        let Some(member) = self.members.get(user_id) else {
+            return Err("You are not in this party".to_string());
+        };
+        if member.chat_muted {
+            return Err("The host muted you in this party".to_string());
+        }
+
+        let content = validate_chat_message(content)?;
+        let filtered = filter_chat_message(&content);
+
+        let message = dguesser_db::parties::add_party_message(
+            &self.db,
+            &self.party_id,
+            user_id,
+            &filtered.text,
+            filtered.flagged.then_some(content.as_str()),
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, party_id = %self.party_id, "Failed to store chat message");
+            "Failed to send message".to_string()
+        })?;
+
+        if filtered.flagged {
+            self.report_filtered_message(user_id, &content).await;
+        }
+
+        let payload = chat_message_payload(message);
+        if self.chat.len() == CHAT_HISTORY_LIMIT {
+            self.chat.pop_front();
+        }
+        self.chat.push_back(payload.clone());
+
+        let _ =
+            self.emitter.emit_to_room(&self.party_id, events::party::CHAT_MESSAGE, &payload).await;
+
+        Ok(())
+    }
+
+    /// Put a message caught by the chat filter in the player report queue.
+    /// The message is sent masked either way.
+    async fn report_filtered_message(&self, user_id: &str, content: &str) {
+        match dguesser_db::player_reports::create_from_chat_filter(
+            &self.db,
+            user_id,
+            self.current_game_id.as_deref(),
+            content,
+        )
+        .await
+        {
+            Ok(true) => {
+                tracing::info!(
+                    party_id = %self.party_id,
+                    user_id = %user_id,
+                    "Chat filter reported a player"
+                );
+            }
+            Ok(false) => {}
+            Err(e) => {
+                tracing::warn!(error = %e, party_id = %self.party_id, "Failed to report filtered chat");
+            }
+        }
+    }
+
     async fn handle_disband(&mut self, user_id: &str) -> Result<(), String> {
         if user_id != self.host_id {
             return Err("Only the host can disband the party".to_string());
@@ -609,10 +798,23 @@ impl PartyActor {
         );
     }
 
+    /// How long a disconnected host keeps the party, unless changed by a
+    /// runtime config reload
+    fn host_grace_secs(&self) -> u64 {
+        self.app_state
+            .as_ref()
+            .and_then(AppState::party_host_grace_secs)
+            .unwrap_or(HOST_DISCONNECT_GRACE_SECS)
+    }
+
     async fn handle_tick(&mut self) {
+        if self.last_roster_sync.elapsed().as_secs() >= ROSTER_SYNC_SECS {
+            self.sync_roster().await;
+        }
+
         // Check host disconnect grace period
         if let Some(disconnect_time) = self.host_disconnect_at
-            && disconnect_time.elapsed().as_secs() >= HOST_DISCONNECT_GRACE_SECS
+            && disconnect_time.elapsed().as_secs() >= self.host_grace_secs()
         {
             tracing::info!(
                 party_id = %self.party_id,
@@ -675,6 +877,44 @@ impl PartyActor {
     // Helper Methods
     // =========================================================================
 
+    /// Drop members whose membership ended outside the actor (REST leave).
+    async fn sync_roster(&mut self) {
+        self.last_roster_sync = std::time::Instant::now();
+
+        let active: HashSet<String> =
+            match dguesser_db::parties::get_party_members(&self.db, &self.party_id).await {
+                Ok(members) => members.into_iter().map(|m| m.user_id).collect(),
+                Err(e) => {
+                    tracing::error!(
+                        error = %e,
+                        party_id = %self.party_id,
+                        "Failed to sync party roster"
+                    );
+                    return;
+                }
+            };
+
+        let departed: Vec<String> =
+            self.members.keys().filter(|id| !active.contains(*id)).cloned().collect();
+        for user_id in departed {
+            tracing::info!(
+                party_id = %self.party_id,
+                user_id = %user_id,
+                "Member left outside the party actor"
+            );
+            self.handle_leave(&user_id).await;
+        }
+    }
+
+    /// Send recent chat messages to one socket
+    async fn send_chat_history(&self, socket_id: &str) {
+        if self.chat.is_empty() {
+            return;
+        }
+        let payload = PartyChatHistoryPayload { messages: self.chat.iter().cloned().collect() };
+        let _ = self.emitter.emit_to_socket(socket_id, events::party::CHAT_HISTORY, &payload).await;
+    }
+
     /// Transfer host to the longest-tenured connected member
     async fn transfer_host(&mut self) {
         let new_host = self
@@ -744,6 +984,7 @@ impl PartyActor {
                 display_name: m.display_name.clone(),
                 avatar_url: m.avatar_url.clone(),
                 connected: m.connected,
+                chat_muted: m.chat_muted,
             })
             .collect();
 
@@ -767,6 +1008,15 @@ impl PartyActor {
                 movement_allowed: self.settings.movement_allowed,
                 zoom_allowed: self.settings.zoom_allowed,
                 rotation_allowed: self.settings.rotation_allowed,
+                time_budget_seconds: self.settings.time_budget_seconds,
+                reconnect_grace_seconds: self.settings.reconnect_grace_seconds,
+                disconnect_policy: self.settings.disconnect_policy.to_string(),
+                reactions_enabled: self.settings.reactions_enabled,
+                allow_late_join: self.settings.allow_late_join,
+                rounds_plan: rounds_plan_payload(&self.settings.rounds_plan),
+                classroom: self.settings.classroom,
+                hints_enabled: self.settings.hints_enabled,
+                hint_cost: self.settings.hint_cost,
             },
             current_game_id: self.current_game_id.clone(),
             phase: phase.to_string(),
@@ -774,15 +1024,40 @@ impl PartyActor {
     }
 }
 
-/// Generate a 6-character join code (same charset as game codes)
-fn generate_join_code() -> String {
-    use rand::RngExt;
-    const CHARSET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
-    let mut rng = rand::rng();
-    (0..6)
-        .map(|_| {
-            let idx = rng.random_range(0..CHARSET.len());
-            CHARSET[idx] as char
-        })
-        .collect()
+fn chat_message_payload(message: PartyMessage) -> PartyChatMessagePayload {
+    PartyChatMessagePayload {
+        id: message.id,
+        user_id: message.user_id,
+        display_name: message.display_name,
+        content: message.content,
+        sent_at: message.created_at.timestamp_millis(),
+    }
+}
+
+/// Trim a chat message and check it is non-empty and not too long.
+/// Control characters other than newlines are removed.
+fn validate_chat_message(content: &str) -> Result<String, String> {
+    let content: String = content.chars().filter(|c| !c.is_control() || *c == '\n').collect();
+    let content = content.trim();
+    if content.is_empty() {
+        return Err("Message cannot be empty".to_string());
+    }
+    if content.chars().count() > MAX_CHAT_MESSAGE_CHARS {
+        return Err(format!("Message must be at most {MAX_CHAT_MESSAGE_CHARS} characters"));
+    }
+    Ok(content.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_chat_message() {
+        assert_eq!(validate_chat_message("  gg wp \u{7}").unwrap(), "gg wp");
+        assert_eq!(validate_chat_message("one\ntwo").unwrap(), "one\ntwo");
+        assert!(validate_chat_message(" \t ").is_err());
+        assert!(validate_chat_message(&"a".repeat(MAX_CHAT_MESSAGE_CHARS)).is_ok());
+        assert!(validate_chat_message(&"a".repeat(MAX_CHAT_MESSAGE_CHARS + 1)).is_err());
+    }
 }
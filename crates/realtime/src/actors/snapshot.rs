@@ -0,0 +1,33 @@
+//! Full in-memory state of a game actor
+//!
+//! Unlike the Redis cache, which keeps what a restarted actor needs to carry
+//! on, a snapshot holds the reducer's [`GameState`] exactly as it was, plus
+//! the actor's own bookkeeping. Snapshots are exported and imported through
+//! the internal endpoints (see [`crate::internal`]) to reproduce reducer bugs
+//! from production on a development server.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use dguesser_core::game::GameState;
+use serde::{Deserialize, Serialize};
+
+/// Exported state of one game actor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameSnapshot {
+    /// Game ID (gam_xxxxxxxxxxxx)
+    pub game_id: String,
+    /// When the snapshot was taken
+    pub taken_at: DateTime<Utc>,
+    /// Reducer state
+    pub state: GameState,
+    /// Connected sockets (user_id -> socket_id)
+    #[serde(default)]
+    pub socket_ids: HashMap<String, String>,
+    /// Database ID of the current round
+    #[serde(default)]
+    pub current_round_db_id: Option<String>,
+    /// Recent round-trip times per player (ms, oldest first)
+    #[serde(default)]
+    pub rtt_samples: HashMap<String, Vec<u32>>,
+}
@@ -3,6 +3,7 @@
 use std::env;
 
 use anyhow::{Context, Result};
+use dguesser_redis::RedisConfig;
 
 /// Location provider type.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -34,6 +35,10 @@ pub struct R2LocationConfig {
     pub version: String,
     /// Maximum disabled hashes to cache in memory
     pub max_disabled_cache: usize,
+    /// Memory budget for cached pack ranges in MiB (0 disables the cache)
+    pub range_cache_mb: usize,
+    /// Share cached pack ranges between instances through Redis
+    pub range_cache_redis: bool,
 }
 
 impl R2LocationConfig {
@@ -45,8 +50,13 @@ impl R2LocationConfig {
             .ok()
             .and_then(|s| s.parse().ok())
             .unwrap_or(200_000);
+        let range_cache_mb =
+            env::var("LOCATION_RANGE_CACHE_MB").ok().and_then(|s| s.parse().ok()).unwrap_or(64);
+        let range_cache_redis = env::var("LOCATION_RANGE_CACHE_REDIS")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(false);
 
-        Some(Self { base_url, version, max_disabled_cache })
+        Some(Self { base_url, version, max_disabled_cache, range_cache_mb, range_cache_redis })
     }
 
     /// Get the local path (if local).
@@ -66,7 +76,8 @@ impl R2LocationConfig {
 pub struct Config {
     pub port: u16,
     pub database_url: String,
-    pub redis_url: String,
+    /// Redis URL and topology (standalone, Sentinel or Cluster)
+    pub redis: RedisConfig,
     /// Frontend URL for CORS
     pub frontend_url: String,
     /// Location provider type
@@ -78,6 +89,16 @@ pub struct Config {
     pub trusted_proxy_count: u8,
     /// Whether to trust Cloudflare headers (CF-Connecting-IP)
     pub trust_cloudflare: bool,
+    /// Shared secret for verifying socket handshake tokens minted by the API.
+    /// Handshake tokens are rejected when unset; the `auth` event still works.
+    pub socket_token_secret: Option<String>,
+    /// Games per player whose panoramas are not served again (0 disables)
+    pub location_repeat_window: usize,
+    /// Hours without activity before a lobby or active game is abandoned
+    pub stale_game_hours: i32,
+    /// Bearer token for the internal debugging endpoints, which are not
+    /// served when unset
+    pub internal_api_secret: Option<String>,
 }
 
 impl Config {
@@ -103,8 +124,7 @@ impl Config {
                 .parse()
                 .context("Invalid PORT/REALTIME_PORT")?,
             database_url: env::var("DATABASE_URL").context("DATABASE_URL not set")?,
-            redis_url: env::var("REDIS_URL")
-                .unwrap_or_else(|_| "redis://localhost:6379".to_string()),
+            redis: RedisConfig::from_env()?,
             frontend_url: env::var("FRONTEND_URL")
                 .unwrap_or_else(|_| "http://localhost:5173".to_string()),
             location_provider_type,
@@ -116,6 +136,17 @@ impl Config {
             trust_cloudflare: env::var("TRUST_CLOUDFLARE")
                 .map(|v| v == "true" || v == "1")
                 .unwrap_or(true), // Default: trust Cloudflare headers
+            socket_token_secret: env::var("SOCKET_TOKEN_SECRET").ok().filter(|s| !s.is_empty()),
+            location_repeat_window: env::var("LOCATION_REPEAT_WINDOW_GAMES")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(10),
+            stale_game_hours: env::var("STALE_GAME_HOURS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|&h: &i32| h > 0)
+                .unwrap_or(12),
+            internal_api_secret: env::var("INTERNAL_API_SECRET").ok().filter(|s| !s.is_empty()),
         })
     }
 }
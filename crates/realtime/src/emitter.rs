@@ -4,7 +4,7 @@
 //! Uses socketioxide-emitter to publish via Redis, which is then received by
 //! the Redis adapter and forwarded to connected clients.
 
-use redis::aio::MultiplexedConnection;
+use dguesser_redis::RedisConnection;
 use serde::Serialize;
 use socketioxide_emitter::{Driver, IoEmitter};
 use std::sync::Arc;
@@ -12,7 +12,7 @@ use tokio::sync::RwLock;
 
 /// Redis driver for socketioxide-emitter
 #[derive(Clone)]
-struct RedisDriver(MultiplexedConnection);
+struct RedisDriver(RedisConnection);
 
 impl Driver for RedisDriver {
     type Error = redis::RedisError;
@@ -27,7 +27,7 @@ impl Driver for RedisDriver {
 /// Broadcast emitter for sending Socket.IO events via Redis
 #[derive(Clone)]
 pub struct BroadcastEmitter {
-    inner: Arc<RwLock<Option<MultiplexedConnection>>>,
+    inner: Arc<RwLock<Option<RedisConnection>>>,
 }
 
 impl BroadcastEmitter {
@@ -37,7 +37,7 @@ impl BroadcastEmitter {
     }
 
     /// Set the Redis connection
-    pub async fn set_connection(&self, conn: MultiplexedConnection) {
+    pub async fn set_connection(&self, conn: RedisConnection) {
         *self.inner.write().await = Some(conn);
     }
 
@@ -1,9 +1,23 @@
-//! Socket authentication handler
+//! Socket authentication handlers
+//!
+//! Sockets authenticate either with a signed token in the handshake query
+//! (`?token=...`, minted by `POST /api/v1/auth/socket-token`), which needs no
+//! database lookup, or by sending an `auth` event with their session after
+//! connecting.
+//!
+//! Either way the client states its protocol version (`?protocol_version=`
+//! in the handshake or `protocol_version` in the event). Unsupported versions
+//! are turned away; otherwise `auth:success` lists the capabilities the
+//! socket may use (see [`dguesser_protocol::version`]).
 
+use dguesser_protocol::version::{
+    Compatibility, DEFAULT_CLIENT_VERSION, PROTOCOL_VERSION, capabilities_for, compatibility,
+};
 use serde::{Deserialize, Serialize};
 use socketioxide::adapter::Adapter;
 use socketioxide::extract::{Data, SocketRef, State};
 
+use crate::presence;
 use crate::rate_limit::{SocketRateLimitConfig, check_rate_limit, get_socket_ip};
 use crate::state::AppState;
 
@@ -16,6 +30,9 @@ pub struct AuthPayload {
     /// Session token - if empty, will be extracted from cookie
     #[serde(default)]
     pub session_id: String,
+    /// Protocol version the client speaks
+    #[serde(default)]
+    pub protocol_version: Option<u32>,
 }
 
 /// Response for authentication
@@ -27,6 +44,33 @@ pub struct AuthResponse {
     pub user_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Protocol version of the server
+    pub protocol_version: u32,
+    /// Capabilities the socket may use (on success)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<Vec<&'static str>>,
+}
+
+impl AuthResponse {
+    fn success(user_id: String, client_version: u32) -> Self {
+        Self {
+            success: true,
+            user_id: Some(user_id),
+            error: None,
+            protocol_version: PROTOCOL_VERSION,
+            capabilities: Some(capabilities_for(client_version)),
+        }
+    }
+
+    fn error(error: impl Into<String>) -> Self {
+        Self {
+            success: false,
+            user_id: None,
+            error: Some(error.into()),
+            protocol_version: PROTOCOL_VERSION,
+            capabilities: None,
+        }
+    }
 }
 
 /// Extract session ID from cookie header
@@ -53,6 +97,99 @@ fn extract_session_from_cookie<A: Adapter>(socket: &SocketRef<A>) -> Option<Stri
         .next_back()
 }
 
+/// Extract a parameter from the handshake query string
+fn handshake_param<A: Adapter>(socket: &SocketRef<A>, name: &str) -> Option<String> {
+    let query = socket.req_parts().uri.query()?;
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// Check the client's protocol version, remembering it for the socket.
+///
+/// Returns the version, or `None` after turning away an unsupported client.
+async fn negotiate_protocol<A: Adapter>(
+    socket: &SocketRef<A>,
+    state: &AppState,
+    client_version: Option<u32>,
+) -> Option<u32> {
+    let version = client_version.unwrap_or(DEFAULT_CLIENT_VERSION);
+    match compatibility(version) {
+        Compatibility::Unsupported => {
+            tracing::debug!(socket_id = %socket.id, version, "Rejected outdated client protocol");
+            socket
+                .emit(
+                    "auth:error",
+                    &AuthResponse::error(format!(
+                        "Protocol version {version} is no longer supported, please reload the page"
+                    )),
+                )
+                .ok();
+            socket.clone().disconnect().ok();
+            None
+        }
+        compat => {
+            if compat == Compatibility::Degraded {
+                tracing::debug!(socket_id = %socket.id, version, "Client protocol differs from server");
+            }
+            state.set_socket_protocol(&socket.id.to_string(), version).await;
+            Some(version)
+        }
+    }
+}
+
+/// Authenticate a socket from its handshake token, if it sent one.
+///
+/// Returns `true` once the socket is authenticated. A token that fails
+/// verification disconnects the socket immediately.
+pub async fn authenticate_handshake<A: Adapter>(socket: &SocketRef<A>, state: &AppState) -> bool {
+    let Some(token) = handshake_param(socket, "token") else {
+        return false;
+    };
+    let socket_id = socket.id.to_string();
+    let client_version =
+        handshake_param(socket, "protocol_version").and_then(|v| v.parse::<u32>().ok());
+    let Some(version) = negotiate_protocol(socket, state, client_version).await else {
+        return false;
+    };
+
+    let claims = match state.socket_token_signer() {
+        Some(signer) => signer.verify(&token, chrono::Utc::now()).map_err(|e| e.to_string()),
+        None => Err("Socket tokens are not enabled".to_string()),
+    };
+
+    match claims {
+        Ok(claims) => {
+            state.register_socket(&socket_id, &claims.user_id).await;
+            if let Some(ref game_id) = claims.game_id {
+                state.bind_socket_game(&socket_id, game_id).await;
+            }
+            join_user_room(socket, state, &claims.user_id).await;
+
+            socket
+                .emit("auth:success", &AuthResponse::success(claims.user_id.clone(), version))
+                .ok();
+
+            tracing::info!(
+                socket_id = %socket_id,
+                user_id = %claims.user_id,
+                game_id = ?claims.game_id,
+                "Socket authenticated via handshake token"
+            );
+            true
+        }
+        Err(err) => {
+            tracing::debug!(socket_id = %socket_id, error = %err, "Rejected socket handshake token");
+            socket.emit("auth:error", &AuthResponse::error(err)).ok();
+            socket.clone().disconnect().ok();
+            false
+        }
+    }
+}
+
 /// Handle authentication request
 pub async fn handle_auth<A: Adapter>(
     socket: SocketRef<A>,
@@ -63,17 +200,20 @@ pub async fn handle_auth<A: Adapter>(
 
     // Rate limit by IP (unauthenticated, so we use IP)
     let client_ip = get_socket_ip(&socket, state.config());
-    match check_rate_limit(state.redis(), &SocketRateLimitConfig::AUTH, &client_ip).await {
+    match check_rate_limit(
+        state.redis(),
+        &SocketRateLimitConfig::AUTH,
+        &client_ip,
+        state.socket_rate_limit_multiplier(),
+    )
+    .await
+    {
         Ok(result) if result.allowed => {}
         Ok(_) => {
             socket
                 .emit(
                     "auth:error",
-                    &AuthResponse {
-                        success: false,
-                        user_id: None,
-                        error: Some("Too many authentication attempts, please wait".to_string()),
-                    },
+                    &AuthResponse::error("Too many authentication attempts, please wait"),
                 )
                 .ok();
             return;
@@ -84,6 +224,10 @@ pub async fn handle_auth<A: Adapter>(
         }
     }
 
+    let Some(version) = negotiate_protocol(&socket, &state, payload.protocol_version).await else {
+        return;
+    };
+
     // Try to get session_id from payload first, then from cookie
     let session_id = if payload.session_id.is_empty() {
         extract_session_from_cookie(&socket)
@@ -92,16 +236,7 @@ pub async fn handle_auth<A: Adapter>(
     };
 
     let Some(session_id) = session_id else {
-        socket
-            .emit(
-                "auth:error",
-                &AuthResponse {
-                    success: false,
-                    user_id: None,
-                    error: Some("No session found".to_string()),
-                },
-            )
-            .ok();
+        socket.emit("auth:error", &AuthResponse::error("No session found")).ok();
         return;
     };
 
@@ -109,27 +244,25 @@ pub async fn handle_auth<A: Adapter>(
         Ok(user_id) => {
             // Register socket-user mapping
             state.register_socket(&socket_id, &user_id).await;
+            join_user_room(&socket, &state, &user_id).await;
 
-            socket
-                .emit(
-                    "auth:success",
-                    &AuthResponse { success: true, user_id: Some(user_id.clone()), error: None },
-                )
-                .ok();
+            socket.emit("auth:success", &AuthResponse::success(user_id.clone(), version)).ok();
 
             tracing::info!("Socket {} authenticated as user {}", socket_id, user_id);
         }
         Err(err) => {
-            socket
-                .emit(
-                    "auth:error",
-                    &AuthResponse { success: false, user_id: None, error: Some(err) },
-                )
-                .ok();
+            socket.emit("auth:error", &AuthResponse::error(err)).ok();
         }
     }
 }
 
+/// Join the user's personal room, which receives friend events from any
+/// instance, and mark the user online.
+async fn join_user_room<A: Adapter>(socket: &SocketRef<A>, state: &AppState, user_id: &str) {
+    socket.join(user_id.to_string());
+    presence::socket_connected(state, user_id, &socket.id.to_string()).await;
+}
+
 /// Validate session and return user ID
 async fn authenticate(state: &AppState, session_id: &str) -> Result<String, String> {
     // Validate session
@@ -142,6 +275,18 @@ async fn authenticate(state: &AppState, session_id: &str) -> Result<String, Stri
         }
     };
 
+    // Admins signed in as a user for support cannot play as them
+    match dguesser_db::impersonation::get_by_session(state.db(), session_id).await {
+        Ok(Some(impersonation)) if impersonation.ended_at.is_none() => {
+            return Err("Impersonation sessions cannot connect".to_string());
+        }
+        Ok(_) => {}
+        Err(e) => {
+            tracing::error!(error = %e, "Database error during impersonation check");
+            return Err("An internal error occurred".to_string());
+        }
+    }
+
     // Touch session to update last_accessed_at
     dguesser_db::sessions::touch(state.db(), session_id).await.ok();
 
@@ -0,0 +1,188 @@
+//! Friend event handlers
+
+use dguesser_db::games::GameStatus;
+use dguesser_protocol::socket::events;
+use dguesser_protocol::socket::payloads::{
+    ErrorPayload, FriendInvitePayload, FriendInviteSentPayload, FriendInvitedPayload, LobbyKind,
+};
+use dguesser_push::Notification;
+use socketioxide::adapter::Adapter;
+use socketioxide::extract::{Data, SocketRef, State};
+
+use crate::rate_limit::{SocketRateLimitConfig, check_rate_limit};
+use crate::state::AppState;
+
+/// Rate limit config for friend invites
+const FRIEND_INVITE_LIMIT: SocketRateLimitConfig =
+    SocketRateLimitConfig { event: "friend:invite", max_requests: 20, window_secs: 60 };
+
+/// Handle inviting a friend to the sender's party or game lobby
+pub async fn handle_invite<A: Adapter>(
+    socket: SocketRef<A>,
+    State(state): State<AppState>,
+    Data(payload): Data<FriendInvitePayload>,
+) {
+    let socket_id = socket.id.to_string();
+
+    let user_id = match state.get_user_for_socket(&socket_id).await {
+        Some(id) => id,
+        None => {
+            emit_error(&socket, "NOT_AUTHENTICATED", "Please authenticate first");
+            return;
+        }
+    };
+
+    match check_rate_limit(
+        state.redis(),
+        &FRIEND_INVITE_LIMIT,
+        &user_id,
+        state.socket_rate_limit_multiplier(),
+    )
+    .await
+    {
+        Ok(result) if result.allowed => {}
+        Ok(_) => {
+            emit_error(&socket, "RATE_LIMITED", "Too many invites, please slow down");
+            return;
+        }
+        Err(e) => {
+            tracing::error!(error = %e, event = FRIEND_INVITE_LIMIT.event, "Rate limit Redis error");
+        }
+    }
+
+    match dguesser_db::friends::are_friends(state.db(), &user_id, &payload.user_id).await {
+        Ok(true) => {}
+        Ok(false) => {
+            emit_error(&socket, "NOT_FRIENDS", "You can only invite friends");
+            return;
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to check friendship");
+            emit_error(&socket, "INTERNAL_ERROR", "An internal error occurred");
+            return;
+        }
+    }
+
+    let (lobby_kind, join_code) = match lobby_join_code(&state, &user_id, &payload.lobby_id).await {
+        Ok(lobby) => lobby,
+        Err((code, message)) => {
+            emit_error(&socket, code, message);
+            return;
+        }
+    };
+
+    let sender = match dguesser_db::users::get_by_id(state.db(), &user_id).await {
+        Ok(Some(u)) => u,
+        Ok(None) => {
+            emit_error(&socket, "USER_NOT_FOUND", "User not found");
+            return;
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to get user");
+            emit_error(&socket, "INTERNAL_ERROR", "An internal error occurred");
+            return;
+        }
+    };
+
+    // Also reach the friend when the app is closed
+    let lobby_path = match lobby_kind {
+        LobbyKind::Party => "party",
+        LobbyKind::Game => "game",
+    };
+    let notification = Notification::friend_invite(
+        &sender.display_name,
+        lobby_path,
+        &payload.lobby_id,
+        &join_code,
+    );
+    if let Err(e) = dguesser_push::queue::notify(state.db(), &payload.user_id, &notification).await
+    {
+        tracing::warn!(error = %e, "Failed to queue friend invite push");
+    }
+
+    let invited = FriendInvitedPayload {
+        from_user_id: user_id.clone(),
+        from_display_name: sender.display_name,
+        lobby_kind,
+        lobby_id: payload.lobby_id.clone(),
+        join_code,
+    };
+    if let Err(e) =
+        state.emitter().emit_to_room(&payload.user_id, events::friend::INVITED, &invited).await
+    {
+        tracing::error!(error = %e, "Failed to deliver friend invite");
+        emit_error(&socket, "INVITE_FAILED", "Failed to send invite");
+        return;
+    }
+
+    socket
+        .emit(
+            events::friend::INVITE_SENT,
+            &FriendInviteSentPayload { user_id: payload.user_id.clone() },
+        )
+        .ok();
+
+    tracing::info!(
+        user_id = %user_id,
+        friend_id = %payload.user_id,
+        lobby_id = %payload.lobby_id,
+        "Friend invited to lobby"
+    );
+}
+
+/// Look up the join code of a party or game lobby the user belongs to.
+async fn lobby_join_code(
+    state: &AppState,
+    user_id: &str,
+    lobby_id: &str,
+) -> Result<(LobbyKind, String), (&'static str, &'static str)> {
+    let internal = |e: sqlx::Error| {
+        tracing::error!(error = %e, lobby_id = %lobby_id, "Failed to look up lobby");
+        ("INTERNAL_ERROR", "An internal error occurred")
+    };
+
+    if lobby_id.starts_with("pty_") {
+        let party = dguesser_db::parties::get_party_by_id(state.db(), lobby_id)
+            .await
+            .map_err(internal)?
+            .filter(|p| p.status == "active")
+            .ok_or(("LOBBY_NOT_FOUND", "Party not found"))?;
+        let members = dguesser_db::parties::get_party_members(state.db(), lobby_id)
+            .await
+            .map_err(internal)?;
+        if !members.iter().any(|m| m.user_id == user_id) {
+            return Err(("NOT_IN_LOBBY", "You are not in this party"));
+        }
+        return Ok((LobbyKind::Party, party.join_code));
+    }
+
+    if lobby_id.starts_with("gam_") {
+        let game = dguesser_db::games::get_game_by_id(state.db(), lobby_id)
+            .await
+            .map_err(internal)?
+            .ok_or(("LOBBY_NOT_FOUND", "Game not found"))?;
+        if game.status != GameStatus::Lobby {
+            return Err(("GAME_STARTED", "This game has already started"));
+        }
+        let join_code = game.join_code.ok_or(("LOBBY_NOT_FOUND", "Game not found"))?;
+        if !dguesser_db::games::is_player_in_game(state.db(), lobby_id, user_id)
+            .await
+            .map_err(internal)?
+        {
+            return Err(("NOT_IN_LOBBY", "You are not in this game"));
+        }
+        return Ok((LobbyKind::Game, join_code));
+    }
+
+    Err(("LOBBY_NOT_FOUND", "Unknown lobby"))
+}
+
+/// Emit an error to the socket
+fn emit_error<A: Adapter>(socket: &SocketRef<A>, code: &str, message: &str) {
+    socket
+        .emit(
+            events::friend::ERROR,
+            &ErrorPayload { code: code.to_string(), message: message.to_string() },
+        )
+        .ok();
+}
@@ -24,6 +24,22 @@ pub struct GuessPayload {
     pub lat: f64,
     pub lng: f64,
     pub time_taken_ms: Option<u32>,
+    /// Panorama the client was viewing when it guessed
+    #[serde(default)]
+    pub panorama_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HintPayload {
+    /// Game ID (prefixed nanoid: gam_xxxxxxxxxxxx)
+    pub game_id: String,
+    /// Hint kind (continent, country_letter, direction)
+    pub kind: String,
+    /// Point the direction hint is measured from (usually the player's marker)
+    #[serde(default)]
+    pub from_lat: Option<f64>,
+    #[serde(default)]
+    pub from_lng: Option<f64>,
 }
 
 /// Handle player joining a game
@@ -48,6 +64,14 @@ pub async fn handle_join<A: Adapter>(
         return;
     }
 
+    // Sockets authenticated with a game-bound token may only join that game
+    if let Some(bound_game) = state.get_socket_game(&socket_id).await
+        && bound_game != payload.game_id
+    {
+        emit_error(&socket, "TOKEN_SCOPE", "Socket token is not valid for this game");
+        return;
+    }
+
     // Verify game exists and player can join
     let game = match dguesser_db::games::get_game_by_id(state.db(), &payload.game_id).await {
         Ok(Some(g)) => g,
@@ -252,6 +276,7 @@ pub async fn handle_guess<A: Adapter>(
             lat: payload.lat,
             lng: payload.lng,
             time_ms: payload.time_taken_ms,
+            panorama_id: payload.panorama_id,
             respond: tx,
         })
         .await
@@ -293,6 +318,14 @@ pub struct UpdateSettingsPayload {
     pub movement_allowed: Option<bool>,
     pub zoom_allowed: Option<bool>,
     pub rotation_allowed: Option<bool>,
+    pub time_budget_seconds: Option<u32>,
+    pub reconnect_grace_seconds: Option<u32>,
+    pub disconnect_policy: Option<dguesser_core::game::DisconnectPolicy>,
+    pub reactions_enabled: Option<bool>,
+    pub allow_late_join: Option<bool>,
+    pub hints_enabled: Option<bool>,
+    pub hint_cost: Option<u32>,
+    pub rounds_plan: Option<Vec<dguesser_core::game::MapRounds>>,
 }
 
 /// Handle settings update from the host (lobby only)
@@ -354,6 +387,21 @@ pub async fn handle_update_settings<A: Adapter>(
         movement_allowed: payload.movement_allowed.unwrap_or(current_settings.movement_allowed),
         zoom_allowed: payload.zoom_allowed.unwrap_or(current_settings.zoom_allowed),
         rotation_allowed: payload.rotation_allowed.unwrap_or(current_settings.rotation_allowed),
+        scoring: current_settings.scoring.clone(),
+        streak: current_settings.streak,
+        time_budget_seconds: payload
+            .time_budget_seconds
+            .unwrap_or(current_settings.time_budget_seconds),
+        reconnect_grace_seconds: payload
+            .reconnect_grace_seconds
+            .unwrap_or(current_settings.reconnect_grace_seconds),
+        disconnect_policy: payload.disconnect_policy.unwrap_or(current_settings.disconnect_policy),
+        reactions_enabled: payload.reactions_enabled.unwrap_or(current_settings.reactions_enabled),
+        allow_late_join: payload.allow_late_join.unwrap_or(current_settings.allow_late_join),
+        rounds_plan: payload.rounds_plan.unwrap_or_else(|| current_settings.rounds_plan.clone()),
+        classroom: current_settings.classroom,
+        hints_enabled: payload.hints_enabled.unwrap_or(current_settings.hints_enabled),
+        hint_cost: payload.hint_cost.unwrap_or(current_settings.hint_cost),
     };
 
     let (tx, rx) = oneshot::channel();
@@ -384,6 +432,71 @@ pub async fn handle_update_settings<A: Adapter>(
     }
 }
 
+/// Payload for setting a player's score multiplier
+#[derive(Debug, Deserialize)]
+pub struct SetHandicapPayload {
+    /// Game ID (prefixed nanoid: gam_xxxxxxxxxxxx)
+    pub game_id: String,
+    /// Player whose multiplier changes (usr_xxxxxxxxxxxx)
+    pub user_id: String,
+    /// Multiplier applied to the player's round scores
+    pub handicap: f64,
+}
+
+/// Handle the host setting a player's handicap (lobby only)
+pub async fn handle_set_handicap<A: Adapter>(
+    socket: SocketRef<A>,
+    State(state): State<AppState>,
+    Data(payload): Data<SetHandicapPayload>,
+) {
+    let socket_id = socket.id.to_string();
+
+    let user_id = match state.get_user_for_socket(&socket_id).await {
+        Some(id) => id,
+        None => {
+            emit_error(&socket, "NOT_AUTHENTICATED", "Please authenticate first");
+            return;
+        }
+    };
+
+    // Same budget as settings changes - both are host lobby edits
+    if !check_user_rate_limit(&state, &SocketRateLimitConfig::UPDATE_SETTINGS, &user_id, &socket)
+        .await
+    {
+        return;
+    }
+
+    let handle = match state.get_game(&payload.game_id).await {
+        Some(h) => h,
+        None => {
+            emit_error(&socket, "GAME_NOT_FOUND", "Game not active");
+            return;
+        }
+    };
+
+    let (tx, rx) = oneshot::channel();
+    if handle
+        .tx
+        .send(GameCommand::SetHandicap {
+            user_id,
+            player_id: payload.user_id,
+            handicap: payload.handicap,
+            respond: tx,
+        })
+        .await
+        .is_err()
+    {
+        emit_error(&socket, "GAME_ERROR", "Failed to set handicap");
+        return;
+    }
+
+    match rx.await {
+        Ok(Ok(())) => {}
+        Ok(Err(err)) => emit_error(&socket, "HANDICAP_FAILED", &err),
+        Err(_) => emit_error(&socket, "GAME_ERROR", "Game actor unavailable"),
+    }
+}
+
 /// Handle host force-skipping the between-rounds wait
 pub async fn handle_skip_wait<A: Adapter>(
     socket: SocketRef<A>,
@@ -437,6 +550,211 @@ pub async fn handle_skip_wait<A: Adapter>(
     }
 }
 
+/// Handle the host rerolling the current round's location
+pub async fn handle_reroll<A: Adapter>(
+    socket: SocketRef<A>,
+    State(state): State<AppState>,
+    Data(payload): Data<JoinPayload>,
+) {
+    let socket_id = socket.id.to_string();
+
+    let user_id = match state.get_user_for_socket(&socket_id).await {
+        Some(id) => id,
+        None => {
+            emit_error(&socket, "NOT_AUTHENTICATED", "Please authenticate first");
+            return;
+        }
+    };
+
+    if !check_user_rate_limit(&state, &SocketRateLimitConfig::REROLL, &user_id, &socket).await {
+        return;
+    }
+
+    let handle = match state.get_game(&payload.game_id).await {
+        Some(h) => h,
+        None => {
+            emit_error(&socket, "GAME_NOT_FOUND", "Game not active");
+            return;
+        }
+    };
+
+    let (tx, rx) = oneshot::channel();
+    if handle.tx.send(GameCommand::Reroll { user_id: user_id.clone(), respond: tx }).await.is_err()
+    {
+        emit_error(&socket, "GAME_ERROR", "Failed to reroll round");
+        return;
+    }
+
+    match rx.await {
+        Ok(Ok(())) => {
+            tracing::info!("Host {} rerolled the round for game {}", user_id, payload.game_id);
+        }
+        Ok(Err(err)) => {
+            emit_error(&socket, "REROLL_FAILED", &err);
+        }
+        Err(_) => {
+            emit_error(&socket, "GAME_ERROR", "Game actor unavailable");
+        }
+    }
+}
+
+/// Handle the teacher pausing a classroom game
+pub async fn handle_pause<A: Adapter>(
+    socket: SocketRef<A>,
+    State(state): State<AppState>,
+    Data(payload): Data<JoinPayload>,
+) {
+    let socket_id = socket.id.to_string();
+
+    let user_id = match state.get_user_for_socket(&socket_id).await {
+        Some(id) => id,
+        None => {
+            emit_error(&socket, "NOT_AUTHENTICATED", "Please authenticate first");
+            return;
+        }
+    };
+
+    if !check_user_rate_limit(&state, &SocketRateLimitConfig::CLASSROOM_PAUSE, &user_id, &socket)
+        .await
+    {
+        return;
+    }
+
+    let handle = match state.get_game(&payload.game_id).await {
+        Some(h) => h,
+        None => {
+            emit_error(&socket, "GAME_NOT_FOUND", "Game not active");
+            return;
+        }
+    };
+
+    let (tx, rx) = oneshot::channel();
+    if handle.tx.send(GameCommand::Pause { user_id: user_id.clone(), respond: tx }).await.is_err() {
+        emit_error(&socket, "GAME_ERROR", "Failed to pause game");
+        return;
+    }
+
+    match rx.await {
+        Ok(Ok(())) => {
+            tracing::info!("Teacher {} paused game {}", user_id, payload.game_id);
+        }
+        Ok(Err(err)) => {
+            emit_error(&socket, "PAUSE_FAILED", &err);
+        }
+        Err(_) => {
+            emit_error(&socket, "GAME_ERROR", "Game actor unavailable");
+        }
+    }
+}
+
+/// Handle the teacher resuming a classroom game
+pub async fn handle_resume<A: Adapter>(
+    socket: SocketRef<A>,
+    State(state): State<AppState>,
+    Data(payload): Data<JoinPayload>,
+) {
+    let socket_id = socket.id.to_string();
+
+    let user_id = match state.get_user_for_socket(&socket_id).await {
+        Some(id) => id,
+        None => {
+            emit_error(&socket, "NOT_AUTHENTICATED", "Please authenticate first");
+            return;
+        }
+    };
+
+    if !check_user_rate_limit(&state, &SocketRateLimitConfig::CLASSROOM_PAUSE, &user_id, &socket)
+        .await
+    {
+        return;
+    }
+
+    let handle = match state.get_game(&payload.game_id).await {
+        Some(h) => h,
+        None => {
+            emit_error(&socket, "GAME_NOT_FOUND", "Game not active");
+            return;
+        }
+    };
+
+    let (tx, rx) = oneshot::channel();
+    if handle.tx.send(GameCommand::Resume { user_id: user_id.clone(), respond: tx }).await.is_err()
+    {
+        emit_error(&socket, "GAME_ERROR", "Failed to resume game");
+        return;
+    }
+
+    match rx.await {
+        Ok(Ok(())) => {
+            tracing::info!("Teacher {} resumed game {}", user_id, payload.game_id);
+        }
+        Ok(Err(err)) => {
+            emit_error(&socket, "RESUME_FAILED", &err);
+        }
+        Err(_) => {
+            emit_error(&socket, "GAME_ERROR", "Game actor unavailable");
+        }
+    }
+}
+
+/// Handle a player asking for a hint on the current round
+pub async fn handle_request_hint<A: Adapter>(
+    socket: SocketRef<A>,
+    State(state): State<AppState>,
+    Data(payload): Data<HintPayload>,
+) {
+    let socket_id = socket.id.to_string();
+
+    let user_id = match state.get_user_for_socket(&socket_id).await {
+        Some(id) => id,
+        None => {
+            emit_error(&socket, "NOT_AUTHENTICATED", "Please authenticate first");
+            return;
+        }
+    };
+
+    if !check_user_rate_limit(&state, &SocketRateLimitConfig::HINT, &user_id, &socket).await {
+        return;
+    }
+
+    let Ok(kind) = payload.kind.parse::<dguesser_core::game::HintKind>() else {
+        emit_error(&socket, "INVALID_HINT", "Unknown hint kind");
+        return;
+    };
+    let from = payload.from_lat.zip(payload.from_lng);
+
+    let handle = match state.get_game(&payload.game_id).await {
+        Some(h) => h,
+        None => {
+            emit_error(&socket, "GAME_NOT_FOUND", "Game not active");
+            return;
+        }
+    };
+
+    let (tx, rx) = oneshot::channel();
+    if handle
+        .tx
+        .send(GameCommand::RequestHint { user_id: user_id.clone(), kind, from, respond: tx })
+        .await
+        .is_err()
+    {
+        emit_error(&socket, "GAME_ERROR", "Failed to request hint");
+        return;
+    }
+
+    match rx.await {
+        Ok(Ok(())) => {
+            tracing::debug!("Player {} took a hint in game {}", user_id, payload.game_id);
+        }
+        Ok(Err(err)) => {
+            emit_error(&socket, "HINT_FAILED", &err);
+        }
+        Err(_) => {
+            emit_error(&socket, "GAME_ERROR", "Game actor unavailable");
+        }
+    }
+}
+
 /// Handle player voting to skip the between-rounds wait
 pub async fn handle_vote_skip<A: Adapter>(
     socket: SocketRef<A>,
@@ -532,7 +850,9 @@ async fn check_user_rate_limit<A: Adapter>(
     user_id: &str,
     socket: &SocketRef<A>,
 ) -> bool {
-    match check_rate_limit(state.redis(), config, user_id).await {
+    match check_rate_limit(state.redis(), config, user_id, state.socket_rate_limit_multiplier())
+        .await
+    {
         Ok(result) if result.allowed => true,
         Ok(_) => {
             emit_error(socket, "RATE_LIMITED", "Too many requests, please slow down");
@@ -1,8 +1,11 @@
 //! Socket.IO event handlers
 
 pub mod auth;
+pub mod friends;
 pub mod game;
 pub mod party;
+pub mod reactions;
+pub mod time;
 
 use std::time::Duration;
 
@@ -11,11 +14,14 @@ use socketioxide::extract::{SocketRef, State};
 use socketioxide::socket::DisconnectReason;
 use tracing::info;
 
+use crate::presence;
 use crate::state::{AppState, GameCommand, PartyCommand};
 
 /// Timeout for unauthenticated socket connections (in seconds)
-/// Sockets that don't authenticate within this time will be disconnected
-const AUTH_TIMEOUT_SECS: u64 = 30;
+/// Sockets that don't authenticate within this time will be disconnected.
+/// Clients send `auth` right after connecting, so this only needs to cover
+/// one round-trip; sockets with a handshake token never wait at all.
+const AUTH_TIMEOUT_SECS: u64 = 10;
 
 /// Main connection handler - called when a socket connects
 pub async fn on_connect<A: Adapter>(socket: SocketRef<A>, State(state): State<AppState>) {
@@ -31,10 +37,18 @@ pub async fn on_connect<A: Adapter>(socket: SocketRef<A>, State(state): State<Ap
     socket.on("game:leave", game::handle_leave::<A>);
     socket.on("game:start", game::handle_start::<A>);
     socket.on("game:update_settings", game::handle_update_settings::<A>);
+    socket.on("player:set_handicap", game::handle_set_handicap::<A>);
     socket.on("guess:submit", game::handle_guess::<A>);
     socket.on("round:skip", game::handle_skip_wait::<A>);
     socket.on("round:vote_skip", game::handle_vote_skip::<A>);
+    socket.on("game:reroll", game::handle_reroll::<A>);
+    socket.on("classroom:pause", game::handle_pause::<A>);
+    socket.on("classroom:resume", game::handle_resume::<A>);
+    socket.on("hint:request", game::handle_request_hint::<A>);
     socket.on("player:ready", game::handle_ready::<A>);
+    socket.on("game:react", reactions::handle_react::<A>);
+    socket.on("time:sync", time::handle_sync::<A>);
+    socket.on("time:pong", time::handle_pong::<A>);
 
     // Party event handlers
     socket.on("party:create", party::handle_create_party::<A>);
@@ -43,11 +57,21 @@ pub async fn on_connect<A: Adapter>(socket: SocketRef<A>, State(state): State<Ap
     socket.on("party:start_game", party::handle_start_game::<A>);
     socket.on("party:update_settings", party::handle_update_settings::<A>);
     socket.on("party:kick", party::handle_kick::<A>);
+    socket.on("party:mute", party::handle_mute::<A>);
     socket.on("party:disband", party::handle_disband::<A>);
+    socket.on("party:chat", party::handle_chat::<A>);
+
+    // Friend event handlers
+    socket.on("friend:invite", friends::handle_invite::<A>);
 
     // Handle disconnect
     socket.on_disconnect(handle_disconnect::<A>);
 
+    // Sockets presenting a valid handshake token are authenticated immediately
+    if auth::authenticate_handshake(&socket, &state).await {
+        return;
+    }
+
     // Spawn auth timeout task - disconnect if not authenticated within timeout
     let timeout_socket = socket.clone();
     let timeout_state = state.clone();
@@ -77,6 +101,11 @@ async fn handle_disconnect<A: Adapter>(
     let socket_id = socket.id.to_string();
     info!("Socket disconnected: {} - {:?}", socket_id, reason);
 
+    // Presence is tracked per socket, so every authenticated socket is removed
+    if let Some(user_id) = state.get_user_for_socket(&socket_id).await {
+        presence::socket_disconnected(&state, &user_id, &socket_id).await;
+    }
+
     // Get user for this socket
     if let Some(user_id) = state.unregister_socket(&socket_id).await {
         // Get all rooms this socket was in
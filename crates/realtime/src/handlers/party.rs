@@ -1,12 +1,14 @@
 //! Party event handlers
 
 use dguesser_core::game::GameSettings;
+use dguesser_core::join_code::{DEFAULT_JOIN_CODE_LENGTH, generate_join_code};
 use dguesser_protocol::socket::payloads::{ErrorPayload, GameSettingsPayload, PartyCreatedPayload};
 use serde::Deserialize;
 use socketioxide::adapter::Adapter;
 use socketioxide::extract::{Data, SocketRef, State};
 use tokio::sync::oneshot;
 
+use crate::actors::rounds_plan_from_payload;
 use crate::rate_limit::{SocketRateLimitConfig, check_rate_limit};
 use crate::state::{AppState, PartyCommand};
 
@@ -17,6 +19,8 @@ const PARTY_JOIN_LIMIT: SocketRateLimitConfig =
     SocketRateLimitConfig { event: "party:join", max_requests: 10, window_secs: 60 };
 const PARTY_ACTION_LIMIT: SocketRateLimitConfig =
     SocketRateLimitConfig { event: "party:action", max_requests: 20, window_secs: 60 };
+const PARTY_CHAT_LIMIT: SocketRateLimitConfig =
+    SocketRateLimitConfig { event: "party:chat", max_requests: 10, window_secs: 10 };
 
 // =========================================================================
 // Payloads
@@ -30,8 +34,6 @@ pub struct CreatePartyPayload {
 #[derive(Debug, Deserialize)]
 pub struct JoinPartyPayload {
     pub party_id: String,
-    /// Optional legacy join code (party ID links are sufficient)
-    pub code: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -51,6 +53,19 @@ pub struct KickPayload {
     pub user_id: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct MutePayload {
+    pub party_id: String,
+    pub user_id: String,
+    pub muted: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChatPayload {
+    pub party_id: String,
+    pub content: String,
+}
+
 // =========================================================================
 // Handlers
 // =========================================================================
@@ -104,7 +119,7 @@ pub async fn handle_create_party<A: Adapter>(
     };
 
     let party_id = dguesser_core::generate_party_id();
-    let join_code = generate_join_code();
+    let join_code = generate_join_code(DEFAULT_JOIN_CODE_LENGTH);
 
     // Convert settings payload to GameSettings
     let settings = payload
@@ -116,8 +131,22 @@ pub async fn handle_create_party<A: Adapter>(
             movement_allowed: s.movement_allowed,
             zoom_allowed: s.zoom_allowed,
             rotation_allowed: s.rotation_allowed,
+            scoring: Default::default(),
+            streak: false,
+            time_budget_seconds: s.time_budget_seconds,
+            reconnect_grace_seconds: s.reconnect_grace_seconds,
+            disconnect_policy: s.disconnect_policy.parse().unwrap_or_default(),
+            reactions_enabled: s.reactions_enabled,
+            allow_late_join: s.allow_late_join,
+            rounds_plan: rounds_plan_from_payload(&s.rounds_plan),
+            classroom: s.classroom,
+            hints_enabled: s.hints_enabled,
+            hint_cost: s.hint_cost,
         })
-        .unwrap_or_default();
+        .unwrap_or_else(|| GameSettings {
+            reconnect_grace_seconds: state.default_reconnect_grace_secs(),
+            ..Default::default()
+        });
 
     let settings_json = serde_json::to_value(&settings).unwrap_or_default();
 
@@ -442,6 +471,17 @@ pub async fn handle_update_settings<A: Adapter>(
         movement_allowed: payload.settings.movement_allowed,
         zoom_allowed: payload.settings.zoom_allowed,
         rotation_allowed: payload.settings.rotation_allowed,
+        scoring: Default::default(),
+        streak: false,
+        time_budget_seconds: payload.settings.time_budget_seconds,
+        reconnect_grace_seconds: payload.settings.reconnect_grace_seconds,
+        disconnect_policy: payload.settings.disconnect_policy.parse().unwrap_or_default(),
+        reactions_enabled: payload.settings.reactions_enabled,
+        allow_late_join: payload.settings.allow_late_join,
+        rounds_plan: rounds_plan_from_payload(&payload.settings.rounds_plan),
+        classroom: payload.settings.classroom,
+        hints_enabled: payload.settings.hints_enabled,
+        hint_cost: payload.settings.hint_cost,
     };
 
     let (tx, rx) = oneshot::channel();
@@ -508,6 +548,103 @@ pub async fn handle_kick<A: Adapter>(
     }
 }
 
+/// Handle muting or unmuting a member in chat
+pub async fn handle_mute<A: Adapter>(
+    socket: SocketRef<A>,
+    State(state): State<AppState>,
+    Data(payload): Data<MutePayload>,
+) {
+    let socket_id = socket.id.to_string();
+
+    let user_id = match state.get_user_for_socket(&socket_id).await {
+        Some(id) => id,
+        None => {
+            emit_error(&socket, "NOT_AUTHENTICATED", "Please authenticate first");
+            return;
+        }
+    };
+
+    if !check_user_rate_limit(&state, &PARTY_ACTION_LIMIT, &user_id, &socket).await {
+        return;
+    }
+
+    let handle = match state.get_party(&payload.party_id).await {
+        Some(h) => h,
+        None => {
+            emit_error(&socket, "PARTY_NOT_FOUND", "Party not found");
+            return;
+        }
+    };
+
+    let (tx, rx) = oneshot::channel();
+    if handle
+        .tx
+        .send(PartyCommand::Mute {
+            user_id,
+            target_user_id: payload.user_id,
+            muted: payload.muted,
+            respond: tx,
+        })
+        .await
+        .is_err()
+    {
+        emit_error(&socket, "PARTY_ERROR", "Party actor unavailable");
+        return;
+    }
+
+    match rx.await {
+        Ok(Ok(())) => {}
+        Ok(Err(err)) => emit_error(&socket, "MUTE_FAILED", &err),
+        Err(_) => emit_error(&socket, "PARTY_ERROR", "Party actor unavailable"),
+    }
+}
+
+/// Handle a chat message sent to the party
+pub async fn handle_chat<A: Adapter>(
+    socket: SocketRef<A>,
+    State(state): State<AppState>,
+    Data(payload): Data<ChatPayload>,
+) {
+    let socket_id = socket.id.to_string();
+
+    let user_id = match state.get_user_for_socket(&socket_id).await {
+        Some(id) => id,
+        None => {
+            emit_error(&socket, "NOT_AUTHENTICATED", "Please authenticate first");
+            return;
+        }
+    };
+
+    if !check_user_rate_limit(&state, &PARTY_CHAT_LIMIT, &user_id, &socket).await {
+        return;
+    }
+
+    let handle = match state.get_party(&payload.party_id).await {
+        Some(h) => h,
+        None => {
+            emit_error(&socket, "PARTY_NOT_FOUND", "Party not found");
+            return;
+        }
+    };
+
+    let (tx, rx) = oneshot::channel();
+    if handle
+        .tx
+        .send(PartyCommand::Chat { user_id, content: payload.content, respond: tx })
+        .await
+        .is_err()
+    {
+        emit_error(&socket, "PARTY_ERROR", "Party actor unavailable");
+        return;
+    }
+
+    match rx.await {
+        Ok(Ok(())) => {}
+        Ok(Err(err)) => emit_error(&socket, "CHAT_FAILED", &err),
+        Err(_) => emit_error(&socket, "PARTY_ERROR", "Party actor unavailable"),
+    }
+}
+
 /// Handle disbanding a party
 pub async fn handle_disband<A: Adapter>(
     socket: SocketRef<A>,
@@ -558,7 +695,9 @@ async fn check_user_rate_limit<A: Adapter>(
     user_id: &str,
     socket: &SocketRef<A>,
 ) -> bool {
-    match check_rate_limit(state.redis(), config, user_id).await {
+    match check_rate_limit(state.redis(), config, user_id, state.socket_rate_limit_multiplier())
+        .await
+    {
         Ok(result) if result.allowed => true,
         Ok(_) => {
             emit_error(socket, "RATE_LIMITED", "Too many requests, please slow down");
@@ -577,16 +716,3 @@ fn emit_error<A: Adapter>(socket: &SocketRef<A>, code: &str, message: &str) {
         .emit("party:error", &ErrorPayload { code: code.to_string(), message: message.to_string() })
         .ok();
 }
-
-/// Generate a 6-character join code
-fn generate_join_code() -> String {
-    use rand::RngExt;
-    const CHARSET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
-    let mut rng = rand::rng();
-    (0..6)
-        .map(|_| {
-            let idx = rng.random_range(0..CHARSET.len());
-            CHARSET[idx] as char
-        })
-        .collect()
-}
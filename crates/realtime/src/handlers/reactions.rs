@@ -0,0 +1,94 @@
+//! Reaction event handlers
+//!
+//! Players can send emoji reactions during a game. Reactions are broadcast to
+//! the game room by the game actor and never stored.
+
+use dguesser_protocol::socket::payloads::{ErrorPayload, REACTIONS, ReactPayload};
+use socketioxide::adapter::Adapter;
+use socketioxide::extract::{Data, SocketRef, State};
+use tokio::sync::oneshot;
+
+use crate::rate_limit::{SocketRateLimitConfig, check_rate_limit};
+use crate::state::{AppState, GameCommand};
+
+/// Handle a player sending a reaction
+pub async fn handle_react<A: Adapter>(
+    socket: SocketRef<A>,
+    State(state): State<AppState>,
+    Data(payload): Data<ReactPayload>,
+) {
+    let socket_id = socket.id.to_string();
+
+    let user_id = match state.get_user_for_socket(&socket_id).await {
+        Some(id) => id,
+        None => {
+            emit_error(&socket, "NOT_AUTHENTICATED", "Please authenticate first");
+            return;
+        }
+    };
+
+    if !check_user_rate_limit(&state, &SocketRateLimitConfig::REACT_BURST, &user_id, &socket).await
+        || !check_user_rate_limit(&state, &SocketRateLimitConfig::REACT, &user_id, &socket).await
+    {
+        return;
+    }
+
+    if !REACTIONS.contains(&payload.reaction.as_str()) {
+        emit_error(&socket, "INVALID_REACTION", "Unknown reaction");
+        return;
+    }
+
+    let handle = match state.get_game(&payload.game_id).await {
+        Some(h) => h,
+        None => {
+            emit_error(&socket, "GAME_NOT_FOUND", "Game not active");
+            return;
+        }
+    };
+
+    let (tx, rx) = oneshot::channel();
+    if handle
+        .tx
+        .send(GameCommand::React { user_id, reaction: payload.reaction, respond: tx })
+        .await
+        .is_err()
+    {
+        emit_error(&socket, "GAME_ERROR", "Game actor unavailable");
+        return;
+    }
+
+    match rx.await {
+        Ok(Ok(())) => {}
+        Ok(Err(err)) => emit_error(&socket, "REACT_FAILED", &err),
+        Err(_) => emit_error(&socket, "GAME_ERROR", "Game actor unavailable"),
+    }
+}
+
+/// Check rate limit for a user
+async fn check_user_rate_limit<A: Adapter>(
+    state: &AppState,
+    config: &SocketRateLimitConfig,
+    user_id: &str,
+    socket: &SocketRef<A>,
+) -> bool {
+    match check_rate_limit(state.redis(), config, user_id, state.socket_rate_limit_multiplier())
+        .await
+    {
+        Ok(result) if result.allowed => true,
+        Ok(_) => {
+            emit_error(socket, "RATE_LIMITED", "Too many reactions, please slow down");
+            false
+        }
+        Err(e) => {
+            tracing::error!(error = %e, event = config.event, "Rate limit Redis error");
+            true // fail-open
+        }
+    }
+}
+
+/// Emit an error to the socket
+fn emit_error<A: Adapter>(socket: &SocketRef<A>, code: &str, message: &str) {
+    socket
+        .emit("error", &ErrorPayload { code: code.to_string(), message: message.to_string() })
+        .ok();
+}
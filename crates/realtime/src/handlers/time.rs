@@ -0,0 +1,126 @@
+//! Clock sync event handlers
+//!
+//! Clients send `time:sync` to learn the server time and correct their
+//! countdowns for clock skew. They echo the reply's `server_time` back in a
+//! `time:pong`, which gives the server a round-trip sample for the player's
+//! game. Only the latest reply of each socket can be answered, so a client
+//! cannot report a round trip shorter than the real one.
+
+use chrono::Utc;
+use dguesser_protocol::socket::events;
+use dguesser_protocol::socket::payloads::{
+    ErrorPayload, TimePongPayload, TimeSyncPayload, TimeSyncRequest,
+};
+use dguesser_protocol::version::capability;
+use socketioxide::adapter::Adapter;
+use socketioxide::extract::{Data, SocketRef, State};
+
+use crate::rate_limit::{SocketRateLimitConfig, check_rate_limit};
+use crate::state::{AppState, GameCommand};
+
+/// Round trips longer than this are dropped as stale replies
+const MAX_RTT_MS: i64 = 60_000;
+
+/// Handle a client asking for the server time
+pub async fn handle_sync<A: Adapter>(
+    socket: SocketRef<A>,
+    State(state): State<AppState>,
+    Data(payload): Data<TimeSyncRequest>,
+) {
+    let socket_id = socket.id.to_string();
+
+    if !state.is_socket_authenticated(&socket_id).await {
+        emit_error(&socket, "NOT_AUTHENTICATED", "Please authenticate first");
+        return;
+    }
+    if !state.socket_supports(&socket_id, capability::CLOCK_SYNC).await {
+        emit_error(&socket, "UNSUPPORTED", "Clock sync needs a newer protocol version");
+        return;
+    }
+
+    match check_rate_limit(
+        state.redis(),
+        &SocketRateLimitConfig::TIME_SYNC,
+        &socket_id,
+        state.socket_rate_limit_multiplier(),
+    )
+    .await
+    {
+        Ok(result) if !result.allowed => {
+            emit_error(&socket, "RATE_LIMITED", "Too many clock syncs, please slow down");
+            return;
+        }
+        Ok(_) => {}
+        Err(e) => {
+            tracing::error!(error = %e, event = "time:sync", "Rate limit Redis error");
+        }
+    }
+
+    let server_time = Utc::now().timestamp_millis();
+    state.set_socket_ping(&socket_id, server_time).await;
+    socket
+        .emit(
+            events::server::TIME_SYNC,
+            &TimeSyncPayload { client_time: payload.client_time, server_time },
+        )
+        .ok();
+}
+
+/// Handle a client echoing a `time:sync` reply
+pub async fn handle_pong<A: Adapter>(
+    socket: SocketRef<A>,
+    State(state): State<AppState>,
+    Data(payload): Data<TimePongPayload>,
+) {
+    let socket_id = socket.id.to_string();
+
+    if !state.take_socket_ping(&socket_id, payload.server_time).await {
+        return;
+    }
+    let Some(rtt_ms) = rtt_since(payload.server_time, Utc::now().timestamp_millis()) else {
+        return;
+    };
+
+    let Some(game_id) = payload.game_id else {
+        return;
+    };
+    // Only record against a game this socket has joined
+    if !socket.rooms().iter().any(|room| room.as_ref() == game_id) {
+        return;
+    }
+    let (Some(user_id), Some(handle)) =
+        (state.get_user_for_socket(&socket_id).await, state.get_game(&game_id).await)
+    else {
+        return;
+    };
+
+    let _ = handle.tx.send(GameCommand::RecordRtt { user_id, rtt_ms }).await;
+}
+
+/// Round-trip time from a reply sent at `sent` and answered at `now`, or
+/// `None` if the clock went backwards or the answer is stale.
+fn rtt_since(sent: i64, now: i64) -> Option<u32> {
+    let rtt = now - sent;
+    (0..=MAX_RTT_MS).contains(&rtt).then_some(rtt as u32)
+}
+
+/// Emit an error to the socket
+fn emit_error<A: Adapter>(socket: &SocketRef<A>, code: &str, message: &str) {
+    socket
+        .emit("error", &ErrorPayload { code: code.to_string(), message: message.to_string() })
+        .ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rtt_since() {
+        assert_eq!(rtt_since(1_000, 1_080), Some(80));
+        assert_eq!(rtt_since(1_000, 1_000), Some(0));
+        assert_eq!(rtt_since(1_000, 999), None);
+        assert_eq!(rtt_since(0, MAX_RTT_MS), Some(MAX_RTT_MS as u32));
+        assert_eq!(rtt_since(0, MAX_RTT_MS + 1), None);
+    }
+}
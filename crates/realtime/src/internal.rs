@@ -0,0 +1,142 @@
+//! Internal debugging endpoints
+//!
+//! Export the full state of a game actor running on this server, or start a
+//! fresh actor from an exported snapshot, e.g. to replay a reducer bug from
+//! production on a development server. Requests need the
+//! `INTERNAL_API_SECRET` as a bearer token; without one configured the
+//! endpoints are not served.
+
+use std::time::Duration;
+
+use axum::extract::{Path, Request, State};
+use axum::http::{StatusCode, header};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Serialize;
+use tokio::sync::oneshot;
+
+use crate::actors::GameSnapshot;
+use crate::state::{AppState, GameCommand};
+
+/// How long to wait for an actor to answer a dump
+const DUMP_TIMEOUT_SECS: u64 = 5;
+
+#[derive(Clone)]
+struct InternalState {
+    app: AppState,
+    secret: String,
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+#[derive(Serialize)]
+struct RestoreResponse {
+    game_id: String,
+    players: usize,
+}
+
+fn error(status: StatusCode, message: impl Into<String>) -> Response {
+    (status, Json(ErrorBody { error: message.into() })).into_response()
+}
+
+/// Build the internal router, or `None` when no secret is configured.
+pub fn router(app: AppState) -> Option<Router> {
+    let secret = app.config().internal_api_secret.clone()?;
+    let state = InternalState { app, secret };
+
+    Some(
+        Router::new()
+            .route("/internal/games/{game_id}/dump", get(dump_game))
+            .route("/internal/games/restore", post(restore_game))
+            .layer(middleware::from_fn_with_state(state.clone(), require_secret))
+            .with_state(state),
+    )
+}
+
+/// Compare without stopping at the first differing byte.
+fn secrets_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given.bytes().zip(expected.bytes()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+async fn require_secret(
+    State(state): State<InternalState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let authorized = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| secrets_match(token, &state.secret));
+
+    if !authorized {
+        return error(StatusCode::UNAUTHORIZED, "Invalid internal API token");
+    }
+    next.run(request).await
+}
+
+/// Write out a local game's pending state and export its full state.
+async fn dump_game(State(state): State<InternalState>, Path(game_id): Path<String>) -> Response {
+    let Some(handle) = state.app.get_game(&game_id).await else {
+        return error(StatusCode::NOT_FOUND, "Game is not running on this server");
+    };
+
+    let (tx, rx) = oneshot::channel();
+    if handle.tx.send(GameCommand::DumpState { respond: tx }).await.is_err() {
+        return error(StatusCode::SERVICE_UNAVAILABLE, "Game actor unavailable");
+    }
+
+    match tokio::time::timeout(Duration::from_secs(DUMP_TIMEOUT_SECS), rx).await {
+        Ok(Ok(Ok(snapshot))) => {
+            tracing::info!(game_id = %game_id, "Exported game state");
+            Json(snapshot).into_response()
+        }
+        Ok(Ok(Err(e))) => error(StatusCode::CONFLICT, e),
+        Ok(Err(_)) => error(StatusCode::SERVICE_UNAVAILABLE, "Game actor unavailable"),
+        Err(_) => error(StatusCode::GATEWAY_TIMEOUT, "Game actor did not answer in time"),
+    }
+}
+
+/// Start a fresh actor from an exported snapshot, replacing any local actor
+/// of the same game.
+async fn restore_game(
+    State(state): State<InternalState>,
+    Json(snapshot): Json<GameSnapshot>,
+) -> Response {
+    if snapshot.state.game_id != snapshot.game_id {
+        return error(StatusCode::BAD_REQUEST, "Snapshot state belongs to another game");
+    }
+
+    let response = RestoreResponse {
+        game_id: snapshot.game_id.clone(),
+        players: snapshot.state.players.len(),
+    };
+    tracing::warn!(
+        game_id = %response.game_id,
+        taken_at = %snapshot.taken_at,
+        "Restoring game from snapshot"
+    );
+    state.app.restore_game(snapshot).await;
+
+    Json(response).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secrets_match() {
+        assert!(secrets_match("s3cret", "s3cret"));
+        assert!(!secrets_match("s3cres", "s3cret"));
+        assert!(!secrets_match("s3cre", "s3cret"));
+        assert!(!secrets_match("", "s3cret"));
+    }
+}
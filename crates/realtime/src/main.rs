@@ -1,14 +1,20 @@
 //! DGuesser Realtime server (Socket.IO)
 
 use std::net::SocketAddr;
+use std::sync::Arc;
 use std::time::Instant;
 
 use axum::routing::get;
 use axum::{Json, Router, extract::State, http::StatusCode};
+use dguesser_core::streetview::{QUOTA_REDIS_KEY, QuotaBreaker, QuotaState};
+use dguesser_locations::LocationHealth;
+use dguesser_locations::health::CheckOutcome;
 use dguesser_protocol::api::service::ServiceInfo;
+use dguesser_protocol::version::ProtocolInfo;
+use dguesser_redis::RedisConnection;
 use serde::Serialize;
 use socketioxide::SocketIo;
-use socketioxide_redis::{RedisAdapter, RedisAdapterCtr};
+use socketioxide_redis::{CustomRedisAdapter, RedisAdapterConfig, RedisAdapterCtr};
 use tokio::signal;
 use tower::ServiceBuilder;
 use tower_http::cors::{AllowOrigin, CorsLayer};
@@ -24,11 +30,17 @@ mod actors;
 mod config;
 mod emitter;
 mod handlers;
+mod internal;
+mod presence;
+mod pubsub;
 mod rate_limit;
 mod redis_state;
+mod runtime_config;
 mod state;
+mod sweeper;
 
 use config::Config;
+use pubsub::{ResilientRedisDriver, SubscriptionHealth};
 use redis_state::RedisStateManager;
 use state::AppState;
 
@@ -48,13 +60,15 @@ async fn main() -> anyhow::Result<()> {
     let db = dguesser_db::create_pool(&config.database_url).await?;
     tracing::info!("Connected to database");
 
-    // Create Redis client (ensure RESP3 protocol for adapter)
-    let redis_url = ensure_resp3_protocol(&config.redis_url);
-    let redis = redis::Client::open(redis_url.as_str())?;
+    // Connect to Redis (directly, through Sentinel, or as a Cluster)
+    let redis = RedisConnection::connect(&config.redis).await?;
     tracing::info!("Connected to Redis");
 
-    // Create Redis adapter for Socket.IO (enables cross-process broadcasts)
-    let adapter = RedisAdapterCtr::new_with_redis(&redis).await?;
+    // Create Redis adapter for Socket.IO (enables cross-process broadcasts),
+    // resubscribing after Redis restarts
+    let driver = ResilientRedisDriver::new(&config.redis).await?;
+    let pubsub = driver.health();
+    let adapter = RedisAdapterCtr::new_with_driver(driver, RedisAdapterConfig::default());
     tracing::info!("Redis adapter initialized for Socket.IO");
 
     // Create Redis state manager
@@ -66,23 +80,37 @@ async fn main() -> anyhow::Result<()> {
     // Create Socket.IO layer with state and Redis adapter
     let (socket_layer, io) = SocketIo::builder()
         .with_state(state.clone())
-        .with_adapter::<RedisAdapter<_>>(adapter)
+        .with_adapter::<CustomRedisAdapter<_, ResilientRedisDriver>>(adapter)
         .build_layer();
 
-    // Initialize the broadcast emitter with a Redis connection
-    let emitter_conn = redis.get_multiplexed_async_connection().await?;
-    state.init_emitter(emitter_conn).await;
+    // Initialize the broadcast emitter with the shared Redis connection
+    state.init_emitter(redis.clone()).await;
     tracing::info!("Broadcast emitter initialized");
 
     // Recover active games from Redis on startup
     recover_active_games(&state).await;
 
+    // Keep this instance's sockets marked online for friends
+    presence::spawn_presence_refresh_task(state.clone());
+
+    // Abandon multiplayer games left idle in lobby or mid-game
+    sweeper::spawn_stale_game_sweeper(state.clone());
+
+    // Apply operational settings reloaded by admins
+    runtime_config::spawn_config_watcher(state.clone());
+
     // Register socket handlers
     io.ns("/", handlers::on_connect).await?;
 
     // Build router with health endpoints
-    let http_state =
-        HttpState { db: state.db().clone(), redis, started_at: Instant::now(), is_production };
+    let http_state = HttpState {
+        db: state.db().clone(),
+        redis,
+        pubsub,
+        locations: state.location_health().clone(),
+        started_at: Instant::now(),
+        is_production,
+    };
 
     // Configure CORS - restrict to frontend origin only
     // Use mirror_request() for methods/headers when credentials are enabled
@@ -99,15 +127,22 @@ async fn main() -> anyhow::Result<()> {
         .allow_headers(AllowHeaders::mirror_request())
         .allow_credentials(true);
 
-    let app = Router::new()
+    let mut app = Router::new()
         .route("/", get(service_info))
         .route("/health", get(health_check))
         .route("/livez", get(liveness))
         .route("/readyz", get(readiness))
-        .with_state(http_state)
-        .layer(
-            ServiceBuilder::new().layer(cors).layer(socket_layer).layer(TraceLayer::new_for_http()),
-        );
+        .with_state(http_state);
+
+    // Actor state export and import for debugging (only with a secret)
+    if let Some(internal_routes) = internal::router(state.clone()) {
+        tracing::info!("Internal debugging endpoints enabled");
+        app = app.merge(internal_routes);
+    }
+
+    let app = app.layer(
+        ServiceBuilder::new().layer(cors).layer(socket_layer).layer(TraceLayer::new_for_http()),
+    );
 
     // Start server with graceful shutdown
     let addr = SocketAddr::from(([0, 0, 0, 0], config.port));
@@ -210,7 +245,9 @@ async fn shutdown_signal() {
 #[derive(Clone)]
 struct HttpState {
     db: sqlx::PgPool,
-    redis: redis::Client,
+    redis: RedisConnection,
+    pubsub: Arc<SubscriptionHealth>,
+    locations: Arc<LocationHealth>,
     started_at: Instant,
     is_production: bool,
 }
@@ -226,6 +263,16 @@ struct HealthResponse {
 struct HealthChecks {
     database: CheckResult,
     redis: CheckResult,
+    /// Socket.IO adapter subscription, needed for cross-instance broadcasts
+    pubsub: pubsub::SubscriptionStatus,
+    /// Location provider self-test
+    locations: CheckResult,
+    /// R2 pack storage reachability (if packs are used)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pack_storage: Option<CheckResult>,
+    /// Street View quota shared by the API instances; only degrades the
+    /// service
+    street_view: CheckResult,
 }
 
 #[derive(Serialize)]
@@ -245,6 +292,19 @@ impl CheckResult {
     fn unhealthy(error: String) -> Self {
         Self { status: "unhealthy".to_string(), latency_ms: None, error: Some(error) }
     }
+
+    fn is_healthy(&self) -> bool {
+        self.status == "healthy"
+    }
+}
+
+impl From<CheckOutcome> for CheckResult {
+    fn from(outcome: CheckOutcome) -> Self {
+        match outcome {
+            Ok(latency_ms) => Self::healthy(latency_ms),
+            Err(error) => Self::unhealthy(error),
+        }
+    }
 }
 
 async fn service_info(State(state): State<HttpState>) -> Json<ServiceInfo> {
@@ -259,19 +319,40 @@ async fn service_info(State(state): State<HttpState>) -> Json<ServiceInfo> {
         rust_version: env!("RUST_VERSION"),
         build_timestamp: env!("BUILD_TIMESTAMP"),
         uptime_seconds: state.started_at.elapsed().as_secs(),
+        protocol: ProtocolInfo::current(),
     })
 }
 
 async fn health_check(State(state): State<HttpState>) -> (StatusCode, Json<HealthResponse>) {
-    let (db_check, redis_check) =
-        tokio::join!(check_database(&state.db), check_redis(&state.redis));
-
-    let overall_healthy = db_check.status == "healthy" && redis_check.status == "healthy";
+    let (db_check, redis_check, location_checks, street_view_check) = tokio::join!(
+        check_database(&state.db),
+        check_redis(&state),
+        state.locations.check(),
+        check_street_view(&state.redis)
+    );
+    let pubsub_check = state.pubsub.status();
+
+    let overall_healthy = db_check.is_healthy()
+        && redis_check.is_healthy()
+        && pubsub_check.is_healthy()
+        && location_checks.is_ready();
+    let status = match (overall_healthy, street_view_check.is_healthy()) {
+        (false, _) => "unhealthy",
+        (true, false) => "degraded",
+        (true, true) => "healthy",
+    };
 
     let response = HealthResponse {
-        status: if overall_healthy { "healthy".to_string() } else { "unhealthy".to_string() },
+        status: status.to_string(),
         version: env!("CARGO_PKG_VERSION"),
-        checks: HealthChecks { database: db_check, redis: redis_check },
+        checks: HealthChecks {
+            database: db_check,
+            redis: redis_check,
+            pubsub: pubsub_check,
+            locations: location_checks.provider.into(),
+            pack_storage: location_checks.storage.map(CheckResult::from),
+            street_view: street_view_check,
+        },
     };
 
     let status_code =
@@ -285,10 +366,14 @@ async fn liveness() -> StatusCode {
 }
 
 async fn readiness(State(state): State<HttpState>) -> StatusCode {
-    let (db_check, redis_check) =
-        tokio::join!(check_database(&state.db), check_redis(&state.redis));
-
-    if db_check.status == "healthy" && redis_check.status == "healthy" {
+    let (db_check, redis_check, location_checks) =
+        tokio::join!(check_database(&state.db), check_redis(&state), state.locations.check());
+
+    if db_check.is_healthy()
+        && redis_check.is_healthy()
+        && state.pubsub.status().is_healthy()
+        && location_checks.is_ready()
+    {
         StatusCode::OK
     } else {
         StatusCode::SERVICE_UNAVAILABLE
@@ -307,34 +392,42 @@ async fn check_database(pool: &sqlx::PgPool) -> CheckResult {
     }
 }
 
-async fn check_redis(client: &redis::Client) -> CheckResult {
+/// Ping Redis; with Sentinel, also check the node is still the master, which
+/// moves the connection to the new master after a failover.
+async fn check_redis(state: &HttpState) -> CheckResult {
     let start = std::time::Instant::now();
+    let mut conn = state.redis.clone();
 
-    match client.get_multiplexed_async_connection().await {
-        Ok(mut conn) => match redis::cmd("PING").query_async::<String>(&mut conn).await {
-            Ok(_) => CheckResult::healthy(start.elapsed().as_millis() as u64),
-            Err(e) => {
-                tracing::error!(error = %e, "Redis PING failed");
-                CheckResult::unhealthy(e.to_string())
-            }
-        },
-        Err(e) => {
-            tracing::error!(error = %e, "Redis connection failed");
-            CheckResult::unhealthy(e.to_string())
-        }
+    if let Err(e) = redis::cmd("PING").query_async::<String>(&mut conn).await {
+        tracing::error!(error = %e, "Redis PING failed");
+        return CheckResult::unhealthy(e.to_string());
+    }
+    if let Err(e) = conn.check_master().await {
+        tracing::error!(error = %e, "Redis master check failed");
+        return CheckResult::unhealthy(e.to_string());
     }
+    CheckResult::healthy(start.elapsed().as_millis() as u64)
 }
 
-/// Ensure Redis URL has RESP3 protocol parameter (required for socketioxide-redis adapter)
-fn ensure_resp3_protocol(url: &str) -> String {
-    if url.contains("protocol=") {
-        // Already has protocol parameter
-        url.to_string()
-    } else if url.contains('?') {
-        // Has other query params, append
-        format!("{}&protocol=resp3", url)
-    } else {
-        // No query params
-        format!("{}?protocol=resp3", url)
+/// Check the Street View quota breaker the API instances share through Redis
+async fn check_street_view(conn: &RedisConnection) -> CheckResult {
+    let quota: Result<Option<String>, redis::RedisError> =
+        redis::cmd("GET").arg(QUOTA_REDIS_KEY).query_async(&mut conn.clone()).await;
+
+    let breaker = match quota {
+        Ok(json) => json
+            .and_then(|json| serde_json::from_str::<QuotaBreaker>(&json).ok())
+            .unwrap_or_default(),
+        Err(e) => return CheckResult::unhealthy(e.to_string()),
+    };
+    match breaker.state(chrono::Utc::now()) {
+        QuotaState::Open { until } => CheckResult {
+            status: "degraded".to_string(),
+            latency_ms: None,
+            error: Some(format!("Quota exhausted, lookups paused until {until}")),
+        },
+        QuotaState::Closed | QuotaState::HalfOpen => {
+            CheckResult { status: "healthy".to_string(), latency_ms: None, error: None }
+        }
     }
 }
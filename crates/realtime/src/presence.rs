@@ -0,0 +1,272 @@
+//! Online presence for friends
+//!
+//! Every authenticated socket is recorded in its user's presence set (see
+//! [`dguesser_protocol::socket::presence`]). Game actors record what their
+//! players are doing through a [`PresenceTracker`]. When a user comes online,
+//! goes offline, or joins or leaves a lobby or game, their friends are told
+//! through the friends' personal rooms, unless the user hides their presence.
+
+use std::time::Duration;
+
+use chrono::Utc;
+use dguesser_db::DbPool;
+use dguesser_protocol::socket::events;
+use dguesser_protocol::socket::payloads::FriendPresencePayload;
+use dguesser_protocol::socket::presence::{
+    PRESENCE_REFRESH_SECS, PRESENCE_TTL_SECS, PresenceActivity, PresenceInfo, PresenceVisibility,
+    activity_key, presence_key,
+};
+use dguesser_redis::RedisConnection;
+
+use crate::emitter::BroadcastEmitter;
+use crate::state::AppState;
+
+/// Record an authenticated socket and tell friends if the user came online.
+pub async fn socket_connected(state: &AppState, user_id: &str, socket_id: &str) {
+    match add_socket(state.redis(), user_id, socket_id).await {
+        Ok(true) => {
+            let tracker = state.presence();
+            let activity = tracker.get_activity(user_id).await.ok().flatten();
+            tracker.notify_friends(user_id, PresenceInfo::online(activity)).await;
+        }
+        Ok(false) => {}
+        Err(e) => tracing::warn!(error = %e, user_id = %user_id, "Failed to record presence"),
+    }
+}
+
+/// Remove a socket and tell friends if it was the user's last one.
+pub async fn socket_disconnected(state: &AppState, user_id: &str, socket_id: &str) {
+    match remove_socket(state.redis(), user_id, socket_id).await {
+        Ok(true) => state.presence().notify_friends(user_id, PresenceInfo::default()).await,
+        Ok(false) => {}
+        Err(e) => tracing::warn!(error = %e, user_id = %user_id, "Failed to clear presence"),
+    }
+}
+
+/// Records what users are doing and tells their friends
+#[derive(Clone)]
+pub struct PresenceTracker {
+    redis: RedisConnection,
+    db: DbPool,
+    emitter: BroadcastEmitter,
+}
+
+impl PresenceTracker {
+    pub fn new(redis: RedisConnection, db: DbPool, emitter: BroadcastEmitter) -> Self {
+        Self { redis, db, emitter }
+    }
+
+    /// Record a user's lobby or game activity and tell friends if it changed.
+    pub async fn set_activity(&self, user_id: &str, activity: &PresenceActivity) {
+        let json = match serde_json::to_string(activity) {
+            Ok(json) => json,
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to serialize presence activity");
+                return;
+            }
+        };
+
+        let previous: Result<Option<String>, redis::RedisError> = redis::cmd("SET")
+            .arg(activity_key(user_id))
+            .arg(&json)
+            .arg("EX")
+            .arg(PRESENCE_TTL_SECS)
+            .arg("GET")
+            .query_async(&mut self.redis.clone())
+            .await;
+
+        match previous {
+            Ok(previous) if previous.as_deref() == Some(json.as_str()) => {}
+            Ok(_) => {
+                self.notify_friends(user_id, PresenceInfo::online(Some(activity.clone()))).await;
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, user_id = %user_id, "Failed to record presence activity");
+            }
+        }
+    }
+
+    /// Clear a user's activity if it still belongs to `game_id`, and tell
+    /// friends the user is back to plain online (if still connected).
+    pub async fn clear_activity(&self, user_id: &str, game_id: &str) {
+        let result: Result<bool, redis::RedisError> = async {
+            match self.get_activity(user_id).await? {
+                Some(activity) if activity.game_id == game_id => {
+                    let mut conn = self.redis.clone();
+                    let cutoff = Utc::now().timestamp() - PRESENCE_TTL_SECS;
+                    let (online,): (i64,) = redis::pipe()
+                        .cmd("DEL")
+                        .arg(activity_key(user_id))
+                        .ignore()
+                        .cmd("ZCOUNT")
+                        .arg(presence_key(user_id))
+                        .arg(cutoff)
+                        .arg("+inf")
+                        .query_async(&mut conn)
+                        .await?;
+                    Ok(online > 0)
+                }
+                _ => Ok(false),
+            }
+        }
+        .await;
+
+        match result {
+            Ok(true) => self.notify_friends(user_id, PresenceInfo::online(None)).await,
+            Ok(false) => {}
+            Err(e) => {
+                tracing::warn!(error = %e, user_id = %user_id, "Failed to clear presence activity");
+            }
+        }
+    }
+
+    /// Read a user's current activity.
+    async fn get_activity(
+        &self,
+        user_id: &str,
+    ) -> Result<Option<PresenceActivity>, redis::RedisError> {
+        let mut conn = self.redis.clone();
+        let json: Option<String> =
+            redis::cmd("GET").arg(activity_key(user_id)).query_async(&mut conn).await?;
+        Ok(json.and_then(|json| serde_json::from_str(&json).ok()))
+    }
+
+    /// Tell a user's friends about their presence, unless they hide it.
+    async fn notify_friends(&self, user_id: &str, presence: PresenceInfo) {
+        let visibility = match dguesser_db::profiles::get_presence_visibilities(
+            &self.db,
+            &[user_id.to_string()],
+        )
+        .await
+        {
+            Ok(rows) => rows
+                .first()
+                .map(|(_, visibility)| PresenceVisibility::parse(visibility))
+                .unwrap_or_default(),
+            Err(e) => {
+                tracing::error!(error = %e, user_id = %user_id, "Failed to load presence visibility");
+                return;
+            }
+        };
+        if !visibility.allows(false, true) {
+            return;
+        }
+
+        let friend_ids = match dguesser_db::friends::list_friend_ids(&self.db, user_id).await {
+            Ok(ids) => ids,
+            Err(e) => {
+                tracing::error!(error = %e, user_id = %user_id, "Failed to load friends for presence");
+                return;
+            }
+        };
+
+        let payload = FriendPresencePayload {
+            user_id: user_id.to_string(),
+            online: presence.is_online(),
+            presence,
+        };
+        for friend_id in friend_ids {
+            let _ = self.emitter.emit_to_room(&friend_id, events::friend::PRESENCE, &payload).await;
+        }
+    }
+}
+
+/// Spawn the task that keeps this instance's sockets marked online.
+pub fn spawn_presence_refresh_task(state: AppState) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(PRESENCE_REFRESH_SECS));
+        loop {
+            interval.tick().await;
+            let sockets = state.authenticated_sockets().await;
+            if sockets.is_empty() {
+                continue;
+            }
+            if let Err(e) = refresh_sockets(state.redis(), &sockets).await {
+                tracing::warn!(error = %e, sockets = sockets.len(), "Failed to refresh presence");
+            }
+        }
+    });
+}
+
+/// Add a socket to the user's presence set.
+/// Returns true if the user had no live sockets before.
+async fn add_socket(
+    redis: &RedisConnection,
+    user_id: &str,
+    socket_id: &str,
+) -> Result<bool, redis::RedisError> {
+    let mut conn = redis.clone();
+    let key = presence_key(user_id);
+    let now = Utc::now().timestamp();
+
+    let (live_before,): (i64,) = redis::pipe()
+        .atomic()
+        .cmd("ZREMRANGEBYSCORE")
+        .arg(&key)
+        .arg("-inf")
+        .arg(now - PRESENCE_TTL_SECS)
+        .ignore()
+        .cmd("ZCARD")
+        .arg(&key)
+        .cmd("ZADD")
+        .arg(&key)
+        .arg(now)
+        .arg(socket_id)
+        .ignore()
+        .cmd("EXPIRE")
+        .arg(&key)
+        .arg(PRESENCE_TTL_SECS)
+        .ignore()
+        .query_async(&mut conn)
+        .await?;
+
+    Ok(live_before == 0)
+}
+
+/// Remove a socket from the user's presence set.
+/// Returns true if the user has no live sockets left.
+async fn remove_socket(
+    redis: &RedisConnection,
+    user_id: &str,
+    socket_id: &str,
+) -> Result<bool, redis::RedisError> {
+    let mut conn = redis.clone();
+    let key = presence_key(user_id);
+    let now = Utc::now().timestamp();
+
+    let (live_after,): (i64,) = redis::pipe()
+        .atomic()
+        .cmd("ZREM")
+        .arg(&key)
+        .arg(socket_id)
+        .ignore()
+        .cmd("ZREMRANGEBYSCORE")
+        .arg(&key)
+        .arg("-inf")
+        .arg(now - PRESENCE_TTL_SECS)
+        .ignore()
+        .cmd("ZCARD")
+        .arg(&key)
+        .query_async(&mut conn)
+        .await?;
+
+    Ok(live_after == 0)
+}
+
+/// Bump the last-seen time of connected sockets.
+async fn refresh_sockets(
+    redis: &RedisConnection,
+    sockets: &[(String, String)],
+) -> Result<(), redis::RedisError> {
+    let mut conn = redis.clone();
+    let now = Utc::now().timestamp();
+
+    let mut pipe = redis::pipe();
+    for (socket_id, user_id) in sockets {
+        let key = presence_key(user_id);
+        pipe.cmd("ZADD").arg(&key).arg(now).arg(socket_id).ignore();
+        pipe.cmd("EXPIRE").arg(&key).arg(PRESENCE_TTL_SECS).ignore();
+        pipe.cmd("EXPIRE").arg(activity_key(user_id)).arg(PRESENCE_TTL_SECS).ignore();
+    }
+    pipe.query_async::<()>(&mut conn).await
+}
@@ -0,0 +1,251 @@
+//! Redis pub/sub driver for the Socket.IO adapter
+//!
+//! Speaks the same protocol as socketioxide-redis' own driver, but over a
+//! subscriber [`RedisConnection`]: when Redis goes away or Sentinel fails
+//! over, the connection is re-established and every channel is subscribed
+//! again, so broadcasts from other instances resume without a restart.
+//! [`SubscriptionHealth`] tracks the subscription for health checks.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use dguesser_redis::{RedisConfig, RedisConnection};
+use redis::{AsyncCommands, FromRedisValue, PushInfo, PushKind};
+use serde::Serialize;
+use socketioxide_redis::drivers::{ChanItem, Driver, MessageStream};
+use tokio::sync::mpsc;
+
+/// How often the subscriber connection is pinged, which also triggers a
+/// reconnect when the backoff gave up
+const WATCHDOG_INTERVAL_SECS: u64 = 5;
+
+type HandlerMap = HashMap<String, mpsc::Sender<ChanItem>>;
+
+/// State of the pub/sub subscription
+#[derive(Debug)]
+pub struct SubscriptionHealth {
+    connected: AtomicBool,
+    reconnects: AtomicU64,
+    disconnected_at: Mutex<Option<Instant>>,
+    channels: Arc<RwLock<HandlerMap>>,
+}
+
+/// Snapshot of [`SubscriptionHealth`] for health checks
+#[derive(Debug, Serialize)]
+pub struct SubscriptionStatus {
+    pub status: &'static str,
+    /// Channels the adapter listens on
+    pub channels: usize,
+    /// Times the subscription was restored after a disconnect
+    pub reconnects: u64,
+    /// How long the subscription has been down
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disconnected_secs: Option<u64>,
+}
+
+impl SubscriptionStatus {
+    pub fn is_healthy(&self) -> bool {
+        self.status == "healthy"
+    }
+}
+
+impl SubscriptionHealth {
+    fn new(channels: Arc<RwLock<HandlerMap>>) -> Self {
+        Self {
+            connected: AtomicBool::new(false),
+            reconnects: AtomicU64::new(0),
+            disconnected_at: Mutex::new(None),
+            channels,
+        }
+    }
+
+    /// Current state of the subscription.
+    pub fn status(&self) -> SubscriptionStatus {
+        let connected = self.connected.load(Ordering::Relaxed);
+        SubscriptionStatus {
+            status: if connected { "healthy" } else { "unhealthy" },
+            channels: self.channels.read().unwrap().len(),
+            reconnects: self.reconnects.load(Ordering::Relaxed),
+            disconnected_secs: self
+                .disconnected_at
+                .lock()
+                .unwrap()
+                .map(|at| at.elapsed().as_secs()),
+        }
+    }
+
+    fn on_subscribed(&self) {
+        self.connected.store(true, Ordering::Relaxed);
+        if let Some(at) = self.disconnected_at.lock().unwrap().take() {
+            let reconnects = self.reconnects.fetch_add(1, Ordering::Relaxed) + 1;
+            tracing::info!(
+                down_ms = at.elapsed().as_millis() as u64,
+                reconnects,
+                "Redis pub/sub subscription restored"
+            );
+        }
+    }
+
+    fn on_disconnected(&self) {
+        if self.connected.swap(false, Ordering::Relaxed) {
+            *self.disconnected_at.lock().unwrap() = Some(Instant::now());
+            tracing::warn!("Redis pub/sub connection lost, reconnecting");
+        }
+    }
+}
+
+/// Socket.IO adapter driver that survives Redis restarts
+#[derive(Clone)]
+pub struct ResilientRedisDriver {
+    handlers: Arc<RwLock<HandlerMap>>,
+    conn: RedisConnection,
+    health: Arc<SubscriptionHealth>,
+}
+
+impl ResilientRedisDriver {
+    /// Connect to Redis and start the watchdog that keeps the subscription
+    /// alive.
+    pub async fn new(config: &RedisConfig) -> Result<Self, redis::RedisError> {
+        let handlers = Arc::new(RwLock::new(HashMap::new()));
+        let health = Arc::new(SubscriptionHealth::new(handlers.clone()));
+
+        let (push_handlers, push_health) = (handlers.clone(), health.clone());
+        let conn = RedisConnection::subscriber(config, move |msg| {
+            handle_push(msg, &push_handlers, &push_health)
+        })
+        .await?;
+
+        let driver = Self { handlers, conn, health };
+        driver.spawn_watchdog();
+        Ok(driver)
+    }
+
+    /// Health of the subscription
+    pub fn health(&self) -> Arc<SubscriptionHealth> {
+        self.health.clone()
+    }
+
+    fn spawn_watchdog(&self) {
+        let mut conn = self.conn.clone();
+        let health = self.health.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(WATCHDOG_INTERVAL_SECS));
+            loop {
+                interval.tick().await;
+                if let Err(e) = redis::cmd("PING").query_async::<String>(&mut conn).await {
+                    tracing::debug!(error = %e, "Redis pub/sub ping failed");
+                    health.on_disconnected();
+                }
+            }
+        });
+    }
+}
+
+fn handle_push(msg: PushInfo, handlers: &RwLock<HandlerMap>, health: &SubscriptionHealth) {
+    match msg.kind {
+        PushKind::Message => {
+            let mut data = msg.data.into_iter();
+            let (Some(channel), Some(message)) = (data.next(), data.next()) else {
+                return;
+            };
+            let parsed = String::from_redis_value(channel)
+                .and_then(|channel| Ok((channel, Vec::<u8>::from_redis_value(message)?)));
+            match parsed {
+                Ok((channel, message)) => match handlers.read().unwrap().get(&channel) {
+                    Some(tx) => {
+                        if let Err(e) = tx.try_send((channel, message)) {
+                            tracing::warn!(error = %e, "Redis pub/sub channel full");
+                        }
+                    }
+                    None => tracing::warn!(channel, "No handler for Redis pub/sub channel"),
+                },
+                Err(e) => tracing::error!(error = %e, "Invalid Redis pub/sub message"),
+            }
+        }
+        PushKind::Subscribe => health.on_subscribed(),
+        PushKind::Disconnection => health.on_disconnected(),
+        _ => {}
+    }
+}
+
+impl Driver for ResilientRedisDriver {
+    type Error = redis::RedisError;
+
+    async fn publish(&self, chan: String, val: Vec<u8>) -> Result<(), Self::Error> {
+        self.conn.clone().publish::<_, _, redis::Value>(chan, val).await?;
+        Ok(())
+    }
+
+    async fn subscribe(
+        &self,
+        chan: String,
+        size: usize,
+    ) -> Result<MessageStream<ChanItem>, Self::Error> {
+        self.conn.subscribe(&chan).await?;
+        let (tx, rx) = mpsc::channel(size);
+        self.handlers.write().unwrap().insert(chan, tx);
+        Ok(MessageStream::new(rx))
+    }
+
+    async fn unsubscribe(&self, chan: String) -> Result<(), Self::Error> {
+        self.handlers.write().unwrap().remove(&chan);
+        self.conn.unsubscribe(&chan).await?;
+        Ok(())
+    }
+
+    async fn num_serv(&self, chan: &str) -> Result<u16, Self::Error> {
+        let mut conn = self.conn.clone();
+        let (_, count): (String, u16) =
+            redis::cmd("PUBSUB").arg("NUMSUB").arg(chan).query_async(&mut conn).await?;
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push(kind: PushKind, data: Vec<redis::Value>) -> PushInfo {
+        PushInfo { kind, data }
+    }
+
+    #[test]
+    fn test_reconnect_is_tracked() {
+        let handlers = Arc::new(RwLock::new(HashMap::new()));
+        let health = SubscriptionHealth::new(handlers.clone());
+        assert!(!health.status().is_healthy());
+
+        handle_push(push(PushKind::Subscribe, vec![]), &handlers, &health);
+        assert!(health.status().is_healthy());
+        assert_eq!(health.status().reconnects, 0);
+
+        handle_push(push(PushKind::Disconnection, vec![]), &handlers, &health);
+        let status = health.status();
+        assert!(!status.is_healthy());
+        assert_eq!(status.disconnected_secs, Some(0));
+
+        handle_push(push(PushKind::Subscribe, vec![]), &handlers, &health);
+        let status = health.status();
+        assert!(status.is_healthy());
+        assert_eq!((status.reconnects, status.disconnected_secs), (1, None));
+    }
+
+    #[test]
+    fn test_messages_reach_their_channel() {
+        let handlers = Arc::new(RwLock::new(HashMap::new()));
+        let health = SubscriptionHealth::new(handlers.clone());
+        let (tx, mut rx) = mpsc::channel(1);
+        handlers.write().unwrap().insert("socket.io#/#".to_string(), tx);
+
+        let data = vec![
+            redis::Value::BulkString(b"socket.io#/#".to_vec()),
+            redis::Value::BulkString(vec![1, 2, 3]),
+        ];
+        handle_push(push(PushKind::Message, data), &handlers, &health);
+
+        assert_eq!(rx.try_recv().unwrap(), ("socket.io#/#".to_string(), vec![1, 2, 3]));
+        assert_eq!(health.status().channels, 1);
+    }
+}
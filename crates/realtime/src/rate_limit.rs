@@ -6,6 +6,7 @@
 use std::net::IpAddr;
 
 use axum::http::HeaderMap;
+use dguesser_redis::RedisConnection;
 use redis::AsyncCommands;
 use socketioxide::adapter::Adapter;
 use socketioxide::extract::SocketRef;
@@ -58,6 +59,29 @@ impl SocketRateLimitConfig {
     /// Vote to skip: 10 requests per minute per user
     pub const VOTE_SKIP: Self =
         Self { event: "round:vote_skip", max_requests: 10, window_secs: 60 };
+
+    /// Host reroll: 5 requests per minute per user (the game allows few
+    /// rerolls anyway)
+    pub const REROLL: Self = Self { event: "game:reroll", max_requests: 5, window_secs: 60 };
+
+    /// Classroom pause and resume: 20 requests per minute per user
+    pub const CLASSROOM_PAUSE: Self =
+        Self { event: "classroom:pause", max_requests: 20, window_secs: 60 };
+
+    /// Hint requests: 20 requests per minute per user
+    pub const HINT: Self = Self { event: "hint:request", max_requests: 20, window_secs: 60 };
+
+    /// Reactions: 30 requests per minute per user
+    pub const REACT: Self = Self { event: "game:react", max_requests: 30, window_secs: 60 };
+
+    /// Reaction burst: 3 requests per second per user
+    /// Stops a held-down button from flooding the room
+    pub const REACT_BURST: Self =
+        Self { event: "game:react:burst", max_requests: 3, window_secs: 1 };
+
+    /// Clock sync: 30 requests per minute per socket
+    /// Clients sync a few times on connect, then every half minute
+    pub const TIME_SYNC: Self = Self { event: "time:sync", max_requests: 30, window_secs: 60 };
 }
 
 /// Result of a rate limit check
@@ -76,14 +100,19 @@ pub struct RateLimitResult {
 /// Check rate limit for a socket event
 ///
 /// Returns whether the request is allowed and remaining quota.
-/// Uses Redis INCR with EXPIRE for sliding window counting.
+/// Uses Redis INCR with EXPIRE for sliding window counting. The event's limit
+/// is scaled by `multiplier` (see [`AppState::socket_rate_limit_multiplier`]).
+///
+/// [`AppState::socket_rate_limit_multiplier`]: crate::state::AppState::socket_rate_limit_multiplier
 pub async fn check_rate_limit(
-    redis: &redis::Client,
+    redis: &RedisConnection,
     config: &SocketRateLimitConfig,
     identifier: &str,
+    multiplier: u32,
 ) -> Result<RateLimitResult, redis::RedisError> {
+    let max_requests = config.max_requests.saturating_mul(multiplier.max(1));
     let key = format!("ratelimit:socket:{}:{}", config.event, identifier);
-    let mut conn = redis.get_multiplexed_async_connection().await?;
+    let mut conn = redis.clone();
 
     // Increment counter
     let count: u32 = conn.incr(&key, 1).await?;
@@ -93,15 +122,15 @@ pub async fn check_rate_limit(
         conn.expire::<_, ()>(&key, config.window_secs as i64).await?;
     }
 
-    let allowed = count <= config.max_requests;
-    let remaining = config.max_requests.saturating_sub(count);
+    let allowed = count <= max_requests;
+    let remaining = max_requests.saturating_sub(count);
 
     if !allowed {
         tracing::warn!(
             event = config.event,
             identifier = %identifier,
             count = count,
-            limit = config.max_requests,
+            limit = max_requests,
             window_secs = config.window_secs,
             "Socket.IO rate limit exceeded"
         );
@@ -3,19 +3,22 @@
 //! Provides Redis-based caching for active game state to support:
 //! - Server restart recovery
 //! - State persistence during reconnection grace period
+//! - Live game inspection by admins through the API
 
 use std::collections::HashMap;
 
+use dguesser_core::game::Hint;
+use dguesser_core::streetview::ImageryProvider;
+use dguesser_protocol::api::admin::GAME_STATE_KEY_PREFIX as GAME_STATE_PREFIX;
+use dguesser_redis::RedisConnection;
 use redis::AsyncCommands;
 use serde::{Deserialize, Serialize};
 
-/// Redis key prefix for game state
-const GAME_STATE_PREFIX: &str = "dguesser:game:";
-
 /// TTL for cached game state (2 hours)
 const GAME_STATE_TTL_SECS: u64 = 7200;
 
-/// Serializable game state for Redis persistence
+/// Serializable game state for Redis persistence. The API reads this for
+/// admin inspection, so renamed fields need a serde alias.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CachedGameState {
     /// Game ID (gam_xxxxxxxxxxxx)
@@ -38,6 +41,18 @@ pub struct CachedGameState {
     /// User IDs who have voted to skip the between-rounds wait
     #[serde(default)]
     pub skip_votes: Vec<String>,
+    /// Unix timestamp (ms) when a time-attack game's budget runs out
+    #[serde(default)]
+    pub time_budget_ends_at: Option<i64>,
+    /// Unix timestamp (ms) when the game was paused for a disconnected host
+    #[serde(default)]
+    pub paused_at_ms: Option<i64>,
+    /// Number of rounds the host has rerolled
+    #[serde(default)]
+    pub rerolls_used: u8,
+    /// Whether the teacher of a classroom game paused it
+    #[serde(default)]
+    pub teacher_paused: bool,
 }
 
 /// Serializable player state
@@ -57,6 +72,22 @@ pub struct CachedPlayerState {
     pub connected: bool,
     /// Disconnect timestamp (unix ms)
     pub disconnect_time_ms: Option<i64>,
+    /// Score multiplier
+    #[serde(default = "default_handicap")]
+    pub handicap: f64,
+    /// First round a late joiner could play
+    #[serde(default)]
+    pub joined_round: Option<u8>,
+    /// Median of the recent round-trip times (ms)
+    #[serde(default)]
+    pub rtt_ms: Option<u32>,
+    /// Recent round-trip times (ms), oldest first
+    #[serde(default)]
+    pub rtt_samples: Vec<u32>,
+}
+
+fn default_handicap() -> f64 {
+    1.0
 }
 
 /// Serializable round state
@@ -77,12 +108,18 @@ pub struct CachedRoundState {
     /// Default heading/direction for panorama
     #[serde(default)]
     pub heading: Option<f64>,
+    /// Imagery provider of the panorama
+    #[serde(default)]
+    pub provider: ImageryProvider,
     /// Start timestamp (unix ms)
     pub started_at_ms: i64,
     /// Time limit in ms
     pub time_limit_ms: Option<u32>,
     /// Guesses submitted (user_id -> guess)
     pub guesses: HashMap<String, CachedGuess>,
+    /// Hints taken (user_id -> hints)
+    #[serde(default)]
+    pub hints: HashMap<String, Vec<Hint>>,
 }
 
 /// Serializable guess
@@ -96,13 +133,13 @@ pub struct CachedGuess {
 
 /// Redis state manager
 pub struct RedisStateManager {
-    client: redis::Client,
+    conn: RedisConnection,
 }
 
 impl RedisStateManager {
     /// Create a new Redis state manager
-    pub fn new(client: redis::Client) -> Self {
-        Self { client }
+    pub fn new(conn: RedisConnection) -> Self {
+        Self { conn }
     }
 
     /// Get the Redis key for a game's state
@@ -112,7 +149,7 @@ impl RedisStateManager {
 
     /// Save game state to Redis
     pub async fn save_game_state(&self, state: &CachedGameState) -> Result<(), redis::RedisError> {
-        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let mut conn = self.conn.clone();
         let key = Self::game_key(&state.game_id);
         let json = serde_json::to_string(state).map_err(|e| {
             redis::RedisError::from((
@@ -132,7 +169,7 @@ impl RedisStateManager {
         &self,
         game_id: &str,
     ) -> Result<Option<CachedGameState>, redis::RedisError> {
-        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let mut conn = self.conn.clone();
         let key = Self::game_key(game_id);
 
         let json: Option<String> = conn.get(&key).await?;
@@ -155,7 +192,7 @@ impl RedisStateManager {
 
     /// Delete game state from Redis
     pub async fn delete_game_state(&self, game_id: &str) -> Result<(), redis::RedisError> {
-        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let mut conn = self.conn.clone();
         let key = Self::game_key(game_id);
         let _: () = conn.del(&key).await?;
         tracing::debug!("Deleted game state from Redis: {}", game_id);
@@ -165,7 +202,7 @@ impl RedisStateManager {
     /// Refresh the TTL for a game's state
     #[allow(dead_code)]
     pub async fn refresh_ttl(&self, game_id: &str) -> Result<(), redis::RedisError> {
-        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let mut conn = self.conn.clone();
         let key = Self::game_key(game_id);
         let _: bool = conn.expire(&key, GAME_STATE_TTL_SECS as i64).await?;
         Ok(())
@@ -173,38 +210,18 @@ impl RedisStateManager {
 
     /// Get all active game IDs from Redis using SCAN (safe for production).
     pub async fn get_active_game_ids(&self) -> Result<Vec<String>, redis::RedisError> {
-        let mut conn = self.client.get_multiplexed_async_connection().await?;
-        let pattern = format!("{}*", GAME_STATE_PREFIX);
-
-        let mut game_ids = Vec::new();
-        let mut cursor: u64 = 0;
-        loop {
-            let (next_cursor, keys): (u64, Vec<String>) = redis::cmd("SCAN")
-                .arg(cursor)
-                .arg("MATCH")
-                .arg(&pattern)
-                .arg("COUNT")
-                .arg(100)
-                .query_async(&mut conn)
-                .await?;
-            for key in keys {
-                if let Some(id) = key.strip_prefix(GAME_STATE_PREFIX) {
-                    game_ids.push(id.to_string());
-                }
-            }
-            cursor = next_cursor;
-            if cursor == 0 {
-                break;
-            }
-        }
-
-        Ok(game_ids)
+        let keys = self.conn.scan_match(&format!("{}*", GAME_STATE_PREFIX)).await?;
+        Ok(keys
+            .iter()
+            .filter_map(|key| key.strip_prefix(GAME_STATE_PREFIX))
+            .map(String::from)
+            .collect())
     }
 
     /// Check if Redis is available
     #[allow(dead_code)]
     pub async fn health_check(&self) -> Result<bool, redis::RedisError> {
-        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let mut conn = self.conn.clone();
         let pong: String = redis::cmd("PING").query_async(&mut conn).await?;
         Ok(pong == "PONG")
     }
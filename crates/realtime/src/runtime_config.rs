@@ -0,0 +1,57 @@
+//! Runtime config reloading
+//!
+//! Admins change operational settings through the API's `/admin/reload`,
+//! which stores them in Redis under an increasing version (see
+//! [`dguesser_protocol::api::admin::StoredRuntimeSettings`]). This server
+//! polls the version and applies the settings it uses: the party host grace
+//! period and the socket event rate limit multiplier.
+
+use std::time::Duration;
+
+use dguesser_protocol::api::admin::{RUNTIME_SETTINGS_KEY, StoredRuntimeSettings};
+use dguesser_redis::RedisConnection;
+use redis::AsyncCommands;
+
+use crate::state::AppState;
+
+/// How often the stored settings are checked
+const POLL_INTERVAL_SECS: u64 = 10;
+
+/// Spawn the background task that applies newly stored settings.
+pub fn spawn_config_watcher(state: AppState) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(POLL_INTERVAL_SECS));
+
+        loop {
+            interval.tick().await;
+
+            match load(state.redis()).await {
+                Ok(Some(stored)) if stored.version > state.runtime_config_version() => {
+                    tracing::info!(
+                        version = stored.version,
+                        updated_by = stored.updated_by.as_deref().unwrap_or("-"),
+                        "Applied runtime config"
+                    );
+                    state.set_runtime_config(stored);
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!(error = %e, "Failed to load runtime config"),
+            }
+        }
+    });
+
+    tracing::info!(interval_secs = POLL_INTERVAL_SECS, "Runtime config watcher started");
+}
+
+/// Read the stored settings, if any were ever stored.
+async fn load(redis: &RedisConnection) -> Result<Option<StoredRuntimeSettings>, redis::RedisError> {
+    let mut conn = redis.clone();
+    let json: Option<String> = conn.get(RUNTIME_SETTINGS_KEY).await?;
+    Ok(json.and_then(|json| match serde_json::from_str(&json) {
+        Ok(stored) => Some(stored),
+        Err(e) => {
+            tracing::error!(error = %e, "Stored runtime config is invalid, ignoring it");
+            None
+        }
+    }))
+}
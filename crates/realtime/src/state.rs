@@ -7,15 +7,23 @@ use std::sync::Arc;
 
 use tokio::sync::{RwLock, mpsc, oneshot};
 
-use crate::actors::{GameActor, PartyActor};
-use crate::config::{Config, LocationProviderType};
+use crate::actors::{GameActor, GameSnapshot, PartyActor};
+use crate::config::{Config, LocationProviderType, R2LocationConfig};
 use crate::emitter::BroadcastEmitter;
+use crate::presence::PresenceTracker;
 use crate::redis_state::RedisStateManager;
+use dguesser_auth::SocketTokenSigner;
 use dguesser_core::game::GameSettings;
-use dguesser_core::location::LocationProvider;
+use dguesser_core::location::{LocationProvider, Map};
 use dguesser_db::{DbPool, LocationRepository};
-use dguesser_locations::reader::{FileReader, HttpReader};
-use dguesser_locations::{PackProvider, PackProviderConfig};
+use dguesser_locations::reader::{FileReader, HttpReader, RangeReader};
+use dguesser_locations::{
+    CachedReader, LocationHealth, PackProvider, PackProviderConfig, RangeCache, RangeCacheConfig,
+    RecentLocations,
+};
+use dguesser_protocol::api::admin::StoredRuntimeSettings;
+use dguesser_protocol::version::{DEFAULT_CLIENT_VERSION, capabilities_for};
+use dguesser_redis::RedisConnection;
 
 /// Application state shared across all socket connections
 #[derive(Clone)]
@@ -25,7 +33,8 @@ pub struct AppState {
 
 struct AppStateInner {
     pub db: DbPool,
-    pub redis: redis::Client,
+    /// Shared connection for every Redis command, following failovers
+    pub redis: RedisConnection,
     pub redis_state: RedisStateManager,
     #[allow(dead_code)]
     pub config: Config,
@@ -39,14 +48,28 @@ struct AppStateInner {
     pub socket_users: RwLock<HashMap<String, String>>,
     /// User ID to Socket ID mapping (for reconnects)
     pub user_sockets: RwLock<HashMap<String, String>>,
+    /// Socket ID to the game its handshake token was bound to
+    pub socket_games: RwLock<HashMap<String, String>>,
+    /// Socket ID to the server time of its unanswered `time:sync` reply
+    pub socket_pings: RwLock<HashMap<String, i64>>,
+    /// Socket ID to the protocol version negotiated when it authenticated
+    pub socket_protocols: RwLock<HashMap<String, u32>>,
+    /// Verifier for socket handshake tokens (if configured)
+    pub socket_token_signer: Option<SocketTokenSigner>,
     /// Location provider for game location selection
     pub location_provider: Arc<dyn LocationProvider>,
+    /// Readiness checks for the location provider and pack storage
+    pub location_health: Arc<LocationHealth>,
+    /// Recently served locations per player (unless disabled)
+    pub recent_locations: Option<RecentLocations>,
     /// Channel for game actors to request cleanup when they finish
     pub game_cleanup_tx: mpsc::Sender<String>,
     /// Channel for party actors to request cleanup when they disband
     pub party_cleanup_tx: mpsc::Sender<String>,
     /// Channel for game actors to notify parties when a game ends
     pub party_game_ended_tx: mpsc::Sender<(String, String)>,
+    /// Settings reloaded at runtime (see [`crate::runtime_config`])
+    pub runtime_config: std::sync::RwLock<StoredRuntimeSettings>,
 }
 
 /// Handle to communicate with a game actor
@@ -65,12 +88,6 @@ pub struct PartyHandle {
     pub tx: mpsc::Sender<PartyCommand>,
 }
 
-/// Grace period for reconnection in lobby (seconds).
-/// NOTE: During active games, players can reconnect for the entire game duration.
-/// See dguesser_core::game::reducer for timeout constants.
-#[allow(dead_code)]
-pub const RECONNECTION_GRACE_PERIOD_SECS: u64 = 30;
-
 /// Tick interval for game actors in seconds
 const TICK_INTERVAL_SECS: u64 = 1;
 
@@ -95,6 +112,7 @@ pub enum GameCommand {
         lat: f64,
         lng: f64,
         time_ms: Option<u32>,
+        panorama_id: Option<String>,
         respond: oneshot::Sender<Result<GuessResult, String>>,
     },
     Reconnect {
@@ -107,6 +125,13 @@ pub enum GameCommand {
         settings: dguesser_core::game::GameSettings,
         respond: oneshot::Sender<Result<(), String>>,
     },
+    /// Set a player's score multiplier (host only, lobby phase only)
+    SetHandicap {
+        user_id: String,
+        player_id: String,
+        handicap: f64,
+        respond: oneshot::Sender<Result<(), String>>,
+    },
     /// Host force-skips the between-rounds wait
     SkipWait {
         user_id: String,
@@ -117,6 +142,43 @@ pub enum GameCommand {
         user_id: String,
         respond: oneshot::Sender<Result<(), String>>,
     },
+    /// Host replaces the current round's location
+    Reroll {
+        user_id: String,
+        respond: oneshot::Sender<Result<(), String>>,
+    },
+    /// Teacher pauses a classroom game
+    Pause {
+        user_id: String,
+        respond: oneshot::Sender<Result<(), String>>,
+    },
+    /// Teacher resumes a classroom game
+    Resume {
+        user_id: String,
+        respond: oneshot::Sender<Result<(), String>>,
+    },
+    /// Player asks for a hint on the current round
+    RequestHint {
+        user_id: String,
+        kind: dguesser_core::game::HintKind,
+        from: Option<(f64, f64)>,
+        respond: oneshot::Sender<Result<(), String>>,
+    },
+    /// Player sends an emoji reaction to the room
+    React {
+        user_id: String,
+        reaction: String,
+        respond: oneshot::Sender<Result<(), String>>,
+    },
+    /// Round-trip time measured on a player's socket
+    RecordRtt {
+        user_id: String,
+        rtt_ms: u32,
+    },
+    /// Write out pending state and export the actor's full state
+    DumpState {
+        respond: oneshot::Sender<Result<GameSnapshot, String>>,
+    },
     Tick,
     Shutdown,
 }
@@ -163,6 +225,17 @@ pub enum PartyCommand {
         target_user_id: String,
         respond: oneshot::Sender<Result<(), String>>,
     },
+    Mute {
+        user_id: String,
+        target_user_id: String,
+        muted: bool,
+        respond: oneshot::Sender<Result<(), String>>,
+    },
+    Chat {
+        user_id: String,
+        content: String,
+        respond: oneshot::Sender<Result<(), String>>,
+    },
     Disband {
         user_id: String,
         respond: oneshot::Sender<Result<(), String>>,
@@ -177,7 +250,7 @@ pub enum PartyCommand {
 impl AppState {
     pub async fn new(
         db: DbPool,
-        redis: redis::Client,
+        redis: RedisConnection,
         redis_state: RedisStateManager,
         config: Config,
     ) -> Self {
@@ -196,47 +269,45 @@ impl AppState {
                 let pack_config = PackProviderConfig {
                     cache_indexes: true,
                     max_disabled_cache: r2_config.max_disabled_cache,
+                    ..PackProviderConfig::default()
                 };
 
                 // Load maps from database to register with PackProvider
                 let maps = dguesser_db::locations::list_maps(&db).await.unwrap_or_default();
-                let map_count = maps.len();
+
+                let cache = range_cache(r2_config, &redis);
 
                 if let Some(local_path) = r2_config.local_path() {
                     tracing::info!(path = %local_path, version = %r2_config.version, "Using local file location provider");
                     let reader = FileReader::new(local_path, &r2_config.version);
-                    let provider = PackProvider::new(reader, pack_config);
-
-                    // Register maps with provider
-                    for map in maps {
-                        tracing::debug!(map_id = %map.id, slug = %map.slug, "Registering map");
-                        provider.register_map(map).await;
-                    }
-                    tracing::info!("Registered {} maps with R2 location provider", map_count);
-
-                    Arc::new(provider)
+                    pack_provider(reader, cache, pack_config, maps).await
                 } else {
                     tracing::info!(url = %r2_config.base_url, version = %r2_config.version, "Using R2 HTTP location provider");
                     let reader = HttpReader::new(&r2_config.base_url, &r2_config.version);
-                    let provider = PackProvider::new(reader, pack_config);
-
-                    // Register maps with provider
-                    for map in maps {
-                        tracing::debug!(map_id = %map.id, slug = %map.slug, "Registering map");
-                        provider.register_map(map).await;
-                    }
-                    tracing::info!("Registered {} maps with R2 location provider", map_count);
-
-                    Arc::new(provider)
+                    pack_provider(reader, cache, pack_config, maps).await
                 }
             }
         };
 
+        let pack_storage = match config.location_provider_type {
+            LocationProviderType::Postgres => None,
+            LocationProviderType::R2 => config.r2_location_config.as_ref().map(pack_storage),
+        };
+        let location_health =
+            Arc::new(LocationHealth::new(location_provider.clone(), pack_storage));
+
+        let recent_locations = recent_locations(config.location_repeat_window, &redis);
+
         // Create cleanup channels
         let (game_cleanup_tx, game_cleanup_rx) = mpsc::channel::<String>(100);
         let (party_cleanup_tx, party_cleanup_rx) = mpsc::channel::<String>(100);
         let (party_game_ended_tx, party_game_ended_rx) = mpsc::channel::<(String, String)>(100);
 
+        let socket_token_signer = config.socket_token_secret.as_deref().map(SocketTokenSigner::new);
+        if socket_token_signer.is_none() {
+            tracing::warn!("SOCKET_TOKEN_SECRET not set, socket handshake tokens disabled");
+        }
+
         let state = Self {
             inner: Arc::new(AppStateInner {
                 db,
@@ -248,10 +319,17 @@ impl AppState {
                 parties: RwLock::new(HashMap::new()),
                 socket_users: RwLock::new(HashMap::new()),
                 user_sockets: RwLock::new(HashMap::new()),
+                socket_games: RwLock::new(HashMap::new()),
+                socket_pings: RwLock::new(HashMap::new()),
+                socket_protocols: RwLock::new(HashMap::new()),
+                socket_token_signer,
                 location_provider,
+                location_health,
+                recent_locations,
                 game_cleanup_tx,
                 party_cleanup_tx,
                 party_game_ended_tx,
+                runtime_config: std::sync::RwLock::new(StoredRuntimeSettings::default()),
             }),
         };
 
@@ -291,7 +369,7 @@ impl AppState {
     }
 
     /// Initialize the broadcast emitter with a Redis connection
-    pub async fn init_emitter(&self, conn: redis::aio::MultiplexedConnection) {
+    pub async fn init_emitter(&self, conn: RedisConnection) {
         self.inner.emitter.set_connection(conn).await;
     }
 
@@ -305,16 +383,63 @@ impl AppState {
         &self.inner.db
     }
 
+    /// Readiness checks for the location provider and pack storage
+    pub fn location_health(&self) -> &Arc<LocationHealth> {
+        &self.inner.location_health
+    }
+
+    /// Version of the runtime config applied to this instance
+    pub fn runtime_config_version(&self) -> u64 {
+        self.read_runtime_config().version
+    }
+
+    /// Replace the runtime config
+    pub fn set_runtime_config(&self, stored: StoredRuntimeSettings) {
+        *self.inner.runtime_config.write().unwrap_or_else(|poisoned| poisoned.into_inner()) =
+            stored;
+    }
+
+    /// Party host grace period set by a runtime config reload, if any
+    pub fn party_host_grace_secs(&self) -> Option<u64> {
+        self.read_runtime_config().settings.party_host_grace_secs
+    }
+
+    /// Reconnection grace period for new games, in seconds
+    pub fn default_reconnect_grace_secs(&self) -> u32 {
+        self.read_runtime_config()
+            .settings
+            .reconnect_grace_secs
+            .unwrap_or(dguesser_core::game::DEFAULT_RECONNECT_GRACE_SECONDS)
+    }
+
+    /// Multiplier for socket event rate limits (1 unless reloaded)
+    pub fn socket_rate_limit_multiplier(&self) -> u32 {
+        self.read_runtime_config().settings.socket_rate_limit_multiplier.unwrap_or(1)
+    }
+
+    fn read_runtime_config(&self) -> std::sync::RwLockReadGuard<'_, StoredRuntimeSettings> {
+        self.inner.runtime_config.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
     #[allow(dead_code)]
     pub fn config(&self) -> &Config {
         &self.inner.config
     }
 
-    #[allow(dead_code)]
-    pub fn redis(&self) -> &redis::Client {
+    /// Shared Redis connection
+    pub fn redis(&self) -> &RedisConnection {
         &self.inner.redis
     }
 
+    /// Presence tracker sharing this state's connections
+    pub fn presence(&self) -> PresenceTracker {
+        PresenceTracker::new(
+            self.inner.redis.clone(),
+            self.inner.db.clone(),
+            self.inner.emitter.clone(),
+        )
+    }
+
     /// Get the Redis state manager
     pub fn redis_state(&self) -> &RedisStateManager {
         &self.inner.redis_state
@@ -340,6 +465,10 @@ impl AppState {
     /// Returns `None` if the socket was unknown or the user still has another
     /// active socket (e.g., another browser tab).
     pub async fn unregister_socket(&self, socket_id: &str) -> Option<String> {
+        self.inner.socket_games.write().await.remove(socket_id);
+        self.inner.socket_pings.write().await.remove(socket_id);
+        self.inner.socket_protocols.write().await.remove(socket_id);
+
         let mut socket_users = self.inner.socket_users.write().await;
         let mut user_sockets = self.inner.user_sockets.write().await;
 
@@ -363,6 +492,67 @@ impl AppState {
         self.inner.socket_users.read().await.get(socket_id).cloned()
     }
 
+    /// All authenticated sockets on this instance, as (socket ID, user ID)
+    pub async fn authenticated_sockets(&self) -> Vec<(String, String)> {
+        self.inner
+            .socket_users
+            .read()
+            .await
+            .iter()
+            .map(|(socket_id, user_id)| (socket_id.clone(), user_id.clone()))
+            .collect()
+    }
+
+    /// Restrict a socket to the game its handshake token was bound to
+    pub async fn bind_socket_game(&self, socket_id: &str, game_id: &str) {
+        self.inner.socket_games.write().await.insert(socket_id.to_string(), game_id.to_string());
+    }
+
+    /// Get the game a socket is restricted to, if any
+    pub async fn get_socket_game(&self, socket_id: &str) -> Option<String> {
+        self.inner.socket_games.read().await.get(socket_id).cloned()
+    }
+
+    /// Remember the protocol version a socket authenticated with
+    pub async fn set_socket_protocol(&self, socket_id: &str, version: u32) {
+        self.inner.socket_protocols.write().await.insert(socket_id.to_string(), version);
+    }
+
+    /// Whether a socket negotiated a capability (see [`dguesser_protocol::version`])
+    pub async fn socket_supports(&self, socket_id: &str, capability: &str) -> bool {
+        let version = self
+            .inner
+            .socket_protocols
+            .read()
+            .await
+            .get(socket_id)
+            .copied()
+            .unwrap_or(DEFAULT_CLIENT_VERSION);
+        capabilities_for(version).contains(&capability)
+    }
+
+    /// Remember the server time sent in a socket's latest `time:sync` reply
+    pub async fn set_socket_ping(&self, socket_id: &str, server_time: i64) {
+        self.inner.socket_pings.write().await.insert(socket_id.to_string(), server_time);
+    }
+
+    /// Take a socket's outstanding `time:sync` reply if `server_time` matches
+    /// it, so each reply yields at most one round-trip sample
+    pub async fn take_socket_ping(&self, socket_id: &str, server_time: i64) -> bool {
+        let mut pings = self.inner.socket_pings.write().await;
+        if pings.get(socket_id) == Some(&server_time) {
+            pings.remove(socket_id);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Get the socket handshake token verifier (if configured)
+    pub fn socket_token_signer(&self) -> Option<&SocketTokenSigner> {
+        self.inner.socket_token_signer.as_ref()
+    }
+
     /// Check if a socket is authenticated
     pub async fn is_socket_authenticated(&self, socket_id: &str) -> bool {
         self.inner.socket_users.read().await.contains_key(socket_id)
@@ -392,6 +582,13 @@ impl AppState {
             return handle.clone();
         }
 
+        let handle = self.spawn_game_actor(game_id, None);
+        games.insert(game_id.to_string(), handle.clone());
+        handle
+    }
+
+    /// Spawn a game actor and its tick timer. The caller registers the handle.
+    fn spawn_game_actor(&self, game_id: &str, snapshot: Option<GameSnapshot>) -> GameHandle {
         let (tx, rx) = mpsc::channel(100);
         let handle = GameHandle { game_id: game_id.to_string(), tx };
 
@@ -401,13 +598,18 @@ impl AppState {
         let emitter = self.inner.emitter.clone();
         let redis_state = std::sync::Arc::new(RedisStateManager::new(self.inner.redis.clone()));
         let location_provider = self.inner.location_provider.clone();
+        let recent_locations = self.inner.recent_locations.clone();
         let cleanup_tx = self.inner.game_cleanup_tx.clone();
         let party_notify_tx = self.inner.party_game_ended_tx.clone();
+        let presence = self.presence();
         tokio::spawn(async move {
             let mut actor = GameActor::new(&gid, db, rx, emitter, location_provider)
                 .with_redis(redis_state)
+                .with_presence(presence)
+                .with_recent_locations(recent_locations)
                 .with_cleanup(cleanup_tx)
-                .with_party_notify(party_notify_tx);
+                .with_party_notify(party_notify_tx)
+                .with_snapshot(snapshot);
             actor.run().await;
         });
 
@@ -425,7 +627,20 @@ impl AppState {
             }
         });
 
-        games.insert(game_id.to_string(), handle.clone());
+        handle
+    }
+
+    /// Replace any local actor of the snapshot's game with a fresh one that
+    /// starts from the snapshot.
+    pub async fn restore_game(&self, snapshot: GameSnapshot) -> GameHandle {
+        let game_id = snapshot.game_id.clone();
+        let mut games = self.inner.games.write().await;
+        if let Some(old) = games.remove(&game_id) {
+            let _ = old.tx.send(GameCommand::Shutdown).await;
+        }
+
+        let handle = self.spawn_game_actor(&game_id, Some(snapshot));
+        games.insert(game_id, handle.clone());
         handle
     }
 
@@ -434,13 +649,19 @@ impl AppState {
         self.inner.games.read().await.get(game_id).cloned()
     }
 
-    /// Remove a game actor (when game ends).
+    /// IDs of the games with an actor on this instance
+    pub async fn local_game_ids(&self) -> Vec<String> {
+        self.inner.games.read().await.keys().cloned().collect()
+    }
+
+    /// Remove a game actor and stop it.
     ///
-    /// Note: This is also triggered automatically via the cleanup channel
-    /// when a game actor signals it has finished.
-    #[allow(dead_code)]
+    /// Finished games are removed automatically via the cleanup channel when
+    /// their actor signals it has finished; this is for games ended elsewhere.
     pub async fn remove_game(&self, game_id: &str) {
-        self.inner.games.write().await.remove(game_id);
+        if let Some(handle) = self.inner.games.write().await.remove(game_id) {
+            let _ = handle.tx.send(GameCommand::Shutdown).await;
+        }
     }
 
     // =========================================================================
@@ -545,3 +766,70 @@ impl AppState {
         self.inner.parties.read().await.get(party_id).cloned()
     }
 }
+
+/// Create the recent location history store unless the window is 0.
+fn recent_locations(window_games: usize, redis: &RedisConnection) -> Option<RecentLocations> {
+    if window_games == 0 {
+        tracing::info!("Location repeat protection disabled");
+        return None;
+    }
+    tracing::info!(window_games, "Location repeat protection enabled");
+    Some(RecentLocations::new(redis.clone(), window_games))
+}
+
+/// Uncached reader for the pack storage, used by health checks.
+fn pack_storage(r2_config: &R2LocationConfig) -> Arc<dyn RangeReader> {
+    match r2_config.local_path() {
+        Some(local_path) => Arc::new(FileReader::new(local_path, &r2_config.version)),
+        None => Arc::new(HttpReader::new(&r2_config.base_url, &r2_config.version)),
+    }
+}
+
+/// Create the pack range cache, with a Redis tier if configured.
+fn range_cache(r2_config: &R2LocationConfig, redis: &RedisConnection) -> Option<Arc<RangeCache>> {
+    if r2_config.range_cache_mb == 0 {
+        return None;
+    }
+
+    let cache = RangeCache::new(RangeCacheConfig {
+        max_bytes: r2_config.range_cache_mb * 1024 * 1024,
+        ..RangeCacheConfig::default()
+    });
+    if !r2_config.range_cache_redis {
+        return Some(Arc::new(cache));
+    }
+    Some(Arc::new(
+        cache.with_redis(redis.clone(), &format!("locations:packs:{}", r2_config.version)),
+    ))
+}
+
+/// Build a pack provider over `reader` (through `cache` if set) and register
+/// the given maps with it.
+async fn pack_provider<R: RangeReader + 'static>(
+    reader: R,
+    cache: Option<Arc<RangeCache>>,
+    pack_config: PackProviderConfig,
+    maps: Vec<Map>,
+) -> Arc<dyn LocationProvider> {
+    match cache {
+        Some(cache) => {
+            let reader = CachedReader::new(reader, cache);
+            register_maps(PackProvider::new(reader, pack_config), maps).await
+        }
+        None => register_maps(PackProvider::new(reader, pack_config), maps).await,
+    }
+}
+
+async fn register_maps<R: RangeReader + 'static>(
+    provider: PackProvider<R>,
+    maps: Vec<Map>,
+) -> Arc<dyn LocationProvider> {
+    let map_count = maps.len();
+    for map in maps {
+        tracing::debug!(map_id = %map.id, slug = %map.slug, "Registering map");
+        provider.register_map(map).await;
+    }
+    tracing::info!("Registered {} maps with R2 location provider", map_count);
+
+    Arc::new(provider)
+}
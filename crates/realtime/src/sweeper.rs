@@ -0,0 +1,109 @@
+//! Stale game sweeper
+//!
+//! Multiplayer games stuck in lobby or active with no activity for
+//! [`Config::stale_game_hours`](crate::config::Config) are abandoned in the
+//! database. Their actors are stopped, their Redis state is removed and any
+//! players still in the room are told. Solo and streak games are swept by the
+//! API.
+//!
+//! Actors of games that ended elsewhere (abandoned by another instance's
+//! sweep, or ended by an admin through the API) are stopped more often.
+
+use std::time::Duration;
+
+use dguesser_protocol::socket::events;
+use dguesser_protocol::socket::payloads::GameAbandonedPayload;
+
+use crate::state::AppState;
+
+/// How often to look for stale games
+const SWEEP_INTERVAL_SECS: u64 = 15 * 60;
+
+/// How often to check whether local games ended elsewhere
+const ENDED_CHECK_INTERVAL_SECS: u64 = 30;
+
+/// Spawn the tasks that abandon stale multiplayer games and stop the actors
+/// of games that ended.
+pub fn spawn_stale_game_sweeper(state: AppState) {
+    let ended_state = state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(SWEEP_INTERVAL_SECS));
+
+        // Skip the first immediate tick; recovered games get a chance to resume
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+            sweep(&state).await;
+        }
+    });
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(ENDED_CHECK_INTERVAL_SECS));
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+            release_ended(&ended_state).await;
+        }
+    });
+
+    tracing::info!("Stale game sweeper started");
+}
+
+/// Abandon stale games.
+async fn sweep(state: &AppState) {
+    let hours = state.config().stale_game_hours;
+    let abandoned = match dguesser_db::games::abandon_stale(
+        state.db(),
+        &[dguesser_db::GameMode::Multiplayer],
+        hours,
+    )
+    .await
+    {
+        Ok(ids) => ids,
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to abandon stale games");
+            return;
+        }
+    };
+
+    if !abandoned.is_empty() {
+        tracing::info!(abandoned_count = abandoned.len(), "Abandoned stale games");
+    }
+    for game_id in &abandoned {
+        release_game(state, game_id).await;
+
+        let payload = GameAbandonedPayload {
+            game_id: game_id.clone(),
+            reason: format!("No activity for {hours} hours"),
+        };
+        let _ =
+            state.emitter().emit_to_room(game_id, events::server::GAME_ABANDONED, &payload).await;
+    }
+}
+
+/// Stop local actors of games that are no longer in lobby or active.
+async fn release_ended(state: &AppState) {
+    let local = state.local_game_ids().await;
+    if local.is_empty() {
+        return;
+    }
+    match dguesser_db::games::filter_ended(state.db(), &local).await {
+        Ok(ended) => {
+            for game_id in &ended {
+                tracing::info!(game_id = %game_id, "Stopping actor of ended game");
+                release_game(state, game_id).await;
+            }
+        }
+        Err(e) => tracing::warn!(error = %e, "Failed to check local games for staleness"),
+    }
+}
+
+/// Stop a game's actor and remove its Redis state.
+async fn release_game(state: &AppState, game_id: &str) {
+    state.remove_game(game_id).await;
+    if let Err(e) = state.redis_state().delete_game_state(game_id).await {
+        tracing::warn!(error = %e, game_id = %game_id, "Failed to delete stale game state");
+    }
+}
@@ -0,0 +1,90 @@
+//! Redis topology configuration
+
+use std::env;
+
+use anyhow::{Result, bail};
+
+/// Where Redis lives and how to reach it
+#[derive(Debug, Clone)]
+pub struct RedisConfig {
+    /// Redis URL; with Sentinel, only its credentials and database are used,
+    /// and with Cluster it is ignored
+    pub url: String,
+    pub topology: RedisTopology,
+}
+
+/// How the Redis deployment is laid out
+#[derive(Debug, Clone)]
+pub enum RedisTopology {
+    /// A single node at [`RedisConfig::url`]
+    Standalone,
+    /// A master resolved through Sentinel, re-resolved after failovers
+    Sentinel(RedisSentinelConfig),
+    /// A Redis Cluster, discovered from its seed nodes
+    Cluster(RedisClusterConfig),
+}
+
+/// Redis Sentinel configuration, used instead of connecting to `REDIS_URL`
+/// directly.
+#[derive(Debug, Clone)]
+pub struct RedisSentinelConfig {
+    /// Sentinel node URLs (e.g., "redis://sentinel-1:26379")
+    pub urls: Vec<String>,
+    /// Name of the monitored master
+    pub service_name: String,
+}
+
+/// Redis Cluster configuration
+#[derive(Debug, Clone)]
+pub struct RedisClusterConfig {
+    /// Seed node URLs (e.g., "redis://:password@node-1:6379"); the rest of the
+    /// cluster is discovered from them
+    pub urls: Vec<String>,
+}
+
+impl RedisConfig {
+    /// Create from environment variables: `REDIS_URL`, plus either
+    /// `REDIS_SENTINEL_URLS` or `REDIS_CLUSTER_URLS`.
+    pub fn from_env() -> Result<Self> {
+        let url = env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string());
+        let topology = match (RedisSentinelConfig::from_env(), RedisClusterConfig::from_env()) {
+            (None, None) => RedisTopology::Standalone,
+            (Some(sentinel), None) => RedisTopology::Sentinel(sentinel),
+            (None, Some(cluster)) => RedisTopology::Cluster(cluster),
+            (Some(_), Some(_)) => {
+                bail!("REDIS_SENTINEL_URLS and REDIS_CLUSTER_URLS cannot both be set")
+            }
+        };
+        Ok(Self { url, topology })
+    }
+}
+
+impl RedisSentinelConfig {
+    /// Create from environment variables (`REDIS_SENTINEL_URLS`, a
+    /// comma-separated list, and `REDIS_SENTINEL_SERVICE`).
+    pub fn from_env() -> Option<Self> {
+        let urls = url_list("REDIS_SENTINEL_URLS")?;
+        let service_name =
+            env::var("REDIS_SENTINEL_SERVICE").unwrap_or_else(|_| "mymaster".to_string());
+
+        Some(Self { urls, service_name })
+    }
+}
+
+impl RedisClusterConfig {
+    /// Create from `REDIS_CLUSTER_URLS`, a comma-separated list of seed nodes.
+    pub fn from_env() -> Option<Self> {
+        Some(Self { urls: url_list("REDIS_CLUSTER_URLS")? })
+    }
+}
+
+/// Comma-separated URLs from an environment variable, `None` if unset or empty
+fn url_list(var: &str) -> Option<Vec<String>> {
+    let urls: Vec<String> = env::var(var)
+        .ok()?
+        .split(',')
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty())
+        .collect();
+    (!urls.is_empty()).then_some(urls)
+}
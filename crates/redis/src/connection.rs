@@ -0,0 +1,576 @@
+//! The shared Redis connection
+//!
+//! Clones of a [`RedisConnection`] share one multiplexed connection (or one
+//! cluster connection), so request handlers pipeline their commands over it
+//! instead of opening a connection per call.
+//!
+//! With Sentinel, the master is resolved at startup and again whenever it
+//! looks gone: a READONLY reply (the node was demoted to a replica), a lost
+//! connection, or a failed [`RedisConnection::check_master`]. When Sentinel
+//! names a new master, the connection moves there and its pub/sub channels
+//! are subscribed again. Commands rejected as READONLY never ran, so they are
+//! retried once on the new master; other failures are returned as-is.
+
+use std::collections::BTreeSet;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use redis::aio::{ConnectionLike, ConnectionManager, ConnectionManagerConfig};
+use redis::cluster::ClusterClientBuilder;
+use redis::cluster_async::ClusterConnection;
+use redis::cluster_routing::{RoutingInfo, SingleNodeRoutingInfo};
+use redis::sentinel::{SentinelClient, SentinelNodeConnectionInfo, SentinelServerType};
+use redis::{
+    Cmd, ErrorKind, FromRedisValue, IntoConnectionInfo, Pipeline, ProtocolVersion, PushInfo,
+    RedisError, RedisFuture, RedisResult, ServerErrorKind, Value,
+};
+
+use crate::config::{RedisConfig, RedisTopology};
+
+/// First reconnect delay; doubles with every failed attempt
+const RECONNECT_MIN_DELAY: Duration = Duration::from_millis(100);
+
+/// Longest delay between reconnect attempts
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(10);
+
+/// Reconnect attempts before waiting for the next command to try again
+const RECONNECT_RETRIES: usize = 10;
+
+/// Timeout for establishing a connection and for each response
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Shortest time between two Sentinel lookups, so an outage doesn't turn
+/// every failed command into a lookup
+const MIN_RESOLVE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Keys asked for per SCAN call
+const SCAN_COUNT: usize = 100;
+
+type PushHandler = Arc<dyn Fn(PushInfo) + Send + Sync>;
+
+/// Shared Redis connection; cheap to clone
+#[derive(Clone)]
+pub struct RedisConnection {
+    shared: Arc<Shared>,
+}
+
+struct Shared {
+    current: RwLock<Current>,
+    failover: Option<Failover>,
+    /// Channels subscribed through this connection, restored after a failover
+    channels: Mutex<BTreeSet<String>>,
+    db: i64,
+}
+
+#[derive(Clone)]
+struct Current {
+    /// Bumped every time the connection moves to another master
+    generation: u64,
+    backend: Backend,
+}
+
+#[derive(Clone)]
+enum Backend {
+    Single(ConnectionManager),
+    Cluster(ClusterConnection),
+}
+
+/// What it takes to follow the master through Sentinel failovers
+struct Failover {
+    service_name: String,
+    config: ConnectionManagerConfig,
+    resolver: tokio::sync::Mutex<Resolver>,
+}
+
+struct Resolver {
+    sentinel: SentinelClient,
+    /// Address of the master the connection points at
+    master: String,
+    resolved_at: Option<Instant>,
+}
+
+impl RedisConnection {
+    /// Connect to the configured topology.
+    ///
+    /// A standalone node is connected lazily, so the service starts while
+    /// Redis is down; Sentinel and Cluster need their topology up front.
+    pub async fn connect(config: &RedisConfig) -> RedisResult<Self> {
+        Self::open(config, None).await
+    }
+
+    /// Connect over RESP3 and hand every pub/sub push to `on_push`. Channels
+    /// are subscribed again after reconnects and failovers.
+    pub async fn subscriber(
+        config: &RedisConfig,
+        on_push: impl Fn(PushInfo) + Send + Sync + 'static,
+    ) -> RedisResult<Self> {
+        Self::open(config, Some(Arc::new(on_push))).await
+    }
+
+    /// Lazily connect to a single node, for tools and tests.
+    pub fn open_url(url: &str) -> RedisResult<Self> {
+        let client = redis::Client::open(url)?;
+        let db = client.get_connection_info().redis_settings().db();
+        let conn = client.get_connection_manager_lazy(manager_config())?;
+        Ok(Self::new(Backend::Single(conn), None, db))
+    }
+
+    fn new(backend: Backend, failover: Option<Failover>, db: i64) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                current: RwLock::new(Current { generation: 0, backend }),
+                failover,
+                channels: Mutex::new(BTreeSet::new()),
+                db,
+            }),
+        }
+    }
+
+    async fn open(config: &RedisConfig, on_push: Option<PushHandler>) -> RedisResult<Self> {
+        let url = match on_push {
+            Some(_) => ensure_resp3_protocol(&config.url),
+            None => config.url.clone(),
+        };
+        let mut manager = manager_config();
+        if let Some(on_push) = on_push.clone() {
+            manager = manager
+                .set_push_sender(move |msg| {
+                    on_push(msg);
+                    Ok::<(), Infallible>(())
+                })
+                .set_automatic_resubscription();
+        }
+
+        match &config.topology {
+            RedisTopology::Standalone => {
+                let client = redis::Client::open(url.as_str())?;
+                let db = client.get_connection_info().redis_settings().db();
+                let conn = client.get_connection_manager_lazy(manager)?;
+                Ok(Self::new(Backend::Single(conn), None, db))
+            }
+            RedisTopology::Sentinel(sentinel) => {
+                let node_info = url.as_str().into_connection_info()?.redis_settings().clone();
+                let db = node_info.db();
+                let mut client = SentinelClient::build(
+                    sentinel.urls.clone(),
+                    sentinel.service_name.clone(),
+                    Some(
+                        SentinelNodeConnectionInfo::default().set_redis_connection_info(node_info),
+                    ),
+                    SentinelServerType::Master,
+                )?;
+                let master_client = client.async_get_client().await?;
+                let master = master_client.get_connection_info().addr().to_string();
+                let conn =
+                    master_client.get_connection_manager_with_config(manager.clone()).await?;
+
+                tracing::info!(
+                    service = %sentinel.service_name,
+                    master = %master,
+                    "Resolved Redis master through Sentinel"
+                );
+                let failover = Failover {
+                    service_name: sentinel.service_name.clone(),
+                    config: manager,
+                    resolver: tokio::sync::Mutex::new(Resolver {
+                        sentinel: client,
+                        master,
+                        resolved_at: Some(Instant::now()),
+                    }),
+                };
+                Ok(Self::new(Backend::Single(conn), Some(failover), db))
+            }
+            RedisTopology::Cluster(cluster) => {
+                let mut builder = ClusterClientBuilder::new(cluster.urls.clone())
+                    .connection_timeout(TIMEOUT)
+                    .response_timeout(TIMEOUT)
+                    .retries(RECONNECT_RETRIES as u32)
+                    .min_retry_wait(RECONNECT_MIN_DELAY.as_millis() as u64)
+                    .max_retry_wait(RECONNECT_MAX_DELAY.as_millis() as u64);
+                if let Some(on_push) = on_push {
+                    builder =
+                        builder.use_protocol(ProtocolVersion::RESP3).push_sender(move |msg| {
+                            on_push(msg);
+                            Ok::<(), Infallible>(())
+                        });
+                }
+                let conn = builder.build()?.get_async_connection().await?;
+
+                tracing::info!(seeds = cluster.urls.len(), "Connected to Redis Cluster");
+                Ok(Self::new(Backend::Cluster(conn), None, 0))
+            }
+        }
+    }
+
+    /// Check the connection still talks to the master, which stops being
+    /// true when Sentinel fails over to a replica. A failed check moves the
+    /// connection to the master Sentinel names now and checks again.
+    ///
+    /// Always passes for standalone nodes and clusters.
+    pub async fn check_master(&self) -> RedisResult<()> {
+        if self.shared.failover.is_none() {
+            return Ok(());
+        }
+        let current = self.shared.current();
+        let Err(e) = role_is_master(current.backend).await else {
+            return Ok(());
+        };
+        if !self.shared.fail_over(current.generation).await {
+            return Err(e);
+        }
+        role_is_master(self.shared.current().backend).await
+    }
+
+    /// Subscribe to a channel; pushes go to the handler given to
+    /// [`RedisConnection::subscriber`].
+    pub async fn subscribe(&self, channel: &str) -> RedisResult<()> {
+        self.shared.channels.lock().unwrap().insert(channel.to_string());
+        match self.shared.current().backend {
+            Backend::Single(mut conn) => conn.subscribe(channel).await,
+            Backend::Cluster(mut conn) => conn.subscribe(channel).await,
+        }
+    }
+
+    /// Unsubscribe from a channel.
+    pub async fn unsubscribe(&self, channel: &str) -> RedisResult<()> {
+        self.shared.channels.lock().unwrap().remove(channel);
+        match self.shared.current().backend {
+            Backend::Single(mut conn) => conn.unsubscribe(channel).await,
+            Backend::Cluster(mut conn) => conn.unsubscribe(channel).await,
+        }
+    }
+
+    /// Every key matching `pattern`, scanned incrementally. On a cluster,
+    /// each master is scanned in turn, since SCAN only sees one node's keys.
+    pub async fn scan_match(&self, pattern: &str) -> RedisResult<Vec<String>> {
+        let mut keys = Vec::new();
+        let Backend::Cluster(mut conn) = self.shared.current().backend else {
+            let mut conn = self.clone();
+            let mut cursor = 0;
+            loop {
+                let (next_cursor, batch): (u64, Vec<String>) =
+                    scan_cmd(cursor, pattern).query_async(&mut conn).await?;
+                keys.extend(batch);
+                cursor = next_cursor;
+                if cursor == 0 {
+                    return Ok(keys);
+                }
+            }
+        };
+
+        let nodes = conn
+            .route_command(
+                redis::cmd("CLUSTER").arg("NODES").clone(),
+                RoutingInfo::SingleNode(SingleNodeRoutingInfo::RandomPrimary),
+            )
+            .await?;
+        for (host, port) in cluster_masters(&String::from_redis_value(nodes)?) {
+            let mut cursor = 0;
+            loop {
+                let routing = RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress {
+                    host: host.clone(),
+                    port,
+                });
+                let reply = conn.route_command(scan_cmd(cursor, pattern), routing).await?;
+                let (next_cursor, batch): (u64, Vec<String>) =
+                    FromRedisValue::from_redis_value(reply)?;
+                keys.extend(batch);
+                cursor = next_cursor;
+                if cursor == 0 {
+                    break;
+                }
+            }
+        }
+        Ok(keys)
+    }
+
+    /// Run a request, following the master if it moved.
+    async fn dispatch<'a, T>(
+        &'a self,
+        request: impl Fn(Backend) -> RedisFuture<'a, T>,
+    ) -> RedisResult<T> {
+        let current = self.shared.current();
+        match request(current.backend).await {
+            Err(e) if self.shared.failover.is_some() && lost_master(&e) => {
+                let moved = self.shared.fail_over(current.generation).await;
+                if moved && is_read_only(&e) {
+                    return request(self.shared.current().backend).await;
+                }
+                Err(e)
+            }
+            result => result,
+        }
+    }
+}
+
+impl Shared {
+    fn current(&self) -> Current {
+        self.current.read().unwrap().clone()
+    }
+
+    /// Ask Sentinel for the master and move the connection there if it is
+    /// not the one behind `generation`. Returns whether the connection now
+    /// points somewhere else.
+    async fn fail_over(&self, generation: u64) -> bool {
+        let Some(failover) = &self.failover else {
+            return false;
+        };
+        let mut resolver = failover.resolver.lock().await;
+        if self.current().generation != generation {
+            // Another request already moved the connection
+            return true;
+        }
+        if resolver.resolved_at.is_some_and(|at| at.elapsed() < MIN_RESOLVE_INTERVAL) {
+            return false;
+        }
+        resolver.resolved_at = Some(Instant::now());
+
+        let client = match resolver.sentinel.async_get_client().await {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to resolve the Redis master through Sentinel");
+                return false;
+            }
+        };
+        let master = client.get_connection_info().addr().to_string();
+        if master == resolver.master {
+            return false;
+        }
+        let mut conn = match client
+            .get_connection_manager_with_config(failover.config.clone())
+            .await
+        {
+            Ok(conn) => conn,
+            Err(e) => {
+                tracing::warn!(error = %e, master = %master, "Failed to connect to the new Redis master");
+                return false;
+            }
+        };
+        let channels: Vec<String> = self.channels.lock().unwrap().iter().cloned().collect();
+        for channel in channels {
+            if let Err(e) = conn.subscribe(&channel).await {
+                tracing::warn!(error = %e, channel = %channel, "Failed to resubscribe after failover");
+            }
+        }
+
+        {
+            let mut current = self.current.write().unwrap();
+            current.generation += 1;
+            current.backend = Backend::Single(conn);
+        }
+        tracing::warn!(
+            service = %failover.service_name,
+            from = %resolver.master,
+            to = %master,
+            "Redis master moved, reconnected through Sentinel"
+        );
+        resolver.master = master;
+        true
+    }
+}
+
+impl ConnectionLike for RedisConnection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        let this = &*self;
+        Box::pin(this.dispatch(move |mut backend| {
+            Box::pin(async move { backend.req_packed_command(cmd).await })
+        }))
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        pipeline: &'a Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisFuture<'a, Vec<Value>> {
+        let this = &*self;
+        Box::pin(this.dispatch(move |mut backend| {
+            Box::pin(async move { backend.req_packed_commands(pipeline, offset, count).await })
+        }))
+    }
+
+    fn get_db(&self) -> i64 {
+        self.shared.db
+    }
+}
+
+impl ConnectionLike for Backend {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        match self {
+            Backend::Single(conn) => conn.req_packed_command(cmd),
+            Backend::Cluster(conn) => conn.req_packed_command(cmd),
+        }
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        pipeline: &'a Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisFuture<'a, Vec<Value>> {
+        match self {
+            Backend::Single(conn) => conn.req_packed_commands(pipeline, offset, count),
+            Backend::Cluster(conn) => Box::pin(async move {
+                match conn.req_packed_commands(pipeline, offset, count).await {
+                    // A plain pipeline (not MULTI, which starts at an
+                    // offset) spanning slots runs command by command
+                    Err(e) if offset == 0 && is_cross_slot(&e) => {
+                        let mut values = Vec::with_capacity(count);
+                        for cmd in pipeline.cmd_iter() {
+                            values.push(conn.req_packed_command(cmd).await?);
+                        }
+                        Ok(values)
+                    }
+                    result => result,
+                }
+            }),
+        }
+    }
+
+    fn get_db(&self) -> i64 {
+        match self {
+            Backend::Single(conn) => conn.get_db(),
+            Backend::Cluster(conn) => conn.get_db(),
+        }
+    }
+}
+
+/// Reconnect policy: exponential backoff from 100ms up to 10s.
+fn manager_config() -> ConnectionManagerConfig {
+    ConnectionManagerConfig::new()
+        .set_min_delay(RECONNECT_MIN_DELAY)
+        .set_max_delay(RECONNECT_MAX_DELAY)
+        .set_number_of_retries(RECONNECT_RETRIES)
+        .set_connection_timeout(Some(TIMEOUT))
+        .set_response_timeout(Some(TIMEOUT))
+}
+
+/// Ensure the Redis URL asks for RESP3, which pub/sub pushes need
+fn ensure_resp3_protocol(url: &str) -> String {
+    if url.contains("protocol=") {
+        // Already has protocol parameter
+        url.to_string()
+    } else if url.contains('?') {
+        // Has other query params, append
+        format!("{}&protocol=resp3", url)
+    } else {
+        // No query params
+        format!("{}?protocol=resp3", url)
+    }
+}
+
+async fn role_is_master(mut backend: Backend) -> RedisResult<()> {
+    let role: Vec<Value> = redis::cmd("ROLE").query_async(&mut backend).await?;
+    match role.first() {
+        Some(Value::BulkString(role)) if role.as_slice() == b"master" => Ok(()),
+        Some(Value::SimpleString(role)) if role == "master" => Ok(()),
+        _ => Err(RedisError::from((
+            ErrorKind::Client,
+            "Connected Redis node is no longer the master",
+        ))),
+    }
+}
+
+/// One SCAN call, continuing from `cursor`
+fn scan_cmd(cursor: u64, pattern: &str) -> Cmd {
+    let mut cmd = redis::cmd("SCAN");
+    cmd.arg(cursor).arg("MATCH").arg(pattern).arg("COUNT").arg(SCAN_COUNT);
+    cmd
+}
+
+/// Addresses of the reachable masters in a CLUSTER NODES reply.
+fn cluster_masters(nodes: &str) -> Vec<(String, u16)> {
+    nodes
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let address = fields.nth(1)?;
+            let flags: Vec<&str> = fields.next()?.split(',').collect();
+            if !flags.contains(&"master") || flags.iter().any(|flag| flag.starts_with("fail")) {
+                return None;
+            }
+            let (host, port) = address.split(['@', ',']).next()?.rsplit_once(':')?;
+            Some((host.to_string(), port.parse().ok()?))
+        })
+        .collect()
+}
+
+fn is_read_only(e: &RedisError) -> bool {
+    e.kind() == ErrorKind::Server(ServerErrorKind::ReadOnly)
+}
+
+fn is_cross_slot(e: &RedisError) -> bool {
+    e.kind() == ErrorKind::Server(ServerErrorKind::CrossSlot)
+}
+
+/// Whether an error suggests the master moved: it was demoted to a replica,
+/// or it stopped answering.
+fn lost_master(e: &RedisError) -> bool {
+    is_read_only(e) || e.is_io_error() || e.is_connection_refusal() || e.is_connection_dropped()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ensure_resp3_protocol() {
+        assert_eq!(ensure_resp3_protocol("redis://localhost"), "redis://localhost?protocol=resp3");
+        assert_eq!(
+            ensure_resp3_protocol("redis://localhost?db=1"),
+            "redis://localhost?db=1&protocol=resp3"
+        );
+        assert_eq!(
+            ensure_resp3_protocol("redis://localhost?protocol=resp2"),
+            "redis://localhost?protocol=resp2"
+        );
+    }
+
+    #[test]
+    fn test_cluster_masters() {
+        let nodes = "\
+07c37dfeb235213a872192d90877d0cd55635b91 127.0.0.1:30004@31004 slave e7d1eecce10fd6bb5eb35b9f99a514335d9ba9ca 0 1426238317239 4 connected
+67ed2db8d677e59ec4a4cefb06858cf2a1a89fa1 127.0.0.1:30002@31002,node-2 master - 0 1426238316232 2 connected 5461-10922
+e7d1eecce10fd6bb5eb35b9f99a514335d9ba9ca 127.0.0.1:30001@31001 myself,master - 0 0 1 connected 0-5460
+6ec23923021cf3ffec47632106199cb7f496ce01 127.0.0.1:30005@31005 master,fail - 1426238316232 0 5 disconnected
+";
+        assert_eq!(
+            cluster_masters(nodes),
+            vec![("127.0.0.1".to_string(), 30002), ("127.0.0.1".to_string(), 30001)]
+        );
+    }
+
+    #[test]
+    fn test_failover_errors() {
+        let read_only = RedisError::from((
+            ErrorKind::Server(ServerErrorKind::ReadOnly),
+            "You can't write against a read only replica.",
+        ));
+        assert!(is_read_only(&read_only) && lost_master(&read_only));
+
+        let refused = RedisError::from(std::io::Error::from(std::io::ErrorKind::ConnectionRefused));
+        assert!(lost_master(&refused) && !is_read_only(&refused));
+
+        let wrong_type =
+            RedisError::from((ErrorKind::Server(ServerErrorKind::NoScript), "NOSCRIPT"));
+        assert!(!lost_master(&wrong_type));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Redis"]
+    async fn test_commands_share_one_connection() {
+        let conn = RedisConnection::open_url("redis://127.0.0.1:6379").unwrap();
+        let key = "test:redis_connection";
+        let mut a = conn.clone();
+        let mut b = conn.clone();
+        let _: () = redis::cmd("SET").arg(key).arg("1").query_async(&mut a).await.unwrap();
+        let value: String = redis::cmd("GET").arg(key).query_async(&mut b).await.unwrap();
+        let _: () = redis::cmd("DEL").arg(key).query_async(&mut a).await.unwrap();
+        assert_eq!(value, "1");
+
+        assert!(conn.check_master().await.is_ok());
+        let keys = conn.scan_match("test:redis_connection:none:*").await.unwrap();
+        assert!(keys.is_empty());
+    }
+}
@@ -0,0 +1,11 @@
+//! Redis connections shared by the DGuesser services
+//!
+//! Every request path talks to Redis through one [`RedisConnection`]: a
+//! multiplexed connection that reconnects with backoff, follows the master
+//! when Sentinel fails over, and speaks Redis Cluster when configured.
+
+mod config;
+mod connection;
+
+pub use config::{RedisClusterConfig, RedisConfig, RedisSentinelConfig, RedisTopology};
+pub use connection::RedisConnection;
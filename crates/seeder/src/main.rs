@@ -4,18 +4,31 @@
 //! - Vali JSON output files (recommended)
 //! - JSON files with location arrays
 //! - CSV files with lat/lng columns
+//! - Mapillary images, fetched from the Mapillary API by bounding box
 //!
 //! Usage:
 //! ```bash
 //! # Import from Vali output (recommended)
 //! seeder import-vali --file world-locations.json --map world
 //!
+//! # Continue an interrupted Vali import from its checkpoint
+//! seeder import-vali --file world-locations.json --map world --resume
+//!
 //! # Import from JSON file
 //! seeder import --file locations.json --map world
 //!
+//! # Validate against the Street View metadata API, 64 lookups at a time
+//! seeder import --file locations.json --map world --google-api-key KEY --concurrency 64
+//!
 //! # Import from CSV file
 //! seeder import --file locations.csv --map world
 //!
+//! # Import Mapillary panoramas inside a bounding box (min_lng,min_lat,max_lng,max_lat)
+//! seeder import-mapillary --bbox 13.30,52.45,13.50,52.57 --map berlin
+//!
+//! # Export a map's locations (json, csv, or vali)
+//! seeder export --map world --format vali --output world-backup.json
+//!
 //! # Generate sample locations (development only)
 //! seeder generate --count 100 --map world
 //!
@@ -29,15 +42,22 @@
 //! seeder disable-old --before-year 2012
 //! ```
 
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use anyhow::Result;
-use chrono::NaiveDate;
+use chrono::{Datelike, NaiveDate, Utc};
 use clap::{Parser, Subcommand};
-use dguesser_core::location::MapRules;
+use dguesser_core::geo::haversine_distance;
+use dguesser_core::location::{Location, MapRules, normalize_tags};
+use dguesser_core::streetview::{ImageryProvider, OVER_QUERY_LIMIT, QuotaBreaker, QuotaState};
 use dguesser_db::locations::CreateLocationParams;
+use futures::{StreamExt, stream};
 use indicatif::{ProgressBar, ProgressStyle};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 // =============================================================================
 // CLI Interface
@@ -79,6 +99,18 @@ enum Commands {
         #[arg(long)]
         limit: Option<usize>,
 
+        /// Locations inserted per transaction
+        #[arg(long, default_value_t = 1000)]
+        batch_size: usize,
+
+        /// Checkpoint file (default: the input file with `.checkpoint.json` appended)
+        #[arg(long)]
+        checkpoint: Option<PathBuf>,
+
+        /// Continue from the checkpoint of an interrupted import
+        #[arg(long)]
+        resume: bool,
+
         /// Run without making changes
         #[arg(long)]
         dry_run: bool,
@@ -105,6 +137,64 @@ enum Commands {
         /// Limit number of locations to import
         #[arg(long)]
         limit: Option<usize>,
+
+        /// Street View metadata requests in flight at once
+        #[arg(long, default_value_t = 32)]
+        concurrency: usize,
+
+        /// Retries per location for transient errors and rate limiting
+        #[arg(long, default_value_t = 4)]
+        max_retries: u32,
+    },
+
+    /// Import Mapillary images inside a bounding box
+    ImportMapillary {
+        /// Bounding box as min_lng,min_lat,max_lng,max_lat
+        #[arg(long)]
+        bbox: String,
+
+        /// Map slug to add locations to
+        #[arg(short, long, default_value = "world")]
+        map: String,
+
+        /// Mapillary client access token
+        #[arg(long, env = "MAPILLARY_ACCESS_TOKEN")]
+        access_token: String,
+
+        /// Also import flat (non-360°) images
+        #[arg(long)]
+        include_flat: bool,
+
+        /// Minimum capture year
+        #[arg(long)]
+        min_year: Option<i32>,
+
+        /// Minimum distance between imported images in meters
+        #[arg(long, default_value = "250")]
+        min_spacing_m: f64,
+
+        /// Maximum number of locations to import
+        #[arg(long, default_value = "1000")]
+        limit: usize,
+
+        /// Run without making changes
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Export a map's locations to a file
+    Export {
+        /// Map slug to export
+        #[arg(short, long)]
+        map: String,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = ExportFormat::Json)]
+        format: ExportFormat,
+
+        /// Output file (default: `<map>.<format>` in the current directory)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
     },
 
     /// Generate random sample locations (for development)
@@ -174,6 +264,10 @@ enum MapCommands {
         #[arg(long)]
         outdoor_only: bool,
 
+        /// Only include one imagery provider (e.g., "mapillary")
+        #[arg(long, value_parser = |s: &str| s.parse::<ImageryProvider>())]
+        provider: Option<ImageryProvider>,
+
         /// Make this the default map
         #[arg(long)]
         default: bool,
@@ -195,7 +289,7 @@ struct InputLocation {
     /// Longitude
     lng: f64,
     /// Panorama ID (optional, will be validated if Google API key provided)
-    #[serde(default)]
+    #[serde(default, alias = "panorama_id")]
     pano_id: Option<String>,
     /// Country code (optional)
     #[serde(default)]
@@ -211,7 +305,7 @@ struct InputLocation {
 
 /// Vali output location format.
 /// This matches the JSON format produced by the Vali CLI tool.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct ValiLocation {
     /// Latitude
@@ -225,11 +319,11 @@ struct ValiLocation {
     #[serde(default)]
     heading: Option<f64>,
     /// Pitch (not stored, but present in Vali output)
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     #[allow(dead_code)]
     pitch: Option<f64>,
     /// Zoom (not stored, but present in Vali output)
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     #[allow(dead_code)]
     zoom: Option<f64>,
     /// ISO 3166-1 alpha-2 country code
@@ -263,8 +357,7 @@ struct ValiLocation {
     #[serde(default)]
     elevation: Option<i32>,
     /// Tags from Vali
-    #[serde(default)]
-    #[allow(dead_code)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     tags: Option<Vec<String>>,
 }
 
@@ -288,7 +381,7 @@ impl ValiLocation {
             country_code: self.country_code.clone(),
             subdivision_code: self.subdivision_code.clone(),
             capture_date,
-            provider: "google_streetview".to_string(),
+            provider: ImageryProvider::GoogleStreetView,
             source: "vali".to_string(),
             surface: self.surface.clone(),
             arrow_count: self.arrow_count,
@@ -298,6 +391,7 @@ impl ValiLocation {
             elevation: self.elevation,
             heading: self.heading,
             review_status: "approved".to_string(), // Vali locations are pre-verified
+            tags: self.tags.as_deref().map(normalize_tags).unwrap_or_default(),
         }
     }
 
@@ -349,6 +443,119 @@ struct StreetViewLocation {
     lng: f64,
 }
 
+// =============================================================================
+// Mapillary Format
+// =============================================================================
+
+/// Mapillary Graph API image search endpoint
+const MAPILLARY_IMAGES_URL: &str = "https://graph.mapillary.com/images";
+
+/// Side of the tiles a bounding box is searched in, in degrees. The API
+/// rejects large boxes and caps results per request.
+const MAPILLARY_TILE_DEGREES: f64 = 0.05;
+
+/// Images requested per tile
+const MAPILLARY_TILE_LIMIT: usize = 500;
+
+/// Mapillary image search response.
+#[derive(Debug, Deserialize)]
+struct MapillaryImages {
+    data: Vec<MapillaryImage>,
+}
+
+/// One Mapillary image.
+#[derive(Debug, Deserialize)]
+struct MapillaryImage {
+    /// Numeric image key
+    id: String,
+    /// Position snapped by Mapillary's reconstruction, when available
+    #[serde(default)]
+    computed_geometry: Option<MapillaryPoint>,
+    /// Position reported by the capture device
+    geometry: MapillaryPoint,
+    /// Capture time (unix ms)
+    #[serde(default)]
+    captured_at: Option<i64>,
+    /// Camera heading in degrees
+    #[serde(default)]
+    compass_angle: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MapillaryPoint {
+    /// `[lng, lat]`
+    coordinates: [f64; 2],
+}
+
+impl MapillaryImage {
+    /// Best known position as `(lat, lng)`.
+    fn position(&self) -> (f64, f64) {
+        let [lng, lat] = self.computed_geometry.as_ref().unwrap_or(&self.geometry).coordinates;
+        (lat, lng)
+    }
+
+    fn capture_date(&self) -> Option<NaiveDate> {
+        self.captured_at
+            .and_then(chrono::DateTime::from_timestamp_millis)
+            .map(|captured| captured.date_naive())
+    }
+
+    /// Convert to database creation parameters.
+    fn to_create_params(&self) -> CreateLocationParams {
+        let (lat, lng) = self.position();
+        CreateLocationParams {
+            panorama_id: self.id.clone(),
+            lat,
+            lng,
+            capture_date: self.capture_date(),
+            provider: ImageryProvider::Mapillary,
+            source: "crawled".to_string(),
+            heading: self.compass_angle.filter(|h| (0.0..=360.0).contains(h)),
+            review_status: "approved".to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Parse a `min_lng,min_lat,max_lng,max_lat` bounding box.
+fn parse_bbox(bbox: &str) -> Result<[f64; 4]> {
+    let values = bbox
+        .split(',')
+        .map(|v| v.trim().parse::<f64>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| anyhow::anyhow!("Bounding box must be four numbers"))?;
+    let [min_lng, min_lat, max_lng, max_lat] = values[..] else {
+        anyhow::bail!("Bounding box must be min_lng,min_lat,max_lng,max_lat");
+    };
+    if !(-180.0..=180.0).contains(&min_lng)
+        || !(-180.0..=180.0).contains(&max_lng)
+        || !(-90.0..=90.0).contains(&min_lat)
+        || !(-90.0..=90.0).contains(&max_lat)
+        || min_lng >= max_lng
+        || min_lat >= max_lat
+    {
+        anyhow::bail!("Bounding box is out of range or empty");
+    }
+    Ok([min_lng, min_lat, max_lng, max_lat])
+}
+
+/// Split a bounding box into tiles of at most [`MAPILLARY_TILE_DEGREES`].
+fn bbox_tiles([min_lng, min_lat, max_lng, max_lat]: [f64; 4]) -> Vec<[f64; 4]> {
+    let mut tiles = Vec::new();
+    let mut lat = min_lat;
+    while lat < max_lat {
+        let next_lat = (lat + MAPILLARY_TILE_DEGREES).min(max_lat);
+        let mut lng = min_lng;
+        while lng < max_lng {
+            let next_lng = (lng + MAPILLARY_TILE_DEGREES).min(max_lng);
+            tiles.push([lng, lat, next_lng, next_lat]);
+            lng = next_lng;
+        }
+        lat = next_lat;
+    }
+    tiles
+}
+
 // =============================================================================
 // Main
 // =============================================================================
@@ -375,22 +582,67 @@ async fn main() -> Result<()> {
     tracing::info!("Connected to database");
 
     match cli.command {
-        Commands::ImportVali { file, map, min_year, max_year, outdoor_only, limit, dry_run } => {
-            import_vali_locations(
-                &pool,
-                &file,
-                &map,
-                min_year,
-                max_year,
-                outdoor_only,
+        Commands::ImportVali {
+            file,
+            map,
+            min_year,
+            max_year,
+            outdoor_only,
+            limit,
+            batch_size,
+            checkpoint,
+            resume,
+            dry_run,
+        } => {
+            let checkpoint = checkpoint.unwrap_or_else(|| ImportCheckpoint::default_path(&file));
+            let options = ValiImportOptions {
+                filters: ValiFilters { min_year, max_year, outdoor_only },
                 limit,
+                batch_size: batch_size.max(1),
+                checkpoint,
+                resume,
                 dry_run,
-            )
-            .await?;
+            };
+            import_vali_locations(&pool, &file, &map, &options).await?;
         }
-        Commands::Import { file, map, google_api_key, skip_validation, limit } => {
-            import_locations(&pool, &file, &map, google_api_key.as_deref(), skip_validation, limit)
-                .await?;
+        Commands::Import {
+            file,
+            map,
+            google_api_key,
+            skip_validation,
+            limit,
+            concurrency,
+            max_retries,
+        } => {
+            let validator = google_api_key
+                .filter(|_| !skip_validation)
+                .map(|key| Validator::new(key, max_retries));
+            let options = ImportOptions {
+                validator,
+                skip_validation,
+                limit,
+                concurrency: concurrency.max(1),
+            };
+            import_locations(&pool, &file, &map, &options).await?;
+        }
+        Commands::ImportMapillary {
+            bbox,
+            map,
+            access_token,
+            include_flat,
+            min_year,
+            min_spacing_m,
+            limit,
+            dry_run,
+        } => {
+            let options =
+                MapillaryImportOptions { include_flat, min_year, min_spacing_m, limit, dry_run };
+            import_mapillary_locations(&pool, &bbox, &map, &access_token, &options).await?;
+        }
+        Commands::Export { map, format, output } => {
+            let output =
+                output.unwrap_or_else(|| PathBuf::from(format!("{}.{}", map, format.extension())));
+            export_map_locations(&pool, &map, format, &output).await?;
         }
         Commands::Generate { count, map } => {
             generate_sample_locations(&pool, count, &map).await?;
@@ -404,6 +656,7 @@ async fn main() -> Result<()> {
                 min_year,
                 max_year,
                 outdoor_only,
+                provider,
                 default,
             } => {
                 create_map(
@@ -415,6 +668,7 @@ async fn main() -> Result<()> {
                     min_year,
                     max_year,
                     outdoor_only,
+                    provider,
                     default,
                 )
                 .await?;
@@ -441,30 +695,118 @@ async fn main() -> Result<()> {
 // Vali Import Command
 // =============================================================================
 
-#[allow(clippy::too_many_arguments)]
-async fn import_vali_locations(
-    pool: &dguesser_db::DbPool,
-    file: &PathBuf,
-    map_slug: &str,
+/// Filters applied to a Vali file before import.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct ValiFilters {
     min_year: Option<i32>,
     max_year: Option<i32>,
     outdoor_only: bool,
+}
+
+struct ValiImportOptions {
+    filters: ValiFilters,
     limit: Option<usize>,
+    batch_size: usize,
+    checkpoint: PathBuf,
+    resume: bool,
     dry_run: bool,
-) -> Result<()> {
-    // Verify map exists
-    let map = dguesser_db::locations::list_maps(pool)
+}
+
+/// Progress of a Vali import, saved after every committed batch so an
+/// interrupted import can continue where it stopped.
+#[derive(Debug, Serialize, Deserialize)]
+struct ImportCheckpoint {
+    /// SHA-256 of the input file; progress in a different file is meaningless
+    content_hash: String,
+    map_id: String,
+    filters: ValiFilters,
+    /// Index of the next filtered location to import
+    next_index: usize,
+    imported: usize,
+    skipped: usize,
+    failed: usize,
+}
+
+impl ImportCheckpoint {
+    fn default_path(file: &Path) -> PathBuf {
+        let mut path = file.as_os_str().to_owned();
+        path.push(".checkpoint.json");
+        PathBuf::from(path)
+    }
+
+    fn load(path: &Path) -> Result<Option<Self>> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => {
+                Ok(Some(serde_json::from_str(&content).map_err(|e| {
+                    anyhow::anyhow!("Invalid checkpoint {}: {}", path.display(), e)
+                })?))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Write the checkpoint through a temporary file so a crash mid-write
+    /// never leaves a truncated checkpoint behind.
+    fn save(&self, path: &Path) -> Result<()> {
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(self)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Check this checkpoint was made by the same import.
+    fn ensure_matches(&self, content_hash: &str, map_id: &str, filters: ValiFilters) -> Result<()> {
+        if self.content_hash != content_hash {
+            anyhow::bail!("Input file changed since the checkpoint was written");
+        }
+        if self.map_id != map_id {
+            anyhow::bail!("Checkpoint belongs to map {}", self.map_id);
+        }
+        if self.filters != filters {
+            anyhow::bail!("Checkpoint was made with different filters ({:?})", self.filters);
+        }
+        Ok(())
+    }
+}
+
+/// Look up a map by slug. The default world map is created if it is missing.
+async fn find_map(
+    pool: &dguesser_db::DbPool,
+    map_slug: &str,
+) -> Result<dguesser_core::location::Map> {
+    if map_slug == dguesser_db::defaults::WORLD_MAP_SLUG
+        && dguesser_db::defaults::ensure_world_map(pool).await?
+    {
+        tracing::info!(slug = map_slug, "Created missing world map");
+    }
+
+    dguesser_db::locations::list_maps(pool)
         .await?
         .into_iter()
         .find(|m| m.slug == map_slug)
-        .ok_or_else(|| anyhow::anyhow!("Map '{}' not found", map_slug))?;
+        .ok_or_else(|| anyhow::anyhow!("Map '{}' not found", map_slug))
+}
+
+async fn import_vali_locations(
+    pool: &dguesser_db::DbPool,
+    file: &Path,
+    map_slug: &str,
+    options: &ValiImportOptions,
+) -> Result<()> {
+    let filters = options.filters;
+
+    let map = find_map(pool, map_slug).await?;
 
     tracing::info!(map_id = %map.id, map_name = %map.name, "Found target map");
 
     // Read Vali locations from file
-    let content = std::fs::read_to_string(file)?;
-    let locations: Vec<ValiLocation> = serde_json::from_str(&content)
+    let content = std::fs::read(file)?;
+    let content_hash = hex::encode(Sha256::digest(&content));
+    let locations: Vec<ValiLocation> = serde_json::from_slice(&content)
         .map_err(|e| anyhow::anyhow!("Failed to parse Vali JSON: {}", e))?;
+    drop(content);
 
     let total_raw = locations.len();
     tracing::info!(total = %total_raw, "Read locations from Vali file");
@@ -472,7 +814,7 @@ async fn import_vali_locations(
     // Filter locations
     let filtered: Vec<_> = locations
         .into_iter()
-        .filter(|loc| !loc.should_filter(min_year, max_year, outdoor_only))
+        .filter(|loc| !loc.should_filter(filters.min_year, filters.max_year, filters.outdoor_only))
         .collect();
 
     let filtered_count = total_raw - filtered.len();
@@ -484,24 +826,60 @@ async fn import_vali_locations(
         );
     }
 
-    let total = limit.map(|l| l.min(filtered.len())).unwrap_or(filtered.len());
+    let total = options.limit.map(|l| l.min(filtered.len())).unwrap_or(filtered.len());
 
-    if dry_run {
+    // Pick up an earlier run's progress
+    let mut checkpoint = match ImportCheckpoint::load(&options.checkpoint)? {
+        Some(checkpoint) if options.resume => {
+            checkpoint.ensure_matches(&content_hash, &map.id, filters)?;
+            tracing::info!(next_index = %checkpoint.next_index, "Resuming from checkpoint");
+            checkpoint
+        }
+        existing => {
+            if options.resume {
+                tracing::warn!(
+                    path = %options.checkpoint.display(),
+                    "No checkpoint found, starting from the beginning"
+                );
+            } else if existing.is_some() && !options.dry_run {
+                tracing::warn!(
+                    path = %options.checkpoint.display(),
+                    "Overwriting existing checkpoint; pass --resume to continue it"
+                );
+            }
+            ImportCheckpoint {
+                content_hash,
+                map_id: map.id.clone(),
+                filters,
+                next_index: 0,
+                imported: 0,
+                skipped: 0,
+                failed: 0,
+            }
+        }
+    };
+    let start = checkpoint.next_index.min(total);
+
+    if options.dry_run {
         println!("\n=== Dry Run Results ===\n");
         println!("  File: {}", file.display());
         println!("  Target map: {} ({})", map.name, map.slug);
         println!("  Total locations in file: {}", total_raw);
         println!("  After filtering: {}", filtered.len());
-        println!("  Would import: {}", total);
-        if let Some(min) = min_year {
+        println!("  Would import: {}", total - start);
+        if start > 0 {
+            println!("  Resuming after: {}", start);
+        }
+        if let Some(min) = filters.min_year {
             println!("  Min year filter: {}", min);
         }
-        if let Some(max) = max_year {
+        if let Some(max) = filters.max_year {
             println!("  Max year filter: {}", max);
         }
-        if outdoor_only {
+        if filters.outdoor_only {
             println!("  Outdoor only: yes");
         }
+        println!("  Batch size: {}", options.batch_size);
         println!();
         return Ok(());
     }
@@ -515,77 +893,106 @@ async fn import_vali_locations(
             )?
             .progress_chars("#>-"),
     );
+    pb.set_position(start as u64);
 
-    let mut imported = 0;
-    let mut skipped = 0;
-    let mut failed = 0;
-
-    for loc in filtered.into_iter().take(total) {
-        pb.inc(1);
-
-        let params = loc.to_create_params();
+    for batch in filtered[start..total].chunks(options.batch_size) {
+        let params: Vec<_> = batch.iter().map(ValiLocation::to_create_params).collect();
 
-        // Try to insert the location
-        match dguesser_db::locations::create_location_full(pool, &params).await {
-            Ok(location) => {
-                // Add to map
-                if let Err(e) =
-                    dguesser_db::locations::add_location_to_map(pool, &map.id, &location.id).await
-                {
-                    tracing::debug!(error = %e, "Failed to add location to map (might already exist)");
-                }
-                imported += 1;
+        match dguesser_db::locations::create_locations_batch(pool, &map.id, &params).await {
+            Ok(result) => {
+                checkpoint.imported += result.inserted;
+                checkpoint.skipped += result.skipped;
             }
             Err(e) => {
-                let err_str = e.to_string();
-                if err_str.contains("duplicate") || err_str.contains("unique") {
-                    // Duplicate panorama_id - skip
-                    skipped += 1;
-                } else {
-                    tracing::debug!(error = %e, "Failed to create location");
-                    failed += 1;
+                // One bad row fails the whole transaction; retry the batch
+                // row by row so only the bad rows are lost
+                tracing::debug!(error = %e, "Batch insert failed, retrying row by row");
+                for params in &params {
+                    match insert_vali_location(pool, &map.id, params).await {
+                        Ok(true) => checkpoint.imported += 1,
+                        Ok(false) => checkpoint.skipped += 1,
+                        Err(e) => {
+                            tracing::debug!(error = %e, "Failed to create location");
+                            checkpoint.failed += 1;
+                        }
+                    }
                 }
             }
         }
+
+        checkpoint.next_index += batch.len();
+        checkpoint.save(&options.checkpoint)?;
+        pb.inc(batch.len() as u64);
     }
 
     pb.finish_with_message("Done");
 
+    // The import is complete; a later run should start over
+    if let Err(e) = std::fs::remove_file(&options.checkpoint)
+        && e.kind() != std::io::ErrorKind::NotFound
+    {
+        tracing::warn!(error = %e, "Failed to remove checkpoint");
+    }
+
     println!("\n=== Import Results ===\n");
-    println!("  Imported: {}", imported);
-    println!("  Skipped (duplicates): {}", skipped);
-    println!("  Failed: {}", failed);
+    println!("  Imported: {}", checkpoint.imported);
+    println!("  Skipped (duplicates): {}", checkpoint.skipped);
+    println!("  Failed: {}", checkpoint.failed);
     println!();
 
     Ok(())
 }
 
+/// Insert a single location and add it to the map. Returns `false` for a
+/// duplicate panorama.
+async fn insert_vali_location(
+    pool: &dguesser_db::DbPool,
+    map_id: &str,
+    params: &CreateLocationParams,
+) -> Result<bool> {
+    match dguesser_db::locations::create_location_full(pool, params).await {
+        Ok(location) => {
+            dguesser_db::locations::add_location_to_map(pool, map_id, &location.id).await?;
+            Ok(true)
+        }
+        Err(e) => {
+            let err_str = e.to_string();
+            if err_str.contains("duplicate") || err_str.contains("unique") {
+                Ok(false)
+            } else {
+                Err(e.into())
+            }
+        }
+    }
+}
+
 // =============================================================================
 // Legacy Import Command
 // =============================================================================
 
+struct ImportOptions {
+    /// Set when locations are validated against the metadata API
+    validator: Option<Validator>,
+    skip_validation: bool,
+    limit: Option<usize>,
+    concurrency: usize,
+}
+
 async fn import_locations(
     pool: &dguesser_db::DbPool,
     file: &PathBuf,
     map_slug: &str,
-    google_api_key: Option<&str>,
-    skip_validation: bool,
-    limit: Option<usize>,
+    options: &ImportOptions,
 ) -> Result<()> {
-    // Verify map exists
-    let map = dguesser_db::locations::list_maps(pool)
-        .await?
-        .into_iter()
-        .find(|m| m.slug == map_slug)
-        .ok_or_else(|| anyhow::anyhow!("Map '{}' not found", map_slug))?;
+    let map = find_map(pool, map_slug).await?;
 
     tracing::info!(map_id = %map.id, map_name = %map.name, "Found target map");
 
     // Read locations from file
     let locations = read_locations_from_file(file)?;
-    let total = limit.map(|l| l.min(locations.len())).unwrap_or(locations.len());
+    let total = options.limit.map(|l| l.min(locations.len())).unwrap_or(locations.len());
 
-    tracing::info!(total = %total, "Read locations from file");
+    tracing::info!(total = %total, concurrency = %options.concurrency, "Read locations from file");
 
     // Setup progress bar
     let pb = ProgressBar::new(total as u64);
@@ -600,42 +1007,49 @@ async fn import_locations(
     let mut imported = 0;
     let mut skipped = 0;
     let mut failed = 0;
+    let mut processed = 0;
+    let mut failures: BTreeMap<String, usize> = BTreeMap::new();
+    let mut aborted = None;
 
-    // Create HTTP client for validation
-    let client = reqwest::Client::new();
+    // Validate concurrently; inserts happen one at a time as results arrive.
+    // Breaking out of the loop drops the stream and cancels pending lookups.
+    let mut resolved = stream::iter(locations.into_iter().take(total))
+        .map(|loc| resolve_location(options, loc))
+        .buffer_unordered(options.concurrency);
 
-    for loc in locations.into_iter().take(total) {
+    while let Some((loc, validation)) = resolved.next().await {
         pb.inc(1);
-
-        // Determine panorama_id
-        let (panorama_id, canonical_lat, canonical_lng) = if skip_validation {
-            // Generate a fake panorama ID if none provided
-            let pano = loc.pano_id.unwrap_or_else(|| format!("fake_{:.6}_{:.6}", loc.lat, loc.lng));
-            (pano, loc.lat, loc.lng)
-        } else if let Some(api_key) = &google_api_key {
-            // Validate with Google API
-            match validate_location(&client, loc.lat, loc.lng, api_key).await {
-                Ok(Some(meta)) => {
-                    let loc = meta.location.unwrap();
-                    (meta.pano_id.unwrap(), loc.lat, loc.lng)
-                }
-                Ok(None) => {
-                    skipped += 1;
-                    continue;
-                }
-                Err(e) => {
-                    tracing::debug!(error = %e, lat = %loc.lat, lng = %loc.lng, "Validation failed");
-                    failed += 1;
-                    continue;
-                }
+        processed += 1;
+
+        let (panorama_id, canonical_lat, canonical_lng) = match validation {
+            Validation::Found { panorama_id, lat, lng } => (panorama_id, lat, lng),
+            Validation::Missing(reason) => {
+                tracing::debug!(
+                    lat = %loc.lat,
+                    lng = %loc.lng,
+                    reason = %reason,
+                    "Skipping location"
+                );
+                skipped += 1;
+                continue;
+            }
+            Validation::Failed(reason) => {
+                tracing::debug!(
+                    lat = %loc.lat,
+                    lng = %loc.lng,
+                    reason = %reason,
+                    "Validation failed"
+                );
+                *failures.entry(reason).or_default() += 1;
+                failed += 1;
+                continue;
+            }
+            Validation::Fatal(reason) => {
+                *failures.entry(reason.clone()).or_default() += 1;
+                failed += 1;
+                aborted = Some(reason);
+                break;
             }
-        } else if let Some(pano) = loc.pano_id {
-            // Use provided panorama ID without validation
-            (pano, loc.lat, loc.lng)
-        } else {
-            tracing::debug!(lat = %loc.lat, lng = %loc.lng, "Skipping location without panorama_id (no API key for validation)");
-            skipped += 1;
-            continue;
         };
 
         // Insert location
@@ -647,7 +1061,7 @@ async fn import_locations(
             loc.country_code.as_deref(),
             loc.subdivision_code.as_deref(),
             None, // capture_date
-            "google_streetview",
+            ImageryProvider::GoogleStreetView,
         )
         .await
         {
@@ -666,16 +1080,46 @@ async fn import_locations(
 
     pb.finish_with_message("Done");
 
-    tracing::info!(
-        imported = %imported,
-        skipped = %skipped,
-        failed = %failed,
-        "Import complete"
-    );
+    println!("\n=== Import Results ===\n");
+    println!("  Imported: {}", imported);
+    println!("  Skipped (no coverage or duplicates): {}", skipped);
+    println!("  Failed: {}", failed);
+    if !failures.is_empty() {
+        println!("\n  Failures by reason:");
+        for (reason, count) in &failures {
+            println!("    {:>8}  {}", count, reason);
+        }
+    }
+    if let Some(reason) = aborted {
+        println!("\n  Stopped early: {}", reason);
+        println!("  Not processed: {}", total - processed);
+    }
+    println!();
 
     Ok(())
 }
 
+/// Work out the panorama to import for a location.
+async fn resolve_location(
+    options: &ImportOptions,
+    loc: InputLocation,
+) -> (InputLocation, Validation) {
+    let validation = if options.skip_validation {
+        // Generate a fake panorama ID if none provided
+        let panorama_id =
+            loc.pano_id.clone().unwrap_or_else(|| format!("fake_{:.6}_{:.6}", loc.lat, loc.lng));
+        Validation::Found { panorama_id, lat: loc.lat, lng: loc.lng }
+    } else if let Some(validator) = &options.validator {
+        validator.validate(loc.lat, loc.lng).await
+    } else if let Some(panorama_id) = loc.pano_id.clone() {
+        // Use provided panorama ID without validation
+        Validation::Found { panorama_id, lat: loc.lat, lng: loc.lng }
+    } else {
+        Validation::Missing("no panorama ID and no API key for validation".to_string())
+    };
+    (loc, validation)
+}
+
 fn read_locations_from_file(file: &PathBuf) -> Result<Vec<InputLocation>> {
     let content = std::fs::read_to_string(file)?;
 
@@ -718,21 +1162,494 @@ fn read_locations_from_file(file: &PathBuf) -> Result<Vec<InputLocation>> {
     }
 }
 
-async fn validate_location(
+// =============================================================================
+// Street View Validation
+// =============================================================================
+
+/// Street View metadata API endpoint
+const STREETVIEW_METADATA_URL: &str = "https://maps.googleapis.com/maps/api/streetview/metadata";
+
+/// Delay before the first retry; doubled for each further retry
+const VALIDATION_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Outcome of validating one location.
+enum Validation {
+    /// Panorama found, with its canonical position
+    Found { panorama_id: String, lat: f64, lng: f64 },
+    /// No coverage; the location is skipped
+    Missing(String),
+    /// Lookup failed for this location
+    Failed(String),
+    /// Lookup failed in a way that will fail every other lookup too
+    Fatal(String),
+}
+
+/// Result of one metadata request.
+enum Attempt {
+    Done(Validation),
+    /// Transient failure worth retrying. `rate_limited` pauses every lookup,
+    /// not just this one.
+    Retry {
+        reason: String,
+        rate_limited: bool,
+    },
+}
+
+/// Validates locations against the Street View metadata API. Shared by all
+/// concurrent lookups so rate limiting slows the whole pipeline down.
+struct Validator {
+    client: reqwest::Client,
+    api_key: String,
+    max_retries: u32,
+    /// Pauses all lookups after the API reports `OVER_QUERY_LIMIT`
+    quota: std::sync::Mutex<QuotaBreaker>,
+}
+
+impl Validator {
+    fn new(api_key: String, max_retries: u32) -> Self {
+        let client =
+            reqwest::Client::builder().timeout(Duration::from_secs(10)).build().unwrap_or_default();
+        Self { client, api_key, max_retries, quota: std::sync::Mutex::default() }
+    }
+
+    /// Find the outdoor panorama nearest to a location, retrying transient
+    /// errors with exponential backoff.
+    async fn validate(&self, lat: f64, lng: f64) -> Validation {
+        let mut retries = 0;
+        loop {
+            self.wait_for_quota().await;
+
+            let (reason, rate_limited) = match self.request(lat, lng).await {
+                Attempt::Done(validation) => {
+                    if matches!(validation, Validation::Found { .. } | Validation::Missing(_)) {
+                        self.quota.lock().unwrap().record_success();
+                    }
+                    return validation;
+                }
+                Attempt::Retry { reason, rate_limited } => (reason, rate_limited),
+            };
+
+            if retries >= self.max_retries {
+                // Still rate limited after backing off: the quota is spent
+                return if rate_limited {
+                    Validation::Fatal(format!("{reason} (quota exhausted)"))
+                } else {
+                    Validation::Failed(reason)
+                };
+            }
+
+            if rate_limited {
+                let until = self.quota.lock().unwrap().trip(Utc::now());
+                tracing::debug!(paused_until = %until, "Street View quota exhausted, pausing lookups");
+            } else {
+                let jitter = Duration::from_millis(rand::random_range(0..250));
+                tokio::time::sleep(VALIDATION_BACKOFF * 2u32.pow(retries) + jitter).await;
+            }
+            retries += 1;
+        }
+    }
+
+    async fn request(&self, lat: f64, lng: f64) -> Attempt {
+        let location = format!("{lat},{lng}");
+        let params =
+            [("location", location.as_str()), ("source", "outdoor"), ("key", &self.api_key)];
+        let url = match reqwest::Url::parse_with_params(STREETVIEW_METADATA_URL, params) {
+            Ok(url) => url,
+            Err(e) => return Attempt::Done(Validation::Failed(e.to_string())),
+        };
+
+        let response = match self.client.get(url).send().await {
+            Ok(response) => response,
+            Err(e) => {
+                let reason = if e.is_timeout() { "timeout" } else { "connection error" };
+                return Attempt::Retry { reason: reason.to_string(), rate_limited: false };
+            }
+        };
+
+        let status = response.status();
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Attempt::Retry { reason: format!("HTTP {status}"), rate_limited: true };
+        }
+        if status.is_server_error() {
+            return Attempt::Retry { reason: format!("HTTP {status}"), rate_limited: false };
+        }
+
+        let meta: StreetViewMetadata = match response.json().await {
+            Ok(meta) => meta,
+            Err(_) => {
+                return Attempt::Done(Validation::Failed(format!(
+                    "invalid response (HTTP {status})"
+                )));
+            }
+        };
+
+        Attempt::Done(match meta.status.as_str() {
+            "OK" => match (meta.pano_id, meta.location) {
+                (Some(panorama_id), Some(location)) => {
+                    Validation::Found { panorama_id, lat: location.lat, lng: location.lng }
+                }
+                _ => Validation::Failed("incomplete metadata".to_string()),
+            },
+            "ZERO_RESULTS" | "NOT_FOUND" => Validation::Missing(meta.status),
+            OVER_QUERY_LIMIT => {
+                return Attempt::Retry { reason: meta.status, rate_limited: true };
+            }
+            "UNKNOWN_ERROR" => {
+                return Attempt::Retry { reason: meta.status, rate_limited: false };
+            }
+            "REQUEST_DENIED" => Validation::Fatal(format!("{} (check the API key)", meta.status)),
+            _ => Validation::Failed(meta.status),
+        })
+    }
+
+    async fn wait_for_quota(&self) {
+        let now = Utc::now();
+        let state = self.quota.lock().unwrap().state(now);
+        if let QuotaState::Open { until } = state {
+            tokio::time::sleep((until - now).to_std().unwrap_or_default()).await;
+        }
+    }
+}
+
+// =============================================================================
+// Mapillary Import Command
+// =============================================================================
+
+/// Filters and limits for a Mapillary import.
+struct MapillaryImportOptions {
+    include_flat: bool,
+    min_year: Option<i32>,
+    min_spacing_m: f64,
+    limit: usize,
+    dry_run: bool,
+}
+
+async fn import_mapillary_locations(
+    pool: &dguesser_db::DbPool,
+    bbox: &str,
+    map_slug: &str,
+    access_token: &str,
+    options: &MapillaryImportOptions,
+) -> Result<()> {
+    let tiles = bbox_tiles(parse_bbox(bbox)?);
+
+    let map = find_map(pool, map_slug).await?;
+
+    tracing::info!(map_id = %map.id, map_name = %map.name, tiles = %tiles.len(), "Searching Mapillary");
+
+    let pb = ProgressBar::new(tiles.len() as u64);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template(
+                "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} tiles ({eta})",
+            )?
+            .progress_chars("#>-"),
+    );
+
+    let client = reqwest::Client::new();
+    let mut selected: Vec<MapillaryImage> = Vec::new();
+    let mut fetched = 0;
+
+    for tile in tiles {
+        pb.inc(1);
+        if selected.len() >= options.limit {
+            break;
+        }
+
+        let images = match fetch_mapillary_tile(&client, access_token, tile, options).await {
+            Ok(images) => images,
+            Err(e) => {
+                tracing::warn!(error = %e, ?tile, "Failed to fetch Mapillary tile");
+                continue;
+            }
+        };
+        fetched += images.len();
+
+        for image in images {
+            if selected.len() >= options.limit {
+                break;
+            }
+            if ImageryProvider::Mapillary.validate_image_id(Some(&image.id)).is_err() {
+                continue;
+            }
+            if let Some(min) = options.min_year
+                && image.capture_date().is_none_or(|date| date.year() < min)
+            {
+                continue;
+            }
+
+            // Sequences are dense; keep images spread out
+            let (lat, lng) = image.position();
+            let too_close = selected.iter().any(|other| {
+                let (other_lat, other_lng) = other.position();
+                haversine_distance(lat, lng, other_lat, other_lng) < options.min_spacing_m
+            });
+            if !too_close {
+                selected.push(image);
+            }
+        }
+    }
+
+    pb.finish_with_message("Done");
+
+    if options.dry_run {
+        println!("\n=== Dry Run Results ===\n");
+        println!("  Bounding box: {}", bbox);
+        println!("  Target map: {} ({})", map.name, map.slug);
+        println!("  Images fetched: {}", fetched);
+        println!("  Would import: {}", selected.len());
+        println!();
+        return Ok(());
+    }
+
+    let mut imported = 0;
+    let mut skipped = 0;
+    let mut failed = 0;
+
+    for image in &selected {
+        match dguesser_db::locations::create_location_full(pool, &image.to_create_params()).await {
+            Ok(location) => {
+                dguesser_db::locations::add_location_to_map(pool, &map.id, &location.id).await?;
+                imported += 1;
+            }
+            Err(e) => {
+                let err_str = e.to_string();
+                if err_str.contains("duplicate") || err_str.contains("unique") {
+                    skipped += 1;
+                } else {
+                    tracing::debug!(error = %e, image_id = %image.id, "Failed to create location");
+                    failed += 1;
+                }
+            }
+        }
+    }
+
+    println!("\n=== Import Results ===\n");
+    println!("  Images fetched: {}", fetched);
+    println!("  Imported: {}", imported);
+    println!("  Skipped (duplicates): {}", skipped);
+    println!("  Failed: {}", failed);
+    println!();
+
+    Ok(())
+}
+
+/// Fetch the images inside one tile.
+async fn fetch_mapillary_tile(
     client: &reqwest::Client,
+    access_token: &str,
+    [min_lng, min_lat, max_lng, max_lat]: [f64; 4],
+    options: &MapillaryImportOptions,
+) -> Result<Vec<MapillaryImage>> {
+    let bbox = format!("{min_lng},{min_lat},{max_lng},{max_lat}");
+    let limit = MAPILLARY_TILE_LIMIT.to_string();
+    let mut query = vec![
+        ("access_token", access_token),
+        ("fields", "id,geometry,computed_geometry,captured_at,compass_angle"),
+        ("bbox", bbox.as_str()),
+        ("limit", limit.as_str()),
+    ];
+    if !options.include_flat {
+        query.push(("is_pano", "true"));
+    }
+
+    let url = reqwest::Url::parse_with_params(MAPILLARY_IMAGES_URL, &query)?;
+    let response = client.get(url).send().await?;
+    let status = response.status();
+    if !status.is_success() {
+        anyhow::bail!("Mapillary API returned {}", status);
+    }
+    let images: MapillaryImages = response.json().await?;
+    Ok(images.data)
+}
+
+// =============================================================================
+// Export Command
+// =============================================================================
+
+/// Locations read from the database per page while exporting
+const EXPORT_PAGE_SIZE: i64 = 1000;
+
+/// File format for `seeder export`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum ExportFormat {
+    /// JSON array with every location column
+    Json,
+    /// CSV with every location column
+    Csv,
+    /// Vali JSON, readable by `import-vali` and `pack-builder build`
+    Vali,
+}
+
+impl ExportFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Json | Self::Vali => "json",
+            Self::Csv => "csv",
+        }
+    }
+}
+
+/// One exported location in the JSON and CSV formats. The column names match
+/// the `locations` table.
+#[derive(Debug, Serialize)]
+struct ExportedLocation<'a> {
+    id: &'a str,
+    panorama_id: &'a str,
     lat: f64,
     lng: f64,
-    api_key: &str,
-) -> Result<Option<StreetViewMetadata>> {
-    let url = format!(
-        "https://maps.googleapis.com/maps/api/streetview/metadata?location={},{}&source=outdoor&key={}",
-        lat, lng, api_key
-    );
+    country_code: Option<&'a str>,
+    subdivision_code: Option<&'a str>,
+    capture_date: Option<NaiveDate>,
+    provider: &'static str,
+    source: String,
+    surface: Option<&'a str>,
+    arrow_count: Option<i32>,
+    is_scout: bool,
+    buildings_100: Option<i32>,
+    roads_100: Option<i32>,
+    elevation: Option<i32>,
+    heading: Option<f64>,
+}
+
+impl<'a> From<&'a Location> for ExportedLocation<'a> {
+    fn from(loc: &'a Location) -> Self {
+        Self {
+            id: &loc.id,
+            panorama_id: &loc.panorama_id,
+            lat: loc.lat,
+            lng: loc.lng,
+            country_code: loc.country_code.as_deref(),
+            subdivision_code: loc.subdivision_code.as_deref(),
+            capture_date: loc.capture_date,
+            provider: loc.provider.as_str(),
+            source: loc.source.to_string(),
+            surface: loc.surface.as_deref(),
+            arrow_count: loc.arrow_count,
+            is_scout: loc.is_scout,
+            buildings_100: loc.buildings_100,
+            roads_100: loc.roads_100,
+            elevation: loc.elevation,
+            heading: loc.heading,
+        }
+    }
+}
+
+impl From<&Location> for ValiLocation {
+    fn from(loc: &Location) -> Self {
+        Self {
+            lat: loc.lat,
+            lng: loc.lng,
+            pano_id: Some(loc.panorama_id.clone()),
+            heading: loc.heading,
+            pitch: None,
+            zoom: None,
+            country_code: loc.country_code.clone(),
+            subdivision_code: loc.subdivision_code.clone(),
+            year: loc.capture_date.map(|d| d.year()),
+            month: loc.capture_date.map(|d| d.month() as i32),
+            surface: loc.surface.clone(),
+            arrow_count: loc.arrow_count,
+            is_scout: Some(loc.is_scout),
+            buildings100: loc.buildings_100,
+            roads100: loc.roads_100,
+            elevation: loc.elevation,
+            tags: (!loc.tags.is_empty()).then(|| loc.tags.clone()),
+        }
+    }
+}
+
+/// Writes locations one at a time in an export format.
+enum ExportWriter<W: Write> {
+    Json { out: W, format: ExportFormat, written: usize },
+    Csv(Box<csv::Writer<W>>),
+}
+
+impl<W: Write> ExportWriter<W> {
+    fn new(mut out: W, format: ExportFormat) -> Result<Self> {
+        Ok(match format {
+            ExportFormat::Json | ExportFormat::Vali => {
+                out.write_all(b"[")?;
+                Self::Json { out, format, written: 0 }
+            }
+            ExportFormat::Csv => Self::Csv(Box::new(csv::Writer::from_writer(out))),
+        })
+    }
 
-    let response = client.get(&url).send().await?;
-    let meta: StreetViewMetadata = response.json().await?;
+    fn write(&mut self, loc: &Location) -> Result<()> {
+        match self {
+            Self::Json { out, format, written } => {
+                out.write_all(if *written == 0 { b"\n  " } else { b",\n  " })?;
+                if *format == ExportFormat::Vali {
+                    serde_json::to_writer(&mut *out, &ValiLocation::from(loc))?;
+                } else {
+                    serde_json::to_writer(&mut *out, &ExportedLocation::from(loc))?;
+                }
+                *written += 1;
+            }
+            Self::Csv(writer) => writer.serialize(ExportedLocation::from(loc))?,
+        }
+        Ok(())
+    }
 
-    if meta.status == "OK" { Ok(Some(meta)) } else { Ok(None) }
+    fn finish(self) -> Result<()> {
+        match self {
+            Self::Json { mut out, .. } => {
+                out.write_all(b"\n]\n")?;
+                out.flush()?;
+            }
+            Self::Csv(mut writer) => writer.flush()?,
+        }
+        Ok(())
+    }
+}
+
+/// Export a map's locations, reading them page by page so memory use does
+/// not grow with the map.
+async fn export_map_locations(
+    pool: &dguesser_db::DbPool,
+    map_slug: &str,
+    format: ExportFormat,
+    output: &Path,
+) -> Result<()> {
+    let map = find_map(pool, map_slug).await?;
+
+    tracing::info!(map_id = %map.id, map_name = %map.name, "Exporting map");
+
+    let file = std::fs::File::create(output)?;
+    let mut writer = ExportWriter::new(std::io::BufWriter::new(file), format)?;
+
+    let pb = ProgressBar::new_spinner();
+    pb.set_style(ProgressStyle::default_spinner().template("{spinner:.green} {pos} locations")?);
+
+    let mut after: Option<String> = None;
+    loop {
+        let page = dguesser_db::locations::get_map_locations_after(
+            pool,
+            &map,
+            after.as_deref(),
+            EXPORT_PAGE_SIZE,
+        )
+        .await?;
+
+        for loc in &page {
+            writer.write(loc)?;
+        }
+        pb.inc(page.len() as u64);
+
+        // A short page is the last one
+        match page.last() {
+            Some(last) if page.len() as i64 == EXPORT_PAGE_SIZE => after = Some(last.id.clone()),
+            _ => break,
+        }
+    }
+
+    writer.finish()?;
+    pb.finish_and_clear();
+
+    println!("\nExported {} locations from {} to {}\n", pb.position(), map.slug, output.display());
+
+    Ok(())
 }
 
 // =============================================================================
@@ -746,12 +1663,7 @@ async fn generate_sample_locations(
 ) -> Result<()> {
     use rand::RngExt;
 
-    // Verify map exists
-    let map = dguesser_db::locations::list_maps(pool)
-        .await?
-        .into_iter()
-        .find(|m| m.slug == map_slug)
-        .ok_or_else(|| anyhow::anyhow!("Map '{}' not found", map_slug))?;
+    let map = find_map(pool, map_slug).await?;
 
     tracing::info!(map_id = %map.id, map_name = %map.name, count = %count, "Generating sample locations");
 
@@ -783,7 +1695,7 @@ async fn generate_sample_locations(
             None,
             None,
             None,
-            "sample",
+            ImageryProvider::Sample,
         )
         .await
         {
@@ -878,6 +1790,7 @@ async fn create_map(
     min_year: Option<i32>,
     max_year: Option<i32>,
     outdoor_only: bool,
+    provider: Option<ImageryProvider>,
     is_default: bool,
 ) -> Result<()> {
     // Parse countries list
@@ -890,6 +1803,7 @@ async fn create_map(
         min_year,
         max_year,
         outdoor_only,
+        provider,
         ..Default::default()
     };
 
@@ -915,6 +1829,9 @@ async fn create_map(
     if map.rules.outdoor_only {
         println!("  Outdoor Only: yes");
     }
+    if let Some(provider) = map.rules.provider {
+        println!("  Provider: {}", provider.display_name());
+    }
     if map.is_default {
         println!("  Default: yes");
     }
@@ -1,4 +1,5 @@
 import { api } from './client';
+import type { ReportCategory, ReportStatus } from './reports';
 
 // =============================================================================
 // Types
@@ -14,6 +15,67 @@ export interface AdminStats {
   by_review_status: Record<string, number>;
 }
 
+export interface HealthCheckRun {
+  id: string;
+  started_at: string;
+  finished_at: string;
+  checked: number;
+  healthy: number;
+  dead: number;
+  errors: number;
+  /** Inactive locations proposed for re-approval */
+  revived: number;
+}
+
+export interface HealthTrendPoint {
+  date: string;
+  runs: number;
+  checked: number;
+  healthy: number;
+  dead: number;
+  errors: number;
+  revived: number;
+}
+
+export interface LocationHealth {
+  last_run: HealthCheckRun | null;
+  daily: HealthTrendPoint[];
+}
+
+export interface PackCacheStats {
+  enabled: boolean;
+  block_hits: number;
+  block_misses: number;
+  redis_hits: number;
+  index_hits: number;
+  index_misses: number;
+  hit_rate: number;
+  blocks: number;
+  bytes: number;
+}
+
+export interface StreetViewQuota {
+  configured: boolean;
+  state: 'closed' | 'open' | 'half_open';
+  paused_until: string | null;
+  consecutive_trips: number;
+  last_tripped_at: string | null;
+  requests_today: number | null;
+}
+
+export interface MapTileUsageEntry {
+  user_id: string;
+  display_name: string;
+  map_type: 'streetview' | 'roadmap' | 'satellite';
+  tiles: number;
+}
+
+export interface MapTileUsage {
+  date: string;
+  total_tiles: number;
+  users: MapTileUsageEntry[];
+}
+
 export interface ReviewQueueItem {
   id: string;
   panorama_id: string;
@@ -25,6 +87,61 @@ export interface ReviewQueueItem {
   last_report_reason: string | null;
   review_status: string;
   created_at: string;
+  /** Internal notes, newest first */
+  notes: LocationNote[];
+  /** Reviewer with an active claim */
+  claimed_by: string | null;
+  claimed_by_name: string | null;
+  claim_expires_at: string | null;
+}
+
+export interface ReapprovalItem {
+  location_id: string;
+  panorama_id: string;
+  lat: number;
+  lng: number;
+  country_code: string | null;
+  /** Review status when the panorama was found alive again */
+  previous_status: string | null;
+  /** Why the location was deactivated */
+  previous_reason: string | null;
+  proposed_at: string;
+}
+
+export interface ReapprovalQueueResponse {
+  locations: ReapprovalItem[];
+  total: number;
+  page: number;
+  per_page: number;
+  total_pages: number;
+}
+
+export type ReviewAssignment = 'mine' | 'unclaimed' | 'all';
+
+export interface ClaimReviewBatchResponse {
+  /** Newly claimed location IDs, in queue order */
+  location_ids: string[];
+  /** Locations now claimed, including earlier claims */
+  active_claims: number;
+  expires_at: string | null;
+}
+
+export interface ReviewerStats {
+  reviewer_id: string;
+  reviewer_name: string | null;
+  reviewed: number;
+  approved: number;
+  rejected: number;
+  flagged: number;
+  /** Claimed and not yet decided */
+  in_progress: number;
+  /** Mean seconds from claim to decision */
+  avg_review_secs: number | null;
+}
+
+export interface ReviewerStatsResponse {
+  days: number;
+  reviewers: ReviewerStats[];
 }
 
 export interface ReviewQueueResponse {
@@ -63,6 +180,10 @@ export interface LocationDetail {
   roads_100: number | null;
   elevation: number | null;
   heading: number | null;
+  /** 0-100 rating from past guesses; null until rated */
+  difficulty: number | null;
+  median_guess_distance_meters: number | null;
+  median_guess_score: number | null;
   failure_count: number;
   last_failure_reason: string | null;
   review_status: string;
@@ -70,6 +191,36 @@ export interface LocationDetail {
   reviewed_by: string | null;
   created_at: string;
   reports: LocationReport[];
+  /** Internal notes, newest first */
+  notes: LocationNote[];
+}
+
+/** Internal note on a location by an admin or a map curator; never shown to players */
+export interface LocationNote {
+  id: number;
+  location_id: string;
+  author_id: string | null;
+  author_name: string | null;
+  /** Map a curator wrote the note from; null for admin notes */
+  map_id: string | null;
+  body: string;
+  tags: string[];
+  created_at: string;
+}
+
+export interface CreateLocationNoteRequest {
+  /** 1-2000 characters */
+  body: string;
+  /** Max 10; normalized to lowercase with spaces replaced by `-` */
+  tags?: string[];
+}
+
+export interface LocationNotesResponse {
+  notes: LocationNote[];
+  total: number;
+  page: number;
+  per_page: number;
+  total_pages: number;
 }
 
 export interface ReportWithLocation {
@@ -102,6 +253,335 @@ export interface UpdateReviewStatusResponse {
 
 export type ReviewStatus = 'approved' | 'rejected' | 'flagged' | 'pending';
 
+export interface ModerateProfileRequest {
+  reset_avatar?: boolean;
+  reset_bio?: boolean;
+  reset_display_name?: boolean;
+  reset_country?: boolean;
+  reset_color?: boolean;
+  /** Lock (true) or unlock (false) the profile; unchanged when absent */
+  locked?: boolean;
+}
+
+export interface ModerateProfileResponse {
+  message: string;
+  user_id: string;
+  locked: boolean;
+}
+
+export interface ForceRenameRequest {
+  /** New display name; a generic "Player XXXX" name when absent */
+  display_name?: string;
+  /** Lock (true) or unlock (false) the profile; unchanged when absent */
+  locked?: boolean;
+}
+
+export interface ForceRenameResponse {
+  user_id: string;
+  display_name: string;
+  locked: boolean;
+}
+
+export type NameFlagReason = 'impersonation' | 'link' | 'confusables' | 'reported';
+
+export interface NameFlag {
+  id: string;
+  user_id: string;
+  /** The name that was flagged */
+  display_name: string;
+  /** The user's display name now */
+  current_display_name: string;
+  reason: NameFlagReason;
+  status: 'pending' | 'dismissed' | 'renamed';
+  created_at: string;
+}
+
+export interface NameFlagsResponse {
+  flags: NameFlag[];
+  total: number;
+  page: number;
+  per_page: number;
+  total_pages: number;
+}
+
+export interface ChatTranscriptMessage {
+  id: number;
+  user_id: string;
+  display_name: string;
+  /** Text as other players saw it */
+  content: string;
+  /** Text before the chat filter masked it, if it did */
+  original_content: string | null;
+  sent_at: string;
+}
+
+export interface GameChatTranscript {
+  game_id: string;
+  party_id: string | null;
+  messages: ChatTranscriptMessage[];
+}
+
+export interface LiveGame {
+  game_id: string;
+  status: string;
+  round_number: number;
+  total_rounds: number;
+  players: number;
+  connected_players: number;
+  round_ends_at: string | null;
+  paused: boolean;
+}
+
+export interface LivePlayerState {
+  user_id: string;
+  display_name: string;
+  is_host: boolean;
+  total_score: number;
+  connected: boolean;
+  disconnected_at: string | null;
+  /** Median of recent socket round-trip times */
+  rtt_ms: number | null;
+  rtt_samples: number[];
+}
+
+export interface LiveRoundState {
+  round_id: string;
+  round_number: number;
+  location_id: string | null;
+  started_at: string | null;
+  time_limit_ms: number | null;
+  ends_at: string | null;
+  /** Players who have guessed */
+  guessed: string[];
+}
+
+export interface LiveGameState {
+  game_id: string;
+  status: string;
+  round_number: number;
+  total_rounds: number;
+  settings: Record<string, unknown>;
+  players: LivePlayerState[];
+  current_round: LiveRoundState | null;
+  between_rounds_ends_at: string | null;
+  time_budget_ends_at: string | null;
+  paused_at: string | null;
+  teacher_paused: boolean;
+  skip_votes: string[];
+  rerolls_used: number;
+}
+
+export interface ReportedPlayerSignals {
+  /** Open reports against the player, this one included */
+  open_reports: number;
+  total_reports: number;
+  cheat_signals: number;
+  /** Whether the player's display name is waiting for review */
+  name_flagged: boolean;
+}
+
+export interface PlayerReportItem {
+  id: string;
+  reporter_id: string | null;
+  reporter_name: string | null;
+  reported_user_id: string;
+  reported_name: string;
+  category: ReportCategory;
+  game_id: string | null;
+  chat_excerpt: string | null;
+  notes: string | null;
+  status: ReportStatus;
+  resolution_note: string | null;
+  reviewed_by: string | null;
+  reviewed_at: string | null;
+  created_at: string;
+  signals: ReportedPlayerSignals;
+}
+
+export interface PlayerReportsResponse {
+  reports: PlayerReportItem[];
+  total: number;
+  page: number;
+  per_page: number;
+  total_pages: number;
+}
+
+export interface UpdatePlayerReportResponse {
+  id: string;
+  status: ReportStatus;
+  reviewed_at: string | null;
+}
+
+export interface StartImpersonationRequest {
+  reason: string;
+  /** Only allow reads (default true) */
+  read_only?: boolean;
+  /** Minutes until the session expires (5-60, default 15) */
+  minutes?: number;
+}
+
+export interface ImpersonationResponse {
+  user_id: string;
+  read_only: boolean;
+  expires_at: string;
+}
+
+export interface AuditLogItem {
+  id: number;
+  actor_id: string | null;
+  action: string;
+  target_user_id: string | null;
+  details: Record<string, unknown>;
+  created_at: string;
+}
+
+export interface AuditLogResponse {
+  entries: AuditLogItem[];
+  page: number;
+  per_page: number;
+}
+
+/** Operational settings that can be changed without a restart */
+export interface RuntimeSettings {
+  rate_limit_guest_multiplier?: number | null;
+  rate_limit_registered_multiplier?: number | null;
+  rate_limit_admin_multiplier?: number | null;
+  extra_cors_origins?: string[];
+  cache_ttls?: Record<string, { fresh_secs: number; stale_secs: number }>;
+  party_host_grace_secs?: number | null;
+  reconnect_grace_secs?: number | null;
+  socket_rate_limit_multiplier?: number | null;
+}
+
+export interface ReloadConfigResponse {
+  config: {
+    version: number;
+    settings: RuntimeSettings;
+    updated_by: string | null;
+    updated_at: string | null;
+  };
+  changed: string[];
+}
+
+/** Location selection rules of a system map */
+export interface SystemMapRules {
+  countries?: string[];
+  min_year?: number | null;
+  max_year?: number | null;
+  outdoor_only?: boolean;
+  min_spread_distance_km?: number | null;
+  provider?: string;
+}
+
+export interface SystemMap {
+  id: string;
+  slug: string;
+  name: string;
+  description: string | null;
+  rules: SystemMapRules;
+  is_default: boolean;
+  active: boolean;
+  location_count: number;
+  play_count: number;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface CreateSystemMapRequest {
+  slug: string;
+  name: string;
+  description?: string;
+  rules?: SystemMapRules;
+  is_default?: boolean;
+}
+
+export interface UpdateSystemMapRequest {
+  name?: string;
+  /** An empty string removes the description */
+  description?: string;
+  rules?: SystemMapRules;
+}
+
+export interface ScheduleFeaturedMapRequest {
+  map_id: string;
+  title?: string;
+  /** ISO 8601; a past time starts the feature right away */
+  starts_at: string;
+  ends_at: string;
+}
+
+export interface ScheduledFeature {
+  id: number;
+  map_id: string;
+  map_slug: string;
+  map_name: string;
+  title: string | null;
+  starts_at: string;
+  ends_at: string;
+  created_by: string | null;
+  activated_at: string | null;
+  finished_at: string | null;
+}
+
+export interface ModeGameStats {
+  mode: string;
+  created: number;
+  finished: number;
+  avg_duration_secs: number | null;
+}
+
+export interface DailyActivityPoint {
+  date: string;
+  active_users: number;
+  new_users: number;
+  guesses: number;
+  games: ModeGameStats[];
+}
+
+export interface ActivityAnalytics {
+  daily: DailyActivityPoint[];
+  totals_by_mode: ModeGameStats[];
+  refreshed_at: string | null;
+}
+
+export interface Percentiles {
+  p10: number;
+  p25: number;
+  p50: number;
+  p75: number;
+  p90: number;
+}
+
+export interface GuessDistributionPoint {
+  date: string;
+  guesses: number;
+  score: Percentiles | null;
+  distance_km: Percentiles | null;
+}
+
+export interface RetentionCohort {
+  cohort_week: string;
+  size: number;
+  retained_users: number[];
+  retention: number[];
+}
+
+export interface CountryGuessStats {
+  country_code: string;
+  guesses: number;
+  avg_score: number;
+  avg_distance_km: number;
+}
+
+export interface ContinentGuessStats {
+  /** Continent identifier, e.g. "europe"; "unknown" for unrecognized codes */
+  continent: string;
+  guesses: number;
+  avg_score: number;
+  avg_distance_km: number;
+  countries: CountryGuessStats[];
+}
+
 // =============================================================================
 // API Client
 // =============================================================================
@@ -112,27 +592,115 @@ export const adminApi = {
     return api.get<AdminStats>('/admin/stats');
   },
 
+  /** Get location coverage health trends */
+  async getLocationHealth(days?: number): Promise<LocationHealth> {
+    const path = days ? `/admin/locations/health?days=${days}` : '/admin/locations/health';
+    return api.get<LocationHealth>(path);
+  },
+
+  /** Get pack range cache counters for the API instance serving the request */
+  async getPackCacheStats(): Promise<PackCacheStats> {
+    return api.get<PackCacheStats>('/admin/locations/cache');
+  },
+
+  /** Get the Street View metadata API quota status */
+  async getStreetViewQuota(): Promise<StreetViewQuota> {
+    return api.get<StreetViewQuota>('/admin/streetview/quota');
+  },
+
+  /** Get map tiles served per user on a day (YYYY-MM-DD, default today) */
+  async getMapTileUsage(date?: string): Promise<MapTileUsage> {
+    const query = date ? `?date=${encodeURIComponent(date)}` : '';
+    return api.get<MapTileUsage>(`/admin/streetview/usage${query}`);
+  },
+
   /** Get paginated review queue */
   async getReviewQueue(params?: {
     page?: number;
     per_page?: number;
     status?: string;
+    assignment?: ReviewAssignment;
   }): Promise<ReviewQueueResponse> {
     const searchParams = new URLSearchParams();
     if (params?.page) searchParams.set('page', String(params.page));
     if (params?.per_page) searchParams.set('per_page', String(params.per_page));
     if (params?.status) searchParams.set('status', params.status);
+    if (params?.assignment) searchParams.set('assignment', params.assignment);
 
     const query = searchParams.toString();
     const path = query ? `/admin/locations/review-queue?${query}` : '/admin/locations/review-queue';
     return api.get<ReviewQueueResponse>(path);
   },
 
+  /** Claim a batch of unclaimed locations from the front of the queue for 30 minutes */
+  async claimReviewBatch(count?: number): Promise<ClaimReviewBatchResponse> {
+    return api.post<ClaimReviewBatchResponse>('/admin/locations/review-queue/claim', { count });
+  },
+
+  /** Release all of your review claims */
+  async releaseReviewClaims(): Promise<{ released: number }> {
+    return api.delete<{ released: number }>('/admin/locations/review-queue/claims');
+  },
+
+  /** Get inactive locations the health checker found alive again */
+  async getReapprovals(params?: { page?: number; per_page?: number }): Promise<ReapprovalQueueResponse> {
+    const searchParams = new URLSearchParams();
+    if (params?.page) searchParams.set('page', String(params.page));
+    if (params?.per_page) searchParams.set('per_page', String(params.per_page));
+
+    const query = searchParams.toString();
+    return api.get<ReapprovalQueueResponse>(
+      query ? `/admin/locations/reapprovals?${query}` : '/admin/locations/reapprovals'
+    );
+  },
+
+  /** Accept (reactivate and approve) or dismiss a re-approval proposal */
+  async decideReapproval(locationId: string, accept: boolean): Promise<void> {
+    return api.post<void>(`/admin/locations/reapprovals/${locationId}`, { accept });
+  },
+
+  /** Get per-reviewer throughput */
+  async getReviewerStats(days?: number): Promise<ReviewerStatsResponse> {
+    const query = days ? `?days=${days}` : '';
+    return api.get<ReviewerStatsResponse>(`/admin/locations/review-stats${query}`);
+  },
+
   /** Get detailed location information */
   async getLocationDetail(locationId: string): Promise<LocationDetail> {
     return api.get<LocationDetail>(`/admin/locations/${locationId}`);
   },
 
+  /** Add an internal note to a location */
+  async createLocationNote(locationId: string, request: CreateLocationNoteRequest): Promise<LocationNote> {
+    return api.post<LocationNote>(`/admin/locations/${locationId}/notes`, request);
+  },
+
+  /** Search location notes by text, tag, location or author, newest first */
+  async searchLocationNotes(params?: {
+    q?: string;
+    tag?: string;
+    location_id?: string;
+    author_id?: string;
+    page?: number;
+    per_page?: number;
+  }): Promise<LocationNotesResponse> {
+    const searchParams = new URLSearchParams();
+    if (params?.q) searchParams.set('q', params.q);
+    if (params?.tag) searchParams.set('tag', params.tag);
+    if (params?.location_id) searchParams.set('location_id', params.location_id);
+    if (params?.author_id) searchParams.set('author_id', params.author_id);
+    if (params?.page) searchParams.set('page', String(params.page));
+    if (params?.per_page) searchParams.set('per_page', String(params.per_page));
+
+    const query = searchParams.toString();
+    return api.get<LocationNotesResponse>(query ? `/admin/location-notes?${query}` : '/admin/location-notes');
+  },
+
+  /** Delete a location note */
+  async deleteLocationNote(noteId: number): Promise<void> {
+    return api.delete<void>(`/admin/location-notes/${noteId}`);
+  },
+
   /** Update location review status */
   async updateReviewStatus(
     locationId: string,
@@ -145,6 +713,107 @@ export const adminApi = {
     });
   },
 
+  /** Reset parts of a user's profile and lock or unlock it */
+  async moderateProfile(
+    userId: string,
+    request: ModerateProfileRequest
+  ): Promise<ModerateProfileResponse> {
+    return api.post<ModerateProfileResponse>(`/admin/users/${userId}/profile/moderate`, request);
+  },
+
+  /** Get player reports, oldest first */
+  async getPlayerReports(params?: {
+    status?: ReportStatus;
+    category?: ReportCategory;
+    user_id?: string;
+    page?: number;
+    per_page?: number;
+  }): Promise<PlayerReportsResponse> {
+    const query = new URLSearchParams();
+    if (params?.status) query.set('status', params.status);
+    if (params?.category) query.set('category', params.category);
+    if (params?.user_id) query.set('user_id', params.user_id);
+    if (params?.page) query.set('page', String(params.page));
+    if (params?.per_page) query.set('per_page', String(params.per_page));
+    const qs = query.toString();
+    return api.get<PlayerReportsResponse>(`/admin/player-reports${qs ? `?${qs}` : ''}`);
+  },
+
+  /** Move a player report to a new status */
+  async updatePlayerReport(
+    reportId: string,
+    status: ReportStatus,
+    resolutionNote?: string
+  ): Promise<UpdatePlayerReportResponse> {
+    return api.put<UpdatePlayerReportResponse>(`/admin/player-reports/${reportId}/status`, {
+      status,
+      resolution_note: resolutionNote,
+    });
+  },
+
+  /** Get the chat sent during a game */
+  async getGameChat(gameId: string): Promise<GameChatTranscript> {
+    return api.get<GameChatTranscript>(`/admin/games/${gameId}/chat`);
+  },
+
+  /** List games running on the realtime servers */
+  async getLiveGames(): Promise<LiveGame[]> {
+    const response = await api.get<{ games: LiveGame[] }>('/admin/games/live');
+    return response.games;
+  },
+
+  /** Get the last saved state of a running game */
+  async getLiveGameState(gameId: string): Promise<LiveGameState> {
+    return api.get<LiveGameState>(`/admin/games/${gameId}/state`);
+  },
+
+  /** End a running game; `ended` is false if it had already ended */
+  async forceEndGame(gameId: string, reason?: string): Promise<{ game_id: string; ended: boolean }> {
+    return api.post<{ game_id: string; ended: boolean }>(`/admin/games/${gameId}/end`, { reason });
+  },
+
+  /** Rename a user (a generic name when none is given), resolving any name flag */
+  async forceRename(userId: string, request: ForceRenameRequest): Promise<ForceRenameResponse> {
+    return api.post<ForceRenameResponse>(`/admin/users/${userId}/rename`, request);
+  },
+
+  /** Get display names flagged for review, oldest first */
+  async getNameFlags(page = 1, perPage = 20): Promise<NameFlagsResponse> {
+    return api.get<NameFlagsResponse>(`/admin/names/flags?page=${page}&per_page=${perPage}`);
+  },
+
+  /** Dismiss a name flag, keeping the user's name */
+  async dismissNameFlag(flagId: string): Promise<NameFlag> {
+    return api.post<NameFlag>(`/admin/names/flags/${flagId}/dismiss`);
+  },
+
+  /** Sign in as a user for support; the browser switches to their session */
+  async impersonate(
+    userId: string,
+    request: StartImpersonationRequest
+  ): Promise<ImpersonationResponse> {
+    return api.post<ImpersonationResponse>(`/admin/users/${userId}/impersonate`, request);
+  },
+
+  /** Get recorded admin actions, newest first */
+  async getAuditLog(params?: {
+    user_id?: string;
+    page?: number;
+    per_page?: number;
+  }): Promise<AuditLogResponse> {
+    const query = new URLSearchParams();
+    if (params?.user_id) query.set('user_id', params.user_id);
+    if (params?.page) query.set('page', String(params.page));
+    if (params?.per_page) query.set('per_page', String(params.per_page));
+    const qs = query.toString();
+    return api.get<AuditLogResponse>(`/admin/audit-log${qs ? `?${qs}` : ''}`);
+  },
+
+  /** Store new runtime settings, or re-read the stored ones when omitted */
+  async reloadConfig(settings?: RuntimeSettings): Promise<ReloadConfigResponse> {
+    return api.post<ReloadConfigResponse>('/admin/reload', { settings: settings ?? null });
+  },
+
   /** Get paginated reports list */
   async getReports(params?: {
     page?: number;
@@ -162,4 +831,73 @@ export const adminApi = {
     const path = query ? `/admin/reports?${query}` : '/admin/reports';
     return api.get<ReportsListResponse>(path);
   },
+
+  /** List all system maps, inactive ones included */
+  async listSystemMaps(): Promise<SystemMap[]> {
+    const response = await api.get<{ maps: SystemMap[] }>('/admin/maps');
+    return response.maps;
+  },
+
+  /** Create a system map */
+  async createSystemMap(request: CreateSystemMapRequest): Promise<SystemMap> {
+    return api.post<SystemMap>('/admin/maps', request);
+  },
+
+  /** Edit a system map */
+  async updateSystemMap(mapId: string, request: UpdateSystemMapRequest): Promise<SystemMap> {
+    return api.put<SystemMap>(`/admin/maps/${mapId}`, request);
+  },
+
+  /** Make a system map the default map */
+  async setDefaultMap(mapId: string): Promise<SystemMap> {
+    return api.put<SystemMap>(`/admin/maps/${mapId}/default`);
+  },
+
+  /** Activate or deactivate a system map */
+  async setMapActive(mapId: string, active: boolean): Promise<SystemMap> {
+    return api.put<SystemMap>(`/admin/maps/${mapId}/active`, { active });
+  },
+
+  /** List pending, running and recently finished featured maps */
+  async listFeaturedMaps(): Promise<ScheduledFeature[]> {
+    const response = await api.get<{ features: ScheduledFeature[] }>('/admin/maps/featured');
+    return response.features;
+  },
+
+  /** Schedule a system map to be the default map for a period */
+  async scheduleFeaturedMap(request: ScheduleFeaturedMapRequest): Promise<ScheduledFeature> {
+    return api.post<ScheduledFeature>('/admin/maps/featured', request);
+  },
+
+  /** Cancel a pending feature or end a running one */
+  async cancelFeaturedMap(featureId: number): Promise<void> {
+    return api.delete<void>(`/admin/maps/featured/${featureId}`);
+  },
+
+  /** Get daily active users and games per mode */
+  async getActivityAnalytics(days?: number): Promise<ActivityAnalytics> {
+    const path = days ? `/admin/analytics/activity?days=${days}` : '/admin/analytics/activity';
+    return api.get<ActivityAnalytics>(path);
+  },
+
+  /** Get daily guess score and distance percentiles */
+  async getGuessAnalytics(days?: number): Promise<GuessDistributionPoint[]> {
+    const path = days ? `/admin/analytics/guesses?days=${days}` : '/admin/analytics/guesses';
+    const response = await api.get<{ daily: GuessDistributionPoint[] }>(path);
+    return response.daily;
+  },
+
+  /** Get weekly signup cohort retention */
+  async getRetention(weeks?: number): Promise<RetentionCohort[]> {
+    const path = weeks ? `/admin/analytics/retention?weeks=${weeks}` : '/admin/analytics/retention';
+    const response = await api.get<{ cohorts: RetentionCohort[] }>(path);
+    return response.cohorts;
+  },
+
+  /** Get guesses, scores and distances by continent of the round location */
+  async getContinentAnalytics(days?: number): Promise<ContinentGuessStats[]> {
+    const path = days ? `/admin/analytics/continents?days=${days}` : '/admin/analytics/continents';
+    const response = await api.get<{ continents: ContinentGuessStats[] }>(path);
+    return response.continents;
+  },
 };
@@ -1,4 +1,4 @@
-import { api, API_BASE } from './client';
+import { api, API_BASE, captchaHeaders } from './client';
 
 /**
  * User role for access control
@@ -25,12 +25,38 @@ export interface User {
   best_score: number;
   /** Whether the user has opted into public leaderboard visibility */
   leaderboard_public: boolean;
+  /** Set when an admin is signed in as this user for support */
+  impersonation?: Impersonation;
+}
+
+/** The admin behind an impersonation session */
+export interface Impersonation {
+  admin_id: string;
+  read_only: boolean;
+  expires_at: string;
+}
+
+/** CAPTCHA widget settings; `provider` is null when CAPTCHA is disabled */
+export interface CaptchaSettings {
+  provider: 'turnstile' | 'hcaptcha' | null;
+  site_key: string | null;
+  /** Whether guest creation and joining by code need a token right now */
+  required: boolean;
 }
 
 export const authApi = {
-  /** Create a guest session */
-  async createGuest(): Promise<User> {
-    return api.post<User>('/auth/guest');
+  /**
+   * Create a guest session. Pass a CAPTCHA token when the API answered
+   * CAPTCHA_REQUIRED or {@link authApi.getCaptchaSettings} says one is required.
+   */
+  async createGuest(captchaToken?: string, displayName?: string): Promise<User> {
+    const body = displayName ? { display_name: displayName } : undefined;
+    return api.post<User>('/auth/guest', body, captchaHeaders(captchaToken));
+  },
+
+  /** Get the CAPTCHA provider and whether this visitor must solve one */
+  async getCaptchaSettings(): Promise<CaptchaSettings> {
+    return api.get<CaptchaSettings>('/auth/captcha');
   },
 
   /** Get current authenticated user */
@@ -43,6 +69,11 @@ export const authApi = {
     return api.post('/auth/logout');
   },
 
+  /** End impersonation and return to the admin's own session */
+  async endImpersonation(): Promise<void> {
+    return api.post('/auth/impersonation/end');
+  },
+
   /** Get Google OAuth URL */
   getGoogleAuthUrl(redirectTo?: string): string {
     const params = redirectTo ? `?redirect_to=${encodeURIComponent(redirectTo)}` : '';
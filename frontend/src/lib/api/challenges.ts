@@ -0,0 +1,114 @@
+import { api } from './client';
+import type {
+  CurrentRoundInfo,
+  GameResultsResponse,
+  GameSettings,
+  GameStatus,
+  GuessResult,
+  RoundInfo,
+} from './games';
+
+export interface CreateChallengeRequest {
+  opponent_id: string;
+  rounds?: number;
+  time_limit_seconds?: number;
+  map_id?: string;
+  movement_allowed?: boolean;
+  zoom_allowed?: boolean;
+  rotation_allowed?: boolean;
+}
+
+export interface CreateChallengeResponse {
+  id: string;
+  expires_at: string;
+}
+
+export interface ChallengeListItem {
+  id: string;
+  status: GameStatus;
+  created_by_you: boolean;
+  created_at: string;
+  expires_at: string;
+  ended_at: string | null;
+  total_rounds: number;
+  opponent_id: string;
+  opponent_display_name: string;
+  opponent_avatar_url: string | null;
+  rounds_played: number;
+  opponent_rounds_played: number;
+  your_turn: boolean;
+  score: number;
+  /** Hidden until the challenge is finished */
+  opponent_score: number | null;
+}
+
+export interface ChallengeListResponse {
+  challenges: ChallengeListItem[];
+}
+
+export interface ChallengePlayer {
+  user_id: string;
+  display_name: string;
+  avatar_url: string | null;
+  rounds_played: number;
+  finished: boolean;
+  /** Other players' scores are hidden until the challenge is finished */
+  score: number | null;
+}
+
+export interface ChallengeDetails {
+  id: string;
+  status: GameStatus;
+  created_by: string;
+  created_at: string;
+  expires_at: string;
+  ended_at: string | null;
+  settings: GameSettings;
+  players: ChallengePlayer[];
+  your_turn: boolean;
+  results: GameResultsResponse | null;
+}
+
+export const challengesApi = {
+  /** Challenge a friend */
+  async create(request: CreateChallengeRequest): Promise<CreateChallengeResponse> {
+    return api.post<CreateChallengeResponse>('/challenges', request);
+  },
+
+  /** List active and recently finished challenges */
+  async list(): Promise<ChallengeListResponse> {
+    return api.get<ChallengeListResponse>('/challenges');
+  },
+
+  /** Get a challenge with every player's progress */
+  async get(challengeId: string): Promise<ChallengeDetails> {
+    return api.get<ChallengeDetails>(`/challenges/${challengeId}`);
+  },
+
+  /** Get your current round (for resuming) */
+  async getCurrentRound(challengeId: string): Promise<CurrentRoundInfo> {
+    return api.get<CurrentRoundInfo>(`/challenges/${challengeId}/rounds/current`);
+  },
+
+  /** Start your next round */
+  async nextRound(challengeId: string): Promise<RoundInfo> {
+    return api.post<RoundInfo>(`/challenges/${challengeId}/rounds/next`);
+  },
+
+  /** Submit a guess for your current round */
+  async submitGuess(
+    challengeId: string,
+    roundNumber: number,
+    lat: number,
+    lng: number,
+    timeTakenMs?: number,
+    panoramaId?: string | null
+  ): Promise<GuessResult> {
+    return api.post<GuessResult>(`/challenges/${challengeId}/rounds/${roundNumber}/guess`, {
+      lat,
+      lng,
+      time_taken_ms: timeTakenMs,
+      panorama_id: panoramaId ?? undefined,
+    });
+  },
+};
@@ -1,6 +1,14 @@
 /** Base URL for the backend API, configurable via VITE_API_URL env var */
 export const API_BASE = import.meta.env.VITE_API_URL || 'http://localhost:3001';
 
+/** Header carrying a solved CAPTCHA token */
+export const CAPTCHA_HEADER = 'X-Captcha-Token';
+
+/** Headers for a request that may need a CAPTCHA token */
+export function captchaHeaders(captchaToken?: string): Record<string, string> | undefined {
+  return captchaToken ? { [CAPTCHA_HEADER]: captchaToken } : undefined;
+}
+
 interface ApiErrorBody {
   code: string;
   message: string;
@@ -16,18 +24,23 @@ class ApiClient {
   private async request<T>(
     method: string,
     path: string,
-    body?: unknown
+    body?: unknown,
+    extraHeaders?: Record<string, string>
   ): Promise<T> {
     const url = `${this.baseUrl}${path}`;
 
-    const headers: HeadersInit = {};
+    const headers: Record<string, string> = { ...extraHeaders };
     const options: RequestInit = {
       method,
       headers,
       credentials: 'include', // Send cookies
     };
 
-    if (body !== undefined) {
+    if (body instanceof Blob) {
+      // Raw uploads (e.g. images) are sent as-is
+      headers['Content-Type'] = body.type || 'application/octet-stream';
+      options.body = body;
+    } else if (body !== undefined) {
       headers['Content-Type'] = 'application/json';
       options.body = JSON.stringify(body);
     }
@@ -66,8 +79,8 @@ class ApiClient {
     return this.request<T>('GET', path);
   }
 
-  post<T>(path: string, body?: unknown): Promise<T> {
-    return this.request<T>('POST', path, body);
+  post<T>(path: string, body?: unknown, headers?: Record<string, string>): Promise<T> {
+    return this.request<T>('POST', path, body, headers);
   }
 
   put<T>(path: string, body?: unknown): Promise<T> {
@@ -78,8 +91,8 @@ class ApiClient {
     return this.request<T>('PATCH', path, body);
   }
 
-  delete<T>(path: string): Promise<T> {
-    return this.request<T>('DELETE', path);
+  delete<T>(path: string, body?: unknown): Promise<T> {
+    return this.request<T>('DELETE', path, body);
   }
 }
 
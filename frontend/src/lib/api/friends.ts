@@ -0,0 +1,73 @@
+import type { FriendshipStatus, PresenceStatus } from '$lib/generated/protocol';
+import { api } from './client';
+
+export type { FriendshipStatus, PresenceStatus };
+
+export interface Friend {
+  user_id: string;
+  username: string | null;
+  display_name: string;
+  avatar_url: string | null;
+  online: boolean;
+  /** What the friend is doing (offline if they hide their presence) */
+  status: PresenceStatus;
+  /** Game mode while in a lobby or game */
+  mode: string | null;
+  /** Map name while in a lobby or game */
+  map_name: string | null;
+  since: string;
+}
+
+export interface FriendsListResponse {
+  friends: Friend[];
+}
+
+export interface FriendRequest {
+  user_id: string;
+  username: string | null;
+  display_name: string;
+  avatar_url: string | null;
+  sent_at: string;
+}
+
+export interface FriendRequestsResponse {
+  incoming: FriendRequest[];
+  outgoing: FriendRequest[];
+}
+
+export interface SendFriendRequestResponse {
+  user_id: string;
+  status: FriendshipStatus;
+}
+
+export const friendsApi = {
+  /** List friends with their online status and current activity */
+  async list(): Promise<FriendsListResponse> {
+    return api.get<FriendsListResponse>('/friends');
+  },
+
+  /** List pending requests sent and received */
+  async requests(): Promise<FriendRequestsResponse> {
+    return api.get<FriendRequestsResponse>('/friends/requests');
+  },
+
+  /** Send a friend request by username (accepts theirs if they already asked) */
+  async sendRequest(username: string): Promise<SendFriendRequestResponse> {
+    return api.post<SendFriendRequestResponse>('/friends/requests', { username });
+  },
+
+  /** Accept a friend request */
+  async accept(userId: string): Promise<void> {
+    await api.post<void>(`/friends/requests/${userId}/accept`);
+  },
+
+  /** Decline a friend request */
+  async decline(userId: string): Promise<void> {
+    await api.post<void>(`/friends/requests/${userId}/decline`);
+  },
+
+  /** Remove a friend or cancel a sent request */
+  async remove(userId: string): Promise<void> {
+    await api.delete<void>(`/friends/${userId}`);
+  },
+};
@@ -1,7 +1,15 @@
-import { api } from './client';
+import { api, API_BASE, captchaHeaders } from './client';
+import type { ImageryProvider } from '$lib/imagery';
 
-export type GameMode = 'solo' | 'multiplayer' | 'challenge';
+export type GameMode = 'solo' | 'multiplayer' | 'challenge' | 'streak';
 export type GameStatus = 'lobby' | 'active' | 'finished' | 'abandoned';
+export type DisconnectPolicy = 'remove_player' | 'keep_scoring_zero' | 'pause_if_host';
+
+/** A run of consecutive rounds played on one map */
+export interface MapRounds {
+  map_id: string;
+  rounds: number;
+}
 
 export interface GameSettings {
   rounds: number;
@@ -10,11 +18,40 @@ export interface GameSettings {
   movement_allowed: boolean;
   zoom_allowed: boolean;
   rotation_allowed: boolean;
+  /** Time-attack budget shared by all rounds in seconds (0 = off) */
+  time_budget_seconds?: number;
+  /** Seconds a disconnected player has to reconnect (5-300) */
+  reconnect_grace_seconds?: number;
+  disconnect_policy?: DisconnectPolicy;
+  /** Let players send emoji reactions (default true) */
+  reactions_enabled?: boolean;
+  /** Let players join after the game has started (default false) */
+  allow_late_join?: boolean;
+  /** Maps the rounds are played on, in round order; runs must add up to `rounds` */
+  rounds_plan?: MapRounds[];
+  /** Classroom game: the host is a teacher who watches and does not play */
+  classroom?: boolean;
+  /** Let players take hints that cost score points (default false) */
+  hints_enabled?: boolean;
+  /** Score points each hint costs (max 5000, default 500) */
+  hint_cost?: number;
+}
+
+export type HintKind = 'continent' | 'country_letter' | 'direction';
+
+export interface HintResult {
+  kind: HintKind;
+  /** A continent ID, a letter or a compass point */
+  value: string;
+  /** Points taken off this round's score (0 when the hint was already taken) */
+  cost: number;
 }
 
 export interface CreateGameRequest {
   mode: GameMode;
   settings?: Partial<GameSettings>;
+  /** Built-in preset ID or saved preset ID the settings start from */
+  preset_id?: string;
 }
 
 /**
@@ -39,6 +76,112 @@ export interface Player {
   score: number;
 }
 
+export interface CreateGameInviteRequest {
+  /** Players the invite admits (1-50, default 1) */
+  max_uses?: number;
+  /** Minutes until the invite expires (5-10080, default 1440) */
+  expires_in_minutes?: number;
+}
+
+export interface GameInvite {
+  id: number;
+  max_uses: number;
+  uses: number;
+  expires_at: string;
+  revoked_at: string | null;
+  usable: boolean;
+  created_at: string;
+}
+
+export interface CreatedGameInvite extends GameInvite {
+  /** Only returned when the invite is created */
+  token: string;
+  url: string;
+}
+
+/** A public replay link, without its token */
+export interface GameShare {
+  id: number;
+  views: number;
+  expires_at: string;
+  revoked_at: string | null;
+  active: boolean;
+  created_at: string;
+}
+
+export interface CreatedGameShare extends GameShare {
+  /** Only returned when the link is created */
+  token: string;
+  url: string;
+}
+
+/** A redacted replay opened through a share link; players are only named */
+export interface SharedReplay {
+  mode: string;
+  players: { display_name: string; score: number; rank: number }[];
+  rounds: {
+    round_number: number;
+    location_lat: number;
+    location_lng: number;
+    country_code: string | null;
+    /** Best first; `player` indexes `players` */
+    guesses: {
+      player: number;
+      guess_lat: number;
+      guess_lng: number;
+      distance_meters: number;
+      score: number;
+      time_taken_ms: number | null;
+    }[];
+  }[];
+  highlights: {
+    best_guess: { round_number: number; display_name: string; distance_meters: number; score: number } | null;
+    closest_round: { round_number: number; average_distance_meters: number } | null;
+    biggest_blunder: { round_number: number; display_name: string; distance_meters: number; score: number } | null;
+  };
+  viewer_is_player: boolean;
+  expires_at: string;
+}
+
+/** A country the player can pick in the lobby warm-up quiz */
+export interface QuizChoice {
+  code: string;
+  name: string;
+}
+
+/** A warm-up quiz question, without its answer */
+export interface QuizQuestion {
+  question_id: string;
+  prompt: 'flag' | 'bounds';
+  /** Flag emoji, for flag questions */
+  flag: string | null;
+  /** Bounding box to draw, for bounds questions (min_lng > max_lng across the antimeridian) */
+  bounds: { min_lat: number; min_lng: number; max_lat: number; max_lng: number } | null;
+  choices: QuizChoice[];
+  /** Unix ms when answers stop being accepted */
+  expires_at: number;
+}
+
+export interface QuizScore {
+  user_id: string;
+  score: number;
+}
+
+export interface QuizAnswerResult {
+  correct: boolean;
+  points: number;
+  answer: QuizChoice;
+  /** Highest first */
+  scores: QuizScore[];
+}
+
+export interface QuizState {
+  question: QuizQuestion | null;
+  /** The latest question's answer, once it has expired */
+  answer: QuizChoice | null;
+  scores: QuizScore[];
+}
+
 export interface GameDetails {
   /** Game ID (prefixed nanoid: gam_xxxxxxxxxxxx) */
   id: string;
@@ -62,6 +205,8 @@ export interface Location {
   heading?: number | null;
   /** Location ID for reporting (loc_xxxxxxxxxxxx) */
   location_id?: string | null;
+  /** Imagery provider the panorama ID belongs to */
+  provider?: ImageryProvider;
 }
 
 export interface RoundInfo {
@@ -88,11 +233,28 @@ export interface UserGuessInfo {
   score: number;
 }
 
+/** How past players did on a location */
+export interface GlobalGuessStats {
+  guesses: number;
+  median_distance_meters: number;
+}
+
 export interface GuessResult {
   distance_meters: number;
   score: number;
   total_score: number;
   correct_location: Location;
+  /** Percentage of past guesses on this location that were further away */
+  percentile?: number;
+  global_stats?: GlobalGuessStats;
+  /** Country of the location (streak games) */
+  correct_country_code?: string;
+  /** Current streak length (streak games) */
+  streak?: number;
+  /** Round started straight after this guess (time-attack games) */
+  next_round?: RoundInfo;
+  /** Signature chaining this round's score to the previous rounds (solo games) */
+  score_signature?: string;
 }
 
 export interface RoundResultInfo {
@@ -110,6 +272,8 @@ export interface FinalStandingInfo {
   user_id: string;
   display_name: string;
   total_score: number;
+  /** Longest country streak (streak games) */
+  streak?: number;
 }
 
 export interface CompletedRoundInfo {
@@ -124,6 +288,48 @@ export interface GameResultsResponse {
   rounds: CompletedRoundInfo[];
 }
 
+/** A guess singled out in a game summary */
+export interface GuessHighlight {
+  round_number: number;
+  user_id: string;
+  display_name: string;
+  distance_meters: number;
+  score: number;
+}
+
+/** Round-by-round breakdown of a finished game */
+export interface GameSummaryResponse {
+  game_id: string;
+  players: {
+    user_id: string;
+    display_name: string;
+    score: number;
+    rank: number | null;
+    streak?: number;
+  }[];
+  rounds: {
+    round_number: number;
+    location_lat: number;
+    location_lng: number;
+    country_code: string | null;
+    /** Best first; players who did not guess are left out */
+    guesses: {
+      user_id: string;
+      guess_lat: number;
+      guess_lng: number;
+      distance_meters: number;
+      score: number;
+      time_taken_ms: number | null;
+    }[];
+  }[];
+  /** Each highlight is null when nobody guessed */
+  highlights: {
+    best_guess: GuessHighlight | null;
+    closest_round: { round_number: number; average_distance_meters: number } | null;
+    biggest_blunder: GuessHighlight | null;
+  };
+}
+
 export interface GameSummary {
   /** Game ID (prefixed nanoid: gam_xxxxxxxxxxxx) */
   id: string;
@@ -140,6 +346,19 @@ export interface UpdateSettingsRequest {
   movement_allowed?: boolean;
   zoom_allowed?: boolean;
   rotation_allowed?: boolean;
+  time_budget_seconds?: number;
+  reconnect_grace_seconds?: number;
+  disconnect_policy?: DisconnectPolicy;
+  /** Let players send emoji reactions (default true) */
+  reactions_enabled?: boolean;
+  /** Let players join after the game has started (default false) */
+  allow_late_join?: boolean;
+  /** Maps the rounds are played on, in round order; runs must add up to `rounds` */
+  rounds_plan?: MapRounds[];
+  /** Let players take hints that cost score points */
+  hints_enabled?: boolean;
+  /** Score points each hint costs (max 5000) */
+  hint_cost?: number;
 }
 
 export interface UpdateSettingsResponse {
@@ -147,6 +366,16 @@ export interface UpdateSettingsResponse {
 }
 
 export const gamesApi = {
+  /** URL of a shareable PNG of one player's round result (map and score) */
+  roundCardUrl(gameId: string, roundNumber: number, userId: string): string {
+    return `${API_BASE}/api/v1/games/${gameId}/rounds/${roundNumber}/card?user_id=${encodeURIComponent(userId)}`;
+  },
+
+  /** URL of a classroom game's results as CSV (teacher only) */
+  exportUrl(gameId: string): string {
+    return `${API_BASE}/api/v1/games/${gameId}/export`;
+  },
+
   /** Create a new game */
   async create(request: CreateGameRequest): Promise<CreateGameResponse> {
     return api.post<CreateGameResponse>('/games', request);
@@ -183,12 +412,30 @@ export const gamesApi = {
     roundNumber: number,
     lat: number,
     lng: number,
-    timeTakenMs?: number
+    timeTakenMs?: number,
+    panoramaId?: string | null,
+    countryCode?: string
   ): Promise<GuessResult> {
     return api.post<GuessResult>(`/games/${gameId}/rounds/${roundNumber}/guess`, {
       lat,
       lng,
       time_taken_ms: timeTakenMs,
+      panorama_id: panoramaId ?? undefined,
+      country_code: countryCode,
+    });
+  },
+
+  /** Take a hint for the current round; the direction hint needs a point */
+  async requestHint(
+    gameId: string,
+    roundNumber: number,
+    kind: HintKind,
+    from?: { lat: number; lng: number }
+  ): Promise<HintResult> {
+    return api.post<HintResult>(`/games/${gameId}/rounds/${roundNumber}/hint`, {
+      kind,
+      from_lat: from?.lat,
+      from_lng: from?.lng,
     });
   },
 
@@ -202,9 +449,74 @@ export const gamesApi = {
     return api.get<GameResultsResponse>(`/games/${gameId}/results`);
   },
 
+  /** Get the round-by-round breakdown of a finished game */
+  async getSummary(gameId: string): Promise<GameSummaryResponse> {
+    return api.get<GameSummaryResponse>(`/games/${gameId}/summary`);
+  },
+
   /** Join a game by code */
-  async joinByCode(code: string): Promise<GameDetails> {
-    return api.post<GameDetails>('/games/join', { code });
+  async joinByCode(code: string, captchaToken?: string): Promise<GameDetails> {
+    return api.post<GameDetails>('/games/join', { code }, captchaHeaders(captchaToken));
+  },
+
+  /** Redeem a game invite link */
+  async redeemInvite(token: string): Promise<GameDetails> {
+    return api.post<GameDetails>('/games/invites/redeem', { token });
+  },
+
+  /** Create an invite link for a lobby (host only) */
+  async createInvite(gameId: string, request: CreateGameInviteRequest = {}): Promise<CreatedGameInvite> {
+    return api.post<CreatedGameInvite>(`/games/${gameId}/invites`, request);
+  },
+
+  /** List a lobby's invites (host only) */
+  async listInvites(gameId: string): Promise<GameInvite[]> {
+    const response = await api.get<{ invites: GameInvite[] }>(`/games/${gameId}/invites`);
+    return response.invites;
+  },
+
+  /** Create a public replay link for a finished game (1-30 days, default 7) */
+  async createShare(gameId: string, expiresInDays?: number): Promise<CreatedGameShare> {
+    return api.post<CreatedGameShare>(`/games/${gameId}/share`, { expires_in_days: expiresInDays });
+  },
+
+  /** List your replay links for a game */
+  async listShares(gameId: string): Promise<GameShare[]> {
+    const response = await api.get<{ shares: GameShare[] }>(`/games/${gameId}/share`);
+    return response.shares;
+  },
+
+  /** Revoke one of your replay links */
+  async revokeShare(gameId: string, shareId: number): Promise<void> {
+    return api.delete<void>(`/games/${gameId}/share/${shareId}`);
+  },
+
+  /** Open a shared replay; works without signing in */
+  async getSharedReplay(token: string): Promise<SharedReplay> {
+    return api.get<SharedReplay>(`/replays/${encodeURIComponent(token)}`);
+  },
+
+  /** Revoke an invite (host only) */
+  async revokeInvite(gameId: string, inviteId: number): Promise<void> {
+    return api.delete<void>(`/games/${gameId}/invites/${inviteId}`);
+  },
+
+  /** Get the lobby's warm-up quiz question and scores */
+  async getQuiz(gameId: string): Promise<QuizState> {
+    return api.get<QuizState>(`/games/${gameId}/quiz`);
+  },
+
+  /** Ask a new warm-up quiz question; it is broadcast to the lobby */
+  async askQuizQuestion(gameId: string): Promise<QuizQuestion> {
+    return api.post<QuizQuestion>(`/games/${gameId}/quiz/question`);
+  },
+
+  /** Answer the open warm-up quiz question with a country code */
+  async answerQuiz(gameId: string, questionId: string, answer: string): Promise<QuizAnswerResult> {
+    return api.post<QuizAnswerResult>(`/games/${gameId}/quiz/answer`, {
+      question_id: questionId,
+      answer,
+    });
   },
 
   /** Update game settings (host only, lobby only) */
@@ -1,6 +1,6 @@
 // API client module
-export { api, ApiClientError } from './client';
-export { authApi, type User } from './auth';
+export { api, ApiClientError, API_BASE, CAPTCHA_HEADER } from './client';
+export { authApi, type User, type CaptchaSettings } from './auth';
 export {
   gamesApi,
   type GameMode,
@@ -13,6 +13,7 @@ export {
   type Location,
   type RoundInfo,
   type GuessResult,
+  type GlobalGuessStats,
   type GameSummary,
 } from './games';
 export {
@@ -26,19 +27,50 @@ export {
 export {
   usersApi,
   sessionsApi,
+  MAX_AVATAR_BYTES,
   type UserProfile,
+  type PublicProfile,
+  type PublicProfileStats,
+  type PresenceVisibility,
+  type UserPresence,
   type UpdateProfileRequest,
   type DeleteAccountResponse,
   type SessionInfo,
   type SessionsListResponse,
   type RevokeSessionResponse,
 } from './users';
+export {
+  friendsApi,
+  type FriendshipStatus,
+  type PresenceStatus,
+  type Friend,
+  type FriendsListResponse,
+  type FriendRequest,
+  type FriendRequestsResponse,
+  type SendFriendRequestResponse,
+} from './friends';
+export {
+  challengesApi,
+  type CreateChallengeRequest,
+  type CreateChallengeResponse,
+  type ChallengeListItem,
+  type ChallengeListResponse,
+  type ChallengePlayer,
+  type ChallengeDetails,
+} from './challenges';
+export {
+  notificationsApi,
+  type NotificationPreferences,
+  type PushSubscriptionJson,
+} from './notifications';
 export {
   mapsApi,
   locationsApi,
   type MapVisibility,
   type MapSummary,
   type MapDetails,
+  type ScoringConfig,
+  type DifficultyBand,
   type CreateMapRequest,
   type CreateMapResponse,
   type UpdateMapRequest,
@@ -57,3 +89,19 @@ export {
   type SubdivisionInfo,
   type SubdivisionsResponse,
 } from './maps';
+export { telemetryApi, type ClientErrorKind, type ClientErrorReport } from './telemetry';
+export {
+  streetViewApi,
+  tileUrl,
+  type StreetViewMetadata,
+  type TileMapType,
+  type TileSession,
+} from './streetview';
+export {
+  reportsApi,
+  type ReportCategory,
+  type ReportStatus,
+  type CreatePlayerReportRequest,
+  type PlayerReport,
+} from './reports';
+export { practiceApi, type PracticeRound, type PracticeGuessResult } from './practice';
@@ -1,7 +1,7 @@
+import type { LeaderboardType, TimePeriod } from '$lib/generated/protocol';
 import { api } from './client';
 
-export type LeaderboardType = 'total_score' | 'best_game' | 'games_played' | 'average_score';
-export type TimePeriod = 'all_time' | 'daily' | 'weekly' | 'monthly';
+export type { LeaderboardType, TimePeriod };
 
 export interface LeaderboardEntry {
   rank: number;
@@ -1,5 +1,7 @@
 // Maps API client
 import { api } from './client';
+import type { ImageryProvider } from '$lib/imagery';
+import type { CreateLocationNoteRequest, LocationNote } from './admin';
 
 // =============================================================================
 // Types
@@ -7,6 +9,23 @@ import { api } from './client';
 
 export type MapVisibility = 'private' | 'unlisted' | 'public';
 
+export type MapSort = 'newest' | 'popular' | 'most_liked';
+
+/** GeoJSON polygon boundary with [lng, lat] positions */
+export type MapRegion =
+  | { type: 'Polygon'; coordinates: [number, number][][] }
+  | { type: 'MultiPolygon'; coordinates: [number, number][][][] };
+
+/** Scoring curve: score = max_points * (1 - (distance / zero_score_distance) ^ curve_exponent) */
+export interface ScoringConfig {
+  max_points: number;
+  /** Distance in meters at which a guess scores 0 */
+  zero_score_distance: number;
+  curve_exponent: number;
+  /** Guesses within this many meters score max_points */
+  perfect_radius_meters: number;
+}
+
 export interface MapSummary {
   id: string;
   slug: string;
@@ -15,7 +34,12 @@ export interface MapSummary {
   visibility: MapVisibility;
   is_system_map: boolean;
   is_owned: boolean;
+  can_edit: boolean;
   location_count: number;
+  like_count: number;
+  play_count: number;
+  avg_score: number | null;
+  is_liked: boolean;
   created_at: string;
 }
 
@@ -23,10 +47,20 @@ export interface ListMapsResponse {
   maps: MapSummary[];
 }
 
+/** Difficulty band derived from past guesses on a location */
+export type DifficultyBand = 'easy' | 'medium' | 'hard' | 'extreme';
+
 export interface CreateMapRequest {
   name: string;
   description?: string;
   visibility?: MapVisibility;
+  region?: MapRegion;
+  /** Only play locations from this imagery provider */
+  provider?: ImageryProvider;
+  /** Omitted fields use the default curve */
+  scoring?: Partial<ScoringConfig>;
+  /** Only play locations rated in this difficulty band */
+  difficulty?: DifficultyBand;
 }
 
 export interface CreateMapResponse {
@@ -34,6 +68,35 @@ export interface CreateMapResponse {
   slug: string;
 }
 
+export interface FeaturedMap {
+  map_id: string;
+  slug: string;
+  name: string;
+  title: string | null;
+  starts_at: string;
+  ends_at: string;
+}
+
+export interface FeaturedMapsResponse {
+  current: FeaturedMap | null;
+  upcoming: FeaturedMap[];
+}
+
+export interface ValidateRulesRequest {
+  rules: Record<string, unknown>;
+  map_id?: string;
+}
+
+export interface RulesCountryCount {
+  country_code: string | null;
+  count: number;
+}
+
+export interface ValidateRulesResponse {
+  total: number;
+  countries: RulesCountryCount[];
+}
+
 export interface MapDetails {
   id: string;
   slug: string;
@@ -42,8 +105,17 @@ export interface MapDetails {
   visibility: MapVisibility;
   is_system_map: boolean;
   is_owned: boolean;
+  can_edit: boolean;
   is_default: boolean;
   location_count: number;
+  like_count: number;
+  play_count: number;
+  avg_score: number | null;
+  is_liked: boolean;
+  region?: MapRegion;
+  provider?: ImageryProvider;
+  scoring: ScoringConfig;
+  difficulty?: DifficultyBand;
   created_at: string;
   updated_at: string;
 }
@@ -52,6 +124,77 @@ export interface UpdateMapRequest {
   name?: string;
   description?: string;
   visibility?: MapVisibility;
+  /** null removes the region */
+  region?: MapRegion | null;
+  /** null restores the default curve */
+  scoring?: Partial<ScoringConfig> | null;
+  /** null allows every difficulty */
+  difficulty?: DifficultyBand | null;
+}
+
+export interface MapLikeResponse {
+  liked: boolean;
+  like_count: number;
+}
+
+export type CollaboratorStatus = 'pending' | 'accepted';
+
+export interface MapCollaborator {
+  user_id: string;
+  username: string | null;
+  display_name: string;
+  avatar_url: string | null;
+  status: CollaboratorStatus;
+  invited_at: string;
+  accepted_at: string | null;
+}
+
+export interface MapCollaboratorsResponse {
+  collaborators: MapCollaborator[];
+}
+
+export interface MapInvitation {
+  map_id: string;
+  map_slug: string;
+  map_name: string;
+  invited_by: string | null;
+  inviter_name: string | null;
+  invited_at: string;
+}
+
+export interface MapInvitationsResponse {
+  invitations: MapInvitation[];
+}
+
+export type MapVersionKind = 'initial' | 'locations' | 'rules' | 'import' | 'restore';
+
+export interface MapVersionItem {
+  version: number;
+  kind: MapVersionKind;
+  user_id: string | null;
+  added_count: number;
+  removed_count: number;
+  rules: Record<string, unknown>;
+  restored_from: number | null;
+  created_at: string;
+}
+
+export interface MapVersionsResponse {
+  versions: MapVersionItem[];
+  total: number;
+  page: number;
+  per_page: number;
+}
+
+export interface MapVersionDetails {
+  version: number;
+  kind: MapVersionKind;
+  user_id: string | null;
+  added_location_ids: string[];
+  removed_location_ids: string[];
+  rules: Record<string, unknown>;
+  restored_from: number | null;
+  created_at: string;
 }
 
 export interface MapLocationItem {
@@ -70,6 +213,20 @@ export interface MapLocationsResponse {
   per_page: number;
 }
 
+export interface HeatmapBin {
+  geohash: string;
+  lat: number;
+  lng: number;
+  count: number;
+}
+
+export interface MapHeatmapResponse {
+  map_id: string;
+  precision: number;
+  locations: HeatmapBin[];
+  guesses?: HeatmapBin[];
+}
+
 export interface AddLocationsRequest {
   location_ids: string[];
 }
@@ -83,11 +240,22 @@ export interface AddLocationsFromUrlsRequest {
   urls: string[];
 }
 
+export type UrlImportStatus =
+  | 'added'
+  | 'already_exists'
+  | 'invalid_url'
+  | 'short_link_failed'
+  | 'panorama_not_found'
+  | 'validation_failed'
+  | 'error';
+
 export interface UrlParseResult {
   url: string;
+  status: UrlImportStatus;
   success: boolean;
   error: string | null;
   location_id: string | null;
+  panorama_id: string | null;
   already_exists: boolean;
 }
 
@@ -105,6 +273,11 @@ export interface LocationSearchFilters {
   max_year?: number;
   outdoor_only?: boolean;
   exclude_map_id?: string;
+  difficulty?: DifficultyBand;
+  /** Locations must have all of these tags */
+  tags?: string[];
+  /** Free text matched against tags, country, subdivision and surface */
+  q?: string;
   page?: number;
   per_page?: number;
 }
@@ -117,6 +290,9 @@ export interface LocationSearchItem {
   country_code: string | null;
   subdivision_code: string | null;
   capture_year: number | null;
+  /** 0-100 rating from past guesses; null until rated */
+  difficulty: number | null;
+  tags: string[];
 }
 
 export interface SearchLocationsResponse {
@@ -128,6 +304,8 @@ export interface SearchLocationsResponse {
 
 export interface CountryInfo {
   code: string;
+  /** Display name in the response language */
+  name: string;
   count: number;
 }
 
@@ -137,6 +315,8 @@ export interface CountriesResponse {
 
 export interface SubdivisionInfo {
   code: string;
+  /** Display name in the response language, or the code when unknown */
+  name: string;
   count: number;
 }
 
@@ -152,8 +332,29 @@ export const mapsApi = {
   /**
    * List maps visible to the current user.
    */
-  async list(): Promise<ListMapsResponse> {
-    return api.get<ListMapsResponse>('/maps');
+  async list(sort: MapSort = 'newest'): Promise<ListMapsResponse> {
+    return api.get<ListMapsResponse>(`/maps?sort=${sort}`);
+  },
+
+  /**
+   * List maps the current user has liked.
+   */
+  async favorites(): Promise<ListMapsResponse> {
+    return api.get<ListMapsResponse>('/maps/favorites');
+  },
+
+  /**
+   * Like a map.
+   */
+  async like(id: string): Promise<MapLikeResponse> {
+    return api.post<MapLikeResponse>(`/maps/${id}/like`);
+  },
+
+  /**
+   * Remove a like from a map.
+   */
+  async unlike(id: string): Promise<MapLikeResponse> {
+    return api.delete<MapLikeResponse>(`/maps/${id}/like`);
   },
 
   /**
@@ -170,6 +371,20 @@ export const mapsApi = {
     return api.post<CreateMapResponse>('/maps', data);
   },
 
+  /**
+   * Get the current and upcoming featured maps.
+   */
+  async featured(): Promise<FeaturedMapsResponse> {
+    return api.get<FeaturedMapsResponse>('/maps/featured');
+  },
+
+  /**
+   * Validate draft map rules and count the locations they match.
+   */
+  async validateRules(data: ValidateRulesRequest): Promise<ValidateRulesResponse> {
+    return api.post<ValidateRulesResponse>('/maps/validate-rules', data);
+  },
+
   /**
    * Update a map.
    */
@@ -200,6 +415,22 @@ export const mapsApi = {
     );
   },
 
+  /**
+   * Get a coverage heatmap for a map, optionally with where players guess.
+   */
+  async getHeatmap(
+    mapId: string,
+    precision = 4,
+    includeGuesses = false
+  ): Promise<MapHeatmapResponse> {
+    const params = new URLSearchParams();
+    params.set('precision', precision.toString());
+    params.set('guesses', includeGuesses.toString());
+    return api.get<MapHeatmapResponse>(
+      `/maps/${mapId}/heatmap?${params.toString()}`
+    );
+  },
+
   /**
    * Add locations to a map by IDs.
    */
@@ -217,11 +448,12 @@ export const mapsApi = {
    */
   async addLocationsFromUrls(
     mapId: string,
-    urls: string[]
+    urls: string[],
+    validate = false
   ): Promise<AddLocationsFromUrlsResponse> {
     return api.post<AddLocationsFromUrlsResponse>(
       `/maps/${mapId}/locations/from-urls`,
-      { urls }
+      { urls, validate }
     );
   },
 
@@ -231,6 +463,97 @@ export const mapsApi = {
   async removeLocation(mapId: string, locationId: string): Promise<void> {
     return api.delete<void>(`/maps/${mapId}/locations/${locationId}`);
   },
+
+  /** List internal notes on a location in the map (owner and editors) */
+  async getLocationNotes(mapId: string, locationId: string): Promise<LocationNote[]> {
+    return api.get<LocationNote[]>(`/maps/${mapId}/locations/${locationId}/notes`);
+  },
+
+  /** Add an internal note to a location in the map (owner and editors) */
+  async addLocationNote(
+    mapId: string,
+    locationId: string,
+    request: CreateLocationNoteRequest
+  ): Promise<LocationNote> {
+    return api.post<LocationNote>(`/maps/${mapId}/locations/${locationId}/notes`, request);
+  },
+
+  /** Delete a note you wrote */
+  async deleteLocationNote(mapId: string, locationId: string, noteId: number): Promise<void> {
+    return api.delete<void>(`/maps/${mapId}/locations/${locationId}/notes/${noteId}`);
+  },
+
+  /**
+   * List a map's collaborators and pending invitations.
+   */
+  async getCollaborators(id: string): Promise<MapCollaboratorsResponse> {
+    return api.get<MapCollaboratorsResponse>(`/maps/${id}/collaborators`);
+  },
+
+  /**
+   * Invite a user to edit a map (owner only).
+   */
+  async inviteCollaborator(id: string, username: string): Promise<MapCollaborator> {
+    return api.post<MapCollaborator>(`/maps/${id}/collaborators`, { username });
+  },
+
+  /**
+   * Remove a collaborator, or leave a map as a collaborator.
+   */
+  async removeCollaborator(id: string, userId: string): Promise<void> {
+    return api.delete<void>(`/maps/${id}/collaborators/${userId}`);
+  },
+
+  /**
+   * List the current user's pending invitations.
+   */
+  async invitations(): Promise<MapInvitationsResponse> {
+    return api.get<MapInvitationsResponse>('/maps/invitations');
+  },
+
+  /**
+   * Accept an invitation to edit a map.
+   */
+  async acceptInvitation(id: string): Promise<MapCollaborator> {
+    return api.post<MapCollaborator>(`/maps/${id}/invitation`);
+  },
+
+  /**
+   * Decline an invitation to edit a map.
+   */
+  async declineInvitation(id: string): Promise<void> {
+    return api.delete<void>(`/maps/${id}/invitation`);
+  },
+
+  /**
+   * Get a map's version history (newest first).
+   */
+  async getVersions(
+    id: string,
+    page = 1,
+    perPage = 50
+  ): Promise<MapVersionsResponse> {
+    const params = new URLSearchParams();
+    params.set('page', page.toString());
+    params.set('per_page', perPage.toString());
+    return api.get<MapVersionsResponse>(
+      `/maps/${id}/versions?${params.toString()}`
+    );
+  },
+
+  /**
+   * Get one version with its location diff.
+   */
+  async getVersion(id: string, version: number): Promise<MapVersionDetails> {
+    return api.get<MapVersionDetails>(`/maps/${id}/versions/${version}`);
+  },
+
+  /**
+   * Restore a map to an earlier version (owner only).
+   */
+  async restoreVersion(id: string, version: number): Promise<MapVersionDetails> {
+    return api.post<MapVersionDetails>(`/maps/${id}/versions/${version}/restore`);
+  },
 };
 
 // =============================================================================
@@ -252,6 +575,9 @@ export const locationsApi = {
     if (filters.outdoor_only) params.set('outdoor_only', 'true');
     if (filters.exclude_map_id)
       params.set('exclude_map_id', filters.exclude_map_id);
+    if (filters.difficulty) params.set('difficulty', filters.difficulty);
+    if (filters.tags?.length) params.set('tags', filters.tags.join(','));
+    if (filters.q) params.set('q', filters.q);
     if (filters.page) params.set('page', filters.page.toString());
     if (filters.per_page) params.set('per_page', filters.per_page.toString());
 
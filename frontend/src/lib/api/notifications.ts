@@ -0,0 +1,43 @@
+import { api } from './client';
+
+export interface NotificationPreferences {
+  challenge_turns: boolean;
+  friend_invites: boolean;
+  daily_challenge: boolean;
+}
+
+/** Shape of `PushSubscription.toJSON()` */
+export interface PushSubscriptionJson {
+  endpoint: string;
+  keys: { p256dh: string; auth: string };
+}
+
+export const notificationsApi = {
+  /** VAPID public key to subscribe with (503 when push is not configured) */
+  async getPublicKey(): Promise<string> {
+    const response = await api.get<{ public_key: string }>('/notifications/push/public-key');
+    return response.public_key;
+  },
+
+  /** Register this browser's push subscription */
+  async subscribe(subscription: PushSubscriptionJson): Promise<void> {
+    await api.post<void>('/notifications/push/subscriptions', subscription);
+  },
+
+  /** Remove this browser's push subscription */
+  async unsubscribe(endpoint: string): Promise<void> {
+    await api.delete<void>('/notifications/push/subscriptions', { endpoint });
+  },
+
+  /** Get notification preferences */
+  async getPreferences(): Promise<NotificationPreferences> {
+    return api.get<NotificationPreferences>('/notifications/preferences');
+  },
+
+  /** Update notification preferences */
+  async updatePreferences(
+    updates: Partial<NotificationPreferences>
+  ): Promise<NotificationPreferences> {
+    return api.put<NotificationPreferences>('/notifications/preferences', updates);
+  },
+};
@@ -44,4 +44,9 @@ export const partiesApi = {
   async joinByCode(code: string): Promise<JoinByCodeResponse> {
     return api.post<JoinByCodeResponse>('/parties/join', { code });
   },
+
+  /** Leave a party without a socket connection */
+  async leave(partyId: string): Promise<void> {
+    await api.post<void>(`/parties/${partyId}/leave`);
+  },
 };
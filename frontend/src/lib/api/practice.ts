@@ -0,0 +1,36 @@
+import { api } from './client';
+import type { Location } from './games';
+
+export interface PracticeRound {
+  /** Practice round ID (prc_xxxxxxxxxxxx) */
+  round_id: string;
+  map_id: string;
+  location: Location;
+  /** When the round can no longer be guessed */
+  expires_at: string;
+}
+
+export interface PracticeGuessResult {
+  distance_meters: number;
+  score: number;
+  /** Best possible score on the round's map */
+  max_score: number;
+  /** Guesses made on this round so far, including this one */
+  attempts: number;
+  best_score: number;
+  /** The correct location, revealed after every guess */
+  location: Location;
+  country_code: string | null;
+}
+
+export const practiceApi = {
+  /** Start a practice round; nothing is recorded to games or leaderboards */
+  async createRound(mapId?: string): Promise<PracticeRound> {
+    return api.post<PracticeRound>('/practice/round', { map_id: mapId });
+  },
+
+  /** Guess a practice round; rounds can be guessed again until they expire */
+  async guess(roundId: string, lat: number, lng: number): Promise<PracticeGuessResult> {
+    return api.post<PracticeGuessResult>(`/practice/round/${roundId}/guess`, { lat, lng });
+  },
+};
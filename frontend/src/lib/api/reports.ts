@@ -0,0 +1,30 @@
+import { api } from './client';
+
+export type ReportCategory = 'cheating' | 'abusive_name' | 'abusive_chat' | 'griefing' | 'other';
+
+export type ReportStatus = 'open' | 'reviewing' | 'actioned' | 'dismissed';
+
+export interface CreatePlayerReportRequest {
+  /** Player being reported */
+  user_id: string;
+  category: ReportCategory;
+  /** Game it happened in (required for cheating and griefing) */
+  game_id?: string;
+  /** Offending chat messages (required for abusive_chat, max 500 characters) */
+  chat_excerpt?: string;
+  /** What happened (required for other, max 1000 characters) */
+  notes?: string;
+}
+
+export interface PlayerReport {
+  id: string;
+  status: ReportStatus;
+  created_at: string;
+}
+
+export const reportsApi = {
+  /** Report a player for cheating or abuse */
+  async reportPlayer(request: CreatePlayerReportRequest): Promise<PlayerReport> {
+    return api.post<PlayerReport>('/reports/users', request);
+  },
+};
@@ -0,0 +1,57 @@
+import { api, API_BASE } from './client';
+
+export interface StreetViewMetadata {
+  panorama_id: string;
+  status: 'ok' | 'not_found';
+  lat: number | null;
+  lng: number | null;
+  /** First day of the capture month (YYYY-MM-DD) */
+  capture_date: string | null;
+}
+
+export type TileMapType = 'streetview' | 'roadmap' | 'satellite';
+
+export interface TileSession {
+  /** Signed tile token; request a new session before it expires */
+  token: string;
+  expires_at: string;
+  map_type: TileMapType;
+  tile_width: number;
+  tile_height: number;
+  image_format: 'jpeg' | 'png';
+  /** Tile path on the API host with {z}, {x}, {y} (and {pano}) placeholders */
+  tile_url: string;
+}
+
+/** Absolute URL of one tile from a tile session. */
+export function tileUrl(
+  session: TileSession,
+  z: number,
+  x: number,
+  y: number,
+  panoramaId?: string
+): string {
+  const path = session.tile_url
+    .replace('{z}', String(z))
+    .replace('{x}', String(x))
+    .replace('{y}', String(y))
+    .replace('{pano}', encodeURIComponent(panoramaId ?? ''));
+  return `${API_BASE}${path}`;
+}
+
+export const streetViewApi = {
+  /**
+   * Look up a panorama through the server, which holds the Maps API key.
+   * Panoramas reported "not_found" are taken out of rotation server-side.
+   */
+  async getMetadata(panoramaId: string): Promise<StreetViewMetadata> {
+    return api.get<StreetViewMetadata>(
+      `/streetview/metadata?pano=${encodeURIComponent(panoramaId)}`
+    );
+  },
+
+  /** Get a tile token for loading imagery through the API */
+  async createTileSession(mapType: TileMapType): Promise<TileSession> {
+    return api.post<TileSession>('/streetview/session', { map_type: mapType });
+  },
+};
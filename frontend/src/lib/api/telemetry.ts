@@ -0,0 +1,34 @@
+import { api } from './client';
+
+export type ClientErrorKind =
+  | 'pano_load_failed'
+  | 'socket_disconnected'
+  | 'map_load_failed'
+  | 'unhandled';
+
+export interface ClientErrorReport {
+  kind: ClientErrorKind;
+  message?: string;
+  location_id?: string | null;
+  panorama_id?: string | null;
+  game_id?: string | null;
+  page?: string;
+  context?: Record<string, unknown>;
+}
+
+export const telemetryApi = {
+  /**
+   * Report a client-side error. Never throws: a failed report is only logged,
+   * since it usually means the connection is already in trouble.
+   */
+  async reportClientError(report: ClientErrorReport): Promise<void> {
+    try {
+      await api.post<void>('/telemetry/client-errors', {
+        ...report,
+        page: report.page ?? (typeof location !== 'undefined' ? location.pathname : undefined),
+      });
+    } catch (e) {
+      console.debug('[Telemetry] Failed to report client error:', e);
+    }
+  },
+};
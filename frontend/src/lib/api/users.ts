@@ -1,5 +1,10 @@
 import { api } from './client';
 import type { User } from './auth';
+import type { PresenceStatus } from './friends';
+import type { GameSettings } from './games';
+
+/** Who can see whether you are online and what you are playing */
+export type PresenceVisibility = 'everyone' | 'friends' | 'nobody';
 
 /**
  * User profile (public view)
@@ -15,6 +20,56 @@ export interface UserProfile {
   best_score: number;
   /** Whether the user has opted into public leaderboard visibility */
   leaderboard_public: boolean;
+  /** Short bio */
+  bio: string | null;
+  /** Home country (ISO 3166-1 alpha-2), shown as a flag */
+  country_code: string | null;
+  /** Profile accent color (#rrggbb) */
+  profile_color: string | null;
+  /** Whether a moderator has locked the profile against edits */
+  profile_locked: boolean;
+  presence_visibility: PresenceVisibility;
+}
+
+/**
+ * A user's presence (offline when hidden by their privacy settings)
+ */
+export interface UserPresence {
+  user_id: string;
+  status: PresenceStatus;
+  mode: string | null;
+  map_name: string | null;
+}
+
+/**
+ * Lifetime stats on a public profile
+ */
+export interface PublicProfileStats {
+  games_played: number;
+  total_score: number;
+  best_score: number;
+  average_score: number | null;
+  rounds_played: number;
+  average_distance_meters: number | null;
+  best_streak: number | null;
+  /** All-time rank by total score */
+  rank: number | null;
+}
+
+/**
+ * Public profile with stats (only for users who opted in)
+ */
+export interface PublicProfile {
+  username: string;
+  display_name: string;
+  avatar_url: string | null;
+  bio: string | null;
+  country_code: string | null;
+  profile_color: string | null;
+  member_since: string;
+  stats: PublicProfileStats;
+  /** API path of the shareable stats card (PNG; add ?format=svg for SVG) */
+  card_url: string;
 }
 
 /**
@@ -25,8 +80,18 @@ export interface UpdateProfileRequest {
   display_name?: string;
   avatar_url?: string;
   leaderboard_public?: boolean;
+  /** Empty string clears the bio */
+  bio?: string;
+  /** Empty string clears the country */
+  country_code?: string;
+  /** Empty string clears the color */
+  profile_color?: string;
+  presence_visibility?: PresenceVisibility;
 }
 
+/** Largest accepted avatar upload in bytes */
+export const MAX_AVATAR_BYTES = 512 * 1024;
+
 /**
  * Delete account response
  */
@@ -62,12 +127,75 @@ export interface RevokeSessionResponse {
   revoked_count: number;
 }
 
+/** A saved settings preset; pass its ID as `preset_id` when creating a game */
+export interface SettingsPreset {
+  id: string;
+  name: string;
+  settings: GameSettings;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface SettingsPresetsResponse {
+  presets: SettingsPreset[];
+  /** How many presets a user can save */
+  limit: number;
+}
+
 export const usersApi = {
+  /** Get current user's profile */
+  async getProfile(): Promise<UserProfile> {
+    return api.get<UserProfile>('/users/me');
+  },
+
   /** Update current user's profile */
   async updateProfile(data: UpdateProfileRequest): Promise<UserProfile> {
     return api.put<UserProfile>('/users/me', data);
   },
 
+  /** Get a user's public profile with stats */
+  async getPublicProfile(username: string): Promise<PublicProfile> {
+    return api.get<PublicProfile>(`/users/${encodeURIComponent(username)}/public`);
+  },
+
+  /** Get whether a user is online and what they are playing */
+  async getPresence(userId: string): Promise<UserPresence> {
+    return api.get<UserPresence>(`/users/${encodeURIComponent(userId)}/presence`);
+  },
+
+  /** Upload a PNG, JPEG or WebP avatar */
+  async uploadAvatar(file: Blob): Promise<UserProfile> {
+    return api.put<UserProfile>('/users/me/avatar', file);
+  },
+
+  /** Remove the current avatar */
+  async deleteAvatar(): Promise<UserProfile> {
+    return api.delete<UserProfile>('/users/me/avatar');
+  },
+
+  /** List the current user's saved settings presets */
+  async listPresets(): Promise<SettingsPresetsResponse> {
+    return api.get<SettingsPresetsResponse>('/users/me/presets');
+  },
+
+  /** Save a settings preset */
+  async createPreset(name: string, settings: GameSettings): Promise<SettingsPreset> {
+    return api.post<SettingsPreset>('/users/me/presets', { name, settings });
+  },
+
+  /** Rename a preset or replace its settings */
+  async updatePreset(
+    presetId: string,
+    update: { name?: string; settings?: GameSettings }
+  ): Promise<SettingsPreset> {
+    return api.put<SettingsPreset>(`/users/me/presets/${presetId}`, update);
+  },
+
+  /** Delete a saved preset */
+  async deletePreset(presetId: string): Promise<void> {
+    return api.delete<void>(`/users/me/presets/${presetId}`);
+  },
+
   /** Delete current user's account (soft delete) */
   async deleteAccount(): Promise<DeleteAccountResponse> {
     return api.delete<DeleteAccountResponse>('/users/me');
@@ -0,0 +1,2582 @@
+// Generated by `cargo run -p dguesser-protocol --bin protocol-ts` from the DTOs
+// in crates/protocol. Do not edit by hand.
+
+/** Game and player activity response */
+export interface ActivityAnalyticsResponse {
+  /** Daily activity, oldest first */
+  daily: DailyActivityPoint[];
+  /** When the rollups were last refreshed */
+  refreshed_at?: string | null;
+  /** Games by mode over the whole period */
+  totals_by_mode: ModeGameStats[];
+}
+
+/** Request to add a member, or change a member's role */
+export interface AddOrgMemberRequest {
+  role?: OrgMemberRole;
+  /** User to add */
+  user_id: string;
+}
+
+/** Dashboard statistics response */
+export interface AdminStatsResponse {
+  /** Number of active (playable) locations */
+  active_locations: number;
+  /** Location counts by review status */
+  by_review_status: Record<string, number>;
+  /** Location counts by source */
+  by_source: Record<string, number>;
+  /** Location counts by validation status */
+  by_status: Record<string, number>;
+  /** Number of locations pending review */
+  pending_review: number;
+  /** Number of reports in the last 7 days */
+  recent_reports: number;
+  /** Total number of locations in the database */
+  total_locations: number;
+}
+
+/** Query parameters for daily analytics */
+export interface AnalyticsParams {
+  /** Days of history to include (default 30, max 365) */
+  days?: number;
+}
+
+/** An admin action */
+export interface AuditLogItem {
+  /** What they did */
+  action: string;
+  /** Admin who acted */
+  actor_id?: string | null;
+  /** When it happened */
+  created_at: string;
+  /** Action details */
+  details: Record<string, unknown>;
+  /** Entry ID */
+  id: number;
+  /** User the action was about */
+  target_user_id?: string | null;
+}
+
+/** Query parameters for the audit log */
+export interface AuditLogParams {
+  /** Page number (1-based) */
+  page?: number;
+  /** Items per page (max 100) */
+  per_page?: number;
+  /** Only entries about this user */
+  user_id?: string | null;
+}
+
+/** Audit log page */
+export interface AuditLogResponse {
+  /** Entries, newest first */
+  entries: AuditLogItem[];
+  /** Current page */
+  page: number;
+  /** Items per page */
+  per_page: number;
+}
+
+/** Response cache lifetime */
+export interface CacheTtl {
+  /** Entries younger than this are served without a refresh */
+  fresh_secs: number;
+  /** How much longer stale entries are served while being refreshed */
+  stale_secs: number;
+}
+
+/** A chat message in a game transcript */
+export interface ChatTranscriptMessage {
+  /** Text as other players saw it */
+  content: string;
+  /** Sender's current display name */
+  display_name: string;
+  /** Message ID, increasing in send order */
+  id: number;
+  /** Text before the chat filter masked it, if it did */
+  original_content?: string | null;
+  /** When the message was sent */
+  sent_at: string;
+  /** Sender user ID */
+  user_id: string;
+}
+
+/** Request to claim a batch of locations from the review queue */
+export interface ClaimReviewBatchRequest {
+  /** How many locations to claim (default 20) */
+  count?: number | null;
+}
+
+/** Locations claimed for review */
+export interface ClaimReviewBatchResponse {
+  /** Locations the reviewer now has claimed, including earlier claims */
+  active_claims: number;
+  /** When the new claims lapse; `None` if nothing was claimed */
+  expires_at?: string | null;
+  /** Newly claimed location IDs, in queue order */
+  location_ids: string[];
+}
+
+/**
+ * Server to teacher: a student's guess in a classroom game, sent as soon
+ * as it is made
+ */
+export interface ClassroomGuessPayload {
+  /** Display name of the student */
+  display_name: string;
+  /** Distance from correct location in meters */
+  distance_meters: number;
+  /** Guessed latitude */
+  lat: number;
+  /** Guessed longitude */
+  lng: number;
+  /** Round the guess was made in */
+  round_number: number;
+  /** Score for this guess */
+  score: number;
+  /** How long the student took to guess, in milliseconds */
+  time_taken_ms?: number | null;
+  /** User ID of the student (e.g., usr_V1StGXR8_Z5j) */
+  user_id: string;
+}
+
+/** Guesses by continent response */
+export interface ContinentAnalyticsResponse {
+  /** Continents, most guessed first */
+  continents: ContinentGuessStats[];
+}
+
+/** Guesses on rounds in one continent over a period */
+export interface ContinentGuessStats {
+  /** Average guess distance in km */
+  avg_distance_km: number;
+  /** Average guess score */
+  avg_score: number;
+  /** Continent, or "unknown" for country codes without metadata */
+  continent: string;
+  /** Countries in the continent, most guessed first */
+  countries: CountryGuessStats[];
+  /** Guesses submitted */
+  guesses: number;
+}
+
+/** Guesses on rounds in one country over a period */
+export interface CountryGuessStats {
+  /** Average guess distance in km */
+  avg_distance_km: number;
+  /** Average guess score */
+  avg_score: number;
+  /** ISO 3166-1 alpha-2 code of the round location */
+  country_code: string;
+  /** Guesses submitted */
+  guesses: number;
+}
+
+/** Create game request */
+export interface CreateGameRequest {
+  /** Allow movement in Street View */
+  allow_move?: boolean | null;
+  /** Allow panning/rotation */
+  allow_pan?: boolean | null;
+  /** Allow zooming */
+  allow_zoom?: boolean | null;
+  /** Map/region identifier */
+  map_id?: string | null;
+  /** Number of rounds (1-20) */
+  rounds?: number | null;
+  /** Time limit per round in seconds (0 = unlimited, max 600) */
+  time_limit?: number | null;
+}
+
+/** Add a note to a location */
+export interface CreateLocationNoteRequest {
+  /** Note text (1-2000 characters) */
+  body: string;
+  /** Tags (max 10); trimmed, lowercased and spaces replaced with `-` */
+  tags?: string[];
+}
+
+/** Request to create an organization */
+export interface CreateOrganizationRequest {
+  /** User to make the organization's first admin */
+  admin_user_id?: string | null;
+  /** Custom domain to serve the organization from */
+  domain?: string | null;
+  /** Display name (3-100 characters) */
+  name: string;
+  /** Subdomain (lowercase letters, digits, and hyphens) */
+  slug: string;
+}
+
+/** Client request to create a party */
+export interface CreatePartyPayload {
+  settings?: null | GameSettingsPayload;
+}
+
+/** Report a player */
+export interface CreatePlayerReportRequest {
+  /** "cheating", "abusive_name", "abusive_chat", "griefing" or "other" */
+  category: string;
+  /** The offending chat messages (required for abusive chat, max 500 characters) */
+  chat_excerpt?: string | null;
+  /** Game it happened in (required for cheating and griefing) */
+  game_id?: string | null;
+  /** What happened (required for "other", max 1000 characters) */
+  notes?: string | null;
+  /** Player being reported */
+  user_id: string;
+}
+
+/** Request to create a system map */
+export interface CreateSystemMapRequest {
+  /** Description (max 500 characters) */
+  description?: string | null;
+  /** Make this the default map */
+  is_default?: boolean;
+  /** Display name (3-100 characters) */
+  name: string;
+  /** Location selection rules; omitted rules match every location */
+  rules?: Record<string, unknown> | null;
+  /** URL-friendly slug (lowercase letters, digits, and hyphens) */
+  slug: string;
+}
+
+/** Request a map tile session */
+export interface CreateTileSessionRequest {
+  /** "streetview", "roadmap" or "satellite" */
+  map_type: string;
+}
+
+/** Current user info (includes private data) */
+export interface CurrentUser {
+  /** Avatar URL */
+  avatar_url?: string | null;
+  /** Account creation timestamp */
+  created_at: string;
+  /** Display name */
+  display_name: string;
+  /** Email address (only for authenticated users) */
+  email?: string | null;
+  /** User ID (e.g., usr_V1StGXR8_Z5j) */
+  id: string;
+  /** Whether the user is a guest */
+  is_guest: boolean;
+  /** Unique username (e.g., coolplayer42) */
+  username?: string | null;
+}
+
+/** Player and game activity for one day */
+export interface DailyActivityPoint {
+  /** Users who submitted at least one guess */
+  active_users: number;
+  /** Day (UTC) */
+  date: string;
+  /** Games by mode */
+  games: ModeGameStats[];
+  /** Guesses submitted */
+  guesses: number;
+  /** Accounts created, guests included */
+  new_users: number;
+}
+
+/** Request to accept or dismiss a re-approval proposal */
+export interface DecideReapprovalRequest {
+  /** `true` reactivates and approves the location; `false` leaves it inactive */
+  accept: boolean;
+}
+
+/** Error payload */
+export interface ErrorPayload {
+  /** Error code */
+  code: string;
+  /** Human-readable error message */
+  message: string;
+}
+
+/** Featured map schedule response */
+export interface FeaturedMapScheduleResponse {
+  /**
+   * Pending and running features plus those finished in the last 30 days,
+   * by start time
+   */
+  features: ScheduledFeatureItem[];
+}
+
+/** Final standing for a player */
+export interface FinalStanding {
+  /** Display name */
+  display_name: string;
+  /**
+   * First round the player could play, if they joined after the game
+   * started; earlier rounds scored 0
+   */
+  joined_round?: number | null;
+  /** Rank (1 = first place) */
+  rank: number;
+  /** Number of countries named correctly in a row (streak games only) */
+  streak?: number | null;
+  /** Total score */
+  total_score: number;
+  /** User ID (e.g., usr_V1StGXR8_Z5j) */
+  user_id: string;
+}
+
+/** Request to end a running game */
+export interface ForceEndGameRequest {
+  /** Shown to players in the room and kept in the audit log */
+  reason?: string | null;
+}
+
+/** Result of ending a running game */
+export interface ForceEndGameResponse {
+  /** False if the game had already ended */
+  ended: boolean;
+  game_id: string;
+}
+
+/** Request to rename a user */
+export interface ForceRenameRequest {
+  /** New display name; a generic "Player XXXX" name when absent */
+  display_name?: string | null;
+  /** Lock (true) or unlock (false) the profile against edits; unchanged when absent */
+  locked?: boolean | null;
+}
+
+/** Response after renaming a user */
+export interface ForceRenameResponse {
+  /** The user's display name now */
+  display_name: string;
+  /** Whether the profile is now locked */
+  locked: boolean;
+  /** The renamed user */
+  user_id: string;
+}
+
+/** Client request to invite a friend to a party or game lobby */
+export interface FriendInvitePayload {
+  /** Party ID (pty_...) or game ID (gam_...) to invite them to */
+  lobby_id: string;
+  /** Friend's user ID */
+  user_id: string;
+}
+
+/** Server: your invite was delivered */
+export interface FriendInviteSentPayload {
+  /** Invited friend's user ID */
+  user_id: string;
+}
+
+/** Server: a friend invited you to their lobby */
+export interface FriendInvitedPayload {
+  /** Inviting friend's display name */
+  from_display_name: string;
+  /** Inviting friend's user ID */
+  from_user_id: string;
+  /** Join code of the lobby */
+  join_code: string;
+  /** Party or game ID */
+  lobby_id: string;
+  /** Whether the lobby is a party or a game */
+  lobby_kind: LobbyKind;
+}
+
+/** A friend */
+export type FriendItem = PresenceInfo & {
+  /** Avatar URL */
+  avatar_url?: string | null;
+  /** Display name */
+  display_name: string;
+  /**
+   * Whether the friend is connected to the realtime server (and shares
+   * their presence)
+   */
+  online: boolean;
+  /** When the friendship started */
+  since: string;
+  /** User ID */
+  user_id: string;
+  /** Username */
+  username?: string | null;
+};
+
+/**
+ * Server broadcast to friends: a user came online, went offline, or
+ * joined or left a lobby or game
+ */
+export type FriendPresencePayload = PresenceInfo & {
+  /** Whether the friend is now online */
+  online: boolean;
+  /** Friend's user ID */
+  user_id: string;
+};
+
+/** A pending friend request */
+export interface FriendRequestItem {
+  /** The other user's avatar URL */
+  avatar_url?: string | null;
+  /** The other user's display name */
+  display_name: string;
+  /** When the request was sent */
+  sent_at: string;
+  /** The other user's ID */
+  user_id: string;
+  /** The other user's username */
+  username?: string | null;
+}
+
+/** Server: a friend request was received or accepted */
+export interface FriendRequestPayload {
+  /** The other user's avatar URL */
+  avatar_url?: string | null;
+  /** The other user's display name */
+  display_name: string;
+  /** The other user's ID */
+  user_id: string;
+}
+
+/** Pending friend requests response */
+export interface FriendRequestsResponse {
+  /** Requests other users sent to you, newest first */
+  incoming: FriendRequestItem[];
+  /** Requests you sent, newest first */
+  outgoing: FriendRequestItem[];
+}
+
+/** Friend list response */
+export interface FriendsListResponse {
+  /** Friends, ordered by display name */
+  friends: FriendItem[];
+}
+
+/** State of a friendship after a request */
+export type FriendshipStatus = "pending" | "accepted";
+
+/** Game abandoned payload (all players disconnected for too long) */
+export interface GameAbandonedPayload {
+  /** Game ID (e.g., gam_FybH2oF9Xaw8) */
+  game_id: string;
+  /** Reason for abandonment */
+  reason: string;
+}
+
+/** Chat sent in a game's party while the game ran */
+export interface GameChatTranscriptResponse {
+  /** Game ID */
+  game_id: string;
+  /** Messages, oldest first. Messages older than the retention window are gone. */
+  messages: ChatTranscriptMessage[];
+  /** Party the chat belongs to (absent for games without a party) */
+  party_id?: string | null;
+}
+
+/** Server broadcast: game ended */
+export interface GameEndPayload {
+  /** Final standings for all players */
+  final_standings: FinalStanding[];
+  /** Game ID (e.g., gam_FybH2oF9Xaw8) */
+  game_id: string;
+}
+
+/** Game info response */
+export interface GameInfo {
+  /** Join code for multiplayer games */
+  code?: string | null;
+  /** Host user ID (e.g., usr_V1StGXR8_Z5j) */
+  host_id: string;
+  /** Game ID (e.g., gam_FybH2oF9Xaw8) */
+  id: string;
+  /** Number of players currently in the game */
+  player_count: number;
+  /** Game settings */
+  settings: GameSettingsResponse;
+  /** Game status: lobby, active, finished, abandoned */
+  status: string;
+}
+
+/** Game list response */
+export interface GameListResponse {
+  /** List of games */
+  games: GameInfo[];
+}
+
+/**
+ * Game paused payload (host disconnected mid-game, or the teacher paused
+ * a classroom game)
+ */
+export interface GamePausedPayload {
+  /**
+   * How long the game waits for the host before resuming, in
+   * milliseconds; absent when paused until the teacher resumes
+   */
+  grace_period_ms?: number | null;
+  /** User ID of the host (e.g., usr_V1StGXR8_Z5j) */
+  user_id: string;
+}
+
+/** Game result response */
+export interface GameResultResponse {
+  /** Game ID (e.g., gam_FybH2oF9Xaw8) */
+  game_id: string;
+  /** Round-by-round results */
+  rounds: RoundResultResponse[];
+  /** Final standings */
+  standings: PlayerGameStats[];
+}
+
+/** Game resumed payload */
+export interface GameResumedPayload {
+  /**
+   * How long the game was paused in milliseconds; round timers are
+   * extended by this much
+   */
+  paused_ms: number;
+}
+
+/** Game settings payload for socket events */
+export interface GameSettingsPayload {
+  /** Whether players can join after the game has started */
+  allow_late_join?: boolean;
+  /**
+   * Classroom game: the host is a teacher who sees guesses live and
+   * can pause, but does not play
+   */
+  classroom?: boolean;
+  /**
+   * What happens when a player disconnects: remove_player,
+   * keep_scoring_zero or pause_if_host
+   */
+  disconnect_policy?: string;
+  /** Score points each hint costs */
+  hint_cost?: number;
+  /** Whether players can take hints during a round */
+  hints_enabled?: boolean;
+  /** Map/region identifier */
+  map_id: string;
+  /** Whether players can move in Street View */
+  movement_allowed: boolean;
+  /** Whether players can send emoji reactions */
+  reactions_enabled?: boolean;
+  /** Seconds a disconnected player has to reconnect */
+  reconnect_grace_seconds?: number;
+  /** Whether rotation/compass is allowed */
+  rotation_allowed: boolean;
+  /** Number of rounds in the game */
+  rounds: number;
+  /**
+   * Maps the rounds are played on, in round order (empty = every round
+   * on `map_id`)
+   */
+  rounds_plan?: MapRoundsPayload[];
+  /** Time-attack budget shared by all rounds in seconds (0 = off) */
+  time_budget_seconds?: number;
+  /** Time limit per round in seconds (0 = unlimited) */
+  time_limit_seconds: number;
+  /** Whether zoom is allowed */
+  zoom_allowed: boolean;
+}
+
+/** Game settings response */
+export interface GameSettingsResponse {
+  /** Map/region identifier */
+  map_id: string;
+  /** Movement allowed */
+  movement_allowed: boolean;
+  /** Rotation allowed */
+  rotation_allowed: boolean;
+  /** Number of rounds */
+  rounds: number;
+  /** Time limit per round in seconds */
+  time_limit_seconds: number;
+  /** Zoom allowed */
+  zoom_allowed: boolean;
+}
+
+/** Full game state (sent when player joins) */
+export interface GameStatePayload {
+  /** Current round number (0 if in lobby) */
+  current_round: number;
+  /** Game ID (e.g., gam_FybH2oF9Xaw8) */
+  game_id: string;
+  /** User ID of the game creator/host */
+  host_id: string;
+  location?: null | RoundLocation;
+  /** Unix timestamp (ms) when the next round will auto-start (if between rounds) */
+  next_round_at?: number | null;
+  /** All players in the game */
+  players: PlayerInfo[];
+  /** Game settings */
+  settings: GameSettingsPayload;
+  /** User IDs who have voted to skip (for reconnecting clients) */
+  skip_vote_user_ids?: string[] | null;
+  skip_votes?: null | SkipVoteUpdatePayload;
+  /** Current game status */
+  status: string;
+  /** Time remaining in milliseconds (if timed) */
+  time_remaining_ms?: number | null;
+  /** Total rounds */
+  total_rounds: number;
+}
+
+/** Post-game breakdown of every round for every player */
+export interface GameSummaryResponse {
+  /** Game ID (e.g., gam_FybH2oF9Xaw8) */
+  game_id: string;
+  /** Standout moments of the game */
+  highlights: SummaryHighlights;
+  /** Players, best first */
+  players: PlayerGameStats[];
+  /** Rounds in order, with each player's guess */
+  rounds: SummaryRound[];
+}
+
+/**
+ * Server broadcast: an in-flight game transition was cleared/cancelled.
+ *
+ * Emitted when the server-side work following a `game:transitioning` broadcast
+ * fails (e.g. a DB write error). Clients clear their loading UI and may show
+ * a recoverable error; the authoritative game state is unchanged from before
+ * the transition broadcast.
+ */
+export interface GameTransitionClearedPayload {
+  /** Which transition was cleared (matches the phase we previously emitted). */
+  phase: TransitionPhase;
+  /** Optional short code describing why it was cleared (e.g. `"start_failed"`). */
+  reason?: string | null;
+}
+
+/**
+ * Server broadcast: the game is transitioning between phases.
+ *
+ * Emitted to every client in the game room so all participants can render
+ * a loading state while the server performs the (potentially slow) work of
+ * advancing the game. The subsequent `round:start` or `game:end` event
+ * supersedes this state.
+ */
+export interface GameTransitioningPayload {
+  /**
+   * User that triggered the transition, if any.
+   * `None` means the server triggered it (e.g. between-rounds timer expired).
+   */
+  initiated_by?: string | null;
+  /** What kind of transition is happening. */
+  phase: TransitionPhase;
+}
+
+/** Historical guesses on a location by all players */
+export interface GlobalGuessStats {
+  /** Number of past guesses aggregated */
+  guesses: number;
+  /** Median distance of past guesses in meters */
+  median_distance_meters: number;
+}
+
+/** Guess distribution response */
+export interface GuessAnalyticsResponse {
+  /** Daily distributions, oldest first */
+  daily: GuessDistributionPoint[];
+}
+
+/** Guess distribution for one day */
+export interface GuessDistributionPoint {
+  /** Day (UTC) */
+  date: string;
+  distance_km?: null | Percentiles;
+  /** Guesses submitted */
+  guesses: number;
+  score?: null | Percentiles;
+}
+
+/** A guess singled out in a game summary */
+export interface GuessHighlight {
+  /** Display name */
+  display_name: string;
+  /** Distance from correct location in meters */
+  distance_meters: number;
+  /** Round the guess was made in */
+  round_number: number;
+  /** Score awarded */
+  score: number;
+  /** User ID (e.g., usr_V1StGXR8_Z5j) */
+  user_id: string;
+}
+
+/** Individual guess result */
+export interface GuessResult {
+  /** Distance from correct location in meters */
+  distance_meters: number;
+  /** Guessed latitude */
+  guess_lat: number;
+  /** Guessed longitude */
+  guess_lng: number;
+  /** Score awarded */
+  score: number;
+  /** User ID (e.g., usr_V1StGXR8_Z5j) */
+  user_id: string;
+}
+
+/** Guest session creation response */
+export interface GuestSessionResponse {
+  /** Display name for the guest */
+  display_name: string;
+  /** User ID for the created guest (e.g., usr_V1StGXR8_Z5j) */
+  user_id: string;
+}
+
+/** Handicap updated payload (broadcast to all players in lobby) */
+export interface HandicapUpdatedPayload {
+  /** Game ID (e.g., gam_FybH2oF9Xaw8) */
+  game_id: string;
+  /** New multiplier applied to the player's round scores */
+  handicap: number;
+  /** User ID of the player (e.g., usr_V1StGXR8_Z5j) */
+  user_id: string;
+}
+
+/** Summary of one health check run */
+export interface HealthCheckRunItem {
+  /** Locations checked */
+  checked: number;
+  /** Panoramas that are gone (location deactivated) */
+  dead: number;
+  /** Lookups that failed and will be retried */
+  errors: number;
+  /** When the run finished */
+  finished_at: string;
+  /** Panoramas that still exist */
+  healthy: number;
+  /** Run ID */
+  id: string;
+  /** Inactive locations found alive again and proposed for re-approval */
+  revived: number;
+  /** When the run started */
+  started_at: string;
+}
+
+/** Health check totals for one day */
+export interface HealthTrendPoint {
+  /** Locations checked */
+  checked: number;
+  /** Day (UTC) */
+  date: string;
+  /** Panoramas that are gone */
+  dead: number;
+  /** Lookups that failed */
+  errors: number;
+  /** Panoramas that still exist */
+  healthy: number;
+  /** Inactive locations proposed for re-approval */
+  revived: number;
+  /** Number of runs that day */
+  runs: number;
+}
+
+/** Server to player: a hint revealed for the current round */
+export interface HintRevealedPayload {
+  /** Points taken off this round's score (0 when the hint was already taken) */
+  cost: number;
+  /** Hint kind (continent, country_letter, direction) */
+  kind: string;
+  /** Round the hint is for */
+  round_number: number;
+  /** What the hint reveals: a continent ID, a letter or a compass point */
+  value: string;
+}
+
+/** An impersonation session that was started */
+export interface ImpersonationResponse {
+  /** When the session expires */
+  expires_at: string;
+  /** Whether only reads are allowed */
+  read_only: boolean;
+  /** Impersonated user */
+  user_id: string;
+}
+
+/** Join code rotated payload (broadcast to all players in lobby) */
+export interface JoinCodeRotatedPayload {
+  /** Game ID (e.g., gam_FybH2oF9Xaw8) */
+  game_id: string;
+  /** New join code */
+  join_code: string;
+}
+
+/** Client request to join a game */
+export interface JoinGamePayload {
+  /** Game ID to join (e.g., gam_FybH2oF9Xaw8) */
+  game_id: string;
+}
+
+/** Join game by code request */
+export interface JoinGameRequest {
+  /** Join code (4-8 alphanumeric characters) */
+  code: string;
+}
+
+/** Client request to join a party */
+export interface JoinPartyPayload {
+  /** Party ID */
+  party_id: string;
+}
+
+/** Single entry in the leaderboard */
+export interface LeaderboardEntry {
+  /** Avatar URL (null when anonymized) */
+  avatar_url?: string | null;
+  /** Display name (shows "Anonymous Player" when anonymized) */
+  display_name: string;
+  /** Number of games played (for context) */
+  games_played: number;
+  /** Whether this entry has been anonymized due to privacy settings */
+  is_anonymous?: boolean;
+  /** Whether this entry is the current authenticated user */
+  is_current_user: boolean;
+  /** Rank on the leaderboard (1-indexed) */
+  rank: number;
+  /** Score value (context-dependent based on leaderboard type) */
+  score: number;
+  /** User ID (e.g., usr_V1StGXR8_Z5j). Empty string when anonymized. */
+  user_id: string;
+}
+
+/** Game mode a leaderboard is limited to */
+export type LeaderboardMode = "solo" | "multiplayer" | "challenge";
+
+/** Leaderboard query parameters */
+export interface LeaderboardQuery {
+  /** Maximum entries to return (default: 50, max: 100) */
+  limit?: number;
+  /** Only count games on this map (default: all maps) */
+  map_id?: string | null;
+  mode?: null | LeaderboardMode;
+  /** Offset for pagination (default: 0) */
+  offset?: number;
+  /** Time period (default: all_time) */
+  period?: TimePeriod;
+  /** Type of leaderboard (default: total_score) */
+  type?: LeaderboardType;
+}
+
+/** Leaderboard response */
+export interface LeaderboardResponse {
+  /** Current user's rank (if authenticated and on leaderboard) */
+  current_user_rank?: number | null;
+  /** Current user's score (if authenticated and on leaderboard) */
+  current_user_score?: number | null;
+  /** Leaderboard entries */
+  entries: LeaderboardEntry[];
+  /** Type of leaderboard */
+  leaderboard_type: LeaderboardType;
+  /** Time period */
+  time_period: TimePeriod;
+  /** Total number of ranked players */
+  total_players: number;
+}
+
+/** Leaderboard type */
+export type LeaderboardType = "total_score" | "best_game" | "games_played" | "average_score" | "streak";
+
+/** A game a realtime server is running */
+export interface LiveGameItem {
+  connected_players: number;
+  /** Game ID */
+  game_id: string;
+  /** Paused by a disconnected host or a teacher */
+  paused: boolean;
+  players: number;
+  /** When the current round's time runs out */
+  round_ends_at?: string | null;
+  round_number: number;
+  /** Actor phase (lobby, active, round_in_progress, between_rounds, finished) */
+  status: string;
+  total_rounds: number;
+}
+
+/** Snapshot of a running game as last saved by its actor */
+export interface LiveGameStateResponse {
+  /** When the between-rounds wait ends */
+  between_rounds_ends_at?: string | null;
+  current_round?: null | LiveRoundState;
+  game_id: string;
+  /** When the game was paused for a disconnected host */
+  paused_at?: string | null;
+  players: LivePlayerState[];
+  rerolls_used: number;
+  round_number: number;
+  /** Game settings */
+  settings: Record<string, unknown>;
+  /** Players who voted to skip the between-rounds wait */
+  skip_votes: string[];
+  status: string;
+  teacher_paused: boolean;
+  /** When a time-attack game's time budget runs out */
+  time_budget_ends_at?: string | null;
+  total_rounds: number;
+}
+
+/** Games running on the realtime servers */
+export interface LiveGamesResponse {
+  games: LiveGameItem[];
+}
+
+/** A player in a running game */
+export interface LivePlayerState {
+  connected: boolean;
+  /** When the player's reconnect grace period started */
+  disconnected_at?: string | null;
+  display_name: string;
+  is_host: boolean;
+  /** Median of the player's recent socket round-trip times */
+  rtt_ms?: number | null;
+  /** Recent round-trip times, oldest first */
+  rtt_samples: number[];
+  total_score: number;
+  user_id: string;
+}
+
+/** The round in progress in a running game */
+export interface LiveRoundState {
+  /** When the round's time runs out (absent without a time limit) */
+  ends_at?: string | null;
+  /** Players who have guessed */
+  guessed: string[];
+  location_id?: string | null;
+  round_id: string;
+  round_number: number;
+  started_at?: string | null;
+  time_limit_ms?: number | null;
+}
+
+/** Kind of lobby a friend was invited to */
+export type LobbyKind = "party" | "game";
+
+/** Full location details for admin review */
+export interface LocationDetailResponse {
+  /** Whether the location is active */
+  active: boolean;
+  /** Number of arrows */
+  arrow_count?: number | null;
+  /** Building count within 100m */
+  buildings_100?: number | null;
+  /** Capture date */
+  capture_date?: string | null;
+  /** Country code */
+  country_code?: string | null;
+  /** When created */
+  created_at: string;
+  /** Difficulty rating (0-100) from past guesses; absent until rated */
+  difficulty?: number | null;
+  /** Elevation in meters */
+  elevation?: number | null;
+  /** Failure count */
+  failure_count: number;
+  /** Default heading */
+  heading?: number | null;
+  /** Location ID */
+  id: string;
+  /** Is scout/trekker */
+  is_scout: boolean;
+  /** Last failure reason */
+  last_failure_reason?: string | null;
+  /** Latitude */
+  lat: number;
+  /** Longitude */
+  lng: number;
+  /** Median distance of past guesses in meters */
+  median_guess_distance_meters?: number | null;
+  /** Median score of past guesses */
+  median_guess_score?: number | null;
+  /** Internal notes on the location, newest first */
+  notes: LocationNoteItem[];
+  /** Panorama ID */
+  panorama_id: string;
+  /** Provider */
+  provider: string;
+  /** Reports for this location */
+  reports: LocationReportItem[];
+  /** Review status */
+  review_status: string;
+  /** When reviewed */
+  reviewed_at?: string | null;
+  /** Reviewed by user ID */
+  reviewed_by?: string | null;
+  /** Road count within 100m */
+  roads_100?: number | null;
+  /** Location source */
+  source: string;
+  /** Subdivision code */
+  subdivision_code?: string | null;
+  /** Surface type */
+  surface?: string | null;
+  /** Validation status */
+  validation_status: string;
+}
+
+/** Query parameters for location health trends */
+export interface LocationHealthParams {
+  /** Days of history to include (default 30, max 90) */
+  days?: number;
+}
+
+/** Location coverage health response */
+export interface LocationHealthResponse {
+  /** Daily totals, oldest first */
+  daily: HealthTrendPoint[];
+  last_run?: null | HealthCheckRunItem;
+}
+
+/** An internal note on a location, written by an admin or a map curator */
+export interface LocationNoteItem {
+  /** Who wrote it (absent if their account was deleted) */
+  author_id?: string | null;
+  /** Author's display name */
+  author_name?: string | null;
+  /** Note text */
+  body: string;
+  /** When the note was written */
+  created_at: string;
+  /** Note ID */
+  id: number;
+  /** Location the note is on */
+  location_id: string;
+  /** Map a curator wrote the note from; absent for admin notes */
+  map_id?: string | null;
+  /** Normalized tags */
+  tags: string[];
+}
+
+/** Query parameters for searching location notes */
+export interface LocationNotesParams {
+  /** Only notes by this user */
+  author_id?: string | null;
+  /** Only notes on this location */
+  location_id?: string | null;
+  /** Page number (1-based) */
+  page?: number;
+  /** Items per page (max 100) */
+  per_page?: number;
+  /** Text the note body contains */
+  q?: string | null;
+  /** Tag the note has */
+  tag?: string | null;
+}
+
+/** Location notes page */
+export interface LocationNotesResponse {
+  /** Notes, newest first */
+  notes: LocationNoteItem[];
+  /** Current page */
+  page: number;
+  /** Items per page */
+  per_page: number;
+  /** Total matching notes */
+  total: number;
+  /** Total number of pages */
+  total_pages: number;
+}
+
+/** A report for a location */
+export interface LocationReportItem {
+  /** When the report was created */
+  created_at: string;
+  /** Report ID */
+  id: string;
+  /** Location ID */
+  location_id: string;
+  /** Additional notes */
+  notes?: string | null;
+  /** Report reason */
+  reason: string;
+  /** User ID who submitted the report (if any) */
+  user_id?: string | null;
+}
+
+/** A report with location summary */
+export interface LocationReportWithLocation {
+  /** Country code */
+  country_code?: string | null;
+  /** When the report was created */
+  created_at: string;
+  /** Report ID */
+  id: string;
+  /** Location latitude */
+  lat: number;
+  /** Location longitude */
+  lng: number;
+  /** Location ID */
+  location_id: string;
+  /** Current location review status */
+  location_review_status: string;
+  /** Additional notes */
+  notes?: string | null;
+  /** Panorama ID for preview */
+  panorama_id: string;
+  /** Report reason */
+  reason: string;
+  /** User ID who submitted the report (if any) */
+  user_id?: string | null;
+}
+
+/** Logout response */
+export interface LogoutResponse {
+  /** Confirmation message */
+  message: string;
+}
+
+/** A run of consecutive rounds played on one map */
+export interface MapRoundsPayload {
+  /** Map/region identifier */
+  map_id: string;
+  /** Number of consecutive rounds played on the map */
+  rounds: number;
+}
+
+/** Map tiles served to one user on one day */
+export interface MapTileUsageEntry {
+  display_name: string;
+  /** "streetview", "roadmap" or "satellite" */
+  map_type: string;
+  tiles: number;
+  user_id: string;
+}
+
+/** Map tile usage per user for a day, for billing attribution */
+export interface MapTileUsageResponse {
+  date: string;
+  /** Tiles served to all users */
+  total_tiles: number;
+  /** Usage per user and map type, most tiles first */
+  users: MapTileUsageEntry[];
+}
+
+/** Current user response */
+export interface MeResponse {
+  /** Avatar URL */
+  avatar_url?: string | null;
+  /** Display name */
+  display_name: string;
+  /** User ID (e.g., usr_V1StGXR8_Z5j) */
+  id: string;
+  /** Whether the user is a guest */
+  is_guest: boolean;
+}
+
+/** Games of one mode over a period */
+export interface ModeGameStats {
+  /** Average start-to-end time of finished games, in seconds */
+  avg_duration_secs?: number | null;
+  /** Games created */
+  created: number;
+  /** Games finished */
+  finished: number;
+  /** Game mode */
+  mode: string;
+}
+
+/** Request to reset parts of a user's profile and optionally lock it */
+export interface ModerateProfileRequest {
+  /** Lock (true) or unlock (false) the profile against edits; unchanged when absent */
+  locked?: boolean | null;
+  /** Remove the avatar (uploaded images are deleted from storage) */
+  reset_avatar?: boolean;
+  /** Clear the bio */
+  reset_bio?: boolean;
+  /** Clear the profile color */
+  reset_color?: boolean;
+  /** Clear the home country */
+  reset_country?: boolean;
+  /** Replace the display name with a generic one */
+  reset_display_name?: boolean;
+}
+
+/** Response after moderating a profile */
+export interface ModerateProfileResponse {
+  /** Whether the profile is now locked */
+  locked: boolean;
+  /** Success message */
+  message: string;
+  /** The moderated user */
+  user_id: string;
+}
+
+/** A display name flagged for review */
+export interface NameFlagItem {
+  /** When the name was flagged */
+  created_at: string;
+  /** The user's display name now */
+  current_display_name: string;
+  /** The name that was flagged */
+  display_name: string;
+  /** Flag ID */
+  id: string;
+  /** "impersonation", "link", "confusables" or "reported" */
+  reason: string;
+  /** "pending", "dismissed" or "renamed" */
+  status: string;
+  /** Flagged user */
+  user_id: string;
+}
+
+/** Query parameters for flagged display names */
+export interface NameFlagsParams {
+  /** Page number (1-based) */
+  page?: number;
+  /** Items per page (max 100) */
+  per_page?: number;
+}
+
+/** Pending flagged display names, oldest first */
+export interface NameFlagsResponse {
+  /** Flags on this page */
+  flags: NameFlagItem[];
+  /** Current page */
+  page: number;
+  /** Items per page */
+  per_page: number;
+  /** Total pending flags */
+  total: number;
+  /** Total number of pages */
+  total_pages: number;
+}
+
+/** Which push notifications a user receives */
+export interface NotificationPreferencesResponse {
+  /** It is your turn in a challenge */
+  challenge_turns: boolean;
+  /** A new daily challenge is available */
+  daily_challenge: boolean;
+  /** A friend invited you to a lobby */
+  friend_invites: boolean;
+}
+
+/** OAuth callback query parameters */
+export interface OAuthCallbackParams {
+  /** Authorization code from OAuth provider */
+  code: string;
+  /** State parameter for CSRF protection */
+  state: string;
+}
+
+/** OAuth login initiation response */
+export interface OAuthUrlResponse {
+  /** OAuth provider authorization URL */
+  url: string;
+}
+
+/** How an organization's instance looks */
+export interface OrgBranding {
+  /** Secondary color as `#rrggbb` */
+  accent_color?: string | null;
+  /** Logo shown in place of the DGuesser logo */
+  logo_url?: string | null;
+  /** Main color as `#rrggbb` */
+  primary_color?: string | null;
+}
+
+/** A member of an organization */
+export interface OrgMemberItem {
+  avatar_url?: string | null;
+  display_name: string;
+  joined_at: string;
+  role: OrgMemberRole;
+  user_id: string;
+}
+
+/** Role of a member within an organization */
+export type OrgMemberRole = "member" | "admin";
+
+/** Members of an organization */
+export interface OrgMembersResponse {
+  /** Admins first, then by join date */
+  members: OrgMemberItem[];
+}
+
+/** An organization */
+export interface OrganizationInfo {
+  /** Whether the organization can be used */
+  active: boolean;
+  branding: OrgBranding;
+  /** When the organization was created */
+  created_at: string;
+  /** Custom domain the organization is served from */
+  domain?: string | null;
+  /** Organization ID */
+  id: string;
+  /** Display name */
+  name: string;
+  /** Subdomain and `X-Organization` header value */
+  slug: string;
+}
+
+/** All organizations */
+export interface OrganizationsListResponse {
+  /** Inactive organizations included */
+  organizations: OrganizationInfo[];
+}
+
+/** Pack range cache counters for this API instance */
+export interface PackCacheStatsResponse {
+  /** Block reads served from memory */
+  block_hits: number;
+  /** Block reads that missed memory */
+  block_misses: number;
+  /** Blocks currently cached in memory */
+  blocks: number;
+  /** Bytes currently cached in memory */
+  bytes: number;
+  /** Whether the range cache is enabled */
+  enabled: boolean;
+  /** Share of block reads that avoided storage (0.0 - 1.0) */
+  hit_rate: number;
+  /** Country index reads served from memory */
+  index_hits: number;
+  /** Country index reads that went to storage */
+  index_misses: number;
+  /** Memory misses served from the shared Redis tier */
+  redis_hits: number;
+}
+
+/** Server: recent chat messages, sent when a member joins or reconnects */
+export interface PartyChatHistoryPayload {
+  /** Messages, oldest first */
+  messages: PartyChatMessagePayload[];
+}
+
+/** Server broadcast: chat message sent to the party */
+export interface PartyChatMessagePayload {
+  /** Message text */
+  content: string;
+  /** Sender display name */
+  display_name: string;
+  /** Message ID, increasing in send order */
+  id: number;
+  /** Unix timestamp (ms) when the message was sent */
+  sent_at: number;
+  /** Sender user ID */
+  user_id: string;
+}
+
+/** Client request to send a chat message to the party */
+export interface PartyChatPayload {
+  /** Message text (1-500 characters) */
+  content: string;
+  /** Party ID */
+  party_id: string;
+}
+
+/** Server response: party created */
+export interface PartyCreatedPayload {
+  /** Join code for sharing */
+  join_code: string;
+  /** Party ID (e.g., pty_FybH2oF9Xaw8) */
+  party_id: string;
+}
+
+/** Client request to disband a party */
+export interface PartyDisbandPayload {
+  /** Party ID */
+  party_id: string;
+}
+
+/** Server broadcast: party disbanded */
+export interface PartyDisbandedPayload {
+  /** Reason for disbanding */
+  reason: string;
+}
+
+/** Party error payload */
+export interface PartyErrorPayload {
+  code: string;
+  message: string;
+}
+
+/** Server broadcast: game ended, return to party lobby */
+export interface PartyGameEndedPayload {
+  /** Game ID that ended */
+  game_id: string;
+}
+
+/** Server broadcast: game starting from party */
+export interface PartyGameStartingPayload {
+  /** Game ID to navigate to */
+  game_id: string;
+}
+
+/** Server broadcast: party host changed */
+export interface PartyHostChangedPayload {
+  /** New host user ID */
+  new_host_id: string;
+  /** New host display name */
+  new_host_name: string;
+}
+
+/** Client request to kick a member */
+export interface PartyKickPayload {
+  /** Party ID */
+  party_id: string;
+  /** User ID of the member to kick */
+  user_id: string;
+}
+
+/** Server: member was kicked */
+export interface PartyKickedPayload {
+  user_id: string;
+}
+
+/** Member info in party state */
+export interface PartyMemberInfo {
+  /** Avatar URL */
+  avatar_url?: string | null;
+  /** Whether the host muted the member in chat */
+  chat_muted: boolean;
+  /** Whether the member is currently connected */
+  connected: boolean;
+  /** Display name */
+  display_name: string;
+  /** User ID */
+  user_id: string;
+}
+
+/** Server broadcast: member joined party */
+export interface PartyMemberJoinedPayload {
+  member: PartyMemberInfo;
+}
+
+/** Server broadcast: member left party */
+export interface PartyMemberLeftPayload {
+  display_name: string;
+  user_id: string;
+}
+
+/** Server broadcast: the host muted or unmuted a member in chat */
+export interface PartyMemberMutedPayload {
+  display_name: string;
+  muted: boolean;
+  user_id: string;
+}
+
+/** Client request to mute or unmute a member in chat (host only) */
+export interface PartyMutePayload {
+  /** `false` to unmute */
+  muted: boolean;
+  /** Party ID */
+  party_id: string;
+  /** User ID of the member to mute */
+  user_id: string;
+}
+
+/** Server broadcast: party settings updated */
+export interface PartySettingsUpdatedPayload {
+  settings: GameSettingsPayload;
+}
+
+/** Client request to start a game from the party */
+export interface PartyStartGamePayload {
+  /** Party ID */
+  party_id: string;
+}
+
+/** Full party state (sent when member joins or reconnects) */
+export interface PartyStatePayload {
+  /** Current game ID (if a game is in progress) */
+  current_game_id?: string | null;
+  /** User ID of the party host */
+  host_id: string;
+  /** Join code */
+  join_code: string;
+  /** All party members */
+  members: PartyMemberInfo[];
+  /** Party ID */
+  party_id: string;
+  /** Party phase: "lobby", "starting", or "in_game" */
+  phase: string;
+  /** Default game settings */
+  settings: GameSettingsPayload;
+}
+
+/** Client request to update party settings */
+export interface PartyUpdateSettingsPayload {
+  /** Party ID */
+  party_id: string;
+  /** Updated settings */
+  settings: GameSettingsPayload;
+}
+
+/** Percentiles of a distribution */
+export interface Percentiles {
+  p10: number;
+  p25: number;
+  p50: number;
+  p75: number;
+  p90: number;
+}
+
+/** Player disconnected payload (grace period started) */
+export interface PlayerDisconnectedPayload {
+  /** Display name */
+  display_name: string;
+  /** Grace period in milliseconds (None = until game ends, player won't be kicked mid-game) */
+  grace_period_ms?: number | null;
+  /** User ID of the player who disconnected (e.g., usr_V1StGXR8_Z5j) */
+  user_id: string;
+}
+
+/** Player stats for a game */
+export interface PlayerGameStats {
+  /** Display name */
+  display_name: string;
+  /** Final rank (1 = first place) */
+  rank?: number | null;
+  /** Total score in the game */
+  score: number;
+  /** Number of countries named correctly in a row (streak games only) */
+  streak?: number | null;
+  /** User ID (e.g., usr_V1StGXR8_Z5j) */
+  user_id: string;
+}
+
+/** Server broadcast: player guessed (without revealing location) */
+export interface PlayerGuessedPayload {
+  /** Display name of the player */
+  display_name: string;
+  /** User ID of the player who guessed (e.g., usr_V1StGXR8_Z5j) */
+  user_id: string;
+}
+
+/** Player info in game state */
+export interface PlayerInfo {
+  /** Avatar URL */
+  avatar_url?: string | null;
+  /** Whether the player is currently connected */
+  connected?: boolean;
+  /** Unix timestamp (ms) when player disconnected (if disconnected) */
+  disconnected_at?: number | null;
+  /** Display name */
+  display_name: string;
+  /** Multiplier applied to the player's round scores */
+  handicap?: number;
+  /** Whether the player has submitted a guess this round */
+  has_guessed: boolean;
+  /** User ID (e.g., usr_V1StGXR8_Z5j) */
+  id: string;
+  /**
+   * First round the player could play, if they joined after the game
+   * started
+   */
+  joined_round?: number | null;
+  /** Current score */
+  score: number;
+}
+
+/** Player joined payload */
+export interface PlayerJoinedPayload {
+  /** Player info */
+  player: PlayerInfo;
+}
+
+/** Player left payload */
+export interface PlayerLeftPayload {
+  /** Display name */
+  display_name: string;
+  /** User ID of the player who left (e.g., usr_V1StGXR8_Z5j) */
+  user_id: string;
+}
+
+/** Player reconnected payload (within grace period) */
+export interface PlayerReconnectedPayload {
+  /** Display name */
+  display_name: string;
+  /** User ID of the player who reconnected (e.g., usr_V1StGXR8_Z5j) */
+  user_id: string;
+}
+
+/** A report about a player */
+export interface PlayerReportItem {
+  /** Report category */
+  category: string;
+  /** Quoted chat */
+  chat_excerpt?: string | null;
+  /** When the report was submitted */
+  created_at: string;
+  /** Game it happened in */
+  game_id?: string | null;
+  /** Report ID */
+  id: string;
+  /** Reporter's notes */
+  notes?: string | null;
+  /** Reported player's display name */
+  reported_name: string;
+  /** Reported player */
+  reported_user_id: string;
+  /** Who reported (absent if their account was deleted) */
+  reporter_id?: string | null;
+  /** Reporter's display name */
+  reporter_name?: string | null;
+  /** Moderator's note on the outcome */
+  resolution_note?: string | null;
+  /** When the status last changed */
+  reviewed_at?: string | null;
+  /** Moderator who last changed the status */
+  reviewed_by?: string | null;
+  /** Other signals against the reported player */
+  signals: ReportedPlayerSignals;
+  /** "open", "reviewing", "actioned" or "dismissed" */
+  status: string;
+}
+
+/** A submitted report */
+export interface PlayerReportResponse {
+  /** When the report was submitted */
+  created_at: string;
+  /** Report ID */
+  id: string;
+  /** "open" until a moderator picks it up */
+  status: string;
+}
+
+/** Query parameters for the player report queue */
+export interface PlayerReportsParams {
+  /** Only reports in this category */
+  category?: string | null;
+  /** Page number (1-based) */
+  page?: number;
+  /** Items per page (max 100) */
+  per_page?: number;
+  /** Only reports in this status ("open", "reviewing", "actioned", "dismissed") */
+  status?: string | null;
+  /** Only reports about this player */
+  user_id?: string | null;
+}
+
+/** Player report queue page */
+export interface PlayerReportsResponse {
+  /** Current page */
+  page: number;
+  /** Items per page */
+  per_page: number;
+  /** Reports, oldest first */
+  reports: PlayerReportItem[];
+  /** Total matching reports */
+  total: number;
+  /** Total number of pages */
+  total_pages: number;
+}
+
+/** Player score info for live scoreboard */
+export interface PlayerScoreInfo {
+  /** Avatar URL */
+  avatar_url?: string | null;
+  /** Whether the player is connected */
+  connected: boolean;
+  /** Display name */
+  display_name: string;
+  /** Multiplier applied to round scores before they are added to the total */
+  handicap?: number;
+  /** Whether the player has guessed this round */
+  has_guessed: boolean;
+  /** Current rank (1 = first place) */
+  rank: number;
+  /** Score from the current round before the handicap (0 if not yet guessed) */
+  round_score: number;
+  /** Total score so far, with the handicap applied */
+  total_score: number;
+  /** User ID (e.g., usr_V1StGXR8_Z5j) */
+  user_id: string;
+}
+
+/** Player timeout payload (grace period expired) */
+export interface PlayerTimeoutPayload {
+  /** Display name */
+  display_name: string;
+  /** User ID of the player who timed out (e.g., usr_V1StGXR8_Z5j) */
+  user_id: string;
+}
+
+/** Presence as shown to other users */
+export interface PresenceInfo {
+  /** Map name while in a lobby or game */
+  map_name?: string | null;
+  /** Game mode while in a lobby or game */
+  mode?: string | null;
+  /** Current status */
+  status: PresenceStatus;
+}
+
+/** What a user is currently doing */
+export type PresenceStatus = "offline" | "online" | "in_lobby" | "in_game";
+
+/** Who may see a user's presence */
+export type PresenceVisibility = "everyone" | "friends" | "nobody";
+
+/** Protocol a server speaks, advertised in its service info */
+export interface ProtocolInfo {
+  /** Capabilities of this build */
+  capabilities: string[];
+  /** Oldest client protocol version accepted */
+  min_version: number;
+  /** Protocol version of this build */
+  version: number;
+}
+
+/** VAPID public key browsers subscribe with */
+export interface PushPublicKeyResponse {
+  /** Pass as `applicationServerKey` to `PushManager.subscribe()` (base64url) */
+  public_key: string;
+}
+
+/** Keys of a browser push subscription */
+export interface PushSubscriptionKeys {
+  /** Auth secret (base64url) */
+  auth: string;
+  /** Browser public key (base64url) */
+  p256dh: string;
+}
+
+/**
+ * Server broadcast: a player answered the warm-up quiz question. The
+ * answer itself is only returned to the player who answered.
+ */
+export interface QuizAnsweredPayload {
+  correct: boolean;
+  /** Points the answer earned */
+  points: number;
+  question_id: string;
+  /** Lobby scores, highest first */
+  scores: QuizScore[];
+  /** Player who answered */
+  user_id: string;
+}
+
+/** Bounding box shown by a `bounds` quiz question */
+export interface QuizBounds {
+  max_lat: number;
+  max_lng: number;
+  min_lat: number;
+  min_lng: number;
+}
+
+/** A country the player can pick in the warm-up quiz */
+export interface QuizChoice {
+  /** ISO 3166-1 alpha-2 code */
+  code: string;
+  /** English country name */
+  name: string;
+}
+
+/** Server broadcast: a warm-up quiz question, without its answer */
+export interface QuizQuestionPayload {
+  bounds?: null | QuizBounds;
+  /** Countries to pick from */
+  choices: QuizChoice[];
+  /** When answers stop being accepted (Unix ms) */
+  expires_at: number;
+  /** Flag emoji, for `flag` questions */
+  flag?: string | null;
+  /** What the question shows (flag, bounds) */
+  prompt: string;
+  /** Question ID, sent back with the answer */
+  question_id: string;
+}
+
+/** A player's warm-up quiz score for the current lobby */
+export interface QuizScore {
+  score: number;
+  user_id: string;
+}
+
+/** Client sending a reaction */
+export interface ReactPayload {
+  /** Game ID */
+  game_id: string;
+  /** One of [`REACTIONS`] */
+  reaction: string;
+}
+
+/** Server broadcast: a player reacted. Reactions are not stored. */
+export interface ReactionPayload {
+  /** One of [`REACTIONS`] */
+  reaction: string;
+  /** Player who reacted */
+  user_id: string;
+}
+
+/** An inactive location whose panorama exists again */
+export interface ReapprovalItem {
+  /** Country code */
+  country_code?: string | null;
+  /** Latitude */
+  lat: number;
+  /** Longitude */
+  lng: number;
+  /** Location ID */
+  location_id: string;
+  /** Panorama ID for preview */
+  panorama_id: string;
+  /** Why the location was deactivated */
+  previous_reason?: string | null;
+  /** Review status when the location was found alive */
+  previous_status?: string | null;
+  /** When the health checker proposed re-approval */
+  proposed_at: string;
+}
+
+/** Query parameters for the re-approval queue */
+export interface ReapprovalQueueParams {
+  /** Page number (1-based) */
+  page?: number;
+  /** Items per page (max 100) */
+  per_page?: number;
+}
+
+/** Paginated re-approval queue response */
+export interface ReapprovalQueueResponse {
+  /** Proposals, oldest first */
+  locations: ReapprovalItem[];
+  /** Current page number */
+  page: number;
+  /** Items per page */
+  per_page: number;
+  /** Total pending proposals */
+  total: number;
+  /** Total number of pages */
+  total_pages: number;
+}
+
+/** Register a push subscription; the shape of `PushSubscription.toJSON()` */
+export interface RegisterPushSubscriptionRequest {
+  /** Push service URL */
+  endpoint: string;
+  keys: PushSubscriptionKeys;
+}
+
+/** Response after releasing the caller's claims */
+export interface ReleaseReviewClaimsResponse {
+  /** Number of claims released */
+  released: number;
+}
+
+/** Request to reload the runtime config */
+export interface ReloadConfigRequest {
+  settings?: null | RuntimeSettings;
+}
+
+/** Runtime config after a reload */
+export interface ReloadConfigResponse {
+  /** Settings that changed on this instance */
+  changed: string[];
+  config: StoredRuntimeSettings;
+}
+
+/** Remove a push subscription */
+export interface RemovePushSubscriptionRequest {
+  /** Push service URL of the subscription */
+  endpoint: string;
+}
+
+/** Other signals against a reported player */
+export interface ReportedPlayerSignals {
+  /** Cheating signals recorded for the player */
+  cheat_signals: number;
+  /** Whether the player's display name is waiting for review */
+  name_flagged: boolean;
+  /** Open reports against the player, this one included */
+  open_reports: number;
+  /** All reports against the player */
+  total_reports: number;
+}
+
+/** Request parameters for the reports list */
+export interface ReportsListParams {
+  /** Filter by location review status */
+  location_status?: string | null;
+  /** Page number (1-indexed) */
+  page?: number;
+  /** Items per page */
+  per_page?: number;
+  /** Filter by reason */
+  reason?: string | null;
+}
+
+/** Paginated reports response */
+export interface ReportsListResponse {
+  /** Current page number */
+  page: number;
+  /** Items per page */
+  per_page: number;
+  /** List of reports */
+  reports: LocationReportWithLocation[];
+  /** Total number of reports */
+  total: number;
+  /** Total number of pages */
+  total_pages: number;
+}
+
+/** Retention cohorts response */
+export interface RetentionAnalyticsResponse {
+  /** Cohorts, oldest first */
+  cohorts: RetentionCohort[];
+}
+
+/** Retention of one weekly signup cohort */
+export interface RetentionCohort {
+  /** Monday of the signup week (UTC) */
+  cohort_week: string;
+  /** Users active in each week since signup; index 0 is the signup week */
+  retained_users: number[];
+  /** `retained_users` as a share of the cohort (0.0 - 1.0) */
+  retention: number[];
+  /** Users who signed up that week */
+  size: number;
+}
+
+/** Query parameters for retention cohorts */
+export interface RetentionParams {
+  /** Weekly signup cohorts to include (default 12, max 52) */
+  weeks?: number;
+}
+
+/** A location in the review queue */
+export interface ReviewQueueItem {
+  /** When the active claim lapses */
+  claim_expires_at?: string | null;
+  /** Reviewer with an active claim on the location */
+  claimed_by?: string | null;
+  /** Display name of the claiming reviewer */
+  claimed_by_name?: string | null;
+  /** Country code */
+  country_code?: string | null;
+  /** When the location was created */
+  created_at: string;
+  /** Number of failure reports */
+  failure_count: number;
+  /** Location ID */
+  id: string;
+  /** Most recent report reason */
+  last_report_reason?: string | null;
+  /** Latitude */
+  lat: number;
+  /** Longitude */
+  lng: number;
+  /** Internal notes on the location, newest first */
+  notes: LocationNoteItem[];
+  /** Panorama ID for preview */
+  panorama_id: string;
+  /** Number of user reports */
+  report_count: number;
+  /** Current review status */
+  review_status: string;
+}
+
+/** Request parameters for the review queue */
+export interface ReviewQueueParams {
+  /** Page number (1-indexed) */
+  page?: number;
+  /** Items per page */
+  per_page?: number;
+  /** Filter by status (pending, flagged, or all) */
+  status?: string | null;
+}
+
+/** Paginated review queue response */
+export interface ReviewQueueResponse {
+  /** List of locations to review */
+  locations: ReviewQueueItem[];
+  /** Current page number */
+  page: number;
+  /** Items per page */
+  per_page: number;
+  /** Total number of items matching the filter */
+  total: number;
+  /** Total number of pages */
+  total_pages: number;
+}
+
+/** Review throughput for one reviewer */
+export interface ReviewerStatsItem {
+  /** Locations approved */
+  approved: number;
+  /** Mean seconds from claim to decision, for claimed locations */
+  avg_review_secs?: number | null;
+  /** Locations flagged for further review */
+  flagged: number;
+  /** Locations currently claimed and not yet decided */
+  in_progress: number;
+  /** Locations rejected */
+  rejected: number;
+  /** Review decisions made in the period */
+  reviewed: number;
+  /** Reviewer user ID */
+  reviewer_id: string;
+  /** Reviewer display name */
+  reviewer_name?: string | null;
+}
+
+/** Query parameters for reviewer throughput */
+export interface ReviewerStatsParams {
+  /** Days of decisions to include (default 7, max 90) */
+  days?: number;
+}
+
+/** Per-reviewer throughput response */
+export interface ReviewerStatsResponse {
+  /** Days of decisions included */
+  days: number;
+  /** Reviewers, busiest first */
+  reviewers: ReviewerStatsItem[];
+}
+
+/** Response after revoking a session */
+export interface RevokeSessionResponse {
+  /** Confirmation message */
+  message: string;
+  /** Number of sessions revoked */
+  revoked_count: number;
+}
+
+/** Server broadcast: round ended with results */
+export interface RoundEndPayload {
+  /** The correct location */
+  correct_location: RoundLocation;
+  global_stats?: null | GlobalGuessStats;
+  /** Unix timestamp (ms) when the next round will auto-start (multiplayer only) */
+  next_round_at?: number | null;
+  /** Results for all players */
+  results: RoundResult[];
+  /** Round number that ended */
+  round_number: number;
+}
+
+/** A round singled out in a game summary */
+export interface RoundHighlight {
+  /** Mean distance of the round's guesses in meters */
+  average_distance_meters: number;
+  /** Round number */
+  round_number: number;
+}
+
+/** Location data for a round */
+export interface RoundLocation {
+  /** Optional heading/direction for Street View panorama (degrees, 0-360) */
+  heading?: number | null;
+  /** Latitude */
+  lat: number;
+  /** Longitude */
+  lng: number;
+  /** Optional Street View panorama ID */
+  panorama_id?: string | null;
+  /** Imagery provider the panorama comes from, which decides the viewer */
+  provider: string;
+}
+
+/**
+ * Server broadcast: the host rerolled the current round. Guesses on the
+ * old location were discarded; a `round:start` with the new location follows.
+ */
+export interface RoundRerolledPayload {
+  /** Players whose guesses were discarded */
+  discarded_guesses: string[];
+  /** Host who rerolled the round */
+  rerolled_by: string;
+  /** Rerolls the host has left in this game */
+  rerolls_left: number;
+  /** Round that was rerolled */
+  round_number: number;
+}
+
+/** Individual player result for a round */
+export interface RoundResult {
+  /** Display name */
+  display_name: string;
+  /** Distance from correct location in meters */
+  distance_meters: number;
+  /** Guessed latitude */
+  guess_lat: number;
+  /** Guessed longitude */
+  guess_lng: number;
+  /** Percentage of past guesses on this location that were further away */
+  percentile?: number | null;
+  /** Score for this round */
+  score: number;
+  /** Cumulative total score */
+  total_score: number;
+  /** User ID (e.g., usr_V1StGXR8_Z5j) */
+  user_id: string;
+}
+
+/** Round result response */
+export interface RoundResultResponse {
+  /** Player guesses */
+  guesses: GuessResult[];
+  /** Correct location latitude */
+  location_lat: number;
+  /** Correct location longitude */
+  location_lng: number;
+  /** Round number */
+  round_number: number;
+}
+
+/** Server broadcast: round started */
+export interface RoundStartPayload {
+  /** Location data for the round */
+  location: RoundLocation;
+  /** Current round number (1-indexed) */
+  round_number: number;
+  /** Unix timestamp (ms) when round started */
+  started_at: number;
+  /** Time limit in milliseconds (None = unlimited) */
+  time_limit_ms?: number | null;
+  /** Total rounds in the game */
+  total_rounds: number;
+}
+
+/**
+ * Operational settings that can be changed without restarting the servers.
+ * Unset fields keep the value the server was started with.
+ */
+export interface RuntimeSettings {
+  /** Response cache lifetimes by cache namespace */
+  cache_ttls?: Record<string, CacheTtl>;
+  /** Origins allowed by CORS in addition to the frontend URL */
+  extra_cors_origins?: string[];
+  /** Seconds a party waits for a disconnected host before passing host on */
+  party_host_grace_secs?: number | null;
+  /** API rate limit multiplier for admins */
+  rate_limit_admin_multiplier?: number | null;
+  /** API rate limit multiplier for guests */
+  rate_limit_guest_multiplier?: number | null;
+  /** API rate limit multiplier for registered users */
+  rate_limit_registered_multiplier?: number | null;
+  /**
+   * Seconds a disconnected player has to reconnect, for new games that
+   * don't come from a saved preset
+   */
+  reconnect_grace_secs?: number | null;
+  /** Multiplier applied to every socket event rate limit */
+  socket_rate_limit_multiplier?: number | null;
+}
+
+/** Request to feature a system map for a period */
+export interface ScheduleFeaturedMapRequest {
+  /** When the previous default map is restored */
+  ends_at: string;
+  /** System map to make the default while featured */
+  map_id: string;
+  /** When the map becomes the default; a past time starts it right away */
+  starts_at: string;
+  /** Event name shown alongside the map (max 100 characters) */
+  title?: string | null;
+}
+
+/** A scheduled, running, or finished feature */
+export interface ScheduledFeatureItem {
+  /** When the map was made the default */
+  activated_at?: string | null;
+  /** Admin who scheduled the feature */
+  created_by?: string | null;
+  /** Scheduled end */
+  ends_at: string;
+  /** When the feature ended, or was skipped because the map was unavailable */
+  finished_at?: string | null;
+  /** Feature ID */
+  id: number;
+  /** Featured map ID */
+  map_id: string;
+  /** Featured map name */
+  map_name: string;
+  /** Featured map slug */
+  map_slug: string;
+  /** Scheduled start */
+  starts_at: string;
+  /** Event name */
+  title?: string | null;
+}
+
+/** Live scoreboard update payload */
+export interface ScoresUpdatePayload {
+  /** Current round number */
+  round_number: number;
+  /** All player scores, sorted by total_score descending */
+  scores: PlayerScoreInfo[];
+  /** Total rounds */
+  total_rounds: number;
+}
+
+/** Send a friend request, identifying the other user by ID or username */
+export interface SendFriendRequest {
+  /** User ID (e.g., usr_V1StGXR8_Z5j) */
+  user_id?: string | null;
+  /** Username (e.g., coolplayer42) */
+  username?: string | null;
+}
+
+/** Result of sending a friend request */
+export interface SendFriendRequestResponse {
+  /** `accepted` when the other user had already sent a request */
+  status: FriendshipStatus;
+  /** User the request was sent to */
+  user_id: string;
+}
+
+/** Service information response for root endpoint */
+export interface ServiceInfo {
+  /** Description of the service */
+  about: string;
+  /** ISO 8601 timestamp when the binary was built */
+  build_timestamp: string;
+  /** Runtime environment */
+  environment: string;
+  /** Git commit SHA (short) */
+  git_sha: string;
+  /** Service/crate name */
+  name: string;
+  /** Client protocol spoken by this build */
+  protocol: ProtocolInfo;
+  /** Rust compiler version used to build */
+  rust_version: string;
+  /** Seconds since service started */
+  uptime_seconds: number;
+  /** Service version from Cargo.toml */
+  version: string;
+}
+
+/** Session info (for listing user's active sessions) */
+export interface SessionInfo {
+  /** When the session was created */
+  created_at: string;
+  /** When the session expires */
+  expires_at: string;
+  /** Session ID (truncated for security) */
+  id: string;
+  /** IP address used to create the session */
+  ip_address?: string | null;
+  /** Whether this is the current session */
+  is_current: boolean;
+  /** When the session was last accessed */
+  last_accessed_at: string;
+  /** User agent string */
+  user_agent?: string | null;
+}
+
+/** List of active sessions response */
+export interface SessionsListResponse {
+  /** List of active sessions */
+  sessions: SessionInfo[];
+}
+
+/** Request to activate or deactivate a system map */
+export interface SetMapActiveRequest {
+  /** Whether the map can be played */
+  active: boolean;
+}
+
+/** Request to activate or deactivate an organization */
+export interface SetOrganizationActiveRequest {
+  /** Whether the organization can be used */
+  active: boolean;
+}
+
+/** Settings updated payload (broadcast to all players in lobby) */
+export interface SettingsUpdatedPayload {
+  /** Game ID (e.g., gam_FybH2oF9Xaw8) */
+  game_id: string;
+  /** Updated settings */
+  settings: GameSettingsPayload;
+}
+
+/** Skip vote update payload (broadcast when a player votes to skip the between-rounds wait) */
+export interface SkipVoteUpdatePayload {
+  /** Number of votes required to skip (majority threshold) */
+  required: number;
+  /** Number of players who have voted to skip */
+  votes: number;
+}
+
+/** Request to sign in as a user for support */
+export interface StartImpersonationRequest {
+  /** Minutes until the session expires (5-60, default 15) */
+  minutes?: number | null;
+  /** Only allow reads (default true) */
+  read_only?: boolean;
+  /** Why the admin needs to sign in as the user (recorded in the audit log) */
+  reason: string;
+}
+
+/** Runtime settings as stored in Redis */
+export interface StoredRuntimeSettings {
+  settings: RuntimeSettings;
+  updated_at?: string | null;
+  /** Admin who made the change */
+  updated_by?: string | null;
+  /** Incremented on every change; 0 means nothing was ever stored */
+  version: number;
+}
+
+/** Metadata of a Street View panorama, looked up by the server */
+export interface StreetViewMetadataResponse {
+  /** Capture month (first day of the month), when reported */
+  capture_date?: string | null;
+  /** Panorama latitude, when it exists */
+  lat?: number | null;
+  /** Panorama longitude, when it exists */
+  lng?: number | null;
+  /** Panorama ID that was looked up */
+  panorama_id: string;
+  /** "ok" if the panorama exists, "not_found" if it is gone */
+  status: string;
+}
+
+/** Street View metadata API quota status, shared by all API instances */
+export interface StreetViewQuotaResponse {
+  /** Whether a Maps API key is configured */
+  configured: boolean;
+  /** Quota errors since the last successful lookup */
+  consecutive_trips: number;
+  /** When the quota was last reported exhausted */
+  last_tripped_at?: string | null;
+  /** Lookups are paused until this time while open */
+  paused_until?: string | null;
+  /**
+   * Metadata requests sent today (UTC) by all instances; unknown without
+   * Redis
+   */
+  requests_today?: number | null;
+  /**
+   * Breaker state: "closed" (quota available), "open" (lookups paused) or
+   * "half_open" (next lookup probes the quota)
+   */
+  state: string;
+}
+
+/** Client submitting a guess */
+export interface SubmitGuessPayload {
+  /** Game ID (e.g., gam_FybH2oF9Xaw8) */
+  game_id: string;
+  /** Guessed latitude */
+  lat: number;
+  /** Guessed longitude */
+  lng: number;
+  /**
+   * Panorama the client was viewing when it guessed.
+   * Must match the round's panorama in no-move modes.
+   */
+  panorama_id?: string | null;
+  /** Time taken to submit guess in milliseconds */
+  time_taken_ms?: number | null;
+}
+
+/** One player's guess in a game summary */
+export interface SummaryGuess {
+  /** Distance from correct location in meters */
+  distance_meters: number;
+  /** Guessed latitude */
+  guess_lat: number;
+  /** Guessed longitude */
+  guess_lng: number;
+  /** Score awarded */
+  score: number;
+  /** How long the player took to guess, in milliseconds */
+  time_taken_ms?: number | null;
+  /** User ID (e.g., usr_V1StGXR8_Z5j) */
+  user_id: string;
+}
+
+/** Standout moments of a game. Each is absent when nobody guessed. */
+export interface SummaryHighlights {
+  best_guess?: null | GuessHighlight;
+  biggest_blunder?: null | GuessHighlight;
+  closest_round?: null | RoundHighlight;
+}
+
+/** One round of a game summary */
+export interface SummaryRound {
+  /** Country of the correct location (ISO 3166-1 alpha-2) */
+  country_code?: string | null;
+  /** Guesses, best first. Players who did not guess are left out. */
+  guesses: SummaryGuess[];
+  /** Correct location latitude */
+  location_lat: number;
+  /** Correct location longitude */
+  location_lng: number;
+  /** Round number */
+  round_number: number;
+}
+
+/** A system map (a map with no creator) */
+export interface SystemMapItem {
+  /** Whether the map can be played */
+  active: boolean;
+  /** When the map was created */
+  created_at: string;
+  /** Description */
+  description?: string | null;
+  /** Map ID */
+  id: string;
+  /** Whether this is the default map */
+  is_default: boolean;
+  /** Number of locations */
+  location_count: number;
+  /** Display name */
+  name: string;
+  /** Number of finished plays */
+  play_count: number;
+  /** Location selection rules (countries, year range, outdoor only, ...) */
+  rules: Record<string, unknown>;
+  /** URL-friendly slug */
+  slug: string;
+  /** When the map was last updated */
+  updated_at: string;
+}
+
+/** System maps list response */
+export interface SystemMapsListResponse {
+  /** All system maps, inactive ones included */
+  maps: SystemMapItem[];
+}
+
+/** A signed tile token for fetching tiles through the API */
+export interface TileSessionResponse {
+  /** When the token stops being accepted; request a new one before then */
+  expires_at: string;
+  /** "jpeg" or "png" */
+  image_format: string;
+  /** Map type the token serves */
+  map_type: string;
+  /** Tile height in pixels */
+  tile_height: number;
+  /**
+   * Tile path on the API host, with `{z}`, `{x}` and `{y}` placeholders
+   * (and `{pano}` for Street View)
+   */
+  tile_url: string;
+  /** Tile width in pixels */
+  tile_width: number;
+  /** Token to pass as `token` when fetching tiles */
+  token: string;
+}
+
+/** Time period for leaderboard */
+export type TimePeriod = "all_time" | "daily" | "weekly" | "monthly";
+
+/** Client echo of a `time:sync` reply, used to measure round-trip time */
+export interface TimePongPayload {
+  /** Game the player is in, if any, to record the round-trip time against */
+  game_id?: string | null;
+  /** The `server_time` of the reply being answered */
+  server_time: number;
+}
+
+/**
+ * Server reply to a clock sync request. The client estimates its clock
+ * offset as `server_time + rtt / 2 - now`, where `rtt` is measured from
+ * `client_time`, then echoes `server_time` back in a `time:pong`.
+ */
+export interface TimeSyncPayload {
+  /** The request's `client_time`, echoed back */
+  client_time: number;
+  /** Server clock when the reply was sent (unix ms) */
+  server_time: number;
+}
+
+/** Client asking for the server time */
+export interface TimeSyncRequest {
+  /** Client clock when the request was sent (unix ms) */
+  client_time: number;
+}
+
+/** Phase of a broadcast game transition. */
+export type TransitionPhase = "starting" | "advancing_round" | "ending_game";
+
+/** Update notification preferences; omitted fields are unchanged */
+export interface UpdateNotificationPreferencesRequest {
+  challenge_turns?: boolean | null;
+  daily_challenge?: boolean | null;
+  friend_invites?: boolean | null;
+}
+
+/**
+ * Request to change an organization's name or branding. Omitted fields are
+ * left unchanged.
+ */
+export interface UpdateOrganizationRequest {
+  branding?: null | OrgBranding;
+  /** New display name (3-100 characters) */
+  name?: string | null;
+}
+
+/** Move a player report through the workflow */
+export interface UpdatePlayerReportRequest {
+  /** Note on the outcome (max 1000 characters) */
+  resolution_note?: string | null;
+  /** New status: "open", "reviewing", "actioned" or "dismissed" */
+  status: string;
+}
+
+/** Result of a status change */
+export interface UpdatePlayerReportResponse {
+  /** Report ID */
+  id: string;
+  /** When the status changed */
+  reviewed_at?: string | null;
+  /** The report's status now */
+  status: string;
+}
+
+/** Update profile request */
+export interface UpdateProfileRequest {
+  /** New avatar URL */
+  avatar_url?: string | null;
+  /** New display name (3-50 characters) */
+  display_name?: string | null;
+  /** Whether to show identity publicly on the leaderboard */
+  leaderboard_public?: boolean | null;
+  /** New username (3-30 characters, lowercase alphanumeric and underscores) */
+  username?: string | null;
+}
+
+/** Request to update a location's review status */
+export interface UpdateReviewStatusRequest {
+  /** Optional notes about the review decision */
+  notes?: string | null;
+  /** New review status: approved, rejected, or flagged */
+  status: string;
+}
+
+/** Response after updating review status */
+export interface UpdateReviewStatusResponse {
+  /** Whether the location is now active */
+  active: boolean;
+  /** Success message */
+  message: string;
+  /** The updated review status */
+  status: string;
+}
+
+/** Request to edit a system map. Omitted fields are left unchanged. */
+export interface UpdateSystemMapRequest {
+  /** New description; an empty string removes it */
+  description?: string | null;
+  /** New display name (3-100 characters) */
+  name?: string | null;
+  /** New location selection rules, replacing the current ones */
+  rules?: Record<string, unknown> | null;
+}
+
+/** Public user profile (safe to expose) */
+export interface UserProfile {
+  /** Avatar URL */
+  avatar_url?: string | null;
+  /** Best score in a single game */
+  best_score: number;
+  /** Display name */
+  display_name: string;
+  /** Number of games played */
+  games_played: number;
+  /** User ID (e.g., usr_V1StGXR8_Z5j) */
+  id: string;
+  /** Whether the user is a guest */
+  is_guest: boolean;
+  /** Total score across all games */
+  total_score: number;
+  /** Unique username (e.g., coolplayer42) */
+  username?: string | null;
+}
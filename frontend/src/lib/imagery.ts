@@ -0,0 +1,30 @@
+/** Street-level imagery sources a location can come from. */
+export type ImageryProvider = 'google_streetview' | 'mapillary' | 'bing_streetside' | 'sample';
+
+export const IMAGERY_PROVIDERS: { value: ImageryProvider; label: string }[] = [
+  { value: 'google_streetview', label: 'Google Street View' },
+  { value: 'mapillary', label: 'Mapillary' },
+  { value: 'bing_streetside', label: 'Bing Streetside' },
+];
+
+/**
+ * Embed URL for providers shown in an iframe, or null for Google Street View,
+ * which is rendered with the Maps JavaScript API.
+ */
+export function imageryEmbedUrl(
+  provider: ImageryProvider | null | undefined,
+  lat: number,
+  lng: number,
+  imageId: string | null,
+): string | null {
+  switch (provider) {
+    case 'mapillary':
+      return imageId
+        ? `https://www.mapillary.com/embed?image_key=${encodeURIComponent(imageId)}&style=photo`
+        : null;
+    case 'bing_streetside':
+      return `https://www.bing.com/maps/embed?cp=${lat}~${lng}&lvl=19&sty=x`;
+    default:
+      return null;
+  }
+}
@@ -0,0 +1,58 @@
+import { notificationsApi, type PushSubscriptionJson } from '$lib/api';
+
+/** Whether this browser can receive Web Push notifications */
+export function isPushSupported(): boolean {
+  return (
+    typeof window !== 'undefined' &&
+    'serviceWorker' in navigator &&
+    'PushManager' in window &&
+    'Notification' in window
+  );
+}
+
+/** The current push subscription of this browser, if any */
+export async function getPushSubscription(): Promise<PushSubscription | null> {
+  if (!isPushSupported()) return null;
+  const registration = await navigator.serviceWorker.ready;
+  return registration.pushManager.getSubscription();
+}
+
+/** Ask for permission, subscribe this browser, and register it with the API */
+export async function enablePush(): Promise<boolean> {
+  if (!isPushSupported()) return false;
+
+  const permission = await Notification.requestPermission();
+  if (permission !== 'granted') return false;
+
+  const registration = await navigator.serviceWorker.ready;
+  const subscription =
+    (await registration.pushManager.getSubscription()) ??
+    (await registration.pushManager.subscribe({
+      userVisibleOnly: true,
+      applicationServerKey: urlBase64ToUint8Array(await notificationsApi.getPublicKey()),
+    }));
+
+  await notificationsApi.subscribe(subscription.toJSON() as PushSubscriptionJson);
+  return true;
+}
+
+/** Unsubscribe this browser and remove it from the API */
+export async function disablePush(): Promise<void> {
+  const subscription = await getPushSubscription();
+  if (!subscription) return;
+
+  await notificationsApi.unsubscribe(subscription.endpoint).catch(() => {});
+  await subscription.unsubscribe();
+}
+
+function urlBase64ToUint8Array(value: string): Uint8Array<ArrayBuffer> {
+  const base64 = (value + '='.repeat((4 - (value.length % 4)) % 4))
+    .replace(/-/g, '+')
+    .replace(/_/g, '/');
+  const raw = atob(base64);
+  const bytes = new Uint8Array(raw.length);
+  for (let i = 0; i < raw.length; i++) {
+    bytes[i] = raw.charCodeAt(i);
+  }
+  return bytes;
+}
@@ -1,12 +1,17 @@
 import { io, Socket } from 'socket.io-client';
 import { writable, get, type Writable } from 'svelte/store';
 import { toastStore } from '$lib/stores/toast';
+import { telemetryApi } from '$lib/api/telemetry';
+import { recordClockSample } from './clock';
 
 // Re-export for backward compatibility
 export { toastStore, type Toast, type ToastType } from '$lib/stores/toast';
 
 const REALTIME_URL = import.meta.env.VITE_REALTIME_URL || 'http://localhost:3002';
 
+/** Socket protocol version this client speaks (see dguesser_protocol::version) */
+export const PROTOCOL_VERSION = 2;
+
 /** Reconnection configuration */
 const RECONNECTION_CONFIG = {
   reconnection: true,
@@ -17,6 +22,11 @@ const RECONNECTION_CONFIG = {
   timeout: 20000,
 } as const;
 
+/** Clock syncs sent back to back after authenticating */
+const CLOCK_SYNC_BURST = 5;
+/** Interval between clock syncs afterwards (ms) */
+const CLOCK_SYNC_INTERVAL_MS = 30000;
+
 export type ConnectionStatus =
   | 'disconnected'
   | 'connecting'
@@ -36,6 +46,8 @@ export interface SocketState {
   activeGameId: string | null;
   /** Game phase - used to determine if auto-rejoin is allowed */
   activeGamePhase: GamePhase;
+  /** Capabilities negotiated with the server on authentication */
+  capabilities: string[];
 }
 
 class SocketClient {
@@ -44,6 +56,11 @@ class SocketClient {
   private reconnectCallbacks: Array<() => void> = [];
   /** Listeners registered before socket was created */
   private pendingListeners: Array<{ event: string; callback: (data: unknown) => void }> = [];
+  /** Why the connection last dropped, reported once it is back */
+  private droppedReason: string | null = null;
+  /** Clock syncs left in the current burst */
+  private clockSyncsPending = 0;
+  private clockSyncTimer: ReturnType<typeof setInterval> | null = null;
 
   constructor() {
     this.state = writable({
@@ -53,6 +70,7 @@ class SocketClient {
       maxReconnectAttempts: RECONNECTION_CONFIG.reconnectionAttempts,
       activeGameId: null,
       activeGamePhase: null,
+      capabilities: [],
     });
   }
 
@@ -107,6 +125,15 @@ class SocketClient {
       if (wasReconnecting) {
         toastStore.add('success', 'Reconnected!');
       }
+
+      if (this.droppedReason) {
+        void telemetryApi.reportClientError({
+          kind: 'socket_disconnected',
+          message: this.droppedReason,
+          game_id: currentState.activeGameId,
+        });
+        this.droppedReason = null;
+      }
     });
 
     // Disconnection
@@ -123,6 +150,7 @@ class SocketClient {
       // Show toast for unexpected disconnections
       if (isTransportClose || isServerDisconnect) {
         toastStore.add('warning', 'Connection lost. Reconnecting...');
+        this.droppedReason = reason;
       }
 
       // If server explicitly disconnected us, we might need to manually reconnect
@@ -179,8 +207,12 @@ class SocketClient {
     });
 
     // Authentication success
-    this.socket.on('auth:success', () => {
-      this.state.update((s) => ({ ...s, status: 'authenticated' }));
+    this.socket.on('auth:success', (data?: { capabilities?: string[] }) => {
+      const capabilities = data?.capabilities ?? [];
+      this.state.update((s) => ({ ...s, status: 'authenticated', capabilities }));
+      if (capabilities.includes('clock_sync')) {
+        this.startClockSync();
+      }
 
       // Auto-rejoin active game ONLY if it was in active phase (not lobby)
       // In lobby phase, disconnection should require manual rejoin
@@ -198,11 +230,48 @@ class SocketClient {
     this.socket.on('auth:error', (data: { error: string }) => {
       this.state.update((s) => ({ ...s, error: data.error }));
     });
+
+    // Clock sync reply: update the offset and echo it so the server can
+    // measure our round-trip time
+    this.socket.on('time:sync', (data: { client_time: number; server_time: number }) => {
+      recordClockSample(data.client_time, data.server_time, Date.now());
+      this.socket?.emit('time:pong', {
+        server_time: data.server_time,
+        game_id: get(this.state).activeGameId,
+      });
+
+      if (this.clockSyncsPending > 0) {
+        this.clockSyncsPending -= 1;
+        this.sendClockSync();
+      }
+    });
+
+    this.socket.on('disconnect', () => this.stopClockSync());
+  }
+
+  /** Sync the clock a few times now, then periodically while connected */
+  private startClockSync(): void {
+    this.stopClockSync();
+    this.clockSyncsPending = CLOCK_SYNC_BURST - 1;
+    this.sendClockSync();
+    this.clockSyncTimer = setInterval(() => this.sendClockSync(), CLOCK_SYNC_INTERVAL_MS);
+  }
+
+  private stopClockSync(): void {
+    this.clockSyncsPending = 0;
+    if (this.clockSyncTimer) {
+      clearInterval(this.clockSyncTimer);
+      this.clockSyncTimer = null;
+    }
+  }
+
+  private sendClockSync(): void {
+    this.socket?.emit('time:sync', { client_time: Date.now() });
   }
 
   private authenticate(): void {
     // Session ID is sent via cookie, just trigger auth
-    this.socket?.emit('auth', { session_id: '' });
+    this.socket?.emit('auth', { session_id: '', protocol_version: PROTOCOL_VERSION });
   }
 
   /** Set the active game ID and phase (for auto-rejoin on reconnect) */
@@ -289,6 +358,7 @@ class SocketClient {
   }
 
   disconnect(): void {
+    this.stopClockSync();
     this.socket?.disconnect();
     this.socket = null;
     this.pendingListeners = [];
@@ -299,6 +369,7 @@ class SocketClient {
       maxReconnectAttempts: RECONNECTION_CONFIG.reconnectionAttempts,
       activeGameId: null,
       activeGamePhase: null,
+      capabilities: [],
     });
   }
 
@@ -0,0 +1,40 @@
+/**
+ * Server clock estimate.
+ *
+ * Round timestamps from the server (`started_at`, `next_round_at`) are in
+ * server time. The socket client syncs with `time:sync` and the offset comes
+ * from the fastest recent round trip, whose midpoint is the best guess of
+ * when the server read its clock.
+ */
+
+/** Recent samples to pick the offset from */
+const MAX_SAMPLES = 5;
+
+interface ClockSample {
+  rttMs: number;
+  offsetMs: number;
+}
+
+let samples: ClockSample[] = [];
+let offsetMs = 0;
+
+/** Record one `time:sync` reply received at local time `receivedAt`. */
+export function recordClockSample(clientTime: number, serverTime: number, receivedAt: number): void {
+  const rttMs = receivedAt - clientTime;
+  if (rttMs < 0) return;
+
+  samples = [...samples, { rttMs, offsetMs: serverTime + rttMs / 2 - receivedAt }].slice(
+    -MAX_SAMPLES
+  );
+  offsetMs = samples.reduce((best, s) => (s.rttMs < best.rttMs ? s : best)).offsetMs;
+}
+
+/** Current time on the server clock (unix ms) */
+export function serverNow(): number {
+  return Date.now() + offsetMs;
+}
+
+/** Convert a server timestamp to the local clock, for countdowns */
+export function toLocalTime(serverTime: number): number {
+  return serverTime - offsetMs;
+}
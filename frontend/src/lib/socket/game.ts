@@ -1,8 +1,16 @@
 import { writable, get } from 'svelte/store';
 import { gameAudio } from '$lib/audio/game-audio';
 import { socketClient, toastStore, type GamePhase } from './client';
-import type { GameSettings } from '$lib/api/games';
+import { toLocalTime } from './clock';
+import type {
+  GameSettings,
+  GlobalGuessStats,
+  HintKind,
+  QuizQuestion,
+  QuizScore,
+} from '$lib/api/games';
 import { authStore } from '$lib/stores/auth';
+import type { ImageryProvider } from '$lib/imagery';
 
 // Types matching backend protocol
 export interface RoundLocation {
@@ -12,6 +20,8 @@ export interface RoundLocation {
   heading?: number | null;
   /** Location ID for reporting (loc_xxxxxxxxxxxx) */
   location_id?: string | null;
+  /** Imagery provider the panorama ID belongs to */
+  provider?: ImageryProvider;
 }
 
 export interface RoundStartPayload {
@@ -37,6 +47,8 @@ export interface RoundResult {
   distance_meters: number;
   score: number;
   total_score: number;
+  /** Percentage of past guesses on this location that were further away */
+  percentile?: number;
 }
 
 export interface RoundEndPayload {
@@ -45,6 +57,8 @@ export interface RoundEndPayload {
   results: RoundResult[];
   /** Unix timestamp (ms) when the next round will auto-start (multiplayer only) */
   next_round_at?: number | null;
+  /** How past players did on this location (absent until enough have guessed) */
+  global_stats?: GlobalGuessStats;
 }
 
 export interface FinalStanding {
@@ -53,6 +67,10 @@ export interface FinalStanding {
   user_id: string;
   display_name: string;
   total_score: number;
+  /** Longest country streak (streak games) */
+  streak?: number;
+  /** First round played, if the player joined after the game started */
+  joined_round?: number;
 }
 
 export interface GameEndPayload {
@@ -70,6 +88,10 @@ export interface PlayerInfo {
   has_guessed: boolean;
   connected?: boolean;
   disconnected_at?: number | null;
+  /** Score multiplier set by the host (1.0 = none) */
+  handicap?: number;
+  /** First round the player could play, if they joined after the game started */
+  joined_round?: number;
 }
 
 /** Skip vote update payload */
@@ -80,6 +102,37 @@ export interface SkipVoteUpdatePayload {
   required: number;
 }
 
+/** Reactions players can send, matching the server's list */
+export const REACTIONS = [
+  'thumbs_up',
+  'laugh',
+  'wow',
+  'fire',
+  'clap',
+  'thinking',
+  'sad',
+  'party',
+] as const;
+
+export type Reaction = (typeof REACTIONS)[number];
+
+/** A player's reaction, broadcast to the room */
+export interface ReactionPayload {
+  user_id: string;
+  reaction: Reaction;
+}
+
+/** The host replaced the current round's location; a `round:start` follows */
+export interface RoundRerolledPayload {
+  round_number: number;
+  rerolled_by: string;
+  discarded_guesses: string[];
+  rerolls_left: number;
+}
+
+/** How long a received reaction stays in the store */
+const REACTION_DISPLAY_MS = 3_000;
+
 /** Full game state payload (sent on join/reconnect) */
 export interface GameStatePayload {
   game_id: string;
@@ -105,6 +158,13 @@ export interface SettingsUpdatedPayload {
   settings: GameSettings;
 }
 
+/** Player handicap updated payload */
+export interface HandicapUpdatedPayload {
+  game_id: string;
+  user_id: string;
+  handicap: number;
+}
+
 /** Player disconnected payload */
 export interface PlayerDisconnectedPayload {
   user_id: string;
@@ -125,6 +185,47 @@ export interface PlayerTimedOutPayload {
   display_name: string;
 }
 
+/** Game paused payload (host disconnected mid-game, or the teacher paused) */
+export interface GamePausedPayload {
+  user_id: string;
+  /** Absent when the game stays paused until the teacher resumes */
+  grace_period_ms?: number;
+}
+
+/** A student's guess in a classroom game, sent to the teacher only */
+export interface ClassroomGuessPayload {
+  round_number: number;
+  user_id: string;
+  display_name: string;
+  lat: number;
+  lng: number;
+  distance_meters: number;
+  score: number;
+  time_taken_ms: number | null;
+}
+
+/** A hint the player took for the current round, sent to that player only */
+export interface HintRevealedPayload {
+  round_number: number;
+  kind: HintKind;
+  value: string;
+  cost: number;
+}
+
+/** A player answered the lobby warm-up quiz question */
+export interface QuizAnsweredPayload {
+  question_id: string;
+  user_id: string;
+  correct: boolean;
+  points: number;
+  scores: QuizScore[];
+}
+
+/** Game resumed payload; round timers were extended by paused_ms */
+export interface GameResumedPayload {
+  paused_ms: number;
+}
+
 /** Game abandoned payload (all players disconnected for too long) */
 export interface GameAbandonedPayload {
   game_id: string;
@@ -180,6 +281,8 @@ export interface PlayerScoreInfo {
   has_guessed: boolean;
   rank: number;
   connected: boolean;
+  /** Score multiplier applied to this player's round scores */
+  handicap: number;
 }
 
 /** Extended player state in store */
@@ -190,6 +293,7 @@ export interface PlayerState {
   hasGuessed: boolean;
   connected: boolean;
   disconnectedAt: number | null;
+  handicap: number;
 }
 
 export interface GameState {
@@ -229,6 +333,18 @@ export interface GameState {
   hasVotedToSkip: boolean;
   /** Active server-broadcast transition, or null if none in flight. */
   transition: GameTransition | null;
+  /** Recent reactions, oldest first; each is removed after a few seconds */
+  reactions: { id: number; userId: string; reaction: Reaction }[];
+  /** Whether the game is paused */
+  paused: boolean;
+  /** Students' guesses this round, live (classroom teacher only) */
+  classroomGuesses: ClassroomGuessPayload[];
+  /** Hints taken this round */
+  hints: HintRevealedPayload[];
+  /** Open lobby warm-up quiz question, cleared when the game starts */
+  quizQuestion: QuizQuestion | null;
+  /** Lobby warm-up quiz scores, highest first */
+  quizScores: QuizScore[];
 }
 
 function createGameStore() {
@@ -256,10 +372,18 @@ function createGameStore() {
     skipVotesRequired: 0,
     hasVotedToSkip: false,
     transition: null,
+    reactions: [],
+    paused: false,
+    classroomGuesses: [],
+    hints: [],
+    quizQuestion: null,
+    quizScores: [],
   };
 
   const { subscribe, set, update } = writable<GameState>(initialState);
 
+  let reactionSeq = 0;
+
   /** Local watchdog timer: if a transition doesn't resolve within N seconds,
    *  we clear it ourselves so the UI recovers even if the server fails to emit
    *  a `round:start` / `game:end` / `game:transition_cleared` follow-up. */
@@ -329,7 +453,19 @@ function createGameStore() {
       }
     },
 
-    submitGuess(lat: number, lng: number, timeTakenMs?: number): void {
+    /** Set a player's score multiplier (host only, lobby only) */
+    setHandicap(userId: string, handicap: number): void {
+      const currentState = get({ subscribe });
+      if (currentState.gameId) {
+        socketClient.emit('player:set_handicap', {
+          game_id: currentState.gameId,
+          user_id: userId,
+          handicap,
+        });
+      }
+    },
+
+    submitGuess(lat: number, lng: number, timeTakenMs?: number, panoramaId?: string | null): void {
       update((s) => {
         if (s.gameId && !s.hasGuessed) {
           socketClient.emit('guess:submit', {
@@ -337,6 +473,7 @@ function createGameStore() {
             lat,
             lng,
             time_taken_ms: timeTakenMs,
+            panorama_id: panoramaId ?? undefined,
           });
           return { ...s, hasGuessed: true };
         }
@@ -361,8 +498,65 @@ function createGameStore() {
       }
     },
 
+    /** Send an emoji reaction to the other players */
+    react(reaction: Reaction): void {
+      const currentState = get({ subscribe });
+      if (currentState.gameId && currentState.settings?.reactions_enabled !== false) {
+        socketClient.emit('game:react', { game_id: currentState.gameId, reaction });
+      }
+    },
+
+    /** Teacher pauses a classroom game */
+    pause(): void {
+      const currentState = get({ subscribe });
+      if (currentState.gameId) {
+        socketClient.emit('classroom:pause', { game_id: currentState.gameId });
+      }
+    },
+
+    /** Teacher resumes a classroom game */
+    resume(): void {
+      const currentState = get({ subscribe });
+      if (currentState.gameId) {
+        socketClient.emit('classroom:resume', { game_id: currentState.gameId });
+      }
+    },
+
+    /** Take a hint for the current round; the direction hint needs a point */
+    requestHint(kind: HintKind, from?: { lat: number; lng: number }): void {
+      const currentState = get({ subscribe });
+      if (currentState.gameId && currentState.settings?.hints_enabled) {
+        socketClient.emit('hint:request', {
+          game_id: currentState.gameId,
+          kind,
+          from_lat: from?.lat,
+          from_lng: from?.lng,
+        });
+      }
+    },
+
+    /** Host replaces the current round's location (e.g. a broken panorama) */
+    reroll(): void {
+      const currentState = get({ subscribe });
+      if (currentState.gameId) {
+        socketClient.emit('game:reroll', { game_id: currentState.gameId });
+      }
+    },
+
     // Event handlers
 
+    /** Handle a player's reaction */
+    handleReaction(payload: ReactionPayload): void {
+      const id = ++reactionSeq;
+      update((s) => ({
+        ...s,
+        reactions: [...s.reactions, { id, userId: payload.user_id, reaction: payload.reaction }],
+      }));
+      setTimeout(() => {
+        update((s) => ({ ...s, reactions: s.reactions.filter((r) => r.id !== id) }));
+      }, REACTION_DISPLAY_MS);
+    },
+
     /** Handle full game state sync (on join or reconnect) */
     handleGameState(payload: GameStatePayload): void {
       const players = new Map<string, PlayerState>();
@@ -374,6 +568,7 @@ function createGameStore() {
           hasGuessed: p.has_guessed,
           connected: p.connected ?? true,
           disconnectedAt: p.disconnected_at ?? null,
+          handicap: p.handicap ?? 1,
         });
       }
 
@@ -420,6 +615,7 @@ function createGameStore() {
           has_guessed: p.has_guessed,
           rank: 0, // Will be assigned after sorting
           connected: p.connected ?? true,
+          handicap: p.handicap ?? 1,
         }))
         .sort((a, b) => b.total_score - a.total_score)
         .map((p, i) => ({ ...p, rank: i + 1 }));
@@ -447,7 +643,7 @@ function createGameStore() {
         hasGuessed: payload.players.some((p) => p.has_guessed && p.id === getCurrentUserId()),
         players,
         liveScores,
-        nextRoundAt: payload.next_round_at ?? null,
+        nextRoundAt: payload.next_round_at ? toLocalTime(payload.next_round_at) : null,
         skipVotes: payload.skip_votes?.votes ?? 0,
         skipVotesRequired: payload.skip_votes?.required ?? 0,
         hasVotedToSkip: payload.skip_vote_user_ids?.includes(getCurrentUserId() ?? '') ?? false,
@@ -503,10 +699,13 @@ function createGameStore() {
           totalRounds: payload.total_rounds,
           location: payload.location,
           timeLimit: payload.time_limit_ms,
-          roundStartedAt: payload.started_at,
+          roundStartedAt: toLocalTime(payload.started_at),
           timeRemainingMs: payload.time_limit_ms,
           hasGuessed: false,
           results: [],
+          classroomGuesses: [],
+          hints: [],
+          quizQuestion: null,
           players: new Map(
             [...s.players].map(([id, p]) => [id, { ...p, hasGuessed: false }])
           ),
@@ -567,7 +766,7 @@ function createGameStore() {
           location: payload.correct_location,
           players,
           // Store between-rounds countdown deadline
-          nextRoundAt: payload.next_round_at ?? null,
+          nextRoundAt: payload.next_round_at ? toLocalTime(payload.next_round_at) : null,
           skipVotes: 0,
           skipVotesRequired: 0,
           hasVotedToSkip: false,
@@ -642,6 +841,60 @@ function createGameStore() {
       );
     },
 
+    /** Handle game paused while the host is disconnected or by the teacher */
+    handleGamePaused(payload: GamePausedPayload): void {
+      update((s) => ({ ...s, paused: true }));
+      if (payload.grace_period_ms === undefined) {
+        toastStore.add('info', 'The teacher paused the game');
+        return;
+      }
+      const seconds = Math.round(payload.grace_period_ms / 1000);
+      toastStore.add('warning', `Host disconnected. Game paused for up to ${seconds}s`);
+    },
+
+    /** Handle a student's guess in a classroom game (teacher only) */
+    handleClassroomGuess(payload: ClassroomGuessPayload): void {
+      update((s) => ({
+        ...s,
+        // Rejoining resends the round's guesses
+        classroomGuesses: [
+          ...s.classroomGuesses.filter(
+            (g) => g.user_id !== payload.user_id || g.round_number !== payload.round_number,
+          ),
+          payload,
+        ],
+      }));
+    },
+
+    /** Handle a hint the player took; asking again replaces it */
+    handleHintRevealed(payload: HintRevealedPayload): void {
+      update((s) => ({
+        ...s,
+        hints: [...s.hints.filter((h) => h.kind !== payload.kind), payload],
+      }));
+    },
+
+    /** Handle a warm-up quiz question asked in the lobby */
+    handleQuizQuestion(payload: QuizQuestion): void {
+      update((s) => ({ ...s, quizQuestion: payload }));
+    },
+
+    /** Handle a warm-up quiz answer; the broadcast carries the new scores */
+    handleQuizAnswered(payload: QuizAnsweredPayload): void {
+      update((s) => ({ ...s, quizScores: payload.scores }));
+    },
+
+    /** Handle game resumed: shift local timers by the pause length */
+    handleGameResumed(payload: GameResumedPayload): void {
+      update((s) => ({
+        ...s,
+        paused: false,
+        roundStartedAt: s.roundStartedAt !== null ? s.roundStartedAt + payload.paused_ms : null,
+        nextRoundAt: s.nextRoundAt !== null ? s.nextRoundAt + payload.paused_ms : null,
+      }));
+      toastStore.add('info', 'Game resumed');
+    },
+
     /** Handle game abandoned (all players disconnected for too long) */
     handleGameAbandoned(payload: GameAbandonedPayload): void {
       socketClient.setActiveGame(null, null);
@@ -665,6 +918,7 @@ function createGameStore() {
           hasGuessed: payload.player.has_guessed,
           connected: true,
           disconnectedAt: null,
+          handicap: payload.player.handicap ?? 1,
         });
         return { ...s, players };
       });
@@ -752,6 +1006,21 @@ function createGameStore() {
       }));
     },
 
+    /** Handle a player's handicap being changed by the host */
+    handleHandicapUpdated(payload: HandicapUpdatedPayload): void {
+      update((s) => {
+        const players = new Map(s.players);
+        const existing = players.get(payload.user_id);
+        if (existing) {
+          players.set(payload.user_id, { ...existing, handicap: payload.handicap });
+        }
+        const liveScores = s.liveScores.map((p) =>
+          p.user_id === payload.user_id ? { ...p, handicap: payload.handicap } : p,
+        );
+        return { ...s, players, liveScores };
+      });
+    },
+
     /** Set round info (for restoring state) */
     setRoundInfo(currentRound: number, totalRounds: number): void {
       update((s) => ({
@@ -824,6 +1093,24 @@ export function initGameSocketListeners(): () => void {
     socketClient.on<PlayerTimedOutPayload>('player:timeout', (data) => {
       gameStore.handlePlayerTimedOut(data);
     }),
+    socketClient.on<GamePausedPayload>('game:paused', (data) => {
+      gameStore.handleGamePaused(data);
+    }),
+    socketClient.on<GameResumedPayload>('game:resumed', (data) => {
+      gameStore.handleGameResumed(data);
+    }),
+    socketClient.on<ClassroomGuessPayload>('classroom:guess', (data) => {
+      gameStore.handleClassroomGuess(data);
+    }),
+    socketClient.on<HintRevealedPayload>('hint:revealed', (data) => {
+      gameStore.handleHintRevealed(data);
+    }),
+    socketClient.on<QuizQuestion>('quiz:question', (data) => {
+      gameStore.handleQuizQuestion(data);
+    }),
+    socketClient.on<QuizAnsweredPayload>('quiz:answered', (data) => {
+      gameStore.handleQuizAnswered(data);
+    }),
     // Live scores update
     socketClient.on<ScoresUpdatePayload>('scores:update', (data) => {
       gameStore.handleScoresUpdate(data);
@@ -832,6 +1119,23 @@ export function initGameSocketListeners(): () => void {
     socketClient.on<SettingsUpdatedPayload>('game:settings_updated', (data) => {
       gameStore.handleSettingsUpdated(data);
     }),
+    // Handicap updated (in lobby)
+    socketClient.on<HandicapUpdatedPayload>('player:handicap_updated', (data) => {
+      gameStore.handleHandicapUpdated(data);
+    }),
+    socketClient.on<ReactionPayload>('game:reaction', (data) => {
+      gameStore.handleReaction(data);
+    }),
+    // Round rerolled; the following round:start resets guesses
+    socketClient.on<RoundRerolledPayload>('round:rerolled', (data) => {
+      const guessed = data.discarded_guesses.includes(getCurrentUserId() ?? '');
+      toastStore.add(
+        'info',
+        guessed
+          ? 'The host picked a new location; your guess was discarded'
+          : 'The host picked a new location for this round',
+      );
+    }),
     // Error handling
     socketClient.on<{ code: string; message: string }>('error', (data) => {
       console.error('[Socket Error]', data.code, data.message);
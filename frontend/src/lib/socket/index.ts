@@ -1,5 +1,6 @@
 // Socket.IO client module
 export { socketClient, type ConnectionStatus } from './client';
+export { serverNow, toLocalTime } from './clock';
 export {
   gameStore,
   initGameSocketListeners,
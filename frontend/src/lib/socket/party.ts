@@ -12,6 +12,8 @@ export interface PartyMemberInfo {
   display_name: string;
   avatar_url: string | null;
   connected: boolean;
+  /** Muted in chat by the host */
+  chat_muted: boolean;
 }
 
 export interface PartyStatePayload {
@@ -49,6 +51,12 @@ export interface PartyKickedPayload {
   user_id: string;
 }
 
+export interface PartyMemberMutedPayload {
+  user_id: string;
+  display_name: string;
+  muted: boolean;
+}
+
 export interface PartyHostChangedPayload {
   new_host_id: string;
   new_host_name: string;
@@ -58,6 +66,21 @@ export interface PartySettingsUpdatedPayload {
   settings: GameSettings;
 }
 
+export interface PartyChatMessage {
+  id: number;
+  user_id: string;
+  display_name: string;
+  content: string;
+  sent_at: number;
+}
+
+export interface PartyChatHistoryPayload {
+  messages: PartyChatMessage[];
+}
+
+/** Messages kept in the store, matching the server's history */
+const MAX_CHAT_MESSAGES = 50;
+
 // =============================================================================
 // Store
 // =============================================================================
@@ -70,6 +93,7 @@ export interface PartyState {
   settings: GameSettings | null;
   status: 'idle' | 'lobby' | 'starting' | 'in_game';
   currentGameId: string | null;
+  messages: PartyChatMessage[];
 }
 
 const initialState: PartyState = {
@@ -80,6 +104,7 @@ const initialState: PartyState = {
   settings: null,
   status: 'idle',
   currentGameId: null,
+  messages: [],
 };
 
 function createPartyStore() {
@@ -170,6 +195,26 @@ function createPartyStore() {
       }
     },
 
+    /** Mute or unmute a member in chat (host only) */
+    setMemberMuted(userId: string, muted: boolean) {
+      const state = get({ subscribe });
+      if (state.partyId) {
+        socketClient.emit('party:mute', {
+          party_id: state.partyId,
+          user_id: userId,
+          muted,
+        });
+      }
+    },
+
+    /** Send a chat message to the party */
+    sendMessage(content: string) {
+      const state = get({ subscribe });
+      if (state.partyId) {
+        socketClient.emit('party:chat', { party_id: state.partyId, content });
+      }
+    },
+
     /** Disband the party (host only) */
     disbandParty() {
       const state = get({ subscribe });
@@ -193,7 +238,7 @@ function createPartyStore() {
         members.set(m.user_id, m);
       }
 
-      set({
+      update((state) => ({
         partyId: payload.party_id,
         joinCode: payload.join_code,
         hostId: payload.host_id,
@@ -201,7 +246,8 @@ function createPartyStore() {
         settings: payload.settings,
         status: payload.phase,
         currentGameId: payload.current_game_id,
-      });
+        messages: state.partyId === payload.party_id ? state.messages : [],
+      }));
     },
 
     handleMemberJoined(payload: PartyMemberJoinedPayload) {
@@ -226,6 +272,21 @@ function createPartyStore() {
       });
     },
 
+    handleMemberMuted(payload: PartyMemberMutedPayload) {
+      update((state) => {
+        const member = state.members.get(payload.user_id);
+        if (!member) {
+          return state;
+        }
+        const members = new Map(state.members);
+        members.set(payload.user_id, { ...member, chat_muted: payload.muted });
+        return {
+          ...state,
+          members,
+        };
+      });
+    },
+
     handleGameStarting(payload: PartyGameStartingPayload) {
       update((state) => ({
         ...state,
@@ -271,6 +332,25 @@ function createPartyStore() {
       }));
     },
 
+    handleChatHistory(payload: PartyChatHistoryPayload) {
+      update((state) => ({
+        ...state,
+        messages: payload.messages,
+      }));
+    },
+
+    handleChatMessage(payload: PartyChatMessage) {
+      update((state) => {
+        if (state.messages.some((m) => m.id === payload.id)) {
+          return state;
+        }
+        return {
+          ...state,
+          messages: [...state.messages, payload].slice(-MAX_CHAT_MESSAGES),
+        };
+      });
+    },
+
     handleKicked(_payload: PartyKickedPayload) {
       const state = get({ subscribe });
       if (state.partyId) {
@@ -304,6 +384,10 @@ export function initPartySocketListeners(): () => void {
       partyStore.handleMemberLeft(payload);
     }),
 
+    socketClient.on('party:member_muted', (payload: PartyMemberMutedPayload) => {
+      partyStore.handleMemberMuted(payload);
+    }),
+
     socketClient.on('party:game_starting', (payload: PartyGameStartingPayload) => {
       partyStore.handleGameStarting(payload);
     }),
@@ -328,6 +412,14 @@ export function initPartySocketListeners(): () => void {
       partyStore.handleKicked(payload);
     }),
 
+    socketClient.on('party:chat_history', (payload: PartyChatHistoryPayload) => {
+      partyStore.handleChatHistory(payload);
+    }),
+
+    socketClient.on('party:chat_message', (payload: PartyChatMessage) => {
+      partyStore.handleChatMessage(payload);
+    }),
+
     socketClient.on('party:error', (payload: { code: string; message: string }) => {
       toastStore.add('error', payload.message);
     }),
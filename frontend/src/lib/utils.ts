@@ -86,3 +86,12 @@ export function formatDistance(meters: number): string {
   }
   return `${(meters / 1000).toFixed(1)} km`;
 }
+
+/**
+ * Flag emoji for an ISO 3166-1 alpha-2 country code (e.g. "SE" -> 🇸🇪)
+ */
+export function countryFlag(code: string): string {
+  return code
+    .toUpperCase()
+    .replace(/[A-Z]/g, (c) => String.fromCodePoint(0x1f1e6 + c.charCodeAt(0) - 65));
+}
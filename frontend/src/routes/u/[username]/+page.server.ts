@@ -1,17 +1,20 @@
 import { error } from '@sveltejs/kit';
 
 import { serverApi, ServerApiError } from '$lib/server/api';
-import type { UserProfile } from '$lib/api/users';
+import type { PublicProfile, UserProfile } from '$lib/api/users';
 import type { PageServerLoad } from './$types';
 
 export const load: PageServerLoad = async ({ params, request }) => {
   try {
     const cookieHeader = request.headers.get('cookie') || '';
-    const profile = await serverApi.get<UserProfile>(
-      `/users/u/${params.username}`,
-      { cookies: cookieHeader }
-    );
-    return { profile };
+    const [profile, publicProfile] = await Promise.all([
+      serverApi.get<UserProfile>(`/users/u/${params.username}`, { cookies: cookieHeader }),
+      // Only users who opted in have public stats and a share card
+      serverApi
+        .get<PublicProfile>(`/users/${params.username}/public`, { cookies: cookieHeader })
+        .catch(() => null),
+    ]);
+    return { profile, publicProfile };
   } catch (e) {
     if (e instanceof ServerApiError && e.status === 404) {
       throw error(404, 'User not found');
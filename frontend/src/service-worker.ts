@@ -0,0 +1,63 @@
+/// <reference types="@sveltejs/kit" />
+/// <reference no-default-lib="true"/>
+/// <reference lib="esnext" />
+/// <reference lib="webworker" />
+
+// Only handles push notifications; requests are not cached or intercepted.
+
+const sw = self as unknown as ServiceWorkerGlobalScope;
+
+interface PushPayload {
+  kind: string;
+  title: string;
+  body: string;
+  url: string;
+  tag: string;
+}
+
+sw.addEventListener('install', () => {
+  void sw.skipWaiting();
+});
+
+sw.addEventListener('activate', (event) => {
+  event.waitUntil(sw.clients.claim());
+});
+
+sw.addEventListener('push', (event) => {
+  if (!event.data) return;
+
+  let payload: PushPayload;
+  try {
+    payload = event.data.json() as PushPayload;
+  } catch {
+    return;
+  }
+
+  event.waitUntil(
+    sw.registration.showNotification(payload.title, {
+      body: payload.body,
+      tag: payload.tag,
+      icon: '/apple-touch-icon.png',
+      badge: '/favicon-32.png',
+      data: { url: payload.url },
+    })
+  );
+});
+
+sw.addEventListener('notificationclick', (event) => {
+  event.notification.close();
+  const url = new URL(event.notification.data?.url ?? '/', sw.location.origin).href;
+
+  event.waitUntil(
+    (async () => {
+      const windows = await sw.clients.matchAll({ type: 'window', includeUncontrolled: true });
+      const existing = windows.find((client) => client.url.startsWith(sw.location.origin));
+      if (existing) {
+        await existing.focus();
+        await existing.navigate(url);
+      } else {
+        await sw.clients.openWindow(url);
+      }
+    })()
+  );
+});
@@ -0,0 +1,40 @@
+-- Native email/password authentication.
+--
+-- Password credentials live in their own table so OAuth-only accounts never
+-- carry a password column, and a user can have at most one password.
+
+CREATE TABLE password_credentials (
+    user_id         VARCHAR(16) PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    -- Argon2id hash in PHC string format
+    password_hash   TEXT NOT NULL,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at      TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TRIGGER password_credentials_updated_at
+    BEFORE UPDATE ON password_credentials
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at();
+
+-- Single-use tokens for email verification and password resets.
+-- Only the SHA-256 digest of the token is stored.
+CREATE TABLE auth_tokens (
+    token_hash      CHAR(64) PRIMARY KEY,
+    user_id         VARCHAR(16) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    purpose         VARCHAR(32) NOT NULL,
+    -- Email address the token was issued for (verification only)
+    email           VARCHAR(255),
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at      TIMESTAMPTZ NOT NULL,
+    consumed_at     TIMESTAMPTZ,
+
+    CONSTRAINT auth_tokens_purpose_valid
+        CHECK (purpose IN ('email_verification', 'password_reset'))
+);
+
+CREATE INDEX idx_auth_tokens_user_purpose ON auth_tokens(user_id, purpose)
+    WHERE consumed_at IS NULL;
+CREATE INDEX idx_auth_tokens_expires ON auth_tokens(expires_at);
+
+-- Case-insensitive email lookup for password sign-in
+CREATE INDEX idx_users_email_lower ON users(LOWER(email)) WHERE email IS NOT NULL;
@@ -0,0 +1,41 @@
+-- Outgoing email queue.
+--
+-- Emails are rendered when enqueued and delivered by a background worker, so
+-- request handlers never block on (or fail because of) the mail provider.
+-- Failed sends are retried with exponential backoff until max_attempts.
+
+CREATE TABLE email_outbox (
+    id              VARCHAR(16) PRIMARY KEY,           -- eml_XXXXXXXXXXXX
+    user_id         VARCHAR(16) REFERENCES users(id) ON DELETE SET NULL,
+    template        VARCHAR(32) NOT NULL,
+    to_address      VARCHAR(255) NOT NULL,
+    subject         TEXT NOT NULL,
+    text_body       TEXT NOT NULL,
+    html_body       TEXT NOT NULL,
+    status          VARCHAR(16) NOT NULL DEFAULT 'pending',
+    attempts        INTEGER NOT NULL DEFAULT 0,
+    max_attempts    INTEGER NOT NULL DEFAULT 5,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_error      TEXT,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    sent_at         TIMESTAMPTZ,
+
+    CONSTRAINT email_outbox_status_valid
+        CHECK (status IN ('pending', 'sending', 'sent', 'failed'))
+);
+
+CREATE INDEX idx_email_outbox_due ON email_outbox(next_attempt_at)
+    WHERE status = 'pending';
+CREATE INDEX idx_email_outbox_user ON email_outbox(user_id, created_at DESC);
+
+-- Per-user email preferences. Absence of a row means defaults (no digest).
+CREATE TABLE email_preferences (
+    user_id         VARCHAR(16) PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    weekly_digest   BOOLEAN NOT NULL DEFAULT FALSE,
+    updated_at      TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TRIGGER email_preferences_updated_at
+    BEFORE UPDATE ON email_preferences
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at();
@@ -0,0 +1,28 @@
+-- Device tracking for sessions.
+--
+-- A device is a browser/OS combination seen for a user. Sessions reference
+-- the device they were created from, so signing in from an unfamiliar device
+-- can be detected and the user can mark devices they recognise as trusted.
+
+CREATE TABLE user_devices (
+    id              VARCHAR(16) PRIMARY KEY,           -- dev_XXXXXXXXXXXX
+    user_id         VARCHAR(16) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- SHA-256 of browser family, OS family and device type
+    device_key      CHAR(64) NOT NULL,
+    browser         VARCHAR(64),
+    os              VARCHAR(64),
+    device_type     VARCHAR(16) NOT NULL,
+    -- ISO 3166-1 alpha-2 country of the most recent sign-in, if known
+    last_country    CHAR(2),
+    trusted         BOOLEAN NOT NULL DEFAULT FALSE,
+    first_seen_at   TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen_at    TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT user_devices_user_key_unique UNIQUE (user_id, device_key)
+);
+
+ALTER TABLE sessions
+    ADD COLUMN device_id VARCHAR(16) REFERENCES user_devices(id) ON DELETE SET NULL,
+    ADD COLUMN geo_country CHAR(2);
+
+CREATE INDEX idx_sessions_device ON sessions(device_id) WHERE device_id IS NOT NULL;
@@ -0,0 +1,7 @@
+-- Sliding session renewal.
+--
+-- Renewal issues a successor session that records its predecessor in
+-- rotated_from; looking successors up by predecessor lets racing requests
+-- detect that a session was already renewed.
+
+CREATE INDEX idx_sessions_rotated_from ON sessions(rotated_from) WHERE rotated_from IS NOT NULL;
@@ -0,0 +1,26 @@
+-- Location health check runs.
+--
+-- A background worker periodically samples active locations and asks the
+-- Street View metadata API whether their panoramas still exist. Each run is
+-- summarised here so the admin dashboard can show coverage health over time;
+-- per-location outcomes are stored on the locations row itself.
+
+CREATE TABLE location_health_checks (
+    id              VARCHAR(16) PRIMARY KEY,           -- lhc_XXXXXXXXXXXX
+    started_at      TIMESTAMPTZ NOT NULL,
+    finished_at     TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- Locations sampled in this run
+    checked         INTEGER NOT NULL DEFAULT 0,
+    -- Panorama still exists
+    healthy         INTEGER NOT NULL DEFAULT 0,
+    -- Panorama is gone; location was deactivated
+    dead            INTEGER NOT NULL DEFAULT 0,
+    -- Lookup failed (network, quota); location left unchanged
+    errors          INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX idx_location_health_checks_started ON location_health_checks(started_at DESC);
+
+-- Oldest-checked active locations are sampled first
+CREATE INDEX idx_locations_health_sample ON locations(last_validated_at NULLS FIRST)
+    WHERE active = TRUE;
@@ -0,0 +1,25 @@
+-- Polygon map regions.
+--
+-- Maps can restrict play to an arbitrary area (GeoJSON Polygon/MultiPolygon in
+-- rules.region). The geometry is mirrored into maps.region so location
+-- selection can filter spatially with a GiST index instead of relying on a
+-- precomputed map_locations list.
+
+CREATE EXTENSION IF NOT EXISTS postgis;
+
+-- Location point, derived from lat/lng so it can never drift out of sync
+ALTER TABLE locations
+    ADD COLUMN geom geometry(Point, 4326)
+    GENERATED ALWAYS AS (ST_SetSRID(ST_MakePoint(lng, lat), 4326)) STORED;
+
+CREATE INDEX idx_locations_geom ON locations USING GIST (geom) WHERE active = TRUE;
+
+-- Map boundary; NULL for maps defined by their map_locations list
+ALTER TABLE maps ADD COLUMN region geometry(MultiPolygon, 4326);
+
+CREATE INDEX idx_maps_region ON maps USING GIST (region);
+
+UPDATE maps
+SET region = ST_Multi(ST_CollectionExtract(
+        ST_MakeValid(ST_SetSRID(ST_GeomFromGeoJSON(rules->>'region'), 4326)), 3))
+WHERE rules ? 'region';
@@ -0,0 +1,9 @@
+-- Per-map location source.
+--
+-- Maps can be served from the Postgres locations table or from pre-built R2
+-- location packs. NULL means the server default (LOCATION_PROVIDER).
+
+ALTER TABLE maps ADD COLUMN source VARCHAR(16)
+    CHECK (source IS NULL OR source IN ('database', 'pack'));
+
+COMMENT ON COLUMN maps.source IS 'Location source override: database, pack, or NULL for the server default';
@@ -0,0 +1,24 @@
+-- No-move enforcement and cheating signals.
+--
+-- Clients report the panorama they were viewing when they guessed. In no-move
+-- modes it must be the round's panorama; mismatches are rejected and logged
+-- here as cheating signals for review.
+
+ALTER TABLE guesses ADD COLUMN reported_panorama_id TEXT;
+
+CREATE TABLE cheat_signals (
+    id          VARCHAR(16) PRIMARY KEY,           -- chs_XXXXXXXXXXXX
+    user_id     VARCHAR(16) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    game_id     VARCHAR(16) REFERENCES games(id) ON DELETE SET NULL,
+    round_id    VARCHAR(16) REFERENCES rounds(id) ON DELETE SET NULL,
+    -- Signal type, e.g. 'panorama_mismatch'
+    kind        TEXT NOT NULL,
+    -- Kind-specific context (expected vs reported values)
+    details     JSONB NOT NULL DEFAULT '{}',
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT cheat_signals_id_format CHECK (id ~ '^chs_[A-Za-z0-9_]{12}$')
+);
+
+CREATE INDEX idx_cheat_signals_user ON cheat_signals(user_id, created_at DESC);
+CREATE INDEX idx_cheat_signals_kind ON cheat_signals(kind, created_at DESC);
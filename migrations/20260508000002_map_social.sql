@@ -0,0 +1,26 @@
+-- Map likes and play statistics.
+--
+-- Players can like maps; liked maps double as their favorites list. Like and
+-- play totals are denormalized onto maps so listings can sort by popularity
+-- without aggregating on every request.
+
+CREATE TABLE map_likes (
+    user_id VARCHAR(16) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    map_id VARCHAR(16) NOT NULL REFERENCES maps(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, map_id)
+);
+
+CREATE INDEX idx_map_likes_map ON map_likes(map_id);
+CREATE INDEX idx_map_likes_user_created ON map_likes(user_id, created_at DESC);
+
+ALTER TABLE maps
+    ADD COLUMN like_count INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN play_count INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN score_sum BIGINT NOT NULL DEFAULT 0;
+
+COMMENT ON COLUMN maps.play_count IS 'Finished games played on this map, counted once per player';
+COMMENT ON COLUMN maps.score_sum IS 'Sum of final player scores across play_count plays';
+
+CREATE INDEX idx_maps_popular ON maps(play_count DESC, like_count DESC)
+    WHERE visibility = 'public' AND active = TRUE;
@@ -0,0 +1,18 @@
+-- Map collaborators.
+--
+-- A map owner can invite other users to edit a map. Invitations are pending
+-- until the invitee accepts them; accepted collaborators can add and remove
+-- locations but cannot change map settings or delete the map.
+
+CREATE TABLE map_collaborators (
+    map_id VARCHAR(16) NOT NULL REFERENCES maps(id) ON DELETE CASCADE,
+    user_id VARCHAR(16) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    invited_by VARCHAR(16) REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    accepted_at TIMESTAMPTZ,
+    PRIMARY KEY (map_id, user_id)
+);
+
+COMMENT ON COLUMN map_collaborators.accepted_at IS 'NULL while the invitation is pending';
+
+CREATE INDEX idx_map_collaborators_user ON map_collaborators(user_id, created_at DESC);
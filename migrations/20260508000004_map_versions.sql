@@ -0,0 +1,23 @@
+-- Map version history.
+--
+-- Every change to a map's locations or rules appends a version holding the
+-- location IDs it added and removed plus the rules after the change. Replaying
+-- the diffs backwards restores any earlier version. A map's first recorded
+-- change is preceded by an 'initial' version holding the state before it.
+
+CREATE TABLE map_versions (
+    map_id VARCHAR(16) NOT NULL REFERENCES maps(id) ON DELETE CASCADE,
+    version INTEGER NOT NULL,
+    kind VARCHAR(16) NOT NULL
+        CHECK (kind IN ('initial', 'locations', 'rules', 'import', 'restore')),
+    user_id VARCHAR(16) REFERENCES users(id) ON DELETE SET NULL,
+    added_location_ids TEXT[] NOT NULL DEFAULT '{}',
+    removed_location_ids TEXT[] NOT NULL DEFAULT '{}',
+    rules JSONB NOT NULL,
+    restored_from INTEGER,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (map_id, version)
+);
+
+COMMENT ON COLUMN map_versions.rules IS 'Map rules after this version';
+COMMENT ON COLUMN map_versions.restored_from IS 'Version restored, for restore entries';
@@ -0,0 +1,11 @@
+-- Imagery providers.
+--
+-- Locations already record which imagery service they come from
+-- (google_streetview, mapillary, bing_streetside, sample). Rounds now copy it
+-- so clients pick the right viewer for games in progress.
+
+ALTER TABLE rounds
+    ADD COLUMN provider VARCHAR(50) NOT NULL DEFAULT 'google_streetview';
+
+-- Maps restricted to one provider filter on it during selection
+CREATE INDEX idx_locations_provider ON locations(provider) WHERE active = TRUE;
@@ -0,0 +1,49 @@
+-- Pre-aggregated game and player activity for the admin analytics dashboard.
+--
+-- A background job in the API recomputes the most recent days every hour
+-- (and backfills history on first run), so dashboard queries never scan the
+-- guesses or games tables. All days and weeks are UTC.
+
+-- Player activity and guess distribution per day
+CREATE TABLE analytics_daily (
+    day                  DATE PRIMARY KEY,
+    -- Users who submitted at least one guess
+    active_users         INTEGER NOT NULL DEFAULT 0,
+    -- Accounts created (guests included)
+    new_users            INTEGER NOT NULL DEFAULT 0,
+    guesses              INTEGER NOT NULL DEFAULT 0,
+    -- p10, p25, p50, p75, p90 of guess scores; NULL without guesses
+    score_percentiles    DOUBLE PRECISION[],
+    -- p10, p25, p50, p75, p90 of guess distances in km; NULL without guesses
+    distance_percentiles DOUBLE PRECISION[],
+    updated_at           TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Games per day and mode
+CREATE TABLE analytics_daily_games (
+    day                  DATE NOT NULL,
+    mode                 VARCHAR(20) NOT NULL,
+    games_created        INTEGER NOT NULL DEFAULT 0,
+    -- Games that finished this day
+    games_finished       INTEGER NOT NULL DEFAULT 0,
+    -- Total start-to-end time of the finished games
+    duration_secs_total  BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (day, mode)
+);
+
+-- Weekly signup cohorts: how many of the users who signed up in a week were
+-- active (guessed) N weeks later
+CREATE TABLE analytics_retention (
+    cohort_week          DATE NOT NULL,       -- Monday of the signup week
+    week_offset          SMALLINT NOT NULL,   -- 0 = the signup week itself
+    cohort_size          INTEGER NOT NULL,
+    retained_users       INTEGER NOT NULL,
+    updated_at           TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (cohort_week, week_offset)
+);
+
+-- Rollups select each day's guesses, signups, and created games by time
+-- range (finished games use idx_games_ended_at)
+CREATE INDEX idx_guesses_submitted ON guesses(submitted_at);
+CREATE INDEX idx_users_created ON users(created_at);
+CREATE INDEX idx_games_created ON games(created_at);
@@ -0,0 +1,12 @@
+-- Party chat: messages stay with the party across games, so members who
+-- join or reconnect later see the recent conversation.
+
+CREATE TABLE party_messages (
+    id          BIGSERIAL PRIMARY KEY,
+    party_id    VARCHAR(16) NOT NULL REFERENCES parties(id) ON DELETE CASCADE,
+    user_id     VARCHAR(16) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    content     TEXT NOT NULL,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_party_messages_party ON party_messages(party_id, id DESC);
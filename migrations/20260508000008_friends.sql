@@ -0,0 +1,18 @@
+-- Friends: a request from one registered user to another that becomes a
+-- friendship once accepted. Declining or removing a friend deletes the row,
+-- so there is at most one row per pair of users in either direction.
+
+CREATE TABLE friendships (
+    requester_id    VARCHAR(16) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    addressee_id    VARCHAR(16) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    status          VARCHAR(20) NOT NULL DEFAULT 'pending',
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    accepted_at     TIMESTAMPTZ,
+    PRIMARY KEY (requester_id, addressee_id),
+    CONSTRAINT friendships_not_self CHECK (requester_id <> addressee_id),
+    CONSTRAINT friendships_status CHECK (status IN ('pending', 'accepted'))
+);
+
+CREATE UNIQUE INDEX idx_friendships_pair
+    ON friendships (LEAST(requester_id, addressee_id), GREATEST(requester_id, addressee_id));
+CREATE INDEX idx_friendships_addressee ON friendships(addressee_id, status);
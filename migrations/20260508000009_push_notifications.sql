@@ -0,0 +1,53 @@
+-- Web Push notifications.
+--
+-- Each browser that enables notifications registers a push subscription.
+-- Notifications are queued per subscription and delivered by a background
+-- worker that encrypts the payload for the browser and signs a VAPID token.
+
+CREATE TABLE push_subscriptions (
+    id              VARCHAR(16) PRIMARY KEY,           -- psb_XXXXXXXXXXXX
+    user_id         VARCHAR(16) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    endpoint        TEXT NOT NULL UNIQUE,
+    p256dh          VARCHAR(128) NOT NULL,             -- browser public key (base64url)
+    auth            VARCHAR(64) NOT NULL,              -- auth secret (base64url)
+    user_agent      TEXT,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_used_at    TIMESTAMPTZ
+);
+
+CREATE INDEX idx_push_subscriptions_user ON push_subscriptions(user_id, created_at DESC);
+
+CREATE TABLE push_outbox (
+    id              BIGSERIAL PRIMARY KEY,
+    subscription_id VARCHAR(16) NOT NULL REFERENCES push_subscriptions(id) ON DELETE CASCADE,
+    kind            VARCHAR(32) NOT NULL,
+    payload         JSONB NOT NULL,
+    ttl_secs        INTEGER NOT NULL,
+    status          VARCHAR(16) NOT NULL DEFAULT 'pending',
+    attempts        INTEGER NOT NULL DEFAULT 0,
+    max_attempts    INTEGER NOT NULL DEFAULT 5,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_error      TEXT,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    sent_at         TIMESTAMPTZ,
+
+    CONSTRAINT push_outbox_status_valid
+        CHECK (status IN ('pending', 'sending', 'sent', 'failed'))
+);
+
+CREATE INDEX idx_push_outbox_due ON push_outbox(next_attempt_at)
+    WHERE status = 'pending';
+
+-- Per-user notification preferences. Absence of a row means everything is on.
+CREATE TABLE notification_preferences (
+    user_id         VARCHAR(16) PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    challenge_turns BOOLEAN NOT NULL DEFAULT TRUE,
+    friend_invites  BOOLEAN NOT NULL DEFAULT TRUE,
+    daily_challenge BOOLEAN NOT NULL DEFAULT TRUE,
+    updated_at      TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TRIGGER notification_preferences_updated_at
+    BEFORE UPDATE ON notification_preferences
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at();
@@ -0,0 +1,20 @@
+-- Asynchronous challenges.
+--
+-- A challenge is a game between friends whose rounds are all created up
+-- front. Each player plays them on their own schedule: a row here records
+-- when a player started a round and when they finished it (by guessing or
+-- running out of time). Results are revealed once every player is done.
+
+CREATE TABLE player_rounds (
+    round_id        VARCHAR(16) NOT NULL REFERENCES rounds(id) ON DELETE CASCADE,
+    user_id         VARCHAR(16) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    started_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at    TIMESTAMPTZ,
+    PRIMARY KEY (round_id, user_id)
+);
+
+CREATE INDEX idx_player_rounds_user ON player_rounds(user_id, started_at DESC);
+
+-- Expiring challenges that were never finished
+CREATE INDEX idx_games_active_challenges ON games(created_at)
+    WHERE mode = 'challenge' AND status = 'active';
@@ -0,0 +1,8 @@
+-- Per-player score multipliers for casual lobbies.
+--
+-- The host can give newer players a handicap (e.g. 1.25x) before the game
+-- starts. Round scores are multiplied by it as they are added to score_total.
+
+ALTER TABLE game_players
+    ADD COLUMN handicap DOUBLE PRECISION NOT NULL DEFAULT 1.0
+        CHECK (handicap BETWEEN 0.5 AND 2.0);
@@ -0,0 +1,18 @@
+-- Historical guess distances per location, used to tell players how their
+-- guess compares to everyone else's on the same location.
+--
+-- A background job in the API refreshes the locations guessed since its last
+-- run every hour, so round results never scan the guesses table. Timed-out
+-- rounds (no guess) are excluded.
+
+CREATE TABLE location_guess_stats (
+    -- Matches rounds.location_id (database or R2 location ID)
+    location_id            VARCHAR(24) PRIMARY KEY,
+    guesses                INTEGER NOT NULL,
+    median_distance_meters DOUBLE PRECISION NOT NULL,
+    -- Guess distance in meters at every 5% (p0, p5, ..., p100)
+    distance_quantiles     DOUBLE PRECISION[] NOT NULL,
+    updated_at             TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_location_guess_stats_updated ON location_guess_stats(updated_at);
@@ -0,0 +1,17 @@
+-- Difficulty rating per location, derived from gameplay.
+--
+-- The location stats job (see location_guess_stats) also tracks the median
+-- score of past guesses and copies the medians onto database locations along
+-- with a 0-100 difficulty rating. Locations stay unrated (NULL) until enough
+-- guesses have been made on them.
+
+ALTER TABLE location_guess_stats
+    ADD COLUMN median_score DOUBLE PRECISION NOT NULL DEFAULT 0;
+
+ALTER TABLE locations
+    ADD COLUMN difficulty SMALLINT CHECK (difficulty BETWEEN 0 AND 100),
+    ADD COLUMN median_guess_distance_meters DOUBLE PRECISION,
+    ADD COLUMN median_guess_score DOUBLE PRECISION;
+
+-- Map rules and map builder searches filter on difficulty bands
+CREATE INDEX idx_locations_difficulty ON locations(difficulty) WHERE difficulty IS NOT NULL;
@@ -0,0 +1,12 @@
+-- Streak mode: solo games that continue until the player names the wrong
+-- country.
+--
+-- Rounds record the country of their location and guesses the country the
+-- player named. A streak game's score is its streak length, so the streak
+-- leaderboard reads finished streak games from game_players.
+
+ALTER TYPE game_mode ADD VALUE IF NOT EXISTS 'streak';
+
+ALTER TABLE rounds ADD COLUMN country_code TEXT;
+
+ALTER TABLE guesses ADD COLUMN country_code TEXT;
@@ -0,0 +1,32 @@
+-- Vali tags on locations and search indexes for the map builder.
+--
+-- Tags were previously discarded on import. The map builder search filters on
+-- tags (all must match) and on free text matched against tags, country,
+-- subdivision and surface, with full-text and trigram indexes.
+
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+ALTER TABLE locations ADD COLUMN tags TEXT[] NOT NULL DEFAULT '{}';
+
+-- Text matched by free-text searches. array_to_string is only STABLE, so it is
+-- wrapped in an IMMUTABLE function for use in index expressions.
+CREATE FUNCTION location_search_text(
+    tags TEXT[],
+    country_code TEXT,
+    subdivision_code TEXT,
+    surface TEXT
+) RETURNS TEXT
+LANGUAGE sql IMMUTABLE PARALLEL SAFE
+AS $$
+    SELECT lower(concat_ws(' ', array_to_string(tags, ' '), country_code, subdivision_code, surface))
+$$;
+
+CREATE INDEX idx_locations_tags ON locations USING GIN (tags);
+
+CREATE INDEX idx_locations_search_fts ON locations USING GIN (
+    to_tsvector('simple', location_search_text(tags, country_code, subdivision_code, surface))
+);
+
+CREATE INDEX idx_locations_search_trgm ON locations USING GIN (
+    location_search_text(tags, country_code, subdivision_code, surface) gin_trgm_ops
+);
@@ -0,0 +1,19 @@
+-- Profile customization: bio, home country, profile color and uploaded
+-- avatars, kept out of the users table.
+--
+-- avatar_key is the object storage key of an uploaded avatar; users.avatar_url
+-- points at its public URL. Admins can reset inappropriate content and lock
+-- the profile so the user can't change it again.
+
+CREATE TABLE user_profiles (
+    user_id         VARCHAR(16) PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    bio             VARCHAR(160),
+    country_code    CHAR(2),
+    profile_color   CHAR(7),
+    avatar_key      VARCHAR(200),
+    locked          BOOLEAN NOT NULL DEFAULT FALSE,
+    moderated_at    TIMESTAMPTZ,
+    moderated_by    VARCHAR(16) REFERENCES users(id) ON DELETE SET NULL,
+    updated_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT user_profiles_color CHECK (profile_color ~ '^#[0-9a-f]{6}$')
+);
@@ -0,0 +1,8 @@
+-- Who can see a user's presence (online, in lobby, in game): everyone,
+-- friends only (the default, matching the friend list's online dots) or
+-- nobody, in which case the user always appears offline.
+
+ALTER TABLE user_profiles
+    ADD COLUMN presence_visibility VARCHAR(10) NOT NULL DEFAULT 'friends',
+    ADD CONSTRAINT user_profiles_presence_visibility
+        CHECK (presence_visibility IN ('everyone', 'friends', 'nobody'));
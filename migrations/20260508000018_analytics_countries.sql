@@ -0,0 +1,14 @@
+-- Guesses per day and round country, so the analytics dashboard can compare
+-- how players do across countries and continents. Rounds without a recorded
+-- country are left out. Maintained by the same hourly job as the other
+-- analytics rollups.
+
+CREATE TABLE analytics_daily_countries (
+    day                  DATE NOT NULL,
+    -- ISO 3166-1 alpha-2 code of the round's location
+    country_code         TEXT NOT NULL,
+    guesses              INTEGER NOT NULL DEFAULT 0,
+    score_total          BIGINT NOT NULL DEFAULT 0,
+    distance_km_total    DOUBLE PRECISION NOT NULL DEFAULT 0,
+    PRIMARY KEY (day, country_code)
+);
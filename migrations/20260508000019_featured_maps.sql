@@ -0,0 +1,24 @@
+-- Scheduled featured maps: admins queue system maps to become the default
+-- map for a period, e.g. a weekly themed event. A background task makes the
+-- map the default once the feature starts and restores the map that was the
+-- default before it when the feature ends. Features never overlap.
+
+CREATE TABLE featured_maps (
+    id                      BIGSERIAL PRIMARY KEY,
+    map_id                  VARCHAR(16) NOT NULL REFERENCES maps(id) ON DELETE CASCADE,
+    -- Event name shown alongside the map, e.g. "Island Week"
+    title                   VARCHAR(100),
+    starts_at               TIMESTAMPTZ NOT NULL,
+    ends_at                 TIMESTAMPTZ NOT NULL,
+    created_by              VARCHAR(16) REFERENCES users(id) ON DELETE SET NULL,
+    created_at              TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- Set by the scheduler when the map is made the default
+    activated_at            TIMESTAMPTZ,
+    -- Set by the scheduler once the feature is over (or was skipped)
+    finished_at             TIMESTAMPTZ,
+    -- Default map before the feature started, restored when it ends
+    previous_default_map_id VARCHAR(16) REFERENCES maps(id) ON DELETE SET NULL,
+    CONSTRAINT featured_maps_period CHECK (ends_at > starts_at)
+);
+
+CREATE INDEX idx_featured_maps_pending ON featured_maps(starts_at) WHERE finished_at IS NULL;
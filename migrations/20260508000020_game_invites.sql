@@ -0,0 +1,25 @@
+-- Game invites: links a host shares with specific people instead of the
+-- lobby's join code. Each invite has an expiry and a use limit and can be
+-- revoked. Only a hash of the token is stored.
+
+CREATE TABLE game_invites (
+    id          BIGSERIAL PRIMARY KEY,
+    game_id     VARCHAR(16) NOT NULL REFERENCES games(id) ON DELETE CASCADE,
+    token_hash  TEXT NOT NULL UNIQUE,
+    created_by  VARCHAR(16) REFERENCES users(id) ON DELETE SET NULL,
+    max_uses    INTEGER NOT NULL CHECK (max_uses > 0),
+    uses        INTEGER NOT NULL DEFAULT 0,
+    expires_at  TIMESTAMPTZ NOT NULL,
+    revoked_at  TIMESTAMPTZ,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_game_invites_game ON game_invites(game_id, created_at DESC);
+
+-- Users who redeemed an invite; redeeming again does not use it up further
+CREATE TABLE game_invite_redemptions (
+    invite_id   BIGINT NOT NULL REFERENCES game_invites(id) ON DELETE CASCADE,
+    user_id     VARCHAR(16) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    redeemed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (invite_id, user_id)
+);
@@ -0,0 +1,20 @@
+-- Round rerolls: a host replaced a round's location shortly after it
+-- started (e.g. a broken panorama). Guesses on the old location are
+-- discarded; this table keeps the old location for auditing and counts
+-- toward the game's reroll quota.
+
+CREATE TABLE round_rerolls (
+    id                      BIGSERIAL PRIMARY KEY,
+    game_id                 VARCHAR(16) NOT NULL REFERENCES games(id) ON DELETE CASCADE,
+    round_id                VARCHAR(16) NOT NULL REFERENCES rounds(id) ON DELETE CASCADE,
+    round_number            SMALLINT NOT NULL,
+    rerolled_by             VARCHAR(16) REFERENCES users(id) ON DELETE SET NULL,
+    previous_lat            DOUBLE PRECISION NOT NULL,
+    previous_lng            DOUBLE PRECISION NOT NULL,
+    previous_panorama_id    VARCHAR(100),
+    previous_location_id    VARCHAR(24),
+    discarded_guesses       INTEGER NOT NULL DEFAULT 0,
+    created_at              TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_round_rerolls_game ON round_rerolls(game_id, created_at);
@@ -0,0 +1,20 @@
+-- Client error reports: structured errors sent by the frontend (a panorama
+-- that failed to load, a dropped socket). Reports linked to a location let
+-- repeated panorama failures feed the location's failure counter.
+
+CREATE TABLE client_errors (
+    id          BIGSERIAL PRIMARY KEY,
+    kind        VARCHAR(32) NOT NULL,
+    message     TEXT,
+    user_id     VARCHAR(16) REFERENCES users(id) ON DELETE SET NULL,
+    location_id VARCHAR(16) REFERENCES locations(id) ON DELETE SET NULL,
+    game_id     VARCHAR(16) REFERENCES games(id) ON DELETE SET NULL,
+    page        TEXT,
+    user_agent  TEXT,
+    context     JSONB NOT NULL DEFAULT '{}',
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_client_errors_kind ON client_errors(kind, created_at DESC);
+CREATE INDEX idx_client_errors_location ON client_errors(location_id, created_at DESC)
+    WHERE location_id IS NOT NULL;
@@ -0,0 +1,33 @@
+-- Admin audit log and impersonation sessions.
+--
+-- Admins can sign in as a user for support. The impersonation session is a
+-- normal session row plus an entry here that names the admin, limits what
+-- the session may do, and points back to the admin's own session so it can
+-- be restored when impersonation ends.
+
+CREATE TABLE admin_audit_log (
+    id              BIGSERIAL PRIMARY KEY,
+    actor_id        VARCHAR(16) REFERENCES users(id) ON DELETE SET NULL,
+    action          VARCHAR(64) NOT NULL,
+    target_user_id  VARCHAR(16) REFERENCES users(id) ON DELETE SET NULL,
+    details         JSONB NOT NULL DEFAULT '{}',
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_admin_audit_log_created ON admin_audit_log(created_at DESC);
+CREATE INDEX idx_admin_audit_log_target ON admin_audit_log(target_user_id, created_at DESC)
+    WHERE target_user_id IS NOT NULL;
+
+CREATE TABLE impersonation_sessions (
+    session_id       VARCHAR(47) PRIMARY KEY REFERENCES sessions(id) ON DELETE CASCADE,
+    admin_id         VARCHAR(16) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    admin_session_id VARCHAR(47),
+    user_id          VARCHAR(16) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    read_only        BOOLEAN NOT NULL DEFAULT TRUE,
+    reason           TEXT NOT NULL,
+    created_at       TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at       TIMESTAMPTZ NOT NULL,
+    ended_at         TIMESTAMPTZ
+);
+
+CREATE INDEX idx_impersonation_sessions_admin ON impersonation_sessions(admin_id, created_at DESC);
@@ -0,0 +1,25 @@
+-- Outgoing Socket.IO event queue.
+--
+-- Events about a game change are written in the same transaction as the
+-- change and published to Redis by a relay task, so a crash between the
+-- commit and the publish delays the event instead of losing it. Delivery is
+-- at least once: clients may see an event twice.
+
+CREATE TABLE socket_event_outbox (
+    id              BIGSERIAL PRIMARY KEY,
+    room            VARCHAR(64) NOT NULL,
+    event           VARCHAR(64) NOT NULL,
+    payload         JSONB NOT NULL,
+    status          VARCHAR(16) NOT NULL DEFAULT 'pending',
+    attempts        INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_error      TEXT,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    delivered_at    TIMESTAMPTZ,
+
+    CONSTRAINT socket_event_outbox_status_valid
+        CHECK (status IN ('pending', 'sending', 'delivered', 'failed'))
+);
+
+CREATE INDEX idx_socket_event_outbox_due ON socket_event_outbox(next_attempt_at, id)
+    WHERE status IN ('pending', 'sending');
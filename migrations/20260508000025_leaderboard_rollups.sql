@@ -0,0 +1,51 @@
+-- Leaderboard rollups.
+--
+-- Per-player totals for each day, ISO week and all time, per map and game
+-- mode, updated when a game finishes instead of aggregated from raw games
+-- on every read. map_id '*' and mode '*' hold the totals across all maps and
+-- all non-streak modes; streak games only count towards mode 'streak'.
+
+CREATE TABLE leaderboard_rollups (
+    bucket          VARCHAR(8) NOT NULL,               -- day, week or all
+    bucket_start    DATE NOT NULL,                     -- 1970-01-01 for all
+    map_id          VARCHAR(64) NOT NULL,
+    mode            VARCHAR(16) NOT NULL,
+    user_id         VARCHAR(16) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    total_score     BIGINT NOT NULL DEFAULT 0,
+    best_score      BIGINT NOT NULL DEFAULT 0,
+    games_played    INTEGER NOT NULL DEFAULT 0,
+    updated_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (bucket, bucket_start, map_id, mode, user_id),
+    CONSTRAINT leaderboard_rollups_bucket_valid CHECK (bucket IN ('day', 'week', 'all'))
+);
+
+CREATE INDEX idx_leaderboard_rollups_total
+    ON leaderboard_rollups(bucket, bucket_start, map_id, mode, total_score DESC);
+CREATE INDEX idx_leaderboard_rollups_user ON leaderboard_rollups(user_id);
+
+-- Set once a finished game has been added to the rollups
+ALTER TABLE games ADD COLUMN leaderboard_recorded_at TIMESTAMPTZ;
+
+-- Backfill from the games finished so far
+INSERT INTO leaderboard_rollups
+    (bucket, bucket_start, map_id, mode, user_id, total_score, best_score, games_played)
+SELECT b.bucket, b.bucket_start, m.map_id, md.mode, gp.user_id,
+       SUM(gp.score_total), MAX(gp.score_total), COUNT(*)
+FROM games g
+INNER JOIN game_players gp ON gp.game_id = g.id
+CROSS JOIN LATERAL (VALUES
+    ('day', (g.ended_at AT TIME ZONE 'UTC')::date),
+    ('week', date_trunc('week', g.ended_at AT TIME ZONE 'UTC')::date),
+    ('all', DATE '1970-01-01')
+) AS b(bucket, bucket_start)
+CROSS JOIN LATERAL (VALUES (COALESCE(g.settings->>'map_id', '')), ('*')) AS m(map_id)
+CROSS JOIN LATERAL (VALUES
+    (g.mode::text),
+    (CASE WHEN g.mode = 'streak' THEN NULL ELSE '*' END)
+) AS md(mode)
+WHERE g.status = 'finished' AND g.ended_at IS NOT NULL AND md.mode IS NOT NULL
+GROUP BY b.bucket, b.bucket_start, m.map_id, md.mode, gp.user_id;
+
+UPDATE games SET leaderboard_recorded_at = NOW()
+WHERE status = 'finished' AND ended_at IS NOT NULL;
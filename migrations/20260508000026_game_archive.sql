@@ -0,0 +1,15 @@
+-- Game archival.
+--
+-- Rounds, guesses, challenge progress and rerolls of old finished games are
+-- moved to compressed archives in object storage. The games and game_players
+-- rows stay as the summary; archive_key points at the archive so the game
+-- can be restored on demand.
+
+ALTER TABLE games
+    ADD COLUMN archived_at TIMESTAMPTZ,
+    ADD COLUMN archive_key TEXT,
+    ADD COLUMN rehydrated_at TIMESTAMPTZ;
+
+-- Games waiting to be archived
+CREATE INDEX idx_games_archivable ON games(ended_at)
+    WHERE archived_at IS NULL AND status IN ('finished', 'abandoned');
@@ -0,0 +1,47 @@
+-- Organizations (multi-tenant instances).
+--
+-- An organization is a separate instance of the game for a classroom or
+-- company, reached through its own subdomain or domain. It owns the maps
+-- created in it, its games and its leaderboards; users take part through a
+-- membership. Rows with no org_id belong to the public instance.
+
+CREATE TABLE organizations (
+    id              VARCHAR(16) PRIMARY KEY,           -- org_XXXXXXXXXXXX
+    slug            VARCHAR(50) NOT NULL UNIQUE,       -- subdomain and X-Organization value
+    name            VARCHAR(100) NOT NULL,
+    domain          VARCHAR(253) UNIQUE,               -- custom domain, lowercase
+    branding        JSONB NOT NULL DEFAULT '{}',       -- logo, colors, ...
+    active          BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT organizations_slug_valid CHECK (slug ~ '^[a-z0-9]([a-z0-9-]*[a-z0-9])?$')
+);
+
+CREATE TABLE organization_members (
+    org_id          VARCHAR(16) NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    user_id         VARCHAR(16) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role            VARCHAR(16) NOT NULL DEFAULT 'member',
+    joined_at       TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (org_id, user_id),
+    CONSTRAINT organization_members_role_valid CHECK (role IN ('member', 'admin'))
+);
+
+CREATE INDEX idx_organization_members_user ON organization_members(user_id);
+
+ALTER TABLE maps ADD COLUMN org_id VARCHAR(16) REFERENCES organizations(id) ON DELETE CASCADE;
+CREATE INDEX idx_maps_org ON maps(org_id) WHERE org_id IS NOT NULL;
+
+ALTER TABLE games ADD COLUMN org_id VARCHAR(16) REFERENCES organizations(id) ON DELETE SET NULL;
+CREATE INDEX idx_games_org ON games(org_id, ended_at DESC) WHERE org_id IS NOT NULL;
+
+-- Leaderboards are kept per organization; '' is the public instance
+ALTER TABLE leaderboard_rollups ADD COLUMN org_id VARCHAR(16) NOT NULL DEFAULT '';
+ALTER TABLE leaderboard_rollups DROP CONSTRAINT leaderboard_rollups_pkey;
+ALTER TABLE leaderboard_rollups
+    ADD PRIMARY KEY (org_id, bucket, bucket_start, map_id, mode, user_id);
+
+DROP INDEX idx_leaderboard_rollups_total;
+CREATE INDEX idx_leaderboard_rollups_total
+    ON leaderboard_rollups(org_id, bucket, bucket_start, map_id, mode, total_score DESC);
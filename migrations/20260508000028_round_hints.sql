@@ -0,0 +1,13 @@
+-- Round hints: clues a player took during a round (continent, first letter
+-- of the country, direction to the location). Each costs score points,
+-- already taken off the player's guess; this table keeps what was revealed.
+
+CREATE TABLE round_hints (
+    round_id        VARCHAR(16) NOT NULL REFERENCES rounds(id) ON DELETE CASCADE,
+    user_id         VARCHAR(16) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind            VARCHAR(20) NOT NULL,
+    hint            JSONB NOT NULL,
+    cost            INTEGER NOT NULL,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (round_id, user_id, kind)
+);
@@ -0,0 +1,15 @@
+-- Saved game-settings presets: a user's own named settings, offered next to
+-- the built-in presets when creating a game.
+
+CREATE TABLE settings_presets (
+    id              VARCHAR(16) PRIMARY KEY,           -- prs_XXXXXXXXXXXX
+    user_id         VARCHAR(16) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name            VARCHAR(50) NOT NULL,
+    settings        JSONB NOT NULL,                    -- GameSettings
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    UNIQUE (user_id, name)
+);
+
+CREATE INDEX idx_settings_presets_user ON settings_presets(user_id, created_at);
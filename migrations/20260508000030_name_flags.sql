@@ -0,0 +1,17 @@
+-- Display names flagged for moderator review: accepted names that look like
+-- staff impersonation, advertise links, or mix in look-alike letters.
+
+CREATE TABLE name_flags (
+    id              VARCHAR(16) PRIMARY KEY,           -- nfl_XXXXXXXXXXXX
+    user_id         VARCHAR(16) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    display_name    VARCHAR(100) NOT NULL,             -- the name that was flagged
+    reason          VARCHAR(20) NOT NULL,              -- impersonation, link, confusables
+    status          VARCHAR(20) NOT NULL DEFAULT 'pending', -- pending, dismissed, renamed
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    resolved_at     TIMESTAMPTZ,
+    resolved_by     VARCHAR(16) REFERENCES users(id) ON DELETE SET NULL
+);
+
+-- One open flag per user; re-flagging updates it
+CREATE UNIQUE INDEX idx_name_flags_pending_user ON name_flags(user_id) WHERE status = 'pending';
+CREATE INDEX idx_name_flags_status ON name_flags(status, created_at);
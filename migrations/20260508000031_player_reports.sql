@@ -0,0 +1,33 @@
+-- Reports about players (cheating, abusive names or chat) and the
+-- moderation workflow they go through. Name reports also flag the name
+-- (name_flags.reason = 'reported') and cheating reports add a cheat signal
+-- (cheat_signals.kind = 'player_report').
+
+CREATE TABLE player_reports (
+    id                  VARCHAR(16) PRIMARY KEY,       -- prp_XXXXXXXXXXXX
+    reporter_id         VARCHAR(16) REFERENCES users(id) ON DELETE SET NULL,
+    reported_user_id    VARCHAR(16) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- cheating, abusive_name, abusive_chat, griefing, other
+    category            VARCHAR(20) NOT NULL,
+    game_id             VARCHAR(16) REFERENCES games(id) ON DELETE SET NULL,
+    chat_excerpt        TEXT,
+    notes               TEXT,
+    -- open, reviewing, actioned, dismissed
+    status              VARCHAR(20) NOT NULL DEFAULT 'open',
+    resolution_note     TEXT,
+    reviewed_by         VARCHAR(16) REFERENCES users(id) ON DELETE SET NULL,
+    reviewed_at         TIMESTAMPTZ,
+    created_at          TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at          TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT player_reports_id_format CHECK (id ~ '^prp_[A-Za-z0-9_]{12}$'),
+    CONSTRAINT player_reports_not_self CHECK (reporter_id IS DISTINCT FROM reported_user_id)
+);
+
+-- One open report per reporter, player and category
+CREATE UNIQUE INDEX idx_player_reports_open_unique
+    ON player_reports(reporter_id, reported_user_id, category)
+    WHERE status IN ('open', 'reviewing');
+CREATE INDEX idx_player_reports_status ON player_reports(status, created_at);
+CREATE INDEX idx_player_reports_reported ON player_reports(reported_user_id, created_at DESC);
+CREATE INDEX idx_player_reports_reporter ON player_reports(reporter_id, created_at DESC);
@@ -0,0 +1,11 @@
+-- Chat moderation: hosts can mute party members, messages caught by the
+-- word filter keep their original text for moderators, and old messages
+-- are deleted after the retention window.
+
+-- Survives leaving and rejoining so a muted member can't reset it
+ALTER TABLE party_members ADD COLUMN chat_muted BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Set only when the filter masked part of the message
+ALTER TABLE party_messages ADD COLUMN original_content TEXT;
+
+CREATE INDEX idx_party_messages_created ON party_messages(created_at);
@@ -0,0 +1,4 @@
+-- Solo round results are signed when stored; each signature chains to the
+-- previous round's, and the chain is checked before a finished game reaches
+-- the leaderboards.
+ALTER TABLE guesses ADD COLUMN score_mac TEXT;
@@ -0,0 +1,16 @@
+-- Internal notes on locations, written by admins and by the curators (owner
+-- and editors) of maps the location is in, e.g. "pano replaced 2025-03" or
+-- "borderline trekker". Never shown to players.
+
+CREATE TABLE location_notes (
+    id          BIGSERIAL PRIMARY KEY,
+    location_id VARCHAR(16) NOT NULL REFERENCES locations(id) ON DELETE CASCADE,
+    author_id   VARCHAR(16) REFERENCES users(id) ON DELETE SET NULL,
+    map_id      VARCHAR(16) REFERENCES maps(id) ON DELETE SET NULL, -- map a curator wrote it from; NULL for admins
+    body        TEXT NOT NULL,
+    tags        TEXT[] NOT NULL DEFAULT '{}',
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_location_notes_location ON location_notes(location_id, created_at);
+CREATE INDEX idx_location_notes_tags ON location_notes USING GIN (tags);
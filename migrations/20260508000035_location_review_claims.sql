@@ -0,0 +1,25 @@
+-- Review queue assignment: a reviewer claims a batch of queued locations so
+-- other admins skip them. Claims lapse on their own after a while; a claim
+-- is dropped when the reviewer decides on the location.
+
+CREATE TABLE location_review_claims (
+    location_id VARCHAR(16) PRIMARY KEY REFERENCES locations(id) ON DELETE CASCADE,
+    reviewer_id VARCHAR(16) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    claimed_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at  TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_location_review_claims_reviewer ON location_review_claims(reviewer_id, expires_at);
+
+-- Every review decision made from the admin queue, for reviewer throughput
+CREATE TABLE location_review_decisions (
+    id          BIGSERIAL PRIMARY KEY,
+    location_id VARCHAR(16) NOT NULL REFERENCES locations(id) ON DELETE CASCADE,
+    reviewer_id VARCHAR(16) REFERENCES users(id) ON DELETE SET NULL,
+    status      VARCHAR(32) NOT NULL,             -- approved, rejected, flagged, pending
+    notes       TEXT,
+    claimed_at  TIMESTAMPTZ,                      -- when the reviewer claimed it, if they did
+    decided_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_location_review_decisions_reviewer ON location_review_decisions(reviewer_id, decided_at);
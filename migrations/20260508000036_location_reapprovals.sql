@@ -0,0 +1,23 @@
+-- Re-approval proposals: the health checker rechecks inactive (dead,
+-- rejected or flagged) locations, and when their panorama exists again it
+-- proposes bringing them back. An admin accepts or dismisses each proposal.
+
+CREATE TABLE location_reapprovals (
+    location_id      VARCHAR(16) PRIMARY KEY REFERENCES locations(id) ON DELETE CASCADE,
+    status           VARCHAR(16) NOT NULL DEFAULT 'proposed', -- proposed, accepted, dismissed
+    -- Review status and failure reason when the proposal was made
+    previous_status  VARCHAR(32),
+    previous_reason  TEXT,
+    proposed_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    decided_by       VARCHAR(16) REFERENCES users(id) ON DELETE SET NULL,
+    decided_at       TIMESTAMPTZ
+);
+
+CREATE INDEX idx_location_reapprovals_proposed ON location_reapprovals(proposed_at)
+    WHERE status = 'proposed';
+
+-- Index for picking inactive locations to recheck
+CREATE INDEX idx_locations_recheck ON locations(last_validated_at NULLS FIRST)
+    WHERE active = FALSE;
+
+ALTER TABLE location_health_checks ADD COLUMN revived INT NOT NULL DEFAULT 0;
@@ -0,0 +1,16 @@
+-- Shareable replay links: a player of a finished game creates a public link
+-- that shows a redacted replay to anyone, signed in or not. Links expire
+-- and can be revoked. Only a hash of the token is stored.
+
+CREATE TABLE game_share_links (
+    id          BIGSERIAL PRIMARY KEY,
+    game_id     VARCHAR(16) NOT NULL REFERENCES games(id) ON DELETE CASCADE,
+    token_hash  TEXT NOT NULL UNIQUE,
+    created_by  VARCHAR(16) REFERENCES users(id) ON DELETE SET NULL,
+    views       INTEGER NOT NULL DEFAULT 0,
+    expires_at  TIMESTAMPTZ NOT NULL,
+    revoked_at  TIMESTAMPTZ,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_game_share_links_game ON game_share_links(game_id, created_at DESC);